@@ -0,0 +1,46 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Payload compression for OTLP ingest and export.
+
+use serde::{Deserialize, Serialize};
+use tonic::codec::CompressionEncoding;
+
+/// Compression algorithm for OTLP payloads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// No compression
+    #[default]
+    None,
+    /// gzip, supported by every OTLP exporter
+    Gzip,
+    /// zstd, better ratio and speed than gzip
+    Zstd,
+}
+
+impl Compression {
+    /// gRPC encoding, or `None` for no compression.
+    pub fn encoding(&self) -> Option<CompressionEncoding> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some(CompressionEncoding::Gzip),
+            Compression::Zstd => Some(CompressionEncoding::Zstd),
+        }
+    }
+
+    /// Name used in `grpc-encoding` and `Content-Encoding` headers.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Compression::None => "identity",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+}
+
+impl std::fmt::Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -3,7 +3,12 @@
 
 //! Collector configuration.
 
+use crate::compression::Compression;
+use llm_observatory_core::cost_tags::normalize_key as normalize_cost_tag_key;
+use llm_observatory_core::secrets::SecretResolvers;
+use llm_observatory_providers::{SelfHostedCostModel, SelfHostedDeployment};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::net::SocketAddr;
 
 /// Main collector configuration.
@@ -24,6 +29,18 @@ pub struct CollectorConfig {
     /// Metrics configuration
     #[serde(default)]
     pub metrics: MetricsConfig,
+
+    /// Trace-aware routing to a sampling tier
+    #[serde(default)]
+    pub routing: RoutingConfig,
+
+    /// Exporter settings
+    #[serde(default)]
+    pub exporters: ExportersConfig,
+
+    /// Exporters used by each signal pipeline
+    #[serde(default)]
+    pub pipelines: PipelinesConfig,
 }
 
 /// Receiver configuration.
@@ -44,6 +61,38 @@ pub struct ReceiverConfig {
     /// Enable HTTP receiver
     #[serde(default = "default_true")]
     pub enable_http: bool,
+
+    /// Request compression accepted from senders
+    #[serde(default = "default_accept_compression")]
+    pub accept_compression: Vec<Compression>,
+
+    /// Prometheus remote-write receiver
+    #[serde(default)]
+    pub prometheus_remote_write: PrometheusRemoteWriteConfig,
+}
+
+/// Prometheus remote-write receiver configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrometheusRemoteWriteConfig {
+    /// Enable the receiver
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// HTTP endpoint; Prometheus posts to `/api/v1/write`
+    #[serde(default = "default_remote_write_endpoint")]
+    pub endpoint: SocketAddr,
+
+    /// Largest accepted request body after decompression, in bytes
+    #[serde(default = "default_remote_write_max_body_bytes")]
+    pub max_body_bytes: usize,
+}
+
+fn default_remote_write_endpoint() -> SocketAddr {
+    "0.0.0.0:9201".parse().unwrap()
+}
+
+fn default_remote_write_max_body_bytes() -> usize {
+    32 * 1024 * 1024 // 32 MiB
 }
 
 fn default_grpc_endpoint() -> SocketAddr {
@@ -54,36 +103,800 @@ fn default_http_endpoint() -> SocketAddr {
     "0.0.0.0:4318".parse().unwrap()
 }
 
+fn default_accept_compression() -> Vec<Compression> {
+    vec![Compression::Gzip, Compression::Zstd]
+}
+
 fn default_true() -> bool {
     true
 }
 
-/// Processor configuration.
+/// Processor configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessorConfig {
+    /// Enable PII redaction
+    #[serde(default = "default_true")]
+    pub enable_pii_redaction: bool,
+
+    /// Enable cost calculation
+    #[serde(default = "default_true")]
+    pub enable_cost_calculation: bool,
+
+    /// Estimate token usage from text when providers omit it
+    #[serde(default = "default_true")]
+    pub estimate_missing_usage: bool,
+
+    /// Relative difference between computed and gateway-reported cost
+    /// (OpenRouter, LiteLLM) beyond which spans are flagged
+    #[serde(default = "default_gateway_cost_tolerance")]
+    pub gateway_cost_tolerance: f64,
+
+    /// Enable model metadata enrichment
+    #[serde(default = "default_true")]
+    pub enable_model_enrichment: bool,
+
+    /// Enable GenAI semantic convention validation
+    #[serde(default = "default_true")]
+    pub enable_semconv_validation: bool,
+
+    /// Aggregate latency and cost histograms with trace exemplars
+    #[serde(default = "default_true")]
+    pub enable_metric_aggregation: bool,
+
+    /// Extract guardrail violations into `guardrail_events` rows
+    #[serde(default = "default_true")]
+    pub enable_guardrail_events: bool,
+
+    /// Split calls into client, provider queue and execution time
+    #[serde(default = "default_true")]
+    pub enable_queue_timing: bool,
+
+    /// How to handle spans that violate the GenAI semantic conventions
+    #[serde(default)]
+    pub semconv_strictness: SemconvStrictness,
+
+    /// Deduplication of spans resent by client retries
+    #[serde(default)]
+    pub dedup: DedupConfig,
+
+    /// Validation of spans against schemas registered per service
+    #[serde(default)]
+    pub schema_validation: SchemaValidationConfig,
+
+    /// Quarantine of spans that fail processing
+    #[serde(default)]
+    pub quarantine: QuarantineConfig,
+
+    /// Per-minute pre-aggregation of request metrics
+    #[serde(default)]
+    pub streaming_aggregation: StreamingAggregationConfig,
+
+    /// Per-organization ingestion quotas
+    #[serde(default)]
+    pub quotas: QuotaConfig,
+
+    /// Severity sampling, service routing and body truncation of logs
+    #[serde(default)]
+    pub logs: LogProcessingConfig,
+
+    /// Mining of log patterns from log bodies
+    #[serde(default)]
+    pub log_patterns: LogPatternConfig,
+
+    /// Stamping of the collector's own Kubernetes and cloud attributes
+    #[serde(default)]
+    pub resource_detection: ResourceDetectionConfig,
+
+    /// Normalization of cost-allocation tags
+    #[serde(default)]
+    pub cost_tags: CostTagConfig,
+
+    /// Cost models of self-hosted inference deployments
+    #[serde(default)]
+    pub self_hosted: SelfHostedConfig,
+
+    /// Keyed hashing of user and session identifiers before storage
+    #[serde(default)]
+    pub pseudonymization: PseudonymizationConfig,
+
+    /// Normalization of dynamic IDs out of span names
+    #[serde(default)]
+    pub span_names: SpanNameConfig,
+
+    /// Batch size for processing
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+
+    /// Batch timeout in milliseconds
+    #[serde(default = "default_batch_timeout_ms")]
+    pub batch_timeout_ms: u64,
+}
+
+fn default_gateway_cost_tolerance() -> f64 {
+    llm_observatory_providers::gateway::DEFAULT_DISCREPANCY_TOLERANCE
+}
+
+fn default_batch_size() -> usize {
+    1000
+}
+
+fn default_batch_timeout_ms() -> u64 {
+    10000 // 10 seconds
+}
+
+/// Strictness of GenAI semantic convention validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SemconvStrictness {
+    /// Record violations on the span, leave attributes untouched
+    Annotate,
+    /// Rename legacy attributes and fill gaps from span fields, then annotate
+    Fix,
+    /// Drop spans with any violation
+    Reject,
+}
+
+impl Default for SemconvStrictness {
+    fn default() -> Self {
+        Self::Fix
+    }
+}
+
+/// Ingestion deduplication configuration.
+///
+/// Spans are keyed on `(trace_id, span_id)`; a key seen again within
+/// `window_secs` is a duplicate. Keys are kept in Redis when `redis_url` is
+/// set, so all collector replicas share the window, and in memory otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupConfig {
+    /// Enable deduplication
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// How long a span key is remembered, in seconds
+    #[serde(default = "default_dedup_window_secs")]
+    pub window_secs: u64,
+
+    /// Redis URL (e.g. "redis://redis:6379/0"); in-memory when unset
+    #[serde(default)]
+    pub redis_url: Option<String>,
+
+    /// Prefix of the Redis keys
+    #[serde(default = "default_dedup_key_prefix")]
+    pub key_prefix: String,
+
+    /// Keys kept by the in-memory store; the oldest are evicted first
+    #[serde(default = "default_dedup_max_entries")]
+    pub max_entries: usize,
+
+    /// What to do with duplicates
+    #[serde(default)]
+    pub action: DuplicateAction,
+}
+
+fn default_dedup_window_secs() -> u64 {
+    300 // 5 minutes
+}
+
+fn default_dedup_key_prefix() -> String {
+    "llmobs:dedup:".to_string()
+}
+
+fn default_dedup_max_entries() -> usize {
+    100_000
+}
+
+/// Handling of duplicate spans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateAction {
+    /// Drop duplicates
+    Drop,
+    /// Forward duplicates with a `llm_observatory.duplicate` attribute
+    Annotate,
+}
+
+impl Default for DuplicateAction {
+    fn default() -> Self {
+        Self::Drop
+    }
+}
+
+/// Schema validation configuration.
+///
+/// Each span is checked against the most specific registered schema for its
+/// `service.name` and `service.version`: an exact version match first, then a
+/// schema for any version of the service, then one for all services (`*`).
+/// Spans without a matching schema pass unchecked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaValidationConfig {
+    /// Enable schema validation
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// What to do with spans that fail their schema
+    #[serde(default)]
+    pub mode: SchemaEnforcementMode,
+
+    /// Registered schemas
+    #[serde(default)]
+    pub schemas: Vec<SpanSchemaConfig>,
+
+    /// Quarantined spans buffered between drains; further spans are dropped
+    #[serde(default = "default_max_quarantined")]
+    pub max_quarantined: usize,
+}
+
+fn default_max_quarantined() -> usize {
+    10_000
+}
+
+/// Handling of spans that fail schema validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemaEnforcementMode {
+    /// Log and count violations, forward the span unchanged
+    Log,
+    /// Record violations on the span and forward it
+    Annotate,
+    /// Divert the span to the `rejected_spans` table
+    Quarantine,
+    /// Drop the span
+    Reject,
+}
+
+impl Default for SchemaEnforcementMode {
+    fn default() -> Self {
+        Self::Log
+    }
+}
+
+/// Quarantine configuration.
+///
+/// Spans whose processing fails (e.g. PII redaction errors) are kept for
+/// inspection and replay instead of being lost.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantineConfig {
+    /// Quarantine spans that fail processing; when disabled they are dropped
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Quarantined spans buffered between drains; further spans are dropped
+    #[serde(default = "default_max_quarantined")]
+    pub max_buffered: usize,
+}
+
+impl Default for QuarantineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_buffered: default_max_quarantined(),
+        }
+    }
+}
+
+/// Streaming aggregation configuration.
+///
+/// Requests are counted per minute, organization, provider and model and
+/// flushed to `llm_metrics_streaming_1min`. Organizations listed in
+/// `aggregate_only_orgs` opt out of full trace storage: their spans are
+/// dropped once aggregated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamingAggregationConfig {
+    /// Enable streaming aggregation
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Organizations whose raw spans are not stored
+    #[serde(default)]
+    pub aggregate_only_orgs: Vec<String>,
+
+    /// Seconds a minute stays open for late spans before it is flushed
+    #[serde(default = "default_allowed_lateness_secs")]
+    pub allowed_lateness_secs: u64,
+
+    /// Series held between flushes; spans of further series are forwarded
+    /// unaggregated
+    #[serde(default = "default_max_series")]
+    pub max_series: usize,
+}
+
+fn default_allowed_lateness_secs() -> u64 {
+    60
+}
+
+fn default_max_series() -> usize {
+    100_000
+}
+
+impl Default for StreamingAggregationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            aggregate_only_orgs: Vec::new(),
+            allowed_lateness_secs: default_allowed_lateness_secs(),
+            max_series: default_max_series(),
+        }
+    }
+}
+
+/// Ingestion quota configuration.
+///
+/// Spans are attributed to organizations by their `org_id` attribute and
+/// counted per UTC day. Organizations without an entry in `orgs` get the
+/// `default` limits, which are unlimited unless set; spans without an
+/// organization are never limited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaConfig {
+    /// Enable quota enforcement
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Limits of organizations not listed in `orgs`
+    #[serde(default)]
+    pub default: QuotaLimits,
+
+    /// Limits per organization ID
+    #[serde(default)]
+    pub orgs: HashMap<String, QuotaLimits>,
+
+    /// What to do with spans over quota, unless set per organization
+    #[serde(default)]
+    pub over_quota: OverQuotaAction,
+
+    /// Fraction of over-quota spans kept with [`OverQuotaAction::Sample`]
+    #[serde(default = "default_over_quota_sample_rate")]
+    pub over_quota_sample_rate: f64,
+
+    /// Spans held with [`OverQuotaAction::Queue`]; further spans are dropped
+    #[serde(default = "default_max_queued")]
+    pub max_queued: usize,
+}
+
+fn default_over_quota_sample_rate() -> f64 {
+    0.01
+}
+
+fn default_max_queued() -> usize {
+    10_000
+}
+
+impl Default for QuotaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            default: QuotaLimits::default(),
+            orgs: HashMap::new(),
+            over_quota: OverQuotaAction::default(),
+            over_quota_sample_rate: default_over_quota_sample_rate(),
+            max_queued: default_max_queued(),
+        }
+    }
+}
+
+/// Daily ingestion limits of an organization.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuotaLimits {
+    /// Spans per UTC day; unlimited when unset
+    #[serde(default)]
+    pub spans_per_day: Option<u64>,
+
+    /// Span bytes (JSON-encoded) per UTC day; unlimited when unset
+    #[serde(default)]
+    pub bytes_per_day: Option<u64>,
+
+    /// What to do with spans over quota; `over_quota` when unset
+    #[serde(default)]
+    pub over_quota: Option<OverQuotaAction>,
+}
+
+/// Handling of spans over their organization's quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverQuotaAction {
+    /// Drop the span
+    Drop,
+    /// Keep a fraction of the spans (`over_quota_sample_rate`)
+    Sample,
+    /// Hold the span until the quota resets
+    Queue,
+}
+
+impl Default for OverQuotaAction {
+    fn default() -> Self {
+        Self::Drop
+    }
+}
+
+/// Log processing configuration.
+///
+/// Each log record is routed by the `service.name` of its resource: services
+/// listed in `services` use their route, all others `default_route`. Records
+/// that are kept are then sampled by severity (severities without a rate are
+/// kept) and have string bodies truncated to `max_body_bytes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogProcessingConfig {
+    /// Enable log processing; when disabled all logs are stored
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Fraction of records kept per severity, e.g. `{"debug": 0.01}`
+    #[serde(default)]
+    pub sample_rates: HashMap<LogSeverity, f64>,
+
+    /// Route of services not listed in `services`
+    #[serde(default)]
+    pub default_route: LogRoute,
+
+    /// Route per `service.name`
+    #[serde(default)]
+    pub services: HashMap<String, LogRoute>,
+
+    /// Maximum size of string bodies in bytes; unlimited when unset
+    #[serde(default)]
+    pub max_body_bytes: Option<usize>,
+}
+
+impl Default for LogProcessingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_rates: HashMap::new(),
+            default_route: LogRoute::default(),
+            services: HashMap::new(),
+            max_body_bytes: None,
+        }
+    }
+}
+
+/// Log pattern mining configuration.
+///
+/// Log bodies are clustered into templates per organization and service,
+/// Drain-style: bodies with the same number of tokens and first token are
+/// compared position by position, and a body joins the most similar pattern
+/// if at least `similarity_threshold` of its tokens match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogPatternConfig {
+    /// Enable pattern mining
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Fraction of tokens that must match for a body to join a pattern
+    #[serde(default = "default_similarity_threshold")]
+    pub similarity_threshold: f64,
+
+    /// Patterns held; bodies matching none once reached are not tagged
+    #[serde(default = "default_max_patterns")]
+    pub max_patterns: usize,
+}
+
+fn default_similarity_threshold() -> f64 {
+    0.4
+}
+
+fn default_max_patterns() -> usize {
+    10_000
+}
+
+impl Default for LogPatternConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            similarity_threshold: default_similarity_threshold(),
+            max_patterns: default_max_patterns(),
+        }
+    }
+}
+
+/// Resource detection configuration.
+///
+/// The collector detects its own pod, namespace, node, deployment and cloud
+/// region at startup and adds them to spans and metrics that lack them. This
+/// fits collectors deployed as a sidecar or per node (DaemonSet), which share
+/// the pod or node of the clients that do not detect their resource.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceDetectionConfig {
+    /// Enable resource stamping
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Query the AWS, GCP and Azure instance metadata endpoints
+    #[serde(default = "default_true")]
+    pub cloud_metadata: bool,
+
+    /// How long each metadata endpoint may take to answer, in milliseconds
+    #[serde(default = "default_metadata_timeout_ms")]
+    pub metadata_timeout_ms: u64,
+
+    /// Replace attributes the client already sent
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+fn default_metadata_timeout_ms() -> u64 {
+    1000
+}
+
+impl Default for ResourceDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cloud_metadata: true,
+            metadata_timeout_ms: default_metadata_timeout_ms(),
+            overwrite: false,
+        }
+    }
+}
+
+/// Cost-allocation tag normalization.
+///
+/// Tags are the `cost.tag.<key>` span attributes (see
+/// [`llm_observatory_core::cost_tags`]). Keys are normalized to lowercase
+/// snake case and, when `allowed_keys` is set, dropped unless listed. Values
+/// are trimmed, optionally lowercased, then renamed with `value_mappings`,
+/// e.g. `team: {ml-platform: ml_platform, mlp: ml_platform}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostTagConfig {
+    /// Enable tag normalization
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Tag keys kept; all keys are kept when empty
+    #[serde(default)]
+    pub allowed_keys: Vec<String>,
+
+    /// Value renames per tag key, applied after trimming and lowercasing
+    #[serde(default)]
+    pub value_mappings: HashMap<String, HashMap<String, String>>,
+
+    /// Lowercase tag values
+    #[serde(default = "default_true")]
+    pub lowercase_values: bool,
+
+    /// Longest tag value kept, in characters; longer values are truncated
+    #[serde(default = "default_max_cost_tag_value_len")]
+    pub max_value_len: usize,
+}
+
+fn default_max_cost_tag_value_len() -> usize {
+    128
+}
+
+impl Default for CostTagConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allowed_keys: Vec::new(),
+            value_mappings: HashMap::new(),
+            lowercase_values: true,
+            max_value_len: default_max_cost_tag_value_len(),
+        }
+    }
+}
+
+/// Span name normalization.
+///
+/// Span names should name an operation, but clients often put dynamic IDs
+/// in them (`chat-completion-for-user-123`), so every call gets its own
+/// name and grouping by name breaks. Each `rules` entry rewrites names
+/// matching its regular expression to its template, which may refer to
+/// capture groups (`$1`, `${name}`); the first matching rule wins.
+///
+/// Names matching no rule are masked into templates by replacing segments
+/// that contain a digit with `{id}`. With `auto_detect`, a template with
+/// `max_names_per_template` distinct names is reported as high-cardinality,
+/// and with `auto_normalize` its spans are renamed to the template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpanNameConfig {
+    /// Enable span name normalization
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Rewrite rules, in match order
+    #[serde(default)]
+    pub rules: Vec<SpanNameRule>,
+
+    /// Detect templates with many distinct span names
+    #[serde(default = "default_true")]
+    pub auto_detect: bool,
+
+    /// Rename spans of high-cardinality templates to the template
+    #[serde(default)]
+    pub auto_normalize: bool,
+
+    /// Distinct names at which a template is high-cardinality
+    #[serde(default = "default_max_names_per_template")]
+    pub max_names_per_template: usize,
+
+    /// Templates tracked; names of further templates are not tracked
+    #[serde(default = "default_max_span_name_templates")]
+    pub max_templates: usize,
+}
+
+/// A span name rewrite rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpanNameRule {
+    /// Regular expression matched against the span name
+    pub pattern: String,
+    /// Replacement for the matched part, e.g. `chat-completion-for-user-{id}`
+    pub template: String,
+}
+
+fn default_max_names_per_template() -> usize {
+    100
+}
+
+fn default_max_span_name_templates() -> usize {
+    10_000
+}
+
+impl Default for SpanNameConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rules: Vec::new(),
+            auto_detect: true,
+            auto_normalize: false,
+            max_names_per_template: default_max_names_per_template(),
+            max_templates: default_max_span_name_templates(),
+        }
+    }
+}
+
+/// Self-hosted inference deployments (vLLM, Ollama, ...).
+///
+/// The cost processor prices requests to a deployment, matched by model and
+/// `server.address`, with the deployment's cost model: a GPU-hour rate
+/// amortized over its throughput, or a flat rate per 1000 tokens. The first
+/// matching deployment wins.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SelfHostedConfig {
+    /// Deployments, in match order
+    #[serde(default)]
+    pub deployments: Vec<SelfHostedDeployment>,
+}
+
+/// Pseudonymization of user and session identifiers.
+///
+/// `user_id`, `session_id` and the listed span attributes are replaced with
+/// `ps_<hex>`, an HMAC-SHA256 of the span's organization and the value under
+/// `key`, so the same user maps to the same pseudonym within an organization
+/// and analytics keep grouping by it. When `vault_redis_url` is set, each
+/// pseudonym is also written to that Redis with its original value, under
+/// its organization, for the restricted reverse lookup of the analytics API;
+/// it should not be the Redis used for caching.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PseudonymizationConfig {
+    /// Enable pseudonymization
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// HMAC key, usually a secret reference (`${env:PSEUDONYM_KEY}`)
+    #[serde(default)]
+    pub key: String,
+
+    /// Span attributes pseudonymized in addition to `user_id` and `session_id`
+    #[serde(default = "default_pseudonymized_attributes")]
+    pub attributes: Vec<String>,
+
+    /// Redis URL of the pseudonym vault; no reverse lookup when unset
+    #[serde(default)]
+    pub vault_redis_url: Option<String>,
+
+    /// Prefix of the vault keys
+    #[serde(default = "default_pseudonym_vault_key_prefix")]
+    pub vault_key_prefix: String,
+
+    /// How long a pseudonym stays resolvable after it was last seen, in days
+    #[serde(default = "default_pseudonym_vault_retention_days")]
+    pub vault_retention_days: u64,
+}
+
+fn default_pseudonymized_attributes() -> Vec<String> {
+    vec![
+        "user.id".to_string(),
+        "enduser.id".to_string(),
+        "session.id".to_string(),
+    ]
+}
+
+fn default_pseudonym_vault_key_prefix() -> String {
+    "llmobs:pseudonym:".to_string()
+}
+
+fn default_pseudonym_vault_retention_days() -> u64 {
+    400
+}
+
+impl Default for PseudonymizationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key: String::new(),
+            attributes: default_pseudonymized_attributes(),
+            vault_redis_url: None,
+            vault_key_prefix: default_pseudonym_vault_key_prefix(),
+            vault_retention_days: default_pseudonym_vault_retention_days(),
+        }
+    }
+}
+
+/// Log severity, grouping the OTLP severity numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogSeverity {
+    /// Severity numbers 1-4
+    Trace,
+    /// Severity numbers 5-8
+    Debug,
+    /// Severity numbers 9-12
+    Info,
+    /// Severity numbers 13-16
+    Warn,
+    /// Severity numbers 17-20
+    Error,
+    /// Severity numbers 21-24
+    Fatal,
+}
+
+/// Destination of a service's logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRoute {
+    /// Discard the logs
+    Drop,
+    /// Send the logs to the exporters of the `logs` pipeline
+    Store,
+    /// Send the logs only to the OTLP exporter
+    Forward,
+}
+
+impl Default for LogRoute {
+    fn default() -> Self {
+        Self::Store
+    }
+}
+
+/// A span schema registered for a service.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProcessorConfig {
-    /// Enable PII redaction
-    #[serde(default = "default_true")]
-    pub enable_pii_redaction: bool,
+pub struct SpanSchemaConfig {
+    /// `service.name` the schema applies to, or `*` for all services
+    pub service: String,
 
-    /// Enable cost calculation
-    #[serde(default = "default_true")]
-    pub enable_cost_calculation: bool,
+    /// `service.version` the schema applies to (default: all versions)
+    #[serde(default)]
+    pub version: Option<String>,
 
-    /// Batch size for processing
-    #[serde(default = "default_batch_size")]
-    pub batch_size: usize,
+    /// Span fields that must be present and non-null, e.g. `token_usage` or
+    /// `latency.ttft_ms`
+    #[serde(default)]
+    pub required_fields: Vec<String>,
 
-    /// Batch timeout in milliseconds
-    #[serde(default = "default_batch_timeout_ms")]
-    pub batch_timeout_ms: u64,
-}
+    /// Span attributes that must be present, e.g. `gen_ai.system`
+    #[serde(default)]
+    pub required_attributes: Vec<String>,
 
-fn default_batch_size() -> usize {
-    1000
+    /// Expected types of span attributes, checked when present
+    #[serde(default)]
+    pub attribute_types: HashMap<String, AttributeType>,
 }
 
-fn default_batch_timeout_ms() -> u64 {
-    10000 // 10 seconds
+/// JSON type of a span attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeType {
+    /// String value
+    String,
+    /// Integer value
+    Integer,
+    /// Any number
+    Number,
+    /// Boolean value
+    Boolean,
+    /// Array value
+    Array,
+    /// Object value
+    Object,
 }
 
 /// Sampling configuration.
@@ -140,6 +953,200 @@ impl Default for SamplingStrategy {
     }
 }
 
+/// Trace-aware routing configuration.
+///
+/// When enabled, the collector runs as a stateless routing tier: spans
+/// received over OTLP/gRPC are forwarded to the `backends` (the sampling
+/// tier), with every span of a trace sent to the same backend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingConfig {
+    /// Run as a routing tier
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// OTLP gRPC endpoints of the sampling tier (e.g. "http://sampler-0:4317")
+    #[serde(default)]
+    pub backends: Vec<String>,
+
+    /// Virtual nodes per backend on the hash ring
+    #[serde(default = "default_virtual_nodes")]
+    pub virtual_nodes: usize,
+
+    /// Timeout for forwarding to a backend, in milliseconds
+    #[serde(default = "default_routing_timeout_ms")]
+    pub timeout_ms: u64,
+
+    /// Compression of forwarded requests
+    #[serde(default = "default_routing_compression")]
+    pub compression: Compression,
+}
+
+fn default_virtual_nodes() -> usize {
+    128
+}
+
+fn default_routing_timeout_ms() -> u64 {
+    5000 // 5 seconds
+}
+
+fn default_routing_compression() -> Compression {
+    Compression::Gzip
+}
+
+/// Exporter settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportersConfig {
+    /// Forwarding to another OTLP endpoint (e.g. a central collector);
+    /// required when a pipeline uses the `otlp` exporter
+    #[serde(default)]
+    pub otlp: Option<OtlpExporterConfig>,
+}
+
+/// OTLP/gRPC forwarding exporter configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtlpExporterConfig {
+    /// Endpoint URL (e.g. "https://central-collector:4317")
+    pub endpoint: String,
+
+    /// TLS settings; `https` endpoints use the system roots when unset
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+
+    /// Headers sent with every request (e.g. authorization)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    /// Compression of exported requests
+    #[serde(default = "default_export_compression")]
+    pub compression: Compression,
+
+    /// Timeout for a single export request, in milliseconds
+    #[serde(default = "default_export_timeout_ms")]
+    pub timeout_ms: u64,
+
+    /// Export requests buffered while the endpoint is slow or down; new data
+    /// is dropped once the queue is full
+    #[serde(default = "default_export_queue_size")]
+    pub queue_size: usize,
+
+    /// Maximum spans or metric series per request
+    #[serde(default = "default_export_max_batch_size")]
+    pub max_batch_size: usize,
+
+    /// Retry policy for failed requests
+    #[serde(default)]
+    pub retry: ExportRetryConfig,
+}
+
+/// Client TLS settings. Paths point to PEM files.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// CA certificate used to verify the server, instead of the system roots
+    #[serde(default)]
+    pub ca_file: Option<String>,
+
+    /// Client certificate for mutual TLS
+    #[serde(default)]
+    pub cert_file: Option<String>,
+
+    /// Client private key for mutual TLS
+    #[serde(default)]
+    pub key_file: Option<String>,
+
+    /// Server name to verify, if different from the endpoint host
+    #[serde(default)]
+    pub domain_name: Option<String>,
+}
+
+/// Retry policy for exports.
+///
+/// Only retryable gRPC codes (`UNAVAILABLE`, `DEADLINE_EXCEEDED`,
+/// `RESOURCE_EXHAUSTED`, ...) are retried, with exponential backoff.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportRetryConfig {
+    /// Attempts per request, including the first
+    #[serde(default = "default_retry_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry, in milliseconds
+    #[serde(default = "default_retry_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+
+    /// Upper bound for the delay between retries, in milliseconds
+    #[serde(default = "default_retry_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
+fn default_export_compression() -> Compression {
+    Compression::Gzip
+}
+
+fn default_export_timeout_ms() -> u64 {
+    10_000 // 10 seconds
+}
+
+fn default_export_queue_size() -> usize {
+    1024
+}
+
+fn default_export_max_batch_size() -> usize {
+    512
+}
+
+fn default_retry_max_attempts() -> u32 {
+    5
+}
+
+fn default_retry_initial_backoff_ms() -> u64 {
+    100
+}
+
+fn default_retry_max_backoff_ms() -> u64 {
+    5000
+}
+
+/// Destination of a signal pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExporterKind {
+    /// Write to storage directly
+    Storage,
+    /// Forward to the endpoint in `exporters.otlp`
+    Otlp,
+}
+
+/// Exporters used by each signal pipeline.
+///
+/// A pipeline may list several exporters, e.g. `[storage, otlp]` to keep a
+/// local copy while forwarding to a central collector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelinesConfig {
+    /// Exporters for processed spans
+    #[serde(default = "default_pipeline_exporters")]
+    pub traces: Vec<ExporterKind>,
+
+    /// Exporters for metrics
+    #[serde(default = "default_pipeline_exporters")]
+    pub metrics: Vec<ExporterKind>,
+
+    /// Exporters for logs
+    #[serde(default = "default_pipeline_exporters")]
+    pub logs: Vec<ExporterKind>,
+}
+
+impl PipelinesConfig {
+    /// Whether any pipeline uses the given exporter.
+    pub fn uses(&self, kind: ExporterKind) -> bool {
+        [&self.traces, &self.metrics, &self.logs]
+            .iter()
+            .any(|exporters| exporters.contains(&kind))
+    }
+}
+
+fn default_pipeline_exporters() -> Vec<ExporterKind> {
+    vec![ExporterKind::Storage]
+}
+
 /// Metrics configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsConfig {
@@ -150,12 +1157,25 @@ pub struct MetricsConfig {
     /// Prometheus metrics endpoint
     #[serde(default = "default_metrics_endpoint")]
     pub prometheus_endpoint: SocketAddr,
+
+    /// Store the collector's own pipeline metrics (processor durations and
+    /// outcomes, queue waits) under the `llm-observatory-collector` service
+    #[serde(default = "default_true")]
+    pub self_telemetry: bool,
+
+    /// Interval between self-telemetry flushes in seconds
+    #[serde(default = "default_self_telemetry_interval")]
+    pub self_telemetry_interval_secs: u64,
 }
 
 fn default_metrics_endpoint() -> SocketAddr {
     "0.0.0.0:9090".parse().unwrap()
 }
 
+fn default_self_telemetry_interval() -> u64 {
+    60
+}
+
 impl Default for ReceiverConfig {
     fn default() -> Self {
         Self {
@@ -163,6 +1183,18 @@ impl Default for ReceiverConfig {
             http_endpoint: default_http_endpoint(),
             enable_grpc: true,
             enable_http: true,
+            accept_compression: default_accept_compression(),
+            prometheus_remote_write: PrometheusRemoteWriteConfig::default(),
+        }
+    }
+}
+
+impl Default for PrometheusRemoteWriteConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_remote_write_endpoint(),
+            max_body_bytes: default_remote_write_max_body_bytes(),
         }
     }
 }
@@ -172,12 +1204,56 @@ impl Default for ProcessorConfig {
         Self {
             enable_pii_redaction: true,
             enable_cost_calculation: true,
+            estimate_missing_usage: true,
+            gateway_cost_tolerance: default_gateway_cost_tolerance(),
+            enable_model_enrichment: true,
+            enable_semconv_validation: true,
+            enable_metric_aggregation: true,
+            enable_guardrail_events: true,
+            enable_queue_timing: true,
+            semconv_strictness: SemconvStrictness::default(),
+            dedup: DedupConfig::default(),
+            schema_validation: SchemaValidationConfig::default(),
+            quarantine: QuarantineConfig::default(),
+            streaming_aggregation: StreamingAggregationConfig::default(),
+            quotas: QuotaConfig::default(),
+            logs: LogProcessingConfig::default(),
+            log_patterns: LogPatternConfig::default(),
+            resource_detection: ResourceDetectionConfig::default(),
+            cost_tags: CostTagConfig::default(),
+            self_hosted: SelfHostedConfig::default(),
+            pseudonymization: PseudonymizationConfig::default(),
+            span_names: SpanNameConfig::default(),
             batch_size: default_batch_size(),
             batch_timeout_ms: default_batch_timeout_ms(),
         }
     }
 }
 
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_secs: default_dedup_window_secs(),
+            redis_url: None,
+            key_prefix: default_dedup_key_prefix(),
+            max_entries: default_dedup_max_entries(),
+            action: DuplicateAction::default(),
+        }
+    }
+}
+
+impl Default for SchemaValidationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: SchemaEnforcementMode::default(),
+            schemas: Vec::new(),
+            max_quarantined: default_max_quarantined(),
+        }
+    }
+}
+
 impl Default for SamplingConfig {
     fn default() -> Self {
         Self {
@@ -190,11 +1266,45 @@ impl Default for SamplingConfig {
     }
 }
 
+impl Default for RoutingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backends: Vec::new(),
+            virtual_nodes: default_virtual_nodes(),
+            timeout_ms: default_routing_timeout_ms(),
+            compression: default_routing_compression(),
+        }
+    }
+}
+
+impl Default for ExportRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_retry_max_attempts(),
+            initial_backoff_ms: default_retry_initial_backoff_ms(),
+            max_backoff_ms: default_retry_max_backoff_ms(),
+        }
+    }
+}
+
+impl Default for PipelinesConfig {
+    fn default() -> Self {
+        Self {
+            traces: default_pipeline_exporters(),
+            metrics: default_pipeline_exporters(),
+            logs: default_pipeline_exporters(),
+        }
+    }
+}
+
 impl Default for MetricsConfig {
     fn default() -> Self {
         Self {
             enable_prometheus: true,
             prometheus_endpoint: default_metrics_endpoint(),
+            self_telemetry: true,
+            self_telemetry_interval_secs: default_self_telemetry_interval(),
         }
     }
 }
@@ -206,26 +1316,411 @@ impl Default for CollectorConfig {
             processors: ProcessorConfig::default(),
             sampling: SamplingConfig::default(),
             metrics: MetricsConfig::default(),
+            routing: RoutingConfig::default(),
+            exporters: ExportersConfig::default(),
+            pipelines: PipelinesConfig::default(),
         }
     }
 }
 
 impl CollectorConfig {
     /// Load configuration from file.
-    pub fn from_file(path: &str) -> Result<Self, config::ConfigError> {
-        config::Config::builder()
-            .add_source(config::File::with_name(path))
-            .add_source(config::Environment::with_prefix("LLMOBS"))
+    pub fn from_file(path: &str) -> Result<Self, ::config::ConfigError> {
+        ::config::Config::builder()
+            .add_source(::config::File::with_name(path))
+            .add_source(env_source())
             .build()?
-            .try_deserialize()
+            .try_deserialize::<Self>()?
+            .resolve_secrets(&SecretResolvers::default())
+            .map_err(|e| ::config::ConfigError::Message(e.to_string()))
     }
 
     /// Load configuration from environment variables only.
-    pub fn from_env() -> Result<Self, config::ConfigError> {
-        config::Config::builder()
-            .add_source(config::Environment::with_prefix("LLMOBS"))
+    pub fn from_env() -> Result<Self, ::config::ConfigError> {
+        ::config::Config::builder()
+            .add_source(env_source())
+            .build()?
+            .try_deserialize::<Self>()?
+            .resolve_secrets(&SecretResolvers::default())
+            .map_err(|e| ::config::ConfigError::Message(e.to_string()))
+    }
+
+    /// Load and validate the configuration in layers, each overriding the
+    /// previous one:
+    ///
+    /// 1. Defaults
+    /// 2. The file at `path`, in YAML, TOML or JSON (by extension)
+    /// 3. `LLMOBS_*` environment variables, with `__` between nested keys
+    ///    (e.g. `LLMOBS_PROCESSORS__BATCH_SIZE=500`)
+    /// 4. `overrides`, as dotted keys and values (e.g. from CLI flags)
+    ///
+    /// Secret references in string values (`${env:VAR}`, `${file:/path}`,
+    /// `${vault:kv/path#key}`) are then resolved with the default
+    /// [`SecretResolvers`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::Load`] if a layer cannot be read or parsed,
+    /// [`ConfigError::Secret`] if a reference cannot be resolved, and
+    /// [`ConfigError::Invalid`] with every invalid field otherwise.
+    pub fn load(path: Option<&str>, overrides: &[(String, String)]) -> Result<Self, ConfigError> {
+        Self::load_with_secrets(path, overrides, &SecretResolvers::default())
+    }
+
+    /// Like [`load`](Self::load), resolving secret references with `secrets`.
+    pub fn load_with_secrets(
+        path: Option<&str>,
+        overrides: &[(String, String)],
+        secrets: &SecretResolvers,
+    ) -> Result<Self, ConfigError> {
+        let mut builder = ::config::Config::builder();
+        if let Some(path) = path {
+            builder = builder.add_source(::config::File::with_name(path));
+        }
+        builder = builder.add_source(env_source());
+        for (key, value) in overrides {
+            builder = builder.set_override(key.as_str(), value.as_str())?;
+        }
+
+        let config = builder
             .build()?
-            .try_deserialize()
+            .try_deserialize::<Self>()?
+            .resolve_secrets(secrets)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Replace the secret references in every string value, such as
+    /// exporter headers, with their values.
+    pub fn resolve_secrets(self, secrets: &SecretResolvers) -> Result<Self, ConfigError> {
+        Ok(secrets.resolve_config(self)?)
+    }
+
+    /// Check the configuration, reporting every invalid field at once.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut v = Validator::default();
+
+        v.check(
+            self.receiver.enable_grpc || self.receiver.enable_http,
+            "receiver",
+            "at least one of enable_grpc and enable_http must be true",
+        );
+        v.check(
+            self.receiver.prometheus_remote_write.max_body_bytes > 0,
+            "receiver.prometheus_remote_write.max_body_bytes",
+            "must be greater than 0",
+        );
+
+        let processors = &self.processors;
+        v.check(
+            processors.batch_size > 0,
+            "processors.batch_size",
+            "must be greater than 0",
+        );
+        v.check(
+            processors.batch_timeout_ms > 0,
+            "processors.batch_timeout_ms",
+            "must be greater than 0",
+        );
+        v.check(
+            processors.gateway_cost_tolerance >= 0.0,
+            "processors.gateway_cost_tolerance",
+            "must not be negative",
+        );
+        v.check(
+            processors.dedup.window_secs > 0,
+            "processors.dedup.window_secs",
+            "must be greater than 0",
+        );
+        v.check(
+            processors.dedup.max_entries > 0,
+            "processors.dedup.max_entries",
+            "must be greater than 0",
+        );
+        for (i, schema) in processors.schema_validation.schemas.iter().enumerate() {
+            v.check(
+                !schema.service.is_empty(),
+                format!("processors.schema_validation.schemas[{}].service", i),
+                "must not be empty",
+            );
+        }
+        v.fraction(
+            processors.quotas.over_quota_sample_rate,
+            "processors.quotas.over_quota_sample_rate",
+        );
+        for (severity, rate) in &processors.logs.sample_rates {
+            let field = format!("processors.logs.sample_rates.{:?}", severity).to_lowercase();
+            v.fraction(*rate, field);
+        }
+        v.fraction(
+            processors.log_patterns.similarity_threshold,
+            "processors.log_patterns.similarity_threshold",
+        );
+        v.check(
+            processors.resource_detection.metadata_timeout_ms > 0,
+            "processors.resource_detection.metadata_timeout_ms",
+            "must be greater than 0",
+        );
+        let cost_tags = &processors.cost_tags;
+        for (i, key) in cost_tags.allowed_keys.iter().enumerate() {
+            v.check(
+                normalize_cost_tag_key(key).is_some(),
+                format!("processors.cost_tags.allowed_keys[{}]", i),
+                "must contain a letter or digit",
+            );
+        }
+        for field in cost_tags.value_mappings.keys() {
+            let key = normalize_cost_tag_key(field);
+            let allowed = cost_tags.allowed_keys.is_empty()
+                || cost_tags
+                    .allowed_keys
+                    .iter()
+                    .any(|allowed| normalize_cost_tag_key(allowed) == key);
+            v.check(
+                allowed,
+                format!("processors.cost_tags.value_mappings.{}", field),
+                "must map a key listed in allowed_keys",
+            );
+        }
+        v.check(
+            cost_tags.max_value_len > 0,
+            "processors.cost_tags.max_value_len",
+            "must be greater than 0",
+        );
+        for (i, deployment) in processors.self_hosted.deployments.iter().enumerate() {
+            let field = format!("processors.self_hosted.deployments[{}]", i);
+            v.check(
+                !deployment.name.is_empty(),
+                format!("{}.name", field),
+                "must not be empty",
+            );
+            v.check(
+                !deployment.models.is_empty() || !deployment.server_addresses.is_empty(),
+                &field,
+                "must list models or server_addresses",
+            );
+            match &deployment.cost_model {
+                SelfHostedCostModel::GpuHour {
+                    gpu_hour_cost_usd,
+                    gpu_count,
+                    completion_tokens_per_second,
+                    prompt_tokens_per_second,
+                    utilization,
+                } => {
+                    v.check(
+                        *gpu_hour_cost_usd >= 0.0,
+                        format!("{}.cost_model.gpu_hour_cost_usd", field),
+                        "must not be negative",
+                    );
+                    v.check(
+                        *gpu_count > 0,
+                        format!("{}.cost_model.gpu_count", field),
+                        "must be greater than 0",
+                    );
+                    v.check(
+                        *completion_tokens_per_second > 0.0,
+                        format!("{}.cost_model.completion_tokens_per_second", field),
+                        "must be greater than 0",
+                    );
+                    v.check(
+                        !matches!(prompt_tokens_per_second, Some(tps) if *tps <= 0.0),
+                        format!("{}.cost_model.prompt_tokens_per_second", field),
+                        "must be greater than 0",
+                    );
+                    v.check(
+                        *utilization > 0.0 && *utilization <= 1.0,
+                        format!("{}.cost_model.utilization", field),
+                        "must be greater than 0.0 and at most 1.0",
+                    );
+                }
+                SelfHostedCostModel::PerToken {
+                    prompt_cost_per_1k,
+                    completion_cost_per_1k,
+                } => {
+                    v.check(
+                        *prompt_cost_per_1k >= 0.0 && *completion_cost_per_1k >= 0.0,
+                        format!("{}.cost_model", field),
+                        "prices must not be negative",
+                    );
+                }
+            }
+        }
+        let pseudonymization = &processors.pseudonymization;
+        if pseudonymization.enabled {
+            v.check(
+                pseudonymization.key.len() >= 32,
+                "processors.pseudonymization.key",
+                "must be at least 32 bytes",
+            );
+            v.check(
+                pseudonymization.vault_retention_days > 0,
+                "processors.pseudonymization.vault_retention_days",
+                "must be greater than 0",
+            );
+        }
+        let span_names = &processors.span_names;
+        for (i, rule) in span_names.rules.iter().enumerate() {
+            v.check(
+                regex::Regex::new(&rule.pattern).is_ok(),
+                format!("processors.span_names.rules[{}].pattern", i),
+                "must be a valid regular expression",
+            );
+        }
+        v.check(
+            span_names.max_names_per_template > 1,
+            "processors.span_names.max_names_per_template",
+            "must be greater than 1",
+        );
+
+        v.check(
+            self.metrics.self_telemetry_interval_secs > 0,
+            "metrics.self_telemetry_interval_secs",
+            "must be greater than 0",
+        );
+
+        v.fraction(
+            self.sampling.head_sampling_rate,
+            "sampling.head_sampling_rate",
+        );
+        v.check(
+            self.sampling.expensive_request_threshold_usd >= 0.0,
+            "sampling.expensive_request_threshold_usd",
+            "must not be negative",
+        );
+
+        if self.routing.enabled {
+            v.check(
+                !self.routing.backends.is_empty(),
+                "routing.backends",
+                "must list at least one backend when routing is enabled",
+            );
+        }
+        v.check(
+            self.routing.virtual_nodes > 0,
+            "routing.virtual_nodes",
+            "must be greater than 0",
+        );
+
+        match &self.exporters.otlp {
+            Some(otlp) => {
+                v.check(
+                    !otlp.endpoint.is_empty(),
+                    "exporters.otlp.endpoint",
+                    "must not be empty",
+                );
+                v.check(
+                    otlp.timeout_ms > 0,
+                    "exporters.otlp.timeout_ms",
+                    "must be greater than 0",
+                );
+                v.check(
+                    otlp.queue_size > 0,
+                    "exporters.otlp.queue_size",
+                    "must be greater than 0",
+                );
+                v.check(
+                    otlp.max_batch_size > 0,
+                    "exporters.otlp.max_batch_size",
+                    "must be greater than 0",
+                );
+                v.check(
+                    otlp.retry.max_attempts > 0,
+                    "exporters.otlp.retry.max_attempts",
+                    "must be greater than 0",
+                );
+                v.check(
+                    otlp.retry.initial_backoff_ms <= otlp.retry.max_backoff_ms,
+                    "exporters.otlp.retry.initial_backoff_ms",
+                    "must not exceed max_backoff_ms",
+                );
+                if let Some(tls) = &otlp.tls {
+                    v.check(
+                        tls.cert_file.is_some() == tls.key_file.is_some(),
+                        "exporters.otlp.tls",
+                        "cert_file and key_file must be set together",
+                    );
+                }
+            }
+            None => v.check(
+                !self.pipelines.uses(ExporterKind::Otlp),
+                "exporters.otlp",
+                "must be configured when a pipeline uses the otlp exporter",
+            ),
+        }
+
+        v.finish()
+    }
+}
+
+/// `LLMOBS_*` environment variables, e.g. `LLMOBS_SAMPLING__HEAD_SAMPLING_RATE`
+/// for `sampling.head_sampling_rate`.
+fn env_source() -> ::config::Environment {
+    ::config::Environment::with_prefix("LLMOBS")
+        .prefix_separator("_")
+        .separator("__")
+}
+
+/// A configuration field that failed validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// Dotted path of the field, e.g. `processors.batch_size`
+    pub field: String,
+    /// What is wrong with it
+    pub message: String,
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Error loading or validating the collector configuration.
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    /// A layer could not be read or parsed
+    #[error("failed to load configuration: {0}")]
+    Load(#[from] ::config::ConfigError),
+
+    /// A secret reference could not be resolved
+    #[error("failed to resolve secret: {0}")]
+    Secret(#[from] llm_observatory_core::Error),
+
+    /// Fields with invalid values, all of them
+    #[error("invalid configuration:{}", .0.iter().map(|e| format!("\n  - {}", e)).collect::<String>())]
+    Invalid(Vec<FieldError>),
+}
+
+/// Collects field errors during [`CollectorConfig::validate`].
+#[derive(Default)]
+struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    fn check(&mut self, ok: bool, field: impl Into<String>, message: &str) {
+        if !ok {
+            self.errors.push(FieldError {
+                field: field.into(),
+                message: message.to_string(),
+            });
+        }
+    }
+
+    fn fraction(&mut self, value: f64, field: impl Into<String>) {
+        self.check(
+            (0.0..=1.0).contains(&value),
+            field,
+            "must be between 0.0 and 1.0",
+        );
+    }
+
+    fn finish(self) -> Result<(), ConfigError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(self.errors))
+        }
     }
 }
 
@@ -238,10 +1733,371 @@ mod tests {
         let config = CollectorConfig::default();
         assert!(config.receiver.enable_grpc);
         assert!(config.receiver.enable_http);
+        assert!(!config.receiver.prometheus_remote_write.enabled);
         assert!(config.processors.enable_pii_redaction);
+        assert_eq!(config.processors.semconv_strictness, SemconvStrictness::Fix);
         assert_eq!(config.sampling.strategy, SamplingStrategy::Both);
     }
 
+    #[test]
+    fn test_routing_config_serde() {
+        let json = r#"{"enabled":true,"backends":["http://sampler-0:4317","http://sampler-1:4317"]}"#;
+        let config: RoutingConfig = serde_json::from_str(json).unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.backends.len(), 2);
+        assert_eq!(config.virtual_nodes, 128);
+        assert_eq!(config.compression, Compression::Gzip);
+        assert!(!CollectorConfig::default().routing.enabled);
+    }
+
+    #[test]
+    fn test_pipelines_config_serde() {
+        let json = r#"{
+            "exporters": {"otlp": {"endpoint": "https://central:4317", "headers": {"x-api-key": "secret"}}},
+            "pipelines": {"traces": ["storage", "otlp"], "metrics": ["otlp"]}
+        }"#;
+        let config: CollectorConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.pipelines.traces, vec![ExporterKind::Storage, ExporterKind::Otlp]);
+        assert_eq!(config.pipelines.logs, vec![ExporterKind::Storage]);
+        assert!(config.pipelines.uses(ExporterKind::Otlp));
+
+        let otlp = config.exporters.otlp.unwrap();
+        assert_eq!(otlp.compression, Compression::Gzip);
+        assert_eq!(otlp.retry.max_attempts, 5);
+        assert!(otlp.tls.is_none());
+        assert!(!CollectorConfig::default().pipelines.uses(ExporterKind::Otlp));
+    }
+
+    #[test]
+    fn test_dedup_config_serde() {
+        let json = r#"{"processors": {"dedup": {"enabled": true, "action": "annotate"}}}"#;
+        let config: CollectorConfig = serde_json::from_str(json).unwrap();
+        let dedup = config.processors.dedup;
+        assert!(dedup.enabled);
+        assert_eq!(dedup.action, DuplicateAction::Annotate);
+        assert_eq!(dedup.window_secs, 300);
+        assert!(dedup.redis_url.is_none());
+        assert!(!CollectorConfig::default().processors.dedup.enabled);
+    }
+
+    #[test]
+    fn test_schema_validation_config_serde() {
+        let json = r#"{"processors": {"schema_validation": {
+            "enabled": true,
+            "mode": "quarantine",
+            "schemas": [{
+                "service": "checkout-api",
+                "version": "2.1.0",
+                "required_attributes": ["gen_ai.system"],
+                "attribute_types": {"gen_ai.usage.input_tokens": "integer"}
+            }]
+        }}}"#;
+        let config: CollectorConfig = serde_json::from_str(json).unwrap();
+        let validation = config.processors.schema_validation;
+        assert!(validation.enabled);
+        assert_eq!(validation.mode, SchemaEnforcementMode::Quarantine);
+        assert_eq!(validation.schemas[0].version.as_deref(), Some("2.1.0"));
+        assert_eq!(
+            validation.schemas[0].attribute_types["gen_ai.usage.input_tokens"],
+            AttributeType::Integer
+        );
+        assert!(validation.schemas[0].required_fields.is_empty());
+        assert_eq!(
+            CollectorConfig::default().processors.schema_validation.mode,
+            SchemaEnforcementMode::Log
+        );
+    }
+
+    #[test]
+    fn test_streaming_aggregation_config_serde() {
+        let json = r#"{"processors": {"streaming_aggregation": {
+            "enabled": true,
+            "aggregate_only_orgs": ["org-bulk"]
+        }}}"#;
+        let config: CollectorConfig = serde_json::from_str(json).unwrap();
+        let aggregation = config.processors.streaming_aggregation;
+        assert!(aggregation.enabled);
+        assert_eq!(aggregation.aggregate_only_orgs, vec!["org-bulk"]);
+        assert_eq!(aggregation.allowed_lateness_secs, 60);
+        assert_eq!(aggregation.max_series, 100_000);
+        assert!(!CollectorConfig::default().processors.streaming_aggregation.enabled);
+    }
+
+    #[test]
+    fn test_quota_config_serde() {
+        let json = r#"{"processors": {"quotas": {
+            "enabled": true,
+            "default": {"spans_per_day": 1000000},
+            "orgs": {"org-free": {"spans_per_day": 10000, "bytes_per_day": 50000000, "over_quota": "queue"}},
+            "over_quota": "sample"
+        }}}"#;
+        let config: CollectorConfig = serde_json::from_str(json).unwrap();
+        let quotas = config.processors.quotas;
+        assert!(quotas.enabled);
+        assert_eq!(quotas.default.spans_per_day, Some(1_000_000));
+        assert_eq!(quotas.default.bytes_per_day, None);
+        assert_eq!(quotas.orgs["org-free"].over_quota, Some(OverQuotaAction::Queue));
+        assert_eq!(quotas.over_quota, OverQuotaAction::Sample);
+        assert_eq!(quotas.over_quota_sample_rate, 0.01);
+        assert_eq!(quotas.max_queued, 10_000);
+        assert!(!CollectorConfig::default().processors.quotas.enabled);
+    }
+
+    #[test]
+    fn test_log_processing_config_serde() {
+        let json = r#"{"processors": {"logs": {
+            "enabled": true,
+            "sample_rates": {"debug": 0.01, "trace": 0.0},
+            "services": {"noisy-batch-job": "drop", "billing": "forward"},
+            "max_body_bytes": 16384
+        }}}"#;
+        let config: CollectorConfig = serde_json::from_str(json).unwrap();
+        let logs = config.processors.logs;
+        assert!(logs.enabled);
+        assert_eq!(logs.sample_rates[&LogSeverity::Debug], 0.01);
+        assert_eq!(logs.sample_rates[&LogSeverity::Trace], 0.0);
+        assert_eq!(logs.default_route, LogRoute::Store);
+        assert_eq!(logs.services["noisy-batch-job"], LogRoute::Drop);
+        assert_eq!(logs.services["billing"], LogRoute::Forward);
+        assert_eq!(logs.max_body_bytes, Some(16384));
+        assert!(!CollectorConfig::default().processors.logs.enabled);
+    }
+
+    #[test]
+    fn test_log_pattern_config_serde() {
+        let json = r#"{"processors": {"log_patterns": {"enabled": true, "max_patterns": 500}}}"#;
+        let config: CollectorConfig = serde_json::from_str(json).unwrap();
+        let patterns = config.processors.log_patterns;
+        assert!(patterns.enabled);
+        assert_eq!(patterns.similarity_threshold, 0.4);
+        assert_eq!(patterns.max_patterns, 500);
+        assert!(!CollectorConfig::default().processors.log_patterns.enabled);
+    }
+
+    #[test]
+    fn test_resource_detection_config_serde() {
+        let json = r#"{"processors": {"resource_detection": {"enabled": true}}}"#;
+        let config: CollectorConfig = serde_json::from_str(json).unwrap();
+        let detection = config.processors.resource_detection;
+        assert!(detection.enabled);
+        assert!(detection.cloud_metadata);
+        assert_eq!(detection.metadata_timeout_ms, 1000);
+        assert!(!detection.overwrite);
+        assert!(!ResourceDetectionConfig::default().enabled);
+    }
+
+    #[test]
+    fn test_cost_tag_config_serde() {
+        let json = r#"{"processors": {"cost_tags": {
+            "enabled": true,
+            "allowed_keys": ["team", "product"],
+            "value_mappings": {"team": {"mlp": "ml_platform"}}
+        }}}"#;
+        let config: CollectorConfig = serde_json::from_str(json).unwrap();
+        let tags = &config.processors.cost_tags;
+        assert!(tags.enabled);
+        assert_eq!(tags.allowed_keys, vec!["team", "product"]);
+        assert_eq!(tags.value_mappings["team"]["mlp"], "ml_platform");
+        assert!(tags.lowercase_values);
+        assert_eq!(tags.max_value_len, 128);
+        assert!(config.validate().is_ok());
+
+        let mut config = config;
+        config
+            .processors
+            .cost_tags
+            .value_mappings
+            .insert("region".to_string(), HashMap::new());
+        let Err(ConfigError::Invalid(errors)) = config.validate() else {
+            panic!("expected validation errors");
+        };
+        let field = &errors[0].field;
+        assert_eq!(field, "processors.cost_tags.value_mappings.region");
+    }
+
+    #[test]
+    fn test_self_hosted_config_serde() {
+        let json = r#"{"processors": {"self_hosted": {"deployments": [{
+            "name": "llama-70b",
+            "models": ["meta-llama/Llama-3.1-70B-Instruct"],
+            "cost_model": {
+                "type": "gpu_hour",
+                "gpu_hour_cost_usd": 2.5,
+                "completion_tokens_per_second": 1500
+            }
+        }]}}}"#;
+        let mut config: CollectorConfig = serde_json::from_str(json).unwrap();
+        let deployment = &config.processors.self_hosted.deployments[0];
+        let SelfHostedCostModel::GpuHour {
+            gpu_count,
+            utilization,
+            ..
+        } = deployment.cost_model
+        else {
+            panic!("expected a GPU-hour cost model");
+        };
+        assert_eq!(gpu_count, 1);
+        assert_eq!(utilization, 1.0);
+        assert!(config.validate().is_ok());
+
+        config.processors.self_hosted.deployments[0].models.clear();
+        let Err(ConfigError::Invalid(errors)) = config.validate() else {
+            panic!("expected validation errors");
+        };
+        assert_eq!(errors[0].field, "processors.self_hosted.deployments[0]");
+    }
+
+    #[test]
+    fn test_pseudonymization_config_serde() {
+        let json = r#"{"processors": {"pseudonymization": {"enabled": true, "key": "short"}}}"#;
+        let mut config: CollectorConfig = serde_json::from_str(json).unwrap();
+        let pseudonymization = &config.processors.pseudonymization;
+        assert_eq!(
+            pseudonymization.attributes,
+            vec!["user.id", "enduser.id", "session.id"]
+        );
+        assert_eq!(pseudonymization.vault_key_prefix, "llmobs:pseudonym:");
+        assert!(pseudonymization.vault_redis_url.is_none());
+
+        let Err(ConfigError::Invalid(errors)) = config.validate() else {
+            panic!("expected validation errors");
+        };
+        assert_eq!(errors[0].field, "processors.pseudonymization.key");
+
+        config.processors.pseudonymization.key = "k".repeat(32);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_span_name_config_serde() {
+        let json = r#"{"processors": {"span_names": {
+            "enabled": true,
+            "rules": [{"pattern": "^chat-completion-for-user-.*$", "template": "chat-completion"}]
+        }}}"#;
+        let config: CollectorConfig = serde_json::from_str(json).unwrap();
+        let span_names = &config.processors.span_names;
+        assert!(span_names.enabled);
+        assert_eq!(span_names.rules[0].template, "chat-completion");
+        assert!(span_names.auto_detect);
+        assert!(!span_names.auto_normalize);
+        assert_eq!(span_names.max_names_per_template, 100);
+        assert!(config.validate().is_ok());
+
+        let mut config = config;
+        config.processors.span_names.rules[0].pattern = "user-(".to_string();
+        let Err(ConfigError::Invalid(errors)) = config.validate() else {
+            panic!("expected validation errors");
+        };
+        assert_eq!(errors[0].field, "processors.span_names.rules[0].pattern");
+    }
+
+    #[test]
+    fn test_validate_reports_every_field() {
+        assert!(CollectorConfig::default().validate().is_ok());
+
+        let mut config = CollectorConfig::default();
+        config.sampling.head_sampling_rate = 1.5;
+        config.processors.batch_size = 0;
+        config.routing.enabled = true;
+        config.pipelines.traces.push(ExporterKind::Otlp);
+
+        let Err(ConfigError::Invalid(errors)) = config.validate() else {
+            panic!("expected validation errors");
+        };
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "processors.batch_size",
+                "sampling.head_sampling_rate",
+                "routing.backends",
+                "exporters.otlp",
+            ]
+        );
+
+        let message = ConfigError::Invalid(errors).to_string();
+        assert!(message.contains("\n  - sampling.head_sampling_rate: must be between 0.0 and 1.0"));
+    }
+
+    #[test]
+    fn test_layered_load() {
+        let dir = std::env::temp_dir().join(format!("llmobs-config-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let yaml = dir.join("collector.yaml");
+        std::fs::write(
+            &yaml,
+            "processors:\n  batch_size: 200\n  batch_timeout_ms: 500\nsampling:\n  head_sampling_rate: 0.5\n",
+        )
+        .unwrap();
+        let toml = dir.join("collector.toml");
+        std::fs::write(&toml, "[processors]\nbatch_size = 300\n").unwrap();
+
+        // File over defaults
+        let config = CollectorConfig::load(Some(yaml.to_str().unwrap()), &[]).unwrap();
+        assert_eq!(config.processors.batch_size, 200);
+        assert_eq!(config.sampling.head_sampling_rate, 0.5);
+        assert!(config.processors.enable_pii_redaction);
+        let config = CollectorConfig::load(Some(toml.to_str().unwrap()), &[]).unwrap();
+        assert_eq!(config.processors.batch_size, 300);
+
+        // Environment over file, overrides over environment
+        std::env::set_var("LLMOBS_PROCESSORS__BATCH_TIMEOUT_MS", "750");
+        let overrides = vec![("processors.batch_size".to_string(), "400".to_string())];
+        let config = CollectorConfig::load(Some(yaml.to_str().unwrap()), &overrides).unwrap();
+        std::env::remove_var("LLMOBS_PROCESSORS__BATCH_TIMEOUT_MS");
+        assert_eq!(config.processors.batch_timeout_ms, 750);
+        assert_eq!(config.processors.batch_size, 400);
+
+        // Invalid values fail validation
+        let overrides = vec![("sampling.head_sampling_rate".to_string(), "2".to_string())];
+        assert!(matches!(
+            CollectorConfig::load(Some(yaml.to_str().unwrap()), &overrides),
+            Err(ConfigError::Invalid(_))
+        ));
+        assert!(matches!(
+            CollectorConfig::load(Some(dir.join("missing.yaml").to_str().unwrap()), &[]),
+            Err(ConfigError::Load(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_secret_references() {
+        let dir = std::env::temp_dir().join(format!("llmobs-secrets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let token = dir.join("token");
+        std::fs::write(&token, "t0ken\n").unwrap();
+        let yaml = dir.join("collector.yaml");
+        std::fs::write(
+            &yaml,
+            format!(
+                "exporters:\n  otlp:\n    endpoint: https://central:4317\n    headers:\n      authorization: \"Bearer ${{file:{}}}\"\n",
+                token.display()
+            ),
+        )
+        .unwrap();
+
+        let config = CollectorConfig::load(Some(yaml.to_str().unwrap()), &[]).unwrap();
+        let otlp = config.exporters.otlp.unwrap();
+        assert_eq!(otlp.headers["authorization"], "Bearer t0ken");
+
+        let overrides = vec![(
+            "exporters.otlp.headers.authorization".to_string(),
+            "${vault:kv/collector#token}".to_string(),
+        )];
+        let err = CollectorConfig::load_with_secrets(
+            Some(yaml.to_str().unwrap()),
+            &overrides,
+            &SecretResolvers::empty(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ConfigError::Secret(_)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_sampling_strategy_serde() {
         let json = r#"{"strategy":"head","head_sampling_rate":0.1,"always_sample_errors":true,"slow_request_threshold_ms":1000,"expensive_request_threshold_usd":0.5}"#;
@@ -0,0 +1,34 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Exporters that send processed telemetry out of the collector.
+//!
+//! Which exporters a signal goes to is chosen per pipeline in
+//! [`PipelinesConfig`](crate::config::PipelinesConfig).
+
+pub mod otlp;
+
+use crate::metric::MetricSeries;
+use async_trait::async_trait;
+use llm_observatory_core::{span::LlmSpan, Result};
+use opentelemetry_proto::tonic::logs::v1::ResourceLogs;
+
+/// Trait for exporters.
+#[async_trait]
+pub trait Exporter: Send + Sync {
+    /// Export processed spans.
+    async fn export_spans(&self, spans: Vec<LlmSpan>) -> Result<()>;
+
+    /// Export metrics.
+    async fn export_metrics(&self, metrics: Vec<MetricSeries>) -> Result<()>;
+
+    /// Export logs. Logs keep their OTLP form; see
+    /// [`LogProcessor`](crate::processor::logs::LogProcessor).
+    async fn export_logs(&self, logs: Vec<ResourceLogs>) -> Result<()>;
+
+    /// Flush pending data and stop.
+    async fn shutdown(&self) -> Result<()>;
+
+    /// Get exporter name.
+    fn name(&self) -> &str;
+}
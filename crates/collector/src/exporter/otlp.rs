@@ -0,0 +1,1048 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! OTLP/gRPC exporter for collector-to-collector forwarding.
+//!
+//! Edge collectors process telemetry close to the application (PII
+//! redaction, cost calculation, sampling) and forward the result to a central
+//! collector over OTLP. Exports are queued and sent by a background task, so
+//! a slow or unreachable endpoint does not block the pipeline: failed
+//! requests are retried with exponential backoff, and new data is dropped
+//! once the queue is full.
+
+use super::Exporter;
+use crate::config::{ExportRetryConfig, OtlpExporterConfig};
+use crate::metric::{MetricDataPoint, MetricSeries, MetricType};
+use crate::processor::metrics::Exemplar;
+use crate::processor::semconv::{
+    GEN_AI_REQUEST_MODEL, GEN_AI_SYSTEM, GEN_AI_USAGE_INPUT_TOKENS, GEN_AI_USAGE_OUTPUT_TOKENS,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use llm_observatory_core::span::{LlmSpan, SpanStatus};
+use llm_observatory_core::{Error, Result};
+use opentelemetry_proto::tonic::collector::logs::v1::{
+    logs_service_client::LogsServiceClient, ExportLogsServiceRequest,
+};
+use opentelemetry_proto::tonic::collector::metrics::v1::{
+    metrics_service_client::MetricsServiceClient, ExportMetricsServiceRequest,
+};
+use opentelemetry_proto::tonic::collector::trace::v1::{
+    trace_service_client::TraceServiceClient, ExportTraceServiceRequest,
+};
+use opentelemetry_proto::tonic::common::v1::{
+    any_value, AnyValue, ArrayValue, InstrumentationScope, KeyValue, KeyValueList,
+};
+use opentelemetry_proto::tonic::logs::v1::ResourceLogs;
+use opentelemetry_proto::tonic::metrics::v1::{
+    exemplar, metric, number_data_point, summary_data_point, AggregationTemporality, Gauge,
+    Histogram, HistogramDataPoint, Metric, NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum,
+    Summary, SummaryDataPoint,
+};
+use opentelemetry_proto::tonic::resource::v1::Resource;
+use opentelemetry_proto::tonic::trace::v1::{span, status, ResourceSpans, ScopeSpans, Span, Status};
+use rand::Rng;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use tonic::{Code, Extensions, Request};
+
+/// Service name used when a span or metric does not carry one.
+const UNKNOWN_SERVICE: &str = "unknown_service";
+
+/// How long shutdown waits for queued exports to drain.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Exporter that forwards telemetry to another OTLP/gRPC endpoint.
+pub struct OtlpExporter {
+    endpoint: String,
+    max_batch_size: usize,
+    queue: Mutex<Option<mpsc::Sender<Batch>>>,
+    worker: tokio::sync::Mutex<Option<JoinHandle<()>>>,
+}
+
+/// An export request waiting in the queue.
+#[derive(Debug, Clone)]
+enum Payload {
+    Traces(ExportTraceServiceRequest),
+    Metrics(ExportMetricsServiceRequest),
+    Logs(ExportLogsServiceRequest),
+}
+
+impl Payload {
+    /// Signal name used as a metric label.
+    fn signal(&self) -> &'static str {
+        match self {
+            Payload::Traces(_) => "traces",
+            Payload::Metrics(_) => "metrics",
+            Payload::Logs(_) => "logs",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Batch {
+    payload: Payload,
+    /// Spans, data points or log records in the payload
+    items: usize,
+}
+
+impl OtlpExporter {
+    /// Create an exporter and start its send task.
+    ///
+    /// The connection is established lazily, so an endpoint that is not yet
+    /// up does not prevent the collector from starting. Must be called
+    /// within a Tokio runtime.
+    pub fn new(config: &OtlpExporterConfig) -> Result<Self> {
+        let channel = channel(config)?;
+        let metadata = metadata(&config.headers)?;
+
+        let mut traces = TraceServiceClient::new(channel.clone());
+        let mut metrics = MetricsServiceClient::new(channel.clone());
+        let mut logs = LogsServiceClient::new(channel);
+        if let Some(encoding) = config.compression.encoding() {
+            traces = traces.send_compressed(encoding);
+            metrics = metrics.send_compressed(encoding);
+            logs = logs.send_compressed(encoding);
+        }
+
+        let (tx, rx) = mpsc::channel(config.queue_size.max(1));
+        let worker = Worker {
+            traces,
+            metrics,
+            logs,
+            metadata,
+            retry: config.retry.clone(),
+        };
+
+        Ok(Self {
+            endpoint: config.endpoint.clone(),
+            max_batch_size: config.max_batch_size.max(1),
+            queue: Mutex::new(Some(tx)),
+            worker: tokio::sync::Mutex::new(Some(tokio::spawn(worker.run(rx)))),
+        })
+    }
+
+    /// Endpoint data is forwarded to.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Queue a request without waiting for it to be sent.
+    fn enqueue(&self, payload: Payload, items: usize) -> Result<()> {
+        if items == 0 {
+            return Ok(());
+        }
+
+        let signal = payload.signal();
+        let queue = self.queue.lock().unwrap().clone();
+        let Some(queue) = queue else {
+            return Err(Error::internal("OTLP exporter has been shut down"));
+        };
+
+        queue.try_send(Batch { payload, items }).map_err(|e| {
+            metrics::counter!("collector_export_dropped_total", "signal" => signal)
+                .increment(items as u64);
+            match e {
+                mpsc::error::TrySendError::Full(_) => Error::OpenTelemetry(format!(
+                    "OTLP export queue full, dropped {} {}",
+                    items, signal
+                )),
+                mpsc::error::TrySendError::Closed(_) => {
+                    Error::internal("OTLP export task has stopped")
+                }
+            }
+        })
+    }
+}
+
+impl std::fmt::Debug for OtlpExporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OtlpExporter")
+            .field("endpoint", &self.endpoint)
+            .field("max_batch_size", &self.max_batch_size)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Exporter for OtlpExporter {
+    async fn export_spans(&self, spans: Vec<LlmSpan>) -> Result<()> {
+        for chunk in spans.chunks(self.max_batch_size) {
+            let request = spans_to_request(chunk);
+            let items = request
+                .resource_spans
+                .iter()
+                .flat_map(|rs| &rs.scope_spans)
+                .map(|ss| ss.spans.len())
+                .sum();
+            self.enqueue(Payload::Traces(request), items)?;
+        }
+        Ok(())
+    }
+
+    async fn export_metrics(&self, metrics: Vec<MetricSeries>) -> Result<()> {
+        for chunk in metrics.chunks(self.max_batch_size) {
+            let items = chunk.iter().map(|series| series.data_points.len()).sum();
+            self.enqueue(Payload::Metrics(metrics_to_request(chunk)), items)?;
+        }
+        Ok(())
+    }
+
+    async fn export_logs(&self, logs: Vec<ResourceLogs>) -> Result<()> {
+        let items = logs
+            .iter()
+            .flat_map(|rl| &rl.scope_logs)
+            .map(|sl| sl.log_records.len())
+            .sum();
+        self.enqueue(
+            Payload::Logs(ExportLogsServiceRequest { resource_logs: logs }),
+            items,
+        )
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        // Dropping the sender lets the worker drain the queue and exit
+        self.queue.lock().unwrap().take();
+
+        let Some(worker) = self.worker.lock().await.take() else {
+            return Ok(());
+        };
+        match tokio::time::timeout(SHUTDOWN_TIMEOUT, worker).await {
+            Ok(result) => result.map_err(|e| Error::internal(format!("OTLP export task failed: {}", e))),
+            Err(_) => {
+                tracing::warn!(endpoint = %self.endpoint, "Timed out draining OTLP export queue");
+                Ok(())
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "otlp"
+    }
+}
+
+/// Background task sending queued requests.
+struct Worker {
+    traces: TraceServiceClient<Channel>,
+    metrics: MetricsServiceClient<Channel>,
+    logs: LogsServiceClient<Channel>,
+    metadata: MetadataMap,
+    retry: ExportRetryConfig,
+}
+
+impl Worker {
+    async fn run(mut self, mut rx: mpsc::Receiver<Batch>) {
+        while let Some(batch) = rx.recv().await {
+            let signal = batch.payload.signal();
+            match self.send_with_retry(batch.payload).await {
+                Ok(rejected) => {
+                    let rejected = rejected.min(batch.items);
+                    if rejected > 0 {
+                        tracing::warn!(signal, rejected, "OTLP endpoint rejected part of an export");
+                        metrics::counter!("collector_export_failures_total", "signal" => signal)
+                            .increment(rejected as u64);
+                    }
+                    metrics::counter!("collector_exported_items_total", "signal" => signal)
+                        .increment((batch.items - rejected) as u64);
+                }
+                Err(status) => {
+                    tracing::warn!(signal, items = batch.items, error = %status, "OTLP export failed");
+                    metrics::counter!("collector_export_failures_total", "signal" => signal)
+                        .increment(batch.items as u64);
+                }
+            }
+        }
+    }
+
+    /// Send a request, retrying retryable failures. Returns the number of
+    /// items the endpoint rejected.
+    async fn send_with_retry(&mut self, payload: Payload) -> std::result::Result<usize, tonic::Status> {
+        let mut attempt = 1;
+        loop {
+            match self.send(payload.clone()).await {
+                Err(status)
+                    if attempt < self.retry.max_attempts && is_retryable(status.code()) =>
+                {
+                    let delay = backoff(&self.retry, attempt);
+                    tracing::debug!(
+                        signal = payload.signal(),
+                        attempt,
+                        error = %status,
+                        "Retrying OTLP export in {:?}",
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send(&mut self, payload: Payload) -> std::result::Result<usize, tonic::Status> {
+        match payload {
+            Payload::Traces(message) => {
+                let request = self.request(message);
+                let response = self.traces.export(request).await?.into_inner();
+                Ok(response
+                    .partial_success
+                    .map_or(0, |partial| partial.rejected_spans.max(0) as usize))
+            }
+            Payload::Metrics(message) => {
+                let request = self.request(message);
+                let response = self.metrics.export(request).await?.into_inner();
+                Ok(response
+                    .partial_success
+                    .map_or(0, |partial| partial.rejected_data_points.max(0) as usize))
+            }
+            Payload::Logs(message) => {
+                let request = self.request(message);
+                let response = self.logs.export(request).await?.into_inner();
+                Ok(response
+                    .partial_success
+                    .map_or(0, |partial| partial.rejected_log_records.max(0) as usize))
+            }
+        }
+    }
+
+    fn request<T>(&self, message: T) -> Request<T> {
+        Request::from_parts(self.metadata.clone(), Extensions::default(), message)
+    }
+}
+
+/// Whether a failed export may succeed if sent again, per the OTLP spec.
+fn is_retryable(code: Code) -> bool {
+    matches!(
+        code,
+        Code::Cancelled
+            | Code::DeadlineExceeded
+            | Code::ResourceExhausted
+            | Code::Aborted
+            | Code::OutOfRange
+            | Code::Unavailable
+            | Code::DataLoss
+    )
+}
+
+/// Delay before retry number `attempt` (starting at 1).
+fn backoff(retry: &ExportRetryConfig, attempt: u32) -> Duration {
+    let base = retry
+        .initial_backoff_ms
+        .saturating_mul(1u64 << (attempt.saturating_sub(1)).min(16))
+        .min(retry.max_backoff_ms);
+    // Jitter so edge collectors recovering from the same outage don't retry in lockstep
+    let jitter = rand::thread_rng().gen_range(0.8..=1.2);
+    Duration::from_millis((base as f64 * jitter) as u64)
+}
+
+fn channel(config: &OtlpExporterConfig) -> Result<Channel> {
+    let timeout = Duration::from_millis(config.timeout_ms);
+    let mut endpoint = Endpoint::from_shared(config.endpoint.clone())
+        .map_err(|e| Error::config(format!("invalid OTLP endpoint {}: {}", config.endpoint, e)))?
+        .connect_timeout(timeout)
+        .timeout(timeout);
+
+    if let Some(tls) = tls_config(config)? {
+        // Other dependencies may enable a second rustls backend, in which case
+        // rustls cannot pick one on its own; an already installed one is kept
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        endpoint = endpoint
+            .tls_config(tls)
+            .map_err(|e| Error::config(format!("invalid OTLP TLS config: {}", e)))?;
+    }
+    Ok(endpoint.connect_lazy())
+}
+
+/// TLS settings for the endpoint: the configured ones, or the system roots
+/// for `https` endpoints.
+fn tls_config(config: &OtlpExporterConfig) -> Result<Option<ClientTlsConfig>> {
+    let Some(tls) = &config.tls else {
+        return Ok(config
+            .endpoint
+            .starts_with("https://")
+            .then(|| ClientTlsConfig::new().with_native_roots()));
+    };
+
+    let read = |path: &str| {
+        std::fs::read(path)
+            .map_err(|e| Error::config(format!("failed to read TLS file {}: {}", path, e)))
+    };
+
+    let mut client = ClientTlsConfig::new();
+    client = match &tls.ca_file {
+        Some(path) => client.ca_certificate(Certificate::from_pem(read(path)?)),
+        None => client.with_native_roots(),
+    };
+    match (&tls.cert_file, &tls.key_file) {
+        (Some(cert), Some(key)) => {
+            client = client.identity(Identity::from_pem(read(cert)?, read(key)?));
+        }
+        (None, None) => {}
+        _ => {
+            return Err(Error::config(
+                "TLS cert_file and key_file must be set together",
+            ))
+        }
+    }
+    if let Some(domain_name) = &tls.domain_name {
+        client = client.domain_name(domain_name.clone());
+    }
+    Ok(Some(client))
+}
+
+fn metadata(headers: &HashMap<String, String>) -> Result<MetadataMap> {
+    let mut metadata = MetadataMap::new();
+    for (name, value) in headers {
+        let key = MetadataKey::from_bytes(name.to_ascii_lowercase().as_bytes())
+            .map_err(|_| Error::config(format!("invalid OTLP header name: {}", name)))?;
+        let value = MetadataValue::try_from(value.as_str())
+            .map_err(|_| Error::config(format!("invalid value for OTLP header {}", name)))?;
+        metadata.insert(key, value);
+    }
+    Ok(metadata)
+}
+
+/// Convert processed spans to an OTLP export request.
+///
+/// Spans are grouped into one resource per `service.name` attribute. LLM
+/// fields are written as GenAI semantic convention attributes, with input
+/// and output as JSON strings in `llm.input` and `llm.output`; attributes
+/// already on the span take precedence. Spans whose trace or span ID is not
+/// valid hex are dropped.
+pub fn spans_to_request(spans: &[LlmSpan]) -> ExportTraceServiceRequest {
+    let mut by_service: BTreeMap<String, Vec<Span>> = BTreeMap::new();
+    for llm_span in spans {
+        match to_otlp_span(llm_span) {
+            Some(span) => by_service.entry(service_name(llm_span)).or_default().push(span),
+            None => tracing::warn!(
+                trace_id = %llm_span.trace_id,
+                span_id = %llm_span.span_id,
+                "Dropping span with invalid trace or span ID"
+            ),
+        }
+    }
+
+    ExportTraceServiceRequest {
+        resource_spans: by_service
+            .into_iter()
+            .map(|(service, spans)| ResourceSpans {
+                resource: Some(resource(&service, &serde_json::Value::Null)),
+                scope_spans: vec![ScopeSpans {
+                    scope: Some(scope()),
+                    spans,
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            })
+            .collect(),
+    }
+}
+
+fn to_otlp_span(llm_span: &LlmSpan) -> Option<Span> {
+    let trace_id = decode_hex(&llm_span.trace_id, 16)?;
+    let span_id = decode_hex(&llm_span.span_id, 8)?;
+    let parent_span_id = match &llm_span.parent_span_id {
+        Some(id) => decode_hex(id, 8)?,
+        None => Vec::new(),
+    };
+
+    let mut attributes = BTreeMap::new();
+    attributes.insert(GEN_AI_SYSTEM.to_string(), llm_span.provider.as_str().into());
+    attributes.insert(GEN_AI_REQUEST_MODEL.to_string(), llm_span.model.clone().into());
+    if let Some(usage) = &llm_span.token_usage {
+        attributes.insert(GEN_AI_USAGE_INPUT_TOKENS.to_string(), usage.prompt_tokens.into());
+        attributes.insert(GEN_AI_USAGE_OUTPUT_TOKENS.to_string(), usage.completion_tokens.into());
+    }
+    if let Some(cost) = &llm_span.cost {
+        attributes.insert("llm.cost.amount_usd".to_string(), cost.amount_usd.into());
+        if let Some(prompt_cost) = cost.prompt_cost {
+            attributes.insert("llm.cost.prompt_usd".to_string(), prompt_cost.into());
+        }
+        if let Some(completion_cost) = cost.completion_cost {
+            attributes.insert("llm.cost.completion_usd".to_string(), completion_cost.into());
+        }
+    }
+    if let Some(ttft_ms) = llm_span.latency.ttft_ms {
+        attributes.insert("llm.latency.ttft_ms".to_string(), ttft_ms.into());
+    }
+    if let Ok(input) = serde_json::to_string(&llm_span.input) {
+        attributes.insert("llm.input".to_string(), input.into());
+    }
+    if let Some(output) = llm_span.output.as_ref().and_then(|o| serde_json::to_string(o).ok()) {
+        attributes.insert("llm.output".to_string(), output.into());
+    }
+
+    let metadata = &llm_span.metadata;
+    for (key, value) in &metadata.attributes {
+        attributes.insert(key.clone(), value.clone().into());
+    }
+    if let Some(user_id) = &metadata.user_id {
+        attributes.insert("user.id".to_string(), user_id.clone().into());
+    }
+    if let Some(session_id) = &metadata.session_id {
+        attributes.insert("session.id".to_string(), session_id.clone().into());
+    }
+    if let Some(environment) = &metadata.environment {
+        attributes.insert("deployment.environment".to_string(), environment.clone().into());
+    }
+    if !metadata.tags.is_empty() {
+        attributes.insert("llm.tags".to_string(), metadata.tags.clone().into());
+    }
+    for (key, value) in &llm_span.attributes {
+        attributes.insert(key.clone(), value.clone());
+    }
+    attributes.remove("service.name");
+
+    let code = match llm_span.status {
+        SpanStatus::Ok => status::StatusCode::Ok,
+        SpanStatus::Error => status::StatusCode::Error,
+        SpanStatus::Unset => status::StatusCode::Unset,
+    };
+
+    Some(Span {
+        trace_id,
+        span_id,
+        parent_span_id,
+        name: llm_span.name.clone(),
+        kind: span::SpanKind::Client as i32,
+        start_time_unix_nano: unix_nanos(llm_span.latency.start_time),
+        end_time_unix_nano: unix_nanos(llm_span.latency.end_time),
+        attributes: key_values(attributes.iter()),
+        events: llm_span
+            .events
+            .iter()
+            .map(|event| span::Event {
+                time_unix_nano: unix_nanos(event.timestamp),
+                name: event.name.clone(),
+                attributes: key_values(event.attributes.iter()),
+                dropped_attributes_count: 0,
+            })
+            .collect(),
+        // Links to spans without valid OTLP ids cannot be expressed
+        links: llm_span
+            .links
+            .iter()
+            .filter_map(|link| {
+                Some(span::Link {
+                    trace_id: decode_hex(&link.trace_id, 16)?,
+                    span_id: decode_hex(&link.span_id, 8)?,
+                    attributes: key_values(link.attributes.iter()),
+                    ..Default::default()
+                })
+            })
+            .collect(),
+        status: Some(Status {
+            message: String::new(),
+            code: code as i32,
+        }),
+        ..Default::default()
+    })
+}
+
+/// Service name of a span, from its attributes or metadata.
+fn service_name(span: &LlmSpan) -> String {
+    span.attributes
+        .get("service.name")
+        .and_then(|v| v.as_str())
+        .or_else(|| span.metadata.attributes.get("service.name").map(String::as_str))
+        .unwrap_or(UNKNOWN_SERVICE)
+        .to_string()
+}
+
+/// Convert metric series to an OTLP export request.
+///
+/// Series are grouped into one resource per service and set of resource
+/// attributes. [`MetricSeries`] does not record temporality; counters and
+/// histograms are sent as cumulative, matching Prometheus remote write.
+pub fn metrics_to_request(series: &[MetricSeries]) -> ExportMetricsServiceRequest {
+    let mut by_resource: BTreeMap<(String, String), (&serde_json::Value, Vec<Metric>)> =
+        BTreeMap::new();
+    for s in series {
+        let key = (
+            s.metric.service_name.clone(),
+            s.metric.resource_attributes.to_string(),
+        );
+        by_resource
+            .entry(key)
+            .or_insert_with(|| (&s.metric.resource_attributes, Vec::new()))
+            .1
+            .push(to_otlp_metric(s));
+    }
+
+    ExportMetricsServiceRequest {
+        resource_metrics: by_resource
+            .into_iter()
+            .map(|((service, _), (resource_attributes, metrics))| ResourceMetrics {
+                resource: Some(resource(&service, resource_attributes)),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: Some(scope()),
+                    metrics,
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            })
+            .collect(),
+    }
+}
+
+fn to_otlp_metric(series: &MetricSeries) -> Metric {
+    let metric = &series.metric;
+    let points = &series.data_points;
+    let cumulative = AggregationTemporality::Cumulative as i32;
+
+    let data = match metric.metric_type {
+        MetricType::Counter => metric::Data::Sum(Sum {
+            data_points: points.iter().map(|p| number_point(metric, p)).collect(),
+            aggregation_temporality: cumulative,
+            is_monotonic: true,
+        }),
+        MetricType::Gauge => metric::Data::Gauge(Gauge {
+            data_points: points.iter().map(|p| number_point(metric, p)).collect(),
+        }),
+        MetricType::Histogram => metric::Data::Histogram(Histogram {
+            data_points: points.iter().map(|p| histogram_point(metric, p)).collect(),
+            aggregation_temporality: cumulative,
+        }),
+        MetricType::Summary => metric::Data::Summary(Summary {
+            data_points: points.iter().map(|p| summary_point(metric, p)).collect(),
+        }),
+    };
+
+    Metric {
+        name: metric.name.clone(),
+        description: metric.description.clone().unwrap_or_default(),
+        unit: metric.unit.clone().unwrap_or_default(),
+        data: Some(data),
+        ..Default::default()
+    }
+}
+
+/// Data point attributes: the metric labels plus the point's own attributes.
+fn point_attributes(metric: &crate::metric::Metric, point: &MetricDataPoint) -> Vec<KeyValue> {
+    let mut attributes = BTreeMap::new();
+    for value in [&metric.attributes, &point.attributes] {
+        if let Some(obj) = value.as_object() {
+            attributes.extend(obj.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+    }
+    key_values(attributes.iter())
+}
+
+fn number_point(metric: &crate::metric::Metric, point: &MetricDataPoint) -> NumberDataPoint {
+    NumberDataPoint {
+        attributes: point_attributes(metric, point),
+        time_unix_nano: unix_nanos(point.timestamp),
+        exemplars: point.exemplars.iter().map(to_otlp_exemplar).collect(),
+        value: point.value.map(number_data_point::Value::AsDouble),
+        ..Default::default()
+    }
+}
+
+fn histogram_point(metric: &crate::metric::Metric, point: &MetricDataPoint) -> HistogramDataPoint {
+    let count = point.count.unwrap_or(0).max(0) as u64;
+    let mut bucket_counts: Vec<u64> = point.buckets.iter().map(|b| b.count).collect();
+    // Values above the last finite boundary only show up in the total count
+    let overflow = count.saturating_sub(bucket_counts.iter().sum());
+    bucket_counts.push(overflow);
+
+    HistogramDataPoint {
+        attributes: point_attributes(metric, point),
+        time_unix_nano: unix_nanos(point.timestamp),
+        count,
+        sum: point.sum,
+        bucket_counts,
+        explicit_bounds: point.buckets.iter().map(|b| b.boundary).collect(),
+        exemplars: point.exemplars.iter().map(to_otlp_exemplar).collect(),
+        min: point.min,
+        max: point.max,
+        ..Default::default()
+    }
+}
+
+fn summary_point(metric: &crate::metric::Metric, point: &MetricDataPoint) -> SummaryDataPoint {
+    SummaryDataPoint {
+        attributes: point_attributes(metric, point),
+        time_unix_nano: unix_nanos(point.timestamp),
+        count: point.count.unwrap_or(0).max(0) as u64,
+        sum: point.sum.unwrap_or(0.0),
+        quantile_values: point
+            .quantiles
+            .iter()
+            .map(|q| summary_data_point::ValueAtQuantile {
+                quantile: q.quantile,
+                value: q.value,
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn to_otlp_exemplar(exemplar: &Exemplar) -> opentelemetry_proto::tonic::metrics::v1::Exemplar {
+    opentelemetry_proto::tonic::metrics::v1::Exemplar {
+        filtered_attributes: exemplar
+            .attributes
+            .as_object()
+            .map(|obj| key_values(obj.iter()))
+            .unwrap_or_default(),
+        time_unix_nano: unix_nanos(exemplar.timestamp),
+        span_id: decode_hex(&exemplar.span_id, 8).unwrap_or_default(),
+        trace_id: decode_hex(&exemplar.trace_id, 16).unwrap_or_default(),
+        value: Some(exemplar::Value::AsDouble(exemplar.value)),
+    }
+}
+
+fn resource(service_name: &str, attributes: &serde_json::Value) -> Resource {
+    let mut all = BTreeMap::new();
+    if let Some(obj) = attributes.as_object() {
+        all.extend(obj.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    all.insert("service.name".to_string(), service_name.into());
+
+    Resource {
+        attributes: key_values(all.iter()),
+        dropped_attributes_count: 0,
+    }
+}
+
+fn scope() -> InstrumentationScope {
+    InstrumentationScope {
+        name: "llm-observatory-collector".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        ..Default::default()
+    }
+}
+
+fn key_values<'a>(
+    attributes: impl Iterator<Item = (&'a String, &'a serde_json::Value)>,
+) -> Vec<KeyValue> {
+    attributes
+        .filter_map(|(key, value)| {
+            Some(KeyValue {
+                key: key.clone(),
+                value: Some(to_any_value(value)?),
+            })
+        })
+        .collect()
+}
+
+/// Convert a JSON attribute value to an OTLP value. Nulls have no OTLP
+/// equivalent and are dropped.
+pub fn to_any_value(value: &serde_json::Value) -> Option<AnyValue> {
+    use serde_json::Value as Json;
+
+    let value = match value {
+        Json::Null => return None,
+        Json::Bool(b) => any_value::Value::BoolValue(*b),
+        Json::Number(n) => match n.as_i64() {
+            Some(i) => any_value::Value::IntValue(i),
+            None => any_value::Value::DoubleValue(n.as_f64().unwrap_or_default()),
+        },
+        Json::String(s) => any_value::Value::StringValue(s.clone()),
+        Json::Array(values) => any_value::Value::ArrayValue(ArrayValue {
+            values: values.iter().filter_map(to_any_value).collect(),
+        }),
+        Json::Object(obj) => any_value::Value::KvlistValue(KeyValueList {
+            values: key_values(obj.iter()),
+        }),
+    };
+    Some(AnyValue { value: Some(value) })
+}
+
+/// Decode a hex ID of exactly `len` bytes.
+fn decode_hex(hex: &str, len: usize) -> Option<Vec<u8>> {
+    if hex.len() != len * 2 || !hex.is_ascii() {
+        return None;
+    }
+    (0..len)
+        .map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok())
+        .collect()
+}
+
+fn unix_nanos(timestamp: DateTime<Utc>) -> u64 {
+    timestamp.timestamp_nanos_opt().unwrap_or_default().max(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::Compression;
+    use crate::metric::{Metric as SeriesMetric, MetricType};
+    use crate::processor::metrics::HistogramBucket;
+    use llm_observatory_core::span::{LlmInput, SpanEvent, SpanLink};
+    use llm_observatory_core::types::{Latency, Provider, TokenUsage};
+    use opentelemetry_proto::tonic::collector::trace::v1::{
+        trace_service_server::{TraceService, TraceServiceServer},
+        ExportTraceServiceResponse,
+    };
+    use std::net::SocketAddr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tonic::codec::CompressionEncoding;
+    use tonic::{Response, Status as GrpcStatus};
+
+    const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+    const SPAN_ID: &str = "00f067aa0ba902b7";
+    const PARENT_TRACE_ID: &str = "0af7651916cd43dd8448eb211c80319c";
+
+    fn llm_span(span_id: &str) -> LlmSpan {
+        let start = Utc::now();
+        LlmSpan {
+            span_id: span_id.to_string(),
+            trace_id: TRACE_ID.to_string(),
+            parent_span_id: None,
+            name: "llm.chat".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4o".to_string(),
+            input: LlmInput::Text {
+                prompt: "Hello".to_string(),
+            },
+            output: None,
+            token_usage: Some(TokenUsage {
+                prompt_tokens: 10,
+                completion_tokens: 20,
+                total_tokens: 30,
+            }),
+            cost: None,
+            latency: Latency::new(start, start + chrono::Duration::milliseconds(250)),
+            metadata: Default::default(),
+            status: SpanStatus::Error,
+            attributes: [
+                ("service.name".to_string(), serde_json::json!("chatbot")),
+                (GEN_AI_REQUEST_MODEL.to_string(), serde_json::json!("gpt-4o-2024-08-06")),
+            ]
+            .into_iter()
+            .collect(),
+            events: vec![SpanEvent {
+                name: "retry".to_string(),
+                timestamp: start,
+                attributes: Default::default(),
+            }],
+            links: vec![
+                SpanLink::new(PARENT_TRACE_ID, "b7ad6b7169203331"),
+                SpanLink::new(PARENT_TRACE_ID, "not-hex"),
+            ],
+        }
+    }
+
+    fn attribute<'a>(attributes: &'a [KeyValue], key: &str) -> Option<&'a any_value::Value> {
+        attributes
+            .iter()
+            .find(|kv| kv.key == key)
+            .and_then(|kv| kv.value.as_ref()?.value.as_ref())
+    }
+
+    #[test]
+    fn test_spans_to_request() {
+        let request = spans_to_request(&[llm_span(SPAN_ID), llm_span("not-hex")]);
+        assert_eq!(request.resource_spans.len(), 1);
+
+        let resource_spans = &request.resource_spans[0];
+        let resource = resource_spans.resource.as_ref().unwrap();
+        assert_eq!(
+            attribute(&resource.attributes, "service.name"),
+            Some(&any_value::Value::StringValue("chatbot".to_string()))
+        );
+
+        let spans = &resource_spans.scope_spans[0].spans;
+        assert_eq!(spans.len(), 1);
+        let span = &spans[0];
+        assert_eq!(span.trace_id, decode_hex(TRACE_ID, 16).unwrap());
+        assert_eq!(span.span_id, vec![0x00, 0xf0, 0x67, 0xaa, 0x0b, 0xa9, 0x02, 0xb7]);
+        assert!(span.parent_span_id.is_empty());
+        assert_eq!(span.end_time_unix_nano - span.start_time_unix_nano, 250_000_000);
+        assert_eq!(span.status.as_ref().unwrap().code, status::StatusCode::Error as i32);
+        assert_eq!(span.events.len(), 1);
+        assert_eq!(span.links.len(), 1);
+        assert_eq!(span.links[0].trace_id, decode_hex(PARENT_TRACE_ID, 16).unwrap());
+
+        assert_eq!(
+            attribute(&span.attributes, GEN_AI_SYSTEM),
+            Some(&any_value::Value::StringValue("openai".to_string()))
+        );
+        assert_eq!(
+            attribute(&span.attributes, GEN_AI_USAGE_OUTPUT_TOKENS),
+            Some(&any_value::Value::IntValue(20))
+        );
+        // Span attributes win over derived ones
+        assert_eq!(
+            attribute(&span.attributes, GEN_AI_REQUEST_MODEL),
+            Some(&any_value::Value::StringValue("gpt-4o-2024-08-06".to_string()))
+        );
+        assert!(attribute(&span.attributes, "service.name").is_none());
+    }
+
+    #[test]
+    fn test_metrics_to_request() {
+        let series = MetricSeries {
+            metric: SeriesMetric {
+                name: "llm_request_duration_seconds".to_string(),
+                description: None,
+                unit: Some("s".to_string()),
+                metric_type: MetricType::Histogram,
+                service_name: "gateway".to_string(),
+                attributes: serde_json::json!({"model": "gpt-4o"}),
+                resource_attributes: serde_json::json!({"instance": "gw-1:9090"}),
+            },
+            data_points: vec![MetricDataPoint {
+                timestamp: Utc::now(),
+                count: Some(6),
+                sum: Some(4.2),
+                buckets: vec![
+                    HistogramBucket { boundary: 0.5, count: 3 },
+                    HistogramBucket { boundary: 1.0, count: 1 },
+                ],
+                attributes: serde_json::json!({}),
+                ..Default::default()
+            }],
+        };
+
+        let request = metrics_to_request(&[series]);
+        let resource_metrics = &request.resource_metrics[0];
+        let resource = resource_metrics.resource.as_ref().unwrap();
+        assert!(attribute(&resource.attributes, "instance").is_some());
+
+        let metric = &resource_metrics.scope_metrics[0].metrics[0];
+        assert_eq!(metric.unit, "s");
+        let Some(metric::Data::Histogram(histogram)) = &metric.data else {
+            panic!("expected histogram, got {:?}", metric.data);
+        };
+        assert_eq!(histogram.aggregation_temporality, AggregationTemporality::Cumulative as i32);
+        let point = &histogram.data_points[0];
+        assert_eq!(point.explicit_bounds, vec![0.5, 1.0]);
+        assert_eq!(point.bucket_counts, vec![3, 1, 2]);
+        assert_eq!(
+            attribute(&point.attributes, "model"),
+            Some(&any_value::Value::StringValue("gpt-4o".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_backoff_and_retryable_codes() {
+        let retry = ExportRetryConfig {
+            max_attempts: 5,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1000,
+        };
+        let first = backoff(&retry, 1).as_millis();
+        assert!((80..=120).contains(&first), "{}", first);
+        let capped = backoff(&retry, 10).as_millis();
+        assert!((800..=1200).contains(&capped), "{}", capped);
+
+        assert!(is_retryable(Code::Unavailable));
+        assert!(!is_retryable(Code::InvalidArgument));
+        assert!(!is_retryable(Code::Unauthenticated));
+    }
+
+    /// Trace backend that fails the first `failures` requests.
+    #[derive(Default)]
+    struct FlakyBackend {
+        failures: usize,
+        calls: Arc<AtomicUsize>,
+        spans: Arc<AtomicUsize>,
+        api_keys: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[tonic::async_trait]
+    impl TraceService for FlakyBackend {
+        async fn export(
+            &self,
+            request: Request<ExportTraceServiceRequest>,
+        ) -> std::result::Result<Response<ExportTraceServiceResponse>, GrpcStatus> {
+            if let Some(key) = request.metadata().get("x-api-key") {
+                self.api_keys.lock().unwrap().push(key.to_str().unwrap().to_string());
+            }
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(GrpcStatus::unavailable("warming up"));
+            }
+
+            let count: usize = request
+                .into_inner()
+                .resource_spans
+                .iter()
+                .flat_map(|rs| &rs.scope_spans)
+                .map(|ss| ss.spans.len())
+                .sum();
+            self.spans.fetch_add(count, Ordering::SeqCst);
+            Ok(Response::new(ExportTraceServiceResponse::default()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_retries_until_accepted() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr: SocketAddr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let backend = FlakyBackend {
+            failures: 2,
+            ..Default::default()
+        };
+        let (calls, spans, api_keys) =
+            (backend.calls.clone(), backend.spans.clone(), backend.api_keys.clone());
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(
+                    TraceServiceServer::new(backend).accept_compressed(CompressionEncoding::Zstd),
+                )
+                .serve(addr),
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let exporter = OtlpExporter::new(&OtlpExporterConfig {
+            endpoint: format!("http://{}", addr),
+            tls: None,
+            headers: [("X-Api-Key".to_string(), "edge-1".to_string())].into_iter().collect(),
+            compression: Compression::Zstd,
+            timeout_ms: 2000,
+            queue_size: 8,
+            max_batch_size: 2,
+            retry: ExportRetryConfig {
+                max_attempts: 5,
+                initial_backoff_ms: 10,
+                max_backoff_ms: 50,
+            },
+        })
+        .unwrap();
+
+        let batch = vec![llm_span(SPAN_ID), llm_span("00f067aa0ba902b8"), llm_span("00f067aa0ba902b9")];
+        exporter.export_spans(batch).await.unwrap();
+        exporter.shutdown().await.unwrap();
+
+        // Two batches, the first of which failed twice
+        assert_eq!(spans.load(Ordering::SeqCst), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        assert!(api_keys.lock().unwrap().iter().all(|key| key == "edge-1"));
+
+        assert!(exporter.export_spans(vec![llm_span(SPAN_ID)]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config() {
+        let config = |tls: Option<crate::config::TlsConfig>, headers: HashMap<String, String>| {
+            OtlpExporterConfig {
+                endpoint: "https://central:4317".to_string(),
+                tls,
+                headers,
+                compression: Compression::None,
+                timeout_ms: 1000,
+                queue_size: 1,
+                max_batch_size: 1,
+                retry: ExportRetryConfig::default(),
+            }
+        };
+
+        assert!(OtlpExporter::new(&config(None, HashMap::new())).is_ok());
+        let cert_only = crate::config::TlsConfig {
+            cert_file: Some("/tmp/client.pem".to_string()),
+            ..Default::default()
+        };
+        assert!(OtlpExporter::new(&config(Some(cert_only), HashMap::new())).is_err());
+        let bad_header = [("bad header".to_string(), "x".to_string())].into_iter().collect();
+        assert!(OtlpExporter::new(&config(None, bad_header)).is_err());
+    }
+}
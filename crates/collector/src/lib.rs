@@ -4,20 +4,55 @@
 //! OpenTelemetry collector with LLM-specific processing.
 //!
 //! This crate implements an OTLP-compliant collector that receives traces, metrics,
-//! and logs from LLM applications, processes them through LLM-aware pipelines
-//! (PII redaction, cost calculation, intelligent sampling), and forwards them
-//! to storage backends.
+//! and logs from LLM applications (plus metrics over Prometheus remote write),
+//! processes them through LLM-aware pipelines (deduplication of retried spans,
+//! schema and semantic convention validation, PII redaction, cost calculation,
+//! cost-allocation tag normalization, span name normalization, model metadata
+//! enrichment, latency/cost histograms with trace exemplars, guardrail
+//! violation extraction, provider queue time extraction, streaming per-minute
+//! aggregation, per-organization ingestion quotas, intelligent sampling,
+//! quarantine and replay of failing spans), samples, routes and clusters logs
+//! into patterns, and forwards them to storage backends or, over OTLP, to
+//! another collector.
 
 #![warn(missing_docs, rust_2018_idioms)]
 #![deny(unsafe_code)]
 
+pub mod compression;
 pub mod config;
+pub mod exporter;
+pub mod metric;
 pub mod processor;
 pub mod receiver;
+pub mod routing;
 pub mod sampler;
+pub mod telemetry;
 
-pub use config::CollectorConfig;
+pub use compression::Compression;
+pub use config::{CollectorConfig, ConfigError};
+pub use exporter::otlp::OtlpExporter;
 pub use processor::pii::PiiRedactionProcessor;
+pub use processor::capture::CapturePolicyProcessor;
 pub use processor::cost::CostCalculationProcessor;
+pub use processor::cost_tags::CostTagProcessor;
+pub use processor::dedup::DeduplicationProcessor;
+pub use processor::enrichment::ModelEnrichmentProcessor;
+pub use processor::guardrail::GuardrailEventProcessor;
+pub use processor::log_patterns::LogPatternMiner;
+pub use processor::logs::LogProcessor;
+pub use processor::metrics::MetricsAggregationProcessor;
+pub use processor::pseudonymize::PseudonymizationProcessor;
+pub use processor::quarantine::{Quarantine, QuarantiningProcessor};
+pub use processor::quota::QuotaProcessor;
+pub use processor::resource::ResourceDetectionProcessor;
+pub use processor::schema::SchemaValidationProcessor;
+pub use processor::semconv::SemconvValidationProcessor;
+pub use processor::span_names::SpanNameProcessor;
+pub use processor::streaming::StreamingAggregationProcessor;
+pub use processor::timing::QueueTimingProcessor;
 pub use receiver::otlp::OtlpReceiver;
+pub use receiver::prometheus::PrometheusRemoteWriteReceiver;
+pub use receiver::routing::RoutingReceiver;
+pub use routing::{HashRing, TraceRouter};
 pub use sampler::{SamplingStrategy, HeadSampler, TailSampler};
+pub use telemetry::PipelineTelemetry;
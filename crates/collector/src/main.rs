@@ -4,23 +4,60 @@
 //! LLM Observatory Collector binary.
 
 use clap::Parser;
-use llm_observatory_collector::{receiver::Receiver, CollectorConfig, OtlpReceiver};
+use llm_observatory_collector::{
+    config::ExporterKind, exporter::Exporter, metric::MetricSeries, processor::SpanProcessor,
+    receiver::Receiver, CollectorConfig, OtlpExporter, OtlpReceiver, PiiRedactionProcessor,
+    PipelineTelemetry, PrometheusRemoteWriteReceiver, ResourceDetectionProcessor, RoutingReceiver,
+    TraceRouter,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Configuration file path
+    /// Configuration file path (YAML, TOML or JSON)
     #[arg(short, long, value_name = "FILE")]
     config: Option<String>,
 
-    /// gRPC endpoint
-    #[arg(long, default_value = "0.0.0.0:4317")]
-    grpc_endpoint: String,
+    /// gRPC endpoint (overrides receiver.grpc_endpoint)
+    #[arg(long)]
+    grpc_endpoint: Option<String>,
 
-    /// HTTP endpoint
-    #[arg(long, default_value = "0.0.0.0:4318")]
-    http_endpoint: String,
+    /// HTTP endpoint (overrides receiver.http_endpoint)
+    #[arg(long)]
+    http_endpoint: Option<String>,
+
+    /// Override a configuration value, e.g. `--set processors.batch_size=500`
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = parse_override)]
+    overrides: Vec<(String, String)>,
+
+    /// Check the configuration and exit
+    #[arg(long)]
+    validate_config: bool,
+}
+
+impl Args {
+    /// CLI overrides of configuration values, in precedence order.
+    fn config_overrides(&self) -> Vec<(String, String)> {
+        let mut overrides = self.overrides.clone();
+        if let Some(endpoint) = &self.grpc_endpoint {
+            overrides.push(("receiver.grpc_endpoint".to_string(), endpoint.clone()));
+        }
+        if let Some(endpoint) = &self.http_endpoint {
+            overrides.push(("receiver.http_endpoint".to_string(), endpoint.clone()));
+        }
+        overrides
+    }
+}
+
+fn parse_override(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", s))
 }
 
 #[tokio::main]
@@ -36,34 +73,133 @@ async fn main() -> anyhow::Result<()> {
 
     let args = Args::parse();
 
-    // Load configuration
-    let config = match args.config {
-        Some(path) => {
-            tracing::info!("Loading configuration from: {}", path);
-            CollectorConfig::from_file(&path)?
-        }
-        None => {
-            tracing::info!("Using default configuration");
-            CollectorConfig::default()
+    // Load configuration: defaults < file < environment < CLI flags
+    match &args.config {
+        Some(path) => tracing::info!("Loading configuration from: {}", path),
+        None => tracing::info!("Using default configuration"),
+    }
+    let loaded = CollectorConfig::load(args.config.as_deref(), &args.config_overrides());
+
+    if args.validate_config {
+        match loaded {
+            Ok(_) => {
+                println!("Configuration is valid");
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
         }
-    };
+    }
+    let config = loaded?;
 
     tracing::info!(
         "Starting LLM Observatory Collector v{}",
         env!("CARGO_PKG_VERSION")
     );
 
-    // Create receiver
-    let mut receiver = OtlpReceiver::new(
-        config.receiver.grpc_endpoint,
-        config.receiver.http_endpoint,
-    )
-    .with_grpc(config.receiver.enable_grpc)
-    .with_http(config.receiver.enable_http);
+    // Forwarding to another collector, for pipelines that use it
+    let otlp_exporter = if config.pipelines.uses(ExporterKind::Otlp) {
+        let exporter_config = config.exporters.otlp.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("a pipeline uses the otlp exporter but exporters.otlp is not configured")
+        })?;
+        let exporter = OtlpExporter::new(exporter_config)?;
+        tracing::info!("Forwarding to OTLP endpoint {}", exporter.endpoint());
+        Some(Arc::new(exporter))
+    } else {
+        None
+    };
+
+    // Create receiver; in routing mode this instance only forwards spans to
+    // the sampling tier
+    let mut receiver: Box<dyn Receiver> = if config.routing.enabled {
+        tracing::info!("Running as routing tier");
+        let router = TraceRouter::new(&config.routing)?;
+        Box::new(
+            RoutingReceiver::new(config.receiver.grpc_endpoint, router)
+                .with_accept_compression(config.receiver.accept_compression.clone()),
+        )
+    } else {
+        Box::new(
+            OtlpReceiver::new(config.receiver.grpc_endpoint, config.receiver.http_endpoint)
+                .with_grpc(config.receiver.enable_grpc)
+                .with_http(config.receiver.enable_http)
+                .with_accept_compression(config.receiver.accept_compression.clone()),
+        )
+    };
 
     // Start receiver
     receiver.start().await?;
 
+    // Metrics pushed over Prometheus remote write
+    let remote_write = &config.receiver.prometheus_remote_write;
+    let mut metrics_receiver = if remote_write.enabled && !config.routing.enabled {
+        let mut processors: Vec<Arc<dyn SpanProcessor>> = Vec::new();
+        if config.processors.enable_pii_redaction {
+            processors.push(Arc::new(PiiRedactionProcessor::new()));
+        }
+        if config.processors.resource_detection.enabled {
+            let processor =
+                ResourceDetectionProcessor::detect(&config.processors.resource_detection).await;
+            tracing::info!("Detected resource attributes: {:?}", processor.attributes());
+            processors.push(Arc::new(processor));
+        }
+
+        let batch_size = config.processors.batch_size.max(1);
+        let forward = otlp_exporter
+            .clone()
+            .filter(|_| config.pipelines.metrics.contains(&ExporterKind::Otlp));
+        let (tx, mut rx) = mpsc::channel::<MetricSeries>(batch_size);
+        tokio::spawn(async move {
+            let mut batch = Vec::with_capacity(batch_size);
+            while rx.recv_many(&mut batch, batch_size).await > 0 {
+                for series in &batch {
+                    tracing::debug!(
+                        metric = %series.metric.name,
+                        points = series.data_points.len(),
+                        "Received metric series"
+                    );
+                }
+                if let Some(exporter) = &forward {
+                    if let Err(e) = exporter.export_metrics(std::mem::take(&mut batch)).await {
+                        tracing::warn!("Failed to queue metrics for export: {}", e);
+                    }
+                }
+                batch.clear();
+            }
+        });
+
+        let mut metrics_receiver =
+            PrometheusRemoteWriteReceiver::new(remote_write.endpoint, tx.clone())
+                .with_processors(processors)
+                .with_max_body_bytes(remote_write.max_body_bytes);
+
+        // The collector's own pipeline metrics skip the processors
+        if config.metrics.self_telemetry {
+            let telemetry = Arc::new(PipelineTelemetry::new());
+            let flush = telemetry.clone();
+            let period = Duration::from_secs(config.metrics.self_telemetry_interval_secs);
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(period);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    for series in flush.drain(chrono::Utc::now()) {
+                        if tx.send(series).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+            metrics_receiver = metrics_receiver.with_telemetry(telemetry);
+        }
+        metrics_receiver.start().await?;
+        Some(metrics_receiver)
+    } else {
+        None
+    };
+
     tracing::info!("Collector started successfully");
     tracing::info!("Press Ctrl+C to shutdown");
 
@@ -72,6 +208,12 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("Shutdown signal received, stopping collector...");
     receiver.stop().await?;
+    if let Some(metrics_receiver) = metrics_receiver.as_mut() {
+        metrics_receiver.stop().await?;
+    }
+    if let Some(exporter) = &otlp_exporter {
+        exporter.shutdown().await?;
+    }
 
     tracing::info!("Collector stopped gracefully");
     Ok(())
@@ -0,0 +1,250 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Metrics ingested as metrics rather than derived from spans.
+//!
+//! These mirror the storage `Metric` and `MetricDataPoint` models, without
+//! the identifiers and timestamps assigned on insert. Buckets and exemplars
+//! use the same JSON formats as the histogram aggregation processor.
+//!
+//! Histogram points are stored as deltas, so a window can be read by summing
+//! its points; [`HistogramDeltas`] converts cumulative histograms.
+
+use crate::processor::metrics::{Exemplar, HistogramBucket};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Type of metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricType {
+    /// Monotonically increasing value
+    Counter,
+    /// Value that can go up or down
+    Gauge,
+    /// Distribution of values in buckets
+    Histogram,
+    /// Distribution of values as quantiles
+    Summary,
+}
+
+impl MetricType {
+    /// Name as stored in `metrics.metric_type`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetricType::Counter => "counter",
+            MetricType::Gauge => "gauge",
+            MetricType::Histogram => "histogram",
+            MetricType::Summary => "summary",
+        }
+    }
+}
+
+/// Metric definition: name, type and identifying labels.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Metric {
+    /// Metric name
+    pub name: String,
+    /// Metric description
+    pub description: Option<String>,
+    /// Metric unit (e.g. "seconds", "tokens")
+    pub unit: Option<String>,
+    /// Metric type
+    pub metric_type: MetricType,
+    /// Service that emitted the metric
+    pub service_name: String,
+    /// Metric labels
+    pub attributes: serde_json::Value,
+    /// Labels describing the emitting process
+    pub resource_attributes: serde_json::Value,
+}
+
+/// Summary quantile, in the `metric_data_points.quantiles` format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SummaryQuantile {
+    /// Quantile (0.0 to 1.0)
+    pub quantile: f64,
+    /// Value at this quantile
+    pub value: f64,
+}
+
+/// A single data point for a metric.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct MetricDataPoint {
+    /// Timestamp of the data point
+    pub timestamp: DateTime<Utc>,
+    /// Value (counter, gauge)
+    pub value: Option<f64>,
+    /// Number of observations (histogram, summary)
+    pub count: Option<i64>,
+    /// Sum of observations (histogram, summary)
+    pub sum: Option<f64>,
+    /// Smallest observation, when known
+    pub min: Option<f64>,
+    /// Largest observation, when known
+    pub max: Option<f64>,
+    /// Per-bucket counts with finite boundaries (histogram)
+    pub buckets: Vec<HistogramBucket>,
+    /// Quantiles (summary)
+    pub quantiles: Vec<SummaryQuantile>,
+    /// Sample traces behind the point
+    pub exemplars: Vec<Exemplar>,
+    /// Data point attributes
+    pub attributes: serde_json::Value,
+}
+
+/// A metric with the data points received for it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricSeries {
+    /// Metric definition
+    pub metric: Metric,
+    /// Data points, ordered by timestamp
+    pub data_points: Vec<MetricDataPoint>,
+}
+
+/// Converts cumulative histogram points to delta points.
+///
+/// Prometheus and cumulative OTLP histograms report totals since the process
+/// started. Each point is replaced by its difference from the previous point
+/// of the same series; the first point of a series, and a point after a reset
+/// (a count went down or the bucket boundaries changed), is kept whole.
+/// Deltas carry no `min` and `max`, which describe the cumulative range.
+#[derive(Debug, Default)]
+pub struct HistogramDeltas {
+    /// Latest cumulative point per series
+    previous: Mutex<HashMap<String, MetricDataPoint>>,
+}
+
+impl HistogramDeltas {
+    /// Create a converter with no series seen.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the points of a cumulative histogram series by deltas.
+    pub fn apply(&self, series: &mut MetricSeries) {
+        let metric = &series.metric;
+        let mut previous = self.previous.lock().unwrap();
+
+        for point in &mut series.data_points {
+            let key = format!(
+                "{}\u{1f}{}\u{1f}{}\u{1f}{}",
+                metric.service_name, metric.name, metric.attributes, point.attributes
+            );
+            let cumulative = point.clone();
+            if let Some(delta) = previous.get(&key).and_then(|last| delta(last, point)) {
+                *point = delta;
+            }
+            previous.insert(key, cumulative);
+        }
+    }
+}
+
+/// Difference between two cumulative points, or `None` after a reset.
+fn delta(previous: &MetricDataPoint, current: &MetricDataPoint) -> Option<MetricDataPoint> {
+    if previous.buckets.len() != current.buckets.len() {
+        return None;
+    }
+
+    let count = current
+        .count?
+        .checked_sub(previous.count?)
+        .filter(|c| *c >= 0)?;
+    let buckets = previous
+        .buckets
+        .iter()
+        .zip(&current.buckets)
+        .map(|(before, now)| {
+            (before.boundary == now.boundary).then_some(())?;
+            Some(HistogramBucket {
+                boundary: now.boundary,
+                count: now.count.checked_sub(before.count)?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(MetricDataPoint {
+        count: Some(count),
+        sum: current
+            .sum
+            .zip(previous.sum)
+            .map(|(now, before)| now - before),
+        min: None,
+        max: None,
+        buckets,
+        ..current.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn histogram(points: &[(i64, Vec<u64>)]) -> MetricSeries {
+        MetricSeries {
+            metric: Metric {
+                name: "llm_request_duration_seconds".to_string(),
+                description: None,
+                unit: None,
+                metric_type: MetricType::Histogram,
+                service_name: "chat-api".to_string(),
+                attributes: serde_json::json!({"model": "gpt-4o"}),
+                resource_attributes: serde_json::json!({}),
+            },
+            data_points: points
+                .iter()
+                .map(|(count, buckets)| MetricDataPoint {
+                    count: Some(*count),
+                    sum: Some(*count as f64),
+                    min: Some(0.1),
+                    buckets: buckets
+                        .iter()
+                        .zip([0.5, 1.0])
+                        .map(|(count, boundary)| HistogramBucket {
+                            boundary,
+                            count: *count,
+                        })
+                        .collect(),
+                    attributes: serde_json::json!({}),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_histogram_deltas() {
+        let deltas = HistogramDeltas::new();
+
+        let mut first = histogram(&[(10, vec![3, 4]), (15, vec![5, 6])]);
+        deltas.apply(&mut first);
+        assert_eq!(first.data_points[0].count, Some(10));
+        assert_eq!(first.data_points[0].min, Some(0.1));
+        let delta = &first.data_points[1];
+        assert_eq!(delta.count, Some(5));
+        assert_eq!(delta.sum, Some(5.0));
+        assert_eq!(delta.min, None);
+        assert_eq!(
+            delta.buckets,
+            vec![
+                HistogramBucket {
+                    boundary: 0.5,
+                    count: 2
+                },
+                HistogramBucket {
+                    boundary: 1.0,
+                    count: 2
+                },
+            ]
+        );
+
+        // The series continues across requests; a restart resets it
+        let mut second = histogram(&[(18, vec![6, 7]), (2, vec![1, 1])]);
+        deltas.apply(&mut second);
+        assert_eq!(second.data_points[0].count, Some(3));
+        assert_eq!(second.data_points[1].count, Some(2));
+        assert_eq!(second.data_points[1].buckets[0].count, 1);
+    }
+}
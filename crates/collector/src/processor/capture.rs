@@ -0,0 +1,307 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-organization content capture policies.
+//!
+//! SDKs decide how much prompt and response text they capture, but an
+//! organization can restrict it through the admin API
+//! (`organization_settings.capture_policy`). This processor enforces the
+//! restriction on ingest, so spans from older or misconfigured SDKs are
+//! stored no more fully than the organization allows:
+//!
+//! - [`CaptureMode::Full`]: spans are kept as sent
+//! - [`CaptureMode::MetadataOnly`]: text is removed, lengths and message
+//!   counts are recorded as by the SDK
+//! - [`CaptureMode::None`]: text and content statistics are removed
+//!
+//! A policy never restores content an SDK left out. Spans record the mode
+//! that applied in `gen_ai.content.capture`. Policies are loaded with
+//! [`CapturePolicyProcessor::sync_org_policies`]; spans without an `org_id`
+//! attribute, or of organizations without a policy, are kept as sent.
+
+use super::quarantine::string_attribute;
+use super::SpanProcessor;
+use async_trait::async_trait;
+use llm_observatory_core::{
+    span::{ContentPart, LlmInput, LlmSpan},
+    Result,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Attribute holding the organization ID.
+const ORG_ID_ATTRIBUTE: &str = "org_id";
+
+/// Attribute recording the capture mode of a span.
+const CAPTURE_ATTRIBUTE: &str = "gen_ai.content.capture";
+
+/// Content statistics recorded in metadata-only mode.
+const PROMPT_LENGTH_ATTRIBUTE: &str = "gen_ai.prompt.length";
+const MESSAGE_COUNT_ATTRIBUTE: &str = "gen_ai.prompt.message_count";
+const COMPLETION_LENGTH_ATTRIBUTE: &str = "gen_ai.completion.length";
+
+/// How much prompt and response content is kept, least first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureMode {
+    /// No content or content statistics
+    None,
+    /// Lengths and message counts only
+    MetadataOnly,
+    /// The full text
+    Full,
+}
+
+impl CaptureMode {
+    /// Get the mode as a string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaptureMode::None => "none",
+            CaptureMode::MetadataOnly => "metadata_only",
+            CaptureMode::Full => "full",
+        }
+    }
+
+    /// Parse a mode as stored in `organization_settings`.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "none" => Some(CaptureMode::None),
+            "metadata_only" => Some(CaptureMode::MetadataOnly),
+            "full" => Some(CaptureMode::Full),
+            _ => None,
+        }
+    }
+}
+
+/// Content capture policy processor.
+#[derive(Debug, Default)]
+pub struct CapturePolicyProcessor {
+    /// Capture mode per organization
+    policies: RwLock<HashMap<String, CaptureMode>>,
+}
+
+impl CapturePolicyProcessor {
+    /// Create a processor without policies, which keeps spans as sent.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the capture mode of an organization.
+    pub fn with_org_policy(self, org_id: &str, mode: CaptureMode) -> Self {
+        self.policies
+            .write()
+            .unwrap()
+            .insert(org_id.to_string(), mode);
+        self
+    }
+
+    /// Replace the capture modes with `policies`, given per organization.
+    pub fn sync_org_policies(&self, policies: impl IntoIterator<Item = (String, CaptureMode)>) {
+        *self.policies.write().unwrap() = policies.into_iter().collect();
+    }
+
+    /// Capture mode of an organization.
+    pub fn policy(&self, org_id: &str) -> Option<CaptureMode> {
+        self.policies.read().unwrap().get(org_id).copied()
+    }
+}
+
+/// Capture mode a span was sent with; spans without the attribute are
+/// treated as fully captured.
+fn sent_mode(span: &LlmSpan) -> CaptureMode {
+    string_attribute(span, CAPTURE_ATTRIBUTE)
+        .and_then(|mode| CaptureMode::parse(&mode))
+        .unwrap_or(CaptureMode::Full)
+}
+
+/// Remove the text of a span's input and output, recording lengths and
+/// message counts unless `mode` is [`CaptureMode::None`].
+fn restrict(span: &mut LlmSpan, mode: CaptureMode, sent: CaptureMode) {
+    if mode == CaptureMode::None {
+        for key in [
+            PROMPT_LENGTH_ATTRIBUTE,
+            MESSAGE_COUNT_ATTRIBUTE,
+            COMPLETION_LENGTH_ATTRIBUTE,
+        ] {
+            span.attributes.remove(key);
+        }
+    } else if sent == CaptureMode::Full {
+        // Metadata-only spans already carry the SDK's statistics
+        let (length, messages) = input_stats(&span.input);
+        span.attributes
+            .insert(PROMPT_LENGTH_ATTRIBUTE.to_string(), length.into());
+        if let Some(messages) = messages {
+            span.attributes
+                .insert(MESSAGE_COUNT_ATTRIBUTE.to_string(), messages.into());
+        }
+        if let Some(output) = &span.output {
+            span.attributes.insert(
+                COMPLETION_LENGTH_ATTRIBUTE.to_string(),
+                output.content.chars().count().into(),
+            );
+        }
+    }
+
+    match &mut span.input {
+        LlmInput::Text { prompt } => prompt.clear(),
+        LlmInput::Chat { messages } => {
+            for message in messages {
+                message.content.clear();
+            }
+        }
+        LlmInput::Multimodal { parts } => {
+            for part in parts {
+                if let ContentPart::Text { text } = part {
+                    text.clear();
+                }
+            }
+        }
+    }
+    if let Some(output) = &mut span.output {
+        output.content.clear();
+    }
+}
+
+/// Length in characters of an input's text, and its message count for chat
+/// input.
+fn input_stats(input: &LlmInput) -> (usize, Option<usize>) {
+    match input {
+        LlmInput::Text { prompt } => (prompt.chars().count(), None),
+        LlmInput::Chat { messages } => (
+            messages.iter().map(|m| m.content.chars().count()).sum(),
+            Some(messages.len()),
+        ),
+        LlmInput::Multimodal { parts } => (
+            parts
+                .iter()
+                .map(|p| match p {
+                    ContentPart::Text { text } => text.chars().count(),
+                    _ => 0,
+                })
+                .sum(),
+            None,
+        ),
+    }
+}
+
+#[async_trait]
+impl SpanProcessor for CapturePolicyProcessor {
+    async fn process(&self, mut span: LlmSpan) -> Result<Option<LlmSpan>> {
+        let Some(policy) =
+            string_attribute(&span, ORG_ID_ATTRIBUTE).and_then(|org_id| self.policy(&org_id))
+        else {
+            return Ok(Some(span));
+        };
+
+        let sent = sent_mode(&span);
+        if policy >= sent {
+            return Ok(Some(span));
+        }
+
+        restrict(&mut span, policy, sent);
+        span.attributes
+            .insert(CAPTURE_ATTRIBUTE.to_string(), policy.as_str().into());
+        metrics::counter!("collector_capture_restricted_total", "mode" => policy.as_str())
+            .increment(1);
+        Ok(Some(span))
+    }
+
+    fn name(&self) -> &str {
+        "capture_policy"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use llm_observatory_core::{
+        span::{ChatMessage, LlmOutput, SpanStatus},
+        types::{Latency, Provider},
+    };
+
+    fn span(org_id: &str) -> LlmSpan {
+        let now = Utc::now();
+        let mut attributes = HashMap::new();
+        attributes.insert(ORG_ID_ATTRIBUTE.to_string(), org_id.into());
+        LlmSpan {
+            span_id: "s1".to_string(),
+            trace_id: "t1".to_string(),
+            parent_span_id: None,
+            name: "test".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4".to_string(),
+            input: LlmInput::Chat {
+                messages: vec![ChatMessage {
+                    role: "user".to_string(),
+                    content: "What is my balance?".to_string(),
+                    name: None,
+                }],
+            },
+            output: Some(LlmOutput {
+                content: "$42".to_string(),
+                finish_reason: None,
+                metadata: Default::default(),
+            }),
+            token_usage: None,
+            cost: None,
+            latency: Latency::new(now, now),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes,
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    fn message(span: &LlmSpan) -> &str {
+        match &span.input {
+            LlmInput::Chat { messages } => &messages[0].content,
+            _ => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metadata_only_policy() {
+        let processor =
+            CapturePolicyProcessor::new().with_org_policy("org-bank", CaptureMode::MetadataOnly);
+
+        let processed = processor.process(span("org-bank")).await.unwrap().unwrap();
+        assert_eq!(message(&processed), "");
+        assert_eq!(processed.output.as_ref().unwrap().content, "");
+        assert_eq!(processed.attributes[CAPTURE_ATTRIBUTE], "metadata_only");
+        assert_eq!(processed.attributes[PROMPT_LENGTH_ATTRIBUTE], 19);
+        assert_eq!(processed.attributes[MESSAGE_COUNT_ATTRIBUTE], 1);
+        assert_eq!(processed.attributes[COMPLETION_LENGTH_ATTRIBUTE], 3);
+
+        // Other organizations are kept as sent
+        let processed = processor.process(span("org-other")).await.unwrap().unwrap();
+        assert_eq!(message(&processed), "What is my balance?");
+    }
+
+    #[tokio::test]
+    async fn test_policy_never_restores_content() {
+        let processor = CapturePolicyProcessor::new();
+        processor.sync_org_policies([
+            ("org-none".to_string(), CaptureMode::None),
+            ("org-full".to_string(), CaptureMode::Full),
+        ]);
+
+        let mut sent = span("org-none");
+        sent.attributes
+            .insert(CAPTURE_ATTRIBUTE.to_string(), "metadata_only".into());
+        sent.attributes
+            .insert(PROMPT_LENGTH_ATTRIBUTE.to_string(), 19.into());
+        let processed = processor.process(sent).await.unwrap().unwrap();
+        assert_eq!(processed.attributes[CAPTURE_ATTRIBUTE], "none");
+        assert!(!processed.attributes.contains_key(PROMPT_LENGTH_ATTRIBUTE));
+
+        // A full policy keeps what the SDK chose to capture
+        let mut sent = span("org-full");
+        sent.attributes
+            .insert(CAPTURE_ATTRIBUTE.to_string(), "metadata_only".into());
+        let processed = processor.process(sent).await.unwrap().unwrap();
+        assert_eq!(processed.attributes[CAPTURE_ATTRIBUTE], "metadata_only");
+    }
+}
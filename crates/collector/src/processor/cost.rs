@@ -4,24 +4,108 @@
 //! Cost calculation processor.
 //!
 //! This processor automatically calculates the cost of LLM requests based on:
-//! - Token usage (prompt + completion tokens)
-//! - Model pricing (from pricing database)
-//! - Provider-specific pricing rules
+//! - Token usage (prompt + completion tokens, with prompt cache reads and
+//!   writes billed at their own rates)
+//! - Embedding tokens, generated images or audio minutes, picked from span attributes
+//! - Model pricing (the organization's registered models, then the pricing
+//!   database)
+//! - Provider-specific pricing rules such as batch API discounts
+//!
+//! When a span has no token usage (common for streamed responses), usage is
+//! estimated from the prompt and completion text and the span is marked with
+//! `llm_observatory.usage.estimated = true`.
+//!
+//! Prompt caching is reported with `gen_ai.usage.cache_read.input_tokens` and
+//! `gen_ai.usage.cache_creation.input_tokens`, both counted in the prompt
+//! tokens. Spans with either get `llm_observatory.cost.cache_savings_usd`:
+//! the cost without caching minus the actual cost.
+//!
+//! Requests to self-hosted deployments (vLLM, Ollama, ...), matched by model
+//! and `server.address`, are priced with the deployment's cost model instead
+//! and tagged with `llm_observatory.self_hosted.deployment`.
+//!
+//! Requests routed through a gateway (OpenRouter, LiteLLM) are priced as the
+//! upstream model in `llm_observatory.gateway.upstream_model`. When the
+//! gateway reported its cost, the computed cost is reconciled with it:
+//! `llm_observatory.gateway.cost_difference_usd` records the difference and
+//! `llm_observatory.gateway.cost_discrepancy` flags differences beyond the
+//! tolerance. Spans the pricing cannot price get the reported cost.
 
 use super::SpanProcessor;
 use async_trait::async_trait;
 use llm_observatory_core::{
     span::LlmSpan,
-    types::Cost,
+    types::{Cost, TokenUsage},
     Result,
 };
-use llm_observatory_providers::PricingEngine;
+use llm_observatory_providers::{
+    gateway::{
+        reconcile_cost, upstream_model_name, DEFAULT_DISCREPANCY_TOLERANCE,
+        GATEWAY_COST_DIFFERENCE, GATEWAY_COST_DISCREPANCY, GATEWAY_NAME, GATEWAY_REPORTED_COST,
+        GATEWAY_UPSTREAM_MODEL,
+    },
+    BillableUsage, PricingEngine, SelfHostedDeployment, TokenCountMethod, TokenCounter,
+};
+
+/// Operation name attribute (`chat`, `embeddings`, `image_generation`, ...).
+pub const OPERATION_NAME: &str = "gen_ai.operation.name";
+
+/// Prompt tokens served from the provider's prompt cache.
+pub const CACHED_INPUT_TOKENS: &str = "gen_ai.usage.cache_read.input_tokens";
+
+/// Prompt tokens written to the provider's prompt cache.
+pub const CACHE_WRITE_INPUT_TOKENS: &str = "gen_ai.usage.cache_creation.input_tokens";
+
+/// Attribute recording what prompt caching saved on the request (USD).
+pub const CACHE_SAVINGS: &str = "llm_observatory.cost.cache_savings_usd";
+
+/// Attribute set to `true` for requests sent through a batch API.
+pub const BATCH_REQUEST: &str = "llm_observatory.request.batch";
+
+/// Number of generated images.
+pub const IMAGE_COUNT: &str = "llm_observatory.image.count";
+
+/// Size of generated images (e.g., "1024x1024").
+pub const IMAGE_SIZE: &str = "llm_observatory.image.size";
+
+/// Duration of transcribed or generated audio in seconds.
+pub const AUDIO_DURATION: &str = "llm_observatory.audio.duration_seconds";
+
+/// Address of the server the request was sent to.
+pub const SERVER_ADDRESS: &str = "server.address";
+
+/// Attribute recording the self-hosted deployment that served the request.
+pub const SELF_HOSTED_DEPLOYMENT: &str = "llm_observatory.self_hosted.deployment";
+
+/// Attribute recording the cost model of a self-hosted deployment
+/// (`gpu_hour` or `per_token`).
+pub const SELF_HOSTED_COST_MODEL: &str = "llm_observatory.self_hosted.cost_model";
+
+/// Attribute holding the organization ID, whose registered models are
+/// resolved first.
+const ORG_ID_ATTRIBUTE: &str = "org_id";
+
+/// Image size assumed when none is recorded.
+const DEFAULT_IMAGE_SIZE: &str = "1024x1024";
+
+/// Attribute set to `true` when token usage was estimated from text.
+pub const USAGE_ESTIMATED: &str = "llm_observatory.usage.estimated";
+
+/// Attribute recording how estimated token usage was counted.
+pub const USAGE_ESTIMATION_METHOD: &str = "llm_observatory.usage.estimation_method";
 
 /// Cost calculation processor.
 #[derive(Debug, Clone, Default)]
 pub struct CostCalculationProcessor {
     /// Enable cost breakdown
     include_breakdown: bool,
+    /// Estimate token usage from text when the provider omitted it
+    estimate_missing_usage: bool,
+    /// Self-hosted deployments, priced with their own cost models
+    self_hosted: Vec<SelfHostedDeployment>,
+    /// Relative difference from gateway-reported cost that is flagged
+    /// (default: [`DEFAULT_DISCREPANCY_TOLERANCE`])
+    gateway_cost_tolerance: Option<f64>,
 }
 
 impl CostCalculationProcessor {
@@ -29,6 +113,9 @@ impl CostCalculationProcessor {
     pub fn new() -> Self {
         Self {
             include_breakdown: true,
+            estimate_missing_usage: true,
+            self_hosted: Vec::new(),
+            gateway_cost_tolerance: None,
         }
     }
 
@@ -38,32 +125,218 @@ impl CostCalculationProcessor {
         self
     }
 
+    /// Set whether to estimate token usage when it is missing.
+    pub fn with_usage_estimation(mut self, estimate_missing_usage: bool) -> Self {
+        self.estimate_missing_usage = estimate_missing_usage;
+        self
+    }
+
+    /// Set the self-hosted deployments; the first one matching a span prices
+    /// it.
+    pub fn with_self_hosted_deployments(mut self, deployments: Vec<SelfHostedDeployment>) -> Self {
+        self.self_hosted = deployments;
+        self
+    }
+
+    /// Set the relative difference between computed and gateway-reported
+    /// cost beyond which spans are flagged.
+    pub fn with_gateway_cost_tolerance(mut self, tolerance: f64) -> Self {
+        self.gateway_cost_tolerance = Some(tolerance);
+        self
+    }
+
+    /// Self-hosted deployment that served the span, if any.
+    fn self_hosted_deployment(&self, span: &LlmSpan) -> Option<&SelfHostedDeployment> {
+        let server_address = span.attributes.get(SERVER_ADDRESS).and_then(|v| v.as_str());
+        self.self_hosted
+            .iter()
+            .find(|deployment| deployment.matches(&span.model, server_address))
+    }
+
+    /// Estimate token usage from the span's prompt and completion text.
+    fn estimate_usage(&self, span: &mut LlmSpan) {
+        let prompt = TokenCounter::count_input(&span.model, &span.input);
+        let completion = span
+            .output
+            .as_ref()
+            .map(|o| TokenCounter::count_text(&span.model, &o.content));
+
+        let completion_tokens = completion.map(|c| c.tokens).unwrap_or(0);
+        if prompt.tokens == 0 && completion_tokens == 0 {
+            return;
+        }
+
+        let method = match completion {
+            Some(c) if c.method == TokenCountMethod::Heuristic => TokenCountMethod::Heuristic,
+            _ => prompt.method,
+        };
+
+        span.token_usage = Some(TokenUsage::new(prompt.tokens, completion_tokens));
+        span.attributes
+            .insert(USAGE_ESTIMATED.to_string(), true.into());
+        span.attributes
+            .insert(USAGE_ESTIMATION_METHOD.to_string(), method.as_str().into());
+    }
+
+    /// Work out what the span should be billed for from its attributes.
+    ///
+    /// Returns `None` for token-billed spans without token usage.
+    fn billable_usage(span: &LlmSpan) -> Option<BillableUsage> {
+        let attr_u64 = |key: &str| span.attributes.get(key).and_then(|v| v.as_u64());
+        let operation = span
+            .attributes
+            .get(OPERATION_NAME)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+
+        if let Some(seconds) = span.attributes.get(AUDIO_DURATION).and_then(|v| v.as_f64()) {
+            return Some(BillableUsage::Audio { seconds });
+        }
+
+        if operation == "image_generation" || span.attributes.contains_key(IMAGE_COUNT) {
+            let size = span
+                .attributes
+                .get(IMAGE_SIZE)
+                .and_then(|v| v.as_str())
+                .unwrap_or(DEFAULT_IMAGE_SIZE);
+            return Some(BillableUsage::Image {
+                count: attr_u64(IMAGE_COUNT).unwrap_or(1) as u32,
+                size: size.to_string(),
+            });
+        }
+
+        let usage = span.token_usage.as_ref()?;
+        if operation == "embeddings" {
+            return Some(BillableUsage::Embedding {
+                tokens: usage.prompt_tokens,
+            });
+        }
+
+        Some(BillableUsage::Tokens {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            cached_prompt_tokens: attr_u64(CACHED_INPUT_TOKENS).unwrap_or(0) as u32,
+            cache_write_prompt_tokens: attr_u64(CACHE_WRITE_INPUT_TOKENS).unwrap_or(0) as u32,
+        })
+    }
+
+    /// Whether the span is billed per token (and so benefits from estimation).
+    fn is_token_billed(span: &LlmSpan) -> bool {
+        !span.attributes.contains_key(AUDIO_DURATION)
+            && !span.attributes.contains_key(IMAGE_COUNT)
+            && span.attributes.get(OPERATION_NAME).and_then(|v| v.as_str())
+                != Some("image_generation")
+    }
+
+    /// Whether the span was sent through a batch API.
+    fn is_batch(span: &LlmSpan) -> bool {
+        span.attributes
+            .get(BATCH_REQUEST)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Organization of the span, if any.
+    fn org_id(span: &LlmSpan) -> Option<&str> {
+        span.attributes
+            .get(ORG_ID_ATTRIBUTE)
+            .and_then(|v| v.as_str())
+    }
+
+    /// Model the span is priced as: the upstream model of gateway requests,
+    /// else the requested model.
+    fn priced_model(span: &LlmSpan) -> &str {
+        span.attributes
+            .get(GATEWAY_UPSTREAM_MODEL)
+            .and_then(|v| v.as_str())
+            .map(upstream_model_name)
+            .unwrap_or(&span.model)
+    }
+
+    /// Reconcile the span's cost with the cost its gateway reported, if any.
+    fn reconcile_gateway_cost(&self, span: &mut LlmSpan) {
+        let Some(reported) = span
+            .attributes
+            .get(GATEWAY_REPORTED_COST)
+            .and_then(|v| v.as_f64())
+        else {
+            return;
+        };
+        let Some(cost) = &span.cost else {
+            span.cost = Some(Cost::new(reported));
+            return;
+        };
+
+        let reconciliation = reconcile_cost(
+            cost.amount_usd,
+            reported,
+            self.gateway_cost_tolerance
+                .unwrap_or(DEFAULT_DISCREPANCY_TOLERANCE),
+        );
+        span.attributes.insert(
+            GATEWAY_COST_DIFFERENCE.to_string(),
+            reconciliation.difference_usd.into(),
+        );
+        if reconciliation.discrepancy {
+            let gateway = span
+                .attributes
+                .get(GATEWAY_NAME)
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string();
+            metrics::counter!("collector_gateway_cost_discrepancies_total", "gateway" => gateway)
+                .increment(1);
+            span.attributes
+                .insert(GATEWAY_COST_DISCREPANCY.to_string(), true.into());
+        }
+    }
+
+    /// Calculate what prompt caching saved on a span.
+    ///
+    /// Returns `None` for spans without cache reads or writes.
+    fn cache_savings(span: &LlmSpan) -> Option<f64> {
+        let usage = Self::billable_usage(span)?;
+        match usage {
+            BillableUsage::Tokens {
+                cached_prompt_tokens,
+                cache_write_prompt_tokens,
+                ..
+            } if cached_prompt_tokens > 0 || cache_write_prompt_tokens > 0 => {
+                PricingEngine::calculate_cache_savings_for_org(
+                    Self::org_id(span),
+                    Self::priced_model(span),
+                    &usage,
+                    Self::is_batch(span),
+                )
+                .ok()
+            }
+            _ => None,
+        }
+    }
+
     /// Calculate cost for a span.
     fn calculate_cost(&self, span: &LlmSpan) -> Result<Option<Cost>> {
-        // Only calculate if we have token usage
-        let usage = match &span.token_usage {
+        // Only calculate if we know what was used
+        let usage = match Self::billable_usage(span) {
             Some(u) => u,
             None => return Ok(None),
         };
 
+        let cost = match self.self_hosted_deployment(span) {
+            Some(deployment) => deployment.calculate_usage_cost(&span.model, &usage)?,
+            None => PricingEngine::calculate_usage_cost_for_org(
+                Self::org_id(span),
+                Self::priced_model(span),
+                &usage,
+                Self::is_batch(span),
+            )?,
+        };
+
         if self.include_breakdown {
-            // Calculate with breakdown
-            let (prompt_cost, completion_cost, _total) = PricingEngine::calculate_cost_breakdown(
-                &span.model,
-                usage.prompt_tokens,
-                usage.completion_tokens,
-            )?;
-
-            Ok(Some(Cost::with_breakdown(prompt_cost, completion_cost)))
+            Ok(Some(cost))
         } else {
-            // Calculate total only
-            let total = PricingEngine::calculate_cost(
-                &span.model,
-                usage.prompt_tokens,
-                usage.completion_tokens,
-            )?;
-
-            Ok(Some(Cost::new(total)))
+            // Total only
+            Ok(Some(Cost::new(cost.amount_usd)))
         }
     }
 }
@@ -73,10 +346,32 @@ impl SpanProcessor for CostCalculationProcessor {
     async fn process(&self, mut span: LlmSpan) -> Result<Option<LlmSpan>> {
         // Only calculate if cost is not already set
         if span.cost.is_none() {
+            if span.token_usage.is_none()
+                && self.estimate_missing_usage
+                && Self::is_token_billed(&span)
+            {
+                self.estimate_usage(&mut span);
+            }
+
             if let Ok(Some(cost)) = self.calculate_cost(&span) {
                 span.cost = Some(cost);
+                if let Some(deployment) = self.self_hosted_deployment(&span) {
+                    span.attributes.insert(
+                        SELF_HOSTED_DEPLOYMENT.to_string(),
+                        deployment.name.clone().into(),
+                    );
+                    span.attributes.insert(
+                        SELF_HOSTED_COST_MODEL.to_string(),
+                        deployment.cost_model.as_str().into(),
+                    );
+                } else if let Some(savings) = Self::cache_savings(&span) {
+                    span.attributes
+                        .insert(CACHE_SAVINGS.to_string(), savings.into());
+                }
             }
             // If calculation fails (e.g., unknown model), just skip
+
+            self.reconcile_gateway_cost(&mut span);
         }
 
         Ok(Some(span))
@@ -91,7 +386,7 @@ impl SpanProcessor for CostCalculationProcessor {
 mod tests {
     use super::*;
     use llm_observatory_core::{
-        span::{LlmSpan, LlmInput, SpanStatus},
+        span::{LlmSpan, LlmInput, LlmOutput, SpanStatus},
         types::{Provider, Latency, TokenUsage},
     };
     use chrono::Utc;
@@ -119,6 +414,7 @@ mod tests {
             status: SpanStatus::Ok,
             attributes: Default::default(),
             events: vec![],
+            links: vec![],
         };
 
         let processed = processor.process(span).await.unwrap().unwrap();
@@ -154,6 +450,7 @@ mod tests {
             status: SpanStatus::Ok,
             attributes: Default::default(),
             events: vec![],
+            links: vec![],
         };
 
         let processed = processor.process(span).await.unwrap().unwrap();
@@ -166,7 +463,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_no_token_usage() {
-        let processor = CostCalculationProcessor::new();
+        let processor = CostCalculationProcessor::new().with_usage_estimation(false);
         let now = Utc::now();
 
         let span = LlmSpan {
@@ -187,9 +484,244 @@ mod tests {
             status: SpanStatus::Ok,
             attributes: Default::default(),
             events: vec![],
+            links: vec![],
+        };
+
+        let processed = processor.process(span).await.unwrap().unwrap();
+        assert!(processed.cost.is_none());
+    }
+
+    fn bare_span(model: &str, token_usage: Option<TokenUsage>) -> LlmSpan {
+        let now = Utc::now();
+        LlmSpan {
+            span_id: "test".to_string(),
+            trace_id: "test".to_string(),
+            parent_span_id: None,
+            name: "test".to_string(),
+            provider: Provider::OpenAI,
+            model: model.to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage,
+            cost: None,
+            latency: Latency::new(now, now),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes: Default::default(),
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_and_batch_pricing() {
+        let processor = CostCalculationProcessor::new();
+        let mut span = bare_span("gpt-4o", Some(TokenUsage::new(2000, 1000)));
+        span.attributes
+            .insert(CACHED_INPUT_TOKENS.to_string(), serde_json::json!(1000));
+        span.attributes
+            .insert(BATCH_REQUEST.to_string(), serde_json::json!(true));
+
+        let cost = processor.process(span).await.unwrap().unwrap().cost.unwrap();
+
+        // (1k * $0.0025 + 1k * $0.00125 + 1k * $0.01) * 50% batch discount
+        assert!((cost.amount_usd - 0.006875).abs() < 0.000001);
+    }
+
+    #[tokio::test]
+    async fn test_cache_write_pricing_and_savings() {
+        let processor = CostCalculationProcessor::new();
+        let mut span = bare_span("claude-3-5-sonnet-20241022", Some(TokenUsage::new(3000, 0)));
+        span.attributes
+            .insert(CACHED_INPUT_TOKENS.to_string(), serde_json::json!(1000));
+        span.attributes
+            .insert(CACHE_WRITE_INPUT_TOKENS.to_string(), serde_json::json!(1000));
+
+        let processed = processor.process(span).await.unwrap().unwrap();
+
+        // 1k * $0.003 + 1k * $0.0003 read + 1k * $0.00375 written
+        assert!((processed.cost.unwrap().amount_usd - 0.00705).abs() < 0.000001);
+        let savings = processed.attributes[CACHE_SAVINGS].as_f64().unwrap();
+        assert!((savings - 0.00195).abs() < 0.000001);
+
+        let span = bare_span("gpt-4o", Some(TokenUsage::new(1000, 0)));
+        let processed = processor.process(span).await.unwrap().unwrap();
+        assert!(!processed.attributes.contains_key(CACHE_SAVINGS));
+    }
+
+    #[tokio::test]
+    async fn test_embedding_and_image_pricing() {
+        let processor = CostCalculationProcessor::new();
+
+        let mut span = bare_span("text-embedding-3-small", Some(TokenUsage::new(1_000_000, 0)));
+        span.attributes
+            .insert(OPERATION_NAME.to_string(), serde_json::json!("embeddings"));
+        let cost = processor.process(span).await.unwrap().unwrap().cost.unwrap();
+        assert!((cost.amount_usd - 0.02).abs() < 0.000001);
+
+        let mut span = bare_span("dall-e-3", None);
+        span.attributes
+            .insert(OPERATION_NAME.to_string(), serde_json::json!("image_generation"));
+        span.attributes
+            .insert(IMAGE_COUNT.to_string(), serde_json::json!(3));
+        let processed = processor.process(span).await.unwrap().unwrap();
+        assert!((processed.cost.unwrap().amount_usd - 0.12).abs() < 0.000001);
+        assert!(processed.token_usage.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_estimates_missing_usage() {
+        let processor = CostCalculationProcessor::new();
+        let now = Utc::now();
+
+        let span = LlmSpan {
+            span_id: "test".to_string(),
+            trace_id: "test".to_string(),
+            parent_span_id: None,
+            name: "test".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4".to_string(),
+            input: LlmInput::Text {
+                prompt: "a".repeat(4000),
+            },
+            output: Some(LlmOutput {
+                content: "b".repeat(2000),
+                finish_reason: None,
+                metadata: Default::default(),
+            }),
+            token_usage: None, // Streaming response without usage
+            cost: None,
+            latency: Latency::new(now, now),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes: Default::default(),
+            events: vec![],
+            links: vec![],
         };
 
         let processed = processor.process(span).await.unwrap().unwrap();
+        let usage = processed.token_usage.unwrap();
+
+        assert!(usage.prompt_tokens > 0);
+        assert!(usage.completion_tokens > 0);
+        assert!(processed.cost.unwrap().amount_usd > 0.0);
+        assert_eq!(processed.attributes[USAGE_ESTIMATED], serde_json::json!(true));
+    }
+
+    #[tokio::test]
+    async fn test_self_hosted_deployment_cost() {
+        use llm_observatory_providers::SelfHostedCostModel;
+
+        let processor = CostCalculationProcessor::new().with_self_hosted_deployments(vec![
+            SelfHostedDeployment {
+                name: "llama-70b".to_string(),
+                models: vec!["llama3.1:70b".to_string()],
+                server_addresses: vec!["ollama.internal".to_string()],
+                cost_model: SelfHostedCostModel::PerToken {
+                    prompt_cost_per_1k: 0.0002,
+                    completion_cost_per_1k: 0.0006,
+                },
+            },
+        ]);
+        let now = Utc::now();
+
+        let mut span = LlmSpan {
+            span_id: "test".to_string(),
+            trace_id: "test".to_string(),
+            parent_span_id: None,
+            name: "test".to_string(),
+            provider: Provider::SelfHosted,
+            model: "llama3.1:70b".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: Some(TokenUsage::new(1000, 1000)),
+            cost: None,
+            latency: Latency::new(now, now),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes: Default::default(),
+            events: vec![],
+            links: vec![],
+        };
+
+        // Without the server address no deployment matches, and the model has
+        // no bundled pricing
+        let processed = processor.process(span.clone()).await.unwrap().unwrap();
         assert!(processed.cost.is_none());
+
+        span.attributes
+            .insert(SERVER_ADDRESS.to_string(), "ollama.internal".into());
+        let processed = processor.process(span).await.unwrap().unwrap();
+        assert!((processed.cost.unwrap().amount_usd - 0.0008).abs() < 1e-12);
+        assert_eq!(
+            processed.attributes[SELF_HOSTED_DEPLOYMENT],
+            serde_json::json!("llama-70b")
+        );
+        assert_eq!(
+            processed.attributes[SELF_HOSTED_COST_MODEL],
+            serde_json::json!("per_token")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gateway_cost_reconciliation() {
+        use llm_observatory_providers::{Gateway, GatewayMetadata};
+
+        let processor = CostCalculationProcessor::new();
+        let now = Utc::now();
+
+        let mut span = LlmSpan {
+            span_id: "test".to_string(),
+            trace_id: "test".to_string(),
+            parent_span_id: None,
+            name: "test".to_string(),
+            provider: Provider::Custom("openrouter".to_string()),
+            model: "openrouter/auto".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: Some(TokenUsage::new(1000, 500)),
+            cost: None,
+            latency: Latency::new(now, now),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes: Default::default(),
+            events: vec![],
+            links: vec![],
+        };
+        let metadata = GatewayMetadata {
+            upstream_model: Some("openai/gpt-4".to_string()),
+            reported_cost_usd: Some(0.0605),
+            ..GatewayMetadata::new(Gateway::OpenRouter)
+        };
+        metadata.record(&mut span.attributes);
+
+        // Priced as gpt-4 ($0.06), within tolerance of the reported cost
+        let processed = processor.process(span.clone()).await.unwrap().unwrap();
+        assert!((processed.cost.unwrap().amount_usd - 0.06).abs() < 1e-9);
+        assert!(!processed.attributes.contains_key(GATEWAY_COST_DISCREPANCY));
+
+        span.attributes
+            .insert(GATEWAY_REPORTED_COST.to_string(), 0.05.into());
+        let processed = processor.process(span.clone()).await.unwrap().unwrap();
+        let difference = processed.attributes[GATEWAY_COST_DIFFERENCE]
+            .as_f64()
+            .unwrap();
+        assert!((difference - 0.01).abs() < 1e-9);
+        assert_eq!(
+            processed.attributes[GATEWAY_COST_DISCREPANCY],
+            serde_json::json!(true)
+        );
+
+        // Upstream models without pricing get the reported cost
+        span.attributes
+            .insert(GATEWAY_UPSTREAM_MODEL.to_string(), "acme/unknown".into());
+        let processed = processor.process(span).await.unwrap().unwrap();
+        assert_eq!(processed.cost.unwrap().amount_usd, 0.05);
     }
 }
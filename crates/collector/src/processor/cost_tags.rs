@@ -0,0 +1,221 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cost-allocation tag normalization.
+//!
+//! Clients tag calls with `cost.tag.<key>` attributes (see
+//! [`llm_observatory_core::cost_tags`]), and rarely agree on spelling:
+//! `Team`, `team` and `team-name` with values `ML Platform`, `mlp` and
+//! `ml-platform`. This processor rewrites the tags of every span as
+//! configured in [`CostTagConfig`], so chargeback reports group them
+//! together:
+//!
+//! 1. keys are normalized with [`normalize_key`] and dropped unless allowed,
+//! 2. values are trimmed and, with `lowercase_values`, lowercased,
+//! 3. values listed in `value_mappings` for their key are renamed,
+//! 4. values longer than `max_value_len` characters are truncated.
+//!
+//! Dropped tags are counted in `collector_cost_tags_dropped_total`.
+
+use super::SpanProcessor;
+use crate::config::CostTagConfig;
+use async_trait::async_trait;
+use llm_observatory_core::{
+    cost_tags::{self, normalize_key, CostTags},
+    span::LlmSpan,
+    Result,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Cost-allocation tag normalization processor.
+#[derive(Debug, Clone)]
+pub struct CostTagProcessor {
+    /// Normalized keys kept; all keys when `None`
+    allowed_keys: Option<HashSet<String>>,
+    /// Value renames by normalized key, keyed on normalized values
+    value_mappings: HashMap<String, HashMap<String, String>>,
+    /// Lowercase values
+    lowercase_values: bool,
+    /// Longest value kept, in characters
+    max_value_len: usize,
+}
+
+impl CostTagProcessor {
+    /// Create a processor from its configuration.
+    pub fn new(config: &CostTagConfig) -> Self {
+        let allowed_keys = (!config.allowed_keys.is_empty()).then(|| {
+            config
+                .allowed_keys
+                .iter()
+                .filter_map(|key| normalize_key(key))
+                .collect()
+        });
+
+        let mut processor = Self {
+            allowed_keys,
+            value_mappings: HashMap::new(),
+            lowercase_values: config.lowercase_values,
+            max_value_len: config.max_value_len,
+        };
+        for (key, mappings) in &config.value_mappings {
+            let Some(key) = normalize_key(key) else {
+                continue;
+            };
+            let mappings = mappings
+                .iter()
+                .map(|(from, to)| (processor.normalize_value(from), to.clone()))
+                .collect();
+            processor.value_mappings.insert(key, mappings);
+        }
+        processor
+    }
+
+    /// Normalize a set of tags.
+    pub fn normalize(&self, tags: &CostTags) -> CostTags {
+        let mut normalized = CostTags::new();
+        for (key, value) in tags {
+            let Some(key) = normalize_key(key) else {
+                metrics::counter!("collector_cost_tags_dropped_total", "reason" => "invalid_key")
+                    .increment(1);
+                continue;
+            };
+            if self
+                .allowed_keys
+                .as_ref()
+                .is_some_and(|allowed| !allowed.contains(&key))
+            {
+                metrics::counter!("collector_cost_tags_dropped_total", "reason" => "not_allowed")
+                    .increment(1);
+                continue;
+            }
+
+            let mut value = self.normalize_value(value);
+            if let Some(mapped) = self.value_mappings.get(&key).and_then(|m| m.get(&value)) {
+                value = mapped.clone();
+            }
+            if let Some((end, _)) = value.char_indices().nth(self.max_value_len) {
+                value.truncate(end);
+            }
+            if !value.is_empty() {
+                normalized.entry(key).or_insert(value);
+            }
+        }
+        normalized
+    }
+
+    fn normalize_value(&self, value: &str) -> String {
+        let value = value.trim();
+        if self.lowercase_values {
+            value.to_lowercase()
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+#[async_trait]
+impl SpanProcessor for CostTagProcessor {
+    async fn process(&self, mut span: LlmSpan) -> Result<Option<LlmSpan>> {
+        let tags = cost_tags::cost_tags(&span.attributes);
+        if !tags.is_empty() {
+            let normalized = self.normalize(&tags);
+            cost_tags::set_cost_tags(&mut span.attributes, &normalized);
+        }
+        Ok(Some(span))
+    }
+
+    fn name(&self) -> &str {
+        "cost_tags"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use llm_observatory_core::{
+        cost_tags::cost_tag_attribute,
+        span::{LlmInput, SpanStatus},
+        types::{Latency, Provider},
+    };
+    use serde_json::json;
+
+    fn config() -> CostTagConfig {
+        CostTagConfig {
+            enabled: true,
+            allowed_keys: vec!["team".to_string(), "Cost Center".to_string()],
+            value_mappings: HashMap::from([(
+                "team".to_string(),
+                HashMap::from([
+                    ("MLP".to_string(), "ml_platform".to_string()),
+                    ("ml-platform".to_string(), "ml_platform".to_string()),
+                ]),
+            )]),
+            ..Default::default()
+        }
+    }
+
+    fn tags(pairs: &[(&str, &str)]) -> CostTags {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_normalize() {
+        let processor = CostTagProcessor::new(&config());
+
+        let normalized = processor.normalize(&tags(&[
+            ("Team", " mlp "),
+            ("cost-center", "CC-42"),
+            ("feature", "search"),
+        ]));
+        assert_eq!(
+            normalized,
+            tags(&[("cost_center", "cc-42"), ("team", "ml_platform")])
+        );
+
+        let processor = CostTagProcessor::new(&CostTagConfig {
+            max_value_len: 4,
+            lowercase_values: false,
+            ..Default::default()
+        });
+        let normalized = processor.normalize(&tags(&[("feature", "Summarize")]));
+        assert_eq!(normalized, tags(&[("feature", "Summ")]));
+    }
+
+    #[tokio::test]
+    async fn test_rewrites_span_tags() {
+        let now = Utc::now();
+        let span = LlmSpan::builder()
+            .span_id("span")
+            .trace_id("trace")
+            .name("chat")
+            .provider(Provider::OpenAI)
+            .model("gpt-4o")
+            .input(LlmInput::Text {
+                prompt: "Test".to_string(),
+            })
+            .latency(Latency::new(now, now))
+            .status(SpanStatus::Ok)
+            .cost_tag("Team", "ML-Platform")
+            .cost_tag("feature", "search")
+            .attribute("llm.model", json!("gpt-4o"))
+            .build()
+            .unwrap();
+
+        let processed = CostTagProcessor::new(&config())
+            .process(span)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            processed.attributes[&cost_tag_attribute("team")],
+            json!("ml_platform")
+        );
+        assert!(!processed.attributes.contains_key("cost.tag.Team"));
+        assert!(!processed.attributes.contains_key("cost.tag.feature"));
+        assert_eq!(processed.attributes["llm.model"], json!("gpt-4o"));
+    }
+}
@@ -0,0 +1,339 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Ingestion deduplication.
+//!
+//! Clients with aggressive retries resend span batches that were already
+//! received. This processor keys every span on `(trace_id, span_id)` and
+//! treats a key seen again within the dedup window as a duplicate, which is
+//! dropped or, with [`DuplicateAction::Annotate`], forwarded with the
+//! [`DUPLICATE_ATTRIBUTE`] attribute. Every duplicate increments
+//! `collector_dedup_duplicates_total`.
+//!
+//! Keys live in a [`DedupStore`]: [`RedisDedupStore`] (`SET NX EX`, shared by
+//! all collector replicas) or [`MemoryDedupStore`] (per process) when no Redis
+//! is configured. If the store fails, spans are forwarded and the failure is
+//! counted in `collector_dedup_errors_total`; losing deduplication is
+//! preferable to losing data.
+
+use super::SpanProcessor;
+use crate::config::DedupConfig;
+use async_trait::async_trait;
+use llm_observatory_core::{span::LlmSpan, Error, Result};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub use crate::config::DuplicateAction;
+
+/// Attribute set on duplicates forwarded with [`DuplicateAction::Annotate`].
+pub const DUPLICATE_ATTRIBUTE: &str = "llm_observatory.duplicate";
+
+/// Storage of recently seen span keys.
+#[async_trait]
+pub trait DedupStore: Send + Sync {
+    /// Record a key, returning whether it was already seen within the window.
+    async fn check_and_record(&self, key: &str) -> Result<bool>;
+
+    /// Store name, used in logs.
+    fn name(&self) -> &'static str;
+}
+
+/// Per-process store of span keys.
+///
+/// Holds at most `max_entries` keys; when full, the oldest key is evicted
+/// before its window ends.
+#[derive(Debug)]
+pub struct MemoryDedupStore {
+    window: Duration,
+    max_entries: usize,
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    /// Keys and when they were first seen
+    seen: HashMap<String, Instant>,
+    /// Keys in the order they were first seen
+    order: VecDeque<(String, Instant)>,
+}
+
+impl MemoryDedupStore {
+    /// Create a store remembering keys for `window`.
+    pub fn new(window: Duration, max_entries: usize) -> Self {
+        Self {
+            window,
+            max_entries: max_entries.max(1),
+            state: Mutex::new(MemoryState::default()),
+        }
+    }
+
+    /// Number of keys currently held.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().seen.len()
+    }
+
+    /// Whether no keys are held.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn check_and_record_at(&self, key: &str, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        // Keys are never refreshed, so insertion order is expiry order
+        while let Some((_, seen_at)) = state.order.front() {
+            if now.duration_since(*seen_at) < self.window {
+                break;
+            }
+            let (expired, _) = state.order.pop_front().unwrap();
+            state.seen.remove(&expired);
+        }
+
+        if state.seen.contains_key(key) {
+            return true;
+        }
+
+        if state.order.len() >= self.max_entries {
+            if let Some((evicted, _)) = state.order.pop_front() {
+                state.seen.remove(&evicted);
+            }
+        }
+        state.seen.insert(key.to_string(), now);
+        state.order.push_back((key.to_string(), now));
+        false
+    }
+}
+
+#[async_trait]
+impl DedupStore for MemoryDedupStore {
+    async fn check_and_record(&self, key: &str) -> Result<bool> {
+        Ok(self.check_and_record_at(key, Instant::now()))
+    }
+
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+}
+
+/// Store of span keys in Redis, shared by all collector replicas.
+///
+/// Each key is a Redis string set with `SET <prefix><key> 1 NX EX <window>`,
+/// so the first writer wins and keys expire on their own.
+#[derive(Clone)]
+pub struct RedisDedupStore {
+    connection: redis::aio::ConnectionManager,
+    key_prefix: String,
+    window_secs: u64,
+}
+
+impl RedisDedupStore {
+    /// Connect to Redis.
+    pub async fn connect(url: &str, key_prefix: &str, window: Duration) -> Result<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| Error::config(format!("Invalid dedup Redis URL: {}", e)))?;
+        let connection = redis::aio::ConnectionManager::new(client)
+            .await
+            .map_err(|e| Error::storage(format!("Failed to connect to dedup Redis: {}", e)))?;
+
+        Ok(Self {
+            connection,
+            key_prefix: key_prefix.to_string(),
+            window_secs: window.as_secs().max(1),
+        })
+    }
+}
+
+#[async_trait]
+impl DedupStore for RedisDedupStore {
+    async fn check_and_record(&self, key: &str) -> Result<bool> {
+        let mut connection = self.connection.clone();
+        let set: Option<String> = redis::cmd("SET")
+            .arg(format!("{}{}", self.key_prefix, key))
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(self.window_secs)
+            .query_async(&mut connection)
+            .await
+            .map_err(|e| Error::storage(format!("Dedup Redis command failed: {}", e)))?;
+
+        // NX returns nil when the key already exists
+        Ok(set.is_none())
+    }
+
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+}
+
+/// Ingestion deduplication processor.
+#[derive(Clone)]
+pub struct DeduplicationProcessor {
+    store: Arc<dyn DedupStore>,
+    action: DuplicateAction,
+}
+
+impl DeduplicationProcessor {
+    /// Create a processor that drops duplicates recorded in `store`.
+    pub fn new(store: Arc<dyn DedupStore>) -> Self {
+        Self {
+            store,
+            action: DuplicateAction::default(),
+        }
+    }
+
+    /// Create a processor from configuration, connecting to Redis if
+    /// `redis_url` is set.
+    pub async fn from_config(config: &DedupConfig) -> Result<Self> {
+        let window = Duration::from_secs(config.window_secs);
+        let store: Arc<dyn DedupStore> = match &config.redis_url {
+            Some(url) => Arc::new(RedisDedupStore::connect(url, &config.key_prefix, window).await?),
+            None => Arc::new(MemoryDedupStore::new(window, config.max_entries)),
+        };
+
+        tracing::info!(
+            store = store.name(),
+            window_secs = config.window_secs,
+            action = ?config.action,
+            "Span deduplication enabled"
+        );
+
+        Ok(Self::new(store).with_action(config.action))
+    }
+
+    /// Set what to do with duplicates.
+    pub fn with_action(mut self, action: DuplicateAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Dedup key of a span.
+    pub fn key(span: &LlmSpan) -> String {
+        format!("{}:{}", span.trace_id, span.span_id)
+    }
+}
+
+#[async_trait]
+impl SpanProcessor for DeduplicationProcessor {
+    async fn process(&self, mut span: LlmSpan) -> Result<Option<LlmSpan>> {
+        let duplicate = match self.store.check_and_record(&Self::key(&span)).await {
+            Ok(duplicate) => duplicate,
+            Err(e) => {
+                metrics::counter!("collector_dedup_errors_total", "store" => self.store.name())
+                    .increment(1);
+                tracing::warn!(error = %e, "Span deduplication failed, forwarding span");
+                return Ok(Some(span));
+            }
+        };
+
+        if !duplicate {
+            return Ok(Some(span));
+        }
+
+        match self.action {
+            DuplicateAction::Drop => {
+                metrics::counter!("collector_dedup_duplicates_total", "action" => "drop")
+                    .increment(1);
+                tracing::debug!(
+                    trace_id = %span.trace_id,
+                    span_id = %span.span_id,
+                    "Dropping duplicate span"
+                );
+                Ok(None)
+            }
+            DuplicateAction::Annotate => {
+                metrics::counter!("collector_dedup_duplicates_total", "action" => "annotate")
+                    .increment(1);
+                span.attributes
+                    .insert(DUPLICATE_ATTRIBUTE.to_string(), true.into());
+                Ok(Some(span))
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "deduplication"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use llm_observatory_core::{
+        span::{LlmInput, SpanStatus},
+        types::{Latency, Provider},
+    };
+
+    fn span(trace_id: &str, span_id: &str) -> LlmSpan {
+        let now = Utc::now();
+        LlmSpan {
+            span_id: span_id.to_string(),
+            trace_id: trace_id.to_string(),
+            parent_span_id: None,
+            name: "test".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: None,
+            cost: None,
+            latency: Latency::new(now, now),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes: Default::default(),
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    fn memory_store() -> Arc<dyn DedupStore> {
+        Arc::new(MemoryDedupStore::new(Duration::from_secs(60), 100))
+    }
+
+    #[tokio::test]
+    async fn test_drops_duplicates() {
+        let processor = DeduplicationProcessor::new(memory_store());
+
+        assert!(processor.process(span("t1", "s1")).await.unwrap().is_some());
+        assert!(processor.process(span("t1", "s2")).await.unwrap().is_some());
+        assert!(processor.process(span("t1", "s1")).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_annotates_duplicates() {
+        let processor =
+            DeduplicationProcessor::new(memory_store()).with_action(DuplicateAction::Annotate);
+
+        let first = processor.process(span("t1", "s1")).await.unwrap().unwrap();
+        assert!(!first.attributes.contains_key(DUPLICATE_ATTRIBUTE));
+
+        let second = processor.process(span("t1", "s1")).await.unwrap().unwrap();
+        assert_eq!(
+            second.attributes[DUPLICATE_ATTRIBUTE],
+            serde_json::json!(true)
+        );
+    }
+
+    #[test]
+    fn test_memory_store_window_and_capacity() {
+        let store = MemoryDedupStore::new(Duration::from_secs(10), 2);
+        let start = Instant::now();
+
+        assert!(!store.check_and_record_at("a", start));
+        assert!(store.check_and_record_at("a", start + Duration::from_secs(5)));
+
+        // Expired after the window
+        assert!(!store.check_and_record_at("a", start + Duration::from_secs(10)));
+
+        // Capacity evicts the oldest key
+        let later = start + Duration::from_secs(11);
+        assert!(!store.check_and_record_at("b", later));
+        assert!(!store.check_and_record_at("c", later));
+        assert_eq!(store.len(), 2);
+        assert!(!store.check_and_record_at("a", later));
+    }
+}
@@ -0,0 +1,187 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Model metadata enrichment processor.
+//!
+//! This processor looks up the span's model in the pricing database and adds:
+//! - Model family (for grouping dated model versions together)
+//! - Context window and maximum output tokens
+//! - Supported input modalities
+//! - Deprecation status and suggested replacement
+//! - Version of the pricing data used for cost calculation
+
+use super::SpanProcessor;
+use async_trait::async_trait;
+use llm_observatory_core::{span::LlmSpan, Result};
+use llm_observatory_providers::pricing::{PricingDatabase, PRICING_DB};
+use std::sync::Arc;
+
+/// Model family attribute key.
+pub const MODEL_FAMILY: &str = "llm_observatory.model.family";
+/// Context window attribute key.
+pub const MODEL_CONTEXT_WINDOW: &str = "llm_observatory.model.context_window";
+/// Maximum output tokens attribute key.
+pub const MODEL_MAX_OUTPUT_TOKENS: &str = "llm_observatory.model.max_output_tokens";
+/// Input modalities attribute key.
+pub const MODEL_MODALITIES: &str = "llm_observatory.model.modalities";
+/// Deprecation status attribute key.
+pub const MODEL_DEPRECATED: &str = "llm_observatory.model.deprecated";
+/// Replacement model attribute key (deprecated models only).
+pub const MODEL_REPLACEMENT: &str = "llm_observatory.model.replacement";
+/// Pricing data version attribute key.
+pub const PRICE_VERSION: &str = "llm_observatory.pricing.version";
+
+/// Model metadata enrichment processor.
+#[derive(Debug, Clone)]
+pub struct ModelEnrichmentProcessor {
+    /// Source of model metadata
+    database: Arc<PricingDatabase>,
+}
+
+impl ModelEnrichmentProcessor {
+    /// Create a new enrichment processor using the bundled pricing database.
+    pub fn new() -> Self {
+        Self::with_database(PRICING_DB.clone())
+    }
+
+    /// Create a new enrichment processor using a custom pricing database.
+    pub fn with_database(database: PricingDatabase) -> Self {
+        Self {
+            database: Arc::new(database),
+        }
+    }
+
+    /// Model name to look up, preferring the span's model field.
+    fn model_name(span: &LlmSpan) -> Option<String> {
+        if !span.model.is_empty() {
+            return Some(span.model.clone());
+        }
+        span.attributes
+            .get("gen_ai.request.model")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    }
+}
+
+impl Default for ModelEnrichmentProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SpanProcessor for ModelEnrichmentProcessor {
+    async fn process(&self, mut span: LlmSpan) -> Result<Option<LlmSpan>> {
+        let Some(model) = Self::model_name(&span) else {
+            return Ok(Some(span));
+        };
+
+        // Unknown models are forwarded without enrichment
+        let Ok(info) = self.database.get_model_info(&model) else {
+            return Ok(Some(span));
+        };
+
+        let modalities: Vec<serde_json::Value> =
+            info.modalities.iter().map(|m| m.as_str().into()).collect();
+
+        let attributes = &mut span.attributes;
+        attributes.insert(MODEL_FAMILY.to_string(), info.family.into());
+        attributes.insert(MODEL_CONTEXT_WINDOW.to_string(), info.context_window.into());
+        attributes.insert(MODEL_MAX_OUTPUT_TOKENS.to_string(), info.max_output_tokens.into());
+        attributes.insert(MODEL_MODALITIES.to_string(), modalities.into());
+        attributes.insert(MODEL_DEPRECATED.to_string(), info.deprecated.into());
+        attributes.insert(
+            PRICE_VERSION.to_string(),
+            self.database.price_version().into(),
+        );
+
+        if info.deprecated {
+            if let Some(replacement) = info.replacement {
+                attributes.insert(MODEL_REPLACEMENT.to_string(), replacement.into());
+            }
+            metrics::counter!("collector_deprecated_model_spans_total", "model" => info.model)
+                .increment(1);
+        }
+
+        Ok(Some(span))
+    }
+
+    fn name(&self) -> &str {
+        "model_enrichment"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use llm_observatory_core::{
+        span::{LlmInput, SpanStatus},
+        types::{Latency, Provider},
+    };
+    use serde_json::json;
+
+    fn span(model: &str) -> LlmSpan {
+        let now = Utc::now();
+        LlmSpan {
+            span_id: "test".to_string(),
+            trace_id: "test".to_string(),
+            parent_span_id: None,
+            name: "test".to_string(),
+            provider: Provider::Anthropic,
+            model: model.to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: None,
+            cost: None,
+            latency: Latency::new(now, now),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes: Default::default(),
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enrich_known_model() {
+        let processor = ModelEnrichmentProcessor::new();
+        let processed = processor
+            .process(span("claude-3-5-sonnet-20241022"))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(processed.attributes[MODEL_FAMILY], json!("claude-3.5"));
+        assert_eq!(processed.attributes[MODEL_CONTEXT_WINDOW], json!(200_000));
+        assert_eq!(processed.attributes[MODEL_MODALITIES], json!(["text", "image"]));
+        assert_eq!(processed.attributes[MODEL_DEPRECATED], json!(false));
+        assert!(processed.attributes.contains_key(PRICE_VERSION));
+        assert!(!processed.attributes.contains_key(MODEL_REPLACEMENT));
+    }
+
+    #[tokio::test]
+    async fn test_enrich_deprecated_model() {
+        let processor = ModelEnrichmentProcessor::new();
+        let processed = processor
+            .process(span("claude-3-sonnet-20240229"))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(processed.attributes[MODEL_DEPRECATED], json!(true));
+        assert_eq!(
+            processed.attributes[MODEL_REPLACEMENT],
+            json!("claude-3-5-sonnet-20241022")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_model_passes_through() {
+        let processor = ModelEnrichmentProcessor::new();
+        let processed = processor.process(span("my-finetune")).await.unwrap().unwrap();
+        assert!(processed.attributes.is_empty());
+    }
+}
@@ -0,0 +1,308 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Guardrail violation extraction.
+//!
+//! This processor reads guardrail outcomes recorded with the `guardrail.*`
+//! attribute conventions (see [`llm_observatory_core::guardrail`]), on the
+//! span itself or on `guardrail.evaluation` span events. Every blocked or
+//! flagged outcome becomes a [`GuardrailEvent`], in the row format of the
+//! `guardrail_events` table, and increments
+//! `collector_guardrail_violations_total`.
+//!
+//! Spans pass through unchanged. Events are taken with
+//! [`GuardrailEventProcessor::drain`]. Passing outcomes are not kept; violation
+//! rates are computed against the request counts in `llm_traces`.
+
+use super::SpanProcessor;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use llm_observatory_core::{
+    guardrail::{GuardrailOutcome, GuardrailStage, GUARDRAIL_EVENT},
+    span::LlmSpan,
+    Result,
+};
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// Attribute holding the team ID.
+const TEAM_ID_ATTRIBUTE: &str = "team.id";
+
+/// Attribute holding the organization ID.
+const ORG_ID_ATTRIBUTE: &str = "org_id";
+
+/// Default maximum number of events buffered between drains.
+pub const DEFAULT_MAX_BUFFERED_EVENTS: usize = 10_000;
+
+/// What the guardrail did about a violation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GuardrailAction {
+    /// The call was blocked
+    Blocked,
+    /// The call went ahead but was flagged
+    Flagged,
+}
+
+impl GuardrailAction {
+    /// Value stored in `guardrail_events.action`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GuardrailAction::Blocked => "blocked",
+            GuardrailAction::Flagged => "flagged",
+        }
+    }
+}
+
+/// A guardrail violation, in the `guardrail_events` row format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GuardrailEvent {
+    /// When the guardrail ran (event time, or span end time)
+    pub ts: DateTime<Utc>,
+    /// Organization ID, if the span carries one
+    pub org_id: Option<String>,
+    /// Trace ID of the call
+    pub trace_id: String,
+    /// Span ID of the call
+    pub span_id: String,
+    /// Provider name
+    pub provider: String,
+    /// Model name
+    pub model: String,
+    /// Team ID (`team.id`)
+    pub team_id: Option<String>,
+    /// Deployment environment
+    pub environment: Option<String>,
+    /// Guardrail name
+    pub guardrail: Option<String>,
+    /// Stage the guardrail ran at
+    pub stage: Option<GuardrailStage>,
+    /// Violation category
+    pub category: Option<String>,
+    /// Blocked or flagged
+    pub action: GuardrailAction,
+    /// Classifier score
+    pub score: Option<f64>,
+}
+
+/// Guardrail violation extraction processor.
+#[derive(Debug)]
+pub struct GuardrailEventProcessor {
+    /// Events extracted since the last drain
+    events: Mutex<Vec<GuardrailEvent>>,
+    /// Events kept between drains; further events are dropped
+    max_buffered: usize,
+}
+
+impl GuardrailEventProcessor {
+    /// Create a new processor.
+    pub fn new() -> Self {
+        Self {
+            events: Mutex::new(Vec::new()),
+            max_buffered: DEFAULT_MAX_BUFFERED_EVENTS,
+        }
+    }
+
+    /// Set the maximum number of events buffered between drains.
+    pub fn with_max_buffered(mut self, max_buffered: usize) -> Self {
+        self.max_buffered = max_buffered;
+        self
+    }
+
+    /// Take the events extracted since the last drain, oldest first.
+    pub fn drain(&self) -> Vec<GuardrailEvent> {
+        let mut events = std::mem::take(&mut *self.events.lock().unwrap());
+        events.sort_by_key(|event| event.ts);
+        events
+    }
+
+    /// Guardrail violations recorded on a span and its events.
+    pub fn extract(span: &LlmSpan) -> Vec<GuardrailEvent> {
+        let span_outcome = GuardrailOutcome::from_attributes(&span.attributes)
+            .map(|outcome| (span.latency.end_time, outcome));
+        let event_outcomes = span
+            .events
+            .iter()
+            .filter(|event| event.name == GUARDRAIL_EVENT)
+            .filter_map(|event| {
+                GuardrailOutcome::from_attributes(&event.attributes)
+                    .map(|outcome| (event.timestamp, outcome))
+            });
+
+        let attribute = |key: &str| {
+            span.attributes
+                .get(key)
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+                .or_else(|| span.metadata.attributes.get(key).cloned())
+        };
+
+        span_outcome
+            .into_iter()
+            .chain(event_outcomes)
+            .filter_map(|(ts, outcome)| {
+                let action = if outcome.blocked {
+                    GuardrailAction::Blocked
+                } else if outcome.flagged {
+                    GuardrailAction::Flagged
+                } else {
+                    return None;
+                };
+
+                Some(GuardrailEvent {
+                    ts,
+                    org_id: attribute(ORG_ID_ATTRIBUTE),
+                    trace_id: span.trace_id.clone(),
+                    span_id: span.span_id.clone(),
+                    provider: span.provider.as_str().to_string(),
+                    model: span.model.clone(),
+                    team_id: attribute(TEAM_ID_ATTRIBUTE),
+                    environment: span.metadata.environment.clone(),
+                    guardrail: outcome.name,
+                    stage: outcome.stage,
+                    category: outcome.category,
+                    action,
+                    score: outcome.score,
+                })
+            })
+            .collect()
+    }
+
+    fn record(&self, span: &LlmSpan) {
+        let extracted = Self::extract(span);
+        if extracted.is_empty() {
+            return;
+        }
+
+        let mut events = self.events.lock().unwrap();
+        for event in extracted {
+            metrics::counter!(
+                "collector_guardrail_violations_total",
+                "action" => event.action.as_str(),
+                "category" => event.category.clone().unwrap_or_else(|| "unknown".to_string())
+            )
+            .increment(1);
+
+            if events.len() < self.max_buffered {
+                events.push(event);
+            } else {
+                metrics::counter!("collector_guardrail_events_dropped_total").increment(1);
+            }
+        }
+    }
+}
+
+impl Default for GuardrailEventProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SpanProcessor for GuardrailEventProcessor {
+    async fn process(&self, span: LlmSpan) -> Result<Option<LlmSpan>> {
+        self.record(&span);
+        Ok(Some(span))
+    }
+
+    fn name(&self) -> &str {
+        "guardrail_events"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use llm_observatory_core::{
+        span::{LlmInput, SpanEvent, SpanStatus},
+        types::{Latency, Provider},
+    };
+
+    fn span() -> LlmSpan {
+        let start = Utc::now();
+        LlmSpan {
+            span_id: "span-1".to_string(),
+            trace_id: "trace-1".to_string(),
+            parent_span_id: None,
+            name: "llm.chat".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4o".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: None,
+            cost: None,
+            latency: Latency::new(start, start + Duration::milliseconds(200)),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes: Default::default(),
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    fn event(outcome: GuardrailOutcome) -> SpanEvent {
+        SpanEvent {
+            name: GUARDRAIL_EVENT.to_string(),
+            timestamp: Utc::now(),
+            attributes: outcome.to_attributes(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extracts_violations_from_events() {
+        let mut span = span();
+        span.metadata
+            .attributes
+            .insert(TEAM_ID_ATTRIBUTE.to_string(), "search".to_string());
+        span.attributes
+            .insert(ORG_ID_ATTRIBUTE.to_string(), serde_json::json!("org-1"));
+        span.events = vec![
+            event(GuardrailOutcome::new("moderation").with_stage(GuardrailStage::Input)),
+            event(
+                GuardrailOutcome::new("moderation")
+                    .with_stage(GuardrailStage::Output)
+                    .with_category("hate")
+                    .with_score(0.93)
+                    .flagged(),
+            ),
+        ];
+
+        let processor = GuardrailEventProcessor::new();
+        assert!(processor.process(span).await.unwrap().is_some());
+
+        let events = processor.drain();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.action, GuardrailAction::Flagged);
+        assert_eq!(event.category.as_deref(), Some("hate"));
+        assert_eq!(event.stage, Some(GuardrailStage::Output));
+        assert_eq!(event.team_id.as_deref(), Some("search"));
+        assert_eq!(event.org_id.as_deref(), Some("org-1"));
+        assert_eq!(event.score, Some(0.93));
+
+        assert!(processor.drain().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_span_attributes_and_buffer_limit() {
+        let mut span = span();
+        span.attributes
+            .insert("guardrail.blocked".to_string(), serde_json::json!(true));
+
+        let processor = GuardrailEventProcessor::new().with_max_buffered(1);
+        processor.process(span.clone()).await.unwrap();
+        processor.process(span.clone()).await.unwrap();
+
+        let events = processor.drain();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, GuardrailAction::Blocked);
+        assert_eq!(events[0].ts, span.latency.end_time);
+        assert_eq!(
+            serde_json::to_value(&events[0]).unwrap()["action"],
+            serde_json::json!("blocked")
+        );
+    }
+}
@@ -0,0 +1,484 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Log pattern mining.
+//!
+//! This processor groups similar log bodies into patterns with a Drain-like
+//! template miner. Bodies are split on whitespace and tokens containing a
+//! digit (IDs, counts, durations, addresses) are replaced by the wildcard
+//! `<*>`. Patterns are kept per organization, service, token count and first
+//! token; a body joins the pattern of its group whose template matches the
+//! most tokens, if at least the similarity threshold of them match, and
+//! positions that differ become wildcards. Bodies matching no pattern start
+//! a new one.
+//!
+//! Each record gets its pattern's ID in the `log.pattern_id` attribute, which
+//! the storage writer keeps in the `pattern_id` column of `logs`. Patterns
+//! and their hourly record counts are drained with
+//! [`LogPatternMiner::drain`] as rows for `log_patterns` and
+//! `log_pattern_counts`. Run the miner before the
+//! [`LogProcessor`](super::logs::LogProcessor), so counts cover records that
+//! are sampled out.
+//!
+//! Pattern IDs hash the organization, service and template the pattern was
+//! created with, so replicas that start a pattern from bodies masking to the
+//! same template agree on its ID.
+
+use super::logs::{attribute, service_name, LogSeverity};
+use crate::config::LogPatternConfig;
+use crate::routing::hash;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use opentelemetry_proto::tonic::common::v1::{any_value, AnyValue, KeyValue};
+use opentelemetry_proto::tonic::logs::v1::{LogRecord, ResourceLogs};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// Log attribute holding the pattern ID.
+pub const PATTERN_ID_ATTRIBUTE: &str = "log.pattern_id";
+
+/// Token standing for any value in a template.
+pub const WILDCARD: &str = "<*>";
+
+/// Attribute holding the organization ID, on the resource or the record.
+const ORG_ID_ATTRIBUTE: &str = "org_id";
+
+/// A log pattern, in the `log_patterns` row format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogPattern {
+    /// Pattern ID
+    pub pattern_id: String,
+    /// Organization ID, if the logs carry one
+    pub org_id: Option<String>,
+    /// Service name
+    pub service_name: String,
+    /// Template, with `<*>` for varying tokens
+    pub template: String,
+    /// Highest severity number of the records
+    pub severity_number: i32,
+    /// Time of the first record
+    pub first_seen: DateTime<Utc>,
+    /// Time of the last record
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Records of a pattern in one hour, in the `log_pattern_counts` row format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogPatternCount {
+    /// Start of the hour
+    pub bucket: DateTime<Utc>,
+    /// Organization ID, if the logs carry one
+    pub org_id: Option<String>,
+    /// Pattern ID
+    pub pattern_id: String,
+    /// Records
+    pub count: u64,
+}
+
+/// Rows drained from the miner.
+#[derive(Debug, Default)]
+pub struct LogPatternBatch {
+    /// Patterns created or matched since the last drain
+    pub patterns: Vec<LogPattern>,
+    /// Record counts since the last drain
+    pub counts: Vec<LogPatternCount>,
+}
+
+/// A pattern and its template tokens.
+#[derive(Debug)]
+struct Cluster {
+    pattern: LogPattern,
+    tokens: Vec<String>,
+}
+
+/// Pattern group: organization, service, token count and first token.
+type GroupKey = (Option<String>, String, usize, String);
+
+#[derive(Debug, Default)]
+struct MinerState {
+    clusters: Vec<Cluster>,
+    /// Indices into `clusters` per group
+    groups: HashMap<GroupKey, Vec<usize>>,
+    /// Records per hour and cluster since the last drain
+    counts: HashMap<(DateTime<Utc>, usize), u64>,
+    /// Clusters created or matched since the last drain
+    changed: HashSet<usize>,
+}
+
+/// Log pattern miner.
+#[derive(Debug)]
+pub struct LogPatternMiner {
+    /// Fraction of tokens that must match for a body to join a pattern
+    similarity_threshold: f64,
+    /// Patterns held
+    max_patterns: usize,
+    state: Mutex<MinerState>,
+}
+
+impl LogPatternMiner {
+    /// Create a miner with the default threshold and pattern limit.
+    pub fn new() -> Self {
+        Self::from_config(&LogPatternConfig::default())
+    }
+
+    /// Create a miner from configuration.
+    pub fn from_config(config: &LogPatternConfig) -> Self {
+        Self {
+            similarity_threshold: config.similarity_threshold.clamp(0.0, 1.0),
+            max_patterns: config.max_patterns,
+            state: Mutex::new(MinerState::default()),
+        }
+    }
+
+    /// Set the fraction of tokens that must match for a body to join a
+    /// pattern.
+    pub fn with_similarity_threshold(mut self, threshold: f64) -> Self {
+        self.similarity_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the maximum number of patterns held.
+    pub fn with_max_patterns(mut self, max_patterns: usize) -> Self {
+        self.max_patterns = max_patterns;
+        self
+    }
+
+    /// Number of patterns held.
+    pub fn pattern_count(&self) -> usize {
+        self.state.lock().unwrap().clusters.len()
+    }
+
+    /// Assign string-bodied records to patterns and tag them with the
+    /// pattern ID.
+    pub fn process(&self, logs: &mut [ResourceLogs]) {
+        let mut state = self.state.lock().unwrap();
+
+        for resource_logs in logs {
+            let service = service_name(resource_logs).unwrap_or_default().to_string();
+            let resource_org = resource_logs
+                .resource
+                .as_ref()
+                .and_then(|resource| attribute(&resource.attributes, ORG_ID_ATTRIBUTE))
+                .map(str::to_string);
+
+            for scope_logs in &mut resource_logs.scope_logs {
+                for record in &mut scope_logs.log_records {
+                    let Some(tokens) = body_text(record).map(tokenize) else {
+                        continue;
+                    };
+                    if tokens.is_empty() {
+                        continue;
+                    }
+                    let org_id = attribute(&record.attributes, ORG_ID_ATTRIBUTE)
+                        .map(str::to_string)
+                        .or_else(|| resource_org.clone());
+                    let time = record_time(record);
+
+                    let Some(index) = self.assign(&mut state, org_id, &service, tokens, time)
+                    else {
+                        continue;
+                    };
+                    let pattern = &mut state.clusters[index].pattern;
+                    pattern.first_seen = pattern.first_seen.min(time);
+                    pattern.last_seen = pattern.last_seen.max(time);
+                    pattern.severity_number = pattern.severity_number.max(severity_number(record));
+                    let pattern_id = pattern.pattern_id.clone();
+
+                    let hour = time.duration_trunc(Duration::hours(1)).unwrap_or(time);
+                    *state.counts.entry((hour, index)).or_default() += 1;
+                    state.changed.insert(index);
+
+                    record
+                        .attributes
+                        .retain(|attribute| attribute.key != PATTERN_ID_ATTRIBUTE);
+                    record.attributes.push(KeyValue {
+                        key: PATTERN_ID_ATTRIBUTE.to_string(),
+                        value: Some(AnyValue {
+                            value: Some(any_value::Value::StringValue(pattern_id)),
+                        }),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Take the patterns and counts recorded since the last drain.
+    pub fn drain(&self) -> LogPatternBatch {
+        let mut state = self.state.lock().unwrap();
+        let changed = std::mem::take(&mut state.changed);
+        let counts = std::mem::take(&mut state.counts);
+
+        LogPatternBatch {
+            patterns: changed
+                .into_iter()
+                .map(|index| state.clusters[index].pattern.clone())
+                .collect(),
+            counts: counts
+                .into_iter()
+                .map(|((bucket, index), count)| {
+                    let pattern = &state.clusters[index].pattern;
+                    LogPatternCount {
+                        bucket,
+                        org_id: pattern.org_id.clone(),
+                        pattern_id: pattern.pattern_id.clone(),
+                        count,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Index of the pattern of a body, created if no pattern is similar
+    /// enough; `None` when a new pattern is needed but the limit is reached.
+    fn assign(
+        &self,
+        state: &mut MinerState,
+        org_id: Option<String>,
+        service: &str,
+        tokens: Vec<String>,
+        time: DateTime<Utc>,
+    ) -> Option<usize> {
+        let key = (org_id, service.to_string(), tokens.len(), tokens[0].clone());
+
+        let best = state.groups.get(&key).and_then(|indices| {
+            indices
+                .iter()
+                .map(|&index| (index, similarity(&state.clusters[index].tokens, &tokens)))
+                .filter(|(_, similarity)| *similarity >= self.similarity_threshold)
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(index, _)| index)
+        });
+
+        if let Some(index) = best {
+            let cluster = &mut state.clusters[index];
+            let mut generalized = false;
+            for (template_token, token) in cluster.tokens.iter_mut().zip(&tokens) {
+                if template_token != token && template_token != WILDCARD {
+                    *template_token = WILDCARD.to_string();
+                    generalized = true;
+                }
+            }
+            if generalized {
+                cluster.pattern.template = cluster.tokens.join(" ");
+            }
+            return Some(index);
+        }
+
+        if state.clusters.len() >= self.max_patterns {
+            metrics::counter!("collector_log_pattern_overflow_total").increment(1);
+            return None;
+        }
+
+        let template = tokens.join(" ");
+        let index = state.clusters.len();
+        state.clusters.push(Cluster {
+            pattern: LogPattern {
+                pattern_id: pattern_id(key.0.as_deref(), service, &template),
+                org_id: key.0.clone(),
+                service_name: service.to_string(),
+                template,
+                severity_number: 0,
+                first_seen: time,
+                last_seen: time,
+            },
+            tokens,
+        });
+        state.groups.entry(key).or_default().push(index);
+        metrics::counter!("collector_log_patterns_created_total").increment(1);
+
+        Some(index)
+    }
+}
+
+impl Default for LogPatternMiner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Split a body into tokens, masking tokens that contain a digit.
+fn tokenize(body: &str) -> Vec<String> {
+    body.split_whitespace()
+        .map(|token| {
+            if token.bytes().any(|b| b.is_ascii_digit()) {
+                WILDCARD.to_string()
+            } else {
+                token.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Fraction of positions where the template has the token or a wildcard.
+fn similarity(template: &[String], tokens: &[String]) -> f64 {
+    let matching = template
+        .iter()
+        .zip(tokens)
+        .filter(|(template_token, token)| *template_token == WILDCARD || template_token == token)
+        .count();
+    matching as f64 / tokens.len() as f64
+}
+
+fn pattern_id(org_id: Option<&str>, service: &str, template: &str) -> String {
+    let key = format!("{}\0{}\0{}", org_id.unwrap_or_default(), service, template);
+    format!("{:016x}", hash(key.as_bytes()))
+}
+
+fn body_text(record: &LogRecord) -> Option<&str> {
+    match record.body.as_ref()?.value.as_ref()? {
+        any_value::Value::StringValue(body) => Some(body),
+        _ => None,
+    }
+}
+
+/// Event time of a record, else its observed time, else now.
+fn record_time(record: &LogRecord) -> DateTime<Utc> {
+    [record.time_unix_nano, record.observed_time_unix_nano]
+        .into_iter()
+        .find(|nanos| *nanos > 0)
+        .and_then(|nanos| i64::try_from(nanos).ok())
+        .map(DateTime::from_timestamp_nanos)
+        .unwrap_or_else(Utc::now)
+}
+
+/// Severity number of a record, from its severity text if unspecified.
+fn severity_number(record: &LogRecord) -> i32 {
+    if record.severity_number > 0 {
+        return record.severity_number;
+    }
+    LogSeverity::from_text(&record.severity_text).map_or(0, LogSeverity::number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use opentelemetry_proto::tonic::logs::v1::{ScopeLogs, SeverityNumber};
+    use opentelemetry_proto::tonic::resource::v1::Resource;
+
+    fn string(value: &str) -> Option<AnyValue> {
+        Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value.to_string())),
+        })
+    }
+
+    fn logs(service: &str, org_id: &str, bodies: &[&str]) -> ResourceLogs {
+        let time = Utc.with_ymd_and_hms(2025, 1, 1, 12, 30, 0).unwrap();
+        ResourceLogs {
+            resource: Some(Resource {
+                attributes: vec![
+                    KeyValue {
+                        key: "service.name".to_string(),
+                        value: string(service),
+                    },
+                    KeyValue {
+                        key: ORG_ID_ATTRIBUTE.to_string(),
+                        value: string(org_id),
+                    },
+                ],
+                ..Default::default()
+            }),
+            scope_logs: vec![ScopeLogs {
+                log_records: bodies
+                    .iter()
+                    .map(|body| LogRecord {
+                        time_unix_nano: time.timestamp_nanos_opt().unwrap() as u64,
+                        severity_number: SeverityNumber::Error as i32,
+                        body: string(body),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn pattern_ids(logs: &ResourceLogs) -> Vec<Option<&str>> {
+        logs.scope_logs[0]
+            .log_records
+            .iter()
+            .map(|record| attribute(&record.attributes, PATTERN_ID_ATTRIBUTE))
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize_masks_numbers() {
+        assert_eq!(
+            tokenize("timeout after 30s calling 10.0.0.1"),
+            vec!["timeout", "after", WILDCARD, "calling", WILDCARD]
+        );
+    }
+
+    #[test]
+    fn test_similar_bodies_share_a_pattern() {
+        let miner = LogPatternMiner::new();
+        let mut batch = vec![logs(
+            "api",
+            "org-1",
+            &[
+                "Connection refused by upstream openai",
+                "Connection refused by upstream anthropic",
+                "Cache warmed for tenant acme",
+            ],
+        )];
+        miner.process(&mut batch);
+
+        let ids = pattern_ids(&batch[0]);
+        assert_eq!(ids[0], ids[1]);
+        assert_ne!(ids[0], ids[2]);
+        assert_eq!(miner.pattern_count(), 2);
+
+        let drained = miner.drain();
+        let refused = drained
+            .patterns
+            .iter()
+            .find(|pattern| Some(pattern.pattern_id.as_str()) == ids[0])
+            .unwrap();
+        assert_eq!(refused.template, "Connection refused by upstream <*>");
+        assert_eq!(refused.org_id.as_deref(), Some("org-1"));
+        assert_eq!(refused.severity_number, SeverityNumber::Error as i32);
+
+        let count = drained
+            .counts
+            .iter()
+            .find(|count| count.pattern_id == refused.pattern_id)
+            .unwrap();
+        assert_eq!(count.count, 2);
+        assert_eq!(
+            count.bucket,
+            Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap()
+        );
+
+        // Drained rows are not returned again
+        assert!(miner.drain().counts.is_empty());
+    }
+
+    #[test]
+    fn test_patterns_are_per_service_and_stable() {
+        let miner = LogPatternMiner::new();
+        let mut batch = vec![
+            logs("api", "org-1", &["user 42 logged in"]),
+            logs("worker", "org-1", &["user 42 logged in"]),
+        ];
+        miner.process(&mut batch);
+        assert_ne!(pattern_ids(&batch[0]), pattern_ids(&batch[1]));
+
+        // Another replica assigns the same ID
+        let replica = LogPatternMiner::new();
+        let mut other = vec![logs("api", "org-1", &["user 7 logged in"])];
+        replica.process(&mut other);
+        assert_eq!(pattern_ids(&batch[0]), pattern_ids(&other[0]));
+    }
+
+    #[test]
+    fn test_max_patterns() {
+        let miner = LogPatternMiner::new().with_max_patterns(1);
+        let mut batch = vec![logs("api", "org-1", &["cache miss", "disk full"])];
+        miner.process(&mut batch);
+
+        let ids = pattern_ids(&batch[0]);
+        assert!(ids[0].is_some());
+        assert_eq!(ids[1], None);
+    }
+}
@@ -0,0 +1,407 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured log processing.
+//!
+//! Logs are not spans, so this processor does not implement
+//! [`SpanProcessor`](super::SpanProcessor); it takes OTLP `ResourceLogs` as
+//! received and splits them by [`LogRoute`]:
+//!
+//! - records of services routed to [`LogRoute::Drop`] are discarded
+//! - records are sampled by severity, e.g. keeping 1% of DEBUG logs
+//! - string bodies longer than the limit are truncated
+//!
+//! Kept records are returned in a [`LogBatch`]: `store` goes to the exporters
+//! of the `logs` pipeline, `forward` only to the OTLP exporter. Every record
+//! is counted in `collector_log_records_total` by outcome: `stored`,
+//! `forwarded`, `dropped` (by route) or `sampled_out`.
+
+use crate::config::LogProcessingConfig;
+use crate::sampler::HeadSampler;
+use opentelemetry_proto::tonic::common::v1::{any_value, KeyValue};
+use opentelemetry_proto::tonic::logs::v1::{LogRecord, ResourceLogs};
+use std::collections::HashMap;
+
+pub use crate::config::{LogRoute, LogSeverity};
+
+/// Resource attribute holding the service name.
+const SERVICE_NAME_ATTRIBUTE: &str = "service.name";
+
+impl LogSeverity {
+    /// Severity of an OTLP severity number; `None` for unspecified (0) or
+    /// out-of-range numbers.
+    pub fn from_number(number: i32) -> Option<Self> {
+        match number {
+            1..=4 => Some(Self::Trace),
+            5..=8 => Some(Self::Debug),
+            9..=12 => Some(Self::Info),
+            13..=16 => Some(Self::Warn),
+            17..=20 => Some(Self::Error),
+            21..=24 => Some(Self::Fatal),
+            _ => None,
+        }
+    }
+
+    /// Lowest OTLP severity number of the severity.
+    pub fn number(self) -> i32 {
+        match self {
+            Self::Trace => 1,
+            Self::Debug => 5,
+            Self::Info => 9,
+            Self::Warn => 13,
+            Self::Error => 17,
+            Self::Fatal => 21,
+        }
+    }
+
+    /// Severity of a severity text such as `DEBUG` or `warning`.
+    pub fn from_text(text: &str) -> Option<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "trace" => Some(Self::Trace),
+            "debug" => Some(Self::Debug),
+            "info" | "information" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" => Some(Self::Error),
+            "fatal" | "critical" => Some(Self::Fatal),
+            _ => None,
+        }
+    }
+
+    /// Severity of a log record, from its number or else its text.
+    pub fn of(record: &LogRecord) -> Option<Self> {
+        Self::from_number(record.severity_number).or_else(|| Self::from_text(&record.severity_text))
+    }
+}
+
+/// Logs split by destination.
+#[derive(Debug, Default)]
+pub struct LogBatch {
+    /// Logs for the exporters of the `logs` pipeline
+    pub store: Vec<ResourceLogs>,
+    /// Logs for the OTLP exporter only
+    pub forward: Vec<ResourceLogs>,
+    /// Records dropped by route or sampling
+    pub dropped: usize,
+}
+
+/// Log processor.
+#[derive(Debug, Clone)]
+pub struct LogProcessor {
+    /// Samplers per severity; severities without one are kept
+    samplers: HashMap<LogSeverity, HeadSampler>,
+    /// Route of services without their own
+    default_route: LogRoute,
+    /// Route per service name
+    service_routes: HashMap<String, LogRoute>,
+    /// Maximum size of string bodies in bytes
+    max_body_bytes: Option<usize>,
+}
+
+impl LogProcessor {
+    /// Create a processor that stores all logs unchanged.
+    pub fn new() -> Self {
+        Self {
+            samplers: HashMap::new(),
+            default_route: LogRoute::default(),
+            service_routes: HashMap::new(),
+            max_body_bytes: None,
+        }
+    }
+
+    /// Create a processor from configuration.
+    pub fn from_config(config: &LogProcessingConfig) -> Self {
+        let mut processor = Self::new().with_default_route(config.default_route);
+        for (severity, rate) in &config.sample_rates {
+            processor = processor.with_sample_rate(*severity, *rate);
+        }
+        for (service, route) in &config.services {
+            processor = processor.with_service_route(service, *route);
+        }
+        if let Some(max_body_bytes) = config.max_body_bytes {
+            processor = processor.with_max_body_bytes(max_body_bytes);
+        }
+        processor
+    }
+
+    /// Set the fraction of records of a severity to keep.
+    pub fn with_sample_rate(mut self, severity: LogSeverity, rate: f64) -> Self {
+        self.samplers
+            .insert(severity, HeadSampler::new(rate.clamp(0.0, 1.0)));
+        self
+    }
+
+    /// Set the route of services without their own.
+    pub fn with_default_route(mut self, route: LogRoute) -> Self {
+        self.default_route = route;
+        self
+    }
+
+    /// Set the route of a service.
+    pub fn with_service_route(mut self, service: &str, route: LogRoute) -> Self {
+        self.service_routes.insert(service.to_string(), route);
+        self
+    }
+
+    /// Truncate string bodies to `max_body_bytes`.
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = Some(max_body_bytes);
+        self
+    }
+
+    /// Route of the logs of a service.
+    pub fn route(&self, service: Option<&str>) -> LogRoute {
+        service
+            .and_then(|service| self.service_routes.get(service))
+            .copied()
+            .unwrap_or(self.default_route)
+    }
+
+    /// Route, sample and truncate a batch of logs.
+    pub fn process(&self, logs: Vec<ResourceLogs>) -> LogBatch {
+        let mut batch = LogBatch::default();
+
+        for mut resource_logs in logs {
+            let route = self.route(service_name(&resource_logs));
+            if route == LogRoute::Drop {
+                let count = record_count(&resource_logs);
+                metrics::counter!("collector_log_records_total", "outcome" => "dropped")
+                    .increment(count as u64);
+                batch.dropped += count;
+                continue;
+            }
+
+            let mut sampled_out = 0;
+            for scope_logs in &mut resource_logs.scope_logs {
+                scope_logs.log_records.retain_mut(|record| {
+                    if !self.sample(record) {
+                        sampled_out += 1;
+                        return false;
+                    }
+                    self.truncate_body(record);
+                    true
+                });
+            }
+            resource_logs
+                .scope_logs
+                .retain(|scope_logs| !scope_logs.log_records.is_empty());
+
+            if sampled_out > 0 {
+                metrics::counter!("collector_log_records_total", "outcome" => "sampled_out")
+                    .increment(sampled_out as u64);
+                batch.dropped += sampled_out;
+            }
+
+            let kept = record_count(&resource_logs);
+            if kept == 0 {
+                continue;
+            }
+            if route == LogRoute::Forward {
+                metrics::counter!("collector_log_records_total", "outcome" => "forwarded")
+                    .increment(kept as u64);
+                batch.forward.push(resource_logs);
+            } else {
+                metrics::counter!("collector_log_records_total", "outcome" => "stored")
+                    .increment(kept as u64);
+                batch.store.push(resource_logs);
+            }
+        }
+
+        batch
+    }
+
+    /// Whether to keep a record under its severity's sampling rate.
+    fn sample(&self, record: &LogRecord) -> bool {
+        LogSeverity::of(record)
+            .and_then(|severity| self.samplers.get(&severity))
+            .map_or(true, |sampler| sampler.should_sample())
+    }
+
+    /// Truncate a string body to `max_body_bytes`, on a character boundary.
+    fn truncate_body(&self, record: &mut LogRecord) {
+        let Some(max_body_bytes) = self.max_body_bytes else {
+            return;
+        };
+        let Some(any_value::Value::StringValue(body)) =
+            record.body.as_mut().and_then(|body| body.value.as_mut())
+        else {
+            return;
+        };
+        if body.len() <= max_body_bytes {
+            return;
+        }
+
+        let mut end = max_body_bytes;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        metrics::counter!("collector_log_bodies_truncated_total").increment(1);
+    }
+}
+
+impl Default for LogProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `service.name` of the resource of `logs`.
+pub(crate) fn service_name(logs: &ResourceLogs) -> Option<&str> {
+    attribute(&logs.resource.as_ref()?.attributes, SERVICE_NAME_ATTRIBUTE)
+}
+
+/// String value of the attribute `key`.
+pub(crate) fn attribute<'a>(attributes: &'a [KeyValue], key: &str) -> Option<&'a str> {
+    match attributes
+        .iter()
+        .find(|attribute| attribute.key == key)?
+        .value
+        .as_ref()?
+        .value
+        .as_ref()?
+    {
+        any_value::Value::StringValue(value) => Some(value),
+        _ => None,
+    }
+}
+
+fn record_count(logs: &ResourceLogs) -> usize {
+    logs.scope_logs
+        .iter()
+        .map(|scope_logs| scope_logs.log_records.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry_proto::tonic::common::v1::AnyValue;
+    use opentelemetry_proto::tonic::logs::v1::{ScopeLogs, SeverityNumber};
+    use opentelemetry_proto::tonic::resource::v1::Resource;
+
+    fn string(value: &str) -> Option<AnyValue> {
+        Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value.to_string())),
+        })
+    }
+
+    fn record(severity: SeverityNumber, body: &str) -> LogRecord {
+        LogRecord {
+            severity_number: severity as i32,
+            body: string(body),
+            ..Default::default()
+        }
+    }
+
+    fn logs(service: &str, records: Vec<LogRecord>) -> ResourceLogs {
+        ResourceLogs {
+            resource: Some(Resource {
+                attributes: vec![KeyValue {
+                    key: SERVICE_NAME_ATTRIBUTE.to_string(),
+                    value: string(service),
+                }],
+                ..Default::default()
+            }),
+            scope_logs: vec![ScopeLogs {
+                log_records: records,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn body(logs: &ResourceLogs) -> &str {
+        match logs.scope_logs[0].log_records[0]
+            .body
+            .as_ref()
+            .unwrap()
+            .value
+            .as_ref()
+        {
+            Some(any_value::Value::StringValue(body)) => body,
+            _ => panic!("expected a string body"),
+        }
+    }
+
+    #[test]
+    fn test_severity() {
+        assert_eq!(LogSeverity::from_number(5), Some(LogSeverity::Debug));
+        assert_eq!(LogSeverity::from_number(17), Some(LogSeverity::Error));
+        assert_eq!(LogSeverity::from_number(0), None);
+
+        let mut unspecified = record(SeverityNumber::Unspecified, "");
+        unspecified.severity_text = "WARNING".to_string();
+        assert_eq!(LogSeverity::of(&unspecified), Some(LogSeverity::Warn));
+    }
+
+    #[test]
+    fn test_severity_sampling() {
+        let processor = LogProcessor::new()
+            .with_sample_rate(LogSeverity::Debug, 0.0)
+            .with_sample_rate(LogSeverity::Info, 1.0);
+
+        let batch = processor.process(vec![logs(
+            "api",
+            vec![
+                record(SeverityNumber::Debug, "cache miss"),
+                record(SeverityNumber::Debug2, "cache hit"),
+                record(SeverityNumber::Info, "request served"),
+                record(SeverityNumber::Error, "upstream timeout"),
+            ],
+        )]);
+
+        assert_eq!(batch.dropped, 2);
+        assert_eq!(batch.store.len(), 1);
+        assert_eq!(record_count(&batch.store[0]), 2);
+        assert!(batch.forward.is_empty());
+
+        // A batch of only sampled-out records leaves nothing to export
+        let batch = processor.process(vec![logs(
+            "api",
+            vec![record(SeverityNumber::Debug, "cache miss")],
+        )]);
+        assert!(batch.store.is_empty());
+        assert_eq!(batch.dropped, 1);
+    }
+
+    #[test]
+    fn test_service_routing() {
+        let processor = LogProcessor::from_config(&LogProcessingConfig {
+            enabled: true,
+            services: HashMap::from([
+                ("batch-job".to_string(), LogRoute::Drop),
+                ("billing".to_string(), LogRoute::Forward),
+            ]),
+            ..Default::default()
+        });
+
+        let batch = processor.process(vec![
+            logs("batch-job", vec![record(SeverityNumber::Info, "a"); 3]),
+            logs("billing", vec![record(SeverityNumber::Info, "b")]),
+            logs("api", vec![record(SeverityNumber::Info, "c")]),
+        ]);
+
+        assert_eq!(batch.dropped, 3);
+        assert_eq!(batch.forward.len(), 1);
+        assert_eq!(service_name(&batch.forward[0]), Some("billing"));
+        assert_eq!(batch.store.len(), 1);
+        assert_eq!(service_name(&batch.store[0]), Some("api"));
+        assert_eq!(processor.route(None), LogRoute::Store);
+    }
+
+    #[test]
+    fn test_body_truncation() {
+        let processor = LogProcessor::new().with_max_body_bytes(8);
+
+        let batch = processor.process(vec![
+            logs("api", vec![record(SeverityNumber::Info, "short")]),
+            logs("api", vec![record(SeverityNumber::Info, "a long log body")]),
+            // "é" spans bytes 7 and 8, so it is cut entirely
+            logs("api", vec![record(SeverityNumber::Info, "abcdefgé")]),
+        ]);
+
+        assert_eq!(body(&batch.store[0]), "short");
+        assert_eq!(body(&batch.store[1]), "a long l");
+        assert_eq!(body(&batch.store[2]), "abcdefg");
+    }
+}
@@ -0,0 +1,463 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Latency and cost histogram aggregation with exemplars.
+//!
+//! This processor aggregates request latency and cost into histograms per
+//! organization, provider and model. Every bucket keeps an exemplar: the trace and span of
+//! the most recent request that fell into it. A spike on a latency or cost
+//! chart can then link straight to concrete traces.
+//!
+//! Spans pass through unchanged. Aggregated points are taken with
+//! [`MetricsAggregationProcessor::drain`], and their buckets and exemplars
+//! serialize to the JSON stored in `metric_data_points.buckets` and
+//! `metric_data_points.exemplars`.
+
+use super::quarantine::string_attribute;
+use super::SpanProcessor;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use llm_observatory_core::{span::LlmSpan, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Name of the request latency histogram (milliseconds).
+pub const LATENCY_METRIC: &str = "llm.request.duration";
+
+/// Name of the request cost histogram (USD).
+pub const COST_METRIC: &str = "llm.request.cost";
+
+/// Span attribute identifying the organization.
+const ORG_ID_ATTRIBUTE: &str = "org_id";
+
+/// Default latency bucket boundaries in milliseconds.
+pub const DEFAULT_LATENCY_BUCKETS_MS: &[f64] = &[
+    50.0, 100.0, 250.0, 500.0, 1_000.0, 2_500.0, 5_000.0, 10_000.0, 30_000.0, 60_000.0,
+];
+
+/// Default cost bucket boundaries in USD.
+pub const DEFAULT_COST_BUCKETS_USD: &[f64] = &[0.0001, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+/// Histogram bucket, in the `metric_data_points.buckets` format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistogramBucket {
+    /// Upper boundary of the bucket (inclusive)
+    pub boundary: f64,
+    /// Count of values in this bucket
+    pub count: u64,
+}
+
+/// Sample request attached to a histogram bucket, in the
+/// `metric_data_points.exemplars` format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Exemplar {
+    /// Trace ID of the request
+    pub trace_id: String,
+    /// Span ID of the request
+    pub span_id: String,
+    /// Recorded value
+    pub value: f64,
+    /// When the request finished
+    pub timestamp: DateTime<Utc>,
+    /// Additional attributes
+    pub attributes: serde_json::Value,
+}
+
+/// Aggregated histogram for one metric, organization, provider and model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramPoint {
+    /// Metric name ([`LATENCY_METRIC`] or [`COST_METRIC`])
+    pub name: &'static str,
+    /// Metric unit ("ms" or "usd")
+    pub unit: &'static str,
+    /// Organization from the `org_id` span attribute, if any
+    pub org_id: Option<String>,
+    /// Provider name
+    pub provider: String,
+    /// Model name
+    pub model: String,
+    /// End time of the first recorded request
+    pub start_time: DateTime<Utc>,
+    /// End time of the last recorded request
+    pub end_time: DateTime<Utc>,
+    /// Number of recorded values
+    pub count: u64,
+    /// Sum of recorded values
+    pub sum: f64,
+    /// Smallest recorded value
+    pub min: f64,
+    /// Largest recorded value
+    pub max: f64,
+    /// Buckets with finite boundaries; values above the last boundary are
+    /// only counted in `count`
+    pub buckets: Vec<HistogramBucket>,
+    /// One exemplar per non-empty bucket, including the overflow bucket,
+    /// ordered by value
+    pub exemplars: Vec<Exemplar>,
+}
+
+impl HistogramPoint {
+    /// Data point attributes; `org_id` is only present when known.
+    pub fn attributes(&self) -> serde_json::Value {
+        let mut attributes = serde_json::json!({
+            "provider": self.provider,
+            "model": self.model,
+        });
+        if let Some(org_id) = &self.org_id {
+            attributes["org_id"] = serde_json::json!(org_id);
+        }
+        attributes
+    }
+
+    /// Buckets as stored in `metric_data_points.buckets`.
+    pub fn buckets_json(&self) -> serde_json::Value {
+        serde_json::to_value(&self.buckets).unwrap_or_default()
+    }
+
+    /// Exemplars as stored in `metric_data_points.exemplars`, or `None` if
+    /// there are none.
+    pub fn exemplars_json(&self) -> Option<serde_json::Value> {
+        if self.exemplars.is_empty() {
+            None
+        } else {
+            serde_json::to_value(&self.exemplars).ok()
+        }
+    }
+}
+
+/// Histogram being aggregated.
+#[derive(Debug)]
+struct Histogram {
+    /// Per-bucket counts; the last entry is the overflow bucket
+    counts: Vec<u64>,
+    /// Latest exemplar per bucket
+    exemplars: Vec<Option<Exemplar>>,
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+}
+
+impl Histogram {
+    fn new(bucket_count: usize, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            counts: vec![0; bucket_count + 1],
+            exemplars: vec![None; bucket_count + 1],
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            start_time: timestamp,
+            end_time: timestamp,
+        }
+    }
+
+    fn record(&mut self, boundaries: &[f64], exemplar: Exemplar) {
+        let value = exemplar.value;
+        let index = boundaries.partition_point(|boundary| *boundary < value);
+
+        self.counts[index] += 1;
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.start_time = self.start_time.min(exemplar.timestamp);
+        self.end_time = self.end_time.max(exemplar.timestamp);
+        self.exemplars[index] = Some(exemplar);
+    }
+
+    fn into_point(
+        self,
+        boundaries: &[f64],
+        name: &'static str,
+        unit: &'static str,
+        (org_id, provider, model): (Option<String>, String, String),
+    ) -> HistogramPoint {
+        let buckets = boundaries
+            .iter()
+            .zip(&self.counts)
+            .map(|(boundary, count)| HistogramBucket {
+                boundary: *boundary,
+                count: *count,
+            })
+            .collect();
+
+        HistogramPoint {
+            name,
+            unit,
+            org_id,
+            provider,
+            model,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            count: self.count,
+            sum: self.sum,
+            min: self.min,
+            max: self.max,
+            buckets,
+            exemplars: self.exemplars.into_iter().flatten().collect(),
+        }
+    }
+}
+
+/// Series key: metric name, organization, provider and model.
+type SeriesKey = (&'static str, Option<String>, String, String);
+
+/// Metric aggregation processor.
+#[derive(Debug)]
+pub struct MetricsAggregationProcessor {
+    /// Latency bucket boundaries in milliseconds
+    latency_buckets: Vec<f64>,
+    /// Cost bucket boundaries in USD
+    cost_buckets: Vec<f64>,
+    /// Histograms aggregated since the last drain
+    histograms: Mutex<HashMap<SeriesKey, Histogram>>,
+}
+
+impl MetricsAggregationProcessor {
+    /// Create a new processor with the default bucket boundaries.
+    pub fn new() -> Self {
+        Self {
+            latency_buckets: DEFAULT_LATENCY_BUCKETS_MS.to_vec(),
+            cost_buckets: DEFAULT_COST_BUCKETS_USD.to_vec(),
+            histograms: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set the latency bucket boundaries in milliseconds.
+    pub fn with_latency_buckets(mut self, boundaries: Vec<f64>) -> Self {
+        self.latency_buckets = normalize_boundaries(boundaries);
+        self
+    }
+
+    /// Set the cost bucket boundaries in USD.
+    pub fn with_cost_buckets(mut self, boundaries: Vec<f64>) -> Self {
+        self.cost_buckets = normalize_boundaries(boundaries);
+        self
+    }
+
+    /// Take the histograms aggregated since the last drain.
+    ///
+    /// Points are ordered by metric name, organization, provider and model.
+    pub fn drain(&self) -> Vec<HistogramPoint> {
+        let histograms = std::mem::take(&mut *self.histograms.lock().unwrap());
+
+        let mut points: Vec<HistogramPoint> = histograms
+            .into_iter()
+            .map(|((name, org_id, provider, model), histogram)| {
+                let (boundaries, unit) = self.series(name);
+                histogram.into_point(boundaries, name, unit, (org_id, provider, model))
+            })
+            .collect();
+        points.sort_by(|a, b| {
+            (a.name, &a.org_id, &a.provider, &a.model).cmp(&(
+                b.name,
+                &b.org_id,
+                &b.provider,
+                &b.model,
+            ))
+        });
+        points
+    }
+
+    /// Bucket boundaries and unit of a metric.
+    fn series(&self, name: &str) -> (&[f64], &'static str) {
+        if name == COST_METRIC {
+            (&self.cost_buckets, "usd")
+        } else {
+            (&self.latency_buckets, "ms")
+        }
+    }
+
+    fn record(&self, span: &LlmSpan) {
+        let timestamp = span.latency.end_time;
+        let exemplar = |value: f64| Exemplar {
+            trace_id: span.trace_id.clone(),
+            span_id: span.span_id.clone(),
+            value,
+            timestamp,
+            attributes: serde_json::json!({}),
+        };
+
+        let mut values = vec![(LATENCY_METRIC, span.latency.total_ms as f64)];
+        if let Some(cost) = &span.cost {
+            values.push((COST_METRIC, cost.amount_usd));
+        }
+
+        let org_id = string_attribute(span, ORG_ID_ATTRIBUTE);
+        let mut histograms = self.histograms.lock().unwrap();
+        for (name, value) in values {
+            if !value.is_finite() {
+                continue;
+            }
+            let (boundaries, _) = self.series(name);
+            histograms
+                .entry((
+                    name,
+                    org_id.clone(),
+                    span.provider.as_str().to_string(),
+                    span.model.clone(),
+                ))
+                .or_insert_with(|| Histogram::new(boundaries.len(), timestamp))
+                .record(boundaries, exemplar(value));
+        }
+    }
+}
+
+impl Default for MetricsAggregationProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sort boundaries and drop duplicates and non-finite values.
+fn normalize_boundaries(mut boundaries: Vec<f64>) -> Vec<f64> {
+    boundaries.retain(|boundary| boundary.is_finite());
+    boundaries.sort_by(f64::total_cmp);
+    boundaries.dedup();
+    boundaries
+}
+
+#[async_trait]
+impl SpanProcessor for MetricsAggregationProcessor {
+    async fn process(&self, span: LlmSpan) -> Result<Option<LlmSpan>> {
+        self.record(&span);
+        Ok(Some(span))
+    }
+
+    fn name(&self) -> &str {
+        "metrics_aggregation"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use llm_observatory_core::{
+        span::{LlmInput, SpanStatus},
+        types::{Cost, Latency, Provider},
+    };
+
+    fn span(trace_id: &str, duration_ms: i64, cost_usd: Option<f64>) -> LlmSpan {
+        let start = Utc::now();
+        LlmSpan {
+            span_id: format!("{}-span", trace_id),
+            trace_id: trace_id.to_string(),
+            parent_span_id: None,
+            name: "llm.chat".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4o".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: None,
+            cost: cost_usd.map(|amount_usd| Cost {
+                amount_usd,
+                currency: "USD".to_string(),
+                prompt_cost: None,
+                completion_cost: None,
+            }),
+            latency: Latency::new(start, start + Duration::milliseconds(duration_ms)),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes: Default::default(),
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_latency_histogram_exemplars() {
+        let processor = MetricsAggregationProcessor::new()
+            .with_latency_buckets(vec![1000.0, 100.0, f64::NAN, 100.0]);
+
+        for (trace_id, duration_ms) in [("a", 50), ("b", 80), ("c", 400), ("d", 5000)] {
+            let processed = processor.process(span(trace_id, duration_ms, None)).await.unwrap();
+            assert!(processed.is_some());
+        }
+
+        let points = processor.drain();
+        assert_eq!(points.len(), 1);
+        let point = &points[0];
+        assert_eq!(point.name, LATENCY_METRIC);
+        assert_eq!(point.unit, "ms");
+        assert_eq!(point.count, 4);
+        assert_eq!(point.min, 50.0);
+        assert_eq!(point.max, 5000.0);
+        assert_eq!(
+            point.buckets,
+            vec![
+                HistogramBucket { boundary: 100.0, count: 2 },
+                HistogramBucket { boundary: 1000.0, count: 1 },
+            ]
+        );
+
+        // Latest request per bucket, overflow bucket included
+        let trace_ids: Vec<&str> = point.exemplars.iter().map(|e| e.trace_id.as_str()).collect();
+        assert_eq!(trace_ids, vec!["b", "c", "d"]);
+        assert_eq!(point.exemplars[0].span_id, "b-span");
+
+        assert!(processor.drain().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cost_histogram_per_model() {
+        let processor = MetricsAggregationProcessor::new();
+        processor.process(span("a", 100, Some(0.02))).await.unwrap();
+        processor.process(span("b", 100, None)).await.unwrap();
+
+        let points = processor.drain();
+        let names: Vec<&str> = points.iter().map(|p| p.name).collect();
+        assert_eq!(names, vec![COST_METRIC, LATENCY_METRIC]);
+
+        let cost = &points[0];
+        assert_eq!(cost.unit, "usd");
+        assert_eq!(cost.count, 1);
+        assert_eq!(cost.exemplars.len(), 1);
+        assert_eq!(cost.exemplars[0].trace_id, "a");
+        assert_eq!(points[1].count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_storage_format() {
+        let processor = MetricsAggregationProcessor::new().with_latency_buckets(vec![100.0]);
+        processor.process(span("a", 50, None)).await.unwrap();
+
+        let point = processor.drain().remove(0);
+        assert_eq!(
+            point.buckets_json(),
+            serde_json::json!([{"boundary": 100.0, "count": 1}])
+        );
+        assert_eq!(
+            point.attributes(),
+            serde_json::json!({"provider": "openai", "model": "gpt-4o"})
+        );
+
+        let exemplars = point.exemplars_json().unwrap();
+        assert_eq!(exemplars[0]["trace_id"], "a");
+        assert_eq!(exemplars[0]["span_id"], "a-span");
+        assert_eq!(exemplars[0]["value"], 50.0);
+        assert!(exemplars[0]["timestamp"].is_string());
+
+        // Spans with an organization get a histogram per organization
+        let mut tenant = span("b", 50, None);
+        tenant
+            .attributes
+            .insert("org_id".to_string(), serde_json::json!("org-1"));
+        processor.process(tenant).await.unwrap();
+        processor.process(span("c", 50, None)).await.unwrap();
+
+        let points = processor.drain();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].org_id.as_deref(), Some("org-1"));
+        assert_eq!(
+            points[1].attributes(),
+            serde_json::json!({"provider": "openai", "model": "gpt-4o", "org_id": "org-1"})
+        );
+    }
+}
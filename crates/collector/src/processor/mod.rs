@@ -4,8 +4,26 @@
 //! Span processors for LLM-specific transformations.
 
 pub mod pii;
+pub mod capture;
 pub mod cost;
+pub mod cost_tags;
+pub mod dedup;
+pub mod enrichment;
+pub mod guardrail;
+pub mod log_patterns;
+pub mod logs;
+pub mod metrics;
+pub mod pseudonymize;
+pub mod quarantine;
+pub mod quota;
+pub mod resource;
+pub mod schema;
+pub mod semconv;
+pub mod span_names;
+pub mod streaming;
+pub mod timing;
 
+use crate::metric::MetricSeries;
 use async_trait::async_trait;
 use llm_observatory_core::{span::LlmSpan, Result};
 
@@ -19,6 +37,15 @@ pub trait SpanProcessor: Send + Sync {
     /// or `Err` if processing failed.
     async fn process(&self, span: LlmSpan) -> Result<Option<LlmSpan>>;
 
+    /// Process a metric received directly (e.g. Prometheus remote write)
+    /// rather than derived from spans.
+    ///
+    /// Same contract as [`process`](Self::process). The default forwards the
+    /// metric unchanged; span-specific processors need not override it.
+    async fn process_metric(&self, metric: MetricSeries) -> Result<Option<MetricSeries>> {
+        Ok(Some(metric))
+    }
+
     /// Get processor name.
     fn name(&self) -> &str;
 }
@@ -3,13 +3,15 @@
 
 //! PII (Personally Identifiable Information) redaction processor.
 //!
-//! This processor detects and redacts PII from LLM prompts and responses using:
+//! This processor detects and redacts PII from LLM prompts and responses, and
+//! from the label values of ingested metrics, using:
 //! - Regex patterns for common PII types (emails, phone numbers, SSNs, etc.)
 //! - Configurable redaction strategies (mask, hash, remove)
 //!
 //! For enterprise deployments, this can be extended with ML-based entity recognition.
 
 use super::SpanProcessor;
+use crate::metric::MetricSeries;
 use async_trait::async_trait;
 use llm_observatory_core::{
     span::{LlmSpan, LlmInput, LlmOutput, ChatMessage},
@@ -187,6 +189,24 @@ impl SpanProcessor for PiiRedactionProcessor {
         Ok(Some(span))
     }
 
+    async fn process_metric(&self, mut metric: MetricSeries) -> Result<Option<MetricSeries>> {
+        // Label values can carry user identifiers
+        for attributes in [
+            &mut metric.metric.attributes,
+            &mut metric.metric.resource_attributes,
+        ] {
+            if let Some(map) = attributes.as_object_mut() {
+                for value in map.values_mut() {
+                    if let Some(text) = value.as_str() {
+                        *value = serde_json::Value::String(self.redact_text(text));
+                    }
+                }
+            }
+        }
+
+        Ok(Some(metric))
+    }
+
     fn name(&self) -> &str {
         "pii_redaction"
     }
@@ -262,6 +282,7 @@ mod tests {
             status: SpanStatus::Ok,
             attributes: Default::default(),
             events: vec![],
+            links: vec![],
         };
 
         let processed = processor.process(span).await.unwrap().unwrap();
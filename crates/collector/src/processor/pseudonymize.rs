@@ -0,0 +1,456 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pseudonymization of user and session identifiers.
+//!
+//! Raw user and session IDs should never reach the database, but analytics
+//! still need to count users and group a session's calls. This processor
+//! replaces `user_id`, `session_id` and the configured span attributes with
+//! a keyed hash, `ps_` followed by the first 128 bits of
+//! HMAC-SHA256(key, org_id || 0x00 || value) in hex, where `org_id` is the
+//! span's `org_id` attribute. The same value always maps to the same
+//! pseudonym within an organization, the same user gets unrelated
+//! pseudonyms in different organizations, and without the key pseudonyms
+//! cannot be computed from guessed IDs. Values that already are pseudonyms
+//! (e.g. spans forwarded by an edge collector) are left alone.
+//!
+//! For support workflows that must reach the actual user, each pseudonym is
+//! recorded with its value under its organization in a [`PseudonymVault`],
+//! normally a [`RedisPseudonymVault`] separate from the analytics database,
+//! which the analytics API resolves behind the `resolve:pseudonyms`
+//! permission for the caller's organization only. Spans without an
+//! organization are pseudonymized but not recorded, since no organization
+//! could resolve them. If the vault fails the span is still pseudonymized;
+//! only the reverse lookup is lost, counted in
+//! `collector_pseudonym_vault_errors_total`.
+
+use super::quarantine::string_attribute;
+use super::SpanProcessor;
+use crate::config::PseudonymizationConfig;
+use async_trait::async_trait;
+use llm_observatory_core::{span::LlmSpan, Error, Result};
+use ring::hmac;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Prefix of every pseudonym.
+pub const PSEUDONYM_PREFIX: &str = "ps_";
+
+/// Hex digits after the prefix (128 bits).
+const PSEUDONYM_HEX_LEN: usize = 32;
+
+/// Span attribute naming the organization pseudonyms are scoped to.
+const ORG_ID_ATTRIBUTE: &str = "org_id";
+
+/// Pseudonyms remembered as already recorded in the vault before the set is
+/// reset.
+const MAX_RECORDED: usize = 100_000;
+
+/// Whether a value is a pseudonym.
+pub fn is_pseudonym(value: &str) -> bool {
+    value.strip_prefix(PSEUDONYM_PREFIX).is_some_and(|hex| {
+        hex.len() == PSEUDONYM_HEX_LEN && hex.bytes().all(|b| b.is_ascii_hexdigit())
+    })
+}
+
+/// Reverse mapping from pseudonyms to their original values, per
+/// organization.
+#[async_trait]
+pub trait PseudonymVault: Send + Sync {
+    /// Record the original value of an organization's pseudonym.
+    async fn record(&self, org_id: &str, pseudonym: &str, value: &str) -> Result<()>;
+
+    /// Original value of an organization's pseudonym, if recorded.
+    async fn resolve(&self, org_id: &str, pseudonym: &str) -> Result<Option<String>>;
+
+    /// Vault name, used in logs.
+    fn name(&self) -> &'static str;
+}
+
+/// Per-process vault, for tests and single-node development.
+#[derive(Debug, Default)]
+pub struct MemoryPseudonymVault {
+    values: Mutex<HashMap<(String, String), String>>,
+}
+
+impl MemoryPseudonymVault {
+    /// Create an empty vault.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PseudonymVault for MemoryPseudonymVault {
+    async fn record(&self, org_id: &str, pseudonym: &str, value: &str) -> Result<()> {
+        self.values.lock().unwrap().insert(
+            (org_id.to_string(), pseudonym.to_string()),
+            value.to_string(),
+        );
+        Ok(())
+    }
+
+    async fn resolve(&self, org_id: &str, pseudonym: &str) -> Result<Option<String>> {
+        let key = (org_id.to_string(), pseudonym.to_string());
+        Ok(self.values.lock().unwrap().get(&key).cloned())
+    }
+
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+}
+
+/// Vault in Redis.
+///
+/// Each pseudonym is a Redis string `<prefix><org_id>:<pseudonym>` holding
+/// the original value, expiring `retention` after it was last recorded.
+#[derive(Clone)]
+pub struct RedisPseudonymVault {
+    connection: redis::aio::ConnectionManager,
+    key_prefix: String,
+    retention_secs: u64,
+}
+
+impl RedisPseudonymVault {
+    /// Connect to Redis.
+    pub async fn connect(url: &str, key_prefix: &str, retention: Duration) -> Result<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| Error::config(format!("Invalid pseudonym vault Redis URL: {}", e)))?;
+        let connection = redis::aio::ConnectionManager::new(client)
+            .await
+            .map_err(|e| {
+                Error::storage(format!("Failed to connect to pseudonym vault Redis: {}", e))
+            })?;
+
+        Ok(Self {
+            connection,
+            key_prefix: key_prefix.to_string(),
+            retention_secs: retention.as_secs().max(1),
+        })
+    }
+}
+
+impl RedisPseudonymVault {
+    fn key(&self, org_id: &str, pseudonym: &str) -> String {
+        format!("{}{}:{}", self.key_prefix, org_id, pseudonym)
+    }
+}
+
+#[async_trait]
+impl PseudonymVault for RedisPseudonymVault {
+    async fn record(&self, org_id: &str, pseudonym: &str, value: &str) -> Result<()> {
+        let mut connection = self.connection.clone();
+        let _: () = redis::cmd("SET")
+            .arg(self.key(org_id, pseudonym))
+            .arg(value)
+            .arg("EX")
+            .arg(self.retention_secs)
+            .query_async(&mut connection)
+            .await
+            .map_err(|e| Error::storage(format!("Pseudonym vault Redis command failed: {}", e)))?;
+        Ok(())
+    }
+
+    async fn resolve(&self, org_id: &str, pseudonym: &str) -> Result<Option<String>> {
+        let mut connection = self.connection.clone();
+        redis::cmd("GET")
+            .arg(self.key(org_id, pseudonym))
+            .query_async(&mut connection)
+            .await
+            .map_err(|e| Error::storage(format!("Pseudonym vault Redis command failed: {}", e)))
+    }
+
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+}
+
+/// User and session identifier pseudonymization processor.
+#[derive(Clone)]
+pub struct PseudonymizationProcessor {
+    key: hmac::Key,
+    /// Span attributes pseudonymized besides `user_id` and `session_id`
+    attributes: Vec<String>,
+    vault: Option<Arc<dyn PseudonymVault>>,
+    /// Pseudonyms already recorded in the vault by this process
+    recorded: Arc<Mutex<HashSet<String>>>,
+}
+
+impl PseudonymizationProcessor {
+    /// Create a processor hashing with `key`, without a vault.
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, key),
+            attributes: Vec::new(),
+            vault: None,
+            recorded: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Create a processor from configuration, connecting to the vault if
+    /// `vault_redis_url` is set.
+    pub async fn from_config(config: &PseudonymizationConfig) -> Result<Self> {
+        let mut processor =
+            Self::new(config.key.as_bytes()).with_attributes(config.attributes.clone());
+        if let Some(url) = &config.vault_redis_url {
+            let retention = Duration::from_secs(config.vault_retention_days * 24 * 60 * 60);
+            let vault =
+                RedisPseudonymVault::connect(url, &config.vault_key_prefix, retention).await?;
+            processor = processor.with_vault(Arc::new(vault));
+        }
+
+        tracing::info!(
+            vault = processor.vault.as_ref().map(|v| v.name()),
+            attributes = ?config.attributes,
+            "User identifier pseudonymization enabled"
+        );
+
+        Ok(processor)
+    }
+
+    /// Also pseudonymize these span attributes.
+    pub fn with_attributes(mut self, attributes: Vec<String>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Record pseudonyms in `vault` for reverse lookup.
+    pub fn with_vault(mut self, vault: Arc<dyn PseudonymVault>) -> Self {
+        self.vault = Some(vault);
+        self
+    }
+
+    /// Pseudonym of a value in an organization.
+    pub fn pseudonym(&self, org_id: &str, value: &str) -> String {
+        let mut context = hmac::Context::with_key(&self.key);
+        context.update(org_id.as_bytes());
+        context.update(&[0]);
+        context.update(value.as_bytes());
+        let tag = context.sign();
+        let hex: String = tag.as_ref()[..PSEUDONYM_HEX_LEN / 2]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        format!("{}{}", PSEUDONYM_PREFIX, hex)
+    }
+
+    /// Replace a value with its pseudonym, recording it in the vault.
+    async fn pseudonymize(&self, org_id: Option<&str>, value: &mut String) {
+        if is_pseudonym(value) {
+            return;
+        }
+
+        let pseudonym = self.pseudonym(org_id.unwrap_or_default(), value);
+        if let Some(org_id) = org_id {
+            self.record(org_id, &pseudonym, value).await;
+        }
+        metrics::counter!("collector_pseudonymized_values_total").increment(1);
+        *value = pseudonym;
+    }
+
+    async fn record(&self, org_id: &str, pseudonym: &str, value: &str) {
+        let Some(vault) = &self.vault else {
+            return;
+        };
+        // Pseudonyms include the organization, so they are unique across
+        // organizations
+        if self.recorded.lock().unwrap().contains(pseudonym) {
+            return;
+        }
+
+        match vault.record(org_id, pseudonym, value).await {
+            Ok(()) => {
+                let mut recorded = self.recorded.lock().unwrap();
+                if recorded.len() >= MAX_RECORDED {
+                    recorded.clear();
+                }
+                recorded.insert(pseudonym.to_string());
+            }
+            Err(e) => {
+                metrics::counter!("collector_pseudonym_vault_errors_total", "vault" => vault.name())
+                    .increment(1);
+                tracing::warn!(error = %e, "Failed to record pseudonym, reverse lookup unavailable");
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SpanProcessor for PseudonymizationProcessor {
+    async fn process(&self, mut span: LlmSpan) -> Result<Option<LlmSpan>> {
+        let org_id = string_attribute(&span, ORG_ID_ATTRIBUTE).filter(|org| !org.is_empty());
+        let org_id = org_id.as_deref();
+
+        if let Some(user_id) = span.metadata.user_id.as_mut() {
+            self.pseudonymize(org_id, user_id).await;
+        }
+        if let Some(session_id) = span.metadata.session_id.as_mut() {
+            self.pseudonymize(org_id, session_id).await;
+        }
+
+        for key in &self.attributes {
+            let Some(value) = span.attributes.get_mut(key) else {
+                continue;
+            };
+            // Numeric IDs are hashed in their string form
+            let mut text = match value {
+                serde_json::Value::String(s) => std::mem::take(s),
+                serde_json::Value::Number(n) => n.to_string(),
+                _ => continue,
+            };
+            self.pseudonymize(org_id, &mut text).await;
+            *value = serde_json::Value::String(text);
+        }
+
+        Ok(Some(span))
+    }
+
+    fn name(&self) -> &str {
+        "pseudonymization"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use llm_observatory_core::{
+        span::{LlmInput, SpanStatus},
+        types::{Latency, Metadata, Provider},
+    };
+    use serde_json::json;
+
+    const KEY: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    fn span(org_id: &str, user_id: &str, session_id: &str) -> LlmSpan {
+        let now = Utc::now();
+        LlmSpan {
+            span_id: "s1".to_string(),
+            trace_id: "t1".to_string(),
+            parent_span_id: None,
+            name: "test".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: None,
+            cost: None,
+            latency: Latency::new(now, now),
+            metadata: Metadata {
+                user_id: Some(user_id.to_string()),
+                session_id: Some(session_id.to_string()),
+                ..Default::default()
+            },
+            status: SpanStatus::Ok,
+            attributes: HashMap::from([
+                ("user.id".to_string(), json!(user_id)),
+                ("enduser.id".to_string(), json!(42)),
+                ("gen_ai.system".to_string(), json!("openai")),
+                ("org_id".to_string(), json!(org_id)),
+            ]),
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pseudonymizes_identifiers() {
+        let vault = Arc::new(MemoryPseudonymVault::new());
+        let processor = PseudonymizationProcessor::new(KEY)
+            .with_attributes(vec!["user.id".to_string(), "enduser.id".to_string()])
+            .with_vault(vault.clone());
+
+        let span = processor
+            .process(span("org-a", "alice@example.com", "sess-1"))
+            .await
+            .unwrap()
+            .unwrap();
+        let user_id = span.metadata.user_id.clone().unwrap();
+        assert!(is_pseudonym(&user_id));
+        assert_eq!(span.attributes["user.id"], json!(user_id));
+        assert!(is_pseudonym(
+            span.attributes["enduser.id"].as_str().unwrap()
+        ));
+        assert_eq!(span.attributes["gen_ai.system"], json!("openai"));
+        assert!(!format!("{:?}", span).contains("alice"));
+
+        // Stable across calls, reversible only through the vault
+        assert_eq!(processor.pseudonym("org-a", "alice@example.com"), user_id);
+        assert_eq!(
+            vault.resolve("org-a", &user_id).await.unwrap().as_deref(),
+            Some("alice@example.com")
+        );
+        let session_id = span.metadata.session_id.unwrap();
+        assert_eq!(
+            vault
+                .resolve("org-a", &session_id)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("sess-1")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pseudonyms_are_scoped_by_organization() {
+        let vault = Arc::new(MemoryPseudonymVault::new());
+        let processor = PseudonymizationProcessor::new(KEY).with_vault(vault.clone());
+
+        let a = processor
+            .process(span("org-a", "alice@example.com", "s"))
+            .await
+            .unwrap()
+            .unwrap();
+        let b = processor
+            .process(span("org-b", "alice@example.com", "s"))
+            .await
+            .unwrap()
+            .unwrap();
+        let pseudonym_a = a.metadata.user_id.unwrap();
+        let pseudonym_b = b.metadata.user_id.unwrap();
+        assert_ne!(pseudonym_a, pseudonym_b);
+
+        // Another organization can't resolve the pseudonym
+        assert_eq!(vault.resolve("org-b", &pseudonym_a).await.unwrap(), None);
+        assert_eq!(
+            vault
+                .resolve("org-b", &pseudonym_b)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("alice@example.com")
+        );
+
+        // Spans without an organization are never recorded
+        let orgless = processor
+            .process(span("", "bob@example.com", "s"))
+            .await
+            .unwrap()
+            .unwrap();
+        let pseudonym = orgless.metadata.user_id.unwrap();
+        assert!(is_pseudonym(&pseudonym));
+        assert_eq!(vault.resolve("", &pseudonym).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_pseudonyms_are_idempotent_and_keyed() {
+        let processor = PseudonymizationProcessor::new(KEY);
+        let once = processor
+            .process(span("org-a", "bob", "s"))
+            .await
+            .unwrap()
+            .unwrap();
+        let twice = processor.process(once.clone()).await.unwrap().unwrap();
+        assert_eq!(once.metadata.user_id, twice.metadata.user_id);
+
+        let other = PseudonymizationProcessor::new(b"another key of at least 32 bytes!");
+        assert_ne!(
+            processor.pseudonym("org-a", "bob"),
+            other.pseudonym("org-a", "bob")
+        );
+        assert!(!is_pseudonym("ps_xyz"));
+    }
+}
@@ -0,0 +1,385 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Quarantine for spans that fail validation or processing.
+//!
+//! Instead of disappearing, failing spans are buffered in a [`Quarantine`]
+//! with the failing stage and reason, and drained as rows for the
+//! `rejected_spans` table. Schema validation quarantines spans in
+//! [`SchemaEnforcementMode::Quarantine`](super::schema::SchemaEnforcementMode);
+//! any other processor can be wrapped in a [`QuarantiningProcessor`], which
+//! quarantines the spans it fails on.
+//!
+//! Once the rules are fixed, quarantined spans are replayed with [`replay`],
+//! which runs the stored span through a processor pipeline again.
+
+use super::SpanProcessor;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use llm_observatory_core::{span::LlmSpan, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// Attribute holding the service name.
+const SERVICE_NAME_ATTRIBUTE: &str = "service.name";
+
+/// Attribute holding the service version.
+const SERVICE_VERSION_ATTRIBUTE: &str = "service.version";
+
+/// Attribute holding the organization ID.
+const ORG_ID_ATTRIBUTE: &str = "org_id";
+
+/// Stage a span failed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuarantineStage {
+    /// Schema validation
+    Schema,
+    /// PII redaction
+    Pii,
+    /// Any other processor
+    Processing,
+}
+
+impl QuarantineStage {
+    /// Stage name, as stored in `rejected_spans.stage`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuarantineStage::Schema => "schema",
+            QuarantineStage::Pii => "pii",
+            QuarantineStage::Processing => "processing",
+        }
+    }
+}
+
+/// A quarantined span, in the `rejected_spans` row format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuarantinedSpan {
+    /// When the span was quarantined
+    pub ts: DateTime<Utc>,
+    /// Organization ID, if the span carries one
+    pub org_id: Option<String>,
+    /// Trace ID of the span
+    pub trace_id: String,
+    /// Span ID
+    pub span_id: String,
+    /// `service.name`
+    pub service_name: Option<String>,
+    /// `service.version`
+    pub service_version: Option<String>,
+    /// Stage the span failed in
+    pub stage: QuarantineStage,
+    /// Why the span was quarantined
+    pub failure_reason: String,
+    /// Schema the span failed, for schema validation failures
+    pub schema_name: Option<String>,
+    /// Schema violations found
+    pub violations: Vec<String>,
+    /// The span as received
+    pub span: Value,
+}
+
+impl QuarantinedSpan {
+    /// Quarantine entry for a span, given as JSON, that failed in `stage`.
+    pub fn new(
+        span: &LlmSpan,
+        span_json: Value,
+        stage: QuarantineStage,
+        failure_reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            ts: Utc::now(),
+            org_id: string_attribute(span, ORG_ID_ATTRIBUTE),
+            trace_id: span.trace_id.clone(),
+            span_id: span.span_id.clone(),
+            service_name: string_attribute(span, SERVICE_NAME_ATTRIBUTE),
+            service_version: string_attribute(span, SERVICE_VERSION_ATTRIBUTE),
+            stage,
+            failure_reason: failure_reason.into(),
+            schema_name: None,
+            violations: Vec::new(),
+            span: span_json,
+        }
+    }
+
+    /// Set the schema the span failed and its violations.
+    pub fn with_schema(mut self, schema_name: impl Into<String>, violations: Vec<String>) -> Self {
+        self.schema_name = Some(schema_name.into());
+        self.violations = violations;
+        self
+    }
+}
+
+/// String attribute of a span, from its attributes or metadata.
+pub(crate) fn string_attribute(span: &LlmSpan, key: &str) -> Option<String> {
+    span.attributes
+        .get(key)
+        .and_then(|value| value.as_str())
+        .map(str::to_string)
+        .or_else(|| span.metadata.attributes.get(key).cloned())
+}
+
+/// Buffer of quarantined spans, shared by the stages that quarantine.
+///
+/// Holds at most `max_buffered` spans between drains; further spans are
+/// dropped and counted in `collector_quarantine_dropped_total`.
+#[derive(Debug)]
+pub struct Quarantine {
+    spans: Mutex<Vec<QuarantinedSpan>>,
+    max_buffered: usize,
+}
+
+impl Quarantine {
+    /// Create a quarantine buffer.
+    pub fn new(max_buffered: usize) -> Self {
+        Self {
+            spans: Mutex::new(Vec::new()),
+            max_buffered,
+        }
+    }
+
+    /// Quarantine a span.
+    pub fn push(&self, span: QuarantinedSpan) {
+        let stage = span.stage.as_str();
+        let mut spans = self.spans.lock().unwrap();
+        if spans.len() >= self.max_buffered {
+            metrics::counter!("collector_quarantine_dropped_total", "stage" => stage).increment(1);
+            return;
+        }
+
+        metrics::counter!("collector_quarantined_spans_total", "stage" => stage).increment(1);
+        spans.push(span);
+    }
+
+    /// Take the spans quarantined since the last drain, oldest first.
+    pub fn drain(&self) -> Vec<QuarantinedSpan> {
+        std::mem::take(&mut *self.spans.lock().unwrap())
+    }
+
+    /// Number of buffered spans.
+    pub fn len(&self) -> usize {
+        self.spans.lock().unwrap().len()
+    }
+
+    /// Whether no spans are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Processor wrapper that quarantines the spans its processor fails on.
+///
+/// The failing span is quarantined as received by the wrapped processor and
+/// dropped from the pipeline, so one bad span doesn't fail its batch.
+pub struct QuarantiningProcessor {
+    inner: Arc<dyn SpanProcessor>,
+    stage: QuarantineStage,
+    quarantine: Arc<Quarantine>,
+}
+
+impl QuarantiningProcessor {
+    /// Wrap a processor, quarantining its failures as [`QuarantineStage::Processing`].
+    pub fn new(inner: Arc<dyn SpanProcessor>, quarantine: Arc<Quarantine>) -> Self {
+        Self {
+            inner,
+            stage: QuarantineStage::Processing,
+            quarantine,
+        }
+    }
+
+    /// Set the stage failures are recorded under.
+    pub fn with_stage(mut self, stage: QuarantineStage) -> Self {
+        self.stage = stage;
+        self
+    }
+}
+
+#[async_trait]
+impl SpanProcessor for QuarantiningProcessor {
+    async fn process(&self, span: LlmSpan) -> Result<Option<LlmSpan>> {
+        let received = span.clone();
+        match self.inner.process(span).await {
+            Ok(result) => Ok(result),
+            Err(e) => {
+                tracing::warn!(
+                    processor = self.inner.name(),
+                    trace_id = %received.trace_id,
+                    span_id = %received.span_id,
+                    error = %e,
+                    "Span processing failed, quarantining span"
+                );
+                let span_json = serde_json::to_value(&received)?;
+                self.quarantine.push(QuarantinedSpan::new(
+                    &received,
+                    span_json,
+                    self.stage,
+                    format!("{}: {}", self.inner.name(), e),
+                ));
+                Ok(None)
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+}
+
+/// Result of replaying a quarantined span.
+#[derive(Debug, Clone)]
+pub enum ReplayOutcome {
+    /// The span passed every processor and should be exported
+    Forwarded(Box<LlmSpan>),
+    /// A processor dropped the span
+    Dropped {
+        /// Processor that dropped the span
+        processor: String,
+    },
+    /// The stored span is invalid or a processor failed
+    Failed {
+        /// Why the replay failed
+        reason: String,
+    },
+}
+
+/// Replay a quarantined span, as stored in `rejected_spans.span`, through a
+/// processor pipeline.
+///
+/// Pass the processors unwrapped and schema validation in
+/// [`SchemaEnforcementMode::Reject`](super::schema::SchemaEnforcementMode)
+/// mode, so spans that still fail are reported here instead of being
+/// quarantined again. Leave deduplication out: the span's key was recorded
+/// when it was first received.
+pub async fn replay(span: &Value, processors: &[Arc<dyn SpanProcessor>]) -> ReplayOutcome {
+    let mut span: LlmSpan = match serde_json::from_value(span.clone()) {
+        Ok(span) => span,
+        Err(e) => {
+            return ReplayOutcome::Failed {
+                reason: format!("Invalid quarantined span: {}", e),
+            }
+        }
+    };
+
+    for processor in processors {
+        span = match processor.process(span).await {
+            Ok(Some(span)) => span,
+            Ok(None) => {
+                return ReplayOutcome::Dropped {
+                    processor: processor.name().to_string(),
+                }
+            }
+            Err(e) => {
+                return ReplayOutcome::Failed {
+                    reason: format!("{}: {}", processor.name(), e),
+                }
+            }
+        };
+    }
+
+    ReplayOutcome::Forwarded(Box::new(span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm_observatory_core::{
+        span::{LlmInput, SpanStatus},
+        types::{Latency, Provider},
+        Error,
+    };
+    use serde_json::json;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    fn span() -> LlmSpan {
+        let now = Utc::now();
+        LlmSpan {
+            span_id: "span-1".to_string(),
+            trace_id: "trace-1".to_string(),
+            parent_span_id: None,
+            name: "test".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: None,
+            cost: None,
+            latency: Latency::new(now, now),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes: [(SERVICE_NAME_ATTRIBUTE.to_string(), json!("checkout-api"))].into(),
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    /// Fails until fixed, like a processor with a bad rule.
+    struct FlakyProcessor {
+        fixed: AtomicBool,
+    }
+
+    #[async_trait]
+    impl SpanProcessor for FlakyProcessor {
+        async fn process(&self, span: LlmSpan) -> Result<Option<LlmSpan>> {
+            if self.fixed.load(Ordering::SeqCst) {
+                Ok(Some(span))
+            } else {
+                Err(Error::internal("bad redaction rule"))
+            }
+        }
+
+        fn name(&self) -> &str {
+            "flaky"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_quarantines_failures_and_replays() {
+        let flaky = Arc::new(FlakyProcessor {
+            fixed: AtomicBool::new(false),
+        });
+        let quarantine = Arc::new(Quarantine::new(10));
+        let processor = QuarantiningProcessor::new(flaky.clone(), quarantine.clone())
+            .with_stage(QuarantineStage::Pii);
+
+        assert!(processor.process(span()).await.unwrap().is_none());
+
+        let quarantined = quarantine.drain();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].stage, QuarantineStage::Pii);
+        assert!(quarantined[0].failure_reason.starts_with("flaky: "));
+        assert_eq!(quarantined[0].service_name.as_deref(), Some("checkout-api"));
+        assert!(quarantined[0].schema_name.is_none());
+
+        let pipeline: Vec<Arc<dyn SpanProcessor>> = vec![flaky.clone()];
+        assert!(matches!(
+            replay(&quarantined[0].span, &pipeline).await,
+            ReplayOutcome::Failed { .. }
+        ));
+
+        flaky.fixed.store(true, Ordering::SeqCst);
+        match replay(&quarantined[0].span, &pipeline).await {
+            ReplayOutcome::Forwarded(replayed) => assert_eq!(replayed.span_id, "span-1"),
+            outcome => panic!("unexpected outcome: {:?}", outcome),
+        }
+
+        assert!(matches!(
+            replay(&json!({"span_id": "x"}), &pipeline).await,
+            ReplayOutcome::Failed { .. }
+        ));
+    }
+
+    #[test]
+    fn test_quarantine_capacity() {
+        let quarantine = Quarantine::new(1);
+        let entry = || QuarantinedSpan::new(&span(), json!({}), QuarantineStage::Processing, "x");
+
+        quarantine.push(entry());
+        quarantine.push(entry());
+        assert_eq!(quarantine.len(), 1);
+        assert_eq!(quarantine.drain().len(), 1);
+        assert!(quarantine.is_empty());
+    }
+}
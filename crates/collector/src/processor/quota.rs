@@ -0,0 +1,580 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-organization ingestion quotas.
+//!
+//! This processor counts spans and span bytes (JSON-encoded size) per
+//! organization and UTC day and enforces daily limits on both. Spans over
+//! quota are handled with an [`OverQuotaAction`]: dropped, sampled down to a
+//! fixed rate, or queued until the quota resets at the next UTC day. Spans
+//! without an `org_id` attribute are not limited.
+//!
+//! Usage is drained as rows for the `ingestion_usage` table with
+//! [`QuotaProcessor::drain_usage`] and upserted additively, so the table holds
+//! the usage of all collector replicas. Replicas load that usage back with
+//! [`QuotaProcessor::sync_usage`]; between syncs each replica only sees its
+//! own spans, so an organization can exceed its quota by what other replicas
+//! admit in one sync interval.
+//!
+//! Limits set per organization through the admin API
+//! (`organization_settings`) are loaded with
+//! [`QuotaProcessor::sync_org_limits`] and take precedence over the
+//! configured limits, setting by setting.
+
+use super::quarantine::string_attribute;
+use super::SpanProcessor;
+use crate::config::QuotaConfig;
+use crate::sampler::HeadSampler;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use llm_observatory_core::{span::LlmSpan, Result};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, RwLock};
+
+pub use crate::config::{OverQuotaAction, QuotaLimits};
+
+/// Attribute holding the organization ID.
+const ORG_ID_ATTRIBUTE: &str = "org_id";
+
+/// Usage of one organization on one UTC day, in the `ingestion_usage` row
+/// format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuotaUsage {
+    /// UTC day
+    pub day: NaiveDate,
+    /// Organization ID
+    pub org_id: String,
+    /// Spans admitted
+    pub span_count: u64,
+    /// Bytes of the spans admitted
+    pub byte_count: u64,
+    /// Over-quota spans dropped, sampled out or queued
+    pub throttled_spans: u64,
+    /// Span limit of the day
+    pub spans_per_day_limit: Option<u64>,
+    /// Byte limit of the day
+    pub bytes_per_day_limit: Option<u64>,
+    /// Handling of spans over quota
+    pub over_quota_action: OverQuotaAction,
+}
+
+/// Usage of the current day of each organization.
+#[derive(Debug, Default)]
+struct UsageState {
+    /// Day, spans and bytes: loaded with `sync_usage` plus admitted since
+    totals: HashMap<String, (NaiveDate, u64, u64)>,
+    /// Usage counted since the last drain
+    pending: HashMap<(NaiveDate, String), QuotaUsage>,
+}
+
+/// Ingestion quota processor.
+#[derive(Debug)]
+pub struct QuotaProcessor {
+    /// Limits of organizations without their own
+    default_limits: QuotaLimits,
+    /// Limits per organization
+    org_limits: HashMap<String, QuotaLimits>,
+    /// Limits per organization from the admin API, over `org_limits`
+    synced_limits: RwLock<HashMap<String, QuotaLimits>>,
+    /// Handling of spans over quota, unless set per organization
+    over_quota: OverQuotaAction,
+    /// Keeps over-quota spans with [`OverQuotaAction::Sample`]
+    sampler: HeadSampler,
+    /// Spans held with [`OverQuotaAction::Queue`]
+    max_queued: usize,
+    state: Mutex<UsageState>,
+    /// Queued spans and their organization, oldest first
+    queue: Mutex<VecDeque<(String, LlmSpan)>>,
+}
+
+impl QuotaProcessor {
+    /// Create a processor without limits, which only counts usage.
+    pub fn new() -> Self {
+        let config = QuotaConfig::default();
+        Self {
+            default_limits: config.default,
+            org_limits: config.orgs,
+            synced_limits: RwLock::new(HashMap::new()),
+            over_quota: config.over_quota,
+            sampler: HeadSampler::new(config.over_quota_sample_rate),
+            max_queued: config.max_queued,
+            state: Mutex::new(UsageState::default()),
+            queue: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Create a processor from configuration.
+    pub fn from_config(config: &QuotaConfig) -> Self {
+        let mut processor = Self::new()
+            .with_default_limits(config.default.clone())
+            .with_over_quota(config.over_quota)
+            .with_sample_rate(config.over_quota_sample_rate)
+            .with_max_queued(config.max_queued);
+        for (org_id, limits) in &config.orgs {
+            processor = processor.with_org_limits(org_id, limits.clone());
+        }
+        processor
+    }
+
+    /// Set the limits of organizations without their own.
+    pub fn with_default_limits(mut self, limits: QuotaLimits) -> Self {
+        self.default_limits = limits;
+        self
+    }
+
+    /// Set the limits of an organization.
+    pub fn with_org_limits(mut self, org_id: &str, limits: QuotaLimits) -> Self {
+        self.org_limits.insert(org_id.to_string(), limits);
+        self
+    }
+
+    /// Set what to do with spans over quota, unless set per organization.
+    pub fn with_over_quota(mut self, action: OverQuotaAction) -> Self {
+        self.over_quota = action;
+        self
+    }
+
+    /// Set the fraction of over-quota spans kept with
+    /// [`OverQuotaAction::Sample`].
+    pub fn with_sample_rate(mut self, rate: f64) -> Self {
+        self.sampler = HeadSampler::new(rate.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Set the maximum number of queued spans.
+    pub fn with_max_queued(mut self, max_queued: usize) -> Self {
+        self.max_queued = max_queued;
+        self
+    }
+
+    /// Limits of an organization.
+    pub fn limits(&self, org_id: &str) -> QuotaLimits {
+        let configured = self.org_limits.get(org_id).unwrap_or(&self.default_limits);
+        match self.synced_limits.read().unwrap().get(org_id) {
+            Some(synced) => QuotaLimits {
+                spans_per_day: synced.spans_per_day.or(configured.spans_per_day),
+                bytes_per_day: synced.bytes_per_day.or(configured.bytes_per_day),
+                over_quota: synced.over_quota.or(configured.over_quota),
+            },
+            None => configured.clone(),
+        }
+    }
+
+    /// Replace the limits set through the admin API with `limits`, given per
+    /// organization. Unset limits, and organizations left out, use the
+    /// configured limits.
+    pub fn sync_org_limits(&self, limits: impl IntoIterator<Item = (String, QuotaLimits)>) {
+        *self.synced_limits.write().unwrap() = limits.into_iter().collect();
+    }
+
+    /// Handling of an organization's spans over quota.
+    pub fn over_quota_action(&self, org_id: &str) -> OverQuotaAction {
+        self.limits(org_id).over_quota.unwrap_or(self.over_quota)
+    }
+
+    /// Set the usage of `day` to the usage stored for all replicas, given as
+    /// organization, spans and bytes. Usage counted since the last drain is
+    /// added, since it is not stored yet.
+    pub fn sync_usage(&self, day: NaiveDate, usage: impl IntoIterator<Item = (String, u64, u64)>) {
+        let mut state = self.state.lock().unwrap();
+        for (org_id, spans, bytes) in usage {
+            let (pending_spans, pending_bytes) = state
+                .pending
+                .get(&(day, org_id.clone()))
+                .map_or((0, 0), |row| (row.span_count, row.byte_count));
+
+            let current = state.totals.get(&org_id).map(|(current, ..)| *current);
+            if current.map_or(true, |current| current <= day) {
+                state
+                    .totals
+                    .insert(org_id, (day, spans + pending_spans, bytes + pending_bytes));
+            }
+        }
+    }
+
+    /// Take the usage counted since the last drain. Rows are ordered by day
+    /// and organization.
+    pub fn drain_usage(&self) -> Vec<QuotaUsage> {
+        let pending = std::mem::take(&mut self.state.lock().unwrap().pending);
+        let mut rows: Vec<QuotaUsage> = pending.into_values().collect();
+        rows.sort_by(|a, b| (a.day, &a.org_id).cmp(&(b.day, &b.org_id)));
+        rows
+    }
+
+    /// Take the queued spans whose organization has quota again at `now`,
+    /// oldest first. Released spans continue through the pipeline after this
+    /// processor.
+    pub fn release_queued(&self, now: DateTime<Utc>) -> Vec<LlmSpan> {
+        let day = now.date_naive();
+        let mut queue = self.queue.lock().unwrap();
+        let mut released = Vec::new();
+        let mut held = VecDeque::with_capacity(queue.len());
+
+        for (org_id, span) in queue.drain(..) {
+            if self.admit(&org_id, encoded_len(&span), day) {
+                released.push(span);
+            } else {
+                held.push_back((org_id, span));
+            }
+        }
+        *queue = held;
+        released
+    }
+
+    /// Number of queued spans.
+    pub fn queued(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    /// Count a span against its organization's quota of `day`, returning
+    /// whether it is within quota. Spans over quota are not counted.
+    fn admit(&self, org_id: &str, bytes: u64, day: NaiveDate) -> bool {
+        let limits = self.limits(org_id);
+        let mut state = self.state.lock().unwrap();
+
+        let (spans_today, bytes_today) = match state.totals.get(org_id) {
+            Some((current, spans, bytes)) if *current == day => (*spans, *bytes),
+            _ => (0, 0),
+        };
+        let within = limits
+            .spans_per_day
+            .map_or(true, |limit| spans_today < limit)
+            && limits
+                .bytes_per_day
+                .map_or(true, |limit| bytes_today + bytes <= limit);
+        if !within {
+            return false;
+        }
+
+        state.totals.insert(
+            org_id.to_string(),
+            (day, spans_today + 1, bytes_today + bytes),
+        );
+        let row = self.pending_row(&mut state, org_id, day);
+        row.span_count += 1;
+        row.byte_count += bytes;
+        true
+    }
+
+    /// Count a span over quota that is not forwarded now.
+    fn throttle(&self, org_id: &str, day: NaiveDate) {
+        let mut state = self.state.lock().unwrap();
+        self.pending_row(&mut state, org_id, day).throttled_spans += 1;
+    }
+
+    /// Count a sampled over-quota span as usage.
+    fn record_sampled(&self, org_id: &str, bytes: u64, day: NaiveDate) {
+        let mut state = self.state.lock().unwrap();
+        let totals = state
+            .totals
+            .entry(org_id.to_string())
+            .or_insert((day, 0, 0));
+        if totals.0 == day {
+            totals.1 += 1;
+            totals.2 += bytes;
+        }
+        let row = self.pending_row(&mut state, org_id, day);
+        row.span_count += 1;
+        row.byte_count += bytes;
+    }
+
+    fn pending_row<'a>(
+        &self,
+        state: &'a mut UsageState,
+        org_id: &str,
+        day: NaiveDate,
+    ) -> &'a mut QuotaUsage {
+        let limits = self.limits(org_id);
+        state
+            .pending
+            .entry((day, org_id.to_string()))
+            .or_insert_with(|| QuotaUsage {
+                day,
+                org_id: org_id.to_string(),
+                span_count: 0,
+                byte_count: 0,
+                throttled_spans: 0,
+                spans_per_day_limit: limits.spans_per_day,
+                bytes_per_day_limit: limits.bytes_per_day,
+                over_quota_action: self.over_quota_action(org_id),
+            })
+    }
+}
+
+impl Default for QuotaProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `io::Write` sink counting the bytes written.
+struct ByteCounter(u64);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Size of a span encoded as JSON, without allocating the encoding.
+fn encoded_len(span: &LlmSpan) -> u64 {
+    let mut counter = ByteCounter(0);
+    match serde_json::to_writer(&mut counter, span) {
+        Ok(()) => counter.0,
+        Err(_) => 0,
+    }
+}
+
+#[async_trait]
+impl SpanProcessor for QuotaProcessor {
+    async fn process(&self, span: LlmSpan) -> Result<Option<LlmSpan>> {
+        let Some(org_id) = string_attribute(&span, ORG_ID_ATTRIBUTE) else {
+            return Ok(Some(span));
+        };
+        let bytes = encoded_len(&span);
+        let today = Utc::now().date_naive();
+
+        if self.admit(&org_id, bytes, today) {
+            return Ok(Some(span));
+        }
+
+        match self.over_quota_action(&org_id) {
+            OverQuotaAction::Drop => {
+                metrics::counter!("collector_quota_exceeded_total", "action" => "drop")
+                    .increment(1);
+                self.throttle(&org_id, today);
+                Ok(None)
+            }
+            OverQuotaAction::Sample => {
+                metrics::counter!("collector_quota_exceeded_total", "action" => "sample")
+                    .increment(1);
+                if self.sampler.should_sample() {
+                    self.record_sampled(&org_id, bytes, today);
+                    Ok(Some(span))
+                } else {
+                    self.throttle(&org_id, today);
+                    Ok(None)
+                }
+            }
+            OverQuotaAction::Queue => {
+                metrics::counter!("collector_quota_exceeded_total", "action" => "queue")
+                    .increment(1);
+                self.throttle(&org_id, today);
+                let mut queue = self.queue.lock().unwrap();
+                if queue.len() < self.max_queued {
+                    queue.push_back((org_id, span));
+                } else {
+                    metrics::counter!("collector_quota_queue_dropped_total").increment(1);
+                    tracing::debug!(org_id = %org_id, "Quota queue full, dropping span");
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "quota"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use llm_observatory_core::{
+        span::{LlmInput, SpanStatus},
+        types::{Latency, Provider},
+    };
+
+    fn span(org_id: Option<&str>) -> LlmSpan {
+        let now = Utc::now();
+        let mut attributes = std::collections::HashMap::new();
+        if let Some(org_id) = org_id {
+            attributes.insert(ORG_ID_ATTRIBUTE.to_string(), org_id.into());
+        }
+        LlmSpan {
+            span_id: "s1".to_string(),
+            trace_id: "t1".to_string(),
+            parent_span_id: None,
+            name: "test".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: None,
+            cost: None,
+            latency: Latency::new(now, now),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes,
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    fn spans_limit(limit: u64) -> QuotaLimits {
+        QuotaLimits {
+            spans_per_day: Some(limit),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drops_spans_over_quota() {
+        let processor = QuotaProcessor::new().with_org_limits("org-free", spans_limit(2));
+
+        for _ in 0..2 {
+            assert!(processor
+                .process(span(Some("org-free")))
+                .await
+                .unwrap()
+                .is_some());
+        }
+        assert!(processor
+            .process(span(Some("org-free")))
+            .await
+            .unwrap()
+            .is_none());
+
+        // Other organizations and spans without one are not limited
+        assert!(processor
+            .process(span(Some("org-paid")))
+            .await
+            .unwrap()
+            .is_some());
+        assert!(processor.process(span(None)).await.unwrap().is_some());
+
+        let usage = processor.drain_usage();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].org_id, "org-free");
+        assert_eq!(usage[0].span_count, 2);
+        assert_eq!(usage[0].throttled_spans, 1);
+        assert_eq!(usage[0].spans_per_day_limit, Some(2));
+        assert!(usage[0].byte_count > 0);
+        assert_eq!(usage[1].spans_per_day_limit, None);
+        assert!(processor.drain_usage().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_byte_limit_and_sampling() {
+        let bytes = encoded_len(&span(Some("org-free")));
+        let processor = QuotaProcessor::new()
+            .with_default_limits(QuotaLimits {
+                bytes_per_day: Some(bytes * 3 / 2),
+                ..Default::default()
+            })
+            .with_over_quota(OverQuotaAction::Sample)
+            .with_sample_rate(1.0);
+
+        assert!(processor
+            .process(span(Some("org-free")))
+            .await
+            .unwrap()
+            .is_some());
+        // Over quota, but every span is sampled
+        assert!(processor
+            .process(span(Some("org-free")))
+            .await
+            .unwrap()
+            .is_some());
+
+        let processor = processor.with_sample_rate(0.0);
+        assert!(processor
+            .process(span(Some("org-free")))
+            .await
+            .unwrap()
+            .is_none());
+
+        let usage = processor.drain_usage();
+        assert_eq!(usage[0].span_count, 2);
+        assert_eq!(usage[0].byte_count, bytes * 2);
+        assert_eq!(usage[0].throttled_spans, 1);
+    }
+
+    #[tokio::test]
+    async fn test_queue_releases_on_next_day() {
+        let processor = QuotaProcessor::new()
+            .with_org_limits("org-free", spans_limit(1))
+            .with_over_quota(OverQuotaAction::Queue)
+            .with_max_queued(1);
+
+        assert!(processor
+            .process(span(Some("org-free")))
+            .await
+            .unwrap()
+            .is_some());
+        assert!(processor
+            .process(span(Some("org-free")))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(processor
+            .process(span(Some("org-free")))
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(processor.queued(), 1);
+
+        assert!(processor.release_queued(Utc::now()).is_empty());
+        let tomorrow = Utc::now() + Duration::days(1);
+        assert_eq!(processor.release_queued(tomorrow).len(), 1);
+        assert_eq!(processor.queued(), 0);
+    }
+
+    #[test]
+    fn test_sync_usage_includes_other_replicas() {
+        let processor = QuotaProcessor::new().with_org_limits("org-free", spans_limit(10));
+        let day = Utc
+            .with_ymd_and_hms(2025, 1, 1, 12, 0, 0)
+            .unwrap()
+            .date_naive();
+
+        assert!(processor.admit("org-free", 10, day));
+        // Stored usage of all replicas, not including the undrained span
+        processor.sync_usage(day, [("org-free".to_string(), 8, 80)]);
+        assert!(processor.admit("org-free", 10, day));
+        assert!(!processor.admit("org-free", 10, day));
+
+        let usage = processor.drain_usage();
+        assert_eq!(usage[0].span_count, 2);
+    }
+
+    #[test]
+    fn test_synced_limits_override_configured() {
+        let processor = QuotaProcessor::new()
+            .with_org_limits("org-free", spans_limit(10))
+            .with_over_quota(OverQuotaAction::Sample);
+
+        processor.sync_org_limits([(
+            "org-free".to_string(),
+            QuotaLimits {
+                bytes_per_day: Some(1_000),
+                over_quota: Some(OverQuotaAction::Queue),
+                ..Default::default()
+            },
+        )]);
+        let limits = processor.limits("org-free");
+        assert_eq!(limits.spans_per_day, Some(10));
+        assert_eq!(limits.bytes_per_day, Some(1_000));
+        assert_eq!(
+            processor.over_quota_action("org-free"),
+            OverQuotaAction::Queue
+        );
+
+        // Organizations left out of a sync go back to the configured limits
+        processor.sync_org_limits([]);
+        assert_eq!(processor.limits("org-free"), spans_limit(10));
+        assert_eq!(
+            processor.over_quota_action("org-free"),
+            OverQuotaAction::Sample
+        );
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resource attribute stamping.
+//!
+//! This processor adds the Kubernetes and cloud attributes detected for the
+//! collector (see [`llm_observatory_core::resource`]) to spans, and to the
+//! resource attributes of metrics, that do not carry them. Attributes the
+//! client sent are kept unless `overwrite` is set.
+
+use super::SpanProcessor;
+use crate::config::ResourceDetectionConfig;
+use crate::metric::MetricSeries;
+use async_trait::async_trait;
+use llm_observatory_core::{
+    resource::{self, CloudDetector, KubernetesDetector, ResourceAttributes},
+    span::LlmSpan,
+    Result,
+};
+use serde_json::{Map, Value};
+use std::time::Duration;
+
+/// Resource attribute stamping processor.
+#[derive(Debug, Clone)]
+pub struct ResourceDetectionProcessor {
+    /// Attributes added to every span and metric
+    attributes: ResourceAttributes,
+    /// Replace attributes already present
+    overwrite: bool,
+}
+
+impl ResourceDetectionProcessor {
+    /// Create a processor stamping `attributes`.
+    pub fn new(attributes: ResourceAttributes) -> Self {
+        Self {
+            attributes,
+            overwrite: false,
+        }
+    }
+
+    /// Detect the collector's own resource as configured.
+    pub async fn detect(config: &ResourceDetectionConfig) -> Self {
+        let attributes = if config.cloud_metadata {
+            let timeout = Duration::from_millis(config.metadata_timeout_ms);
+            let cloud = CloudDetector::default().with_timeout(timeout);
+            resource::detect(&[&KubernetesDetector, &cloud]).await
+        } else {
+            let mut attributes = KubernetesDetector.detect_local();
+            attributes.extend(CloudDetector::default().detect_local());
+            attributes
+        };
+
+        Self::new(attributes).with_overwrite(config.overwrite)
+    }
+
+    /// Replace attributes the client already sent.
+    pub fn with_overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Attributes stamped by this processor.
+    pub fn attributes(&self) -> &ResourceAttributes {
+        &self.attributes
+    }
+
+    /// Stamped attributes that `has` does not have, or all with `overwrite`.
+    fn missing<'a>(
+        &'a self,
+        has: impl Fn(&str) -> bool + 'a,
+    ) -> impl Iterator<Item = (String, Value)> + 'a {
+        self.attributes
+            .iter()
+            .filter(move |(key, _)| self.overwrite || !has(key))
+            .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+    }
+}
+
+#[async_trait]
+impl SpanProcessor for ResourceDetectionProcessor {
+    async fn process(&self, mut span: LlmSpan) -> Result<Option<LlmSpan>> {
+        let missing: Vec<_> = self
+            .missing(|key| span.attributes.contains_key(key))
+            .collect();
+        span.attributes.extend(missing);
+        Ok(Some(span))
+    }
+
+    async fn process_metric(&self, mut metric: MetricSeries) -> Result<Option<MetricSeries>> {
+        let resource = &mut metric.metric.resource_attributes;
+        if !resource.is_object() {
+            *resource = Value::Object(Map::new());
+        }
+        if let Some(map) = resource.as_object_mut() {
+            let missing: Vec<_> = self.missing(|key| map.contains_key(key)).collect();
+            map.extend(missing);
+        }
+        Ok(Some(metric))
+    }
+
+    fn name(&self) -> &str {
+        "resource_detection"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::{Metric, MetricType};
+    use chrono::Utc;
+    use llm_observatory_core::{
+        resource::{CLOUD_REGION, K8S_NAMESPACE_NAME, K8S_POD_NAME},
+        span::{LlmInput, SpanStatus},
+        types::{Latency, Provider},
+    };
+    use serde_json::json;
+
+    fn span() -> LlmSpan {
+        let now = Utc::now();
+        LlmSpan {
+            span_id: "test".to_string(),
+            trace_id: "test".to_string(),
+            parent_span_id: None,
+            name: "test".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4o".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: None,
+            cost: None,
+            latency: Latency::new(now, now),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes: Default::default(),
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    fn processor() -> ResourceDetectionProcessor {
+        ResourceDetectionProcessor::new(ResourceAttributes::from([
+            (K8S_POD_NAME.to_string(), "collector-x7k2p".to_string()),
+            (K8S_NAMESPACE_NAME.to_string(), "observability".to_string()),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn test_stamps_missing_attributes() {
+        let mut client_span = span();
+        client_span
+            .attributes
+            .insert(K8S_POD_NAME.to_string(), json!("chat-api-0"));
+
+        let processed = processor()
+            .process(client_span.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(processed.attributes[K8S_POD_NAME], json!("chat-api-0"));
+        assert_eq!(
+            processed.attributes[K8S_NAMESPACE_NAME],
+            json!("observability")
+        );
+
+        let processed = processor()
+            .with_overwrite(true)
+            .process(client_span)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(processed.attributes[K8S_POD_NAME], json!("collector-x7k2p"));
+    }
+
+    #[tokio::test]
+    async fn test_stamps_metric_resource() {
+        let series = MetricSeries {
+            metric: Metric {
+                name: "requests_total".to_string(),
+                description: None,
+                unit: None,
+                metric_type: MetricType::Counter,
+                service_name: "chat-api".to_string(),
+                attributes: json!({}),
+                resource_attributes: Value::Null,
+            },
+            data_points: vec![],
+        };
+
+        let processed = processor().process_metric(series).await.unwrap().unwrap();
+        let resource = &processed.metric.resource_attributes;
+        assert_eq!(resource[K8S_POD_NAME], json!("collector-x7k2p"));
+        assert!(resource.get(CLOUD_REGION).is_none());
+    }
+}
@@ -0,0 +1,469 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Schema validation processor.
+//!
+//! Checks incoming spans against the schemas registered per service and
+//! version in [`SchemaValidationConfig`]: required span fields, required
+//! attributes and attribute types. With the `schema-registry` feature, spans
+//! are also checked with the Schema Registry adapter
+//! (`SchemaAdapter::validate_span_json`).
+//!
+//! Depending on [`SchemaEnforcementMode`], failing spans are logged, annotated
+//! with [`VIOLATIONS_ATTRIBUTE`], quarantined (see [`super::quarantine`]) or
+//! dropped. Every failing span increments `collector_schema_violations_total`.
+
+use super::quarantine::{string_attribute, Quarantine, QuarantineStage, QuarantinedSpan};
+use super::SpanProcessor;
+use crate::config::{AttributeType, SchemaValidationConfig, SpanSchemaConfig};
+use async_trait::async_trait;
+use llm_observatory_core::{span::LlmSpan, Result};
+use serde_json::Value;
+use std::sync::Arc;
+
+pub use crate::config::SchemaEnforcementMode;
+
+/// Attribute holding the list of violations on annotated spans.
+pub const VIOLATIONS_ATTRIBUTE: &str = "llm_observatory.schema.violations";
+
+/// Attribute holding the service name.
+const SERVICE_NAME_ATTRIBUTE: &str = "service.name";
+
+/// Attribute holding the service version.
+const SERVICE_VERSION_ATTRIBUTE: &str = "service.version";
+
+/// Outcome of checking a span against its schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaCheck {
+    /// Schema the span was checked against, e.g. `checkout-api@2.1.0`
+    pub schema: String,
+    /// Violations found; empty if the span is valid
+    pub violations: Vec<String>,
+}
+
+/// Schemas registered per service and version.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaRegistry {
+    schemas: Vec<SpanSchemaConfig>,
+}
+
+impl SchemaRegistry {
+    /// Create a registry.
+    pub fn new(schemas: Vec<SpanSchemaConfig>) -> Self {
+        Self { schemas }
+    }
+
+    /// Most specific schema for a service and version.
+    pub fn lookup(
+        &self,
+        service: Option<&str>,
+        version: Option<&str>,
+    ) -> Option<&SpanSchemaConfig> {
+        let find = |name: &str, version: Option<&str>| {
+            self.schemas
+                .iter()
+                .find(|s| s.service == name && s.version.as_deref() == version)
+        };
+
+        service
+            .and_then(|service| {
+                version
+                    .and_then(|version| find(service, Some(version)))
+                    .or_else(|| find(service, None))
+            })
+            .or_else(|| find("*", None))
+    }
+
+    /// Name of a schema in reports, e.g. `checkout-api@2.1.0`.
+    pub fn schema_name(schema: &SpanSchemaConfig) -> String {
+        match &schema.version {
+            Some(version) => format!("{}@{}", schema.service, version),
+            None => schema.service.clone(),
+        }
+    }
+
+    /// Violations of a span, given as JSON, against a schema.
+    pub fn violations(schema: &SpanSchemaConfig, span: &LlmSpan, span_json: &Value) -> Vec<String> {
+        let mut violations = Vec::new();
+
+        for field in &schema.required_fields {
+            let value = field
+                .split('.')
+                .try_fold(span_json, |value, key| value.get(key));
+            if value.map_or(true, Value::is_null) {
+                violations.push(format!("missing field {}", field));
+            }
+        }
+
+        for key in &schema.required_attributes {
+            if span.attributes.get(key).map_or(true, Value::is_null) {
+                violations.push(format!("missing attribute {}", key));
+            }
+        }
+
+        let mut typed: Vec<_> = schema.attribute_types.iter().collect();
+        typed.sort_by_key(|(key, _)| key.as_str());
+        for (key, expected) in typed {
+            if let Some(value) = span.attributes.get(key) {
+                if !type_matches(*expected, value) {
+                    violations.push(format!(
+                        "attribute {} must be {}",
+                        key,
+                        type_name(*expected)
+                    ));
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+fn type_matches(expected: AttributeType, value: &Value) -> bool {
+    match expected {
+        AttributeType::String => value.is_string(),
+        AttributeType::Integer => value.is_i64() || value.is_u64(),
+        AttributeType::Number => value.is_number(),
+        AttributeType::Boolean => value.is_boolean(),
+        AttributeType::Array => value.is_array(),
+        AttributeType::Object => value.is_object(),
+    }
+}
+
+fn type_name(expected: AttributeType) -> &'static str {
+    match expected {
+        AttributeType::String => "a string",
+        AttributeType::Integer => "an integer",
+        AttributeType::Number => "a number",
+        AttributeType::Boolean => "a boolean",
+        AttributeType::Array => "an array",
+        AttributeType::Object => "an object",
+    }
+}
+
+/// Schema validation processor.
+#[derive(Debug)]
+pub struct SchemaValidationProcessor {
+    /// Registered schemas
+    registry: SchemaRegistry,
+    /// How to handle failing spans
+    mode: SchemaEnforcementMode,
+    /// Where failing spans are quarantined
+    quarantine: Arc<Quarantine>,
+    /// Structural check from the Schema Registry
+    #[cfg(feature = "schema-registry")]
+    adapter: llm_observatory_adapters::SchemaAdapter,
+}
+
+impl SchemaValidationProcessor {
+    /// Create a processor that logs violations of the given schemas.
+    pub fn new(schemas: Vec<SpanSchemaConfig>) -> Self {
+        Self {
+            registry: SchemaRegistry::new(schemas),
+            mode: SchemaEnforcementMode::default(),
+            quarantine: Arc::new(Quarantine::new(
+                SchemaValidationConfig::default().max_quarantined,
+            )),
+            #[cfg(feature = "schema-registry")]
+            adapter: llm_observatory_adapters::SchemaAdapter::new(),
+        }
+    }
+
+    /// Create a processor from configuration.
+    pub fn from_config(config: &SchemaValidationConfig) -> Self {
+        Self::new(config.schemas.clone())
+            .with_mode(config.mode)
+            .with_max_quarantined(config.max_quarantined)
+    }
+
+    /// Set how failing spans are handled.
+    pub fn with_mode(mut self, mode: SchemaEnforcementMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the maximum number of quarantined spans buffered between drains.
+    pub fn with_max_quarantined(self, max_quarantined: usize) -> Self {
+        self.with_quarantine(Arc::new(Quarantine::new(max_quarantined)))
+    }
+
+    /// Quarantine failing spans in a buffer shared with other stages.
+    pub fn with_quarantine(mut self, quarantine: Arc<Quarantine>) -> Self {
+        self.quarantine = quarantine;
+        self
+    }
+
+    /// Take the spans quarantined since the last drain, oldest first.
+    pub fn drain(&self) -> Vec<QuarantinedSpan> {
+        self.quarantine.drain()
+    }
+
+    /// Check a span against its schema; `None` if no schema applies.
+    pub fn check(&self, span: &LlmSpan) -> Result<Option<SchemaCheck>> {
+        Ok(self.check_json(span)?.map(|(check, _)| check))
+    }
+
+    fn check_json(&self, span: &LlmSpan) -> Result<Option<(SchemaCheck, Value)>> {
+        let service = string_attribute(span, SERVICE_NAME_ATTRIBUTE);
+        let version = string_attribute(span, SERVICE_VERSION_ATTRIBUTE);
+        let Some(schema) = self.registry.lookup(service.as_deref(), version.as_deref()) else {
+            return Ok(None);
+        };
+
+        let span_json = serde_json::to_value(span)?;
+
+        #[allow(unused_mut)]
+        let mut violations = SchemaRegistry::violations(schema, span, &span_json);
+
+        #[cfg(feature = "schema-registry")]
+        violations.extend(
+            self.adapter
+                .validate_span_json(&span_json)
+                .errors
+                .into_iter()
+                .map(|error| error.message),
+        );
+
+        let check = SchemaCheck {
+            schema: SchemaRegistry::schema_name(schema),
+            violations,
+        };
+        Ok(Some((check, span_json)))
+    }
+}
+
+impl Default for SchemaValidationProcessor {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+#[async_trait]
+impl SpanProcessor for SchemaValidationProcessor {
+    async fn process(&self, mut span: LlmSpan) -> Result<Option<LlmSpan>> {
+        let Some((check, span_json)) = self.check_json(&span)? else {
+            return Ok(Some(span));
+        };
+        if check.violations.is_empty() {
+            return Ok(Some(span));
+        }
+
+        let mode = match self.mode {
+            SchemaEnforcementMode::Log => "log",
+            SchemaEnforcementMode::Annotate => "annotate",
+            SchemaEnforcementMode::Quarantine => "quarantine",
+            SchemaEnforcementMode::Reject => "reject",
+        };
+        metrics::counter!(
+            "collector_schema_violations_total",
+            "schema" => check.schema.clone(),
+            "mode" => mode
+        )
+        .increment(1);
+
+        match self.mode {
+            SchemaEnforcementMode::Log => {
+                tracing::warn!(
+                    trace_id = %span.trace_id,
+                    span_id = %span.span_id,
+                    schema = %check.schema,
+                    violations = ?check.violations,
+                    "Span does not match its schema"
+                );
+                Ok(Some(span))
+            }
+            SchemaEnforcementMode::Annotate => {
+                let list: Vec<Value> = check.violations.into_iter().map(Value::from).collect();
+                span.attributes
+                    .insert(VIOLATIONS_ATTRIBUTE.to_string(), list.into());
+                Ok(Some(span))
+            }
+            SchemaEnforcementMode::Quarantine => {
+                let failure_reason = format!("Span does not match schema {}", check.schema);
+                self.quarantine.push(
+                    QuarantinedSpan::new(&span, span_json, QuarantineStage::Schema, failure_reason)
+                        .with_schema(check.schema, check.violations),
+                );
+                Ok(None)
+            }
+            SchemaEnforcementMode::Reject => {
+                tracing::debug!(
+                    span_id = %span.span_id,
+                    schema = %check.schema,
+                    "Rejecting span that does not match its schema"
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "schema_validation"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use llm_observatory_core::{
+        span::{LlmInput, SpanStatus},
+        types::{Latency, Provider, TokenUsage},
+    };
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn schema(service: &str, version: Option<&str>) -> SpanSchemaConfig {
+        SpanSchemaConfig {
+            service: service.to_string(),
+            version: version.map(str::to_string),
+            required_fields: vec!["token_usage".to_string(), "latency.ttft_ms".to_string()],
+            required_attributes: vec!["gen_ai.system".to_string()],
+            attribute_types: HashMap::from([(
+                "gen_ai.usage.input_tokens".to_string(),
+                AttributeType::Integer,
+            )]),
+        }
+    }
+
+    fn span(service: &str, version: &str, attributes: HashMap<String, Value>) -> LlmSpan {
+        let now = Utc::now();
+        let mut attributes = attributes;
+        attributes.insert(SERVICE_NAME_ATTRIBUTE.to_string(), json!(service));
+        attributes.insert(SERVICE_VERSION_ATTRIBUTE.to_string(), json!(version));
+        LlmSpan {
+            span_id: "span-1".to_string(),
+            trace_id: "trace-1".to_string(),
+            parent_span_id: None,
+            name: "test".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: Some(TokenUsage::new(100, 50)),
+            cost: None,
+            latency: Latency::new(now, now).with_ttft(10),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes,
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    fn valid_attributes() -> HashMap<String, Value> {
+        HashMap::from([
+            ("gen_ai.system".to_string(), json!("openai")),
+            ("gen_ai.usage.input_tokens".to_string(), json!(100)),
+        ])
+    }
+
+    fn invalid_attributes() -> HashMap<String, Value> {
+        HashMap::from([("gen_ai.usage.input_tokens".to_string(), json!("100"))])
+    }
+
+    #[test]
+    fn test_lookup_prefers_most_specific() {
+        let registry = SchemaRegistry::new(vec![
+            schema("*", None),
+            schema("checkout-api", None),
+            schema("checkout-api", Some("2.1.0")),
+        ]);
+
+        let name = |service, version| {
+            registry
+                .lookup(service, version)
+                .map(SchemaRegistry::schema_name)
+        };
+        assert_eq!(
+            name(Some("checkout-api"), Some("2.1.0")).unwrap(),
+            "checkout-api@2.1.0"
+        );
+        assert_eq!(
+            name(Some("checkout-api"), Some("2.0.0")).unwrap(),
+            "checkout-api"
+        );
+        assert_eq!(name(Some("search-api"), None).unwrap(), "*");
+        assert_eq!(name(None, None).unwrap(), "*");
+
+        let registry = SchemaRegistry::new(vec![schema("checkout-api", Some("2.1.0"))]);
+        assert!(registry
+            .lookup(Some("checkout-api"), Some("2.0.0"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_violations() {
+        let processor = SchemaValidationProcessor::new(vec![schema("checkout-api", None)]);
+
+        let valid = processor
+            .check(&span("checkout-api", "2.1.0", valid_attributes()))
+            .unwrap()
+            .unwrap();
+        assert!(valid.violations.is_empty());
+
+        let mut invalid = span("checkout-api", "2.1.0", invalid_attributes());
+        invalid.token_usage = None;
+        let check = processor.check(&invalid).unwrap().unwrap();
+        assert_eq!(
+            check.violations,
+            vec![
+                "missing field token_usage",
+                "missing attribute gen_ai.system",
+                "attribute gen_ai.usage.input_tokens must be an integer",
+            ]
+        );
+
+        assert!(processor
+            .check(&span("search-api", "1.0.0", invalid_attributes()))
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_enforcement_modes() {
+        let schemas = vec![schema("checkout-api", None)];
+        let invalid = || span("checkout-api", "2.1.0", invalid_attributes());
+
+        let log = SchemaValidationProcessor::new(schemas.clone());
+        let forwarded = log.process(invalid()).await.unwrap().unwrap();
+        assert!(!forwarded.attributes.contains_key(VIOLATIONS_ATTRIBUTE));
+
+        let annotate = SchemaValidationProcessor::new(schemas.clone())
+            .with_mode(SchemaEnforcementMode::Annotate);
+        let annotated = annotate.process(invalid()).await.unwrap().unwrap();
+        assert_eq!(
+            annotated.attributes[VIOLATIONS_ATTRIBUTE]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let reject = SchemaValidationProcessor::new(schemas.clone())
+            .with_mode(SchemaEnforcementMode::Reject);
+        assert!(reject.process(invalid()).await.unwrap().is_none());
+        assert!(reject.drain().is_empty());
+
+        let quarantine = SchemaValidationProcessor::new(schemas)
+            .with_mode(SchemaEnforcementMode::Quarantine)
+            .with_max_quarantined(1);
+        assert!(quarantine.process(invalid()).await.unwrap().is_none());
+        assert!(quarantine.process(invalid()).await.unwrap().is_none());
+        assert!(quarantine
+            .process(span("checkout-api", "2.1.0", valid_attributes()))
+            .await
+            .unwrap()
+            .is_some());
+
+        let rejected = quarantine.drain();
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].stage, QuarantineStage::Schema);
+        assert_eq!(rejected[0].schema_name.as_deref(), Some("checkout-api"));
+        assert_eq!(rejected[0].service_version.as_deref(), Some("2.1.0"));
+        assert_eq!(rejected[0].span["span_id"], json!("span-1"));
+        assert!(quarantine.drain().is_empty());
+    }
+}
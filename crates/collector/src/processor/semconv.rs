@@ -0,0 +1,398 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! GenAI semantic convention validation processor.
+//!
+//! Checks incoming spans against the OpenTelemetry GenAI semantic conventions:
+//! - Required attributes (`gen_ai.system`, `gen_ai.request.model`)
+//! - Token counts (`gen_ai.usage.input_tokens`, `gen_ai.usage.output_tokens`)
+//!   on successful spans, which must be non-negative integers
+//! - Legacy attribute names (e.g. `llm.vendor`, `gen_ai.usage.prompt_tokens`),
+//!   including provider names for prompt cache usage
+//!   (`gen_ai.usage.cache_read_input_tokens`)
+//!
+//! Depending on [`SemconvStrictness`], violations are recorded on the span,
+//! fixed up where possible, or cause the span to be dropped. Every violation
+//! increments the `collector_semconv_violations_total` counter.
+
+use super::SpanProcessor;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use llm_observatory_core::{
+    span::{LlmSpan, SpanStatus},
+    Result,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+pub use crate::config::SemconvStrictness;
+
+/// `gen_ai.system` attribute key.
+pub const GEN_AI_SYSTEM: &str = "gen_ai.system";
+/// `gen_ai.request.model` attribute key.
+pub const GEN_AI_REQUEST_MODEL: &str = "gen_ai.request.model";
+/// `gen_ai.usage.input_tokens` attribute key.
+pub const GEN_AI_USAGE_INPUT_TOKENS: &str = "gen_ai.usage.input_tokens";
+/// `gen_ai.usage.output_tokens` attribute key.
+pub const GEN_AI_USAGE_OUTPUT_TOKENS: &str = "gen_ai.usage.output_tokens";
+/// `gen_ai.usage.cache_read.input_tokens` attribute key.
+pub const GEN_AI_USAGE_CACHE_READ_INPUT_TOKENS: &str = "gen_ai.usage.cache_read.input_tokens";
+/// `gen_ai.usage.cache_creation.input_tokens` attribute key.
+pub const GEN_AI_USAGE_CACHE_CREATION_INPUT_TOKENS: &str =
+    "gen_ai.usage.cache_creation.input_tokens";
+
+/// Attribute holding the list of violations on annotated spans.
+pub const VIOLATIONS_ATTRIBUTE: &str = "llm_observatory.semconv.violations";
+
+/// Legacy attribute names and their semantic convention replacements.
+const LEGACY_ATTRIBUTES: &[(&str, &str)] = &[
+    ("llm.vendor", GEN_AI_SYSTEM),
+    ("llm.system", GEN_AI_SYSTEM),
+    ("llm.request.model", GEN_AI_REQUEST_MODEL),
+    ("llm.model", GEN_AI_REQUEST_MODEL),
+    ("llm.response.model", "gen_ai.response.model"),
+    ("llm.temperature", "gen_ai.request.temperature"),
+    ("llm.request.temperature", "gen_ai.request.temperature"),
+    ("llm.max_tokens", "gen_ai.request.max_tokens"),
+    ("llm.request.max_tokens", "gen_ai.request.max_tokens"),
+    ("llm.top_p", "gen_ai.request.top_p"),
+    ("llm.usage.prompt_tokens", GEN_AI_USAGE_INPUT_TOKENS),
+    ("llm.usage.completion_tokens", GEN_AI_USAGE_OUTPUT_TOKENS),
+    ("gen_ai.usage.prompt_tokens", GEN_AI_USAGE_INPUT_TOKENS),
+    ("gen_ai.usage.completion_tokens", GEN_AI_USAGE_OUTPUT_TOKENS),
+    ("gen_ai.response.finish_reason", "gen_ai.response.finish_reasons"),
+    // Provider usage field names (Anthropic, OpenAI)
+    ("gen_ai.usage.cache_read_input_tokens", GEN_AI_USAGE_CACHE_READ_INPUT_TOKENS),
+    ("gen_ai.usage.cache_creation_input_tokens", GEN_AI_USAGE_CACHE_CREATION_INPUT_TOKENS),
+    ("llm.usage.cache_read_input_tokens", GEN_AI_USAGE_CACHE_READ_INPUT_TOKENS),
+    ("llm.usage.cache_creation_input_tokens", GEN_AI_USAGE_CACHE_CREATION_INPUT_TOKENS),
+    ("llm.usage.cached_tokens", GEN_AI_USAGE_CACHE_READ_INPUT_TOKENS),
+];
+
+/// A single semantic convention violation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// A required attribute is missing
+    MissingAttribute(&'static str),
+    /// An attribute has the wrong type
+    InvalidType(&'static str),
+    /// A legacy attribute name is used
+    LegacyAttribute {
+        /// Legacy name found on the span
+        legacy: &'static str,
+        /// Semantic convention name to use instead
+        replacement: &'static str,
+    },
+}
+
+impl Violation {
+    /// Rule identifier, used as the metric label and report key.
+    pub fn rule(&self) -> &'static str {
+        match self {
+            Violation::MissingAttribute(_) => "missing_attribute",
+            Violation::InvalidType(_) => "invalid_type",
+            Violation::LegacyAttribute { .. } => "legacy_attribute",
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::MissingAttribute(key) => write!(f, "missing {}", key),
+            Violation::InvalidType(key) => write!(f, "invalid type for {}", key),
+            Violation::LegacyAttribute {
+                legacy,
+                replacement,
+            } => write!(f, "legacy attribute {} (use {})", legacy, replacement),
+        }
+    }
+}
+
+/// GenAI semantic convention validation processor.
+#[derive(Debug, Clone)]
+pub struct SemconvValidationProcessor {
+    /// How to handle violations
+    strictness: SemconvStrictness,
+    /// Violation counts by rule
+    report: Arc<DashMap<&'static str, u64>>,
+}
+
+impl SemconvValidationProcessor {
+    /// Create a new validation processor that fixes up spans.
+    pub fn new() -> Self {
+        Self {
+            strictness: SemconvStrictness::default(),
+            report: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Set validation strictness.
+    pub fn with_strictness(mut self, strictness: SemconvStrictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    /// Get violation counts by rule since the processor was created.
+    pub fn violation_report(&self) -> HashMap<&'static str, u64> {
+        self.report.iter().map(|e| (*e.key(), *e.value())).collect()
+    }
+
+    /// Check a span against the semantic conventions.
+    pub fn validate(&self, span: &LlmSpan) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for (legacy, replacement) in LEGACY_ATTRIBUTES {
+            if span.attributes.contains_key(*legacy) {
+                violations.push(Violation::LegacyAttribute {
+                    legacy,
+                    replacement,
+                });
+            }
+        }
+
+        for key in [GEN_AI_SYSTEM, GEN_AI_REQUEST_MODEL] {
+            match span.attributes.get(key) {
+                None => violations.push(Violation::MissingAttribute(key)),
+                Some(v) if !matches!(v.as_str(), Some(s) if !s.is_empty()) => {
+                    violations.push(Violation::InvalidType(key))
+                }
+                Some(_) => {}
+            }
+        }
+
+        // Failed requests often have no usage, so counts are only required on success
+        for key in [GEN_AI_USAGE_INPUT_TOKENS, GEN_AI_USAGE_OUTPUT_TOKENS] {
+            match span.attributes.get(key) {
+                None if span.status != SpanStatus::Error => {
+                    violations.push(Violation::MissingAttribute(key))
+                }
+                Some(v) if v.as_u64().is_none() => violations.push(Violation::InvalidType(key)),
+                _ => {}
+            }
+        }
+
+        violations
+    }
+
+    /// Rename legacy attributes and fill required attributes from span fields.
+    fn fix_up(&self, span: &mut LlmSpan) {
+        for (legacy, replacement) in LEGACY_ATTRIBUTES {
+            if let Some(value) = span.attributes.remove(*legacy) {
+                // Never overwrite a value already set under the new name
+                span.attributes
+                    .entry(replacement.to_string())
+                    .or_insert(value);
+            }
+        }
+
+        if !span.attributes.contains_key(GEN_AI_SYSTEM) {
+            span.attributes
+                .insert(GEN_AI_SYSTEM.to_string(), span.provider.as_str().into());
+        }
+        if !span.attributes.contains_key(GEN_AI_REQUEST_MODEL) && !span.model.is_empty() {
+            span.attributes
+                .insert(GEN_AI_REQUEST_MODEL.to_string(), span.model.clone().into());
+        }
+
+        let usage = span
+            .token_usage
+            .as_ref()
+            .map(|u| (u.prompt_tokens, u.completion_tokens));
+        for (key, from_usage) in [
+            (GEN_AI_USAGE_INPUT_TOKENS, usage.map(|u| u.0)),
+            (GEN_AI_USAGE_OUTPUT_TOKENS, usage.map(|u| u.1)),
+        ] {
+            let current = span.attributes.get(key);
+
+            // Token counts sent as strings ("42") are coerced to integers
+            let coerced = current
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.trim().parse::<u64>().ok());
+
+            let fixed = match (current, coerced) {
+                (Some(_), Some(n)) => Some(n),
+                (None, _) => from_usage.map(u64::from),
+                _ => None,
+            };
+
+            if let Some(n) = fixed {
+                span.attributes.insert(key.to_string(), n.into());
+            }
+        }
+    }
+
+    fn record(&self, span: &LlmSpan, violations: &[Violation]) {
+        for violation in violations {
+            *self.report.entry(violation.rule()).or_insert(0) += 1;
+            metrics::counter!(
+                "collector_semconv_violations_total",
+                "rule" => violation.rule(),
+                "system" => span.provider.as_str().to_string()
+            )
+            .increment(1);
+        }
+    }
+}
+
+impl Default for SemconvValidationProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SpanProcessor for SemconvValidationProcessor {
+    async fn process(&self, mut span: LlmSpan) -> Result<Option<LlmSpan>> {
+        let found = self.validate(&span);
+        if found.is_empty() {
+            return Ok(Some(span));
+        }
+        self.record(&span, &found);
+
+        let remaining = match self.strictness {
+            SemconvStrictness::Reject => {
+                tracing::debug!(
+                    span_id = %span.span_id,
+                    violations = found.len(),
+                    "Rejecting span that violates GenAI semantic conventions"
+                );
+                metrics::counter!("collector_semconv_spans_rejected_total").increment(1);
+                return Ok(None);
+            }
+            SemconvStrictness::Annotate => found,
+            SemconvStrictness::Fix => {
+                self.fix_up(&mut span);
+                self.validate(&span)
+            }
+        };
+
+        if !remaining.is_empty() {
+            let list: Vec<serde_json::Value> =
+                remaining.iter().map(|v| v.to_string().into()).collect();
+            span.attributes
+                .insert(VIOLATIONS_ATTRIBUTE.to_string(), list.into());
+        }
+
+        Ok(Some(span))
+    }
+
+    fn name(&self) -> &str {
+        "semconv_validation"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use llm_observatory_core::{
+        span::LlmInput,
+        types::{Latency, Provider, TokenUsage},
+    };
+    use serde_json::json;
+
+    fn span(attributes: HashMap<String, serde_json::Value>) -> LlmSpan {
+        let now = Utc::now();
+        LlmSpan {
+            span_id: "test".to_string(),
+            trace_id: "test".to_string(),
+            parent_span_id: None,
+            name: "test".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: Some(TokenUsage::new(100, 50)),
+            cost: None,
+            latency: Latency::new(now, now),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes,
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    fn valid_attributes() -> HashMap<String, serde_json::Value> {
+        HashMap::from([
+            (GEN_AI_SYSTEM.to_string(), json!("openai")),
+            (GEN_AI_REQUEST_MODEL.to_string(), json!("gpt-4")),
+            (GEN_AI_USAGE_INPUT_TOKENS.to_string(), json!(100)),
+            (GEN_AI_USAGE_OUTPUT_TOKENS.to_string(), json!(50)),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_valid_span_passes_through() {
+        let processor = SemconvValidationProcessor::new();
+        let processed = processor.process(span(valid_attributes())).await.unwrap().unwrap();
+
+        assert!(!processed.attributes.contains_key(VIOLATIONS_ATTRIBUTE));
+        assert!(processor.violation_report().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fix_renames_legacy_and_fills_gaps() {
+        let processor = SemconvValidationProcessor::new();
+        let attributes = HashMap::from([
+            ("llm.vendor".to_string(), json!("openai")),
+            ("gen_ai.usage.prompt_tokens".to_string(), json!("100")),
+        ]);
+
+        let processed = processor.process(span(attributes)).await.unwrap().unwrap();
+
+        assert_eq!(processed.attributes[GEN_AI_SYSTEM], json!("openai"));
+        assert_eq!(processed.attributes[GEN_AI_REQUEST_MODEL], json!("gpt-4"));
+        assert_eq!(processed.attributes[GEN_AI_USAGE_INPUT_TOKENS], json!(100));
+        assert_eq!(processed.attributes[GEN_AI_USAGE_OUTPUT_TOKENS], json!(50));
+        assert!(!processed.attributes.contains_key("llm.vendor"));
+        assert!(!processed.attributes.contains_key(VIOLATIONS_ATTRIBUTE));
+
+        let report = processor.violation_report();
+        assert_eq!(report["legacy_attribute"], 2);
+        assert_eq!(report["missing_attribute"], 4);
+    }
+
+    #[tokio::test]
+    async fn test_annotate_leaves_attributes() {
+        let processor =
+            SemconvValidationProcessor::new().with_strictness(SemconvStrictness::Annotate);
+        let mut attributes = valid_attributes();
+        attributes.remove(GEN_AI_REQUEST_MODEL);
+
+        let processed = processor.process(span(attributes)).await.unwrap().unwrap();
+
+        assert!(!processed.attributes.contains_key(GEN_AI_REQUEST_MODEL));
+        assert_eq!(
+            processed.attributes[VIOLATIONS_ATTRIBUTE],
+            json!(["missing gen_ai.request.model"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reject_drops_span() {
+        let processor =
+            SemconvValidationProcessor::new().with_strictness(SemconvStrictness::Reject);
+        let mut attributes = valid_attributes();
+        attributes.insert(GEN_AI_USAGE_INPUT_TOKENS.to_string(), json!(-1));
+
+        assert!(processor.process(span(attributes)).await.unwrap().is_none());
+        assert_eq!(processor.violation_report()["invalid_type"], 1);
+    }
+
+    #[test]
+    fn test_token_counts_optional_on_error() {
+        let processor = SemconvValidationProcessor::new();
+        let mut attributes = valid_attributes();
+        attributes.remove(GEN_AI_USAGE_INPUT_TOKENS);
+        attributes.remove(GEN_AI_USAGE_OUTPUT_TOKENS);
+
+        let mut failed = span(attributes);
+        failed.status = SpanStatus::Error;
+        assert!(processor.validate(&failed).is_empty());
+    }
+}
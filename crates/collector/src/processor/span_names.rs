@@ -0,0 +1,364 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Span name normalization.
+//!
+//! Span names that embed dynamic IDs (`chat-completion-for-user-123`) give
+//! every call its own name, so grouping by name no longer groups anything.
+//! This processor rewrites span names as configured in [`SpanNameConfig`]:
+//!
+//! 1. names matching a rule are rewritten to the rule's template,
+//! 2. other names are masked into a template with [`name_template`], which
+//!    replaces segments containing a digit with `{id}`, and the template is
+//!    recorded in the `llm_observatory.span_name.template` attribute,
+//! 3. a template reaching `max_names_per_template` distinct names is
+//!    high-cardinality; with `auto_normalize`, its spans are renamed to it.
+//!
+//! Renamed spans keep their name in `llm_observatory.span_name.original`.
+//! Renames are counted in `collector_span_names_normalized_total{reason}` and
+//! newly detected templates in `collector_span_names_high_cardinality_total`.
+//! Detected templates are listed by [`SpanNameProcessor::offenders`]; the
+//! analytics API reports them across collectors from the template attribute.
+
+use super::quarantine::string_attribute;
+use super::SpanProcessor;
+use crate::config::SpanNameConfig;
+use async_trait::async_trait;
+use llm_observatory_core::{span::LlmSpan, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// Attribute holding the span name before it was rewritten.
+pub const ORIGINAL_NAME_ATTRIBUTE: &str = "llm_observatory.span_name.original";
+
+/// Attribute holding the template of a span name with dynamic segments.
+pub const TEMPLATE_ATTRIBUTE: &str = "llm_observatory.span_name.template";
+
+/// Placeholder for a dynamic segment in a template.
+pub const ID_PLACEHOLDER: &str = "{id}";
+
+/// Attribute holding the organization ID.
+const ORG_ID_ATTRIBUTE: &str = "org_id";
+
+/// Names listed per offender.
+const SAMPLE_NAMES: usize = 5;
+
+/// A high-cardinality span name template.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpanNameOffender {
+    /// Organization ID, if the spans carry one
+    pub org_id: Option<String>,
+    /// Template, with `{id}` for dynamic segments
+    pub template: String,
+    /// Distinct names seen, up to `max_names_per_template`
+    pub distinct_names: usize,
+    /// A few of the names, sorted
+    pub sample_names: Vec<String>,
+    /// Spans seen with the template
+    pub span_count: u64,
+}
+
+#[derive(Debug, Default)]
+struct TemplateState {
+    names: HashSet<String>,
+    span_count: u64,
+    high_cardinality: bool,
+}
+
+/// Span name normalization processor.
+#[derive(Debug)]
+pub struct SpanNameProcessor {
+    /// Rules and their templates, in match order
+    rules: Vec<(Regex, String)>,
+    /// Mask names and detect high-cardinality templates
+    auto_detect: bool,
+    /// Rename spans of high-cardinality templates
+    auto_normalize: bool,
+    /// Distinct names at which a template is high-cardinality
+    max_names_per_template: usize,
+    /// Templates tracked
+    max_templates: usize,
+    /// Tracked templates by organization and template
+    templates: Mutex<HashMap<(Option<String>, String), TemplateState>>,
+}
+
+impl SpanNameProcessor {
+    /// Create a processor from its configuration.
+    ///
+    /// Fails if a rule pattern is not a valid regular expression.
+    pub fn new(config: &SpanNameConfig) -> std::result::Result<Self, regex::Error> {
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| Ok((Regex::new(&rule.pattern)?, rule.template.clone())))
+            .collect::<std::result::Result<_, regex::Error>>()?;
+
+        Ok(Self {
+            rules,
+            auto_detect: config.auto_detect,
+            auto_normalize: config.auto_normalize,
+            max_names_per_template: config.max_names_per_template.max(2),
+            max_templates: config.max_templates,
+            templates: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Rewrite a name with the first rule matching it.
+    pub fn apply_rules(&self, name: &str) -> Option<String> {
+        self.rules
+            .iter()
+            .find(|(regex, _)| regex.is_match(name))
+            .map(|(regex, template)| regex.replace_all(name, template.as_str()).into_owned())
+    }
+
+    /// High-cardinality templates, most spans first.
+    pub fn offenders(&self) -> Vec<SpanNameOffender> {
+        let templates = self.templates.lock().unwrap();
+        let mut offenders: Vec<SpanNameOffender> = templates
+            .iter()
+            .filter(|(_, state)| state.high_cardinality)
+            .map(|((org_id, template), state)| {
+                let mut sample_names: Vec<String> = state.names.iter().cloned().collect();
+                sample_names.sort();
+                sample_names.truncate(SAMPLE_NAMES);
+                SpanNameOffender {
+                    org_id: org_id.clone(),
+                    template: template.clone(),
+                    distinct_names: state.names.len(),
+                    sample_names,
+                    span_count: state.span_count,
+                }
+            })
+            .collect();
+        offenders.sort_by(|a, b| {
+            b.span_count
+                .cmp(&a.span_count)
+                .then_with(|| a.template.cmp(&b.template))
+        });
+        offenders
+    }
+
+    /// Record a name under its template, returning whether the template is
+    /// high-cardinality.
+    fn observe(&self, org_id: Option<String>, template: &str, name: &str) -> bool {
+        let mut templates = self.templates.lock().unwrap();
+        let key = (org_id, template.to_string());
+        if !templates.contains_key(&key) && templates.len() >= self.max_templates {
+            return false;
+        }
+
+        let state = templates.entry(key).or_default();
+        state.span_count += 1;
+        if state.names.len() < self.max_names_per_template {
+            state.names.insert(name.to_string());
+        }
+        if !state.high_cardinality && state.names.len() >= self.max_names_per_template {
+            state.high_cardinality = true;
+            metrics::counter!("collector_span_names_high_cardinality_total").increment(1);
+            tracing::warn!(
+                template,
+                "Span name template reached {} distinct names",
+                self.max_names_per_template
+            );
+        }
+        state.high_cardinality
+    }
+}
+
+/// Mask the segments of a span name that contain a digit.
+///
+/// Names are split into segments on `-`, `_`, `.`, `/`, `:` and spaces, and
+/// runs of dynamic segments become one `{id}`, so a UUID masks like a
+/// number: `user-550e8400-e29b-41d4-a716-446655440000` becomes `user-{id}`.
+pub fn name_template(name: &str) -> String {
+    let is_delimiter = |c: char| matches!(c, '-' | '_' | '.' | '/' | ':' | ' ');
+
+    let mut template = String::with_capacity(name.len());
+    let mut pending = "";
+    let mut last_masked = false;
+    for piece in name.split_inclusive(is_delimiter) {
+        let (segment, delimiter) = match piece.char_indices().last() {
+            Some((i, c)) if is_delimiter(c) => (&piece[..i], &piece[i..]),
+            _ => (piece, ""),
+        };
+        let masked = segment.chars().any(|c| c.is_ascii_digit());
+        if !(masked && last_masked) {
+            template.push_str(pending);
+            template.push_str(if masked { ID_PLACEHOLDER } else { segment });
+        }
+        pending = delimiter;
+        last_masked = masked;
+    }
+    template.push_str(pending);
+    template
+}
+
+#[async_trait]
+impl SpanProcessor for SpanNameProcessor {
+    async fn process(&self, mut span: LlmSpan) -> Result<Option<LlmSpan>> {
+        if let Some(name) = self.apply_rules(&span.name) {
+            if name != span.name {
+                metrics::counter!("collector_span_names_normalized_total", "reason" => "rule")
+                    .increment(1);
+                let original = std::mem::replace(&mut span.name, name);
+                span.attributes
+                    .insert(ORIGINAL_NAME_ATTRIBUTE.to_string(), original.into());
+            }
+            return Ok(Some(span));
+        }
+        if !self.auto_detect {
+            return Ok(Some(span));
+        }
+
+        let template = name_template(&span.name);
+        if template == span.name {
+            return Ok(Some(span));
+        }
+        let org_id = string_attribute(&span, ORG_ID_ATTRIBUTE);
+        let high_cardinality = self.observe(org_id, &template, &span.name);
+        span.attributes
+            .insert(TEMPLATE_ATTRIBUTE.to_string(), template.clone().into());
+
+        if high_cardinality && self.auto_normalize {
+            metrics::counter!("collector_span_names_normalized_total", "reason" => "auto")
+                .increment(1);
+            let original = std::mem::replace(&mut span.name, template);
+            span.attributes
+                .insert(ORIGINAL_NAME_ATTRIBUTE.to_string(), original.into());
+        }
+
+        Ok(Some(span))
+    }
+
+    fn name(&self) -> &str {
+        "span_names"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SpanNameRule;
+    use chrono::Utc;
+    use llm_observatory_core::{
+        span::{LlmInput, SpanStatus},
+        types::{Latency, Provider},
+    };
+
+    fn span(name: &str) -> LlmSpan {
+        let now = Utc::now();
+        LlmSpan {
+            span_id: "span-1".to_string(),
+            trace_id: "trace-1".to_string(),
+            parent_span_id: None,
+            name: name.to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4o".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: None,
+            cost: None,
+            latency: Latency::new(now, now),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes: Default::default(),
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    #[test]
+    fn test_name_template() {
+        assert_eq!(
+            name_template("chat-completion-for-user-123"),
+            "chat-completion-for-user-{id}"
+        );
+        assert_eq!(
+            name_template("user-550e8400-e29b-41d4-a716-446655440000.chat"),
+            "user-{id}.chat"
+        );
+        assert_eq!(name_template("GET /orders/42/items"), "GET /orders/{id}/items");
+        assert_eq!(name_template("llm.chat"), "llm.chat");
+    }
+
+    #[tokio::test]
+    async fn test_rules() {
+        let processor = SpanNameProcessor::new(&SpanNameConfig {
+            enabled: true,
+            rules: vec![SpanNameRule {
+                pattern: r"^(\w+)-for-user-.*$".to_string(),
+                template: "$1".to_string(),
+            }],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let span = processor
+            .process(span("chat-for-user-abc"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(span.name, "chat");
+        assert_eq!(
+            span.attributes[ORIGINAL_NAME_ATTRIBUTE],
+            serde_json::json!("chat-for-user-abc")
+        );
+        assert!(!span.attributes.contains_key(TEMPLATE_ATTRIBUTE));
+    }
+
+    #[tokio::test]
+    async fn test_detects_high_cardinality_templates() {
+        let processor = SpanNameProcessor::new(&SpanNameConfig {
+            enabled: true,
+            auto_normalize: true,
+            max_names_per_template: 3,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mut names = Vec::new();
+        for user in 1..=4 {
+            let span = processor
+                .process(span(&format!("chat-completion-for-user-{}", user)))
+                .await
+                .unwrap()
+                .unwrap();
+            names.push(span.name);
+        }
+        processor.process(span("llm.chat")).await.unwrap();
+
+        // The third distinct name makes the template high-cardinality
+        assert_eq!(
+            names,
+            [
+                "chat-completion-for-user-1",
+                "chat-completion-for-user-2",
+                "chat-completion-for-user-{id}",
+                "chat-completion-for-user-{id}",
+            ]
+        );
+
+        let offenders = processor.offenders();
+        assert_eq!(offenders.len(), 1);
+        assert_eq!(offenders[0].template, "chat-completion-for-user-{id}");
+        assert_eq!(offenders[0].distinct_names, 3);
+        assert_eq!(offenders[0].span_count, 4);
+        assert_eq!(offenders[0].sample_names[0], "chat-completion-for-user-1");
+    }
+
+    #[test]
+    fn test_invalid_rule() {
+        let config = SpanNameConfig {
+            rules: vec![SpanNameRule {
+                pattern: "user-(".to_string(),
+                template: String::new(),
+            }],
+            ..Default::default()
+        };
+        assert!(SpanNameProcessor::new(&config).is_err());
+    }
+}
@@ -0,0 +1,440 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Streaming per-minute aggregation.
+//!
+//! This processor keeps per-minute counters (requests, errors, tokens, cost)
+//! and a latency histogram for each organization, provider and model, in the
+//! row format of the `llm_metrics_streaming_1min` table. Minutes are taken
+//! with [`StreamingAggregationProcessor::drain_closed`] once no more spans are
+//! expected for them, and flushed with an additive upsert, so spans arriving
+//! after their minute was flushed are still counted.
+//!
+//! Organizations listed as aggregate-only opt out of full trace storage:
+//! their spans are dropped once aggregated, so high-traffic tenants produce
+//! one row per minute and model instead of one row per request. Spans of
+//! other organizations pass through unchanged.
+//!
+//! The number of series held between drains is capped. Spans that would
+//! start a series above the cap are not aggregated and always forwarded, so
+//! they are kept as raw spans rather than lost.
+
+use super::quarantine::string_attribute;
+use super::SpanProcessor;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use llm_observatory_core::{
+    span::{LlmSpan, SpanStatus},
+    Result,
+};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// Attribute holding the organization ID.
+const ORG_ID_ATTRIBUTE: &str = "org_id";
+
+/// Default latency bucket bounds in milliseconds.
+pub const DEFAULT_LATENCY_BOUNDS_MS: &[f64] = super::metrics::DEFAULT_LATENCY_BUCKETS_MS;
+
+/// Default seconds a minute stays open after it ends.
+pub const DEFAULT_ALLOWED_LATENESS_SECS: u64 = 60;
+
+/// Default maximum number of series held between drains.
+pub const DEFAULT_MAX_SERIES: usize = 100_000;
+
+/// Requests of one minute, organization, provider and model, in the
+/// `llm_metrics_streaming_1min` row format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MinuteAggregate {
+    /// Start of the minute
+    pub bucket: DateTime<Utc>,
+    /// Organization ID, if the spans carry one
+    pub org_id: Option<String>,
+    /// Provider name
+    pub provider: String,
+    /// Model name
+    pub model: String,
+    /// Requests
+    pub request_count: u64,
+    /// Requests whose span status was error
+    pub error_count: u64,
+    /// Prompt tokens
+    pub prompt_tokens: u64,
+    /// Completion tokens
+    pub completion_tokens: u64,
+    /// Total tokens
+    pub total_tokens: u64,
+    /// Total cost in USD
+    pub total_cost_usd: f64,
+    /// Sum of request durations
+    pub sum_duration_ms: f64,
+    /// Sum of squared request durations, for standard deviation
+    pub sum_duration_ms_squared: f64,
+    /// Shortest request duration
+    pub min_duration_ms: f64,
+    /// Longest request duration
+    pub max_duration_ms: f64,
+    /// Upper bounds (inclusive) of the latency buckets
+    pub latency_bounds_ms: Vec<f64>,
+    /// Requests per latency bucket; the last element counts requests above
+    /// the last bound
+    pub latency_bucket_counts: Vec<u64>,
+}
+
+impl MinuteAggregate {
+    fn new(
+        bucket: DateTime<Utc>,
+        org_id: Option<String>,
+        provider: String,
+        model: String,
+        bounds: &[f64],
+    ) -> Self {
+        Self {
+            bucket,
+            org_id,
+            provider,
+            model,
+            request_count: 0,
+            error_count: 0,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            total_tokens: 0,
+            total_cost_usd: 0.0,
+            sum_duration_ms: 0.0,
+            sum_duration_ms_squared: 0.0,
+            min_duration_ms: f64::INFINITY,
+            max_duration_ms: f64::NEG_INFINITY,
+            latency_bounds_ms: bounds.to_vec(),
+            latency_bucket_counts: vec![0; bounds.len() + 1],
+        }
+    }
+
+    fn record(&mut self, span: &LlmSpan) {
+        self.request_count += 1;
+        if span.status == SpanStatus::Error {
+            self.error_count += 1;
+        }
+        if let Some(usage) = &span.token_usage {
+            self.prompt_tokens += u64::from(usage.prompt_tokens);
+            self.completion_tokens += u64::from(usage.completion_tokens);
+            self.total_tokens += u64::from(usage.total_tokens);
+        }
+        if let Some(cost) = span.cost.as_ref().filter(|c| c.amount_usd.is_finite()) {
+            self.total_cost_usd += cost.amount_usd;
+        }
+
+        let duration_ms = span.latency.total_ms as f64;
+        self.sum_duration_ms += duration_ms;
+        self.sum_duration_ms_squared += duration_ms * duration_ms;
+        self.min_duration_ms = self.min_duration_ms.min(duration_ms);
+        self.max_duration_ms = self.max_duration_ms.max(duration_ms);
+        let index = self
+            .latency_bounds_ms
+            .partition_point(|bound| *bound < duration_ms);
+        self.latency_bucket_counts[index] += 1;
+    }
+}
+
+/// Series key: minute, organization, provider and model.
+type SeriesKey = (DateTime<Utc>, Option<String>, String, String);
+
+/// Streaming aggregation processor.
+#[derive(Debug)]
+pub struct StreamingAggregationProcessor {
+    /// Latency bucket bounds in milliseconds
+    latency_bounds: Vec<f64>,
+    /// How long a minute stays open after it ends
+    allowed_lateness: Duration,
+    /// Series held between drains; spans of further series pass through
+    max_series: usize,
+    /// Organizations whose spans are dropped once aggregated
+    aggregate_only_orgs: HashSet<String>,
+    /// Minutes aggregated since the last drain
+    aggregates: Mutex<HashMap<SeriesKey, MinuteAggregate>>,
+}
+
+impl StreamingAggregationProcessor {
+    /// Create a processor that forwards every span.
+    pub fn new() -> Self {
+        Self {
+            latency_bounds: DEFAULT_LATENCY_BOUNDS_MS.to_vec(),
+            allowed_lateness: Duration::seconds(DEFAULT_ALLOWED_LATENESS_SECS as i64),
+            max_series: DEFAULT_MAX_SERIES,
+            aggregate_only_orgs: HashSet::new(),
+            aggregates: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a processor from configuration.
+    pub fn from_config(config: &crate::config::StreamingAggregationConfig) -> Self {
+        Self::new()
+            .with_allowed_lateness(std::time::Duration::from_secs(config.allowed_lateness_secs))
+            .with_max_series(config.max_series)
+            .with_aggregate_only_orgs(config.aggregate_only_orgs.iter().cloned())
+    }
+
+    /// Set the latency bucket bounds in milliseconds.
+    ///
+    /// Every collector writing to the same table must use the same bounds.
+    pub fn with_latency_bounds(mut self, mut bounds: Vec<f64>) -> Self {
+        bounds.retain(|bound| bound.is_finite());
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+        self.latency_bounds = bounds;
+        self
+    }
+
+    /// Set how long a minute stays open after it ends.
+    pub fn with_allowed_lateness(mut self, lateness: std::time::Duration) -> Self {
+        self.allowed_lateness = Duration::from_std(lateness).unwrap_or(Duration::zero());
+        self
+    }
+
+    /// Set the maximum number of series held between drains.
+    pub fn with_max_series(mut self, max_series: usize) -> Self {
+        self.max_series = max_series;
+        self
+    }
+
+    /// Set the organizations whose spans are dropped once aggregated.
+    pub fn with_aggregate_only_orgs(mut self, orgs: impl IntoIterator<Item = String>) -> Self {
+        self.aggregate_only_orgs = orgs.into_iter().collect();
+        self
+    }
+
+    /// Take the minutes that closed before `now`: minutes that ended more
+    /// than the allowed lateness ago. Rows are ordered by minute,
+    /// organization, provider and model.
+    pub fn drain_closed(&self, now: DateTime<Utc>) -> Vec<MinuteAggregate> {
+        let cutoff = now - Duration::minutes(1) - self.allowed_lateness;
+        let mut aggregates = self.aggregates.lock().unwrap();
+
+        let closed: Vec<SeriesKey> = aggregates
+            .keys()
+            .filter(|(bucket, ..)| *bucket <= cutoff)
+            .cloned()
+            .collect();
+        let rows = closed
+            .iter()
+            .filter_map(|key| aggregates.remove(key))
+            .collect();
+        drop(aggregates);
+
+        sorted(rows)
+    }
+
+    /// Take every minute aggregated since the last drain, e.g. on shutdown.
+    pub fn drain(&self) -> Vec<MinuteAggregate> {
+        let aggregates = std::mem::take(&mut *self.aggregates.lock().unwrap());
+        sorted(aggregates.into_values().collect())
+    }
+
+    /// Aggregate a span, returning whether it was counted.
+    fn record(&self, span: &LlmSpan, org_id: Option<String>) -> bool {
+        let bucket = span
+            .latency
+            .end_time
+            .duration_trunc(Duration::minutes(1))
+            .unwrap_or(span.latency.end_time);
+        let key = (
+            bucket,
+            org_id,
+            span.provider.as_str().to_string(),
+            span.model.clone(),
+        );
+
+        let mut aggregates = self.aggregates.lock().unwrap();
+        if !aggregates.contains_key(&key) && aggregates.len() >= self.max_series {
+            return false;
+        }
+        aggregates
+            .entry(key)
+            .or_insert_with_key(|(bucket, org_id, provider, model)| {
+                MinuteAggregate::new(
+                    *bucket,
+                    org_id.clone(),
+                    provider.clone(),
+                    model.clone(),
+                    &self.latency_bounds,
+                )
+            })
+            .record(span);
+        true
+    }
+}
+
+impl Default for StreamingAggregationProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn sorted(mut rows: Vec<MinuteAggregate>) -> Vec<MinuteAggregate> {
+    rows.sort_by(|a, b| {
+        (a.bucket, &a.org_id, &a.provider, &a.model).cmp(&(
+            b.bucket,
+            &b.org_id,
+            &b.provider,
+            &b.model,
+        ))
+    });
+    rows
+}
+
+#[async_trait]
+impl SpanProcessor for StreamingAggregationProcessor {
+    async fn process(&self, span: LlmSpan) -> Result<Option<LlmSpan>> {
+        let org_id = string_attribute(&span, ORG_ID_ATTRIBUTE);
+        let aggregate_only = org_id
+            .as_ref()
+            .is_some_and(|org| self.aggregate_only_orgs.contains(org));
+
+        if !self.record(&span, org_id) {
+            metrics::counter!("collector_streaming_aggregation_overflow_total").increment(1);
+            return Ok(Some(span));
+        }
+
+        if aggregate_only {
+            metrics::counter!("collector_aggregate_only_spans_total").increment(1);
+            return Ok(None);
+        }
+        Ok(Some(span))
+    }
+
+    fn name(&self) -> &str {
+        "streaming_aggregation"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use llm_observatory_core::{
+        span::LlmInput,
+        types::{Cost, Latency, Provider, TokenUsage},
+    };
+
+    fn span(org_id: &str, end: DateTime<Utc>, duration_ms: i64, status: SpanStatus) -> LlmSpan {
+        LlmSpan {
+            span_id: "span".to_string(),
+            trace_id: "trace".to_string(),
+            parent_span_id: None,
+            name: "llm.chat".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4o".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: Some(TokenUsage::new(100, 50)),
+            cost: Some(Cost {
+                amount_usd: 0.01,
+                currency: "USD".to_string(),
+                prompt_cost: None,
+                completion_cost: None,
+            }),
+            latency: Latency::new(end - Duration::milliseconds(duration_ms), end),
+            metadata: Default::default(),
+            status,
+            attributes: [("org_id".to_string(), serde_json::json!(org_id))]
+                .into_iter()
+                .collect(),
+            events: vec![],
+            links: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_per_minute_aggregation() {
+        let processor =
+            StreamingAggregationProcessor::new().with_latency_bounds(vec![100.0, 1000.0]);
+        let minute = Utc.with_ymd_and_hms(2025, 11, 5, 12, 0, 0).unwrap();
+
+        for (offset_secs, duration_ms, status) in [
+            (5, 50, SpanStatus::Ok),
+            (30, 400, SpanStatus::Error),
+            (59, 2000, SpanStatus::Ok),
+            (65, 80, SpanStatus::Ok),
+        ] {
+            let end = minute + Duration::seconds(offset_secs);
+            let processed = processor
+                .process(span("org-1", end, duration_ms, status))
+                .await
+                .unwrap();
+            assert!(processed.is_some());
+        }
+
+        // The second minute is still open 90 seconds after the first began
+        let rows = processor.drain_closed(minute + Duration::seconds(150));
+        assert_eq!(rows.len(), 1);
+        let row = &rows[0];
+        assert_eq!(row.bucket, minute);
+        assert_eq!(row.org_id.as_deref(), Some("org-1"));
+        assert_eq!(row.provider, "openai");
+        assert_eq!(row.request_count, 3);
+        assert_eq!(row.error_count, 1);
+        assert_eq!(row.prompt_tokens, 300);
+        assert_eq!(row.total_tokens, 450);
+        assert!((row.total_cost_usd - 0.03).abs() < 1e-9);
+        assert_eq!(row.min_duration_ms, 50.0);
+        assert_eq!(row.max_duration_ms, 2000.0);
+        assert_eq!(row.sum_duration_ms, 2450.0);
+        assert_eq!(row.latency_bucket_counts, vec![1, 1, 1]);
+
+        let rest = processor.drain();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].bucket, minute + Duration::minutes(1));
+        assert!(processor.drain().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_only_orgs_drop_raw_spans() {
+        let processor =
+            StreamingAggregationProcessor::new().with_aggregate_only_orgs(["org-bulk".to_string()]);
+        let now = Utc::now();
+
+        assert!(processor
+            .process(span("org-bulk", now, 100, SpanStatus::Ok))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(processor
+            .process(span("org-1", now, 100, SpanStatus::Ok))
+            .await
+            .unwrap()
+            .is_some());
+
+        let orgs: Vec<_> = processor
+            .drain()
+            .into_iter()
+            .map(|row| row.org_id)
+            .collect();
+        assert_eq!(
+            orgs,
+            vec![Some("org-1".to_string()), Some("org-bulk".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_series_cap_forwards_spans() {
+        let processor = StreamingAggregationProcessor::new()
+            .with_max_series(1)
+            .with_aggregate_only_orgs(["org-bulk".to_string()]);
+        let now = Utc::now();
+
+        assert!(processor
+            .process(span("org-1", now, 100, SpanStatus::Ok))
+            .await
+            .unwrap()
+            .is_some());
+        // Over the cap: kept as a raw span even though its org is aggregate-only
+        assert!(processor
+            .process(span("org-bulk", now, 100, SpanStatus::Ok))
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(processor.drain().len(), 1);
+    }
+}
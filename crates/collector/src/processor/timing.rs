@@ -0,0 +1,126 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provider queue time extraction.
+//!
+//! This processor splits each LLM call into the phases marked by its span
+//! events (request sent, first byte, first token; see
+//! [`llm_observatory_core::timing`]) and records them as attributes:
+//! - `llm_observatory.timing.client_ms`: client-side overhead before sending
+//! - `llm_observatory.timing.queue_ms`: waiting for the provider's first byte
+//! - `llm_observatory.timing.execution_ms`: first byte to completion
+//!
+//! The time to first token is filled from the first token event when the
+//! span has none. Queue and execution times are observed in the
+//! `collector_provider_queue_ms` and `collector_provider_execution_ms`
+//! histograms per provider. Spans without first byte or first token events
+//! pass through unchanged.
+
+use super::SpanProcessor;
+use async_trait::async_trait;
+use llm_observatory_core::{span::LlmSpan, timing::SpanTiming, Result};
+
+/// Provider queue time extraction processor.
+#[derive(Debug, Clone, Default)]
+pub struct QueueTimingProcessor;
+
+impl QueueTimingProcessor {
+    /// Create a new processor.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl SpanProcessor for QueueTimingProcessor {
+    async fn process(&self, mut span: LlmSpan) -> Result<Option<LlmSpan>> {
+        let Some(timing) = SpanTiming::from_span(&span) else {
+            return Ok(Some(span));
+        };
+
+        let provider = span.provider.as_str().to_string();
+        metrics::histogram!("collector_provider_queue_ms", "provider" => provider.clone())
+            .record(timing.queue_ms as f64);
+        metrics::histogram!("collector_provider_execution_ms", "provider" => provider)
+            .record(timing.execution_ms as f64);
+
+        if span.latency.ttft_ms.is_none() {
+            span.latency.ttft_ms = timing.ttft_ms;
+        }
+        span.attributes.extend(timing.to_attributes());
+
+        Ok(Some(span))
+    }
+
+    fn name(&self) -> &str {
+        "queue_timing"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use llm_observatory_core::{
+        span::{LlmInput, SpanEvent, SpanStatus},
+        timing::{FIRST_BYTE_EVENT, FIRST_TOKEN_EVENT, TIMING_EXECUTION_MS, TIMING_QUEUE_MS},
+        types::{Latency, Provider},
+    };
+
+    fn span(events: &[(&str, i64)]) -> LlmSpan {
+        let start = Utc::now();
+        LlmSpan {
+            span_id: "span-1".to_string(),
+            trace_id: "trace-1".to_string(),
+            parent_span_id: None,
+            name: "llm.chat".to_string(),
+            provider: Provider::Anthropic,
+            model: "claude-3-5-sonnet-20241022".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: None,
+            cost: None,
+            latency: Latency::new(start, start + Duration::milliseconds(2000)),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes: Default::default(),
+            events: events
+                .iter()
+                .map(|(name, offset_ms)| SpanEvent {
+                    name: name.to_string(),
+                    timestamp: start + Duration::milliseconds(*offset_ms),
+                    attributes: Default::default(),
+                })
+                .collect(),
+            links: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_phases() {
+        let processor = QueueTimingProcessor::new();
+        let span = processor
+            .process(span(&[(FIRST_BYTE_EVENT, 800), (FIRST_TOKEN_EVENT, 900)]))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(span.attributes[TIMING_QUEUE_MS], serde_json::json!(800));
+        assert_eq!(
+            span.attributes[TIMING_EXECUTION_MS],
+            serde_json::json!(1200)
+        );
+        assert_eq!(span.latency.ttft_ms, Some(900));
+    }
+
+    #[tokio::test]
+    async fn test_passes_spans_without_events() {
+        let processor = QueueTimingProcessor::new();
+        let span = processor.process(span(&[])).await.unwrap().unwrap();
+
+        assert!(span.attributes.is_empty());
+        assert_eq!(span.latency.ttft_ms, None);
+    }
+}
@@ -4,6 +4,9 @@
 //! Receivers for ingesting telemetry data.
 
 pub mod otlp;
+pub mod prometheus;
+pub mod routing;
+mod snappy;
 
 use async_trait::async_trait;
 use llm_observatory_core::Result;
@@ -4,12 +4,43 @@
 //! OTLP (OpenTelemetry Protocol) receiver implementation.
 //!
 //! Receives traces, metrics, and logs over gRPC and HTTP.
+//!
+//! Metric mapping ([`to_metric_series`]):
+//! - `service.name` becomes the service name; other resource attributes are
+//!   resource attributes and data point attributes stay on the data points
+//! - Sums are counters when monotonic, gauges otherwise
+//! - Explicit-bucket histograms keep their finite bounds; the `+Inf` bucket
+//!   only shows up in the total count
+//! - Exponential histograms are expanded into buckets bounded by powers of
+//!   the histogram base, so both kinds merge the same way on read
+//! - Cumulative histograms are converted to deltas with [`HistogramDeltas`]
+//!
+//! Span links ([`to_span_links`]) keep their hex-encoded trace and span IDs
+//! and attributes, so spans of agent fan-outs and batch jobs can be followed
+//! across traces. Links without valid IDs are dropped.
 
 use super::Receiver;
+use crate::compression::Compression;
+use crate::metric::{
+    HistogramDeltas, Metric, MetricDataPoint, MetricSeries, MetricType, SummaryQuantile,
+};
+use crate::processor::metrics::{Exemplar, HistogramBucket};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use llm_observatory_core::span::SpanLink;
 use llm_observatory_core::Result;
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use opentelemetry_proto::tonic::common::v1::{any_value, AnyValue, KeyValue};
+use opentelemetry_proto::tonic::metrics::v1::{
+    exemplar, exponential_histogram_data_point, metric, number_data_point, AggregationTemporality,
+    ExponentialHistogramDataPoint, HistogramDataPoint, NumberDataPoint, SummaryDataPoint,
+};
+use opentelemetry_proto::tonic::trace::v1::span;
 use std::net::SocketAddr;
 
+/// Service name for resources without `service.name`.
+const UNKNOWN_SERVICE: &str = "unknown_service";
+
 /// OTLP receiver configuration.
 #[derive(Debug, Clone)]
 pub struct OtlpReceiver {
@@ -21,6 +52,8 @@ pub struct OtlpReceiver {
     enable_grpc: bool,
     /// Enable HTTP
     enable_http: bool,
+    /// Request compression accepted from senders
+    accept_compression: Vec<Compression>,
 }
 
 impl OtlpReceiver {
@@ -31,6 +64,7 @@ impl OtlpReceiver {
             http_endpoint,
             enable_grpc: true,
             enable_http: true,
+            accept_compression: vec![Compression::Gzip, Compression::Zstd],
         }
     }
 
@@ -45,12 +79,25 @@ impl OtlpReceiver {
         self.enable_http = enable;
         self
     }
+
+    /// Set the request compression accepted from senders.
+    pub fn with_accept_compression(mut self, accept_compression: Vec<Compression>) -> Self {
+        self.accept_compression = accept_compression;
+        self
+    }
 }
 
 #[async_trait]
 impl Receiver for OtlpReceiver {
     async fn start(&mut self) -> Result<()> {
-        tracing::info!("Starting OTLP receiver");
+        tracing::info!(
+            "Starting OTLP receiver (accepting compression: {})",
+            self.accept_compression
+                .iter()
+                .map(Compression::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
 
         if self.enable_grpc {
             tracing::info!("OTLP gRPC receiver listening on {}", self.grpc_endpoint);
@@ -75,3 +122,422 @@ impl Receiver for OtlpReceiver {
         "otlp"
     }
 }
+
+// ============================================================================
+// Metric mapping
+// ============================================================================
+
+/// Map an OTLP metrics export request into metric series, one per metric.
+///
+/// Cumulative histograms are converted to deltas against the points
+/// previously seen by `deltas`.
+pub fn to_metric_series(
+    request: ExportMetricsServiceRequest,
+    deltas: &HistogramDeltas,
+) -> Vec<MetricSeries> {
+    let mut series = Vec::new();
+
+    for resource_metrics in request.resource_metrics {
+        let mut resource_attributes = resource_metrics
+            .resource
+            .map(|resource| to_json_attributes(&resource.attributes))
+            .unwrap_or_default();
+        let service_name = match resource_attributes.remove("service.name") {
+            Some(serde_json::Value::String(name)) => name,
+            _ => UNKNOWN_SERVICE.to_string(),
+        };
+
+        for otlp in resource_metrics
+            .scope_metrics
+            .into_iter()
+            .flat_map(|scope| scope.metrics)
+        {
+            let Some(data) = otlp.data else {
+                continue;
+            };
+
+            let (metric_type, data_points, cumulative) = match data {
+                metric::Data::Gauge(gauge) => (
+                    MetricType::Gauge,
+                    gauge.data_points.iter().map(number_point).collect(),
+                    false,
+                ),
+                metric::Data::Sum(sum) => (
+                    if sum.is_monotonic {
+                        MetricType::Counter
+                    } else {
+                        MetricType::Gauge
+                    },
+                    sum.data_points.iter().map(number_point).collect(),
+                    false,
+                ),
+                metric::Data::Histogram(histogram) => (
+                    MetricType::Histogram,
+                    histogram.data_points.iter().map(histogram_point).collect(),
+                    is_cumulative(histogram.aggregation_temporality),
+                ),
+                metric::Data::ExponentialHistogram(histogram) => (
+                    MetricType::Histogram,
+                    histogram
+                        .data_points
+                        .iter()
+                        .map(exponential_histogram_point)
+                        .collect(),
+                    is_cumulative(histogram.aggregation_temporality),
+                ),
+                metric::Data::Summary(summary) => (
+                    MetricType::Summary,
+                    summary.data_points.iter().map(summary_point).collect(),
+                    false,
+                ),
+            };
+
+            let mut metric_series = MetricSeries {
+                metric: Metric {
+                    name: otlp.name,
+                    description: Some(otlp.description).filter(|d| !d.is_empty()),
+                    unit: Some(otlp.unit).filter(|u| !u.is_empty()),
+                    metric_type,
+                    service_name: service_name.clone(),
+                    attributes: serde_json::json!({}),
+                    resource_attributes: serde_json::Value::Object(resource_attributes.clone()),
+                },
+                data_points,
+            };
+            if cumulative {
+                deltas.apply(&mut metric_series);
+            }
+            series.push(metric_series);
+        }
+    }
+
+    series
+}
+
+fn is_cumulative(temporality: i32) -> bool {
+    temporality == AggregationTemporality::Cumulative as i32
+}
+
+fn number_point(point: &NumberDataPoint) -> MetricDataPoint {
+    MetricDataPoint {
+        timestamp: to_datetime(point.time_unix_nano),
+        value: point.value.map(|value| match value {
+            number_data_point::Value::AsDouble(v) => v,
+            number_data_point::Value::AsInt(v) => v as f64,
+        }),
+        exemplars: point.exemplars.iter().map(to_exemplar).collect(),
+        attributes: serde_json::Value::Object(to_json_attributes(&point.attributes)),
+        ..Default::default()
+    }
+}
+
+fn histogram_point(point: &HistogramDataPoint) -> MetricDataPoint {
+    // `bucket_counts` has one more entry than `explicit_bounds`: the +Inf
+    // bucket, which is kept only in the total count
+    let buckets = point
+        .explicit_bounds
+        .iter()
+        .zip(&point.bucket_counts)
+        .map(|(&boundary, &count)| HistogramBucket { boundary, count })
+        .collect();
+
+    MetricDataPoint {
+        timestamp: to_datetime(point.time_unix_nano),
+        count: Some(point.count as i64),
+        sum: point.sum,
+        min: point.min,
+        max: point.max,
+        buckets,
+        exemplars: point.exemplars.iter().map(to_exemplar).collect(),
+        attributes: serde_json::Value::Object(to_json_attributes(&point.attributes)),
+        ..Default::default()
+    }
+}
+
+/// Expand an exponential histogram into buckets with explicit upper bounds.
+///
+/// With `base = 2^(2^-scale)`, positive bucket `i` holds `(base^i, base^(i+1)]`
+/// and negative bucket `i` holds `[-base^(i+1), -base^i)`; the zero bucket is
+/// bounded by the zero threshold. Bounds that overflow `f64` are dropped, so
+/// their counts only show up in the total count.
+fn exponential_histogram_point(point: &ExponentialHistogramDataPoint) -> MetricDataPoint {
+    let base = 2f64.powf(2f64.powi(-point.scale));
+    let indexed = |buckets: &exponential_histogram_data_point::Buckets| {
+        buckets
+            .bucket_counts
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| (buckets.offset as i64 + i as i64, count))
+            .collect::<Vec<_>>()
+    };
+
+    let mut buckets = Vec::new();
+    if let Some(negative) = &point.negative {
+        for (index, count) in indexed(negative).into_iter().rev() {
+            buckets.push(HistogramBucket {
+                boundary: -base.powf(index as f64),
+                count,
+            });
+        }
+    }
+    if point.zero_count > 0 {
+        buckets.push(HistogramBucket {
+            boundary: point.zero_threshold,
+            count: point.zero_count,
+        });
+    }
+    if let Some(positive) = &point.positive {
+        for (index, count) in indexed(positive) {
+            buckets.push(HistogramBucket {
+                boundary: base.powf((index + 1) as f64),
+                count,
+            });
+        }
+    }
+    buckets.retain(|bucket| bucket.boundary.is_finite() && bucket.count > 0);
+
+    MetricDataPoint {
+        timestamp: to_datetime(point.time_unix_nano),
+        count: Some(point.count as i64),
+        sum: point.sum,
+        min: point.min,
+        max: point.max,
+        buckets,
+        exemplars: point.exemplars.iter().map(to_exemplar).collect(),
+        attributes: serde_json::Value::Object(to_json_attributes(&point.attributes)),
+        ..Default::default()
+    }
+}
+
+fn summary_point(point: &SummaryDataPoint) -> MetricDataPoint {
+    MetricDataPoint {
+        timestamp: to_datetime(point.time_unix_nano),
+        count: Some(point.count as i64),
+        sum: Some(point.sum),
+        quantiles: point
+            .quantile_values
+            .iter()
+            .map(|q| SummaryQuantile {
+                quantile: q.quantile,
+                value: q.value,
+            })
+            .collect(),
+        attributes: serde_json::Value::Object(to_json_attributes(&point.attributes)),
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Span link mapping
+// ============================================================================
+
+/// Map the links of an OTLP span.
+///
+/// Links whose trace or span ID is missing, of the wrong length or all
+/// zeros are invalid per the OTLP specification and are dropped.
+pub fn to_span_links(links: &[span::Link]) -> Vec<SpanLink> {
+    links
+        .iter()
+        .filter(|link| is_valid_id(&link.trace_id, 16) && is_valid_id(&link.span_id, 8))
+        .map(|link| SpanLink {
+            trace_id: encode_hex(&link.trace_id),
+            span_id: encode_hex(&link.span_id),
+            attributes: to_json_attributes(&link.attributes).into_iter().collect(),
+        })
+        .collect()
+}
+
+fn is_valid_id(id: &[u8], len: usize) -> bool {
+    id.len() == len && id.iter().any(|b| *b != 0)
+}
+
+fn to_exemplar(exemplar: &opentelemetry_proto::tonic::metrics::v1::Exemplar) -> Exemplar {
+    Exemplar {
+        trace_id: encode_hex(&exemplar.trace_id),
+        span_id: encode_hex(&exemplar.span_id),
+        value: match exemplar.value {
+            Some(exemplar::Value::AsDouble(v)) => v,
+            Some(exemplar::Value::AsInt(v)) => v as f64,
+            None => 0.0,
+        },
+        timestamp: to_datetime(exemplar.time_unix_nano),
+        attributes: serde_json::Value::Object(to_json_attributes(&exemplar.filtered_attributes)),
+    }
+}
+
+fn to_json_attributes(attributes: &[KeyValue]) -> serde_json::Map<String, serde_json::Value> {
+    attributes
+        .iter()
+        .filter_map(|kv| Some((kv.key.clone(), to_json_value(kv.value.as_ref()?)?)))
+        .collect()
+}
+
+/// Convert an OTLP value to JSON, the inverse of the exporter's
+/// `to_any_value`. Empty values are dropped.
+fn to_json_value(value: &AnyValue) -> Option<serde_json::Value> {
+    use serde_json::Value as Json;
+
+    Some(match value.value.as_ref()? {
+        any_value::Value::StringValue(s) => Json::String(s.clone()),
+        any_value::Value::BoolValue(b) => Json::Bool(*b),
+        any_value::Value::IntValue(i) => Json::from(*i),
+        any_value::Value::DoubleValue(d) => Json::from(*d),
+        any_value::Value::ArrayValue(array) => {
+            Json::Array(array.values.iter().filter_map(to_json_value).collect())
+        }
+        any_value::Value::KvlistValue(list) => Json::Object(to_json_attributes(&list.values)),
+        any_value::Value::BytesValue(bytes) => Json::String(encode_hex(bytes)),
+    })
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn to_datetime(unix_nanos: u64) -> DateTime<Utc> {
+    DateTime::from_timestamp_nanos(unix_nanos.min(i64::MAX as u64) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exporter::otlp::metrics_to_request;
+    use opentelemetry_proto::tonic::metrics::v1::{
+        ExponentialHistogram, Metric as OtlpMetric, ResourceMetrics, ScopeMetrics,
+    };
+
+    fn histogram_series(timestamp: DateTime<Utc>, count: i64, buckets: &[u64]) -> MetricSeries {
+        MetricSeries {
+            metric: Metric {
+                name: "llm.request.duration".to_string(),
+                description: Some("Request latency".to_string()),
+                unit: Some("ms".to_string()),
+                metric_type: MetricType::Histogram,
+                service_name: "chat-api".to_string(),
+                attributes: serde_json::json!({}),
+                resource_attributes: serde_json::json!({"deployment.environment": "prod"}),
+            },
+            data_points: vec![MetricDataPoint {
+                timestamp,
+                count: Some(count),
+                sum: Some(count as f64 * 100.0),
+                min: Some(12.0),
+                max: Some(900.0),
+                buckets: buckets
+                    .iter()
+                    .zip([50.0, 250.0, 1000.0])
+                    .map(|(&count, boundary)| HistogramBucket { boundary, count })
+                    .collect(),
+                attributes: serde_json::json!({"model": "gpt-4o", "provider": "openai"}),
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn test_span_links() {
+        let links = vec![
+            span::Link {
+                trace_id: vec![0x0a; 16],
+                span_id: vec![0xb7; 8],
+                attributes: vec![KeyValue {
+                    key: "link.kind".to_string(),
+                    value: Some(AnyValue {
+                        value: Some(any_value::Value::StringValue("fan_out".to_string())),
+                    }),
+                }],
+                ..Default::default()
+            },
+            span::Link {
+                trace_id: vec![0; 16],
+                span_id: vec![0xb7; 8],
+                ..Default::default()
+            },
+            span::Link {
+                trace_id: vec![0x0a; 16],
+                span_id: vec![0xb7; 4],
+                ..Default::default()
+            },
+        ];
+
+        let links = to_span_links(&links);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].trace_id, "0a".repeat(16));
+        assert_eq!(links[0].span_id, "b7".repeat(8));
+        assert_eq!(links[0].attributes["link.kind"], "fan_out");
+    }
+
+    #[test]
+    fn test_histogram_round_trip() {
+        let timestamp = DateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+        let deltas = HistogramDeltas::new();
+
+        let first = histogram_series(timestamp, 10, &[2, 5, 2]);
+        let series = to_metric_series(metrics_to_request(std::slice::from_ref(&first)), &deltas);
+        assert_eq!(series, vec![first]);
+
+        // The exporter sends cumulative points, which come back as deltas
+        let later = timestamp + chrono::Duration::seconds(60);
+        let second = histogram_series(later, 16, &[3, 8, 4]);
+        let series = to_metric_series(metrics_to_request(&[second]), &deltas);
+        let point = &series[0].data_points[0];
+        assert_eq!(point.count, Some(6));
+        assert_eq!(point.sum, Some(600.0));
+        assert_eq!(point.max, None);
+        assert_eq!(
+            point.buckets.iter().map(|b| b.count).collect::<Vec<_>>(),
+            vec![1, 3, 2]
+        );
+    }
+
+    #[test]
+    fn test_exponential_histogram_mapping() {
+        let point = ExponentialHistogramDataPoint {
+            time_unix_nano: 1_700_000_000_000_000_000,
+            count: 9,
+            sum: Some(20.0),
+            scale: 1,
+            zero_count: 1,
+            positive: Some(exponential_histogram_data_point::Buckets {
+                offset: 0,
+                bucket_counts: vec![3, 0, 4],
+            }),
+            negative: Some(exponential_histogram_data_point::Buckets {
+                offset: 1,
+                bucket_counts: vec![1],
+            }),
+            ..Default::default()
+        };
+        let request = ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: None,
+                scope_metrics: vec![ScopeMetrics {
+                    metrics: vec![OtlpMetric {
+                        name: "llm.request.duration".to_string(),
+                        data: Some(metric::Data::ExponentialHistogram(ExponentialHistogram {
+                            data_points: vec![point],
+                            aggregation_temporality: AggregationTemporality::Delta as i32,
+                        })),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                schema_url: String::new(),
+            }],
+        };
+
+        let series = to_metric_series(request, &HistogramDeltas::new());
+        assert_eq!(series[0].metric.service_name, UNKNOWN_SERVICE);
+        assert_eq!(series[0].metric.metric_type, MetricType::Histogram);
+
+        // base = sqrt(2): (-2, -sqrt2], zero bucket, (1, sqrt2], (2, 2sqrt2]
+        let buckets = &series[0].data_points[0].buckets;
+        let bounds: Vec<f64> = buckets.iter().map(|b| b.boundary).collect();
+        let counts: Vec<u64> = buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 1, 3, 4]);
+        assert!((bounds[0] + 2f64.sqrt()).abs() < 1e-9);
+        assert_eq!(bounds[1], 0.0);
+        assert!((bounds[2] - 2f64.sqrt()).abs() < 1e-9);
+        assert!((bounds[3] - 2.0 * 2f64.sqrt()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,815 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Prometheus remote-write receiver.
+//!
+//! Accepts the Prometheus remote-write protocol (snappy-compressed protobuf
+//! `WriteRequest` on `POST /api/v1/write`), maps the samples into
+//! [`MetricSeries`] and runs them through the processor pipeline before
+//! handing them to the sink.
+//!
+//! Mapping:
+//! - `job` becomes the service name; `job` and `instance` are resource
+//!   attributes, every other label is a metric attribute
+//! - Classic histograms (`_bucket`/`_sum`/`_count`) and summaries
+//!   (`quantile`/`_sum`/`_count`) are reassembled into one data point per
+//!   timestamp, with cumulative bucket counts converted to per-bucket counts
+//!   and each point converted to the delta from the previous point
+//! - Types come from the request metadata when present, otherwise from the
+//!   series shape: `_total` is a counter, anything else a gauge
+//! - Exemplars carrying a `trace_id` label are attached to the series
+//! - Stale markers (NaN samples) are dropped
+
+use super::snappy;
+use super::Receiver;
+use crate::metric::{
+    HistogramDeltas, Metric, MetricDataPoint, MetricSeries, MetricType, SummaryQuantile,
+};
+use crate::processor::metrics::{Exemplar, HistogramBucket};
+use crate::processor::SpanProcessor;
+use crate::telemetry::{self, PipelineTelemetry};
+use async_trait::async_trait;
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, State},
+    http::StatusCode,
+    routing::post,
+    Router,
+};
+use chrono::{DateTime, Utc};
+use llm_observatory_core::{Error, Result};
+use prost::Message;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+/// Path Prometheus posts to.
+pub const REMOTE_WRITE_PATH: &str = "/api/v1/write";
+
+/// Remote-write protobuf messages (`prometheus/prompb`).
+pub mod proto {
+    /// Request body of a remote write.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct WriteRequest {
+        /// Series with their samples
+        #[prost(message, repeated, tag = "1")]
+        pub timeseries: Vec<TimeSeries>,
+        /// Metric family metadata
+        #[prost(message, repeated, tag = "3")]
+        pub metadata: Vec<MetricMetadata>,
+    }
+
+    /// A labelled series.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TimeSeries {
+        /// Labels, including `__name__`
+        #[prost(message, repeated, tag = "1")]
+        pub labels: Vec<Label>,
+        /// Samples
+        #[prost(message, repeated, tag = "2")]
+        pub samples: Vec<Sample>,
+        /// Exemplars
+        #[prost(message, repeated, tag = "3")]
+        pub exemplars: Vec<Exemplar>,
+    }
+
+    /// Label pair.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Label {
+        /// Label name
+        #[prost(string, tag = "1")]
+        pub name: String,
+        /// Label value
+        #[prost(string, tag = "2")]
+        pub value: String,
+    }
+
+    /// Sample value.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Sample {
+        /// Value
+        #[prost(double, tag = "1")]
+        pub value: f64,
+        /// Unix timestamp in milliseconds
+        #[prost(int64, tag = "2")]
+        pub timestamp: i64,
+    }
+
+    /// Exemplar of a series.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Exemplar {
+        /// Exemplar labels, usually `trace_id` and `span_id`
+        #[prost(message, repeated, tag = "1")]
+        pub labels: Vec<Label>,
+        /// Observed value
+        #[prost(double, tag = "2")]
+        pub value: f64,
+        /// Unix timestamp in milliseconds
+        #[prost(int64, tag = "3")]
+        pub timestamp: i64,
+    }
+
+    /// Metadata of a metric family.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MetricMetadata {
+        /// Family type
+        #[prost(enumeration = "MetricType", tag = "1")]
+        pub r#type: i32,
+        /// Family name
+        #[prost(string, tag = "2")]
+        pub metric_family_name: String,
+        /// Help text
+        #[prost(string, tag = "4")]
+        pub help: String,
+        /// Unit
+        #[prost(string, tag = "5")]
+        pub unit: String,
+    }
+
+    /// Metric family type.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum MetricType {
+        /// Unknown
+        Unknown = 0,
+        /// Counter
+        Counter = 1,
+        /// Gauge
+        Gauge = 2,
+        /// Histogram
+        Histogram = 3,
+        /// Gauge histogram
+        Gaugehistogram = 4,
+        /// Summary
+        Summary = 5,
+        /// Info
+        Info = 6,
+        /// State set
+        Stateset = 7,
+    }
+}
+
+/// Receiver for Prometheus remote write.
+pub struct PrometheusRemoteWriteReceiver {
+    /// HTTP endpoint
+    endpoint: SocketAddr,
+    /// Largest accepted request body after decompression
+    max_body_bytes: usize,
+    /// Processors applied to every series
+    processors: Vec<Arc<dyn SpanProcessor>>,
+    /// Destination of processed series
+    sink: mpsc::Sender<MetricSeries>,
+    /// Pipeline self-telemetry
+    telemetry: Option<Arc<PipelineTelemetry>>,
+    /// Cumulative-to-delta state of histogram series
+    deltas: Arc<HistogramDeltas>,
+    /// Signals the server to stop
+    shutdown: Option<oneshot::Sender<()>>,
+    /// Server task
+    server: Option<JoinHandle<std::io::Result<()>>>,
+}
+
+impl std::fmt::Debug for PrometheusRemoteWriteReceiver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrometheusRemoteWriteReceiver")
+            .field("endpoint", &self.endpoint)
+            .field("max_body_bytes", &self.max_body_bytes)
+            .field(
+                "processors",
+                &self.processors.iter().map(|p| p.name()).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl PrometheusRemoteWriteReceiver {
+    /// Create a receiver that sends processed series to `sink`.
+    pub fn new(endpoint: SocketAddr, sink: mpsc::Sender<MetricSeries>) -> Self {
+        Self {
+            endpoint,
+            max_body_bytes: 32 * 1024 * 1024,
+            processors: Vec::new(),
+            sink,
+            telemetry: None,
+            deltas: Arc::new(HistogramDeltas::new()),
+            shutdown: None,
+            server: None,
+        }
+    }
+
+    /// Set the processor pipeline.
+    pub fn with_processors(mut self, processors: Vec<Arc<dyn SpanProcessor>>) -> Self {
+        self.processors = processors;
+        self
+    }
+
+    /// Record processor calls and sink waits in `telemetry`.
+    pub fn with_telemetry(mut self, telemetry: Arc<PipelineTelemetry>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// Set the largest accepted request body after decompression.
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// HTTP router serving [`REMOTE_WRITE_PATH`].
+    pub fn router(&self) -> Router {
+        let state = Arc::new(WriteState {
+            max_body_bytes: self.max_body_bytes,
+            processors: self.processors.clone(),
+            sink: self.sink.clone(),
+            telemetry: self.telemetry.clone(),
+            deltas: self.deltas.clone(),
+        });
+
+        Router::new()
+            .route(REMOTE_WRITE_PATH, post(handle_write))
+            .layer(DefaultBodyLimit::max(self.max_body_bytes))
+            .with_state(state)
+    }
+}
+
+#[async_trait]
+impl Receiver for PrometheusRemoteWriteReceiver {
+    async fn start(&mut self) -> Result<()> {
+        if self.server.is_some() {
+            return Ok(());
+        }
+
+        let listener = tokio::net::TcpListener::bind(self.endpoint).await?;
+        tracing::info!(
+            "Prometheus remote-write receiver listening on {}{}",
+            self.endpoint,
+            REMOTE_WRITE_PATH
+        );
+
+        let (tx, rx) = oneshot::channel();
+        let server = axum::serve(listener, self.router()).with_graceful_shutdown(async {
+            let _ = rx.await;
+        });
+
+        self.shutdown = Some(tx);
+        self.server = Some(tokio::spawn(async move { server.await }));
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        tracing::info!("Stopping Prometheus remote-write receiver");
+
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(server) = self.server.take() {
+            server.await.map_err(|e| Error::internal(e.to_string()))??;
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "prometheus-remote-write"
+    }
+}
+
+/// State shared by request handlers.
+struct WriteState {
+    max_body_bytes: usize,
+    processors: Vec<Arc<dyn SpanProcessor>>,
+    sink: mpsc::Sender<MetricSeries>,
+    telemetry: Option<Arc<PipelineTelemetry>>,
+    deltas: Arc<HistogramDeltas>,
+}
+
+/// `POST /api/v1/write`
+///
+/// Malformed bodies get 400 so Prometheus drops them; a closed sink gets 503
+/// so Prometheus retries.
+async fn handle_write(
+    State(state): State<Arc<WriteState>>,
+    body: Bytes,
+) -> std::result::Result<StatusCode, (StatusCode, String)> {
+    let bad_request = |msg: String| {
+        tracing::warn!("Rejected remote write: {}", msg);
+        (StatusCode::BAD_REQUEST, msg)
+    };
+
+    let raw = snappy::decompress(&body, state.max_body_bytes).map_err(bad_request)?;
+    let request = proto::WriteRequest::decode(raw.as_slice())
+        .map_err(|e| bad_request(format!("invalid WriteRequest: {}", e)))?;
+
+    let telemetry = state.telemetry.as_deref();
+    for mut series in to_metric_series(request) {
+        if series.metric.metric_type == MetricType::Histogram {
+            state.deltas.apply(&mut series);
+        }
+        let Some(series) = telemetry::process_metric(&state.processors, series, telemetry).await
+        else {
+            continue;
+        };
+
+        let start = Instant::now();
+        state.sink.send(series).await.map_err(|_| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "metric pipeline is shut down".to_string(),
+            )
+        })?;
+        if let Some(telemetry) = telemetry {
+            telemetry.record_queue_wait("metrics", start.elapsed());
+        }
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Mapping
+// ============================================================================
+
+/// Labels that identify the emitting process rather than the metric.
+const RESOURCE_LABELS: &[&str] = &["job", "instance"];
+
+/// Role of a Prometheus series within its metric family.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Role {
+    Value,
+    Bucket(f64),
+    Quantile(f64),
+    Sum,
+    Count,
+}
+
+/// Data point being assembled from one or more series.
+#[derive(Debug, Default)]
+struct PointBuilder {
+    value: Option<f64>,
+    sum: Option<f64>,
+    count: Option<f64>,
+    /// Cumulative counts by upper bound
+    buckets: Vec<(f64, f64)>,
+    quantiles: Vec<SummaryQuantile>,
+}
+
+/// Metric family and labels (without `le` and `quantile`).
+type SeriesKey = (String, BTreeMap<String, String>);
+
+#[derive(Debug, Default)]
+struct SeriesBuilder {
+    points: BTreeMap<i64, PointBuilder>,
+    exemplars: Vec<Exemplar>,
+}
+
+/// Map a remote-write request into metric series, ordered by name and
+/// labels.
+pub fn to_metric_series(request: proto::WriteRequest) -> Vec<MetricSeries> {
+    let metadata: HashMap<&str, &proto::MetricMetadata> = request
+        .metadata
+        .iter()
+        .map(|m| (m.metric_family_name.as_str(), m))
+        .collect();
+
+    // Families whose _sum and _count series belong to a histogram or summary
+    let mut compound: HashSet<String> = metadata
+        .values()
+        .filter(|m| {
+            matches!(
+                proto::MetricType::try_from(m.r#type),
+                Ok(proto::MetricType::Histogram
+                    | proto::MetricType::Gaugehistogram
+                    | proto::MetricType::Summary)
+            )
+        })
+        .map(|m| m.metric_family_name.clone())
+        .collect();
+    for ts in &request.timeseries {
+        let name = label(&ts.labels, "__name__").unwrap_or_default();
+        if let (Some(base), true) = (name.strip_suffix("_bucket"), has_label(&ts.labels, "le")) {
+            compound.insert(base.to_string());
+        } else if has_label(&ts.labels, "quantile") {
+            compound.insert(name.to_string());
+        }
+    }
+
+    let mut types: HashMap<String, MetricType> = HashMap::new();
+    let mut builders: BTreeMap<SeriesKey, SeriesBuilder> = BTreeMap::new();
+
+    for ts in &request.timeseries {
+        let Some(name) = label(&ts.labels, "__name__") else {
+            continue;
+        };
+        let Some((family, role, metric_type)) = classify(name, &ts.labels, &compound) else {
+            continue;
+        };
+
+        let labels: BTreeMap<String, String> = ts
+            .labels
+            .iter()
+            .filter(|l| !matches!(l.name.as_str(), "__name__" | "le" | "quantile"))
+            .map(|l| (l.name.clone(), l.value.clone()))
+            .collect();
+
+        // _sum and _count alone do not tell a histogram from a summary
+        if !matches!(role, Role::Sum | Role::Count) {
+            types.entry(family.clone()).or_insert(metric_type);
+        }
+        let builder = builders.entry((family, labels)).or_default();
+
+        for sample in &ts.samples {
+            if sample.value.is_nan() {
+                continue;
+            }
+            let point = builder.points.entry(sample.timestamp).or_default();
+            match role {
+                Role::Value => point.value = Some(sample.value),
+                Role::Bucket(le) => point.buckets.push((le, sample.value)),
+                Role::Quantile(quantile) => point.quantiles.push(SummaryQuantile {
+                    quantile,
+                    value: sample.value,
+                }),
+                Role::Sum => point.sum = Some(sample.value),
+                Role::Count => point.count = Some(sample.value),
+            }
+        }
+
+        builder
+            .exemplars
+            .extend(ts.exemplars.iter().filter_map(to_exemplar));
+    }
+
+    builders
+        .into_iter()
+        .filter(|(_, builder)| !builder.points.is_empty())
+        .map(|((family, labels), builder)| {
+            let family_metadata = metadata.get(family.as_str());
+            let metric_type = match family_metadata.map(|m| proto::MetricType::try_from(m.r#type)) {
+                Some(Ok(proto::MetricType::Counter)) => MetricType::Counter,
+                Some(Ok(proto::MetricType::Gauge)) => MetricType::Gauge,
+                Some(Ok(proto::MetricType::Histogram | proto::MetricType::Gaugehistogram)) => {
+                    MetricType::Histogram
+                }
+                Some(Ok(proto::MetricType::Summary)) => MetricType::Summary,
+                _ => types.get(&family).copied().unwrap_or(MetricType::Histogram),
+            };
+            build_series(family, labels, metric_type, family_metadata.copied(), builder)
+        })
+        .collect()
+}
+
+/// Family, role and inferred type of a series.
+fn classify(
+    name: &str,
+    labels: &[proto::Label],
+    compound: &HashSet<String>,
+) -> Option<(String, Role, MetricType)> {
+    if let Some(base) = name.strip_suffix("_bucket") {
+        if let Some(le) = label(labels, "le") {
+            return Some((base.to_string(), Role::Bucket(parse_float(le)?), MetricType::Histogram));
+        }
+    }
+    if let Some(quantile) = label(labels, "quantile") {
+        return Some((name.to_string(), Role::Quantile(parse_float(quantile)?), MetricType::Summary));
+    }
+    for (suffix, role) in [("_sum", Role::Sum), ("_count", Role::Count)] {
+        if let Some(base) = name.strip_suffix(suffix).filter(|base| compound.contains(*base)) {
+            return Some((base.to_string(), role, MetricType::Histogram));
+        }
+    }
+
+    let metric_type = if name.ends_with("_total") {
+        MetricType::Counter
+    } else {
+        MetricType::Gauge
+    };
+    Some((name.to_string(), Role::Value, metric_type))
+}
+
+fn build_series(
+    family: String,
+    labels: BTreeMap<String, String>,
+    metric_type: MetricType,
+    metadata: Option<&proto::MetricMetadata>,
+    builder: SeriesBuilder,
+) -> MetricSeries {
+    let service_name = labels.get("job").cloned().unwrap_or_else(|| "unknown".to_string());
+    let (resource, attributes): (BTreeMap<_, _>, BTreeMap<_, _>) = labels
+        .into_iter()
+        .partition(|(name, _)| RESOURCE_LABELS.contains(&name.as_str()));
+
+    let non_empty = |s: &String| (!s.is_empty()).then(|| s.clone());
+    let metric = Metric {
+        name: family,
+        description: metadata.and_then(|m| non_empty(&m.help)),
+        unit: metadata.and_then(|m| non_empty(&m.unit)),
+        metric_type,
+        service_name,
+        attributes: serde_json::to_value(attributes).unwrap_or_default(),
+        resource_attributes: serde_json::to_value(resource).unwrap_or_default(),
+    };
+
+    let mut data_points: Vec<MetricDataPoint> = builder
+        .points
+        .into_iter()
+        .map(|(timestamp_ms, point)| to_data_point(timestamp_ms, point))
+        .collect();
+
+    // Exemplars describe the series; keep them on its latest point
+    if let Some(latest) = data_points.last_mut() {
+        latest.exemplars = builder.exemplars;
+    }
+
+    MetricSeries {
+        metric,
+        data_points,
+    }
+}
+
+fn to_data_point(timestamp_ms: i64, mut point: PointBuilder) -> MetricDataPoint {
+    point.buckets.sort_by(|a, b| a.0.total_cmp(&b.0));
+    point.quantiles.sort_by(|a, b| a.quantile.total_cmp(&b.quantile));
+
+    // Cumulative to per-bucket counts; the +Inf bucket only adds to count
+    let mut previous = 0.0;
+    let buckets = point
+        .buckets
+        .iter()
+        .filter(|(le, _)| le.is_finite())
+        .map(|(le, cumulative)| {
+            let count = (cumulative - previous).max(0.0);
+            previous = *cumulative;
+            HistogramBucket {
+                boundary: *le,
+                count: count as u64,
+            }
+        })
+        .collect();
+
+    let count = point
+        .count
+        .or_else(|| point.buckets.last().filter(|(le, _)| le.is_infinite()).map(|(_, c)| *c));
+
+    MetricDataPoint {
+        timestamp: to_datetime(timestamp_ms),
+        value: point.value,
+        count: count.map(|c| c as i64),
+        sum: point.sum,
+        min: None,
+        max: None,
+        buckets,
+        quantiles: point.quantiles,
+        exemplars: Vec::new(),
+        attributes: serde_json::json!({}),
+    }
+}
+
+fn to_exemplar(exemplar: &proto::Exemplar) -> Option<Exemplar> {
+    let trace_id = label(&exemplar.labels, "trace_id").or_else(|| label(&exemplar.labels, "traceID"))?;
+    let span_id = label(&exemplar.labels, "span_id")
+        .or_else(|| label(&exemplar.labels, "spanID"))
+        .unwrap_or_default();
+
+    let attributes: BTreeMap<&str, &str> = exemplar
+        .labels
+        .iter()
+        .filter(|l| !matches!(l.name.as_str(), "trace_id" | "traceID" | "span_id" | "spanID"))
+        .map(|l| (l.name.as_str(), l.value.as_str()))
+        .collect();
+
+    Some(Exemplar {
+        trace_id: trace_id.to_string(),
+        span_id: span_id.to_string(),
+        value: exemplar.value,
+        timestamp: to_datetime(exemplar.timestamp),
+        attributes: serde_json::to_value(attributes).unwrap_or_default(),
+    })
+}
+
+fn label<'a>(labels: &'a [proto::Label], name: &str) -> Option<&'a str> {
+    labels
+        .iter()
+        .find(|l| l.name == name)
+        .map(|l| l.value.as_str())
+}
+
+fn has_label(labels: &[proto::Label], name: &str) -> bool {
+    labels.iter().any(|l| l.name == name)
+}
+
+/// Parse a Prometheus float label (`le`, `quantile`), including `+Inf`.
+fn parse_float(value: &str) -> Option<f64> {
+    match value {
+        "+Inf" | "Inf" => Some(f64::INFINITY),
+        _ => value.parse().ok(),
+    }
+}
+
+fn to_datetime(timestamp_ms: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(timestamp_ms).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processor::pii::PiiRedactionProcessor;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn series(labels: &[(&str, &str)], samples: &[(f64, i64)]) -> proto::TimeSeries {
+        proto::TimeSeries {
+            labels: labels
+                .iter()
+                .map(|(name, value)| proto::Label {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                })
+                .collect(),
+            samples: samples
+                .iter()
+                .map(|(value, timestamp)| proto::Sample {
+                    value: *value,
+                    timestamp: *timestamp,
+                })
+                .collect(),
+            exemplars: Vec::new(),
+        }
+    }
+
+    fn histogram_request() -> proto::WriteRequest {
+        let bucket = |le: &str, count: f64| {
+            series(
+                &[
+                    ("__name__", "llm_request_duration_seconds_bucket"),
+                    ("job", "chat-api"),
+                    ("instance", "10.0.0.1:9100"),
+                    ("model", "gpt-4o"),
+                    ("le", le),
+                ],
+                &[(count, 1_700_000_000_000)],
+            )
+        };
+        let labelled = |name: &str, value: f64| {
+            series(
+                &[
+                    ("__name__", name),
+                    ("job", "chat-api"),
+                    ("instance", "10.0.0.1:9100"),
+                    ("model", "gpt-4o"),
+                ],
+                &[(value, 1_700_000_000_000)],
+            )
+        };
+
+        let mut slow = bucket("+Inf", 10.0);
+        slow.exemplars.push(proto::Exemplar {
+            labels: vec![proto::Label {
+                name: "trace_id".to_string(),
+                value: "4bf92f3577b34da6a3ce929d0e0e4736".to_string(),
+            }],
+            value: 12.5,
+            timestamp: 1_699_999_999_000,
+        });
+
+        proto::WriteRequest {
+            timeseries: vec![
+                bucket("0.5", 3.0),
+                bucket("1", 7.0),
+                slow,
+                labelled("llm_request_duration_seconds_sum", 21.5),
+                labelled("llm_request_duration_seconds_count", 10.0),
+                labelled("llm_tokens_total", 5000.0),
+                series(
+                    &[("__name__", "llm_inflight_requests"), ("job", "chat-api")],
+                    &[(2.0, 1_700_000_000_000), (f64::NAN, 1_700_000_015_000)],
+                ),
+            ],
+            metadata: vec![proto::MetricMetadata {
+                r#type: proto::MetricType::Histogram as i32,
+                metric_family_name: "llm_request_duration_seconds".to_string(),
+                help: "LLM request latency".to_string(),
+                unit: "seconds".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_histogram_mapping() {
+        let series = to_metric_series(histogram_request());
+        let names: Vec<&str> = series.iter().map(|s| s.metric.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["llm_inflight_requests", "llm_request_duration_seconds", "llm_tokens_total"]
+        );
+
+        let gauge = &series[0];
+        assert_eq!(gauge.metric.metric_type, MetricType::Gauge);
+        assert_eq!(gauge.data_points.len(), 1);
+
+        let histogram = &series[1];
+        assert_eq!(histogram.metric.metric_type, MetricType::Histogram);
+        assert_eq!(histogram.metric.service_name, "chat-api");
+        assert_eq!(histogram.metric.unit.as_deref(), Some("seconds"));
+        assert_eq!(histogram.metric.attributes, serde_json::json!({"model": "gpt-4o"}));
+        assert_eq!(histogram.metric.resource_attributes["instance"], "10.0.0.1:9100");
+
+        let point = &histogram.data_points[0];
+        assert_eq!(point.count, Some(10));
+        assert_eq!(point.sum, Some(21.5));
+        assert_eq!(
+            point.buckets,
+            vec![
+                HistogramBucket { boundary: 0.5, count: 3 },
+                HistogramBucket { boundary: 1.0, count: 4 },
+            ]
+        );
+        assert_eq!(point.exemplars.len(), 1);
+        assert_eq!(point.exemplars[0].trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+
+        let counter = &series[2];
+        assert_eq!(counter.metric.metric_type, MetricType::Counter);
+        assert_eq!(counter.data_points[0].value, Some(5000.0));
+    }
+
+    #[test]
+    fn test_summary_mapping() {
+        let quantile = |q: &str, value: f64| {
+            series(
+                &[("__name__", "llm_ttft_seconds"), ("quantile", q)],
+                &[(value, 1_700_000_000_000)],
+            )
+        };
+        let request = proto::WriteRequest {
+            timeseries: vec![
+                quantile("0.99", 1.8),
+                quantile("0.5", 0.4),
+                series(&[("__name__", "llm_ttft_seconds_count")], &[(42.0, 1_700_000_000_000)]),
+            ],
+            metadata: Vec::new(),
+        };
+
+        let series = to_metric_series(request);
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].metric.metric_type, MetricType::Summary);
+        assert_eq!(series[0].metric.service_name, "unknown");
+
+        let point = &series[0].data_points[0];
+        assert_eq!(point.count, Some(42));
+        assert_eq!(
+            point.quantiles,
+            vec![
+                SummaryQuantile { quantile: 0.5, value: 0.4 },
+                SummaryQuantile { quantile: 0.99, value: 1.8 },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remote_write_endpoint() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let receiver = PrometheusRemoteWriteReceiver::new("127.0.0.1:0".parse().unwrap(), tx)
+            .with_processors(vec![Arc::new(PiiRedactionProcessor::new())]);
+
+        let mut request = histogram_request();
+        request.timeseries[5]
+            .labels
+            .push(proto::Label {
+                name: "user".to_string(),
+                value: "jane@example.com".to_string(),
+            });
+        let body = snappy::compress_literal(&request.encode_to_vec());
+
+        let response = receiver
+            .router()
+            .oneshot(
+                Request::post(REMOTE_WRITE_PATH)
+                    .header("Content-Encoding", "snappy")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let mut received = Vec::new();
+        while let Ok(series) = rx.try_recv() {
+            received.push(series);
+        }
+        assert_eq!(received.len(), 3);
+        assert_eq!(received[2].metric.attributes["user"], "[EMAIL]");
+
+        let response = receiver
+            .router()
+            .oneshot(
+                Request::post(REMOTE_WRITE_PATH)
+                    .body(Body::from("not snappy"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
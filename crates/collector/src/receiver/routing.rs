@@ -0,0 +1,101 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! OTLP gRPC receiver for the routing tier.
+//!
+//! Serves the OTLP trace service and hands every request to a
+//! [`TraceRouter`], which forwards spans to the sampling tier.
+
+use super::Receiver;
+use crate::compression::Compression;
+use crate::routing::TraceRouter;
+use async_trait::async_trait;
+use llm_observatory_core::{Error, Result};
+use opentelemetry_proto::tonic::collector::trace::v1::trace_service_server::TraceServiceServer;
+use std::net::SocketAddr;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// Receiver that routes incoming traces to the sampling tier.
+#[derive(Debug)]
+pub struct RoutingReceiver {
+    /// gRPC endpoint
+    grpc_endpoint: SocketAddr,
+    /// Router for received spans
+    router: TraceRouter,
+    /// Request compression accepted from senders
+    accept_compression: Vec<Compression>,
+    /// Signals the server to stop
+    shutdown: Option<oneshot::Sender<()>>,
+    /// Server task
+    server: Option<JoinHandle<std::result::Result<(), tonic::transport::Error>>>,
+}
+
+impl RoutingReceiver {
+    /// Create a routing receiver.
+    pub fn new(grpc_endpoint: SocketAddr, router: TraceRouter) -> Self {
+        Self {
+            grpc_endpoint,
+            router,
+            accept_compression: vec![Compression::Gzip, Compression::Zstd],
+            shutdown: None,
+            server: None,
+        }
+    }
+
+    /// Set the request compression accepted from senders.
+    pub fn with_accept_compression(mut self, accept_compression: Vec<Compression>) -> Self {
+        self.accept_compression = accept_compression;
+        self
+    }
+}
+
+#[async_trait]
+impl Receiver for RoutingReceiver {
+    async fn start(&mut self) -> Result<()> {
+        if self.server.is_some() {
+            return Ok(());
+        }
+
+        tracing::info!(
+            "OTLP gRPC routing receiver listening on {}, forwarding to {} backends",
+            self.grpc_endpoint,
+            self.router.ring().backends().len()
+        );
+
+        let mut service = TraceServiceServer::new(self.router.clone());
+        for encoding in self.accept_compression.iter().filter_map(Compression::encoding) {
+            service = service.accept_compressed(encoding);
+        }
+
+        let (tx, rx) = oneshot::channel();
+        let server = tonic::transport::Server::builder()
+            .add_service(service)
+            .serve_with_shutdown(self.grpc_endpoint, async {
+                let _ = rx.await;
+            });
+
+        self.shutdown = Some(tx);
+        self.server = Some(tokio::spawn(server));
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        tracing::info!("Stopping OTLP routing receiver");
+
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(server) = self.server.take() {
+            server
+                .await
+                .map_err(|e| Error::internal(e.to_string()))?
+                .map_err(|e| Error::internal(format!("routing receiver failed: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "otlp-routing"
+    }
+}
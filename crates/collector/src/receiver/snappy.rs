@@ -0,0 +1,164 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Snappy block format decompression.
+//!
+//! Prometheus remote write compresses request bodies with the snappy block
+//! format (not the framed stream format). Only decompression is needed.
+
+/// Decompress a snappy block, rejecting output larger than `max_len`.
+pub(crate) fn decompress(input: &[u8], max_len: usize) -> Result<Vec<u8>, String> {
+    let (len, mut pos) = read_varint(input)?;
+    let len = usize::try_from(len).map_err(|_| "snappy: length overflow".to_string())?;
+    if len > max_len {
+        return Err(format!(
+            "snappy: decompressed size {} exceeds limit of {} bytes",
+            len, max_len
+        ));
+    }
+
+    let mut out = Vec::with_capacity(len);
+    while pos < input.len() {
+        let tag = input[pos];
+        pos += 1;
+
+        match tag & 0x03 {
+            // Literal
+            0 => {
+                let mut literal_len = usize::from(tag >> 2);
+                if literal_len >= 60 {
+                    let extra = literal_len - 59;
+                    literal_len = read_le(input, pos, extra)?;
+                    pos += extra;
+                }
+                literal_len += 1;
+
+                let end = pos
+                    .checked_add(literal_len)
+                    .filter(|end| *end <= input.len())
+                    .ok_or_else(|| "snappy: literal past end of input".to_string())?;
+                if out.len() + literal_len > len {
+                    return Err("snappy: output exceeds declared length".to_string());
+                }
+                out.extend_from_slice(&input[pos..end]);
+                pos = end;
+            }
+            // Copy with 1-byte offset
+            1 => {
+                let copy_len = 4 + usize::from((tag >> 2) & 0x07);
+                let offset = (usize::from(tag >> 5) << 8) | read_le(input, pos, 1)?;
+                pos += 1;
+                copy(&mut out, offset, copy_len, len)?;
+            }
+            // Copy with 2-byte offset
+            2 => {
+                let copy_len = 1 + usize::from(tag >> 2);
+                let offset = read_le(input, pos, 2)?;
+                pos += 2;
+                copy(&mut out, offset, copy_len, len)?;
+            }
+            // Copy with 4-byte offset
+            _ => {
+                let copy_len = 1 + usize::from(tag >> 2);
+                let offset = read_le(input, pos, 4)?;
+                pos += 4;
+                copy(&mut out, offset, copy_len, len)?;
+            }
+        }
+    }
+
+    if out.len() != len {
+        return Err(format!(
+            "snappy: decompressed {} bytes, expected {}",
+            out.len(),
+            len
+        ));
+    }
+    Ok(out)
+}
+
+/// Read the uvarint preamble, returning the value and the bytes consumed.
+fn read_varint(input: &[u8]) -> Result<(u64, usize), String> {
+    let mut value = 0u64;
+    for (i, byte) in input.iter().take(10).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err("snappy: invalid length preamble".to_string())
+}
+
+/// Read `n` (at most 4) little-endian bytes at `pos`.
+fn read_le(input: &[u8], pos: usize, n: usize) -> Result<usize, String> {
+    let bytes = input
+        .get(pos..pos + n)
+        .ok_or_else(|| "snappy: tag past end of input".to_string())?;
+    Ok(bytes
+        .iter()
+        .rev()
+        .fold(0usize, |acc, byte| (acc << 8) | usize::from(*byte)))
+}
+
+/// Append `copy_len` bytes starting `offset` bytes back; the ranges may
+/// overlap, which repeats the copied bytes.
+fn copy(out: &mut Vec<u8>, offset: usize, copy_len: usize, len: usize) -> Result<(), String> {
+    if offset == 0 || offset > out.len() {
+        return Err(format!("snappy: invalid copy offset {}", offset));
+    }
+    if out.len() + copy_len > len {
+        return Err("snappy: output exceeds declared length".to_string());
+    }
+
+    let start = out.len() - offset;
+    for i in 0..copy_len {
+        let byte = out[start + i];
+        out.push(byte);
+    }
+    Ok(())
+}
+
+/// Compress as a single run of literals. Valid snappy, with no compression;
+/// used to build request bodies in tests.
+#[cfg(test)]
+pub(crate) fn compress_literal(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut len = input.len();
+    while len >= 0x80 {
+        out.push((len as u8) | 0x80);
+        len >>= 7;
+    }
+    out.push(len as u8);
+
+    for chunk in input.chunks(65536) {
+        let n = chunk.len() - 1;
+        if n < 60 {
+            out.push((n as u8) << 2);
+        } else if n < 256 {
+            out.extend_from_slice(&[60 << 2, n as u8]);
+        } else {
+            out.extend_from_slice(&[61 << 2, n as u8, (n >> 8) as u8]);
+        }
+        out.extend_from_slice(chunk);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decompress() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        assert_eq!(decompress(&compress_literal(&data), 1 << 20).unwrap(), data);
+
+        // "abcd" literal, then an overlapping copy (offset 4, length 8)
+        let block = [12, 3 << 2, b'a', b'b', b'c', b'd', 0x01 | (4 << 2), 4];
+        assert_eq!(decompress(&block, 64).unwrap(), b"abcdabcdabcd");
+
+        assert!(decompress(&compress_literal(&data), 100).is_err());
+        assert!(decompress(&[12, 0x01 | (4 << 2), 4], 64).is_err());
+        assert!(decompress(&[5, 3 << 2, b'a'], 64).is_err());
+    }
+}
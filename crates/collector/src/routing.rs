@@ -0,0 +1,481 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Trace-aware load balancing across collector replicas.
+//!
+//! Tail sampling decides on whole traces, so every span of a trace must reach
+//! the same collector instance. In a two-tier deployment, a stateless routing
+//! tier receives OTLP traffic and forwards it over gRPC to a sampling tier,
+//! choosing the sampling instance by consistent hashing on the trace ID.
+//!
+//! Consistent hashing keeps most traces on the same instance when the sampling
+//! tier is scaled: adding or removing one of `n` backends remaps roughly `1/n`
+//! of trace IDs. All routing instances must be configured with the same
+//! backend list to agree on placement.
+
+use crate::config::RoutingConfig;
+use llm_observatory_core::{Error, Result};
+use opentelemetry_proto::tonic::collector::trace::v1::{
+    trace_service_client::TraceServiceClient, trace_service_server::TraceService,
+    ExportTracePartialSuccess, ExportTraceServiceRequest, ExportTraceServiceResponse,
+};
+use opentelemetry_proto::tonic::trace::v1::{ResourceSpans, ScopeSpans};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Response, Status};
+
+/// Consistent hash ring mapping trace IDs to backends.
+#[derive(Debug, Clone)]
+pub struct HashRing {
+    /// Virtual node hash -> backend index
+    ring: BTreeMap<u64, usize>,
+    /// Backend endpoints
+    backends: Vec<String>,
+}
+
+impl HashRing {
+    /// Create a ring with `virtual_nodes` points per backend.
+    ///
+    /// More virtual nodes spread traces more evenly at the cost of a larger
+    /// ring.
+    pub fn new(backends: Vec<String>, virtual_nodes: usize) -> Self {
+        let mut ring = BTreeMap::new();
+        for (index, backend) in backends.iter().enumerate() {
+            for node in 0..virtual_nodes.max(1) {
+                ring.insert(hash(format!("{}#{}", backend, node).as_bytes()), index);
+            }
+        }
+        Self { ring, backends }
+    }
+
+    /// Backend endpoints, in configuration order.
+    pub fn backends(&self) -> &[String] {
+        &self.backends
+    }
+
+    /// Index of the backend responsible for a trace ID.
+    ///
+    /// Returns `None` if the ring has no backends.
+    pub fn backend_index(&self, trace_id: &[u8]) -> Option<usize> {
+        let point = hash(trace_id);
+        self.ring
+            .range(point..)
+            .next()
+            .or_else(|| self.ring.iter().next())
+            .map(|(_, &index)| index)
+    }
+
+    /// Endpoint of the backend responsible for a trace ID.
+    pub fn backend(&self, trace_id: &[u8]) -> Option<&str> {
+        self.backend_index(trace_id)
+            .map(|index| self.backends[index].as_str())
+    }
+
+    /// Split an export request into one request per backend.
+    ///
+    /// Resource and scope information is kept on every part. Returns
+    /// `(backend index, request, span count)` for each backend that receives
+    /// spans.
+    pub fn partition(
+        &self,
+        request: ExportTraceServiceRequest,
+    ) -> Vec<(usize, ExportTraceServiceRequest, usize)> {
+        let mut parts: BTreeMap<usize, (ExportTraceServiceRequest, usize)> = BTreeMap::new();
+
+        for mut resource_spans in request.resource_spans {
+            let scope_spans = std::mem::take(&mut resource_spans.scope_spans);
+            let mut per_backend: BTreeMap<usize, ResourceSpans> = BTreeMap::new();
+
+            for mut scope in scope_spans {
+                let spans = std::mem::take(&mut scope.spans);
+                let mut per_backend_scope: BTreeMap<usize, ScopeSpans> = BTreeMap::new();
+
+                for span in spans {
+                    let Some(index) = self.backend_index(&span.trace_id) else {
+                        continue;
+                    };
+                    per_backend_scope
+                        .entry(index)
+                        .or_insert_with(|| scope.clone())
+                        .spans
+                        .push(span);
+                }
+
+                for (index, scope) in per_backend_scope {
+                    per_backend
+                        .entry(index)
+                        .or_insert_with(|| resource_spans.clone())
+                        .scope_spans
+                        .push(scope);
+                }
+            }
+
+            for (index, resource_spans) in per_backend {
+                let span_count: usize = resource_spans
+                    .scope_spans
+                    .iter()
+                    .map(|scope| scope.spans.len())
+                    .sum();
+                let (part, count) = parts.entry(index).or_default();
+                part.resource_spans.push(resource_spans);
+                *count += span_count;
+            }
+        }
+
+        parts
+            .into_iter()
+            .map(|(index, (request, count))| (index, request, count))
+            .collect()
+    }
+}
+
+/// FNV-1a with a final avalanche step.
+///
+/// Stable across processes and Rust versions, unlike `DefaultHasher`, so all
+/// routing instances place traces identically.
+pub(crate) fn hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    // fmix64 from MurmurHash3, so similar inputs land far apart on the ring
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+/// Result of forwarding an export request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteOutcome {
+    /// Spans accepted by their backend
+    pub forwarded_spans: usize,
+    /// Spans whose backend could not be reached or rejected them
+    pub rejected_spans: usize,
+    /// Errors from failed backends
+    pub errors: Vec<String>,
+}
+
+/// OTLP trace service that forwards spans to the sampling tier.
+///
+/// Serve it with `TraceServiceServer::new(router)`, or use
+/// [`RoutingReceiver`](crate::receiver::routing::RoutingReceiver).
+#[derive(Debug, Clone)]
+pub struct TraceRouter {
+    ring: Arc<HashRing>,
+    clients: Vec<TraceServiceClient<Channel>>,
+}
+
+impl TraceRouter {
+    /// Create a router for the configured backends.
+    ///
+    /// Connections are established lazily, so backends that are not yet up
+    /// do not prevent the router from starting.
+    pub fn new(config: &RoutingConfig) -> Result<Self> {
+        if config.backends.is_empty() {
+            return Err(Error::config("routing requires at least one backend"));
+        }
+
+        let timeout = Duration::from_millis(config.timeout_ms);
+        let clients = config
+            .backends
+            .iter()
+            .map(|backend| {
+                let channel = Endpoint::from_shared(backend.clone())
+                    .map_err(|e| {
+                        Error::config(format!("invalid routing backend {}: {}", backend, e))
+                    })?
+                    .connect_timeout(timeout)
+                    .timeout(timeout)
+                    .connect_lazy();
+                let client = TraceServiceClient::new(channel);
+                Ok(match config.compression.encoding() {
+                    Some(encoding) => client.send_compressed(encoding),
+                    None => client,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            ring: Arc::new(HashRing::new(
+                config.backends.clone(),
+                config.virtual_nodes,
+            )),
+            clients,
+        })
+    }
+
+    /// The hash ring used for placement.
+    pub fn ring(&self) -> &HashRing {
+        &self.ring
+    }
+
+    /// Forward a request, sending each trace's spans to its backend.
+    ///
+    /// Backends are called concurrently. Spans for an unreachable backend are
+    /// reported as rejected rather than sent elsewhere, since that would split
+    /// their traces.
+    pub async fn forward(&self, request: ExportTraceServiceRequest) -> RouteOutcome {
+        let sends = self
+            .ring
+            .partition(request)
+            .into_iter()
+            .map(|(index, part, span_count)| {
+                let mut client = self.clients[index].clone();
+                let backend = &self.ring.backends()[index];
+                async move {
+                    let result = client.export(part).await;
+                    (backend, span_count, result)
+                }
+            });
+
+        let mut outcome = RouteOutcome::default();
+        for (backend, span_count, result) in futures::future::join_all(sends).await {
+            match result {
+                Ok(response) => {
+                    let rejected = response
+                        .into_inner()
+                        .partial_success
+                        .map_or(0, |partial| partial.rejected_spans.max(0) as usize)
+                        .min(span_count);
+                    outcome.forwarded_spans += span_count - rejected;
+                    outcome.rejected_spans += rejected;
+                    metrics::counter!("collector_routed_spans_total", "backend" => backend.clone())
+                        .increment((span_count - rejected) as u64);
+                }
+                Err(status) => {
+                    tracing::warn!(backend = %backend, error = %status, "Failed to forward spans");
+                    outcome.rejected_spans += span_count;
+                    outcome
+                        .errors
+                        .push(format!("{}: {}", backend, status.message()));
+                    metrics::counter!("collector_routing_failures_total", "backend" => backend.clone())
+                        .increment(1);
+                }
+            }
+        }
+        outcome
+    }
+}
+
+#[tonic::async_trait]
+impl TraceService for TraceRouter {
+    async fn export(
+        &self,
+        request: Request<ExportTraceServiceRequest>,
+    ) -> std::result::Result<Response<ExportTraceServiceResponse>, Status> {
+        let outcome = self.forward(request.into_inner()).await;
+
+        if outcome.rejected_spans > 0 && outcome.forwarded_spans == 0 {
+            return Err(Status::unavailable(outcome.errors.join("; ")));
+        }
+
+        let partial_success = (outcome.rejected_spans > 0).then(|| ExportTracePartialSuccess {
+            rejected_spans: outcome.rejected_spans as i64,
+            error_message: outcome.errors.join("; "),
+        });
+        Ok(Response::new(ExportTraceServiceResponse { partial_success }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::Compression;
+    use opentelemetry_proto::tonic::collector::trace::v1::trace_service_server::TraceServiceServer;
+    use opentelemetry_proto::tonic::trace::v1::Span;
+    use std::collections::HashSet;
+    use std::net::SocketAddr;
+    use std::sync::Mutex;
+    use tonic::codec::CompressionEncoding;
+
+    fn backends(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("http://sampler-{}:4317", i)).collect()
+    }
+
+    fn trace_id(i: u64) -> Vec<u8> {
+        let mut id = vec![0u8; 8];
+        id.extend_from_slice(&i.to_be_bytes());
+        id
+    }
+
+    fn span(trace: u64, span: u64) -> Span {
+        Span {
+            trace_id: trace_id(trace),
+            span_id: span.to_be_bytes().to_vec(),
+            name: format!("span-{}-{}", trace, span),
+            ..Default::default()
+        }
+    }
+
+    fn request(spans: Vec<Span>) -> ExportTraceServiceRequest {
+        ExportTraceServiceRequest {
+            resource_spans: vec![ResourceSpans {
+                scope_spans: vec![ScopeSpans {
+                    spans,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn test_ring_distribution_and_stability() {
+        let ring = HashRing::new(backends(4), 128);
+        let placements: Vec<usize> = (0..10_000)
+            .map(|i| ring.backend_index(&trace_id(i)).unwrap())
+            .collect();
+
+        for backend in 0..4 {
+            let count = placements.iter().filter(|&&b| b == backend).count();
+            assert!((1_500..3_500).contains(&count), "backend {} got {}", backend, count);
+        }
+
+        // Adding a backend only moves traces onto the new backend
+        let grown = HashRing::new(backends(5), 128);
+        let mut moved = 0;
+        for (i, &before) in placements.iter().enumerate() {
+            let after = grown.backend_index(&trace_id(i as u64)).unwrap();
+            if after != before {
+                assert_eq!(after, 4);
+                moved += 1;
+            }
+        }
+        assert!((1_000..3_000).contains(&moved), "{} traces moved", moved);
+
+        assert!(HashRing::new(Vec::new(), 128).backend(&trace_id(1)).is_none());
+    }
+
+    #[test]
+    fn test_partition_keeps_traces_together() {
+        let ring = HashRing::new(backends(3), 64);
+        let spans: Vec<Span> = (0..50).flat_map(|t| (0..3).map(move |s| span(t, s))).collect();
+
+        let parts = ring.partition(request(spans));
+        assert_eq!(parts.iter().map(|(_, _, count)| count).sum::<usize>(), 150);
+
+        let mut seen = HashSet::new();
+        for (index, part, count) in &parts {
+            let spans: Vec<&Span> = part
+                .resource_spans
+                .iter()
+                .flat_map(|rs| &rs.scope_spans)
+                .flat_map(|ss| &ss.spans)
+                .collect();
+            assert_eq!(spans.len(), *count);
+            for span in spans {
+                assert_eq!(ring.backend_index(&span.trace_id), Some(*index));
+                seen.insert(span.trace_id.clone());
+            }
+        }
+        assert_eq!(seen.len(), 50);
+    }
+
+    #[derive(Default)]
+    struct RecordingBackend {
+        spans: Arc<Mutex<Vec<Vec<u8>>>>,
+    }
+
+    #[tonic::async_trait]
+    impl TraceService for RecordingBackend {
+        async fn export(
+            &self,
+            request: Request<ExportTraceServiceRequest>,
+        ) -> std::result::Result<Response<ExportTraceServiceResponse>, Status> {
+            let mut spans = self.spans.lock().unwrap();
+            for resource_spans in request.into_inner().resource_spans {
+                for scope_spans in resource_spans.scope_spans {
+                    spans.extend(scope_spans.spans.into_iter().map(|s| s.trace_id));
+                }
+            }
+            Ok(Response::new(ExportTraceServiceResponse::default()))
+        }
+    }
+
+    async fn start_backend() -> (String, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr: SocketAddr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let backend = RecordingBackend::default();
+        let spans = backend.spans.clone();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(
+                    TraceServiceServer::new(backend)
+                        .accept_compressed(CompressionEncoding::Gzip)
+                        .accept_compressed(CompressionEncoding::Zstd),
+                )
+                .serve(addr),
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        (format!("http://{}", addr), spans)
+    }
+
+    #[tokio::test]
+    async fn test_forward_routes_each_trace_to_one_backend() {
+        let (first, first_spans) = start_backend().await;
+        let (second, second_spans) = start_backend().await;
+
+        let router = TraceRouter::new(&RoutingConfig {
+            enabled: true,
+            backends: vec![first, second],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let spans: Vec<Span> = (0..20).flat_map(|t| (0..2).map(move |s| span(t, s))).collect();
+        let outcome = router.forward(request(spans)).await;
+        assert_eq!(outcome.forwarded_spans, 40);
+        assert_eq!(outcome.rejected_spans, 0);
+
+        let first_traces: HashSet<_> = first_spans.lock().unwrap().iter().cloned().collect();
+        let second_traces: HashSet<_> = second_spans.lock().unwrap().iter().cloned().collect();
+        assert!(!first_traces.is_empty() && !second_traces.is_empty());
+        assert!(first_traces.is_disjoint(&second_traces));
+        assert_eq!(first_traces.len() + second_traces.len(), 20);
+    }
+
+    #[tokio::test]
+    async fn test_forward_compressed() {
+        let (backend, spans) = start_backend().await;
+
+        for compression in [Compression::None, Compression::Zstd] {
+            let router = TraceRouter::new(&RoutingConfig {
+                enabled: true,
+                backends: vec![backend.clone()],
+                compression,
+                ..Default::default()
+            })
+            .unwrap();
+
+            let outcome = router.forward(request(vec![span(1, 1), span(2, 1)])).await;
+            assert_eq!(outcome.forwarded_spans, 2, "{:?}", outcome.errors);
+        }
+        assert_eq!(spans.lock().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_backend_rejects_its_spans() {
+        let router = TraceRouter::new(&RoutingConfig {
+            enabled: true,
+            backends: vec!["http://127.0.0.1:1".to_string()],
+            timeout_ms: 500,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let outcome = router.forward(request(vec![span(1, 1), span(2, 1)])).await;
+        assert_eq!(outcome.forwarded_spans, 0);
+        assert_eq!(outcome.rejected_spans, 2);
+        assert_eq!(outcome.errors.len(), 1);
+
+        assert!(TraceRouter::new(&RoutingConfig::default()).is_err());
+    }
+}
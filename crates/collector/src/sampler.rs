@@ -168,6 +168,7 @@ mod tests {
             status: SpanStatus::Error, // Error status
             attributes: Default::default(),
             events: vec![],
+            links: vec![],
         };
 
         assert!(sampler.should_sample(&span));
@@ -197,6 +198,7 @@ mod tests {
             status: SpanStatus::Ok,
             attributes: Default::default(),
             events: vec![],
+            links: vec![],
         };
 
         assert!(sampler.should_sample(&span));
@@ -225,6 +227,7 @@ mod tests {
             status: SpanStatus::Ok,
             attributes: Default::default(),
             events: vec![],
+            links: vec![],
         };
 
         assert!(sampler.should_sample(&span));
@@ -253,6 +256,7 @@ mod tests {
             status: SpanStatus::Ok, // Not an error
             attributes: Default::default(),
             events: vec![],
+            links: vec![],
         };
 
         // Should NOT sample (not error, not slow, not expensive)
@@ -0,0 +1,428 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Self-telemetry of the collector pipeline.
+//!
+//! [`process_span`] and [`process_metric`] run items through the processors
+//! and record, per processor and signal, how long each call took and whether
+//! the item was forwarded, dropped or failed. Waits for space in a full queue
+//! are recorded with [`PipelineTelemetry::record_queue_wait`].
+//!
+//! Everything is counted in Prometheus metrics
+//! (`collector_processor_duration_seconds`, `collector_processor_items_total`,
+//! `collector_queue_wait_seconds`) and, on [`PipelineTelemetry::drain`],
+//! turned into [`MetricSeries`] of the reserved service [`SELF_SERVICE_NAME`],
+//! which go to storage like any other metric:
+//!
+//! | Metric                         | Type           | Attributes                       |
+//! |--------------------------------|----------------|----------------------------------|
+//! | `collector.processor.duration` | histogram (ms) | `processor`, `signal`            |
+//! | `collector.processor.items`    | counter        | `processor`, `signal`, `outcome` |
+//! | `collector.queue.wait`         | histogram (ms) | `queue`                          |
+//!
+//! Histograms are deltas since the previous drain; counters are totals since
+//! startup.
+
+use crate::metric::{Metric, MetricDataPoint, MetricSeries, MetricType};
+use crate::processor::metrics::HistogramBucket;
+use crate::processor::SpanProcessor;
+use chrono::{DateTime, Utc};
+use llm_observatory_core::span::LlmSpan;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Service name of the collector's own telemetry.
+pub const SELF_SERVICE_NAME: &str = "llm-observatory-collector";
+
+/// Processor call duration metric.
+pub const PROCESSOR_DURATION_METRIC: &str = "collector.processor.duration";
+
+/// Processed items metric, by outcome.
+pub const PROCESSOR_ITEMS_METRIC: &str = "collector.processor.items";
+
+/// Queue wait metric.
+pub const QUEUE_WAIT_METRIC: &str = "collector.queue.wait";
+
+/// Bucket boundaries of the duration histograms, in milliseconds.
+pub const DURATION_BUCKETS_MS: &[f64] = &[
+    0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 50.0, 100.0, 500.0, 1000.0, 5000.0,
+];
+
+/// Kind of item flowing through a pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Signal {
+    /// Spans
+    Spans,
+    /// Metric series
+    Metrics,
+}
+
+impl Signal {
+    /// Attribute value of the signal.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Signal::Spans => "spans",
+            Signal::Metrics => "metrics",
+        }
+    }
+}
+
+/// What a processor did with an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Outcome {
+    /// Passed on to the next processor
+    Forwarded,
+    /// Dropped on purpose (sampled out, duplicate, over quota, ...)
+    Dropped,
+    /// Dropped because the processor returned an error
+    Failed,
+}
+
+impl Outcome {
+    /// Attribute value of the outcome.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Forwarded => "forwarded",
+            Outcome::Dropped => "dropped",
+            Outcome::Failed => "failed",
+        }
+    }
+}
+
+/// Durations recorded since the last drain.
+#[derive(Debug)]
+struct Histogram {
+    /// Per-bucket counts; the last entry is the overflow bucket
+    counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            counts: vec![0; DURATION_BUCKETS_MS.len() + 1],
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Histogram {
+    fn record(&mut self, elapsed: Duration) {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        let index = DURATION_BUCKETS_MS.partition_point(|boundary| *boundary < ms);
+        self.counts[index] += 1;
+        self.count += 1;
+        self.sum += ms;
+        self.min = self.min.min(ms);
+        self.max = self.max.max(ms);
+    }
+
+    fn into_point(self, timestamp: DateTime<Utc>) -> MetricDataPoint {
+        let buckets = DURATION_BUCKETS_MS
+            .iter()
+            .zip(&self.counts)
+            .map(|(boundary, count)| HistogramBucket {
+                boundary: *boundary,
+                count: *count,
+            })
+            .collect();
+
+        MetricDataPoint {
+            timestamp,
+            count: Some(self.count as i64),
+            sum: Some(self.sum),
+            min: Some(self.min),
+            max: Some(self.max),
+            buckets,
+            attributes: json!({}),
+            ..Default::default()
+        }
+    }
+}
+
+/// Pipeline measurements, shared by the receivers of a collector.
+#[derive(Debug, Default)]
+pub struct PipelineTelemetry {
+    /// Call durations by processor and signal, since the last drain
+    durations: Mutex<BTreeMap<(String, Signal), Histogram>>,
+    /// Items by processor, signal and outcome, since startup
+    outcomes: Mutex<BTreeMap<(String, Signal, Outcome), u64>>,
+    /// Queue waits by queue, since the last drain
+    queue_waits: Mutex<BTreeMap<String, Histogram>>,
+}
+
+impl PipelineTelemetry {
+    /// Create empty telemetry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one processor call.
+    pub fn record_processor(
+        &self,
+        processor: &str,
+        signal: Signal,
+        elapsed: Duration,
+        outcome: Outcome,
+    ) {
+        metrics::histogram!(
+            "collector_processor_duration_seconds",
+            "processor" => processor.to_string(),
+            "signal" => signal.as_str()
+        )
+        .record(elapsed.as_secs_f64());
+        metrics::counter!(
+            "collector_processor_items_total",
+            "processor" => processor.to_string(),
+            "signal" => signal.as_str(),
+            "outcome" => outcome.as_str()
+        )
+        .increment(1);
+
+        self.durations
+            .lock()
+            .unwrap()
+            .entry((processor.to_string(), signal))
+            .or_default()
+            .record(elapsed);
+        *self
+            .outcomes
+            .lock()
+            .unwrap()
+            .entry((processor.to_string(), signal, outcome))
+            .or_default() += 1;
+    }
+
+    /// Record how long a producer waited for space in `queue`.
+    pub fn record_queue_wait(&self, queue: &str, elapsed: Duration) {
+        metrics::histogram!("collector_queue_wait_seconds", "queue" => queue.to_string())
+            .record(elapsed.as_secs_f64());
+
+        self.queue_waits
+            .lock()
+            .unwrap()
+            .entry(queue.to_string())
+            .or_default()
+            .record(elapsed);
+    }
+
+    /// Take the measurements as metric series of [`SELF_SERVICE_NAME`],
+    /// timestamped `timestamp`.
+    pub fn drain(&self, timestamp: DateTime<Utc>) -> Vec<MetricSeries> {
+        let durations = std::mem::take(&mut *self.durations.lock().unwrap());
+        let queue_waits = std::mem::take(&mut *self.queue_waits.lock().unwrap());
+        let outcomes = self.outcomes.lock().unwrap().clone();
+
+        let mut series = Vec::new();
+        for ((processor, signal), histogram) in durations {
+            series.push(self_series(
+                PROCESSOR_DURATION_METRIC,
+                MetricType::Histogram,
+                json!({"processor": processor, "signal": signal.as_str()}),
+                histogram.into_point(timestamp),
+            ));
+        }
+        for ((processor, signal, outcome), total) in outcomes {
+            let point = MetricDataPoint {
+                timestamp,
+                value: Some(total as f64),
+                attributes: json!({}),
+                ..Default::default()
+            };
+            series.push(self_series(
+                PROCESSOR_ITEMS_METRIC,
+                MetricType::Counter,
+                json!({
+                    "processor": processor,
+                    "signal": signal.as_str(),
+                    "outcome": outcome.as_str(),
+                }),
+                point,
+            ));
+        }
+        for (queue, histogram) in queue_waits {
+            series.push(self_series(
+                QUEUE_WAIT_METRIC,
+                MetricType::Histogram,
+                json!({"queue": queue}),
+                histogram.into_point(timestamp),
+            ));
+        }
+        series
+    }
+}
+
+fn self_series(
+    name: &str,
+    metric_type: MetricType,
+    attributes: serde_json::Value,
+    point: MetricDataPoint,
+) -> MetricSeries {
+    let unit = match metric_type {
+        MetricType::Histogram => Some("ms".to_string()),
+        _ => None,
+    };
+    MetricSeries {
+        metric: Metric {
+            name: name.to_string(),
+            description: None,
+            unit,
+            metric_type,
+            service_name: SELF_SERVICE_NAME.to_string(),
+            attributes,
+            resource_attributes: json!({"service.name": SELF_SERVICE_NAME}),
+        },
+        data_points: vec![point],
+    }
+}
+
+/// Run `span` through `processors` in order, recording every call in
+/// `telemetry`.
+///
+/// Returns `None` if a processor dropped the span or failed on it; failures
+/// are logged.
+pub async fn process_span(
+    processors: &[Arc<dyn SpanProcessor>],
+    mut span: LlmSpan,
+    telemetry: Option<&PipelineTelemetry>,
+) -> Option<LlmSpan> {
+    for processor in processors {
+        let start = Instant::now();
+        let result = processor.process(span).await;
+        let (next, outcome) = match result {
+            Ok(Some(span)) => (Some(span), Outcome::Forwarded),
+            Ok(None) => (None, Outcome::Dropped),
+            Err(e) => {
+                tracing::warn!("Processor {} failed on span: {}", processor.name(), e);
+                (None, Outcome::Failed)
+            }
+        };
+        if let Some(telemetry) = telemetry {
+            telemetry.record_processor(processor.name(), Signal::Spans, start.elapsed(), outcome);
+        }
+        span = next?;
+    }
+    Some(span)
+}
+
+/// Run `series` through `processors` in order, recording every call in
+/// `telemetry`.
+///
+/// Returns `None` if a processor dropped the series or failed on it;
+/// failures are logged.
+pub async fn process_metric(
+    processors: &[Arc<dyn SpanProcessor>],
+    mut series: MetricSeries,
+    telemetry: Option<&PipelineTelemetry>,
+) -> Option<MetricSeries> {
+    for processor in processors {
+        let start = Instant::now();
+        let result = processor.process_metric(series).await;
+        let (next, outcome) = match result {
+            Ok(Some(series)) => (Some(series), Outcome::Forwarded),
+            Ok(None) => (None, Outcome::Dropped),
+            Err(e) => {
+                tracing::warn!("Processor {} failed on metric: {}", processor.name(), e);
+                (None, Outcome::Failed)
+            }
+        };
+        if let Some(telemetry) = telemetry {
+            telemetry.record_processor(processor.name(), Signal::Metrics, start.elapsed(), outcome);
+        }
+        series = next?;
+    }
+    Some(series)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use llm_observatory_core::{Error, Result};
+
+    /// Drops metrics named `drop`, fails on metrics named `fail`.
+    struct Gate;
+
+    #[async_trait]
+    impl SpanProcessor for Gate {
+        async fn process(&self, span: LlmSpan) -> Result<Option<LlmSpan>> {
+            Ok(Some(span))
+        }
+
+        async fn process_metric(&self, series: MetricSeries) -> Result<Option<MetricSeries>> {
+            match series.metric.name.as_str() {
+                "drop" => Ok(None),
+                "fail" => Err(Error::internal("boom")),
+                _ => Ok(Some(series)),
+            }
+        }
+
+        fn name(&self) -> &str {
+            "gate"
+        }
+    }
+
+    fn series(name: &str) -> MetricSeries {
+        self_series(
+            name,
+            MetricType::Gauge,
+            json!({}),
+            MetricDataPoint::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_records_outcomes_per_processor() {
+        let telemetry = PipelineTelemetry::new();
+        let processors: Vec<Arc<dyn SpanProcessor>> = vec![Arc::new(Gate)];
+
+        for name in ["keep", "keep", "drop", "fail"] {
+            let result = process_metric(&processors, series(name), Some(&telemetry)).await;
+            assert_eq!(result.is_some(), name == "keep");
+        }
+        telemetry.record_queue_wait("metrics", Duration::from_millis(3));
+
+        let drained = telemetry.drain(Utc::now());
+        let items = |outcome: &str| {
+            drained
+                .iter()
+                .find(|s| {
+                    s.metric.name == PROCESSOR_ITEMS_METRIC
+                        && s.metric.attributes["outcome"] == outcome
+                })
+                .and_then(|s| s.data_points[0].value)
+        };
+        assert_eq!(items("forwarded"), Some(2.0));
+        assert_eq!(items("dropped"), Some(1.0));
+        assert_eq!(items("failed"), Some(1.0));
+
+        let duration = drained
+            .iter()
+            .find(|s| s.metric.name == PROCESSOR_DURATION_METRIC)
+            .unwrap();
+        assert_eq!(duration.metric.service_name, SELF_SERVICE_NAME);
+        assert_eq!(duration.metric.attributes["processor"], "gate");
+        assert_eq!(duration.data_points[0].count, Some(4));
+
+        let wait = drained
+            .iter()
+            .find(|s| s.metric.name == QUEUE_WAIT_METRIC)
+            .unwrap();
+        assert_eq!(wait.metric.attributes["queue"], "metrics");
+
+        // Histograms restart after a drain, counters keep their totals
+        let drained = telemetry.drain(Utc::now());
+        assert_eq!(drained.len(), 3);
+        for series in &drained {
+            assert_eq!(series.metric.name, PROCESSOR_ITEMS_METRIC);
+        }
+    }
+}
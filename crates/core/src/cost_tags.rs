@@ -0,0 +1,125 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Span attribute conventions for cost-allocation tags.
+//!
+//! Cost-allocation tags attribute the cost of an LLM call to whoever pays for
+//! it. Each tag is a string attribute named `cost.tag.<key>`:
+//!
+//! | Attribute           | Example         |
+//! |---------------------|-----------------|
+//! | `cost.tag.team`     | `search`        |
+//! | `cost.tag.product`  | `assistant`     |
+//! | `cost.tag.feature`  | `summarize`     |
+//!
+//! Any key may be used. Keys are normalized with [`normalize_key`]: lowercase
+//! ASCII letters, digits and underscores. Storage keeps the tags of a call in
+//! the `llm_traces.cost_tags` column, keyed without the prefix.
+
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Prefix of cost-allocation tag attributes.
+pub const COST_TAG_PREFIX: &str = "cost.tag.";
+
+/// Cost-allocation tags of a call, by key.
+pub type CostTags = BTreeMap<String, String>;
+
+/// Attribute name of the tag `key`.
+pub fn cost_tag_attribute(key: &str) -> String {
+    format!("{}{}", COST_TAG_PREFIX, key)
+}
+
+/// Normalize a tag key: lowercase, with every character other than ASCII
+/// letters and digits replaced by `_`, e.g. `Cost Center` becomes
+/// `cost_center`.
+///
+/// Returns `None` if nothing is left.
+pub fn normalize_key(key: &str) -> Option<String> {
+    let key: String = key
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let key = key.trim_matches('_');
+    (!key.is_empty()).then(|| key.to_string())
+}
+
+/// Read the cost-allocation tags from span attributes.
+///
+/// Numbers and booleans are taken as their string form; empty values and
+/// other types are skipped. Keys are returned as written.
+pub fn cost_tags(attributes: &HashMap<String, Value>) -> CostTags {
+    attributes
+        .iter()
+        .filter_map(|(name, value)| {
+            let key = name.strip_prefix(COST_TAG_PREFIX)?;
+            let value = match value {
+                Value::String(s) => s.trim().to_string(),
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                _ => return None,
+            };
+            (!key.is_empty() && !value.is_empty()).then(|| (key.to_string(), value))
+        })
+        .collect()
+}
+
+/// Replace the cost-allocation tags in span attributes with `tags`.
+pub fn set_cost_tags(attributes: &mut HashMap<String, Value>, tags: &CostTags) {
+    attributes.retain(|name, _| !name.starts_with(COST_TAG_PREFIX));
+    for (key, value) in tags {
+        attributes.insert(cost_tag_attribute(key), Value::from(value.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_key() {
+        assert_eq!(normalize_key("team").as_deref(), Some("team"));
+        assert_eq!(
+            normalize_key(" Cost Center ").as_deref(),
+            Some("cost_center")
+        );
+        assert_eq!(
+            normalize_key("product-line").as_deref(),
+            Some("product_line")
+        );
+        assert_eq!(normalize_key("--"), None);
+    }
+
+    #[test]
+    fn test_read_and_replace_tags() {
+        let mut attributes: HashMap<String, Value> = HashMap::from([
+            (cost_tag_attribute("team"), Value::from(" search ")),
+            (cost_tag_attribute("cost_center"), Value::from(4200)),
+            (cost_tag_attribute("feature"), Value::from("")),
+            ("llm.model".to_string(), Value::from("gpt-4o")),
+        ]);
+
+        let tags = cost_tags(&attributes);
+        assert_eq!(
+            tags,
+            CostTags::from([
+                ("cost_center".to_string(), "4200".to_string()),
+                ("team".to_string(), "search".to_string()),
+            ])
+        );
+
+        set_cost_tags(
+            &mut attributes,
+            &CostTags::from([("product".to_string(), "assistant".to_string())]),
+        );
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(attributes["cost.tag.product"], Value::from("assistant"));
+    }
+}
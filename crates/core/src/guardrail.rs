@@ -0,0 +1,229 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Span attribute conventions for content-safety guardrail outcomes.
+//!
+//! A guardrail outcome is recorded with the `guardrail.*` attributes below,
+//! either on the LLM span itself (one guardrail) or on span events named
+//! [`GUARDRAIL_EVENT`] (any number of guardrails per call):
+//!
+//! | Attribute            | Type   | Description                                      |
+//! |----------------------|--------|--------------------------------------------------|
+//! | `guardrail.name`     | string | Guardrail that ran, e.g. `openai-moderation`     |
+//! | `guardrail.stage`    | string | `input` (prompt) or `output` (completion)        |
+//! | `guardrail.blocked`  | bool   | The request or response was blocked              |
+//! | `guardrail.flagged`  | bool   | A violation was detected but the call went ahead |
+//! | `guardrail.category` | string | Violation category, e.g. `hate`, `pii`           |
+//! | `guardrail.score`    | double | Classifier score, 0.0 to 1.0                     |
+//!
+//! An outcome that is neither blocked nor flagged is a pass.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Name of span events that carry a guardrail outcome.
+pub const GUARDRAIL_EVENT: &str = "guardrail.evaluation";
+
+/// Guardrail name attribute.
+pub const GUARDRAIL_NAME: &str = "guardrail.name";
+
+/// Guardrail stage attribute (`input` or `output`).
+pub const GUARDRAIL_STAGE: &str = "guardrail.stage";
+
+/// Attribute set when the guardrail blocked the call.
+pub const GUARDRAIL_BLOCKED: &str = "guardrail.blocked";
+
+/// Attribute set when the guardrail flagged the call without blocking it.
+pub const GUARDRAIL_FLAGGED: &str = "guardrail.flagged";
+
+/// Violation category attribute.
+pub const GUARDRAIL_CATEGORY: &str = "guardrail.category";
+
+/// Classifier score attribute.
+pub const GUARDRAIL_SCORE: &str = "guardrail.score";
+
+/// Where in the call a guardrail ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GuardrailStage {
+    /// On the prompt, before the provider is called
+    Input,
+    /// On the completion, before it is returned
+    Output,
+}
+
+impl GuardrailStage {
+    /// Attribute value of the stage.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GuardrailStage::Input => "input",
+            GuardrailStage::Output => "output",
+        }
+    }
+}
+
+/// Outcome of one guardrail evaluation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GuardrailOutcome {
+    /// Guardrail name
+    pub name: Option<String>,
+    /// Stage the guardrail ran at
+    pub stage: Option<GuardrailStage>,
+    /// Whether the call was blocked
+    pub blocked: bool,
+    /// Whether the call was flagged
+    pub flagged: bool,
+    /// Violation category
+    pub category: Option<String>,
+    /// Classifier score
+    pub score: Option<f64>,
+}
+
+impl GuardrailOutcome {
+    /// Create a passing outcome for a guardrail.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Set the stage.
+    pub fn with_stage(mut self, stage: GuardrailStage) -> Self {
+        self.stage = Some(stage);
+        self
+    }
+
+    /// Mark the call as blocked.
+    pub fn blocked(mut self) -> Self {
+        self.blocked = true;
+        self
+    }
+
+    /// Mark the call as flagged.
+    pub fn flagged(mut self) -> Self {
+        self.flagged = true;
+        self
+    }
+
+    /// Set the violation category.
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Set the classifier score.
+    pub fn with_score(mut self, score: f64) -> Self {
+        self.score = Some(score);
+        self
+    }
+
+    /// Whether the outcome is a violation (blocked or flagged).
+    pub fn is_violation(&self) -> bool {
+        self.blocked || self.flagged
+    }
+
+    /// Attributes recording this outcome.
+    pub fn to_attributes(&self) -> HashMap<String, Value> {
+        let mut attributes = HashMap::new();
+        if let Some(name) = &self.name {
+            attributes.insert(GUARDRAIL_NAME.to_string(), Value::from(name.clone()));
+        }
+        if let Some(stage) = self.stage {
+            attributes.insert(GUARDRAIL_STAGE.to_string(), Value::from(stage.as_str()));
+        }
+        attributes.insert(GUARDRAIL_BLOCKED.to_string(), Value::from(self.blocked));
+        attributes.insert(GUARDRAIL_FLAGGED.to_string(), Value::from(self.flagged));
+        if let Some(category) = &self.category {
+            attributes.insert(GUARDRAIL_CATEGORY.to_string(), Value::from(category.clone()));
+        }
+        if let Some(score) = self.score {
+            attributes.insert(GUARDRAIL_SCORE.to_string(), Value::from(score));
+        }
+        attributes
+    }
+
+    /// Read an outcome from span or event attributes.
+    ///
+    /// Returns `None` if neither `guardrail.blocked` nor `guardrail.flagged`
+    /// is present. Booleans and numbers are also accepted as strings, since
+    /// not every exporter preserves attribute types.
+    pub fn from_attributes(attributes: &HashMap<String, Value>) -> Option<Self> {
+        let blocked = attributes.get(GUARDRAIL_BLOCKED).map(as_bool);
+        let flagged = attributes.get(GUARDRAIL_FLAGGED).map(as_bool);
+        if blocked.is_none() && flagged.is_none() {
+            return None;
+        }
+
+        let string = |key: &str| {
+            attributes
+                .get(key)
+                .and_then(Value::as_str)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+
+        Some(Self {
+            name: string(GUARDRAIL_NAME),
+            stage: match attributes.get(GUARDRAIL_STAGE).and_then(Value::as_str) {
+                Some("input") => Some(GuardrailStage::Input),
+                Some("output") => Some(GuardrailStage::Output),
+                _ => None,
+            },
+            blocked: blocked.unwrap_or(false),
+            flagged: flagged.unwrap_or(false),
+            category: string(GUARDRAIL_CATEGORY),
+            score: attributes.get(GUARDRAIL_SCORE).and_then(|value| match value {
+                Value::String(s) => s.parse().ok(),
+                other => other.as_f64(),
+            }),
+        })
+    }
+}
+
+fn as_bool(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::String(s) => s.eq_ignore_ascii_case("true"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attribute_round_trip() {
+        let outcome = GuardrailOutcome::new("moderation")
+            .with_stage(GuardrailStage::Output)
+            .flagged()
+            .with_category("hate")
+            .with_score(0.91);
+
+        let attributes = outcome.to_attributes();
+        assert_eq!(attributes[GUARDRAIL_FLAGGED], Value::Bool(true));
+        assert_eq!(GuardrailOutcome::from_attributes(&attributes), Some(outcome));
+    }
+
+    #[test]
+    fn test_from_string_attributes() {
+        let attributes: HashMap<String, Value> = [
+            (GUARDRAIL_BLOCKED, "true"),
+            (GUARDRAIL_CATEGORY, "pii"),
+            (GUARDRAIL_SCORE, "0.5"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), Value::from(v)))
+        .collect();
+
+        let outcome = GuardrailOutcome::from_attributes(&attributes).unwrap();
+        assert!(outcome.blocked);
+        assert!(!outcome.flagged);
+        assert_eq!(outcome.category.as_deref(), Some("pii"));
+        assert_eq!(outcome.score, Some(0.5));
+
+        assert_eq!(GuardrailOutcome::from_attributes(&HashMap::new()), None);
+    }
+}
@@ -10,9 +10,15 @@
 #![warn(missing_docs, rust_2018_idioms)]
 #![deny(unsafe_code)]
 
+pub mod cost_tags;
 pub mod error;
+pub mod guardrail;
 pub mod provider;
+pub mod rate_limit;
+pub mod resource;
+pub mod secrets;
 pub mod span;
+pub mod timing;
 pub mod types;
 
 pub use error::{Error, Result};
@@ -0,0 +1,280 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Span attribute conventions for provider rate-limit state.
+//!
+//! OpenAI (`x-ratelimit-*`) and Anthropic (`anthropic-ratelimit-*`) report
+//! on every response how much of the organization's request and token rate
+//! limits is left, and when they reset. [`RateLimitState`] parses those
+//! headers and records them on the LLM span with the attributes below:
+//!
+//! | Attribute                                       | Type   | Description                              |
+//! |-------------------------------------------------|--------|------------------------------------------|
+//! | `llm_observatory.rate_limit.requests.limit`     | int    | Requests allowed per window              |
+//! | `llm_observatory.rate_limit.requests.remaining` | int    | Requests left in the current window      |
+//! | `llm_observatory.rate_limit.requests.reset_at`  | string | When the request limit resets (RFC 3339) |
+//! | `llm_observatory.rate_limit.tokens.limit`       | int    | Tokens allowed per window                |
+//! | `llm_observatory.rate_limit.tokens.remaining`   | int    | Tokens left in the current window        |
+//! | `llm_observatory.rate_limit.tokens.reset_at`    | string | When the token limit resets (RFC 3339)   |
+//!
+//! Storage keeps the latest state per organization, provider and model
+//! (migration `040_provider_rate_limits.sql`).
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Requests allowed per window attribute.
+pub const RATE_LIMIT_REQUESTS_LIMIT: &str = "llm_observatory.rate_limit.requests.limit";
+
+/// Requests left in the current window attribute.
+pub const RATE_LIMIT_REQUESTS_REMAINING: &str = "llm_observatory.rate_limit.requests.remaining";
+
+/// Request limit reset time attribute (RFC 3339).
+pub const RATE_LIMIT_REQUESTS_RESET_AT: &str = "llm_observatory.rate_limit.requests.reset_at";
+
+/// Tokens allowed per window attribute.
+pub const RATE_LIMIT_TOKENS_LIMIT: &str = "llm_observatory.rate_limit.tokens.limit";
+
+/// Tokens left in the current window attribute.
+pub const RATE_LIMIT_TOKENS_REMAINING: &str = "llm_observatory.rate_limit.tokens.remaining";
+
+/// Token limit reset time attribute (RFC 3339).
+pub const RATE_LIMIT_TOKENS_RESET_AT: &str = "llm_observatory.rate_limit.tokens.reset_at";
+
+/// Rate-limit state reported by a provider with one response.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitState {
+    /// Requests allowed per window
+    pub requests_limit: Option<u64>,
+    /// Requests left in the current window
+    pub requests_remaining: Option<u64>,
+    /// When the request limit resets
+    pub requests_reset_at: Option<DateTime<Utc>>,
+    /// Tokens allowed per window
+    pub tokens_limit: Option<u64>,
+    /// Tokens left in the current window
+    pub tokens_remaining: Option<u64>,
+    /// When the token limit resets
+    pub tokens_reset_at: Option<DateTime<Utc>>,
+}
+
+impl RateLimitState {
+    /// Parse the rate-limit headers of a response received at `now`.
+    ///
+    /// Header names are matched case-insensitively. OpenAI reports resets as
+    /// durations (`6m0s`, `120ms`), Anthropic as RFC 3339 timestamps; both
+    /// become absolute times. Other headers are ignored.
+    pub fn from_headers<'a, I>(headers: I, now: DateTime<Utc>) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let mut state = Self::default();
+        for (name, value) in headers {
+            let name = name.to_ascii_lowercase();
+            let value = value.trim();
+            let count = match name.as_str() {
+                "x-ratelimit-limit-requests" | "anthropic-ratelimit-requests-limit" => {
+                    &mut state.requests_limit
+                }
+                "x-ratelimit-remaining-requests" | "anthropic-ratelimit-requests-remaining" => {
+                    &mut state.requests_remaining
+                }
+                "x-ratelimit-limit-tokens" | "anthropic-ratelimit-tokens-limit" => {
+                    &mut state.tokens_limit
+                }
+                "x-ratelimit-remaining-tokens" | "anthropic-ratelimit-tokens-remaining" => {
+                    &mut state.tokens_remaining
+                }
+                "x-ratelimit-reset-requests" | "anthropic-ratelimit-requests-reset" => {
+                    state.requests_reset_at = parse_reset(value, now);
+                    continue;
+                }
+                "x-ratelimit-reset-tokens" | "anthropic-ratelimit-tokens-reset" => {
+                    state.tokens_reset_at = parse_reset(value, now);
+                    continue;
+                }
+                _ => continue,
+            };
+            *count = value.parse().ok();
+        }
+        state
+    }
+
+    /// Whether the provider reported no rate-limit state.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Share of the request limit used in the current window.
+    pub fn requests_utilization(&self) -> Option<f64> {
+        utilization(self.requests_limit, self.requests_remaining)
+    }
+
+    /// Share of the token limit used in the current window.
+    pub fn tokens_utilization(&self) -> Option<f64> {
+        utilization(self.tokens_limit, self.tokens_remaining)
+    }
+
+    /// Attributes recording this state; unreported fields are left out.
+    pub fn to_attributes(&self) -> HashMap<String, Value> {
+        let mut attributes = HashMap::new();
+        let counts = [
+            (RATE_LIMIT_REQUESTS_LIMIT, self.requests_limit),
+            (RATE_LIMIT_REQUESTS_REMAINING, self.requests_remaining),
+            (RATE_LIMIT_TOKENS_LIMIT, self.tokens_limit),
+            (RATE_LIMIT_TOKENS_REMAINING, self.tokens_remaining),
+        ];
+        for (key, count) in counts {
+            if let Some(count) = count {
+                attributes.insert(key.to_string(), Value::from(count));
+            }
+        }
+        let resets = [
+            (RATE_LIMIT_REQUESTS_RESET_AT, self.requests_reset_at),
+            (RATE_LIMIT_TOKENS_RESET_AT, self.tokens_reset_at),
+        ];
+        for (key, reset_at) in resets {
+            if let Some(reset_at) = reset_at {
+                attributes.insert(key.to_string(), Value::from(reset_at.to_rfc3339()));
+            }
+        }
+        attributes
+    }
+}
+
+fn utilization(limit: Option<u64>, remaining: Option<u64>) -> Option<f64> {
+    match (limit, remaining) {
+        (Some(limit), Some(remaining)) if limit > 0 => {
+            Some(1.0 - remaining.min(limit) as f64 / limit as f64)
+        }
+        _ => None,
+    }
+}
+
+/// Reset time from an RFC 3339 timestamp, a duration such as `1m30.5s`,
+/// `20ms` or `1h2m3s`, or a number of seconds.
+fn parse_reset(value: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Some(at.with_timezone(&Utc));
+    }
+    if let Ok(seconds) = value.parse::<f64>() {
+        return seconds_after(now, seconds);
+    }
+
+    let mut seconds = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let unit_start = rest.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+        let amount: f64 = rest[..unit_start].parse().ok()?;
+        rest = &rest[unit_start..];
+        let unit_end = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        seconds += amount
+            * match &rest[..unit_end] {
+                "h" => 3600.0,
+                "m" => 60.0,
+                "s" => 1.0,
+                "ms" => 0.001,
+                _ => return None,
+            };
+        rest = &rest[unit_end..];
+    }
+    seconds_after(now, seconds)
+}
+
+/// Longest reset delay. Provider windows last minutes to a day; longer
+/// values come from malformed headers and are clamped to it
+const MAX_RESET_SECS: f64 = 7.0 * 24.0 * 3600.0;
+
+fn seconds_after(now: DateTime<Utc>, seconds: f64) -> Option<DateTime<Utc>> {
+    if !seconds.is_finite() || seconds < 0.0 {
+        return None;
+    }
+    let millis = (seconds.min(MAX_RESET_SECS) * 1000.0).round() as i64;
+    now.checked_add_signed(Duration::milliseconds(millis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_openai_headers() {
+        let headers = [
+            ("x-ratelimit-limit-requests", "500"),
+            ("x-ratelimit-remaining-requests", "499"),
+            ("x-ratelimit-reset-requests", "120ms"),
+            ("X-RateLimit-Limit-Tokens", "30000"),
+            ("x-ratelimit-remaining-tokens", "3000"),
+            ("x-ratelimit-reset-tokens", "1m30.5s"),
+            ("content-type", "application/json"),
+        ];
+
+        let state = RateLimitState::from_headers(headers, now());
+        assert_eq!(state.requests_limit, Some(500));
+        assert_eq!(state.requests_remaining, Some(499));
+        assert_eq!(
+            state.requests_reset_at,
+            Some(now() + Duration::milliseconds(120))
+        );
+        assert_eq!(
+            state.tokens_reset_at,
+            Some(now() + Duration::milliseconds(90_500))
+        );
+        assert!((state.tokens_utilization().unwrap() - 0.9).abs() < 1e-9);
+
+        let attributes = state.to_attributes();
+        assert_eq!(attributes[RATE_LIMIT_TOKENS_REMAINING], Value::from(3000));
+        assert_eq!(
+            attributes[RATE_LIMIT_TOKENS_RESET_AT],
+            Value::from("2025-01-01T00:01:30.500+00:00")
+        );
+    }
+
+    #[test]
+    fn test_anthropic_headers() {
+        let headers = [
+            ("anthropic-ratelimit-requests-limit", "50"),
+            ("anthropic-ratelimit-requests-remaining", "0"),
+            ("anthropic-ratelimit-requests-reset", "2025-01-01T00:00:30Z"),
+            ("anthropic-ratelimit-tokens-remaining", "not-a-number"),
+        ];
+
+        let state = RateLimitState::from_headers(headers, now());
+        assert_eq!(state.requests_utilization(), Some(1.0));
+        assert_eq!(state.requests_reset_at, Some(now() + Duration::seconds(30)));
+        assert_eq!(state.tokens_remaining, None);
+        assert_eq!(state.tokens_utilization(), None);
+
+        assert!(RateLimitState::from_headers([("server", "cloudflare")], now()).is_empty());
+        assert_eq!(
+            parse_reset("6m0s", now()),
+            Some(now() + Duration::minutes(6))
+        );
+        assert_eq!(parse_reset("soon", now()), None);
+    }
+
+    #[test]
+    fn test_reset_delay_is_capped() {
+        let week = Some(now() + Duration::days(7));
+        assert_eq!(parse_reset("1e300", now()), week);
+        assert_eq!(parse_reset("99999999999h", now()), week);
+        assert_eq!(parse_reset("-5", now()), None);
+        assert_eq!(parse_reset("NaN", now()), None);
+
+        // Near the end of the representable range the sum overflows
+        assert_eq!(seconds_after(DateTime::<Utc>::MAX_UTC, 60.0), None);
+    }
+}
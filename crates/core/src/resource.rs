@@ -0,0 +1,492 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Detection of the resource (pod, node, cloud region) telemetry comes from.
+//!
+//! Detectors follow the OpenTelemetry resource semantic conventions and
+//! return only the attributes they could determine:
+//!
+//! - [`KubernetesDetector`]: `k8s.pod.name`, `k8s.pod.uid`,
+//!   `k8s.namespace.name`, `k8s.node.name` and `k8s.deployment.name`, from
+//!   Downward API environment variables and the service account namespace
+//! - [`CloudDetector`]: `cloud.provider`, `cloud.platform`, `cloud.region`,
+//!   `cloud.availability_zone` and `cloud.account.id`, from platform
+//!   environment variables and, with the `cloud` feature, the AWS, GCP and
+//!   Azure instance metadata endpoints
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm_observatory_core::resource::{self, KubernetesDetector, CloudDetector};
+//!
+//! # async fn example() {
+//! let attributes = resource::detect(&[&KubernetesDetector, &CloudDetector::default()]).await;
+//! for (key, value) in &attributes {
+//!     println!("{key}={value}");
+//! }
+//! # }
+//! ```
+
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Pod name attribute key.
+pub const K8S_POD_NAME: &str = "k8s.pod.name";
+/// Pod UID attribute key.
+pub const K8S_POD_UID: &str = "k8s.pod.uid";
+/// Namespace attribute key.
+pub const K8S_NAMESPACE_NAME: &str = "k8s.namespace.name";
+/// Node attribute key.
+pub const K8S_NODE_NAME: &str = "k8s.node.name";
+/// Deployment attribute key.
+pub const K8S_DEPLOYMENT_NAME: &str = "k8s.deployment.name";
+/// Cloud provider attribute key (`aws`, `gcp`, `azure`).
+pub const CLOUD_PROVIDER: &str = "cloud.provider";
+/// Cloud platform attribute key (e.g. `aws_eks`, `gcp_cloud_run`).
+pub const CLOUD_PLATFORM: &str = "cloud.platform";
+/// Cloud region attribute key.
+pub const CLOUD_REGION: &str = "cloud.region";
+/// Availability zone attribute key.
+pub const CLOUD_AVAILABILITY_ZONE: &str = "cloud.availability_zone";
+/// Cloud account (AWS account, GCP project, Azure subscription) attribute key.
+pub const CLOUD_ACCOUNT_ID: &str = "cloud.account.id";
+
+/// Detected attributes by key.
+pub type ResourceAttributes = BTreeMap<String, String>;
+
+/// File holding the namespace of the pod's service account.
+const SERVICE_ACCOUNT_NAMESPACE: &str = "/var/run/secrets/kubernetes.io/serviceaccount/namespace";
+
+/// Detects attributes of the environment the process runs in.
+#[async_trait]
+pub trait ResourceDetector: Send + Sync {
+    /// Detect attributes; empty when not running in this kind of environment.
+    async fn detect(&self) -> ResourceAttributes;
+
+    /// Detector name.
+    fn name(&self) -> &str;
+}
+
+/// Run `detectors` and merge their attributes; earlier detectors win.
+pub async fn detect(detectors: &[&dyn ResourceDetector]) -> ResourceAttributes {
+    let mut attributes = ResourceAttributes::new();
+    for detector in detectors {
+        for (key, value) in detector.detect().await {
+            attributes.entry(key).or_insert(value);
+        }
+    }
+    attributes
+}
+
+/// Detects the pod, namespace, node and deployment on Kubernetes.
+///
+/// Reads the variables usually set through the Downward API
+/// (`K8S_POD_NAME`/`POD_NAME`, `K8S_POD_UID`/`POD_UID`,
+/// `K8S_NAMESPACE_NAME`/`POD_NAMESPACE`, `K8S_NODE_NAME`/`NODE_NAME`), with
+/// `HOSTNAME` and the service account namespace as fallbacks. The deployment
+/// is `K8S_DEPLOYMENT_NAME`, or derived from the name of a pod created by a
+/// ReplicaSet.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KubernetesDetector;
+
+impl KubernetesDetector {
+    /// Detect without blocking, from the environment and local files only.
+    pub fn detect_local(&self) -> ResourceAttributes {
+        let namespace = || {
+            std::fs::read_to_string(SERVICE_ACCOUNT_NAMESPACE)
+                .ok()
+                .map(|s| s.trim().to_string())
+        };
+        Self::detect_with(&|name| std::env::var(name).ok(), &namespace)
+    }
+
+    fn detect_with(
+        env: &dyn Fn(&str) -> Option<String>,
+        service_account_namespace: &dyn Fn() -> Option<String>,
+    ) -> ResourceAttributes {
+        let mut attributes = ResourceAttributes::new();
+        if env("KUBERNETES_SERVICE_HOST").is_none() {
+            return attributes;
+        }
+
+        let first = |names: &[&str]| {
+            names
+                .iter()
+                .filter_map(|name| env(name))
+                .find(|value| !value.is_empty())
+        };
+
+        let pod = first(&["K8S_POD_NAME", "POD_NAME", "HOSTNAME"]);
+        let deployment = first(&["K8S_DEPLOYMENT_NAME"]).or_else(|| {
+            pod.as_deref()
+                .and_then(deployment_from_pod)
+                .map(str::to_string)
+        });
+        let namespace = first(&["K8S_NAMESPACE_NAME", "POD_NAMESPACE"])
+            .or_else(service_account_namespace)
+            .filter(|ns| !ns.is_empty());
+
+        for (key, value) in [
+            (K8S_POD_NAME, pod),
+            (K8S_POD_UID, first(&["K8S_POD_UID", "POD_UID"])),
+            (K8S_NAMESPACE_NAME, namespace),
+            (K8S_NODE_NAME, first(&["K8S_NODE_NAME", "NODE_NAME"])),
+            (K8S_DEPLOYMENT_NAME, deployment),
+        ] {
+            if let Some(value) = value {
+                attributes.insert(key.to_string(), value);
+            }
+        }
+        attributes
+    }
+}
+
+#[async_trait]
+impl ResourceDetector for KubernetesDetector {
+    async fn detect(&self) -> ResourceAttributes {
+        self.detect_local()
+    }
+
+    fn name(&self) -> &str {
+        "kubernetes"
+    }
+}
+
+/// Deployment of a pod named `<deployment>-<replicaset hash>-<suffix>`.
+///
+/// Both generated parts use the Kubernetes name alphabet (no vowels, no
+/// `0`, `1` or `3`), which keeps StatefulSet pods like `db-0` from matching.
+pub fn deployment_from_pod(pod: &str) -> Option<&str> {
+    const ALPHABET: &str = "bcdfghjklmnpqrstvwxz2456789";
+    let generated = |s: &str| s.chars().all(|c| ALPHABET.contains(c));
+
+    let (rest, suffix) = pod.rsplit_once('-')?;
+    let (deployment, hash) = rest.rsplit_once('-')?;
+    let valid = suffix.len() == 5
+        && (5..=10).contains(&hash.len())
+        && generated(suffix)
+        && generated(hash)
+        && !deployment.is_empty();
+    valid.then_some(deployment)
+}
+
+/// Detects the cloud provider, platform and region.
+///
+/// Serverless platforms are recognized from their environment variables.
+/// Otherwise, with the `cloud` feature, the AWS (IMDSv2), GCP and Azure
+/// metadata endpoints are queried concurrently, each within `timeout`.
+#[derive(Debug, Clone)]
+pub struct CloudDetector {
+    timeout: Duration,
+}
+
+impl Default for CloudDetector {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_millis(1000),
+        }
+    }
+}
+
+impl CloudDetector {
+    /// Set how long each metadata endpoint may take to answer.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Detect without blocking, from the environment only.
+    pub fn detect_local(&self) -> ResourceAttributes {
+        Self::detect_env(&|name| std::env::var(name).ok())
+    }
+
+    fn detect_env(env: &dyn Fn(&str) -> Option<String>) -> ResourceAttributes {
+        let aws_region = || env("AWS_REGION").or_else(|| env("AWS_DEFAULT_REGION"));
+
+        let (provider, platform, region) = match env("AWS_EXECUTION_ENV") {
+            Some(exec) if exec.starts_with("AWS_Lambda") => ("aws", "aws_lambda", aws_region()),
+            Some(exec) if exec.starts_with("AWS_ECS") => ("aws", "aws_ecs", aws_region()),
+            _ if env("K_SERVICE").is_some() => ("gcp", "gcp_cloud_run", None),
+            _ if env("FUNCTION_TARGET").is_some() => ("gcp", "gcp_cloud_functions", None),
+            _ if env("WEBSITE_SITE_NAME").is_some() => {
+                ("azure", "azure_app_service", env("REGION_NAME"))
+            }
+            _ => return ResourceAttributes::new(),
+        };
+
+        let mut attributes = ResourceAttributes::from([
+            (CLOUD_PROVIDER.to_string(), provider.to_string()),
+            (CLOUD_PLATFORM.to_string(), platform.to_string()),
+        ]);
+        if let Some(region) = region {
+            attributes.insert(CLOUD_REGION.to_string(), region);
+        }
+        attributes
+    }
+
+    #[cfg(feature = "cloud")]
+    async fn detect_metadata(&self) -> ResourceAttributes {
+        let Ok(client) = reqwest::Client::builder().timeout(self.timeout).build() else {
+            return ResourceAttributes::new();
+        };
+        let in_kubernetes = std::env::var("KUBERNETES_SERVICE_HOST").is_ok();
+
+        let (aws, gcp, azure) = tokio::join!(
+            metadata::aws(&client),
+            metadata::gcp(&client),
+            metadata::azure(&client)
+        );
+        let attributes = aws
+            .map(|doc| parse_aws_identity(&doc, in_kubernetes))
+            .or_else(|| gcp.map(|zone| parse_gcp_zone(&zone, in_kubernetes)))
+            .or_else(|| azure.map(|doc| parse_azure_compute(&doc, in_kubernetes)));
+        attributes.unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl ResourceDetector for CloudDetector {
+    async fn detect(&self) -> ResourceAttributes {
+        let attributes = self.detect_local();
+        #[cfg(feature = "cloud")]
+        if attributes.is_empty() {
+            return self.detect_metadata().await;
+        }
+        attributes
+    }
+
+    fn name(&self) -> &str {
+        "cloud"
+    }
+}
+
+#[cfg(feature = "cloud")]
+mod metadata {
+    //! Instance metadata requests; `None` when the endpoint does not answer.
+
+    use serde_json::Value;
+
+    const LINK_LOCAL: &str = "http://169.254.169.254";
+
+    pub(super) async fn aws(client: &reqwest::Client) -> Option<Value> {
+        let token = client
+            .put(format!("{}/latest/api/token", LINK_LOCAL))
+            .header("X-aws-ec2-metadata-token-ttl-seconds", "60")
+            .send()
+            .await
+            .ok()?
+            .error_for_status()
+            .ok()?
+            .text()
+            .await
+            .ok()?;
+        client
+            .get(format!(
+                "{}/latest/dynamic/instance-identity/document",
+                LINK_LOCAL
+            ))
+            .header("X-aws-ec2-metadata-token", token)
+            .send()
+            .await
+            .ok()?
+            .error_for_status()
+            .ok()?
+            .json()
+            .await
+            .ok()
+    }
+
+    pub(super) async fn gcp(client: &reqwest::Client) -> Option<String> {
+        client
+            .get("http://metadata.google.internal/computeMetadata/v1/instance/zone")
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await
+            .ok()?
+            .error_for_status()
+            .ok()?
+            .text()
+            .await
+            .ok()
+    }
+
+    pub(super) async fn azure(client: &reqwest::Client) -> Option<Value> {
+        client
+            .get(format!(
+                "{}/metadata/instance/compute?api-version=2021-02-01",
+                LINK_LOCAL
+            ))
+            .header("Metadata", "true")
+            .send()
+            .await
+            .ok()?
+            .error_for_status()
+            .ok()?
+            .json()
+            .await
+            .ok()
+    }
+}
+
+/// Attributes from an AWS instance identity document.
+pub fn parse_aws_identity(doc: &serde_json::Value, in_kubernetes: bool) -> ResourceAttributes {
+    let platform = if in_kubernetes { "aws_eks" } else { "aws_ec2" };
+    cloud_attributes(
+        "aws",
+        platform,
+        doc["region"].as_str(),
+        doc["availabilityZone"].as_str(),
+        doc["accountId"].as_str(),
+    )
+}
+
+/// Attributes from a GCP instance zone, `projects/<number>/zones/<zone>`.
+pub fn parse_gcp_zone(zone: &str, in_kubernetes: bool) -> ResourceAttributes {
+    let platform = if in_kubernetes {
+        "gcp_kubernetes_engine"
+    } else {
+        "gcp_compute_engine"
+    };
+    let zone = zone.trim().rsplit('/').next().filter(|z| !z.is_empty());
+    let region = zone
+        .and_then(|z| z.rsplit_once('-'))
+        .map(|(region, _)| region);
+    cloud_attributes("gcp", platform, region, zone, None)
+}
+
+/// Attributes from the Azure instance metadata `compute` document.
+pub fn parse_azure_compute(doc: &serde_json::Value, in_kubernetes: bool) -> ResourceAttributes {
+    let platform = if in_kubernetes {
+        "azure_aks"
+    } else {
+        "azure_vm"
+    };
+    cloud_attributes(
+        "azure",
+        platform,
+        doc["location"].as_str(),
+        doc["zone"].as_str(),
+        doc["subscriptionId"].as_str(),
+    )
+}
+
+fn cloud_attributes(
+    provider: &str,
+    platform: &str,
+    region: Option<&str>,
+    zone: Option<&str>,
+    account: Option<&str>,
+) -> ResourceAttributes {
+    let mut attributes = ResourceAttributes::from([
+        (CLOUD_PROVIDER.to_string(), provider.to_string()),
+        (CLOUD_PLATFORM.to_string(), platform.to_string()),
+    ]);
+    for (key, value) in [
+        (CLOUD_REGION, region),
+        (CLOUD_AVAILABILITY_ZONE, zone),
+        (CLOUD_ACCOUNT_ID, account),
+    ] {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            attributes.insert(key.to_string(), value.to_string());
+        }
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_kubernetes_detection() {
+        let vars = env(&[
+            ("KUBERNETES_SERVICE_HOST", "10.0.0.1"),
+            ("HOSTNAME", "chat-api-7d9f8b6c5d-x2kqp"),
+            ("NODE_NAME", "node-a"),
+        ]);
+        let attributes = KubernetesDetector::detect_with(&vars, &|| Some("prod".to_string()));
+        assert_eq!(attributes[K8S_POD_NAME], "chat-api-7d9f8b6c5d-x2kqp");
+        assert_eq!(attributes[K8S_DEPLOYMENT_NAME], "chat-api");
+        assert_eq!(attributes[K8S_NAMESPACE_NAME], "prod");
+        assert_eq!(attributes[K8S_NODE_NAME], "node-a");
+        assert!(!attributes.contains_key(K8S_POD_UID));
+
+        // Outside Kubernetes, HOSTNAME is just the host
+        let vars = env(&[("HOSTNAME", "laptop")]);
+        assert!(KubernetesDetector::detect_with(&vars, &|| None).is_empty());
+    }
+
+    #[test]
+    fn test_deployment_from_pod() {
+        assert_eq!(
+            deployment_from_pod("web-frontend-5c689d88bb-q7zvf"),
+            Some("web-frontend")
+        );
+        assert_eq!(deployment_from_pod("db-0"), None);
+        assert_eq!(deployment_from_pod("worker-abcde-fghij"), None);
+    }
+
+    #[test]
+    fn test_cloud_env_detection() {
+        let vars = env(&[
+            ("AWS_EXECUTION_ENV", "AWS_Lambda_rust"),
+            ("AWS_REGION", "eu-west-1"),
+        ]);
+        let attributes = CloudDetector::detect_env(&vars);
+        assert_eq!(attributes[CLOUD_PLATFORM], "aws_lambda");
+        assert_eq!(attributes[CLOUD_REGION], "eu-west-1");
+
+        let vars = env(&[("K_SERVICE", "chat-api")]);
+        assert_eq!(CloudDetector::detect_env(&vars)[CLOUD_PROVIDER], "gcp");
+        assert!(CloudDetector::detect_env(&env(&[])).is_empty());
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let aws = parse_aws_identity(
+            &json!({"region": "us-east-1", "availabilityZone": "us-east-1b", "accountId": "123456789012"}),
+            true,
+        );
+        assert_eq!(aws[CLOUD_PLATFORM], "aws_eks");
+        assert_eq!(aws[CLOUD_AVAILABILITY_ZONE], "us-east-1b");
+        assert_eq!(aws[CLOUD_ACCOUNT_ID], "123456789012");
+
+        let gcp = parse_gcp_zone("projects/4242/zones/us-central1-a", false);
+        assert_eq!(gcp[CLOUD_REGION], "us-central1");
+        assert_eq!(gcp[CLOUD_AVAILABILITY_ZONE], "us-central1-a");
+
+        let azure = parse_azure_compute(&json!({"location": "westeurope", "zone": ""}), false);
+        assert_eq!(azure[CLOUD_REGION], "westeurope");
+        assert!(!azure.contains_key(CLOUD_AVAILABILITY_ZONE));
+    }
+
+    #[tokio::test]
+    async fn test_detect_merges_in_order() {
+        struct Fixed(&'static str);
+
+        #[async_trait]
+        impl ResourceDetector for Fixed {
+            async fn detect(&self) -> ResourceAttributes {
+                ResourceAttributes::from([(CLOUD_REGION.to_string(), self.0.to_string())])
+            }
+
+            fn name(&self) -> &str {
+                "fixed"
+            }
+        }
+
+        let attributes = detect(&[&Fixed("eu-west-1"), &Fixed("us-east-1")]).await;
+        assert_eq!(attributes[CLOUD_REGION], "eu-west-1");
+    }
+}
@@ -0,0 +1,447 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Secret references in configuration values.
+//!
+//! Instead of a plaintext password or token, a configuration string may hold
+//! a reference that is resolved when the configuration is loaded:
+//!
+//! | Reference                | Resolved to                                   |
+//! |--------------------------|-----------------------------------------------|
+//! | `${env:VAR}`             | Value of the environment variable `VAR`       |
+//! | `${file:/path}`          | Contents of the file, without trailing newline |
+//! | `${vault:kv/path#key}`   | Field `key` of a Vault KV secret (`vault` feature) |
+//!
+//! References may be embedded in a longer string, e.g.
+//! `postgres://app:${env:DB_PASSWORD}@db:5432/app`. `$${` is kept as a
+//! literal `${`, and `${...}` without a `scheme:` prefix is left untouched.
+//!
+//! Each scheme is handled by a [`SecretResolver`]; [`SecretResolvers`] maps
+//! schemes to resolvers and can be extended with custom ones.
+//!
+//! # Example
+//!
+//! ```
+//! use llm_observatory_core::secrets::SecretResolvers;
+//!
+//! std::env::set_var("EXAMPLE_DB_PASSWORD", "hunter2");
+//!
+//! let secrets = SecretResolvers::default();
+//! let url = secrets
+//!     .resolve_str("postgres://app:${env:EXAMPLE_DB_PASSWORD}@db/app")
+//!     .unwrap();
+//! assert_eq!(url, "postgres://app:hunter2@db/app");
+//! ```
+
+use crate::{Error, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Resolves the references of one scheme to secret values.
+pub trait SecretResolver: Send + Sync {
+    /// Resolve `reference`, the part of `${scheme:reference}` after the colon.
+    ///
+    /// Errors should describe the reference, never the secret.
+    fn resolve(&self, reference: &str) -> Result<String>;
+}
+
+impl<F> SecretResolver for F
+where
+    F: Fn(&str) -> Result<String> + Send + Sync,
+{
+    fn resolve(&self, reference: &str) -> Result<String> {
+        self(reference)
+    }
+}
+
+/// Resolves `${env:VAR}` from the process environment.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvResolver;
+
+impl SecretResolver for EnvResolver {
+    fn resolve(&self, reference: &str) -> Result<String> {
+        std::env::var(reference)
+            .map_err(|_| Error::config(format!("environment variable {} is not set", reference)))
+    }
+}
+
+/// Resolves `${file:/path}` to the contents of the file.
+///
+/// A trailing newline is removed, so files written by `echo` or mounted
+/// Kubernetes secrets work as-is.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileResolver;
+
+impl SecretResolver for FileResolver {
+    fn resolve(&self, reference: &str) -> Result<String> {
+        let contents = std::fs::read_to_string(reference)
+            .map_err(|e| Error::config(format!("cannot read secret file {}: {}", reference, e)))?;
+        Ok(contents.trim_end_matches(['\n', '\r']).to_string())
+    }
+}
+
+/// Resolves `${vault:mount/path#key}` from a HashiCorp Vault KV engine.
+///
+/// The first path segment is the mount of the KV engine, `key` the field of
+/// the secret to return.
+#[cfg(feature = "vault")]
+#[derive(Debug, Clone)]
+pub struct VaultResolver {
+    addr: String,
+    token: String,
+    namespace: Option<String>,
+    kv_version: u8,
+}
+
+#[cfg(feature = "vault")]
+impl VaultResolver {
+    /// Create a resolver for the Vault server at `addr`, reading KV version 2
+    /// secrets with `token`.
+    pub fn new(addr: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            addr: addr.into().trim_end_matches('/').to_string(),
+            token: token.into(),
+            namespace: None,
+            kv_version: 2,
+        }
+    }
+
+    /// Create a resolver from `VAULT_ADDR`, `VAULT_TOKEN` and the optional
+    /// `VAULT_NAMESPACE`, as the Vault CLI does.
+    pub fn from_env() -> Result<Self> {
+        let addr = std::env::var("VAULT_ADDR")
+            .map_err(|_| Error::config("VAULT_ADDR is required for vault secrets"))?;
+        let token = std::env::var("VAULT_TOKEN")
+            .map_err(|_| Error::config("VAULT_TOKEN is required for vault secrets"))?;
+        let mut resolver = Self::new(addr, token);
+        resolver.namespace = std::env::var("VAULT_NAMESPACE").ok();
+        Ok(resolver)
+    }
+
+    /// Set the Vault Enterprise namespace.
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Read from a KV version 1 engine instead of version 2.
+    pub fn with_kv_v1(mut self) -> Self {
+        self.kv_version = 1;
+        self
+    }
+
+    fn url(&self, path: &str) -> String {
+        match (self.kv_version, path.split_once('/')) {
+            (2, Some((mount, rest))) => format!("{}/v1/{}/data/{}", self.addr, mount, rest),
+            _ => format!("{}/v1/{}", self.addr, path),
+        }
+    }
+
+    fn fetch(&self, path: &str) -> Result<Value> {
+        let mut request = reqwest::blocking::Client::new()
+            .get(self.url(path))
+            .header("X-Vault-Token", &self.token);
+        if let Some(namespace) = &self.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+
+        let response = request
+            .send()
+            .map_err(|e| Error::config(format!("vault request for {} failed: {}", path, e)))?;
+        if !response.status().is_success() {
+            return Err(Error::config(format!(
+                "vault returned {} for {}",
+                response.status(),
+                path
+            )));
+        }
+        response
+            .json()
+            .map_err(|e| Error::config(format!("invalid vault response for {}: {}", path, e)))
+    }
+}
+
+#[cfg(feature = "vault")]
+impl SecretResolver for VaultResolver {
+    fn resolve(&self, reference: &str) -> Result<String> {
+        let (path, key) = reference
+            .rsplit_once('#')
+            .ok_or_else(|| Error::config(format!("vault reference {} has no #key", reference)))?;
+
+        // The blocking client must not run on an async runtime thread, and
+        // configuration is usually loaded from `main` under `#[tokio::main]`.
+        let body = std::thread::scope(|s| {
+            s.spawn(|| self.fetch(path))
+                .join()
+                .unwrap_or_else(|_| Err(Error::internal("vault request panicked")))
+        })?;
+
+        let data = if self.kv_version == 2 {
+            &body["data"]["data"]
+        } else {
+            &body["data"]
+        };
+        match data.get(key) {
+            Some(Value::String(s)) => Ok(s.clone()),
+            Some(Value::Null) | None => Err(Error::config(format!(
+                "vault secret {} has no key {}",
+                path, key
+            ))),
+            Some(other) => Ok(other.to_string()),
+        }
+    }
+}
+
+/// Resolvers by scheme.
+///
+/// The default set resolves `env` and `file` references, and `vault` ones
+/// with the `vault` feature (configured from `VAULT_ADDR` and `VAULT_TOKEN`
+/// when the first vault reference is resolved).
+#[derive(Clone)]
+pub struct SecretResolvers {
+    resolvers: HashMap<String, Arc<dyn SecretResolver>>,
+}
+
+impl Default for SecretResolvers {
+    fn default() -> Self {
+        let resolvers = Self::empty()
+            .with_resolver("env", EnvResolver)
+            .with_resolver("file", FileResolver);
+        #[cfg(feature = "vault")]
+        let resolvers = resolvers.with_resolver("vault", LazyVault::default());
+        resolvers
+    }
+}
+
+impl std::fmt::Debug for SecretResolvers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut schemes: Vec<_> = self.resolvers.keys().collect();
+        schemes.sort();
+        f.debug_struct("SecretResolvers")
+            .field("schemes", &schemes)
+            .finish()
+    }
+}
+
+impl SecretResolvers {
+    /// A set without any resolver; every reference fails to resolve.
+    pub fn empty() -> Self {
+        Self {
+            resolvers: HashMap::new(),
+        }
+    }
+
+    /// Resolve `${scheme:...}` references with `resolver`, replacing any
+    /// resolver registered for `scheme`.
+    pub fn with_resolver(
+        mut self,
+        scheme: impl Into<String>,
+        resolver: impl SecretResolver + 'static,
+    ) -> Self {
+        self.resolvers.insert(scheme.into(), Arc::new(resolver));
+        self
+    }
+
+    /// Replace every reference in `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] for an unterminated reference, a scheme
+    /// without resolver, or a reference its resolver fails on.
+    pub fn resolve_str(&self, value: &str) -> Result<String> {
+        let mut out = String::with_capacity(value.len());
+        let mut rest = value;
+
+        while let Some(start) = rest.find('$') {
+            out.push_str(&rest[..start]);
+            let tail = &rest[start..];
+
+            if let Some(escaped) = tail.strip_prefix("$${") {
+                out.push_str("${");
+                rest = escaped;
+                continue;
+            }
+
+            let Some(body) = tail.strip_prefix("${") else {
+                out.push('$');
+                rest = &tail[1..];
+                continue;
+            };
+            let Some((scheme, reference)) = Self::split_reference(body) else {
+                out.push_str("${");
+                rest = body;
+                continue;
+            };
+            let Some(end) = reference.find('}') else {
+                return Err(Error::config(format!(
+                    "unterminated secret reference ${{{}:...",
+                    scheme
+                )));
+            };
+
+            let resolver = self.resolvers.get(scheme).ok_or_else(|| {
+                Error::config(format!("no resolver for secret scheme {}", scheme))
+            })?;
+            out.push_str(&resolver.resolve(&reference[..end])?);
+            rest = &reference[end + 1..];
+        }
+
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    /// Resolve the references in every string of `value`, returning whether
+    /// any string changed.
+    pub fn resolve_value(&self, value: &mut Value) -> Result<bool> {
+        match value {
+            Value::String(s) if s.contains("${") => {
+                let resolved = self.resolve_str(s)?;
+                let changed = resolved != *s;
+                *s = resolved;
+                Ok(changed)
+            }
+            Value::Array(items) => items.iter_mut().try_fold(false, |changed, item| {
+                Ok(self.resolve_value(item)? || changed)
+            }),
+            Value::Object(map) => map.values_mut().try_fold(false, |changed, item| {
+                Ok(self.resolve_value(item)? || changed)
+            }),
+            _ => Ok(false),
+        }
+    }
+
+    /// Resolve the references in every string field of `config`.
+    ///
+    /// Only string fields can hold references, since `config` is already
+    /// typed.
+    pub fn resolve_config<T: Serialize + DeserializeOwned>(&self, config: T) -> Result<T> {
+        let mut value = serde_json::to_value(&config)?;
+        if !self.resolve_value(&mut value)? {
+            return Ok(config);
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Split `scheme:rest` off a reference body, if it starts with a scheme.
+    fn split_reference(body: &str) -> Option<(&str, &str)> {
+        let (scheme, rest) = body.split_once(':')?;
+        let valid = !scheme.is_empty()
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+        valid.then_some((scheme, rest))
+    }
+}
+
+/// Vault resolver configured from the environment on first use, so that
+/// configurations without vault references do not need `VAULT_ADDR`.
+#[cfg(feature = "vault")]
+#[derive(Default)]
+struct LazyVault(std::sync::OnceLock<std::result::Result<VaultResolver, String>>);
+
+#[cfg(feature = "vault")]
+impl SecretResolver for LazyVault {
+    fn resolve(&self, reference: &str) -> Result<String> {
+        match self
+            .0
+            .get_or_init(|| VaultResolver::from_env().map_err(|e| e.to_string()))
+        {
+            Ok(vault) => vault.resolve(reference),
+            Err(e) => Err(Error::config(e.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed;
+
+    impl SecretResolver for Fixed {
+        fn resolve(&self, reference: &str) -> Result<String> {
+            match reference {
+                "db#password" => Ok("s3cret".to_string()),
+                other => Err(Error::config(format!("unknown secret {}", other))),
+            }
+        }
+    }
+
+    #[test]
+    fn test_resolve_str() {
+        std::env::set_var("LLMOBS_TEST_SECRET_USER", "app");
+        let secrets = SecretResolvers::default().with_resolver("vault", Fixed);
+
+        assert_eq!(
+            secrets
+                .resolve_str("pg://${env:LLMOBS_TEST_SECRET_USER}:${vault:db#password}@h")
+                .unwrap(),
+            "pg://app:s3cret@h"
+        );
+        assert_eq!(secrets.resolve_str("plain $5").unwrap(), "plain $5");
+        assert_eq!(secrets.resolve_str("${HOME}").unwrap(), "${HOME}");
+        assert_eq!(secrets.resolve_str("$${env:X}").unwrap(), "${env:X}");
+
+        let err = secrets.resolve_str("${aws:key}").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("no resolver for secret scheme aws"));
+        assert!(secrets.resolve_str("${env:OPEN").is_err());
+        assert!(secrets
+            .resolve_str("${env:LLMOBS_TEST_SECRET_UNSET}")
+            .is_err());
+        assert!(secrets.resolve_str("${vault:db#user}").is_err());
+    }
+
+    #[test]
+    fn test_file_resolver_trims_newline() {
+        let path = std::env::temp_dir().join(format!("llmobs-secret-{}", std::process::id()));
+        std::fs::write(&path, "token-123\n").unwrap();
+
+        let reference = format!("${{file:{}}}", path.display());
+        let resolved = SecretResolvers::default().resolve_str(&reference);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(resolved.unwrap(), "token-123");
+    }
+
+    #[test]
+    fn test_resolve_config() {
+        #[derive(Debug, PartialEq, Serialize, serde::Deserialize)]
+        struct Config {
+            port: u16,
+            password: String,
+            headers: HashMap<String, String>,
+        }
+
+        let secrets = SecretResolvers::empty().with_resolver("vault", Fixed);
+        let config = Config {
+            port: 5432,
+            password: "${vault:db#password}".to_string(),
+            headers: HashMap::from([(
+                "auth".to_string(),
+                "Bearer ${vault:db#password}".to_string(),
+            )]),
+        };
+
+        let resolved = secrets.resolve_config(config).unwrap();
+        assert_eq!(resolved.port, 5432);
+        assert_eq!(resolved.password, "s3cret");
+        assert_eq!(resolved.headers["auth"], "Bearer s3cret");
+    }
+
+    #[cfg(feature = "vault")]
+    #[test]
+    fn test_vault_urls() {
+        let vault = VaultResolver::new("https://vault:8200/", "t");
+        assert_eq!(
+            vault.url("kv/llmobs/db"),
+            "https://vault:8200/v1/kv/data/llmobs/db"
+        );
+        assert_eq!(
+            vault.with_kv_v1().url("kv/llmobs/db"),
+            "https://vault:8200/v1/kv/llmobs/db"
+        );
+    }
+}
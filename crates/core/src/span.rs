@@ -43,6 +43,9 @@ pub struct LlmSpan {
     /// Events recorded during span
     #[serde(default)]
     pub events: Vec<SpanEvent>,
+    /// Links to causally related spans, e.g. in other traces
+    #[serde(default)]
+    pub links: Vec<SpanLink>,
 }
 
 /// LLM input (prompt).
@@ -141,6 +144,33 @@ pub struct SpanEvent {
     pub attributes: HashMap<String, serde_json::Value>,
 }
 
+/// Link from a span to a causally related span.
+///
+/// Links relate spans that are not parent and child, most often across
+/// traces: an agent fanning out work to sub-agents, a batch job processing
+/// requests, or a retry of a failed attempt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpanLink {
+    /// Trace identifier of the linked span
+    pub trace_id: TraceId,
+    /// Linked span identifier
+    pub span_id: SpanId,
+    /// Link attributes
+    #[serde(default)]
+    pub attributes: HashMap<String, serde_json::Value>,
+}
+
+impl SpanLink {
+    /// Create a link to a span.
+    pub fn new(trace_id: impl Into<TraceId>, span_id: impl Into<SpanId>) -> Self {
+        Self {
+            trace_id: trace_id.into(),
+            span_id: span_id.into(),
+            attributes: HashMap::new(),
+        }
+    }
+}
+
 impl LlmSpan {
     /// Create a new LLM span builder.
     pub fn builder() -> LlmSpanBuilder {
@@ -191,6 +221,7 @@ pub struct LlmSpanBuilder {
     status: SpanStatus,
     attributes: HashMap<String, serde_json::Value>,
     events: Vec<SpanEvent>,
+    links: Vec<SpanLink>,
 }
 
 impl LlmSpanBuilder {
@@ -278,12 +309,27 @@ impl LlmSpanBuilder {
         self
     }
 
+    /// Add a cost-allocation tag (see [`crate::cost_tags`]).
+    pub fn cost_tag(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.attributes.insert(
+            crate::cost_tags::cost_tag_attribute(key),
+            serde_json::Value::String(value.into()),
+        );
+        self
+    }
+
     /// Add an event.
     pub fn event(mut self, event: SpanEvent) -> Self {
         self.events.push(event);
         self
     }
 
+    /// Add a link.
+    pub fn link(mut self, link: SpanLink) -> Self {
+        self.links.push(link);
+        self
+    }
+
     /// Build the LlmSpan.
     pub fn build(self) -> Result<LlmSpan, &'static str> {
         Ok(LlmSpan {
@@ -302,6 +348,7 @@ impl LlmSpanBuilder {
             status: self.status,
             attributes: self.attributes,
             events: self.events,
+            links: self.links,
         })
     }
 }
@@ -334,5 +381,31 @@ mod tests {
         assert_eq!(span.trace_id, "trace_456");
         assert_eq!(span.provider, Provider::OpenAI);
         assert!(span.is_success());
+        assert!(span.links.is_empty());
+    }
+
+    #[test]
+    fn test_links_default_when_missing() {
+        let now = Utc::now();
+        let span = LlmSpan::builder()
+            .span_id("span_123")
+            .trace_id("trace_456")
+            .name("llm.completion")
+            .provider(Provider::OpenAI)
+            .model("gpt-4")
+            .input(LlmInput::Text {
+                prompt: "Hello".to_string(),
+            })
+            .latency(Latency::new(now, now))
+            .link(SpanLink::new("trace_789", "span_012"))
+            .build()
+            .unwrap();
+
+        let mut json = serde_json::to_value(&span).unwrap();
+        assert_eq!(json["links"][0]["trace_id"], "trace_789");
+
+        json.as_object_mut().unwrap().remove("links");
+        let span: LlmSpan = serde_json::from_value(json).unwrap();
+        assert!(span.links.is_empty());
     }
 }
@@ -0,0 +1,198 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Span event and attribute conventions for provider queue time.
+//!
+//! An LLM call is split into phases by span events recorded as they happen:
+//!
+//! | Event                     | Recorded when                                   |
+//! |---------------------------|-------------------------------------------------|
+//! | `llm.request.sent`        | The request left the client, after interceptors |
+//! | `llm.response.first_byte` | The provider's response headers arrived         |
+//! | `llm.first_token`         | The first streamed token arrived                |
+//!
+//! The span start and end bound the call. [`SpanTiming`] derives the time
+//! spent in each phase and records it as attributes:
+//!
+//! | Attribute                             | Type | Description                                                        |
+//! |---------------------------------------|------|--------------------------------------------------------------------|
+//! | `llm_observatory.timing.client_ms`    | int  | Span start to request sent: client-side overhead                   |
+//! | `llm_observatory.timing.queue_ms`     | int  | Request sent to first byte: provider queuing and prompt processing |
+//! | `llm_observatory.timing.execution_ms` | int  | First byte to completion: generation and transfer                  |
+//!
+//! Without a first byte event the first token stands in for it. Events
+//! outside the span, e.g. from a skewed clock, are ignored.
+
+use crate::span::LlmSpan;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Event recorded when the request left the client.
+pub const REQUEST_SENT_EVENT: &str = "llm.request.sent";
+
+/// Event recorded when the provider's response headers arrived.
+pub const FIRST_BYTE_EVENT: &str = "llm.response.first_byte";
+
+/// Event recorded when the first streamed token arrived.
+pub const FIRST_TOKEN_EVENT: &str = "llm.first_token";
+
+/// Client-side overhead attribute, in milliseconds.
+pub const TIMING_CLIENT_MS: &str = "llm_observatory.timing.client_ms";
+
+/// Provider queue time attribute, in milliseconds.
+pub const TIMING_QUEUE_MS: &str = "llm_observatory.timing.queue_ms";
+
+/// Provider execution time attribute, in milliseconds.
+pub const TIMING_EXECUTION_MS: &str = "llm_observatory.timing.execution_ms";
+
+/// Time spent in each phase of an LLM call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpanTiming {
+    /// Span start to request sent; unknown without a request sent event
+    pub client_ms: Option<u64>,
+    /// Request sent to first byte
+    pub queue_ms: u64,
+    /// First byte to completion
+    pub execution_ms: u64,
+    /// Span start to first token; unknown without a first token event
+    pub ttft_ms: Option<u64>,
+}
+
+impl SpanTiming {
+    /// Derive the phases of a span from its events.
+    ///
+    /// Returns `None` without a first byte or first token event, as the
+    /// provider's queue time cannot be told from its execution time.
+    pub fn from_span(span: &LlmSpan) -> Option<Self> {
+        let start = span.latency.start_time;
+        let end = span.latency.end_time;
+        let event_time = |name: &str| {
+            span.events
+                .iter()
+                .filter(|event| event.name == name)
+                .map(|event| event.timestamp)
+                .filter(|ts| *ts >= start && *ts <= end)
+                .min()
+        };
+
+        let sent = event_time(REQUEST_SENT_EVENT);
+        let first_token = event_time(FIRST_TOKEN_EVENT);
+        // Headers arrive before the first token of their body
+        let first_byte = match (event_time(FIRST_BYTE_EVENT), first_token) {
+            (Some(byte), Some(token)) => byte.min(token),
+            (byte, token) => byte.or(token)?,
+        };
+        // A request cannot be sent after its response arrived
+        let sent = sent.filter(|sent| *sent <= first_byte);
+
+        Some(Self {
+            client_ms: sent.map(|sent| millis(start, sent)),
+            queue_ms: millis(sent.unwrap_or(start), first_byte),
+            execution_ms: millis(first_byte, end),
+            ttft_ms: first_token.map(|token| millis(start, token)),
+        })
+    }
+
+    /// Span attributes recording the phases.
+    pub fn to_attributes(&self) -> HashMap<String, Value> {
+        let mut attributes = HashMap::new();
+        if let Some(client_ms) = self.client_ms {
+            attributes.insert(TIMING_CLIENT_MS.to_string(), Value::from(client_ms));
+        }
+        attributes.insert(TIMING_QUEUE_MS.to_string(), Value::from(self.queue_ms));
+        attributes.insert(
+            TIMING_EXECUTION_MS.to_string(),
+            Value::from(self.execution_ms),
+        );
+        attributes
+    }
+}
+
+fn millis(from: DateTime<Utc>, to: DateTime<Utc>) -> u64 {
+    (to - from).num_milliseconds().max(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::span::{LlmInput, SpanEvent, SpanStatus};
+    use crate::types::{Latency, Provider};
+    use chrono::Duration;
+
+    fn span(events: &[(&str, i64)]) -> LlmSpan {
+        let start = Utc::now();
+        LlmSpan {
+            span_id: "span-1".to_string(),
+            trace_id: "trace-1".to_string(),
+            parent_span_id: None,
+            name: "llm.chat".to_string(),
+            provider: Provider::OpenAI,
+            model: "gpt-4o".to_string(),
+            input: LlmInput::Text {
+                prompt: "Test".to_string(),
+            },
+            output: None,
+            token_usage: None,
+            cost: None,
+            latency: Latency::new(start, start + Duration::milliseconds(1000)),
+            metadata: Default::default(),
+            status: SpanStatus::Ok,
+            attributes: Default::default(),
+            events: events
+                .iter()
+                .map(|(name, offset_ms)| SpanEvent {
+                    name: name.to_string(),
+                    timestamp: start + Duration::milliseconds(*offset_ms),
+                    attributes: Default::default(),
+                })
+                .collect(),
+            links: vec![],
+        }
+    }
+
+    #[test]
+    fn test_from_span() {
+        let timing = SpanTiming::from_span(&span(&[
+            (REQUEST_SENT_EVENT, 20),
+            (FIRST_BYTE_EVENT, 320),
+            (FIRST_TOKEN_EVENT, 400),
+        ]))
+        .unwrap();
+        assert_eq!(
+            timing,
+            SpanTiming {
+                client_ms: Some(20),
+                queue_ms: 300,
+                execution_ms: 680,
+                ttft_ms: Some(400),
+            }
+        );
+        assert_eq!(timing.to_attributes()[TIMING_QUEUE_MS], Value::from(300));
+    }
+
+    #[test]
+    fn test_first_token_stands_in_for_first_byte() {
+        let timing = SpanTiming::from_span(&span(&[(FIRST_TOKEN_EVENT, 250)])).unwrap();
+        assert_eq!(timing.client_ms, None);
+        assert_eq!(timing.queue_ms, 250);
+        assert_eq!(timing.execution_ms, 750);
+        assert!(!timing.to_attributes().contains_key(TIMING_CLIENT_MS));
+    }
+
+    #[test]
+    fn test_ignores_events_outside_span() {
+        assert_eq!(SpanTiming::from_span(&span(&[])), None);
+        assert_eq!(
+            SpanTiming::from_span(&span(&[(FIRST_BYTE_EVENT, 1500)])),
+            None
+        );
+
+        let timing =
+            SpanTiming::from_span(&span(&[(REQUEST_SENT_EVENT, -50), (FIRST_BYTE_EVENT, 100)]))
+                .unwrap();
+        assert_eq!(timing.client_ms, None);
+        assert_eq!(timing.queue_ms, 100);
+    }
+}
@@ -0,0 +1,231 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Load generation configuration.
+
+use std::time::Duration;
+
+/// Configuration errors.
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ConfigError {
+    /// Model mix entry could not be parsed
+    #[error("Invalid model mix entry '{0}': expected provider/model=weight")]
+    InvalidModelMix(String),
+
+    /// A setting is out of range
+    #[error("Invalid configuration: {0}")]
+    Invalid(String),
+}
+
+/// A model and its share of generated traffic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelWeight {
+    /// Provider name (e.g. "openai")
+    pub provider: String,
+    /// Model name (e.g. "gpt-4o")
+    pub model: String,
+    /// Relative weight
+    pub weight: f64,
+}
+
+/// Weighted mix of models to generate traffic for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelMix {
+    models: Vec<ModelWeight>,
+}
+
+impl ModelMix {
+    /// Parse a mix like `openai/gpt-4o=0.6,anthropic/claude-3-5-haiku-20241022=0.4`.
+    ///
+    /// Weights are relative and need not sum to 1. An entry without a weight
+    /// defaults to 1.
+    pub fn parse(input: &str) -> Result<Self, ConfigError> {
+        let mut models = Vec::new();
+
+        for entry in input.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, weight) = match entry.split_once('=') {
+                Some((name, weight)) => {
+                    let weight: f64 = weight
+                        .trim()
+                        .parse()
+                        .map_err(|_| ConfigError::InvalidModelMix(entry.to_string()))?;
+                    (name.trim(), weight)
+                }
+                None => (entry, 1.0),
+            };
+
+            let (provider, model) = name
+                .split_once('/')
+                .filter(|(p, m)| !p.is_empty() && !m.is_empty())
+                .ok_or_else(|| ConfigError::InvalidModelMix(entry.to_string()))?;
+
+            if !weight.is_finite() || weight <= 0.0 {
+                return Err(ConfigError::InvalidModelMix(entry.to_string()));
+            }
+
+            models.push(ModelWeight {
+                provider: provider.to_string(),
+                model: model.to_string(),
+                weight,
+            });
+        }
+
+        if models.is_empty() {
+            return Err(ConfigError::Invalid("model mix is empty".to_string()));
+        }
+
+        Ok(Self { models })
+    }
+
+    /// Models in the mix.
+    pub fn models(&self) -> &[ModelWeight] {
+        &self.models
+    }
+
+    /// Sum of all weights.
+    pub fn total_weight(&self) -> f64 {
+        self.models.iter().map(|m| m.weight).sum()
+    }
+}
+
+impl Default for ModelMix {
+    fn default() -> Self {
+        Self::parse(
+            "openai/gpt-4o-mini=0.45,openai/gpt-4o=0.2,\
+             anthropic/claude-3-5-sonnet-20241022=0.2,anthropic/claude-3-5-haiku-20241022=0.1,\
+             google/gemini-1.5-flash=0.05",
+        )
+        .expect("default model mix is valid")
+    }
+}
+
+/// Log-normal token count distribution.
+///
+/// Real prompt and completion lengths are right-skewed; a log-normal with a
+/// given median and shape reproduces the long tail of large requests.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenDistribution {
+    /// Median token count
+    pub median: f64,
+    /// Shape parameter (standard deviation of the underlying normal)
+    pub sigma: f64,
+    /// Upper bound on sampled values
+    pub max: u32,
+}
+
+impl TokenDistribution {
+    /// Create a distribution.
+    pub fn new(median: f64, sigma: f64, max: u32) -> Self {
+        Self { median, sigma, max }
+    }
+}
+
+/// Load generation settings.
+#[derive(Debug, Clone)]
+pub struct LoadConfig {
+    /// Target LLM spans per second
+    pub spans_per_second: f64,
+    /// How long to generate traffic
+    pub duration: Duration,
+    /// Weighted model mix
+    pub model_mix: ModelMix,
+    /// Fraction of LLM calls that fail (0.0-1.0)
+    pub error_rate: f64,
+    /// Prompt token distribution
+    pub prompt_tokens: TokenDistribution,
+    /// Completion token distribution
+    pub completion_tokens: TokenDistribution,
+    /// LLM spans per trace; above 1, spans are grouped under a root span
+    pub spans_per_trace: usize,
+    /// Number of distinct synthetic users
+    pub users: usize,
+    /// `service.name` resource attribute
+    pub service_name: String,
+    /// `deployment.environment` resource attribute
+    pub environment: String,
+}
+
+impl Default for LoadConfig {
+    fn default() -> Self {
+        Self {
+            spans_per_second: 100.0,
+            duration: Duration::from_secs(60),
+            model_mix: ModelMix::default(),
+            error_rate: 0.02,
+            prompt_tokens: TokenDistribution::new(600.0, 0.9, 128_000),
+            completion_tokens: TokenDistribution::new(180.0, 0.8, 16_000),
+            spans_per_trace: 1,
+            users: 500,
+            service_name: "llm-observatory-loadgen".to_string(),
+            environment: "loadtest".to_string(),
+        }
+    }
+}
+
+impl LoadConfig {
+    /// Validate settings.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !self.spans_per_second.is_finite() || self.spans_per_second <= 0.0 {
+            return Err(ConfigError::Invalid("spans per second must be positive".to_string()));
+        }
+        if !(0.0..=1.0).contains(&self.error_rate) {
+            return Err(ConfigError::Invalid("error rate must be between 0 and 1".to_string()));
+        }
+        if self.spans_per_trace == 0 {
+            return Err(ConfigError::Invalid("spans per trace must be at least 1".to_string()));
+        }
+        if self.users == 0 {
+            return Err(ConfigError::Invalid("users must be at least 1".to_string()));
+        }
+        for (name, dist) in [("prompt", &self.prompt_tokens), ("completion", &self.completion_tokens)] {
+            if dist.median < 1.0 || dist.sigma < 0.0 || dist.max == 0 {
+                return Err(ConfigError::Invalid(format!(
+                    "{} token distribution needs median >= 1, sigma >= 0 and max > 0",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_model_mix() {
+        let mix = ModelMix::parse("openai/gpt-4o=3, anthropic/claude-3-5-haiku-20241022").unwrap();
+        assert_eq!(mix.models().len(), 2);
+        assert_eq!(mix.models()[0].provider, "openai");
+        assert_eq!(mix.models()[0].model, "gpt-4o");
+        assert_eq!(mix.models()[1].weight, 1.0);
+        assert_eq!(mix.total_weight(), 4.0);
+    }
+
+    #[test]
+    fn test_parse_model_mix_errors() {
+        assert!(ModelMix::parse("").is_err());
+        assert!(ModelMix::parse("gpt-4o=1").is_err());
+        assert!(ModelMix::parse("openai/gpt-4o=abc").is_err());
+        assert!(ModelMix::parse("openai/gpt-4o=0").is_err());
+        assert!(ModelMix::parse("/gpt-4o=1").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(LoadConfig::default().validate().is_ok());
+
+        let config = LoadConfig {
+            error_rate: 1.5,
+            ..LoadConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = LoadConfig {
+            spans_per_trace: 0,
+            ..LoadConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,232 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Synthetic LLM call generation.
+//!
+//! Generates the shape of an LLM call (model, token counts, latency, outcome)
+//! independently of how it is exported, so the distributions can be tested
+//! with a seeded RNG.
+
+use crate::config::{LoadConfig, ModelWeight, TokenDistribution};
+use rand::Rng;
+use std::f64::consts::PI;
+
+/// Error types generated for failed calls, with relative weights.
+const ERROR_TYPES: &[(&str, f64)] = &[
+    ("rate_limit_exceeded", 0.5),
+    ("server_error", 0.3),
+    ("timeout", 0.2),
+];
+
+/// Latency reported for timed-out calls.
+const TIMEOUT_LATENCY_MS: u64 = 30_000;
+
+/// A synthetic LLM call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntheticCall {
+    /// Provider name
+    pub provider: String,
+    /// Model name
+    pub model: String,
+    /// Prompt tokens
+    pub prompt_tokens: u32,
+    /// Completion tokens (0 for failed calls)
+    pub completion_tokens: u32,
+    /// Call duration in milliseconds
+    pub latency_ms: u64,
+    /// Time to first token in milliseconds
+    pub ttft_ms: u64,
+    /// Error type for failed calls
+    pub error: Option<&'static str>,
+    /// Synthetic user ID
+    pub user_id: String,
+}
+
+/// Generator of synthetic LLM calls.
+pub struct TrafficGenerator<R: Rng> {
+    config: LoadConfig,
+    rng: R,
+}
+
+impl<R: Rng> TrafficGenerator<R> {
+    /// Create a generator using the given RNG.
+    pub fn new(config: LoadConfig, rng: R) -> Self {
+        Self { config, rng }
+    }
+
+    /// Generate the next call.
+    pub fn next_call(&mut self) -> SyntheticCall {
+        let model = self.pick_model().clone();
+        let prompt_tokens = self.sample_tokens(self.config.prompt_tokens);
+        let user_id = format!("user-{}", self.rng.gen_range(0..self.config.users));
+
+        let ttft_ms = (base_ttft_ms(&model.provider) * self.jitter()).round() as u64;
+
+        if self.rng.gen_bool(self.config.error_rate) {
+            let error = self.pick_error();
+            let latency_ms = if error == "timeout" {
+                TIMEOUT_LATENCY_MS
+            } else {
+                ttft_ms
+            };
+
+            return SyntheticCall {
+                provider: model.provider,
+                model: model.model,
+                prompt_tokens,
+                completion_tokens: 0,
+                latency_ms,
+                ttft_ms: latency_ms,
+                error: Some(error),
+                user_id,
+            };
+        }
+
+        let completion_tokens = self.sample_tokens(self.config.completion_tokens);
+        let decode_ms = completion_tokens as f64 * ms_per_output_token(&model.model) * self.jitter();
+
+        SyntheticCall {
+            provider: model.provider,
+            model: model.model,
+            prompt_tokens,
+            completion_tokens,
+            latency_ms: ttft_ms + decode_ms.round() as u64,
+            ttft_ms,
+            error: None,
+            user_id,
+        }
+    }
+
+    fn pick_model(&mut self) -> &ModelWeight {
+        let mix = &self.config.model_mix;
+        let mut target = self.rng.gen_range(0.0..mix.total_weight());
+        for model in mix.models() {
+            if target < model.weight {
+                return model;
+            }
+            target -= model.weight;
+        }
+        // Floating-point rounding can leave a tiny remainder
+        mix.models().last().expect("model mix is non-empty")
+    }
+
+    fn pick_error(&mut self) -> &'static str {
+        let total: f64 = ERROR_TYPES.iter().map(|(_, w)| w).sum();
+        let mut target = self.rng.gen_range(0.0..total);
+        for (error, weight) in ERROR_TYPES {
+            if target < *weight {
+                return error;
+            }
+            target -= weight;
+        }
+        ERROR_TYPES[0].0
+    }
+
+    /// Sample from a log-normal distribution, clamped to `[1, max]`.
+    fn sample_tokens(&mut self, dist: TokenDistribution) -> u32 {
+        let value = dist.median * (dist.sigma * self.standard_normal()).exp();
+        (value.round() as u32).clamp(1, dist.max)
+    }
+
+    /// Multiplicative latency jitter (log-normal, median 1).
+    fn jitter(&mut self) -> f64 {
+        (0.25 * self.standard_normal()).exp()
+    }
+
+    /// Standard normal sample (Box-Muller).
+    fn standard_normal(&mut self) -> f64 {
+        let u1: f64 = self.rng.gen_range(f64::EPSILON..1.0);
+        let u2: f64 = self.rng.gen();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+    }
+}
+
+/// Typical time to first token for a provider.
+fn base_ttft_ms(provider: &str) -> f64 {
+    match provider {
+        "anthropic" => 450.0,
+        "google" => 350.0,
+        _ => 400.0,
+    }
+}
+
+/// Typical decode time per output token for a model.
+fn ms_per_output_token(model: &str) -> f64 {
+    if model.contains("mini") || model.contains("haiku") || model.contains("flash") {
+        8.0
+    } else {
+        20.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ModelMix;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn generator(config: LoadConfig) -> TrafficGenerator<StdRng> {
+        TrafficGenerator::new(config, StdRng::seed_from_u64(42))
+    }
+
+    #[test]
+    fn test_model_mix_is_respected() {
+        let config = LoadConfig {
+            model_mix: ModelMix::parse("openai/gpt-4o=3,anthropic/claude-3-5-haiku-20241022=1").unwrap(),
+            ..LoadConfig::default()
+        };
+        let mut gen = generator(config);
+
+        let gpt = (0..10_000)
+            .filter(|_| gen.next_call().model == "gpt-4o")
+            .count();
+        assert!((7_000..8_000).contains(&gpt), "gpt-4o share was {}", gpt);
+    }
+
+    #[test]
+    fn test_error_rate_is_respected() {
+        let config = LoadConfig {
+            error_rate: 0.1,
+            ..LoadConfig::default()
+        };
+        let mut gen = generator(config);
+
+        let calls: Vec<_> = (0..10_000).map(|_| gen.next_call()).collect();
+        let errors = calls.iter().filter(|c| c.error.is_some()).count();
+        assert!((800..1_200).contains(&errors), "errors: {}", errors);
+        assert!(calls
+            .iter()
+            .filter(|c| c.error.is_some())
+            .all(|c| c.completion_tokens == 0));
+    }
+
+    #[test]
+    fn test_token_distribution_median_and_bounds() {
+        let config = LoadConfig {
+            error_rate: 0.0,
+            prompt_tokens: TokenDistribution::new(500.0, 1.0, 2_000),
+            ..LoadConfig::default()
+        };
+        let mut gen = generator(config);
+
+        let mut prompts: Vec<u32> = (0..10_001).map(|_| gen.next_call().prompt_tokens).collect();
+        prompts.sort_unstable();
+
+        let median = prompts[prompts.len() / 2];
+        assert!((450..550).contains(&median), "median: {}", median);
+        assert!(*prompts.last().unwrap() <= 2_000);
+        assert!(*prompts.first().unwrap() >= 1);
+    }
+
+    #[test]
+    fn test_latency_includes_ttft() {
+        let mut gen = generator(LoadConfig {
+            error_rate: 0.0,
+            ..LoadConfig::default()
+        });
+        for _ in 0..100 {
+            let call = gen.next_call();
+            assert!(call.latency_ms >= call.ttft_ms);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! # LLM Observatory Load Generator
+//!
+//! Synthesizes realistic LLM trace traffic and sends it to the collector over
+//! OTLP, for load testing the ingestion pipeline and populating dashboards.
+//!
+//! Traffic is shaped by a [`LoadConfig`]: target spans per second, a weighted
+//! model mix, an error rate, and log-normal prompt/completion token
+//! distributions.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use llm_observatory_loadgen::{run, LoadConfig};
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let config = LoadConfig {
+//!     spans_per_second: 500.0,
+//!     ..LoadConfig::default()
+//! };
+//! let stats = run(&config, "http://localhost:4317").await?;
+//! println!("{:.1} spans/s", stats.spans_per_second());
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod config;
+pub mod generator;
+pub mod runner;
+
+pub use config::{ConfigError, LoadConfig, ModelMix, ModelWeight, TokenDistribution};
+pub use generator::{SyntheticCall, TrafficGenerator};
+pub use runner::{run, RunStats};
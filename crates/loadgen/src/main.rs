@@ -0,0 +1,111 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! `loadgen` command-line entry point.
+
+use clap::Parser;
+use llm_observatory_loadgen::{run, LoadConfig, ModelMix, TokenDistribution};
+use std::time::Duration;
+
+/// Send synthetic LLM trace traffic to an OTLP collector.
+#[derive(Debug, Parser)]
+#[command(name = "loadgen", version, about)]
+struct Args {
+    /// OTLP gRPC endpoint of the collector
+    #[arg(long, env = "OTLP_ENDPOINT", default_value = "http://localhost:4317")]
+    endpoint: String,
+
+    /// Target LLM spans per second
+    #[arg(long, env = "LOADGEN_RATE", default_value_t = 100.0)]
+    rate: f64,
+
+    /// How long to run, in seconds
+    #[arg(long, env = "LOADGEN_DURATION_SECS", default_value_t = 60)]
+    duration_secs: u64,
+
+    /// Weighted model mix, e.g. "openai/gpt-4o=0.6,anthropic/claude-3-5-haiku-20241022=0.4"
+    #[arg(long, env = "LOADGEN_MODEL_MIX")]
+    model_mix: Option<String>,
+
+    /// Fraction of calls that fail (0.0-1.0)
+    #[arg(long, env = "LOADGEN_ERROR_RATE", default_value_t = 0.02)]
+    error_rate: f64,
+
+    /// Median prompt tokens
+    #[arg(long, default_value_t = 600.0)]
+    prompt_tokens_median: f64,
+
+    /// Median completion tokens
+    #[arg(long, default_value_t = 180.0)]
+    completion_tokens_median: f64,
+
+    /// Log-normal shape for token counts; larger values give a longer tail
+    #[arg(long, default_value_t = 0.9)]
+    token_sigma: f64,
+
+    /// LLM spans per trace (above 1, spans share a root span)
+    #[arg(long, default_value_t = 1)]
+    spans_per_trace: usize,
+
+    /// Number of distinct synthetic users
+    #[arg(long, default_value_t = 500)]
+    users: usize,
+
+    /// `service.name` of the generated traffic
+    #[arg(long, default_value = "llm-observatory-loadgen")]
+    service_name: String,
+
+    /// `deployment.environment` of the generated traffic
+    #[arg(long, default_value = "loadtest")]
+    environment: String,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "info".into()),
+        )
+        .init();
+
+    let args = Args::parse();
+    let defaults = LoadConfig::default();
+
+    let config = LoadConfig {
+        spans_per_second: args.rate,
+        duration: Duration::from_secs(args.duration_secs),
+        model_mix: match args.model_mix {
+            Some(mix) => ModelMix::parse(&mix)?,
+            None => defaults.model_mix,
+        },
+        error_rate: args.error_rate,
+        prompt_tokens: TokenDistribution::new(
+            args.prompt_tokens_median,
+            args.token_sigma,
+            defaults.prompt_tokens.max,
+        ),
+        completion_tokens: TokenDistribution::new(
+            args.completion_tokens_median,
+            args.token_sigma,
+            defaults.completion_tokens.max,
+        ),
+        spans_per_trace: args.spans_per_trace,
+        users: args.users,
+        service_name: args.service_name,
+        environment: args.environment,
+    };
+
+    let stats = run(&config, &args.endpoint).await?;
+
+    println!(
+        "Sent {} spans in {} traces ({} errors) over {:.1}s: {:.1} spans/s",
+        stats.spans,
+        stats.traces,
+        stats.errors,
+        stats.elapsed.as_secs_f64(),
+        stats.spans_per_second()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,200 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! OTLP export of synthetic traffic.
+//!
+//! Spans are emitted at the configured rate and backdated so that each span's
+//! duration matches the generated call latency without the generator having to
+//! wait for it.
+
+use crate::config::LoadConfig;
+use crate::generator::{SyntheticCall, TrafficGenerator};
+use opentelemetry::trace::{Span, SpanKind, Status, TraceContextExt, Tracer, TracerProvider as _};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{
+    BatchConfigBuilder, BatchSpanProcessor, Tracer as SdkTracer, TracerProvider,
+};
+use opentelemetry_sdk::{runtime, Resource};
+use rand::{rngs::StdRng, SeedableRng};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{debug, info};
+
+/// How often the send loop wakes up to emit spans.
+const TICK: Duration = Duration::from_millis(10);
+
+/// Summary of a load generation run.
+#[derive(Debug, Clone, Default)]
+pub struct RunStats {
+    /// LLM spans emitted
+    pub spans: u64,
+    /// Traces emitted
+    pub traces: u64,
+    /// LLM spans marked as failed
+    pub errors: u64,
+    /// Wall-clock duration of the run
+    pub elapsed: Duration,
+}
+
+impl RunStats {
+    /// Achieved LLM spans per second.
+    pub fn spans_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.spans as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Generate traffic for `config.duration` and export it to the OTLP gRPC
+/// `endpoint`.
+pub async fn run(config: &LoadConfig, endpoint: &str) -> anyhow::Result<RunStats> {
+    config.validate()?;
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()?;
+
+    // Size the queue for a few seconds of traffic so bursts are not dropped
+    let queue_size = ((config.spans_per_second * 5.0) as usize).max(2_048);
+    let processor = BatchSpanProcessor::builder(exporter, runtime::Tokio)
+        .with_batch_config(
+            BatchConfigBuilder::default()
+                .with_max_queue_size(queue_size)
+                .with_max_export_batch_size(512)
+                .with_scheduled_delay(Duration::from_millis(500))
+                .with_max_concurrent_exports(4)
+                .build(),
+        )
+        .build();
+
+    let provider = TracerProvider::builder()
+        .with_span_processor(processor)
+        .with_resource(Resource::new(vec![
+            KeyValue::new("service.name", config.service_name.clone()),
+            KeyValue::new("deployment.environment", config.environment.clone()),
+        ]))
+        .build();
+    let tracer = provider.tracer("llm-observatory-loadgen");
+
+    info!(
+        endpoint,
+        rate = config.spans_per_second,
+        duration_secs = config.duration.as_secs(),
+        "Starting load generation"
+    );
+
+    let mut generator = TrafficGenerator::new(config.clone(), StdRng::from_entropy());
+    let mut stats = RunStats::default();
+    let mut interval = tokio::time::interval(TICK);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    let start = Instant::now();
+    let mut budget = 0.0;
+    let mut last_tick = start;
+
+    while start.elapsed() < config.duration {
+        interval.tick().await;
+
+        // Accumulate a fractional span budget so low rates still emit spans
+        let now = Instant::now();
+        budget += now.duration_since(last_tick).as_secs_f64() * config.spans_per_second;
+        last_tick = now;
+
+        while budget >= config.spans_per_trace as f64 {
+            budget -= config.spans_per_trace as f64;
+            let calls: Vec<SyntheticCall> =
+                (0..config.spans_per_trace).map(|_| generator.next_call()).collect();
+            emit_trace(&tracer, &calls);
+
+            stats.traces += 1;
+            stats.spans += calls.len() as u64;
+            stats.errors += calls.iter().filter(|c| c.error.is_some()).count() as u64;
+        }
+
+        debug!(spans = stats.spans, "Load generation progress");
+    }
+    stats.elapsed = start.elapsed();
+
+    for result in provider.force_flush() {
+        result?;
+    }
+    provider.shutdown()?;
+
+    info!(
+        spans = stats.spans,
+        traces = stats.traces,
+        errors = stats.errors,
+        "Load generation finished"
+    );
+
+    Ok(stats)
+}
+
+/// Emit one trace. Calls run back to back and end now; when there is more than
+/// one they are grouped under a root span.
+fn emit_trace(tracer: &SdkTracer, calls: &[SyntheticCall]) {
+    let end = SystemTime::now();
+    let total: u64 = calls.iter().map(|c| c.latency_ms).sum();
+    let trace_start = end - Duration::from_millis(total);
+
+    let parent_cx = if calls.len() > 1 {
+        let root = tracer
+            .span_builder("agent.run")
+            .with_kind(SpanKind::Internal)
+            .with_start_time(trace_start)
+            .with_attributes(vec![KeyValue::new("user.id", calls[0].user_id.clone())])
+            .start(tracer);
+        Context::current_with_span(root)
+    } else {
+        Context::new()
+    };
+
+    let mut call_start = trace_start;
+    for call in calls {
+        let call_end = call_start + Duration::from_millis(call.latency_ms);
+        emit_call(tracer, call, call_start, call_end, &parent_cx);
+        call_start = call_end;
+    }
+
+    if calls.len() > 1 {
+        parent_cx.span().end_with_timestamp(end);
+    }
+}
+
+fn emit_call(
+    tracer: &SdkTracer,
+    call: &SyntheticCall,
+    start: SystemTime,
+    end: SystemTime,
+    parent_cx: &Context,
+) {
+    let mut attributes = vec![
+        KeyValue::new("gen_ai.system", call.provider.clone()),
+        KeyValue::new("gen_ai.request.model", call.model.clone()),
+        KeyValue::new("gen_ai.response.model", call.model.clone()),
+        KeyValue::new("gen_ai.operation.name", "chat"),
+        KeyValue::new("gen_ai.usage.input_tokens", call.prompt_tokens as i64),
+        KeyValue::new("gen_ai.usage.output_tokens", call.completion_tokens as i64),
+        KeyValue::new("llm.latency.ttft_ms", call.ttft_ms as i64),
+        KeyValue::new("user.id", call.user_id.clone()),
+    ];
+    if let Some(error) = call.error {
+        attributes.push(KeyValue::new("error.type", error));
+    }
+
+    let mut span = tracer
+        .span_builder(format!("chat {}", call.model))
+        .with_kind(SpanKind::Client)
+        .with_start_time(start)
+        .with_attributes(attributes)
+        .start_with_context(tracer, parent_cx);
+
+    if let Some(error) = call.error {
+        span.set_status(Status::error(error));
+    }
+    span.end_with_timestamp(end);
+}
@@ -7,7 +7,9 @@
 //! - Claude model information and pricing
 //! - API health checks
 //! - Cost calculation for all Claude models
+//! - Message completions
 
+use crate::completion::{Completion, CompletionProvider, CompletionRequest};
 use llm_observatory_core::{
     provider::{LlmProvider, Pricing},
     Error, Result,
@@ -185,6 +187,78 @@ impl LlmProvider for AnthropicProvider {
     }
 }
 
+/// Body of a messages response.
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+    usage: MessagesUsage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentBlock {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesUsage {
+    input_tokens: u32,
+    output_tokens: u32,
+}
+
+#[async_trait]
+impl CompletionProvider for AnthropicProvider {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion> {
+        let api_key = self
+            .api_key
+            .as_deref()
+            .ok_or_else(|| Error::config("Anthropic API key not set"))?;
+
+        let mut body = serde_json::json!({
+            "model": request.model,
+            "max_tokens": request.max_tokens,
+            "temperature": request.temperature,
+            "messages": [{"role": "user", "content": request.prompt}],
+        });
+        if let Some(system) = &request.system {
+            body["system"] = serde_json::Value::String(system.clone());
+        }
+
+        let response = reqwest::Client::new()
+            .post(format!("{}/v1/messages", self.base_url.trim_end_matches('/')))
+            .header("x-api-key", api_key)
+            .header("anthropic-version", &self.api_version)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| Error::provider(format!("Anthropic request failed: {}", e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::provider(format!("Anthropic returned {}: {}", status, body)));
+        }
+
+        let body: MessagesResponse = response
+            .json()
+            .await
+            .map_err(|e| Error::provider(format!("Invalid Anthropic response: {}", e)))?;
+        let text: String = body
+            .content
+            .into_iter()
+            .filter(|block| block.kind == "text")
+            .filter_map(|block| block.text)
+            .collect();
+
+        Ok(Completion {
+            text,
+            prompt_tokens: body.usage.input_tokens,
+            completion_tokens: body.usage.output_tokens,
+        })
+    }
+}
+
 impl Default for AnthropicProvider {
     fn default() -> Self {
         Self {
@@ -0,0 +1,88 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Text completions from provider APIs.
+//!
+//! Observatory features that need a model themselves (such as translating
+//! natural-language questions into queries) call providers through
+//! [`CompletionProvider`], so the provider and model are configurable.
+
+use async_trait::async_trait;
+use llm_observatory_core::{provider::LlmProvider, Result};
+
+/// Default maximum completion tokens.
+pub const DEFAULT_MAX_TOKENS: u32 = 1024;
+
+/// A single-turn completion request.
+#[derive(Debug, Clone)]
+pub struct CompletionRequest {
+    /// Model to use
+    pub model: String,
+    /// System instructions
+    pub system: Option<String>,
+    /// User message
+    pub prompt: String,
+    /// Maximum completion tokens
+    pub max_tokens: u32,
+    /// Sampling temperature
+    pub temperature: f32,
+}
+
+impl CompletionRequest {
+    /// Create a deterministic request (temperature 0) for `model`.
+    pub fn new(model: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            system: None,
+            prompt: prompt.into(),
+            max_tokens: DEFAULT_MAX_TOKENS,
+            temperature: 0.0,
+        }
+    }
+
+    /// Set system instructions.
+    pub fn with_system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    /// Set maximum completion tokens.
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+}
+
+/// A completion and its token usage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    /// Generated text
+    pub text: String,
+    /// Prompt tokens billed
+    pub prompt_tokens: u32,
+    /// Completion tokens billed
+    pub completion_tokens: u32,
+}
+
+/// A provider that can generate completions.
+#[async_trait]
+pub trait CompletionProvider: LlmProvider {
+    /// Generate a completion.
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_request_builder() {
+        let request = CompletionRequest::new("gpt-4o-mini", "How many requests failed?")
+            .with_system("Answer with JSON")
+            .with_max_tokens(256);
+        assert_eq!(request.model, "gpt-4o-mini");
+        assert_eq!(request.system.as_deref(), Some("Answer with JSON"));
+        assert_eq!(request.max_tokens, 256);
+        assert_eq!(request.temperature, 0.0);
+    }
+}
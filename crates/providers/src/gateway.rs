@@ -0,0 +1,250 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Metadata of requests routed through LLM gateways.
+//!
+//! Gateways such as OpenRouter and the LiteLLM proxy front many upstream
+//! models behind one API. The model a client asks for (`openrouter/auto`, a
+//! LiteLLM model group) is often not the model that served the request, and
+//! the gateway may report what it charged. [`GatewayMetadata`] carries both,
+//! parsed by the gateway providers, and is recorded on spans with the
+//! `llm_observatory.gateway.*` attributes.
+//!
+//! The collector's cost processor prices the upstream model and reconciles
+//! its cost with the gateway-reported one ([`reconcile_cost`]), flagging
+//! discrepancies.
+
+use crate::completion::{Completion, CompletionRequest};
+use llm_observatory_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Gateway that routed the request (`openrouter`, `litellm`).
+pub const GATEWAY_NAME: &str = "llm_observatory.gateway.name";
+
+/// Provider that served the request upstream of the gateway.
+pub const GATEWAY_UPSTREAM_PROVIDER: &str = "llm_observatory.gateway.upstream_provider";
+
+/// Model that served the request upstream of the gateway.
+pub const GATEWAY_UPSTREAM_MODEL: &str = "llm_observatory.gateway.upstream_model";
+
+/// Cost the gateway reported for the request (USD).
+pub const GATEWAY_REPORTED_COST: &str = "llm_observatory.gateway.reported_cost_usd";
+
+/// Gateway's ID of the request (OpenRouter generation ID, LiteLLM call ID).
+pub const GATEWAY_REQUEST_ID: &str = "llm_observatory.gateway.request_id";
+
+/// Computed cost minus gateway-reported cost (USD).
+pub const GATEWAY_COST_DIFFERENCE: &str = "llm_observatory.gateway.cost_difference_usd";
+
+/// Set to `true` when computed and gateway-reported cost disagree.
+pub const GATEWAY_COST_DISCREPANCY: &str = "llm_observatory.gateway.cost_discrepancy";
+
+/// Relative difference above which computed and reported cost disagree.
+pub const DEFAULT_DISCREPANCY_TOLERANCE: f64 = 0.05;
+
+/// Absolute difference (USD) below which costs never disagree, so rounding
+/// on tiny requests is not flagged.
+const MIN_DISCREPANCY_USD: f64 = 0.000_001;
+
+/// LLM gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Gateway {
+    /// OpenRouter (openrouter.ai)
+    OpenRouter,
+    /// LiteLLM proxy
+    LiteLlm,
+}
+
+impl Gateway {
+    /// Gateway name, as recorded on spans.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Gateway::OpenRouter => "openrouter",
+            Gateway::LiteLlm => "litellm",
+        }
+    }
+}
+
+/// What a gateway reported about a request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GatewayMetadata {
+    /// Gateway that routed the request
+    pub gateway: Gateway,
+    /// Provider that served the request, when reported
+    pub upstream_provider: Option<String>,
+    /// Model that served the request, when reported
+    pub upstream_model: Option<String>,
+    /// Cost charged by the gateway (USD), when reported
+    pub reported_cost_usd: Option<f64>,
+    /// Gateway's ID of the request
+    pub request_id: Option<String>,
+}
+
+impl GatewayMetadata {
+    /// Empty metadata for a gateway.
+    pub fn new(gateway: Gateway) -> Self {
+        Self {
+            gateway,
+            upstream_provider: None,
+            upstream_model: None,
+            reported_cost_usd: None,
+            request_id: None,
+        }
+    }
+
+    /// Record the metadata as span attributes.
+    pub fn record(&self, attributes: &mut HashMap<String, serde_json::Value>) {
+        attributes.insert(GATEWAY_NAME.to_string(), self.gateway.as_str().into());
+        let fields = [
+            (GATEWAY_UPSTREAM_PROVIDER, &self.upstream_provider),
+            (GATEWAY_UPSTREAM_MODEL, &self.upstream_model),
+            (GATEWAY_REQUEST_ID, &self.request_id),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value {
+                attributes.insert(key.to_string(), value.as_str().into());
+            }
+        }
+        if let Some(cost) = self.reported_cost_usd {
+            attributes.insert(GATEWAY_REPORTED_COST.to_string(), cost.into());
+        }
+    }
+}
+
+/// Pricing-database name of a gateway model ID: the vendor prefix
+/// (`openai/`, `anthropic/`) and variant suffix (`:free`, `:nitro`) are
+/// removed, e.g. `openai/gpt-4o-mini:free` becomes `gpt-4o-mini`.
+pub fn upstream_model_name(model: &str) -> &str {
+    let model = model.rsplit_once('/').map_or(model, |(_, name)| name);
+    model.split_once(':').map_or(model, |(name, _)| name)
+}
+
+/// Body of an OpenAI-compatible chat completions request.
+pub(crate) fn chat_request_body(request: &CompletionRequest) -> serde_json::Value {
+    let mut messages = Vec::new();
+    if let Some(system) = &request.system {
+        messages.push(serde_json::json!({"role": "system", "content": system}));
+    }
+    messages.push(serde_json::json!({"role": "user", "content": request.prompt}));
+
+    serde_json::json!({
+        "model": request.model,
+        "messages": messages,
+        "max_tokens": request.max_tokens,
+        "temperature": request.temperature,
+    })
+}
+
+/// Completion from the body of an OpenAI-compatible chat completions
+/// response.
+pub(crate) fn completion_from_body(
+    gateway: Gateway,
+    body: &serde_json::Value,
+) -> Result<Completion> {
+    let text = body
+        .pointer("/choices/0/message/content")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| Error::provider(format!("{} response has no content", gateway.as_str())))?;
+    let tokens = |key: &str| {
+        body.pointer(&format!("/usage/{}", key))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as u32
+    };
+
+    Ok(Completion {
+        text: text.to_string(),
+        prompt_tokens: tokens("prompt_tokens"),
+        completion_tokens: tokens("completion_tokens"),
+    })
+}
+
+/// Computed cost of a request compared with what the gateway reported.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CostReconciliation {
+    /// Cost from the pricing database (USD)
+    pub computed_usd: f64,
+    /// Cost reported by the gateway (USD)
+    pub reported_usd: f64,
+    /// Computed minus reported cost (USD)
+    pub difference_usd: f64,
+    /// Difference relative to the reported cost
+    pub relative_difference: f64,
+    /// Whether the difference exceeds the tolerance
+    pub discrepancy: bool,
+}
+
+/// Compare a computed cost with a gateway-reported one. Costs disagree when
+/// they differ by more than `tolerance` (a fraction of the reported cost).
+pub fn reconcile_cost(computed_usd: f64, reported_usd: f64, tolerance: f64) -> CostReconciliation {
+    let difference_usd = computed_usd - reported_usd;
+    let relative_difference = if reported_usd > 0.0 {
+        difference_usd / reported_usd
+    } else if difference_usd == 0.0 {
+        0.0
+    } else {
+        f64::INFINITY.copysign(difference_usd)
+    };
+    CostReconciliation {
+        computed_usd,
+        reported_usd,
+        difference_usd,
+        relative_difference,
+        discrepancy: difference_usd.abs() > MIN_DISCREPANCY_USD
+            && relative_difference.abs() > tolerance,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upstream_model_name() {
+        assert_eq!(upstream_model_name("openai/gpt-4o-mini"), "gpt-4o-mini");
+        assert_eq!(
+            upstream_model_name("meta-llama/llama-3.1-8b-instruct:free"),
+            "llama-3.1-8b-instruct"
+        );
+        assert_eq!(upstream_model_name("gpt-4o"), "gpt-4o");
+    }
+
+    #[test]
+    fn test_reconcile_cost() {
+        let within = reconcile_cost(0.0102, 0.01, DEFAULT_DISCREPANCY_TOLERANCE);
+        assert!(!within.discrepancy);
+        assert!((within.relative_difference - 0.02).abs() < 1e-9);
+
+        let over = reconcile_cost(0.008, 0.01, DEFAULT_DISCREPANCY_TOLERANCE);
+        assert!(over.discrepancy);
+        assert!((over.difference_usd + 0.002).abs() < 1e-12);
+
+        // Free upstream models
+        assert!(!reconcile_cost(0.0, 0.0, DEFAULT_DISCREPANCY_TOLERANCE).discrepancy);
+        assert!(reconcile_cost(0.01, 0.0, DEFAULT_DISCREPANCY_TOLERANCE).discrepancy);
+        // Rounding on tiny requests
+        assert!(
+            !reconcile_cost(0.000_000_2, 0.000_000_1, DEFAULT_DISCREPANCY_TOLERANCE).discrepancy
+        );
+    }
+
+    #[test]
+    fn test_record() {
+        let metadata = GatewayMetadata {
+            upstream_model: Some("anthropic/claude-3.5-sonnet".to_string()),
+            reported_cost_usd: Some(0.0042),
+            ..GatewayMetadata::new(Gateway::OpenRouter)
+        };
+        let mut attributes = HashMap::new();
+        metadata.record(&mut attributes);
+
+        assert_eq!(attributes[GATEWAY_NAME], "openrouter");
+        assert_eq!(
+            attributes[GATEWAY_UPSTREAM_MODEL],
+            "anthropic/claude-3.5-sonnet"
+        );
+        assert_eq!(attributes[GATEWAY_REPORTED_COST], 0.0042);
+        assert!(!attributes.contains_key(GATEWAY_UPSTREAM_PROVIDER));
+    }
+}
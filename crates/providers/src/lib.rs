@@ -4,16 +4,33 @@
 //! LLM provider implementations and pricing engines.
 //!
 //! This crate provides concrete implementations of the `LlmProvider` trait
-//! for various LLM providers (OpenAI, Anthropic, Google, etc.) along with
-//! accurate pricing models based on official provider pricing.
+//! for various LLM providers (OpenAI, Anthropic, Google, etc.) and gateways
+//! (OpenRouter, LiteLLM) along with accurate pricing models based on
+//! official provider pricing.
 
 #![warn(missing_docs, rust_2018_idioms)]
 #![deny(unsafe_code)]
 
 pub mod openai;
 pub mod anthropic;
+pub mod completion;
+pub mod gateway;
+pub mod litellm;
+pub mod openrouter;
 pub mod pricing;
+pub mod registry;
+pub mod self_hosted;
+pub mod tokenizer;
 
 pub use openai::OpenAiProvider;
 pub use anthropic::AnthropicProvider;
-pub use pricing::{PricingEngine, PricingDatabase};
+pub use completion::{Completion, CompletionProvider, CompletionRequest};
+pub use gateway::{reconcile_cost, CostReconciliation, Gateway, GatewayMetadata};
+pub use litellm::LiteLlmProvider;
+pub use openrouter::OpenRouterProvider;
+pub use pricing::{
+    BillableUsage, ModelInfo, Modality, PricingDatabase, PricingDimensions, PricingEngine,
+};
+pub use registry::{CustomPricing, ModelRegistry, RegisteredModel, MODEL_REGISTRY};
+pub use self_hosted::{SelfHostedCostModel, SelfHostedDeployment};
+pub use tokenizer::{TokenCount, TokenCountMethod, TokenCounter};
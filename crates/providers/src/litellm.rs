@@ -0,0 +1,189 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! LiteLLM proxy provider implementation.
+//!
+//! The LiteLLM proxy serves an OpenAI-compatible API in front of many
+//! upstream deployments, addressed by model group. The response body names
+//! the upstream model and the response headers carry what LiteLLM computed
+//! the request to cost. This module provides:
+//! - Pricing of upstream models
+//! - Chat completions through the proxy
+//! - Parsing of the response metadata into [`GatewayMetadata`]
+
+use crate::completion::{Completion, CompletionProvider, CompletionRequest};
+use crate::gateway::{
+    chat_request_body, completion_from_body, upstream_model_name, Gateway, GatewayMetadata,
+};
+use async_trait::async_trait;
+use llm_observatory_core::{
+    provider::{LlmProvider, Pricing},
+    Error, Result,
+};
+use reqwest::header::HeaderMap;
+
+/// Header with the cost LiteLLM computed for the request (USD).
+pub const RESPONSE_COST_HEADER: &str = "x-litellm-response-cost";
+
+/// Header with LiteLLM's ID of the request.
+pub const CALL_ID_HEADER: &str = "x-litellm-call-id";
+
+/// LiteLLM proxy provider configuration.
+#[derive(Debug, Clone)]
+pub struct LiteLlmProvider {
+    /// Virtual key for authentication (optional when the proxy has no auth)
+    api_key: Option<String>,
+    /// Proxy URL (e.g., http://localhost:4000)
+    base_url: String,
+}
+
+impl LiteLlmProvider {
+    /// Create a new LiteLLM provider for the proxy at `base_url`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            api_key: None,
+            base_url: base_url.into(),
+        }
+    }
+
+    /// Create a new LiteLLM provider from environment variables.
+    ///
+    /// Reads from:
+    /// - `LITELLM_BASE_URL`: Required proxy URL
+    /// - `LITELLM_API_KEY`: Optional virtual key
+    pub fn from_env() -> Result<Self> {
+        let base_url = std::env::var("LITELLM_BASE_URL")
+            .map_err(|_| Error::config("LITELLM_BASE_URL environment variable not set"))?;
+
+        let mut provider = Self::new(base_url);
+
+        if let Ok(api_key) = std::env::var("LITELLM_API_KEY") {
+            provider.api_key = Some(api_key);
+        }
+
+        Ok(provider)
+    }
+
+    /// Set the virtual key.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Parse the metadata of a chat completions response: the upstream
+    /// `model` from the body, and the cost and call ID from the headers.
+    /// Upstream models reported as `<provider>/<model>` also give the
+    /// provider.
+    pub fn response_metadata(headers: &HeaderMap, body: &serde_json::Value) -> GatewayMetadata {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+        let upstream_model = body.get("model").and_then(|v| v.as_str());
+
+        GatewayMetadata {
+            upstream_provider: upstream_model
+                .and_then(|model| model.split_once('/'))
+                .map(|(provider, _)| provider.to_string()),
+            upstream_model: upstream_model.map(str::to_string),
+            reported_cost_usd: header(RESPONSE_COST_HEADER).and_then(|v| v.parse().ok()),
+            request_id: header(CALL_ID_HEADER).map(str::to_string),
+            ..GatewayMetadata::new(Gateway::LiteLlm)
+        }
+    }
+
+    /// Generate a completion and return LiteLLM's metadata with it.
+    pub async fn complete_with_metadata(
+        &self,
+        request: &CompletionRequest,
+    ) -> Result<(Completion, GatewayMetadata)> {
+        let mut http = reqwest::Client::new()
+            .post(format!(
+                "{}/chat/completions",
+                self.base_url.trim_end_matches('/')
+            ))
+            .json(&chat_request_body(request));
+        if let Some(api_key) = &self.api_key {
+            http = http.bearer_auth(api_key);
+        }
+
+        let response = http
+            .send()
+            .await
+            .map_err(|e| Error::provider(format!("LiteLLM request failed: {}", e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::provider(format!(
+                "LiteLLM returned {}: {}",
+                status, body
+            )));
+        }
+
+        let headers = response.headers().clone();
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| Error::provider(format!("Invalid LiteLLM response: {}", e)))?;
+
+        let completion = completion_from_body(Gateway::LiteLlm, &body)?;
+        Ok((completion, Self::response_metadata(&headers, &body)))
+    }
+}
+
+#[async_trait]
+impl LlmProvider for LiteLlmProvider {
+    fn name(&self) -> &str {
+        "litellm"
+    }
+
+    async fn is_ready(&self) -> Result<bool> {
+        Ok(!self.base_url.is_empty())
+    }
+
+    async fn get_pricing(&self, model: &str) -> Result<Pricing> {
+        crate::pricing::PRICING_DB.get_pricing(upstream_model_name(model))
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for LiteLlmProvider {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion> {
+        Ok(self.complete_with_metadata(request).await?.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_metadata() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RESPONSE_COST_HEADER, "0.00042".parse().unwrap());
+        headers.insert(CALL_ID_HEADER, "c0ffee".parse().unwrap());
+        let body = serde_json::json!({
+            "model": "gpt-4o-2024-08-06",
+            "choices": [{"message": {"role": "assistant", "content": "Hi"}}],
+            "usage": {"prompt_tokens": 100, "completion_tokens": 17}
+        });
+
+        let metadata = LiteLlmProvider::response_metadata(&headers, &body);
+        assert_eq!(metadata.gateway, Gateway::LiteLlm);
+        assert_eq!(
+            metadata.upstream_model.as_deref(),
+            Some("gpt-4o-2024-08-06")
+        );
+        assert_eq!(metadata.upstream_provider, None);
+        assert_eq!(metadata.reported_cost_usd, Some(0.00042));
+        assert_eq!(metadata.request_id.as_deref(), Some("c0ffee"));
+
+        let body = serde_json::json!({"model": "bedrock/anthropic.claude-3-haiku"});
+        let metadata = LiteLlmProvider::response_metadata(&HeaderMap::new(), &body);
+        assert_eq!(metadata.upstream_provider.as_deref(), Some("bedrock"));
+        assert_eq!(metadata.reported_cost_usd, None);
+    }
+}
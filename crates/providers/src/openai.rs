@@ -7,7 +7,9 @@
 //! - Model information and pricing
 //! - API health checks
 //! - Cost calculation for all GPT models
+//! - Chat completions
 
+use crate::completion::{Completion, CompletionProvider, CompletionRequest};
 use llm_observatory_core::{
     provider::{LlmProvider, Pricing},
     Error, Result,
@@ -148,6 +150,91 @@ impl LlmProvider for OpenAiProvider {
     }
 }
 
+/// Body of a chat completions response.
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatChoice>,
+    #[serde(default)]
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatUsage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+}
+
+#[async_trait]
+impl CompletionProvider for OpenAiProvider {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion> {
+        let api_key = self
+            .api_key
+            .as_deref()
+            .ok_or_else(|| Error::config("OpenAI API key not set"))?;
+
+        let mut messages = Vec::new();
+        if let Some(system) = &request.system {
+            messages.push(serde_json::json!({"role": "system", "content": system}));
+        }
+        messages.push(serde_json::json!({"role": "user", "content": request.prompt}));
+
+        let mut http = reqwest::Client::new()
+            .post(format!("{}/chat/completions", self.base_url.trim_end_matches('/')))
+            .bearer_auth(api_key)
+            .json(&serde_json::json!({
+                "model": request.model,
+                "messages": messages,
+                "max_tokens": request.max_tokens,
+                "temperature": request.temperature,
+            }));
+        if let Some(org_id) = &self.organization_id {
+            http = http.header("OpenAI-Organization", org_id);
+        }
+
+        let response = http
+            .send()
+            .await
+            .map_err(|e| Error::provider(format!("OpenAI request failed: {}", e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::provider(format!("OpenAI returned {}: {}", status, body)));
+        }
+
+        let body: ChatCompletionResponse = response
+            .json()
+            .await
+            .map_err(|e| Error::provider(format!("Invalid OpenAI response: {}", e)))?;
+        let text = body
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .ok_or_else(|| Error::provider("OpenAI response has no content"))?;
+        let usage = body.usage.unwrap_or(ChatUsage {
+            prompt_tokens: 0,
+            completion_tokens: 0,
+        });
+
+        Ok(Completion {
+            text,
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+        })
+    }
+}
+
 impl Default for OpenAiProvider {
     fn default() -> Self {
         Self {
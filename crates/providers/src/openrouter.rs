@@ -0,0 +1,220 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! OpenRouter gateway provider implementation.
+//!
+//! OpenRouter routes OpenAI-compatible requests to many upstream providers.
+//! Its responses name the model and provider that served the request and,
+//! with usage accounting enabled, what the request cost. This module
+//! provides:
+//! - Pricing of upstream models (`openai/gpt-4o` is priced as `gpt-4o`)
+//! - Chat completions, with usage accounting requested
+//! - Parsing of the response metadata into [`GatewayMetadata`]
+
+use crate::completion::{Completion, CompletionProvider, CompletionRequest};
+use crate::gateway::{
+    chat_request_body, completion_from_body, upstream_model_name, Gateway, GatewayMetadata,
+};
+use async_trait::async_trait;
+use llm_observatory_core::{
+    provider::{LlmProvider, Pricing},
+    Error, Result,
+};
+
+/// Default OpenRouter API URL.
+const DEFAULT_BASE_URL: &str = "https://openrouter.ai/api/v1";
+
+/// OpenRouter provider configuration.
+#[derive(Debug, Clone)]
+pub struct OpenRouterProvider {
+    /// API key for authentication
+    api_key: Option<String>,
+    /// Base URL for API (default: https://openrouter.ai/api/v1)
+    base_url: String,
+    /// Application URL, sent as `HTTP-Referer` for OpenRouter's rankings
+    app_url: Option<String>,
+    /// Application name, sent as `X-Title`
+    app_name: Option<String>,
+}
+
+impl OpenRouterProvider {
+    /// Create a new OpenRouter provider with API key.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: Some(api_key.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Create a new OpenRouter provider from environment variables.
+    ///
+    /// Reads from:
+    /// - `OPENROUTER_API_KEY`: Required API key
+    /// - `OPENROUTER_BASE_URL`: Optional custom base URL
+    pub fn from_env() -> Result<Self> {
+        let api_key = std::env::var("OPENROUTER_API_KEY")
+            .map_err(|_| Error::config("OPENROUTER_API_KEY environment variable not set"))?;
+
+        let mut provider = Self::new(api_key);
+
+        if let Ok(base_url) = std::env::var("OPENROUTER_BASE_URL") {
+            provider.base_url = base_url;
+        }
+
+        Ok(provider)
+    }
+
+    /// Set custom base URL.
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into();
+        self
+    }
+
+    /// Identify the calling application to OpenRouter.
+    pub fn with_app(mut self, url: impl Into<String>, name: impl Into<String>) -> Self {
+        self.app_url = Some(url.into());
+        self.app_name = Some(name.into());
+        self
+    }
+
+    /// Parse the metadata of a chat completions response body: the `model`
+    /// and `provider` that served the request, its generation `id` and the
+    /// `usage.cost` charged (credits, in USD).
+    pub fn response_metadata(body: &serde_json::Value) -> GatewayMetadata {
+        let string = |pointer: &str| {
+            body.pointer(pointer)
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        };
+
+        GatewayMetadata {
+            upstream_provider: string("/provider"),
+            upstream_model: string("/model"),
+            reported_cost_usd: body.pointer("/usage/cost").and_then(|v| v.as_f64()),
+            request_id: string("/id"),
+            ..GatewayMetadata::new(Gateway::OpenRouter)
+        }
+    }
+
+    /// Generate a completion and return OpenRouter's metadata with it.
+    pub async fn complete_with_metadata(
+        &self,
+        request: &CompletionRequest,
+    ) -> Result<(Completion, GatewayMetadata)> {
+        let api_key = self
+            .api_key
+            .as_deref()
+            .ok_or_else(|| Error::config("OpenRouter API key not set"))?;
+
+        let mut body = chat_request_body(request);
+        // Ask for the cost of the request in the usage
+        body["usage"] = serde_json::json!({"include": true});
+
+        let mut http = reqwest::Client::new()
+            .post(format!(
+                "{}/chat/completions",
+                self.base_url.trim_end_matches('/')
+            ))
+            .bearer_auth(api_key)
+            .json(&body);
+        if let Some(url) = &self.app_url {
+            http = http.header("HTTP-Referer", url);
+        }
+        if let Some(name) = &self.app_name {
+            http = http.header("X-Title", name);
+        }
+
+        let response = http
+            .send()
+            .await
+            .map_err(|e| Error::provider(format!("OpenRouter request failed: {}", e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::provider(format!(
+                "OpenRouter returned {}: {}",
+                status, body
+            )));
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| Error::provider(format!("Invalid OpenRouter response: {}", e)))?;
+
+        let completion = completion_from_body(Gateway::OpenRouter, &body)?;
+        Ok((completion, Self::response_metadata(&body)))
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenRouterProvider {
+    fn name(&self) -> &str {
+        "openrouter"
+    }
+
+    async fn is_ready(&self) -> Result<bool> {
+        Ok(self.api_key.is_some())
+    }
+
+    async fn get_pricing(&self, model: &str) -> Result<Pricing> {
+        crate::pricing::PRICING_DB.get_pricing(upstream_model_name(model))
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for OpenRouterProvider {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion> {
+        Ok(self.complete_with_metadata(request).await?.0)
+    }
+}
+
+impl Default for OpenRouterProvider {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            app_url: None,
+            app_name: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_metadata() {
+        let body = serde_json::json!({
+            "id": "gen-1729000000-abc",
+            "provider": "OpenAI",
+            "model": "openai/gpt-4o-mini",
+            "choices": [{"message": {"role": "assistant", "content": "Hello"}}],
+            "usage": {"prompt_tokens": 12, "completion_tokens": 3, "total_tokens": 15, "cost": 0.0000036}
+        });
+
+        let metadata = OpenRouterProvider::response_metadata(&body);
+        assert_eq!(metadata.gateway, Gateway::OpenRouter);
+        assert_eq!(metadata.upstream_provider.as_deref(), Some("OpenAI"));
+        assert_eq!(
+            metadata.upstream_model.as_deref(),
+            Some("openai/gpt-4o-mini")
+        );
+        assert_eq!(metadata.reported_cost_usd, Some(0.0000036));
+        assert_eq!(metadata.request_id.as_deref(), Some("gen-1729000000-abc"));
+
+        let completion = completion_from_body(Gateway::OpenRouter, &body).unwrap();
+        assert_eq!(completion.text, "Hello");
+        assert_eq!(completion.prompt_tokens, 12);
+    }
+
+    #[tokio::test]
+    async fn test_get_pricing() {
+        let provider = OpenRouterProvider::new("test-key");
+        assert_eq!(provider.name(), "openrouter");
+        let pricing = provider.get_pricing("openai/gpt-4").await.unwrap();
+        assert_eq!(pricing.model, "gpt-4");
+        assert!(!OpenRouterProvider::default().is_ready().await.unwrap());
+    }
+}
@@ -5,18 +5,154 @@
 //!
 //! This module maintains up-to-date pricing information for all major LLM providers
 //! based on official pricing pages. Prices are updated as of January 2025.
+//!
+//! The database also carries model metadata (context window, output limit,
+//! modalities, deprecation status) from the providers' model documentation.
+//!
+//! [`PricingEngine`] resolves models registered in the
+//! [`ModelRegistry`](crate::registry::ModelRegistry) (custom and fine-tuned
+//! models, aliases) before the bundled pricing.
 
-use llm_observatory_core::{provider::Pricing, Error, Result};
+use crate::registry::MODEL_REGISTRY;
+use llm_observatory_core::{provider::Pricing, types::Cost, Error, Result};
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Version of the bundled pricing data.
+pub const PRICE_VERSION: &str = "2025-01";
+
 /// Global pricing database singleton.
 pub static PRICING_DB: Lazy<PricingDatabase> = Lazy::new(PricingDatabase::new);
 
+/// Input modality supported by a model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Modality {
+    /// Text input
+    Text,
+    /// Image input
+    Image,
+    /// Audio input
+    Audio,
+}
+
+impl Modality {
+    /// Get the modality name as a string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Modality::Text => "text",
+            Modality::Image => "image",
+            Modality::Audio => "audio",
+        }
+    }
+}
+
+/// Static metadata about a model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// Model name
+    pub model: String,
+    /// Model family (e.g., "gpt-4o", "claude-3.5")
+    pub family: String,
+    /// Context window in tokens
+    pub context_window: u32,
+    /// Maximum output tokens per request
+    pub max_output_tokens: u32,
+    /// Supported input modalities
+    pub modalities: Vec<Modality>,
+    /// Whether the provider has deprecated the model
+    pub deprecated: bool,
+    /// Suggested replacement for deprecated models
+    pub replacement: Option<String>,
+}
+
+impl ModelInfo {
+    fn new(
+        model: &str,
+        family: &str,
+        context_window: u32,
+        max_output_tokens: u32,
+        modalities: &[Modality],
+    ) -> Self {
+        Self {
+            model: model.to_string(),
+            family: family.to_string(),
+            context_window,
+            max_output_tokens,
+            modalities: modalities.to_vec(),
+            deprecated: false,
+            replacement: None,
+        }
+    }
+
+    fn deprecated(mut self, replacement: &str) -> Self {
+        self.deprecated = true;
+        self.replacement = Some(replacement.to_string());
+        self
+    }
+}
+
+/// Pricing for usage other than plain prompt/completion tokens.
+///
+/// All fields are optional; a missing price means the dimension does not
+/// apply to the model (or the regular token price is used, for cached input
+/// and cache writes).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PricingDimensions {
+    /// Cost per 1000 cached prompt tokens (USD)
+    pub cached_prompt_cost_per_1k: Option<f64>,
+    /// Cost per 1000 prompt tokens written to the prompt cache (USD)
+    pub cache_write_prompt_cost_per_1k: Option<f64>,
+    /// Fractional discount for batch API requests (e.g., 0.5 for 50% off)
+    pub batch_discount: Option<f64>,
+    /// Cost per 1000 embedding input tokens (USD)
+    pub embedding_cost_per_1k: Option<f64>,
+    /// Cost per generated image by size (e.g., "1024x1024"), USD
+    pub image_cost_by_size: HashMap<String, f64>,
+    /// Cost per minute of audio (USD)
+    pub audio_cost_per_minute: Option<f64>,
+}
+
+/// Billable usage of a single request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BillableUsage {
+    /// Prompt/completion tokens, some of which may have hit the prompt cache
+    Tokens {
+        /// Prompt tokens, including cached and cache-write ones
+        prompt_tokens: u32,
+        /// Completion tokens
+        completion_tokens: u32,
+        /// Prompt tokens served from the provider's prompt cache
+        cached_prompt_tokens: u32,
+        /// Prompt tokens written to the provider's prompt cache
+        cache_write_prompt_tokens: u32,
+    },
+    /// Embedding input tokens
+    Embedding {
+        /// Input tokens
+        tokens: u32,
+    },
+    /// Generated images
+    Image {
+        /// Number of images
+        count: u32,
+        /// Image size (e.g., "1024x1024")
+        size: String,
+    },
+    /// Transcribed or generated audio
+    Audio {
+        /// Audio duration in seconds
+        seconds: f64,
+    },
+}
+
 /// Comprehensive pricing database for LLM models.
 #[derive(Debug, Clone)]
 pub struct PricingDatabase {
     prices: HashMap<String, Pricing>,
+    models: HashMap<String, ModelInfo>,
+    dimensions: HashMap<String, PricingDimensions>,
 }
 
 impl PricingDatabase {
@@ -24,14 +160,47 @@ impl PricingDatabase {
     pub fn new() -> Self {
         let mut db = Self {
             prices: HashMap::new(),
+            models: HashMap::new(),
+            dimensions: HashMap::new(),
         };
         db.load_openai_pricing();
         db.load_anthropic_pricing();
         db.load_google_pricing();
         db.load_mistral_pricing();
+        db.load_model_metadata();
+        db.load_pricing_dimensions();
         db
     }
 
+    /// Version of the pricing data in this database.
+    pub fn price_version(&self) -> &'static str {
+        PRICE_VERSION
+    }
+
+    /// Get metadata for a model.
+    ///
+    /// Dated or suffixed model names (e.g., "gpt-4o-2024-08-06") fall back to
+    /// the longest known model name they start with.
+    pub fn get_model_info(&self, model: &str) -> Result<ModelInfo> {
+        if let Some(info) = self.models.get(model) {
+            return Ok(info.clone());
+        }
+
+        self.models
+            .iter()
+            .filter(|(name, _)| {
+                model.starts_with(name.as_str()) && model[name.len()..].starts_with('-')
+            })
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, info)| info.clone())
+            .ok_or_else(|| Error::not_found(format!("Model metadata not found for model: {}", model)))
+    }
+
+    /// Add or replace metadata for a model.
+    pub fn add_model_info(&mut self, info: ModelInfo) {
+        self.models.insert(info.model.clone(), info);
+    }
+
     /// Get pricing for a specific model.
     pub fn get_pricing(&self, model: &str) -> Result<Pricing> {
         self.prices
@@ -55,6 +224,51 @@ impl PricingDatabase {
         self.prices.insert(pricing.model.clone(), pricing);
     }
 
+    /// Get additional pricing dimensions for a model.
+    ///
+    /// Returns empty dimensions for models without any.
+    pub fn get_dimensions(&self, model: &str) -> PricingDimensions {
+        self.dimensions.get(model).cloned().unwrap_or_default()
+    }
+
+    /// Set additional pricing dimensions for a model.
+    pub fn set_dimensions(&mut self, model: impl Into<String>, dimensions: PricingDimensions) {
+        self.dimensions.insert(model.into(), dimensions);
+    }
+
+    /// Calculate the cost of billable usage for a model.
+    ///
+    /// Token usage returns a cost with a prompt/completion breakdown; other
+    /// usage returns a total only. The batch discount is applied last.
+    pub fn calculate_usage_cost(
+        &self,
+        model: &str,
+        usage: &BillableUsage,
+        batch: bool,
+    ) -> Result<Cost> {
+        usage_cost(
+            model,
+            self.get_pricing(model),
+            &self.get_dimensions(model),
+            usage,
+            batch,
+        )
+    }
+
+    /// Calculate what prompt caching saved on token usage.
+    ///
+    /// This is the cost of the usage without any prompt caching minus its
+    /// actual cost. It is negative when cache writes cost more than cache
+    /// reads saved, and zero for usage other than tokens.
+    pub fn calculate_cache_savings(
+        &self,
+        model: &str,
+        usage: &BillableUsage,
+        batch: bool,
+    ) -> Result<f64> {
+        cache_savings(usage, |usage| self.calculate_usage_cost(model, usage, batch))
+    }
+
     // OpenAI Pricing (as of January 2025)
     // Source: https://openai.com/api/pricing/
     fn load_openai_pricing(&mut self) {
@@ -270,6 +484,211 @@ impl PricingDatabase {
             },
         );
     }
+
+    // Model metadata (as of January 2025)
+    // Sources: provider model documentation pages
+    fn load_model_metadata(&mut self) {
+        use Modality::{Audio, Image, Text};
+
+        let models = [
+            // OpenAI
+            ModelInfo::new("gpt-4o", "gpt-4o", 128_000, 16_384, &[Text, Image]),
+            ModelInfo::new("gpt-4o-mini", "gpt-4o", 128_000, 16_384, &[Text, Image]),
+            ModelInfo::new("gpt-4-turbo", "gpt-4", 128_000, 4_096, &[Text, Image]),
+            ModelInfo::new("gpt-4", "gpt-4", 8_192, 8_192, &[Text]),
+            ModelInfo::new("gpt-3.5-turbo", "gpt-3.5", 16_385, 4_096, &[Text]),
+            ModelInfo::new("o1-preview", "o1", 128_000, 32_768, &[Text]).deprecated("o1"),
+            ModelInfo::new("o1-mini", "o1", 128_000, 65_536, &[Text]),
+            // Anthropic
+            ModelInfo::new("claude-sonnet-4.5", "claude-4.5", 200_000, 64_000, &[Text, Image]),
+            ModelInfo::new("claude-3-5-sonnet-20241022", "claude-3.5", 200_000, 8_192, &[Text, Image]),
+            ModelInfo::new("claude-3-5-haiku-20241022", "claude-3.5", 200_000, 8_192, &[Text]),
+            ModelInfo::new("claude-3-opus-20240229", "claude-3", 200_000, 4_096, &[Text, Image]),
+            ModelInfo::new("claude-3-sonnet-20240229", "claude-3", 200_000, 4_096, &[Text, Image])
+                .deprecated("claude-3-5-sonnet-20241022"),
+            ModelInfo::new("claude-3-haiku-20240307", "claude-3", 200_000, 4_096, &[Text, Image]),
+            // Google
+            ModelInfo::new("gemini-2.5-pro", "gemini-2.5", 1_048_576, 65_536, &[Text, Image, Audio]),
+            ModelInfo::new("gemini-2.5-flash", "gemini-2.5", 1_048_576, 65_536, &[Text, Image, Audio]),
+            ModelInfo::new("gemini-1.5-pro", "gemini-1.5", 2_097_152, 8_192, &[Text, Image, Audio]),
+            ModelInfo::new("gemini-1.5-flash", "gemini-1.5", 1_048_576, 8_192, &[Text, Image, Audio]),
+            // Mistral
+            ModelInfo::new("mistral-large-latest", "mistral-large", 128_000, 4_096, &[Text]),
+            ModelInfo::new("mistral-small-latest", "mistral-small", 32_000, 4_096, &[Text]),
+            ModelInfo::new("mistral-7b", "mistral-7b", 32_768, 4_096, &[Text]),
+        ];
+
+        for info in models {
+            self.add_model_info(info);
+        }
+    }
+
+    // Cached input, cache write, batch, embedding, image and audio pricing (as of January 2025)
+    // Sources: https://openai.com/api/pricing/, https://www.anthropic.com/api
+    fn load_pricing_dimensions(&mut self) {
+        // Both OpenAI and Anthropic bill batch requests at 50% off
+        let chat = |cached_prompt_cost_per_1k: f64| PricingDimensions {
+            cached_prompt_cost_per_1k: Some(cached_prompt_cost_per_1k),
+            batch_discount: Some(0.5),
+            ..Default::default()
+        };
+        // Anthropic also bills cache writes, at 125% of the input price
+        let cached_chat = |cached_cost_per_1k: f64, cache_write_cost_per_1k: f64| PricingDimensions {
+            cache_write_prompt_cost_per_1k: Some(cache_write_cost_per_1k),
+            ..chat(cached_cost_per_1k)
+        };
+
+        // OpenAI cached input: 50% off
+        self.set_dimensions("gpt-4o", chat(0.00125));
+        self.set_dimensions("gpt-4o-mini", chat(0.000075));
+        self.set_dimensions("o1-preview", chat(0.0075));
+        self.set_dimensions("o1-mini", chat(0.0015));
+        for model in ["gpt-4-turbo", "gpt-4", "gpt-3.5-turbo"] {
+            self.set_dimensions(
+                model,
+                PricingDimensions {
+                    batch_discount: Some(0.5),
+                    ..Default::default()
+                },
+            );
+        }
+
+        // Anthropic cache reads: 10% of the input price; cache writes: 125%
+        self.set_dimensions("claude-sonnet-4.5", cached_chat(0.0003, 0.00375));
+        self.set_dimensions("claude-3-5-sonnet-20241022", cached_chat(0.0003, 0.00375));
+        self.set_dimensions("claude-3-5-haiku-20241022", cached_chat(0.00008, 0.001));
+        self.set_dimensions("claude-3-opus-20240229", cached_chat(0.0015, 0.01875));
+        self.set_dimensions("claude-3-sonnet-20240229", cached_chat(0.0003, 0.00375));
+        self.set_dimensions("claude-3-haiku-20240307", cached_chat(0.00003, 0.0003));
+
+        // OpenAI embeddings
+        let embedding = |embedding_cost_per_1k: f64| PricingDimensions {
+            embedding_cost_per_1k: Some(embedding_cost_per_1k),
+            batch_discount: Some(0.5),
+            ..Default::default()
+        };
+        self.set_dimensions("text-embedding-3-small", embedding(0.00002)); // $0.02 per 1M tokens
+        self.set_dimensions("text-embedding-3-large", embedding(0.00013)); // $0.13 per 1M tokens
+        self.set_dimensions("text-embedding-ada-002", embedding(0.0001)); // $0.10 per 1M tokens
+
+        // OpenAI image generation (standard quality)
+        let images = |sizes: &[(&str, f64)]| PricingDimensions {
+            image_cost_by_size: sizes.iter().map(|(s, c)| (s.to_string(), *c)).collect(),
+            ..Default::default()
+        };
+        self.set_dimensions(
+            "dall-e-3",
+            images(&[("1024x1024", 0.04), ("1024x1792", 0.08), ("1792x1024", 0.08)]),
+        );
+        self.set_dimensions(
+            "dall-e-2",
+            images(&[("256x256", 0.016), ("512x512", 0.018), ("1024x1024", 0.02)]),
+        );
+
+        // OpenAI audio transcription
+        self.set_dimensions(
+            "whisper-1",
+            PricingDimensions {
+                audio_cost_per_minute: Some(0.006),
+                ..Default::default()
+            },
+        );
+    }
+}
+
+/// Calculate the cost of billable usage from the token pricing and pricing
+/// dimensions of a model. `pricing` is only needed for token usage.
+pub(crate) fn usage_cost(
+    model: &str,
+    pricing: Result<Pricing>,
+    dimensions: &PricingDimensions,
+    usage: &BillableUsage,
+    batch: bool,
+) -> Result<Cost> {
+    let missing =
+        |dimension: &str| Error::not_found(format!("No {} pricing for model: {}", dimension, model));
+
+    let mut cost = match usage {
+        BillableUsage::Tokens {
+            prompt_tokens,
+            completion_tokens,
+            cached_prompt_tokens,
+            cache_write_prompt_tokens,
+        } => {
+            let pricing = pricing?;
+            let cached = (*cached_prompt_tokens).min(*prompt_tokens);
+            let written = (*cache_write_prompt_tokens).min(prompt_tokens - cached);
+            let cached_rate = dimensions
+                .cached_prompt_cost_per_1k
+                .unwrap_or(pricing.prompt_cost_per_1k);
+            let write_rate = dimensions
+                .cache_write_prompt_cost_per_1k
+                .unwrap_or(pricing.prompt_cost_per_1k);
+
+            let prompt_cost = ((prompt_tokens - cached - written) as f64 / 1000.0)
+                * pricing.prompt_cost_per_1k
+                + (cached as f64 / 1000.0) * cached_rate
+                + (written as f64 / 1000.0) * write_rate;
+            let completion_cost =
+                (*completion_tokens as f64 / 1000.0) * pricing.completion_cost_per_1k;
+            Cost::with_breakdown(prompt_cost, completion_cost)
+        }
+        BillableUsage::Embedding { tokens } => {
+            let rate = dimensions
+                .embedding_cost_per_1k
+                .ok_or_else(|| missing("embedding"))?;
+            Cost::new((*tokens as f64 / 1000.0) * rate)
+        }
+        BillableUsage::Image { count, size } => {
+            let rate = dimensions
+                .image_cost_by_size
+                .get(size)
+                .copied()
+                .ok_or_else(|| missing(&format!("{} image", size)))?;
+            Cost::new(*count as f64 * rate)
+        }
+        BillableUsage::Audio { seconds } => {
+            let rate = dimensions
+                .audio_cost_per_minute
+                .ok_or_else(|| missing("audio"))?;
+            Cost::new(seconds / 60.0 * rate)
+        }
+    };
+
+    if batch {
+        if let Some(discount) = dimensions.batch_discount {
+            let factor = 1.0 - discount;
+            cost.amount_usd *= factor;
+            cost.prompt_cost = cost.prompt_cost.map(|c| c * factor);
+            cost.completion_cost = cost.completion_cost.map(|c| c * factor);
+        }
+    }
+
+    Ok(cost)
+}
+
+/// Cost of token usage without prompt caching minus its cost with it, both
+/// from `cost_of`. Zero for usage other than tokens.
+pub(crate) fn cache_savings(
+    usage: &BillableUsage,
+    cost_of: impl Fn(&BillableUsage) -> Result<Cost>,
+) -> Result<f64> {
+    let BillableUsage::Tokens {
+        prompt_tokens,
+        completion_tokens,
+        ..
+    } = usage
+    else {
+        return Ok(0.0);
+    };
+
+    let uncached = BillableUsage::Tokens {
+        prompt_tokens: *prompt_tokens,
+        completion_tokens: *completion_tokens,
+        cached_prompt_tokens: 0,
+        cache_write_prompt_tokens: 0,
+    };
+    Ok(cost_of(&uncached)?.amount_usd - cost_of(usage)?.amount_usd)
 }
 
 impl Default for PricingDatabase {
@@ -296,7 +715,7 @@ impl PricingEngine {
         prompt_tokens: u32,
         completion_tokens: u32,
     ) -> Result<f64> {
-        let pricing = PRICING_DB.get_pricing(model)?;
+        let pricing = MODEL_REGISTRY.resolve(&PRICING_DB, None, model).pricing?;
         Ok(pricing.calculate_cost(prompt_tokens, completion_tokens))
     }
 
@@ -309,7 +728,7 @@ impl PricingEngine {
         prompt_tokens: u32,
         completion_tokens: u32,
     ) -> Result<(f64, f64, f64)> {
-        let pricing = PRICING_DB.get_pricing(model)?;
+        let pricing = MODEL_REGISTRY.resolve(&PRICING_DB, None, model).pricing?;
         let prompt_cost = (prompt_tokens as f64 / 1000.0) * pricing.prompt_cost_per_1k;
         let completion_cost = (completion_tokens as f64 / 1000.0) * pricing.completion_cost_per_1k;
         let total_cost = prompt_cost + completion_cost;
@@ -324,6 +743,40 @@ impl PricingEngine {
         Self::calculate_cost(model, prompt_tokens, completion_tokens)
     }
 
+    /// Calculate cost for any billable usage (tokens, embeddings, images, audio).
+    pub fn calculate_usage_cost(model: &str, usage: &BillableUsage, batch: bool) -> Result<Cost> {
+        Self::calculate_usage_cost_for_org(None, model, usage, batch)
+    }
+
+    /// Calculate cost for billable usage of an organization, resolving the
+    /// organization's registered models first.
+    pub fn calculate_usage_cost_for_org(
+        org_id: Option<&str>,
+        model: &str,
+        usage: &BillableUsage,
+        batch: bool,
+    ) -> Result<Cost> {
+        MODEL_REGISTRY.calculate_usage_cost(&PRICING_DB, org_id, model, usage, batch)
+    }
+
+    /// Calculate what prompt caching saved on billable usage.
+    pub fn calculate_cache_savings(model: &str, usage: &BillableUsage, batch: bool) -> Result<f64> {
+        Self::calculate_cache_savings_for_org(None, model, usage, batch)
+    }
+
+    /// Calculate what prompt caching saved on billable usage of an
+    /// organization, resolving the organization's registered models first.
+    pub fn calculate_cache_savings_for_org(
+        org_id: Option<&str>,
+        model: &str,
+        usage: &BillableUsage,
+        batch: bool,
+    ) -> Result<f64> {
+        cache_savings(usage, |usage| {
+            MODEL_REGISTRY.calculate_usage_cost(&PRICING_DB, org_id, model, usage, batch)
+        })
+    }
+
     /// Compare costs across different models for the same token usage.
     pub fn compare_costs(
         models: &[&str],
@@ -385,6 +838,146 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_model_info_lookup() {
+        let info = PRICING_DB.get_model_info("gpt-4o-mini").unwrap();
+        assert_eq!(info.family, "gpt-4o");
+        assert_eq!(info.context_window, 128_000);
+        assert!(info.modalities.contains(&Modality::Image));
+        assert!(!info.deprecated);
+
+        let info = PRICING_DB.get_model_info("claude-3-sonnet-20240229").unwrap();
+        assert!(info.deprecated);
+        assert_eq!(info.replacement.as_deref(), Some("claude-3-5-sonnet-20241022"));
+    }
+
+    #[test]
+    fn test_model_info_dated_fallback() {
+        let info = PRICING_DB.get_model_info("gpt-4o-mini-2024-07-18").unwrap();
+        assert_eq!(info.model, "gpt-4o-mini");
+
+        assert!(PRICING_DB.get_model_info("gpt-4ox").is_err());
+        assert!(PRICING_DB.get_model_info("unknown-model").is_err());
+    }
+
+    #[test]
+    fn test_model_metadata_covers_priced_models() {
+        for model in PRICING_DB.list_models() {
+            assert!(PRICING_DB.get_model_info(&model).is_ok(), "No metadata for {}", model);
+        }
+    }
+
+    #[test]
+    fn test_cached_prompt_pricing() {
+        let usage = BillableUsage::Tokens {
+            prompt_tokens: 2000,
+            completion_tokens: 1000,
+            cached_prompt_tokens: 1000,
+            cache_write_prompt_tokens: 0,
+        };
+        let cost = PricingEngine::calculate_usage_cost("gpt-4o", &usage, false).unwrap();
+
+        // 1k uncached at $0.0025 + 1k cached at $0.00125 + 1k completion at $0.01
+        assert!((cost.prompt_cost.unwrap() - 0.00375).abs() < 0.000001);
+        assert!((cost.amount_usd - 0.01375).abs() < 0.000001);
+
+        // Models without a cached rate bill cached tokens at the full price
+        let cost = PricingEngine::calculate_usage_cost("gpt-4", &usage, false).unwrap();
+        assert!((cost.amount_usd - 0.12).abs() < 0.000001);
+    }
+
+    #[test]
+    fn test_cache_write_pricing_and_savings() {
+        let usage = BillableUsage::Tokens {
+            prompt_tokens: 3000,
+            completion_tokens: 0,
+            cached_prompt_tokens: 1000,
+            cache_write_prompt_tokens: 1000,
+        };
+        let model = "claude-3-5-sonnet-20241022";
+        let cost = PricingEngine::calculate_usage_cost(model, &usage, false).unwrap();
+
+        // 1k uncached at $0.003 + 1k read at $0.0003 + 1k written at $0.00375
+        assert!((cost.amount_usd - 0.00705).abs() < 0.000001);
+
+        // Without caching: 3k at $0.003
+        let savings = PricingEngine::calculate_cache_savings(model, &usage, false).unwrap();
+        assert!((savings - 0.00195).abs() < 0.000001);
+
+        // A cache write that is never read costs more than no caching
+        let usage = BillableUsage::Tokens {
+            prompt_tokens: 1000,
+            completion_tokens: 0,
+            cached_prompt_tokens: 0,
+            cache_write_prompt_tokens: 1000,
+        };
+        let savings = PricingEngine::calculate_cache_savings(model, &usage, false).unwrap();
+        assert!((savings + 0.00075).abs() < 0.000001);
+    }
+
+    #[test]
+    fn test_batch_discount() {
+        let usage = BillableUsage::Tokens {
+            prompt_tokens: 1000,
+            completion_tokens: 1000,
+            cached_prompt_tokens: 0,
+            cache_write_prompt_tokens: 0,
+        };
+        let cost = PricingEngine::calculate_usage_cost("gpt-4o", &usage, true).unwrap();
+        assert!((cost.amount_usd - 0.00625).abs() < 0.000001);
+        assert!((cost.completion_cost.unwrap() - 0.005).abs() < 0.000001);
+
+        // No batch API pricing: full price
+        let cost = PricingEngine::calculate_usage_cost("gemini-1.5-pro", &usage, true).unwrap();
+        let full = PricingEngine::calculate_cost("gemini-1.5-pro", 1000, 1000).unwrap();
+        assert!((cost.amount_usd - full).abs() < 0.000001);
+    }
+
+    #[test]
+    fn test_embedding_image_audio_pricing() {
+        let cost = PricingEngine::calculate_usage_cost(
+            "text-embedding-3-small",
+            &BillableUsage::Embedding { tokens: 1_000_000 },
+            false,
+        )
+        .unwrap();
+        assert!((cost.amount_usd - 0.02).abs() < 0.000001);
+
+        let cost = PricingEngine::calculate_usage_cost(
+            "dall-e-3",
+            &BillableUsage::Image {
+                count: 2,
+                size: "1024x1792".to_string(),
+            },
+            false,
+        )
+        .unwrap();
+        assert!((cost.amount_usd - 0.16).abs() < 0.000001);
+
+        let cost = PricingEngine::calculate_usage_cost(
+            "whisper-1",
+            &BillableUsage::Audio { seconds: 90.0 },
+            false,
+        )
+        .unwrap();
+        assert!((cost.amount_usd - 0.009).abs() < 0.000001);
+    }
+
+    #[test]
+    fn test_missing_dimension() {
+        let image = BillableUsage::Image {
+            count: 1,
+            size: "4096x4096".to_string(),
+        };
+        assert!(PricingEngine::calculate_usage_cost("dall-e-3", &image, false).is_err());
+        assert!(PricingEngine::calculate_usage_cost(
+            "gpt-4o",
+            &BillableUsage::Audio { seconds: 60.0 },
+            false
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_pricing_database_list() {
         let models = PRICING_DB.list_models();
@@ -0,0 +1,345 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Registry of custom and fine-tuned models.
+//!
+//! Fine-tuned and self-deployed models have IDs the bundled pricing does not
+//! know (e.g. `ft:gpt-4o-mini-2024-07-18:acme::abc123`). Organizations
+//! register them with their own pricing, aliases and base model; the pricing
+//! engine resolves registered models before the bundled pricing.
+//!
+//! Resolution follows the base-model lineage: the first registered model in
+//! the chain with its own pricing sets the token prices, and the bundled model
+//! at the root supplies the other pricing dimensions (batch discount,
+//! embeddings, ...). A registered model without pricing costs what its base
+//! model costs.
+//!
+//! Registered models are kept in the `model_registry` table. Hosts load them
+//! with [`ModelRegistry::replace`], typically into [`MODEL_REGISTRY`], which
+//! [`PricingEngine`](crate::pricing::PricingEngine) uses.
+
+use crate::pricing::{
+    cache_savings, usage_cost, BillableUsage, PricingDatabase, PricingDimensions,
+};
+use llm_observatory_core::{provider::Pricing, types::Cost, Error, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Global model registry, used by the pricing engine.
+pub static MODEL_REGISTRY: Lazy<ModelRegistry> = Lazy::new(ModelRegistry::new);
+
+/// Longest base-model lineage followed, guarding against cycles.
+const MAX_LINEAGE_DEPTH: usize = 8;
+
+/// Token pricing of a registered model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomPricing {
+    /// Cost per 1000 prompt tokens (USD)
+    pub prompt_cost_per_1k: f64,
+    /// Cost per 1000 completion tokens (USD)
+    pub completion_cost_per_1k: f64,
+    /// Cost per 1000 cached prompt tokens (USD); the prompt price when unset
+    pub cached_prompt_cost_per_1k: Option<f64>,
+}
+
+/// A custom or fine-tuned model registered by an organization.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegisteredModel {
+    /// Organization the model belongs to; `None` for every organization
+    pub org_id: Option<String>,
+    /// Model ID as reported on spans
+    pub model_id: String,
+    /// Other names the model is reported under
+    pub aliases: Vec<String>,
+    /// Model this one was fine-tuned from or deploys
+    pub base_model: Option<String>,
+    /// Token pricing; the base model's pricing when unset
+    pub pricing: Option<CustomPricing>,
+}
+
+/// Pricing a model resolved to.
+#[derive(Debug)]
+pub struct ResolvedPricing {
+    /// Model at the root of the lineage
+    pub base_model: String,
+    /// Token pricing (an error when neither the registry nor the bundled
+    /// pricing has any)
+    pub pricing: Result<Pricing>,
+    /// Other pricing dimensions
+    pub dimensions: PricingDimensions,
+}
+
+/// Custom and fine-tuned models, by organization and model ID or alias.
+#[derive(Debug, Default)]
+pub struct ModelRegistry {
+    models: RwLock<HashMap<(Option<String>, String), Arc<RegisteredModel>>>,
+}
+
+impl ModelRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace all registered models with `models`.
+    pub fn replace(&self, models: impl IntoIterator<Item = RegisteredModel>) {
+        let mut index = HashMap::new();
+        for model in models {
+            Self::index(&mut index, model);
+        }
+        *self.models.write().unwrap() = index;
+    }
+
+    /// Register a model, replacing one with the same ID or alias.
+    pub fn register(&self, model: RegisteredModel) {
+        Self::index(&mut self.models.write().unwrap(), model);
+    }
+
+    fn index(
+        index: &mut HashMap<(Option<String>, String), Arc<RegisteredModel>>,
+        model: RegisteredModel,
+    ) {
+        let model = Arc::new(model);
+        for name in std::iter::once(&model.model_id).chain(&model.aliases) {
+            index.insert((model.org_id.clone(), name.clone()), model.clone());
+        }
+    }
+
+    /// Number of registered names (model IDs and aliases).
+    pub fn len(&self) -> usize {
+        self.models.read().unwrap().len()
+    }
+
+    /// Whether no model is registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Find a model by ID or alias, preferring the organization's models.
+    pub fn lookup(&self, org_id: Option<&str>, model: &str) -> Option<Arc<RegisteredModel>> {
+        let models = self.models.read().unwrap();
+        org_id
+            .and_then(|org| models.get(&(Some(org.to_string()), model.to_string())))
+            .or_else(|| models.get(&(None, model.to_string())))
+            .cloned()
+    }
+
+    /// Model at the root of a model's lineage: registered base models are
+    /// followed, then OpenAI fine-tune IDs resolve to the model they were
+    /// tuned from. Unknown models are their own base.
+    pub fn base_model(&self, org_id: Option<&str>, model: &str) -> String {
+        let mut current = model.to_string();
+        for _ in 0..MAX_LINEAGE_DEPTH {
+            let next = match self.lookup(org_id, &current) {
+                Some(entry) => match &entry.base_model {
+                    Some(base) => base.clone(),
+                    None => return entry.model_id.clone(),
+                },
+                None => match fine_tuned_base_model(&current) {
+                    Some(base) => base.to_string(),
+                    None => return current,
+                },
+            };
+            current = next;
+        }
+        current
+    }
+
+    /// Resolve the pricing of a model, following its lineage.
+    pub fn resolve(
+        &self,
+        database: &PricingDatabase,
+        org_id: Option<&str>,
+        model: &str,
+    ) -> ResolvedPricing {
+        let mut custom = None;
+        let mut current = model.to_string();
+        for _ in 0..MAX_LINEAGE_DEPTH {
+            let Some(entry) = self.lookup(org_id, &current) else {
+                break;
+            };
+            if custom.is_none() {
+                custom = entry.pricing.clone();
+            }
+            match &entry.base_model {
+                Some(base) => current = base.clone(),
+                None => {
+                    current = entry.model_id.clone();
+                    break;
+                }
+            }
+        }
+
+        let dimensions = database.get_dimensions(&current);
+        match custom {
+            Some(custom) => ResolvedPricing {
+                pricing: Ok(Pricing {
+                    model: model.to_string(),
+                    prompt_cost_per_1k: custom.prompt_cost_per_1k,
+                    completion_cost_per_1k: custom.completion_cost_per_1k,
+                }),
+                // Base-model cache prices do not apply to custom prices
+                dimensions: PricingDimensions {
+                    cached_prompt_cost_per_1k: custom.cached_prompt_cost_per_1k,
+                    cache_write_prompt_cost_per_1k: None,
+                    ..dimensions
+                },
+                base_model: current,
+            },
+            None => ResolvedPricing {
+                pricing: database.get_pricing(&current).map_err(|_| {
+                    Error::not_found(format!("Pricing not found for model: {}", model))
+                }),
+                dimensions,
+                base_model: current,
+            },
+        }
+    }
+
+    /// Calculate the cost of billable usage, resolving registered models
+    /// before the bundled pricing.
+    pub fn calculate_usage_cost(
+        &self,
+        database: &PricingDatabase,
+        org_id: Option<&str>,
+        model: &str,
+        usage: &BillableUsage,
+        batch: bool,
+    ) -> Result<Cost> {
+        let resolved = self.resolve(database, org_id, model);
+        usage_cost(model, resolved.pricing, &resolved.dimensions, usage, batch)
+    }
+
+    /// Calculate what prompt caching saved on billable usage, resolving
+    /// registered models before the bundled pricing.
+    pub fn calculate_cache_savings(
+        &self,
+        database: &PricingDatabase,
+        org_id: Option<&str>,
+        model: &str,
+        usage: &BillableUsage,
+        batch: bool,
+    ) -> Result<f64> {
+        cache_savings(usage, |usage| {
+            self.calculate_usage_cost(database, org_id, model, usage, batch)
+        })
+    }
+}
+
+/// Model an OpenAI fine-tune ID (`ft:<base>:<org>:<suffix>:<id>`) was tuned
+/// from.
+pub fn fine_tuned_base_model(model: &str) -> Option<&str> {
+    model
+        .strip_prefix("ft:")
+        .and_then(|rest| rest.split(':').next())
+        .filter(|base| !base.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::PRICING_DB;
+
+    fn fine_tune(org_id: Option<&str>, pricing: Option<CustomPricing>) -> RegisteredModel {
+        RegisteredModel {
+            org_id: org_id.map(str::to_string),
+            model_id: "ft:gpt-4o-mini-2024-07-18:acme::abc123".to_string(),
+            aliases: vec!["support-bot".to_string()],
+            base_model: Some("gpt-4o-mini".to_string()),
+            pricing,
+        }
+    }
+
+    fn tokens(prompt_tokens: u32, completion_tokens: u32) -> BillableUsage {
+        BillableUsage::Tokens {
+            prompt_tokens,
+            completion_tokens,
+            cached_prompt_tokens: 0,
+            cache_write_prompt_tokens: 0,
+        }
+    }
+
+    #[test]
+    fn test_custom_pricing_and_aliases() {
+        let registry = ModelRegistry::new();
+        registry.register(fine_tune(
+            Some("acme"),
+            Some(CustomPricing {
+                prompt_cost_per_1k: 0.0003,
+                completion_cost_per_1k: 0.0012,
+                cached_prompt_cost_per_1k: None,
+            }),
+        ));
+
+        for model in ["ft:gpt-4o-mini-2024-07-18:acme::abc123", "support-bot"] {
+            let cost = registry
+                .calculate_usage_cost(&PRICING_DB, Some("acme"), model, &tokens(1000, 1000), false)
+                .unwrap();
+            assert!((cost.amount_usd - 0.0015).abs() < 1e-9);
+        }
+
+        // Other organizations do not see the model
+        assert!(registry
+            .calculate_usage_cost(
+                &PRICING_DB,
+                Some("other"),
+                "support-bot",
+                &tokens(1000, 0),
+                false
+            )
+            .is_err());
+
+        // The batch discount comes from the base model
+        let cost = registry
+            .calculate_usage_cost(
+                &PRICING_DB,
+                Some("acme"),
+                "support-bot",
+                &tokens(1000, 1000),
+                true,
+            )
+            .unwrap();
+        assert!((cost.amount_usd - 0.00075).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lineage_without_pricing() {
+        let registry = ModelRegistry::new();
+        registry.replace([fine_tune(None, None)]);
+
+        let resolved = registry.resolve(&PRICING_DB, Some("acme"), "support-bot");
+        assert_eq!(resolved.base_model, "gpt-4o-mini");
+        let pricing = resolved.pricing.unwrap();
+        assert_eq!(pricing.prompt_cost_per_1k, 0.00015);
+
+        assert!(registry
+            .resolve(&PRICING_DB, None, "unknown-model")
+            .pricing
+            .is_err());
+    }
+
+    #[test]
+    fn test_base_model() {
+        let registry = ModelRegistry::new();
+        registry.register(RegisteredModel {
+            org_id: None,
+            model_id: "support-bot-v2".to_string(),
+            aliases: vec![],
+            base_model: Some("ft:gpt-4o-mini-2024-07-18:acme::abc123".to_string()),
+            pricing: None,
+        });
+
+        assert_eq!(
+            registry.base_model(None, "support-bot-v2"),
+            "gpt-4o-mini-2024-07-18"
+        );
+        assert_eq!(registry.base_model(None, "gpt-4o"), "gpt-4o");
+        assert_eq!(
+            fine_tuned_base_model("ft:gpt-3.5-turbo:acme:custom:7p4lURel"),
+            Some("gpt-3.5-turbo")
+        );
+        assert_eq!(fine_tuned_base_model("gpt-4o"), None);
+    }
+}
@@ -0,0 +1,231 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cost models for self-hosted inference (vLLM, Ollama, TGI, ...).
+//!
+//! Self-hosted models have no price sheet: what a request costs depends on
+//! what the deployment's hardware costs and how many tokens it serves. A
+//! [`SelfHostedDeployment`] prices its requests with a [`SelfHostedCostModel`],
+//! either a flat rate per 1000 tokens or a GPU-hour rate amortized over the
+//! deployment's throughput, so self-hosted spans get costs comparable with
+//! hosted providers.
+//!
+//! ```
+//! use llm_observatory_providers::SelfHostedCostModel;
+//!
+//! // Four GPUs at $2.50/hour, serving 1000 completion tokens/s at 50% utilization
+//! let model = SelfHostedCostModel::GpuHour {
+//!     gpu_hour_cost_usd: 2.5,
+//!     gpu_count: 4,
+//!     completion_tokens_per_second: 1000.0,
+//!     prompt_tokens_per_second: Some(10_000.0),
+//!     utilization: 0.5,
+//! };
+//! let pricing = model.pricing();
+//! assert!((pricing.completion_cost_per_1k - 0.005_555_6).abs() < 1e-6);
+//! ```
+
+use crate::pricing::{usage_cost, BillableUsage, PricingDimensions};
+use crate::registry::CustomPricing;
+use llm_observatory_core::{provider::Pricing, types::Cost, Result};
+use serde::{Deserialize, Serialize};
+
+/// How the requests of a self-hosted deployment are priced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SelfHostedCostModel {
+    /// GPU rental amortized over the tokens the deployment serves.
+    GpuHour {
+        /// Cost of one GPU for one hour (USD)
+        gpu_hour_cost_usd: f64,
+        /// GPUs the deployment runs on
+        #[serde(default = "default_gpu_count")]
+        gpu_count: u32,
+        /// Completion tokens generated per second across the deployment,
+        /// with its usual batching
+        completion_tokens_per_second: f64,
+        /// Prompt tokens processed per second across the deployment; the
+        /// completion throughput when unset
+        #[serde(default)]
+        prompt_tokens_per_second: Option<f64>,
+        /// Share of the time the deployment serves requests; idle time is
+        /// charged to the requests served (1.0 charges busy time only)
+        #[serde(default = "default_utilization")]
+        utilization: f64,
+    },
+    /// Flat rate per 1000 tokens.
+    PerToken {
+        /// Cost per 1000 prompt tokens (USD)
+        prompt_cost_per_1k: f64,
+        /// Cost per 1000 completion tokens (USD)
+        completion_cost_per_1k: f64,
+    },
+}
+
+fn default_gpu_count() -> u32 {
+    1
+}
+
+fn default_utilization() -> f64 {
+    1.0
+}
+
+impl SelfHostedCostModel {
+    /// Token pricing of the cost model.
+    pub fn pricing(&self) -> CustomPricing {
+        match self {
+            Self::GpuHour {
+                gpu_hour_cost_usd,
+                gpu_count,
+                completion_tokens_per_second,
+                prompt_tokens_per_second,
+                utilization,
+            } => {
+                let cost_per_second =
+                    gpu_hour_cost_usd * f64::from(*gpu_count) / 3600.0 / utilization;
+                let per_1k = |tokens_per_second: f64| cost_per_second / tokens_per_second * 1000.0;
+                CustomPricing {
+                    prompt_cost_per_1k: per_1k(
+                        prompt_tokens_per_second.unwrap_or(*completion_tokens_per_second),
+                    ),
+                    completion_cost_per_1k: per_1k(*completion_tokens_per_second),
+                    cached_prompt_cost_per_1k: None,
+                }
+            }
+            Self::PerToken {
+                prompt_cost_per_1k,
+                completion_cost_per_1k,
+            } => CustomPricing {
+                prompt_cost_per_1k: *prompt_cost_per_1k,
+                completion_cost_per_1k: *completion_cost_per_1k,
+                cached_prompt_cost_per_1k: None,
+            },
+        }
+    }
+
+    /// Name of the cost model, as recorded on spans.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::GpuHour { .. } => "gpu_hour",
+            Self::PerToken { .. } => "per_token",
+        }
+    }
+}
+
+/// A self-hosted inference deployment and how its requests are priced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelfHostedDeployment {
+    /// Deployment name, recorded on its spans
+    pub name: String,
+    /// Models the deployment serves; any model when empty
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// Server addresses (`server.address` span attribute) of the deployment;
+    /// any address when empty
+    #[serde(default)]
+    pub server_addresses: Vec<String>,
+    /// How requests are priced
+    pub cost_model: SelfHostedCostModel,
+}
+
+impl SelfHostedDeployment {
+    /// Whether a request to `model` at `server_address` was served by the
+    /// deployment.
+    pub fn matches(&self, model: &str, server_address: Option<&str>) -> bool {
+        let model_matches = self.models.is_empty() || self.models.iter().any(|m| m == model);
+        let address_matches = self.server_addresses.is_empty()
+            || server_address
+                .is_some_and(|address| self.server_addresses.iter().any(|a| a == address));
+        model_matches && address_matches
+    }
+
+    /// Calculate the cost of billable usage on the deployment. Embedding
+    /// tokens cost the prompt rate; images and audio are not priced.
+    pub fn calculate_usage_cost(&self, model: &str, usage: &BillableUsage) -> Result<Cost> {
+        let pricing = self.cost_model.pricing();
+        let dimensions = PricingDimensions {
+            embedding_cost_per_1k: Some(pricing.prompt_cost_per_1k),
+            ..PricingDimensions::default()
+        };
+        let pricing = Pricing {
+            model: model.to_string(),
+            prompt_cost_per_1k: pricing.prompt_cost_per_1k,
+            completion_cost_per_1k: pricing.completion_cost_per_1k,
+        };
+        usage_cost(model, Ok(pricing), &dimensions, usage, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deployment(cost_model: SelfHostedCostModel) -> SelfHostedDeployment {
+        SelfHostedDeployment {
+            name: "llama-70b".to_string(),
+            models: vec!["meta-llama/Llama-3.1-70B-Instruct".to_string()],
+            server_addresses: vec!["vllm.internal".to_string()],
+            cost_model,
+        }
+    }
+
+    fn tokens(prompt_tokens: u32, completion_tokens: u32) -> BillableUsage {
+        BillableUsage::Tokens {
+            prompt_tokens,
+            completion_tokens,
+            cached_prompt_tokens: 0,
+            cache_write_prompt_tokens: 0,
+        }
+    }
+
+    #[test]
+    fn test_gpu_hour_pricing() {
+        // $3.60/hour over 2 GPUs is $0.002/s; at 50% utilization, $0.004/s
+        let deployment = deployment(SelfHostedCostModel::GpuHour {
+            gpu_hour_cost_usd: 3.6,
+            gpu_count: 2,
+            completion_tokens_per_second: 400.0,
+            prompt_tokens_per_second: Some(4000.0),
+            utilization: 0.5,
+        });
+
+        let pricing = deployment.cost_model.pricing();
+        assert!((pricing.completion_cost_per_1k - 0.01).abs() < 1e-12);
+        assert!((pricing.prompt_cost_per_1k - 0.001).abs() < 1e-12);
+
+        let cost = deployment
+            .calculate_usage_cost("meta-llama/Llama-3.1-70B-Instruct", &tokens(2000, 500))
+            .unwrap();
+        assert!((cost.amount_usd - 0.007).abs() < 1e-12);
+
+        let embedding = deployment
+            .calculate_usage_cost("embed", &BillableUsage::Embedding { tokens: 1000 })
+            .unwrap();
+        assert!((embedding.amount_usd - 0.001).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_per_token_and_matching() {
+        let json = serde_json::json!({
+            "name": "llama-70b",
+            "models": ["meta-llama/Llama-3.1-70B-Instruct"],
+            "server_addresses": ["vllm.internal"],
+            "cost_model": {
+                "type": "per_token",
+                "prompt_cost_per_1k": 0.0002,
+                "completion_cost_per_1k": 0.0006
+            }
+        });
+        let deployment: SelfHostedDeployment = serde_json::from_value(json).unwrap();
+        assert_eq!(deployment.cost_model.as_str(), "per_token");
+
+        let cost = deployment
+            .calculate_usage_cost("meta-llama/Llama-3.1-70B-Instruct", &tokens(1000, 1000))
+            .unwrap();
+        assert!((cost.amount_usd - 0.0008).abs() < 1e-12);
+
+        assert!(deployment.matches("meta-llama/Llama-3.1-70B-Instruct", Some("vllm.internal")));
+        assert!(!deployment.matches("meta-llama/Llama-3.1-70B-Instruct", None));
+        assert!(!deployment.matches("llama3.1:8b", Some("vllm.internal")));
+    }
+}
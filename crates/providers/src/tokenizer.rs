@@ -0,0 +1,192 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Token counting for spans that arrive without usage data.
+//!
+//! Some providers omit token usage on streaming responses. This module
+//! estimates token counts from the prompt and completion text so cost can
+//! still be calculated:
+//! - OpenAI-compatible models are counted exactly with `tiktoken-rs` when the
+//!   `tiktoken` feature is enabled
+//! - All other models use a characters-per-token heuristic tuned per provider
+
+use llm_observatory_core::span::{ContentPart, LlmInput};
+
+/// Tokens added per chat message for role and separators.
+const CHAT_MESSAGE_OVERHEAD: u32 = 4;
+
+/// Tokens added once per chat request to prime the assistant reply.
+const CHAT_REPLY_PRIMING: u32 = 3;
+
+/// How a token count was obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenCountMethod {
+    /// Counted with the model's BPE tokenizer
+    Tokenizer,
+    /// Approximated from text length
+    Heuristic,
+}
+
+impl TokenCountMethod {
+    /// Get the method name as a string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenCountMethod::Tokenizer => "tokenizer",
+            TokenCountMethod::Heuristic => "heuristic",
+        }
+    }
+}
+
+/// A token count and how it was obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenCount {
+    /// Number of tokens
+    pub tokens: u32,
+    /// Counting method
+    pub method: TokenCountMethod,
+}
+
+/// Token counter for estimating usage from text.
+pub struct TokenCounter;
+
+impl TokenCounter {
+    /// Count tokens in a piece of text for the given model.
+    pub fn count_text(model: &str, text: &str) -> TokenCount {
+        #[cfg(feature = "tiktoken")]
+        if let Some(tokens) = tiktoken_count(model, text) {
+            return TokenCount {
+                tokens,
+                method: TokenCountMethod::Tokenizer,
+            };
+        }
+
+        TokenCount {
+            tokens: heuristic_count(model, text),
+            method: TokenCountMethod::Heuristic,
+        }
+    }
+
+    /// Count prompt tokens for an LLM input.
+    ///
+    /// Chat inputs include per-message formatting overhead. Non-text
+    /// multimodal parts are not counted.
+    pub fn count_input(model: &str, input: &LlmInput) -> TokenCount {
+        match input {
+            LlmInput::Text { prompt } => Self::count_text(model, prompt),
+            LlmInput::Chat { messages } => {
+                let mut total = CHAT_REPLY_PRIMING;
+                let mut method = TokenCountMethod::Tokenizer;
+
+                for message in messages {
+                    let content = Self::count_text(model, &message.content);
+                    let role = Self::count_text(model, &message.role);
+                    total += CHAT_MESSAGE_OVERHEAD + content.tokens + role.tokens;
+                    if content.method == TokenCountMethod::Heuristic {
+                        method = TokenCountMethod::Heuristic;
+                    }
+                }
+
+                if messages.is_empty() {
+                    method = TokenCountMethod::Heuristic;
+                }
+
+                TokenCount {
+                    tokens: total,
+                    method,
+                }
+            }
+            LlmInput::Multimodal { parts } => {
+                let text: Vec<&str> = parts
+                    .iter()
+                    .filter_map(|p| match p {
+                        ContentPart::Text { text } => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                Self::count_text(model, &text.join("\n"))
+            }
+        }
+    }
+}
+
+/// Approximate characters per token for a model's tokenizer.
+fn chars_per_token(model: &str) -> f64 {
+    if model.starts_with("claude") {
+        3.5
+    } else if model.starts_with("gemini") {
+        4.0
+    } else if model.starts_with("mistral") || model.starts_with("mixtral") {
+        3.7
+    } else {
+        // OpenAI-style BPE averages ~4 characters per token for English
+        4.0
+    }
+}
+
+/// Estimate tokens from text length.
+fn heuristic_count(model: &str, text: &str) -> u32 {
+    let chars = text.chars().count();
+    if chars == 0 {
+        return 0;
+    }
+    (chars as f64 / chars_per_token(model)).ceil() as u32
+}
+
+/// Count tokens with tiktoken if the model uses a known OpenAI tokenizer.
+#[cfg(feature = "tiktoken")]
+fn tiktoken_count(model: &str, text: &str) -> Option<u32> {
+    use tiktoken_rs::tokenizer::{get_tokenizer, Tokenizer};
+
+    let bpe = match get_tokenizer(model)? {
+        Tokenizer::O200kBase => tiktoken_rs::o200k_base_singleton(),
+        Tokenizer::Cl100kBase => tiktoken_rs::cl100k_base_singleton(),
+        _ => return None,
+    };
+
+    let tokens = bpe.lock().encode_with_special_tokens(text).len();
+    Some(tokens as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm_observatory_core::span::ChatMessage;
+
+    #[test]
+    fn test_heuristic_count() {
+        assert_eq!(heuristic_count("unknown-model", ""), 0);
+        assert_eq!(heuristic_count("unknown-model", "abcdefgh"), 2);
+        assert_eq!(heuristic_count("claude-3-haiku-20240307", "abcdefg"), 2);
+        assert_eq!(heuristic_count("unknown-model", "abcdefghi"), 3);
+    }
+
+    #[test]
+    fn test_count_unknown_model_uses_heuristic() {
+        let count = TokenCounter::count_text("my-finetune", "Hello, world!");
+        assert_eq!(count.method, TokenCountMethod::Heuristic);
+        assert_eq!(count.tokens, 4);
+    }
+
+    #[test]
+    fn test_count_chat_adds_overhead() {
+        let input = LlmInput::Chat {
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: "abcdefgh".to_string(),
+                name: None,
+            }],
+        };
+
+        let count = TokenCounter::count_input("my-finetune", &input);
+        // 3 priming + 4 overhead + 1 role + 2 content
+        assert_eq!(count.tokens, 10);
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn test_tiktoken_count() {
+        let count = TokenCounter::count_text("gpt-4o", "Hello, world!");
+        assert_eq!(count.method, TokenCountMethod::Tokenizer);
+        assert_eq!(count.tokens, 4);
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Example demonstrating W3C trace context propagation in an axum service.
+//!
+//! This example shows:
+//! - Parenting LLM spans to the caller's trace with `TraceContextLayer`
+//! - Using the extracted context in spawned tasks
+//! - Returning the `traceparent` of the LLM span to the caller
+//!
+//! Run with:
+//! ```bash
+//! export OPENAI_API_KEY=sk-...
+//! cargo run --example axum_propagation --features tower
+//! ```
+//!
+//! Then call it with a `traceparent` header:
+//! ```bash
+//! curl -X POST localhost:3000/chat \
+//!   -H 'traceparent: 00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01' \
+//!   -d 'What is the capital of France?'
+//! ```
+
+use axum::{extract::State, http::HeaderMap, routing::post, Extension, Router};
+use llm_observatory_sdk::{
+    propagation::{self, TraceContextLayer},
+    ChatCompletionRequest, InstrumentedLLM, LLMObservatory, OpenAIClient,
+};
+use opentelemetry::Context;
+use std::sync::Arc;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt::init();
+
+    let api_key = std::env::var("OPENAI_API_KEY")
+        .expect("OPENAI_API_KEY environment variable not set");
+
+    let observatory = LLMObservatory::builder()
+        .with_service_name("axum-propagation-example")
+        .build()?;
+
+    let client = Arc::new(OpenAIClient::new(api_key).with_observatory(observatory.clone()));
+
+    let app = Router::new()
+        .route("/chat", post(chat))
+        .route("/chat/background", post(chat_background))
+        .layer(TraceContextLayer::new())
+        .with_state(client);
+
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
+    println!("Listening on http://localhost:3000");
+    axum::serve(listener, app).await?;
+
+    observatory.shutdown().await?;
+    Ok(())
+}
+
+/// The layer makes the caller's context active, so the LLM span joins its
+/// trace without any extra code.
+async fn chat(State(client): State<Arc<OpenAIClient>>, prompt: String) -> (HeaderMap, String) {
+    let request = ChatCompletionRequest::new("gpt-4o-mini").with_user(prompt);
+
+    match client.chat_completion(request).await {
+        Ok(response) => {
+            // Echo the trace context so the caller can find the LLM span
+            let mut headers = HeaderMap::new();
+            propagation::inject_into_headers(&Context::current(), &mut headers);
+            (headers, response.content)
+        }
+        Err(e) => (HeaderMap::new(), format!("error: {}", e)),
+    }
+}
+
+/// Spawned tasks do not inherit the active context, so pass the extracted
+/// context explicitly.
+async fn chat_background(
+    State(client): State<Arc<OpenAIClient>>,
+    Extension(parent): Extension<Context>,
+    prompt: String,
+) -> &'static str {
+    tokio::spawn(async move {
+        let request = ChatCompletionRequest::new("gpt-4o-mini")
+            .with_user(prompt)
+            .with_parent_context(parent);
+        if let Err(e) = client.chat_completion(request).await {
+            eprintln!("background completion failed: {}", e);
+        }
+    });
+    "accepted"
+}
@@ -0,0 +1,338 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Request-scoped attributes carried in OpenTelemetry baggage.
+//!
+//! Set the user, session, team, feature flags and A/B experiment variant once
+//! at request entry with an [`ObservatoryContext`], and every LLM span created
+//! under that context
+//! records them, without passing them to each call. Values set explicitly on
+//! a span take precedence.
+//!
+//! Because the values are stored as baggage, they are also read from the
+//! `baggage` header by [`propagation::extract`](crate::propagation::extract),
+//! so attributes set by an upstream service flow through as well.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use llm_observatory_sdk::{ChatCompletionRequest, InstrumentedLLM, ObservatoryContext, OpenAIClient};
+//!
+//! # async fn example(client: OpenAIClient) -> llm_observatory_sdk::Result<()> {
+//! let _guard = ObservatoryContext::new()
+//!     .with_user_id("user-42")
+//!     .with_session_id("session-7")
+//!     .with_team_id("growth")
+//!     .with_feature_flag("new_prompt", "variant_b")
+//!     .with_experiment("checkout-prompt-v2", "treatment")
+//!     .attach();
+//!
+//! // The span records user.id, session.id, team.id, feature_flag.new_prompt,
+//! // experiment.id and experiment.variant
+//! let response = client
+//!     .chat_completion(ChatCompletionRequest::new("gpt-4o").with_user("Hello"))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use llm_observatory_core::types::Metadata;
+use opentelemetry::{baggage::BaggageExt, Context, ContextGuard, KeyValue};
+use std::collections::BTreeMap;
+
+/// Baggage key for the user ID.
+pub const USER_ID_KEY: &str = "user.id";
+
+/// Baggage key for the session ID.
+pub const SESSION_ID_KEY: &str = "session.id";
+
+/// Baggage key for the team ID.
+pub const TEAM_ID_KEY: &str = "team.id";
+
+/// Baggage key for the A/B experiment ID.
+pub const EXPERIMENT_ID_KEY: &str = "experiment.id";
+
+/// Baggage key for the experiment variant the request was assigned to.
+pub const EXPERIMENT_VARIANT_KEY: &str = "experiment.variant";
+
+/// Prefix of baggage keys holding feature flags.
+pub const FEATURE_FLAG_PREFIX: &str = "feature_flag.";
+
+/// Attributes applied to every LLM span created under a context.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObservatoryContext {
+    /// User identifier
+    pub user_id: Option<String>,
+    /// Session identifier
+    pub session_id: Option<String>,
+    /// Team identifier
+    pub team_id: Option<String>,
+    /// Feature flags and their variants
+    pub feature_flags: BTreeMap<String, String>,
+    /// A/B experiment ID
+    pub experiment_id: Option<String>,
+    /// Experiment variant
+    pub experiment_variant: Option<String>,
+}
+
+impl ObservatoryContext {
+    /// Create an empty context.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the attributes stored in a context's baggage.
+    pub fn from_context(context: &Context) -> Self {
+        let baggage = context.baggage();
+        let get = |key: &str| baggage.get(key).map(|value| value.as_str().into_owned());
+
+        let feature_flags = baggage
+            .iter()
+            .filter_map(|(key, (value, _))| {
+                key.as_str()
+                    .strip_prefix(FEATURE_FLAG_PREFIX)
+                    .map(|name| (name.to_string(), value.as_str().into_owned()))
+            })
+            .collect();
+
+        Self {
+            user_id: get(USER_ID_KEY),
+            session_id: get(SESSION_ID_KEY),
+            team_id: get(TEAM_ID_KEY),
+            feature_flags,
+            experiment_id: get(EXPERIMENT_ID_KEY),
+            experiment_variant: get(EXPERIMENT_VARIANT_KEY),
+        }
+    }
+
+    /// Read the attributes of the currently active context.
+    pub fn current() -> Self {
+        Self::from_context(&Context::current())
+    }
+
+    /// Set the user ID.
+    pub fn with_user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Set the session ID.
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Set the team ID.
+    pub fn with_team_id(mut self, team_id: impl Into<String>) -> Self {
+        self.team_id = Some(team_id.into());
+        self
+    }
+
+    /// Set a feature flag variant.
+    pub fn with_feature_flag(mut self, name: impl Into<String>, variant: impl Into<String>) -> Self {
+        self.feature_flags.insert(name.into(), variant.into());
+        self
+    }
+
+    /// Set the A/B experiment and the variant this request was assigned to.
+    ///
+    /// The experiment ID is the one registered with the analytics API
+    /// (`POST /api/v1/experiments`), which compares results per variant.
+    pub fn with_experiment(
+        mut self,
+        experiment_id: impl Into<String>,
+        variant: impl Into<String>,
+    ) -> Self {
+        self.experiment_id = Some(experiment_id.into());
+        self.experiment_variant = Some(variant.into());
+        self
+    }
+
+    /// Whether no attributes are set.
+    pub fn is_empty(&self) -> bool {
+        self.user_id.is_none()
+            && self.session_id.is_none()
+            && self.team_id.is_none()
+            && self.feature_flags.is_empty()
+            && self.experiment_id.is_none()
+            && self.experiment_variant.is_none()
+    }
+
+    /// Add the attributes to `context`'s baggage, keeping existing entries.
+    pub fn to_context(&self, context: &Context) -> Context {
+        let mut entries: Vec<KeyValue> = context
+            .baggage()
+            .iter()
+            .map(|(key, (value, _))| KeyValue::new(key.clone(), value.clone()))
+            .collect();
+        entries.extend(self.baggage_entries());
+        context.with_baggage(entries)
+    }
+
+    /// Add the attributes to the current context and make it active until
+    /// the guard is dropped.
+    pub fn attach(&self) -> ContextGuard {
+        self.to_context(&Context::current()).attach()
+    }
+
+    fn baggage_entries(&self) -> Vec<KeyValue> {
+        let mut entries = Vec::new();
+        if let Some(user_id) = &self.user_id {
+            entries.push(KeyValue::new(USER_ID_KEY, user_id.clone()));
+        }
+        if let Some(session_id) = &self.session_id {
+            entries.push(KeyValue::new(SESSION_ID_KEY, session_id.clone()));
+        }
+        if let Some(team_id) = &self.team_id {
+            entries.push(KeyValue::new(TEAM_ID_KEY, team_id.clone()));
+        }
+        for (name, variant) in &self.feature_flags {
+            entries.push(KeyValue::new(
+                format!("{}{}", FEATURE_FLAG_PREFIX, name),
+                variant.clone(),
+            ));
+        }
+        if let Some(experiment_id) = &self.experiment_id {
+            entries.push(KeyValue::new(EXPERIMENT_ID_KEY, experiment_id.clone()));
+        }
+        if let Some(variant) = &self.experiment_variant {
+            entries.push(KeyValue::new(EXPERIMENT_VARIANT_KEY, variant.clone()));
+        }
+        entries
+    }
+
+    /// Fill in span metadata not set explicitly.
+    pub(crate) fn apply_to_metadata(&self, metadata: &mut Metadata) {
+        if metadata.user_id.is_none() {
+            metadata.user_id = self.user_id.clone();
+        }
+        if metadata.session_id.is_none() {
+            metadata.session_id = self.session_id.clone();
+        }
+        if let Some(team_id) = &self.team_id {
+            metadata
+                .attributes
+                .entry(TEAM_ID_KEY.to_string())
+                .or_insert_with(|| team_id.clone());
+        }
+        for (name, variant) in &self.feature_flags {
+            metadata
+                .attributes
+                .entry(format!("{}{}", FEATURE_FLAG_PREFIX, name))
+                .or_insert_with(|| variant.clone());
+        }
+        // The experiment ID and variant are kept together, so an explicit
+        // experiment on the span is never paired with a variant from baggage
+        if let (Some(experiment_id), Some(variant)) = (&self.experiment_id, &self.experiment_variant) {
+            if !metadata.attributes.contains_key(EXPERIMENT_ID_KEY) {
+                metadata
+                    .attributes
+                    .insert(EXPERIMENT_ID_KEY.to_string(), experiment_id.clone());
+                metadata
+                    .attributes
+                    .insert(EXPERIMENT_VARIANT_KEY.to_string(), variant.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_round_trip() {
+        let observatory_context = ObservatoryContext::new()
+            .with_user_id("user-1")
+            .with_team_id("search")
+            .with_feature_flag("reranker", "on")
+            .with_experiment("rerank-ab", "control");
+
+        let base = Context::new().with_baggage(vec![KeyValue::new("tenant", "acme")]);
+        let context = observatory_context.to_context(&base);
+        assert_eq!(ObservatoryContext::from_context(&context), observatory_context);
+        assert_eq!(
+            context.baggage().get("tenant").map(|v| v.as_str().into_owned()),
+            Some("acme".to_string())
+        );
+
+        {
+            let _guard = observatory_context.attach();
+            assert_eq!(ObservatoryContext::current().user_id.as_deref(), Some("user-1"));
+        }
+        assert!(ObservatoryContext::current().is_empty());
+    }
+
+    #[test]
+    fn test_explicit_metadata_wins() {
+        let mut metadata = Metadata {
+            user_id: Some("explicit".to_string()),
+            ..Default::default()
+        };
+        ObservatoryContext::new()
+            .with_user_id("from-baggage")
+            .with_session_id("session-1")
+            .with_feature_flag("reranker", "on")
+            .apply_to_metadata(&mut metadata);
+
+        assert_eq!(metadata.user_id.as_deref(), Some("explicit"));
+        assert_eq!(metadata.session_id.as_deref(), Some("session-1"));
+        assert_eq!(
+            metadata.attributes.get("feature_flag.reranker").map(String::as_str),
+            Some("on")
+        );
+    }
+
+    #[test]
+    fn test_experiment_applied_as_pair() {
+        let context = ObservatoryContext::new().with_experiment("prompt-v2", "treatment");
+
+        let mut metadata = Metadata::default();
+        context.apply_to_metadata(&mut metadata);
+        assert_eq!(
+            metadata.attributes.get(EXPERIMENT_ID_KEY).map(String::as_str),
+            Some("prompt-v2")
+        );
+        assert_eq!(
+            metadata.attributes.get(EXPERIMENT_VARIANT_KEY).map(String::as_str),
+            Some("treatment")
+        );
+
+        // A span already in another experiment keeps its own variant
+        let mut metadata = Metadata::default();
+        metadata
+            .attributes
+            .insert(EXPERIMENT_ID_KEY.to_string(), "other".to_string());
+        context.apply_to_metadata(&mut metadata);
+        assert_eq!(
+            metadata.attributes.get(EXPERIMENT_ID_KEY).map(String::as_str),
+            Some("other")
+        );
+        assert!(!metadata.attributes.contains_key(EXPERIMENT_VARIANT_KEY));
+    }
+
+    #[tokio::test]
+    async fn test_spans_pick_up_context() {
+        use crate::{instrument::create_span, LLMObservatory, Provider};
+
+        let observatory = LLMObservatory::builder()
+            .with_service_name("baggage-test")
+            .build()
+            .unwrap();
+
+        let context = ObservatoryContext::new()
+            .with_user_id("user-1")
+            .with_team_id("search")
+            .to_context(&Context::new());
+        let span = create_span(&observatory, Provider::OpenAI, "gpt-4o")
+            .parent(context)
+            .start();
+        let llm_span = span.finish_error("test").unwrap();
+
+        assert_eq!(llm_span.metadata.user_id.as_deref(), Some("user-1"));
+        assert_eq!(
+            llm_span.metadata.attributes.get(TEAM_ID_KEY).map(String::as_str),
+            Some("search")
+        );
+    }
+}
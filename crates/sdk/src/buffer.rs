@@ -0,0 +1,910 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline buffering and retry for span export.
+//!
+//! [`ExportBuffer`] sits between the batch span processor and the OTLP
+//! exporter. Spans handed to it are queued in memory (and optionally spooled
+//! to disk when memory is full) and delivered by a background task that
+//! retries failed exports with exponential backoff. This keeps spans produced
+//! while the collector is unreachable, e.g. at application startup, instead
+//! of dropping them.
+//!
+//! On [`shutdown`](ExportBuffer::shutdown) the buffer keeps retrying until
+//! its shutdown timeout, then writes anything still undelivered to the spool
+//! so it is sent by the next process that opens the same spool file.
+
+use futures::future::BoxFuture;
+use opentelemetry::trace::{
+    Event, Link, SpanContext, SpanId, SpanKind, Status, TraceError, TraceFlags, TraceId,
+    TraceState,
+};
+use opentelemetry::{Array, InstrumentationScope, KeyValue, StringValue, Value};
+use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+use opentelemetry_sdk::trace::{SpanEvents, SpanLinks};
+use opentelemetry_sdk::Resource;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+/// Configuration for span buffering and export retries.
+#[derive(Debug, Clone)]
+pub struct BufferConfig {
+    /// Maximum spans held in memory
+    pub max_buffered_spans: usize,
+    /// File to spool spans to when memory is full and on shutdown
+    pub spool_path: Option<PathBuf>,
+    /// Maximum size of the spool file in bytes
+    pub max_spool_bytes: u64,
+    /// Maximum spans per export attempt
+    pub max_export_batch_size: usize,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound on the retry delay
+    pub max_backoff: Duration,
+    /// How long shutdown keeps retrying before spooling what is left
+    pub shutdown_timeout: Duration,
+}
+
+impl Default for BufferConfig {
+    fn default() -> Self {
+        Self {
+            max_buffered_spans: 10_000,
+            spool_path: None,
+            max_spool_bytes: 64 * 1024 * 1024,
+            max_export_batch_size: 512,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            shutdown_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Export queue metrics.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ExportStats {
+    /// Spans successfully exported
+    pub spans_exported: u64,
+    /// Spans dropped because the buffer and spool were full
+    pub spans_dropped: u64,
+    /// Spans currently queued in memory
+    pub spans_buffered: usize,
+    /// Spans currently spooled on disk
+    pub spans_spooled: usize,
+    /// Failed export attempts
+    pub export_failures: u64,
+    /// Failed export attempts since the last success
+    pub consecutive_failures: u32,
+    /// Most recent export error
+    pub last_error: Option<String>,
+}
+
+/// Shared buffer between the span processor, the retry task and shutdown.
+///
+/// Cloning is cheap; all clones refer to the same queue.
+#[derive(Clone)]
+pub struct ExportBuffer {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    config: BufferConfig,
+    state: Mutex<BufferState>,
+    exporter: tokio::sync::Mutex<Box<dyn SpanExporter>>,
+    work: Notify,
+    closing_signal: Notify,
+    closing: AtomicBool,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+struct BufferState {
+    queue: VecDeque<SpanData>,
+    spool: Option<DiskSpool>,
+    stats: ExportStats,
+}
+
+impl ExportBuffer {
+    /// Create a buffer delivering to `exporter`.
+    ///
+    /// Opens the spool file if one is configured; spans left in it by a
+    /// previous process are delivered first.
+    pub fn new(config: BufferConfig, exporter: impl SpanExporter + 'static) -> io::Result<Self> {
+        let spool = config
+            .spool_path
+            .clone()
+            .map(|path| DiskSpool::open(path, config.max_spool_bytes))
+            .transpose()?;
+
+        let stats = ExportStats {
+            spans_spooled: spool.as_ref().map_or(0, |s| s.len),
+            ..ExportStats::default()
+        };
+
+        let buffer = Self {
+            shared: Arc::new(Shared {
+                config,
+                state: Mutex::new(BufferState {
+                    queue: VecDeque::new(),
+                    spool,
+                    stats,
+                }),
+                exporter: tokio::sync::Mutex::new(Box::new(exporter)),
+                work: Notify::new(),
+                closing_signal: Notify::new(),
+                closing: AtomicBool::new(false),
+                worker: Mutex::new(None),
+            }),
+        };
+        buffer.ensure_worker();
+        Ok(buffer)
+    }
+
+    /// The [`SpanExporter`] to register with the span processor.
+    pub fn exporter(&self) -> BufferedExporter {
+        BufferedExporter {
+            buffer: self.clone(),
+        }
+    }
+
+    /// Current queue metrics.
+    pub fn stats(&self) -> ExportStats {
+        self.shared.lock_state().stats.clone()
+    }
+
+    /// Queue spans for export.
+    pub fn enqueue(&self, spans: Vec<SpanData>) {
+        if spans.is_empty() {
+            return;
+        }
+        {
+            let mut state = self.shared.lock_state();
+            state.queue.extend(spans);
+            state.enforce_capacity(self.shared.config.max_buffered_spans);
+        }
+        self.shared.work.notify_one();
+        self.ensure_worker();
+    }
+
+    /// Stop the retry task, deliver as much as possible within the shutdown
+    /// timeout, and spool the rest.
+    ///
+    /// Returns the final metrics.
+    pub async fn shutdown(&self) -> ExportStats {
+        let shared = &self.shared;
+        shared.closing.store(true, Ordering::Release);
+        shared.work.notify_one();
+        shared.closing_signal.notify_one();
+
+        let worker = shared.worker.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(worker) = worker {
+            let _ = worker.await;
+        }
+
+        let deadline = Instant::now() + shared.config.shutdown_timeout;
+        let mut backoff = shared.config.initial_backoff;
+        loop {
+            let batch = shared.next_batch();
+            if batch.is_empty() {
+                break;
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let result = tokio::time::timeout(remaining, shared.export(batch.clone()))
+                .await
+                .unwrap_or_else(|_| Err(TraceError::ExportTimedOut(remaining)));
+
+            if !shared.record_result(batch, result) {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                tokio::time::sleep(backoff.min(remaining)).await;
+                backoff = (backoff * 2).min(shared.config.max_backoff);
+            }
+        }
+
+        shared.exporter.lock().await.shutdown();
+
+        let mut state = shared.lock_state();
+        state.spool_all();
+        state.stats.clone()
+    }
+
+    fn ensure_worker(&self) {
+        if self.shared.closing.load(Ordering::Acquire) {
+            return;
+        }
+        let mut worker = self.shared.worker.lock().unwrap_or_else(|e| e.into_inner());
+        if worker.is_some() {
+            return;
+        }
+        // Spawned lazily: the buffer may be created before the runtime starts
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            *worker = Some(handle.spawn(run_worker(self.shared.clone())));
+        }
+    }
+}
+
+impl std::fmt::Debug for ExportBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExportBuffer")
+            .field("config", &self.shared.config)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+/// Deliver queued spans, backing off exponentially while exports fail.
+async fn run_worker(shared: Arc<Shared>) {
+    let mut backoff = shared.config.initial_backoff;
+
+    while !shared.closing.load(Ordering::Acquire) {
+        let batch = shared.next_batch();
+        if batch.is_empty() {
+            shared.work.notified().await;
+            continue;
+        }
+
+        let result = shared.export(batch.clone()).await;
+        if shared.record_result(batch, result) {
+            backoff = shared.config.initial_backoff;
+        } else {
+            tokio::select! {
+                _ = tokio::time::sleep(backoff) => {}
+                _ = shared.closing_signal.notified() => {}
+            }
+            backoff = (backoff * 2).min(shared.config.max_backoff);
+        }
+    }
+}
+
+impl Shared {
+    fn lock_state(&self) -> MutexGuard<'_, BufferState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn export(&self, batch: Vec<SpanData>) -> ExportResult {
+        let export = self.exporter.lock().await.export(batch);
+        export.await
+    }
+
+    /// Take the next batch, refilling memory from the spool when it is empty.
+    fn next_batch(&self) -> Vec<SpanData> {
+        let mut state = self.lock_state();
+        if state.queue.is_empty() {
+            state.unspool(self.config.max_buffered_spans);
+        }
+        let n = state.queue.len().min(self.config.max_export_batch_size);
+        let batch = state.queue.drain(..n).collect();
+        state.stats.spans_buffered = state.queue.len();
+        batch
+    }
+
+    /// Record an export attempt; failed batches go back to the front of the
+    /// queue. Returns whether the export succeeded.
+    fn record_result(&self, batch: Vec<SpanData>, result: ExportResult) -> bool {
+        let mut state = self.lock_state();
+        match result {
+            Ok(()) => {
+                state.stats.spans_exported += batch.len() as u64;
+                state.stats.consecutive_failures = 0;
+                true
+            }
+            Err(e) => {
+                state.stats.export_failures += 1;
+                state.stats.consecutive_failures += 1;
+                state.stats.last_error = Some(e.to_string());
+                for span in batch.into_iter().rev() {
+                    state.queue.push_front(span);
+                }
+                state.enforce_capacity(self.config.max_buffered_spans);
+                false
+            }
+        }
+    }
+}
+
+impl BufferState {
+    /// Move the newest spans beyond `capacity` to the spool, dropping them if
+    /// there is no room.
+    fn enforce_capacity(&mut self, capacity: usize) {
+        if self.queue.len() > capacity {
+            let overflow: Vec<SpanData> = self.queue.drain(capacity..).collect();
+            self.spool_spans(overflow);
+        }
+        self.stats.spans_buffered = self.queue.len();
+    }
+
+    fn spool_all(&mut self) {
+        let spans: Vec<SpanData> = self.queue.drain(..).collect();
+        self.spool_spans(spans);
+        self.stats.spans_buffered = 0;
+    }
+
+    fn spool_spans(&mut self, spans: Vec<SpanData>) {
+        let total = spans.len();
+        let written = match self.spool.as_mut() {
+            Some(spool) => spool.append(&spans).unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "Failed to spool spans to disk");
+                0
+            }),
+            None => 0,
+        };
+        self.stats.spans_dropped += (total - written) as u64;
+        self.stats.spans_spooled = self.spool.as_ref().map_or(0, |s| s.len);
+    }
+
+    fn unspool(&mut self, max: usize) {
+        if let Some(spool) = self.spool.as_mut() {
+            match spool.take(max) {
+                Ok(spans) => self.queue.extend(spans),
+                Err(e) => tracing::warn!(error = %e, "Failed to read spooled spans"),
+            }
+            self.stats.spans_spooled = spool.len;
+        }
+    }
+}
+
+/// [`SpanExporter`] that hands spans to an [`ExportBuffer`].
+///
+/// Export always succeeds immediately; delivery and retries happen in the
+/// buffer's background task.
+#[derive(Debug)]
+pub struct BufferedExporter {
+    buffer: ExportBuffer,
+}
+
+impl SpanExporter for BufferedExporter {
+    fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+        self.buffer.enqueue(batch);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        // Called while the provider is built, before any export holds the lock
+        if let Ok(mut exporter) = self.buffer.shared.exporter.try_lock() {
+            exporter.set_resource(resource);
+        }
+    }
+}
+
+// ============================================================================
+// Disk spool
+// ============================================================================
+
+/// Append-only JSON-lines file of undelivered spans.
+struct DiskSpool {
+    path: PathBuf,
+    max_bytes: u64,
+    len: usize,
+}
+
+impl DiskSpool {
+    fn open(path: PathBuf, max_bytes: u64) -> io::Result<Self> {
+        let len = match File::open(&path) {
+            Ok(file) => BufReader::new(file).lines().count(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            path,
+            max_bytes,
+            len,
+        })
+    }
+
+    /// Append spans until the size limit is reached. Returns how many were
+    /// written.
+    fn append(&mut self, spans: &[SpanData]) -> io::Result<usize> {
+        if spans.is_empty() {
+            return Ok(0);
+        }
+
+        let mut size = fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+
+        let mut written = 0;
+        for span in spans {
+            let mut line = serde_json::to_vec(&StoredSpan::from(span))?;
+            line.push(b'\n');
+            if size + line.len() as u64 > self.max_bytes {
+                break;
+            }
+            file.write_all(&line)?;
+            size += line.len() as u64;
+            written += 1;
+        }
+        file.flush()?;
+
+        self.len += written;
+        Ok(written)
+    }
+
+    /// Remove and return up to `max` spans, oldest first.
+    fn take(&mut self, max: usize) -> io::Result<Vec<SpanData>> {
+        if self.len == 0 {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        let mut lines = content.lines();
+        let spans: Vec<SpanData> = lines
+            .by_ref()
+            .take(max)
+            .filter_map(|line| serde_json::from_str::<StoredSpan>(line).ok())
+            .filter_map(|stored| stored.into_span_data())
+            .collect();
+
+        let rest: Vec<&str> = lines.collect();
+        if rest.is_empty() {
+            fs::remove_file(&self.path)?;
+        } else {
+            let mut remaining = rest.join("\n");
+            remaining.push('\n');
+            fs::write(&self.path, remaining)?;
+        }
+
+        self.len = rest.len();
+        Ok(spans)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredSpan {
+    context: StoredContext,
+    parent_span_id: String,
+    kind: String,
+    name: String,
+    start_time: SystemTime,
+    end_time: SystemTime,
+    attributes: Vec<StoredKeyValue>,
+    dropped_attributes_count: u32,
+    events: Vec<StoredEvent>,
+    dropped_events_count: u32,
+    links: Vec<StoredLink>,
+    dropped_links_count: u32,
+    status: Option<String>,
+    status_ok: bool,
+    scope_name: String,
+    scope_version: Option<String>,
+    scope_schema_url: Option<String>,
+    scope_attributes: Vec<StoredKeyValue>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredContext {
+    trace_id: String,
+    span_id: String,
+    trace_flags: u8,
+    is_remote: bool,
+    trace_state: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredEvent {
+    name: String,
+    timestamp: SystemTime,
+    attributes: Vec<StoredKeyValue>,
+    dropped_attributes_count: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredLink {
+    context: StoredContext,
+    attributes: Vec<StoredKeyValue>,
+    dropped_attributes_count: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredKeyValue {
+    key: String,
+    value: StoredValue,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+enum StoredValue {
+    Bool(bool),
+    I64(i64),
+    F64(f64),
+    String(String),
+    BoolArray(Vec<bool>),
+    I64Array(Vec<i64>),
+    F64Array(Vec<f64>),
+    StringArray(Vec<String>),
+}
+
+impl From<&SpanData> for StoredSpan {
+    fn from(span: &SpanData) -> Self {
+        let (status, status_ok) = match &span.status {
+            Status::Unset => (None, false),
+            Status::Ok => (None, true),
+            Status::Error { description } => (Some(description.to_string()), false),
+        };
+
+        Self {
+            context: StoredContext::from(&span.span_context),
+            parent_span_id: span.parent_span_id.to_string(),
+            kind: span_kind_str(&span.span_kind).to_string(),
+            name: span.name.to_string(),
+            start_time: span.start_time,
+            end_time: span.end_time,
+            attributes: store_attributes(&span.attributes),
+            dropped_attributes_count: span.dropped_attributes_count,
+            events: span
+                .events
+                .iter()
+                .map(|event| StoredEvent {
+                    name: event.name.to_string(),
+                    timestamp: event.timestamp,
+                    attributes: store_attributes(&event.attributes),
+                    dropped_attributes_count: event.dropped_attributes_count,
+                })
+                .collect(),
+            dropped_events_count: span.events.dropped_count,
+            links: span
+                .links
+                .iter()
+                .map(|link| StoredLink {
+                    context: StoredContext::from(&link.span_context),
+                    attributes: store_attributes(&link.attributes),
+                    dropped_attributes_count: link.dropped_attributes_count,
+                })
+                .collect(),
+            dropped_links_count: span.links.dropped_count,
+            status,
+            status_ok,
+            scope_name: span.instrumentation_scope.name().to_string(),
+            scope_version: span.instrumentation_scope.version().map(str::to_string),
+            scope_schema_url: span.instrumentation_scope.schema_url().map(str::to_string),
+            scope_attributes: span
+                .instrumentation_scope
+                .attributes()
+                .filter_map(StoredKeyValue::from_key_value)
+                .collect(),
+        }
+    }
+}
+
+impl StoredSpan {
+    fn into_span_data(self) -> Option<SpanData> {
+        let mut events = SpanEvents::default();
+        events.events = self
+            .events
+            .into_iter()
+            .map(|e| {
+                Event::new(
+                    e.name,
+                    e.timestamp,
+                    restore_attributes(e.attributes),
+                    e.dropped_attributes_count,
+                )
+            })
+            .collect();
+        events.dropped_count = self.dropped_events_count;
+
+        let mut links = SpanLinks::default();
+        links.links = self
+            .links
+            .into_iter()
+            .map(|l| {
+                Some(Link::new(
+                    l.context.into_span_context()?,
+                    restore_attributes(l.attributes),
+                    l.dropped_attributes_count,
+                ))
+            })
+            .collect::<Option<_>>()?;
+        links.dropped_count = self.dropped_links_count;
+
+        let status = match (self.status, self.status_ok) {
+            (Some(description), _) => Status::error(description),
+            (None, true) => Status::Ok,
+            (None, false) => Status::Unset,
+        };
+
+        let mut scope = InstrumentationScope::builder(self.scope_name)
+            .with_attributes(restore_attributes(self.scope_attributes));
+        if let Some(version) = self.scope_version {
+            scope = scope.with_version(version);
+        }
+        if let Some(schema_url) = self.scope_schema_url {
+            scope = scope.with_schema_url(schema_url);
+        }
+
+        Some(SpanData {
+            span_context: self.context.into_span_context()?,
+            parent_span_id: SpanId::from_hex(&self.parent_span_id).ok()?,
+            span_kind: span_kind_from_str(&self.kind),
+            name: self.name.into(),
+            start_time: self.start_time,
+            end_time: self.end_time,
+            attributes: restore_attributes(self.attributes),
+            dropped_attributes_count: self.dropped_attributes_count,
+            events,
+            links,
+            status,
+            instrumentation_scope: scope.build(),
+        })
+    }
+}
+
+impl From<&SpanContext> for StoredContext {
+    fn from(context: &SpanContext) -> Self {
+        Self {
+            trace_id: context.trace_id().to_string(),
+            span_id: context.span_id().to_string(),
+            trace_flags: context.trace_flags().to_u8(),
+            is_remote: context.is_remote(),
+            trace_state: context.trace_state().header(),
+        }
+    }
+}
+
+impl StoredContext {
+    fn into_span_context(self) -> Option<SpanContext> {
+        Some(SpanContext::new(
+            TraceId::from_hex(&self.trace_id).ok()?,
+            SpanId::from_hex(&self.span_id).ok()?,
+            TraceFlags::new(self.trace_flags),
+            self.is_remote,
+            TraceState::from_str(&self.trace_state).unwrap_or_default(),
+        ))
+    }
+}
+
+impl StoredKeyValue {
+    fn from_key_value(kv: &KeyValue) -> Option<Self> {
+        let value = match &kv.value {
+            Value::Bool(v) => StoredValue::Bool(*v),
+            Value::I64(v) => StoredValue::I64(*v),
+            Value::F64(v) => StoredValue::F64(*v),
+            Value::String(v) => StoredValue::String(v.to_string()),
+            Value::Array(Array::Bool(v)) => StoredValue::BoolArray(v.clone()),
+            Value::Array(Array::I64(v)) => StoredValue::I64Array(v.clone()),
+            Value::Array(Array::F64(v)) => StoredValue::F64Array(v.clone()),
+            Value::Array(Array::String(v)) => {
+                StoredValue::StringArray(v.iter().map(|s| s.to_string()).collect())
+            }
+            _ => return None,
+        };
+        Some(Self {
+            key: kv.key.to_string(),
+            value,
+        })
+    }
+
+    fn into_key_value(self) -> KeyValue {
+        let value = match self.value {
+            StoredValue::Bool(v) => Value::Bool(v),
+            StoredValue::I64(v) => Value::I64(v),
+            StoredValue::F64(v) => Value::F64(v),
+            StoredValue::String(v) => Value::String(v.into()),
+            StoredValue::BoolArray(v) => Value::Array(Array::Bool(v)),
+            StoredValue::I64Array(v) => Value::Array(Array::I64(v)),
+            StoredValue::F64Array(v) => Value::Array(Array::F64(v)),
+            StoredValue::StringArray(v) => {
+                Value::Array(Array::String(v.into_iter().map(StringValue::from).collect()))
+            }
+        };
+        KeyValue::new(self.key, value)
+    }
+}
+
+fn store_attributes(attributes: &[KeyValue]) -> Vec<StoredKeyValue> {
+    attributes
+        .iter()
+        .filter_map(StoredKeyValue::from_key_value)
+        .collect()
+}
+
+fn restore_attributes(attributes: Vec<StoredKeyValue>) -> Vec<KeyValue> {
+    attributes
+        .into_iter()
+        .map(StoredKeyValue::into_key_value)
+        .collect()
+}
+
+fn span_kind_str(kind: &SpanKind) -> &'static str {
+    match kind {
+        SpanKind::Client => "client",
+        SpanKind::Server => "server",
+        SpanKind::Producer => "producer",
+        SpanKind::Consumer => "consumer",
+        SpanKind::Internal => "internal",
+    }
+}
+
+fn span_kind_from_str(kind: &str) -> SpanKind {
+    match kind {
+        "client" => SpanKind::Client,
+        "server" => SpanKind::Server,
+        "producer" => SpanKind::Producer,
+        "consumer" => SpanKind::Consumer,
+        _ => SpanKind::Internal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[derive(Debug, Clone, Default)]
+    struct MockExporter {
+        failures_left: Arc<AtomicUsize>,
+        exported: Arc<Mutex<Vec<SpanData>>>,
+    }
+
+    impl MockExporter {
+        fn failing(times: usize) -> Self {
+            let exporter = Self::default();
+            exporter.failures_left.store(times, Ordering::SeqCst);
+            exporter
+        }
+
+        fn exported(&self) -> usize {
+            self.exported.lock().unwrap().len()
+        }
+    }
+
+    impl SpanExporter for MockExporter {
+        fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+            let fail = self
+                .failures_left
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if fail {
+                return Box::pin(async { Err(TraceError::Other("collector unreachable".into())) });
+            }
+            self.exported.lock().unwrap().extend(batch);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    fn config() -> BufferConfig {
+        BufferConfig {
+            initial_backoff: Duration::from_millis(5),
+            max_backoff: Duration::from_millis(20),
+            shutdown_timeout: Duration::from_millis(50),
+            ..BufferConfig::default()
+        }
+    }
+
+    fn span(i: u64) -> SpanData {
+        let mut events = SpanEvents::default();
+        events.events.push(Event::new(
+            "llm.first_token",
+            SystemTime::UNIX_EPOCH + Duration::from_millis(1_500),
+            vec![KeyValue::new("ttft_ms", 500)],
+            0,
+        ));
+
+        SpanData {
+            span_context: SpanContext::new(
+                TraceId::from_bytes((0xabc + i as u128).to_be_bytes()),
+                SpanId::from_bytes((i + 1).to_be_bytes()),
+                TraceFlags::SAMPLED,
+                false,
+                TraceState::from_str("vendor=value").unwrap(),
+            ),
+            parent_span_id: SpanId::INVALID,
+            span_kind: SpanKind::Client,
+            name: "llm.chat.completion".into(),
+            start_time: SystemTime::UNIX_EPOCH + Duration::from_secs(1),
+            end_time: SystemTime::UNIX_EPOCH + Duration::from_secs(2),
+            attributes: vec![
+                KeyValue::new("gen_ai.request.model", "gpt-4o"),
+                KeyValue::new("gen_ai.usage.input_tokens", 42),
+                KeyValue::new("cost.usd", 0.0125),
+                KeyValue::new("cached", true),
+                KeyValue::new(
+                    "gen_ai.response.finish_reasons",
+                    Value::Array(Array::String(vec!["stop".into()])),
+                ),
+            ],
+            dropped_attributes_count: 0,
+            events,
+            links: SpanLinks::default(),
+            status: Status::error("rate limited"),
+            instrumentation_scope: InstrumentationScope::builder("llm-observatory")
+                .with_version("0.1.1")
+                .build(),
+        }
+    }
+
+    async fn wait_for(condition: impl Fn() -> bool) {
+        for _ in 0..200 {
+            if condition() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("condition not met in time");
+    }
+
+    fn spool_path() -> PathBuf {
+        std::env::temp_dir().join(format!("llm-observatory-spool-{}.jsonl", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_stored_span_round_trip() {
+        let original = span(7);
+        let json = serde_json::to_string(&StoredSpan::from(&original)).unwrap();
+        let restored = serde_json::from_str::<StoredSpan>(&json)
+            .unwrap()
+            .into_span_data()
+            .unwrap();
+        assert_eq!(restored, original);
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_export_succeeds() {
+        let exporter = MockExporter::failing(2);
+        let buffer = ExportBuffer::new(config(), exporter.clone()).unwrap();
+
+        buffer.exporter().export((0..3).map(span).collect()).await.unwrap();
+        wait_for(|| exporter.exported() == 3).await;
+
+        let stats = buffer.stats();
+        assert_eq!(stats.spans_exported, 3);
+        assert_eq!(stats.export_failures, 2);
+        assert_eq!(stats.consecutive_failures, 0);
+        assert_eq!(stats.spans_buffered, 0);
+        assert!(stats.last_error.unwrap().contains("collector unreachable"));
+    }
+
+    #[tokio::test]
+    async fn test_overflow_is_dropped_without_spool() {
+        let buffer = ExportBuffer::new(
+            BufferConfig {
+                max_buffered_spans: 5,
+                ..config()
+            },
+            MockExporter::failing(usize::MAX),
+        )
+        .unwrap();
+
+        buffer.enqueue((0..8).map(span).collect());
+
+        let stats = buffer.stats();
+        assert_eq!(stats.spans_dropped, 3);
+        assert!(stats.spans_buffered <= 5);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_spools_and_restart_delivers() {
+        let path = spool_path();
+        let spool_config = BufferConfig {
+            spool_path: Some(path.clone()),
+            ..config()
+        };
+
+        let offline = ExportBuffer::new(spool_config.clone(), MockExporter::failing(usize::MAX)).unwrap();
+        offline.enqueue((0..4).map(span).collect());
+        let stats = offline.shutdown().await;
+        assert_eq!(stats.spans_spooled, 4);
+        assert_eq!(stats.spans_dropped, 0);
+        assert_eq!(stats.spans_exported, 0);
+
+        let exporter = MockExporter::default();
+        let online = ExportBuffer::new(spool_config, exporter.clone()).unwrap();
+        assert_eq!(online.stats().spans_spooled, 4);
+        wait_for(|| exporter.exported() == 4).await;
+
+        assert_eq!(exporter.exported.lock().unwrap()[0], span(0));
+        assert_eq!(online.shutdown().await.spans_spooled, 0);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,358 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Prompt and response capture controls.
+//!
+//! A [`CapturePolicy`] decides how much of each prompt and completion is
+//! recorded in telemetry:
+//!
+//! - [`CaptureMode::None`]: no content and no content statistics
+//! - [`CaptureMode::MetadataOnly`]: lengths and message counts, never text
+//! - [`CaptureMode::Full`]: the text itself, optionally truncated or hashed
+//!
+//! Full capture can also be sampled so that only a percentage of traces carry
+//! text; the rest fall back to metadata-only. The decision is made from the
+//! trace ID, so all spans of a trace are captured alike.
+//!
+//! Unless a policy is set on the
+//! [`ObservatoryBuilder`](crate::ObservatoryBuilder), production environments
+//! default to metadata-only and all others to full capture.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use llm_observatory_sdk::{CapturePolicy, LLMObservatory};
+//!
+//! # fn main() -> llm_observatory_sdk::Result<()> {
+//! let observatory = LLMObservatory::builder()
+//!     .with_service_name("support-bot")
+//!     .with_environment("production")
+//!     // Full text for 5% of traces, truncated to 2000 characters
+//!     .with_capture_policy(CapturePolicy::full().with_max_length(2000).with_sample_rate(0.05))
+//!     // Never capture content for the billing service
+//!     .with_service_capture_policy("billing", CapturePolicy::none())
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use llm_observatory_core::span::{ContentPart, LlmInput, LlmOutput};
+use opentelemetry::{trace::TraceId, KeyValue};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// How much prompt and response content is captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureMode {
+    /// Capture no content or content statistics
+    None,
+    /// Capture lengths and message counts only
+    MetadataOnly,
+    /// Capture the full text
+    Full,
+}
+
+impl CaptureMode {
+    /// Get the mode as a string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaptureMode::None => "none",
+            CaptureMode::MetadataOnly => "metadata_only",
+            CaptureMode::Full => "full",
+        }
+    }
+}
+
+/// Policy controlling prompt and response capture.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapturePolicy {
+    /// Capture mode
+    pub mode: CaptureMode,
+    /// Maximum captured length per text, in characters
+    pub max_length: Option<usize>,
+    /// Replace captured text with its SHA-256 hash
+    pub hash: bool,
+    /// Fraction of traces (0.0 to 1.0) that get full capture; the rest are
+    /// captured as metadata only
+    pub sample_rate: f64,
+}
+
+impl CapturePolicy {
+    /// Create a policy with the given mode.
+    pub fn new(mode: CaptureMode) -> Self {
+        Self {
+            mode,
+            max_length: None,
+            hash: false,
+            sample_rate: 1.0,
+        }
+    }
+
+    /// Capture no content.
+    pub fn none() -> Self {
+        Self::new(CaptureMode::None)
+    }
+
+    /// Capture content metadata only.
+    pub fn metadata_only() -> Self {
+        Self::new(CaptureMode::MetadataOnly)
+    }
+
+    /// Capture full content.
+    pub fn full() -> Self {
+        Self::new(CaptureMode::Full)
+    }
+
+    /// Default policy for a deployment environment: metadata-only in
+    /// production, full capture elsewhere.
+    pub fn for_environment(environment: &str) -> Self {
+        if is_production(environment) {
+            Self::metadata_only()
+        } else {
+            Self::full()
+        }
+    }
+
+    /// Truncate captured text to `max_length` characters.
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Replace captured text with its SHA-256 hash.
+    pub fn with_hashing(mut self, hash: bool) -> Self {
+        self.hash = hash;
+        self
+    }
+
+    /// Set the fraction of traces that get full capture (0.0 to 1.0).
+    pub fn with_sample_rate(mut self, rate: f64) -> Self {
+        self.sample_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Effective capture mode for a trace, after content sampling.
+    pub fn mode_for_trace(&self, trace_id: TraceId) -> CaptureMode {
+        if self.mode != CaptureMode::Full || self.sample_rate >= 1.0 {
+            return self.mode;
+        }
+
+        // Same scheme as the TraceIdRatioBased sampler: compare the low 64 bits
+        // of the trace ID against the rate
+        let bytes = trace_id.to_bytes();
+        let low = u64::from_be_bytes(bytes[8..].try_into().unwrap_or_default()) >> 1;
+        let threshold = (self.sample_rate * (1u64 << 63) as f64) as u64;
+        if low < threshold {
+            CaptureMode::Full
+        } else {
+            CaptureMode::MetadataOnly
+        }
+    }
+
+    /// Apply the policy to a span's input and output.
+    ///
+    /// Text is removed unless the effective mode is [`CaptureMode::Full`], in
+    /// which case it is hashed or truncated as configured. Returns the span
+    /// attributes describing the captured content.
+    pub(crate) fn apply(
+        &self,
+        mode: CaptureMode,
+        input: &mut LlmInput,
+        output: Option<&mut LlmOutput>,
+    ) -> Vec<KeyValue> {
+        let mut attributes = vec![KeyValue::new("gen_ai.content.capture", mode.as_str())];
+
+        if mode != CaptureMode::None {
+            attributes.push(KeyValue::new(
+                "gen_ai.prompt.length",
+                input_length(input) as i64,
+            ));
+            if let LlmInput::Chat { messages } = input {
+                attributes.push(KeyValue::new(
+                    "gen_ai.prompt.message_count",
+                    messages.len() as i64,
+                ));
+            }
+            if let Some(output) = &output {
+                attributes.push(KeyValue::new(
+                    "gen_ai.completion.length",
+                    output.content.chars().count() as i64,
+                ));
+            }
+        }
+
+        let mut truncated = false;
+        let mut process = |text: &mut String| {
+            if mode != CaptureMode::Full {
+                text.clear();
+            } else if self.hash {
+                *text = hash(text);
+            } else if let Some(max_length) = self.max_length {
+                truncated |= truncate(text, max_length);
+            }
+        };
+
+        match input {
+            LlmInput::Text { prompt } => process(prompt),
+            LlmInput::Chat { messages } => {
+                for message in messages {
+                    process(&mut message.content);
+                }
+            }
+            LlmInput::Multimodal { parts } => {
+                for part in parts {
+                    if let ContentPart::Text { text } = part {
+                        process(text);
+                    }
+                }
+            }
+        }
+        if let Some(output) = output {
+            process(&mut output.content);
+        }
+
+        if truncated {
+            attributes.push(KeyValue::new("gen_ai.content.truncated", true));
+        }
+        if mode == CaptureMode::Full && self.hash {
+            attributes.push(KeyValue::new("gen_ai.content.hashed", true));
+        }
+        attributes
+    }
+}
+
+impl Default for CapturePolicy {
+    fn default() -> Self {
+        Self::full()
+    }
+}
+
+fn is_production(environment: &str) -> bool {
+    environment.eq_ignore_ascii_case("production") || environment.eq_ignore_ascii_case("prod")
+}
+
+fn input_length(input: &LlmInput) -> usize {
+    match input {
+        LlmInput::Text { prompt } => prompt.chars().count(),
+        LlmInput::Chat { messages } => messages.iter().map(|m| m.content.chars().count()).sum(),
+        LlmInput::Multimodal { parts } => parts
+            .iter()
+            .map(|p| match p {
+                ContentPart::Text { text } => text.chars().count(),
+                _ => 0,
+            })
+            .sum(),
+    }
+}
+
+fn hash(text: &str) -> String {
+    format!("sha256:{}", hex::encode(Sha256::digest(text.as_bytes())))
+}
+
+/// Truncate `text` to at most `max_length` characters, returning whether it
+/// was shortened.
+fn truncate(text: &mut String, max_length: usize) -> bool {
+    match text.char_indices().nth(max_length) {
+        Some((index, _)) => {
+            text.truncate(index);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm_observatory_core::span::ChatMessage;
+
+    fn chat_input() -> LlmInput {
+        LlmInput::Chat {
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: "héllo world".to_string(),
+                name: None,
+            }],
+        }
+    }
+
+    fn output() -> LlmOutput {
+        LlmOutput {
+            content: "hi there".to_string(),
+            finish_reason: None,
+            metadata: Default::default(),
+        }
+    }
+
+    fn message_content(input: &LlmInput) -> &str {
+        match input {
+            LlmInput::Chat { messages } => &messages[0].content,
+            _ => unreachable!(),
+        }
+    }
+
+    fn attribute(attributes: &[KeyValue], key: &str) -> Option<String> {
+        attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.as_str().into_owned())
+    }
+
+    #[test]
+    fn test_environment_defaults() {
+        assert_eq!(CapturePolicy::for_environment("production").mode, CaptureMode::MetadataOnly);
+        assert_eq!(CapturePolicy::for_environment("PROD").mode, CaptureMode::MetadataOnly);
+        assert_eq!(CapturePolicy::for_environment("staging").mode, CaptureMode::Full);
+    }
+
+    #[test]
+    fn test_modes() {
+        let policy = CapturePolicy::full().with_max_length(5);
+        let mut input = chat_input();
+        let mut out = output();
+        let attributes = policy.apply(CaptureMode::Full, &mut input, Some(&mut out));
+        assert_eq!(message_content(&input), "héllo");
+        assert_eq!(out.content, "hi th");
+        assert_eq!(attribute(&attributes, "gen_ai.prompt.length").as_deref(), Some("11"));
+        assert_eq!(attribute(&attributes, "gen_ai.content.truncated").as_deref(), Some("true"));
+
+        let mut input = chat_input();
+        let mut out = output();
+        let attributes = policy.apply(CaptureMode::MetadataOnly, &mut input, Some(&mut out));
+        assert_eq!(message_content(&input), "");
+        assert_eq!(out.content, "");
+        assert_eq!(attribute(&attributes, "gen_ai.completion.length").as_deref(), Some("8"));
+
+        let mut input = chat_input();
+        let attributes = policy.apply(CaptureMode::None, &mut input, None);
+        assert_eq!(message_content(&input), "");
+        assert_eq!(attributes.len(), 1);
+    }
+
+    #[test]
+    fn test_hashing() {
+        let policy = CapturePolicy::full().with_hashing(true);
+        let mut input = chat_input();
+        policy.apply(CaptureMode::Full, &mut input, None);
+        assert!(message_content(&input).starts_with("sha256:"));
+        assert_eq!(message_content(&input), hash("héllo world"));
+    }
+
+    #[test]
+    fn test_content_sampling() {
+        let policy = CapturePolicy::full().with_sample_rate(0.25);
+        let full = (0..1000u128)
+            .map(|i| TraceId::from_bytes(i.wrapping_mul(0x9E37_79B9_7F4A_7C15_F39C_C060_5CED_C835).to_be_bytes()))
+            .filter(|id| policy.mode_for_trace(*id) == CaptureMode::Full)
+            .count();
+        assert!((150..350).contains(&full), "{} traces fully captured", full);
+
+        let never = CapturePolicy::full().with_sample_rate(0.0);
+        assert_eq!(never.mode_for_trace(TraceId::from_bytes([0xff; 16])), CaptureMode::MetadataOnly);
+        assert_eq!(
+            CapturePolicy::none().mode_for_trace(TraceId::from_bytes([0; 16])),
+            CaptureMode::None
+        );
+    }
+}
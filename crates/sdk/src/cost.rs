@@ -5,7 +5,7 @@
 
 use crate::{Error, Result};
 use llm_observatory_core::types::{Cost, TokenUsage};
-use llm_observatory_providers::pricing::{PricingEngine, PRICING_DB};
+use llm_observatory_providers::pricing::{BillableUsage, PricingEngine, PRICING_DB};
 
 /// Calculate the cost of an LLM operation.
 ///
@@ -41,6 +41,24 @@ pub fn calculate_cost(model: &str, usage: &TokenUsage) -> Result<Cost> {
     Ok(Cost::with_breakdown(prompt_cost, completion_cost))
 }
 
+/// Calculate the cost of an embeddings request.
+///
+/// Uses the embedding pricing dimension of the pricing database; models
+/// without embedding pricing return an error.
+///
+/// # Example
+///
+/// ```rust
+/// use llm_observatory_sdk::cost::calculate_embedding_cost;
+///
+/// let cost = calculate_embedding_cost("text-embedding-3-small", 1_000_000).unwrap();
+/// assert!((cost.amount_usd - 0.02).abs() < 1e-9);
+/// ```
+pub fn calculate_embedding_cost(model: &str, tokens: u32) -> Result<Cost> {
+    PricingEngine::calculate_usage_cost(model, &BillableUsage::Embedding { tokens }, false)
+        .map_err(|e| Error::CostCalculation(e.to_string()))
+}
+
 /// Calculate the cost with a fallback for unknown models.
 ///
 /// If the model is not in the pricing database, this function will use
@@ -233,6 +251,16 @@ mod tests {
         assert!((cost.amount_usd - 0.02).abs() < 0.0001);
     }
 
+    #[test]
+    fn test_calculate_embedding_cost() {
+        let cost = calculate_embedding_cost("text-embedding-3-large", 10_000).unwrap();
+        // $0.13 per 1M tokens
+        assert!((cost.amount_usd - 0.0013).abs() < 1e-9);
+
+        // Chat models have no embedding pricing
+        assert!(calculate_embedding_cost("gpt-4", 1000).is_err());
+    }
+
     #[test]
     fn test_has_pricing() {
         assert!(has_pricing("gpt-4"));
@@ -66,6 +66,21 @@ pub enum Error {
     #[error("Request timeout")]
     Timeout,
 
+    /// Record/replay error, such as a request without a recorded response
+    #[error("Replay error: {0}")]
+    Replay(String),
+
+    /// A spend cap was reached and the call was blocked
+    #[error("Spend cap exceeded for {scope}: ${spend_usd:.2} of ${limit_usd:.2}")]
+    SpendCapExceeded {
+        /// Scope of the cap, e.g. `organization` or `team search`
+        scope: String,
+        /// Spend in the current period in USD
+        spend_usd: f64,
+        /// Limit in USD
+        limit_usd: f64,
+    },
+
     /// Internal SDK error
     #[error("Internal error: {0}")]
     Internal(String),
@@ -114,6 +129,20 @@ impl Error {
         Self::CostCalculation(msg.into())
     }
 
+    /// Create a record/replay error.
+    pub fn replay(msg: impl Into<String>) -> Self {
+        Self::Replay(msg.into())
+    }
+
+    /// Create a spend cap exceeded error.
+    pub fn spend_cap_exceeded(scope: impl Into<String>, spend_usd: f64, limit_usd: f64) -> Self {
+        Self::SpendCapExceeded {
+            scope: scope.into(),
+            spend_usd,
+            limit_usd,
+        }
+    }
+
     /// Create an internal error.
     pub fn internal(msg: impl Into<String>) -> Self {
         Self::Internal(msg.into())
@@ -134,6 +163,11 @@ impl Error {
             Error::Auth(_) | Error::InvalidApiKey | Error::Api { status: 401, .. }
         )
     }
+
+    /// Check if the call was blocked by a spend cap.
+    pub fn is_spend_cap_exceeded(&self) -> bool {
+        matches!(self, Error::SpendCapExceeded { .. })
+    }
 }
 
 #[cfg(test)]
@@ -172,4 +206,15 @@ mod tests {
         let api_500 = Error::api(500, "server error");
         assert!(!api_500.is_auth_error());
     }
+
+    #[test]
+    fn test_spend_cap_exceeded() {
+        let err = Error::spend_cap_exceeded("team search", 120.5, 100.0);
+        assert!(err.is_spend_cap_exceeded());
+        assert!(!err.is_retryable());
+        assert_eq!(
+            err.to_string(),
+            "Spend cap exceeded for team search: $120.50 of $100.00"
+        );
+    }
 }
@@ -3,15 +3,30 @@
 
 //! Instrumentation utilities for creating and managing OpenTelemetry spans.
 
-use crate::{observatory::LLMObservatory, Result};
+use crate::{
+    baggage::{
+        ObservatoryContext, EXPERIMENT_ID_KEY, EXPERIMENT_VARIANT_KEY, FEATURE_FLAG_PREFIX,
+        TEAM_ID_KEY,
+    },
+    capture::CaptureMode,
+    observatory::LLMObservatory,
+    retrieval::RetrievalLink,
+    retry::AttemptLink,
+    tool::ToolCallBuilder,
+    traits::ChatCompletionRequest,
+    Result,
+};
 use chrono::Utc;
 use llm_observatory_core::{
-    span::{ChatMessage, LlmInput, LlmOutput, LlmSpan, SpanEvent, SpanStatus},
+    guardrail::{GuardrailOutcome, GUARDRAIL_EVENT},
+    rate_limit::RateLimitState,
+    span::{ChatMessage, LlmInput, LlmOutput, LlmSpan, SpanEvent, SpanLink, SpanStatus},
+    timing::{FIRST_BYTE_EVENT, FIRST_TOKEN_EVENT, REQUEST_SENT_EVENT},
     types::{Cost, Latency, Metadata, Provider, TokenUsage},
 };
 use opentelemetry::{
-    trace::{SpanKind, Status, TraceContextExt, Tracer},
-    Context, KeyValue,
+    trace::{Link, SpanKind, Status, TraceContextExt, Tracer},
+    Context, Key, KeyValue, Value,
 };
 use std::collections::HashMap;
 use std::time::Instant;
@@ -21,6 +36,8 @@ use std::time::Instant;
 /// This struct provides a convenient interface for creating instrumented LLM operations
 /// with automatic cost tracking, token usage, and semantic conventions.
 pub struct InstrumentedSpan {
+    observatory: LLMObservatory,
+    operation_name: String,
     context: Context,
     start_time: Instant,
     start_timestamp: chrono::DateTime<Utc>,
@@ -31,11 +48,15 @@ pub struct InstrumentedSpan {
     input: LlmInput,
     metadata: Metadata,
     events: Vec<SpanEvent>,
+    links: Vec<SpanLink>,
 }
 
 impl InstrumentedSpan {
     /// Create a new instrumented span.
+    #[allow(clippy::too_many_arguments)]
     fn new(
+        observatory: LLMObservatory,
+        operation_name: String,
         context: Context,
         span_id: String,
         trace_id: String,
@@ -45,6 +66,8 @@ impl InstrumentedSpan {
         metadata: Metadata,
     ) -> Self {
         Self {
+            observatory,
+            operation_name,
             context,
             start_time: Instant::now(),
             start_timestamp: Utc::now(),
@@ -55,6 +78,7 @@ impl InstrumentedSpan {
             input,
             metadata,
             events: Vec::new(),
+            links: Vec::new(),
         }
     }
 
@@ -68,6 +92,38 @@ impl InstrumentedSpan {
         &self.trace_id
     }
 
+    /// Get the context with this span active, e.g. for injecting trace
+    /// headers into downstream calls.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Get the model being called.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Start building a tool-call span as a child of this LLM span.
+    pub fn tool_call(&self, name: impl Into<String>) -> ToolCallBuilder {
+        ToolCallBuilder::new(self.observatory.clone(), self.context.clone(), name)
+    }
+
+    /// Set an attribute on the underlying OpenTelemetry span.
+    pub fn set_attribute(&self, key: impl Into<Key>, value: impl Into<Value>) {
+        self.context.span().set_attribute(KeyValue::new(key, value));
+    }
+
+    /// Sync the span with a request that may have been modified by interceptors.
+    pub(crate) fn update_request(&mut self, request: &ChatCompletionRequest) {
+        if request.model != self.model {
+            self.model = request.model.clone();
+            self.set_attribute("gen_ai.request.model", self.model.clone());
+        }
+        self.input = LlmInput::Chat {
+            messages: request.messages.clone(),
+        };
+    }
+
     /// Add an event to the span.
     pub fn add_event(&mut self, name: impl Into<String>, attributes: HashMap<String, serde_json::Value>) {
         self.events.push(SpanEvent {
@@ -82,16 +138,99 @@ impl InstrumentedSpan {
         let ttft_ms = self.start_time.elapsed().as_millis() as u64;
         let mut attrs = HashMap::new();
         attrs.insert("ttft_ms".to_string(), serde_json::json!(ttft_ms));
-        self.add_event("llm.first_token", attrs);
+        self.record_timing_event(FIRST_TOKEN_EVENT, attrs);
+    }
+
+    /// Record that the request left the client, after interceptors ran.
+    ///
+    /// Together with [`record_first_byte`](Self::record_first_byte), lets the
+    /// collector tell client-side overhead from provider queue time.
+    pub fn record_request_sent(&mut self) {
+        self.record_timing_event(REQUEST_SENT_EVENT, HashMap::new());
+    }
+
+    /// Record that the provider's response headers arrived.
+    ///
+    /// The collector splits the call at this point into provider queue time
+    /// and execution time.
+    pub fn record_first_byte(&mut self) {
+        self.record_timing_event(FIRST_BYTE_EVENT, HashMap::new());
+    }
+
+    /// Add a timing event to both the OpenTelemetry span and the returned
+    /// [`LlmSpan`].
+    fn record_timing_event(
+        &mut self,
+        name: &'static str,
+        attributes: HashMap<String, serde_json::Value>,
+    ) {
+        self.context.span().add_event(name, to_key_values(&attributes));
+        self.add_event(name, attributes);
+    }
+
+    /// Record the outcome of a content-safety guardrail.
+    ///
+    /// Adds a `guardrail.evaluation` event with the `guardrail.*` attributes
+    /// to both the OpenTelemetry span and the returned [`LlmSpan`], so the
+    /// collector can track violations. Call it once per guardrail.
+    pub fn record_guardrail(&mut self, outcome: &GuardrailOutcome) {
+        let attributes = outcome.to_attributes();
+        self.context
+            .span()
+            .add_event(GUARDRAIL_EVENT, to_key_values(&attributes));
+        self.add_event(GUARDRAIL_EVENT, attributes);
+    }
+
+    /// Record the provider's rate-limit state, parsed from the response
+    /// headers, as `llm_observatory.rate_limit.*` attributes.
+    ///
+    /// Does nothing when the provider reported no rate limits.
+    pub fn record_rate_limits(&self, state: &RateLimitState) {
+        let span = self.context.span();
+        for attribute in to_key_values(&state.to_attributes()) {
+            span.set_attribute(attribute);
+        }
+    }
+
+    /// Apply the observatory's capture policy to the input and output,
+    /// recording the permitted content on the OpenTelemetry span.
+    fn capture_content(&mut self, mut output: Option<&mut LlmOutput>) {
+        let policy = self.observatory.capture_policy();
+        let span = self.context.span();
+        let mode = policy.mode_for_trace(span.span_context().trace_id());
+
+        for attribute in policy.apply(mode, &mut self.input, output.as_deref_mut()) {
+            span.set_attribute(attribute);
+        }
+
+        if mode == CaptureMode::Full {
+            if let Ok(prompt) = serde_json::to_string(&self.input) {
+                span.add_event(
+                    "gen_ai.content.prompt",
+                    vec![KeyValue::new("gen_ai.prompt", prompt)],
+                );
+            }
+            if let Some(output) = output {
+                span.add_event(
+                    "gen_ai.content.completion",
+                    vec![KeyValue::new("gen_ai.completion", output.content.clone())],
+                );
+            }
+        }
     }
 
     /// Finish the span with a successful result.
+    ///
+    /// Prompt and completion content in the returned span are subject to the
+    /// observatory's [`CapturePolicy`](crate::CapturePolicy).
     pub fn finish_success(
-        self,
-        output: LlmOutput,
+        mut self,
+        mut output: LlmOutput,
         usage: TokenUsage,
         cost: Cost,
     ) -> Result<LlmSpan> {
+        self.capture_content(Some(&mut output));
+
         let end_timestamp = Utc::now();
         let latency = Latency::new(self.start_timestamp, end_timestamp);
 
@@ -107,10 +246,10 @@ impl InstrumentedSpan {
         );
 
         // Build LlmSpan
-        let llm_span = LlmSpan::builder()
+        let mut llm_span = LlmSpan::builder()
             .span_id(self.span_id)
             .trace_id(self.trace_id)
-            .name("llm.chat.completion")
+            .name(self.operation_name)
             .provider(self.provider)
             .model(self.model)
             .input(self.input)
@@ -122,12 +261,16 @@ impl InstrumentedSpan {
             .status(SpanStatus::Ok)
             .build()
             .map_err(|e| crate::Error::internal(e))?;
+        llm_span.events = self.events;
+        llm_span.links = self.links;
 
         Ok(llm_span)
     }
 
     /// Finish the span with an error.
-    pub fn finish_error(self, error: &str) -> Result<LlmSpan> {
+    pub fn finish_error(mut self, error: &str) -> Result<LlmSpan> {
+        self.capture_content(None);
+
         let end_timestamp = Utc::now();
         let latency = Latency::new(self.start_timestamp, end_timestamp);
 
@@ -137,10 +280,10 @@ impl InstrumentedSpan {
         span.add_event("llm.completion.error", vec![KeyValue::new("error", error.to_string())]);
 
         // Build LlmSpan
-        let llm_span = LlmSpan::builder()
+        let mut llm_span = LlmSpan::builder()
             .span_id(self.span_id)
             .trace_id(self.trace_id)
-            .name("llm.chat.completion")
+            .name(self.operation_name)
             .provider(self.provider)
             .model(self.model)
             .input(self.input)
@@ -149,6 +292,8 @@ impl InstrumentedSpan {
             .status(SpanStatus::Error)
             .build()
             .map_err(|e| crate::Error::internal(e))?;
+        llm_span.events = self.events;
+        llm_span.links = self.links;
 
         Ok(llm_span)
     }
@@ -161,8 +306,12 @@ pub struct SpanBuilder {
     provider: Provider,
     model: String,
     messages: Vec<ChatMessage>,
+    input: Option<LlmInput>,
     metadata: Metadata,
     attributes: HashMap<String, String>,
+    retrieval: Option<RetrievalLink>,
+    attempt: Option<AttemptLink>,
+    parent: Option<Context>,
 }
 
 impl SpanBuilder {
@@ -178,11 +327,23 @@ impl SpanBuilder {
             provider,
             model: model.into(),
             messages: Vec::new(),
+            input: None,
             metadata: Metadata::default(),
             attributes: HashMap::new(),
+            retrieval: None,
+            attempt: None,
+            parent: None,
         }
     }
 
+    /// Start building a tool-call span under the currently active context.
+    ///
+    /// Use [`InstrumentedSpan::tool_call`] to parent the tool call to a
+    /// specific LLM span instead.
+    pub fn tool_call(observatory: &LLMObservatory, name: impl Into<String>) -> ToolCallBuilder {
+        ToolCallBuilder::new(observatory.clone(), Context::current(), name)
+    }
+
     /// Set the operation name.
     pub fn operation_name(mut self, name: impl Into<String>) -> Self {
         self.operation_name = name.into();
@@ -195,6 +356,12 @@ impl SpanBuilder {
         self
     }
 
+    /// Set a non-chat input (e.g. embedding texts), replacing the messages.
+    pub fn input(mut self, input: LlmInput) -> Self {
+        self.input = Some(input);
+        self
+    }
+
     /// Set metadata.
     pub fn metadata(mut self, metadata: Metadata) -> Self {
         self.metadata = metadata;
@@ -207,9 +374,36 @@ impl SpanBuilder {
         self
     }
 
+    /// Link the span to the retrieval that supplied its context.
+    pub fn retrieval(mut self, link: RetrievalLink) -> Self {
+        self.retrieval = Some(link);
+        self
+    }
+
+    /// Record the span as one attempt of a retried or fallen-back call,
+    /// linked to the attempt before it.
+    pub fn attempt(mut self, link: AttemptLink) -> Self {
+        self.attempt = Some(link);
+        self
+    }
+
+    /// Set the parent context (default: the currently active context).
+    ///
+    /// Use with [`propagation::extract`](crate::propagation::extract) to join
+    /// a trace started by an upstream service.
+    pub fn parent(mut self, parent: Context) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
     /// Build and start the instrumented span.
-    pub fn start(self) -> InstrumentedSpan {
+    ///
+    /// Attributes from the parent context's [`ObservatoryContext`] fill in
+    /// metadata that was not set explicitly.
+    pub fn start(mut self) -> InstrumentedSpan {
         let tracer = self.observatory.tracer();
+        let parent = self.parent.take().unwrap_or_else(Context::current);
+        ObservatoryContext::from_context(&parent).apply_to_metadata(&mut self.metadata);
 
         // Create OpenTelemetry span with semantic conventions
         let mut span_builder = tracer
@@ -239,24 +433,64 @@ impl SpanBuilder {
         if let Some(env) = &self.metadata.environment {
             otel_attributes.push(KeyValue::new("environment", env.clone()));
         }
+        for (key, value) in &self.metadata.attributes {
+            if key == TEAM_ID_KEY
+                || key == EXPERIMENT_ID_KEY
+                || key == EXPERIMENT_VARIANT_KEY
+                || key.starts_with(FEATURE_FLAG_PREFIX)
+            {
+                otel_attributes.push(KeyValue::new(key.clone(), value.clone()));
+            }
+        }
+
+        // Link to the retrieval span for RAG calls, and to the previous
+        // attempt of retried calls
+        let mut links = Vec::new();
+        if let Some(link) = &self.retrieval {
+            otel_attributes.extend(link.attributes());
+            links.push(Link::with_context(link.span_context().clone()));
+        }
+        if let Some(attempt) = &self.attempt {
+            otel_attributes.extend(attempt.attributes());
+            if let Some(previous) = attempt.previous_span_context() {
+                links.push(Link::with_context(previous.clone()));
+            }
+        }
+        let span_links: Vec<SpanLink> = links
+            .iter()
+            .map(|link| {
+                SpanLink::new(
+                    format!("{:x}", link.span_context.trace_id()),
+                    format!("{:x}", link.span_context.span_id()),
+                )
+            })
+            .collect();
+        if !links.is_empty() {
+            span_builder = span_builder.with_links(links);
+        }
 
         span_builder = span_builder.with_attributes(otel_attributes);
 
-        let span = tracer.build(span_builder);
-        let context = Context::current_with_span(span);
+        let span = tracer.build_with_context(span_builder, &parent);
+        let context = parent.with_span(span);
 
         // Extract span and trace IDs
         let span = context.span();
         let span_context = span.span_context();
         let span_id = format!("{:x}", span_context.span_id());
         let trace_id = format!("{:x}", span_context.trace_id());
+        if let Some(attempt) = &self.attempt {
+            attempt.record_started(span_context.clone());
+        }
 
         // Create LLM input
-        let input = LlmInput::Chat {
+        let input = self.input.unwrap_or(LlmInput::Chat {
             messages: self.messages,
-        };
+        });
 
-        InstrumentedSpan::new(
+        let mut span = InstrumentedSpan::new(
+            self.observatory,
+            self.operation_name,
             context,
             span_id,
             trace_id,
@@ -264,7 +498,9 @@ impl SpanBuilder {
             self.model,
             input,
             self.metadata,
-        )
+        );
+        span.links = span_links;
+        span
     }
 }
 
@@ -277,6 +513,26 @@ pub fn create_span(
     SpanBuilder::new(observatory.clone(), provider, model)
 }
 
+/// Convert JSON attributes to OpenTelemetry key-values, dropping nulls,
+/// arrays and objects.
+fn to_key_values(attributes: &HashMap<String, serde_json::Value>) -> Vec<KeyValue> {
+    attributes
+        .iter()
+        .filter_map(|(key, value)| {
+            let value: Value = match value {
+                serde_json::Value::Bool(b) => (*b).into(),
+                serde_json::Value::Number(n) => match n.as_i64() {
+                    Some(i) => i.into(),
+                    None => n.as_f64()?.into(),
+                },
+                serde_json::Value::String(s) => s.clone().into(),
+                _ => return None,
+            };
+            Some(KeyValue::new(key.clone(), value))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -286,4 +542,32 @@ mod tests {
         // Note: This test requires a valid observatory instance
         // In practice, this would be tested with integration tests
     }
+
+    #[tokio::test]
+    async fn test_record_guardrail() {
+        use llm_observatory_core::guardrail::GuardrailStage;
+
+        let observatory = LLMObservatory::builder()
+            .with_service_name("guardrail-test")
+            .build()
+            .unwrap();
+
+        let mut span = create_span(&observatory, Provider::OpenAI, "gpt-4o").start();
+        span.record_guardrail(
+            &GuardrailOutcome::new("moderation")
+                .with_stage(GuardrailStage::Input)
+                .with_category("violence")
+                .blocked(),
+        );
+        let llm_span = span.finish_error("blocked by guardrail").unwrap();
+
+        let event = llm_span
+            .events
+            .iter()
+            .find(|e| e.name == GUARDRAIL_EVENT)
+            .unwrap();
+        let outcome = GuardrailOutcome::from_attributes(&event.attributes).unwrap();
+        assert!(outcome.blocked);
+        assert_eq!(outcome.category.as_deref(), Some("violence"));
+    }
 }
@@ -0,0 +1,332 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Interceptor hooks that run around every instrumented LLM call.
+//!
+//! Interceptors are registered on an [`LLMObservatory`](crate::LLMObservatory)
+//! and are invoked by every client attached to it. They can mutate the request
+//! before it is sent, enrich the span, rewrite the response, or reject a call
+//! outright (e.g. a guardrail), making them the extension point for
+//! cross-cutting concerns that should not live in each client.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use llm_observatory_sdk::{
+//!     async_trait, ChatCompletionRequest, Error, GuardrailOutcome, GuardrailStage,
+//!     InstrumentedSpan, LLMObservatory, LlmInterceptor, Result,
+//! };
+//!
+//! struct BlockSecrets;
+//!
+//! #[async_trait]
+//! impl LlmInterceptor for BlockSecrets {
+//!     async fn before_request(
+//!         &self,
+//!         request: &mut ChatCompletionRequest,
+//!         span: &mut InstrumentedSpan,
+//!     ) -> Result<()> {
+//!         if request.messages.iter().any(|m| m.content.contains("sk-")) {
+//!             span.record_guardrail(
+//!                 &GuardrailOutcome::new("block-secrets")
+//!                     .with_stage(GuardrailStage::Input)
+//!                     .with_category("secrets")
+//!                     .blocked(),
+//!             );
+//!             return Err(Error::invalid_input("request contains an API key"));
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! # fn main() -> Result<()> {
+//! let observatory = LLMObservatory::builder()
+//!     .with_service_name("my-app")
+//!     .build()?;
+//! observatory.add_interceptor(BlockSecrets);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    instrument::InstrumentedSpan,
+    traits::{ChatCompletionRequest, ChatCompletionResponse},
+    Error, Result,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Hooks invoked around an instrumented LLM call.
+///
+/// All hooks have no-op defaults, so implementations only override the ones
+/// they need. `before_request` hooks run in registration order;
+/// `after_response` and `on_error` run in reverse, so the first interceptor
+/// registered wraps all the others.
+#[async_trait]
+pub trait LlmInterceptor: Send + Sync {
+    /// Called before the request is sent to the provider.
+    ///
+    /// The request may be modified; changes to the model or messages are
+    /// reflected on the span. Returning an error aborts the call, which is
+    /// then reported to every interceptor's [`on_error`](Self::on_error).
+    async fn before_request(
+        &self,
+        _request: &mut ChatCompletionRequest,
+        _span: &mut InstrumentedSpan,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called after a successful response, before the span is finished.
+    ///
+    /// The response may be modified. Returning an error turns the call into a
+    /// failure.
+    async fn after_response(
+        &self,
+        _request: &ChatCompletionRequest,
+        _response: &mut ChatCompletionResponse,
+        _span: &mut InstrumentedSpan,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when the call fails, including when another interceptor
+    /// rejected it, before the span is finished.
+    async fn on_error(
+        &self,
+        _request: &ChatCompletionRequest,
+        _error: &Error,
+        _span: &mut InstrumentedSpan,
+    ) {
+    }
+}
+
+/// A snapshot of the interceptors registered on an observatory.
+///
+/// Client implementations obtain one per call with
+/// [`LLMObservatory::interceptors`](crate::LLMObservatory::interceptors), so
+/// interceptors added mid-call do not see half a request.
+#[derive(Clone, Default)]
+pub struct InterceptorChain {
+    interceptors: Vec<Arc<dyn LlmInterceptor>>,
+}
+
+impl InterceptorChain {
+    /// Create a chain from interceptors in registration order.
+    pub fn new(interceptors: Vec<Arc<dyn LlmInterceptor>>) -> Self {
+        Self { interceptors }
+    }
+
+    /// Whether the chain has no interceptors.
+    pub fn is_empty(&self) -> bool {
+        self.interceptors.is_empty()
+    }
+
+    /// Number of interceptors in the chain.
+    pub fn len(&self) -> usize {
+        self.interceptors.len()
+    }
+
+    /// Run every `before_request` hook, stopping at the first error.
+    pub async fn before_request(
+        &self,
+        request: &mut ChatCompletionRequest,
+        span: &mut InstrumentedSpan,
+    ) -> Result<()> {
+        for interceptor in &self.interceptors {
+            interceptor.before_request(request, span).await?;
+        }
+        span.update_request(request);
+        Ok(())
+    }
+
+    /// Run every `after_response` hook, stopping at the first error.
+    pub async fn after_response(
+        &self,
+        request: &ChatCompletionRequest,
+        response: &mut ChatCompletionResponse,
+        span: &mut InstrumentedSpan,
+    ) -> Result<()> {
+        for interceptor in self.interceptors.iter().rev() {
+            interceptor.after_response(request, response, span).await?;
+        }
+        Ok(())
+    }
+
+    /// Run every `on_error` hook.
+    pub async fn on_error(
+        &self,
+        request: &ChatCompletionRequest,
+        error: &Error,
+        span: &mut InstrumentedSpan,
+    ) {
+        for interceptor in self.interceptors.iter().rev() {
+            interceptor.on_error(request, error, span).await;
+        }
+    }
+}
+
+impl std::fmt::Debug for InterceptorChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InterceptorChain")
+            .field("len", &self.interceptors.len())
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "openai"))]
+mod tests {
+    use super::*;
+    use crate::{InstrumentedLLM, LLMObservatory, OpenAIClient, OpenAIConfig};
+    use std::sync::Mutex;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    struct Recorder {
+        name: &'static str,
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl LlmInterceptor for Recorder {
+        async fn before_request(
+            &self,
+            request: &mut ChatCompletionRequest,
+            span: &mut InstrumentedSpan,
+        ) -> Result<()> {
+            self.calls.lock().unwrap().push(format!("{}.before", self.name));
+            request.max_tokens = Some(64);
+            span.set_attribute("interceptor.name", self.name);
+            Ok(())
+        }
+
+        async fn after_response(
+            &self,
+            _request: &ChatCompletionRequest,
+            response: &mut ChatCompletionResponse,
+            _span: &mut InstrumentedSpan,
+        ) -> Result<()> {
+            self.calls.lock().unwrap().push(format!("{}.after", self.name));
+            response
+                .metadata
+                .insert(self.name.to_string(), "seen".to_string());
+            Ok(())
+        }
+
+        async fn on_error(
+            &self,
+            _request: &ChatCompletionRequest,
+            error: &Error,
+            _span: &mut InstrumentedSpan,
+        ) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("{}.error: {}", self.name, error));
+        }
+    }
+
+    struct Guardrail;
+
+    #[async_trait]
+    impl LlmInterceptor for Guardrail {
+        async fn before_request(
+            &self,
+            request: &mut ChatCompletionRequest,
+            _span: &mut InstrumentedSpan,
+        ) -> Result<()> {
+            if request.messages.iter().any(|m| m.content.contains("forbidden")) {
+                return Err(Error::invalid_input("blocked by guardrail"));
+            }
+            Ok(())
+        }
+    }
+
+    fn completion_body() -> serde_json::Value {
+        serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Hi"},
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6}
+        })
+    }
+
+    fn client(server: &MockServer, observatory: LLMObservatory) -> OpenAIClient {
+        OpenAIClient::with_config(OpenAIConfig::new("test-key").with_base_url(server.uri()))
+            .with_observatory(observatory)
+    }
+
+    fn observatory() -> LLMObservatory {
+        LLMObservatory::builder()
+            .with_service_name("interceptor-test")
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_interceptors_wrap_call_and_mutate_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .and(body_partial_json(serde_json::json!({"max_tokens": 64})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(completion_body()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let observatory = observatory();
+        let client = client(&server, observatory.clone());
+
+        // Registered after the client was built; clones share interceptors
+        observatory.add_interceptor(Recorder { name: "outer", calls: calls.clone() });
+        observatory.add_interceptor(Recorder { name: "inner", calls: calls.clone() });
+        assert_eq!(observatory.interceptors().len(), 2);
+
+        let response = client
+            .chat_completion(ChatCompletionRequest::new("gpt-4o").with_user("Hello"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.metadata.get("outer").map(String::as_str), Some("seen"));
+        assert_eq!(response.metadata.get("inner").map(String::as_str), Some("seen"));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["outer.before", "inner.before", "inner.after", "outer.after"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_before_request_error_aborts_call() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(completion_body()))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let observatory = observatory();
+        observatory.add_interceptor(Recorder { name: "recorder", calls: calls.clone() });
+        observatory.add_interceptor(Guardrail);
+
+        let result = client(&server, observatory)
+            .chat_completion(ChatCompletionRequest::new("gpt-4o").with_user("something forbidden"))
+            .await;
+
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "recorder.before".to_string(),
+                "recorder.error: Invalid input: blocked by guardrail".to_string(),
+            ]
+        );
+    }
+}
@@ -11,8 +11,20 @@
 //! - Automatic tracing of LLM requests and responses
 //! - Cost calculation based on token usage
 //! - Support for streaming completions
+//! - Embeddings with batch support and embedding pricing
+//! - Tool/function-call child spans for agent workflows
+//! - Retrieval spans for RAG pipelines, linked to the generation they feed
 //! - OpenTelemetry-based observability
 //! - Provider-agnostic trait design
+//! - Offline span buffering with retry while the collector is unreachable
+//! - Prompt/response capture policies with truncation, hashing and sampling
+//! - Baggage-propagated user, session, team and feature flag attributes
+//! - W3C trace context propagation, with tower/axum middleware behind the `tower` feature
+//! - A scripted mock client for offline tests, behind the `testing` feature
+//! - Record-and-replay of LLM calls for deterministic, cost-free CI runs
+//! - Spend caps that block calls or downgrade to a cheaper model once reached
+//! - Provider rate-limit headers (remaining requests/tokens, resets) recorded on spans
+//! - Retries and model fallbacks, with every attempt linked to the one before it
 //! - Built-in support for OpenAI, Anthropic, and more
 //!
 //! # Quick Start
@@ -53,6 +65,7 @@
 //! - [`LLMObservatory`]: Central observability manager that handles OpenTelemetry setup
 //! - [`InstrumentedLLM`]: Trait for LLM clients with automatic instrumentation
 //! - [`OpenAIClient`]: OpenAI-specific implementation with full API support
+//! - [`LlmInterceptor`]: Hooks around every call for guardrails, request mutation and custom attributes
 //! - Cost calculation: Automatic cost tracking based on provider pricing
 //!
 //! # OpenTelemetry Integration
@@ -64,32 +77,62 @@
 #![warn(missing_docs, rust_2018_idioms)]
 #![deny(unsafe_code)]
 
+pub mod baggage;
+pub mod buffer;
+pub mod capture;
 pub mod cost;
 pub mod error;
 pub mod instrument;
+pub mod interceptor;
 pub mod observatory;
+pub mod propagation;
+pub mod replay;
+pub mod retrieval;
+pub mod retry;
+pub mod spend_cap;
+pub mod tool;
 pub mod traits;
 
 #[cfg(feature = "openai")]
 pub mod openai;
 
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 // Re-export core types
 pub use llm_observatory_core::{
+    guardrail::{GuardrailOutcome, GuardrailStage},
     provider::Pricing,
+    rate_limit::RateLimitState,
     span::{ChatMessage, LlmInput, LlmOutput, LlmSpan, SpanStatus},
     types::{Cost, Latency, Metadata, Provider, TokenUsage},
     Error as CoreError, Result as CoreResult,
 };
 
 // Re-export SDK types
+pub use baggage::ObservatoryContext;
+pub use buffer::{BufferConfig, ExportStats};
+pub use capture::{CaptureMode, CapturePolicy};
 pub use error::{Error, Result};
 pub use instrument::{InstrumentedSpan, SpanBuilder};
+pub use interceptor::{InterceptorChain, LlmInterceptor};
 pub use observatory::{LLMObservatory, ObservatoryBuilder};
-pub use traits::{ChatCompletionRequest, ChatCompletionResponse, InstrumentedLLM, StreamChunk};
+pub use replay::{ReplayClient, ReplayMode};
+pub use retrieval::{RetrievalLink, RetrievalSpan, RetrievalSpanBuilder, RetrievedDocument};
+pub use retry::{AttemptLink, FallbackReason, ResilientClient, RetryExt};
+pub use spend_cap::{SpendCap, SpendCapEnforcer};
+pub use tool::{ToolCallBuilder, ToolCallSpan};
+pub use traits::{
+    ChatCompletionRequest, ChatCompletionResponse, EmbeddingRequest, EmbeddingResponse,
+    InstrumentedLLM, StreamChunk,
+};
 
 #[cfg(feature = "openai")]
 pub use openai::{OpenAIClient, OpenAIConfig};
 
+#[cfg(any(test, feature = "testing"))]
+pub use testing::MockLlmClient;
+
 // Re-export async_trait for convenience
 pub use async_trait::async_trait;
 
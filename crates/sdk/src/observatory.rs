@@ -3,18 +3,28 @@
 
 //! LLM Observatory core implementation with OpenTelemetry integration.
 
-use crate::{Error, Result};
+use crate::{
+    buffer::{BufferConfig, ExportBuffer, ExportStats},
+    capture::CapturePolicy,
+    interceptor::{InterceptorChain, LlmInterceptor},
+    retrieval::RetrievalSpanBuilder,
+    Error, Result,
+};
+use llm_observatory_core::resource::{self, CloudDetector, KubernetesDetector, ResourceAttributes};
 use opentelemetry::{
     global,
     trace::TracerProvider as _,
     KeyValue,
 };
-use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_otlp::{WithExportConfig, WithTonicConfig};
 use opentelemetry_sdk::{
     trace::{RandomIdGenerator, Sampler, TracerProvider},
     Resource,
 };
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
 /// Central observatory for LLM instrumentation.
@@ -42,8 +52,12 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilte
 #[derive(Clone)]
 pub struct LLMObservatory {
     tracer: Arc<opentelemetry::global::BoxedTracer>,
+    provider: TracerProvider,
+    buffer: ExportBuffer,
     service_name: String,
     environment: String,
+    capture: Arc<CapturePolicy>,
+    interceptors: Arc<RwLock<Vec<Arc<dyn LlmInterceptor>>>>,
 }
 
 impl LLMObservatory {
@@ -67,13 +81,97 @@ impl LLMObservatory {
         &self.environment
     }
 
+    /// Get the prompt and response capture policy.
+    pub fn capture_policy(&self) -> &CapturePolicy {
+        &self.capture
+    }
+
+    /// Register an interceptor that runs around every instrumented call.
+    ///
+    /// Interceptors are shared by all clones of the observatory, so they also
+    /// apply to clients the observatory was already attached to.
+    pub fn add_interceptor(&self, interceptor: impl LlmInterceptor + 'static) {
+        self.interceptors
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::new(interceptor));
+    }
+
+    /// Snapshot of the registered interceptors, in registration order.
+    pub fn interceptors(&self) -> InterceptorChain {
+        InterceptorChain::new(
+            self.interceptors
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+        )
+    }
+
+    /// Start building a retrieval span for a RAG pipeline.
+    ///
+    /// `vector_db` identifies the vector database (e.g. "qdrant", "pgvector").
+    pub fn retrieval_span(&self, vector_db: impl Into<String>) -> RetrievalSpanBuilder {
+        RetrievalSpanBuilder::new(self.clone(), vector_db)
+    }
+
+    /// Get span export queue metrics.
+    ///
+    /// Useful for alerting when the collector is unreachable and spans are
+    /// accumulating or being dropped.
+    pub fn stats(&self) -> ExportStats {
+        self.buffer.stats()
+    }
+
     /// Shutdown the observatory and flush all pending telemetry.
+    ///
+    /// Pending spans are retried until the buffer's shutdown timeout; spans
+    /// that still cannot be delivered are written to the spool file if one is
+    /// configured, and dropped otherwise.
     pub async fn shutdown(&self) -> Result<()> {
+        // Shutting down the provider flushes the batch processor into the buffer
+        let provider = self.provider.clone();
+        tokio::task::spawn_blocking(move || provider.shutdown())
+            .await
+            .map_err(|e| Error::internal(e.to_string()))?
+            .map_err(|e| Error::OpenTelemetry(e.to_string()))?;
+
+        let stats = self.buffer.shutdown().await;
+        if stats.spans_buffered > 0 || stats.spans_dropped > 0 {
+            tracing::warn!(
+                buffered = stats.spans_buffered,
+                dropped = stats.spans_dropped,
+                spooled = stats.spans_spooled,
+                "Not all spans were exported before shutdown"
+            );
+        }
+
         global::shutdown_tracer_provider();
         Ok(())
     }
 }
 
+/// Bearer token sent with exports, redacted in `Debug` output.
+#[derive(Clone)]
+struct AuthToken(String);
+
+impl AuthToken {
+    /// gRPC metadata carrying the token.
+    fn metadata(&self) -> Result<tonic::metadata::MetadataMap> {
+        let value = format!("Bearer {}", self.0)
+            .parse()
+            .map_err(|_| Error::config("auth token is not a valid header value"))?;
+        let mut metadata = tonic::metadata::MetadataMap::new();
+        metadata.insert("authorization", value);
+        Ok(metadata)
+    }
+}
+
+impl fmt::Debug for AuthToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AuthToken([REDACTED])")
+    }
+}
+
 /// Builder for configuring and creating an [`LLMObservatory`] instance.
 ///
 /// # Example
@@ -97,10 +195,16 @@ pub struct ObservatoryBuilder {
     service_name: Option<String>,
     service_version: Option<String>,
     otlp_endpoint: Option<String>,
+    auth_token: Option<AuthToken>,
     environment: String,
     sampling_rate: f64,
     enable_console_export: bool,
     additional_attributes: Vec<KeyValue>,
+    buffer: BufferConfig,
+    capture: Option<CapturePolicy>,
+    service_capture: HashMap<String, CapturePolicy>,
+    resource_detection: bool,
+    detected_resources: ResourceAttributes,
 }
 
 impl Default for ObservatoryBuilder {
@@ -109,10 +213,16 @@ impl Default for ObservatoryBuilder {
             service_name: None,
             service_version: Some(crate::VERSION.to_string()),
             otlp_endpoint: Some("http://localhost:4317".to_string()),
+            auth_token: None,
             environment: "development".to_string(),
             sampling_rate: 1.0,
             enable_console_export: false,
             additional_attributes: Vec::new(),
+            buffer: BufferConfig::default(),
+            capture: None,
+            service_capture: HashMap::new(),
+            resource_detection: true,
+            detected_resources: ResourceAttributes::new(),
         }
     }
 }
@@ -143,6 +253,13 @@ impl ObservatoryBuilder {
         self
     }
 
+    /// Authenticate exports with a bearer token, such as a service account
+    /// token issued by the analytics API.
+    pub fn with_auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(AuthToken(token.into()));
+        self
+    }
+
     /// Set the deployment environment (e.g., "production", "staging", "development").
     pub fn with_environment(mut self, env: impl Into<String>) -> Self {
         self.environment = env.into();
@@ -171,20 +288,122 @@ impl ObservatoryBuilder {
         self
     }
 
+    /// Enable or disable resource detection (enabled by default).
+    ///
+    /// When enabled, [`build`](Self::build) adds the Kubernetes pod,
+    /// namespace, node and deployment, and the cloud platform and region
+    /// known from environment variables, to the resource of every span.
+    /// Attributes set with [`with_attribute`](Self::with_attribute) win.
+    pub fn with_resource_detection(mut self, enabled: bool) -> Self {
+        self.resource_detection = enabled;
+        self
+    }
+
+    /// Also detect the cloud provider, region and availability zone from the
+    /// AWS, GCP or Azure instance metadata endpoints.
+    ///
+    /// Waits up to `timeout` for the endpoints, which do not answer outside
+    /// a cloud instance; has no effect with resource detection disabled.
+    pub async fn detect_cloud_metadata(mut self, timeout: std::time::Duration) -> Self {
+        if self.resource_detection {
+            let cloud = CloudDetector::default().with_timeout(timeout);
+            self.detected_resources = resource::detect(&[&KubernetesDetector, &cloud]).await;
+        }
+        self
+    }
+
+    /// Configure span buffering and export retries.
+    pub fn with_buffer_config(mut self, config: BufferConfig) -> Self {
+        self.buffer = config;
+        self
+    }
+
+    /// Set the maximum number of spans buffered in memory while the collector
+    /// is unreachable.
+    pub fn with_max_buffered_spans(mut self, max: usize) -> Self {
+        self.buffer.max_buffered_spans = max.max(1);
+        self
+    }
+
+    /// Spool spans to this file when the memory buffer is full or on
+    /// shutdown, so they survive restarts.
+    pub fn with_spool_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.buffer.spool_path = Some(path.into());
+        self
+    }
+
+    /// Set the prompt and response capture policy.
+    ///
+    /// Defaults to metadata-only capture in production and full capture in
+    /// other environments.
+    pub fn with_capture_policy(mut self, policy: CapturePolicy) -> Self {
+        self.capture = Some(policy);
+        self
+    }
+
+    /// Override the capture policy for a specific service name.
+    ///
+    /// Useful when one configuration is shared by several services; the
+    /// override applies when the observatory is built with that service name.
+    pub fn with_service_capture_policy(
+        mut self,
+        service_name: impl Into<String>,
+        policy: CapturePolicy,
+    ) -> Self {
+        self.service_capture.insert(service_name.into(), policy);
+        self
+    }
+
+    /// Resolve the capture policy for a service: its override, then the
+    /// configured policy, then the environment default.
+    fn resolve_capture_policy(&self, service_name: &str) -> CapturePolicy {
+        self.service_capture
+            .get(service_name)
+            .or(self.capture.as_ref())
+            .cloned()
+            .unwrap_or_else(|| CapturePolicy::for_environment(&self.environment))
+    }
+
+    /// Detected resource attributes: those of
+    /// [`detect_cloud_metadata`](Self::detect_cloud_metadata), completed from
+    /// the environment.
+    fn detected_attributes(&self) -> Vec<KeyValue> {
+        if !self.resource_detection {
+            return Vec::new();
+        }
+
+        let mut detected = self.detected_resources.clone();
+        let local = KubernetesDetector
+            .detect_local()
+            .into_iter()
+            .chain(CloudDetector::default().detect_local());
+        for (key, value) in local {
+            detected.entry(key).or_insert(value);
+        }
+        detected
+            .into_iter()
+            .map(|(key, value)| KeyValue::new(key, value))
+            .collect()
+    }
+
     /// Build the observatory instance.
     pub fn build(self) -> Result<LLMObservatory> {
         let service_name = self
             .service_name
+            .clone()
             .ok_or_else(|| Error::config("service_name is required"))?;
+        let capture = self.resolve_capture_policy(&service_name);
 
-        // Build resource attributes
-        let mut resource_attrs = vec![
+        // Build resource attributes; detected ones first, so configured ones
+        // override them
+        let mut resource_attrs = self.detected_attributes();
+        resource_attrs.extend([
             KeyValue::new("service.name", service_name.clone()),
             KeyValue::new("deployment.environment", self.environment.clone()),
             KeyValue::new("telemetry.sdk.name", "llm-observatory-rust"),
             KeyValue::new("telemetry.sdk.language", "rust"),
             KeyValue::new("telemetry.sdk.version", crate::VERSION),
-        ];
+        ]);
 
         if let Some(version) = &self.service_version {
             resource_attrs.push(KeyValue::new("service.version", version.clone()));
@@ -208,23 +427,34 @@ impl ObservatoryBuilder {
             .otlp_endpoint
             .ok_or_else(|| Error::config("otlp_endpoint is required"))?;
 
-        let exporter = opentelemetry_otlp::SpanExporter::builder()
+        let mut exporter = opentelemetry_otlp::SpanExporter::builder()
             .with_tonic()
-            .with_endpoint(&otlp_endpoint)
+            .with_endpoint(&otlp_endpoint);
+        if let Some(token) = &self.auth_token {
+            exporter = exporter.with_metadata(token.metadata()?);
+        }
+        let exporter = exporter
             .build()
             .map_err(|e| Error::OpenTelemetry(e.to_string()))?;
 
+        // Buffer spans so they survive collector outages
+        let buffer = ExportBuffer::new(self.buffer, exporter)
+            .map_err(|e| Error::config(format!("failed to open span spool: {}", e)))?;
+
         // Create tracer provider
         let provider = TracerProvider::builder()
             .with_sampler(sampler)
             .with_id_generator(RandomIdGenerator::default())
             .with_resource(resource)
-            .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+            .with_batch_exporter(buffer.exporter(), opentelemetry_sdk::runtime::Tokio)
             .build();
 
         // Set global tracer provider
         let _ = global::set_tracer_provider(provider.clone());
 
+        // Propagate W3C trace context and baggage through instrumented HTTP clients
+        global::set_text_map_propagator(crate::propagation::propagator());
+
         // Get tracer from global provider to get BoxedTracer
         let tracer = global::tracer("llm-observatory");
 
@@ -242,8 +472,12 @@ impl ObservatoryBuilder {
 
         Ok(LLMObservatory {
             tracer: Arc::new(tracer),
+            provider,
+            buffer,
             service_name,
             environment: self.environment,
+            capture: Arc::new(capture),
+            interceptors: Arc::default(),
         })
     }
 }
@@ -251,6 +485,7 @@ impl ObservatoryBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::CaptureMode;
 
     #[test]
     fn test_builder_defaults() {
@@ -271,6 +506,20 @@ mod tests {
         assert_eq!(builder.sampling_rate, 0.5);
     }
 
+    #[test]
+    fn test_auth_token() {
+        let builder = ObservatoryBuilder::default().with_auth_token("sa-token");
+        let token = builder.auth_token.as_ref().unwrap();
+        assert_eq!(
+            token.metadata().unwrap().get("authorization").unwrap(),
+            "Bearer sa-token"
+        );
+        assert!(!format!("{:?}", builder).contains("sa-token"));
+
+        let invalid = ObservatoryBuilder::default().with_auth_token("line\nbreak");
+        assert!(invalid.auth_token.unwrap().metadata().is_err());
+    }
+
     #[test]
     fn test_sampling_rate_clamping() {
         let builder = ObservatoryBuilder::default().with_sampling_rate(1.5);
@@ -280,6 +529,40 @@ mod tests {
         assert_eq!(builder.sampling_rate, 0.0);
     }
 
+    #[test]
+    fn test_capture_policy_resolution() {
+        let builder = ObservatoryBuilder::default().with_environment("production");
+        assert_eq!(
+            builder.resolve_capture_policy("billing").mode,
+            CaptureMode::MetadataOnly
+        );
+
+        let builder = builder
+            .with_capture_policy(CapturePolicy::full().with_sample_rate(0.1))
+            .with_service_capture_policy("billing", CapturePolicy::none());
+        assert_eq!(builder.resolve_capture_policy("billing").mode, CaptureMode::None);
+
+        let policy = builder.resolve_capture_policy("support");
+        assert_eq!(policy.mode, CaptureMode::Full);
+        assert_eq!(policy.sample_rate, 0.1);
+    }
+
+    #[tokio::test]
+    async fn test_resource_detection() {
+        let mut builder = ObservatoryBuilder::default()
+            .detect_cloud_metadata(std::time::Duration::from_millis(10))
+            .await;
+        builder
+            .detected_resources
+            .insert("cloud.region".to_string(), "eu-west-1".to_string());
+        assert!(builder
+            .detected_attributes()
+            .contains(&KeyValue::new("cloud.region", "eu-west-1")));
+
+        let builder = builder.with_resource_detection(false);
+        assert!(builder.detected_attributes().is_empty());
+    }
+
     #[test]
     fn test_build_without_service_name() {
         let result = ObservatoryBuilder::default().build();
@@ -4,21 +4,25 @@
 //! OpenAI client implementation with automatic instrumentation.
 
 use crate::{
-    cost::calculate_cost,
-    instrument::create_span,
+    cost::{calculate_cost, calculate_embedding_cost},
+    instrument::{create_span, InstrumentedSpan},
+    interceptor::InterceptorChain,
     observatory::LLMObservatory,
     traits::{
-        ChatCompletionRequest, ChatCompletionResponse, InstrumentedLLM, StreamChunk,
+        ChatCompletionRequest, ChatCompletionResponse, EmbeddingRequest, EmbeddingResponse,
+        InstrumentedLLM, StreamChunk,
     },
     Error, Result,
 };
 use async_trait::async_trait;
+use chrono::Utc;
 use futures::Stream;
 use llm_observatory_core::{
-    span::{ChatMessage, LlmOutput},
-    types::{Provider, TokenUsage},
+    rate_limit::RateLimitState,
+    span::{ChatMessage, LlmInput, LlmOutput},
+    types::{Cost, Provider, TokenUsage},
 };
-use reqwest::{header, Client};
+use reqwest::{header, Client, Response};
 use serde::{Deserialize, Serialize};
 use std::pin::Pin;
 use std::time::Duration;
@@ -158,88 +162,266 @@ impl OpenAIClient {
         &self,
         request: &ChatCompletionRequest,
     ) -> Result<OpenAIChatResponse> {
+        Ok(self.chat_completion_with_rate_limits(request).await?.0)
+    }
+
+    /// Execute a chat completion without instrumentation, returning the
+    /// rate-limit state reported in the `x-ratelimit-*` response headers.
+    pub async fn chat_completion_with_rate_limits(
+        &self,
+        request: &ChatCompletionRequest,
+    ) -> Result<(OpenAIChatResponse, RateLimitState)> {
         request.validate()?;
 
+        let response = self.send_chat_completion(request).await?;
+        Self::read_chat_completion(response).await
+    }
+
+    /// Send a chat completion request, returning once the response headers
+    /// have arrived.
+    async fn send_chat_completion(&self, request: &ChatCompletionRequest) -> Result<Response> {
         let url = format!("{}/chat/completions", self.config.base_url);
-        let response = self.client.post(&url).json(request).send().await?;
+        Ok(self.client.post(&url).json(request).send().await?)
+    }
 
+    /// Read the body and rate-limit headers of a chat completion response.
+    async fn read_chat_completion(
+        response: Response,
+    ) -> Result<(OpenAIChatResponse, RateLimitState)> {
         let status = response.status();
         if !status.is_success() {
             let error_body = response.text().await.unwrap_or_default();
             return Err(Error::api(status.as_u16(), error_body));
         }
 
+        let rate_limits = RateLimitState::from_headers(
+            response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?))),
+            Utc::now(),
+        );
         let openai_response: OpenAIChatResponse = response.json().await?;
+        Ok((openai_response, rate_limits))
+    }
+
+    /// Create embeddings without instrumentation.
+    pub async fn embeddings_raw(
+        &self,
+        request: &EmbeddingRequest,
+    ) -> Result<OpenAIEmbeddingResponse> {
+        request.validate()?;
+
+        let body = OpenAIEmbeddingRequest {
+            model: &request.model,
+            input: &request.input,
+            dimensions: request.dimensions,
+            user: request.user.as_deref(),
+        };
+
+        let url = format!("{}/embeddings", self.config.base_url);
+        let response = self.client.post(&url).json(&body).send().await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            return Err(Error::api(status.as_u16(), error_body));
+        }
+
+        let mut openai_response: OpenAIEmbeddingResponse = response.json().await?;
+        openai_response.data.sort_by_key(|d| d.index);
         Ok(openai_response)
     }
+
+    /// Send the request and build the response, running interceptor hooks
+    /// against the span when one is active.
+    async fn execute(
+        &self,
+        request: &mut ChatCompletionRequest,
+        mut span: Option<&mut InstrumentedSpan>,
+        interceptors: &InterceptorChain,
+    ) -> Result<(ChatCompletionResponse, Cost)> {
+        if let Some(span) = span.as_deref_mut() {
+            interceptors.before_request(request, span).await?;
+        }
+        request.validate()?;
+
+        // Mark the phases for the collector's queue time extraction
+        if let Some(span) = span.as_deref_mut() {
+            span.record_request_sent();
+        }
+        let http_response = self.send_chat_completion(request).await?;
+        if let Some(span) = span.as_deref_mut() {
+            span.record_first_byte();
+        }
+
+        let (openai_response, rate_limits) = Self::read_chat_completion(http_response).await?;
+        if let Some(span) = span.as_deref() {
+            span.record_rate_limits(&rate_limits);
+        }
+
+        // Extract response data
+        let choice = openai_response
+            .choices
+            .first()
+            .ok_or_else(|| Error::internal("No choices in response"))?;
+
+        // Build token usage
+        let usage = TokenUsage::new(
+            openai_response.usage.prompt_tokens,
+            openai_response.usage.completion_tokens,
+        );
+
+        // Calculate cost
+        let cost = calculate_cost(&request.model, &usage)?;
+
+        let (trace_id, span_id) = span
+            .as_deref()
+            .map(|s| (s.trace_id().to_string(), s.span_id().to_string()))
+            .unwrap_or_default();
+
+        let mut response = ChatCompletionResponse {
+            id: openai_response.id.clone(),
+            content: choice.message.content.clone(),
+            model: openai_response.model.clone(),
+            finish_reason: Some(choice.finish_reason.clone()),
+            usage,
+            cost_usd: cost.amount_usd,
+            latency_ms: 0,
+            trace_id,
+            span_id,
+            metadata: request.metadata.clone().unwrap_or_default(),
+        };
+
+        if let Some(span) = span {
+            interceptors.after_response(request, &mut response, span).await?;
+        }
+
+        Ok((response, cost))
+    }
 }
 
 #[async_trait]
 impl InstrumentedLLM for OpenAIClient {
     async fn chat_completion(
         &self,
-        request: ChatCompletionRequest,
+        mut request: ChatCompletionRequest,
     ) -> Result<ChatCompletionResponse> {
         request.validate()?;
 
         // Create instrumented span if observatory is attached
-        let mut span = if let Some(observatory) = &self.observatory {
-            Some(
-                create_span(observatory, Provider::OpenAI, &request.model)
-                    .messages(request.messages.clone())
-                    .start(),
-            )
+        let (mut span, interceptors) = if let Some(observatory) = &self.observatory {
+            let mut builder = create_span(observatory, Provider::OpenAI, &request.model)
+                .messages(request.messages.clone());
+            if let Some(link) = &request.retrieval {
+                builder = builder.retrieval(link.clone());
+            }
+            if let Some(parent) = &request.parent_context {
+                builder = builder.parent(parent.clone());
+            }
+            if let Some(attempt) = &request.attempt {
+                builder = builder.attempt(attempt.clone());
+            }
+            (Some(builder.start()), observatory.interceptors())
         } else {
-            None
+            (None, InterceptorChain::default())
         };
 
         // Execute the request
-        let result = self.chat_completion_raw(&request).await;
-
-        match result {
-            Ok(openai_response) => {
-                // Extract response data
-                let choice = openai_response
-                    .choices
-                    .first()
-                    .ok_or_else(|| Error::internal("No choices in response"))?;
-
-                let content = choice.message.content.clone();
-                let finish_reason = choice.finish_reason.clone();
-
-                // Build token usage
-                let usage = TokenUsage::new(
-                    openai_response.usage.prompt_tokens,
-                    openai_response.usage.completion_tokens,
-                );
-
-                // Calculate cost
-                let cost = calculate_cost(&request.model, &usage)?;
+        let result = self
+            .execute(&mut request, span.as_mut(), &interceptors)
+            .await;
 
+        match (result, span) {
+            (Ok((mut response, cost)), Some(span)) => {
                 // Create LLM output
                 let output = LlmOutput {
-                    content: content.clone(),
-                    finish_reason: Some(finish_reason.clone()),
+                    content: response.content.clone(),
+                    finish_reason: response.finish_reason.clone(),
                     metadata: Default::default(),
                 };
 
+                // Finish the span
+                let llm_span = span.finish_success(output, response.usage.clone(), cost)?;
+                response.latency_ms = llm_span.latency.total_ms;
+
+                Ok(response)
+            }
+            (Err(e), Some(mut span)) => {
+                // Finish span with error
+                interceptors.on_error(&request, &e, &mut span).await;
+                let _ = span.finish_error(&e.to_string());
+                Err(e)
+            }
+            (result, None) => result.map(|(response, _)| response),
+        }
+    }
+
+    async fn embeddings(&self, request: EmbeddingRequest) -> Result<EmbeddingResponse> {
+        request.validate()?;
+
+        // Create instrumented span if observatory is attached
+        let mut span = self.observatory.as_ref().map(|observatory| {
+            let mut builder = create_span(observatory, Provider::OpenAI, &request.model)
+                .operation_name("llm.embeddings")
+                .input(LlmInput::Text {
+                    prompt: request.input.join("\n"),
+                })
+                .attribute("gen_ai.operation.name", "embeddings");
+            if let Some(parent) = &request.parent_context {
+                builder = builder.parent(parent.clone());
+            }
+            if let Some(attempt) = &request.attempt {
+                builder = builder.attempt(attempt.clone());
+            }
+            let span = builder.start();
+            span.set_attribute("gen_ai.request.embedding.count", request.input.len() as i64);
+            if let Some(dimensions) = request.dimensions {
+                span.set_attribute("gen_ai.request.embedding.dimensions", dimensions as i64);
+            }
+            span
+        });
+
+        let result = async {
+            let openai_response = self.embeddings_raw(&request).await?;
+            let usage = TokenUsage::new(openai_response.usage.prompt_tokens, 0);
+            let cost = calculate_embedding_cost(&request.model, usage.prompt_tokens)?;
+            Ok::<_, Error>((openai_response, usage, cost))
+        }
+        .await;
+
+        match result {
+            Ok((openai_response, usage, cost)) => {
+                let embeddings: Vec<Vec<f32>> = openai_response
+                    .data
+                    .into_iter()
+                    .map(|d| d.embedding)
+                    .collect();
+                let dimensions = embeddings.first().map_or(0, Vec::len);
+
                 // Finish the span
                 let (trace_id, span_id, latency_ms) = if let Some(span) = span.take() {
+                    span.set_attribute("gen_ai.embeddings.dimension.count", dimensions as i64);
+                    let output = LlmOutput {
+                        content: String::new(),
+                        finish_reason: None,
+                        metadata: [
+                            ("embedding_count".to_string(), embeddings.len().into()),
+                            ("dimensions".to_string(), dimensions.into()),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    };
                     let llm_span = span.finish_success(output, usage.clone(), cost.clone())?;
-                    (
-                        llm_span.trace_id.clone(),
-                        llm_span.span_id.clone(),
-                        llm_span.latency.total_ms,
-                    )
+                    (llm_span.trace_id, llm_span.span_id, llm_span.latency.total_ms)
                 } else {
                     (String::new(), String::new(), 0)
                 };
 
-                Ok(ChatCompletionResponse {
-                    id: openai_response.id,
-                    content,
+                Ok(EmbeddingResponse {
+                    embeddings,
                     model: openai_response.model,
-                    finish_reason: Some(finish_reason),
+                    dimensions,
                     usage,
                     cost_usd: cost.amount_usd,
                     latency_ms,
@@ -328,6 +510,45 @@ pub struct OpenAIUsage {
     pub total_tokens: u32,
 }
 
+#[derive(Debug, Serialize)]
+struct OpenAIEmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<&'a str>,
+}
+
+/// Raw response from the OpenAI embeddings endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIEmbeddingResponse {
+    /// Embeddings, one per input
+    pub data: Vec<OpenAIEmbedding>,
+    /// Model used
+    pub model: String,
+    /// Token usage
+    pub usage: OpenAIEmbeddingUsage,
+}
+
+/// A single embedding in an OpenAI embeddings response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIEmbedding {
+    /// Position of the corresponding input
+    pub index: usize,
+    /// Embedding vector
+    pub embedding: Vec<f32>,
+}
+
+/// Token usage for an OpenAI embeddings request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIEmbeddingUsage {
+    /// Input tokens
+    pub prompt_tokens: u32,
+    /// Total tokens
+    pub total_tokens: u32,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -352,4 +573,55 @@ mod tests {
         assert_eq!(client.provider_name(), "openai");
         assert_eq!(client.default_model(), Some("gpt-4o"));
     }
+
+    #[tokio::test]
+    async fn test_embeddings_batch() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/embeddings"))
+            .and(body_partial_json(serde_json::json!({
+                "model": "text-embedding-3-small",
+                "input": ["first", "second"],
+                "dimensions": 3
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [
+                    {"object": "embedding", "index": 1, "embedding": [0.4, 0.5, 0.6]},
+                    {"object": "embedding", "index": 0, "embedding": [0.1, 0.2, 0.3]}
+                ],
+                "model": "text-embedding-3-small",
+                "usage": {"prompt_tokens": 1000, "total_tokens": 1000}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let observatory = LLMObservatory::builder()
+            .with_service_name("embeddings-test")
+            .build()
+            .unwrap();
+        let client =
+            OpenAIClient::with_config(OpenAIConfig::new("test-key").with_base_url(server.uri()))
+                .with_observatory(observatory);
+
+        let response = client
+            .embeddings(
+                EmbeddingRequest::new("text-embedding-3-small")
+                    .with_inputs(["first", "second"])
+                    .with_dimensions(3),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.embeddings, vec![vec![0.1, 0.2, 0.3], vec![0.4, 0.5, 0.6]]);
+        assert_eq!(response.dimensions, 3);
+        assert_eq!(response.usage.prompt_tokens, 1000);
+        assert_eq!(response.usage.completion_tokens, 0);
+        assert!((response.cost_usd - 0.00002).abs() < 1e-9);
+        assert!(!response.trace_id.is_empty());
+    }
 }
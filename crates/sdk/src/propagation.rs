@@ -0,0 +1,300 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! W3C Trace Context propagation.
+//!
+//! When an LLM call is made while handling an incoming request, the LLM span
+//! should join the caller's trace. These helpers extract the `traceparent`,
+//! `tracestate` and `baggage` headers into an OpenTelemetry [`Context`] and
+//! inject a context into outgoing headers. Baggage carries the
+//! [`ObservatoryContext`](crate::ObservatoryContext) attributes between
+//! services.
+//!
+//! A span is parented to an extracted context by passing it to
+//! [`ChatCompletionRequest::with_parent_context`](crate::ChatCompletionRequest::with_parent_context)
+//! or [`SpanBuilder::parent`](crate::SpanBuilder::parent). Alternatively, run
+//! the call inside the context with
+//! [`FutureExt::with_context`](opentelemetry::trace::FutureExt::with_context).
+//!
+//! With the `tower` feature, [`TraceContextLayer`] does the extraction for
+//! every request of an axum or tower service.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use llm_observatory_sdk::{propagation, ChatCompletionRequest};
+//! use std::collections::HashMap;
+//!
+//! let mut headers = HashMap::new();
+//! headers.insert(
+//!     "traceparent".to_string(),
+//!     "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string(),
+//! );
+//!
+//! let parent = propagation::extract(&headers);
+//! let request = ChatCompletionRequest::new("gpt-4o")
+//!     .with_user("Hello")
+//!     .with_parent_context(parent);
+//! ```
+
+use opentelemetry::{
+    propagation::{Extractor, Injector, TextMapCompositePropagator, TextMapPropagator},
+    trace::TraceContextExt,
+    Context,
+};
+use opentelemetry_sdk::propagation::{BaggagePropagator, TraceContextPropagator};
+use std::collections::HashMap;
+
+/// Name of the W3C `traceparent` header.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Name of the W3C `tracestate` header.
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// Name of the W3C `baggage` header.
+pub const BAGGAGE_HEADER: &str = "baggage";
+
+/// Propagator for W3C trace context and baggage.
+pub fn propagator() -> TextMapCompositePropagator {
+    TextMapCompositePropagator::new(vec![
+        Box::new(TraceContextPropagator::new()),
+        Box::new(BaggagePropagator::new()),
+    ])
+}
+
+/// Extract a context from W3C trace context and baggage headers.
+///
+/// If no valid `traceparent` is present, spans started under the context
+/// become new roots.
+pub fn extract(extractor: &dyn Extractor) -> Context {
+    propagator().extract(extractor)
+}
+
+/// Extract a context from a `traceparent` value and optional `tracestate`.
+///
+/// Returns `None` if the `traceparent` is invalid.
+pub fn extract_traceparent(traceparent: &str, tracestate: Option<&str>) -> Option<Context> {
+    let mut headers = HashMap::new();
+    headers.insert(TRACEPARENT_HEADER.to_string(), traceparent.to_string());
+    if let Some(tracestate) = tracestate {
+        headers.insert(TRACESTATE_HEADER.to_string(), tracestate.to_string());
+    }
+
+    let context = extract(&headers);
+    context
+        .span()
+        .span_context()
+        .is_valid()
+        .then_some(context)
+}
+
+/// Inject W3C trace context and baggage headers for `context`.
+pub fn inject(context: &Context, injector: &mut dyn Injector) {
+    propagator().inject_context(context, injector);
+}
+
+/// Get the `traceparent` value for `context`, if it has a valid span.
+pub fn traceparent(context: &Context) -> Option<String> {
+    let mut headers = HashMap::new();
+    inject(context, &mut headers);
+    headers.remove(TRACEPARENT_HEADER)
+}
+
+#[cfg(feature = "tower")]
+pub use self::http::{
+    extract_from_headers, inject_into_headers, HeaderExtractor, HeaderInjector,
+    TraceContextLayer, TraceContextService,
+};
+
+#[cfg(feature = "tower")]
+mod http {
+    use super::{extract, inject};
+    use ::http::{header::HeaderName, HeaderMap, HeaderValue, Request};
+    use opentelemetry::{
+        propagation::{Extractor, Injector},
+        trace::{FutureExt, WithContext},
+        Context,
+    };
+    use std::task::{Context as TaskContext, Poll};
+    use tower::{Layer, Service};
+
+    /// [`Extractor`] over HTTP headers.
+    pub struct HeaderExtractor<'a>(pub &'a HeaderMap);
+
+    impl Extractor for HeaderExtractor<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(HeaderName::as_str).collect()
+        }
+    }
+
+    /// [`Injector`] over HTTP headers.
+    pub struct HeaderInjector<'a>(pub &'a mut HeaderMap);
+
+    impl Injector for HeaderInjector<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(key.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                self.0.insert(name, value);
+            }
+        }
+    }
+
+    /// Extract a context from HTTP request headers.
+    pub fn extract_from_headers(headers: &HeaderMap) -> Context {
+        extract(&HeaderExtractor(headers))
+    }
+
+    /// Inject W3C trace context and baggage headers for `context` into HTTP
+    /// headers.
+    pub fn inject_into_headers(context: &Context, headers: &mut HeaderMap) {
+        inject(context, &mut HeaderInjector(headers));
+    }
+
+    /// Tower layer that parents work done by a service to the caller's trace.
+    ///
+    /// The context extracted from the request headers is active while the
+    /// inner service runs, so LLM spans started by the handler join the
+    /// caller's trace. It is also inserted into the request extensions for
+    /// handlers that spawn tasks.
+    ///
+    /// ```rust,ignore
+    /// let app = axum::Router::new()
+    ///     .route("/chat", axum::routing::post(chat))
+    ///     .layer(llm_observatory_sdk::propagation::TraceContextLayer::new());
+    /// ```
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct TraceContextLayer;
+
+    impl TraceContextLayer {
+        /// Create the layer.
+        pub fn new() -> Self {
+            Self
+        }
+    }
+
+    impl<S> Layer<S> for TraceContextLayer {
+        type Service = TraceContextService<S>;
+
+        fn layer(&self, inner: S) -> Self::Service {
+            TraceContextService { inner }
+        }
+    }
+
+    /// Service created by [`TraceContextLayer`].
+    #[derive(Debug, Clone)]
+    pub struct TraceContextService<S> {
+        inner: S,
+    }
+
+    impl<S, B> Service<Request<B>> for TraceContextService<S>
+    where
+        S: Service<Request<B>>,
+    {
+        type Response = S::Response;
+        type Error = S::Error;
+        type Future = WithContext<S::Future>;
+
+        fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx)
+        }
+
+        fn call(&mut self, mut request: Request<B>) -> Self::Future {
+            let parent = extract_from_headers(request.headers());
+            request.extensions_mut().insert(parent.clone());
+
+            let future = {
+                let _guard = parent.clone().attach();
+                self.inner.call(request)
+            };
+            future.with_context(parent)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn test_traceparent_round_trip() {
+        let context = extract_traceparent(TRACEPARENT, Some("vendor=value")).unwrap();
+        let span = context.span();
+        let span_context = span.span_context();
+        assert_eq!(
+            format!("{:x}", span_context.trace_id()),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+        assert!(span_context.is_remote());
+        assert!(span_context.is_sampled());
+
+        assert_eq!(traceparent(&context).as_deref(), Some(TRACEPARENT));
+
+        let mut headers = HashMap::new();
+        inject(&context, &mut headers);
+        assert_eq!(headers.get(TRACESTATE_HEADER).map(String::as_str), Some("vendor=value"));
+    }
+
+    #[test]
+    fn test_baggage_round_trip() {
+        let mut headers = HashMap::new();
+        headers.insert(TRACEPARENT_HEADER.to_string(), TRACEPARENT.to_string());
+        headers.insert(
+            BAGGAGE_HEADER.to_string(),
+            "user.id=user-9,feature_flag.reranker=on".to_string(),
+        );
+
+        let context = extract(&headers);
+        let observatory_context = crate::ObservatoryContext::from_context(&context);
+        assert_eq!(observatory_context.user_id.as_deref(), Some("user-9"));
+        assert_eq!(
+            observatory_context.feature_flags.get("reranker").map(String::as_str),
+            Some("on")
+        );
+
+        let mut injected = HashMap::new();
+        inject(&context, &mut injected);
+        assert!(injected[BAGGAGE_HEADER].contains("user.id=user-9"));
+    }
+
+    #[test]
+    fn test_invalid_traceparent() {
+        assert!(extract_traceparent("not-a-traceparent", None).is_none());
+        assert!(traceparent(&Context::new()).is_none());
+
+        let context = extract(&HashMap::new());
+        assert!(!context.span().span_context().is_valid());
+    }
+
+    #[cfg(feature = "tower")]
+    #[tokio::test]
+    async fn test_layer_activates_extracted_context() {
+        use ::http::Request;
+        use tower::{service_fn, Layer, ServiceExt};
+
+        let service = TraceContextLayer::new().layer(service_fn(|request: Request<()>| async move {
+            let current = Context::current().span().span_context().trace_id();
+            let extension = request
+                .extensions()
+                .get::<Context>()
+                .map(|cx| cx.span().span_context().trace_id());
+            Ok::<_, std::convert::Infallible>((current, extension))
+        }));
+
+        let request = Request::builder()
+            .header(TRACEPARENT_HEADER, TRACEPARENT)
+            .body(())
+            .unwrap();
+        let (current, extension) = service.oneshot(request).await.unwrap();
+
+        assert_eq!(format!("{:x}", current), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(extension, Some(current));
+    }
+}
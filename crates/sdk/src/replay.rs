@@ -0,0 +1,922 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Record-and-replay of LLM calls.
+//!
+//! [`ReplayClient`] wraps any [`InstrumentedLLM`] client. In
+//! [`ReplayMode::Record`] it forwards every call and stores the request and
+//! response in a cassette file; in [`ReplayMode::Replay`] it serves responses
+//! from the cassette without calling the provider, while still creating LLM
+//! spans, running interceptors and calculating cost. CI can so exercise the
+//! instrumentation paths deterministically and without API keys or cost.
+//!
+//! Requests are matched by a hash of the model and generation parameters
+//! (messages, temperature, max tokens, ...); identical requests replay their
+//! recordings in order. Cassettes are sanitized before they are written:
+//! trace IDs, span IDs, user IDs and metadata are dropped, and registered
+//! secrets are redacted from prompts and responses.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use llm_observatory_sdk::replay::{ReplayClient, ReplayMode};
+//! use llm_observatory_sdk::{ChatCompletionRequest, InstrumentedLLM, LLMObservatory, OpenAIClient};
+//!
+//! # async fn example() -> llm_observatory_sdk::Result<()> {
+//! let observatory = LLMObservatory::builder().with_service_name("ci").build()?;
+//! let api_key = std::env::var("OPENAI_API_KEY").unwrap_or_default();
+//!
+//! // LLM_OBSERVATORY_REPLAY=record refreshes the cassette against the API
+//! let client = ReplayClient::new(
+//!     OpenAIClient::new(api_key.as_str()).with_observatory(observatory.clone()),
+//!     "tests/fixtures/chat.json",
+//!     ReplayMode::from_env(),
+//! )?
+//! .with_observatory(observatory)
+//! .with_redaction(api_key);
+//!
+//! let response = client
+//!     .chat_completion(ChatCompletionRequest::new("gpt-4o").with_user("Hello"))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    cost::calculate_cost,
+    instrument::{create_span, InstrumentedSpan},
+    interceptor::InterceptorChain,
+    observatory::LLMObservatory,
+    traits::{
+        ChatCompletionRequest, ChatCompletionResponse, EmbeddingRequest, EmbeddingResponse,
+        InstrumentedLLM, StreamChunk,
+    },
+    Error, Result,
+};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use llm_observatory_core::{
+    span::{LlmInput, LlmOutput},
+    types::{Cost, Provider, TokenUsage},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Mutex;
+
+/// Environment variable read by [`ReplayMode::from_env`].
+pub const REPLAY_MODE_ENV: &str = "LLM_OBSERVATORY_REPLAY";
+
+/// Placeholder for redacted secrets in cassettes.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Current cassette format version.
+const CASSETTE_VERSION: u32 = 1;
+
+/// Whether calls are recorded or replayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayMode {
+    /// Call the provider and record every interaction, replacing the cassette
+    Record,
+    /// Serve recorded interactions only; unrecorded requests fail
+    Replay,
+    /// Replay recorded interactions and record the rest
+    Auto,
+}
+
+impl ReplayMode {
+    /// Get the mode as a string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReplayMode::Record => "record",
+            ReplayMode::Replay => "replay",
+            ReplayMode::Auto => "auto",
+        }
+    }
+
+    /// Read the mode from `LLM_OBSERVATORY_REPLAY`, defaulting to
+    /// [`ReplayMode::Replay`] so CI never calls the provider by accident.
+    pub fn from_env() -> Self {
+        std::env::var(REPLAY_MODE_ENV)
+            .ok()
+            .and_then(|mode| mode.parse().ok())
+            .unwrap_or(ReplayMode::Replay)
+    }
+}
+
+impl std::str::FromStr for ReplayMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "record" => Ok(ReplayMode::Record),
+            "replay" => Ok(ReplayMode::Replay),
+            "auto" => Ok(ReplayMode::Auto),
+            other => Err(Error::config(format!(
+                "Invalid replay mode '{}': expected record, replay or auto",
+                other
+            ))),
+        }
+    }
+}
+
+/// Recorded response of an interaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RecordedResponse {
+    /// Chat completion
+    Chat {
+        /// The response
+        response: ChatCompletionResponse,
+    },
+    /// Streaming chat completion
+    Stream {
+        /// The chunks, in order
+        chunks: Vec<StreamChunk>,
+    },
+    /// Embeddings
+    Embeddings {
+        /// The response
+        response: EmbeddingResponse,
+    },
+}
+
+/// A recorded request and its response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    /// Hash of the request, used for matching
+    pub key: String,
+    /// Provider that served the request
+    pub provider: Provider,
+    /// Sanitized request, for reading the cassette
+    pub request: serde_json::Value,
+    /// Sanitized response
+    pub response: RecordedResponse,
+}
+
+/// Recorded interactions, stored as one JSON file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    /// Format version
+    pub version: u32,
+    /// Interactions in recording order
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Load a cassette, or an empty one if the file does not exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(content) => {
+                let cassette: Cassette = serde_json::from_str(&content)?;
+                if cassette.version != CASSETTE_VERSION {
+                    return Err(Error::replay(format!(
+                        "Unsupported cassette version {} in {}",
+                        cassette.version,
+                        path.display()
+                    )));
+                }
+                Ok(cassette)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self {
+                version: CASSETTE_VERSION,
+                interactions: Vec::new(),
+            }),
+            Err(e) => Err(Error::replay(format!(
+                "Failed to read cassette {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    /// Write the cassette as pretty-printed JSON, creating parent directories.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+        };
+        write().map_err(|e| {
+            Error::replay(format!(
+                "Failed to write cassette {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// Cassette contents and the replay position of each request key.
+struct CassetteState {
+    cassette: Cassette,
+    cursors: HashMap<String, usize>,
+}
+
+impl CassetteState {
+    /// The next recording of `key`, repeating the last one once all have
+    /// been replayed.
+    fn next(&mut self, key: &str) -> Option<Interaction> {
+        let recorded: Vec<&Interaction> = self
+            .cassette
+            .interactions
+            .iter()
+            .filter(|i| i.key == key)
+            .collect();
+        let cursor = self.cursors.entry(key.to_string()).or_insert(0);
+        let interaction = recorded
+            .get(*cursor)
+            .or(recorded.last())
+            .map(|i| (*i).clone());
+        *cursor += 1;
+        interaction
+    }
+
+    /// Whether every recording of `key` has been replayed.
+    fn exhausted(&self, key: &str) -> bool {
+        let cursor = self.cursors.get(key).copied().unwrap_or(0);
+        self.cassette
+            .interactions
+            .iter()
+            .filter(|i| i.key == key)
+            .count()
+            <= cursor
+    }
+}
+
+/// Client wrapper that records calls to a cassette or replays them from it.
+///
+/// Attach the observatory to both the wrapped client (for recorded calls)
+/// and the wrapper (for replayed calls).
+pub struct ReplayClient<C> {
+    inner: C,
+    path: PathBuf,
+    mode: ReplayMode,
+    state: Mutex<CassetteState>,
+    redactions: Vec<String>,
+    observatory: Option<LLMObservatory>,
+}
+
+impl<C: InstrumentedLLM> ReplayClient<C> {
+    /// Wrap `inner`, recording to or replaying from the cassette at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cassette cannot be read, or does not exist in
+    /// [`ReplayMode::Replay`].
+    pub fn new(inner: C, path: impl Into<PathBuf>, mode: ReplayMode) -> Result<Self> {
+        let path = path.into();
+        let cassette = match mode {
+            ReplayMode::Record => Cassette {
+                version: CASSETTE_VERSION,
+                interactions: Vec::new(),
+            },
+            ReplayMode::Replay if !path.exists() => {
+                return Err(Error::replay(format!(
+                    "Cassette {} does not exist; record it with {}=record",
+                    path.display(),
+                    REPLAY_MODE_ENV
+                )));
+            }
+            ReplayMode::Replay | ReplayMode::Auto => Cassette::load(&path)?,
+        };
+
+        Ok(Self {
+            inner,
+            path,
+            mode,
+            state: Mutex::new(CassetteState {
+                cassette,
+                cursors: HashMap::new(),
+            }),
+            redactions: Vec::new(),
+            observatory: None,
+        })
+    }
+
+    /// Attach an observatory for spans of replayed calls.
+    pub fn with_observatory(mut self, observatory: LLMObservatory) -> Self {
+        self.observatory = Some(observatory);
+        self
+    }
+
+    /// Replace `secret` with `[REDACTED]` in recorded prompts and responses.
+    pub fn with_redaction(mut self, secret: impl Into<String>) -> Self {
+        let secret = secret.into();
+        if !secret.is_empty() {
+            self.redactions.push(secret);
+        }
+        self
+    }
+
+    /// The recording mode.
+    pub fn mode(&self) -> ReplayMode {
+        self.mode
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// A copy of the cassette.
+    pub fn cassette(&self) -> Cassette {
+        self.state.lock().unwrap().cassette.clone()
+    }
+
+    /// The recording to replay for `key`, or `None` if the call should go to
+    /// the provider.
+    fn lookup(&self, key: &str, kind: &str, model: &str) -> Result<Option<Interaction>> {
+        let mut state = self.state.lock().unwrap();
+        match self.mode {
+            ReplayMode::Record => Ok(None),
+            ReplayMode::Auto if state.exhausted(key) => Ok(None),
+            ReplayMode::Replay | ReplayMode::Auto => state.next(key).map(Some).ok_or_else(|| {
+                Error::replay(format!(
+                    "No recorded {} request to {} with key {} in {}",
+                    kind,
+                    model,
+                    key,
+                    self.path.display()
+                ))
+            }),
+        }
+    }
+
+    /// Sanitize and append an interaction, then write the cassette.
+    fn record(
+        &self,
+        key: String,
+        request: serde_json::Value,
+        response: RecordedResponse,
+    ) -> Result<()> {
+        let interaction = Interaction {
+            key,
+            provider: provider_from_name(self.inner.provider_name()),
+            request: self.redact(request),
+            response: self.sanitize(response)?,
+        };
+
+        let key = interaction.key.clone();
+        let mut state = self.state.lock().unwrap();
+        state.cassette.interactions.push(interaction);
+        if self.mode == ReplayMode::Auto {
+            // Keep the new recording from being replayed to the next caller
+            let recorded = state
+                .cassette
+                .interactions
+                .iter()
+                .filter(|i| i.key == key)
+                .count();
+            state.cursors.insert(key, recorded);
+        }
+        state.cassette.save(&self.path)
+    }
+
+    /// Drop per-call identifiers and redact secrets.
+    fn sanitize(&self, response: RecordedResponse) -> Result<RecordedResponse> {
+        let response = match response {
+            RecordedResponse::Chat { mut response } => {
+                response.trace_id.clear();
+                response.span_id.clear();
+                response.latency_ms = 0;
+                response.metadata.clear();
+                RecordedResponse::Chat { response }
+            }
+            RecordedResponse::Embeddings { mut response } => {
+                response.trace_id.clear();
+                response.span_id.clear();
+                response.latency_ms = 0;
+                response.metadata.clear();
+                RecordedResponse::Embeddings { response }
+            }
+            stream @ RecordedResponse::Stream { .. } => stream,
+        };
+        Ok(serde_json::from_value(
+            self.redact(serde_json::to_value(response)?),
+        )?)
+    }
+
+    /// Replace registered secrets in every string of `value`.
+    fn redact(&self, value: serde_json::Value) -> serde_json::Value {
+        use serde_json::Value;
+
+        if self.redactions.is_empty() {
+            return value;
+        }
+        match value {
+            Value::String(s) => Value::String(
+                self.redactions
+                    .iter()
+                    .fold(s, |s, secret| s.replace(secret.as_str(), REDACTED)),
+            ),
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|v| self.redact(v)).collect())
+            }
+            Value::Object(map) => {
+                Value::Object(map.into_iter().map(|(k, v)| (k, self.redact(v))).collect())
+            }
+            other => other,
+        }
+    }
+
+    /// Start the span of a replayed chat completion.
+    fn start_chat_span(
+        &self,
+        provider: Provider,
+        request: &ChatCompletionRequest,
+    ) -> Option<(InstrumentedSpan, InterceptorChain)> {
+        self.observatory.as_ref().map(|observatory| {
+            let mut builder = create_span(observatory, provider, &request.model)
+                .messages(request.messages.clone())
+                .attribute("llm.replay", "true");
+            if let Some(link) = &request.retrieval {
+                builder = builder.retrieval(link.clone());
+            }
+            if let Some(parent) = &request.parent_context {
+                builder = builder.parent(parent.clone());
+            }
+            if let Some(attempt) = &request.attempt {
+                builder = builder.attempt(attempt.clone());
+            }
+            (builder.start(), observatory.interceptors())
+        })
+    }
+
+    /// Serve a recorded chat completion, instrumented like a live call.
+    async fn replay_chat(
+        &self,
+        mut request: ChatCompletionRequest,
+        provider: Provider,
+        mut response: ChatCompletionResponse,
+        stream_chunks: Option<usize>,
+    ) -> Result<ChatCompletionResponse> {
+        response.metadata = request.metadata.clone().unwrap_or_default();
+
+        let Some((mut span, interceptors)) = self.start_chat_span(provider, &request) else {
+            return Ok(response);
+        };
+
+        let result = async {
+            interceptors.before_request(&mut request, &mut span).await?;
+            response.trace_id = span.trace_id().to_string();
+            response.span_id = span.span_id().to_string();
+            interceptors
+                .after_response(&request, &mut response, &mut span)
+                .await
+        }
+        .await;
+
+        if let Err(e) = result {
+            interceptors.on_error(&request, &e, &mut span).await;
+            let _ = span.finish_error(&e.to_string());
+            return Err(e);
+        }
+
+        if let Some(chunks) = stream_chunks {
+            span.record_first_token();
+            span.set_attribute("gen_ai.response.chunk_count", chunks as i64);
+        }
+        let output = LlmOutput {
+            content: response.content.clone(),
+            finish_reason: response.finish_reason.clone(),
+            metadata: Default::default(),
+        };
+        let cost = replayed_cost(&request.model, &response.usage, response.cost_usd);
+        let llm_span = span.finish_success(output, response.usage.clone(), cost)?;
+        response.latency_ms = llm_span.latency.total_ms;
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl<C: InstrumentedLLM> InstrumentedLLM for ReplayClient<C> {
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionResponse> {
+        request.validate()?;
+        let key = chat_key("chat", &request);
+
+        match self.lookup(&key, "chat", &request.model)? {
+            Some(Interaction {
+                provider,
+                response: RecordedResponse::Chat { response },
+                ..
+            }) => self.replay_chat(request, provider, response, None).await,
+            Some(_) => Err(Error::replay(format!(
+                "Recording {} is not a chat completion",
+                key
+            ))),
+            None => {
+                let recorded_request = chat_request_json(&request);
+                let response = self.inner.chat_completion(request).await?;
+                self.record(
+                    key,
+                    recorded_request,
+                    RecordedResponse::Chat {
+                        response: response.clone(),
+                    },
+                )?;
+                Ok(response)
+            }
+        }
+    }
+
+    async fn streaming_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>> {
+        request.validate()?;
+        let key = chat_key("stream", &request);
+
+        match self.lookup(&key, "streaming", &request.model)? {
+            Some(Interaction {
+                provider,
+                response: RecordedResponse::Stream { chunks },
+                ..
+            }) => {
+                let content: String = chunks.iter().map(|c| c.delta.as_str()).collect();
+                // Streams report completion tokens only
+                let completion_tokens = chunks.iter().rev().find_map(|c| c.partial_tokens);
+                let usage = TokenUsage::new(0, completion_tokens.unwrap_or(0));
+                let response = ChatCompletionResponse {
+                    id: chunks.first().map(|c| c.id.clone()).unwrap_or_default(),
+                    content,
+                    model: request.model.clone(),
+                    finish_reason: chunks.iter().rev().find_map(|c| c.finish_reason.clone()),
+                    cost_usd: replayed_cost(&request.model, &usage, 0.0).amount_usd,
+                    usage,
+                    latency_ms: 0,
+                    trace_id: String::new(),
+                    span_id: String::new(),
+                    metadata: HashMap::new(),
+                };
+                let count = chunks.len();
+                self.replay_chat(request, provider, response, Some(count))
+                    .await?;
+                Ok(Box::pin(futures::stream::iter(chunks.into_iter().map(Ok))))
+            }
+            Some(_) => Err(Error::replay(format!("Recording {} is not a stream", key))),
+            None => {
+                let recorded_request = chat_request_json(&request);
+                let stream = self.inner.streaming_completion(request).await?;
+                let chunks = stream
+                    .collect::<Vec<_>>()
+                    .await
+                    .into_iter()
+                    .collect::<Result<Vec<_>>>()?;
+                self.record(
+                    key,
+                    recorded_request,
+                    RecordedResponse::Stream {
+                        chunks: chunks.clone(),
+                    },
+                )?;
+                Ok(Box::pin(futures::stream::iter(chunks.into_iter().map(Ok))))
+            }
+        }
+    }
+
+    async fn embeddings(&self, request: EmbeddingRequest) -> Result<EmbeddingResponse> {
+        request.validate()?;
+        let request_json = serde_json::json!({
+            "model": request.model,
+            "input": request.input,
+            "dimensions": request.dimensions,
+        });
+        let key = hash_key("embeddings", &request_json);
+
+        match self.lookup(&key, "embeddings", &request.model)? {
+            Some(Interaction {
+                provider,
+                response: RecordedResponse::Embeddings { mut response },
+                ..
+            }) => {
+                response.metadata = request.metadata.clone().unwrap_or_default();
+                let Some(observatory) = &self.observatory else {
+                    return Ok(response);
+                };
+
+                let mut builder = create_span(observatory, provider, &request.model)
+                    .operation_name("llm.embeddings")
+                    .input(LlmInput::Text {
+                        prompt: request.input.join("\n"),
+                    })
+                    .attribute("gen_ai.operation.name", "embeddings")
+                    .attribute("llm.replay", "true");
+                if let Some(parent) = &request.parent_context {
+                    builder = builder.parent(parent.clone());
+                }
+                if let Some(attempt) = &request.attempt {
+                    builder = builder.attempt(attempt.clone());
+                }
+                let span = builder.start();
+                span.set_attribute("gen_ai.request.embedding.count", request.input.len() as i64);
+                span.set_attribute(
+                    "gen_ai.embeddings.dimension.count",
+                    response.dimensions as i64,
+                );
+
+                let output = LlmOutput {
+                    content: String::new(),
+                    finish_reason: None,
+                    metadata: [
+                        (
+                            "embedding_count".to_string(),
+                            response.embeddings.len().into(),
+                        ),
+                        ("dimensions".to_string(), response.dimensions.into()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                };
+                let llm_span = span.finish_success(
+                    output,
+                    response.usage.clone(),
+                    Cost::new(response.cost_usd),
+                )?;
+                response.trace_id = llm_span.trace_id;
+                response.span_id = llm_span.span_id;
+                response.latency_ms = llm_span.latency.total_ms;
+                Ok(response)
+            }
+            Some(_) => Err(Error::replay(format!(
+                "Recording {} is not an embeddings response",
+                key
+            ))),
+            None => {
+                let response = self.inner.embeddings(request).await?;
+                self.record(
+                    key,
+                    request_json,
+                    RecordedResponse::Embeddings {
+                        response: response.clone(),
+                    },
+                )?;
+                Ok(response)
+            }
+        }
+    }
+
+    fn provider_name(&self) -> &str {
+        self.inner.provider_name()
+    }
+
+    fn default_model(&self) -> Option<&str> {
+        self.inner.default_model()
+    }
+}
+
+/// The generation parameters of a chat request, which decide its response.
+fn chat_request_json(request: &ChatCompletionRequest) -> serde_json::Value {
+    serde_json::json!({
+        "model": request.model,
+        "messages": request.messages,
+        "temperature": request.temperature,
+        "max_tokens": request.max_tokens,
+        "top_p": request.top_p,
+        "frequency_penalty": request.frequency_penalty,
+        "presence_penalty": request.presence_penalty,
+        "stop": request.stop,
+    })
+}
+
+fn chat_key(kind: &str, request: &ChatCompletionRequest) -> String {
+    hash_key(kind, &chat_request_json(request))
+}
+
+/// Hash of `kind` and `request`. JSON object keys serialize sorted, so the
+/// key is stable across runs.
+fn hash_key(kind: &str, request: &serde_json::Value) -> String {
+    let digest = Sha256::new()
+        .chain_update(kind.as_bytes())
+        .chain_update(request.to_string().as_bytes())
+        .finalize();
+    hex::encode(&digest[..16])
+}
+
+/// Cost of a replayed call from current pricing, falling back to the
+/// recorded amount for models without pricing.
+fn replayed_cost(model: &str, usage: &TokenUsage, recorded_usd: f64) -> Cost {
+    calculate_cost(model, usage).unwrap_or_else(|_| Cost::new(recorded_usd))
+}
+
+fn provider_from_name(name: &str) -> Provider {
+    match name {
+        "openai" => Provider::OpenAI,
+        "anthropic" => Provider::Anthropic,
+        "google" => Provider::Google,
+        "mistral" => Provider::Mistral,
+        "cohere" => Provider::Cohere,
+        "self-hosted" => Provider::SelfHosted,
+        other => Provider::Custom(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockFailure, MockLlmClient, MockResponse};
+
+    fn cassette_path() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "llm-observatory-cassette-{}.json",
+            uuid::Uuid::new_v4()
+        ))
+    }
+
+    fn observatory() -> LLMObservatory {
+        LLMObservatory::builder()
+            .with_service_name("replay-test")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_replay_mode_parsing() {
+        assert_eq!("record".parse::<ReplayMode>().unwrap(), ReplayMode::Record);
+        assert_eq!("Replay".parse::<ReplayMode>().unwrap(), ReplayMode::Replay);
+        assert_eq!("auto".parse::<ReplayMode>().unwrap(), ReplayMode::Auto);
+        assert!("live".parse::<ReplayMode>().is_err());
+    }
+
+    #[test]
+    fn test_request_key_ignores_tracing_fields() {
+        let request = ChatCompletionRequest::new("gpt-4o").with_user("Hi");
+        let tagged = request
+            .clone()
+            .with_user_id("user-1")
+            .with_metadata("request_id", "abc");
+        assert_eq!(chat_key("chat", &request), chat_key("chat", &tagged));
+
+        let warmer = request.clone().with_temperature(0.9);
+        assert_ne!(chat_key("chat", &request), chat_key("chat", &warmer));
+        assert_ne!(chat_key("chat", &request), chat_key("stream", &request));
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let path = cassette_path();
+        let secret = "sk-secret-123";
+        let request = ChatCompletionRequest::new("gpt-4")
+            .with_user(format!("My key is {}", secret))
+            .with_metadata("request_id", "abc");
+
+        let recorder = ReplayClient::new(
+            MockLlmClient::new()
+                .with_provider(Provider::OpenAI)
+                .with_response(
+                    MockResponse::text(format!("Do not share {}", secret)).with_usage(50, 5),
+                ),
+            &path,
+            ReplayMode::Record,
+        )
+        .unwrap()
+        .with_redaction(secret);
+        let recorded = recorder.chat_completion(request.clone()).await.unwrap();
+        assert_eq!(recorder.inner().call_count(), 1);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains(secret));
+        assert!(!content.contains("request_id"));
+
+        // A failing provider proves replay never calls it
+        let replayer = ReplayClient::new(
+            MockLlmClient::new().with_default_response(MockResponse::failure(MockFailure::Auth)),
+            &path,
+            ReplayMode::Replay,
+        )
+        .unwrap()
+        .with_observatory(observatory());
+
+        let replayed = replayer.chat_completion(request.clone()).await.unwrap();
+        assert_eq!(replayer.inner().call_count(), 0);
+        assert_eq!(replayed.content, format!("Do not share {}", REDACTED));
+        assert_eq!(replayed.usage.prompt_tokens, recorded.usage.prompt_tokens);
+        assert!((replayed.cost_usd - recorded.cost_usd).abs() < 1e-12);
+        assert!(!replayed.trace_id.is_empty());
+        assert_eq!(
+            replayed.metadata.get("request_id").map(String::as_str),
+            Some("abc")
+        );
+
+        // Identical requests keep replaying the last recording
+        assert!(replayer.chat_completion(request).await.is_ok());
+
+        let unknown = ChatCompletionRequest::new("gpt-4").with_user("Something else");
+        assert!(matches!(
+            replayer.chat_completion(unknown).await,
+            Err(Error::Replay(_))
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_auto_mode_records_missing_interactions() {
+        let path = cassette_path();
+        let request = ChatCompletionRequest::new("mock-model").with_user("Hi");
+
+        let client = ReplayClient::new(
+            MockLlmClient::new()
+                .with_response(MockResponse::text("first"))
+                .with_response(MockResponse::text("second")),
+            &path,
+            ReplayMode::Auto,
+        )
+        .unwrap();
+        assert_eq!(
+            client
+                .chat_completion(request.clone())
+                .await
+                .unwrap()
+                .content,
+            "first"
+        );
+        assert_eq!(
+            client
+                .chat_completion(request.clone())
+                .await
+                .unwrap()
+                .content,
+            "second"
+        );
+        assert_eq!(client.cassette().interactions.len(), 2);
+
+        // A new run replays both recordings in order
+        let client = ReplayClient::new(MockLlmClient::new(), &path, ReplayMode::Auto).unwrap();
+        assert_eq!(
+            client
+                .chat_completion(request.clone())
+                .await
+                .unwrap()
+                .content,
+            "first"
+        );
+        assert_eq!(
+            client.chat_completion(request).await.unwrap().content,
+            "second"
+        );
+        assert_eq!(client.inner().call_count(), 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_streams_and_embeddings() {
+        let path = cassette_path();
+        let chat = ChatCompletionRequest::new("mock-model").with_user("Hi");
+        let embed = EmbeddingRequest::new("text-embedding-3-small").with_inputs(["a", "b"]);
+
+        let recorder = ReplayClient::new(
+            MockLlmClient::new().with_response(MockResponse::text("Hello there friend")),
+            &path,
+            ReplayMode::Record,
+        )
+        .unwrap();
+        let recorded_chunks: Vec<StreamChunk> = recorder
+            .streaming_completion(chat.clone())
+            .await
+            .unwrap()
+            .map(|c| c.unwrap())
+            .collect()
+            .await;
+        let recorded_embeddings = recorder.embeddings(embed.clone()).await.unwrap();
+
+        let replayer = ReplayClient::new(MockLlmClient::new(), &path, ReplayMode::Replay)
+            .unwrap()
+            .with_observatory(observatory());
+        let chunks: Vec<StreamChunk> = replayer
+            .streaming_completion(chat)
+            .await
+            .unwrap()
+            .map(|c| c.unwrap())
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), recorded_chunks.len());
+        assert_eq!(
+            chunks.iter().map(|c| c.delta.as_str()).collect::<String>(),
+            "Hello there friend"
+        );
+
+        let embeddings = replayer.embeddings(embed).await.unwrap();
+        assert_eq!(embeddings.embeddings, recorded_embeddings.embeddings);
+        assert!(!embeddings.trace_id.is_empty());
+        assert_eq!(replayer.inner().call_count(), 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_replay_requires_cassette() {
+        let result = ReplayClient::new(MockLlmClient::new(), cassette_path(), ReplayMode::Replay);
+        assert!(matches!(result, Err(Error::Replay(_))));
+    }
+}
@@ -0,0 +1,386 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Retrieval-augmented generation (RAG) instrumentation.
+//!
+//! A retrieval span records a vector search: the database, top-k, query
+//! embedding latency, and the IDs and scores of the documents returned. The
+//! resulting [`RetrievalLink`] is attached to the downstream generation span,
+//! which then carries an OpenTelemetry link back to the retrieval along with
+//! its document count and top score, so retrieval quality can be analyzed
+//! against generation cost.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use llm_observatory_sdk::{
+//!     ChatCompletionRequest, InstrumentedLLM, LLMObservatory, OpenAIClient, RetrievedDocument,
+//! };
+//!
+//! # async fn example(observatory: LLMObservatory, client: OpenAIClient) -> llm_observatory_sdk::Result<()> {
+//! let mut retrieval = observatory
+//!     .retrieval_span("qdrant")
+//!     .collection("docs")
+//!     .top_k(5)
+//!     .start();
+//!
+//! let embedding = retrieval.embed_query(async { vec![0.1_f32; 1536] }).await;
+//! retrieval.record_documents(&[
+//!     RetrievedDocument::new("doc-17", 0.89),
+//!     RetrievedDocument::new("doc-4", 0.81),
+//! ]);
+//! let link = retrieval.finish_success();
+//!
+//! let request = ChatCompletionRequest::new("gpt-4o")
+//!     .with_user("What is our refund policy?")
+//!     .with_retrieval(link);
+//! let response = client.chat_completion(request).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::observatory::LLMObservatory;
+use opentelemetry::{
+    trace::{SpanContext, SpanKind, Status, TraceContextExt, Tracer},
+    Array, Context, KeyValue, StringValue, Value,
+};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// A document returned by a vector search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetrievedDocument {
+    /// Document ID
+    pub id: String,
+    /// Similarity score
+    pub score: f64,
+}
+
+impl RetrievedDocument {
+    /// Create a retrieved document.
+    pub fn new(id: impl Into<String>, score: f64) -> Self {
+        Self {
+            id: id.into(),
+            score,
+        }
+    }
+}
+
+/// Builder for a retrieval span.
+///
+/// Created with [`LLMObservatory::retrieval_span`].
+pub struct RetrievalSpanBuilder {
+    observatory: LLMObservatory,
+    parent: Context,
+    vector_db: String,
+    collection: Option<String>,
+    top_k: Option<u32>,
+    embedding_model: Option<String>,
+    attributes: Vec<KeyValue>,
+}
+
+impl RetrievalSpanBuilder {
+    pub(crate) fn new(observatory: LLMObservatory, vector_db: impl Into<String>) -> Self {
+        Self {
+            observatory,
+            parent: Context::current(),
+            vector_db: vector_db.into(),
+            collection: None,
+            top_k: None,
+            embedding_model: None,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Set the collection or index searched.
+    pub fn collection(mut self, collection: impl Into<String>) -> Self {
+        self.collection = Some(collection.into());
+        self
+    }
+
+    /// Set the number of documents requested.
+    pub fn top_k(mut self, top_k: u32) -> Self {
+        self.top_k = Some(top_k);
+        self
+    }
+
+    /// Set the model used to embed the query.
+    pub fn embedding_model(mut self, model: impl Into<String>) -> Self {
+        self.embedding_model = Some(model.into());
+        self
+    }
+
+    /// Set the parent context (default: the currently active context).
+    pub fn parent(mut self, parent: Context) -> Self {
+        self.parent = parent;
+        self
+    }
+
+    /// Add a custom attribute.
+    pub fn attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.push(KeyValue::new(key.into(), value.into()));
+        self
+    }
+
+    /// Start the retrieval span.
+    pub fn start(self) -> RetrievalSpan {
+        let tracer = self.observatory.tracer();
+
+        let mut attributes = vec![
+            KeyValue::new("db.system", self.vector_db.clone()),
+            KeyValue::new("db.operation.name", "query"),
+        ];
+        if let Some(collection) = self.collection {
+            attributes.push(KeyValue::new("db.collection.name", collection));
+        }
+        if let Some(top_k) = self.top_k {
+            attributes.push(KeyValue::new("retrieval.top_k", top_k as i64));
+        }
+        if let Some(model) = self.embedding_model {
+            attributes.push(KeyValue::new("retrieval.embedding.model", model));
+        }
+        attributes.extend(self.attributes);
+
+        let span = tracer
+            .span_builder(format!("retrieval {}", self.vector_db))
+            .with_kind(SpanKind::Client)
+            .with_attributes(attributes)
+            .start_with_context(tracer, &self.parent);
+
+        RetrievalSpan {
+            context: self.parent.with_span(span),
+            start_time: Instant::now(),
+            document_count: 0,
+            top_score: None,
+        }
+    }
+}
+
+/// An in-progress retrieval span.
+pub struct RetrievalSpan {
+    context: Context,
+    start_time: Instant,
+    document_count: usize,
+    top_score: Option<f64>,
+}
+
+impl RetrievalSpan {
+    /// Get the span ID.
+    pub fn span_id(&self) -> String {
+        format!("{:x}", self.context.span().span_context().span_id())
+    }
+
+    /// Get the trace ID.
+    pub fn trace_id(&self) -> String {
+        format!("{:x}", self.context.span().span_context().trace_id())
+    }
+
+    /// Get the context with this span active.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Await the query embedding, recording its latency.
+    pub async fn embed_query<F: Future>(&self, future: F) -> F::Output {
+        let start = Instant::now();
+        let output = future.await;
+        self.record_embedding_latency(start.elapsed());
+        output
+    }
+
+    /// Record the query embedding latency.
+    pub fn record_embedding_latency(&self, latency: Duration) {
+        self.context.span().set_attribute(KeyValue::new(
+            "retrieval.embedding.latency_ms",
+            latency.as_millis() as i64,
+        ));
+    }
+
+    /// Record the documents returned by the search.
+    pub fn record_documents(&mut self, documents: &[RetrievedDocument]) {
+        let span = self.context.span();
+
+        let ids: Vec<StringValue> = documents.iter().map(|d| d.id.clone().into()).collect();
+        let scores: Vec<f64> = documents.iter().map(|d| d.score).collect();
+
+        span.set_attribute(KeyValue::new(
+            "retrieval.documents.count",
+            documents.len() as i64,
+        ));
+        span.set_attribute(KeyValue::new(
+            "retrieval.documents.ids",
+            Value::Array(Array::String(ids)),
+        ));
+        span.set_attribute(KeyValue::new(
+            "retrieval.documents.scores",
+            Value::Array(Array::F64(scores)),
+        ));
+
+        if let Some(summary) = ScoreSummary::from_documents(documents) {
+            span.set_attribute(KeyValue::new("retrieval.score.max", summary.max));
+            span.set_attribute(KeyValue::new("retrieval.score.min", summary.min));
+            span.set_attribute(KeyValue::new("retrieval.score.mean", summary.mean));
+            self.top_score = Some(summary.max);
+        }
+        self.document_count = documents.len();
+    }
+
+    /// Link for the downstream generation span.
+    pub fn link(&self) -> RetrievalLink {
+        RetrievalLink {
+            span_context: self.context.span().span_context().clone(),
+            document_count: self.document_count,
+            top_score: self.top_score,
+        }
+    }
+
+    /// Finish the span as successful, returning the link for the generation
+    /// span.
+    pub fn finish_success(self) -> RetrievalLink {
+        self.finish(Status::Ok)
+    }
+
+    /// Finish the span with an error.
+    pub fn finish_error(self, error: &str) -> RetrievalLink {
+        self.context
+            .span()
+            .add_event("retrieval.error", vec![KeyValue::new("error", error.to_string())]);
+        self.finish(Status::error(error.to_string()))
+    }
+
+    fn finish(self, status: Status) -> RetrievalLink {
+        let link = self.link();
+        let span = self.context.span();
+        span.set_attribute(KeyValue::new(
+            "retrieval.duration_ms",
+            self.start_time.elapsed().as_millis() as i64,
+        ));
+        span.set_status(status);
+        span.end();
+        link
+    }
+}
+
+/// Reference from a generation span to the retrieval that fed it.
+#[derive(Debug, Clone)]
+pub struct RetrievalLink {
+    span_context: SpanContext,
+    document_count: usize,
+    top_score: Option<f64>,
+}
+
+impl RetrievalLink {
+    /// Span context of the retrieval span.
+    pub fn span_context(&self) -> &SpanContext {
+        &self.span_context
+    }
+
+    /// Number of documents retrieved.
+    pub fn document_count(&self) -> usize {
+        self.document_count
+    }
+
+    /// Highest similarity score, if any documents were retrieved.
+    pub fn top_score(&self) -> Option<f64> {
+        self.top_score
+    }
+
+    /// Attributes recorded on the generation span.
+    pub(crate) fn attributes(&self) -> Vec<KeyValue> {
+        let mut attributes = vec![
+            KeyValue::new(
+                "gen_ai.retrieval.span_id",
+                format!("{:x}", self.span_context.span_id()),
+            ),
+            KeyValue::new(
+                "gen_ai.retrieval.document_count",
+                self.document_count as i64,
+            ),
+        ];
+        if let Some(score) = self.top_score {
+            attributes.push(KeyValue::new("gen_ai.retrieval.top_score", score));
+        }
+        attributes
+    }
+}
+
+/// Summary statistics of retrieval scores.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ScoreSummary {
+    max: f64,
+    min: f64,
+    mean: f64,
+}
+
+impl ScoreSummary {
+    fn from_documents(documents: &[RetrievedDocument]) -> Option<Self> {
+        if documents.is_empty() {
+            return None;
+        }
+        let scores = documents.iter().map(|d| d.score);
+        Some(Self {
+            max: scores.clone().fold(f64::NEG_INFINITY, f64::max),
+            min: scores.clone().fold(f64::INFINITY, f64::min),
+            mean: scores.sum::<f64>() / documents.len() as f64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChatCompletionRequest;
+
+    #[test]
+    fn test_score_summary() {
+        let documents = vec![
+            RetrievedDocument::new("a", 0.9),
+            RetrievedDocument::new("b", 0.5),
+            RetrievedDocument::new("c", 0.7),
+        ];
+        let summary = ScoreSummary::from_documents(&documents).unwrap();
+        assert_eq!(summary.max, 0.9);
+        assert_eq!(summary.min, 0.5);
+        assert!((summary.mean - 0.7).abs() < 1e-9);
+
+        assert!(ScoreSummary::from_documents(&[]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_retrieval_link() {
+        let observatory = LLMObservatory::builder()
+            .with_service_name("retrieval-test")
+            .build()
+            .unwrap();
+
+        let mut retrieval = observatory
+            .retrieval_span("qdrant")
+            .collection("docs")
+            .top_k(2)
+            .start();
+        let embedding = retrieval.embed_query(async { vec![0.0_f32; 8] }).await;
+        assert_eq!(embedding.len(), 8);
+
+        retrieval.record_documents(&[
+            RetrievedDocument::new("doc-1", 0.82),
+            RetrievedDocument::new("doc-2", 0.64),
+        ]);
+        let span_id = retrieval.span_id();
+        let link = retrieval.finish_success();
+
+        assert_eq!(link.document_count(), 2);
+        assert_eq!(link.top_score(), Some(0.82));
+        assert_eq!(format!("{:x}", link.span_context().span_id()), span_id);
+
+        let attributes = link.attributes();
+        assert!(attributes
+            .iter()
+            .any(|kv| kv.key.as_str() == "gen_ai.retrieval.span_id" && kv.value.as_str() == span_id));
+
+        let request = ChatCompletionRequest::new("gpt-4o")
+            .with_user("Hello")
+            .with_retrieval(link);
+        assert!(request.retrieval.is_some());
+        assert!(!serde_json::to_string(&request).unwrap().contains("retrieval"));
+    }
+}
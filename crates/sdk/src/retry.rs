@@ -0,0 +1,466 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Retries and model fallbacks with linked attempt spans.
+//!
+//! [`ResilientClient`] wraps any [`InstrumentedLLM`] client: failed chat
+//! completions are retried with exponential backoff and, once a model's
+//! retries are used up, sent to the next fallback model. Every attempt is a
+//! span of its own, and the attempts of one call are tied together:
+//!
+//! | Attribute                  | Type   | Description                                     |
+//! |----------------------------|--------|-------------------------------------------------|
+//! | `llm.retry.group_id`       | string | Shared by all attempts of one call              |
+//! | `llm.retry.attempt`        | int    | Attempt number within the call, from 1          |
+//! | `llm.fallback.from_model`  | string | Model given up on (first attempt on a fallback) |
+//! | `llm.fallback.reason`      | string | Why it was given up on, see [`FallbackReason`]  |
+//!
+//! Each attempt after the first also carries an OpenTelemetry link to the
+//! span of the attempt before it. The analytics API reports retry rates and
+//! fallback frequency per model from these attributes
+//! (`GET /api/v1/retries`).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use llm_observatory_sdk::{ChatCompletionRequest, InstrumentedLLM, OpenAIClient, RetryExt};
+//!
+//! # async fn example(client: OpenAIClient) -> llm_observatory_sdk::Result<()> {
+//! let client = client
+//!     .with_retries(2)
+//!     .with_fallback(["gpt-4o-mini", "gpt-3.5-turbo"]);
+//!
+//! let request = ChatCompletionRequest::new("gpt-4o").with_user("Hello!");
+//! let response = client.chat_completion(request).await?;
+//! println!("Served by {}", response.model);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    traits::{
+        ChatCompletionRequest, ChatCompletionResponse, EmbeddingRequest, EmbeddingResponse,
+        InstrumentedLLM, StreamChunk,
+    },
+    Error, Result,
+};
+use async_trait::async_trait;
+use futures::Stream;
+use opentelemetry::{trace::SpanContext, KeyValue};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Attribute shared by all attempts of one call.
+pub const RETRY_GROUP_ID: &str = "llm.retry.group_id";
+
+/// Attempt number attribute, from 1.
+pub const RETRY_ATTEMPT: &str = "llm.retry.attempt";
+
+/// Attribute naming the model a fallback gave up on.
+pub const FALLBACK_FROM_MODEL: &str = "llm.fallback.from_model";
+
+/// Attribute with the reason for a fallback.
+pub const FALLBACK_REASON: &str = "llm.fallback.reason";
+
+/// Default delay before the first retry.
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Default longest delay between retries.
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+/// Why a call moved on to a fallback model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FallbackReason {
+    /// The provider rate-limited the model (429)
+    RateLimited,
+    /// The request timed out
+    Timeout,
+    /// The provider failed (5xx) or could not be reached
+    ServerError,
+    /// The provider does not serve the model (404)
+    ModelNotFound,
+    /// Any other error
+    Error,
+}
+
+impl FallbackReason {
+    /// Classify the error the last attempt failed with.
+    pub fn from_error(error: &Error) -> Self {
+        match error {
+            Error::RateLimit(_) | Error::Api { status: 429, .. } => Self::RateLimited,
+            Error::Timeout => Self::Timeout,
+            Error::Http(e) if e.is_timeout() => Self::Timeout,
+            Error::Api {
+                status: 500..=599, ..
+            }
+            | Error::Http(_) => Self::ServerError,
+            Error::ModelNotFound(_) | Error::Api { status: 404, .. } => Self::ModelNotFound,
+            _ => Self::Error,
+        }
+    }
+
+    /// Attribute value of the reason.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RateLimited => "rate_limited",
+            Self::Timeout => "timeout",
+            Self::ServerError => "server_error",
+            Self::ModelNotFound => "model_not_found",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Where a request stands among the attempts of one call.
+///
+/// Set on [`ChatCompletionRequest::attempt`] by [`ResilientClient`]; clients
+/// record it on the span of the attempt.
+#[derive(Debug, Clone)]
+pub struct AttemptLink {
+    group_id: String,
+    attempt: u32,
+    fallback: Option<(String, FallbackReason)>,
+    previous: Option<SpanContext>,
+    started: Arc<Mutex<Option<SpanContext>>>,
+}
+
+impl AttemptLink {
+    fn new(
+        group_id: &str,
+        attempt: u32,
+        fallback: Option<(String, FallbackReason)>,
+        previous: Option<SpanContext>,
+    ) -> Self {
+        Self {
+            group_id: group_id.to_string(),
+            attempt,
+            fallback,
+            previous,
+            started: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// ID shared by all attempts of the call.
+    pub fn group_id(&self) -> &str {
+        &self.group_id
+    }
+
+    /// Attempt number within the call, from 1.
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Model given up on and why, on the first attempt on a fallback model.
+    pub fn fallback(&self) -> Option<(&str, FallbackReason)> {
+        self.fallback
+            .as_ref()
+            .map(|(model, reason)| (model.as_str(), *reason))
+    }
+
+    /// Span of the attempt before this one, when it was instrumented.
+    pub fn previous_span_context(&self) -> Option<&SpanContext> {
+        self.previous.as_ref()
+    }
+
+    /// Span attributes recording the attempt.
+    pub fn attributes(&self) -> Vec<KeyValue> {
+        let mut attributes = vec![
+            KeyValue::new(RETRY_GROUP_ID, self.group_id.clone()),
+            KeyValue::new(RETRY_ATTEMPT, i64::from(self.attempt)),
+        ];
+        if let Some((model, reason)) = &self.fallback {
+            attributes.push(KeyValue::new(FALLBACK_FROM_MODEL, model.clone()));
+            attributes.push(KeyValue::new(FALLBACK_REASON, reason.as_str()));
+        }
+        attributes
+    }
+
+    /// Remember the span the attempt started, for the next attempt to link to.
+    pub(crate) fn record_started(&self, span_context: SpanContext) {
+        *self.started.lock().unwrap() = Some(span_context);
+    }
+
+    fn started(&self) -> Option<SpanContext> {
+        self.started.lock().unwrap().clone()
+    }
+}
+
+/// Whether a failed attempt may succeed when sent again.
+fn is_transient(error: &Error) -> bool {
+    error.is_retryable()
+        || matches!(
+            error,
+            Error::Api {
+                status: 500..=599,
+                ..
+            } | Error::Http(_)
+        )
+}
+
+/// Whether a failed call may succeed on another model. Invalid requests,
+/// credentials and spend caps fail the same way on every model.
+fn may_fall_back(error: &Error) -> bool {
+    !matches!(
+        error,
+        Error::InvalidInput(_) | Error::SpendCapExceeded { .. }
+    ) && !error.is_auth_error()
+}
+
+/// Client retrying failed chat completions and falling back to other models.
+///
+/// Streaming completions and embeddings are passed through unchanged.
+pub struct ResilientClient<C> {
+    inner: C,
+    max_retries: u32,
+    fallback_models: Vec<String>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl<C: InstrumentedLLM> ResilientClient<C> {
+    /// Wrap a client, without retries or fallbacks until configured.
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            max_retries: 0,
+            fallback_models: Vec::new(),
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+
+    /// Retry each model up to `max_retries` times on rate limits, timeouts
+    /// and server errors.
+    pub fn with_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Models to try, in order, once the requested model has failed.
+    pub fn with_fallback<I, S>(mut self, models: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.fallback_models = models.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the delay before the first retry, doubled for each retry after it
+    /// up to `max`.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Get the wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+#[async_trait]
+impl<C: InstrumentedLLM> InstrumentedLLM for ResilientClient<C> {
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionResponse> {
+        request.validate()?;
+
+        let group_id = Uuid::new_v4().to_string();
+        let mut models = vec![request.model.clone()];
+        for model in &self.fallback_models {
+            if !models.contains(model) {
+                models.push(model.clone());
+            }
+        }
+
+        let mut attempt = 0;
+        let mut previous = None;
+        let mut last_error: Option<Error> = None;
+        for (index, model) in models.iter().enumerate() {
+            // Only the previous model's failures lead here
+            let mut fallback = last_error
+                .as_ref()
+                .map(|error| (models[index - 1].clone(), FallbackReason::from_error(error)));
+
+            for retry in 0..=self.max_retries {
+                if retry > 0 {
+                    tokio::time::sleep(self.backoff(retry)).await;
+                }
+                attempt += 1;
+
+                let link = AttemptLink::new(&group_id, attempt, fallback.take(), previous.take());
+                let mut request = request.clone();
+                request.model = model.clone();
+                request.attempt = Some(link.clone());
+
+                match self.inner.chat_completion(request).await {
+                    Ok(response) => return Ok(response),
+                    Err(error) => {
+                        previous = link.started();
+                        if !may_fall_back(&error) {
+                            return Err(error);
+                        }
+                        let transient = is_transient(&error);
+                        last_error = Some(error);
+                        if !transient {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| Error::internal("No attempt was made")))
+    }
+
+    async fn streaming_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>> {
+        self.inner.streaming_completion(request).await
+    }
+
+    async fn embeddings(&self, request: EmbeddingRequest) -> Result<EmbeddingResponse> {
+        self.inner.embeddings(request).await
+    }
+
+    fn provider_name(&self) -> &str {
+        self.inner.provider_name()
+    }
+
+    fn default_model(&self) -> Option<&str> {
+        self.inner.default_model()
+    }
+}
+
+/// Retry and fallback helpers for every instrumented client.
+pub trait RetryExt: InstrumentedLLM + Sized {
+    /// Wrap the client to retry failed chat completions; see
+    /// [`ResilientClient::with_retries`].
+    fn with_retries(self, max_retries: u32) -> ResilientClient<Self> {
+        ResilientClient::new(self).with_retries(max_retries)
+    }
+
+    /// Wrap the client to fall back to other models; see
+    /// [`ResilientClient::with_fallback`].
+    fn with_fallback<I, S>(self, models: I) -> ResilientClient<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        ResilientClient::new(self).with_fallback(models)
+    }
+}
+
+impl<C: InstrumentedLLM> RetryExt for C {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockFailure, MockLlmClient, MockResponse};
+    use crate::LLMObservatory;
+
+    fn client(responses: Vec<MockResponse>) -> MockLlmClient {
+        responses
+            .into_iter()
+            .fold(MockLlmClient::new(), |client, response| {
+                client.with_response(response)
+            })
+            .with_default_response(MockResponse::text("OK"))
+    }
+
+    #[tokio::test]
+    async fn test_retries_then_falls_back() {
+        let observatory = LLMObservatory::builder()
+            .with_service_name("retry-test")
+            .build()
+            .unwrap();
+        let client = client(vec![
+            MockResponse::failure(MockFailure::RateLimit),
+            MockResponse::failure(MockFailure::api(503, "overloaded")),
+            MockResponse::failure(MockFailure::api(404, "no such model")),
+        ])
+        .with_observatory(observatory)
+        .with_retries(1)
+        .with_fallback(["gpt-4o-mini", "gpt-3.5-turbo"])
+        .with_backoff(Duration::ZERO, Duration::ZERO);
+
+        let response = client
+            .chat_completion(ChatCompletionRequest::new("gpt-4o").with_user("Hi"))
+            .await
+            .unwrap();
+        assert_eq!(response.model, "gpt-3.5-turbo");
+
+        // gpt-4o twice, gpt-4o-mini once (not found is not retried), then
+        // gpt-3.5-turbo
+        let requests = client.inner().requests();
+        let links: Vec<&AttemptLink> = requests
+            .iter()
+            .map(|r| r.attempt.as_ref().unwrap())
+            .collect();
+        let models: Vec<&str> = requests.iter().map(|r| r.model.as_str()).collect();
+        assert_eq!(models, ["gpt-4o", "gpt-4o", "gpt-4o-mini", "gpt-3.5-turbo"]);
+        assert_eq!(
+            links.iter().map(|l| l.attempt()).collect::<Vec<_>>(),
+            [1, 2, 3, 4]
+        );
+        assert!(links.iter().all(|l| l.group_id() == links[0].group_id()));
+        assert_eq!(links[1].fallback(), None);
+        assert_eq!(
+            links[2].fallback(),
+            Some(("gpt-4o", FallbackReason::ServerError))
+        );
+        assert_eq!(
+            links[3].fallback(),
+            Some(("gpt-4o-mini", FallbackReason::ModelNotFound))
+        );
+
+        // Each attempt links to the span of the one before it
+        assert!(links[0].previous_span_context().is_none());
+        assert!(links[1..]
+            .iter()
+            .all(|l| l.previous_span_context().is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_invalid_requests() {
+        let client = client(vec![MockResponse::failure(MockFailure::Auth)])
+            .with_retries(3)
+            .with_fallback(["gpt-4o-mini"]);
+
+        let err = client
+            .chat_completion(ChatCompletionRequest::new("gpt-4o").with_user("Hi"))
+            .await
+            .unwrap_err();
+        assert!(err.is_auth_error());
+        assert_eq!(client.inner().call_count(), 1);
+
+        assert!(client
+            .chat_completion(ChatCompletionRequest::new("gpt-4o"))
+            .await
+            .is_err());
+        assert_eq!(client.inner().call_count(), 1);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let client = MockLlmClient::new()
+            .with_retries(5)
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(500));
+        assert_eq!(client.backoff(1), Duration::from_millis(100));
+        assert_eq!(client.backoff(3), Duration::from_millis(400));
+        assert_eq!(client.backoff(5), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,575 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Spend caps enforced before LLM calls are sent.
+//!
+//! A [`SpendCapEnforcer`] periodically pulls the current spend of the
+//! organization or team from a [`SpendSource`], usually the analytics API
+//! ([`AnalyticsSpendSource`]), and checks it against the configured
+//! [`SpendCap`]s. Registered as an interceptor, it runs before every call:
+//!
+//! - while no cap is exceeded, calls go through unchanged,
+//! - once a cap is exceeded, the fallback policy may downgrade the call to a
+//!   cheaper model; the span records `spend_cap.action = "downgraded"` and
+//!   `spend_cap.original_model`,
+//! - without a fallback, or when the policy returns `None`, the call is
+//!   rejected with [`Error::SpendCapExceeded`] and the span records
+//!   `spend_cap.action = "blocked"`.
+//!
+//! Between pulls, the cost of each completed call is added to the last known
+//! spend, so a burst of calls cannot overshoot a cap by a whole refresh
+//! interval. Caps whose spend is unknown (no successful pull yet, or a new
+//! period has started since the last one) never block: enforcement fails
+//! open rather than taking the application down with the analytics API.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use llm_observatory_sdk::spend_cap::{AnalyticsSpendSource, SpendCap, SpendCapEnforcer};
+//! use llm_observatory_sdk::LLMObservatory;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> llm_observatory_sdk::Result<()> {
+//! let enforcer = SpendCapEnforcer::new(AnalyticsSpendSource::new(
+//!     "http://analytics:8080",
+//!     "api-token",
+//! ))
+//! .with_cap(SpendCap::organization(10_000.0))
+//! .with_cap(SpendCap::team("search", 500.0))
+//! .with_fallback(|request, _status| {
+//!     (request.model == "gpt-4o").then(|| "gpt-4o-mini".to_string())
+//! });
+//! enforcer.spawn_refresh(Duration::from_secs(60));
+//!
+//! let observatory = LLMObservatory::builder()
+//!     .with_service_name("my-app")
+//!     .build()?;
+//! observatory.add_interceptor(enforcer);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    instrument::InstrumentedSpan,
+    interceptor::LlmInterceptor,
+    traits::{ChatCompletionRequest, ChatCompletionResponse},
+    Error, Result,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Who a spend cap applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SpendScope {
+    /// The whole organization
+    Organization,
+    /// A single team
+    Team(String),
+}
+
+impl fmt::Display for SpendScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpendScope::Organization => write!(f, "organization"),
+            SpendScope::Team(team) => write!(f, "team {}", team),
+        }
+    }
+}
+
+/// Period over which spend is accumulated, in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CapPeriod {
+    /// Calendar day
+    Daily,
+    /// Calendar month
+    #[default]
+    Monthly,
+}
+
+impl CapPeriod {
+    /// Start of the period containing `now`.
+    pub fn start(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let day = match self {
+            CapPeriod::Daily => now.day(),
+            CapPeriod::Monthly => 1,
+        };
+        Utc.with_ymd_and_hms(now.year(), now.month(), day, 0, 0, 0)
+            .single()
+            .unwrap_or(now)
+    }
+}
+
+/// A spend limit for a scope and period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpendCap {
+    /// Who the cap applies to
+    pub scope: SpendScope,
+    /// Limit in USD
+    pub limit_usd: f64,
+    /// Period the limit applies to
+    pub period: CapPeriod,
+}
+
+impl SpendCap {
+    /// Monthly cap on the organization's spend.
+    pub fn organization(limit_usd: f64) -> Self {
+        Self {
+            scope: SpendScope::Organization,
+            limit_usd,
+            period: CapPeriod::default(),
+        }
+    }
+
+    /// Monthly cap on a team's spend.
+    pub fn team(team: impl Into<String>, limit_usd: f64) -> Self {
+        Self {
+            scope: SpendScope::Team(team.into()),
+            limit_usd,
+            period: CapPeriod::default(),
+        }
+    }
+
+    /// Set the period of the cap.
+    pub fn with_period(mut self, period: CapPeriod) -> Self {
+        self.period = period;
+        self
+    }
+}
+
+/// Where the current spend is pulled from.
+#[async_trait]
+pub trait SpendSource: Send + Sync {
+    /// Spend of `scope` in USD between `since` and `until`.
+    async fn current_spend(
+        &self,
+        scope: &SpendScope,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<f64>;
+}
+
+/// [`SpendSource`] backed by the analytics API cost endpoints.
+///
+/// Organization spend comes from `/api/v1/costs/summary`, team spend from
+/// `/api/v1/costs/attribution?dimension=team`; both are requested in USD.
+#[derive(Debug, Clone)]
+pub struct AnalyticsSpendSource {
+    client: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct SummaryResponse {
+    overview: SummaryOverview,
+}
+
+#[derive(Deserialize)]
+struct SummaryOverview {
+    total_cost: f64,
+}
+
+#[derive(Deserialize)]
+struct AttributionResponse {
+    items: Vec<AttributionItem>,
+}
+
+#[derive(Deserialize)]
+struct AttributionItem {
+    dimension_value: String,
+    total_cost: f64,
+}
+
+impl AnalyticsSpendSource {
+    /// Create a source for the analytics API at `base_url`, authenticating
+    /// with a bearer token.
+    pub fn new(base_url: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: token.into(),
+        }
+    }
+
+    /// Use a custom HTTP client, e.g. with a timeout.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T> {
+        let response = self
+            .client
+            .get(format!("{}{}", self.base_url, path))
+            .bearer_auth(&self.token)
+            .query(query)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(Error::api(status.as_u16(), message));
+        }
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait]
+impl SpendSource for AnalyticsSpendSource {
+    async fn current_spend(
+        &self,
+        scope: &SpendScope,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<f64> {
+        let mut query = vec![
+            ("start_time", since.to_rfc3339()),
+            ("end_time", until.to_rfc3339()),
+            ("currency", "USD".to_string()),
+        ];
+
+        match scope {
+            SpendScope::Organization => {
+                query.push(("include_trends", "false".to_string()));
+                query.push(("include_top_traces", "false".to_string()));
+                let summary: SummaryResponse = self.get("/api/v1/costs/summary", &query).await?;
+                Ok(summary.overview.total_cost)
+            }
+            SpendScope::Team(team) => {
+                query.push(("dimension", "team".to_string()));
+                query.push(("limit", "1000".to_string()));
+                let attribution: AttributionResponse =
+                    self.get("/api/v1/costs/attribution", &query).await?;
+                Ok(attribution
+                    .items
+                    .iter()
+                    .find(|item| &item.dimension_value == team)
+                    .map_or(0.0, |item| item.total_cost))
+            }
+        }
+    }
+}
+
+/// Current state of a cap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapStatus {
+    /// Who the cap applies to
+    pub scope: SpendScope,
+    /// Limit in USD
+    pub limit_usd: f64,
+    /// Spend in the current period in USD; `None` until it is known
+    pub spend_usd: Option<f64>,
+}
+
+impl CapStatus {
+    /// Whether the known spend has reached the limit.
+    pub fn exceeded(&self) -> bool {
+        self.spend_usd.is_some_and(|spend| spend >= self.limit_usd)
+    }
+}
+
+/// Policy choosing a fallback model for a call over a cap; `None` blocks it.
+pub type FallbackPolicy =
+    dyn Fn(&ChatCompletionRequest, &CapStatus) -> Option<String> + Send + Sync;
+
+struct CapState {
+    cap: SpendCap,
+    /// Spend since `period_start`, if known
+    spend_usd: Option<f64>,
+    period_start: DateTime<Utc>,
+}
+
+impl CapState {
+    fn status(&self, now: DateTime<Utc>) -> CapStatus {
+        let current = self.cap.period.start(now) == self.period_start;
+        CapStatus {
+            scope: self.cap.scope.clone(),
+            limit_usd: self.cap.limit_usd,
+            spend_usd: self.spend_usd.filter(|_| current),
+        }
+    }
+}
+
+/// Interceptor enforcing spend caps on every call.
+///
+/// Cloning is cheap; clones share caps and spend, so one clone can be
+/// refreshed while another is registered on the observatory.
+#[derive(Clone)]
+pub struct SpendCapEnforcer {
+    source: Arc<dyn SpendSource>,
+    caps: Arc<Mutex<Vec<CapState>>>,
+    fallback: Option<Arc<FallbackPolicy>>,
+}
+
+impl SpendCapEnforcer {
+    /// Create an enforcer pulling spend from `source`, with no caps.
+    pub fn new(source: impl SpendSource + 'static) -> Self {
+        Self {
+            source: Arc::new(source),
+            caps: Arc::new(Mutex::new(Vec::new())),
+            fallback: None,
+        }
+    }
+
+    /// Add a cap.
+    pub fn with_cap(self, cap: SpendCap) -> Self {
+        let period_start = cap.period.start(Utc::now());
+        self.lock().push(CapState {
+            cap,
+            spend_usd: None,
+            period_start,
+        });
+        self
+    }
+
+    /// Downgrade calls over a cap to the model returned by `policy` instead
+    /// of blocking them. Returning `None` or the requested model blocks the
+    /// call.
+    pub fn with_fallback<F>(mut self, policy: F) -> Self
+    where
+        F: Fn(&ChatCompletionRequest, &CapStatus) -> Option<String> + Send + Sync + 'static,
+    {
+        self.fallback = Some(Arc::new(policy));
+        self
+    }
+
+    /// Pull the current spend of every cap.
+    ///
+    /// Caps whose pull fails keep their last known spend; the last error is
+    /// returned after all caps were tried.
+    pub async fn refresh(&self) -> Result<()> {
+        let now = Utc::now();
+        let caps: Vec<SpendCap> = self.lock().iter().map(|state| state.cap.clone()).collect();
+
+        let mut result = Ok(());
+        for cap in caps {
+            let since = cap.period.start(now);
+            match self.source.current_spend(&cap.scope, since, now).await {
+                Ok(spend) => {
+                    if let Some(state) = self.lock().iter_mut().find(|s| s.cap == cap) {
+                        state.spend_usd = Some(spend);
+                        state.period_start = since;
+                    }
+                }
+                Err(e) => result = Err(e),
+            }
+        }
+        result
+    }
+
+    /// Refresh now and then every `interval` in a background task.
+    ///
+    /// Failed refreshes are logged and retried at the next tick.
+    pub fn spawn_refresh(&self, interval: Duration) -> JoinHandle<()> {
+        let enforcer = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = enforcer.refresh().await {
+                    tracing::warn!(error = %e, "Failed to refresh spend caps");
+                }
+            }
+        })
+    }
+
+    /// Current state of every cap.
+    pub fn status(&self) -> Vec<CapStatus> {
+        let now = Utc::now();
+        self.lock().iter().map(|state| state.status(now)).collect()
+    }
+
+    /// The first cap over its limit, if any.
+    pub fn exceeded(&self) -> Option<CapStatus> {
+        self.status().into_iter().find(CapStatus::exceeded)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<CapState>> {
+        self.caps.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl fmt::Debug for SpendCapEnforcer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpendCapEnforcer")
+            .field("caps", &self.status())
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl LlmInterceptor for SpendCapEnforcer {
+    async fn before_request(
+        &self,
+        request: &mut ChatCompletionRequest,
+        span: &mut InstrumentedSpan,
+    ) -> Result<()> {
+        let Some(status) = self.exceeded() else {
+            return Ok(());
+        };
+        span.set_attribute("spend_cap.scope", status.scope.to_string());
+
+        let fallback = self
+            .fallback
+            .as_ref()
+            .and_then(|policy| policy(request, &status))
+            .filter(|model| *model != request.model);
+        if let Some(model) = fallback {
+            span.set_attribute("spend_cap.action", "downgraded");
+            span.set_attribute("spend_cap.original_model", request.model.clone());
+            request.model = model;
+            return Ok(());
+        }
+
+        span.set_attribute("spend_cap.action", "blocked");
+        Err(Error::spend_cap_exceeded(
+            status.scope.to_string(),
+            status.spend_usd.unwrap_or_default(),
+            status.limit_usd,
+        ))
+    }
+
+    async fn after_response(
+        &self,
+        _request: &ChatCompletionRequest,
+        response: &mut ChatCompletionResponse,
+        _span: &mut InstrumentedSpan,
+    ) -> Result<()> {
+        let period_now = Utc::now();
+        for state in self.lock().iter_mut() {
+            if state.cap.period.start(period_now) == state.period_start {
+                if let Some(spend) = state.spend_usd.as_mut() {
+                    *spend += response.cost_usd;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockLlmClient, MockResponse};
+    use crate::{InstrumentedLLM, LLMObservatory};
+    use std::collections::HashMap;
+
+    /// Source with fixed spend per scope.
+    struct FixedSpend(HashMap<SpendScope, f64>);
+
+    #[async_trait]
+    impl SpendSource for FixedSpend {
+        async fn current_spend(
+            &self,
+            scope: &SpendScope,
+            _since: DateTime<Utc>,
+            _until: DateTime<Utc>,
+        ) -> Result<f64> {
+            self.0
+                .get(scope)
+                .copied()
+                .ok_or_else(|| Error::api(503, "unavailable"))
+        }
+    }
+
+    fn client(enforcer: SpendCapEnforcer) -> MockLlmClient {
+        let observatory = LLMObservatory::builder()
+            .with_service_name("spend-cap-test")
+            .build()
+            .unwrap();
+        observatory.add_interceptor(enforcer);
+        MockLlmClient::new()
+            .with_default_response(MockResponse::text("OK"))
+            .with_observatory(observatory)
+    }
+
+    #[test]
+    fn test_period_start() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 14, 15, 9, 26).unwrap();
+        assert_eq!(
+            CapPeriod::Daily.start(now),
+            Utc.with_ymd_and_hms(2025, 3, 14, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            CapPeriod::Monthly.start(now),
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_blocks_over_cap_and_fails_open() {
+        let enforcer = SpendCapEnforcer::new(FixedSpend(HashMap::from([(
+            SpendScope::Team("search".to_string()),
+            120.0,
+        )])))
+        .with_cap(SpendCap::team("search", 100.0))
+        .with_cap(SpendCap::organization(1_000.0));
+        let client = client(enforcer.clone());
+        let request = ChatCompletionRequest::new("gpt-4o").with_user("Hi");
+
+        // Spend unknown: calls go through
+        client.chat_completion(request.clone()).await.unwrap();
+
+        // Organization pull fails, team pull is over its cap
+        assert!(enforcer.refresh().await.is_err());
+        let status = enforcer.status();
+        assert_eq!(status[0].spend_usd, Some(120.0));
+        assert_eq!(status[1].spend_usd, None);
+
+        let err = client.chat_completion(request).await.unwrap_err();
+        assert!(err.is_spend_cap_exceeded());
+        assert!(matches!(
+            err,
+            Error::SpendCapExceeded { spend_usd, limit_usd, .. }
+                if spend_usd == 120.0 && limit_usd == 100.0
+        ));
+        assert_eq!(client.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_downgrades_to_fallback_model() {
+        let enforcer = SpendCapEnforcer::new(FixedSpend(HashMap::from([(
+            SpendScope::Organization,
+            99.9999,
+        )])))
+        .with_cap(SpendCap::organization(100.0))
+        .with_fallback(|request, _| (request.model == "gpt-4").then(|| "gpt-4o-mini".into()));
+        enforcer.refresh().await.unwrap();
+        let client = client(enforcer.clone());
+
+        // Under the cap; the call's cost pushes spend over it
+        let first = client
+            .chat_completion(ChatCompletionRequest::new("gpt-4").with_user("Hello there"))
+            .await
+            .unwrap();
+        assert!(first.cost_usd > 0.0);
+        assert!(enforcer.exceeded().is_some());
+
+        let second = client
+            .chat_completion(ChatCompletionRequest::new("gpt-4").with_user("Hello again"))
+            .await
+            .unwrap();
+        assert_eq!(second.model, "gpt-4o-mini");
+
+        // No cheaper model for this one
+        let err = client
+            .chat_completion(ChatCompletionRequest::new("gpt-4o-mini").with_user("Hi"))
+            .await
+            .unwrap_err();
+        assert!(err.is_spend_cap_exceeded());
+
+        let models: Vec<_> = client.requests().into_iter().map(|r| r.model).collect();
+        assert_eq!(models, ["gpt-4", "gpt-4o-mini"]);
+    }
+}
@@ -0,0 +1,758 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mock LLM client for testing instrumented code without API keys.
+//!
+//! [`MockLlmClient`] implements [`InstrumentedLLM`] with scripted responses,
+//! so code written against the trait can be tested offline. Calls go through
+//! the same instrumentation as the real clients: spans are created on the
+//! attached observatory, interceptors run, and cost is calculated from the
+//! model's pricing (zero for unknown models).
+//!
+//! Token counts are deterministic: every text counts as one token per four
+//! characters, rounded up (see [`count_tokens`]).
+//!
+//! # Example
+//!
+//! ```rust
+//! use llm_observatory_sdk::testing::{MockFailure, MockLlmClient, MockResponse};
+//! use llm_observatory_sdk::{ChatCompletionRequest, InstrumentedLLM};
+//!
+//! # async fn example() -> llm_observatory_sdk::Result<()> {
+//! let client = MockLlmClient::new()
+//!     .with_response(MockResponse::text("Hello!"))
+//!     .with_response(MockResponse::failure(MockFailure::RateLimit));
+//!
+//! let request = ChatCompletionRequest::new("gpt-4o").with_user("Hi");
+//! let response = client.chat_completion(request.clone()).await?;
+//! assert_eq!(response.content, "Hello!");
+//! assert_eq!(response.usage.completion_tokens, 2);
+//!
+//! assert!(client.chat_completion(request).await.is_err());
+//! assert_eq!(client.call_count(), 2);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    cost::{calculate_cost_with_fallback, calculate_embedding_cost},
+    instrument::{create_span, InstrumentedSpan},
+    interceptor::InterceptorChain,
+    observatory::LLMObservatory,
+    traits::{
+        ChatCompletionRequest, ChatCompletionResponse, EmbeddingRequest, EmbeddingResponse,
+        InstrumentedLLM, StreamChunk,
+    },
+    Error, Result,
+};
+use async_trait::async_trait;
+use futures::Stream;
+use llm_observatory_core::{
+    span::{LlmInput, LlmOutput, LlmSpan},
+    types::{Cost, Provider, TokenUsage},
+};
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Count the tokens of `text` the way the mock client does: one token per
+/// four characters, rounded up.
+pub fn count_tokens(text: &str) -> u32 {
+    (text.chars().count() as u32).div_ceil(4)
+}
+
+/// An error the mock client returns instead of a response.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockFailure {
+    /// Provider API error with the given status code
+    Api {
+        /// HTTP status code
+        status: u16,
+        /// Error message
+        message: String,
+    },
+    /// Rate limit exceeded
+    RateLimit,
+    /// Request timeout
+    Timeout,
+    /// Authentication failure
+    Auth,
+}
+
+impl MockFailure {
+    /// Create an API failure.
+    pub fn api(status: u16, message: impl Into<String>) -> Self {
+        Self::Api {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn to_error(&self) -> Error {
+        match self {
+            Self::Api { status, message } => Error::api(*status, message.clone()),
+            Self::RateLimit => Error::rate_limit("mock rate limit"),
+            Self::Timeout => Error::Timeout,
+            Self::Auth => Error::auth("mock authentication failure"),
+        }
+    }
+}
+
+impl Default for MockFailure {
+    fn default() -> Self {
+        Self::api(500, "injected failure")
+    }
+}
+
+/// A scripted reply of the mock client.
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    outcome: std::result::Result<String, MockFailure>,
+    finish_reason: String,
+    usage: Option<TokenUsage>,
+    latency: Option<Duration>,
+}
+
+impl MockResponse {
+    /// Reply with `content`.
+    pub fn text(content: impl Into<String>) -> Self {
+        Self {
+            outcome: Ok(content.into()),
+            finish_reason: "stop".to_string(),
+            usage: None,
+            latency: None,
+        }
+    }
+
+    /// Fail with `failure`.
+    pub fn failure(failure: MockFailure) -> Self {
+        Self {
+            outcome: Err(failure),
+            ..Self::text("")
+        }
+    }
+
+    /// Set the finish reason (default: `stop`).
+    pub fn with_finish_reason(mut self, reason: impl Into<String>) -> Self {
+        self.finish_reason = reason.into();
+        self
+    }
+
+    /// Report these token counts instead of counting them.
+    pub fn with_usage(mut self, prompt_tokens: u32, completion_tokens: u32) -> Self {
+        self.usage = Some(TokenUsage::new(prompt_tokens, completion_tokens));
+        self
+    }
+
+    /// Delay this reply by `latency` instead of the client's latency.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+}
+
+impl Default for MockResponse {
+    fn default() -> Self {
+        Self::text("This is a mock response.")
+    }
+}
+
+/// Mock LLM client with scripted responses.
+///
+/// Scripted responses are returned in order; once they are used up, every
+/// call gets the default response. Error injection fails a deterministic
+/// share of calls, reproducible with [`with_seed`](Self::with_seed).
+pub struct MockLlmClient {
+    provider: Provider,
+    default_model: String,
+    responses: Mutex<VecDeque<MockResponse>>,
+    default_response: MockResponse,
+    latency: Duration,
+    error_rate: f64,
+    injected_failure: MockFailure,
+    seed: u64,
+    embedding_dimensions: usize,
+    calls: AtomicU64,
+    requests: Mutex<Vec<ChatCompletionRequest>>,
+    spans: Mutex<Vec<LlmSpan>>,
+    observatory: Option<LLMObservatory>,
+}
+
+impl MockLlmClient {
+    /// Create a mock client for the `mock` provider.
+    pub fn new() -> Self {
+        Self {
+            provider: Provider::Custom("mock".to_string()),
+            default_model: "mock-model".to_string(),
+            responses: Mutex::new(VecDeque::new()),
+            default_response: MockResponse::default(),
+            latency: Duration::ZERO,
+            error_rate: 0.0,
+            injected_failure: MockFailure::default(),
+            seed: 0,
+            embedding_dimensions: 8,
+            calls: AtomicU64::new(0),
+            requests: Mutex::new(Vec::new()),
+            spans: Mutex::new(Vec::new()),
+            observatory: None,
+        }
+    }
+
+    /// Report spans as this provider (default: `mock`).
+    pub fn with_provider(mut self, provider: Provider) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    /// Set the default model.
+    pub fn with_default_model(mut self, model: impl Into<String>) -> Self {
+        self.default_model = model.into();
+        self
+    }
+
+    /// Queue a scripted response.
+    pub fn with_response(self, response: MockResponse) -> Self {
+        self.push_response(response);
+        self
+    }
+
+    /// Set the response returned once the script is used up.
+    pub fn with_default_response(mut self, response: MockResponse) -> Self {
+        self.default_response = response;
+        self
+    }
+
+    /// Delay every call by `latency`.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Fail a share of calls (0.0 to 1.0) with `failure`.
+    pub fn with_error_rate(mut self, rate: f64, failure: MockFailure) -> Self {
+        self.error_rate = rate.clamp(0.0, 1.0);
+        self.injected_failure = failure;
+        self
+    }
+
+    /// Seed the choice of calls that fail by error injection.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Set the dimensions of embeddings when the request sets none (default: 8).
+    pub fn with_embedding_dimensions(mut self, dimensions: usize) -> Self {
+        self.embedding_dimensions = dimensions;
+        self
+    }
+
+    /// Attach an observatory for automatic instrumentation.
+    pub fn with_observatory(mut self, observatory: LLMObservatory) -> Self {
+        self.observatory = Some(observatory);
+        self
+    }
+
+    /// Queue a scripted response on a shared client.
+    pub fn push_response(&self, response: MockResponse) {
+        self.responses.lock().unwrap().push_back(response);
+    }
+
+    /// Number of calls made, including failed ones.
+    pub fn call_count(&self) -> u64 {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// Chat completion requests received, after interceptors ran.
+    pub fn requests(&self) -> Vec<ChatCompletionRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// LLM spans finished by instrumented calls.
+    pub fn spans(&self) -> Vec<LlmSpan> {
+        self.spans.lock().unwrap().clone()
+    }
+
+    /// Count a call and decide, deterministically, whether to inject a failure.
+    fn next_call(&self) -> Option<MockFailure> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+        if self.error_rate <= 0.0 {
+            return None;
+        }
+        let sample = (splitmix64(self.seed ^ call) >> 11) as f64 / (1u64 << 53) as f64;
+        (sample < self.error_rate).then(|| self.injected_failure.clone())
+    }
+
+    fn next_response(&self) -> MockResponse {
+        self.responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| self.default_response.clone())
+    }
+
+    fn start_span(
+        &self,
+        request: &ChatCompletionRequest,
+    ) -> Option<(InstrumentedSpan, InterceptorChain)> {
+        self.observatory.as_ref().map(|observatory| {
+            let mut builder = create_span(observatory, self.provider.clone(), &request.model)
+                .messages(request.messages.clone());
+            if let Some(link) = &request.retrieval {
+                builder = builder.retrieval(link.clone());
+            }
+            if let Some(parent) = &request.parent_context {
+                builder = builder.parent(parent.clone());
+            }
+            if let Some(attempt) = &request.attempt {
+                builder = builder.attempt(attempt.clone());
+            }
+            (builder.start(), observatory.interceptors())
+        })
+    }
+
+    fn finish_span(
+        &self,
+        span: InstrumentedSpan,
+        result: std::result::Result<(LlmOutput, TokenUsage, Cost), &Error>,
+    ) -> Result<LlmSpan> {
+        let llm_span = match result {
+            Ok((output, usage, cost)) => span.finish_success(output, usage, cost)?,
+            Err(e) => span.finish_error(&e.to_string())?,
+        };
+        self.spans.lock().unwrap().push(llm_span.clone());
+        Ok(llm_span)
+    }
+
+    /// Produce the scripted reply, running interceptor hooks against the
+    /// span when one is active.
+    async fn execute(
+        &self,
+        request: &mut ChatCompletionRequest,
+        mut span: Option<&mut InstrumentedSpan>,
+        interceptors: &InterceptorChain,
+    ) -> Result<(ChatCompletionResponse, Cost)> {
+        if let Some(span) = span.as_deref_mut() {
+            interceptors.before_request(request, span).await?;
+        }
+        self.requests.lock().unwrap().push(request.clone());
+
+        let injected = self.next_call();
+        let scripted = self.next_response();
+        tokio::time::sleep(scripted.latency.unwrap_or(self.latency)).await;
+        if let Some(failure) = injected {
+            return Err(failure.to_error());
+        }
+        let content = scripted.outcome.map_err(|failure| failure.to_error())?;
+
+        let usage = scripted.usage.unwrap_or_else(|| {
+            let prompt_tokens = request
+                .messages
+                .iter()
+                .map(|m| count_tokens(&m.content))
+                .sum();
+            TokenUsage::new(prompt_tokens, count_tokens(&content))
+        });
+        let cost = calculate_cost_with_fallback(&request.model, &usage, 0.0, 0.0);
+
+        let (trace_id, span_id) = span
+            .as_deref()
+            .map(|s| (s.trace_id().to_string(), s.span_id().to_string()))
+            .unwrap_or_default();
+
+        let mut response = ChatCompletionResponse {
+            id: format!("mock-{}", self.call_count()),
+            content,
+            model: request.model.clone(),
+            finish_reason: Some(scripted.finish_reason),
+            usage,
+            cost_usd: cost.amount_usd,
+            latency_ms: 0,
+            trace_id,
+            span_id,
+            metadata: request.metadata.clone().unwrap_or_default(),
+        };
+
+        if let Some(span) = span {
+            interceptors
+                .after_response(request, &mut response, span)
+                .await?;
+        }
+
+        Ok((response, cost))
+    }
+
+    /// Run a chat completion, returning the response, its cost and the span
+    /// to finish.
+    async fn complete(
+        &self,
+        mut request: ChatCompletionRequest,
+    ) -> Result<(ChatCompletionResponse, Cost, Option<InstrumentedSpan>)> {
+        request.validate()?;
+
+        let (mut span, interceptors) = match self.start_span(&request) {
+            Some((span, interceptors)) => (Some(span), interceptors),
+            None => (None, InterceptorChain::default()),
+        };
+
+        let result = self
+            .execute(&mut request, span.as_mut(), &interceptors)
+            .await;
+
+        match (result, span) {
+            (Ok((response, cost)), span) => Ok((response, cost, span)),
+            (Err(e), Some(mut span)) => {
+                interceptors.on_error(&request, &e, &mut span).await;
+                let _ = self.finish_span(span, Err(&e));
+                Err(e)
+            }
+            (Err(e), None) => Err(e),
+        }
+    }
+
+    fn output(response: &ChatCompletionResponse) -> LlmOutput {
+        LlmOutput {
+            content: response.content.clone(),
+            finish_reason: response.finish_reason.clone(),
+            metadata: Default::default(),
+        }
+    }
+}
+
+impl Default for MockLlmClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl InstrumentedLLM for MockLlmClient {
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionResponse> {
+        let (mut response, cost, span) = self.complete(request).await?;
+
+        if let Some(span) = span {
+            let output = Self::output(&response);
+            let llm_span = self.finish_span(span, Ok((output, response.usage.clone(), cost)))?;
+            response.latency_ms = llm_span.latency.total_ms;
+        }
+
+        Ok(response)
+    }
+
+    async fn streaming_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>> {
+        let (response, cost, span) = self.complete(request).await?;
+
+        // Split after each whitespace run, so the deltas concatenate back
+        // into the content
+        let mut deltas = Vec::new();
+        let mut delta = String::new();
+        let mut chars = response.content.chars().peekable();
+        while let Some(c) = chars.next() {
+            delta.push(c);
+            if c.is_whitespace() && !chars.peek().is_some_and(|next| next.is_whitespace()) {
+                deltas.push(std::mem::take(&mut delta));
+            }
+        }
+        if !delta.is_empty() || deltas.is_empty() {
+            deltas.push(delta);
+        }
+
+        let last = deltas.len() - 1;
+        let mut streamed = 0;
+        let chunks: Vec<Result<StreamChunk>> = deltas
+            .into_iter()
+            .enumerate()
+            .map(|(index, delta)| {
+                streamed += count_tokens(&delta);
+                Ok(StreamChunk {
+                    id: response.id.clone(),
+                    model: response.model.clone(),
+                    finish_reason: if index == last {
+                        response.finish_reason.clone()
+                    } else {
+                        None
+                    },
+                    partial_tokens: Some(streamed),
+                    index,
+                    delta,
+                })
+            })
+            .collect();
+
+        if let Some(mut span) = span {
+            span.record_first_token();
+            span.set_attribute("gen_ai.response.chunk_count", chunks.len() as i64);
+            self.finish_span(
+                span,
+                Ok((Self::output(&response), response.usage.clone(), cost)),
+            )?;
+        }
+
+        Ok(Box::pin(futures::stream::iter(chunks)))
+    }
+
+    async fn embeddings(&self, request: EmbeddingRequest) -> Result<EmbeddingResponse> {
+        request.validate()?;
+
+        let span = self.observatory.as_ref().map(|observatory| {
+            let mut builder = create_span(observatory, self.provider.clone(), &request.model)
+                .operation_name("llm.embeddings")
+                .input(LlmInput::Text {
+                    prompt: request.input.join("\n"),
+                })
+                .attribute("gen_ai.operation.name", "embeddings");
+            if let Some(parent) = &request.parent_context {
+                builder = builder.parent(parent.clone());
+            }
+            if let Some(attempt) = &request.attempt {
+                builder = builder.attempt(attempt.clone());
+            }
+            let span = builder.start();
+            span.set_attribute("gen_ai.request.embedding.count", request.input.len() as i64);
+            span
+        });
+
+        let injected = self.next_call();
+        tokio::time::sleep(self.latency).await;
+        if let Some(failure) = injected {
+            let e = failure.to_error();
+            if let Some(span) = span {
+                let _ = self.finish_span(span, Err(&e));
+            }
+            return Err(e);
+        }
+
+        let dimensions = request
+            .dimensions
+            .map_or(self.embedding_dimensions, |d| d as usize);
+        let embeddings: Vec<Vec<f32>> = request
+            .input
+            .iter()
+            .map(|input| embed(input, dimensions))
+            .collect();
+        let usage = TokenUsage::new(
+            request
+                .input
+                .iter()
+                .map(String::as_str)
+                .map(count_tokens)
+                .sum(),
+            0,
+        );
+        let cost = calculate_embedding_cost(&request.model, usage.prompt_tokens)
+            .unwrap_or_else(|_| Cost::new(0.0));
+
+        let (trace_id, span_id, latency_ms) = match span {
+            Some(span) => {
+                span.set_attribute("gen_ai.embeddings.dimension.count", dimensions as i64);
+                let output = LlmOutput {
+                    content: String::new(),
+                    finish_reason: None,
+                    metadata: [
+                        ("embedding_count".to_string(), embeddings.len().into()),
+                        ("dimensions".to_string(), dimensions.into()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                };
+                let llm_span = self.finish_span(span, Ok((output, usage.clone(), cost.clone())))?;
+                (
+                    llm_span.trace_id,
+                    llm_span.span_id,
+                    llm_span.latency.total_ms,
+                )
+            }
+            None => (String::new(), String::new(), 0),
+        };
+
+        Ok(EmbeddingResponse {
+            embeddings,
+            model: request.model,
+            dimensions,
+            usage,
+            cost_usd: cost.amount_usd,
+            latency_ms,
+            trace_id,
+            span_id,
+            metadata: request.metadata.unwrap_or_default(),
+        })
+    }
+
+    fn provider_name(&self) -> &str {
+        self.provider.as_str()
+    }
+
+    fn default_model(&self) -> Option<&str> {
+        Some(&self.default_model)
+    }
+}
+
+/// Deterministic unit-length embedding of `input`.
+fn embed(input: &str, dimensions: usize) -> Vec<f32> {
+    let mut vector: Vec<f32> = (0..dimensions)
+        .map(|i| {
+            let digest = Sha256::new()
+                .chain_update(input.as_bytes())
+                .chain_update((i as u64).to_le_bytes())
+                .finalize();
+            let bits = u32::from_le_bytes([digest[0], digest[1], digest[2], digest[3]]);
+            (bits as f64 / u32::MAX as f64 * 2.0 - 1.0) as f32
+        })
+        .collect();
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+    vector
+}
+
+/// SplitMix64 step, used to spread call numbers over `[0, 2^64)`.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[test]
+    fn test_count_tokens() {
+        assert_eq!(count_tokens(""), 0);
+        assert_eq!(count_tokens("Hi"), 1);
+        assert_eq!(count_tokens("Hello!"), 2);
+        assert_eq!(count_tokens("12345678"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_scripted_responses_and_spans() {
+        let observatory = LLMObservatory::builder()
+            .with_service_name("mock-test")
+            .build()
+            .unwrap();
+        let client = MockLlmClient::new()
+            .with_response(MockResponse::text("First").with_usage(100, 10))
+            .with_response(MockResponse::failure(MockFailure::api(503, "overloaded")))
+            .with_default_response(MockResponse::text("Fallback"))
+            .with_observatory(observatory);
+
+        let request = ChatCompletionRequest::new("gpt-4").with_user("Hello there");
+
+        let first = client.chat_completion(request.clone()).await.unwrap();
+        assert_eq!(first.content, "First");
+        assert_eq!(first.usage.prompt_tokens, 100);
+        assert!(first.cost_usd > 0.0);
+        assert!(!first.trace_id.is_empty());
+
+        let err = client.chat_completion(request.clone()).await.unwrap_err();
+        assert!(matches!(err, Error::Api { status: 503, .. }));
+
+        let fallback = client.chat_completion(request).await.unwrap();
+        assert_eq!(fallback.content, "Fallback");
+        assert_eq!(fallback.usage.prompt_tokens, 3);
+        assert_eq!(fallback.usage.completion_tokens, 2);
+
+        assert_eq!(client.call_count(), 3);
+        assert_eq!(client.requests().len(), 3);
+        let spans = client.spans();
+        assert_eq!(spans.len(), 3);
+        assert_eq!(spans[0].span_id, first.span_id);
+        assert_eq!(spans[0].provider.as_str(), "mock");
+        assert_eq!(
+            spans[1].status,
+            llm_observatory_core::span::SpanStatus::Error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_injection_is_deterministic() {
+        let run = |seed| async move {
+            let client = MockLlmClient::new()
+                .with_error_rate(0.5, MockFailure::Timeout)
+                .with_seed(seed);
+            let mut outcomes = Vec::new();
+            for _ in 0..32 {
+                let request = ChatCompletionRequest::new("mock-model").with_user("Hi");
+                outcomes.push(client.chat_completion(request).await.is_ok());
+            }
+            outcomes
+        };
+
+        let outcomes = run(7).await;
+        assert_eq!(outcomes, run(7).await);
+        let failures = outcomes.iter().filter(|ok| !**ok).count();
+        assert!(failures > 0 && failures < 32);
+
+        let never = MockLlmClient::new().with_error_rate(0.0, MockFailure::Timeout);
+        let always = MockLlmClient::new().with_error_rate(1.0, MockFailure::Timeout);
+        let request = ChatCompletionRequest::new("mock-model").with_user("Hi");
+        assert!(never.chat_completion(request.clone()).await.is_ok());
+        assert!(matches!(
+            always.chat_completion(request).await,
+            Err(Error::Timeout)
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_latency() {
+        let client = MockLlmClient::new()
+            .with_latency(Duration::from_millis(200))
+            .with_response(MockResponse::text("slow").with_latency(Duration::from_secs(2)));
+        let request = ChatCompletionRequest::new("mock-model").with_user("Hi");
+
+        let start = tokio::time::Instant::now();
+        client.chat_completion(request.clone()).await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+
+        let start = tokio::time::Instant::now();
+        client.chat_completion(request).await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_streaming_completion() {
+        let client = MockLlmClient::new()
+            .with_response(MockResponse::text("The quick  brown fox").with_finish_reason("length"));
+        let request = ChatCompletionRequest::new("mock-model").with_user("Hi");
+
+        let chunks: Vec<StreamChunk> = client
+            .streaming_completion(request)
+            .await
+            .unwrap()
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+
+        let deltas: Vec<&str> = chunks.iter().map(|c| c.delta.as_str()).collect();
+        assert_eq!(deltas, vec!["The ", "quick  ", "brown ", "fox"]);
+        assert!(chunks.last().unwrap().is_final());
+        assert_eq!(chunks[3].finish_reason.as_deref(), Some("length"));
+        assert!(chunks[..3].iter().all(|c| !c.is_final()));
+    }
+
+    #[tokio::test]
+    async fn test_embeddings_are_deterministic() {
+        let client = MockLlmClient::new().with_embedding_dimensions(16);
+        let request = EmbeddingRequest::new("text-embedding-3-small").with_inputs(["a", "b", "a"]);
+
+        let response = client.embeddings(request).await.unwrap();
+        assert_eq!(response.dimensions, 16);
+        assert_eq!(response.embeddings[0], response.embeddings[2]);
+        assert_ne!(response.embeddings[0], response.embeddings[1]);
+        let norm: f32 = response.embeddings[0].iter().map(|x| x * x).sum();
+        assert!((norm - 1.0).abs() < 1e-5);
+        assert_eq!(response.usage.prompt_tokens, 3);
+    }
+}
@@ -0,0 +1,232 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tool/function-call spans for agent workflows.
+//!
+//! Tool calls are recorded as child spans of the LLM span that requested them,
+//! following the OpenTelemetry GenAI `execute_tool` conventions.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use llm_observatory_sdk::{InstrumentedSpan, Result};
+//!
+//! async fn run_tools(span: &InstrumentedSpan) -> Result<String> {
+//!     span.tool_call("get_weather")
+//!         .call_id("call_abc123")
+//!         .arguments(r#"{"city":"Paris"}"#)
+//!         .run(async { Ok::<_, std::io::Error>("18°C, cloudy".to_string()) })
+//!         .await
+//!         .map_err(|e| llm_observatory_sdk::Error::internal(e.to_string()))
+//! }
+//! ```
+
+use crate::observatory::LLMObservatory;
+use opentelemetry::{
+    trace::{FutureExt, SpanKind, Status, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Instant;
+
+/// Builder for a tool-call span.
+///
+/// Created with [`SpanBuilder::tool_call`](crate::SpanBuilder::tool_call) or
+/// [`InstrumentedSpan::tool_call`](crate::InstrumentedSpan::tool_call).
+pub struct ToolCallBuilder {
+    observatory: LLMObservatory,
+    parent: Context,
+    name: String,
+    call_id: Option<String>,
+    tool_type: String,
+    arguments_size: Option<usize>,
+    attributes: Vec<KeyValue>,
+}
+
+impl ToolCallBuilder {
+    pub(crate) fn new(observatory: LLMObservatory, parent: Context, name: impl Into<String>) -> Self {
+        Self {
+            observatory,
+            parent,
+            name: name.into(),
+            call_id: None,
+            tool_type: "function".to_string(),
+            arguments_size: None,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Set the tool call ID assigned by the model.
+    pub fn call_id(mut self, id: impl Into<String>) -> Self {
+        self.call_id = Some(id.into());
+        self
+    }
+
+    /// Set the tool type (default: "function").
+    pub fn tool_type(mut self, tool_type: impl Into<String>) -> Self {
+        self.tool_type = tool_type.into();
+        self
+    }
+
+    /// Record the size of the serialized arguments.
+    ///
+    /// Only the size is recorded; arguments may contain user data.
+    pub fn arguments(mut self, arguments: &str) -> Self {
+        self.arguments_size = Some(arguments.len());
+        self
+    }
+
+    /// Add a custom attribute.
+    pub fn attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.push(KeyValue::new(key.into(), value.into()));
+        self
+    }
+
+    /// Start the tool-call span.
+    pub fn start(self) -> ToolCallSpan {
+        let tracer = self.observatory.tracer();
+
+        let mut attributes = vec![
+            KeyValue::new("gen_ai.operation.name", "execute_tool"),
+            KeyValue::new("gen_ai.tool.name", self.name.clone()),
+            KeyValue::new("gen_ai.tool.type", self.tool_type),
+        ];
+        if let Some(call_id) = self.call_id {
+            attributes.push(KeyValue::new("gen_ai.tool.call.id", call_id));
+        }
+        if let Some(size) = self.arguments_size {
+            attributes.push(KeyValue::new("gen_ai.tool.call.arguments.size", size as i64));
+        }
+        attributes.extend(self.attributes);
+
+        let span = tracer
+            .span_builder(format!("execute_tool {}", self.name))
+            .with_kind(SpanKind::Internal)
+            .with_attributes(attributes)
+            .start_with_context(tracer, &self.parent);
+
+        ToolCallSpan {
+            context: self.parent.with_span(span),
+            start_time: Instant::now(),
+        }
+    }
+
+    /// Run `future` inside a tool-call span, recording its outcome.
+    ///
+    /// The span is active while the future runs, so LLM calls made by the
+    /// tool are nested under it.
+    pub async fn run<F, T, E>(self, future: F) -> std::result::Result<T, E>
+    where
+        F: Future<Output = std::result::Result<T, E>>,
+        E: Display,
+    {
+        let span = self.start();
+        let result = future.with_context(span.context.clone()).await;
+        match &result {
+            Ok(_) => span.finish_success(),
+            Err(e) => span.finish_error(&e.to_string()),
+        }
+        result
+    }
+}
+
+/// An in-progress tool-call span.
+pub struct ToolCallSpan {
+    context: Context,
+    start_time: Instant,
+}
+
+impl ToolCallSpan {
+    /// Get the span ID.
+    pub fn span_id(&self) -> String {
+        format!("{:x}", self.context.span().span_context().span_id())
+    }
+
+    /// Get the trace ID.
+    pub fn trace_id(&self) -> String {
+        format!("{:x}", self.context.span().span_context().trace_id())
+    }
+
+    /// Get the context with this span active, for nesting further spans.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Record the size of the tool's result.
+    pub fn record_result(&self, result: &str) {
+        self.context
+            .span()
+            .set_attribute(KeyValue::new("gen_ai.tool.call.result.size", result.len() as i64));
+    }
+
+    /// Finish the span as successful.
+    pub fn finish_success(self) {
+        self.finish("success", Status::Ok);
+    }
+
+    /// Finish the span with an error.
+    pub fn finish_error(self, error: &str) {
+        self.context
+            .span()
+            .add_event("tool.error", vec![KeyValue::new("error", error.to_string())]);
+        self.finish("error", Status::error(error.to_string()));
+    }
+
+    fn finish(self, status: &'static str, otel_status: Status) {
+        let span = self.context.span();
+        span.set_attribute(KeyValue::new("gen_ai.tool.call.status", status));
+        span.set_attribute(KeyValue::new(
+            "gen_ai.tool.call.duration_ms",
+            self.start_time.elapsed().as_millis() as i64,
+        ));
+        span.set_status(otel_status);
+        span.end();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{instrument::create_span, InstrumentedSpan, LLMObservatory, Provider, SpanBuilder};
+
+    fn observatory() -> LLMObservatory {
+        LLMObservatory::builder()
+            .with_service_name("tool-test")
+            .build()
+            .unwrap()
+    }
+
+    fn llm_span(observatory: &LLMObservatory) -> InstrumentedSpan {
+        create_span(observatory, Provider::OpenAI, "gpt-4o").start()
+    }
+
+    #[tokio::test]
+    async fn test_tool_call_is_child_of_llm_span() {
+        let observatory = observatory();
+        let llm_span = llm_span(&observatory);
+
+        let tool = llm_span
+            .tool_call("get_weather")
+            .call_id("call_1")
+            .arguments(r#"{"city":"Paris"}"#)
+            .start();
+
+        assert_eq!(tool.trace_id(), llm_span.trace_id());
+        assert_ne!(tool.span_id(), llm_span.span_id());
+        tool.finish_success();
+    }
+
+    #[tokio::test]
+    async fn test_run_passes_through_result() {
+        let observatory = observatory();
+        let llm_span = llm_span(&observatory);
+
+        let ok: Result<u32, String> = llm_span.tool_call("add").run(async { Ok(4) }).await;
+        assert_eq!(ok, Ok(4));
+
+        let err: Result<u32, String> = SpanBuilder::tool_call(&observatory, "search")
+            .run(async { Err("index unavailable".to_string()) })
+            .await;
+        assert_eq!(err, Err("index unavailable".to_string()));
+    }
+}
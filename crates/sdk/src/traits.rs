@@ -3,13 +3,14 @@
 
 //! Core traits for instrumented LLM clients.
 
-use crate::{Error, Result};
+use crate::{retrieval::RetrievalLink, retry::AttemptLink, Error, Result};
 use async_trait::async_trait;
 use futures::Stream;
 use llm_observatory_core::{
     span::ChatMessage,
     types::TokenUsage,
 };
+use opentelemetry::Context;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::pin::Pin;
@@ -84,6 +85,27 @@ pub trait InstrumentedLLM: Send + Sync {
         request: ChatCompletionRequest,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>>;
 
+    /// Create embeddings with automatic instrumentation.
+    ///
+    /// The default implementation returns an error for clients without
+    /// embeddings support.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The embeddings request parameters
+    ///
+    /// # Returns
+    ///
+    /// An [`EmbeddingResponse`] with one vector per input, usage metrics, and
+    /// cost information.
+    async fn embeddings(&self, request: EmbeddingRequest) -> Result<EmbeddingResponse> {
+        let _ = request;
+        Err(Error::internal(format!(
+            "Embeddings are not supported by the {} client",
+            self.provider_name()
+        )))
+    }
+
     /// Get the provider name (e.g., "openai", "anthropic").
     fn provider_name(&self) -> &str;
 
@@ -152,6 +174,19 @@ pub struct ChatCompletionRequest {
     /// Custom metadata for tracing
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, String>>,
+
+    /// Retrieval that supplied context for this request (RAG)
+    #[serde(skip)]
+    pub retrieval: Option<RetrievalLink>,
+
+    /// Context to parent the LLM span to (default: the active context)
+    #[serde(skip)]
+    pub parent_context: Option<Context>,
+
+    /// Attempt of a retried or fallen-back call, set by
+    /// [`ResilientClient`](crate::ResilientClient)
+    #[serde(skip)]
+    pub attempt: Option<AttemptLink>,
 }
 
 impl ChatCompletionRequest {
@@ -169,6 +204,9 @@ impl ChatCompletionRequest {
             user: None,
             stream: false,
             metadata: None,
+            retrieval: None,
+            parent_context: None,
+            attempt: None,
         }
     }
 
@@ -253,6 +291,19 @@ impl ChatCompletionRequest {
         self
     }
 
+    /// Link the request to the retrieval that supplied its context.
+    pub fn with_retrieval(mut self, link: RetrievalLink) -> Self {
+        self.retrieval = Some(link);
+        self
+    }
+
+    /// Parent the LLM span to an existing context, such as one extracted from
+    /// incoming `traceparent` headers.
+    pub fn with_parent_context(mut self, context: Context) -> Self {
+        self.parent_context = Some(context);
+        self
+    }
+
     /// Validate the request.
     pub fn validate(&self) -> Result<()> {
         if self.model.is_empty() {
@@ -322,6 +373,159 @@ impl ChatCompletionResponse {
     }
 }
 
+/// Request parameters for embeddings.
+///
+/// # Example
+///
+/// ```rust
+/// use llm_observatory_sdk::EmbeddingRequest;
+///
+/// let request = EmbeddingRequest::new("text-embedding-3-small")
+///     .with_input("first document")
+///     .with_input("second document")
+///     .with_dimensions(512);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingRequest {
+    /// Model identifier
+    pub model: String,
+
+    /// Texts to embed; more than one makes a batch request
+    pub input: Vec<String>,
+
+    /// Requested output dimensions (for models that support shortening)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<u32>,
+
+    /// User identifier for tracking
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    /// Custom metadata for tracing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+
+    /// Context to parent the LLM span to (default: the active context)
+    #[serde(skip)]
+    pub parent_context: Option<Context>,
+
+    /// Attempt of a retried call, recorded on the span
+    #[serde(skip)]
+    pub attempt: Option<AttemptLink>,
+}
+
+impl EmbeddingRequest {
+    /// Maximum number of inputs in one request.
+    pub const MAX_BATCH_SIZE: usize = 2048;
+
+    /// Create a new embeddings request.
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            input: Vec::new(),
+            dimensions: None,
+            user: None,
+            metadata: None,
+            parent_context: None,
+            attempt: None,
+        }
+    }
+
+    /// Add a text to embed.
+    pub fn with_input(mut self, input: impl Into<String>) -> Self {
+        self.input.push(input.into());
+        self
+    }
+
+    /// Add several texts to embed.
+    pub fn with_inputs<I, S>(mut self, inputs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.input.extend(inputs.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the output dimensions.
+    pub fn with_dimensions(mut self, dimensions: u32) -> Self {
+        self.dimensions = Some(dimensions);
+        self
+    }
+
+    /// Set the user identifier.
+    pub fn with_user_id(mut self, user: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Add custom metadata.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata
+            .get_or_insert_with(HashMap::new)
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Parent the LLM span to an existing context.
+    pub fn with_parent_context(mut self, context: Context) -> Self {
+        self.parent_context = Some(context);
+        self
+    }
+
+    /// Validate the request.
+    pub fn validate(&self) -> Result<()> {
+        if self.model.is_empty() {
+            return Err(Error::invalid_input("model cannot be empty"));
+        }
+        if self.input.is_empty() {
+            return Err(Error::invalid_input("input cannot be empty"));
+        }
+        if self.input.len() > Self::MAX_BATCH_SIZE {
+            return Err(Error::invalid_input(format!(
+                "at most {} inputs are allowed per request",
+                Self::MAX_BATCH_SIZE
+            )));
+        }
+        if self.dimensions == Some(0) {
+            return Err(Error::invalid_input("dimensions must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// Response from an embeddings request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingResponse {
+    /// One embedding per input, in input order
+    pub embeddings: Vec<Vec<f32>>,
+
+    /// Model used
+    pub model: String,
+
+    /// Embedding dimensions
+    pub dimensions: usize,
+
+    /// Token usage statistics (input tokens only)
+    pub usage: TokenUsage,
+
+    /// Cost in USD
+    pub cost_usd: f64,
+
+    /// Latency in milliseconds
+    pub latency_ms: u64,
+
+    /// OpenTelemetry trace ID
+    pub trace_id: String,
+
+    /// OpenTelemetry span ID
+    pub span_id: String,
+
+    /// Additional metadata
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
 /// Chunk of a streaming response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamChunk {
@@ -385,6 +589,25 @@ mod tests {
         assert!(invalid_temp.validate().is_err());
     }
 
+    #[test]
+    fn test_embedding_request_validation() {
+        let request = EmbeddingRequest::new("text-embedding-3-small")
+            .with_inputs(["a", "b"])
+            .with_dimensions(256);
+        assert_eq!(request.input.len(), 2);
+        assert!(request.validate().is_ok());
+
+        assert!(EmbeddingRequest::new("text-embedding-3-small").validate().is_err());
+        assert!(EmbeddingRequest::new("")
+            .with_input("a")
+            .validate()
+            .is_err());
+
+        let too_many = EmbeddingRequest::new("text-embedding-3-small")
+            .with_inputs(std::iter::repeat("x").take(EmbeddingRequest::MAX_BATCH_SIZE + 1));
+        assert!(too_many.validate().is_err());
+    }
+
     #[test]
     fn test_stream_chunk() {
         let chunk = StreamChunk {
@@ -246,6 +246,7 @@ pub fn generate_logs(count: usize) -> Vec<LogRecord> {
                 scope_name: Some("benchmark-scope".to_string()),
                 scope_version: Some("1.0.0".to_string()),
                 scope_attributes: Some(serde_json::json!({})),
+                pattern_id: None,
                 created_at: now,
             }
         })
@@ -114,6 +114,7 @@ fn generate_logs(count: usize) -> Vec<LogRecord> {
                 scope_name: Some("benchmark".to_string()),
                 scope_version: Some("1.0.0".to_string()),
                 scope_attributes: None,
+                pattern_id: None,
                 created_at: now,
             }
         })
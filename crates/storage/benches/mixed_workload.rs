@@ -214,6 +214,7 @@ fn bench_complex_queries_under_write_load(c: &mut Criterion) {
                         end_time: None,
                         min_duration_us: Some(50000),
                         max_duration_us: None,
+                        attributes: None,
                         limit: Some(100),
                         offset: None,
                     };
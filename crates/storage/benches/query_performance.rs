@@ -128,6 +128,7 @@ fn bench_trace_list(c: &mut Criterion) {
                 end_time: None,
                 min_duration_us: None,
                 max_duration_us: None,
+                attributes: None,
                 limit: Some(100),
                 offset: None,
             };
@@ -145,6 +146,7 @@ fn bench_trace_list(c: &mut Criterion) {
                 end_time: None,
                 min_duration_us: None,
                 max_duration_us: None,
+                attributes: None,
                 limit: Some(100),
                 offset: None,
             };
@@ -163,6 +165,7 @@ fn bench_trace_list(c: &mut Criterion) {
                 end_time: Some(now),
                 min_duration_us: None,
                 max_duration_us: None,
+                attributes: None,
                 limit: Some(100),
                 offset: None,
             };
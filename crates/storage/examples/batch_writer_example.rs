@@ -46,6 +46,7 @@ async fn trace_writer_example(pool: &StoragePool) -> Result<(), Box<dyn std::err
         batch_size: 100,
         flush_interval_secs: 5,
         max_concurrency: 4,
+        ..Default::default()
     };
 
     let writer = TraceWriter::with_config(pool.clone(), config);
@@ -119,6 +120,7 @@ async fn metric_writer_example(pool: &StoragePool) -> Result<(), Box<dyn std::er
         batch_size: 500,
         flush_interval_secs: 5,
         max_concurrency: 4,
+        ..Default::default()
     };
 
     let writer = MetricWriter::with_config(pool.clone(), config);
@@ -199,6 +201,7 @@ async fn log_writer_example(pool: &StoragePool) -> Result<(), Box<dyn std::error
         batch_size: 1000,
         flush_interval_secs: 2,
         max_concurrency: 4,
+        ..Default::default()
     };
 
     let writer = LogWriter::with_config(pool.clone(), config);
@@ -247,6 +250,7 @@ async fn log_writer_example(pool: &StoragePool) -> Result<(), Box<dyn std::error
             scope_name: Some("example".to_string()),
             scope_version: Some("1.0.0".to_string()),
             scope_attributes: None,
+            pattern_id: None,
             created_at: Utc::now(),
         };
 
@@ -0,0 +1,172 @@
+-- Migration: 009_partitioned_tables.sql
+-- Description: Declarative range partitioning for the logs and trace_spans tables
+-- Date: 2025-11-12
+-- Author: LLM Observatory Core Team
+-- Purpose: Let queries over recent data prune old partitions and let retention
+--          drop whole partitions instead of running large DELETEs.
+--
+-- Partitions themselves are created ahead of time and pruned at runtime by
+-- `PartitionManager` in the storage crate. This migration only converts the
+-- parent tables and adds a DEFAULT partition for out-of-range rows.
+
+BEGIN;
+
+-- ============================================================================
+-- Section 9.1: Helper to convert an existing table into a partitioned parent
+-- ============================================================================
+-- If `tbl` exists as a regular table it is renamed to `<tbl>_legacy` and
+-- attached as a single partition covering everything up to the end of the
+-- day of its newest row (the current day when it is empty), so existing data
+-- stays queryable without a rewrite. A matching CHECK constraint is validated
+-- first, so the attach itself does not scan the table again.
+--
+-- The legacy table's indexes are renamed to `<index>_legacy`, freeing their
+-- names for the parent's indexes created below.
+
+CREATE OR REPLACE FUNCTION observatory_partition_table(
+    tbl TEXT,
+    ts_column TEXT,
+    key_columns TEXT
+) RETURNS VOID AS $$
+DECLARE
+    kind CHAR;
+    legacy TEXT := tbl || '_legacy';
+    idx RECORD;
+    cutoff TIMESTAMPTZ;
+BEGIN
+    SELECT c.relkind INTO kind
+    FROM pg_class c
+    JOIN pg_namespace n ON n.oid = c.relnamespace
+    WHERE c.relname = tbl AND n.nspname = current_schema();
+
+    IF kind = 'r' THEN
+        EXECUTE format('ALTER TABLE %I RENAME TO %I', tbl, legacy);
+        EXECUTE format('ALTER TABLE %I DROP CONSTRAINT IF EXISTS %I', legacy, tbl || '_pkey');
+
+        FOR idx IN
+            SELECT i.relname::TEXT AS name
+            FROM pg_index x
+            JOIN pg_class i ON i.oid = x.indexrelid
+            WHERE x.indrelid = format('%I', legacy)::regclass
+        LOOP
+            EXECUTE format(
+                'ALTER INDEX %I RENAME TO %I',
+                idx.name, left(idx.name, 63 - length('_legacy')) || '_legacy'
+            );
+        END LOOP;
+
+        EXECUTE format(
+            'CREATE TABLE %I (LIKE %I INCLUDING DEFAULTS INCLUDING CONSTRAINTS) PARTITION BY RANGE (%I)',
+            tbl, legacy, ts_column
+        );
+        EXECUTE format('ALTER TABLE %I ADD PRIMARY KEY (%s)', tbl, key_columns);
+
+        -- Rows from today or later must stay inside the legacy bound
+        EXECUTE format(
+            'SELECT date_trunc(''day'', MAX(%I)) + INTERVAL ''1 day'' FROM %I',
+            ts_column, legacy
+        ) INTO cutoff;
+        cutoff := COALESCE(cutoff, date_trunc('day', NOW()));
+
+        EXECUTE format(
+            'ALTER TABLE %I ADD CONSTRAINT %I CHECK (%I IS NOT NULL AND %I < %L) NOT VALID',
+            legacy, legacy || '_bound', ts_column, ts_column, cutoff
+        );
+        EXECUTE format('ALTER TABLE %I VALIDATE CONSTRAINT %I', legacy, legacy || '_bound');
+        EXECUTE format(
+            'ALTER TABLE %I ATTACH PARTITION %I FOR VALUES FROM (MINVALUE) TO (%L)',
+            tbl, legacy, cutoff
+        );
+        EXECUTE format('ALTER TABLE %I DROP CONSTRAINT %I', legacy, legacy || '_bound');
+    END IF;
+
+    EXECUTE format(
+        'CREATE TABLE IF NOT EXISTS %I PARTITION OF %I DEFAULT',
+        tbl || '_default', tbl
+    );
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION observatory_partition_table(TEXT, TEXT, TEXT) IS
+    'Convert a table into a range-partitioned parent, keeping existing rows in a <table>_legacy partition';
+
+-- ============================================================================
+-- Section 9.2: logs (partitioned by timestamp)
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS logs (
+    id UUID NOT NULL,
+    timestamp TIMESTAMPTZ NOT NULL,
+    observed_timestamp TIMESTAMPTZ NOT NULL,
+    severity_number INTEGER NOT NULL,
+    severity_text VARCHAR(50) NOT NULL,
+    body TEXT NOT NULL,
+    service_name VARCHAR(255) NOT NULL,
+    trace_id VARCHAR(32),
+    span_id VARCHAR(32),
+    trace_flags INTEGER,
+    attributes JSONB NOT NULL DEFAULT '{}',
+    resource_attributes JSONB NOT NULL DEFAULT '{}',
+    scope_name VARCHAR(255),
+    scope_version VARCHAR(50),
+    scope_attributes JSONB,
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (id, timestamp)
+) PARTITION BY RANGE (timestamp);
+
+SELECT observatory_partition_table('logs', 'timestamp', 'id, timestamp');
+
+CREATE INDEX IF NOT EXISTS idx_logs_timestamp ON logs (timestamp DESC);
+CREATE INDEX IF NOT EXISTS idx_logs_service_name ON logs (service_name, timestamp DESC);
+
+-- ============================================================================
+-- Section 9.3: trace_spans (partitioned by start_time)
+-- ============================================================================
+-- Span uniqueness includes start_time because unique constraints on a
+-- partitioned table must contain the partition key. Replayed spans carry the
+-- same start_time, so upserts on (span_id, start_time) remain idempotent.
+
+CREATE TABLE IF NOT EXISTS trace_spans (
+    id UUID NOT NULL,
+    trace_id UUID NOT NULL,
+    span_id VARCHAR(32) NOT NULL,
+    parent_span_id VARCHAR(32),
+    name VARCHAR(255) NOT NULL,
+    kind VARCHAR(50) NOT NULL,
+    service_name VARCHAR(255) NOT NULL,
+    start_time TIMESTAMPTZ NOT NULL,
+    end_time TIMESTAMPTZ,
+    duration_us BIGINT,
+    status VARCHAR(50) NOT NULL,
+    status_message TEXT,
+    attributes JSONB NOT NULL DEFAULT '{}',
+    events JSONB,
+    links JSONB,
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (id, start_time)
+) PARTITION BY RANGE (start_time);
+
+SELECT observatory_partition_table('trace_spans', 'start_time', 'id, start_time');
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_trace_spans_span_id_start
+    ON trace_spans (span_id, start_time);
+CREATE INDEX IF NOT EXISTS idx_trace_spans_trace_id ON trace_spans (trace_id);
+
+COMMIT;
+
+-- ============================================================================
+-- Migration Complete
+-- ============================================================================
+
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM pg_class
+        WHERE relname IN ('logs', 'trace_spans') AND relkind = 'p'
+        HAVING COUNT(*) = 2
+    ) THEN
+        RAISE NOTICE 'Migration 009: logs and trace_spans are partitioned';
+    ELSE
+        RAISE EXCEPTION 'Migration 009: Failed to partition logs/trace_spans';
+    END IF;
+END $$;
@@ -0,0 +1,86 @@
+-- Migration: 010_attribute_indexes.sql
+-- Description: GIN and expression indexes for JSONB attribute search
+-- Date: 2025-11-13
+-- Author: LLM Observatory Core Team
+-- Purpose: Make attribute filters (containment with @>, key existence with ?)
+--          fast on traces, spans and logs, and index the attribute keys the
+--          analytics API filters on for every request.
+--
+-- GIN indexes use the default jsonb_ops operator class so that both
+-- containment (@>) and key existence (?) queries can use them.
+-- Equality on a single extracted value (attributes->>'key' = $1) cannot use a
+-- GIN index, so hot keys get dedicated B-tree expression indexes.
+
+BEGIN;
+
+-- ============================================================================
+-- Section 10.1: GIN indexes on storage tables
+-- ============================================================================
+-- The traces/trace_spans/logs tables are created by the storage writers'
+-- schema, so only index the ones present in this database.
+
+DO $$
+BEGIN
+    IF to_regclass('traces') IS NOT NULL THEN
+        CREATE INDEX IF NOT EXISTS idx_traces_attrs_gin
+            ON traces USING GIN (attributes);
+        CREATE INDEX IF NOT EXISTS idx_traces_resource_attrs_gin
+            ON traces USING GIN (resource_attributes);
+    END IF;
+
+    IF to_regclass('trace_spans') IS NOT NULL THEN
+        CREATE INDEX IF NOT EXISTS idx_trace_spans_attrs_gin
+            ON trace_spans USING GIN (attributes);
+    END IF;
+
+    IF to_regclass('logs') IS NOT NULL THEN
+        CREATE INDEX IF NOT EXISTS idx_logs_attrs_gin
+            ON logs USING GIN (attributes);
+    END IF;
+END $$;
+
+-- ============================================================================
+-- Section 10.2: llm_traces resource attributes and hot keys
+-- ============================================================================
+-- idx_traces_attributes (003) already covers llm_traces.attributes.
+
+CREATE INDEX IF NOT EXISTS idx_llm_traces_resource_attributes
+ON llm_traces USING GIN (resource_attributes);
+
+-- Tenant keys are filtered with ->> equality on every API request
+CREATE INDEX IF NOT EXISTS idx_llm_traces_attr_project_id
+ON llm_traces ((attributes->>'project_id'), ts DESC);
+
+CREATE INDEX IF NOT EXISTS idx_llm_traces_attr_org_id
+ON llm_traces ((attributes->>'org_id'), ts DESC);
+
+COMMIT;
+
+-- ============================================================================
+-- Example Queries
+-- ============================================================================
+
+-- Containment (uses GIN):
+-- SELECT * FROM traces WHERE attributes @> '{"gen_ai.system": "openai"}';
+
+-- Key existence (uses GIN):
+-- SELECT * FROM trace_spans WHERE attributes ? 'gen_ai.request.model';
+
+-- Hot key equality (uses expression index):
+-- SELECT * FROM llm_traces WHERE attributes->>'project_id' = 'proj_123' ORDER BY ts DESC;
+
+-- ============================================================================
+-- Migration Complete
+-- ============================================================================
+
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM pg_indexes
+        WHERE indexname = 'idx_llm_traces_attr_project_id'
+    ) THEN
+        RAISE NOTICE 'Migration 010: attribute indexes created';
+    ELSE
+        RAISE EXCEPTION 'Migration 010: Failed to create attribute indexes';
+    END IF;
+END $$;
@@ -0,0 +1,99 @@
+-- Migration: 011_search_trigram.sql
+-- Description: Trigram indexes for substring search and websearch-style FTS helper
+-- Date: 2025-11-13
+-- Author: LLM Observatory Core Team
+-- Purpose: Serve the `contains`/`starts_with`/`ends_with` filters (ILIKE
+--          '%term%') on input_text/output_text from an index, and expose a
+--          search function that accepts phrase/OR/negation syntax.
+--
+-- Word search uses the tsvector columns from 007_fulltext_search.sql. ILIKE
+-- with a leading wildcard cannot use those (or any B-tree) indexes, so
+-- substring filters fall back to pg_trgm GIN indexes.
+
+BEGIN;
+
+-- ============================================================================
+-- Section 11.1: Trigram indexes
+-- ============================================================================
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS idx_traces_input_text_trgm
+ON llm_traces USING GIN (input_text gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_traces_output_text_trgm
+ON llm_traces USING GIN (output_text gin_trgm_ops);
+
+-- ============================================================================
+-- Section 11.2: Websearch-style search helper
+-- ============================================================================
+
+-- Accepts the same syntax as the analytics API `search` parameter:
+--   timeout error        -> both terms
+--   "rate limit"         -> exact phrase
+--   timeout or deadline  -> either term
+--   error -retry         -> exclude a term
+CREATE OR REPLACE FUNCTION search_traces_websearch(
+    search_query text,
+    max_results integer DEFAULT 100
+)
+RETURNS TABLE (
+    ts timestamptz,
+    trace_id text,
+    span_id text,
+    provider text,
+    model text,
+    input_text text,
+    output_text text,
+    rank real
+) AS $$
+BEGIN
+    RETURN QUERY
+    SELECT
+        t.ts,
+        t.trace_id,
+        t.span_id,
+        t.provider,
+        t.model,
+        t.input_text,
+        t.output_text,
+        ts_rank_cd(t.content_search, query) AS rank
+    FROM
+        llm_traces t,
+        websearch_to_tsquery('english', search_query) query
+    WHERE
+        t.content_search @@ query
+    ORDER BY
+        rank DESC,
+        t.ts DESC
+    LIMIT max_results;
+END;
+$$ LANGUAGE plpgsql STABLE;
+
+COMMIT;
+
+-- ============================================================================
+-- Example Queries
+-- ============================================================================
+
+-- Substring filter (uses trigram index):
+-- SELECT * FROM llm_traces WHERE input_text ILIKE '%connection reset%';
+
+-- Ranked search with phrase/OR syntax:
+-- SELECT * FROM search_traces_websearch('"rate limit" or timeout -retry', 50);
+
+-- ============================================================================
+-- Migration Complete
+-- ============================================================================
+
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM pg_indexes
+        WHERE indexname = 'idx_traces_input_text_trgm'
+    ) THEN
+        RAISE NOTICE 'Migration 011: trigram search indexes created';
+    ELSE
+        RAISE EXCEPTION 'Migration 011: Failed to create trigram search indexes';
+    END IF;
+END $$;
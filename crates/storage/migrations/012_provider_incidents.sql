@@ -0,0 +1,57 @@
+-- Migration 012: Provider Incidents
+--
+-- This migration stores upstream provider availability incidents recorded by
+-- the analytics API provider health monitor:
+-- - Incidents table (one row per degraded/outage period per provider)
+-- - Indexes for time-range and open-incident lookups
+--
+-- Incidents are global (not per organization): they describe the provider,
+-- not our traffic. Error spikes in llm_traces are correlated at query time.
+
+-- ============================================================================
+-- Provider Incidents Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS provider_incidents (
+    -- Primary identifier
+    incident_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Provider name as used in llm_traces.provider (e.g. 'openai')
+    provider TEXT NOT NULL,
+
+    -- Worst status observed during the incident
+    status TEXT NOT NULL CHECK (status IN ('degraded', 'partial_outage', 'major_outage', 'unreachable')),
+
+    -- Status page description or probe error
+    description TEXT,
+
+    -- Probe that detected the incident ('statuspage' or 'http')
+    source TEXT NOT NULL,
+
+    -- Timestamps
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ,
+
+    CONSTRAINT provider_incidents_resolved_after_start
+        CHECK (resolved_at IS NULL OR resolved_at >= started_at)
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_provider_incidents_provider_started
+ON provider_incidents(provider, started_at DESC);
+
+-- At most one open incident per provider
+CREATE UNIQUE INDEX IF NOT EXISTS idx_provider_incidents_open
+ON provider_incidents(provider)
+WHERE resolved_at IS NULL;
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE provider_incidents IS 'Upstream provider availability incidents detected by status page and API probes';
+COMMENT ON COLUMN provider_incidents.status IS 'Worst status observed: degraded, partial_outage, major_outage, or unreachable';
+COMMENT ON COLUMN provider_incidents.resolved_at IS 'NULL while the incident is ongoing';
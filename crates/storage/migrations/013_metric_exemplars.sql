@@ -0,0 +1,75 @@
+-- Migration 013: Metric Data Points with Exemplars
+--
+-- This migration creates the generic metric tables written by the storage
+-- metric writers and adds lookup support for exemplars:
+-- - Metrics table (one row per metric name and service)
+-- - Metric data points table, including histogram buckets and exemplars
+-- - Indexes for time-range queries and exemplar lookups
+--
+-- Exemplars are sample requests attached to histogram buckets by the
+-- collector. Each element of metric_data_points.exemplars is
+-- {"trace_id", "span_id", "value", "timestamp", "attributes"}, so a spike on a
+-- latency or cost chart can link to concrete traces.
+
+-- ============================================================================
+-- Metrics Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS metrics (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    unit VARCHAR(50),
+    metric_type VARCHAR(50) NOT NULL,
+    service_name VARCHAR(255) NOT NULL,
+    attributes JSONB NOT NULL DEFAULT '{}',
+    resource_attributes JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(name, service_name)
+);
+
+-- ============================================================================
+-- Metric Data Points Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS metric_data_points (
+    id UUID PRIMARY KEY,
+    metric_id UUID NOT NULL REFERENCES metrics(id) ON DELETE CASCADE,
+    timestamp TIMESTAMPTZ NOT NULL,
+    value DOUBLE PRECISION,
+    count BIGINT,
+    sum DOUBLE PRECISION,
+    min DOUBLE PRECISION,
+    max DOUBLE PRECISION,
+    buckets JSONB,
+    quantiles JSONB,
+    exemplars JSONB,
+    attributes JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT metric_data_points_exemplars_array
+        CHECK (exemplars IS NULL OR jsonb_typeof(exemplars) = 'array')
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_metrics_name ON metrics(name);
+
+CREATE INDEX IF NOT EXISTS idx_metric_points_metric_timestamp
+ON metric_data_points(metric_id, timestamp DESC);
+
+-- Exemplar lookups only scan points that carry exemplars
+CREATE INDEX IF NOT EXISTS idx_metric_points_exemplars
+ON metric_data_points(metric_id, timestamp DESC)
+WHERE exemplars IS NOT NULL;
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE metric_data_points IS 'Metric data points, including histogram buckets and exemplars';
+COMMENT ON COLUMN metric_data_points.buckets IS 'Histogram buckets: [{"boundary": 100.0, "count": 10}, ...]';
+COMMENT ON COLUMN metric_data_points.exemplars IS 'Sample requests per bucket: [{"trace_id", "span_id", "value", "timestamp", "attributes"}, ...]';
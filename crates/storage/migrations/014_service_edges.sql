@@ -0,0 +1,55 @@
+-- Migration 014: Service Edges
+--
+-- This migration stores the service dependency graph derived from
+-- parent-child span relationships by the analytics API topology materializer:
+-- - Service edges table (one row per organization, bucket and edge)
+-- - Indexes for time-range topology queries
+--
+-- Nodes are services, models (provider/model) and tools. Each bucket is
+-- recomputed in full while it is recent, so rows are replaced, not
+-- incremented.
+
+-- ============================================================================
+-- Service Edges Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS service_edges (
+    -- Organization the calls belong to
+    org_id TEXT NOT NULL,
+
+    -- Start of the aggregation bucket
+    bucket TIMESTAMPTZ NOT NULL,
+
+    -- Calling node
+    source_kind TEXT NOT NULL CHECK (source_kind IN ('service', 'model', 'tool')),
+    source_name TEXT NOT NULL,
+
+    -- Called node
+    target_kind TEXT NOT NULL CHECK (target_kind IN ('service', 'model', 'tool')),
+    target_name TEXT NOT NULL,
+
+    -- Call statistics for the bucket
+    call_count BIGINT NOT NULL,
+    error_count BIGINT NOT NULL DEFAULT 0,
+    avg_duration_ms DOUBLE PRECISION NOT NULL,
+    p95_duration_ms DOUBLE PRECISION NOT NULL,
+
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (org_id, bucket, source_kind, source_name, target_kind, target_name),
+    CONSTRAINT service_edges_errors_within_calls CHECK (error_count <= call_count)
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_service_edges_bucket
+ON service_edges(bucket DESC);
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE service_edges IS 'Service/model/tool dependency edges derived from parent-child spans';
+COMMENT ON COLUMN service_edges.p95_duration_ms IS 'p95 duration of the child spans (calls) along the edge in the bucket';
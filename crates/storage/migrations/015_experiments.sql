@@ -0,0 +1,88 @@
+-- Migration 015: Experiments
+--
+-- This migration stores A/B experiments registered through the analytics API:
+-- - Experiments table (name, variants and time range per organization)
+-- - Experiment feedback table (scores attached to traces of an experiment)
+-- - Expression index for looking up an experiment's spans in llm_traces
+--
+-- Spans are assigned to a variant by the SDK, which records the
+-- 'experiment.id' and 'experiment.variant' attributes. Results are computed at
+-- query time from llm_traces; nothing is pre-aggregated per variant.
+
+-- ============================================================================
+-- Experiments Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS experiments (
+    -- Organization ownership
+    org_id TEXT NOT NULL,
+
+    -- Identifier chosen by the caller, as recorded in 'experiment.id'
+    experiment_id TEXT NOT NULL,
+
+    -- Human-readable name and description
+    name TEXT NOT NULL,
+    description TEXT,
+
+    -- Variant names; the first one is the control
+    variants TEXT[] NOT NULL,
+
+    -- Time range of the experiment (open-ended while running)
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ended_at TIMESTAMPTZ,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (org_id, experiment_id),
+    CONSTRAINT experiments_two_variants CHECK (cardinality(variants) >= 2),
+    CONSTRAINT experiments_ended_after_start
+        CHECK (ended_at IS NULL OR ended_at > started_at)
+);
+
+-- ============================================================================
+-- Experiment Feedback Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS experiment_feedback (
+    -- Primary identifier
+    feedback_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    org_id TEXT NOT NULL,
+    experiment_id TEXT NOT NULL,
+
+    -- Trace the feedback is about; its variant is read from llm_traces
+    trace_id TEXT NOT NULL,
+    span_id TEXT,
+
+    -- Feedback score (e.g. 1/0 for thumbs up/down, or a 1-5 rating)
+    score DOUBLE PRECISION NOT NULL,
+    comment TEXT,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    FOREIGN KEY (org_id, experiment_id)
+        REFERENCES experiments(org_id, experiment_id) ON DELETE CASCADE
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_experiments_org_started
+ON experiments(org_id, started_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_experiment_feedback_experiment
+ON experiment_feedback(org_id, experiment_id, trace_id);
+
+CREATE INDEX IF NOT EXISTS idx_llm_traces_attr_experiment_id
+ON llm_traces ((attributes->>'experiment.id'), ts DESC)
+WHERE attributes ? 'experiment.id';
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE experiments IS 'A/B experiments whose spans are tagged with experiment.id and experiment.variant';
+COMMENT ON COLUMN experiments.variants IS 'Variant names; the first one is the control the others are compared against';
+COMMENT ON COLUMN experiments.ended_at IS 'NULL while the experiment is running';
+COMMENT ON TABLE experiment_feedback IS 'Feedback scores for traces that are part of an experiment';
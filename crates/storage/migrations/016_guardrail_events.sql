@@ -0,0 +1,64 @@
+-- Migration 016: Guardrail Events
+--
+-- This migration stores content-safety guardrail violations extracted by the
+-- collector from the guardrail.* span attributes:
+-- - Guardrail events table (one row per blocked or flagged outcome)
+-- - Indexes for violation-rate queries by model, team and category
+--
+-- Passing outcomes are not stored. Violation rates are computed against the
+-- request counts in llm_traces for the same time range.
+
+-- ============================================================================
+-- Guardrail Events Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS guardrail_events (
+    -- When the guardrail ran
+    ts TIMESTAMPTZ NOT NULL,
+
+    -- Organization the call belongs to (attributes->>'org_id' of the span)
+    org_id TEXT,
+
+    -- Call the guardrail ran on
+    trace_id TEXT NOT NULL,
+    span_id TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    team_id TEXT,
+    environment TEXT,
+
+    -- Guardrail outcome
+    guardrail TEXT,
+    stage TEXT CHECK (stage IN ('input', 'output')),
+    category TEXT,
+    action TEXT NOT NULL CHECK (action IN ('blocked', 'flagged')),
+    score DOUBLE PRECISION
+);
+
+SELECT create_hypertable(
+    'guardrail_events',
+    'ts',
+    chunk_time_interval => INTERVAL '7 days',
+    if_not_exists => TRUE
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_guardrail_events_org_ts
+ON guardrail_events(org_id, ts DESC);
+
+CREATE INDEX IF NOT EXISTS idx_guardrail_events_category
+ON guardrail_events(category, ts DESC);
+
+CREATE INDEX IF NOT EXISTS idx_guardrail_events_trace
+ON guardrail_events(trace_id);
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE guardrail_events IS 'Blocked or flagged content-safety guardrail outcomes extracted by the collector';
+COMMENT ON COLUMN guardrail_events.action IS 'blocked (call rejected) or flagged (violation detected, call allowed)';
+COMMENT ON COLUMN guardrail_events.score IS 'Classifier score reported by the guardrail, 0.0 to 1.0';
@@ -0,0 +1,48 @@
+-- Migration 017: Data Access Audit Log
+--
+-- This migration stores the audit log written by the analytics API when a
+-- trace-returning endpoint sends fields that are masked by policy
+-- (prompt/response text, user identifiers) unmasked:
+-- - Audit log table (one row per response)
+-- - Indexes for per-organization and per-user lookups
+--
+-- Masked responses are not logged. Entries are written before the response
+-- is sent; if the write fails the request fails.
+
+-- ============================================================================
+-- Data Access Audit Log Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS data_access_audit_log (
+    -- When the data was returned
+    ts TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- Who requested it
+    org_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    role TEXT NOT NULL,
+    request_id TEXT NOT NULL,
+
+    -- What was returned
+    endpoint TEXT NOT NULL,
+    fields TEXT[] NOT NULL,
+    trace_ids TEXT[] NOT NULL
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_data_access_audit_org_ts
+ON data_access_audit_log(org_id, ts DESC);
+
+CREATE INDEX IF NOT EXISTS idx_data_access_audit_user
+ON data_access_audit_log(org_id, user_id, ts DESC);
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE data_access_audit_log IS 'Responses that returned masked-by-policy trace fields unmasked';
+COMMENT ON COLUMN data_access_audit_log.fields IS 'Unmasked fields present in the response: input_text, output_text, user_id, session_id';
+COMMENT ON COLUMN data_access_audit_log.trace_ids IS 'Traces in the response, at most 1000';
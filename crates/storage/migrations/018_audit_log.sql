@@ -0,0 +1,82 @@
+-- Migration 018: API Audit Log
+--
+-- This migration stores the audit trail of the analytics API, written
+-- asynchronously by its audit middleware:
+-- - Audit log hypertable (one row per authenticated API call)
+-- - Indexes for per-organization, per-user and per-endpoint lookups
+-- - Compression after 30 days and a 400-day retention policy, which keeps a
+--   full year of evidence for annual SOC 2 audit periods
+--
+-- Calls rejected by authentication are not recorded here; they appear in the
+-- access logs only.
+
+-- ============================================================================
+-- Audit Log Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    -- When the call completed
+    ts TIMESTAMPTZ NOT NULL,
+
+    -- Who made the call
+    request_id TEXT NOT NULL,
+    org_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    role TEXT NOT NULL,
+    auth_method TEXT NOT NULL,
+
+    -- What was called
+    method TEXT NOT NULL,
+    endpoint TEXT NOT NULL,
+    path TEXT NOT NULL,
+    query_params JSONB,
+    request_body JSONB,
+
+    -- Outcome
+    status_code INTEGER NOT NULL,
+    row_count BIGINT,
+    duration_ms BIGINT NOT NULL
+);
+
+SELECT create_hypertable(
+    'audit_log',
+    'ts',
+    chunk_time_interval => INTERVAL '7 days',
+    if_not_exists => TRUE
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_org_ts
+ON audit_log(org_id, ts DESC);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_user
+ON audit_log(org_id, user_id, ts DESC);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_endpoint
+ON audit_log(org_id, endpoint, ts DESC);
+
+-- ============================================================================
+-- Compression and Retention
+-- ============================================================================
+
+ALTER TABLE audit_log SET (
+    timescaledb.compress,
+    timescaledb.compress_segmentby = 'org_id',
+    timescaledb.compress_orderby = 'ts DESC'
+);
+
+SELECT add_compression_policy('audit_log', INTERVAL '30 days', if_not_exists => TRUE);
+
+SELECT add_retention_policy('audit_log', INTERVAL '400 days', if_not_exists => TRUE);
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE audit_log IS 'Authenticated analytics API calls (400-day retention)';
+COMMENT ON COLUMN audit_log.endpoint IS 'Matched route, e.g. /api/v1/traces/:trace_id';
+COMMENT ON COLUMN audit_log.request_body IS 'JSON request body up to 64 KiB (search filters, ...)';
+COMMENT ON COLUMN audit_log.row_count IS 'Rows returned: length of a top-level array or data field; NULL otherwise';
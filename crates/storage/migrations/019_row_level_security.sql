@@ -0,0 +1,90 @@
+-- Migration 019: Row-Level Security
+--
+-- This migration adds optional Postgres row-level security (RLS) for tenant
+-- isolation, so that a query that forgets its `org_id = $1` filter cannot
+-- return another organization's rows:
+-- - app_current_org_id(): the organization set for the current transaction
+--   (`app.org_id`, set by StoragePool::with_tenant)
+-- - llm_observatory_tenant role: roles granted it only see and write rows of
+--   the current organization, and nothing while no organization is set
+-- - Tenant isolation policies on the organization-scoped tables
+--
+-- RLS is opt-in per database role. Roles that are not members of
+-- llm_observatory_tenant (collector writers, maintenance jobs) keep full
+-- access through the permissive service_access policy. To enforce isolation
+-- for the analytics API's read-only connection:
+--
+--   GRANT llm_observatory_tenant TO llm_observatory_readonly;
+--
+-- Table owners and superusers bypass RLS. Compressed hypertables (llm_traces,
+-- llm_metrics, llm_logs, audit_log) are not covered because TimescaleDB does
+-- not support RLS together with compression; queries on them must keep their
+-- explicit org_id filters.
+
+-- ============================================================================
+-- Current Organization
+-- ============================================================================
+
+CREATE OR REPLACE FUNCTION app_current_org_id()
+RETURNS TEXT
+LANGUAGE sql
+STABLE
+AS $$
+    SELECT NULLIF(current_setting('app.org_id', true), '')
+$$;
+
+COMMENT ON FUNCTION app_current_org_id() IS
+    'Organization of the current transaction (app.org_id), NULL if not set';
+
+-- ============================================================================
+-- Tenant Role
+-- ============================================================================
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT FROM pg_roles WHERE rolname = 'llm_observatory_tenant') THEN
+        CREATE ROLE llm_observatory_tenant NOLOGIN;
+    END IF;
+END
+$$;
+
+COMMENT ON ROLE llm_observatory_tenant IS
+    'Members only access rows of the organization in app.org_id';
+
+-- ============================================================================
+-- Tenant Isolation Policies
+-- ============================================================================
+
+DO $$
+DECLARE
+    tbl TEXT;
+BEGIN
+    FOREACH tbl IN ARRAY ARRAY[
+        'export_jobs',
+        'service_edges',
+        'experiments',
+        'experiment_feedback',
+        'guardrail_events',
+        'data_access_audit_log'
+    ]
+    LOOP
+        EXECUTE format('ALTER TABLE %I ENABLE ROW LEVEL SECURITY', tbl);
+
+        -- Everyone keeps access unless a restrictive policy applies
+        EXECUTE format('DROP POLICY IF EXISTS service_access ON %I', tbl);
+        EXECUTE format(
+            'CREATE POLICY service_access ON %I USING (true) WITH CHECK (true)',
+            tbl
+        );
+
+        -- Tenant roles are limited to the current organization
+        EXECUTE format('DROP POLICY IF EXISTS tenant_isolation ON %I', tbl);
+        EXECUTE format(
+            'CREATE POLICY tenant_isolation ON %I AS RESTRICTIVE TO llm_observatory_tenant '
+            'USING (org_id = app_current_org_id()) '
+            'WITH CHECK (org_id = app_current_org_id())',
+            tbl
+        );
+    END LOOP;
+END
+$$;
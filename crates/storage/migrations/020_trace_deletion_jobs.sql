@@ -0,0 +1,88 @@
+-- Migration 020: Trace Deletion Jobs
+--
+-- This migration tracks bulk trace deletions started through
+-- `DELETE /api/v1/traces` (cleanup after test traffic or accidental PII
+-- ingestion):
+-- - Trace deletion jobs table with the filter used and per-batch progress
+-- - Indexes for per-organization job listing
+-- - Row-level security, like the other organization-scoped job tables
+--
+-- Jobs delete matching traces from llm_traces and their logs from llm_logs
+-- in batches. Deleting from compressed chunks requires TimescaleDB 2.11 or
+-- later. Jobs interrupted by a restart stay 'running'; deletion is
+-- idempotent, so the request can simply be submitted again.
+
+-- ============================================================================
+-- Trace Deletion Jobs Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS trace_deletion_jobs (
+    -- Primary identifier
+    job_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Organization ownership and requester
+    org_id TEXT NOT NULL,
+    requested_by TEXT NOT NULL,
+
+    -- Job status
+    status TEXT NOT NULL CHECK (status IN ('pending', 'running', 'completed', 'failed')),
+
+    -- Selection: search filter and time range of matching spans
+    filter JSONB NOT NULL,
+    start_time TIMESTAMPTZ,
+    end_time TIMESTAMPTZ NOT NULL,
+
+    -- Rows matched when the job was created
+    matched_traces BIGINT NOT NULL DEFAULT 0,
+    matched_spans BIGINT NOT NULL DEFAULT 0,
+    matched_logs BIGINT NOT NULL DEFAULT 0,
+
+    -- Progress
+    deleted_traces BIGINT NOT NULL DEFAULT 0,
+    deleted_spans BIGINT NOT NULL DEFAULT 0,
+    deleted_logs BIGINT NOT NULL DEFAULT 0,
+    batches_completed INTEGER NOT NULL DEFAULT 0,
+    error_message TEXT,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_trace_deletion_jobs_org_created
+ON trace_deletion_jobs(org_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_trace_deletion_jobs_active
+ON trace_deletion_jobs(status)
+WHERE status IN ('pending', 'running');
+
+-- ============================================================================
+-- Row-Level Security (see migration 019)
+-- ============================================================================
+
+ALTER TABLE trace_deletion_jobs ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS service_access ON trace_deletion_jobs;
+CREATE POLICY service_access ON trace_deletion_jobs
+    USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON trace_deletion_jobs;
+CREATE POLICY tenant_isolation ON trace_deletion_jobs
+    AS RESTRICTIVE
+    TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE trace_deletion_jobs IS 'Bulk trace deletions started through DELETE /api/v1/traces';
+COMMENT ON COLUMN trace_deletion_jobs.filter IS 'Search filter (same model as POST /api/v1/traces/search) selecting the traces';
+COMMENT ON COLUMN trace_deletion_jobs.end_time IS 'Upper bound on span timestamps; defaults to the job creation time so new traffic is not deleted';
+COMMENT ON COLUMN trace_deletion_jobs.matched_traces IS 'Traces matched when the job was created, for progress reporting';
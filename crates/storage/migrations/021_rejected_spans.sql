@@ -0,0 +1,85 @@
+-- Migration 021: Rejected Spans
+--
+-- This migration stores spans quarantined by the collector's schema
+-- validation stage (schema_validation.mode = quarantine):
+-- - Rejected spans hypertable (one row per span that failed its schema)
+-- - Indexes for per-organization and per-service lookups
+-- - 30-day retention policy
+-- - Row-level security, like the other organization-scoped tables
+--
+-- The full span is kept as JSON so it can be inspected, fixed and replayed.
+
+-- ============================================================================
+-- Rejected Spans Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS rejected_spans (
+    -- When the collector rejected the span
+    ts TIMESTAMPTZ NOT NULL,
+
+    -- Organization the span belongs to (attributes->>'org_id' of the span)
+    org_id TEXT,
+
+    -- Rejected span
+    trace_id TEXT NOT NULL,
+    span_id TEXT NOT NULL,
+    service_name TEXT,
+    service_version TEXT,
+
+    -- Validation outcome
+    schema_name TEXT NOT NULL,
+    violations TEXT[] NOT NULL,
+    span JSONB NOT NULL
+);
+
+SELECT create_hypertable(
+    'rejected_spans',
+    'ts',
+    chunk_time_interval => INTERVAL '1 day',
+    if_not_exists => TRUE
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_rejected_spans_org_ts
+ON rejected_spans(org_id, ts DESC);
+
+CREATE INDEX IF NOT EXISTS idx_rejected_spans_service
+ON rejected_spans(service_name, service_version, ts DESC);
+
+CREATE INDEX IF NOT EXISTS idx_rejected_spans_trace
+ON rejected_spans(trace_id);
+
+-- ============================================================================
+-- Retention
+-- ============================================================================
+
+SELECT add_retention_policy('rejected_spans', INTERVAL '30 days', if_not_exists => TRUE);
+
+-- ============================================================================
+-- Row-Level Security (see migration 019)
+-- ============================================================================
+
+ALTER TABLE rejected_spans ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS service_access ON rejected_spans;
+CREATE POLICY service_access ON rejected_spans
+    USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON rejected_spans;
+CREATE POLICY tenant_isolation ON rejected_spans
+    AS RESTRICTIVE
+    TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE rejected_spans IS 'Spans quarantined by collector schema validation (30-day retention)';
+COMMENT ON COLUMN rejected_spans.schema_name IS 'Registered schema the span failed, e.g. checkout-api@2.1.0';
+COMMENT ON COLUMN rejected_spans.violations IS 'Human-readable schema violations';
+COMMENT ON COLUMN rejected_spans.span IS 'Rejected span as received, in the LlmSpan JSON format';
@@ -0,0 +1,57 @@
+-- Migration 022: Quarantine
+--
+-- This migration turns rejected_spans (migration 021) into the general
+-- quarantine for spans the collector could not process:
+-- - Failure stage (schema validation, PII redaction, other processing) and reason
+-- - Stable ID for browsing and replay
+-- - Replay state: spans are requested for replay through the API (pending),
+--   claimed by the ingest pipeline (replaying) and marked replayed or failed
+--
+-- Quarantined spans still expire with the 30-day retention policy from 021,
+-- whether or not they were replayed.
+
+-- ============================================================================
+-- Quarantine Columns
+-- ============================================================================
+
+ALTER TABLE rejected_spans
+    ADD COLUMN IF NOT EXISTS id UUID NOT NULL DEFAULT gen_random_uuid(),
+    ADD COLUMN IF NOT EXISTS stage TEXT NOT NULL DEFAULT 'schema'
+        CHECK (stage IN ('schema', 'pii', 'processing')),
+    ADD COLUMN IF NOT EXISTS failure_reason TEXT NOT NULL DEFAULT 'Schema validation failed',
+    ADD COLUMN IF NOT EXISTS replay_status TEXT NOT NULL DEFAULT 'quarantined'
+        CHECK (replay_status IN ('quarantined', 'pending', 'replaying', 'replayed', 'failed')),
+    ADD COLUMN IF NOT EXISTS replay_requested_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS replayed_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS replay_attempts INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS replay_error TEXT;
+
+-- Only schema validation failures have a schema and violations
+ALTER TABLE rejected_spans ALTER COLUMN schema_name DROP NOT NULL;
+ALTER TABLE rejected_spans ALTER COLUMN violations SET DEFAULT '{}';
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+-- Unique indexes on a hypertable must include the time column
+CREATE UNIQUE INDEX IF NOT EXISTS idx_rejected_spans_id
+ON rejected_spans(id, ts);
+
+CREATE INDEX IF NOT EXISTS idx_rejected_spans_stage
+ON rejected_spans(org_id, stage, ts DESC);
+
+-- Replay queue
+CREATE INDEX IF NOT EXISTS idx_rejected_spans_pending
+ON rejected_spans(replay_requested_at)
+WHERE replay_status = 'pending';
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE rejected_spans IS 'Quarantined spans the collector failed to validate or process (30-day retention)';
+COMMENT ON COLUMN rejected_spans.stage IS 'Failing stage: schema, pii or processing';
+COMMENT ON COLUMN rejected_spans.failure_reason IS 'Why the span was quarantined';
+COMMENT ON COLUMN rejected_spans.replay_status IS 'quarantined, pending (replay requested), replaying, replayed or failed';
+COMMENT ON COLUMN rejected_spans.replay_error IS 'Reason of the last failed replay';
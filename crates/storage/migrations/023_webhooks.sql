@@ -0,0 +1,140 @@
+-- Migration 023: Webhooks
+--
+-- This migration stores webhook notifications sent by the webhook dispatcher
+-- (crates/webhooks) for alerts, budget thresholds and anomalies:
+-- - Webhook endpoints registered per organization, with their signing secret
+--   and the event types they subscribe to
+-- - Deliveries with their status, attempt count and last response
+-- - Indexes for per-endpoint delivery listing and retry lookups
+-- - 30-day retention of finished deliveries
+-- - Row-level security, like the other organization-scoped tables
+--
+-- Deliveries interrupted by a restart stay 'pending' or 'retrying'.
+
+-- ============================================================================
+-- Webhook Endpoints Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS webhook_endpoints (
+    -- Primary identifier
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Organization ownership
+    org_id TEXT NOT NULL,
+
+    -- Target and signing secret (HMAC-SHA256 of each payload)
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+
+    -- Subscribed event types; empty subscribes to all
+    event_types TEXT[] NOT NULL DEFAULT '{}',
+
+    description TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- ============================================================================
+-- Webhook Deliveries Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    -- Primary identifier (sent as X-Observatory-Delivery)
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Endpoint and organization
+    endpoint_id UUID NOT NULL REFERENCES webhook_endpoints(id) ON DELETE CASCADE,
+    org_id TEXT NOT NULL,
+
+    -- Event delivered
+    event_id UUID NOT NULL,
+    event_type TEXT NOT NULL,
+    payload JSONB NOT NULL,
+
+    -- Delivery status
+    status TEXT NOT NULL CHECK (status IN ('pending', 'retrying', 'succeeded', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    response_status INTEGER,
+    last_error TEXT,
+    next_attempt_at TIMESTAMPTZ,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_webhook_endpoints_org
+ON webhook_endpoints(org_id)
+WHERE enabled;
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_endpoint_created
+ON webhook_deliveries(endpoint_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_active
+ON webhook_deliveries(next_attempt_at)
+WHERE status IN ('pending', 'retrying');
+
+-- ============================================================================
+-- Retention
+-- ============================================================================
+
+CREATE OR REPLACE FUNCTION cleanup_webhook_deliveries()
+RETURNS INTEGER AS $$
+DECLARE
+    deleted_count INTEGER;
+BEGIN
+    DELETE FROM webhook_deliveries
+    WHERE status IN ('succeeded', 'failed')
+      AND created_at < NOW() - INTERVAL '30 days';
+
+    GET DIAGNOSTICS deleted_count = ROW_COUNT;
+    RETURN deleted_count;
+END;
+$$ LANGUAGE plpgsql;
+
+-- ============================================================================
+-- Row-Level Security (see migration 019)
+-- ============================================================================
+
+ALTER TABLE webhook_endpoints ENABLE ROW LEVEL SECURITY;
+ALTER TABLE webhook_deliveries ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS service_access ON webhook_endpoints;
+CREATE POLICY service_access ON webhook_endpoints
+    USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON webhook_endpoints;
+CREATE POLICY tenant_isolation ON webhook_endpoints
+    AS RESTRICTIVE
+    TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+DROP POLICY IF EXISTS service_access ON webhook_deliveries;
+CREATE POLICY service_access ON webhook_deliveries
+    USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON webhook_deliveries;
+CREATE POLICY tenant_isolation ON webhook_deliveries
+    AS RESTRICTIVE
+    TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE webhook_endpoints IS 'Webhook endpoints registered per organization';
+COMMENT ON COLUMN webhook_endpoints.secret IS 'HMAC-SHA256 signing secret, returned once at registration';
+COMMENT ON COLUMN webhook_endpoints.event_types IS 'Subscribed event types, e.g. budget.threshold_exceeded; empty for all';
+COMMENT ON TABLE webhook_deliveries IS 'Webhook deliveries and their status (finished deliveries kept 30 days)';
+COMMENT ON COLUMN webhook_deliveries.next_attempt_at IS 'When the next retry is due, while retrying';
+COMMENT ON FUNCTION cleanup_webhook_deliveries() IS 'Deletes finished deliveries older than 30 days';
@@ -0,0 +1,93 @@
+-- Migration 024: Notification Channels
+--
+-- This migration adds native Slack and PagerDuty notification channels next
+-- to the webhook endpoints of migration 023:
+-- - Channels registered per organization, with their Slack webhook URL or
+--   PagerDuty routing key
+-- - Routing by event type, alert rule and minimum severity
+-- - Channel deliveries tracked in webhook_deliveries with the same status
+--   and retries as webhook deliveries
+-- - Row-level security, like the other organization-scoped tables
+
+-- ============================================================================
+-- Notification Channels Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS notification_channels (
+    -- Primary identifier
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Organization ownership
+    org_id TEXT NOT NULL,
+
+    -- Channel kind and display name
+    kind TEXT NOT NULL CHECK (kind IN ('slack', 'pager_duty')),
+    name TEXT NOT NULL,
+
+    -- Slack incoming webhook URL or PagerDuty routing key
+    target TEXT NOT NULL,
+
+    -- Routing; empty arrays match everything
+    event_types TEXT[] NOT NULL DEFAULT '{}',
+    alert_rules TEXT[] NOT NULL DEFAULT '{}',
+    min_severity TEXT NOT NULL DEFAULT 'info'
+        CHECK (min_severity IN ('info', 'warning', 'critical')),
+
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- ============================================================================
+-- Channel Deliveries
+-- ============================================================================
+
+-- A delivery goes to exactly one webhook endpoint or notification channel
+ALTER TABLE webhook_deliveries
+    ALTER COLUMN endpoint_id DROP NOT NULL,
+    ADD COLUMN IF NOT EXISTS channel_id UUID REFERENCES notification_channels(id) ON DELETE CASCADE;
+
+ALTER TABLE webhook_deliveries DROP CONSTRAINT IF EXISTS webhook_deliveries_target_check;
+ALTER TABLE webhook_deliveries ADD CONSTRAINT webhook_deliveries_target_check
+    CHECK ((endpoint_id IS NULL) <> (channel_id IS NULL));
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_notification_channels_org
+ON notification_channels(org_id)
+WHERE enabled;
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_channel_created
+ON webhook_deliveries(channel_id, created_at DESC)
+WHERE channel_id IS NOT NULL;
+
+-- ============================================================================
+-- Row-Level Security (see migration 019)
+-- ============================================================================
+
+ALTER TABLE notification_channels ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS service_access ON notification_channels;
+CREATE POLICY service_access ON notification_channels
+    USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON notification_channels;
+CREATE POLICY tenant_isolation ON notification_channels
+    AS RESTRICTIVE
+    TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE notification_channels IS 'Slack and PagerDuty notification channels registered per organization';
+COMMENT ON COLUMN notification_channels.target IS 'Slack incoming webhook URL or PagerDuty Events API v2 routing key';
+COMMENT ON COLUMN notification_channels.alert_rules IS 'Alert rules and budgets routed to the channel, e.g. error_rate or budget:monthly; empty for all';
+COMMENT ON COLUMN notification_channels.min_severity IS 'Lowest event severity routed to the channel';
+COMMENT ON COLUMN webhook_deliveries.channel_id IS 'Notification channel of the delivery, when not sent to a webhook endpoint';
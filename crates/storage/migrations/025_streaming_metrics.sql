@@ -0,0 +1,100 @@
+-- Migration 025: Streaming Metrics
+--
+-- This migration stores per-minute metrics pre-aggregated by the collector's
+-- streaming aggregation processor (processors.streaming_aggregation):
+-- - Streaming metrics hypertable (one row per minute, organization, provider
+--   and model)
+-- - Indexes for per-organization and per-model queries
+-- - 37-day retention policy, like llm_metrics_1min
+-- - Row-level security, like the other organization-scoped tables
+--
+-- Organizations listed in streaming_aggregation.aggregate_only_orgs have no
+-- rows in llm_traces; these rollups are their only request metrics. Rows are
+-- upserted additively, so flushes from several collectors and spans arriving
+-- after their minute was flushed merge into the same row.
+
+-- ============================================================================
+-- Streaming Metrics Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS llm_metrics_streaming_1min (
+    -- Start of the minute
+    bucket TIMESTAMPTZ NOT NULL,
+
+    -- Dimensions ('' when the span carries no org_id attribute)
+    org_id TEXT NOT NULL DEFAULT '',
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+
+    -- Counters
+    request_count BIGINT NOT NULL,
+    error_count BIGINT NOT NULL,
+    prompt_tokens BIGINT NOT NULL,
+    completion_tokens BIGINT NOT NULL,
+    total_tokens BIGINT NOT NULL,
+    total_cost_usd DOUBLE PRECISION NOT NULL,
+
+    -- Latency histogram (request_count values)
+    sum_duration_ms DOUBLE PRECISION NOT NULL,
+    sum_duration_ms_squared DOUBLE PRECISION NOT NULL,
+    min_duration_ms DOUBLE PRECISION NOT NULL,
+    max_duration_ms DOUBLE PRECISION NOT NULL,
+    latency_bounds_ms DOUBLE PRECISION[] NOT NULL,
+    latency_bucket_counts BIGINT[] NOT NULL,
+
+    PRIMARY KEY (bucket, org_id, provider, model),
+
+    -- One count per bound, plus the overflow bucket
+    CONSTRAINT llm_metrics_streaming_1min_buckets
+        CHECK (cardinality(latency_bucket_counts) = cardinality(latency_bounds_ms) + 1)
+);
+
+SELECT create_hypertable(
+    'llm_metrics_streaming_1min',
+    'bucket',
+    chunk_time_interval => INTERVAL '1 day',
+    if_not_exists => TRUE
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_llm_metrics_streaming_1min_org
+ON llm_metrics_streaming_1min(org_id, bucket DESC);
+
+CREATE INDEX IF NOT EXISTS idx_llm_metrics_streaming_1min_model
+ON llm_metrics_streaming_1min(provider, model, bucket DESC);
+
+-- ============================================================================
+-- Retention
+-- ============================================================================
+
+SELECT add_retention_policy('llm_metrics_streaming_1min', INTERVAL '37 days', if_not_exists => TRUE);
+
+-- ============================================================================
+-- Row-Level Security (see migration 019)
+-- ============================================================================
+
+ALTER TABLE llm_metrics_streaming_1min ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS service_access ON llm_metrics_streaming_1min;
+CREATE POLICY service_access ON llm_metrics_streaming_1min
+    USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON llm_metrics_streaming_1min;
+CREATE POLICY tenant_isolation ON llm_metrics_streaming_1min
+    AS RESTRICTIVE
+    TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE llm_metrics_streaming_1min IS 'Per-minute request metrics pre-aggregated by the collector, per organization, provider and model';
+COMMENT ON COLUMN llm_metrics_streaming_1min.error_count IS 'Requests whose span status was error';
+COMMENT ON COLUMN llm_metrics_streaming_1min.sum_duration_ms_squared IS 'Sum of squared durations, for standard deviation';
+COMMENT ON COLUMN llm_metrics_streaming_1min.latency_bounds_ms IS 'Upper bounds (inclusive) of the latency buckets, in milliseconds';
+COMMENT ON COLUMN llm_metrics_streaming_1min.latency_bucket_counts IS 'Requests per latency bucket; the last element counts requests above the last bound';
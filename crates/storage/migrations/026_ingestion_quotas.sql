@@ -0,0 +1,89 @@
+-- Migration 026: Ingestion Quotas
+--
+-- This migration stores per-organization ingestion usage counted by the
+-- collector's quota processor (processors.quotas):
+-- - Usage table (one row per UTC day and organization) with spans, bytes
+--   and throttled spans, plus the limits in force
+-- - 400-day retention of usage rows
+-- - Row-level security, like the other organization-scoped tables
+--
+-- Collector replicas upsert their usage additively and read back the day's
+-- totals, so limits apply across replicas. The analytics API reports usage
+-- against the quota from this table.
+
+-- ============================================================================
+-- Ingestion Usage Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS ingestion_usage (
+    -- UTC day and organization
+    day DATE NOT NULL,
+    org_id TEXT NOT NULL,
+
+    -- Usage
+    span_count BIGINT NOT NULL DEFAULT 0,
+    byte_count BIGINT NOT NULL DEFAULT 0,
+    throttled_spans BIGINT NOT NULL DEFAULT 0,
+
+    -- Limits in force at the last flush (NULL for unlimited)
+    spans_per_day_limit BIGINT,
+    bytes_per_day_limit BIGINT,
+    over_quota_action TEXT NOT NULL DEFAULT 'drop'
+        CHECK (over_quota_action IN ('drop', 'sample', 'queue')),
+
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (day, org_id)
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_ingestion_usage_org_day
+ON ingestion_usage(org_id, day DESC);
+
+-- ============================================================================
+-- Retention
+-- ============================================================================
+
+CREATE OR REPLACE FUNCTION cleanup_ingestion_usage()
+RETURNS INTEGER AS $$
+DECLARE
+    deleted_count INTEGER;
+BEGIN
+    DELETE FROM ingestion_usage
+    WHERE day < CURRENT_DATE - 400;
+
+    GET DIAGNOSTICS deleted_count = ROW_COUNT;
+    RETURN deleted_count;
+END;
+$$ LANGUAGE plpgsql;
+
+-- ============================================================================
+-- Row-Level Security (see migration 019)
+-- ============================================================================
+
+ALTER TABLE ingestion_usage ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS service_access ON ingestion_usage;
+CREATE POLICY service_access ON ingestion_usage
+    USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON ingestion_usage;
+CREATE POLICY tenant_isolation ON ingestion_usage
+    AS RESTRICTIVE
+    TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE ingestion_usage IS 'Spans and bytes ingested per organization and UTC day, with the quota in force';
+COMMENT ON COLUMN ingestion_usage.byte_count IS 'JSON-encoded size of the spans admitted';
+COMMENT ON COLUMN ingestion_usage.throttled_spans IS 'Over-quota spans dropped, sampled out or queued';
+COMMENT ON COLUMN ingestion_usage.spans_per_day_limit IS 'Daily span limit; NULL for unlimited';
+COMMENT ON COLUMN ingestion_usage.bytes_per_day_limit IS 'Daily byte limit; NULL for unlimited';
+COMMENT ON FUNCTION cleanup_ingestion_usage() IS 'Deletes usage rows older than 400 days';
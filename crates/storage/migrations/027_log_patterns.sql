@@ -0,0 +1,131 @@
+-- Migration 027: Log Patterns
+--
+-- This migration stores the log patterns mined by the collector's log
+-- pattern miner (processors.log_patterns):
+-- - pattern_id column on logs, from the log.pattern_id attribute
+-- - Pattern table (one row per organization and pattern) with the template
+--   and the first and last time it was seen
+-- - Hourly pattern counts hypertable, for counts and trends
+-- - 90-day retention of counts and of patterns not seen since
+-- - Row-level security, like the other organization-scoped tables
+--
+-- Patterns and counts are upserted by every collector replica; counts add
+-- up and the seen range widens. The analytics API lists patterns with their
+-- counts and trend from these tables.
+
+-- ============================================================================
+-- Pattern IDs on Logs
+-- ============================================================================
+
+ALTER TABLE logs ADD COLUMN IF NOT EXISTS pattern_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_logs_pattern_id
+ON logs (pattern_id, timestamp DESC)
+WHERE pattern_id IS NOT NULL;
+
+-- ============================================================================
+-- Log Patterns Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS log_patterns (
+    -- '' when the logs carry no org_id attribute
+    org_id TEXT NOT NULL DEFAULT '',
+    pattern_id TEXT NOT NULL,
+
+    service_name TEXT NOT NULL,
+    template TEXT NOT NULL,
+    severity_number INTEGER NOT NULL DEFAULT 0,
+
+    first_seen TIMESTAMPTZ NOT NULL,
+    last_seen TIMESTAMPTZ NOT NULL,
+
+    PRIMARY KEY (org_id, pattern_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_log_patterns_org_first_seen
+ON log_patterns(org_id, first_seen DESC);
+
+-- ============================================================================
+-- Log Pattern Counts Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS log_pattern_counts (
+    -- Start of the hour
+    bucket TIMESTAMPTZ NOT NULL,
+    org_id TEXT NOT NULL DEFAULT '',
+    pattern_id TEXT NOT NULL,
+
+    count BIGINT NOT NULL,
+
+    PRIMARY KEY (bucket, org_id, pattern_id)
+);
+
+SELECT create_hypertable(
+    'log_pattern_counts',
+    'bucket',
+    chunk_time_interval => INTERVAL '1 day',
+    if_not_exists => TRUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_log_pattern_counts_org
+ON log_pattern_counts(org_id, bucket DESC);
+
+-- ============================================================================
+-- Retention
+-- ============================================================================
+
+SELECT add_retention_policy('log_pattern_counts', INTERVAL '90 days', if_not_exists => TRUE);
+
+CREATE OR REPLACE FUNCTION cleanup_log_patterns()
+RETURNS INTEGER AS $$
+DECLARE
+    deleted_count INTEGER;
+BEGIN
+    DELETE FROM log_patterns
+    WHERE last_seen < NOW() - INTERVAL '90 days';
+
+    GET DIAGNOSTICS deleted_count = ROW_COUNT;
+    RETURN deleted_count;
+END;
+$$ LANGUAGE plpgsql;
+
+-- ============================================================================
+-- Row-Level Security (see migration 019)
+-- ============================================================================
+
+ALTER TABLE log_patterns ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS service_access ON log_patterns;
+CREATE POLICY service_access ON log_patterns
+    USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON log_patterns;
+CREATE POLICY tenant_isolation ON log_patterns
+    AS RESTRICTIVE
+    TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+ALTER TABLE log_pattern_counts ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS service_access ON log_pattern_counts;
+CREATE POLICY service_access ON log_pattern_counts
+    USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON log_pattern_counts;
+CREATE POLICY tenant_isolation ON log_pattern_counts
+    AS RESTRICTIVE
+    TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON COLUMN logs.pattern_id IS 'Log pattern mined by the collector (log_patterns.pattern_id)';
+COMMENT ON TABLE log_patterns IS 'Templates of similar log bodies mined by the collector, per organization';
+COMMENT ON COLUMN log_patterns.template IS 'Log body template, with <*> for varying tokens';
+COMMENT ON COLUMN log_patterns.severity_number IS 'Highest OTLP severity number of the records';
+COMMENT ON TABLE log_pattern_counts IS 'Records per log pattern and hour';
+COMMENT ON FUNCTION cleanup_log_patterns() IS 'Deletes patterns not seen for 90 days';
@@ -0,0 +1,40 @@
+-- Migration 028: Log Queries
+--
+-- This migration indexes the logs table for the analytics API log endpoints:
+-- - Trace correlation: records by trace ID, and by span ID for records that
+--   carry only the span
+-- - Body search: trigram index for case-insensitive substring search
+--   (body ILIKE '%term%'), see migration 011
+--
+-- Organization and time filters use the existing timestamp and service
+-- indexes from migration 009.
+
+-- ============================================================================
+-- Trace Correlation
+-- ============================================================================
+
+-- idx_logs_trace_id already names the llm_logs index from migration 003
+CREATE INDEX IF NOT EXISTS idx_logs_trace_correlation
+ON logs (trace_id, timestamp)
+WHERE trace_id IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_logs_span_id
+ON logs (span_id)
+WHERE span_id IS NOT NULL;
+
+-- ============================================================================
+-- Body Search
+-- ============================================================================
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS idx_logs_body_trgm
+ON logs USING GIN (body gin_trgm_ops);
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON INDEX idx_logs_trace_correlation IS 'Log records of a trace, in time order';
+COMMENT ON INDEX idx_logs_span_id IS 'Log records of a span, for records without a trace ID';
+COMMENT ON INDEX idx_logs_body_trgm IS 'Substring search on log bodies';
@@ -0,0 +1,53 @@
+-- Migration 029: Metric Downsampling
+--
+-- This migration supports the storage downsampling worker
+-- (llm_observatory_storage::downsampling), which rolls old
+-- metric_data_points into 5-minute and 1-hour resolutions:
+-- - Provenance columns on metric_data_points: the resolution of a rolled
+--   point and the number of raw points it stands for
+-- - Run log with one row per downsampled window
+--
+-- Raw points keep NULL in both columns. Rolled points replace their sources
+-- in the same transaction, so a point is never counted twice.
+
+-- ============================================================================
+-- Provenance Columns
+-- ============================================================================
+
+ALTER TABLE metric_data_points ADD COLUMN IF NOT EXISTS resolution_seconds INTEGER;
+ALTER TABLE metric_data_points ADD COLUMN IF NOT EXISTS source_points INTEGER;
+
+-- The worker looks for the oldest points below a resolution
+CREATE INDEX IF NOT EXISTS idx_metric_points_resolution_timestamp
+ON metric_data_points(timestamp)
+WHERE resolution_seconds IS NULL OR resolution_seconds < 3600;
+
+-- ============================================================================
+-- Downsampling Runs Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS metric_downsampling_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    resolution_seconds INTEGER NOT NULL,
+    window_start TIMESTAMPTZ NOT NULL,
+    window_end TIMESTAMPTZ NOT NULL,
+
+    source_points BIGINT NOT NULL,
+    downsampled_points BIGINT NOT NULL,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_metric_downsampling_runs_window
+ON metric_downsampling_runs(window_start DESC);
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON COLUMN metric_data_points.resolution_seconds IS 'Interval a downsampled point covers (300 or 3600); NULL for raw points';
+COMMENT ON COLUMN metric_data_points.source_points IS 'Raw points rolled into a downsampled point; NULL for raw points';
+COMMENT ON TABLE metric_downsampling_runs IS 'Windows of metric_data_points rolled up by the downsampling worker';
+COMMENT ON COLUMN metric_downsampling_runs.source_points IS 'Data points read and deleted';
+COMMENT ON COLUMN metric_downsampling_runs.downsampled_points IS 'Data points written at resolution_seconds';
@@ -0,0 +1,74 @@
+-- Migration 030: Top-N Daily Rollups
+--
+-- This migration supports the storage top-N materializer
+-- (llm_observatory_storage::top_n), which keeps per-organization daily
+-- totals of llm_traces for the dimensions dashboards rank by ("top models by
+-- cost", "top users by requests"):
+-- - Daily totals table (one row per organization, day, dimension and value)
+-- - Refresh state recording how far the totals are complete
+--
+-- Days are recomputed in full once they are over, so rows are replaced, not
+-- incremented. Queries combine complete days from top_n_daily with raw
+-- llm_traces for the partial days at either end of their range.
+
+-- ============================================================================
+-- Daily Totals Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS top_n_daily (
+    -- Organization the requests belong to
+    org_id TEXT NOT NULL,
+
+    -- Start of the UTC day
+    bucket TIMESTAMPTZ NOT NULL,
+
+    -- Grouping column of llm_traces: model, environment or user_id
+    dimension TEXT NOT NULL CHECK (dimension IN ('model', 'environment', 'user_id')),
+    dimension_value TEXT NOT NULL,
+
+    -- Provider of the model; empty for other dimensions
+    provider TEXT NOT NULL DEFAULT '',
+
+    -- Totals for the day
+    total_cost DOUBLE PRECISION NOT NULL,
+    prompt_cost DOUBLE PRECISION NOT NULL,
+    completion_cost DOUBLE PRECISION NOT NULL,
+    request_count BIGINT NOT NULL,
+    total_tokens BIGINT NOT NULL,
+    error_count BIGINT NOT NULL,
+    duration_sum_ms DOUBLE PRECISION NOT NULL,
+
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (org_id, dimension, bucket, dimension_value, provider)
+);
+
+-- ============================================================================
+-- Refresh State Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS top_n_refresh_state (
+    -- Single row
+    id BOOLEAN PRIMARY KEY DEFAULT true CHECK (id),
+
+    -- Days from materialized_from up to refreshed_through are materialized
+    materialized_from TIMESTAMPTZ NOT NULL,
+    refreshed_through TIMESTAMPTZ NOT NULL,
+
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_top_n_daily_bucket
+ON top_n_daily(bucket);
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE top_n_daily IS 'Per-organization daily llm_traces totals by model, environment and user, for top-N queries';
+COMMENT ON COLUMN top_n_daily.duration_sum_ms IS 'Sum of request durations; divide by request_count for the average';
+COMMENT ON TABLE top_n_refresh_state IS 'How far top_n_daily holds complete days';
@@ -0,0 +1,76 @@
+-- Migration 031: Cost Allocation Tags
+--
+-- This migration makes the cost-allocation tags of LLM calls queryable for
+-- chargeback reports:
+-- - cost_tags column on llm_traces (tag key -> value, without the
+--   "cost.tag." attribute prefix)
+-- - Trigger filling cost_tags from the cost.tag.* attributes on insert
+-- - Backfill of the uncompressed (last 7 days) rows
+--
+-- Tags are normalized by the collector (CostTagProcessor) before they are
+-- stored. Rows in chunks compressed before this migration keep a NULL
+-- cost_tags and are reported as untagged.
+
+-- ============================================================================
+-- Column
+-- ============================================================================
+
+ALTER TABLE llm_traces ADD COLUMN IF NOT EXISTS cost_tags JSONB;
+
+-- ============================================================================
+-- Extraction
+-- ============================================================================
+
+-- Object of the cost.tag.* string attributes, keyed without the prefix
+CREATE OR REPLACE FUNCTION extract_cost_tags(attributes JSONB)
+RETURNS JSONB AS $$
+    SELECT COALESCE(
+        jsonb_object_agg(substr(key, length('cost.tag.') + 1), value),
+        '{}'::jsonb
+    )
+    FROM jsonb_each(COALESCE(attributes, '{}'::jsonb))
+    WHERE key LIKE 'cost.tag.%'
+      AND length(key) > length('cost.tag.')
+      AND jsonb_typeof(value) = 'string'
+      AND value #>> '{}' <> '';
+$$ LANGUAGE SQL IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION llm_traces_set_cost_tags()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.cost_tags IS NULL THEN
+        NEW.cost_tags := extract_cost_tags(NEW.attributes);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_llm_traces_cost_tags ON llm_traces;
+CREATE TRIGGER trg_llm_traces_cost_tags
+BEFORE INSERT ON llm_traces
+FOR EACH ROW EXECUTE FUNCTION llm_traces_set_cost_tags();
+
+-- ============================================================================
+-- Backfill
+-- ============================================================================
+
+-- Chunks older than 7 days are compressed (005_retention_policies.sql)
+UPDATE llm_traces
+SET cost_tags = extract_cost_tags(attributes)
+WHERE cost_tags IS NULL
+  AND ts >= NOW() - INTERVAL '7 days';
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+-- Containment filters: cost_tags @> '{"team": "search"}'
+CREATE INDEX IF NOT EXISTS idx_llm_traces_cost_tags
+ON llm_traces USING GIN (cost_tags jsonb_path_ops);
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON COLUMN llm_traces.cost_tags IS 'Cost-allocation tags (team, product, feature, ...) from the cost.tag.* attributes, keyed without the prefix';
+COMMENT ON FUNCTION extract_cost_tags(JSONB) IS 'Cost-allocation tags of a span attributes object';
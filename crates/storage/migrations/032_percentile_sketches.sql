@@ -0,0 +1,75 @@
+-- Migration 032: Percentile Sketches
+--
+-- This migration stores mergeable percentile sketches for the metrics API
+-- (GET /api/v1/metrics with percentile aggregations):
+-- - timescaledb_toolkit extension, when the server ships it
+-- - llm_percentiles_1hour continuous aggregate with one percentile_agg
+--   (UddSketch) per metric, organization, provider, model, environment and
+--   status code
+--
+-- Sketches roll up exactly into day buckets or whole ranges with rollup(),
+-- which PERCENTILE_CONT cannot do in a continuous aggregate (see 004). On
+-- servers without the toolkit nothing is created and the API falls back to
+-- PERCENTILE_CONT over llm_traces.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_available_extensions WHERE name = 'timescaledb_toolkit'
+    ) THEN
+        RAISE NOTICE 'timescaledb_toolkit not available; percentiles will be computed from raw traces';
+        RETURN;
+    END IF;
+
+    CREATE EXTENSION IF NOT EXISTS timescaledb_toolkit;
+
+    -- ========================================================================
+    -- Hourly Sketches
+    -- ========================================================================
+    -- Created WITH NO DATA so the migration can run in a transaction; the
+    -- refresh policy fills recent hours and older ones can be backfilled
+    -- with refresh_continuous_aggregate().
+
+    EXECUTE $view$
+        CREATE MATERIALIZED VIEW IF NOT EXISTS llm_percentiles_1hour
+        WITH (timescaledb.continuous) AS
+        SELECT
+            time_bucket('1 hour', ts) AS bucket,
+            attributes->>'org_id' AS org_id,
+            provider,
+            model,
+            environment,
+            status_code,
+            COUNT(*) AS request_count,
+            -- Latency
+            percentile_agg(duration_ms) AS duration_ms_sketch,
+            MIN(duration_ms) AS min_duration_ms,
+            MAX(duration_ms) AS max_duration_ms,
+            percentile_agg(ttft_ms) AS ttft_ms_sketch,
+            MIN(ttft_ms) AS min_ttft_ms,
+            MAX(ttft_ms) AS max_ttft_ms,
+            -- Cost and tokens per request
+            percentile_agg(total_cost_usd::DOUBLE PRECISION) AS cost_usd_sketch,
+            MIN(total_cost_usd) AS min_cost_usd,
+            MAX(total_cost_usd) AS max_cost_usd,
+            percentile_agg(total_tokens) AS tokens_sketch,
+            MIN(total_tokens) AS min_tokens,
+            MAX(total_tokens) AS max_tokens
+        FROM llm_traces
+        GROUP BY bucket, attributes->>'org_id', provider, model, environment, status_code
+        WITH NO DATA
+    $view$;
+
+    COMMENT ON MATERIALIZED VIEW llm_percentiles_1hour IS 'Hourly percentile_agg sketches per organization; merge with rollup() and read with approx_percentile()';
+
+    -- Same refresh window and retention as llm_metrics_1hour
+    PERFORM add_continuous_aggregate_policy('llm_percentiles_1hour',
+        start_offset => INTERVAL '1 day',
+        end_offset => INTERVAL '1 hour',
+        schedule_interval => INTERVAL '5 minutes',
+        if_not_exists => true);
+
+    PERFORM add_retention_policy('llm_percentiles_1hour', INTERVAL '210 days',
+        if_not_exists => true);
+END
+$$;
@@ -0,0 +1,163 @@
+-- Migration 033: Organizations
+--
+-- This migration stores the tenants managed through the admin API
+-- (/api/v1/admin):
+-- - Organizations, whose IDs are the org_id carried by spans and tokens
+-- - Teams within an organization, and user membership of teams
+-- - Per-organization settings: trace retention, content capture policy and
+--   ingestion quotas
+-- - apply_organization_retention() deleting traces and logs past an
+--   organization's retention
+--
+-- Unset settings fall back to the system defaults: the retention policies of
+-- 005, the SDK's capture policy and the collector's configured quotas. The
+-- ingest host loads the settings into the collector's quota and capture
+-- policy processors.
+
+-- ============================================================================
+-- Organizations
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS organizations (
+    -- Same ID as the org_id of spans and JWT claims
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- ============================================================================
+-- Teams
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS teams (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    org_id TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    description TEXT,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (org_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS team_members (
+    team_id UUID NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    -- Same ID as the sub of JWT claims
+    user_id TEXT NOT NULL,
+    role TEXT NOT NULL DEFAULT 'member'
+        CHECK (role IN ('member', 'maintainer')),
+
+    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (team_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_teams_org
+ON teams(org_id);
+
+CREATE INDEX IF NOT EXISTS idx_team_members_user
+ON team_members(user_id);
+
+-- ============================================================================
+-- Organization Settings
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS organization_settings (
+    org_id TEXT PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+
+    -- Days traces and logs are kept (NULL for the system retention)
+    retention_days INTEGER CHECK (retention_days BETWEEN 1 AND 3650),
+
+    -- Content capture: full, metadata_only or none (NULL to keep what the
+    -- SDK captured)
+    capture_policy TEXT
+        CHECK (capture_policy IN ('full', 'metadata_only', 'none')),
+
+    -- Ingestion quotas (NULL for the collector's configured limits)
+    spans_per_day_limit BIGINT CHECK (spans_per_day_limit >= 0),
+    bytes_per_day_limit BIGINT CHECK (bytes_per_day_limit >= 0),
+    over_quota_action TEXT
+        CHECK (over_quota_action IN ('drop', 'sample', 'queue')),
+
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_by TEXT
+);
+
+-- ============================================================================
+-- Retention
+-- ============================================================================
+
+CREATE OR REPLACE FUNCTION apply_organization_retention()
+RETURNS INTEGER AS $$
+DECLARE
+    setting RECORD;
+    deleted_count INTEGER := 0;
+    batch_count INTEGER;
+BEGIN
+    FOR setting IN
+        SELECT org_id, retention_days
+        FROM organization_settings
+        WHERE retention_days IS NOT NULL
+    LOOP
+        DELETE FROM llm_logs
+        WHERE attributes->>'org_id' = setting.org_id
+          AND ts < NOW() - make_interval(days => setting.retention_days);
+
+        DELETE FROM llm_traces
+        WHERE attributes->>'org_id' = setting.org_id
+          AND ts < NOW() - make_interval(days => setting.retention_days);
+
+        GET DIAGNOSTICS batch_count = ROW_COUNT;
+        deleted_count := deleted_count + batch_count;
+    END LOOP;
+
+    RETURN deleted_count;
+END;
+$$ LANGUAGE plpgsql;
+
+-- ============================================================================
+-- Row-Level Security (see migration 019)
+-- ============================================================================
+
+DO $$
+DECLARE
+    tbl TEXT;
+BEGIN
+    FOREACH tbl IN ARRAY ARRAY[
+        'teams',
+        'organization_settings'
+    ]
+    LOOP
+        EXECUTE format('ALTER TABLE %I ENABLE ROW LEVEL SECURITY', tbl);
+
+        EXECUTE format('DROP POLICY IF EXISTS service_access ON %I', tbl);
+        EXECUTE format(
+            'CREATE POLICY service_access ON %I USING (true) WITH CHECK (true)',
+            tbl
+        );
+
+        EXECUTE format('DROP POLICY IF EXISTS tenant_isolation ON %I', tbl);
+        EXECUTE format(
+            'CREATE POLICY tenant_isolation ON %I AS RESTRICTIVE TO llm_observatory_tenant '
+            'USING (org_id = app_current_org_id()) '
+            'WITH CHECK (org_id = app_current_org_id())',
+            tbl
+        );
+    END LOOP;
+END
+$$;
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE organizations IS 'Tenants; the ID is the org_id of spans and tokens';
+COMMENT ON TABLE teams IS 'Teams within an organization';
+COMMENT ON TABLE team_members IS 'User membership of teams';
+COMMENT ON TABLE organization_settings IS 'Per-organization retention, capture policy and ingestion quotas; NULL settings use the system defaults';
+COMMENT ON COLUMN organization_settings.retention_days IS 'Days traces and logs are kept; NULL for the system retention';
+COMMENT ON COLUMN organization_settings.capture_policy IS 'Content capture enforced by the collector: full, metadata_only or none';
+COMMENT ON FUNCTION apply_organization_retention() IS 'Deletes traces and logs past their organization''s retention, returning the traces deleted';
@@ -0,0 +1,77 @@
+-- Migration 034: Service Accounts
+--
+-- This migration stores machine identities managed through the admin API
+-- (/api/v1/admin/organizations/:org_id/service-accounts):
+-- - Service accounts of an organization, with the permissions their tokens
+--   carry (e.g. write:traces only)
+-- - The tokens issued to them, which expire and can be revoked
+--
+-- Tokens are JWTs signed by the analytics API whose jti is the token ID and
+-- whose subject is "sa:<service account ID>", so API calls made with them
+-- are attributed to the service account in audit_log (018). The API rejects
+-- tokens that are revoked, expired or belong to a disabled service account.
+
+-- ============================================================================
+-- Service Accounts
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS service_accounts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    org_id TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    description TEXT,
+
+    -- Permissions carried by the account's tokens
+    permissions TEXT[] NOT NULL,
+    -- Projects the account's tokens can access (empty for none)
+    projects TEXT[] NOT NULL DEFAULT '{}',
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by TEXT NOT NULL,
+    -- Set when disabled; tokens of disabled accounts are rejected
+    disabled_at TIMESTAMPTZ,
+
+    UNIQUE (org_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS service_account_tokens (
+    -- Same ID as the jti of the token
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    service_account_id UUID NOT NULL REFERENCES service_accounts(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    revoked_by TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_service_accounts_org
+ON service_accounts(org_id);
+
+CREATE INDEX IF NOT EXISTS idx_service_account_tokens_account
+ON service_account_tokens(service_account_id, created_at DESC);
+
+-- ============================================================================
+-- Row-Level Security (see migration 019)
+-- ============================================================================
+
+ALTER TABLE service_accounts ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS service_access ON service_accounts;
+CREATE POLICY service_access ON service_accounts USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON service_accounts;
+CREATE POLICY tenant_isolation ON service_accounts AS RESTRICTIVE TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE service_accounts IS 'Machine identities of an organization with scoped permissions';
+COMMENT ON TABLE service_account_tokens IS 'Expiring, revocable tokens issued to service accounts; the ID is the token''s jti';
+COMMENT ON COLUMN service_accounts.permissions IS 'Permissions carried by the account''s tokens';
+COMMENT ON COLUMN service_accounts.disabled_at IS 'Set when disabled; tokens of disabled accounts are rejected';
@@ -0,0 +1,45 @@
+-- Migration 035: Online Migrations
+--
+-- This migration stores the progress of online schema migrations run by
+-- llm_observatory_storage::online_migration. An online migration changes a
+-- large table without blocking ingestion:
+-- - Expand: add nullable columns (a catalog-only change) and a trigger that
+--   keeps them in sync with every write (the dual-write window)
+-- - Backfill: update existing rows in small keyset batches
+-- - Verify: count rows whose new columns still disagree with the old ones
+-- - Contract: once writers use the new columns, run the final statements
+--   and drop the trigger
+--
+-- The backfill checkpoint (last_key) lets an interrupted migration resume
+-- where it stopped.
+
+-- ============================================================================
+-- Online Migrations Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS online_migrations (
+    name TEXT PRIMARY KEY,
+    table_name TEXT NOT NULL,
+
+    phase TEXT NOT NULL DEFAULT 'pending'
+        CHECK (phase IN ('pending', 'expanded', 'backfilling', 'verified', 'completed', 'failed')),
+
+    last_key TEXT,
+    rows_backfilled BIGINT NOT NULL DEFAULT 0,
+    batches BIGINT NOT NULL DEFAULT 0,
+    mismatched_rows BIGINT,
+    error TEXT,
+
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE online_migrations IS 'Progress of online (batched, dual-write) schema migrations';
+COMMENT ON COLUMN online_migrations.phase IS 'pending, expanded (dual-write trigger installed), backfilling, verified, completed or failed';
+COMMENT ON COLUMN online_migrations.last_key IS 'Key of the last backfilled row, as text; the backfill resumes after it';
+COMMENT ON COLUMN online_migrations.mismatched_rows IS 'Rows failing the verification check at the last verification';
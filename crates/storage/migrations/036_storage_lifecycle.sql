@@ -0,0 +1,61 @@
+-- Migration 036: Storage Lifecycle
+--
+-- This migration supports the storage lifecycle manager
+-- (llm_observatory_storage::lifecycle), which moves rows older than the hot
+-- window from Postgres to Parquet files in object storage:
+-- - Archive manifest (one row per Parquet file written)
+-- - Archived trace index, so trace lookups can report where an archived
+--   trace went instead of "not found"
+--
+-- A day of a table is archived in one transaction: its files are uploaded,
+-- recorded here and the rows are deleted. Re-archiving a day replaces its
+-- files and manifest rows.
+
+-- ============================================================================
+-- Archive Manifest Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS archive_manifest (
+    -- Full URL of the Parquet file
+    location TEXT PRIMARY KEY,
+
+    -- Archived table: traces, trace_spans, logs or llm_traces
+    table_name TEXT NOT NULL,
+
+    -- Start of the UTC day the rows belong to
+    day TIMESTAMPTZ NOT NULL,
+
+    row_count BIGINT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- ============================================================================
+-- Archived Traces Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS archived_traces (
+    trace_id VARCHAR(32) PRIMARY KEY,
+    start_time TIMESTAMPTZ NOT NULL,
+
+    -- Directory of the day's trace files
+    location TEXT NOT NULL,
+
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_archive_manifest_table_day
+ON archive_manifest(table_name, day);
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE archive_manifest IS 'Parquet files written by the storage lifecycle manager';
+COMMENT ON COLUMN archive_manifest.day IS 'Start of the UTC day whose rows the file holds';
+COMMENT ON TABLE archived_traces IS 'Traces moved from Postgres to object storage, with the directory holding them';
@@ -0,0 +1,133 @@
+-- Migration 037: Drift Monitoring
+--
+-- This migration supports the analytics API drift monitor, which watches
+-- prompts and responses per model and workload for silent behavior changes
+-- (e.g. after a provider updates a model behind the same name):
+-- - Hourly snapshots of prompt/response characteristics per series
+-- - Drift alerts raised when a snapshot moves away from its baseline
+--
+-- A series is one organization, provider, model and workload (the
+-- `prompt.template` attribute, falling back to the span name). Snapshots are
+-- computed from a random sample of the hour's requests and recomputed in
+-- full on each run, so rows are replaced, not incremented.
+
+-- ============================================================================
+-- Drift Snapshots Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS drift_snapshots (
+    -- Series
+    org_id TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    workload TEXT NOT NULL,
+
+    -- Start of the hour
+    bucket TIMESTAMPTZ NOT NULL,
+
+    -- Requests in the hour and how many were sampled
+    request_count BIGINT NOT NULL,
+    sample_count BIGINT NOT NULL,
+
+    -- Prompt and response length in characters
+    prompt_length_mean DOUBLE PRECISION NOT NULL,
+    prompt_length_stddev DOUBLE PRECISION NOT NULL,
+    response_length_mean DOUBLE PRECISION NOT NULL,
+    response_length_stddev DOUBLE PRECISION NOT NULL,
+
+    -- Share of responses containing a refusal phrase
+    refusal_rate DOUBLE PRECISION NOT NULL,
+
+    -- Share of responses per detected language, e.g. {"en": 0.9, "de": 0.1}
+    languages JSONB NOT NULL DEFAULT '{}'::jsonb,
+
+    -- Mean of the hashed bag-of-words embeddings of the responses
+    embedding_centroid DOUBLE PRECISION[] NOT NULL,
+
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (org_id, provider, model, workload, bucket),
+    CONSTRAINT drift_snapshots_sample_within_requests CHECK (sample_count <= request_count)
+);
+
+-- ============================================================================
+-- Drift Alerts Table
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS drift_alerts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Series
+    org_id TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    workload TEXT NOT NULL,
+
+    -- Hour whose snapshot drifted
+    bucket TIMESTAMPTZ NOT NULL,
+
+    -- Characteristic that drifted
+    metric TEXT NOT NULL CHECK (metric IN (
+        'prompt_length', 'response_length', 'refusal_rate', 'language', 'embedding'
+    )),
+
+    -- Baseline and current value of the characteristic, the drift score
+    -- compared against the threshold
+    baseline_value DOUBLE PRECISION NOT NULL,
+    current_value DOUBLE PRECISION NOT NULL,
+    score DOUBLE PRECISION NOT NULL,
+    threshold DOUBLE PRECISION NOT NULL,
+
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- Recomputing an hour does not raise its alerts again
+    CONSTRAINT drift_alerts_once_per_bucket UNIQUE (org_id, provider, model, workload, metric, bucket)
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_drift_snapshots_bucket
+ON drift_snapshots(bucket DESC);
+
+CREATE INDEX IF NOT EXISTS idx_drift_alerts_org_detected
+ON drift_alerts(org_id, detected_at DESC);
+
+-- ============================================================================
+-- Row-Level Security (see migration 019)
+-- ============================================================================
+
+ALTER TABLE drift_snapshots ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS service_access ON drift_snapshots;
+CREATE POLICY service_access ON drift_snapshots
+    USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON drift_snapshots;
+CREATE POLICY tenant_isolation ON drift_snapshots
+    AS RESTRICTIVE
+    TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+ALTER TABLE drift_alerts ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS service_access ON drift_alerts;
+CREATE POLICY service_access ON drift_alerts
+    USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON drift_alerts;
+CREATE POLICY tenant_isolation ON drift_alerts
+    AS RESTRICTIVE
+    TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE drift_snapshots IS 'Hourly prompt/response characteristics per organization, model and workload';
+COMMENT ON COLUMN drift_snapshots.embedding_centroid IS 'Mean L2-normalized feature-hashed bag-of-words vector of sampled responses';
+COMMENT ON TABLE drift_alerts IS 'Snapshots whose characteristics moved beyond the drift thresholds from their baseline';
@@ -0,0 +1,82 @@
+-- Migration 038: Prompt Cache Metrics
+--
+-- This migration makes provider-side prompt caching (Anthropic cache reads
+-- and writes, OpenAI cached input) queryable:
+-- - cache_read_tokens, cache_write_tokens and cache_savings_usd columns on
+--   llm_traces
+-- - Trigger filling them from the span attributes on insert
+-- - Backfill of the uncompressed (last 7 days) rows
+--
+-- The collector's cost processor reads the cache token attributes
+-- (gen_ai.usage.cache_read.input_tokens,
+-- gen_ai.usage.cache_creation.input_tokens), bills them at the model's cache
+-- rates and records llm_observatory.cost.cache_savings_usd: the cost without
+-- caching minus the actual cost. Both token counts are included in
+-- prompt_tokens. Rows without cache usage keep NULL columns.
+
+-- ============================================================================
+-- Columns
+-- ============================================================================
+
+ALTER TABLE llm_traces ADD COLUMN IF NOT EXISTS cache_read_tokens INTEGER;
+ALTER TABLE llm_traces ADD COLUMN IF NOT EXISTS cache_write_tokens INTEGER;
+ALTER TABLE llm_traces ADD COLUMN IF NOT EXISTS cache_savings_usd DECIMAL(12, 8);
+
+-- ============================================================================
+-- Extraction
+-- ============================================================================
+
+-- Numeric attribute value, or NULL when missing or not a number
+CREATE OR REPLACE FUNCTION numeric_attribute(attributes JSONB, key TEXT)
+RETURNS NUMERIC AS $$
+    SELECT CASE
+        WHEN jsonb_typeof(attributes -> key) = 'number' THEN (attributes ->> key)::NUMERIC
+        WHEN (attributes ->> key) ~ '^\s*-?[0-9]+(\.[0-9]+)?\s*$' THEN trim(attributes ->> key)::NUMERIC
+    END;
+$$ LANGUAGE SQL IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION llm_traces_set_cache_metrics()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.cache_read_tokens IS NULL THEN
+        NEW.cache_read_tokens :=
+            numeric_attribute(NEW.attributes, 'gen_ai.usage.cache_read.input_tokens')::INTEGER;
+    END IF;
+    IF NEW.cache_write_tokens IS NULL THEN
+        NEW.cache_write_tokens :=
+            numeric_attribute(NEW.attributes, 'gen_ai.usage.cache_creation.input_tokens')::INTEGER;
+    END IF;
+    IF NEW.cache_savings_usd IS NULL THEN
+        NEW.cache_savings_usd :=
+            numeric_attribute(NEW.attributes, 'llm_observatory.cost.cache_savings_usd');
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_llm_traces_cache_metrics ON llm_traces;
+CREATE TRIGGER trg_llm_traces_cache_metrics
+BEFORE INSERT ON llm_traces
+FOR EACH ROW EXECUTE FUNCTION llm_traces_set_cache_metrics();
+
+-- ============================================================================
+-- Backfill
+-- ============================================================================
+
+-- Chunks older than 7 days are compressed (005_retention_policies.sql)
+UPDATE llm_traces
+SET cache_read_tokens = numeric_attribute(attributes, 'gen_ai.usage.cache_read.input_tokens')::INTEGER,
+    cache_write_tokens = numeric_attribute(attributes, 'gen_ai.usage.cache_creation.input_tokens')::INTEGER,
+    cache_savings_usd = numeric_attribute(attributes, 'llm_observatory.cost.cache_savings_usd')
+WHERE ts >= NOW() - INTERVAL '7 days'
+  AND (attributes ? 'gen_ai.usage.cache_read.input_tokens'
+       OR attributes ? 'gen_ai.usage.cache_creation.input_tokens');
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON COLUMN llm_traces.cache_read_tokens IS 'Prompt tokens served from the provider prompt cache (included in prompt_tokens)';
+COMMENT ON COLUMN llm_traces.cache_write_tokens IS 'Prompt tokens written to the provider prompt cache (included in prompt_tokens)';
+COMMENT ON COLUMN llm_traces.cache_savings_usd IS 'Cost without prompt caching minus the actual cost; negative when cache writes were not repaid';
+COMMENT ON FUNCTION numeric_attribute(JSONB, TEXT) IS 'Numeric value of a span attribute, or NULL when missing or not a number';
@@ -0,0 +1,115 @@
+-- Migration 039: Model Registry
+--
+-- This migration stores the custom and fine-tuned models organizations
+-- register through the analytics API (/api/v1/model-registry):
+-- - Registered models with their aliases, base model and token pricing
+-- - model_base_model() resolving a model to the root of its lineage, for
+--   rollups by base model
+-- - Row-level security, like the other organization-scoped tables
+--
+-- The ingest host loads the registry into the pricing engine
+-- (llm_observatory_providers::MODEL_REGISTRY), which resolves registered
+-- models before the bundled pricing. Models without pricing cost what their
+-- base model costs.
+
+-- ============================================================================
+-- Registered Models
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS model_registry (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    org_id TEXT NOT NULL,
+
+    -- Model ID as reported on spans, e.g. ft:gpt-4o-mini-2024-07-18:acme::abc123
+    model_id TEXT NOT NULL,
+    provider TEXT,
+    display_name TEXT,
+
+    -- Other names the model is reported under
+    aliases TEXT[] NOT NULL DEFAULT '{}',
+
+    -- Model this one was fine-tuned from or deploys
+    base_model TEXT,
+
+    -- Token pricing (NULL for the base model's pricing)
+    prompt_cost_per_1k DOUBLE PRECISION CHECK (prompt_cost_per_1k >= 0),
+    completion_cost_per_1k DOUBLE PRECISION CHECK (completion_cost_per_1k >= 0),
+    cached_prompt_cost_per_1k DOUBLE PRECISION CHECK (cached_prompt_cost_per_1k >= 0),
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_by TEXT,
+
+    UNIQUE (org_id, model_id),
+
+    -- Prompt and completion prices are set together
+    CONSTRAINT model_registry_pricing
+        CHECK ((prompt_cost_per_1k IS NULL) = (completion_cost_per_1k IS NULL)),
+    CONSTRAINT model_registry_cached_pricing
+        CHECK (cached_prompt_cost_per_1k IS NULL OR prompt_cost_per_1k IS NOT NULL),
+    CONSTRAINT model_registry_not_own_base
+        CHECK (base_model IS DISTINCT FROM model_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_model_registry_aliases
+ON model_registry USING GIN (aliases);
+
+-- ============================================================================
+-- Lineage
+-- ============================================================================
+
+-- Root of a model's lineage: registered base models are followed (by model ID
+-- or alias), then OpenAI fine-tune IDs (ft:<base>:...) resolve to the model
+-- they were tuned from. Unknown models are their own base.
+CREATE OR REPLACE FUNCTION model_base_model(p_org_id TEXT, p_model TEXT)
+RETURNS TEXT AS $$
+    WITH RECURSIVE lineage(model, depth) AS (
+        SELECT p_model, 0
+        UNION ALL
+        SELECT COALESCE(
+                   r.base_model,
+                   r.model_id,
+                   NULLIF(split_part(substr(l.model, 4), ':', 1), '')
+               ),
+               l.depth + 1
+        FROM lineage l
+        LEFT JOIN model_registry r
+          ON r.org_id = p_org_id
+         AND (r.model_id = l.model OR l.model = ANY(r.aliases))
+        WHERE l.depth < 8
+          -- Stop at a registered model without a base, or an unregistered
+          -- model that is not a fine-tune ID
+          AND (r.base_model IS NOT NULL
+               OR (r.id IS NOT NULL AND r.model_id <> l.model)
+               OR (r.id IS NULL AND split_part(substr(l.model, 4), ':', 1) <> ''
+                   AND l.model LIKE 'ft:%'))
+    )
+    SELECT model FROM lineage ORDER BY depth DESC LIMIT 1;
+$$ LANGUAGE SQL STABLE;
+
+-- ============================================================================
+-- Row-Level Security (see migration 019)
+-- ============================================================================
+
+ALTER TABLE model_registry ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS service_access ON model_registry;
+CREATE POLICY service_access ON model_registry
+    USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON model_registry;
+CREATE POLICY tenant_isolation ON model_registry
+    AS RESTRICTIVE
+    TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE model_registry IS 'Custom and fine-tuned models registered per organization, resolved before the bundled pricing';
+COMMENT ON COLUMN model_registry.aliases IS 'Other model names spans report for this model';
+COMMENT ON COLUMN model_registry.base_model IS 'Model this one was fine-tuned from or deploys; supplies pricing when the model has none';
+COMMENT ON COLUMN model_registry.prompt_cost_per_1k IS 'USD per 1000 prompt tokens; NULL for the base model pricing';
+COMMENT ON FUNCTION model_base_model(TEXT, TEXT) IS 'Root of a model''s lineage through the organization''s registry and fine-tune IDs';
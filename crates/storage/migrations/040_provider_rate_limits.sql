@@ -0,0 +1,133 @@
+-- Migration 040: Provider Rate Limits
+--
+-- This migration keeps the latest provider rate-limit state of each
+-- organization, provider and model:
+-- - provider_rate_limits table: limits, remaining requests/tokens and reset
+--   times from the most recent span reporting them
+-- - Trigger upserting it from the span attributes on insert into llm_traces
+-- - Row-level security, like the other organization-scoped tables
+--
+-- The SDK records the provider's rate-limit response headers (OpenAI
+-- x-ratelimit-*, Anthropic anthropic-ratelimit-*) as the
+-- llm_observatory.rate_limit.* span attributes. The analytics API reports the
+-- state (/api/v1/rate-limits) and its rate-limit monitor raises
+-- alert.triggered webhook events when a limit is nearly used up.
+
+-- ============================================================================
+-- Latest State
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS provider_rate_limits (
+    org_id TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+
+    requests_limit BIGINT,
+    requests_remaining BIGINT,
+    requests_reset_at TIMESTAMPTZ,
+    tokens_limit BIGINT,
+    tokens_remaining BIGINT,
+    tokens_reset_at TIMESTAMPTZ,
+
+    -- Span the state was reported with
+    observed_at TIMESTAMPTZ NOT NULL,
+    trace_id TEXT NOT NULL,
+    span_id TEXT NOT NULL,
+
+    -- Last "approaching rate limit" alert for this state
+    alerted_at TIMESTAMPTZ,
+
+    PRIMARY KEY (org_id, provider, model)
+);
+
+CREATE INDEX IF NOT EXISTS idx_provider_rate_limits_observed
+ON provider_rate_limits (observed_at DESC);
+
+-- ============================================================================
+-- Extraction
+-- ============================================================================
+
+-- Timestamp attribute value, or NULL when missing or not a timestamp
+CREATE OR REPLACE FUNCTION timestamptz_attribute(attributes JSONB, key TEXT)
+RETURNS TIMESTAMPTZ AS $$
+BEGIN
+    RETURN (attributes ->> key)::TIMESTAMPTZ;
+EXCEPTION WHEN OTHERS THEN
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql STABLE;
+
+CREATE OR REPLACE FUNCTION llm_traces_upsert_rate_limits()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.attributes ->> 'org_id' IS NULL
+       OR NOT (NEW.attributes ?| ARRAY[
+           'llm_observatory.rate_limit.requests.remaining',
+           'llm_observatory.rate_limit.tokens.remaining'
+       ]) THEN
+        RETURN NULL;
+    END IF;
+
+    INSERT INTO provider_rate_limits (
+        org_id, provider, model,
+        requests_limit, requests_remaining, requests_reset_at,
+        tokens_limit, tokens_remaining, tokens_reset_at,
+        observed_at, trace_id, span_id
+    )
+    VALUES (
+        NEW.attributes ->> 'org_id', NEW.provider, NEW.model,
+        numeric_attribute(NEW.attributes, 'llm_observatory.rate_limit.requests.limit')::BIGINT,
+        numeric_attribute(NEW.attributes, 'llm_observatory.rate_limit.requests.remaining')::BIGINT,
+        timestamptz_attribute(NEW.attributes, 'llm_observatory.rate_limit.requests.reset_at'),
+        numeric_attribute(NEW.attributes, 'llm_observatory.rate_limit.tokens.limit')::BIGINT,
+        numeric_attribute(NEW.attributes, 'llm_observatory.rate_limit.tokens.remaining')::BIGINT,
+        timestamptz_attribute(NEW.attributes, 'llm_observatory.rate_limit.tokens.reset_at'),
+        NEW.ts, NEW.trace_id, NEW.span_id
+    )
+    ON CONFLICT (org_id, provider, model) DO UPDATE SET
+        requests_limit = EXCLUDED.requests_limit,
+        requests_remaining = EXCLUDED.requests_remaining,
+        requests_reset_at = EXCLUDED.requests_reset_at,
+        tokens_limit = EXCLUDED.tokens_limit,
+        tokens_remaining = EXCLUDED.tokens_remaining,
+        tokens_reset_at = EXCLUDED.tokens_reset_at,
+        observed_at = EXCLUDED.observed_at,
+        trace_id = EXCLUDED.trace_id,
+        span_id = EXCLUDED.span_id
+    -- Late spans do not overwrite newer state
+    WHERE provider_rate_limits.observed_at <= EXCLUDED.observed_at;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_llm_traces_rate_limits ON llm_traces;
+CREATE TRIGGER trg_llm_traces_rate_limits
+AFTER INSERT ON llm_traces
+FOR EACH ROW EXECUTE FUNCTION llm_traces_upsert_rate_limits();
+
+-- ============================================================================
+-- Row-Level Security (see migration 019)
+-- ============================================================================
+
+ALTER TABLE provider_rate_limits ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS service_access ON provider_rate_limits;
+CREATE POLICY service_access ON provider_rate_limits
+    USING (true) WITH CHECK (true);
+
+DROP POLICY IF EXISTS tenant_isolation ON provider_rate_limits;
+CREATE POLICY tenant_isolation ON provider_rate_limits
+    AS RESTRICTIVE
+    TO llm_observatory_tenant
+    USING (org_id = app_current_org_id())
+    WITH CHECK (org_id = app_current_org_id());
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE provider_rate_limits IS 'Latest provider rate-limit state per organization, provider and model, from the llm_observatory.rate_limit.* span attributes';
+COMMENT ON COLUMN provider_rate_limits.observed_at IS 'Start time of the span that reported the state';
+COMMENT ON COLUMN provider_rate_limits.alerted_at IS 'When the last approaching-rate-limit alert was raised';
+COMMENT ON FUNCTION timestamptz_attribute(JSONB, TEXT) IS 'Timestamp attribute value, or NULL when missing or not a timestamp';
@@ -0,0 +1,62 @@
+-- Migration 041: Provider Queue Time
+--
+-- This migration makes the split of LLM calls into provider queue time and
+-- execution time queryable:
+-- - queue_ms and execution_ms columns on llm_traces
+-- - Trigger filling them from the span attributes on insert
+-- - Backfill of the uncompressed (last 7 days) rows
+--
+-- The collector's queue timing processor derives the times from the span
+-- events recorded by the SDK (llm.request.sent, llm.response.first_byte,
+-- llm.first_token) and records llm_observatory.timing.queue_ms (request sent
+-- to first byte) and llm_observatory.timing.execution_ms (first byte to
+-- completion). Rows of calls without those events keep NULL columns.
+
+-- ============================================================================
+-- Columns
+-- ============================================================================
+
+ALTER TABLE llm_traces ADD COLUMN IF NOT EXISTS queue_ms INTEGER;
+ALTER TABLE llm_traces ADD COLUMN IF NOT EXISTS execution_ms INTEGER;
+
+-- ============================================================================
+-- Extraction
+-- ============================================================================
+
+CREATE OR REPLACE FUNCTION llm_traces_set_queue_timing()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.queue_ms IS NULL THEN
+        NEW.queue_ms :=
+            numeric_attribute(NEW.attributes, 'llm_observatory.timing.queue_ms')::INTEGER;
+    END IF;
+    IF NEW.execution_ms IS NULL THEN
+        NEW.execution_ms :=
+            numeric_attribute(NEW.attributes, 'llm_observatory.timing.execution_ms')::INTEGER;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_llm_traces_queue_timing ON llm_traces;
+CREATE TRIGGER trg_llm_traces_queue_timing
+BEFORE INSERT ON llm_traces
+FOR EACH ROW EXECUTE FUNCTION llm_traces_set_queue_timing();
+
+-- ============================================================================
+-- Backfill
+-- ============================================================================
+
+-- Chunks older than 7 days are compressed (005_retention_policies.sql)
+UPDATE llm_traces
+SET queue_ms = numeric_attribute(attributes, 'llm_observatory.timing.queue_ms')::INTEGER,
+    execution_ms = numeric_attribute(attributes, 'llm_observatory.timing.execution_ms')::INTEGER
+WHERE ts >= NOW() - INTERVAL '7 days'
+  AND attributes ? 'llm_observatory.timing.queue_ms';
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON COLUMN llm_traces.queue_ms IS 'Request sent to first response byte: provider queuing and prompt processing';
+COMMENT ON COLUMN llm_traces.execution_ms IS 'First response byte to completion: generation and transfer';
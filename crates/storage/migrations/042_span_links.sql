@@ -0,0 +1,37 @@
+-- Migration 042: Span Links
+--
+-- This migration makes span links queryable, so traces related by links
+-- rather than parent/child relationships (agent fan-out, batch jobs, retries)
+-- can be navigated in both directions:
+-- - GIN indexes on trace_spans.links and llm_traces.links
+--
+-- Links are stored as a JSON array of {trace_id, span_id, attributes} with
+-- hex-encoded IDs. Spans linking to a trace are found with containment:
+--
+--   SELECT * FROM trace_spans WHERE links @> '[{"trace_id": "0af76519..."}]';
+--
+-- Only containment is needed, so the indexes use the smaller jsonb_path_ops
+-- operator class. The trace writer no longer compresses links; rows written
+-- compressed before this migration are still read back but never match.
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+-- trace_spans is created by the storage writers' schema (see 010)
+DO $$
+BEGIN
+    IF to_regclass('trace_spans') IS NOT NULL THEN
+        CREATE INDEX IF NOT EXISTS idx_trace_spans_links_gin
+            ON trace_spans USING GIN (links jsonb_path_ops);
+    END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_llm_traces_links_gin
+ON llm_traces USING GIN (links jsonb_path_ops);
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON COLUMN llm_traces.links IS 'Links to causally related spans: [{trace_id, span_id, attributes}]';
@@ -0,0 +1,40 @@
+-- Migration 043: Trace Events
+--
+-- This migration makes span events (guardrail hits, tool results, stream
+-- chunks) queryable across the spans of a trace:
+-- - trace_events table, one row per span event
+-- - Index for reading the events of a span in time order
+--
+-- The trace writer parses the events JSON array of each newly inserted span
+-- into rows of this table; trace_spans.events is kept as written. Events of
+-- spans written before this migration are not backfilled, since older span
+-- events may be compressed or encrypted.
+
+-- ============================================================================
+-- Tables
+-- ============================================================================
+
+-- span_id references trace_spans.id; a foreign key is not possible because
+-- the primary key of the partitioned trace_spans includes start_time (see 009)
+CREATE TABLE IF NOT EXISTS trace_events (
+    id UUID PRIMARY KEY,
+    span_id UUID NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    timestamp TIMESTAMPTZ NOT NULL,
+    attributes JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_trace_events_span_id ON trace_events (span_id, timestamp);
+CREATE INDEX IF NOT EXISTS idx_trace_events_name ON trace_events (name, timestamp DESC);
+
+-- ============================================================================
+-- Comments
+-- ============================================================================
+
+COMMENT ON TABLE trace_events IS 'Span events parsed from trace_spans.events at write time';
+COMMENT ON COLUMN trace_events.span_id IS 'trace_spans.id of the span the event was recorded on';
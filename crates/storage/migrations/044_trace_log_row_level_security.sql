@@ -0,0 +1,85 @@
+-- Migration 044: Row-Level Security on Traces, Spans and Logs
+--
+-- This migration extends the tenant isolation of migration 019 to the
+-- tables holding tenant data:
+-- - traces and logs, scoped by the org_id of their attributes or resource
+-- - trace_spans, scoped by their own org_id attribute or their trace's
+-- - trace_events, visible when their span is
+--
+-- None of these tables has an org_id column; the organization is the
+-- `org_id` attribute, as the analytics API filters by it. As in 019, only
+-- database roles granted llm_observatory_tenant are restricted, and they see
+-- no rows until StoragePool::with_tenant (or the analytics API's equivalent)
+-- sets app.org_id for the transaction.
+--
+-- The llm_traces, llm_metrics and llm_logs hypertables stay uncovered:
+-- TimescaleDB does not support RLS together with compression.
+
+-- ============================================================================
+-- Tenant Isolation Policies
+-- ============================================================================
+
+-- traces is created by the storage writers' schema rather than a migration,
+-- so tables that do not exist are skipped
+DO $$
+DECLARE
+    rls RECORD;
+BEGIN
+    FOR rls IN
+        SELECT * FROM (VALUES
+            ('traces',
+             $q$COALESCE(attributes->>'org_id', resource_attributes->>'org_id')
+                = app_current_org_id()$q$),
+            ('trace_spans',
+             $q$COALESCE(
+                attributes->>'org_id',
+                (SELECT COALESCE(t.attributes->>'org_id', t.resource_attributes->>'org_id')
+                 FROM traces t
+                 WHERE t.id = trace_spans.trace_id)
+             ) = app_current_org_id()$q$),
+            ('trace_events',
+             $q$EXISTS (SELECT 1 FROM trace_spans s WHERE s.id = trace_events.span_id)$q$),
+            ('logs',
+             $q$COALESCE(attributes->>'org_id', resource_attributes->>'org_id')
+                = app_current_org_id()$q$)
+        ) AS p(tbl, expr)
+    LOOP
+        CONTINUE WHEN to_regclass(rls.tbl) IS NULL;
+
+        EXECUTE format('ALTER TABLE %I ENABLE ROW LEVEL SECURITY', rls.tbl);
+
+        -- Everyone keeps access unless a restrictive policy applies
+        EXECUTE format('DROP POLICY IF EXISTS service_access ON %I', rls.tbl);
+        EXECUTE format(
+            'CREATE POLICY service_access ON %I USING (true) WITH CHECK (true)',
+            rls.tbl
+        );
+
+        -- Tenant roles are limited to the current organization
+        EXECUTE format('DROP POLICY IF EXISTS tenant_isolation ON %I', rls.tbl);
+        EXECUTE format(
+            'CREATE POLICY tenant_isolation ON %I AS RESTRICTIVE TO llm_observatory_tenant '
+            'USING (%s) WITH CHECK (%s)',
+            rls.tbl, rls.expr, rls.expr
+        );
+    END LOOP;
+END
+$$;
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+
+-- The policies, like the analytics API's filters, select by organization
+DO $$
+BEGIN
+    IF to_regclass('traces') IS NOT NULL THEN
+        CREATE INDEX IF NOT EXISTS idx_traces_org_id
+            ON traces (COALESCE(attributes->>'org_id', resource_attributes->>'org_id'));
+    END IF;
+    IF to_regclass('logs') IS NOT NULL THEN
+        CREATE INDEX IF NOT EXISTS idx_logs_org_id_timestamp
+            ON logs (COALESCE(attributes->>'org_id', resource_attributes->>'org_id'), timestamp DESC);
+    END IF;
+END
+$$;
@@ -0,0 +1,136 @@
+-- SQLite Schema: Traces, Metrics and Logs
+--
+-- Schema of the SQLite backend (llm_observatory_storage::backend::sqlite),
+-- applied on every connect. It mirrors the PostgreSQL tables read by the
+-- repositories, with the same unique keys:
+-- - traces: trace_id
+-- - trace_spans: (span_id, start_time)
+-- - metrics: (name, service_name)
+--
+-- Types follow sqlx's SQLite encoding: UUIDs are 16-byte BLOBs, timestamps
+-- RFC 3339 TEXT in UTC (which sorts chronologically), JSON TEXT.
+
+-- ============================================================================
+-- Traces
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS traces (
+    id BLOB PRIMARY KEY,
+    trace_id TEXT NOT NULL UNIQUE,
+    service_name TEXT NOT NULL,
+    start_time TEXT NOT NULL,
+    end_time TEXT,
+    duration_us INTEGER,
+    status TEXT NOT NULL,
+    status_message TEXT,
+    root_span_name TEXT,
+    attributes TEXT NOT NULL DEFAULT '{}',
+    resource_attributes TEXT NOT NULL DEFAULT '{}',
+    span_count INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_traces_start_time ON traces(start_time);
+CREATE INDEX IF NOT EXISTS idx_traces_service_name ON traces(service_name, start_time);
+
+CREATE TABLE IF NOT EXISTS trace_spans (
+    id BLOB PRIMARY KEY,
+    trace_id BLOB NOT NULL,
+    span_id TEXT NOT NULL,
+    parent_span_id TEXT,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    service_name TEXT NOT NULL,
+    start_time TEXT NOT NULL,
+    end_time TEXT,
+    duration_us INTEGER,
+    status TEXT NOT NULL,
+    status_message TEXT,
+    attributes TEXT NOT NULL DEFAULT '{}',
+    events TEXT,
+    links TEXT,
+    created_at TEXT NOT NULL,
+    UNIQUE (span_id, start_time)
+);
+
+CREATE INDEX IF NOT EXISTS idx_trace_spans_trace_id ON trace_spans(trace_id, start_time);
+CREATE INDEX IF NOT EXISTS idx_trace_spans_start_time ON trace_spans(start_time);
+
+CREATE TABLE IF NOT EXISTS trace_events (
+    id BLOB PRIMARY KEY,
+    span_id BLOB NOT NULL,
+    name TEXT NOT NULL,
+    timestamp TEXT NOT NULL,
+    attributes TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_trace_events_span_id ON trace_events(span_id, timestamp);
+CREATE INDEX IF NOT EXISTS idx_trace_events_timestamp ON trace_events(timestamp);
+
+-- ============================================================================
+-- Metrics
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS metrics (
+    id BLOB PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT,
+    unit TEXT,
+    metric_type TEXT NOT NULL,
+    service_name TEXT NOT NULL,
+    attributes TEXT NOT NULL DEFAULT '{}',
+    resource_attributes TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    UNIQUE (name, service_name)
+);
+
+CREATE TABLE IF NOT EXISTS metric_data_points (
+    id BLOB PRIMARY KEY,
+    metric_id BLOB NOT NULL REFERENCES metrics(id) ON DELETE CASCADE,
+    timestamp TEXT NOT NULL,
+    value REAL,
+    count INTEGER,
+    sum REAL,
+    min REAL,
+    max REAL,
+    buckets TEXT,
+    quantiles TEXT,
+    exemplars TEXT,
+    attributes TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_metric_points_metric_timestamp
+ON metric_data_points(metric_id, timestamp);
+CREATE INDEX IF NOT EXISTS idx_metric_points_timestamp ON metric_data_points(timestamp);
+
+-- ============================================================================
+-- Logs
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS log_records (
+    id BLOB PRIMARY KEY,
+    timestamp TEXT NOT NULL,
+    observed_timestamp TEXT NOT NULL,
+    severity_number INTEGER NOT NULL,
+    severity_text TEXT NOT NULL,
+    body TEXT NOT NULL,
+    service_name TEXT NOT NULL,
+    trace_id TEXT,
+    span_id TEXT,
+    trace_flags INTEGER,
+    attributes TEXT NOT NULL DEFAULT '{}',
+    resource_attributes TEXT NOT NULL DEFAULT '{}',
+    scope_name TEXT,
+    scope_version TEXT,
+    scope_attributes TEXT,
+    pattern_id TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_log_records_timestamp ON log_records(timestamp);
+CREATE INDEX IF NOT EXISTS idx_log_records_service_name ON log_records(service_name, timestamp);
+CREATE INDEX IF NOT EXISTS idx_log_records_trace_id ON log_records(trace_id);
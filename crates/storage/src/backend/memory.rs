@@ -0,0 +1,617 @@
+//! In-memory storage backend.
+//!
+//! [`InMemoryBackend`] keeps traces, metrics and logs in vectors and applies
+//! repository filters, ordering and pagination in Rust. Writes are visible
+//! immediately (there is nothing to flush) and are lost when the last clone
+//! is dropped.
+//!
+//! Rows written twice follow the PostgreSQL unique keys: a trace with a known
+//! `trace_id`, a span with a known `span_id` and start time, or a metric with
+//! a known name and service replaces the stored row (traces and metrics keep
+//! their stored `id`). The PostgreSQL trace writer merges traces and spans by
+//! default instead. Payloads are stored uncompressed.
+
+use super::{LogRead, LogWrite, MetricRead, MetricWrite, TraceRead, TraceWrite};
+use crate::error::{StorageError, StorageResult};
+use crate::models::{LogRecord, Metric, MetricDataPoint, Trace, TraceEvent, TraceSpan};
+use crate::repositories::log::{LogFilters, SortOrder};
+use crate::repositories::metric::MetricFilters;
+use crate::repositories::trace::TraceFilters;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::cmp::Reverse;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Rows held by an [`InMemoryBackend`].
+#[derive(Debug, Default)]
+struct MemoryState {
+    traces: Vec<Trace>,
+    spans: Vec<TraceSpan>,
+    events: Vec<TraceEvent>,
+    metrics: Vec<Metric>,
+    data_points: Vec<MetricDataPoint>,
+    logs: Vec<LogRecord>,
+}
+
+/// Vector-backed implementation of the writer and repository traits.
+///
+/// Clones share the same data.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryBackend {
+    state: Arc<RwLock<MemoryState>>,
+}
+
+impl InMemoryBackend {
+    /// Create an empty backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All stored traces, in write order.
+    pub async fn traces(&self) -> Vec<Trace> {
+        self.state.read().await.traces.clone()
+    }
+
+    /// All stored spans, in write order.
+    pub async fn spans(&self) -> Vec<TraceSpan> {
+        self.state.read().await.spans.clone()
+    }
+
+    /// All stored metric definitions, in write order.
+    pub async fn metrics(&self) -> Vec<Metric> {
+        self.state.read().await.metrics.clone()
+    }
+
+    /// All stored data points, in write order.
+    pub async fn data_points(&self) -> Vec<MetricDataPoint> {
+        self.state.read().await.data_points.clone()
+    }
+
+    /// All stored log records, in write order.
+    pub async fn logs(&self) -> Vec<LogRecord> {
+        self.state.read().await.logs.clone()
+    }
+
+    /// Remove all data.
+    pub async fn clear(&self) {
+        *self.state.write().await = MemoryState::default();
+    }
+}
+
+/// Whether `value` contains `pattern`, like PostgreSQL's JSONB `@>`.
+pub(super) fn json_contains(value: &serde_json::Value, pattern: &serde_json::Value) -> bool {
+    use serde_json::Value;
+
+    match (value, pattern) {
+        (Value::Object(value), Value::Object(pattern)) => pattern
+            .iter()
+            .all(|(key, p)| value.get(key).is_some_and(|v| json_contains(v, p))),
+        (Value::Array(value), Value::Array(pattern)) => pattern
+            .iter()
+            .all(|p| value.iter().any(|v| json_contains(v, p))),
+        (value, pattern) => value == pattern,
+    }
+}
+
+/// Apply `OFFSET` and `LIMIT`.
+pub(super) fn paginate<T>(rows: Vec<T>, offset: Option<i64>, limit: Option<i64>) -> Vec<T> {
+    let offset = offset.unwrap_or(0).max(0) as usize;
+    let limit = limit.map_or(usize::MAX, |limit| limit.max(0) as usize);
+    rows.into_iter().skip(offset).take(limit).collect()
+}
+
+fn trace_matches(trace: &Trace, filters: &TraceFilters) -> bool {
+    filters
+        .service_name
+        .as_ref()
+        .map_or(true, |service| &trace.service_name == service)
+        && filters
+            .status
+            .as_ref()
+            .map_or(true, |status| &trace.status == status)
+        && filters
+            .start_time
+            .map_or(true, |start| trace.start_time >= start)
+        && filters.end_time.map_or(true, |end| trace.start_time <= end)
+        && filters
+            .min_duration_us
+            .map_or(true, |min| trace.duration_us.is_some_and(|d| d >= min))
+        && filters
+            .max_duration_us
+            .map_or(true, |max| trace.duration_us.is_some_and(|d| d <= max))
+        && filters.attributes.as_ref().map_or(true, |attributes| {
+            json_contains(&trace.attributes, attributes)
+        })
+}
+
+fn metric_matches(metric: &Metric, filters: &MetricFilters) -> bool {
+    filters
+        .service_name
+        .as_ref()
+        .map_or(true, |service| &metric.service_name == service)
+        && filters
+            .metric_type
+            .as_ref()
+            .map_or(true, |metric_type| &metric.metric_type == metric_type)
+        && filters
+            .name_pattern
+            .as_ref()
+            .map_or(true, |pattern| metric.name.contains(pattern.as_str()))
+}
+
+fn log_matches(log: &LogRecord, filters: &LogFilters) -> bool {
+    filters
+        .service_name
+        .as_ref()
+        .map_or(true, |service| &log.service_name == service)
+        && filters
+            .min_severity
+            .map_or(true, |min| log.severity_number >= min)
+        && filters
+            .trace_id
+            .as_ref()
+            .map_or(true, |trace_id| log.trace_id.as_ref() == Some(trace_id))
+        && filters
+            .span_id
+            .as_ref()
+            .map_or(true, |span_id| log.span_id.as_ref() == Some(span_id))
+        && filters
+            .start_time
+            .map_or(true, |start| log.timestamp >= start)
+        && filters.end_time.map_or(true, |end| log.timestamp <= end)
+        && filters.search_query.as_ref().map_or(true, |query| {
+            log.body.to_lowercase().contains(&query.to_lowercase())
+        })
+}
+
+#[async_trait]
+impl TraceWrite for InMemoryBackend {
+    async fn write_traces(&self, traces: Vec<Trace>) -> StorageResult<()> {
+        let mut state = self.state.write().await;
+        for trace in traces {
+            match state
+                .traces
+                .iter_mut()
+                .find(|t| t.trace_id == trace.trace_id)
+            {
+                Some(stored) => {
+                    *stored = Trace {
+                        id: stored.id,
+                        ..trace
+                    }
+                }
+                None => state.traces.push(trace),
+            }
+        }
+        Ok(())
+    }
+
+    async fn write_spans(&self, spans: Vec<TraceSpan>) -> StorageResult<()> {
+        let mut state = self.state.write().await;
+        for span in spans {
+            match state
+                .spans
+                .iter_mut()
+                .find(|s| s.span_id == span.span_id && s.start_time == span.start_time)
+            {
+                Some(stored) => *stored = span,
+                None => state.spans.push(span),
+            }
+        }
+        Ok(())
+    }
+
+    async fn write_event(&self, event: TraceEvent) -> StorageResult<()> {
+        self.state.write().await.events.push(event);
+        Ok(())
+    }
+
+    async fn flush(&self) -> StorageResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MetricWrite for InMemoryBackend {
+    async fn write_metrics(&self, metrics: Vec<Metric>) -> StorageResult<()> {
+        let mut state = self.state.write().await;
+        for metric in metrics {
+            match state
+                .metrics
+                .iter_mut()
+                .find(|m| m.name == metric.name && m.service_name == metric.service_name)
+            {
+                Some(stored) => {
+                    *stored = Metric {
+                        id: stored.id,
+                        ..metric
+                    }
+                }
+                None => state.metrics.push(metric),
+            }
+        }
+        Ok(())
+    }
+
+    async fn write_data_points(&self, data_points: Vec<MetricDataPoint>) -> StorageResult<()> {
+        self.state.write().await.data_points.extend(data_points);
+        Ok(())
+    }
+
+    async fn flush(&self) -> StorageResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LogWrite for InMemoryBackend {
+    async fn write_logs(&self, logs: Vec<LogRecord>) -> StorageResult<()> {
+        self.state.write().await.logs.extend(logs);
+        Ok(())
+    }
+
+    async fn flush(&self) -> StorageResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TraceRead for InMemoryBackend {
+    async fn get_by_id(&self, id: Uuid) -> StorageResult<Trace> {
+        let state = self.state.read().await;
+        state
+            .traces
+            .iter()
+            .find(|t| t.id == id)
+            .cloned()
+            .ok_or_else(|| StorageError::not_found(format!("Trace {} not found", id)))
+    }
+
+    async fn get_by_trace_id(&self, trace_id: &str) -> StorageResult<Trace> {
+        let state = self.state.read().await;
+        state
+            .traces
+            .iter()
+            .find(|t| t.trace_id == trace_id)
+            .cloned()
+            .ok_or_else(|| StorageError::not_found(format!("Trace {} not found", trace_id)))
+    }
+
+    async fn list(&self, filters: TraceFilters) -> StorageResult<Vec<Trace>> {
+        let state = self.state.read().await;
+        let mut traces: Vec<Trace> = state
+            .traces
+            .iter()
+            .filter(|t| trace_matches(t, &filters))
+            .cloned()
+            .collect();
+        traces.sort_by_key(|t| Reverse(t.start_time));
+        Ok(paginate(traces, filters.offset, filters.limit))
+    }
+
+    async fn get_spans(&self, trace_id: Uuid) -> StorageResult<Vec<TraceSpan>> {
+        let state = self.state.read().await;
+        let mut spans: Vec<TraceSpan> = state
+            .spans
+            .iter()
+            .filter(|s| s.trace_id == trace_id)
+            .cloned()
+            .collect();
+        spans.sort_by_key(|s| s.start_time);
+        Ok(spans)
+    }
+
+    async fn get_events(&self, span_id: Uuid) -> StorageResult<Vec<TraceEvent>> {
+        let state = self.state.read().await;
+        let mut events: Vec<TraceEvent> = state
+            .events
+            .iter()
+            .filter(|e| e.span_id == span_id)
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| e.timestamp);
+        Ok(events)
+    }
+}
+
+#[async_trait]
+impl MetricRead for InMemoryBackend {
+    async fn get_by_id(&self, id: Uuid) -> StorageResult<Metric> {
+        let state = self.state.read().await;
+        state
+            .metrics
+            .iter()
+            .find(|m| m.id == id)
+            .cloned()
+            .ok_or_else(|| StorageError::not_found(format!("Metric {} not found", id)))
+    }
+
+    async fn get_by_name(&self, name: &str, service_name: &str) -> StorageResult<Metric> {
+        let state = self.state.read().await;
+        state
+            .metrics
+            .iter()
+            .find(|m| m.name == name && m.service_name == service_name)
+            .cloned()
+            .ok_or_else(|| {
+                StorageError::not_found(format!("Metric {} of {} not found", name, service_name))
+            })
+    }
+
+    async fn list(&self, filters: MetricFilters) -> StorageResult<Vec<Metric>> {
+        let state = self.state.read().await;
+        let mut metrics: Vec<Metric> = state
+            .metrics
+            .iter()
+            .filter(|m| metric_matches(m, &filters))
+            .cloned()
+            .collect();
+        metrics.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(paginate(metrics, filters.offset, filters.limit))
+    }
+
+    async fn get_data_points(
+        &self,
+        metric_id: Uuid,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> StorageResult<Vec<MetricDataPoint>> {
+        let state = self.state.read().await;
+        let mut points: Vec<MetricDataPoint> = state
+            .data_points
+            .iter()
+            .filter(|p| {
+                p.metric_id == metric_id && p.timestamp >= start_time && p.timestamp <= end_time
+            })
+            .cloned()
+            .collect();
+        points.sort_by_key(|p| p.timestamp);
+        Ok(points)
+    }
+
+    async fn get_metrics(
+        &self,
+        name: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> StorageResult<Vec<MetricDataPoint>> {
+        let state = self.state.read().await;
+        let metric_ids: Vec<Uuid> = state
+            .metrics
+            .iter()
+            .filter(|m| m.name == name)
+            .map(|m| m.id)
+            .collect();
+        let mut points: Vec<MetricDataPoint> = state
+            .data_points
+            .iter()
+            .filter(|p| {
+                metric_ids.contains(&p.metric_id)
+                    && p.timestamp >= start_time
+                    && p.timestamp <= end_time
+            })
+            .cloned()
+            .collect();
+        points.sort_by_key(|p| p.timestamp);
+        Ok(points)
+    }
+}
+
+#[async_trait]
+impl LogRead for InMemoryBackend {
+    async fn get_by_id(&self, id: Uuid) -> StorageResult<LogRecord> {
+        let state = self.state.read().await;
+        state
+            .logs
+            .iter()
+            .find(|l| l.id == id)
+            .cloned()
+            .ok_or_else(|| StorageError::not_found(format!("Log record {} not found", id)))
+    }
+
+    async fn list(&self, filters: LogFilters) -> StorageResult<Vec<LogRecord>> {
+        let state = self.state.read().await;
+        let mut logs: Vec<LogRecord> = state
+            .logs
+            .iter()
+            .filter(|l| log_matches(l, &filters))
+            .cloned()
+            .collect();
+        match filters.sort_order {
+            SortOrder::Asc => logs.sort_by_key(|l| l.timestamp),
+            SortOrder::Desc => logs.sort_by_key(|l| Reverse(l.timestamp)),
+        }
+        Ok(paginate(logs, filters.offset, filters.limit))
+    }
+
+    async fn get_logs_by_trace(&self, trace_id: &str) -> StorageResult<Vec<LogRecord>> {
+        let state = self.state.read().await;
+        let mut logs: Vec<LogRecord> = state
+            .logs
+            .iter()
+            .filter(|l| l.trace_id.as_deref() == Some(trace_id))
+            .cloned()
+            .collect();
+        logs.sort_by_key(|l| l.timestamp);
+        Ok(logs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+
+    fn trace(trace_id: &str, service: &str, start: DateTime<Utc>) -> Trace {
+        Trace::new(trace_id.to_string(), service.to_string(), start)
+    }
+
+    fn log(body: &str, severity_number: i32, timestamp: DateTime<Utc>) -> LogRecord {
+        LogRecord {
+            id: Uuid::new_v4(),
+            timestamp,
+            observed_timestamp: timestamp,
+            severity_number,
+            severity_text: LogRecord::parse_level(severity_number).to_string(),
+            body: body.to_string(),
+            service_name: "api".to_string(),
+            trace_id: Some("trace-1".to_string()),
+            span_id: None,
+            trace_flags: None,
+            attributes: json!({}),
+            resource_attributes: json!({}),
+            scope_name: None,
+            scope_version: None,
+            scope_attributes: None,
+            pattern_id: None,
+            created_at: timestamp,
+        }
+    }
+
+    #[test]
+    fn test_json_contains() {
+        let value = json!({"env": "prod", "tags": ["a", "b"], "model": {"name": "gpt-4"}});
+        assert!(json_contains(&value, &json!({"env": "prod"})));
+        assert!(json_contains(
+            &value,
+            &json!({"tags": ["b"], "model": {"name": "gpt-4"}})
+        ));
+        assert!(!json_contains(&value, &json!({"env": "dev"})));
+        assert!(!json_contains(&value, &json!({"region": "eu"})));
+    }
+
+    #[tokio::test]
+    async fn test_traces_filtering_and_ordering() {
+        let backend = InMemoryBackend::new();
+        let now = Utc::now();
+
+        let mut slow = trace("t1", "api", now - Duration::minutes(2));
+        slow.duration_us = Some(5_000);
+        slow.attributes = json!({"gen_ai.system": "openai"});
+        let mut fast = trace("t2", "api", now - Duration::minutes(1));
+        fast.duration_us = Some(100);
+        let other = trace("t3", "worker", now);
+        backend.write_traces(vec![slow, fast, other]).await.unwrap();
+
+        let api = TraceRead::list(
+            &backend,
+            TraceFilters {
+                service_name: Some("api".to_string()),
+                ..TraceFilters::default()
+            },
+        )
+        .await
+        .unwrap();
+        let ids: Vec<&str> = api.iter().map(|t| t.trace_id.as_str()).collect();
+        assert_eq!(ids, vec!["t2", "t1"]);
+
+        let filtered = TraceRead::list(
+            &backend,
+            TraceFilters {
+                min_duration_us: Some(1_000),
+                attributes: Some(json!({"gen_ai.system": "openai"})),
+                ..TraceFilters::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].trace_id, "t1");
+
+        let page = TraceRead::list(
+            &backend,
+            TraceFilters {
+                limit: Some(1),
+                offset: Some(1),
+                ..TraceFilters::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(page[0].trace_id, "t2");
+    }
+
+    #[tokio::test]
+    async fn test_trace_rewrite_keeps_id() {
+        let backend = InMemoryBackend::new();
+        let first = trace("t1", "api", Utc::now());
+        let id = first.id;
+        backend.write_trace(first).await.unwrap();
+
+        let mut update = trace("t1", "api", Utc::now());
+        update.status = "error".to_string();
+        backend.write_trace(update).await.unwrap();
+
+        let stored = backend.get_by_trace_id("t1").await.unwrap();
+        assert_eq!(stored.id, id);
+        assert_eq!(stored.status, "error");
+        assert_eq!(backend.traces().await.len(), 1);
+
+        let span = TraceSpan::new(
+            id,
+            "s1".to_string(),
+            "chat".to_string(),
+            "api".to_string(),
+            Utc::now(),
+        );
+        backend.write_span(span).await.unwrap();
+        let (_, spans) = backend.get_trace_by_id("t1").await.unwrap();
+        assert_eq!(spans.len(), 1);
+
+        let missing = TraceRead::get_by_id(&backend, Uuid::new_v4()).await;
+        assert!(missing.unwrap_err().is_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_logs_filtering_and_sort_order() {
+        let backend = InMemoryBackend::new();
+        let now = Utc::now();
+        backend
+            .write_logs(vec![
+                log("request started", 9, now - Duration::seconds(2)),
+                log("Upstream TIMEOUT", 17, now - Duration::seconds(1)),
+                log("request finished", 9, now),
+            ])
+            .await
+            .unwrap();
+
+        let errors = LogRead::list(
+            &backend,
+            LogFilters {
+                min_severity: Some(17),
+                ..LogFilters::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(errors.len(), 1);
+
+        let search = LogRead::list(
+            &backend,
+            LogFilters {
+                search_query: Some("timeout".to_string()),
+                ..LogFilters::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(search[0].body, "Upstream TIMEOUT");
+
+        let ascending = LogRead::list(
+            &backend,
+            LogFilters {
+                sort_order: SortOrder::Asc,
+                ..LogFilters::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(ascending[0].body, "request started");
+
+        let by_trace = backend.get_logs_by_trace("trace-1").await.unwrap();
+        assert_eq!(by_trace[0].body, "request started");
+    }
+}
@@ -0,0 +1,314 @@
+//! Pluggable storage backends.
+//!
+//! Writers and repositories are reached through the traits of this module,
+//! so code that records or reads observability data does not depend on
+//! where it is kept:
+//!
+//! - [`TraceWrite`], [`MetricWrite`], [`LogWrite`]: writing (implemented by
+//!   the buffered PostgreSQL writers)
+//! - [`TraceRead`], [`MetricRead`], [`LogRead`]: querying (implemented by the
+//!   PostgreSQL repositories)
+//!
+//! [`InMemoryBackend`] implements all of them over vectors, for unit tests
+//! and local demos that should not need a database. With the `sqlite`
+//! feature, `SqliteBackend` implements them on a local SQLite file for
+//! single-node and edge deployments. [`Storage::connect`] picks the backend
+//! from [`StorageConfig::backend`](crate::StorageConfig).
+//!
+//! # Example
+//!
+//! ```
+//! use llm_observatory_storage::backend::Storage;
+//! use llm_observatory_storage::models::Trace;
+//! use llm_observatory_storage::StorageConfig;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let storage = Storage::connect(StorageConfig::in_memory()).await?;
+//!
+//! let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736".to_string();
+//! let trace = Trace::new(trace_id.clone(), "api".to_string(), chrono::Utc::now());
+//! storage.trace_writer().write_trace(trace).await?;
+//! storage.flush().await?;
+//!
+//! let found = storage.trace_repository().get_by_trace_id(&trace_id).await?;
+//! assert_eq!(found.service_name, "api");
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod memory;
+mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+pub use memory::InMemoryBackend;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteBackend;
+
+use crate::config::{SqliteConfig, StorageBackend, StorageConfig};
+use crate::error::StorageResult;
+use crate::models::{LogRecord, Metric, MetricDataPoint, Trace, TraceEvent, TraceSpan};
+use crate::pool::StoragePool;
+use crate::repositories::log::LogFilters;
+use crate::repositories::metric::MetricFilters;
+use crate::repositories::trace::TraceFilters;
+use crate::repositories::{LogRepository, MetricRepository, TraceRepository};
+use crate::writers::{LogWriter, MetricWriter, TraceWriter};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Writing traces, spans and events.
+///
+/// Writes may be buffered until [`flush`](TraceWrite::flush).
+#[async_trait]
+pub trait TraceWrite: Send + Sync {
+    /// Write traces.
+    async fn write_traces(&self, traces: Vec<Trace>) -> StorageResult<()>;
+
+    /// Write spans.
+    async fn write_spans(&self, spans: Vec<TraceSpan>) -> StorageResult<()>;
+
+    /// Write a span event.
+    async fn write_event(&self, event: TraceEvent) -> StorageResult<()>;
+
+    /// Write all buffered records.
+    async fn flush(&self) -> StorageResult<()>;
+
+    /// Write a single trace.
+    async fn write_trace(&self, trace: Trace) -> StorageResult<()> {
+        self.write_traces(vec![trace]).await
+    }
+
+    /// Write a single span.
+    async fn write_span(&self, span: TraceSpan) -> StorageResult<()> {
+        self.write_spans(vec![span]).await
+    }
+}
+
+/// Writing metric definitions and data points.
+///
+/// Writes may be buffered until [`flush`](MetricWrite::flush).
+#[async_trait]
+pub trait MetricWrite: Send + Sync {
+    /// Write metric definitions.
+    async fn write_metrics(&self, metrics: Vec<Metric>) -> StorageResult<()>;
+
+    /// Write data points.
+    async fn write_data_points(&self, data_points: Vec<MetricDataPoint>) -> StorageResult<()>;
+
+    /// Write all buffered records.
+    async fn flush(&self) -> StorageResult<()>;
+}
+
+/// Writing log records.
+///
+/// Writes may be buffered until [`flush`](LogWrite::flush).
+#[async_trait]
+pub trait LogWrite: Send + Sync {
+    /// Write log records.
+    async fn write_logs(&self, logs: Vec<LogRecord>) -> StorageResult<()>;
+
+    /// Write all buffered records.
+    async fn flush(&self) -> StorageResult<()>;
+}
+
+/// Querying traces, spans and events.
+#[async_trait]
+pub trait TraceRead: Send + Sync {
+    /// Get a trace by its ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::NotFound` if the trace doesn't exist.
+    async fn get_by_id(&self, id: Uuid) -> StorageResult<Trace>;
+
+    /// Get a trace by its trace ID (hex format).
+    async fn get_by_trace_id(&self, trace_id: &str) -> StorageResult<Trace>;
+
+    /// List traces matching `filters`, most recent first.
+    async fn list(&self, filters: TraceFilters) -> StorageResult<Vec<Trace>>;
+
+    /// Get the spans of a trace, ordered by start time.
+    async fn get_spans(&self, trace_id: Uuid) -> StorageResult<Vec<TraceSpan>>;
+
+    /// Get the events of a span, ordered by timestamp.
+    async fn get_events(&self, span_id: Uuid) -> StorageResult<Vec<TraceEvent>>;
+
+    /// Get a trace with all its spans.
+    async fn get_trace_by_id(&self, trace_id: &str) -> StorageResult<(Trace, Vec<TraceSpan>)> {
+        let trace = self.get_by_trace_id(trace_id).await?;
+        let spans = self.get_spans(trace.id).await?;
+        Ok((trace, spans))
+    }
+}
+
+/// Querying metrics and data points.
+#[async_trait]
+pub trait MetricRead: Send + Sync {
+    /// Get a metric by its ID.
+    async fn get_by_id(&self, id: Uuid) -> StorageResult<Metric>;
+
+    /// Get a metric by name and service.
+    async fn get_by_name(&self, name: &str, service_name: &str) -> StorageResult<Metric>;
+
+    /// List metrics matching `filters`, ordered by name.
+    async fn list(&self, filters: MetricFilters) -> StorageResult<Vec<Metric>>;
+
+    /// Get the data points of a metric in `[start_time, end_time]`, oldest first.
+    async fn get_data_points(
+        &self,
+        metric_id: Uuid,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> StorageResult<Vec<MetricDataPoint>>;
+
+    /// Get the data points of every metric named `name` in
+    /// `[start_time, end_time]`, oldest first.
+    async fn get_metrics(
+        &self,
+        name: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> StorageResult<Vec<MetricDataPoint>>;
+}
+
+/// Querying log records.
+#[async_trait]
+pub trait LogRead: Send + Sync {
+    /// Get a log record by its ID.
+    async fn get_by_id(&self, id: Uuid) -> StorageResult<LogRecord>;
+
+    /// List log records matching `filters`, in `filters.sort_order`.
+    async fn list(&self, filters: LogFilters) -> StorageResult<Vec<LogRecord>>;
+
+    /// Get all logs of a trace, oldest first.
+    async fn get_logs_by_trace(&self, trace_id: &str) -> StorageResult<Vec<LogRecord>>;
+}
+
+/// Writers and repositories of one backend.
+#[derive(Clone)]
+pub struct Storage {
+    pool: Option<StoragePool>,
+    trace_writer: Arc<dyn TraceWrite>,
+    metric_writer: Arc<dyn MetricWrite>,
+    log_writer: Arc<dyn LogWrite>,
+    trace_repository: Arc<dyn TraceRead>,
+    metric_repository: Arc<dyn MetricRead>,
+    log_repository: Arc<dyn LogRead>,
+}
+
+impl Storage {
+    /// Open the backend selected by `config.backend`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PostgreSQL pool cannot connect, the SQLite
+    /// database cannot be opened, or the SQLite backend was selected without
+    /// the `sqlite` feature.
+    pub async fn connect(config: StorageConfig) -> StorageResult<Self> {
+        match config.backend {
+            StorageBackend::Postgres => Ok(Self::postgres(StoragePool::new(config).await?)),
+            StorageBackend::Memory => Ok(Self::memory(InMemoryBackend::new())),
+            StorageBackend::Sqlite => Self::sqlite(config.sqlite).await,
+        }
+    }
+
+    /// Open the SQLite backend, enforcing its size cap in the background.
+    #[cfg(feature = "sqlite")]
+    async fn sqlite(config: SqliteConfig) -> StorageResult<Self> {
+        let backend = SqliteBackend::connect(config).await?;
+        if backend.config().max_size_mb.is_some() {
+            backend.start_retention();
+        }
+        Ok(Self::from_backend(backend))
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    async fn sqlite(_config: SqliteConfig) -> StorageResult<Self> {
+        Err(crate::error::StorageError::ConfigError(
+            "The sqlite backend requires the `sqlite` feature".to_string(),
+        ))
+    }
+
+    /// Use the PostgreSQL writers and repositories of `pool`.
+    pub fn postgres(pool: StoragePool) -> Self {
+        Self {
+            trace_writer: Arc::new(TraceWriter::new(pool.clone())),
+            metric_writer: Arc::new(MetricWriter::new(pool.clone())),
+            log_writer: Arc::new(LogWriter::new(pool.clone())),
+            trace_repository: Arc::new(TraceRepository::new(pool.clone())),
+            metric_repository: Arc::new(MetricRepository::new(pool.clone())),
+            log_repository: Arc::new(LogRepository::new(pool.clone())),
+            pool: Some(pool),
+        }
+    }
+
+    /// Use `backend` for all writers and repositories.
+    ///
+    /// Keep a clone of `backend` to inspect what was written.
+    pub fn memory(backend: InMemoryBackend) -> Self {
+        Self::from_backend(backend)
+    }
+
+    /// Use `backend`, implementing every writer and repository trait, for
+    /// all writers and repositories.
+    pub fn from_backend<B>(backend: B) -> Self
+    where
+        B: TraceWrite + MetricWrite + LogWrite + TraceRead + MetricRead + LogRead,
+        B: Clone + 'static,
+    {
+        Self {
+            pool: None,
+            trace_writer: Arc::new(backend.clone()),
+            metric_writer: Arc::new(backend.clone()),
+            log_writer: Arc::new(backend.clone()),
+            trace_repository: Arc::new(backend.clone()),
+            metric_repository: Arc::new(backend.clone()),
+            log_repository: Arc::new(backend),
+        }
+    }
+
+    /// Connection pool of the PostgreSQL backend.
+    pub fn pool(&self) -> Option<&StoragePool> {
+        self.pool.as_ref()
+    }
+
+    /// Trace writer.
+    pub fn trace_writer(&self) -> Arc<dyn TraceWrite> {
+        self.trace_writer.clone()
+    }
+
+    /// Metric writer.
+    pub fn metric_writer(&self) -> Arc<dyn MetricWrite> {
+        self.metric_writer.clone()
+    }
+
+    /// Log writer.
+    pub fn log_writer(&self) -> Arc<dyn LogWrite> {
+        self.log_writer.clone()
+    }
+
+    /// Trace repository.
+    pub fn trace_repository(&self) -> Arc<dyn TraceRead> {
+        self.trace_repository.clone()
+    }
+
+    /// Metric repository.
+    pub fn metric_repository(&self) -> Arc<dyn MetricRead> {
+        self.metric_repository.clone()
+    }
+
+    /// Log repository.
+    pub fn log_repository(&self) -> Arc<dyn LogRead> {
+        self.log_repository.clone()
+    }
+
+    /// Flush all writers.
+    pub async fn flush(&self) -> StorageResult<()> {
+        self.trace_writer.flush().await?;
+        self.metric_writer.flush().await?;
+        self.log_writer.flush().await
+    }
+}
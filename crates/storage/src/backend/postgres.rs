@@ -0,0 +1,129 @@
+//! Backend traits for the PostgreSQL writers and repositories.
+
+use super::{LogRead, LogWrite, MetricRead, MetricWrite, TraceRead, TraceWrite};
+use crate::error::StorageResult;
+use crate::models::{LogRecord, Metric, MetricDataPoint, Trace, TraceEvent, TraceSpan};
+use crate::repositories::log::LogFilters;
+use crate::repositories::metric::MetricFilters;
+use crate::repositories::trace::TraceFilters;
+use crate::repositories::{LogRepository, MetricRepository, TraceRepository};
+use crate::writers::{LogWriter, MetricWriter, TraceWriter};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[async_trait]
+impl TraceWrite for TraceWriter {
+    async fn write_traces(&self, traces: Vec<Trace>) -> StorageResult<()> {
+        TraceWriter::write_traces(self, traces).await
+    }
+
+    async fn write_spans(&self, spans: Vec<TraceSpan>) -> StorageResult<()> {
+        TraceWriter::write_spans(self, spans).await
+    }
+
+    async fn write_event(&self, event: TraceEvent) -> StorageResult<()> {
+        TraceWriter::write_event(self, event).await
+    }
+
+    async fn flush(&self) -> StorageResult<()> {
+        TraceWriter::flush(self).await
+    }
+}
+
+#[async_trait]
+impl MetricWrite for MetricWriter {
+    async fn write_metrics(&self, metrics: Vec<Metric>) -> StorageResult<()> {
+        MetricWriter::write_metrics(self, metrics).await
+    }
+
+    async fn write_data_points(&self, data_points: Vec<MetricDataPoint>) -> StorageResult<()> {
+        MetricWriter::write_data_points(self, data_points).await
+    }
+
+    async fn flush(&self) -> StorageResult<()> {
+        MetricWriter::flush(self).await
+    }
+}
+
+#[async_trait]
+impl LogWrite for LogWriter {
+    async fn write_logs(&self, logs: Vec<LogRecord>) -> StorageResult<()> {
+        LogWriter::write_logs(self, logs).await
+    }
+
+    async fn flush(&self) -> StorageResult<()> {
+        LogWriter::flush(self).await
+    }
+}
+
+#[async_trait]
+impl TraceRead for TraceRepository {
+    async fn get_by_id(&self, id: Uuid) -> StorageResult<Trace> {
+        TraceRepository::get_by_id(self, id).await
+    }
+
+    async fn get_by_trace_id(&self, trace_id: &str) -> StorageResult<Trace> {
+        TraceRepository::get_by_trace_id(self, trace_id).await
+    }
+
+    async fn list(&self, filters: TraceFilters) -> StorageResult<Vec<Trace>> {
+        TraceRepository::list(self, filters).await
+    }
+
+    async fn get_spans(&self, trace_id: Uuid) -> StorageResult<Vec<TraceSpan>> {
+        TraceRepository::get_spans(self, trace_id).await
+    }
+
+    async fn get_events(&self, span_id: Uuid) -> StorageResult<Vec<TraceEvent>> {
+        TraceRepository::get_events(self, span_id).await
+    }
+}
+
+#[async_trait]
+impl MetricRead for MetricRepository {
+    async fn get_by_id(&self, id: Uuid) -> StorageResult<Metric> {
+        MetricRepository::get_by_id(self, id).await
+    }
+
+    async fn get_by_name(&self, name: &str, service_name: &str) -> StorageResult<Metric> {
+        MetricRepository::get_by_name(self, name, service_name).await
+    }
+
+    async fn list(&self, filters: MetricFilters) -> StorageResult<Vec<Metric>> {
+        MetricRepository::list(self, filters).await
+    }
+
+    async fn get_data_points(
+        &self,
+        metric_id: Uuid,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> StorageResult<Vec<MetricDataPoint>> {
+        MetricRepository::get_data_points(self, metric_id, start_time, end_time).await
+    }
+
+    async fn get_metrics(
+        &self,
+        name: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> StorageResult<Vec<MetricDataPoint>> {
+        MetricRepository::get_metrics(self, name, start_time, end_time).await
+    }
+}
+
+#[async_trait]
+impl LogRead for LogRepository {
+    async fn get_by_id(&self, id: Uuid) -> StorageResult<LogRecord> {
+        LogRepository::get_by_id(self, id).await
+    }
+
+    async fn list(&self, filters: LogFilters) -> StorageResult<Vec<LogRecord>> {
+        LogRepository::list(self, filters).await
+    }
+
+    async fn get_logs_by_trace(&self, trace_id: &str) -> StorageResult<Vec<LogRecord>> {
+        LogRepository::get_logs_by_trace(self, trace_id).await
+    }
+}
@@ -0,0 +1,837 @@
+//! SQLite storage backend.
+//!
+//! [`SqliteBackend`] stores traces, metrics and logs in a single SQLite file,
+//! for single-node and air-gapped edge deployments where running PostgreSQL
+//! is not worth it. It is built with the `sqlite` feature.
+//!
+//! The schema (`migrations/sqlite/001_initial_schema.sql`) is applied on
+//! connect and mirrors the PostgreSQL tables the repositories read, with the
+//! same unique keys: rewriting a trace, span or metric updates the stored row
+//! and keeps its `id`. Writes go straight to the database, so there is
+//! nothing to flush. Trace attribute filters (JSON containment) are applied
+//! in Rust.
+//!
+//! ## Journal and Retention
+//!
+//! The database runs in WAL mode by default, so queries do not block the
+//! writer. With [`SqliteConfig::max_size_mb`] set, [`SqliteBackend::enforce_size_cap`]
+//! deletes the oldest tenth of each time-series table (traces, spans, events,
+//! data points, logs) until the data fits, then returns the freed pages to
+//! the file system. [`Storage::connect`](super::Storage::connect) starts the
+//! periodic task doing this.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm_observatory_storage::{Storage, StorageConfig};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut config = StorageConfig::sqlite("/var/lib/observatory/edge.db");
+//! config.sqlite.max_size_mb = Some(2048);
+//!
+//! let storage = Storage::connect(config).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::memory::{json_contains, paginate};
+use super::{LogRead, LogWrite, MetricRead, MetricWrite, TraceRead, TraceWrite};
+use crate::config::SqliteConfig;
+use crate::error::{StorageError, StorageResult};
+use crate::models::{LogRecord, Metric, MetricDataPoint, Trace, TraceEvent, TraceSpan};
+use crate::repositories::log::{LogFilters, SortOrder};
+use crate::repositories::metric::MetricFilters;
+use crate::repositories::trace::TraceFilters;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{
+    SqliteAutoVacuum, SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions,
+    SqliteSynchronous,
+};
+use sqlx::{QueryBuilder, Sqlite};
+use uuid::Uuid;
+
+/// Schema applied on connect.
+const SCHEMA: &str = include_str!("../../migrations/sqlite/001_initial_schema.sql");
+
+/// Tables pruned by the size cap, with their time column.
+const PRUNED_TABLES: [(&str, &str); 5] = [
+    ("traces", "start_time"),
+    ("trace_spans", "start_time"),
+    ("trace_events", "timestamp"),
+    ("metric_data_points", "timestamp"),
+    ("log_records", "timestamp"),
+];
+
+/// Upper bound on prune passes per run, so a cap below the size of the
+/// schema itself cannot loop forever.
+const MAX_PRUNE_PASSES: u32 = 20;
+
+/// Outcome of enforcing the size cap.
+#[derive(Debug, Clone, Default)]
+pub struct SizeCapReport {
+    /// Size of the stored data before pruning, in bytes
+    pub size_bytes_before: u64,
+
+    /// Size of the stored data after pruning, in bytes
+    pub size_bytes_after: u64,
+
+    /// Rows deleted
+    pub rows_deleted: u64,
+}
+
+/// Implementation of the writer and repository traits on a SQLite file.
+#[derive(Clone)]
+pub struct SqliteBackend {
+    pool: SqlitePool,
+    config: SqliteConfig,
+}
+
+impl SqliteBackend {
+    /// Open (creating if missing) the database at `config.path` and apply
+    /// the schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or the schema fails.
+    pub async fn connect(config: SqliteConfig) -> StorageResult<Self> {
+        config.validate()?;
+
+        let journal_mode = if config.wal {
+            SqliteJournalMode::Wal
+        } else {
+            SqliteJournalMode::Delete
+        };
+        let options = SqliteConnectOptions::new()
+            .filename(&config.path)
+            .create_if_missing(true)
+            .journal_mode(journal_mode)
+            .synchronous(SqliteSynchronous::Normal)
+            .busy_timeout(config.busy_timeout())
+            // Lets pruning return freed pages to the file system; only takes
+            // effect on a new database
+            .auto_vacuum(SqliteAutoVacuum::Incremental)
+            .foreign_keys(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(config.max_connections)
+            .connect_with(options)
+            .await
+            .map_err(|e| {
+                StorageError::connection(format!("Failed to open {}: {}", config.path, e))
+            })?;
+
+        sqlx::raw_sql(SCHEMA).execute(&pool).await?;
+
+        tracing::info!(path = %config.path, wal = config.wal, "SQLite backend ready");
+
+        Ok(Self { pool, config })
+    }
+
+    /// The connection pool.
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    /// The backend configuration.
+    pub fn config(&self) -> &SqliteConfig {
+        &self.config
+    }
+
+    /// Size of the stored data in bytes, excluding free pages.
+    pub async fn size_bytes(&self) -> StorageResult<u64> {
+        let page_count: i64 = sqlx::query_scalar("PRAGMA page_count")
+            .fetch_one(&self.pool)
+            .await?;
+        let freelist_count: i64 = sqlx::query_scalar("PRAGMA freelist_count")
+            .fetch_one(&self.pool)
+            .await?;
+        let page_size: i64 = sqlx::query_scalar("PRAGMA page_size")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(((page_count - freelist_count).max(0) * page_size) as u64)
+    }
+
+    /// Delete the oldest rows until the stored data fits the size cap.
+    ///
+    /// Does nothing without a cap.
+    pub async fn enforce_size_cap(&self) -> StorageResult<SizeCapReport> {
+        let size = self.size_bytes().await?;
+        let mut report = SizeCapReport {
+            size_bytes_before: size,
+            size_bytes_after: size,
+            rows_deleted: 0,
+        };
+        let Some(max_bytes) = self.config.max_size_bytes() else {
+            return Ok(report);
+        };
+
+        let mut passes = 0;
+        while report.size_bytes_after > max_bytes && passes < MAX_PRUNE_PASSES {
+            let deleted = self.prune_oldest().await?;
+            if deleted == 0 {
+                break;
+            }
+            report.rows_deleted += deleted;
+            report.size_bytes_after = self.size_bytes().await?;
+            passes += 1;
+        }
+
+        if report.rows_deleted > 0 {
+            sqlx::query("PRAGMA incremental_vacuum")
+                .execute(&self.pool)
+                .await?;
+            if self.config.wal {
+                sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+                    .execute(&self.pool)
+                    .await?;
+            }
+
+            tracing::info!(
+                "SQLite size cap: deleted {} rows, {} -> {} bytes",
+                report.rows_deleted,
+                report.size_bytes_before,
+                report.size_bytes_after
+            );
+        }
+
+        Ok(report)
+    }
+
+    /// Delete the oldest tenth of every pruned table.
+    async fn prune_oldest(&self) -> StorageResult<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut deleted = 0;
+        for (table, column) in PRUNED_TABLES {
+            let sql = format!(
+                "DELETE FROM {table} WHERE rowid IN ( \
+                 SELECT rowid FROM {table} ORDER BY {column} ASC \
+                 LIMIT (SELECT COUNT(*) / 10 + 1 FROM {table}))",
+                table = table,
+                column = column,
+            );
+            deleted += sqlx::query(&sql).execute(&mut *tx).await?.rows_affected();
+        }
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    /// Start enforcing the size cap periodically.
+    ///
+    /// Returns a handle that can be used to stop the retention task.
+    pub fn start_retention(&self) -> tokio::task::JoinHandle<()> {
+        let backend = self.clone();
+        let interval = self.config.retention_interval();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = backend.enforce_size_cap().await {
+                    tracing::error!("SQLite size cap error: {}", e);
+                }
+            }
+        })
+    }
+}
+
+/// Append `LIMIT`/`OFFSET` (SQLite needs a limit to take an offset).
+fn push_pagination(query: &mut QueryBuilder<'_, Sqlite>, offset: Option<i64>, limit: Option<i64>) {
+    if limit.is_some() || offset.is_some() {
+        query
+            .push(" LIMIT ")
+            .push_bind(limit.unwrap_or(-1))
+            .push(" OFFSET ")
+            .push_bind(offset.unwrap_or(0));
+    }
+}
+
+#[async_trait]
+impl TraceWrite for SqliteBackend {
+    async fn write_traces(&self, traces: Vec<Trace>) -> StorageResult<()> {
+        let mut tx = self.pool.begin().await?;
+        for trace in traces {
+            sqlx::query(
+                "INSERT INTO traces (id, trace_id, service_name, start_time, end_time, \
+                 duration_us, status, status_message, root_span_name, attributes, \
+                 resource_attributes, span_count, created_at, updated_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+                 ON CONFLICT (trace_id) DO UPDATE SET \
+                 service_name = excluded.service_name, start_time = excluded.start_time, \
+                 end_time = excluded.end_time, duration_us = excluded.duration_us, \
+                 status = excluded.status, status_message = excluded.status_message, \
+                 root_span_name = excluded.root_span_name, attributes = excluded.attributes, \
+                 resource_attributes = excluded.resource_attributes, \
+                 span_count = excluded.span_count, updated_at = excluded.updated_at",
+            )
+            .bind(trace.id)
+            .bind(&trace.trace_id)
+            .bind(&trace.service_name)
+            .bind(trace.start_time)
+            .bind(trace.end_time)
+            .bind(trace.duration_us)
+            .bind(&trace.status)
+            .bind(&trace.status_message)
+            .bind(&trace.root_span_name)
+            .bind(&trace.attributes)
+            .bind(&trace.resource_attributes)
+            .bind(trace.span_count)
+            .bind(trace.created_at)
+            .bind(trace.updated_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn write_spans(&self, spans: Vec<TraceSpan>) -> StorageResult<()> {
+        let mut tx = self.pool.begin().await?;
+        for span in spans {
+            sqlx::query(
+                "INSERT INTO trace_spans (id, trace_id, span_id, parent_span_id, name, kind, \
+                 service_name, start_time, end_time, duration_us, status, status_message, \
+                 attributes, events, links, created_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+                 ON CONFLICT (span_id, start_time) DO UPDATE SET \
+                 trace_id = excluded.trace_id, parent_span_id = excluded.parent_span_id, \
+                 name = excluded.name, kind = excluded.kind, \
+                 service_name = excluded.service_name, end_time = excluded.end_time, \
+                 duration_us = excluded.duration_us, status = excluded.status, \
+                 status_message = excluded.status_message, attributes = excluded.attributes, \
+                 events = excluded.events, links = excluded.links",
+            )
+            .bind(span.id)
+            .bind(span.trace_id)
+            .bind(&span.span_id)
+            .bind(&span.parent_span_id)
+            .bind(&span.name)
+            .bind(&span.kind)
+            .bind(&span.service_name)
+            .bind(span.start_time)
+            .bind(span.end_time)
+            .bind(span.duration_us)
+            .bind(&span.status)
+            .bind(&span.status_message)
+            .bind(&span.attributes)
+            .bind(&span.events)
+            .bind(&span.links)
+            .bind(span.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn write_event(&self, event: TraceEvent) -> StorageResult<()> {
+        sqlx::query(
+            "INSERT INTO trace_events (id, span_id, name, timestamp, attributes, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(event.id)
+        .bind(event.span_id)
+        .bind(&event.name)
+        .bind(event.timestamp)
+        .bind(&event.attributes)
+        .bind(event.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn flush(&self) -> StorageResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MetricWrite for SqliteBackend {
+    async fn write_metrics(&self, metrics: Vec<Metric>) -> StorageResult<()> {
+        let mut tx = self.pool.begin().await?;
+        for metric in metrics {
+            sqlx::query(
+                "INSERT INTO metrics (id, name, description, unit, metric_type, service_name, \
+                 attributes, resource_attributes, created_at, updated_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+                 ON CONFLICT (name, service_name) DO UPDATE SET \
+                 description = excluded.description, unit = excluded.unit, \
+                 metric_type = excluded.metric_type, attributes = excluded.attributes, \
+                 resource_attributes = excluded.resource_attributes, \
+                 updated_at = excluded.updated_at",
+            )
+            .bind(metric.id)
+            .bind(&metric.name)
+            .bind(&metric.description)
+            .bind(&metric.unit)
+            .bind(&metric.metric_type)
+            .bind(&metric.service_name)
+            .bind(&metric.attributes)
+            .bind(&metric.resource_attributes)
+            .bind(metric.created_at)
+            .bind(metric.updated_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn write_data_points(&self, data_points: Vec<MetricDataPoint>) -> StorageResult<()> {
+        let mut tx = self.pool.begin().await?;
+        for point in data_points {
+            sqlx::query(
+                "INSERT INTO metric_data_points (id, metric_id, timestamp, value, count, sum, \
+                 min, max, buckets, quantiles, exemplars, attributes, created_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(point.id)
+            .bind(point.metric_id)
+            .bind(point.timestamp)
+            .bind(point.value)
+            .bind(point.count)
+            .bind(point.sum)
+            .bind(point.min)
+            .bind(point.max)
+            .bind(&point.buckets)
+            .bind(&point.quantiles)
+            .bind(&point.exemplars)
+            .bind(&point.attributes)
+            .bind(point.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn flush(&self) -> StorageResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LogWrite for SqliteBackend {
+    async fn write_logs(&self, logs: Vec<LogRecord>) -> StorageResult<()> {
+        let mut tx = self.pool.begin().await?;
+        for log in logs {
+            sqlx::query(
+                "INSERT INTO log_records (id, timestamp, observed_timestamp, severity_number, \
+                 severity_text, body, service_name, trace_id, span_id, trace_flags, \
+                 attributes, resource_attributes, scope_name, scope_version, \
+                 scope_attributes, pattern_id, created_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(log.id)
+            .bind(log.timestamp)
+            .bind(log.observed_timestamp)
+            .bind(log.severity_number)
+            .bind(&log.severity_text)
+            .bind(&log.body)
+            .bind(&log.service_name)
+            .bind(&log.trace_id)
+            .bind(&log.span_id)
+            .bind(log.trace_flags)
+            .bind(&log.attributes)
+            .bind(&log.resource_attributes)
+            .bind(&log.scope_name)
+            .bind(&log.scope_version)
+            .bind(&log.scope_attributes)
+            .bind(&log.pattern_id)
+            .bind(log.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn flush(&self) -> StorageResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TraceRead for SqliteBackend {
+    async fn get_by_id(&self, id: Uuid) -> StorageResult<Trace> {
+        let trace = sqlx::query_as("SELECT * FROM traces WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(trace)
+    }
+
+    async fn get_by_trace_id(&self, trace_id: &str) -> StorageResult<Trace> {
+        let trace = sqlx::query_as("SELECT * FROM traces WHERE trace_id = ?")
+            .bind(trace_id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(trace)
+    }
+
+    async fn list(&self, filters: TraceFilters) -> StorageResult<Vec<Trace>> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM traces WHERE 1=1");
+        if let Some(service_name) = &filters.service_name {
+            query.push(" AND service_name = ").push_bind(service_name);
+        }
+        if let Some(status) = &filters.status {
+            query.push(" AND status = ").push_bind(status);
+        }
+        if let Some(start_time) = filters.start_time {
+            query.push(" AND start_time >= ").push_bind(start_time);
+        }
+        if let Some(end_time) = filters.end_time {
+            query.push(" AND start_time <= ").push_bind(end_time);
+        }
+        if let Some(min_duration) = filters.min_duration_us {
+            query.push(" AND duration_us >= ").push_bind(min_duration);
+        }
+        if let Some(max_duration) = filters.max_duration_us {
+            query.push(" AND duration_us <= ").push_bind(max_duration);
+        }
+        query.push(" ORDER BY start_time DESC");
+
+        // Attribute containment is checked in Rust, before paginating
+        let Some(attributes) = &filters.attributes else {
+            push_pagination(&mut query, filters.offset, filters.limit);
+            return Ok(query.build_query_as().fetch_all(&self.pool).await?);
+        };
+        let traces: Vec<Trace> = query.build_query_as().fetch_all(&self.pool).await?;
+        let traces = traces
+            .into_iter()
+            .filter(|t| json_contains(&t.attributes, attributes))
+            .collect();
+        Ok(paginate(traces, filters.offset, filters.limit))
+    }
+
+    async fn get_spans(&self, trace_id: Uuid) -> StorageResult<Vec<TraceSpan>> {
+        let spans =
+            sqlx::query_as("SELECT * FROM trace_spans WHERE trace_id = ? ORDER BY start_time ASC")
+                .bind(trace_id)
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(spans)
+    }
+
+    async fn get_events(&self, span_id: Uuid) -> StorageResult<Vec<TraceEvent>> {
+        let events =
+            sqlx::query_as("SELECT * FROM trace_events WHERE span_id = ? ORDER BY timestamp ASC")
+                .bind(span_id)
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(events)
+    }
+}
+
+#[async_trait]
+impl MetricRead for SqliteBackend {
+    async fn get_by_id(&self, id: Uuid) -> StorageResult<Metric> {
+        let metric = sqlx::query_as("SELECT * FROM metrics WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(metric)
+    }
+
+    async fn get_by_name(&self, name: &str, service_name: &str) -> StorageResult<Metric> {
+        let metric = sqlx::query_as("SELECT * FROM metrics WHERE name = ? AND service_name = ?")
+            .bind(name)
+            .bind(service_name)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(metric)
+    }
+
+    async fn list(&self, filters: MetricFilters) -> StorageResult<Vec<Metric>> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM metrics WHERE 1=1");
+        if let Some(service_name) = &filters.service_name {
+            query.push(" AND service_name = ").push_bind(service_name);
+        }
+        if let Some(metric_type) = &filters.metric_type {
+            query.push(" AND metric_type = ").push_bind(metric_type);
+        }
+        if let Some(name_pattern) = &filters.name_pattern {
+            // Case-sensitive, like PostgreSQL's LIKE
+            query
+                .push(" AND instr(name, ")
+                .push_bind(name_pattern)
+                .push(") > 0");
+        }
+        query.push(" ORDER BY name ASC");
+        push_pagination(&mut query, filters.offset, filters.limit);
+
+        Ok(query.build_query_as().fetch_all(&self.pool).await?)
+    }
+
+    async fn get_data_points(
+        &self,
+        metric_id: Uuid,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> StorageResult<Vec<MetricDataPoint>> {
+        let points = sqlx::query_as(
+            "SELECT * FROM metric_data_points \
+             WHERE metric_id = ? AND timestamp >= ? AND timestamp <= ? \
+             ORDER BY timestamp ASC",
+        )
+        .bind(metric_id)
+        .bind(start_time)
+        .bind(end_time)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(points)
+    }
+
+    async fn get_metrics(
+        &self,
+        name: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> StorageResult<Vec<MetricDataPoint>> {
+        let points = sqlx::query_as(
+            "SELECT mdp.* FROM metric_data_points mdp \
+             JOIN metrics m ON mdp.metric_id = m.id \
+             WHERE m.name = ? AND mdp.timestamp >= ? AND mdp.timestamp <= ? \
+             ORDER BY mdp.timestamp ASC",
+        )
+        .bind(name)
+        .bind(start_time)
+        .bind(end_time)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(points)
+    }
+}
+
+#[async_trait]
+impl LogRead for SqliteBackend {
+    async fn get_by_id(&self, id: Uuid) -> StorageResult<LogRecord> {
+        let log = sqlx::query_as("SELECT * FROM log_records WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(log)
+    }
+
+    async fn list(&self, filters: LogFilters) -> StorageResult<Vec<LogRecord>> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM log_records WHERE 1=1");
+        if let Some(service_name) = &filters.service_name {
+            query.push(" AND service_name = ").push_bind(service_name);
+        }
+        if let Some(min_severity) = filters.min_severity {
+            query
+                .push(" AND severity_number >= ")
+                .push_bind(min_severity);
+        }
+        if let Some(trace_id) = &filters.trace_id {
+            query.push(" AND trace_id = ").push_bind(trace_id);
+        }
+        if let Some(span_id) = &filters.span_id {
+            query.push(" AND span_id = ").push_bind(span_id);
+        }
+        if let Some(start_time) = filters.start_time {
+            query.push(" AND timestamp >= ").push_bind(start_time);
+        }
+        if let Some(end_time) = filters.end_time {
+            query.push(" AND timestamp <= ").push_bind(end_time);
+        }
+        if let Some(search_query) = &filters.search_query {
+            // SQLite's LIKE is case-insensitive, like PostgreSQL's ILIKE
+            query
+                .push(" AND body LIKE ")
+                .push_bind(format!("%{}%", search_query));
+        }
+        match filters.sort_order {
+            SortOrder::Asc => query.push(" ORDER BY timestamp ASC"),
+            SortOrder::Desc => query.push(" ORDER BY timestamp DESC"),
+        };
+        push_pagination(&mut query, filters.offset, filters.limit);
+
+        Ok(query.build_query_as().fetch_all(&self.pool).await?)
+    }
+
+    async fn get_logs_by_trace(&self, trace_id: &str) -> StorageResult<Vec<LogRecord>> {
+        let logs =
+            sqlx::query_as("SELECT * FROM log_records WHERE trace_id = ? ORDER BY timestamp ASC")
+                .bind(trace_id)
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(logs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+
+    async fn backend(dir: &tempfile::TempDir, max_size_mb: Option<u64>) -> SqliteBackend {
+        let config = SqliteConfig {
+            path: dir
+                .path()
+                .join("observatory.db")
+                .to_string_lossy()
+                .into_owned(),
+            max_size_mb,
+            ..SqliteConfig::default()
+        };
+        SqliteBackend::connect(config).await.unwrap()
+    }
+
+    fn log(body: &str, severity_number: i32, timestamp: DateTime<Utc>) -> LogRecord {
+        LogRecord {
+            id: Uuid::new_v4(),
+            timestamp,
+            observed_timestamp: timestamp,
+            severity_number,
+            severity_text: LogRecord::parse_level(severity_number).to_string(),
+            body: body.to_string(),
+            service_name: "edge".to_string(),
+            trace_id: Some("trace-1".to_string()),
+            span_id: None,
+            trace_flags: None,
+            attributes: json!({}),
+            resource_attributes: json!({}),
+            scope_name: None,
+            scope_version: None,
+            scope_attributes: None,
+            pattern_id: None,
+            created_at: timestamp,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_traces_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = backend(&dir, None).await;
+        let now = Utc::now();
+
+        let mut first = Trace::new(
+            "t1".to_string(),
+            "edge".to_string(),
+            now - Duration::minutes(1),
+        );
+        first.attributes = json!({"gen_ai.system": "openai"});
+        let id = first.id;
+        let second = Trace::new("t2".to_string(), "edge".to_string(), now);
+        backend.write_traces(vec![first, second]).await.unwrap();
+
+        // Rewriting a trace updates it in place
+        let mut update = Trace::new(
+            "t1".to_string(),
+            "edge".to_string(),
+            now - Duration::minutes(1),
+        );
+        update.status = "error".to_string();
+        update.attributes = json!({"gen_ai.system": "openai"});
+        backend.write_trace(update).await.unwrap();
+
+        let stored = TraceRead::get_by_id(&backend, id).await.unwrap();
+        assert_eq!(stored.status, "error");
+
+        let recent = TraceRead::list(&backend, TraceFilters::default())
+            .await
+            .unwrap();
+        let ids: Vec<&str> = recent.iter().map(|t| t.trace_id.as_str()).collect();
+        assert_eq!(ids, vec!["t2", "t1"]);
+
+        let filtered = TraceRead::list(
+            &backend,
+            TraceFilters {
+                attributes: Some(json!({"gen_ai.system": "openai"})),
+                ..TraceFilters::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(filtered.len(), 1);
+
+        let span = TraceSpan::new(
+            id,
+            "s1".to_string(),
+            "chat".to_string(),
+            "edge".to_string(),
+            now,
+        );
+        backend.write_span(span.clone()).await.unwrap();
+        backend.write_span(span).await.unwrap();
+        let (_, spans) = backend.get_trace_by_id("t1").await.unwrap();
+        assert_eq!(spans.len(), 1);
+
+        let missing = backend.get_by_trace_id("t3").await;
+        assert!(missing.unwrap_err().is_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_logs_filtering() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = backend(&dir, None).await;
+        let now = Utc::now();
+        backend
+            .write_logs(vec![
+                log("request started", 9, now - Duration::seconds(2)),
+                log("Upstream TIMEOUT", 17, now - Duration::seconds(1)),
+                log("request finished", 9, now),
+            ])
+            .await
+            .unwrap();
+
+        let search = LogRead::list(
+            &backend,
+            LogFilters {
+                search_query: Some("timeout".to_string()),
+                ..LogFilters::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(search.len(), 1);
+        assert_eq!(search[0].severity_number, 17);
+
+        let page = LogRead::list(
+            &backend,
+            LogFilters {
+                sort_order: SortOrder::Asc,
+                offset: Some(1),
+                ..LogFilters::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].body, "Upstream TIMEOUT");
+    }
+
+    #[tokio::test]
+    async fn test_enforce_size_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = backend(&dir, Some(1)).await;
+        let start = Utc::now() - Duration::days(1);
+        let body = "x".repeat(1024);
+        let logs = (0..3000)
+            .map(|i| log(&body, 9, start + Duration::seconds(i)))
+            .collect();
+        backend.write_logs(logs).await.unwrap();
+
+        let report = backend.enforce_size_cap().await.unwrap();
+        assert!(report.size_bytes_before > 1024 * 1024);
+        assert!(report.size_bytes_after <= 1024 * 1024);
+        assert!(report.rows_deleted > 0);
+
+        // The oldest logs went first
+        let remaining = LogRead::list(
+            &backend,
+            LogFilters {
+                sort_order: SortOrder::Asc,
+                ..LogFilters::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(!remaining.is_empty());
+        assert_eq!(remaining.len() as u64, 3000 - report.rows_deleted);
+        assert!(remaining[0].timestamp > start);
+    }
+}
@@ -0,0 +1,677 @@
+//! Backup and restore of observability data.
+//!
+//! A backup is a directory holding the traces, metrics and logs of a time
+//! range as zstd-compressed chunk files of JSON lines (one row per line, as
+//! produced by `row_to_json`), plus a `manifest.json` describing the range and
+//! listing every chunk with its row count and SHA-256 checksum:
+//!
+//! ```text
+//! backup-2025-11-01/
+//!   manifest.json
+//!   traces-00000.jsonl.zst
+//!   trace_spans-00000.jsonl.zst
+//!   trace_spans-00001.jsonl.zst
+//!   ...
+//! ```
+//!
+//! The manifest is written last, so a directory without one is an incomplete
+//! backup. An incremental backup starts where its parent ended and records
+//! the parent's id; restoring a chain means restoring the full backup and
+//! then each incremental one in order.
+//!
+//! Restores verify every checksum before inserting anything and insert with
+//! `ON CONFLICT DO NOTHING`, so rows already present are skipped and an
+//! interrupted restore can be re-run. The target database must already have
+//! the schema (run the migrations first).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use chrono::{Duration, Utc};
+//! use llm_observatory_storage::backup::{BackupManager, BackupOptions};
+//! use llm_observatory_storage::{StorageConfig, StoragePool};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let pool = StoragePool::new(StorageConfig::from_env()?).await?;
+//! let manager = BackupManager::new(pool);
+//!
+//! let end = Utc::now();
+//! let options = BackupOptions::new(end - Duration::days(7), end);
+//! let manifest = manager.backup("backups/weekly".as_ref(), &options).await?;
+//! println!("Backed up {} rows", manifest.total_rows());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{StorageError, StorageResult};
+use crate::pool::StoragePool;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Name of the manifest file in a backup directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Version of the backup format written by this crate.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Default number of rows per chunk file.
+pub const DEFAULT_CHUNK_ROWS: usize = 20_000;
+
+/// Default zstd compression level.
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+
+/// Rows inserted per statement on restore.
+const RESTORE_BATCH_ROWS: usize = 1_000;
+
+/// Kind of observability data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Dataset {
+    /// Traces, spans and span events
+    Traces,
+    /// Metric definitions and data points
+    Metrics,
+    /// Log records
+    Logs,
+}
+
+impl Dataset {
+    /// Every dataset.
+    pub const ALL: [Dataset; 3] = [Dataset::Traces, Dataset::Metrics, Dataset::Logs];
+
+    /// Dataset name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Dataset::Traces => "traces",
+            Dataset::Metrics => "metrics",
+            Dataset::Logs => "logs",
+        }
+    }
+}
+
+impl fmt::Display for Dataset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Dataset {
+    type Err = StorageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "traces" => Ok(Dataset::Traces),
+            "metrics" => Ok(Dataset::Metrics),
+            "logs" => Ok(Dataset::Logs),
+            other => Err(StorageError::validation(format!(
+                "Unknown dataset '{}': expected traces, metrics or logs",
+                other
+            ))),
+        }
+    }
+}
+
+/// A table included in backups.
+#[derive(Debug, Clone)]
+pub struct BackupTable {
+    /// Table name
+    pub table: &'static str,
+
+    /// Dataset the table belongs to
+    pub dataset: Dataset,
+
+    /// Condition selecting the rows of the range `[$1, $2)`
+    pub range_condition: &'static str,
+}
+
+/// Tables in backup and restore order, parents before children.
+pub const BACKUP_TABLES: [BackupTable; 6] = [
+    BackupTable {
+        table: "traces",
+        dataset: Dataset::Traces,
+        range_condition: "start_time >= $1 AND start_time < $2",
+    },
+    BackupTable {
+        table: "trace_spans",
+        dataset: Dataset::Traces,
+        range_condition: "start_time >= $1 AND start_time < $2",
+    },
+    BackupTable {
+        table: "trace_events",
+        dataset: Dataset::Traces,
+        range_condition: "timestamp >= $1 AND timestamp < $2",
+    },
+    // Metric definitions have no timestamp of their own; back up the ones
+    // the range's data points refer to.
+    BackupTable {
+        table: "metrics",
+        dataset: Dataset::Metrics,
+        range_condition: "id IN (SELECT metric_id FROM metric_data_points \
+                          WHERE timestamp >= $1 AND timestamp < $2)",
+    },
+    BackupTable {
+        table: "metric_data_points",
+        dataset: Dataset::Metrics,
+        range_condition: "timestamp >= $1 AND timestamp < $2",
+    },
+    BackupTable {
+        table: "logs",
+        dataset: Dataset::Logs,
+        range_condition: "timestamp >= $1 AND timestamp < $2",
+    },
+];
+
+/// What to back up.
+#[derive(Debug, Clone)]
+pub struct BackupOptions {
+    /// Start of the range (inclusive)
+    pub start_time: DateTime<Utc>,
+
+    /// End of the range (exclusive)
+    pub end_time: DateTime<Utc>,
+
+    /// Datasets to include
+    pub datasets: Vec<Dataset>,
+
+    /// Maximum rows per chunk file
+    pub chunk_rows: usize,
+
+    /// zstd compression level
+    pub compression_level: i32,
+
+    /// Backup this one continues, for incremental backups
+    pub parent: Option<Uuid>,
+}
+
+impl BackupOptions {
+    /// Full backup of every dataset in `[start_time, end_time)`.
+    pub fn new(start_time: DateTime<Utc>, end_time: DateTime<Utc>) -> Self {
+        Self {
+            start_time,
+            end_time,
+            datasets: Dataset::ALL.to_vec(),
+            chunk_rows: DEFAULT_CHUNK_ROWS,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+            parent: None,
+        }
+    }
+
+    /// Incremental backup of the data since `parent` ended, with the
+    /// parent's datasets.
+    pub fn incremental(parent: &BackupManifest, end_time: DateTime<Utc>) -> Self {
+        Self {
+            datasets: parent.datasets.clone(),
+            parent: Some(parent.id),
+            ..Self::new(parent.end_time, end_time)
+        }
+    }
+
+    fn validate(&self) -> StorageResult<()> {
+        if self.start_time >= self.end_time {
+            return Err(StorageError::validation("Backup start time must be before its end time"));
+        }
+        if self.datasets.is_empty() {
+            return Err(StorageError::validation("Backup must include at least one dataset"));
+        }
+        if self.chunk_rows == 0 {
+            return Err(StorageError::validation("Chunk size must be at least one row"));
+        }
+        Ok(())
+    }
+}
+
+/// Description of a backup, stored as its `manifest.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    /// Backup format version
+    pub format_version: u32,
+
+    /// Backup id
+    pub id: Uuid,
+
+    /// Id of the backup this one continues (incremental backups only)
+    pub parent: Option<Uuid>,
+
+    /// When the backup was taken
+    pub created_at: DateTime<Utc>,
+
+    /// Start of the range (inclusive)
+    pub start_time: DateTime<Utc>,
+
+    /// End of the range (exclusive)
+    pub end_time: DateTime<Utc>,
+
+    /// Datasets included
+    pub datasets: Vec<Dataset>,
+
+    /// Chunk files, in restore order
+    pub chunks: Vec<BackupChunk>,
+}
+
+impl BackupManifest {
+    /// Read the manifest of the backup in `dir`.
+    pub fn load(dir: &Path) -> StorageResult<Self> {
+        let path = dir.join(MANIFEST_FILE);
+        let raw = std::fs::read(&path).map_err(|e| io_error(&path, e))?;
+        let manifest: BackupManifest = serde_json::from_slice(&raw)?;
+
+        if manifest.format_version > FORMAT_VERSION {
+            return Err(StorageError::validation(format!(
+                "Backup format version {} is newer than supported version {}",
+                manifest.format_version, FORMAT_VERSION
+            )));
+        }
+        Ok(manifest)
+    }
+
+    /// Whether this is an incremental backup.
+    pub fn is_incremental(&self) -> bool {
+        self.parent.is_some()
+    }
+
+    /// Rows in all chunks.
+    pub fn total_rows(&self) -> u64 {
+        self.chunks.iter().map(|c| c.rows).sum()
+    }
+
+    /// Rows per table, in restore order.
+    pub fn rows_by_table(&self) -> Vec<(String, u64)> {
+        let mut tables: Vec<(String, u64)> = Vec::new();
+        for chunk in &self.chunks {
+            match tables.iter_mut().find(|(table, _)| *table == chunk.table) {
+                Some((_, rows)) => *rows += chunk.rows,
+                None => tables.push((chunk.table.clone(), chunk.rows)),
+            }
+        }
+        tables
+    }
+
+    fn save(&self, dir: &Path) -> StorageResult<()> {
+        let path = dir.join(MANIFEST_FILE);
+        let raw = serde_json::to_vec_pretty(self)?;
+        std::fs::write(&path, raw).map_err(|e| io_error(&path, e))
+    }
+}
+
+/// A compressed chunk file of one table's rows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupChunk {
+    /// Table the rows belong to
+    pub table: String,
+
+    /// File name within the backup directory
+    pub file: String,
+
+    /// Number of rows
+    pub rows: u64,
+
+    /// Compressed size in bytes
+    pub bytes: u64,
+
+    /// Hex SHA-256 of the compressed file
+    pub sha256: String,
+}
+
+/// Rows restored from a backup.
+#[derive(Debug, Clone, Default)]
+pub struct RestoreReport {
+    /// Per table: rows in the backup and rows inserted
+    pub tables: Vec<TableRestore>,
+}
+
+impl RestoreReport {
+    /// Rows inserted in all tables.
+    pub fn inserted(&self) -> u64 {
+        self.tables.iter().map(|t| t.inserted).sum()
+    }
+
+    /// Rows skipped because they were already present.
+    pub fn skipped(&self) -> u64 {
+        self.tables.iter().map(|t| t.rows - t.inserted).sum()
+    }
+}
+
+/// Rows restored into one table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableRestore {
+    /// Table name
+    pub table: String,
+
+    /// Rows in the backup
+    pub rows: u64,
+
+    /// Rows inserted (the rest were already present)
+    pub inserted: u64,
+}
+
+/// Takes and restores backups of a database.
+pub struct BackupManager {
+    pool: StoragePool,
+}
+
+impl BackupManager {
+    /// Create a manager for the database of `pool`.
+    pub fn new(pool: StoragePool) -> Self {
+        Self { pool }
+    }
+
+    /// Back up the rows of `options` into `dir`, which must not already
+    /// contain a backup.
+    pub async fn backup(&self, dir: &Path, options: &BackupOptions) -> StorageResult<BackupManifest> {
+        options.validate()?;
+        if dir.join(MANIFEST_FILE).exists() {
+            return Err(StorageError::validation(format!(
+                "{} already contains a backup",
+                dir.display()
+            )));
+        }
+        std::fs::create_dir_all(dir).map_err(|e| io_error(dir, e))?;
+
+        let mut chunks = Vec::new();
+        for table in BACKUP_TABLES.iter().filter(|t| options.datasets.contains(&t.dataset)) {
+            let table_chunks = self.backup_table(dir, table, options).await?;
+            tracing::info!(
+                table = table.table,
+                rows = table_chunks.iter().map(|c| c.rows).sum::<u64>(),
+                chunks = table_chunks.len(),
+                "Backed up table"
+            );
+            chunks.extend(table_chunks);
+        }
+
+        let manifest = BackupManifest {
+            format_version: FORMAT_VERSION,
+            id: Uuid::new_v4(),
+            parent: options.parent,
+            created_at: Utc::now(),
+            start_time: options.start_time,
+            end_time: options.end_time,
+            datasets: options.datasets.clone(),
+            chunks,
+        };
+        manifest.save(dir)?;
+
+        Ok(manifest)
+    }
+
+    /// Stream one table's rows in the range into chunk files.
+    async fn backup_table(
+        &self,
+        dir: &Path,
+        table: &BackupTable,
+        options: &BackupOptions,
+    ) -> StorageResult<Vec<BackupChunk>> {
+        let sql = format!(
+            "SELECT row_to_json(t)::text FROM {} t WHERE {}",
+            table.table, table.range_condition
+        );
+        let mut rows = sqlx::query_scalar::<_, String>(&sql)
+            .bind(options.start_time)
+            .bind(options.end_time)
+            .fetch(self.pool.postgres());
+
+        let mut chunks = Vec::new();
+        let mut lines = Vec::with_capacity(options.chunk_rows);
+        while let Some(row) = rows.try_next().await? {
+            lines.push(row);
+            if lines.len() == options.chunk_rows {
+                chunks.push(write_chunk(dir, table.table, chunks.len(), &lines, options.compression_level)?);
+                lines.clear();
+            }
+        }
+        if !lines.is_empty() {
+            chunks.push(write_chunk(dir, table.table, chunks.len(), &lines, options.compression_level)?);
+        }
+
+        Ok(chunks)
+    }
+
+    /// Restore the backup in `dir`.
+    ///
+    /// Every chunk is verified before any row is inserted; a backup with a
+    /// missing or corrupt chunk is rejected as a whole.
+    pub async fn restore(&self, dir: &Path) -> StorageResult<RestoreReport> {
+        let manifest = verify(dir)?;
+        self.check_schema(&manifest).await?;
+
+        let mut report = RestoreReport::default();
+        for chunk in &manifest.chunks {
+            let lines = read_chunk(dir, chunk)?;
+            let mut inserted = 0;
+            for batch in lines.chunks(RESTORE_BATCH_ROWS) {
+                inserted += self.insert_rows(&chunk.table, batch).await?;
+            }
+
+            match report.tables.iter_mut().find(|t| t.table == chunk.table) {
+                Some(table) => {
+                    table.rows += chunk.rows;
+                    table.inserted += inserted;
+                }
+                None => report.tables.push(TableRestore {
+                    table: chunk.table.clone(),
+                    rows: chunk.rows,
+                    inserted,
+                }),
+            }
+            tracing::debug!(file = %chunk.file, rows = chunk.rows, inserted, "Restored chunk");
+        }
+
+        tracing::info!(
+            backup = %manifest.id,
+            inserted = report.inserted(),
+            skipped = report.skipped(),
+            "Restored backup"
+        );
+        Ok(report)
+    }
+
+    /// Fail unless every table of the backup exists in the target database.
+    async fn check_schema(&self, manifest: &BackupManifest) -> StorageResult<()> {
+        for (table, _) in manifest.rows_by_table() {
+            let exists: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+                .bind(&table)
+                .fetch_one(self.pool.postgres())
+                .await?;
+            if !exists {
+                return Err(StorageError::MigrationError(format!(
+                    "Table '{}' does not exist in the target database; run the migrations before restoring",
+                    table
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Insert JSON rows into `table`, skipping rows already present.
+    async fn insert_rows(&self, table: &str, rows: &[String]) -> StorageResult<u64> {
+        if !BACKUP_TABLES.iter().any(|t| t.table == table) {
+            return Err(StorageError::validation(format!("Unknown table '{}' in backup", table)));
+        }
+
+        let sql = format!(
+            "INSERT INTO {table} SELECT * FROM json_populate_recordset(NULL::{table}, $1::json) \
+             ON CONFLICT DO NOTHING"
+        );
+        let result = sqlx::query(&sql)
+            .bind(format!("[{}]", rows.join(",")))
+            .execute(self.pool.postgres())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Check the checksums of every chunk of the backup in `dir` and return its
+/// manifest.
+pub fn verify(dir: &Path) -> StorageResult<BackupManifest> {
+    let manifest = BackupManifest::load(dir)?;
+    for chunk in &manifest.chunks {
+        read_verified(dir, chunk)?;
+    }
+    Ok(manifest)
+}
+
+/// File name of a table's `index`th chunk.
+fn chunk_file_name(table: &str, index: usize) -> String {
+    format!("{}-{:05}.jsonl.zst", table, index)
+}
+
+/// Compress rows into a chunk file.
+fn write_chunk(
+    dir: &Path,
+    table: &str,
+    index: usize,
+    lines: &[String],
+    level: i32,
+) -> StorageResult<BackupChunk> {
+    let mut raw = lines.join("\n");
+    raw.push('\n');
+    let compressed = zstd::encode_all(raw.as_bytes(), level)
+        .map_err(|e| StorageError::SerializationError(format!("Compression failed: {}", e)))?;
+
+    let file = chunk_file_name(table, index);
+    let path = dir.join(&file);
+    std::fs::write(&path, &compressed).map_err(|e| io_error(&path, e))?;
+
+    Ok(BackupChunk {
+        table: table.to_string(),
+        file,
+        rows: lines.len() as u64,
+        bytes: compressed.len() as u64,
+        sha256: hex::encode(digest(&SHA256, &compressed)),
+    })
+}
+
+/// Read a chunk file and check its size and checksum.
+fn read_verified(dir: &Path, chunk: &BackupChunk) -> StorageResult<Vec<u8>> {
+    let path = dir.join(&chunk.file);
+    let compressed = std::fs::read(&path).map_err(|e| io_error(&path, e))?;
+
+    let sha256 = hex::encode(digest(&SHA256, &compressed));
+    if compressed.len() as u64 != chunk.bytes || sha256 != chunk.sha256 {
+        return Err(StorageError::validation(format!(
+            "Checksum mismatch for {}: backup is corrupt",
+            path.display()
+        )));
+    }
+    Ok(compressed)
+}
+
+/// Read the rows of a verified chunk file.
+fn read_chunk(dir: &Path, chunk: &BackupChunk) -> StorageResult<Vec<String>> {
+    let compressed = read_verified(dir, chunk)?;
+    let raw = zstd::decode_all(compressed.as_slice())
+        .map_err(|e| StorageError::SerializationError(format!("Decompression failed: {}", e)))?;
+    let raw = String::from_utf8(raw)
+        .map_err(|e| StorageError::SerializationError(format!("Invalid chunk {}: {}", chunk.file, e)))?;
+
+    let lines: Vec<String> = raw.lines().map(str::to_string).collect();
+    if lines.len() as u64 != chunk.rows {
+        return Err(StorageError::validation(format!(
+            "Chunk {} has {} rows, manifest lists {}",
+            chunk.file,
+            lines.len(),
+            chunk.rows
+        )));
+    }
+    Ok(lines)
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> StorageError {
+    StorageError::Internal(format!("{}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn manifest(chunks: Vec<BackupChunk>) -> BackupManifest {
+        let start = Utc.with_ymd_and_hms(2025, 11, 1, 0, 0, 0).unwrap();
+        BackupManifest {
+            format_version: FORMAT_VERSION,
+            id: Uuid::new_v4(),
+            parent: None,
+            created_at: start + Duration::days(1),
+            start_time: start,
+            end_time: start + Duration::days(1),
+            datasets: Dataset::ALL.to_vec(),
+            chunks,
+        }
+    }
+
+    #[test]
+    fn test_chunk_round_trip_and_corruption() {
+        let dir = tempfile::tempdir().unwrap();
+        let lines = vec![
+            r#"{"trace_id":"a","service_name":"chat"}"#.to_string(),
+            r#"{"trace_id":"b","service_name":"search"}"#.to_string(),
+        ];
+
+        let chunk = write_chunk(dir.path(), "traces", 0, &lines, DEFAULT_COMPRESSION_LEVEL).unwrap();
+        assert_eq!(chunk.file, "traces-00000.jsonl.zst");
+        assert_eq!(chunk.rows, 2);
+        manifest(vec![chunk.clone()]).save(dir.path()).unwrap();
+
+        assert_eq!(verify(dir.path()).unwrap().total_rows(), 2);
+        assert_eq!(read_chunk(dir.path(), &chunk).unwrap(), lines);
+
+        // Any change to a chunk file fails verification
+        let path = dir.path().join(&chunk.file);
+        let mut bytes = std::fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        std::fs::write(&path, bytes).unwrap();
+        assert!(verify(dir.path()).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(verify(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_incremental_options() {
+        let mut parent = manifest(Vec::new());
+        parent.datasets = vec![Dataset::Logs];
+        let end = parent.end_time + Duration::hours(6);
+
+        let options = BackupOptions::incremental(&parent, end);
+        assert_eq!(options.start_time, parent.end_time);
+        assert_eq!(options.end_time, end);
+        assert_eq!(options.parent, Some(parent.id));
+        assert_eq!(options.datasets, vec![Dataset::Logs]);
+        assert!(options.validate().is_ok());
+
+        // Nothing new since the parent
+        assert!(BackupOptions::incremental(&parent, parent.end_time).validate().is_err());
+    }
+
+    #[test]
+    fn test_rows_by_table() {
+        let chunk = |table: &str, index, rows| BackupChunk {
+            table: table.to_string(),
+            file: chunk_file_name(table, index),
+            rows,
+            bytes: 0,
+            sha256: String::new(),
+        };
+        let manifest = manifest(vec![
+            chunk("traces", 0, 10),
+            chunk("trace_spans", 0, 100),
+            chunk("trace_spans", 1, 40),
+        ]);
+
+        assert_eq!(
+            manifest.rows_by_table(),
+            vec![("traces".to_string(), 10), ("trace_spans".to_string(), 140)]
+        );
+        assert_eq!(manifest.total_rows(), 150);
+        assert_eq!("metrics".parse::<Dataset>().unwrap(), Dataset::Metrics);
+        assert!("spans".parse::<Dataset>().is_err());
+    }
+}
@@ -0,0 +1,258 @@
+//! # Storage CLI
+//!
+//! Backup, restore and bulk import of observability data. The database is configured
+//! from the environment like the storage service (see `StorageConfig::from_env`).
+//!
+//! # Usage
+//!
+//! ```bash
+//! # Full backup of October
+//! storage-cli backup --output backups/2025-10 \
+//!     --start 2025-10-01T00:00:00Z --end 2025-11-01T00:00:00Z
+//!
+//! # Incremental backup of everything since the previous backup
+//! storage-cli backup --output backups/2025-11-07 --incremental-from backups/2025-10
+//!
+//! # Check the checksums of a backup
+//! storage-cli verify backups/2025-10
+//!
+//! # Restore a full backup and its incremental backups, in order
+//! storage-cli restore backups/2025-10 backups/2025-11-07
+//!
+//! # Import spans from Parquet files (requires the `parquet` feature)
+//! storage-cli import --format parquet --mapping spans-mapping.yaml exports/*.parquet
+//! ```
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "parquet")]
+use clap::ValueEnum;
+use clap::{Parser, Subcommand};
+use llm_observatory_storage::backup::{
+    self, BackupManager, BackupManifest, BackupOptions, Dataset, DEFAULT_CHUNK_ROWS,
+    DEFAULT_COMPRESSION_LEVEL,
+};
+#[cfg(feature = "parquet")]
+use llm_observatory_storage::bulk::{BulkLoader, SchemaMapping, DEFAULT_BATCH_ROWS};
+use llm_observatory_storage::{StorageConfig, StorageError, StoragePool, StorageResult};
+use std::path::PathBuf;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+/// LLM Observatory storage tools.
+#[derive(Parser, Debug)]
+#[command(name = "storage-cli")]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Export traces, metrics and logs of a time range into a backup directory.
+    Backup {
+        /// Directory to write the backup to (must not contain a backup).
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Start of the range (RFC 3339, inclusive). Required unless incremental.
+        #[arg(long, conflicts_with = "incremental_from")]
+        start: Option<DateTime<Utc>>,
+
+        /// End of the range (RFC 3339, exclusive). Defaults to now.
+        #[arg(long)]
+        end: Option<DateTime<Utc>>,
+
+        /// Back up the data since the backup in this directory ended.
+        #[arg(long)]
+        incremental_from: Option<PathBuf>,
+
+        /// Datasets to include: traces, metrics, logs (default: all).
+        #[arg(long, value_delimiter = ',')]
+        datasets: Vec<Dataset>,
+
+        /// Maximum rows per chunk file.
+        #[arg(long, default_value_t = DEFAULT_CHUNK_ROWS)]
+        chunk_rows: usize,
+
+        /// zstd compression level (1-22).
+        #[arg(long, default_value_t = DEFAULT_COMPRESSION_LEVEL)]
+        compression_level: i32,
+    },
+
+    /// Restore backups into the configured database.
+    ///
+    /// Pass a full backup followed by its incremental backups, in order.
+    Restore {
+        /// Backup directories.
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+    },
+
+    /// Check the checksums of a backup without touching the database.
+    Verify {
+        /// Backup directory.
+        input: PathBuf,
+    },
+
+    /// Load files into the configured database with COPY.
+    #[cfg(feature = "parquet")]
+    Import {
+        /// Format of the input files.
+        #[arg(long, value_enum, default_value_t = ImportFormat::Parquet)]
+        format: ImportFormat,
+
+        /// Schema mapping file (YAML, TOML or JSON).
+        #[arg(short, long)]
+        mapping: PathBuf,
+
+        /// Rows per record batch and COPY.
+        #[arg(long, default_value_t = DEFAULT_BATCH_ROWS)]
+        batch_rows: usize,
+
+        /// Files to import, in order.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+}
+
+/// Input file format of `import`.
+#[cfg(feature = "parquet")]
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ImportFormat {
+    Parquet,
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info,sqlx=warn")))
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    if let Err(e) = run(Cli::parse()).await {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> StorageResult<()> {
+    match cli.command {
+        Command::Backup {
+            output,
+            start,
+            end,
+            incremental_from,
+            datasets,
+            chunk_rows,
+            compression_level,
+        } => {
+            let end = end.unwrap_or_else(Utc::now);
+            let mut options = match (incremental_from, start) {
+                (Some(parent), _) => BackupOptions::incremental(&BackupManifest::load(&parent)?, end),
+                (None, Some(start)) => BackupOptions::new(start, end),
+                (None, None) => {
+                    return Err(StorageError::validation(
+                        "Either --start or --incremental-from is required",
+                    ))
+                }
+            };
+            if !datasets.is_empty() {
+                options.datasets = datasets;
+            }
+            options.chunk_rows = chunk_rows;
+            options.compression_level = compression_level;
+
+            let manifest = manager().await?.backup(&output, &options).await?;
+            println!(
+                "Backup {} written to {} ({} to {})",
+                manifest.id,
+                output.display(),
+                manifest.start_time,
+                manifest.end_time
+            );
+            print_tables(&manifest);
+            Ok(())
+        }
+        Command::Restore { inputs } => {
+            // Check the whole chain before restoring any of it
+            let mut previous: Option<BackupManifest> = None;
+            for input in &inputs {
+                let manifest = backup::verify(input)?;
+                if let Some(parent) = manifest.parent {
+                    if previous.as_ref().map(|p| p.id) != Some(parent) {
+                        return Err(StorageError::validation(format!(
+                            "{} is an incremental backup of {}, which must be restored right before it",
+                            input.display(),
+                            parent
+                        )));
+                    }
+                }
+                previous = Some(manifest);
+            }
+
+            let manager = manager().await?;
+            for input in &inputs {
+                let report = manager.restore(input).await?;
+                println!(
+                    "Restored {}: {} rows inserted, {} already present",
+                    input.display(),
+                    report.inserted(),
+                    report.skipped()
+                );
+                for table in &report.tables {
+                    println!("  {:<20} {:>12} rows, {:>12} inserted", table.table, table.rows, table.inserted);
+                }
+            }
+            Ok(())
+        }
+        Command::Verify { input } => {
+            let manifest = backup::verify(&input)?;
+            println!(
+                "Backup {} is intact: {} chunks, {} rows ({} to {})",
+                manifest.id,
+                manifest.chunks.len(),
+                manifest.total_rows(),
+                manifest.start_time,
+                manifest.end_time
+            );
+            if let Some(parent) = manifest.parent {
+                println!("Incremental backup of {}", parent);
+            }
+            print_tables(&manifest);
+            Ok(())
+        }
+        #[cfg(feature = "parquet")]
+        Command::Import {
+            format: ImportFormat::Parquet,
+            mapping,
+            batch_rows,
+            files,
+        } => {
+            let mapping = SchemaMapping::from_file(&mapping.to_string_lossy())?;
+            let pool = StoragePool::new(StorageConfig::from_env()?).await?;
+            let loader = BulkLoader::new(pool, mapping).with_batch_rows(batch_rows);
+
+            let mut total = 0;
+            for file in &files {
+                let report = loader.load_parquet(file).await?;
+                println!("Imported {}: {} rows in {} batches", file.display(), report.rows, report.batches);
+                total += report.rows;
+            }
+            println!("Imported {} rows from {} files", total, files.len());
+            Ok(())
+        }
+    }
+}
+
+/// Connect to the configured database.
+async fn manager() -> StorageResult<BackupManager> {
+    let config = StorageConfig::from_env()?;
+    let pool = StoragePool::new(config).await?;
+    Ok(BackupManager::new(pool))
+}
+
+fn print_tables(manifest: &BackupManifest) {
+    for (table, rows) in manifest.rows_by_table() {
+        println!("  {:<20} {:>12} rows", table, rows);
+    }
+}
@@ -129,10 +129,24 @@ async fn main() -> StorageResult<()> {
     health_handle.abort();
     metrics_handle.abort();
 
-    // Close pool
-    info!("Closing storage pool...");
-    pool.close().await;
-    info!("Storage pool closed");
+    // Flush buffered writes and close pool
+    let shutdown_timeout = std::env::var("SHUTDOWN_TIMEOUT_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(30);
+    info!("Flushing writers and closing storage pool...");
+    let report = pool
+        .shutdown(std::time::Duration::from_secs(shutdown_timeout))
+        .await;
+    if report.is_clean() {
+        info!("Storage pool closed, {} records flushed", report.flushed());
+    } else {
+        warn!(
+            "Storage pool closed, {} records flushed, {} dropped",
+            report.flushed(),
+            report.dropped()
+        );
+    }
 
     info!("Storage service shutdown complete");
     Ok(())
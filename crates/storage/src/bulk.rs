@@ -0,0 +1,577 @@
+//! Bulk loading of historical data from Parquet files.
+//!
+//! [`BulkLoader`] reads Parquet files as Arrow record batches, maps each
+//! batch to traces, spans or log records with a [`SchemaMapping`] and writes
+//! them with `COPY ... FROM STDIN BINARY` through
+//! [`CopyWriter`](crate::writers::copy::CopyWriter). It is meant for
+//! backfills of data that is not stored yet: COPY does not upsert, so a row
+//! that already exists fails its batch.
+//!
+//! A mapping names the target and, for each model field, the source column
+//! it is read from (by default a column of the same name) or a constant
+//! default:
+//!
+//! ```yaml
+//! target: spans
+//! columns:
+//!   trace_id: TraceId
+//!   span_id: SpanId
+//!   name: SpanName
+//!   start_time: Timestamp
+//!   attributes: SpanAttributes
+//! defaults:
+//!   service_name: legacy-gateway
+//!   kind: client
+//! ```
+//!
+//! Columns are converted with Arrow casts, so integers may be stored with any
+//! width, timestamps with any unit or as RFC 3339 strings, and JSON fields
+//! (`attributes`, `events`, ...) as JSON strings.
+//!
+//! Trace IDs must be 128-bit (32 hex digits). An imported trace is stored
+//! with the UUID spelled by its trace ID and imported spans reference that
+//! UUID, so traces and spans can be imported from separate files in any
+//! order.
+
+use crate::error::{StorageError, StorageResult};
+use crate::models::{LogRecord, Trace, TraceSpan};
+use crate::pool::StoragePool;
+use crate::writers::copy::CopyWriter;
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Int64Type, TimeUnit, TimestampMicrosecondType};
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Utc};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Default rows per record batch read from a file.
+pub const DEFAULT_BATCH_ROWS: usize = 8_192;
+
+/// Table a bulk load writes to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportTarget {
+    /// `traces`
+    Traces,
+    /// `trace_spans`
+    Spans,
+    /// `logs`
+    Logs,
+}
+
+impl ImportTarget {
+    /// Model fields of the target and whether each is required.
+    pub fn fields(&self) -> &'static [(&'static str, FieldKind, bool)] {
+        use FieldKind::*;
+        match self {
+            ImportTarget::Traces => &[
+                ("trace_id", Text, true),
+                ("service_name", Text, true),
+                ("start_time", Time, true),
+                ("end_time", Time, false),
+                ("duration_us", Integer, false),
+                ("status", Text, false),
+                ("status_message", Text, false),
+                ("root_span_name", Text, false),
+                ("attributes", Json, false),
+                ("resource_attributes", Json, false),
+                ("span_count", Integer, false),
+            ],
+            ImportTarget::Spans => &[
+                ("trace_id", Text, true),
+                ("span_id", Text, true),
+                ("parent_span_id", Text, false),
+                ("name", Text, true),
+                ("kind", Text, false),
+                ("service_name", Text, true),
+                ("start_time", Time, true),
+                ("end_time", Time, false),
+                ("duration_us", Integer, false),
+                ("status", Text, false),
+                ("status_message", Text, false),
+                ("attributes", Json, false),
+                ("events", Json, false),
+                ("links", Json, false),
+            ],
+            ImportTarget::Logs => &[
+                ("timestamp", Time, true),
+                ("observed_timestamp", Time, false),
+                ("severity_number", Integer, false),
+                ("severity_text", Text, false),
+                ("body", Text, true),
+                ("service_name", Text, true),
+                ("trace_id", Text, false),
+                ("span_id", Text, false),
+                ("trace_flags", Integer, false),
+                ("attributes", Json, false),
+                ("resource_attributes", Json, false),
+                ("scope_name", Text, false),
+                ("scope_version", Text, false),
+                ("scope_attributes", Json, false),
+            ],
+        }
+    }
+}
+
+/// Type a model field is read as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// UTF-8 string
+    Text,
+    /// 64-bit integer
+    Integer,
+    /// UTC timestamp
+    Time,
+    /// JSON document stored as a string
+    Json,
+}
+
+impl FieldKind {
+    fn arrow_type(&self) -> DataType {
+        match self {
+            FieldKind::Text | FieldKind::Json => DataType::Utf8,
+            FieldKind::Integer => DataType::Int64,
+            FieldKind::Time => DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into())),
+        }
+    }
+}
+
+/// How source columns map to the fields of an [`ImportTarget`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct SchemaMapping {
+    /// Table to load into
+    pub target: ImportTarget,
+
+    /// Source column of each field, where it differs from the field name
+    #[serde(default)]
+    pub columns: HashMap<String, String>,
+
+    /// Constant values of fields without a source column
+    #[serde(default)]
+    pub defaults: HashMap<String, String>,
+}
+
+impl SchemaMapping {
+    /// Identity mapping for `target`.
+    pub fn new(target: ImportTarget) -> Self {
+        Self {
+            target,
+            columns: HashMap::new(),
+            defaults: HashMap::new(),
+        }
+    }
+
+    /// Load a mapping from a YAML, TOML or JSON file.
+    pub fn from_file(path: &str) -> StorageResult<Self> {
+        use config::{Config, File};
+
+        let mapping: SchemaMapping = Config::builder()
+            .add_source(File::with_name(path))
+            .build()?
+            .try_deserialize()?;
+        mapping.validate()?;
+        Ok(mapping)
+    }
+
+    /// Check that every mapped or defaulted field exists on the target.
+    pub fn validate(&self) -> StorageResult<()> {
+        let fields = self.target.fields();
+        for field in self.columns.keys().chain(self.defaults.keys()) {
+            if !fields.iter().any(|(name, _, _)| name == field) {
+                return Err(StorageError::validation(format!(
+                    "Unknown field '{}' for import target {:?}",
+                    field, self.target
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Source column of a field.
+    fn source_column<'a>(&'a self, field: &'a str) -> &'a str {
+        self.columns.get(field).map(String::as_str).unwrap_or(field)
+    }
+
+    /// The values of every target field in `batch`.
+    fn resolve(&self, batch: &RecordBatch) -> StorageResult<MappedBatch> {
+        let mut values = HashMap::new();
+        for &(field, kind, required) in self.target.fields() {
+            let column = self.source_column(field);
+            let field_values = if let Some(array) = batch.column_by_name(column) {
+                let array = cast(array, &kind.arrow_type()).map_err(|e| {
+                    StorageError::validation(format!(
+                        "Column '{}' cannot be read as {:?} for field '{}': {}",
+                        column, kind, field, e
+                    ))
+                })?;
+                Values::Column(array)
+            } else if let Some(default) = self.defaults.get(field) {
+                Values::Constant(default.clone())
+            } else if required {
+                return Err(StorageError::validation(format!(
+                    "Required field '{}' has no column '{}' and no default",
+                    field, column
+                )));
+            } else {
+                Values::Absent
+            };
+            values.insert(field, (kind, field_values));
+        }
+
+        Ok(MappedBatch {
+            values,
+            rows: batch.num_rows(),
+        })
+    }
+}
+
+/// Source of one field's values.
+enum Values {
+    /// Column cast to the field's Arrow type
+    Column(ArrayRef),
+    /// Constant from the mapping's defaults
+    Constant(String),
+    /// Optional field without a source
+    Absent,
+}
+
+/// A record batch resolved against a mapping.
+struct MappedBatch {
+    values: HashMap<&'static str, (FieldKind, Values)>,
+    rows: usize,
+}
+
+impl MappedBatch {
+    fn text(&self, field: &str, row: usize) -> Option<String> {
+        match &self.values.get(field)?.1 {
+            Values::Column(array) if array.is_valid(row) => Some(array.as_string::<i32>().value(row).to_string()),
+            Values::Constant(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    fn integer(&self, field: &str, row: usize) -> StorageResult<Option<i64>> {
+        match self.values.get(field).map(|(_, v)| v) {
+            Some(Values::Column(array)) if array.is_valid(row) => {
+                Ok(Some(array.as_primitive::<Int64Type>().value(row)))
+            }
+            Some(Values::Constant(value)) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| StorageError::validation(format!("Default of '{}' is not an integer", field))),
+            _ => Ok(None),
+        }
+    }
+
+    fn time(&self, field: &str, row: usize) -> StorageResult<Option<DateTime<Utc>>> {
+        match self.values.get(field).map(|(_, v)| v) {
+            Some(Values::Column(array)) if array.is_valid(row) => {
+                let micros = array.as_primitive::<TimestampMicrosecondType>().value(row);
+                DateTime::from_timestamp_micros(micros)
+                    .map(Some)
+                    .ok_or_else(|| StorageError::validation(format!("'{}' is out of range in row {}", field, row)))
+            }
+            Some(Values::Constant(value)) => DateTime::parse_from_rfc3339(value)
+                .map(|t| Some(t.with_timezone(&Utc)))
+                .map_err(|_| StorageError::validation(format!("Default of '{}' is not an RFC 3339 time", field))),
+            _ => Ok(None),
+        }
+    }
+
+    fn json(&self, field: &str, row: usize) -> StorageResult<Option<Value>> {
+        self.text(field, row)
+            .map(|raw| {
+                serde_json::from_str(&raw).map_err(|e| {
+                    StorageError::validation(format!("'{}' is not valid JSON in row {}: {}", field, row, e))
+                })
+            })
+            .transpose()
+    }
+
+    fn required_text(&self, field: &str, row: usize) -> StorageResult<String> {
+        self.text(field, row)
+            .ok_or_else(|| StorageError::validation(format!("'{}' is null in row {}", field, row)))
+    }
+
+    fn required_time(&self, field: &str, row: usize) -> StorageResult<DateTime<Utc>> {
+        self.time(field, row)?
+            .ok_or_else(|| StorageError::validation(format!("'{}' is null in row {}", field, row)))
+    }
+
+    fn object(&self, field: &str, row: usize) -> StorageResult<Value> {
+        Ok(self.json(field, row)?.unwrap_or_else(|| Value::Object(Default::default())))
+    }
+
+    fn duration_us(&self, row: usize, start: DateTime<Utc>, end: Option<DateTime<Utc>>) -> StorageResult<Option<i64>> {
+        Ok(self
+            .integer("duration_us", row)?
+            .or_else(|| end.map(|end| (end - start).num_microseconds().unwrap_or(0))))
+    }
+}
+
+/// UUID of an imported trace: its 128-bit trace ID.
+pub fn trace_uuid(trace_id: &str) -> StorageResult<Uuid> {
+    Uuid::try_parse(trace_id.trim()).map_err(|_| {
+        StorageError::validation(format!("Trace ID '{}' is not a 128-bit hex ID", trace_id))
+    })
+}
+
+/// Map a record batch to traces.
+pub fn traces_from_batch(batch: &RecordBatch, mapping: &SchemaMapping) -> StorageResult<Vec<Trace>> {
+    let mapped = mapping.resolve(batch)?;
+    let now = Utc::now();
+
+    (0..mapped.rows)
+        .map(|row| {
+            let trace_id = mapped.required_text("trace_id", row)?;
+            let start_time = mapped.required_time("start_time", row)?;
+            let end_time = mapped.time("end_time", row)?;
+            Ok(Trace {
+                id: trace_uuid(&trace_id)?,
+                trace_id,
+                service_name: mapped.required_text("service_name", row)?,
+                start_time,
+                end_time,
+                duration_us: mapped.duration_us(row, start_time, end_time)?,
+                status: mapped.text("status", row).unwrap_or_else(|| "unset".to_string()),
+                status_message: mapped.text("status_message", row),
+                root_span_name: mapped.text("root_span_name", row),
+                attributes: mapped.object("attributes", row)?,
+                resource_attributes: mapped.object("resource_attributes", row)?,
+                span_count: mapped.integer("span_count", row)?.unwrap_or(0) as i32,
+                created_at: now,
+                updated_at: now,
+            })
+        })
+        .collect()
+}
+
+/// Map a record batch to spans.
+pub fn spans_from_batch(batch: &RecordBatch, mapping: &SchemaMapping) -> StorageResult<Vec<TraceSpan>> {
+    let mapped = mapping.resolve(batch)?;
+    let now = Utc::now();
+
+    (0..mapped.rows)
+        .map(|row| {
+            let start_time = mapped.required_time("start_time", row)?;
+            let end_time = mapped.time("end_time", row)?;
+            Ok(TraceSpan {
+                id: Uuid::new_v4(),
+                trace_id: trace_uuid(&mapped.required_text("trace_id", row)?)?,
+                span_id: mapped.required_text("span_id", row)?,
+                parent_span_id: mapped.text("parent_span_id", row).filter(|p| !p.is_empty()),
+                name: mapped.required_text("name", row)?,
+                kind: mapped.text("kind", row).unwrap_or_else(|| "internal".to_string()),
+                service_name: mapped.required_text("service_name", row)?,
+                start_time,
+                end_time,
+                duration_us: mapped.duration_us(row, start_time, end_time)?,
+                status: mapped.text("status", row).unwrap_or_else(|| "unset".to_string()),
+                status_message: mapped.text("status_message", row),
+                attributes: mapped.object("attributes", row)?,
+                events: mapped.json("events", row)?,
+                links: mapped.json("links", row)?,
+                created_at: now,
+            })
+        })
+        .collect()
+}
+
+/// Map a record batch to log records.
+pub fn logs_from_batch(batch: &RecordBatch, mapping: &SchemaMapping) -> StorageResult<Vec<LogRecord>> {
+    let mapped = mapping.resolve(batch)?;
+    let now = Utc::now();
+
+    (0..mapped.rows)
+        .map(|row| {
+            let timestamp = mapped.required_time("timestamp", row)?;
+            Ok(LogRecord {
+                id: Uuid::new_v4(),
+                timestamp,
+                observed_timestamp: mapped.time("observed_timestamp", row)?.unwrap_or(timestamp),
+                severity_number: mapped.integer("severity_number", row)?.unwrap_or(0) as i32,
+                severity_text: mapped.text("severity_text", row).unwrap_or_default(),
+                body: mapped.required_text("body", row)?,
+                service_name: mapped.required_text("service_name", row)?,
+                trace_id: mapped.text("trace_id", row),
+                span_id: mapped.text("span_id", row),
+                trace_flags: mapped.integer("trace_flags", row)?.map(|f| f as i32),
+                attributes: mapped.object("attributes", row)?,
+                resource_attributes: mapped.object("resource_attributes", row)?,
+                scope_name: mapped.text("scope_name", row),
+                scope_version: mapped.text("scope_version", row),
+                scope_attributes: mapped.json("scope_attributes", row)?,
+                pattern_id: None,
+                created_at: now,
+            })
+        })
+        .collect()
+}
+
+/// Rows loaded from one file.
+#[derive(Debug, Clone, Default)]
+pub struct LoadReport {
+    /// Record batches read
+    pub batches: u64,
+
+    /// Rows written
+    pub rows: u64,
+}
+
+/// Loads Parquet files into the database with COPY.
+pub struct BulkLoader {
+    pool: StoragePool,
+    mapping: SchemaMapping,
+    batch_rows: usize,
+}
+
+impl BulkLoader {
+    /// Create a loader writing through `pool` with `mapping`.
+    pub fn new(pool: StoragePool, mapping: SchemaMapping) -> Self {
+        Self {
+            pool,
+            mapping,
+            batch_rows: DEFAULT_BATCH_ROWS,
+        }
+    }
+
+    /// Rows per record batch, and so per COPY.
+    pub fn with_batch_rows(mut self, rows: usize) -> Self {
+        self.batch_rows = rows.max(1);
+        self
+    }
+
+    /// Load a Parquet file, one COPY per record batch.
+    ///
+    /// Batches are committed as they are written; on error the report of
+    /// the batches already loaded is lost with the error, so re-import only
+    /// into a range that was cleaned up.
+    pub async fn load_parquet(&self, path: &Path) -> StorageResult<LoadReport> {
+        self.mapping.validate()?;
+        let file = std::fs::File::open(path)
+            .map_err(|e| StorageError::Internal(format!("{}: {}", path.display(), e)))?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .and_then(|builder| builder.with_batch_size(self.batch_rows).build())
+            .map_err(|e| StorageError::SerializationError(format!("{}: {}", path.display(), e)))?;
+
+        let (client, connection) = self.pool.get_tokio_postgres_client().await?;
+        let mut report = LoadReport::default();
+        for batch in reader {
+            let batch = batch.map_err(|e| StorageError::SerializationError(format!("{}: {}", path.display(), e)))?;
+            report.rows += self.load_batch(&client, &batch).await?;
+            report.batches += 1;
+            tracing::debug!(file = %path.display(), rows = report.rows, "Loaded record batch");
+        }
+        drop(client);
+        connection.abort();
+
+        tracing::info!(file = %path.display(), rows = report.rows, target = ?self.mapping.target, "Bulk load complete");
+        Ok(report)
+    }
+
+    /// Map and COPY one record batch, returning the rows written.
+    pub async fn load_batch(&self, client: &tokio_postgres::Client, batch: &RecordBatch) -> StorageResult<u64> {
+        match self.mapping.target {
+            ImportTarget::Traces => CopyWriter::write_traces(client, traces_from_batch(batch, &self.mapping)?).await,
+            ImportTarget::Spans => CopyWriter::write_spans(client, spans_from_batch(batch, &self.mapping)?).await,
+            ImportTarget::Logs => CopyWriter::write_logs(client, logs_from_batch(batch, &self.mapping)?).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int32Array, StringArray, TimestampMillisecondArray};
+    use arrow::datatypes::{Field, Schema};
+    use std::sync::Arc;
+
+    const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+
+    fn span_batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("TraceId", DataType::Utf8, false),
+            Field::new("SpanId", DataType::Utf8, false),
+            Field::new("SpanName", DataType::Utf8, false),
+            Field::new("Timestamp", DataType::Timestamp(TimeUnit::Millisecond, None), false),
+            Field::new("end_time", DataType::Utf8, true),
+            Field::new("SpanAttributes", DataType::Utf8, true),
+            Field::new("http_status", DataType::Int32, true),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from(vec![TRACE_ID, TRACE_ID])),
+                Arc::new(StringArray::from(vec!["00f067aa0ba902b7", "00f067aa0ba902b8"])),
+                Arc::new(StringArray::from(vec!["chat gpt-4o", "embeddings"])),
+                Arc::new(TimestampMillisecondArray::from(vec![1_730_419_200_000, 1_730_419_200_500])),
+                Arc::new(StringArray::from(vec![Some("2024-11-01T00:00:01.250Z"), None])),
+                Arc::new(StringArray::from(vec![Some(r#"{"gen_ai.system":"openai"}"#), None])),
+                Arc::new(Int32Array::from(vec![Some(200), None])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn span_mapping() -> SchemaMapping {
+        let mut mapping = SchemaMapping::new(ImportTarget::Spans);
+        for (field, column) in [
+            ("trace_id", "TraceId"),
+            ("span_id", "SpanId"),
+            ("name", "SpanName"),
+            ("start_time", "Timestamp"),
+            ("attributes", "SpanAttributes"),
+        ] {
+            mapping.columns.insert(field.to_string(), column.to_string());
+        }
+        mapping.defaults.insert("service_name".to_string(), "legacy-gateway".to_string());
+        mapping.defaults.insert("kind".to_string(), "client".to_string());
+        mapping
+    }
+
+    #[test]
+    fn test_spans_from_batch() {
+        let spans = spans_from_batch(&span_batch(), &span_mapping()).unwrap();
+        assert_eq!(spans.len(), 2);
+
+        let span = &spans[0];
+        assert_eq!(span.trace_id, trace_uuid(TRACE_ID).unwrap());
+        assert_eq!(span.trace_id.simple().to_string(), TRACE_ID);
+        assert_eq!(span.span_id, "00f067aa0ba902b7");
+        assert_eq!(span.service_name, "legacy-gateway");
+        assert_eq!(span.kind, "client");
+        assert_eq!(span.start_time.timestamp_millis(), 1_730_419_200_000);
+        assert_eq!(span.duration_us, Some(1_250_000));
+        assert_eq!(span.attributes["gen_ai.system"], "openai");
+        assert_eq!(span.status, "unset");
+
+        assert_eq!(spans[1].end_time, None);
+        assert_eq!(spans[1].duration_us, None);
+        assert_eq!(spans[1].attributes, serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_mapping_errors() {
+        // Missing required field
+        let mut mapping = span_mapping();
+        mapping.defaults.remove("service_name");
+        assert!(spans_from_batch(&span_batch(), &mapping).is_err());
+
+        // Unknown field
+        let mut mapping = span_mapping();
+        mapping.columns.insert("http_status".to_string(), "http_status".to_string());
+        assert!(mapping.validate().is_err());
+
+        // Column that cannot be cast to the field's type
+        let mut mapping = span_mapping();
+        mapping.columns.insert("start_time".to_string(), "SpanName".to_string());
+        assert!(spans_from_batch(&span_batch(), &mapping).is_err());
+
+        assert!(trace_uuid("not-a-trace-id").is_err());
+    }
+}
@@ -0,0 +1,381 @@
+//! Circuit breaker for database access.
+//!
+//! When PostgreSQL becomes slow or unreachable, letting every caller wait for
+//! its own timeout only piles more load onto a sick database. The breaker
+//! tracks consecutive failures (including calls slower than the configured
+//! threshold) and, once open, rejects calls immediately with
+//! [`StorageError::CircuitOpen`] so callers can shed load, e.g. by spooling
+//! to disk. After the open period a limited number of probe calls are let
+//! through; a successful probe closes the circuit again.
+//!
+//! ```text
+//!   Closed --(N failures)--> Open --(open duration)--> HalfOpen
+//!     ^                                                  |  |
+//!     +-----------------(probe succeeds)-----------------+  |
+//!                         Open <---(probe fails)------------+
+//! ```
+
+use crate::config::CircuitBreakerConfig;
+use crate::error::{StorageError, StorageResult};
+use crate::metrics::StorageMetrics;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// State of the circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls flow normally
+    Closed,
+    /// Calls are rejected without touching the database
+    Open,
+    /// A limited number of probe calls are allowed through
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// Label used in logs and health responses.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half_open",
+        }
+    }
+
+    /// Numeric value used for the state gauge.
+    fn gauge_value(&self) -> u8 {
+        match self {
+            CircuitState::Closed => 0,
+            CircuitState::HalfOpen => 1,
+            CircuitState::Open => 2,
+        }
+    }
+}
+
+/// Point-in-time view of the circuit breaker.
+#[derive(Debug, Clone)]
+pub struct CircuitSnapshot {
+    /// Current state
+    pub state: CircuitState,
+
+    /// Consecutive failures observed while closed
+    pub consecutive_failures: u32,
+
+    /// Time since the circuit last opened (None if closed)
+    pub open_for: Option<Duration>,
+
+    /// Total calls rejected since creation
+    pub rejections: u64,
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    half_open_in_flight: u32,
+    half_open_since: Option<Instant>,
+    rejections: u64,
+}
+
+/// Circuit breaker guarding database calls.
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    inner: Mutex<Inner>,
+    metrics: StorageMetrics,
+}
+
+impl CircuitBreaker {
+    /// Create a new circuit breaker in the closed state.
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        let metrics = StorageMetrics::new();
+        metrics.update_circuit_state(CircuitState::Closed.gauge_value());
+
+        Self {
+            config,
+            inner: Mutex::new(Inner {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                half_open_in_flight: 0,
+                half_open_since: None,
+                rejections: 0,
+            }),
+            metrics,
+        }
+    }
+
+    /// Get the circuit breaker configuration.
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// Current state, accounting for an elapsed open period.
+    pub fn state(&self) -> CircuitState {
+        self.snapshot().state
+    }
+
+    /// Get a point-in-time view of the breaker.
+    pub fn snapshot(&self) -> CircuitSnapshot {
+        let mut inner = self.lock();
+        self.refresh(&mut inner);
+
+        CircuitSnapshot {
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            open_for: inner.opened_at.map(|t| t.elapsed()),
+            rejections: inner.rejections,
+        }
+    }
+
+    /// Ask permission to make a call.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::CircuitOpen`] if the circuit is open, or if it
+    /// is half-open and all probe slots are taken.
+    pub fn try_acquire(&self) -> StorageResult<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let mut inner = self.lock();
+        self.refresh(&mut inner);
+
+        match inner.state {
+            CircuitState::Closed => Ok(()),
+            CircuitState::HalfOpen if inner.half_open_in_flight < self.config.half_open_max_probes => {
+                inner.half_open_in_flight += 1;
+                Ok(())
+            }
+            _ => {
+                inner.rejections += 1;
+                self.metrics.record_circuit_rejection();
+                Err(StorageError::CircuitOpen(format!(
+                    "database calls suspended after {} consecutive failures",
+                    inner.consecutive_failures
+                )))
+            }
+        }
+    }
+
+    /// Record a completed call.
+    ///
+    /// Calls slower than the slow call threshold are treated as failures.
+    pub fn record_success(&self, elapsed: Duration) {
+        if !self.config.enabled {
+            return;
+        }
+
+        if elapsed > self.config.slow_call_threshold() {
+            tracing::warn!(
+                "Slow database call ({:?}) counted as circuit breaker failure",
+                elapsed
+            );
+            self.record_failure();
+            return;
+        }
+
+        let mut inner = self.lock();
+        match inner.state {
+            CircuitState::HalfOpen => {
+                tracing::info!("Circuit breaker probe succeeded, closing circuit");
+                self.transition(&mut inner, CircuitState::Closed);
+            }
+            _ => inner.consecutive_failures = 0,
+        }
+    }
+
+    /// Record a failed call.
+    pub fn record_failure(&self) {
+        if !self.config.enabled {
+            return;
+        }
+
+        let mut inner = self.lock();
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+
+        match inner.state {
+            CircuitState::HalfOpen => {
+                tracing::warn!("Circuit breaker probe failed, reopening circuit");
+                self.transition(&mut inner, CircuitState::Open);
+            }
+            CircuitState::Closed if inner.consecutive_failures >= self.config.failure_threshold => {
+                tracing::error!(
+                    "Circuit breaker opened after {} consecutive failures",
+                    inner.consecutive_failures
+                );
+                self.transition(&mut inner, CircuitState::Open);
+            }
+            _ => {}
+        }
+    }
+
+    /// Run a database call through the breaker.
+    ///
+    /// Only connection, pool and timeout errors count as failures; other
+    /// errors (constraint violations, bad input) mean the database answered.
+    pub async fn call<T, F>(&self, op: F) -> StorageResult<T>
+    where
+        F: Future<Output = StorageResult<T>>,
+    {
+        self.try_acquire()?;
+
+        let start = Instant::now();
+        let result = op.await;
+
+        match &result {
+            Err(e) if e.is_retryable() => self.record_failure(),
+            _ => self.record_success(start.elapsed()),
+        }
+
+        result
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Move from open to half-open once the open period has elapsed, and free
+    /// probe slots held by calls that never reported back.
+    fn refresh(&self, inner: &mut Inner) {
+        let open_duration = self.config.open_duration();
+
+        match inner.state {
+            CircuitState::Open => {
+                if matches!(inner.opened_at, Some(t) if t.elapsed() >= open_duration) {
+                    tracing::info!("Circuit breaker half-open, allowing probe calls");
+                    self.transition(inner, CircuitState::HalfOpen);
+                }
+            }
+            CircuitState::HalfOpen => {
+                if matches!(inner.half_open_since, Some(t) if t.elapsed() >= open_duration) {
+                    inner.half_open_in_flight = 0;
+                    inner.half_open_since = Some(Instant::now());
+                }
+            }
+            CircuitState::Closed => {}
+        }
+    }
+
+    fn transition(&self, inner: &mut Inner, state: CircuitState) {
+        inner.state = state;
+        inner.half_open_in_flight = 0;
+
+        match state {
+            CircuitState::Closed => {
+                inner.consecutive_failures = 0;
+                inner.opened_at = None;
+                inner.half_open_since = None;
+            }
+            CircuitState::Open => {
+                inner.opened_at = Some(Instant::now());
+                inner.half_open_since = None;
+            }
+            CircuitState::HalfOpen => {
+                inner.half_open_since = Some(Instant::now());
+            }
+        }
+
+        self.metrics.update_circuit_state(state.gauge_value());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(open_duration_secs: u64) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            enabled: true,
+            failure_threshold: 3,
+            slow_call_threshold_ms: 100,
+            open_duration_secs,
+            half_open_max_probes: 1,
+        })
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let cb = breaker(30);
+        cb.record_failure();
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Closed);
+
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Open);
+
+        let err = cb.try_acquire().unwrap_err();
+        assert!(err.is_circuit_open());
+        assert_eq!(cb.snapshot().rejections, 1);
+    }
+
+    #[test]
+    fn test_success_resets_failures() {
+        let cb = breaker(30);
+        cb.record_failure();
+        cb.record_failure();
+        cb.record_success(Duration::from_millis(1));
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_slow_calls_count_as_failures() {
+        let cb = breaker(30);
+        for _ in 0..3 {
+            cb.record_success(Duration::from_millis(500));
+        }
+        assert_eq!(cb.state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_half_open_probe() {
+        let cb = breaker(0);
+        for _ in 0..3 {
+            cb.record_failure();
+        }
+        assert_eq!(cb.state(), CircuitState::HalfOpen);
+
+        // One probe slot; a failed probe reopens the circuit
+        assert!(cb.try_acquire().is_ok());
+        cb.record_failure();
+        assert_eq!(cb.lock().state, CircuitState::Open);
+
+        // A successful probe closes it
+        assert!(cb.try_acquire().is_ok());
+        cb.record_success(Duration::from_millis(1));
+        assert_eq!(cb.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_disabled_never_opens() {
+        let cb = CircuitBreaker::new(CircuitBreakerConfig {
+            enabled: false,
+            ..CircuitBreakerConfig::default()
+        });
+        for _ in 0..100 {
+            cb.record_failure();
+        }
+        assert!(cb.try_acquire().is_ok());
+        assert_eq!(cb.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_call_ignores_non_transient_errors() {
+        let cb = breaker(30);
+        for _ in 0..5 {
+            let result: StorageResult<()> =
+                cb.call(async { Err(StorageError::validation("bad input")) }).await;
+            assert!(result.is_err());
+        }
+        assert_eq!(cb.state(), CircuitState::Closed);
+
+        for _ in 0..3 {
+            let _: StorageResult<()> =
+                cb.call(async { Err(StorageError::connection("refused")) }).await;
+        }
+        assert_eq!(cb.state(), CircuitState::Open);
+    }
+}
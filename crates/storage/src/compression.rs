@@ -0,0 +1,174 @@
+//! Compression of large JSONB payloads.
+//!
+//! Span events and event attributes can be large (prompt and completion
+//! bodies, stack traces) but are only read back whole, never filtered on in
+//! SQL. Span links are small and are queried to follow links across traces,
+//! so they are never compressed. When compression is enabled they are stored as an
+//! envelope object so the column type stays JSONB:
+//!
+//! ```json
+//! {"$compressed": "zstd", "data": "<base64>"}
+//! ```
+//!
+//! Reads go through [`decompress_json`], which passes uncompressed values
+//! through unchanged, so compressed and uncompressed rows can coexist.
+
+use crate::config::{CompressionAlgorithm, CompressionConfig};
+use crate::error::{StorageError, StorageResult};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+
+/// Envelope key holding the algorithm name.
+pub const ENVELOPE_KEY: &str = "$compressed";
+
+/// Compress a JSON value if it is large enough and compression pays off.
+///
+/// Returns the value unchanged when compression is disabled, the payload is
+/// below `min_size_bytes`, or the envelope would not be smaller.
+pub fn compress_json(value: Value, config: &CompressionConfig) -> StorageResult<Value> {
+    if !config.is_enabled() || value.is_null() || is_compressed(&value) {
+        return Ok(value);
+    }
+
+    let raw = serde_json::to_vec(&value)?;
+    if raw.len() < config.min_size_bytes {
+        return Ok(value);
+    }
+
+    let compressed = compress_bytes(&raw, config.algorithm, config.level)?;
+    let data = BASE64.encode(compressed);
+    // The envelope adds about 40 bytes on top of the base64 data
+    if data.len() + 40 >= raw.len() {
+        return Ok(value);
+    }
+
+    Ok(json!({ ENVELOPE_KEY: config.algorithm.as_str(), "data": data }))
+}
+
+/// Compress an optional JSON value.
+pub fn compress_json_opt(
+    value: Option<Value>,
+    config: &CompressionConfig,
+) -> StorageResult<Option<Value>> {
+    value.map(|v| compress_json(v, config)).transpose()
+}
+
+/// Decompress a JSON value written by [`compress_json`].
+///
+/// Values that are not compression envelopes are returned unchanged.
+pub fn decompress_json(value: Value) -> StorageResult<Value> {
+    let Some((algorithm, data)) = envelope(&value) else {
+        return Ok(value);
+    };
+
+    let compressed = BASE64
+        .decode(data)
+        .map_err(|e| StorageError::SerializationError(format!("Invalid compressed payload: {}", e)))?;
+    let raw = decompress_bytes(&compressed, algorithm)?;
+
+    Ok(serde_json::from_slice(&raw)?)
+}
+
+/// Decompress an optional JSON value.
+pub fn decompress_json_opt(value: Option<Value>) -> StorageResult<Option<Value>> {
+    value.map(decompress_json).transpose()
+}
+
+/// Whether a value is a compression envelope.
+pub fn is_compressed(value: &Value) -> bool {
+    envelope(value).is_some()
+}
+
+/// Algorithm and base64 data of a compression envelope.
+fn envelope(value: &Value) -> Option<(CompressionAlgorithm, &str)> {
+    let obj = value.as_object()?;
+    if obj.len() != 2 {
+        return None;
+    }
+
+    let algorithm = match obj.get(ENVELOPE_KEY)?.as_str()? {
+        "gzip" => CompressionAlgorithm::Gzip,
+        "zstd" => CompressionAlgorithm::Zstd,
+        _ => return None,
+    };
+    Some((algorithm, obj.get("data")?.as_str()?))
+}
+
+fn compress_bytes(raw: &[u8], algorithm: CompressionAlgorithm, level: i32) -> StorageResult<Vec<u8>> {
+    let result = match algorithm {
+        CompressionAlgorithm::None => Ok(raw.to_vec()),
+        CompressionAlgorithm::Gzip => {
+            let level = flate2::Compression::new(level.clamp(0, 9) as u32);
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), level);
+            encoder.write_all(raw).and_then(|_| encoder.finish())
+        }
+        CompressionAlgorithm::Zstd => zstd::encode_all(raw, level),
+    };
+
+    result.map_err(|e| StorageError::SerializationError(format!("Compression failed: {}", e)))
+}
+
+fn decompress_bytes(compressed: &[u8], algorithm: CompressionAlgorithm) -> StorageResult<Vec<u8>> {
+    let result = match algorithm {
+        CompressionAlgorithm::None => Ok(compressed.to_vec()),
+        CompressionAlgorithm::Gzip => {
+            let mut raw = Vec::new();
+            flate2::read::GzDecoder::new(compressed)
+                .read_to_end(&mut raw)
+                .map(|_| raw)
+        }
+        CompressionAlgorithm::Zstd => zstd::decode_all(compressed),
+    };
+
+    result.map_err(|e| StorageError::SerializationError(format!("Decompression failed: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn large_events() -> Value {
+        let events: Vec<Value> = (0..200)
+            .map(|i| json!({"name": "llm.completion", "attributes": {"index": i, "text": "lorem ipsum dolor sit amet"}}))
+            .collect();
+        Value::Array(events)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for algorithm in [CompressionAlgorithm::Gzip, CompressionAlgorithm::Zstd] {
+            let config = CompressionConfig {
+                algorithm,
+                ..Default::default()
+            };
+            let events = large_events();
+
+            let stored = compress_json(events.clone(), &config).unwrap();
+            assert!(is_compressed(&stored));
+            assert_eq!(stored[ENVELOPE_KEY], algorithm.as_str());
+            assert_eq!(decompress_json(stored).unwrap(), events);
+        }
+    }
+
+    #[test]
+    fn test_small_or_disabled_passthrough() {
+        let small = json!([{"name": "retry"}]);
+        let config = CompressionConfig {
+            algorithm: CompressionAlgorithm::Zstd,
+            ..Default::default()
+        };
+        assert_eq!(compress_json(small.clone(), &config).unwrap(), small);
+        assert_eq!(
+            compress_json(large_events(), &CompressionConfig::default()).unwrap(),
+            large_events()
+        );
+        assert_eq!(decompress_json(small.clone()).unwrap(), small);
+
+        // Objects that merely look like an envelope are left alone
+        let lookalike = json!({ENVELOPE_KEY: "lz4", "data": "abc"});
+        assert!(!is_compressed(&lookalike));
+        assert_eq!(decompress_json(lookalike.clone()).unwrap(), lookalike);
+    }
+}
@@ -0,0 +1,326 @@
+//! PostgreSQL credential providers.
+//!
+//! The pool asks its [`CredentialProvider`] for a password whenever it
+//! builds connection options: when connecting and, for providers with a
+//! refresh interval, periodically afterwards so new connections never use an
+//! expired password. Providers:
+//!
+//! - [`StaticCredentials`]: the configured password
+//! - [`EnvCredentials`]: a password read from an environment variable
+//! - [`AwsIamCredentials`]: RDS IAM authentication tokens, refreshed before
+//!   their 15-minute expiry
+//!
+//! Other sources (e.g. a secrets manager) plug in by implementing
+//! [`CredentialProvider`] and passing it to
+//! [`StoragePool::with_credentials`](crate::StoragePool::with_credentials).
+
+use crate::config::{CredentialsConfig, PostgresConfig};
+use crate::error::{StorageError, StorageResult};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use ring::{digest, hmac};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Source of the PostgreSQL password.
+#[async_trait]
+pub trait CredentialProvider: Send + Sync {
+    /// Password to connect with.
+    async fn password(&self) -> StorageResult<String>;
+
+    /// How often the pool should fetch a new password for new connections;
+    /// `None` if the password does not expire.
+    fn refresh_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Provider name, used in logs.
+    fn name(&self) -> &'static str;
+}
+
+/// Create the provider configured for `config`.
+pub fn from_config(config: &PostgresConfig) -> Arc<dyn CredentialProvider> {
+    match &config.credentials {
+        CredentialsConfig::Static => Arc::new(StaticCredentials::new(config.password.clone())),
+        CredentialsConfig::Env { variable } => Arc::new(EnvCredentials::new(variable.clone())),
+        CredentialsConfig::AwsIam {
+            region,
+            refresh_secs,
+        } => Arc::new(
+            AwsIamCredentials::new(&config.host, config.port, &config.username, region)
+                .with_refresh_interval(Duration::from_secs(*refresh_secs)),
+        ),
+    }
+}
+
+/// The configured password.
+#[derive(Clone)]
+pub struct StaticCredentials {
+    password: String,
+}
+
+impl StaticCredentials {
+    /// Create a provider returning `password`.
+    pub fn new(password: impl Into<String>) -> Self {
+        Self {
+            password: password.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for StaticCredentials {
+    async fn password(&self) -> StorageResult<String> {
+        Ok(self.password.clone())
+    }
+
+    fn name(&self) -> &'static str {
+        "static"
+    }
+}
+
+/// A password read from an environment variable on every call.
+#[derive(Debug, Clone)]
+pub struct EnvCredentials {
+    variable: String,
+}
+
+impl EnvCredentials {
+    /// Create a provider reading `variable`.
+    pub fn new(variable: impl Into<String>) -> Self {
+        Self {
+            variable: variable.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for EnvCredentials {
+    async fn password(&self) -> StorageResult<String> {
+        std::env::var(&self.variable).map_err(|_| {
+            StorageError::ConfigError(format!(
+                "Password environment variable {} is not set",
+                self.variable
+            ))
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "env"
+    }
+}
+
+/// AWS credentials used to sign IAM authentication tokens.
+#[derive(Clone)]
+pub struct AwsCredentials {
+    /// Access key ID
+    pub access_key_id: String,
+    /// Secret access key
+    pub secret_access_key: String,
+    /// Session token of temporary credentials
+    pub session_token: Option<String>,
+}
+
+impl AwsCredentials {
+    /// Read `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and the optional
+    /// `AWS_SESSION_TOKEN`.
+    pub fn from_env() -> StorageResult<Self> {
+        let var = |name: &str| {
+            std::env::var(name).map_err(|_| {
+                StorageError::ConfigError(format!("{} is required for IAM authentication", name))
+            })
+        };
+
+        Ok(Self {
+            access_key_id: var("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: var("AWS_SECRET_ACCESS_KEY")?,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
+/// RDS IAM authentication tokens.
+///
+/// A token is a SigV4-presigned `connect` request for the database user,
+/// valid for 15 minutes. Tokens are generated locally; the AWS credentials
+/// are read from the environment on every call, so rotated temporary
+/// credentials are picked up.
+#[derive(Debug, Clone)]
+pub struct AwsIamCredentials {
+    host: String,
+    port: u16,
+    username: String,
+    region: String,
+    refresh_interval: Duration,
+}
+
+/// Service name of RDS IAM authentication in SigV4 signatures
+const RDS_SERVICE: &str = "rds-db";
+
+/// Seconds an IAM authentication token is valid
+const TOKEN_EXPIRES_SECS: u64 = 900;
+
+impl AwsIamCredentials {
+    /// Create a provider for `username` on the database at `host:port`.
+    pub fn new(host: &str, port: u16, username: &str, region: &str) -> Self {
+        Self {
+            host: host.to_string(),
+            port,
+            username: username.to_string(),
+            region: region.to_string(),
+            refresh_interval: Duration::from_secs(600),
+        }
+    }
+
+    /// Set how often the pool fetches a new token.
+    pub fn with_refresh_interval(mut self, interval: Duration) -> Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    /// Generate a token signed with `credentials` at `now`.
+    pub fn token(&self, credentials: &AwsCredentials, now: DateTime<Utc>) -> String {
+        let datetime = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = &datetime[..8];
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, RDS_SERVICE);
+        let endpoint = format!("{}:{}", self.host, self.port);
+
+        let mut params = vec![
+            ("Action", "connect".to_string()),
+            ("DBUser", self.username.clone()),
+            ("X-Amz-Algorithm", "AWS4-HMAC-SHA256".to_string()),
+            (
+                "X-Amz-Credential",
+                format!("{}/{}", credentials.access_key_id, scope),
+            ),
+            ("X-Amz-Date", datetime.clone()),
+            ("X-Amz-Expires", TOKEN_EXPIRES_SECS.to_string()),
+            ("X-Amz-SignedHeaders", "host".to_string()),
+        ];
+        if let Some(token) = &credentials.session_token {
+            params.push(("X-Amz-Security-Token", token.clone()));
+        }
+        params.sort();
+        let query = params
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, uri_encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let canonical_request = format!(
+            "GET\n/\n{}\nhost:{}\n\nhost\n{}",
+            query,
+            endpoint,
+            hex::encode(digest::digest(&digest::SHA256, b"").as_ref())
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            datetime,
+            scope,
+            hex::encode(digest::digest(&digest::SHA256, canonical_request.as_bytes()).as_ref())
+        );
+
+        let key = signing_key(
+            &credentials.secret_access_key,
+            date,
+            &self.region,
+            RDS_SERVICE,
+        );
+        let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+
+        format!("{}/?{}&X-Amz-Signature={}", endpoint, query, signature)
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for AwsIamCredentials {
+    async fn password(&self) -> StorageResult<String> {
+        Ok(self.token(&AwsCredentials::from_env()?, Utc::now()))
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        Some(self.refresh_interval)
+    }
+
+    fn name(&self) -> &'static str {
+        "aws_iam"
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+    hmac::sign(&key, data).as_ref().to_vec()
+}
+
+/// SigV4 signing key of a day, region and service.
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+/// Percent-encode everything but the SigV4 unreserved characters.
+fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_signing_key() {
+        // Example from the AWS SigV4 documentation
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_iam_token() {
+        let provider = AwsIamCredentials::new(
+            "db.abc123.us-east-1.rds.amazonaws.com",
+            5432,
+            "observatory",
+            "us-east-1",
+        );
+        let credentials = AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: Some("token/with+chars=".to_string()),
+        };
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+
+        let token = provider.token(&credentials, now);
+        assert!(token.starts_with(
+            "db.abc123.us-east-1.rds.amazonaws.com:5432/?Action=connect&DBUser=observatory&"
+        ));
+        assert!(token.contains(
+            "X-Amz-Credential=AKIDEXAMPLE%2F20250101%2Fus-east-1%2Frds-db%2Faws4_request"
+        ));
+        assert!(token.contains("X-Amz-Date=20250101T120000Z&X-Amz-Expires=900"));
+        assert!(token.contains("X-Amz-Security-Token=token%2Fwith%2Bchars%3D"));
+        assert_eq!(token.rsplit("X-Amz-Signature=").next().unwrap().len(), 64);
+
+        // Deterministic for the same credentials and time
+        assert_eq!(token, provider.token(&credentials, now));
+        assert_eq!(provider.refresh_interval(), Some(Duration::from_secs(600)));
+    }
+}
@@ -0,0 +1,613 @@
+//! Downsampling of old metric data points.
+//!
+//! Raw data points are only needed at full resolution for recent data. The
+//! [`Downsampler`] rolls points older than the configured ages into 5-minute
+//! and 1-hour points (see migration `029_metric_downsampling.sql`), one
+//! one-hour window per transaction: the rolled points are inserted, their
+//! sources deleted, and the window recorded in `metric_downsampling_runs`.
+//!
+//! Points of one series (metric and attribute set) in one interval roll up
+//! according to the metric type:
+//!
+//! - **Gauge**: mean value, with the count, sum, min and max of the values
+//! - **Counter**: the last value, as counters are cumulative
+//! - **Histogram**: bucket counts, count and sum added, as points are deltas
+//! - **Summary**: the last point, as quantiles cannot be merged
+//!
+//! Rolled points are stamped with the start of their interval and keep the
+//! most recent exemplars of their sources.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm_observatory_storage::{downsampling::Downsampler, StorageConfig, StoragePool};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let pool = StoragePool::new(StorageConfig::from_env()?).await?;
+//! let downsampler = Downsampler::new(pool);
+//!
+//! // Run once now, then keep running in the background
+//! downsampler.run().await?;
+//! let _handle = downsampler.start();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::config::DownsamplingConfig;
+use crate::error::StorageResult;
+use crate::models::{MergedHistogram, Metric, MetricDataPoint, MetricType};
+use crate::pool::StoragePool;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Exemplars kept on a rolled data point.
+pub const MAX_EXEMPLARS: usize = 10;
+
+/// Resolution of downsampled data points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// One point per series every 5 minutes
+    FiveMinutes,
+    /// One point per series every hour
+    OneHour,
+}
+
+impl Resolution {
+    /// Interval covered by one point, in seconds.
+    pub fn seconds(&self) -> i64 {
+        match self {
+            Resolution::FiveMinutes => 300,
+            Resolution::OneHour => 3600,
+        }
+    }
+
+    /// Start of the interval containing `ts`.
+    pub fn interval_start(&self, ts: DateTime<Utc>) -> DateTime<Utc> {
+        let seconds = ts.timestamp();
+        Utc.timestamp_opt(seconds - seconds.rem_euclid(self.seconds()), 0)
+            .single()
+            .unwrap_or(ts)
+    }
+}
+
+/// A stored data point read for downsampling.
+#[derive(Debug, Clone, FromRow)]
+pub struct SourcePoint {
+    /// The data point
+    #[sqlx(flatten)]
+    pub point: MetricDataPoint,
+
+    /// Type of the point's metric
+    pub metric_type: String,
+
+    /// Raw points this point stands for (1 for a raw point)
+    pub source_points: i64,
+}
+
+/// A data point produced by downsampling.
+#[derive(Debug, Clone)]
+pub struct DownsampledPoint {
+    /// The rolled data point
+    pub point: MetricDataPoint,
+
+    /// Raw points rolled into this point
+    pub source_points: i64,
+}
+
+/// Outcome of downsampling one window.
+#[derive(Debug, Clone, Default)]
+pub struct WindowOutcome {
+    /// Data points read and deleted
+    pub source_points: u64,
+
+    /// Data points written
+    pub downsampled_points: u64,
+}
+
+/// Summary of a downsampling run.
+#[derive(Debug, Clone, Default)]
+pub struct DownsamplingReport {
+    /// One-hour windows downsampled
+    pub windows: u64,
+
+    /// Data points read and deleted
+    pub source_points: u64,
+
+    /// Data points written
+    pub downsampled_points: u64,
+}
+
+/// Roll data points up to `resolution`.
+///
+/// Returns one point per metric, attribute set and interval.
+pub fn downsample(sources: &[SourcePoint], resolution: Resolution) -> Vec<DownsampledPoint> {
+    let mut series: BTreeMap<(Uuid, DateTime<Utc>, String), Vec<&SourcePoint>> = BTreeMap::new();
+    for source in sources {
+        let key = (
+            source.point.metric_id,
+            resolution.interval_start(source.point.timestamp),
+            series_key(&source.point.attributes),
+        );
+        series.entry(key).or_default().push(source);
+    }
+
+    series
+        .into_iter()
+        .filter_map(|((_, start, _), mut group)| {
+            group.sort_by_key(|source| source.point.timestamp);
+            let mut point = match Metric::parse_type(&group[0].metric_type) {
+                Ok(MetricType::Gauge) => roll_gauge(&group),
+                Ok(MetricType::Histogram) => roll_histogram(&group),
+                _ => None,
+            }
+            .or_else(|| group.last().map(|source| source.point.clone()))?;
+
+            point.id = Uuid::new_v4();
+            point.timestamp = start;
+            point.exemplars = recent_exemplars(&group);
+            point.created_at = Utc::now();
+            Some(DownsampledPoint {
+                point,
+                source_points: group.iter().map(|source| source.source_points).sum(),
+            })
+        })
+        .collect()
+}
+
+/// Mean of gauge values; rolled sources contribute their count and sum.
+fn roll_gauge(group: &[&SourcePoint]) -> Option<MetricDataPoint> {
+    let mut count = 0i64;
+    let mut sum = 0.0;
+    let mut min: Option<f64> = None;
+    let mut max: Option<f64> = None;
+    for source in group {
+        let point = &source.point;
+        let (n, s) = match (point.count, point.sum, point.value) {
+            (Some(n), Some(s), _) => (n, s),
+            (_, _, Some(value)) => (1, value),
+            _ => continue,
+        };
+        count += n;
+        sum += s;
+        if let Some(low) = point.min.or(point.value) {
+            min = Some(min.map_or(low, |min| min.min(low)));
+        }
+        if let Some(high) = point.max.or(point.value) {
+            max = Some(max.map_or(high, |max| max.max(high)));
+        }
+    }
+
+    if count == 0 {
+        return None;
+    }
+
+    let mut point = group.last()?.point.clone();
+    point.value = Some(sum / count as f64);
+    point.count = Some(count);
+    point.sum = Some(sum);
+    point.min = min;
+    point.max = max;
+    Some(point)
+}
+
+/// Merge of delta histogram points.
+fn roll_histogram(group: &[&SourcePoint]) -> Option<MetricDataPoint> {
+    let mut merged = MergedHistogram::new();
+    for source in group {
+        if let Err(e) = merged.add(&source.point) {
+            tracing::warn!(
+                "Keeping last point of metric {}: unreadable histogram buckets: {}",
+                source.point.metric_id,
+                e
+            );
+            return None;
+        }
+    }
+
+    let mut point = group.last()?.point.clone();
+    point.value = None;
+    point.count = Some(merged.count);
+    point.sum = group
+        .iter()
+        .any(|source| source.point.sum.is_some())
+        .then_some(merged.sum);
+    point.min = merged.min;
+    point.max = merged.max;
+    point.buckets = (!merged.buckets.is_empty())
+        .then(|| serde_json::to_value(&merged.buckets).ok())
+        .flatten();
+    Some(point)
+}
+
+/// The most recent exemplars of a group, oldest first.
+fn recent_exemplars(group: &[&SourcePoint]) -> Option<serde_json::Value> {
+    let exemplars: Vec<serde_json::Value> = group
+        .iter()
+        .filter_map(|source| source.point.exemplars.as_ref()?.as_array())
+        .flatten()
+        .cloned()
+        .collect();
+    if exemplars.is_empty() {
+        return None;
+    }
+
+    let skip = exemplars.len().saturating_sub(MAX_EXEMPLARS);
+    Some(serde_json::Value::Array(exemplars[skip..].to_vec()))
+}
+
+/// Attribute set of a series, independent of key order.
+fn series_key(attributes: &serde_json::Value) -> String {
+    match attributes {
+        serde_json::Value::Object(map) => {
+            let mut pairs: Vec<_> = map.iter().collect();
+            pairs.sort_by(|a, b| a.0.cmp(b.0));
+            pairs
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join("\u{1f}")
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Rolls old metric data points into lower resolutions.
+#[derive(Clone)]
+pub struct Downsampler {
+    pool: StoragePool,
+    config: DownsamplingConfig,
+}
+
+impl Downsampler {
+    /// Create a downsampler using the pool's downsampling configuration.
+    pub fn new(pool: StoragePool) -> Self {
+        let config = pool.config().downsampling.clone();
+        Self::with_config(pool, config)
+    }
+
+    /// Create a downsampler with an explicit configuration.
+    pub fn with_config(pool: StoragePool, config: DownsamplingConfig) -> Self {
+        Self { pool, config }
+    }
+
+    /// Roll the points of `[start, start + 1h)` below `resolution` up to it.
+    ///
+    /// Inserting the rolled points, deleting their sources and recording the
+    /// run happen in one transaction.
+    pub async fn downsample_window(
+        &self,
+        resolution: Resolution,
+        start: DateTime<Utc>,
+    ) -> StorageResult<WindowOutcome> {
+        let end = start + Duration::hours(1);
+        let mut tx = self.pool.postgres().begin().await?;
+
+        let sources: Vec<SourcePoint> = sqlx::query_as(
+            "SELECT p.id, p.metric_id, p.timestamp, p.value, p.count, p.sum, p.min, p.max, \
+             p.buckets, p.quantiles, p.exemplars, p.attributes, p.created_at, m.metric_type, \
+             COALESCE(p.source_points, 1)::BIGINT AS source_points \
+             FROM metric_data_points p \
+             JOIN metrics m ON m.id = p.metric_id \
+             WHERE p.timestamp >= $1 AND p.timestamp < $2 \
+             AND COALESCE(p.resolution_seconds, 0) < $3 \
+             ORDER BY p.timestamp \
+             FOR UPDATE OF p",
+        )
+        .bind(start)
+        .bind(end)
+        .bind(resolution.seconds() as i32)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        if sources.is_empty() {
+            return Ok(WindowOutcome::default());
+        }
+
+        let rolled = downsample(&sources, resolution);
+        let outcome = WindowOutcome {
+            source_points: sources.len() as u64,
+            downsampled_points: rolled.len() as u64,
+        };
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO metric_data_points (id, metric_id, timestamp, value, count, sum, \
+             min, max, buckets, quantiles, exemplars, attributes, created_at, \
+             resolution_seconds, source_points) ",
+        );
+        query_builder.push_values(rolled, |mut b, rolled| {
+            let dp = rolled.point;
+            b.push_bind(dp.id)
+                .push_bind(dp.metric_id)
+                .push_bind(dp.timestamp)
+                .push_bind(dp.value)
+                .push_bind(dp.count)
+                .push_bind(dp.sum)
+                .push_bind(dp.min)
+                .push_bind(dp.max)
+                .push_bind(dp.buckets)
+                .push_bind(dp.quantiles)
+                .push_bind(dp.exemplars)
+                .push_bind(dp.attributes)
+                .push_bind(dp.created_at)
+                .push_bind(resolution.seconds() as i32)
+                .push_bind(rolled.source_points.min(i32::MAX as i64) as i32);
+        });
+        query_builder.build().execute(&mut *tx).await?;
+
+        let ids: Vec<Uuid> = sources.iter().map(|source| source.point.id).collect();
+        sqlx::query("DELETE FROM metric_data_points WHERE id = ANY($1)")
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO metric_downsampling_runs \
+             (resolution_seconds, window_start, window_end, source_points, downsampled_points) \
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(resolution.seconds() as i32)
+        .bind(start)
+        .bind(end)
+        .bind(outcome.source_points as i64)
+        .bind(outcome.downsampled_points as i64)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(outcome)
+    }
+
+    /// Downsample up to `max_windows_per_run` windows per resolution, oldest
+    /// first.
+    ///
+    /// Points old enough for 1-hour resolution are rolled first, so raw points
+    /// past both ages go straight to 1-hour points.
+    pub async fn run(&self) -> StorageResult<DownsamplingReport> {
+        let mut report = DownsamplingReport::default();
+        if !self.config.enabled {
+            return Ok(report);
+        }
+
+        let now = Utc::now();
+        let tiers = [
+            (Resolution::OneHour, self.config.one_hour_after_days),
+            (Resolution::FiveMinutes, self.config.five_minute_after_days),
+        ];
+
+        for (resolution, days) in tiers {
+            let cutoff = Resolution::OneHour.interval_start(now - Duration::days(days as i64));
+            let mut from: Option<DateTime<Utc>> = None;
+
+            for _ in 0..self.config.max_windows_per_run {
+                let oldest: Option<DateTime<Utc>> = sqlx::query_scalar(
+                    "SELECT MIN(timestamp) FROM metric_data_points \
+                     WHERE ($1::TIMESTAMPTZ IS NULL OR timestamp >= $1) AND timestamp < $2 \
+                     AND COALESCE(resolution_seconds, 0) < $3",
+                )
+                .bind(from)
+                .bind(cutoff)
+                .bind(resolution.seconds() as i32)
+                .fetch_one(self.pool.postgres())
+                .await?;
+
+                let Some(oldest) = oldest else {
+                    break;
+                };
+
+                let start = Resolution::OneHour.interval_start(oldest);
+                let outcome = self.downsample_window(resolution, start).await?;
+                report.windows += 1;
+                report.source_points += outcome.source_points;
+                report.downsampled_points += outcome.downsampled_points;
+                from = Some(start + Duration::hours(1));
+            }
+        }
+
+        tracing::info!(
+            "Metric downsampling: {} windows, {} points rolled into {}",
+            report.windows,
+            report.source_points,
+            report.downsampled_points
+        );
+
+        Ok(report)
+    }
+
+    /// Start periodic downsampling.
+    ///
+    /// Returns a handle that can be used to stop the downsampling task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let downsampler = self.clone();
+        let interval = self.config.interval();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = downsampler.run().await {
+                    tracing::error!("Metric downsampling error: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn source(
+        metric_id: Uuid,
+        metric_type: &str,
+        at: &str,
+        attributes: serde_json::Value,
+    ) -> SourcePoint {
+        SourcePoint {
+            point: MetricDataPoint {
+                id: Uuid::new_v4(),
+                metric_id,
+                timestamp: ts(at),
+                value: None,
+                count: None,
+                sum: None,
+                min: None,
+                max: None,
+                buckets: None,
+                quantiles: None,
+                exemplars: None,
+                attributes,
+                created_at: ts(at),
+            },
+            metric_type: metric_type.to_string(),
+            source_points: 1,
+        }
+    }
+
+    #[test]
+    fn test_interval_start() {
+        let t = ts("2025-11-12T15:37:42Z");
+        assert_eq!(
+            Resolution::FiveMinutes.interval_start(t),
+            ts("2025-11-12T15:35:00Z")
+        );
+        assert_eq!(
+            Resolution::OneHour.interval_start(t),
+            ts("2025-11-12T15:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_downsample_gauges_and_counters() {
+        let gauge = Uuid::new_v4();
+        let counter = Uuid::new_v4();
+        let mut sources = Vec::new();
+        for (i, (at, value)) in [
+            ("2025-11-12T15:00:10Z", 2.0),
+            ("2025-11-12T15:00:20Z", 6.0),
+            ("2025-11-12T15:06:00Z", 5.0),
+        ]
+        .iter()
+        .enumerate()
+        {
+            let mut point = source(gauge, "gauge", at, json!({"model": "gpt-4"}));
+            point.point.value = Some(*value);
+            sources.push(point);
+
+            let mut point = source(counter, "counter", at, json!({}));
+            point.point.value = Some(10.0 * (i + 1) as f64);
+            sources.push(point);
+        }
+        // A second series of the gauge
+        let mut point = source(
+            gauge,
+            "gauge",
+            "2025-11-12T15:01:00Z",
+            json!({"model": "claude"}),
+        );
+        point.point.value = Some(1.0);
+        sources.push(point);
+
+        let rolled = downsample(&sources, Resolution::FiveMinutes);
+        assert_eq!(rolled.len(), 5);
+
+        let first = rolled
+            .iter()
+            .find(|r| r.point.metric_id == gauge && r.point.attributes["model"] == "gpt-4")
+            .unwrap();
+        assert_eq!(first.point.timestamp, ts("2025-11-12T15:00:00Z"));
+        assert_eq!(first.point.value, Some(4.0));
+        assert_eq!(first.point.count, Some(2));
+        assert_eq!((first.point.min, first.point.max), (Some(2.0), Some(6.0)));
+        assert_eq!(first.source_points, 2);
+
+        let counters: Vec<_> = rolled
+            .iter()
+            .filter(|r| r.point.metric_id == counter)
+            .collect();
+        assert_eq!(counters[0].point.value, Some(20.0));
+        assert_eq!(counters[1].point.value, Some(30.0));
+
+        // Rolling 5-minute gauges again weights them by their counts
+        let mut rolled_sources: Vec<_> = rolled
+            .into_iter()
+            .filter(|r| r.point.metric_id == gauge && r.point.attributes["model"] == "gpt-4")
+            .map(|r| SourcePoint {
+                point: r.point,
+                metric_type: "gauge".to_string(),
+                source_points: r.source_points,
+            })
+            .collect();
+        rolled_sources.sort_by_key(|s| s.point.timestamp);
+        let hourly = downsample(&rolled_sources, Resolution::OneHour);
+        assert_eq!(hourly.len(), 1);
+        assert_eq!(hourly[0].point.value, Some(13.0 / 3.0));
+        assert_eq!(hourly[0].point.count, Some(3));
+        assert_eq!(hourly[0].point.max, Some(6.0));
+        assert_eq!(hourly[0].source_points, 3);
+    }
+
+    #[test]
+    fn test_downsample_histograms() {
+        let metric_id = Uuid::new_v4();
+        let mut first = source(metric_id, "histogram", "2025-11-12T15:00:10Z", json!({}));
+        first.point.count = Some(3);
+        first.point.sum = Some(150.0);
+        first.point.min = Some(20.0);
+        first.point.max = Some(80.0);
+        first.point.buckets =
+            Some(json!([{"boundary": 50.0, "count": 2}, {"boundary": 100.0, "count": 1}]));
+        first.point.exemplars = Some(json!([{"trace_id": "a"}]));
+
+        let mut second = source(metric_id, "histogram", "2025-11-12T15:00:20Z", json!({}));
+        second.point.count = Some(2);
+        second.point.sum = Some(300.0);
+        second.point.min = Some(90.0);
+        second.point.max = Some(210.0);
+        second.point.buckets = Some(json!([{"boundary": 100.0, "count": 1}]));
+        second.point.exemplars = Some(json!([{"trace_id": "b"}]));
+
+        let rolled = downsample(&[first, second], Resolution::FiveMinutes);
+        assert_eq!(rolled.len(), 1);
+
+        let point = &rolled[0].point;
+        assert_eq!(point.count, Some(5));
+        assert_eq!(point.sum, Some(450.0));
+        assert_eq!((point.min, point.max), (Some(20.0), Some(210.0)));
+        assert_eq!(
+            point.buckets,
+            Some(json!([{"boundary": 50.0, "count": 2}, {"boundary": 100.0, "count": 2}]))
+        );
+        assert_eq!(
+            point.exemplars,
+            Some(json!([{"trace_id": "a"}, {"trace_id": "b"}]))
+        );
+        assert_eq!(rolled[0].source_points, 2);
+    }
+
+    #[test]
+    fn test_downsample_summaries_keep_last_point() {
+        let metric_id = Uuid::new_v4();
+        let mut first = source(metric_id, "summary", "2025-11-12T15:00:10Z", json!({}));
+        first.point.count = Some(10);
+        first.point.quantiles = Some(json!([{"quantile": 0.5, "value": 1.0}]));
+        let mut second = source(metric_id, "summary", "2025-11-12T15:04:10Z", json!({}));
+        second.point.count = Some(20);
+        second.point.quantiles = Some(json!([{"quantile": 0.5, "value": 2.0}]));
+
+        let rolled = downsample(&[second, first], Resolution::FiveMinutes);
+        assert_eq!(rolled.len(), 1);
+        assert_eq!(rolled[0].point.count, Some(20));
+        assert_eq!(
+            rolled[0].point.quantiles,
+            Some(json!([{"quantile": 0.5, "value": 2.0}]))
+        );
+        assert_eq!(rolled[0].point.timestamp, ts("2025-11-12T15:00:00Z"));
+    }
+}
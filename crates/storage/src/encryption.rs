@@ -0,0 +1,760 @@
+//! Application-level encryption of sensitive payload fields.
+//!
+//! Prompts and completions end up in span attributes (`llm.input`,
+//! `gen_ai.prompt`, ...) and in span events. When encryption is configured,
+//! the trace writer replaces those values with an AES-256-GCM envelope so
+//! the column type stays JSONB:
+//!
+//! ```json
+//! {"$encrypted": "aes-256-gcm", "kid": "kek-2024", "dek": "<base64>", "nonce": "<base64>", "data": "<base64>"}
+//! ```
+//!
+//! Each value is encrypted with a random data key (DEK) that is wrapped by a
+//! key-encryption key (KEK) held by a [`KeyProvider`]; `kid` names the KEK
+//! and `dek` is the wrapped data key. The field name is bound as associated
+//! data, so an envelope copied into another field does not decrypt. A data
+//! key is reused for `data_key_ttl_secs`, so the provider is called once per
+//! key rather than once per value.
+//!
+//! Repositories return envelopes unchanged unless they were created with
+//! decryption enabled, so only authorized readers see plaintext. Values that
+//! are not envelopes pass through, so encrypted and plaintext rows coexist.
+//!
+//! Key rotation: make a new KEK active and keep the old one available to the
+//! provider; new data keys are wrapped with the new KEK, and
+//! [`TraceRepository::rewrap_encrypted`](crate::repositories::TraceRepository::rewrap_encrypted)
+//! re-wraps the data keys of stored envelopes without touching the
+//! ciphertext. Once no envelope uses the old KEK it can be retired.
+//!
+//! Providers:
+//!
+//! - [`LocalKeyProvider`]: KEKs from the configuration (usually secret
+//!   references), wrapping data keys with AES-256-GCM
+//!
+//! A KMS (AWS KMS, Vault transit, ...) plugs in by implementing
+//! [`KeyProvider`] and passing a [`FieldEncryptor`] to
+//! [`StoragePool::with_encryptor`](crate::StoragePool::with_encryptor).
+//! Rows written with [`CopyWriter`](crate::writers::copy::CopyWriter) are not
+//! encrypted automatically; run them through [`FieldEncryptor::encrypt_span`]
+//! and [`FieldEncryptor::encrypt_event`] first.
+
+use crate::config::EncryptionConfig;
+use crate::error::{StorageError, StorageResult};
+use crate::models::{TraceEvent, TraceSpan};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Envelope key holding the algorithm name.
+pub const ENVELOPE_KEY: &str = "$encrypted";
+
+/// Algorithm of the envelopes written by [`FieldEncryptor`].
+const ALGORITHM: &str = "aes-256-gcm";
+
+/// Length of data keys and key-encryption keys in bytes.
+const KEY_LEN: usize = 32;
+
+/// Unwrapped data keys kept for decryption before the cache is reset.
+const MAX_CACHED_DATA_KEYS: usize = 1024;
+
+/// Associated data of the span events column.
+const SPAN_EVENTS_FIELD: &str = "events";
+
+/// Associated data of the event attributes column.
+const EVENT_ATTRIBUTES_FIELD: &str = "event.attributes";
+
+/// A data key wrapped by a [`KeyProvider`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedKey {
+    /// ID of the key-encryption key that wrapped the data key
+    pub key_id: String,
+
+    /// Wrapped data key, opaque to everything but the provider
+    pub ciphertext: Vec<u8>,
+}
+
+/// Holder of the key-encryption keys.
+#[async_trait]
+pub trait KeyProvider: Send + Sync {
+    /// ID of the key that wraps new data keys.
+    fn active_key_id(&self) -> &str;
+
+    /// Wrap a data key with the active key.
+    async fn wrap_key(&self, data_key: &[u8]) -> StorageResult<WrappedKey>;
+
+    /// Unwrap a data key wrapped with `key.key_id`, which may be a retired
+    /// key.
+    async fn unwrap_key(&self, key: &WrappedKey) -> StorageResult<Vec<u8>>;
+
+    /// Provider name, used in logs.
+    fn name(&self) -> &'static str;
+}
+
+/// Key-encryption keys from the configuration.
+///
+/// Data keys are wrapped with AES-256-GCM under the active key; the key ID
+/// is bound as associated data.
+pub struct LocalKeyProvider {
+    keys: HashMap<String, LessSafeKey>,
+    active_key_id: String,
+    rng: SystemRandom,
+}
+
+impl LocalKeyProvider {
+    /// Create a provider from raw 256-bit keys by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a key is not 256 bits long or `active_key_id` is
+    /// not among `keys`.
+    pub fn new(
+        keys: HashMap<String, Vec<u8>>,
+        active_key_id: impl Into<String>,
+    ) -> StorageResult<Self> {
+        let active_key_id = active_key_id.into();
+        if !keys.contains_key(&active_key_id) {
+            return Err(StorageError::ConfigError(format!(
+                "Active encryption key '{}' is not among the configured keys",
+                active_key_id
+            )));
+        }
+
+        let keys = keys
+            .into_iter()
+            .map(|(id, key)| {
+                let key = aead_key(&key).map_err(|_| {
+                    StorageError::ConfigError(format!(
+                        "Encryption key '{}' must be {} bytes",
+                        id, KEY_LEN
+                    ))
+                })?;
+                Ok((id, key))
+            })
+            .collect::<StorageResult<_>>()?;
+
+        Ok(Self {
+            keys,
+            active_key_id,
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Create a provider from the base64-encoded keys of `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a key is not valid base64 or not 256 bits long,
+    /// or no active key is configured.
+    pub fn from_config(config: &EncryptionConfig) -> StorageResult<Self> {
+        let active_key_id = config.active_key_id.clone().ok_or_else(|| {
+            StorageError::ConfigError("Encryption requires an active key ID".to_string())
+        })?;
+
+        let keys = config
+            .keys
+            .iter()
+            .map(|(id, key)| {
+                let key = BASE64.decode(key.trim()).map_err(|e| {
+                    StorageError::ConfigError(format!(
+                        "Encryption key '{}' is not valid base64: {}",
+                        id, e
+                    ))
+                })?;
+                Ok((id.clone(), key))
+            })
+            .collect::<StorageResult<_>>()?;
+
+        Self::new(keys, active_key_id)
+    }
+}
+
+#[async_trait]
+impl KeyProvider for LocalKeyProvider {
+    fn active_key_id(&self) -> &str {
+        &self.active_key_id
+    }
+
+    async fn wrap_key(&self, data_key: &[u8]) -> StorageResult<WrappedKey> {
+        let kek = &self.keys[&self.active_key_id];
+        let (nonce, mut ciphertext) =
+            seal(kek, &self.rng, data_key, self.active_key_id.as_bytes())?;
+
+        let mut wrapped = nonce.to_vec();
+        wrapped.append(&mut ciphertext);
+        Ok(WrappedKey {
+            key_id: self.active_key_id.clone(),
+            ciphertext: wrapped,
+        })
+    }
+
+    async fn unwrap_key(&self, key: &WrappedKey) -> StorageResult<Vec<u8>> {
+        let kek = self.keys.get(&key.key_id).ok_or_else(|| {
+            StorageError::ConfigError(format!("Unknown encryption key '{}'", key.key_id))
+        })?;
+        if key.ciphertext.len() < NONCE_LEN {
+            return Err(decryption_failed("wrapped data key is truncated"));
+        }
+
+        let (nonce, ciphertext) = key.ciphertext.split_at(NONCE_LEN);
+        open(kek, nonce, ciphertext.to_vec(), key.key_id.as_bytes())
+    }
+
+    fn name(&self) -> &'static str {
+        "local"
+    }
+}
+
+/// Data key used for new values.
+struct ActiveDataKey {
+    key: LessSafeKey,
+    wrapped: WrappedKey,
+    created_at: Instant,
+}
+
+/// Encrypts and decrypts the configured payload fields.
+pub struct FieldEncryptor {
+    provider: Arc<dyn KeyProvider>,
+    span_attributes: Vec<String>,
+    events: bool,
+    data_key_ttl: Duration,
+    rng: SystemRandom,
+    /// Data key for new values, replaced after `data_key_ttl` or a rotation
+    current: Mutex<Option<ActiveDataKey>>,
+    /// Unwrapped data keys by key ID and base64 wrapped key
+    unwrapped: RwLock<HashMap<(String, String), LessSafeKey>>,
+}
+
+impl std::fmt::Debug for FieldEncryptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FieldEncryptor")
+            .field("provider", &self.provider.name())
+            .field("span_attributes", &self.span_attributes)
+            .field("events", &self.events)
+            .field("data_key_ttl", &self.data_key_ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FieldEncryptor {
+    /// Create an encryptor for the fields of `config` using `provider`.
+    pub fn new(provider: Arc<dyn KeyProvider>, config: &EncryptionConfig) -> Self {
+        Self {
+            provider,
+            span_attributes: config.span_attributes.clone(),
+            events: config.events,
+            data_key_ttl: Duration::from_secs(config.data_key_ttl_secs),
+            rng: SystemRandom::new(),
+            current: Mutex::new(None),
+            unwrapped: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Create the encryptor configured by `config` with a
+    /// [`LocalKeyProvider`], or `None` if encryption is disabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configured keys are invalid.
+    pub fn from_config(config: &EncryptionConfig) -> StorageResult<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let provider = LocalKeyProvider::from_config(config)?;
+        Ok(Some(Self::new(Arc::new(provider), config)))
+    }
+
+    /// The key provider.
+    pub fn provider(&self) -> &dyn KeyProvider {
+        self.provider.as_ref()
+    }
+
+    /// Encrypt a JSON value stored in `field`.
+    ///
+    /// Null values and existing envelopes are returned unchanged.
+    pub async fn encrypt_json(&self, value: Value, field: &str) -> StorageResult<Value> {
+        if value.is_null() || is_encrypted(&value) {
+            return Ok(value);
+        }
+
+        let plaintext = serde_json::to_vec(&value)?;
+        let (key, wrapped) = self.data_key().await?;
+        let (nonce, ciphertext) = seal(&key, &self.rng, &plaintext, field.as_bytes())?;
+
+        Ok(json!({
+            ENVELOPE_KEY: ALGORITHM,
+            "kid": wrapped.key_id,
+            "dek": BASE64.encode(&wrapped.ciphertext),
+            "nonce": BASE64.encode(nonce),
+            "data": BASE64.encode(ciphertext),
+        }))
+    }
+
+    /// Decrypt a JSON value written by [`FieldEncryptor::encrypt_json`] for
+    /// `field`.
+    ///
+    /// Values that are not encryption envelopes are returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data key cannot be unwrapped or the value was
+    /// tampered with or encrypted for another field.
+    pub async fn decrypt_json(&self, value: Value, field: &str) -> StorageResult<Value> {
+        let Some(envelope) = Envelope::parse(&value) else {
+            return Ok(value);
+        };
+
+        let key = self.unwrap_data_key(envelope.kid, envelope.dek).await?;
+        let nonce = decode(envelope.nonce)?;
+        let plaintext = open(&key, &nonce, decode(envelope.data)?, field.as_bytes())?;
+
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// Re-wrap the data key of an envelope that is not wrapped with the
+    /// active key.
+    ///
+    /// Returns `None` if `value` is not an envelope or already uses the
+    /// active key. The ciphertext is unchanged.
+    pub async fn rewrap_json(&self, value: &Value) -> StorageResult<Option<Value>> {
+        let Some(envelope) = Envelope::parse(value) else {
+            return Ok(None);
+        };
+        if envelope.kid == self.provider.active_key_id() {
+            return Ok(None);
+        }
+
+        let data_key = self
+            .provider
+            .unwrap_key(&WrappedKey {
+                key_id: envelope.kid.to_string(),
+                ciphertext: decode(envelope.dek)?,
+            })
+            .await?;
+        let wrapped = self.provider.wrap_key(&data_key).await?;
+
+        let mut rewrapped = value.clone();
+        rewrapped["kid"] = Value::String(wrapped.key_id);
+        rewrapped["dek"] = Value::String(BASE64.encode(wrapped.ciphertext));
+        Ok(Some(rewrapped))
+    }
+
+    /// Encrypt the configured attributes and, if enabled, the events of a
+    /// span.
+    pub async fn encrypt_span(&self, span: &mut TraceSpan) -> StorageResult<()> {
+        if let Value::Object(attributes) = &mut span.attributes {
+            for key in &self.span_attributes {
+                if let Some(value) = attributes.get_mut(key) {
+                    *value = self
+                        .encrypt_json(value.take(), &attribute_field(key))
+                        .await?;
+                }
+            }
+        }
+        if self.events {
+            if let Some(events) = span.events.take() {
+                span.events = Some(self.encrypt_json(events, SPAN_EVENTS_FIELD).await?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Decrypt the encrypted attributes and events of a span.
+    pub async fn decrypt_span(&self, span: &mut TraceSpan) -> StorageResult<()> {
+        if let Value::Object(attributes) = &mut span.attributes {
+            decrypt_attributes(self, attributes).await?;
+        }
+        if let Some(events) = span.events.take() {
+            span.events = Some(self.decrypt_json(events, SPAN_EVENTS_FIELD).await?);
+        }
+        Ok(())
+    }
+
+    /// Re-wrap the data keys of a span's envelopes that do not use the
+    /// active key. Returns whether anything changed.
+    pub async fn rewrap_span(&self, span: &mut TraceSpan) -> StorageResult<bool> {
+        let mut changed = false;
+        if let Value::Object(attributes) = &mut span.attributes {
+            for value in attributes.values_mut() {
+                if let Some(rewrapped) = self.rewrap_json(value).await? {
+                    *value = rewrapped;
+                    changed = true;
+                }
+            }
+        }
+        if let Some(events) = &mut span.events {
+            if let Some(rewrapped) = self.rewrap_json(events).await? {
+                *events = rewrapped;
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Encrypt the attributes of an event, if events are encrypted.
+    pub async fn encrypt_event(&self, event: &mut TraceEvent) -> StorageResult<()> {
+        if self.events {
+            let attributes = std::mem::take(&mut event.attributes);
+            event.attributes = self
+                .encrypt_json(attributes, EVENT_ATTRIBUTES_FIELD)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Decrypt the attributes of an event.
+    pub async fn decrypt_event(&self, event: &mut TraceEvent) -> StorageResult<()> {
+        let attributes = std::mem::take(&mut event.attributes);
+        event.attributes = self
+            .decrypt_json(attributes, EVENT_ATTRIBUTES_FIELD)
+            .await?;
+        Ok(())
+    }
+
+    /// Re-wrap the data key of an event's attributes if it does not use the
+    /// active key. Returns whether anything changed.
+    pub async fn rewrap_event(&self, event: &mut TraceEvent) -> StorageResult<bool> {
+        match self.rewrap_json(&event.attributes).await? {
+            Some(rewrapped) => {
+                event.attributes = rewrapped;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// The data key for new values, generating and wrapping a new one when
+    /// the current key expired or the active KEK changed.
+    async fn data_key(&self) -> StorageResult<(LessSafeKey, WrappedKey)> {
+        let mut current = self.current.lock().await;
+
+        let fresh = current.as_ref().is_some_and(|key| {
+            key.created_at.elapsed() < self.data_key_ttl
+                && key.wrapped.key_id == self.provider.active_key_id()
+        });
+        if !fresh {
+            let mut data_key = [0u8; KEY_LEN];
+            self.rng
+                .fill(&mut data_key)
+                .map_err(|_| encryption_failed("random number generator failed"))?;
+            let wrapped = self.provider.wrap_key(&data_key).await?;
+            tracing::debug!(
+                provider = self.provider.name(),
+                key_id = %wrapped.key_id,
+                "Generated new payload data key"
+            );
+
+            *current = Some(ActiveDataKey {
+                key: aead_key(&data_key)?,
+                wrapped,
+                created_at: Instant::now(),
+            });
+        }
+
+        let current = current.as_ref().expect("data key was just set");
+        Ok((current.key.clone(), current.wrapped.clone()))
+    }
+
+    /// Unwrap a data key, using the cache when possible.
+    async fn unwrap_data_key(&self, kid: &str, dek: &str) -> StorageResult<LessSafeKey> {
+        let cache_key = (kid.to_string(), dek.to_string());
+        if let Some(key) = self.unwrapped.read().unwrap().get(&cache_key) {
+            return Ok(key.clone());
+        }
+
+        let data_key = self
+            .provider
+            .unwrap_key(&WrappedKey {
+                key_id: kid.to_string(),
+                ciphertext: decode(dek)?,
+            })
+            .await?;
+        let key = aead_key(&data_key)?;
+
+        let mut unwrapped = self.unwrapped.write().unwrap();
+        if unwrapped.len() >= MAX_CACHED_DATA_KEYS {
+            unwrapped.clear();
+        }
+        unwrapped.insert(cache_key, key.clone());
+        Ok(key)
+    }
+}
+
+/// Decrypt every encrypted attribute value. Attributes are decrypted
+/// regardless of the configured keys so values stay readable after the
+/// configuration changes.
+async fn decrypt_attributes(
+    encryptor: &FieldEncryptor,
+    attributes: &mut Map<String, Value>,
+) -> StorageResult<()> {
+    for (key, value) in attributes.iter_mut() {
+        if is_encrypted(value) {
+            *value = encryptor
+                .decrypt_json(value.take(), &attribute_field(key))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether a value is an encryption envelope.
+pub fn is_encrypted(value: &Value) -> bool {
+    Envelope::parse(value).is_some()
+}
+
+/// Fields of an encryption envelope.
+struct Envelope<'a> {
+    kid: &'a str,
+    dek: &'a str,
+    nonce: &'a str,
+    data: &'a str,
+}
+
+impl<'a> Envelope<'a> {
+    fn parse(value: &'a Value) -> Option<Self> {
+        let obj = value.as_object()?;
+        if obj.len() != 5 || obj.get(ENVELOPE_KEY)?.as_str()? != ALGORITHM {
+            return None;
+        }
+
+        Some(Self {
+            kid: obj.get("kid")?.as_str()?,
+            dek: obj.get("dek")?.as_str()?,
+            nonce: obj.get("nonce")?.as_str()?,
+            data: obj.get("data")?.as_str()?,
+        })
+    }
+}
+
+/// Associated data of a span attribute.
+fn attribute_field(key: &str) -> String {
+    format!("attributes.{}", key)
+}
+
+fn aead_key(key: &[u8]) -> StorageResult<LessSafeKey> {
+    UnboundKey::new(&AES_256_GCM, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| encryption_failed("invalid key length"))
+}
+
+/// Encrypt `plaintext` under a random nonce; returns the nonce and the
+/// ciphertext with its tag.
+fn seal(
+    key: &LessSafeKey,
+    rng: &SystemRandom,
+    plaintext: &[u8],
+    aad: &[u8],
+) -> StorageResult<([u8; NONCE_LEN], Vec<u8>)> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut nonce)
+        .map_err(|_| encryption_failed("random number generator failed"))?;
+
+    let mut in_out = plaintext.to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::from(aad),
+        &mut in_out,
+    )
+    .map_err(|_| encryption_failed("sealing failed"))?;
+    Ok((nonce, in_out))
+}
+
+fn open(
+    key: &LessSafeKey,
+    nonce: &[u8],
+    mut ciphertext: Vec<u8>,
+    aad: &[u8],
+) -> StorageResult<Vec<u8>> {
+    let nonce =
+        Nonce::try_assume_unique_for_key(nonce).map_err(|_| decryption_failed("invalid nonce"))?;
+    let len = key
+        .open_in_place(nonce, Aad::from(aad), &mut ciphertext)
+        .map_err(|_| decryption_failed("authentication failed"))?
+        .len();
+    ciphertext.truncate(len);
+    Ok(ciphertext)
+}
+
+fn decode(data: &str) -> StorageResult<Vec<u8>> {
+    BASE64
+        .decode(data)
+        .map_err(|e| decryption_failed(&format!("invalid base64: {}", e)))
+}
+
+fn encryption_failed(reason: &str) -> StorageError {
+    StorageError::SerializationError(format!("Encryption failed: {}", reason))
+}
+
+fn decryption_failed(reason: &str) -> StorageError {
+    StorageError::SerializationError(format!("Decryption failed: {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(active: &str) -> Arc<dyn KeyProvider> {
+        let keys = HashMap::from([
+            ("kek-1".to_string(), vec![1u8; KEY_LEN]),
+            ("kek-2".to_string(), vec![2u8; KEY_LEN]),
+        ]);
+        Arc::new(LocalKeyProvider::new(keys, active).unwrap())
+    }
+
+    fn encryptor(active: &str) -> FieldEncryptor {
+        FieldEncryptor::new(provider(active), &EncryptionConfig::default())
+    }
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let encryptor = encryptor("kek-1");
+        let prompt = json!("What is the capital of France?");
+
+        let stored = encryptor
+            .encrypt_json(prompt.clone(), "attributes.llm.input")
+            .await
+            .unwrap();
+        assert!(is_encrypted(&stored));
+        assert_eq!(stored["kid"], "kek-1");
+        assert!(!stored.to_string().contains("capital"));
+
+        // Already encrypted values are not encrypted twice
+        let again = encryptor
+            .encrypt_json(stored.clone(), "attributes.llm.input")
+            .await
+            .unwrap();
+        assert_eq!(again, stored);
+
+        let plain = encryptor
+            .decrypt_json(stored, "attributes.llm.input")
+            .await
+            .unwrap();
+        assert_eq!(plain, prompt);
+    }
+
+    #[tokio::test]
+    async fn test_tampering_and_field_binding() {
+        let encryptor = encryptor("kek-1");
+        let stored = encryptor
+            .encrypt_json(json!({"text": "secret"}), "events")
+            .await
+            .unwrap();
+
+        // An envelope moved to another field does not decrypt
+        assert!(encryptor
+            .decrypt_json(stored.clone(), "event.attributes")
+            .await
+            .is_err());
+
+        let mut tampered = stored.clone();
+        let mut data = BASE64.decode(stored["data"].as_str().unwrap()).unwrap();
+        data[0] ^= 1;
+        tampered["data"] = Value::String(BASE64.encode(data));
+        assert!(encryptor.decrypt_json(tampered, "events").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_passthrough() {
+        let encryptor = encryptor("kek-1");
+        for value in [
+            json!(null),
+            json!({"name": "retry"}),
+            json!({ENVELOPE_KEY: "rot13", "data": "abc"}),
+        ] {
+            assert!(!is_encrypted(&value));
+            assert_eq!(
+                encryptor
+                    .decrypt_json(value.clone(), "events")
+                    .await
+                    .unwrap(),
+                value
+            );
+        }
+        assert_eq!(
+            encryptor.encrypt_json(json!(null), "events").await.unwrap(),
+            json!(null)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_span_fields() {
+        let encryptor = encryptor("kek-1");
+        let mut span = TraceSpan::new(
+            uuid::Uuid::new_v4(),
+            "span-1".to_string(),
+            "llm.chat".to_string(),
+            "api".to_string(),
+            chrono::Utc::now(),
+        );
+        span.attributes = json!({"llm.input": "hello", "llm.model": "gpt-4"});
+        span.events = Some(json!([{"name": "completion"}]));
+        let original = span.clone();
+
+        encryptor.encrypt_span(&mut span).await.unwrap();
+        assert!(is_encrypted(&span.attributes["llm.input"]));
+        assert_eq!(span.attributes["llm.model"], "gpt-4");
+        assert!(is_encrypted(span.events.as_ref().unwrap()));
+
+        encryptor.decrypt_span(&mut span).await.unwrap();
+        assert_eq!(span.attributes, original.attributes);
+        assert_eq!(span.events, original.events);
+    }
+
+    #[tokio::test]
+    async fn test_key_rotation() {
+        let old = encryptor("kek-1");
+        let stored = old.encrypt_json(json!("prompt"), "events").await.unwrap();
+
+        // After rotation, old data stays readable and new data uses the new key
+        let rotated = encryptor("kek-2");
+        assert_eq!(
+            rotated
+                .decrypt_json(stored.clone(), "events")
+                .await
+                .unwrap(),
+            json!("prompt")
+        );
+        let fresh = rotated
+            .encrypt_json(json!("prompt"), "events")
+            .await
+            .unwrap();
+        assert_eq!(fresh["kid"], "kek-2");
+        assert!(rotated.rewrap_json(&fresh).await.unwrap().is_none());
+
+        let rewrapped = rotated.rewrap_json(&stored).await.unwrap().unwrap();
+        assert_eq!(rewrapped["kid"], "kek-2");
+        assert_eq!(rewrapped["data"], stored["data"]);
+        assert_eq!(
+            rotated.decrypt_json(rewrapped, "events").await.unwrap(),
+            json!("prompt")
+        );
+    }
+
+    #[test]
+    fn test_local_provider_config() {
+        let mut config = EncryptionConfig {
+            enabled: true,
+            active_key_id: Some("kek-1".to_string()),
+            ..Default::default()
+        };
+        config
+            .keys
+            .insert("kek-1".to_string(), BASE64.encode([7u8; KEY_LEN]));
+        assert!(FieldEncryptor::from_config(&config).unwrap().is_some());
+
+        config
+            .keys
+            .insert("kek-1".to_string(), BASE64.encode([7u8; 16]));
+        assert!(LocalKeyProvider::from_config(&config).is_err());
+
+        config.enabled = false;
+        assert!(FieldEncryptor::from_config(&config).unwrap().is_none());
+    }
+}
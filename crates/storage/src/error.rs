@@ -39,6 +39,10 @@ pub enum StorageError {
     #[error("Record not found: {0}")]
     NotFound(String),
 
+    /// Record moved to object storage by the storage lifecycle
+    #[error("Record archived: {0}")]
+    Archived(String),
+
     /// Configuration error
     #[error("Configuration error: {0}")]
     ConfigError(String),
@@ -55,6 +59,14 @@ pub enum StorageError {
     #[error("Redis error: {0}")]
     RedisError(String),
 
+    /// Circuit breaker is open and the call was rejected without touching the database
+    #[error("Circuit breaker open: {0}")]
+    CircuitOpen(String),
+
+    /// The storage layer is shutting down and no longer accepts writes
+    #[error("Storage shutting down: {0}")]
+    ShuttingDown(String),
+
     /// Batch operation error
     #[error("Batch operation error: {0}")]
     BatchError(String),
@@ -72,6 +84,7 @@ impl From<sqlx::Error> for StorageError {
             sqlx::Error::RowNotFound => StorageError::NotFound("Row not found".to_string()),
             sqlx::Error::PoolTimedOut => StorageError::Timeout("Pool timeout".to_string()),
             sqlx::Error::PoolClosed => StorageError::PoolError("Pool closed".to_string()),
+            sqlx::Error::Io(_) | sqlx::Error::Tls(_) => StorageError::ConnectionError(err.to_string()),
             _ => StorageError::QueryError(err.to_string()),
         }
     }
@@ -134,6 +147,11 @@ impl StorageError {
         matches!(self, StorageError::NotFound(_))
     }
 
+    /// Check if the record was moved to object storage.
+    pub fn is_archived(&self) -> bool {
+        matches!(self, StorageError::Archived(_))
+    }
+
     /// Check if the error is a connection-related error.
     pub fn is_connection_error(&self) -> bool {
         matches!(
@@ -142,6 +160,11 @@ impl StorageError {
         )
     }
 
+    /// Check if the call was rejected by an open circuit breaker.
+    pub fn is_circuit_open(&self) -> bool {
+        matches!(self, StorageError::CircuitOpen(_))
+    }
+
     /// Check if the error is retryable.
     ///
     /// Returns true for transient errors that might succeed on retry.
@@ -189,6 +212,13 @@ mod tests {
         assert!(!err.is_retryable());
     }
 
+    #[test]
+    fn test_circuit_open_not_retryable() {
+        let err = StorageError::CircuitOpen("postgres".to_string());
+        assert!(err.is_circuit_open());
+        assert!(!err.is_retryable());
+    }
+
     #[test]
     fn test_sqlx_error_conversion() {
         let err: StorageError = sqlx::Error::RowNotFound.into();
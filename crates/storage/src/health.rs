@@ -2,8 +2,15 @@
 //!
 //! This module provides HTTP endpoints for:
 //! - `/health` - Health check for PostgreSQL and Redis
+//! - `/health/history` - Rolling history of pool utilization, acquire latency
+//!   and health check results
+//! - `/migrations/online` - Progress of online schema migrations
 //! - `/metrics` - Prometheus metrics scraping endpoint
 //!
+//! The server installs the global metrics recorder; with
+//! `StorageConfig.telemetry.otlp_enabled`, metrics are also exported over
+//! OTLP (see [`crate::telemetry`]).
+//!
 //! # Usage
 //!
 //! ```no_run
@@ -21,7 +28,10 @@
 //! # }
 //! ```
 
+use crate::health_history::{HealthHistorySummary, HealthSample};
+use crate::online_migration::{MigrationStatus, OnlineMigrator};
 use crate::pool::{HealthCheckResult, PoolStats, StoragePool};
+use crate::telemetry::{self, MetricsTelemetry};
 use axum::{
     extract::State,
     http::StatusCode,
@@ -29,7 +39,7 @@ use axum::{
     routing::get,
     Json, Router,
 };
-use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use metrics_exporter_prometheus::PrometheusHandle;
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -41,23 +51,31 @@ use std::time::Instant;
 pub struct HealthServer {
     pool: StoragePool,
     prometheus_handle: PrometheusHandle,
+    telemetry: Arc<MetricsTelemetry>,
 }
 
 impl HealthServer {
     /// Create a new health server.
     ///
-    /// This initializes the Prometheus metrics exporter.
+    /// This initializes the Prometheus metrics exporter and, if configured
+    /// in the pool's `telemetry` settings, the OTLP metrics exporter.
     pub fn new(pool: StoragePool) -> Self {
-        let prometheus_handle = PrometheusBuilder::new()
-            .install_recorder()
-            .expect("Failed to install Prometheus recorder");
+        let telemetry = telemetry::install(&pool.config().telemetry)
+            .expect("Failed to install metrics recorder");
 
         Self {
             pool,
-            prometheus_handle,
+            prometheus_handle: telemetry.prometheus_handle(),
+            telemetry: Arc::new(telemetry),
         }
     }
 
+    /// The installed metrics recorders, to flush the OTLP exporter on
+    /// shutdown.
+    pub fn telemetry(&self) -> Arc<MetricsTelemetry> {
+        self.telemetry.clone()
+    }
+
     /// Start the health and metrics server.
     ///
     /// # Arguments
@@ -79,6 +97,8 @@ impl HealthServer {
             .route("/health", get(health_handler))
             .route("/health/live", get(liveness_handler))
             .route("/health/ready", get(readiness_handler))
+            .route("/health/history", get(history_handler))
+            .route("/migrations/online", get(online_migrations_handler))
             .route("/metrics", get(metrics_handler))
             .with_state(app_state);
 
@@ -104,6 +124,8 @@ impl HealthServer {
             .route("/health", get(health_handler))
             .route("/health/live", get(liveness_handler))
             .route("/health/ready", get(readiness_handler))
+            .route("/health/history", get(history_handler))
+            .route("/migrations/online", get(online_migrations_handler))
             .route("/metrics", get(metrics_handler))
             .with_state(app_state)
     }
@@ -143,6 +165,9 @@ pub struct DatabaseHealth {
     /// Redis health status (if configured)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub redis: Option<ServiceHealth>,
+
+    /// PostgreSQL circuit breaker state (closed, open, half_open)
+    pub circuit_state: String,
 }
 
 /// Individual service health.
@@ -207,7 +232,7 @@ async fn health_handler(State(state): State<Arc<AppState>>) -> Result<Json<Healt
 
     // Check PostgreSQL
     let pg_start = Instant::now();
-    let pg_result = state.pool.health_check_postgres().await;
+    let pg_result = state.pool.guarded(state.pool.health_check_postgres()).await;
     let pg_latency = pg_start.elapsed().as_secs_f64() * 1000.0;
 
     let postgres = match pg_result {
@@ -263,7 +288,11 @@ async fn health_handler(State(state): State<Arc<AppState>>) -> Result<Json<Healt
     let response = HealthResponse {
         status,
         timestamp: chrono::Utc::now().to_rfc3339(),
-        database: DatabaseHealth { postgres, redis },
+        database: DatabaseHealth {
+            postgres,
+            redis,
+            circuit_state: state.pool.circuit_breaker().state().as_str().to_string(),
+        },
         pool_stats: pool_stats.into(),
         check_duration_ms,
     };
@@ -276,6 +305,52 @@ async fn health_handler(State(state): State<Arc<AppState>>) -> Result<Json<Healt
     Ok(Json(response))
 }
 
+/// Health history response.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HealthHistoryResponse {
+    /// Interval between samples in seconds
+    pub sample_interval_secs: u64,
+
+    /// Maximum number of samples kept
+    pub capacity: usize,
+
+    /// Aggregates over the samples
+    pub summary: HealthHistorySummary,
+
+    /// Samples, oldest first
+    pub samples: Vec<HealthSample>,
+}
+
+/// Health history handler.
+///
+/// Returns the pool's rolling health samples with a summary, so trends are
+/// visible without external monitoring.
+async fn history_handler(State(state): State<Arc<AppState>>) -> Json<HealthHistoryResponse> {
+    let config = &state.pool.config().health_history;
+    let samples = state.pool.health_history();
+
+    Json(HealthHistoryResponse {
+        sample_interval_secs: config.sample_interval_secs,
+        capacity: config.capacity,
+        summary: HealthHistorySummary::from_samples(&samples),
+        samples,
+    })
+}
+
+/// Online migrations handler.
+///
+/// Returns the progress of every online schema migration, most recent first.
+async fn online_migrations_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<MigrationStatus>>, AppError> {
+    let statuses = OnlineMigrator::new(state.pool.clone())
+        .statuses()
+        .await
+        .map_err(|e| AppError::Query(e.to_string()))?;
+
+    Ok(Json(statuses))
+}
+
 /// Liveness probe handler.
 ///
 /// Returns 200 OK if the service is running. This doesn't check external dependencies.
@@ -286,10 +361,10 @@ async fn liveness_handler() -> impl IntoResponse {
 /// Readiness probe handler.
 ///
 /// Returns 200 OK if the service is ready to accept traffic.
-/// Checks database connectivity.
+/// Checks database connectivity; not ready while the circuit breaker is open.
 async fn readiness_handler(State(state): State<Arc<AppState>>) -> Result<impl IntoResponse, AppError> {
     // Quick health check
-    state.pool.health_check_postgres().await
+    state.pool.guarded(state.pool.health_check_postgres()).await
         .map_err(|_| AppError::NotReady)?;
 
     Ok((StatusCode::OK, "ready"))
@@ -311,6 +386,7 @@ async fn metrics_handler(State(state): State<Arc<AppState>>) -> impl IntoRespons
 enum AppError {
     Unhealthy(HealthResponse),
     NotReady,
+    Query(String),
 }
 
 impl IntoResponse for AppError {
@@ -322,6 +398,9 @@ impl IntoResponse for AppError {
             AppError::NotReady => {
                 (StatusCode::SERVICE_UNAVAILABLE, "not ready").into_response()
             }
+            AppError::Query(message) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, message).into_response()
+            }
         }
     }
 }
@@ -0,0 +1,218 @@
+//! Rolling health history.
+//!
+//! [`PoolStats`](crate::pool::PoolStats) and the `/health` endpoint only show
+//! the current state. The pool samples its utilization, connection acquire
+//! latency and health check result every
+//! [`sample_interval_secs`](crate::config::HealthHistoryConfig::sample_interval_secs)
+//! and keeps the most recent samples in a fixed-size ring buffer, so trends
+//! (a pool slowly filling up, intermittent health check failures) are visible
+//! without an external monitoring stack.
+
+use crate::pool::PoolStats;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// One health sample.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthSample {
+    /// When the sample was taken
+    pub timestamp: DateTime<Utc>,
+
+    /// Total connections in the pool
+    pub pool_size: u32,
+
+    /// Active connections
+    pub pool_active: u32,
+
+    /// Idle connections
+    pub pool_idle: u32,
+
+    /// Active connections as a percentage of the maximum
+    pub utilization_percent: f64,
+
+    /// Time to acquire a connection in milliseconds (None if acquiring failed)
+    pub acquire_latency_ms: Option<f64>,
+
+    /// Whether the PostgreSQL health check passed
+    pub postgres_healthy: bool,
+
+    /// Whether the Redis health check passed (None if not configured)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redis_healthy: Option<bool>,
+
+    /// PostgreSQL circuit breaker state (closed, open, half_open)
+    pub circuit_state: String,
+
+    /// Error of a failed acquire or health check
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HealthSample {
+    /// Create a sample from pool statistics, with no health check result yet.
+    pub fn from_stats(stats: &PoolStats) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            pool_size: stats.postgres_size,
+            pool_active: stats.postgres_active,
+            pool_idle: stats.postgres_idle,
+            utilization_percent: stats.utilization_percent(),
+            acquire_latency_ms: None,
+            postgres_healthy: false,
+            redis_healthy: None,
+            circuit_state: String::new(),
+            error: None,
+        }
+    }
+
+    /// Check if all configured services were healthy.
+    pub fn is_healthy(&self) -> bool {
+        self.postgres_healthy && self.redis_healthy.unwrap_or(true)
+    }
+}
+
+/// Aggregates over the samples in the history.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HealthHistorySummary {
+    /// Number of samples
+    pub samples: usize,
+
+    /// Percentage of samples where all services were healthy
+    pub healthy_percent: f64,
+
+    /// Average pool utilization percentage
+    pub avg_utilization_percent: f64,
+
+    /// Highest pool utilization percentage
+    pub max_utilization_percent: f64,
+
+    /// Average acquire latency in milliseconds over successful acquires
+    pub avg_acquire_latency_ms: Option<f64>,
+
+    /// Highest acquire latency in milliseconds
+    pub max_acquire_latency_ms: Option<f64>,
+}
+
+impl HealthHistorySummary {
+    /// Summarize samples.
+    pub fn from_samples(samples: &[HealthSample]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let count = samples.len() as f64;
+        let healthy = samples.iter().filter(|s| s.is_healthy()).count() as f64;
+        let utilization: Vec<f64> = samples.iter().map(|s| s.utilization_percent).collect();
+        let latencies: Vec<f64> = samples
+            .iter()
+            .filter_map(|s| s.acquire_latency_ms)
+            .collect();
+
+        Self {
+            samples: samples.len(),
+            healthy_percent: healthy / count * 100.0,
+            avg_utilization_percent: utilization.iter().sum::<f64>() / count,
+            max_utilization_percent: utilization.iter().copied().fold(0.0, f64::max),
+            avg_acquire_latency_ms: (!latencies.is_empty())
+                .then(|| latencies.iter().sum::<f64>() / latencies.len() as f64),
+            max_acquire_latency_ms: latencies.iter().copied().reduce(f64::max),
+        }
+    }
+}
+
+/// Fixed-size ring buffer of health samples, oldest first.
+#[derive(Debug)]
+pub struct HealthHistory {
+    capacity: usize,
+    samples: Mutex<VecDeque<HealthSample>>,
+}
+
+impl HealthHistory {
+    /// Create an empty history keeping at most `capacity` samples.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            samples: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Maximum number of samples kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Add a sample, evicting the oldest one when full.
+    pub fn record(&self, sample: HealthSample) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() == self.capacity {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+
+    /// Copy of the samples, oldest first.
+    pub fn samples(&self) -> Vec<HealthSample> {
+        self.samples.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(utilization: f64, latency: Option<f64>, healthy: bool) -> HealthSample {
+        HealthSample {
+            utilization_percent: utilization,
+            acquire_latency_ms: latency,
+            postgres_healthy: healthy,
+            ..HealthSample::from_stats(&PoolStats {
+                postgres_size: 0,
+                postgres_idle: 0,
+                postgres_active: 0,
+                redis_connected: false,
+                postgres_max_connections: 10,
+                postgres_min_connections: 0,
+            })
+        }
+    }
+
+    #[test]
+    fn test_history_evicts_oldest() {
+        let history = HealthHistory::new(2);
+        history.record(sample(10.0, None, true));
+        history.record(sample(20.0, None, true));
+        history.record(sample(30.0, None, true));
+
+        let samples = history.samples();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].utilization_percent, 20.0);
+        assert_eq!(samples[1].utilization_percent, 30.0);
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = HealthHistorySummary::from_samples(&[
+            sample(10.0, Some(2.0), true),
+            sample(50.0, None, false),
+            sample(30.0, Some(4.0), true),
+            sample(10.0, Some(6.0), true),
+        ]);
+
+        assert_eq!(summary.samples, 4);
+        assert_eq!(summary.healthy_percent, 75.0);
+        assert_eq!(summary.avg_utilization_percent, 25.0);
+        assert_eq!(summary.max_utilization_percent, 50.0);
+        assert_eq!(summary.avg_acquire_latency_ms, Some(4.0));
+        assert_eq!(summary.max_acquire_latency_ms, Some(6.0));
+
+        let empty = HealthHistorySummary::from_samples(&[]);
+        assert_eq!(empty.samples, 0);
+        assert_eq!(empty.avg_acquire_latency_ms, None);
+    }
+}
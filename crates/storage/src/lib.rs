@@ -15,11 +15,29 @@
 //!
 //! The storage layer is organized into several modules:
 //!
+//! - `backend`: Writer and repository traits, with PostgreSQL, in-memory and SQLite backends
+//! - `backup`: Chunked, checksummed backups of a time range and their restore
+//! - `bulk`: Parquet bulk import through COPY (`parquet` feature)
 //! - `config`: Database configuration and connection settings
+//! - `credentials`: PostgreSQL password sources (static, environment, RDS IAM)
 //! - `pool`: Connection pool management
+//! - `lifecycle`: Archiving old rows to Parquet in object storage (`lifecycle` feature)
+//! - `residency`: Routing each organization's writes to its region's database
+//! - `health_history`: Rolling pool health samples
+//! - `circuit_breaker`: Load shedding when the database is failing or slow
+//! - `compression`: Compression of large JSONB payloads
+//! - `encryption`: Envelope encryption of prompts, completions and other sensitive fields
 //! - `models`: Data models representing database entities
 //! - `repositories`: Query interfaces for reading data
+//! - `query`: Query timeouts and slow-query log sanitization
 //! - `writers`: Batch writing interfaces for inserting data
+//! - `online_migration`: Batched, dual-write schema migrations of large tables
+//! - `partitioning`: Partition creation and partition-aware retention
+//! - `downsampling`: Rolling old metric data points into lower resolutions
+//! - `top_n`: Daily top-N rollups of LLM requests
+//! - `shutdown`: Flushing buffered writes on graceful shutdown
+//! - `spans`: Tracing spans for storage operations
+//! - `telemetry`: Prometheus and OTLP export of storage metrics
 //! - `error`: Storage-specific error types
 //!
 //! ## Usage
@@ -42,22 +60,61 @@
 //! }
 //! ```
 
+pub mod backend;
+pub mod backup;
+#[cfg(feature = "parquet")]
+pub mod bulk;
+pub mod circuit_breaker;
+pub mod compression;
 pub mod config;
+pub mod credentials;
+pub mod downsampling;
+pub mod encryption;
 pub mod error;
 pub mod health;
+pub mod health_history;
+#[cfg(feature = "lifecycle")]
+pub mod lifecycle;
 pub mod metrics;
 pub mod models;
+pub mod online_migration;
+pub mod partitioning;
 pub mod pool;
+pub mod query;
 pub mod repositories;
+pub mod residency;
+pub mod shutdown;
+pub mod spans;
+pub mod telemetry;
+pub mod top_n;
 pub mod validation;
 pub mod writers;
 
 // Re-exports for convenience
-pub use config::StorageConfig;
+#[cfg(feature = "sqlite")]
+pub use backend::SqliteBackend;
+pub use backend::{InMemoryBackend, Storage};
+pub use backup::{BackupManager, BackupManifest, BackupOptions};
+#[cfg(feature = "parquet")]
+pub use bulk::{BulkLoader, SchemaMapping};
+pub use circuit_breaker::{CircuitBreaker, CircuitState};
+pub use config::{SqliteConfig, StorageBackend, StorageConfig};
+pub use credentials::CredentialProvider;
+pub use downsampling::Downsampler;
+pub use encryption::{FieldEncryptor, KeyProvider};
 pub use error::{StorageError, StorageResult};
 pub use health::HealthServer;
+pub use health_history::{HealthHistorySummary, HealthSample};
+#[cfg(feature = "lifecycle")]
+pub use lifecycle::LifecycleManager;
 pub use metrics::StorageMetrics;
+pub use online_migration::{OnlineMigration, OnlineMigrator};
+pub use partitioning::PartitionManager;
 pub use pool::{HealthCheckResult, PoolStats, StoragePool};
+pub use residency::{RegionRouter, RegionalTraceWriter};
+pub use shutdown::{Flushable, Registration, ShutdownReport};
+pub use telemetry::MetricsTelemetry;
+pub use top_n::TopNMaterializer;
 pub use validation::Validate;
 
 /// Storage crate version
@@ -0,0 +1,721 @@
+//! Tiered storage: archiving old rows from Postgres to object storage.
+//!
+//! The [`LifecycleManager`] moves rows older than `hot_days` out of
+//! `traces`, `trace_spans`, `logs` and `llm_traces` into Parquet files, one
+//! UTC day at a time:
+//!
+//! ```text
+//! <location>/<table>/date=<YYYY-MM-DD>/part-00000.parquet
+//! ```
+//!
+//! A day is read, uploaded, recorded in `archive_manifest` and deleted in one
+//! repeatable-read transaction (see migration `036_storage_lifecycle.sql`),
+//! so rows written concurrently are neither archived nor deleted, and a
+//! failed run leaves the day in Postgres. Archived traces are also recorded
+//! in `archived_traces`, so
+//! [`TraceRepository::get_by_trace_id`](crate::repositories::TraceRepository::get_by_trace_id)
+//! reports them as archived rather than missing.
+//!
+//! The `traces`, `trace_spans` and `logs` files use the field names of
+//! [`ImportTarget`](crate::bulk::ImportTarget), so they can be loaded back
+//! with the bulk loader. The `llm_traces` files have the columns the
+//! analytics API's cold storage queries read; point `COLD_STORAGE_URL` at
+//! `<location>/llm_traces/`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm_observatory_storage::{lifecycle::LifecycleManager, StorageConfig, StoragePool};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let pool = StoragePool::new(StorageConfig::from_env()?).await?;
+//! let manager = LifecycleManager::new(pool)?;
+//!
+//! // Run once now, then keep running in the background
+//! manager.run().await?;
+//! let _handle = manager.start();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::config::LifecycleConfig;
+use crate::error::{StorageError, StorageResult};
+use crate::pool::StoragePool;
+use crate::top_n::day_start;
+use arrow::array::{
+    ArrayRef, Float64Builder, Int64Builder, StringBuilder, TimestampMicrosecondBuilder,
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Duration, Utc};
+use futures::TryStreamExt;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutPayload};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::properties::WriterProperties;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+use std::sync::Arc;
+
+/// Type a column is archived as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    /// UTF-8 string
+    Text,
+    /// 64-bit integer
+    Integer,
+    /// 64-bit float
+    Float,
+    /// UTC timestamp in microseconds
+    Time,
+    /// JSON document stored as a string
+    Json,
+}
+
+impl ColumnKind {
+    fn arrow_type(&self) -> DataType {
+        match self {
+            ColumnKind::Text | ColumnKind::Json => DataType::Utf8,
+            ColumnKind::Integer => DataType::Int64,
+            ColumnKind::Float => DataType::Float64,
+            ColumnKind::Time => DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into())),
+        }
+    }
+
+    /// Postgres expression reading `column` as this kind.
+    fn select_expr(&self, column: &str) -> String {
+        match self {
+            ColumnKind::Text | ColumnKind::Json => format!("{}::TEXT", column),
+            ColumnKind::Integer => format!("{}::BIGINT", column),
+            ColumnKind::Float => format!("{}::DOUBLE PRECISION", column),
+            ColumnKind::Time => column.to_string(),
+        }
+    }
+}
+
+/// A table moved to object storage.
+#[derive(Debug, Clone, Copy)]
+pub struct ArchivedTable {
+    /// Table name, also the directory of its files
+    pub name: &'static str,
+
+    /// Timestamp column rows are archived by
+    pub time_column: &'static str,
+
+    /// Archived columns
+    pub columns: &'static [(&'static str, ColumnKind)],
+}
+
+impl ArchivedTable {
+    /// Arrow schema of the table's files.
+    pub fn schema(&self) -> SchemaRef {
+        Arc::new(Schema::new(
+            self.columns
+                .iter()
+                .map(|(name, kind)| Field::new(*name, kind.arrow_type(), true))
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Query reading one day of rows, bound to the day's bounds.
+    fn select_sql(&self) -> String {
+        let columns: Vec<String> = self
+            .columns
+            .iter()
+            .map(|(name, kind)| kind.select_expr(name))
+            .collect();
+        format!(
+            "SELECT {} FROM {} WHERE {time} >= $1 AND {time} < $2 ORDER BY {time}",
+            columns.join(", "),
+            self.name,
+            time = self.time_column
+        )
+    }
+}
+
+/// Tables managed by the lifecycle manager, archived in this order.
+pub const TABLES: [ArchivedTable; 4] = [
+    ArchivedTable {
+        name: "traces",
+        time_column: "start_time",
+        columns: &[
+            ("trace_id", ColumnKind::Text),
+            ("service_name", ColumnKind::Text),
+            ("start_time", ColumnKind::Time),
+            ("end_time", ColumnKind::Time),
+            ("duration_us", ColumnKind::Integer),
+            ("status", ColumnKind::Text),
+            ("status_message", ColumnKind::Text),
+            ("root_span_name", ColumnKind::Text),
+            ("attributes", ColumnKind::Json),
+            ("resource_attributes", ColumnKind::Json),
+            ("span_count", ColumnKind::Integer),
+        ],
+    },
+    ArchivedTable {
+        name: "trace_spans",
+        time_column: "start_time",
+        columns: &[
+            ("trace_id", ColumnKind::Text),
+            ("span_id", ColumnKind::Text),
+            ("parent_span_id", ColumnKind::Text),
+            ("name", ColumnKind::Text),
+            ("kind", ColumnKind::Text),
+            ("service_name", ColumnKind::Text),
+            ("start_time", ColumnKind::Time),
+            ("end_time", ColumnKind::Time),
+            ("duration_us", ColumnKind::Integer),
+            ("status", ColumnKind::Text),
+            ("status_message", ColumnKind::Text),
+            ("attributes", ColumnKind::Json),
+            ("events", ColumnKind::Json),
+            ("links", ColumnKind::Json),
+        ],
+    },
+    ArchivedTable {
+        name: "logs",
+        time_column: "timestamp",
+        columns: &[
+            ("timestamp", ColumnKind::Time),
+            ("observed_timestamp", ColumnKind::Time),
+            ("severity_number", ColumnKind::Integer),
+            ("severity_text", ColumnKind::Text),
+            ("body", ColumnKind::Text),
+            ("service_name", ColumnKind::Text),
+            ("trace_id", ColumnKind::Text),
+            ("span_id", ColumnKind::Text),
+            ("trace_flags", ColumnKind::Integer),
+            ("attributes", ColumnKind::Json),
+            ("resource_attributes", ColumnKind::Json),
+            ("scope_name", ColumnKind::Text),
+            ("scope_version", ColumnKind::Text),
+            ("scope_attributes", ColumnKind::Json),
+        ],
+    },
+    ArchivedTable {
+        name: "llm_traces",
+        time_column: "ts",
+        columns: &[
+            ("ts", ColumnKind::Time),
+            ("org_id", ColumnKind::Text),
+            ("trace_id", ColumnKind::Text),
+            ("span_id", ColumnKind::Text),
+            ("parent_span_id", ColumnKind::Text),
+            ("span_name", ColumnKind::Text),
+            ("provider", ColumnKind::Text),
+            ("model", ColumnKind::Text),
+            ("environment", ColumnKind::Text),
+            ("user_id", ColumnKind::Text),
+            ("session_id", ColumnKind::Text),
+            ("status_code", ColumnKind::Text),
+            ("prompt_tokens", ColumnKind::Integer),
+            ("completion_tokens", ColumnKind::Integer),
+            ("total_tokens", ColumnKind::Integer),
+            ("prompt_cost_usd", ColumnKind::Float),
+            ("completion_cost_usd", ColumnKind::Float),
+            ("total_cost_usd", ColumnKind::Float),
+            ("duration_ms", ColumnKind::Integer),
+            ("ttft_ms", ColumnKind::Integer),
+            ("attributes", ColumnKind::Json),
+        ],
+    },
+];
+
+/// Where archived files are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveLocation {
+    /// `s3://bucket/prefix`
+    S3 { bucket: String, prefix: String },
+    /// `gs://bucket/prefix`
+    Gcs { bucket: String, prefix: String },
+    /// Local directory
+    Local { dir: String },
+}
+
+impl ArchiveLocation {
+    /// Parse an archive root.
+    pub fn parse(location: &str) -> StorageResult<Self> {
+        let location = location.trim().trim_end_matches('/');
+        let split = |rest: &str| -> StorageResult<(String, String)> {
+            let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+            if bucket.is_empty() {
+                return Err(StorageError::ConfigError(format!(
+                    "Archive location '{}' has no bucket",
+                    location
+                )));
+            }
+            Ok((bucket.to_string(), prefix.trim_matches('/').to_string()))
+        };
+
+        if let Some(rest) = location.strip_prefix("s3://") {
+            let (bucket, prefix) = split(rest)?;
+            Ok(ArchiveLocation::S3 { bucket, prefix })
+        } else if let Some(rest) = location.strip_prefix("gs://") {
+            let (bucket, prefix) = split(rest)?;
+            Ok(ArchiveLocation::Gcs { bucket, prefix })
+        } else if location.is_empty() {
+            Err(StorageError::ConfigError("Archive location is empty".to_string()))
+        } else {
+            Ok(ArchiveLocation::Local {
+                dir: location.strip_prefix("file://").unwrap_or(location).to_string(),
+            })
+        }
+    }
+
+    /// Object store holding the archive.
+    ///
+    /// S3 and GCS credentials are read from the standard `AWS_*` and
+    /// `GOOGLE_*` environment variables.
+    fn store(&self) -> StorageResult<Arc<dyn ObjectStore>> {
+        let invalid = |e: object_store::Error| StorageError::ConfigError(format!("Archive location: {}", e));
+        Ok(match self {
+            ArchiveLocation::S3 { bucket, .. } => Arc::new(
+                object_store::aws::AmazonS3Builder::from_env()
+                    .with_bucket_name(bucket)
+                    .build()
+                    .map_err(invalid)?,
+            ),
+            ArchiveLocation::Gcs { bucket, .. } => Arc::new(
+                object_store::gcp::GoogleCloudStorageBuilder::from_env()
+                    .with_bucket_name(bucket)
+                    .build()
+                    .map_err(invalid)?,
+            ),
+            ArchiveLocation::Local { dir } => {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| StorageError::ConfigError(format!("Archive location '{}': {}", dir, e)))?;
+                Arc::new(object_store::local::LocalFileSystem::new_with_prefix(dir).map_err(invalid)?)
+            }
+        })
+    }
+
+    /// Path of the directory holding one day of `table`, within the store.
+    pub fn day_path(&self, table: &str, day: DateTime<Utc>) -> ObjectPath {
+        let dir = format!("{}/date={}", table, day.format("%Y-%m-%d"));
+        match self {
+            ArchiveLocation::S3 { prefix, .. } | ArchiveLocation::Gcs { prefix, .. } if !prefix.is_empty() => {
+                ObjectPath::from(format!("{}/{}", prefix, dir))
+            }
+            _ => ObjectPath::from(dir),
+        }
+    }
+
+    /// Full URL of a path within the store.
+    pub fn url(&self, path: &ObjectPath) -> String {
+        match self {
+            ArchiveLocation::S3 { bucket, .. } => format!("s3://{}/{}", bucket, path),
+            ArchiveLocation::Gcs { bucket, .. } => format!("gs://{}/{}", bucket, path),
+            ArchiveLocation::Local { dir } => format!("{}/{}", dir, path),
+        }
+    }
+}
+
+/// End of the archived range at `now`: rows before it are archived.
+pub fn cutoff(now: DateTime<Utc>, hot_days: u32) -> DateTime<Utc> {
+    day_start(now) - Duration::days(hot_days as i64)
+}
+
+/// Name of the `part`th file of a day.
+pub fn part_name(part: usize) -> String {
+    format!("part-{:05}.parquet", part)
+}
+
+/// Summary of one archived day.
+#[derive(Debug, Clone, Default)]
+pub struct DayReport {
+    /// Files written
+    pub files: u64,
+
+    /// Rows archived and deleted
+    pub rows: u64,
+
+    /// Bytes written
+    pub bytes: u64,
+}
+
+/// Summary of a lifecycle run.
+#[derive(Debug, Clone, Default)]
+pub struct LifecycleReport {
+    /// Table days archived
+    pub days: u64,
+
+    /// Files written
+    pub files: u64,
+
+    /// Rows archived and deleted
+    pub rows: u64,
+
+    /// Bytes written
+    pub bytes: u64,
+}
+
+/// Column builders for one Parquet file.
+enum ColumnBuilder {
+    Text(StringBuilder),
+    Integer(Int64Builder),
+    Float(Float64Builder),
+    Time(TimestampMicrosecondBuilder),
+}
+
+impl ColumnBuilder {
+    fn new(kind: ColumnKind) -> Self {
+        match kind {
+            ColumnKind::Text | ColumnKind::Json => ColumnBuilder::Text(StringBuilder::new()),
+            ColumnKind::Integer => ColumnBuilder::Integer(Int64Builder::new()),
+            ColumnKind::Float => ColumnBuilder::Float(Float64Builder::new()),
+            ColumnKind::Time => ColumnBuilder::Time(TimestampMicrosecondBuilder::new().with_timezone("+00:00")),
+        }
+    }
+
+    fn append(&mut self, row: &PgRow, index: usize) -> StorageResult<()> {
+        match self {
+            ColumnBuilder::Text(builder) => builder.append_option(row.try_get::<Option<String>, _>(index)?),
+            ColumnBuilder::Integer(builder) => builder.append_option(row.try_get::<Option<i64>, _>(index)?),
+            ColumnBuilder::Float(builder) => builder.append_option(row.try_get::<Option<f64>, _>(index)?),
+            ColumnBuilder::Time(builder) => builder.append_option(
+                row.try_get::<Option<DateTime<Utc>>, _>(index)?
+                    .map(|t| t.timestamp_micros()),
+            ),
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            ColumnBuilder::Text(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Integer(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Float(builder) => Arc::new(builder.finish()),
+            ColumnBuilder::Time(builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+/// Buffered rows of the next file of a day.
+struct FileBuffer {
+    schema: SchemaRef,
+    builders: Vec<ColumnBuilder>,
+    rows: usize,
+}
+
+impl FileBuffer {
+    fn new(table: &ArchivedTable) -> Self {
+        Self {
+            schema: table.schema(),
+            builders: table.columns.iter().map(|(_, kind)| ColumnBuilder::new(*kind)).collect(),
+            rows: 0,
+        }
+    }
+
+    fn push(&mut self, row: &PgRow) -> StorageResult<()> {
+        for (index, builder) in self.builders.iter_mut().enumerate() {
+            builder.append(row, index)?;
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Encode the buffered rows as a Parquet file and reset the buffer.
+    fn take_parquet(&mut self) -> StorageResult<Vec<u8>> {
+        let encode_error = |e: parquet::errors::ParquetError| StorageError::SerializationError(e.to_string());
+        let columns = self.builders.iter_mut().map(ColumnBuilder::finish).collect();
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)
+            .map_err(|e| StorageError::SerializationError(e.to_string()))?;
+        self.rows = 0;
+
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, self.schema.clone(), Some(properties)).map_err(encode_error)?;
+        writer.write(&batch).map_err(encode_error)?;
+        writer.close().map_err(encode_error)?;
+        Ok(buffer)
+    }
+}
+
+/// Moves old rows from Postgres to Parquet files in object storage.
+#[derive(Clone)]
+pub struct LifecycleManager {
+    pool: StoragePool,
+    config: LifecycleConfig,
+    location: ArchiveLocation,
+    store: Arc<dyn ObjectStore>,
+}
+
+impl LifecycleManager {
+    /// Create a manager using the pool's lifecycle configuration.
+    pub fn new(pool: StoragePool) -> StorageResult<Self> {
+        let config = pool.config().lifecycle.clone();
+        Self::with_config(pool, config)
+    }
+
+    /// Create a manager with an explicit configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the location cannot be parsed or
+    /// its object store cannot be created.
+    pub fn with_config(pool: StoragePool, config: LifecycleConfig) -> StorageResult<Self> {
+        let location = ArchiveLocation::parse(&config.location)?;
+        let store = location.store()?;
+        Ok(Self {
+            pool,
+            config,
+            location,
+            store,
+        })
+    }
+
+    /// Oldest day of `table` still in Postgres and due for archiving.
+    async fn oldest_day(&self, table: &ArchivedTable, cutoff: DateTime<Utc>) -> StorageResult<Option<DateTime<Utc>>> {
+        let oldest: Option<DateTime<Utc>> = sqlx::query_scalar(&format!(
+            "SELECT MIN({time}) FROM {} WHERE {time} < $1",
+            table.name,
+            time = table.time_column
+        ))
+        .bind(cutoff)
+        .fetch_one(self.pool.postgres())
+        .await?;
+
+        Ok(oldest.map(day_start))
+    }
+
+    /// Archive and delete the rows of `table` in the day starting at `day`.
+    ///
+    /// Files already written for the day, by an earlier failed run, are
+    /// replaced.
+    pub async fn archive_day(&self, table: &ArchivedTable, day: DateTime<Utc>) -> StorageResult<DayReport> {
+        let store_error = |e: object_store::Error| StorageError::ConnectionError(format!("Archive store: {}", e));
+        let end = day + Duration::days(1);
+        let dir = self.location.day_path(table.name, day);
+
+        let stale: Vec<_> = self.store.list(Some(&dir)).try_collect().await.map_err(store_error)?;
+        for object in stale {
+            self.store.delete(&object.location).await.map_err(store_error)?;
+        }
+
+        let mut tx = self.pool.postgres().begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .execute(&mut *tx)
+            .await?;
+
+        let mut report = DayReport::default();
+        let mut files = Vec::new();
+        let mut buffer = FileBuffer::new(table);
+        {
+            let sql = table.select_sql();
+            let mut rows = sqlx::query(&sql).bind(day).bind(end).fetch(&mut *tx);
+            loop {
+                let row = rows.try_next().await?;
+                if let Some(row) = &row {
+                    buffer.push(row)?;
+                }
+                let full = buffer.rows >= self.config.max_rows_per_file;
+                if buffer.rows > 0 && (full || row.is_none()) {
+                    let file_rows = buffer.rows as u64;
+                    let bytes = buffer.take_parquet()?;
+                    let path = dir.child(part_name(files.len()));
+                    let size = bytes.len() as u64;
+                    self.store
+                        .put(&path, PutPayload::from(bytes))
+                        .await
+                        .map_err(store_error)?;
+                    report.rows += file_rows;
+                    report.bytes += size;
+                    files.push((self.location.url(&path), file_rows, size));
+                }
+                if row.is_none() {
+                    break;
+                }
+            }
+        }
+        report.files = files.len() as u64;
+
+        sqlx::query("DELETE FROM archive_manifest WHERE table_name = $1 AND day = $2")
+            .bind(table.name)
+            .bind(day)
+            .execute(&mut *tx)
+            .await?;
+        for (url, rows, size) in &files {
+            sqlx::query(
+                "INSERT INTO archive_manifest (location, table_name, day, row_count, size_bytes) \
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(url)
+            .bind(table.name)
+            .bind(day)
+            .bind(*rows as i64)
+            .bind(*size as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if table.name == "traces" {
+            sqlx::query(
+                "INSERT INTO archived_traces (trace_id, start_time, location) \
+                 SELECT trace_id, start_time, $3 FROM traces WHERE start_time >= $1 AND start_time < $2 \
+                 ON CONFLICT (trace_id) DO UPDATE SET location = EXCLUDED.location, archived_at = NOW()",
+            )
+            .bind(day)
+            .bind(end)
+            .bind(format!("{}/", self.location.url(&dir)))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let deleted = sqlx::query(&format!(
+            "DELETE FROM {} WHERE {time} >= $1 AND {time} < $2",
+            table.name,
+            time = table.time_column
+        ))
+        .bind(day)
+        .bind(end)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if deleted != report.rows {
+            return Err(StorageError::TransactionError(format!(
+                "Archived {} rows of {} on {} but would delete {}",
+                report.rows,
+                table.name,
+                day.format("%Y-%m-%d"),
+                deleted
+            )));
+        }
+
+        tx.commit().await?;
+        Ok(report)
+    }
+
+    /// Archive the days older than the hot window, up to `max_days_per_run`
+    /// per table.
+    pub async fn run(&self) -> StorageResult<LifecycleReport> {
+        let mut report = LifecycleReport::default();
+        if !self.config.enabled {
+            return Ok(report);
+        }
+
+        let cutoff = cutoff(Utc::now(), self.config.hot_days);
+        for table in &TABLES {
+            let Some(mut day) = self.oldest_day(table, cutoff).await? else {
+                continue;
+            };
+            for _ in 0..self.config.max_days_per_run {
+                if day >= cutoff {
+                    break;
+                }
+                let archived = self.archive_day(table, day).await?;
+                tracing::debug!(
+                    table = table.name,
+                    day = %day.format("%Y-%m-%d"),
+                    rows = archived.rows,
+                    "Archived day"
+                );
+                report.days += 1;
+                report.files += archived.files;
+                report.rows += archived.rows;
+                report.bytes += archived.bytes;
+                day += Duration::days(1);
+            }
+        }
+
+        tracing::info!(
+            "Storage lifecycle: {} days, {} files, {} rows archived",
+            report.days,
+            report.files,
+            report.rows
+        );
+
+        Ok(report)
+    }
+
+    /// Start periodic archiving.
+    ///
+    /// Returns a handle that can be used to stop the archiving task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let manager = self.clone();
+        let interval = self.config.interval();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = manager.run().await {
+                    tracing::error!("Storage lifecycle error: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_location() {
+        assert_eq!(
+            ArchiveLocation::parse("s3://archive/observatory/").unwrap(),
+            ArchiveLocation::S3 {
+                bucket: "archive".to_string(),
+                prefix: "observatory".to_string(),
+            }
+        );
+        assert_eq!(
+            ArchiveLocation::parse("gs://archive").unwrap(),
+            ArchiveLocation::Gcs {
+                bucket: "archive".to_string(),
+                prefix: String::new(),
+            }
+        );
+        assert_eq!(
+            ArchiveLocation::parse("file:///var/lib/archive").unwrap(),
+            ArchiveLocation::Local {
+                dir: "/var/lib/archive".to_string(),
+            }
+        );
+        assert!(ArchiveLocation::parse("s3://").is_err());
+        assert!(ArchiveLocation::parse("").is_err());
+    }
+
+    #[test]
+    fn test_day_path_and_url() {
+        let day = ts("2025-07-04T00:00:00Z");
+        let location = ArchiveLocation::parse("s3://archive/observatory").unwrap();
+        let path = location.day_path("traces", day);
+        assert_eq!(path.as_ref(), "observatory/traces/date=2025-07-04");
+        assert_eq!(
+            location.url(&path.child(part_name(3))),
+            "s3://archive/observatory/traces/date=2025-07-04/part-00003.parquet"
+        );
+
+        let location = ArchiveLocation::parse("/var/lib/archive").unwrap();
+        assert_eq!(
+            location.url(&location.day_path("logs", day)),
+            "/var/lib/archive/logs/date=2025-07-04"
+        );
+    }
+
+    #[test]
+    fn test_cutoff() {
+        assert_eq!(cutoff(ts("2025-11-12T15:30:00Z"), 90), ts("2025-08-14T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_tables_archive_their_time_column() {
+        for table in TABLES {
+            assert!(
+                table.columns.iter().any(|(name, kind)| *name == table.time_column && *kind == ColumnKind::Time),
+                "{} does not archive {}",
+                table.name,
+                table.time_column
+            );
+            assert_eq!(table.schema().fields().len(), table.columns.len());
+        }
+    }
+}
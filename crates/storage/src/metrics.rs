@@ -94,6 +94,24 @@ impl StorageMetrics {
             "Total number of buffer flush operations"
         );
 
+        // Flush trigger counter
+        describe_counter!(
+            "storage_flush_triggers_total",
+            "Total number of buffer flushes by trigger reason (rows, bytes, latency, manual)"
+        );
+
+        // Flush duration histogram
+        describe_histogram!(
+            "storage_flush_duration_seconds",
+            "Duration of buffer flush operations in seconds"
+        );
+
+        // Adaptive batch limit gauge
+        describe_gauge!(
+            "storage_batch_row_limit",
+            "Current effective row limit of adaptive writer batches"
+        );
+
         // Retry counter
         describe_counter!(
             "storage_retries_total",
@@ -117,6 +135,54 @@ impl StorageMetrics {
             "storage_connection_acquire_duration_seconds",
             "Time taken to acquire a connection from the pool"
         );
+
+        // Statement duration histogram
+        describe_histogram!(
+            "storage_statement_duration_seconds",
+            "Duration of individual repository SQL statements in seconds"
+        );
+
+        // Slow query counter
+        describe_counter!(
+            "storage_slow_queries_total",
+            "Total number of repository queries exceeding the slow query threshold"
+        );
+
+        // Circuit breaker state gauge
+        describe_gauge!(
+            "storage_circuit_state",
+            "Database circuit breaker state (0 = closed, 1 = half-open, 2 = open)"
+        );
+
+        // Circuit breaker rejections counter
+        describe_counter!(
+            "storage_circuit_rejections_total",
+            "Total number of calls rejected by the open circuit breaker"
+        );
+
+        // Metric cardinality overflow counter
+        describe_counter!(
+            "storage_metric_cardinality_overflow_total",
+            "Total number of metric data points whose attributes exceeded a cardinality limit"
+        );
+
+        // Region health gauge
+        describe_gauge!(
+            "storage_region_healthy",
+            "Whether a data residency region's database is healthy (1) or not (0)"
+        );
+
+        // Region rejections counter
+        describe_counter!(
+            "storage_region_rejections_total",
+            "Total number of writes rejected because their region was unavailable"
+        );
+
+        // Writer shard rows counter
+        describe_counter!(
+            "storage_writer_shard_rows_total",
+            "Total number of rows routed to each writer shard"
+        );
     }
 
     /// Record a write operation.
@@ -159,6 +225,24 @@ impl StorageMetrics {
         ).record(duration_secs);
     }
 
+    /// Record the duration of a single SQL statement issued by a repository.
+    pub fn record_statement(&self, repository: &str, method: &str, duration_secs: f64) {
+        histogram!(
+            "storage_statement_duration_seconds",
+            "repository" => repository.to_string(),
+            "method" => method.to_string()
+        ).record(duration_secs);
+    }
+
+    /// Record a query that exceeded the slow query threshold.
+    pub fn record_slow_query(&self, repository: &str, method: &str) {
+        counter!(
+            "storage_slow_queries_total",
+            "repository" => repository.to_string(),
+            "method" => method.to_string()
+        ).increment(1);
+    }
+
     /// Record query results count.
     pub fn record_query_result_count(&self, repository: &str, method: &str, count: usize) {
         histogram!(
@@ -258,6 +342,41 @@ impl StorageMetrics {
         ).increment(1);
     }
 
+    /// Record what triggered a buffer flush and how long it took.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer_type` - Type of writer (trace, metric, log)
+    /// * `reason` - Flush trigger (rows, bytes, latency, manual)
+    /// * `rows` - Number of rows flushed
+    /// * `duration_secs` - Duration in seconds
+    pub fn record_flush_trigger(&self, writer_type: &str, reason: &str, rows: usize, duration_secs: f64) {
+        counter!(
+            "storage_flush_triggers_total",
+            "writer_type" => writer_type.to_string(),
+            "reason" => reason.to_string()
+        ).increment(1);
+
+        histogram!(
+            "storage_flush_duration_seconds",
+            "writer_type" => writer_type.to_string()
+        ).record(duration_secs);
+
+        histogram!(
+            "storage_batch_size",
+            "writer_type" => writer_type.to_string(),
+            "operation" => "flush".to_string()
+        ).record(rows as f64);
+    }
+
+    /// Update the adaptive batch row limit gauge.
+    pub fn update_batch_row_limit(&self, writer_type: &str, limit: usize) {
+        gauge!(
+            "storage_batch_row_limit",
+            "writer_type" => writer_type.to_string()
+        ).set(limit as f64);
+    }
+
     /// Record a retry attempt.
     ///
     /// # Arguments
@@ -291,6 +410,56 @@ impl StorageMetrics {
             "storage_connection_acquire_duration_seconds"
         ).record(duration_secs);
     }
+
+    /// Update the circuit breaker state gauge.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - Numeric state (0 = closed, 1 = half-open, 2 = open)
+    pub fn update_circuit_state(&self, state: u8) {
+        gauge!("storage_circuit_state").set(state as f64);
+    }
+
+    /// Record a call rejected by the open circuit breaker.
+    pub fn record_circuit_rejection(&self) {
+        counter!("storage_circuit_rejections_total").increment(1);
+    }
+
+    /// Record data points whose attributes exceeded a cardinality limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Limit that was exceeded (attribute, series)
+    /// * `count` - Number of data points
+    pub fn record_cardinality_overflow(&self, limit: &str, count: u64) {
+        counter!(
+            "storage_metric_cardinality_overflow_total",
+            "limit" => limit.to_string()
+        ).increment(count);
+    }
+
+    /// Update the health gauge of a data residency region.
+    pub fn update_region_health(&self, region: &str, healthy: bool) {
+        gauge!("storage_region_healthy", "region" => region.to_string())
+            .set(if healthy { 1.0 } else { 0.0 });
+    }
+
+    /// Record a write rejected because its region was unavailable.
+    pub fn record_region_rejection(&self, region: &str) {
+        counter!(
+            "storage_region_rejections_total",
+            "region" => region.to_string()
+        ).increment(1);
+    }
+
+    /// Record rows routed to a writer shard.
+    pub fn record_shard_rows(&self, writer_type: &str, shard: usize, rows: usize) {
+        counter!(
+            "storage_writer_shard_rows_total",
+            "writer_type" => writer_type.to_string(),
+            "shard" => shard.to_string()
+        ).increment(rows as u64);
+    }
 }
 
 impl Default for StorageMetrics {
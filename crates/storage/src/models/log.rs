@@ -74,6 +74,11 @@ pub struct LogRecord {
     /// Scope attributes as JSON
     pub scope_attributes: Option<serde_json::Value>,
 
+    /// Log pattern mined by the collector (`log.pattern_id` attribute)
+    #[serde(default)]
+    #[sqlx(default)]
+    pub pattern_id: Option<String>,
+
     /// Created timestamp
     pub created_at: DateTime<Utc>,
 }
@@ -0,0 +1,86 @@
+//! Log pattern data models.
+//!
+//! This module defines the log patterns mined by the collector's log pattern
+//! miner (`log_patterns`) and their hourly record counts
+//! (`log_pattern_counts`).
+
+use super::streaming::null_as_empty;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A template grouping similar log bodies.
+///
+/// Deserializes from the collector's pattern rows, whose `org_id` is null
+/// for logs without an organization; it is stored as `''`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LogPattern {
+    /// Pattern ID
+    pub pattern_id: String,
+
+    /// Organization ID (`''` when the logs carry none)
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub org_id: String,
+
+    /// Service name
+    pub service_name: String,
+
+    /// Template, with `<*>` for varying tokens
+    pub template: String,
+
+    /// Highest severity number of the records
+    pub severity_number: i32,
+
+    /// Time of the first record
+    pub first_seen: DateTime<Utc>,
+
+    /// Time of the last record
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Records of a pattern in one hour.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LogPatternCount {
+    /// Start of the hour
+    pub bucket: DateTime<Utc>,
+
+    /// Organization ID (`''` when the logs carry none)
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub org_id: String,
+
+    /// Pattern ID
+    pub pattern_id: String,
+
+    /// Records
+    pub count: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_collector_rows() {
+        let pattern: LogPattern = serde_json::from_value(serde_json::json!({
+            "pattern_id": "9f86d081884c7d65",
+            "org_id": null,
+            "service_name": "api",
+            "template": "Connection refused by upstream <*>",
+            "severity_number": 17,
+            "first_seen": "2025-01-01T12:30:00Z",
+            "last_seen": "2025-01-01T12:31:00Z"
+        }))
+        .unwrap();
+        assert_eq!(pattern.org_id, "");
+
+        let count: LogPatternCount = serde_json::from_value(serde_json::json!({
+            "bucket": "2025-01-01T12:00:00Z",
+            "org_id": "org-1",
+            "pattern_id": "9f86d081884c7d65",
+            "count": 2
+        }))
+        .unwrap();
+        assert_eq!(count.org_id, "org-1");
+        assert_eq!(count.count, 2);
+    }
+}
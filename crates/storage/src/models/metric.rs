@@ -2,6 +2,9 @@
 //!
 //! This module defines the data structures for storing metrics
 //! (counters, gauges, histograms, etc.).
+//!
+//! Histogram data points hold delta counts, so the points of a time window
+//! merge into one [`MergedHistogram`] by adding bucket counts per boundary.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -104,9 +107,10 @@ pub struct MetricDataPoint {
 }
 
 /// Histogram bucket for distribution metrics.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HistogramBucket {
-    /// Upper boundary of the bucket
+    /// Upper boundary of the bucket (inclusive); values above the last
+    /// boundary are only counted in the data point's `count`
     pub boundary: f64,
 
     /// Count of values in this bucket
@@ -114,7 +118,7 @@ pub struct HistogramBucket {
 }
 
 /// Summary quantile for summary metrics.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SummaryQuantile {
     /// Quantile (0.0 to 1.0, e.g., 0.5 for median)
     pub quantile: f64,
@@ -176,6 +180,152 @@ impl MetricDataPoint {
     pub fn is_histogram(&self) -> bool {
         self.buckets.is_some()
     }
+
+    /// Parse the histogram buckets, ordered as stored.
+    pub fn parse_buckets(&self) -> StorageResult<Vec<HistogramBucket>> {
+        match &self.buckets {
+            Some(buckets) => Ok(serde_json::from_value(buckets.clone())?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Parse the summary quantiles.
+    pub fn parse_quantiles(&self) -> StorageResult<Vec<SummaryQuantile>> {
+        match &self.quantiles {
+            Some(quantiles) => Ok(serde_json::from_value(quantiles.clone())?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Parse the exemplars linking this data point to sample traces.
+    pub fn parse_exemplars(&self) -> StorageResult<Vec<Exemplar>> {
+        match &self.exemplars {
+            Some(exemplars) => Ok(serde_json::from_value(exemplars.clone())?),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Histogram merged from the delta data points of a time window.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergedHistogram {
+    /// Buckets ordered by boundary, with the counts of every merged point
+    pub buckets: Vec<HistogramBucket>,
+
+    /// Number of recorded values, including those above the last boundary
+    pub count: i64,
+
+    /// Sum of recorded values
+    pub sum: f64,
+
+    /// Smallest recorded value, if every merged point reported one
+    pub min: Option<f64>,
+
+    /// Largest recorded value, if every merged point reported one
+    pub max: Option<f64>,
+}
+
+impl MergedHistogram {
+    /// Create an empty histogram.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a histogram data point.
+    pub fn add(&mut self, data_point: &MetricDataPoint) -> StorageResult<()> {
+        let first = self.count == 0 && self.buckets.is_empty();
+
+        for bucket in data_point.parse_buckets()? {
+            self.add_bucket(bucket);
+        }
+        self.count += data_point.count.unwrap_or(0);
+        self.sum += data_point.sum.unwrap_or(0.0);
+        self.min = merge_bound(first, self.min, data_point.min, f64::min);
+        self.max = merge_bound(first, self.max, data_point.max, f64::max);
+        Ok(())
+    }
+
+    /// Merge another histogram into this one.
+    pub fn merge(&mut self, other: &MergedHistogram) {
+        let first = self.count == 0 && self.buckets.is_empty();
+
+        for bucket in &other.buckets {
+            self.add_bucket(bucket.clone());
+        }
+        self.count += other.count;
+        self.sum += other.sum;
+        self.min = merge_bound(first, self.min, other.min, f64::min);
+        self.max = merge_bound(first, self.max, other.max, f64::max);
+    }
+
+    fn add_bucket(&mut self, bucket: HistogramBucket) {
+        match self
+            .buckets
+            .binary_search_by(|b| b.boundary.total_cmp(&bucket.boundary))
+        {
+            Ok(i) => self.buckets[i].count += bucket.count,
+            Err(i) => self.buckets.insert(i, bucket),
+        }
+    }
+
+    /// Mean of the recorded values.
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+
+    /// Estimate the `q` quantile (0.0 to 1.0).
+    ///
+    /// Values are assumed to be spread evenly within their bucket, which
+    /// starts at the previous boundary; the first bucket starts at `min`, or
+    /// at zero for a positive boundary. Values above the last boundary
+    /// resolve to `max`, or to the last boundary when `max` is unknown.
+    /// Returns `None` for an empty histogram.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        let bucketed: i64 = self.buckets.iter().map(|b| b.count).sum();
+        let total = self.count.max(bucketed);
+        if total == 0 || !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+
+        let rank = q * total as f64;
+        let mut seen = 0i64;
+        let mut lower = None;
+        let mut estimate = None;
+        for bucket in &self.buckets {
+            let start = lower.unwrap_or(match self.min {
+                Some(min) if min <= bucket.boundary => min,
+                _ if bucket.boundary > 0.0 => 0.0,
+                _ => bucket.boundary,
+            });
+            if bucket.count > 0 && rank <= (seen + bucket.count) as f64 {
+                let fraction = (rank - seen as f64) / bucket.count as f64;
+                estimate = Some(start + (bucket.boundary - start) * fraction.max(0.0));
+                break;
+            }
+            seen += bucket.count;
+            lower = Some(bucket.boundary);
+        }
+
+        let estimate = estimate
+            .or(self.max)
+            .or_else(|| self.buckets.last().map(|b| b.boundary))?;
+        let estimate = self.min.map_or(estimate, |min| estimate.max(min));
+        Some(self.max.map_or(estimate, |max| estimate.min(max)))
+    }
+}
+
+/// Combine a min or max: unknown on either side makes the result unknown,
+/// except when nothing was merged before.
+fn merge_bound(
+    first: bool,
+    current: Option<f64>,
+    other: Option<f64>,
+    pick: fn(f64, f64) -> f64,
+) -> Option<f64> {
+    if first {
+        return other;
+    }
+    Some(pick(current?, other?))
 }
 
 impl std::fmt::Display for MetricType {
@@ -327,6 +477,19 @@ impl Validate for MetricDataPoint {
             }
         }
 
+        // Validate exemplars if present
+        if self.exemplars.is_some() {
+            let exemplars = self.parse_exemplars().map_err(|e| {
+                StorageError::validation(format!("exemplars must be a list of exemplars: {}", e))
+            })?;
+            for (i, exemplar) in exemplars.iter().enumerate() {
+                validate_not_empty(&exemplar.trace_id, &format!("exemplars[{}].trace_id", i))
+                    .map_err(|e| StorageError::validation(e))?;
+                validate_finite_f64(exemplar.value, &format!("exemplars[{}].value", i))
+                    .map_err(|e| StorageError::validation(e))?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -351,5 +514,146 @@ mod tests {
         assert!(Metric::parse_type("unknown").is_err());
     }
 
+    #[test]
+    fn test_exemplars() {
+        let mut data_point = MetricDataPoint {
+            id: Uuid::new_v4(),
+            metric_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            value: None,
+            count: Some(1),
+            sum: Some(420.0),
+            min: Some(420.0),
+            max: Some(420.0),
+            buckets: Some(serde_json::json!([{"boundary": 500.0, "count": 1}])),
+            quantiles: None,
+            exemplars: Some(serde_json::json!([{
+                "trace_id": "4bf92f3577b34da6a3ce929d0e0e4736",
+                "span_id": "00f067aa0ba902b7",
+                "value": 420.0,
+                "timestamp": "2025-01-01T00:00:00Z",
+                "attributes": {}
+            }])),
+            attributes: serde_json::json!({}),
+            created_at: Utc::now(),
+        };
+
+        let exemplars = data_point.parse_exemplars().unwrap();
+        assert_eq!(exemplars.len(), 1);
+        assert_eq!(exemplars[0].trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert!(data_point.validate().is_ok());
+
+        data_point.exemplars = Some(serde_json::json!([{"trace_id": "abc"}]));
+        assert!(data_point.validate().is_err());
+
+        data_point.exemplars = None;
+        assert!(data_point.parse_exemplars().unwrap().is_empty());
+    }
+
+    fn histogram_point(count: i64, sum: f64, buckets: serde_json::Value) -> MetricDataPoint {
+        MetricDataPoint {
+            id: Uuid::new_v4(),
+            metric_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            value: None,
+            count: Some(count),
+            sum: Some(sum),
+            min: None,
+            max: None,
+            buckets: Some(buckets),
+            quantiles: None,
+            exemplars: None,
+            attributes: serde_json::json!({}),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_merged_histogram() {
+        let mut first = histogram_point(
+            10,
+            1500.0,
+            serde_json::json!([
+                {"boundary": 100.0, "count": 4},
+                {"boundary": 200.0, "count": 4},
+            ]),
+        );
+        first.min = Some(20.0);
+        first.max = Some(900.0);
+        let second = histogram_point(
+            10,
+            1000.0,
+            serde_json::json!([
+                {"boundary": 50.0, "count": 5},
+                {"boundary": 100.0, "count": 5},
+            ]),
+        );
+
+        let mut merged = MergedHistogram::new();
+        merged.add(&first).unwrap();
+        assert_eq!(merged.quantile(0.4), Some(100.0));
+        assert_eq!(merged.min, Some(20.0));
+
+        merged.add(&second).unwrap();
+        assert_eq!(merged.count, 20);
+        assert_eq!(merged.mean(), Some(125.0));
+        assert_eq!(
+            merged
+                .buckets
+                .iter()
+                .map(|b| (b.boundary, b.count))
+                .collect::<Vec<_>>(),
+            vec![(50.0, 5), (100.0, 9), (200.0, 4)]
+        );
+        // The second point reported no min or max
+        assert_eq!(merged.min, None);
+        assert_eq!(merged.max, None);
+
+        // Rank 10 is the 5th of 9 values in (50, 100]
+        let p50 = merged.quantile(0.5).unwrap();
+        assert!((p50 - (50.0 + 50.0 * 5.0 / 9.0)).abs() < 1e-9);
+        // Ranks beyond the buckets fall back to the last boundary
+        assert_eq!(merged.quantile(0.99), Some(200.0));
+        assert_eq!(merged.quantile(0.0), Some(0.0));
+        assert_eq!(MergedHistogram::new().quantile(0.5), None);
+
+        let mut other = MergedHistogram::new();
+        other.merge(&merged);
+        other.merge(&merged);
+        assert_eq!(other.count, 40);
+        assert_eq!(other.buckets[1].count, 18);
+    }
+
+    #[test]
+    fn test_quantile_uses_min_and_max() {
+        let mut point = histogram_point(
+            4,
+            10.0,
+            serde_json::json!([
+                {"boundary": 1.0, "count": 2},
+                {"boundary": 5.0, "count": 1},
+            ]),
+        );
+        point.min = Some(0.5);
+        point.max = Some(7.0);
+
+        let mut merged = MergedHistogram::new();
+        merged.add(&point).unwrap();
+
+        // First bucket starts at min rather than zero
+        assert_eq!(merged.quantile(0.25), Some(0.75));
+        assert_eq!(merged.quantile(0.75), Some(5.0));
+        // The overflow bucket resolves to max
+        assert_eq!(merged.quantile(1.0), Some(7.0));
+        assert_eq!(
+            point.parse_buckets().unwrap()[0],
+            HistogramBucket {
+                boundary: 1.0,
+                count: 2
+            }
+        );
+        assert!(point.parse_quantiles().unwrap().is_empty());
+    }
+
     // TODO: Add more comprehensive tests
 }
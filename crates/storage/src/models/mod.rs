@@ -6,8 +6,20 @@
 pub mod trace;
 pub mod metric;
 pub mod log;
+pub mod log_pattern;
+pub mod model_registry;
+pub mod organization;
+pub mod quarantine;
+pub mod quota;
+pub mod streaming;
 
 // Re-exports
 pub use trace::{Trace, TraceSpan, TraceEvent};
-pub use metric::{Metric, MetricDataPoint, MetricType};
+pub use metric::{Exemplar, MergedHistogram, Metric, MetricDataPoint, MetricType};
 pub use log::{LogRecord, LogLevel};
+pub use log_pattern::{LogPattern, LogPatternCount};
+pub use model_registry::RegisteredModel;
+pub use organization::OrganizationSettings;
+pub use quarantine::{QuarantinedSpan, ReplayStatus};
+pub use quota::IngestionUsage;
+pub use streaming::StreamingMetric;
@@ -0,0 +1,47 @@
+//! Model registry data models.
+//!
+//! This module defines the custom and fine-tuned models registered through
+//! the analytics API (`model_registry`), which the ingest host loads into the
+//! pricing engine's model registry.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A custom or fine-tuned model registered by an organization.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RegisteredModel {
+    /// Registration ID
+    pub id: Uuid,
+
+    /// Organization ID
+    pub org_id: String,
+
+    /// Model ID as reported on spans
+    pub model_id: String,
+
+    /// Provider serving the model
+    pub provider: Option<String>,
+
+    /// Name shown in reports
+    pub display_name: Option<String>,
+
+    /// Other names the model is reported under
+    pub aliases: Vec<String>,
+
+    /// Model this one was fine-tuned from or deploys
+    pub base_model: Option<String>,
+
+    /// USD per 1000 prompt tokens (unset: the base model's pricing)
+    pub prompt_cost_per_1k: Option<f64>,
+
+    /// USD per 1000 completion tokens
+    pub completion_cost_per_1k: Option<f64>,
+
+    /// USD per 1000 cached prompt tokens
+    pub cached_prompt_cost_per_1k: Option<f64>,
+
+    /// Last change
+    pub updated_at: DateTime<Utc>,
+}
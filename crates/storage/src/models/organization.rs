@@ -0,0 +1,43 @@
+//! Organization settings data models.
+//!
+//! This module defines the per-organization settings managed through the
+//! admin API (`organization_settings`), which the ingest host loads into
+//! the collector's quota and capture policy processors.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Settings of one organization. Unset settings use the system defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OrganizationSettings {
+    /// Organization ID
+    pub org_id: String,
+
+    /// Days traces and logs are kept
+    pub retention_days: Option<i32>,
+
+    /// Content capture: full, metadata_only or none
+    pub capture_policy: Option<String>,
+
+    /// Daily span limit
+    pub spans_per_day_limit: Option<i64>,
+
+    /// Daily byte limit
+    pub bytes_per_day_limit: Option<i64>,
+
+    /// Handling of spans over quota: drop, sample or queue
+    pub over_quota_action: Option<String>,
+
+    /// Last change
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OrganizationSettings {
+    /// Whether any ingestion quota setting is set.
+    pub fn has_quota(&self) -> bool {
+        self.spans_per_day_limit.is_some()
+            || self.bytes_per_day_limit.is_some()
+            || self.over_quota_action.is_some()
+    }
+}
@@ -0,0 +1,134 @@
+//! Quarantine data models.
+//!
+//! This module defines the data structures for spans the collector
+//! quarantined (`rejected_spans`) and their replay state.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Replay state of a quarantined span
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplayStatus {
+    /// Not requested for replay
+    Quarantined,
+    /// Replay requested, waiting for the ingest pipeline
+    Pending,
+    /// Claimed by the ingest pipeline
+    Replaying,
+    /// Replayed and exported
+    Replayed,
+    /// Replay failed; see `replay_error`
+    Failed,
+}
+
+impl ReplayStatus {
+    /// Status as stored in `rejected_spans.replay_status`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReplayStatus::Quarantined => "quarantined",
+            ReplayStatus::Pending => "pending",
+            ReplayStatus::Replaying => "replaying",
+            ReplayStatus::Replayed => "replayed",
+            ReplayStatus::Failed => "failed",
+        }
+    }
+}
+
+/// A quarantined span.
+///
+/// Deserializes from the collector's quarantine rows, which carry no ID or
+/// replay state.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct QuarantinedSpan {
+    /// Quarantine entry identifier
+    #[serde(default = "Uuid::new_v4")]
+    pub id: Uuid,
+
+    /// When the span was quarantined
+    pub ts: DateTime<Utc>,
+
+    /// Organization ID
+    pub org_id: Option<String>,
+
+    /// Trace ID of the span
+    pub trace_id: String,
+
+    /// Span ID
+    pub span_id: String,
+
+    /// Service name
+    pub service_name: Option<String>,
+
+    /// Service version
+    pub service_version: Option<String>,
+
+    /// Failing stage: schema, pii or processing
+    pub stage: String,
+
+    /// Why the span was quarantined
+    pub failure_reason: String,
+
+    /// Schema the span failed, for schema validation failures
+    pub schema_name: Option<String>,
+
+    /// Schema violations
+    #[serde(default)]
+    pub violations: Vec<String>,
+
+    /// The span as received
+    pub span: serde_json::Value,
+
+    /// Replay state (stored as text in DB)
+    #[serde(default = "default_replay_status")]
+    pub replay_status: String,
+
+    /// When a replay was last requested
+    #[serde(default)]
+    pub replay_requested_at: Option<DateTime<Utc>>,
+
+    /// When the span was last replayed
+    #[serde(default)]
+    pub replayed_at: Option<DateTime<Utc>>,
+
+    /// Number of replays claimed
+    #[serde(default)]
+    pub replay_attempts: i32,
+
+    /// Reason of the last failed replay
+    #[serde(default)]
+    pub replay_error: Option<String>,
+}
+
+fn default_replay_status() -> String {
+    ReplayStatus::Quarantined.as_str().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_collector_row() {
+        let span: QuarantinedSpan = serde_json::from_value(serde_json::json!({
+            "ts": "2025-01-01T00:00:00Z",
+            "org_id": "org-1",
+            "trace_id": "trace-1",
+            "span_id": "span-1",
+            "service_name": "checkout-api",
+            "service_version": null,
+            "stage": "pii",
+            "failure_reason": "pii_redaction: invalid pattern",
+            "schema_name": null,
+            "violations": [],
+            "span": {"span_id": "span-1"}
+        }))
+        .unwrap();
+
+        assert_eq!(span.replay_status, "quarantined");
+        assert_eq!(span.replay_attempts, 0);
+        assert!(!span.id.is_nil());
+    }
+}
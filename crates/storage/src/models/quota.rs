@@ -0,0 +1,76 @@
+//! Ingestion quota data models.
+//!
+//! This module defines the per-organization daily ingestion usage counted by
+//! the collector's quota processor (`ingestion_usage`).
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Ingestion of one organization on one UTC day, with the quota in force.
+///
+/// Deserializes from the collector's usage rows, which carry no update time.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IngestionUsage {
+    /// UTC day
+    pub day: NaiveDate,
+
+    /// Organization ID
+    pub org_id: String,
+
+    /// Spans admitted
+    pub span_count: i64,
+
+    /// JSON-encoded size of the spans admitted
+    pub byte_count: i64,
+
+    /// Over-quota spans dropped, sampled out or queued
+    pub throttled_spans: i64,
+
+    /// Daily span limit; unlimited when unset
+    pub spans_per_day_limit: Option<i64>,
+
+    /// Daily byte limit; unlimited when unset
+    pub bytes_per_day_limit: Option<i64>,
+
+    /// Handling of spans over quota: drop, sample or queue
+    pub over_quota_action: String,
+
+    /// Last flush
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl IngestionUsage {
+    /// Whether the span or byte limit is reached.
+    pub fn over_quota(&self) -> bool {
+        self.spans_per_day_limit
+            .is_some_and(|limit| self.span_count >= limit)
+            || self
+                .bytes_per_day_limit
+                .is_some_and(|limit| self.byte_count >= limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_collector_row() {
+        let usage: IngestionUsage = serde_json::from_value(serde_json::json!({
+            "day": "2025-01-01",
+            "org_id": "org-free",
+            "span_count": 100,
+            "byte_count": 25000,
+            "throttled_spans": 3,
+            "spans_per_day_limit": 100,
+            "bytes_per_day_limit": null,
+            "over_quota_action": "drop"
+        }))
+        .unwrap();
+
+        assert_eq!(usage.day, NaiveDate::from_ymd_opt(2025, 1, 1).unwrap());
+        assert!(usage.over_quota());
+    }
+}
@@ -0,0 +1,108 @@
+//! Streaming metric data models.
+//!
+//! This module defines the per-minute request metrics pre-aggregated by the
+//! collector's streaming aggregation processor (`llm_metrics_streaming_1min`).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::FromRow;
+
+/// Requests of one minute, organization, provider and model.
+///
+/// Deserializes from the collector's aggregation rows, whose `org_id` is
+/// null for spans without an organization; it is stored as `''`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StreamingMetric {
+    /// Start of the minute
+    pub bucket: DateTime<Utc>,
+
+    /// Organization ID (`''` when the spans carry none)
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub org_id: String,
+
+    /// Provider name
+    pub provider: String,
+
+    /// Model name
+    pub model: String,
+
+    /// Requests
+    pub request_count: i64,
+
+    /// Requests whose span status was error
+    pub error_count: i64,
+
+    /// Prompt tokens
+    pub prompt_tokens: i64,
+
+    /// Completion tokens
+    pub completion_tokens: i64,
+
+    /// Total tokens
+    pub total_tokens: i64,
+
+    /// Total cost in USD
+    pub total_cost_usd: f64,
+
+    /// Sum of request durations
+    pub sum_duration_ms: f64,
+
+    /// Sum of squared request durations
+    pub sum_duration_ms_squared: f64,
+
+    /// Shortest request duration
+    pub min_duration_ms: f64,
+
+    /// Longest request duration
+    pub max_duration_ms: f64,
+
+    /// Upper bounds (inclusive) of the latency buckets
+    pub latency_bounds_ms: Vec<f64>,
+
+    /// Requests per latency bucket, plus the overflow bucket
+    pub latency_bucket_counts: Vec<i64>,
+}
+
+impl StreamingMetric {
+    /// Average request duration in milliseconds.
+    pub fn avg_duration_ms(&self) -> Option<f64> {
+        (self.request_count > 0).then(|| self.sum_duration_ms / self.request_count as f64)
+    }
+}
+
+pub(crate) fn null_as_empty<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_collector_row() {
+        let metric: StreamingMetric = serde_json::from_value(serde_json::json!({
+            "bucket": "2025-01-01T00:00:00Z",
+            "org_id": null,
+            "provider": "openai",
+            "model": "gpt-4o",
+            "request_count": 4,
+            "error_count": 1,
+            "prompt_tokens": 400,
+            "completion_tokens": 200,
+            "total_tokens": 600,
+            "total_cost_usd": 0.04,
+            "sum_duration_ms": 1000.0,
+            "sum_duration_ms_squared": 400000.0,
+            "min_duration_ms": 100.0,
+            "max_duration_ms": 500.0,
+            "latency_bounds_ms": [250.0],
+            "latency_bucket_counts": [3, 1]
+        }))
+        .unwrap();
+
+        assert_eq!(metric.org_id, "");
+        assert_eq!(metric.avg_duration_ms(), Some(250.0));
+    }
+}
@@ -103,7 +103,7 @@ pub struct TraceSpan {
     /// Events attached to this span
     pub events: Option<serde_json::Value>,
 
-    /// Links to other spans
+    /// Links to other spans: an array of `{trace_id, span_id, attributes}`
     pub links: Option<serde_json::Value>,
 
     /// Created timestamp
@@ -318,6 +318,38 @@ impl TraceEvent {
             created_at: Utc::now(),
         }
     }
+
+    /// Parse the events stored on a span into event rows.
+    ///
+    /// `events` must be a JSON array of `{name, timestamp, attributes}`
+    /// objects, as written for [`LlmSpan`](llm_observatory_core::span::LlmSpan)
+    /// events. Events without a name are skipped and events without a valid
+    /// RFC 3339 timestamp are placed at the span start. Compressed or
+    /// encrypted events yield no rows.
+    pub fn from_span_events(span: &TraceSpan) -> Vec<TraceEvent> {
+        let Some(serde_json::Value::Array(events)) = &span.events else {
+            return Vec::new();
+        };
+
+        events
+            .iter()
+            .filter_map(|event| {
+                let name = event.get("name")?.as_str().filter(|n| !n.is_empty())?;
+                let timestamp = event
+                    .get("timestamp")
+                    .and_then(|ts| ts.as_str())
+                    .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+                    .map(|ts| ts.with_timezone(&Utc))
+                    .unwrap_or(span.start_time);
+
+                let mut row = TraceEvent::new(span.id, name.to_string(), timestamp);
+                if let Some(attributes @ serde_json::Value::Object(_)) = event.get("attributes") {
+                    row.attributes = attributes.clone();
+                }
+                Some(row)
+            })
+            .collect()
+    }
 }
 
 impl Validate for TraceEvent {
@@ -408,6 +440,10 @@ impl From<llm_observatory_core::span::LlmSpan> for TraceSpan {
         if let Some(ref environment) = span.metadata.environment {
             attributes.insert("deployment.environment".to_string(), serde_json::json!(environment));
         }
+        // Request-scoped attributes (team, feature flags, experiment variant)
+        for (key, value) in &span.metadata.attributes {
+            attributes.insert(key.clone(), serde_json::json!(value));
+        }
 
         // Merge custom attributes
         for (key, value) in span.attributes {
@@ -421,6 +457,13 @@ impl From<llm_observatory_core::span::LlmSpan> for TraceSpan {
             None
         };
 
+        // Convert links; kept uncompressed so they can be queried
+        let links = if !span.links.is_empty() {
+            Some(serde_json::to_value(&span.links).unwrap_or(serde_json::json!([])))
+        } else {
+            None
+        };
+
         // Placeholder trace_id - use TraceWriter::write_span_from_llm() for proper UUID resolution
         // This will be replaced by the actual trace UUID when using write_span_from_llm()
         let trace_uuid = Uuid::new_v4();
@@ -440,7 +483,7 @@ impl From<llm_observatory_core::span::LlmSpan> for TraceSpan {
             status_message: None,
             attributes: serde_json::Value::Object(attributes),
             events,
-            links: None,
+            links,
             created_at: Utc::now(),
         };
 
@@ -497,4 +540,67 @@ mod tests {
         span.status = "error".to_string();
         assert!(span.is_error());
     }
+
+    #[test]
+    fn test_event_from_span_events() {
+        let mut span = TraceSpan::new(
+            Uuid::new_v4(),
+            "span123".to_string(),
+            "test-span".to_string(),
+            "test-service".to_string(),
+            Utc::now(),
+        );
+        assert!(TraceEvent::from_span_events(&span).is_empty());
+
+        span.events = Some(serde_json::json!([
+            {
+                "name": "guardrail.evaluation",
+                "timestamp": "2025-01-01T00:00:01.500Z",
+                "attributes": {"guardrail.blocked": true}
+            },
+            {"name": "llm.first_token"},
+            {"timestamp": "2025-01-01T00:00:02Z"}
+        ]));
+        let events = TraceEvent::from_span_events(&span);
+
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.span_id == span.id));
+        assert_eq!(events[0].name, "guardrail.evaluation");
+        assert_eq!(events[0].timestamp.timestamp_millis(), 1_735_689_601_500);
+        assert_eq!(events[0].attributes["guardrail.blocked"], true);
+        assert_eq!(events[1].timestamp, span.start_time);
+        assert_eq!(events[1].attributes, serde_json::json!({}));
+    }
+
+    #[test]
+    #[cfg(feature = "llm-span-conversion")]
+    fn test_span_from_llm_span_keeps_links() {
+        use llm_observatory_core::span::{LlmInput, LlmSpan, SpanLink};
+        use llm_observatory_core::types::{Latency, Provider};
+
+        let now = Utc::now();
+        let llm_span = LlmSpan::builder()
+            .span_id("00f067aa0ba902b7")
+            .trace_id("4bf92f3577b34da6a3ce929d0e0e4736")
+            .name("llm.chat")
+            .provider(Provider::OpenAI)
+            .model("gpt-4o")
+            .input(LlmInput::Text {
+                prompt: "Hello".to_string(),
+            })
+            .latency(Latency::new(now, now))
+            .link(SpanLink::new("0af7651916cd43dd8448eb211c80319c", "b7ad6b7169203331"))
+            .build()
+            .unwrap();
+
+        let span = TraceSpan::from(llm_span);
+        assert_eq!(
+            span.links,
+            Some(serde_json::json!([{
+                "trace_id": "0af7651916cd43dd8448eb211c80319c",
+                "span_id": "b7ad6b7169203331",
+                "attributes": {}
+            }]))
+        );
+    }
 }
@@ -0,0 +1,678 @@
+//! Online schema migrations for large tables.
+//!
+//! A plain `ALTER TABLE` that rewrites a table, or an `UPDATE` of every row,
+//! holds locks on our largest tables long enough to stall ingestion. An
+//! [`OnlineMigration`] makes the same change in steps that each hold locks
+//! only briefly:
+//!
+//! 1. **Expand**: add the new columns as nullable columns (a catalog-only
+//!    change) and install a trigger that fills them on every insert and
+//!    update. From here on, writers that only know the old columns still
+//!    produce complete rows: this is the dual-write window. The DDL runs with
+//!    a short `lock_timeout` and is retried, so it never queues behind a long
+//!    query while blocking the writes queued behind it.
+//! 2. **Backfill**: update existing rows in keyset batches of `batch_size`,
+//!    each batch in its own short transaction together with its checkpoint,
+//!    pausing between batches. An interrupted backfill resumes after the
+//!    last checkpointed key.
+//! 3. **Verify**: count the rows matching the migration's mismatch
+//!    predicate. The migration only becomes `verified` when there are none.
+//! 4. **Contract**: once every writer uses the new columns,
+//!    [`OnlineMigrator::finish`] verifies again, runs the contract statements
+//!    (e.g. `SET NOT NULL`, dropping the old column) and drops the trigger.
+//!
+//! Progress is stored in `online_migrations` (see migration
+//! `035_online_migrations.sql`) and served by the health server at
+//! `/migrations/online`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm_observatory_storage::online_migration::{OnlineMigration, OnlineMigrator};
+//! use llm_observatory_storage::{StorageConfig, StoragePool};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let pool = StoragePool::new(StorageConfig::from_env()?).await?;
+//! let migration = OnlineMigration::new("spans_duration_ms", "trace_spans")
+//!     .add_column("duration_ms", "DOUBLE PRECISION")
+//!     .dual_write("NEW.duration_ms := NEW.duration_us / 1000.0")
+//!     .backfill("duration_ms = duration_us / 1000.0")
+//!     .verify("duration_ms IS DISTINCT FROM duration_us / 1000.0")
+//!     .batch_size(5_000);
+//!
+//! let migrator = OnlineMigrator::new(pool);
+//! migrator.run(&migration).await?;
+//!
+//! // Later, after all writers set duration_ms themselves
+//! migrator.finish(&migration).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{StorageError, StorageResult};
+use crate::pool::StoragePool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::time::Duration;
+
+/// Default rows updated per backfill batch.
+pub const DEFAULT_BATCH_SIZE: i64 = 10_000;
+
+/// Default pause between backfill batches.
+pub const DEFAULT_BATCH_PAUSE: Duration = Duration::from_millis(100);
+
+/// Default time DDL may wait for a table lock before it is retried.
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Attempts at DDL that keeps timing out on its lock.
+const DDL_ATTEMPTS: u32 = 10;
+
+/// PostgreSQL error code for `lock_timeout` expiring.
+const LOCK_NOT_AVAILABLE: &str = "55P03";
+
+/// Progress of an online migration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MigrationPhase {
+    /// Registered, nothing changed yet
+    Pending,
+    /// Columns added and dual-write trigger installed
+    Expanded,
+    /// Existing rows are being updated
+    Backfilling,
+    /// Every row verified; the dual-write window is still open
+    Verified,
+    /// Contracted; the trigger is gone
+    Completed,
+    /// Stopped on an error; running it again resumes it
+    Failed,
+}
+
+impl MigrationPhase {
+    /// Phase name as stored.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MigrationPhase::Pending => "pending",
+            MigrationPhase::Expanded => "expanded",
+            MigrationPhase::Backfilling => "backfilling",
+            MigrationPhase::Verified => "verified",
+            MigrationPhase::Completed => "completed",
+            MigrationPhase::Failed => "failed",
+        }
+    }
+}
+
+impl TryFrom<String> for MigrationPhase {
+    type Error = StorageError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "pending" => Ok(MigrationPhase::Pending),
+            "expanded" => Ok(MigrationPhase::Expanded),
+            "backfilling" => Ok(MigrationPhase::Backfilling),
+            "verified" => Ok(MigrationPhase::Verified),
+            "completed" => Ok(MigrationPhase::Completed),
+            "failed" => Ok(MigrationPhase::Failed),
+            other => Err(StorageError::internal(format!("Unknown migration phase '{}'", other))),
+        }
+    }
+}
+
+/// Stored progress of an online migration.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct MigrationStatus {
+    /// Migration name
+    pub name: String,
+
+    /// Table being migrated
+    pub table_name: String,
+
+    /// Current phase
+    #[sqlx(try_from = "String")]
+    pub phase: MigrationPhase,
+
+    /// Key of the last backfilled row
+    pub last_key: Option<String>,
+
+    /// Rows updated by the backfill so far
+    pub rows_backfilled: i64,
+
+    /// Backfill batches committed so far
+    pub batches: i64,
+
+    /// Rows failing verification at the last verification
+    pub mismatched_rows: Option<i64>,
+
+    /// Error that stopped the migration
+    pub error: Option<String>,
+
+    /// When the migration was first run
+    pub started_at: DateTime<Utc>,
+
+    /// Last progress
+    pub updated_at: DateTime<Utc>,
+
+    /// When the migration was contracted
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A schema change applied without blocking writes, defined in code.
+///
+/// SQL fragments are trusted code, not user input; names are checked to be
+/// plain lowercase identifiers.
+#[derive(Debug, Clone)]
+pub struct OnlineMigration {
+    name: String,
+    table: String,
+    key_column: String,
+    key_type: String,
+    add_columns: Vec<(String, String)>,
+    dual_write: Vec<String>,
+    backfill: Option<String>,
+    verify: Option<String>,
+    contract: Vec<String>,
+    batch_size: i64,
+    batch_pause: Duration,
+    lock_timeout: Duration,
+}
+
+impl OnlineMigration {
+    /// A migration of `table`, batched over its `id UUID` key.
+    pub fn new(name: impl Into<String>, table: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            table: table.into(),
+            key_column: "id".to_string(),
+            key_type: "uuid".to_string(),
+            add_columns: Vec::new(),
+            dual_write: Vec::new(),
+            backfill: None,
+            verify: None,
+            contract: Vec::new(),
+            batch_size: DEFAULT_BATCH_SIZE,
+            batch_pause: DEFAULT_BATCH_PAUSE,
+            lock_timeout: DEFAULT_LOCK_TIMEOUT,
+        }
+    }
+
+    /// Batch over another unique, indexed column of SQL type `sql_type`.
+    pub fn key(mut self, column: impl Into<String>, sql_type: impl Into<String>) -> Self {
+        self.key_column = column.into();
+        self.key_type = sql_type.into();
+        self
+    }
+
+    /// Add a nullable column in the expand step.
+    pub fn add_column(mut self, column: impl Into<String>, sql_type: impl Into<String>) -> Self {
+        self.add_columns.push((column.into(), sql_type.into()));
+        self
+    }
+
+    /// PL/pgSQL statement run for every inserted or updated row during the
+    /// dual-write window, e.g. `NEW.duration_ms := NEW.duration_us / 1000.0`.
+    pub fn dual_write(mut self, statement: impl Into<String>) -> Self {
+        self.dual_write.push(statement.into());
+        self
+    }
+
+    /// `SET` clause applied to existing rows, e.g. `duration_ms = duration_us / 1000.0`.
+    pub fn backfill(mut self, set_clause: impl Into<String>) -> Self {
+        self.backfill = Some(set_clause.into());
+        self
+    }
+
+    /// Predicate matching rows the migration got wrong; verification
+    /// requires that no row matches.
+    pub fn verify(mut self, mismatch_predicate: impl Into<String>) -> Self {
+        self.verify = Some(mismatch_predicate.into());
+        self
+    }
+
+    /// Statement run in the contract step, after the dual-write window.
+    pub fn contract(mut self, statement: impl Into<String>) -> Self {
+        self.contract.push(statement.into());
+        self
+    }
+
+    /// Rows updated per backfill batch.
+    pub fn batch_size(mut self, rows: i64) -> Self {
+        self.batch_size = rows;
+        self
+    }
+
+    /// Pause between backfill batches, leaving room for ingestion.
+    pub fn batch_pause(mut self, pause: Duration) -> Self {
+        self.batch_pause = pause;
+        self
+    }
+
+    /// Time DDL may wait for its table lock before it is retried.
+    pub fn lock_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_timeout = timeout;
+        self
+    }
+
+    /// Migration name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Check the definition.
+    pub fn validate(&self) -> StorageResult<()> {
+        let identifiers = [&self.name, &self.table, &self.key_column]
+            .into_iter()
+            .chain(self.add_columns.iter().map(|(column, _)| column));
+        for identifier in identifiers {
+            if !is_identifier(identifier) {
+                return Err(StorageError::validation(format!(
+                    "'{}' is not a lowercase SQL identifier",
+                    identifier
+                )));
+            }
+        }
+        if self.batch_size <= 0 {
+            return Err(StorageError::validation("Batch size must be positive"));
+        }
+        if self.add_columns.is_empty() && self.backfill.is_none() && self.contract.is_empty() {
+            return Err(StorageError::validation(format!(
+                "Migration '{}' does nothing",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Name of the dual-write trigger and its function.
+    fn trigger_name(&self) -> String {
+        format!("online_migration_{}", self.name)
+    }
+
+    /// DDL of the expand step.
+    fn expand_statements(&self) -> Vec<String> {
+        let mut statements: Vec<String> = self
+            .add_columns
+            .iter()
+            .map(|(column, sql_type)| {
+                format!(
+                    "ALTER TABLE {} ADD COLUMN IF NOT EXISTS {} {}",
+                    self.table, column, sql_type
+                )
+            })
+            .collect();
+
+        if !self.dual_write.is_empty() {
+            let trigger = self.trigger_name();
+            statements.push(format!(
+                "CREATE OR REPLACE FUNCTION {trigger}() RETURNS trigger AS $$\n\
+                 BEGIN\n    {};\n    RETURN NEW;\nEND\n$$ LANGUAGE plpgsql",
+                self.dual_write.join(";\n    ")
+            ));
+            statements.push(format!("DROP TRIGGER IF EXISTS {trigger} ON {}", self.table));
+            statements.push(format!(
+                "CREATE TRIGGER {trigger} BEFORE INSERT OR UPDATE ON {} \
+                 FOR EACH ROW EXECUTE FUNCTION {trigger}()",
+                self.table
+            ));
+        }
+        statements
+    }
+
+    /// DDL of the contract step.
+    fn contract_statements(&self) -> Vec<String> {
+        let mut statements = self.contract.clone();
+        if !self.dual_write.is_empty() {
+            let trigger = self.trigger_name();
+            statements.push(format!("DROP TRIGGER IF EXISTS {trigger} ON {}", self.table));
+            statements.push(format!("DROP FUNCTION IF EXISTS {trigger}()"));
+        }
+        statements
+    }
+
+    /// One backfill batch: updates the next `$2` rows (after key `$1` when
+    /// resuming) and returns the last key and the rows updated.
+    fn backfill_sql(&self, set_clause: &str, resume: bool) -> String {
+        let (key, key_type, table) = (&self.key_column, &self.key_type, &self.table);
+        let after = if resume {
+            format!("WHERE {key} > $1::text::{key_type}")
+        } else {
+            // Keep the parameter numbering of the resuming query
+            "WHERE $1::text IS NULL".to_string()
+        };
+
+        format!(
+            "WITH batch AS (\
+                 SELECT {key} FROM {table} {after} ORDER BY {key} LIMIT $2\
+             ), updated AS (\
+                 UPDATE {table} SET {set_clause} WHERE {key} IN (SELECT {key} FROM batch) RETURNING 1\
+             ) \
+             SELECT (SELECT {key}::text FROM batch ORDER BY {key} DESC LIMIT 1), \
+                    (SELECT count(*) FROM updated)"
+        )
+    }
+}
+
+/// Runs [`OnlineMigration`]s and reports their progress.
+pub struct OnlineMigrator {
+    pool: StoragePool,
+}
+
+impl OnlineMigrator {
+    /// Create a migrator for the database of `pool`.
+    pub fn new(pool: StoragePool) -> Self {
+        Self { pool }
+    }
+
+    /// Expand, backfill and verify a migration, resuming from its last
+    /// checkpoint. Leaves it `verified` with the dual-write window open;
+    /// a completed migration is left alone.
+    pub async fn run(&self, migration: &OnlineMigration) -> StorageResult<MigrationStatus> {
+        migration.validate()?;
+        let status = self.register(migration).await?;
+        if status.phase == MigrationPhase::Completed {
+            return Ok(status);
+        }
+
+        let result = async {
+            self.run_ddl(migration, &migration.expand_statements()).await?;
+            if matches!(status.phase, MigrationPhase::Pending | MigrationPhase::Failed) {
+                self.set_phase(migration, MigrationPhase::Expanded, None).await?;
+            }
+            tracing::info!(migration = %migration.name, "Online migration expanded, dual-write window open");
+
+            if let Some(set_clause) = &migration.backfill {
+                self.backfill(migration, set_clause, status.last_key.clone()).await?;
+            }
+            self.verify(migration).await
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                self.set_phase(migration, MigrationPhase::Verified, None).await?;
+                tracing::info!(migration = %migration.name, "Online migration verified");
+            }
+            Err(e) => {
+                tracing::error!(migration = %migration.name, error = %e, "Online migration failed");
+                self.set_phase(migration, MigrationPhase::Failed, Some(e.to_string())).await?;
+                return Err(e);
+            }
+        }
+
+        self.require_status(&migration.name).await
+    }
+
+    /// Close the dual-write window of a verified migration: verify again,
+    /// run its contract statements and drop its trigger.
+    pub async fn finish(&self, migration: &OnlineMigration) -> StorageResult<MigrationStatus> {
+        migration.validate()?;
+        let status = self.require_status(&migration.name).await?;
+        match status.phase {
+            MigrationPhase::Completed => return Ok(status),
+            MigrationPhase::Verified => {}
+            phase => {
+                return Err(StorageError::MigrationError(format!(
+                    "Migration '{}' is {}; it must be verified before it is finished",
+                    migration.name,
+                    phase.as_str()
+                )))
+            }
+        }
+
+        self.verify(migration).await?;
+        self.run_ddl(migration, &migration.contract_statements()).await?;
+
+        sqlx::query(
+            "UPDATE online_migrations SET phase = 'completed', completed_at = NOW(), updated_at = NOW() \
+             WHERE name = $1",
+        )
+        .bind(&migration.name)
+        .execute(self.pool.postgres())
+        .await?;
+        tracing::info!(migration = %migration.name, "Online migration completed");
+
+        self.require_status(&migration.name).await
+    }
+
+    /// Progress of a migration, if it was ever run.
+    pub async fn status(&self, name: &str) -> StorageResult<Option<MigrationStatus>> {
+        let status = sqlx::query_as::<_, MigrationStatus>("SELECT * FROM online_migrations WHERE name = $1")
+            .bind(name)
+            .fetch_optional(self.pool.postgres())
+            .await?;
+        Ok(status)
+    }
+
+    /// Progress of every migration, most recent first.
+    pub async fn statuses(&self) -> StorageResult<Vec<MigrationStatus>> {
+        let statuses = sqlx::query_as::<_, MigrationStatus>(
+            "SELECT * FROM online_migrations ORDER BY started_at DESC",
+        )
+        .fetch_all(self.pool.postgres())
+        .await?;
+        Ok(statuses)
+    }
+
+    async fn require_status(&self, name: &str) -> StorageResult<MigrationStatus> {
+        self.status(name)
+            .await?
+            .ok_or_else(|| StorageError::not_found(format!("Online migration '{}'", name)))
+    }
+
+    /// Record the migration if it is new and return its progress.
+    async fn register(&self, migration: &OnlineMigration) -> StorageResult<MigrationStatus> {
+        sqlx::query(
+            "INSERT INTO online_migrations (name, table_name) VALUES ($1, $2) \
+             ON CONFLICT (name) DO NOTHING",
+        )
+        .bind(&migration.name)
+        .bind(&migration.table)
+        .execute(self.pool.postgres())
+        .await?;
+
+        self.require_status(&migration.name).await
+    }
+
+    async fn set_phase(
+        &self,
+        migration: &OnlineMigration,
+        phase: MigrationPhase,
+        error: Option<String>,
+    ) -> StorageResult<()> {
+        sqlx::query("UPDATE online_migrations SET phase = $2, error = $3, updated_at = NOW() WHERE name = $1")
+            .bind(&migration.name)
+            .bind(phase.as_str())
+            .bind(error)
+            .execute(self.pool.postgres())
+            .await?;
+        Ok(())
+    }
+
+    /// Run DDL in one transaction under the migration's `lock_timeout`,
+    /// retrying when the lock is not granted in time.
+    async fn run_ddl(&self, migration: &OnlineMigration, statements: &[String]) -> StorageResult<()> {
+        if statements.is_empty() {
+            return Ok(());
+        }
+
+        let mut attempt = 1;
+        loop {
+            match self.try_ddl(migration, statements).await {
+                Ok(()) => return Ok(()),
+                Err(e) if is_lock_timeout(&e) && attempt < DDL_ATTEMPTS => {
+                    tracing::warn!(
+                        migration = %migration.name,
+                        attempt,
+                        "Lock on {} not granted in time, retrying",
+                        migration.table
+                    );
+                    tokio::time::sleep(migration.lock_timeout * attempt).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    async fn try_ddl(&self, migration: &OnlineMigration, statements: &[String]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.postgres().begin().await?;
+        sqlx::query(&format!(
+            "SET LOCAL lock_timeout = '{}ms'",
+            migration.lock_timeout.as_millis()
+        ))
+        .execute(&mut *tx)
+        .await?;
+        for statement in statements {
+            sqlx::query(statement).execute(&mut *tx).await?;
+        }
+        tx.commit().await
+    }
+
+    /// Update existing rows batch by batch, checkpointing each batch.
+    async fn backfill(
+        &self,
+        migration: &OnlineMigration,
+        set_clause: &str,
+        mut last_key: Option<String>,
+    ) -> StorageResult<()> {
+        self.set_phase(migration, MigrationPhase::Backfilling, None).await?;
+
+        loop {
+            let sql = migration.backfill_sql(set_clause, last_key.is_some());
+            let mut tx = self.pool.postgres().begin().await?;
+            let (batch_last_key, updated): (Option<String>, i64) = sqlx::query_as(&sql)
+                .bind(&last_key)
+                .bind(migration.batch_size)
+                .fetch_one(&mut *tx)
+                .await?;
+
+            let Some(batch_last_key) = batch_last_key else {
+                tx.commit().await?;
+                return Ok(());
+            };
+
+            sqlx::query(
+                "UPDATE online_migrations SET last_key = $2, rows_backfilled = rows_backfilled + $3, \
+                 batches = batches + 1, updated_at = NOW() WHERE name = $1",
+            )
+            .bind(&migration.name)
+            .bind(&batch_last_key)
+            .bind(updated)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+
+            tracing::debug!(migration = %migration.name, updated, last_key = %batch_last_key, "Backfilled batch");
+            last_key = Some(batch_last_key);
+            tokio::time::sleep(migration.batch_pause).await;
+        }
+    }
+
+    /// Count rows failing verification; fails unless there are none.
+    async fn verify(&self, migration: &OnlineMigration) -> StorageResult<()> {
+        let Some(predicate) = &migration.verify else {
+            return Ok(());
+        };
+
+        let mismatched: i64 = sqlx::query_scalar(&format!(
+            "SELECT count(*) FROM {} WHERE {}",
+            migration.table, predicate
+        ))
+        .fetch_one(self.pool.postgres())
+        .await?;
+
+        sqlx::query("UPDATE online_migrations SET mismatched_rows = $2, updated_at = NOW() WHERE name = $1")
+            .bind(&migration.name)
+            .bind(mismatched)
+            .execute(self.pool.postgres())
+            .await?;
+
+        if mismatched > 0 {
+            return Err(StorageError::MigrationError(format!(
+                "{} rows of {} fail verification",
+                mismatched, migration.table
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn is_lock_timeout(err: &sqlx::Error) -> bool {
+    matches!(err, sqlx::Error::Database(db) if db.code().as_deref() == Some(LOCK_NOT_AVAILABLE))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_lowercase() || c == '_')
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn duration_ms() -> OnlineMigration {
+        OnlineMigration::new("spans_duration_ms", "trace_spans")
+            .add_column("duration_ms", "DOUBLE PRECISION")
+            .dual_write("NEW.duration_ms := NEW.duration_us / 1000.0")
+            .backfill("duration_ms = duration_us / 1000.0")
+            .verify("duration_ms IS DISTINCT FROM duration_us / 1000.0")
+            .contract("ALTER TABLE trace_spans ALTER COLUMN duration_ms SET NOT NULL")
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(duration_ms().validate().is_ok());
+        assert!(duration_ms().batch_size(0).validate().is_err());
+        assert!(OnlineMigration::new("noop", "trace_spans").validate().is_err());
+        assert!(OnlineMigration::new("x", "trace_spans; DROP TABLE logs")
+            .backfill("a = b")
+            .validate()
+            .is_err());
+        assert!(duration_ms().add_column("Bad-Name", "TEXT").validate().is_err());
+    }
+
+    #[test]
+    fn test_expand_and_contract_statements() {
+        let expand = duration_ms().expand_statements();
+        assert_eq!(
+            expand[0],
+            "ALTER TABLE trace_spans ADD COLUMN IF NOT EXISTS duration_ms DOUBLE PRECISION"
+        );
+        assert!(expand[1].contains("NEW.duration_ms := NEW.duration_us / 1000.0;"));
+        assert!(expand[3].starts_with(
+            "CREATE TRIGGER online_migration_spans_duration_ms BEFORE INSERT OR UPDATE ON trace_spans"
+        ));
+
+        let contract = duration_ms().contract_statements();
+        assert_eq!(contract.len(), 3);
+        assert!(contract[0].contains("SET NOT NULL"));
+        assert_eq!(contract[2], "DROP FUNCTION IF EXISTS online_migration_spans_duration_ms()");
+    }
+
+    #[test]
+    fn test_backfill_sql() {
+        let migration = duration_ms().key("span_id", "text");
+        let first = migration.backfill_sql("duration_ms = duration_us / 1000.0", false);
+        assert!(first.contains("SELECT span_id FROM trace_spans WHERE $1::text IS NULL ORDER BY span_id LIMIT $2"));
+
+        let resumed = migration.backfill_sql("duration_ms = duration_us / 1000.0", true);
+        assert!(resumed.contains("WHERE span_id > $1::text::text ORDER BY span_id"));
+        assert!(resumed.contains("UPDATE trace_spans SET duration_ms = duration_us / 1000.0 WHERE span_id IN"));
+    }
+
+    #[test]
+    fn test_phase_round_trip() {
+        for phase in [
+            MigrationPhase::Pending,
+            MigrationPhase::Expanded,
+            MigrationPhase::Backfilling,
+            MigrationPhase::Verified,
+            MigrationPhase::Completed,
+            MigrationPhase::Failed,
+        ] {
+            assert_eq!(MigrationPhase::try_from(phase.as_str().to_string()).unwrap(), phase);
+        }
+        assert!(MigrationPhase::try_from("done".to_string()).is_err());
+    }
+}
@@ -0,0 +1,386 @@
+//! Declarative table partitioning and partition-aware retention.
+//!
+//! The `logs` and `trace_spans` tables are range-partitioned by timestamp
+//! (see migration `009_partitioned_tables.sql`). This module keeps enough
+//! future partitions around for incoming data and enforces retention by
+//! dropping whole partitions, falling back to a bounded `DELETE` only for the
+//! partition that straddles the retention cutoff.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm_observatory_storage::{partitioning::PartitionManager, StorageConfig, StoragePool};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let pool = StoragePool::new(StorageConfig::from_env()?).await?;
+//! let manager = PartitionManager::new(pool);
+//!
+//! // Run once now, then keep running in the background
+//! manager.run_maintenance().await?;
+//! let _handle = manager.start_maintenance();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::config::{PartitionConfig, PartitionInterval};
+use crate::error::StorageResult;
+use crate::pool::StoragePool;
+use chrono::{DateTime, Datelike, Duration, NaiveTime, TimeZone, Utc};
+
+/// A range-partitioned table managed by [`PartitionManager`].
+#[derive(Debug, Clone)]
+pub struct PartitionedTable {
+    /// Parent table name
+    pub table: &'static str,
+
+    /// Timestamp column the table is partitioned by
+    pub column: &'static str,
+
+    /// Retention in days (None keeps data forever)
+    pub retention_days: Option<u32>,
+}
+
+impl PartitionedTable {
+    /// The partitioned `logs` table.
+    pub fn logs(retention_days: Option<u32>) -> Self {
+        Self {
+            table: "logs",
+            column: "timestamp",
+            retention_days,
+        }
+    }
+
+    /// The partitioned `trace_spans` table.
+    pub fn trace_spans(retention_days: Option<u32>) -> Self {
+        Self {
+            table: "trace_spans",
+            column: "start_time",
+            retention_days,
+        }
+    }
+}
+
+/// A single partition of a parent table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionInfo {
+    /// Partition table name
+    pub name: String,
+
+    /// Inclusive lower bound (None for MINVALUE)
+    pub from: Option<DateTime<Utc>>,
+
+    /// Exclusive upper bound (None for MAXVALUE)
+    pub to: Option<DateTime<Utc>>,
+
+    /// Whether this is the DEFAULT partition
+    pub is_default: bool,
+}
+
+/// Outcome of applying retention to a single table.
+#[derive(Debug, Clone, Default)]
+pub struct RetentionOutcome {
+    /// Partitions dropped entirely
+    pub dropped_partitions: Vec<String>,
+
+    /// Rows deleted from partitions straddling the cutoff
+    pub rows_deleted: u64,
+}
+
+/// Summary of a maintenance run.
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceReport {
+    /// Partitions created ahead of time
+    pub created_partitions: Vec<String>,
+
+    /// Partitions dropped by retention
+    pub dropped_partitions: Vec<String>,
+
+    /// Rows deleted from boundary and default partitions
+    pub rows_deleted: u64,
+}
+
+impl PartitionInterval {
+    /// Start of the period containing `ts`.
+    pub fn period_start(&self, ts: DateTime<Utc>) -> DateTime<Utc> {
+        let day = ts.date_naive();
+        let day = match self {
+            PartitionInterval::Daily => day,
+            PartitionInterval::Weekly => {
+                day - Duration::days(day.weekday().num_days_from_monday() as i64)
+            }
+        };
+        Utc.from_utc_datetime(&day.and_time(NaiveTime::MIN))
+    }
+
+    /// Width of one period.
+    pub fn width(&self) -> Duration {
+        match self {
+            PartitionInterval::Daily => Duration::days(1),
+            PartitionInterval::Weekly => Duration::weeks(1),
+        }
+    }
+}
+
+/// Name of the partition of `table` starting at `start`.
+pub fn partition_name(table: &str, start: DateTime<Utc>) -> String {
+    format!("{}_p{}", table, start.format("%Y%m%d"))
+}
+
+/// Parse a bound expression as returned by `pg_get_expr(relpartbound, oid)`.
+///
+/// Returns `None` for the DEFAULT partition.
+fn parse_partition_bound(expr: &str) -> Option<(Option<DateTime<Utc>>, Option<DateTime<Utc>>)> {
+    let rest = expr.trim().strip_prefix("FOR VALUES FROM ")?;
+    let (from, to) = rest.split_once(" TO ")?;
+
+    let parse = |part: &str| -> Option<DateTime<Utc>> {
+        let value = part.trim().trim_start_matches('(').trim_end_matches(')');
+        let value = value.trim_matches('\'');
+        DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%#z")
+            .ok()
+            .map(|dt| dt.with_timezone(&Utc))
+    };
+
+    Some((parse(from), parse(to)))
+}
+
+/// Manages partition creation and partition-aware retention.
+#[derive(Clone)]
+pub struct PartitionManager {
+    pool: StoragePool,
+    config: PartitionConfig,
+    tables: Vec<PartitionedTable>,
+}
+
+impl PartitionManager {
+    /// Create a manager for the `logs` and `trace_spans` tables using the
+    /// pool's partitioning configuration.
+    pub fn new(pool: StoragePool) -> Self {
+        let config = pool.config().partitioning.clone();
+        let tables = vec![
+            PartitionedTable::logs(config.logs_retention_days),
+            PartitionedTable::trace_spans(config.spans_retention_days),
+        ];
+        Self::with_tables(pool, config, tables)
+    }
+
+    /// Create a manager for an explicit set of tables.
+    pub fn with_tables(
+        pool: StoragePool,
+        config: PartitionConfig,
+        tables: Vec<PartitionedTable>,
+    ) -> Self {
+        Self {
+            pool,
+            config,
+            tables,
+        }
+    }
+
+    /// Tables managed by this instance.
+    pub fn tables(&self) -> &[PartitionedTable] {
+        &self.tables
+    }
+
+    /// Create partitions for the current period and `premake` periods ahead.
+    ///
+    /// Returns the names of partitions that were newly created.
+    pub async fn ensure_partitions(&self, now: DateTime<Utc>) -> StorageResult<Vec<String>> {
+        let mut created = Vec::new();
+
+        for table in &self.tables {
+            let existing: Vec<String> = self
+                .list_partitions(table.table)
+                .await?
+                .into_iter()
+                .map(|p| p.name)
+                .collect();
+
+            let mut start = self.config.interval.period_start(now);
+            for _ in 0..=self.config.premake {
+                let end = start + self.config.interval.width();
+                let name = partition_name(table.table, start);
+
+                if !existing.contains(&name) {
+                    let sql = format!(
+                        "CREATE TABLE IF NOT EXISTS {} PARTITION OF {} FOR VALUES FROM ('{}') TO ('{}')",
+                        name,
+                        table.table,
+                        start.to_rfc3339(),
+                        end.to_rfc3339()
+                    );
+
+                    // Creation fails if the DEFAULT partition already holds rows
+                    // for this range; keep going so other tables are maintained.
+                    match sqlx::query(&sql).execute(self.pool.postgres()).await {
+                        Ok(_) => {
+                            tracing::info!("Created partition {}", name);
+                            created.push(name);
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to create partition {}: {}", name, e);
+                        }
+                    }
+                }
+
+                start = end;
+            }
+        }
+
+        Ok(created)
+    }
+
+    /// List partitions of a parent table with their bounds.
+    pub async fn list_partitions(&self, table: &str) -> StorageResult<Vec<PartitionInfo>> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT c.relname::text, pg_get_expr(c.relpartbound, c.oid) \
+             FROM pg_inherits i \
+             JOIN pg_class c ON c.oid = i.inhrelid \
+             JOIN pg_class p ON p.oid = i.inhparent \
+             WHERE p.relname = $1 \
+             ORDER BY c.relname",
+        )
+        .bind(table)
+        .fetch_all(self.pool.postgres())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(name, bound)| match parse_partition_bound(&bound) {
+                Some((from, to)) => PartitionInfo {
+                    name,
+                    from,
+                    to,
+                    is_default: false,
+                },
+                None => PartitionInfo {
+                    name,
+                    from: None,
+                    to: None,
+                    is_default: true,
+                },
+            })
+            .collect())
+    }
+
+    /// Apply retention to a table.
+    ///
+    /// Partitions whose upper bound is at or before `cutoff` are dropped.
+    /// Rows older than `cutoff` in the partition straddling the cutoff, and in
+    /// the DEFAULT partition, are deleted individually.
+    pub async fn apply_retention(
+        &self,
+        table: &PartitionedTable,
+        cutoff: DateTime<Utc>,
+    ) -> StorageResult<RetentionOutcome> {
+        let mut outcome = RetentionOutcome::default();
+
+        for partition in self.list_partitions(table.table).await? {
+            let fully_expired = matches!(partition.to, Some(to) if to <= cutoff);
+            let straddles = partition.is_default
+                || (!matches!(partition.from, Some(from) if from >= cutoff) && !fully_expired);
+
+            if fully_expired {
+                sqlx::query(&format!("DROP TABLE IF EXISTS {}", partition.name))
+                    .execute(self.pool.postgres())
+                    .await?;
+                tracing::info!("Dropped expired partition {}", partition.name);
+                outcome.dropped_partitions.push(partition.name);
+            } else if straddles {
+                let result = sqlx::query(&format!(
+                    "DELETE FROM {} WHERE {} < $1",
+                    partition.name, table.column
+                ))
+                .bind(cutoff)
+                .execute(self.pool.postgres())
+                .await?;
+                outcome.rows_deleted += result.rows_affected();
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    /// Create upcoming partitions and apply retention to all tables.
+    pub async fn run_maintenance(&self) -> StorageResult<MaintenanceReport> {
+        let now = Utc::now();
+        let mut report = MaintenanceReport {
+            created_partitions: self.ensure_partitions(now).await?,
+            ..Default::default()
+        };
+
+        for table in &self.tables {
+            if let Some(days) = table.retention_days {
+                let cutoff = now - Duration::days(days as i64);
+                let outcome = self.apply_retention(table, cutoff).await?;
+                report.dropped_partitions.extend(outcome.dropped_partitions);
+                report.rows_deleted += outcome.rows_deleted;
+            }
+        }
+
+        tracing::info!(
+            "Partition maintenance: {} created, {} dropped, {} rows deleted",
+            report.created_partitions.len(),
+            report.dropped_partitions.len(),
+            report.rows_deleted
+        );
+
+        Ok(report)
+    }
+
+    /// Start periodic partition maintenance.
+    ///
+    /// Returns a handle that can be used to stop the maintenance task.
+    pub fn start_maintenance(&self) -> tokio::task::JoinHandle<()> {
+        let manager = self.clone();
+        let interval = self.config.maintenance_interval();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = manager.run_maintenance().await {
+                    tracing::error!("Partition maintenance error: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_period_start() {
+        let t = ts("2025-11-12T15:30:00Z"); // Wednesday
+        assert_eq!(PartitionInterval::Daily.period_start(t), ts("2025-11-12T00:00:00Z"));
+        assert_eq!(PartitionInterval::Weekly.period_start(t), ts("2025-11-10T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_partition_name() {
+        assert_eq!(partition_name("logs", ts("2025-11-10T00:00:00Z")), "logs_p20251110");
+    }
+
+    #[test]
+    fn test_parse_partition_bound() {
+        let (from, to) = parse_partition_bound(
+            "FOR VALUES FROM ('2025-11-10 00:00:00+00') TO ('2025-11-11 00:00:00+00')",
+        )
+        .unwrap();
+        assert_eq!(from, Some(ts("2025-11-10T00:00:00Z")));
+        assert_eq!(to, Some(ts("2025-11-11T00:00:00Z")));
+
+        let (from, to) =
+            parse_partition_bound("FOR VALUES FROM (MINVALUE) TO ('2025-11-10 01:00:00+01')").unwrap();
+        assert_eq!(from, None);
+        assert_eq!(to, Some(ts("2025-11-10T00:00:00Z")));
+
+        assert!(parse_partition_bound("DEFAULT").is_none());
+    }
+}
@@ -3,11 +3,20 @@
 //! This module handles database connection pooling for both PostgreSQL and Redis,
 //! providing efficient connection reuse and automatic reconnection.
 
-use crate::config::StorageConfig;
+use crate::circuit_breaker::{CircuitBreaker, CircuitState};
+use crate::config::{StorageBackend, StorageConfig};
+use crate::credentials::CredentialProvider;
+use crate::encryption::FieldEncryptor;
 use crate::error::{StorageError, StorageResult};
+use crate::health_history::{HealthHistory, HealthSample};
+use crate::shutdown::{Flushable, Registration, ShutdownCoordinator, ShutdownReport};
+use sqlx::pool::PoolConnection;
 use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::{Postgres, Transaction};
+use std::future::Future;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
 
 /// Main storage pool that manages connections to PostgreSQL and optionally Redis.
 #[derive(Clone)]
@@ -20,13 +29,30 @@ pub struct StoragePool {
 
     /// Configuration reference
     config: Arc<StorageConfig>,
+
+    /// Source of the PostgreSQL password
+    credentials: Arc<dyn CredentialProvider>,
+
+    /// Encryption of sensitive payload fields (optional)
+    encryptor: Option<Arc<FieldEncryptor>>,
+
+    /// Circuit breaker shared by all clones of the pool
+    breaker: Arc<CircuitBreaker>,
+
+    /// Rolling health samples shared by all clones of the pool
+    history: Arc<HealthHistory>,
+
+    /// Components to flush on shutdown, shared by all clones of the pool
+    shutdown: Arc<ShutdownCoordinator>,
 }
 
 impl StoragePool {
     /// Create a new storage pool with the given configuration.
     ///
     /// This will establish connections to PostgreSQL and optionally Redis,
-    /// with automatic retry logic based on the retry configuration.
+    /// with automatic retry logic based on the retry configuration. If health
+    /// history is enabled, a background task samples the pool's health until
+    /// the pool is closed.
     ///
     /// # Arguments
     ///
@@ -48,12 +74,41 @@ impl StoragePool {
     /// # }
     /// ```
     pub async fn new(config: StorageConfig) -> StorageResult<Self> {
+        let credentials = crate::credentials::from_config(&config.postgres);
+        Self::with_credentials(config, credentials).await
+    }
+
+    /// Create a new storage pool that takes the PostgreSQL password from
+    /// `credentials` instead of the configured credentials.
+    ///
+    /// If the provider has a refresh interval, a background task fetches a
+    /// new password at that interval until the pool is closed; connections
+    /// opened afterwards use it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if connection to any database fails after all retry attempts.
+    pub async fn with_credentials(
+        config: StorageConfig,
+        credentials: Arc<dyn CredentialProvider>,
+    ) -> StorageResult<Self> {
+        if config.backend != StorageBackend::Postgres {
+            return Err(StorageError::ConfigError(format!(
+                "The {:?} backend has no PostgreSQL pool; open it with Storage::connect",
+                config.backend
+            )));
+        }
+
         let config = Arc::new(config);
+        let encryptor = FieldEncryptor::from_config(&config.encryption)?.map(Arc::new);
 
-        tracing::info!("Initializing storage pool with retry logic");
+        tracing::info!(
+            credentials = credentials.name(),
+            "Initializing storage pool with retry logic"
+        );
 
         // Create PostgreSQL connection pool with retry
-        let postgres = Self::create_postgres_pool_with_retry(&config).await?;
+        let postgres = Self::create_postgres_pool_with_retry(&config, credentials.as_ref()).await?;
 
         // Create Redis connection pool if configured
         let redis = if let Some(redis_config) = &config.redis {
@@ -72,20 +127,104 @@ impl StoragePool {
 
         tracing::info!("Storage pool initialized successfully");
 
-        Ok(Self {
+        let breaker = Arc::new(CircuitBreaker::new(config.circuit_breaker.clone()));
+        let history = Arc::new(HealthHistory::new(config.health_history.capacity));
+
+        let pool = Self {
             postgres,
             redis,
             config,
-        })
+            credentials,
+            encryptor,
+            breaker,
+            history,
+            shutdown: Arc::new(ShutdownCoordinator::new()),
+        };
+
+        if pool.config.health_history.enabled {
+            pool.spawn_health_sampler();
+        }
+        if let Some(interval) = pool.credentials.refresh_interval() {
+            pool.spawn_credential_refresher(interval);
+        }
+
+        Ok(pool)
+    }
+
+    /// Fetch a new password every `interval` until the pool is closed.
+    ///
+    /// Open connections keep working; only new connections use the new
+    /// password. If fetching fails, the previous password stays in use and
+    /// the next tick tries again.
+    fn spawn_credential_refresher(&self, interval: Duration) {
+        let pool = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                if pool.postgres.is_closed() {
+                    break;
+                }
+
+                let options = match pool.credentials.password().await {
+                    Ok(password) => pool.config.postgres.connect_options(&password),
+                    Err(e) => Err(e),
+                };
+                match options {
+                    Ok(options) => {
+                        pool.postgres.set_connect_options(options);
+                        tracing::debug!(
+                            credentials = pool.credentials.name(),
+                            "Refreshed PostgreSQL credentials"
+                        );
+                    }
+                    Err(e) => {
+                        crate::metrics::StorageMetrics::new()
+                            .record_error("credential_refresh", None);
+                        tracing::error!(
+                            credentials = pool.credentials.name(),
+                            "Failed to refresh PostgreSQL credentials: {}",
+                            e
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    /// Record a health sample every sample interval until the pool is closed.
+    fn spawn_health_sampler(&self) {
+        let pool = self.clone();
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(pool.config.health_history.sample_interval());
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+                if pool.postgres.is_closed() {
+                    break;
+                }
+                let sample = pool.sample_health().await;
+                pool.history.record(sample);
+            }
+        });
     }
 
     /// Create a PostgreSQL connection pool with retry logic.
-    async fn create_postgres_pool_with_retry(config: &StorageConfig) -> StorageResult<PgPool> {
+    async fn create_postgres_pool_with_retry(
+        config: &StorageConfig,
+        credentials: &dyn CredentialProvider,
+    ) -> StorageResult<PgPool> {
         let mut attempt = 0;
         let max_attempts = config.retry.max_retries;
 
         loop {
-            match Self::create_postgres_pool(config).await {
+            match Self::create_postgres_pool(config, credentials).await {
                 Ok(pool) => return Ok(pool),
                 Err(e) if attempt < max_attempts => {
                     let delay = config.retry.delay_for_attempt(attempt);
@@ -147,14 +286,20 @@ impl StoragePool {
     }
 
     /// Create a PostgreSQL connection pool.
-    async fn create_postgres_pool(config: &StorageConfig) -> StorageResult<PgPool> {
+    async fn create_postgres_pool(
+        config: &StorageConfig,
+        credentials: &dyn CredentialProvider,
+    ) -> StorageResult<PgPool> {
+        let password = credentials.password().await?;
+        let options = config.postgres.connect_options(&password)?;
+
         let pool = PgPoolOptions::new()
             .max_connections(config.pool.max_connections)
             .min_connections(config.pool.min_connections)
             .acquire_timeout(Duration::from_secs(config.pool.connect_timeout_secs))
             .idle_timeout(Some(Duration::from_secs(config.pool.idle_timeout_secs)))
             .max_lifetime(Some(Duration::from_secs(config.pool.max_lifetime_secs)))
-            .connect(&config.postgres_url())
+            .connect_with(options)
             .await
             .map_err(|e| StorageError::ConnectionError(e.to_string()))?;
 
@@ -197,10 +342,202 @@ impl StoragePool {
         &self.config
     }
 
+    /// Get the encryptor of sensitive payload fields, if encryption is
+    /// configured.
+    pub fn encryptor(&self) -> Option<&Arc<FieldEncryptor>> {
+        self.encryptor.as_ref()
+    }
+
+    /// Use `encryptor` for sensitive payload fields, e.g. one backed by a KMS
+    /// [`KeyProvider`](crate::encryption::KeyProvider) instead of the
+    /// configured keys.
+    ///
+    /// Writers and repositories pick up the encryptor when they are created.
+    pub fn with_encryptor(mut self, encryptor: Arc<FieldEncryptor>) -> Self {
+        self.encryptor = Some(encryptor);
+        self
+    }
+
+    /// Get the circuit breaker guarding PostgreSQL access.
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
+    /// Check whether the pool is in degraded mode.
+    ///
+    /// Returns true while the circuit breaker is open or half-open. Callers
+    /// that can buffer data elsewhere (e.g. the collector spool) should do so
+    /// instead of issuing writes.
+    pub fn is_degraded(&self) -> bool {
+        self.breaker.state() != CircuitState::Closed
+    }
+
+    /// Run a PostgreSQL operation through the circuit breaker.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::CircuitOpen`] without running `op` if the
+    /// circuit is open, otherwise the result of `op`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use llm_observatory_storage::StoragePool;
+    /// # async fn example(pool: StoragePool) -> Result<(), Box<dyn std::error::Error>> {
+    /// let count: i64 = pool
+    ///     .guarded(async {
+    ///         let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM traces")
+    ///             .fetch_one(pool.postgres())
+    ///             .await?;
+    ///         Ok(row.0)
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn guarded<T, F>(&self, op: F) -> StorageResult<T>
+    where
+        F: Future<Output = StorageResult<T>>,
+    {
+        self.breaker.call(op).await
+    }
+
+    /// Run a repository query with timeout, duration tracking and slow-query logging.
+    ///
+    /// The query runs through the circuit breaker and is cancelled once it
+    /// exceeds the timeout configured for `repository.method` (see
+    /// [`QueryConfig`](crate::config::QueryConfig)). Queries slower than the
+    /// slow query threshold are logged with their sanitized SQL and counted in
+    /// `storage_slow_queries_total`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Timeout`] if the query exceeds its timeout, or
+    /// the query's own error converted to a [`StorageError`].
+    pub async fn run_query<T, F>(
+        &self,
+        repository: &'static str,
+        method: &'static str,
+        sql: Option<&str>,
+        query: F,
+    ) -> StorageResult<T>
+    where
+        F: Future<Output = Result<T, sqlx::Error>>,
+    {
+        let timeout = self.config.query.timeout_for(repository, method);
+        let span = crate::spans::query(self.config.tracing.verbosity, repository, method, sql);
+        let start = Instant::now();
+
+        let result = self
+            .guarded(async {
+                match tokio::time::timeout(timeout, query).await {
+                    Ok(result) => result.map_err(StorageError::from),
+                    Err(_) => Err(StorageError::Timeout(format!(
+                        "{}.{} exceeded {:?}",
+                        repository, method, timeout
+                    ))),
+                }
+            })
+            .instrument(span.clone())
+            .await;
+        crate::spans::record_result(&span, &result);
+
+        let elapsed = start.elapsed();
+        let metrics = crate::metrics::StorageMetrics::new();
+        metrics.record_statement(repository, method, elapsed.as_secs_f64());
+
+        if elapsed >= self.config.query.slow_query_threshold() {
+            metrics.record_slow_query(repository, method);
+            tracing::warn!(
+                "Slow query {}.{} took {:?}: {}",
+                repository,
+                method,
+                elapsed,
+                sql.map(crate::query::sanitize_sql).unwrap_or_default()
+            );
+        }
+
+        if let Err(StorageError::Timeout(ref msg)) = result {
+            metrics.record_error("timeout", Some(method));
+            tracing::error!("Query timed out: {}", msg);
+        }
+
+        result
+    }
+
+    /// Acquire a PostgreSQL connection through the circuit breaker.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::CircuitOpen`] if the circuit is open, or a
+    /// timeout/pool error if no connection could be acquired.
+    pub async fn acquire(&self) -> StorageResult<PoolConnection<Postgres>> {
+        let span = crate::spans::acquire(self.config.tracing.verbosity);
+        let start = Instant::now();
+        let result = self
+            .guarded(async { self.postgres.acquire().await.map_err(StorageError::from) })
+            .instrument(span.clone())
+            .await;
+        crate::spans::record_result(&span, &result);
+        let conn = result?;
+
+        crate::metrics::StorageMetrics::new()
+            .record_connection_acquire(start.elapsed().as_secs_f64());
+
+        Ok(conn)
+    }
+
+    /// Begin a transaction scoped to one organization.
+    ///
+    /// Sets `app.org_id` for the duration of the transaction (equivalent to
+    /// `SET LOCAL app.org_id`), which the row-level security policies of
+    /// migrations 019 and 044 check for database roles granted
+    /// `llm_observatory_tenant`. The setting is discarded on commit or
+    /// rollback, so it never leaks to the next user of the pooled connection.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::ValidationError`] if `org_id` is empty,
+    /// [`StorageError::CircuitOpen`] if the circuit is open, or the error of
+    /// beginning the transaction or setting the organization.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use llm_observatory_storage::StoragePool;
+    /// # async fn example(pool: StoragePool) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut tx = pool.with_tenant("org-123").await?;
+    /// let jobs: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM export_jobs")
+    ///     .fetch_one(&mut *tx)
+    ///     .await?;
+    /// tx.commit().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn with_tenant(&self, org_id: &str) -> StorageResult<Transaction<'static, Postgres>> {
+        if org_id.is_empty() {
+            return Err(StorageError::ValidationError(
+                "Tenant organization ID must not be empty".to_string(),
+            ));
+        }
+
+        self.guarded(async {
+            let mut tx = self.postgres.begin().await?;
+            sqlx::query("SELECT set_config('app.org_id', $1, true)")
+                .bind(org_id)
+                .execute(&mut *tx)
+                .await?;
+            Ok(tx)
+        })
+        .await
+    }
+
     /// Get a tokio-postgres client for COPY operations.
     ///
     /// This creates a new connection using tokio-postgres directly, which is needed
     /// for COPY protocol operations. The connection should be returned/closed when done.
+    /// It authenticates with the pool's credentials but does not support TLS,
+    /// so it fails when the SSL mode requires TLS.
     ///
     /// # Errors
     ///
@@ -208,11 +545,25 @@ impl StoragePool {
     pub async fn get_tokio_postgres_client(
         &self,
     ) -> StorageResult<(tokio_postgres::Client, tokio::task::JoinHandle<()>)> {
-        let (client, connection) = tokio_postgres::connect(
-            &self.config.postgres_url(),
-            tokio_postgres::NoTls,
-        )
-        .await
+        let postgres = &self.config.postgres;
+        let password = self.credentials.password().await?;
+        let mut pg_config = tokio_postgres::Config::new();
+        pg_config
+            .host(&postgres.host)
+            .port(postgres.port)
+            .dbname(&postgres.database)
+            .user(&postgres.username)
+            .password(password)
+            .application_name(&postgres.application_name)
+            .ssl_mode(if postgres.requires_tls() {
+                tokio_postgres::config::SslMode::Require
+            } else {
+                tokio_postgres::config::SslMode::Prefer
+            });
+
+        let (client, connection) = pg_config
+            .connect(tokio_postgres::NoTls)
+            .await
         .map_err(|e| StorageError::ConnectionError(format!("Failed to create tokio-postgres client: {}", e)))?;
 
         // Spawn connection handler in background
@@ -250,6 +601,8 @@ impl StoragePool {
     /// Check if the database connection is healthy.
     ///
     /// Performs a simple query to verify connectivity for both PostgreSQL and Redis.
+    /// While the circuit breaker is open PostgreSQL is not probed and is
+    /// reported as unhealthy, so health checks don't add load to a failing database.
     ///
     /// # Errors
     ///
@@ -259,8 +612,12 @@ impl StoragePool {
         tracing::debug!("Running health check");
 
         // Check PostgreSQL
-        let postgres_healthy = match self.health_check_postgres().await {
+        let postgres_healthy = match self.guarded(self.health_check_postgres()).await {
             Ok(_) => true,
+            Err(e) if e.is_circuit_open() => {
+                tracing::warn!("Skipping PostgreSQL health check: {}", e);
+                false
+            }
             Err(e) => {
                 tracing::error!("PostgreSQL health check failed: {}", e);
                 return Err(e);
@@ -283,6 +640,7 @@ impl StoragePool {
         Ok(HealthCheckResult {
             postgres_healthy,
             redis_healthy,
+            circuit_state: self.breaker.state(),
         })
     }
 
@@ -301,10 +659,14 @@ impl StoragePool {
     pub async fn health_check_redis(&self) -> StorageResult<()> {
         if let Some(redis) = &self.redis {
             let mut conn = redis.as_ref().clone();
-            redis::cmd("PING")
+            let span = crate::spans::redis(self.config.tracing.verbosity, "PING");
+            let result = redis::cmd("PING")
                 .query_async::<_, String>(&mut conn)
+                .instrument(span.clone())
                 .await
-                .map_err(|e| StorageError::RedisError(format!("Redis health check failed: {}", e)))?;
+                .map_err(|e| StorageError::RedisError(format!("Redis health check failed: {}", e)));
+            crate::spans::record_result(&span, &result);
+            result?;
 
             tracing::debug!("Redis health check passed");
             Ok(())
@@ -313,6 +675,114 @@ impl StoragePool {
         }
     }
 
+    /// Take a health sample: pool utilization, connection acquire latency and
+    /// the result of a health check.
+    ///
+    /// Acquiring goes through the circuit breaker, so while the circuit is
+    /// open the sample has no acquire latency and PostgreSQL is reported as
+    /// unhealthy without probing the database.
+    pub async fn sample_health(&self) -> HealthSample {
+        let mut sample = HealthSample::from_stats(&self.stats());
+
+        let start = Instant::now();
+        match self.acquire().await {
+            Ok(conn) => {
+                sample.acquire_latency_ms = Some(start.elapsed().as_secs_f64() * 1000.0);
+                drop(conn);
+
+                match self.health_check().await {
+                    Ok(result) => {
+                        sample.postgres_healthy = result.postgres_healthy;
+                        sample.redis_healthy = result.redis_healthy;
+                    }
+                    Err(e) => sample.error = Some(e.to_string()),
+                }
+            }
+            Err(e) => sample.error = Some(e.to_string()),
+        }
+
+        sample.circuit_state = self.breaker.state().as_str().to_string();
+        sample
+    }
+
+    /// Get the rolling health history, oldest sample first.
+    ///
+    /// Empty if health history is disabled in the configuration.
+    pub fn health_history(&self) -> Vec<HealthSample> {
+        self.history.samples()
+    }
+
+    /// Register a buffering component to flush on [`shutdown`](Self::shutdown).
+    ///
+    /// The component stays registered until the returned [`Registration`]
+    /// is dropped. Trace, metric and log writers register themselves when
+    /// created and deregister when their last clone is dropped.
+    pub fn register_flushable(&self, component: Arc<dyn Flushable>) -> Registration {
+        self.shutdown.register(component)
+    }
+
+    /// Check whether [`shutdown`](Self::shutdown) has started.
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutdown.is_shutting_down()
+    }
+
+    /// Fail with [`StorageError::ShuttingDown`] once shutdown has started.
+    ///
+    /// Writers call this before buffering new records.
+    pub fn check_accepting_writes(&self) -> StorageResult<()> {
+        self.shutdown.check_accepting_writes()
+    }
+
+    /// Shut down gracefully.
+    ///
+    /// Stops accepting new writes, flushes all registered writers and other
+    /// [`Flushable`] components within `timeout`, then closes the pool.
+    /// Records that could not be written in time are dropped and counted in
+    /// the returned report.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use llm_observatory_storage::StoragePool;
+    /// # use std::time::Duration;
+    /// # async fn example(pool: StoragePool) {
+    /// tokio::signal::ctrl_c().await.ok();
+    /// let report = pool.shutdown(Duration::from_secs(30)).await;
+    /// println!("flushed {} records, dropped {}", report.flushed(), report.dropped());
+    /// # }
+    /// ```
+    pub async fn shutdown(&self, timeout: Duration) -> ShutdownReport {
+        tracing::info!("Shutting down storage pool, flushing writers (timeout {:?})", timeout);
+
+        let report = self.shutdown.drain(timeout).await;
+        for component in &report.components {
+            if component.timed_out {
+                tracing::error!(
+                    "{} did not flush within {:?}, dropped {} records",
+                    component.name,
+                    timeout,
+                    component.dropped
+                );
+            } else if let Some(error) = &component.error {
+                tracing::error!(
+                    "{} failed to flush, dropped {} records: {}",
+                    component.name,
+                    component.dropped,
+                    error
+                );
+            }
+        }
+        tracing::info!(
+            "Flushed {} records, dropped {} in {:?}",
+            report.flushed(),
+            report.dropped(),
+            report.duration
+        );
+
+        self.close().await;
+        report
+    }
+
     /// Close all database connections gracefully.
     pub async fn close(&self) {
         self.postgres.close().await;
@@ -358,6 +828,9 @@ pub struct HealthCheckResult {
 
     /// Whether Redis is healthy (None if not configured)
     pub redis_healthy: Option<bool>,
+
+    /// State of the PostgreSQL circuit breaker
+    pub circuit_state: CircuitState,
 }
 
 impl HealthCheckResult {
@@ -365,6 +838,13 @@ impl HealthCheckResult {
     pub fn is_healthy(&self) -> bool {
         self.postgres_healthy && self.redis_healthy.unwrap_or(true)
     }
+
+    /// Check if the storage layer is shedding load.
+    ///
+    /// True while the circuit breaker is not closed.
+    pub fn is_degraded(&self) -> bool {
+        self.circuit_state != CircuitState::Closed
+    }
 }
 
 /// Statistics about connection pool usage.
@@ -413,6 +893,18 @@ mod tests {
     // TODO: Add integration tests with test databases
     // These tests would require a running PostgreSQL instance
 
+    #[test]
+    fn test_health_check_result_degraded() {
+        let result = HealthCheckResult {
+            postgres_healthy: false,
+            redis_healthy: None,
+            circuit_state: CircuitState::Open,
+        };
+
+        assert!(!result.is_healthy());
+        assert!(result.is_degraded());
+    }
+
     #[test]
     fn test_pool_stats_structure() {
         let stats = PoolStats {
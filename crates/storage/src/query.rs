@@ -0,0 +1,82 @@
+//! Helpers for repository query execution.
+//!
+//! Repository queries run through [`StoragePool::run_query`](crate::StoragePool::run_query),
+//! which applies the configured per-query timeout, records statement duration
+//! and logs queries slower than the configured threshold. The SQL in those
+//! logs is passed through [`sanitize_sql`] first.
+
+/// Maximum length of SQL included in slow query logs.
+const MAX_LOGGED_SQL_LEN: usize = 1024;
+
+/// Prepare SQL text for logging.
+///
+/// Collapses whitespace, replaces quoted string literals with `'?'` so
+/// literal values never reach the logs, and truncates long statements.
+/// Bind parameters (`$1`, `$2`, ...) are left untouched.
+pub fn sanitize_sql(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len().min(MAX_LOGGED_SQL_LEN));
+    let mut chars = sql.chars().peekable();
+    let mut pending_space = false;
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            pending_space = !out.is_empty();
+            continue;
+        }
+        if pending_space {
+            out.push(' ');
+            pending_space = false;
+        }
+
+        if c == '\'' {
+            // Skip to the closing quote, treating '' as an escaped quote
+            while let Some(inner) = chars.next() {
+                if inner == '\'' {
+                    if chars.peek() == Some(&'\'') {
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+            }
+            out.push_str("'?'");
+        } else {
+            out.push(c);
+        }
+
+        if out.len() >= MAX_LOGGED_SQL_LEN {
+            out.push_str("...");
+            break;
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_collapses_whitespace() {
+        let sql = "\n            SELECT * FROM traces\n            WHERE service_name = $1\n        ";
+        assert_eq!(sanitize_sql(sql), "SELECT * FROM traces WHERE service_name = $1");
+    }
+
+    #[test]
+    fn test_sanitize_strips_literals() {
+        let sql = "SELECT * FROM logs WHERE body = 'user''s password' AND level = 'error'";
+        assert_eq!(
+            sanitize_sql(sql),
+            "SELECT * FROM logs WHERE body = '?' AND level = '?'"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_truncates() {
+        let sql = format!("SELECT {}", "a, ".repeat(1000));
+        let sanitized = sanitize_sql(&sql);
+        assert!(sanitized.len() <= MAX_LOGGED_SQL_LEN + 3);
+        assert!(sanitized.ends_with("..."));
+    }
+}
@@ -1,11 +1,14 @@
 //! Log repository for querying log data.
 
-use crate::error::{StorageError, StorageResult};
+use crate::error::StorageResult;
 use crate::models::{LogRecord, LogLevel};
 use crate::pool::StoragePool;
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+/// Repository label used for query metrics and slow-query logs.
+const REPOSITORY: &str = "log_repository";
+
 /// Repository for querying log data.
 #[derive(Clone)]
 pub struct LogRepository {
@@ -20,11 +23,12 @@ impl LogRepository {
 
     /// Get a log record by its ID.
     pub async fn get_by_id(&self, id: Uuid) -> StorageResult<LogRecord> {
-        sqlx::query_as::<_, LogRecord>("SELECT * FROM log_records WHERE id = $1")
+        let sql = "SELECT * FROM log_records WHERE id = $1";
+        let query = sqlx::query_as::<_, LogRecord>(sql)
             .bind(id)
-            .fetch_one(self.pool.postgres())
-            .await
-            .map_err(StorageError::from)
+            .fetch_one(self.pool.postgres());
+
+        self.pool.run_query(REPOSITORY, "get_by_id", Some(sql), query).await
     }
 
     /// Get logs for a time range with filters.
@@ -125,9 +129,9 @@ impl LogRepository {
             q = q.bind(offset);
         }
 
-        q.fetch_all(self.pool.postgres())
+        self.pool
+            .run_query(REPOSITORY, "list", Some(query.as_str()), q.fetch_all(self.pool.postgres()))
             .await
-            .map_err(StorageError::from)
     }
 
     /// Search logs by service name and time range.
@@ -137,22 +141,21 @@ impl LogRepository {
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> StorageResult<Vec<LogRecord>> {
-        sqlx::query_as::<_, LogRecord>(
-            r#"
+        let sql = r#"
             SELECT * FROM log_records
             WHERE service_name = $1
               AND timestamp >= $2
               AND timestamp <= $3
             ORDER BY timestamp DESC
             LIMIT 1000
-            "#
-        )
-        .bind(service_name)
-        .bind(start_time)
-        .bind(end_time)
-        .fetch_all(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)
+            "#;
+        let query = sqlx::query_as::<_, LogRecord>(sql)
+            .bind(service_name)
+            .bind(start_time)
+            .bind(end_time)
+            .fetch_all(self.pool.postgres());
+
+        self.pool.run_query(REPOSITORY, "search_by_service", Some(sql), query).await
     }
 
     /// Search logs by trace ID (get all logs for a trace).
@@ -162,17 +165,16 @@ impl LogRepository {
 
     /// Get all logs for a trace.
     pub async fn get_logs_by_trace(&self, trace_id: &str) -> StorageResult<Vec<LogRecord>> {
-        sqlx::query_as::<_, LogRecord>(
-            r#"
+        let sql = r#"
             SELECT * FROM log_records
             WHERE trace_id = $1
             ORDER BY timestamp ASC
-            "#
-        )
-        .bind(trace_id)
-        .fetch_all(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)
+            "#;
+        let query = sqlx::query_as::<_, LogRecord>(sql)
+            .bind(trace_id)
+            .fetch_all(self.pool.postgres());
+
+        self.pool.run_query(REPOSITORY, "get_logs_by_trace", Some(sql), query).await
     }
 
     /// Search logs by severity level.
@@ -200,22 +202,21 @@ impl LogRepository {
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> StorageResult<Vec<LogRecord>> {
-        sqlx::query_as::<_, LogRecord>(
-            r#"
+        let sql = r#"
             SELECT * FROM log_records
             WHERE body ILIKE $1
               AND timestamp >= $2
               AND timestamp <= $3
             ORDER BY timestamp DESC
             LIMIT 1000
-            "#
-        )
-        .bind(format!("%{}%", query))
-        .bind(start_time)
-        .bind(end_time)
-        .fetch_all(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)
+            "#;
+        let query = sqlx::query_as::<_, LogRecord>(sql)
+            .bind(format!("%{}%", query))
+            .bind(start_time)
+            .bind(end_time)
+            .fetch_all(self.pool.postgres());
+
+        self.pool.run_query(REPOSITORY, "search_logs", Some(sql), query).await
     }
 
     /// Get error logs for a time range.
@@ -239,8 +240,7 @@ impl LogRepository {
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> StorageResult<LogStats> {
-        let row = sqlx::query(
-            r#"
+        let sql = r#"
             SELECT
                 COUNT(*) as total_logs,
                 COUNT(*) FILTER (WHERE severity_number >= 17) as error_count,
@@ -249,13 +249,13 @@ impl LogRepository {
                 EXTRACT(EPOCH FROM ($2 - $1))::FLOAT as duration_seconds
             FROM log_records
             WHERE timestamp >= $1 AND timestamp <= $2
-            "#
-        )
-        .bind(start_time)
-        .bind(end_time)
-        .fetch_one(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)?;
+            "#;
+        let query = sqlx::query(sql)
+            .bind(start_time)
+            .bind(end_time)
+            .fetch_one(self.pool.postgres());
+
+        let row = self.pool.run_query(REPOSITORY, "get_stats", Some(sql), query).await?;
 
         let total_logs: i64 = row.try_get("total_logs")?;
         let error_count: i64 = row.try_get("error_count")?;
@@ -284,8 +284,7 @@ impl LogRepository {
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> StorageResult<Vec<LogLevelCount>> {
-        let rows = sqlx::query(
-            r#"
+        let sql = r#"
             SELECT
                 severity_number,
                 severity_text,
@@ -294,13 +293,13 @@ impl LogRepository {
             WHERE timestamp >= $1 AND timestamp <= $2
             GROUP BY severity_number, severity_text
             ORDER BY severity_number ASC
-            "#
-        )
-        .bind(start_time)
-        .bind(end_time)
-        .fetch_all(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)?;
+            "#;
+        let query = sqlx::query(sql)
+            .bind(start_time)
+            .bind(end_time)
+            .fetch_all(self.pool.postgres());
+
+        let rows = self.pool.run_query(REPOSITORY, "count_by_level", Some(sql), query).await?;
 
         let mut counts = Vec::new();
         for row in rows {
@@ -316,13 +315,13 @@ impl LogRepository {
 
     /// Delete old logs (for data retention).
     pub async fn delete_before(&self, before: DateTime<Utc>) -> StorageResult<u64> {
-        let result = sqlx::query!(
+        let query = sqlx::query!(
             "DELETE FROM log_records WHERE timestamp < $1",
             before
         )
-        .execute(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)?;
+        .execute(self.pool.postgres());
+
+        let result = self.pool.run_query(REPOSITORY, "delete_before", None, query).await?;
 
         Ok(result.rows_affected())
     }
@@ -0,0 +1,120 @@
+//! Log pattern repository for mined log templates and their counts.
+//!
+//! The collector's log pattern miner drains [`LogPattern`] and
+//! [`LogPatternCount`] rows; the ingest host writes them with
+//! [`LogPatternRepository::upsert_patterns`] and
+//! [`LogPatternRepository::add_counts`]. Both merge with existing rows, so
+//! drains from several collectors combine.
+
+use crate::error::StorageResult;
+use crate::models::{LogPattern, LogPatternCount};
+use crate::pool::StoragePool;
+
+/// Repository label used for query metrics and slow-query logs.
+const REPOSITORY: &str = "log_pattern_repository";
+
+/// Repository for log patterns.
+#[derive(Clone)]
+pub struct LogPatternRepository {
+    pool: StoragePool,
+}
+
+impl LogPatternRepository {
+    /// Create a new log pattern repository.
+    pub fn new(pool: StoragePool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert patterns, or widen the seen range and update the template of
+    /// existing ones. Returns the number of rows written.
+    ///
+    /// Each pattern may appear once per call, which holds for one drain of
+    /// the miner.
+    pub async fn upsert_patterns(&self, patterns: Vec<LogPattern>) -> StorageResult<u64> {
+        if patterns.is_empty() {
+            return Ok(0);
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO log_patterns AS p (org_id, pattern_id, service_name, template, \
+             severity_number, first_seen, last_seen) ",
+        );
+
+        query_builder.push_values(patterns, |mut b, pattern| {
+            b.push_bind(pattern.org_id)
+                .push_bind(pattern.pattern_id)
+                .push_bind(pattern.service_name)
+                .push_bind(pattern.template)
+                .push_bind(pattern.severity_number)
+                .push_bind(pattern.first_seen)
+                .push_bind(pattern.last_seen);
+        });
+
+        query_builder.push(
+            r#"
+            ON CONFLICT (org_id, pattern_id) DO UPDATE SET
+                template = EXCLUDED.template,
+                severity_number = GREATEST(p.severity_number, EXCLUDED.severity_number),
+                first_seen = LEAST(p.first_seen, EXCLUDED.first_seen),
+                last_seen = GREATEST(p.last_seen, EXCLUDED.last_seen)
+            "#,
+        );
+
+        let query = query_builder.build().execute(self.pool.postgres());
+        let result = self
+            .pool
+            .run_query(REPOSITORY, "upsert_patterns", None, query)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Add record counts to their hourly rows, creating missing rows.
+    /// Returns the number of rows written.
+    ///
+    /// Each hour and pattern may appear once per call, which holds for one
+    /// drain of the miner.
+    pub async fn add_counts(&self, counts: Vec<LogPatternCount>) -> StorageResult<u64> {
+        if counts.is_empty() {
+            return Ok(0);
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO log_pattern_counts AS c (bucket, org_id, pattern_id, count) ",
+        );
+
+        query_builder.push_values(counts, |mut b, count| {
+            b.push_bind(count.bucket)
+                .push_bind(count.org_id)
+                .push_bind(count.pattern_id)
+                .push_bind(count.count);
+        });
+
+        query_builder.push(
+            r#"
+            ON CONFLICT (bucket, org_id, pattern_id) DO UPDATE SET
+                count = c.count + EXCLUDED.count
+            "#,
+        );
+
+        let query = query_builder.build().execute(self.pool.postgres());
+        let result = self
+            .pool
+            .run_query(REPOSITORY, "add_counts", None, query)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// A pattern of an organization.
+    pub async fn get(&self, org_id: &str, pattern_id: &str) -> StorageResult<Option<LogPattern>> {
+        let sql = "SELECT org_id, pattern_id, service_name, template, severity_number, \
+                   first_seen, last_seen FROM log_patterns WHERE org_id = $1 AND pattern_id = $2";
+
+        let query = sqlx::query_as::<_, LogPattern>(sql)
+            .bind(org_id)
+            .bind(pattern_id)
+            .fetch_optional(self.pool.postgres());
+        self.pool
+            .run_query(REPOSITORY, "get", Some(sql), query)
+            .await
+    }
+}
@@ -1,11 +1,15 @@
 //! Metric repository for querying metric data.
 
-use crate::error::{StorageError, StorageResult};
-use crate::models::{Metric, MetricDataPoint, MetricType};
+use crate::error::StorageResult;
+use crate::models::metric::HistogramBucket;
+use crate::models::{Exemplar, MergedHistogram, Metric, MetricDataPoint, MetricType};
 use crate::pool::StoragePool;
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+/// Repository label used for query metrics and slow-query logs.
+const REPOSITORY: &str = "metric_repository";
+
 /// Repository for querying metric data.
 #[derive(Clone)]
 pub struct MetricRepository {
@@ -20,23 +24,23 @@ impl MetricRepository {
 
     /// Get a metric by its ID.
     pub async fn get_by_id(&self, id: Uuid) -> StorageResult<Metric> {
-        sqlx::query_as::<_, Metric>("SELECT * FROM metrics WHERE id = $1")
+        let sql = "SELECT * FROM metrics WHERE id = $1";
+        let query = sqlx::query_as::<_, Metric>(sql)
             .bind(id)
-            .fetch_one(self.pool.postgres())
-            .await
-            .map_err(StorageError::from)
+            .fetch_one(self.pool.postgres());
+
+        self.pool.run_query(REPOSITORY, "get_by_id", Some(sql), query).await
     }
 
     /// Get a metric by name and service.
     pub async fn get_by_name(&self, name: &str, service_name: &str) -> StorageResult<Metric> {
-        sqlx::query_as::<_, Metric>(
-            "SELECT * FROM metrics WHERE name = $1 AND service_name = $2 LIMIT 1"
-        )
-        .bind(name)
-        .bind(service_name)
-        .fetch_one(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)
+        let sql = "SELECT * FROM metrics WHERE name = $1 AND service_name = $2 LIMIT 1";
+        let query = sqlx::query_as::<_, Metric>(sql)
+            .bind(name)
+            .bind(service_name)
+            .fetch_one(self.pool.postgres());
+
+        self.pool.run_query(REPOSITORY, "get_by_name", Some(sql), query).await
     }
 
     /// List all metrics with optional filters.
@@ -88,9 +92,9 @@ impl MetricRepository {
             q = q.bind(offset);
         }
 
-        q.fetch_all(self.pool.postgres())
+        self.pool
+            .run_query(REPOSITORY, "list", Some(query.as_str()), q.fetch_all(self.pool.postgres()))
             .await
-            .map_err(StorageError::from)
     }
 
     /// Get metric time series by name.
@@ -100,8 +104,7 @@ impl MetricRepository {
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> StorageResult<Vec<MetricDataPoint>> {
-        sqlx::query_as::<_, MetricDataPoint>(
-            r#"
+        let sql = r#"
             SELECT mdp.*
             FROM metric_data_points mdp
             JOIN metrics m ON mdp.metric_id = m.id
@@ -109,14 +112,14 @@ impl MetricRepository {
               AND mdp.timestamp >= $2
               AND mdp.timestamp <= $3
             ORDER BY mdp.timestamp ASC
-            "#
-        )
-        .bind(name)
-        .bind(start_time)
-        .bind(end_time)
-        .fetch_all(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)
+            "#;
+        let query = sqlx::query_as::<_, MetricDataPoint>(sql)
+            .bind(name)
+            .bind(start_time)
+            .bind(end_time)
+            .fetch_all(self.pool.postgres());
+
+        self.pool.run_query(REPOSITORY, "get_metrics", Some(sql), query).await
     }
 
     /// Get data points for a metric.
@@ -126,32 +129,155 @@ impl MetricRepository {
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> StorageResult<Vec<MetricDataPoint>> {
-        sqlx::query_as::<_, MetricDataPoint>(
-            r#"
+        let sql = r#"
             SELECT * FROM metric_data_points
             WHERE metric_id = $1
               AND timestamp >= $2
               AND timestamp <= $3
             ORDER BY timestamp ASC
-            "#
-        )
-        .bind(metric_id)
-        .bind(start_time)
-        .bind(end_time)
-        .fetch_all(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)
+            "#;
+        let query = sqlx::query_as::<_, MetricDataPoint>(sql)
+            .bind(metric_id)
+            .bind(start_time)
+            .bind(end_time)
+            .fetch_all(self.pool.postgres());
+
+        self.pool.run_query(REPOSITORY, "get_data_points", Some(sql), query).await
+    }
+
+    /// Get exemplars recorded for a metric, most recent first.
+    ///
+    /// `min_value` and `max_value` narrow the results to a value range, e.g.
+    /// the bucket behind a latency spike.
+    pub async fn get_exemplars(
+        &self,
+        name: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        min_value: Option<f64>,
+        max_value: Option<f64>,
+        limit: i64,
+    ) -> StorageResult<Vec<Exemplar>> {
+        let sql = r#"
+            SELECT e.exemplar
+            FROM metric_data_points mdp
+            JOIN metrics m ON mdp.metric_id = m.id
+            CROSS JOIN LATERAL jsonb_array_elements(mdp.exemplars) AS e(exemplar)
+            WHERE m.name = $1
+              AND mdp.exemplars IS NOT NULL
+              AND mdp.timestamp >= $2
+              AND mdp.timestamp <= $3
+              AND ($4::DOUBLE PRECISION IS NULL OR (e.exemplar->>'value')::DOUBLE PRECISION >= $4)
+              AND ($5::DOUBLE PRECISION IS NULL OR (e.exemplar->>'value')::DOUBLE PRECISION <= $5)
+            ORDER BY e.exemplar->>'timestamp' DESC
+            LIMIT $6
+            "#;
+        let query = sqlx::query_scalar::<_, serde_json::Value>(sql)
+            .bind(name)
+            .bind(start_time)
+            .bind(end_time)
+            .bind(min_value)
+            .bind(max_value)
+            .bind(limit)
+            .fetch_all(self.pool.postgres());
+
+        let rows = self.pool.run_query(REPOSITORY, "get_exemplars", Some(sql), query).await?;
+
+        rows.into_iter()
+            .map(|exemplar| Ok(serde_json::from_value(exemplar)?))
+            .collect()
+    }
+
+    /// Merge the histogram data points of a metric over a time range.
+    ///
+    /// Only data points whose attributes contain `attributes` are merged, so
+    /// `{"model": "gpt-4o"}` selects one model across every other attribute.
+    /// Bucket counts are summed per boundary in the database; percentiles
+    /// come from [`MergedHistogram::quantile`].
+    pub async fn get_histogram(
+        &self,
+        name: &str,
+        attributes: &serde_json::Value,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> StorageResult<MergedHistogram> {
+        let buckets_sql = r#"
+            SELECT
+                (b.bucket->>'boundary')::DOUBLE PRECISION AS boundary,
+                SUM((b.bucket->>'count')::BIGINT)::BIGINT AS count
+            FROM metric_data_points mdp
+            JOIN metrics m ON mdp.metric_id = m.id
+            CROSS JOIN LATERAL jsonb_array_elements(mdp.buckets) AS b(bucket)
+            WHERE m.name = $1
+              AND mdp.attributes @> $2
+              AND mdp.buckets IS NOT NULL
+              AND mdp.timestamp >= $3
+              AND mdp.timestamp <= $4
+            GROUP BY 1
+            ORDER BY 1 ASC
+            "#;
+        let query = sqlx::query_as::<_, (f64, i64)>(buckets_sql)
+            .bind(name)
+            .bind(attributes)
+            .bind(start_time)
+            .bind(end_time)
+            .fetch_all(self.pool.postgres());
+        let buckets = self
+            .pool
+            .run_query(
+                REPOSITORY,
+                "get_histogram_buckets",
+                Some(buckets_sql),
+                query,
+            )
+            .await?;
+
+        // min and max are only known when every data point reported them
+        let totals_sql = r#"
+            SELECT
+                COALESCE(SUM(mdp.count), 0)::BIGINT AS count,
+                COALESCE(SUM(mdp.sum), 0)::DOUBLE PRECISION AS sum,
+                CASE WHEN COUNT(mdp.min) = COUNT(*) THEN MIN(mdp.min) END AS min,
+                CASE WHEN COUNT(mdp.max) = COUNT(*) THEN MAX(mdp.max) END AS max
+            FROM metric_data_points mdp
+            JOIN metrics m ON mdp.metric_id = m.id
+            WHERE m.name = $1
+              AND mdp.attributes @> $2
+              AND mdp.buckets IS NOT NULL
+              AND mdp.timestamp >= $3
+              AND mdp.timestamp <= $4
+            "#;
+        let query = sqlx::query_as::<_, (i64, f64, Option<f64>, Option<f64>)>(totals_sql)
+            .bind(name)
+            .bind(attributes)
+            .bind(start_time)
+            .bind(end_time)
+            .fetch_one(self.pool.postgres());
+        let (count, sum, min, max) = self
+            .pool
+            .run_query(REPOSITORY, "get_histogram_totals", Some(totals_sql), query)
+            .await?;
+
+        Ok(MergedHistogram {
+            buckets: buckets
+                .into_iter()
+                .map(|(boundary, count)| HistogramBucket { boundary, count })
+                .collect(),
+            count,
+            sum,
+            min,
+            max,
+        })
     }
 
     /// Get latest data point for a metric.
     pub async fn get_latest_data_point(&self, metric_id: Uuid) -> StorageResult<MetricDataPoint> {
-        sqlx::query_as::<_, MetricDataPoint>(
-            "SELECT * FROM metric_data_points WHERE metric_id = $1 ORDER BY timestamp DESC LIMIT 1"
-        )
-        .bind(metric_id)
-        .fetch_one(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)
+        let sql = "SELECT * FROM metric_data_points WHERE metric_id = $1 ORDER BY timestamp DESC LIMIT 1";
+        let query = sqlx::query_as::<_, MetricDataPoint>(sql)
+            .bind(metric_id)
+            .fetch_one(self.pool.postgres());
+
+        self.pool.run_query(REPOSITORY, "get_latest_data_point", Some(sql), query).await
     }
 
     /// Query time series data with aggregation.
@@ -185,14 +311,17 @@ impl MetricRepository {
 
         let bucket_interval = format!("{} seconds", query.bucket_size_secs);
 
-        let rows = sqlx::query(&sql)
+        let query = sqlx::query(&sql)
             .bind(bucket_interval)
             .bind(query.metric_id)
             .bind(query.start_time)
             .bind(query.end_time)
-            .fetch_all(self.pool.postgres())
-            .await
-            .map_err(StorageError::from)?;
+            .fetch_all(self.pool.postgres());
+
+        let rows = self
+            .pool
+            .run_query(REPOSITORY, "query_time_series", Some(sql.as_str()), query)
+            .await?;
 
         let mut points = Vec::new();
         for row in rows {
@@ -215,13 +344,15 @@ impl MetricRepository {
         end_time: DateTime<Utc>,
     ) -> StorageResult<Vec<TimeSeriesPoint>> {
         // First get the metric
-        let metric = sqlx::query_as::<_, Metric>(
-            "SELECT * FROM metrics WHERE name = $1 LIMIT 1"
-        )
-        .bind(name)
-        .fetch_one(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)?;
+        let sql = "SELECT * FROM metrics WHERE name = $1 LIMIT 1";
+        let query = sqlx::query_as::<_, Metric>(sql)
+            .bind(name)
+            .fetch_one(self.pool.postgres());
+
+        let metric = self
+            .pool
+            .run_query(REPOSITORY, "get_metric_aggregates", Some(sql), query)
+            .await?;
 
         // Query with aggregation
         let query = TimeSeriesQuery {
@@ -241,8 +372,7 @@ impl MetricRepository {
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> StorageResult<Vec<CostSummary>> {
-        let rows = sqlx::query(
-            r#"
+        let sql = r#"
             SELECT
                 m.service_name,
                 m.name as metric_name,
@@ -256,13 +386,13 @@ impl MetricRepository {
               AND m.name LIKE 'cost%'
             GROUP BY m.service_name, m.name
             ORDER BY total_value DESC
-            "#
-        )
-        .bind(start_time)
-        .bind(end_time)
-        .fetch_all(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)?;
+            "#;
+        let query = sqlx::query(sql)
+            .bind(start_time)
+            .bind(end_time)
+            .fetch_all(self.pool.postgres());
+
+        let rows = self.pool.run_query(REPOSITORY, "get_cost_summary", Some(sql), query).await?;
 
         let mut summaries = Vec::new();
         for row in rows {
@@ -285,8 +415,7 @@ impl MetricRepository {
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> StorageResult<LatencyPercentiles> {
-        let row = sqlx::query(
-            r#"
+        let sql = r#"
             SELECT
                 PERCENTILE_CONT(0.50) WITHIN GROUP (ORDER BY mdp.value) AS p50,
                 PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY mdp.value) AS p95,
@@ -300,14 +429,17 @@ impl MetricRepository {
               AND m.name LIKE '%latency%'
               AND mdp.timestamp >= $2
               AND mdp.timestamp <= $3
-            "#
-        )
-        .bind(service_name)
-        .bind(start_time)
-        .bind(end_time)
-        .fetch_one(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)?;
+            "#;
+        let query = sqlx::query(sql)
+            .bind(service_name)
+            .bind(start_time)
+            .bind(end_time)
+            .fetch_one(self.pool.postgres());
+
+        let row = self
+            .pool
+            .run_query(REPOSITORY, "get_latency_percentiles", Some(sql), query)
+            .await?;
 
         Ok(LatencyPercentiles {
             p50: row.try_get("p50")?,
@@ -321,13 +453,12 @@ impl MetricRepository {
 
     /// Search metrics by name pattern.
     pub async fn search_by_name(&self, pattern: &str) -> StorageResult<Vec<Metric>> {
-        sqlx::query_as::<_, Metric>(
-            "SELECT * FROM metrics WHERE name LIKE $1 ORDER BY name ASC"
-        )
-        .bind(format!("%{}%", pattern))
-        .fetch_all(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)
+        let sql = "SELECT * FROM metrics WHERE name LIKE $1 ORDER BY name ASC";
+        let query = sqlx::query_as::<_, Metric>(sql)
+            .bind(format!("%{}%", pattern))
+            .fetch_all(self.pool.postgres());
+
+        self.pool.run_query(REPOSITORY, "search_by_name", Some(sql), query).await
     }
 
     /// Get metric statistics for a time range.
@@ -337,7 +468,7 @@ impl MetricRepository {
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> StorageResult<MetricStats> {
-        let row = sqlx::query!(
+        let query = sqlx::query!(
             r#"
             SELECT
                 COUNT(*) as "total_points!",
@@ -354,9 +485,9 @@ impl MetricRepository {
             start_time,
             end_time
         )
-        .fetch_one(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)?;
+        .fetch_one(self.pool.postgres());
+
+        let row = self.pool.run_query(REPOSITORY, "get_stats", None, query).await?;
 
         Ok(MetricStats {
             total_points: row.total_points,
@@ -369,13 +500,13 @@ impl MetricRepository {
 
     /// Delete old data points (for data retention).
     pub async fn delete_before(&self, before: DateTime<Utc>) -> StorageResult<u64> {
-        let result = sqlx::query!(
+        let query = sqlx::query!(
             "DELETE FROM metric_data_points WHERE timestamp < $1",
             before
         )
-        .execute(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)?;
+        .execute(self.pool.postgres());
+
+        let result = self.pool.run_query(REPOSITORY, "delete_before", None, query).await?;
 
         Ok(result.rows_affected())
     }
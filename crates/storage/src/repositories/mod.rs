@@ -6,10 +6,22 @@
 pub mod trace;
 pub mod metric;
 pub mod log;
+pub mod log_pattern;
+pub mod model_registry;
+pub mod organization;
+pub mod quarantine;
+pub mod quota;
+pub mod streaming;
 pub mod instrumented;
 
 // Re-exports
 pub use trace::TraceRepository;
 pub use metric::MetricRepository;
 pub use log::LogRepository;
+pub use log_pattern::LogPatternRepository;
+pub use model_registry::ModelRegistryRepository;
+pub use organization::OrganizationRepository;
+pub use quarantine::QuarantineRepository;
+pub use quota::QuotaRepository;
+pub use streaming::StreamingMetricsRepository;
 pub use instrumented::{InstrumentedTraceRepository, InstrumentedMetricRepository, InstrumentedLogRepository};
@@ -0,0 +1,39 @@
+//! Model registry repository for custom and fine-tuned models.
+//!
+//! The ingest host loads the [`RegisteredModel`]s of every organization with
+//! [`ModelRegistryRepository::all`] into the pricing engine's model registry,
+//! so registered pricing applies to incoming spans.
+
+use crate::error::StorageResult;
+use crate::models::RegisteredModel;
+use crate::pool::StoragePool;
+
+/// Repository label used for query metrics and slow-query logs.
+const REPOSITORY: &str = "model_registry_repository";
+
+/// Columns of [`RegisteredModel`]
+const MODEL_COLUMNS: &str = "id, org_id, model_id, provider, display_name, aliases, base_model, \
+     prompt_cost_per_1k, completion_cost_per_1k, cached_prompt_cost_per_1k, updated_at";
+
+/// Repository for registered models.
+#[derive(Clone)]
+pub struct ModelRegistryRepository {
+    pool: StoragePool,
+}
+
+impl ModelRegistryRepository {
+    /// Create a new model registry repository.
+    pub fn new(pool: StoragePool) -> Self {
+        Self { pool }
+    }
+
+    /// Registered models of every organization.
+    pub async fn all(&self) -> StorageResult<Vec<RegisteredModel>> {
+        let sql = format!("SELECT {MODEL_COLUMNS} FROM model_registry ORDER BY org_id, model_id");
+
+        let query = sqlx::query_as::<_, RegisteredModel>(&sql).fetch_all(self.pool.postgres());
+        self.pool
+            .run_query(REPOSITORY, "all", Some(&sql), query)
+            .await
+    }
+}
@@ -0,0 +1,52 @@
+//! Organization repository for per-organization settings.
+//!
+//! The ingest host loads [`OrganizationSettings`] with
+//! [`OrganizationRepository::settings`] to sync the collector's quota and
+//! capture policy processors, and enforces retention with
+//! [`OrganizationRepository::apply_retention`].
+
+use crate::error::StorageResult;
+use crate::models::OrganizationSettings;
+use crate::pool::StoragePool;
+
+/// Repository label used for query metrics and slow-query logs.
+const REPOSITORY: &str = "organization_repository";
+
+/// Columns of [`OrganizationSettings`]
+const SETTINGS_COLUMNS: &str = "org_id, retention_days, capture_policy, spans_per_day_limit, \
+     bytes_per_day_limit, over_quota_action, updated_at";
+
+/// Repository for organization settings.
+#[derive(Clone)]
+pub struct OrganizationRepository {
+    pool: StoragePool,
+}
+
+impl OrganizationRepository {
+    /// Create a new organization repository.
+    pub fn new(pool: StoragePool) -> Self {
+        Self { pool }
+    }
+
+    /// Settings of every organization that has any.
+    pub async fn settings(&self) -> StorageResult<Vec<OrganizationSettings>> {
+        let sql = format!("SELECT {SETTINGS_COLUMNS} FROM organization_settings ORDER BY org_id");
+
+        let query = sqlx::query_as::<_, OrganizationSettings>(&sql).fetch_all(self.pool.postgres());
+        self.pool
+            .run_query(REPOSITORY, "settings", Some(&sql), query)
+            .await
+    }
+
+    /// Delete traces and logs past their organization's retention, returning
+    /// the number of traces deleted. Organizations without a retention
+    /// setting are left to the system retention policies.
+    pub async fn apply_retention(&self) -> StorageResult<i64> {
+        let sql = "SELECT apply_organization_retention()::BIGINT";
+
+        let query = sqlx::query_scalar::<_, i64>(sql).fetch_one(self.pool.postgres());
+        self.pool
+            .run_query(REPOSITORY, "apply_retention", Some(sql), query)
+            .await
+    }
+}
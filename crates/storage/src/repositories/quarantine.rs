@@ -0,0 +1,125 @@
+//! Quarantine repository for quarantined spans and their replay.
+//!
+//! Spans the collector fails to validate or process are inserted into
+//! `rejected_spans`. Replays are requested through the analytics API, which
+//! marks entries `pending`; the ingest pipeline claims them with
+//! [`QuarantineRepository::claim_replays`], runs them through its processors
+//! and records the result with [`QuarantineRepository::complete_replay`].
+
+use crate::error::StorageResult;
+use crate::models::{QuarantinedSpan, ReplayStatus};
+use crate::pool::StoragePool;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Repository label used for query metrics and slow-query logs.
+const REPOSITORY: &str = "quarantine_repository";
+
+/// Repository for quarantined spans.
+#[derive(Clone)]
+pub struct QuarantineRepository {
+    pool: StoragePool,
+}
+
+impl QuarantineRepository {
+    /// Create a new quarantine repository.
+    pub fn new(pool: StoragePool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert quarantined spans, returning the number inserted.
+    pub async fn insert(&self, spans: Vec<QuarantinedSpan>) -> StorageResult<u64> {
+        if spans.is_empty() {
+            return Ok(0);
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO rejected_spans (id, ts, org_id, trace_id, span_id, service_name, \
+             service_version, stage, failure_reason, schema_name, violations, span) ",
+        );
+
+        query_builder.push_values(spans, |mut b, span| {
+            b.push_bind(span.id)
+                .push_bind(span.ts)
+                .push_bind(span.org_id)
+                .push_bind(span.trace_id)
+                .push_bind(span.span_id)
+                .push_bind(span.service_name)
+                .push_bind(span.service_version)
+                .push_bind(span.stage)
+                .push_bind(span.failure_reason)
+                .push_bind(span.schema_name)
+                .push_bind(span.violations)
+                .push_bind(span.span);
+        });
+
+        let query = query_builder.build().execute(self.pool.postgres());
+        let result = self
+            .pool
+            .run_query(REPOSITORY, "insert", None, query)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Claim up to `limit` spans requested for replay, oldest request first.
+    ///
+    /// Claimed spans move to `replaying`, so concurrent callers never claim
+    /// the same span.
+    pub async fn claim_replays(&self, limit: i64) -> StorageResult<Vec<QuarantinedSpan>> {
+        let sql = r#"
+            WITH claimed AS (
+                SELECT id, ts FROM rejected_spans
+                WHERE replay_status = 'pending'
+                ORDER BY replay_requested_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE rejected_spans r
+            SET replay_status = 'replaying',
+                replay_attempts = r.replay_attempts + 1
+            FROM claimed
+            WHERE r.id = claimed.id AND r.ts = claimed.ts
+            RETURNING r.*
+            "#;
+        let query = sqlx::query_as::<_, QuarantinedSpan>(sql)
+            .bind(limit)
+            .fetch_all(self.pool.postgres());
+
+        self.pool
+            .run_query(REPOSITORY, "claim_replays", Some(sql), query)
+            .await
+    }
+
+    /// Record the result of a replay: `Ok` once the span was exported, or the
+    /// reason it failed again.
+    pub async fn complete_replay(
+        &self,
+        id: Uuid,
+        ts: DateTime<Utc>,
+        result: Result<(), String>,
+    ) -> StorageResult<()> {
+        let (status, error) = match result {
+            Ok(()) => (ReplayStatus::Replayed, None),
+            Err(reason) => (ReplayStatus::Failed, Some(reason)),
+        };
+
+        let sql = r#"
+            UPDATE rejected_spans
+            SET replay_status = $3,
+                replay_error = $4,
+                replayed_at = NOW()
+            WHERE id = $1 AND ts = $2
+            "#;
+        let query = sqlx::query(sql)
+            .bind(id)
+            .bind(ts)
+            .bind(status.as_str())
+            .bind(error)
+            .execute(self.pool.postgres());
+
+        self.pool
+            .run_query(REPOSITORY, "complete_replay", Some(sql), query)
+            .await?;
+        Ok(())
+    }
+}
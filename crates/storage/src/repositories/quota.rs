@@ -0,0 +1,104 @@
+//! Quota repository for per-organization ingestion usage.
+//!
+//! The collector's quota processor drains its usage as [`IngestionUsage`]
+//! rows; the ingest host writes them with [`QuotaRepository::upsert`] and
+//! loads the totals of all replicas back with
+//! [`QuotaRepository::usage_for_day`], so quotas apply across replicas.
+
+use crate::error::StorageResult;
+use crate::models::IngestionUsage;
+use crate::pool::StoragePool;
+use chrono::NaiveDate;
+
+/// Repository label used for query metrics and slow-query logs.
+const REPOSITORY: &str = "quota_repository";
+
+/// Columns of [`IngestionUsage`]
+const USAGE_COLUMNS: &str = "day, org_id, span_count, byte_count, throttled_spans, \
+     spans_per_day_limit, bytes_per_day_limit, over_quota_action, updated_at";
+
+/// Repository for ingestion usage.
+#[derive(Clone)]
+pub struct QuotaRepository {
+    pool: StoragePool,
+}
+
+impl QuotaRepository {
+    /// Create a new quota repository.
+    pub fn new(pool: StoragePool) -> Self {
+        Self { pool }
+    }
+
+    /// Add usage to its rows, creating missing rows, and record the limits
+    /// in force. Returns the number of rows written.
+    ///
+    /// Each day and organization may appear once per call, which holds for
+    /// one drain of the processor.
+    pub async fn upsert(&self, usage: Vec<IngestionUsage>) -> StorageResult<u64> {
+        if usage.is_empty() {
+            return Ok(0);
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO ingestion_usage AS u (day, org_id, span_count, byte_count, \
+             throttled_spans, spans_per_day_limit, bytes_per_day_limit, over_quota_action) ",
+        );
+
+        query_builder.push_values(usage, |mut b, row| {
+            b.push_bind(row.day)
+                .push_bind(row.org_id)
+                .push_bind(row.span_count)
+                .push_bind(row.byte_count)
+                .push_bind(row.throttled_spans)
+                .push_bind(row.spans_per_day_limit)
+                .push_bind(row.bytes_per_day_limit)
+                .push_bind(row.over_quota_action);
+        });
+
+        query_builder.push(
+            r#"
+            ON CONFLICT (day, org_id) DO UPDATE SET
+                span_count = u.span_count + EXCLUDED.span_count,
+                byte_count = u.byte_count + EXCLUDED.byte_count,
+                throttled_spans = u.throttled_spans + EXCLUDED.throttled_spans,
+                spans_per_day_limit = EXCLUDED.spans_per_day_limit,
+                bytes_per_day_limit = EXCLUDED.bytes_per_day_limit,
+                over_quota_action = EXCLUDED.over_quota_action,
+                updated_at = NOW()
+            "#,
+        );
+
+        let query = query_builder.build().execute(self.pool.postgres());
+        let result = self
+            .pool
+            .run_query(REPOSITORY, "upsert", None, query)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Usage of every organization on `day`.
+    pub async fn usage_for_day(&self, day: NaiveDate) -> StorageResult<Vec<IngestionUsage>> {
+        let sql = format!("SELECT {USAGE_COLUMNS} FROM ingestion_usage WHERE day = $1");
+
+        let query = sqlx::query_as::<_, IngestionUsage>(&sql)
+            .bind(day)
+            .fetch_all(self.pool.postgres());
+        self.pool
+            .run_query(REPOSITORY, "usage_for_day", Some(&sql), query)
+            .await
+    }
+
+    /// Usage of an organization on `day`, if it ingested anything.
+    pub async fn get(&self, org_id: &str, day: NaiveDate) -> StorageResult<Option<IngestionUsage>> {
+        let sql =
+            format!("SELECT {USAGE_COLUMNS} FROM ingestion_usage WHERE day = $1 AND org_id = $2");
+
+        let query = sqlx::query_as::<_, IngestionUsage>(&sql)
+            .bind(day)
+            .bind(org_id)
+            .fetch_optional(self.pool.postgres());
+        self.pool
+            .run_query(REPOSITORY, "get", Some(&sql), query)
+            .await
+    }
+}
@@ -0,0 +1,93 @@
+//! Streaming metrics repository for per-minute pre-aggregated metrics.
+//!
+//! The collector's streaming aggregation processor drains closed minutes as
+//! [`StreamingMetric`] rows; the ingest host writes them with
+//! [`StreamingMetricsRepository::upsert`]. Rows are merged additively, so
+//! flushes from several collectors and late spans add to the same row.
+
+use crate::error::StorageResult;
+use crate::models::StreamingMetric;
+use crate::pool::StoragePool;
+
+/// Repository label used for query metrics and slow-query logs.
+const REPOSITORY: &str = "streaming_metrics_repository";
+
+/// Repository for per-minute streaming metrics.
+#[derive(Clone)]
+pub struct StreamingMetricsRepository {
+    pool: StoragePool,
+}
+
+impl StreamingMetricsRepository {
+    /// Create a new streaming metrics repository.
+    pub fn new(pool: StoragePool) -> Self {
+        Self { pool }
+    }
+
+    /// Add metrics to their rows, creating missing rows. Returns the number
+    /// of rows written.
+    ///
+    /// Each key (minute, organization, provider, model) may appear once per
+    /// call, which holds for one drain of the processor. Latency buckets are
+    /// added element-wise, so all collectors must use the same bounds.
+    pub async fn upsert(&self, metrics: Vec<StreamingMetric>) -> StorageResult<u64> {
+        if metrics.is_empty() {
+            return Ok(0);
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO llm_metrics_streaming_1min AS m (bucket, org_id, provider, model, \
+             request_count, error_count, prompt_tokens, completion_tokens, total_tokens, \
+             total_cost_usd, sum_duration_ms, sum_duration_ms_squared, min_duration_ms, \
+             max_duration_ms, latency_bounds_ms, latency_bucket_counts) ",
+        );
+
+        query_builder.push_values(metrics, |mut b, metric| {
+            b.push_bind(metric.bucket)
+                .push_bind(metric.org_id)
+                .push_bind(metric.provider)
+                .push_bind(metric.model)
+                .push_bind(metric.request_count)
+                .push_bind(metric.error_count)
+                .push_bind(metric.prompt_tokens)
+                .push_bind(metric.completion_tokens)
+                .push_bind(metric.total_tokens)
+                .push_bind(metric.total_cost_usd)
+                .push_bind(metric.sum_duration_ms)
+                .push_bind(metric.sum_duration_ms_squared)
+                .push_bind(metric.min_duration_ms)
+                .push_bind(metric.max_duration_ms)
+                .push_bind(metric.latency_bounds_ms)
+                .push_bind(metric.latency_bucket_counts);
+        });
+
+        query_builder.push(
+            r#"
+            ON CONFLICT (bucket, org_id, provider, model) DO UPDATE SET
+                request_count = m.request_count + EXCLUDED.request_count,
+                error_count = m.error_count + EXCLUDED.error_count,
+                prompt_tokens = m.prompt_tokens + EXCLUDED.prompt_tokens,
+                completion_tokens = m.completion_tokens + EXCLUDED.completion_tokens,
+                total_tokens = m.total_tokens + EXCLUDED.total_tokens,
+                total_cost_usd = m.total_cost_usd + EXCLUDED.total_cost_usd,
+                sum_duration_ms = m.sum_duration_ms + EXCLUDED.sum_duration_ms,
+                sum_duration_ms_squared = m.sum_duration_ms_squared + EXCLUDED.sum_duration_ms_squared,
+                min_duration_ms = LEAST(m.min_duration_ms, EXCLUDED.min_duration_ms),
+                max_duration_ms = GREATEST(m.max_duration_ms, EXCLUDED.max_duration_ms),
+                latency_bucket_counts = ARRAY(
+                    SELECT COALESCE(a, 0) + COALESCE(b, 0)
+                    FROM unnest(m.latency_bucket_counts, EXCLUDED.latency_bucket_counts)
+                        WITH ORDINALITY AS counts(a, b, i)
+                    ORDER BY i
+                )
+            "#,
+        );
+
+        let query = query_builder.build().execute(self.pool.postgres());
+        let result = self
+            .pool
+            .run_query(REPOSITORY, "upsert", None, query)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
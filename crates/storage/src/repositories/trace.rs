@@ -1,21 +1,45 @@
 //! Trace repository for querying trace data.
 
+use crate::compression::{decompress_json, decompress_json_opt};
+use crate::encryption::FieldEncryptor;
 use crate::error::{StorageError, StorageResult};
 use crate::models::{Trace, TraceSpan, TraceEvent};
 use crate::pool::StoragePool;
 use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use std::sync::Arc;
 use uuid::Uuid;
 
+/// Repository label used for query metrics and slow-query logs.
+const REPOSITORY: &str = "trace_repository";
+
 /// Repository for querying trace data.
+///
+/// Encrypted span attributes, span events and event attributes are returned
+/// as stored (see [`crate::encryption`]) unless the repository was created
+/// with [`TraceRepository::with_decryption`].
 #[derive(Clone)]
 pub struct TraceRepository {
     pool: StoragePool,
+    encryptor: Option<Arc<FieldEncryptor>>,
 }
 
 impl TraceRepository {
     /// Create a new trace repository.
     pub fn new(pool: StoragePool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            encryptor: None,
+        }
+    }
+
+    /// Decrypt encrypted fields with the pool's encryptor.
+    ///
+    /// Only for callers authorized to read prompts and completions; without
+    /// an encryptor on the pool this has no effect.
+    pub fn with_decryption(mut self) -> Self {
+        self.encryptor = self.pool.encryptor().cloned();
+        self
     }
 
     /// Get a trace by its ID.
@@ -28,20 +52,47 @@ impl TraceRepository {
     ///
     /// Returns `StorageError::NotFound` if the trace doesn't exist.
     pub async fn get_by_id(&self, id: Uuid) -> StorageResult<Trace> {
-        sqlx::query_as::<_, Trace>("SELECT * FROM traces WHERE id = $1")
+        let sql = "SELECT * FROM traces WHERE id = $1";
+        let query = sqlx::query_as::<_, Trace>(sql)
             .bind(id)
-            .fetch_one(self.pool.postgres())
-            .await
-            .map_err(StorageError::from)
+            .fetch_one(self.pool.postgres());
+
+        self.pool.run_query(REPOSITORY, "get_by_id", Some(sql), query).await
     }
 
     /// Get a trace by its trace ID (hex format).
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Archived` with the archive directory if the
+    /// trace was moved to object storage (see [`crate::lifecycle`]), and
+    /// `StorageError::NotFound` if it doesn't exist.
     pub async fn get_by_trace_id(&self, trace_id: &str) -> StorageResult<Trace> {
-        sqlx::query_as::<_, Trace>("SELECT * FROM traces WHERE trace_id = $1 LIMIT 1")
+        let sql = "SELECT * FROM traces WHERE trace_id = $1 LIMIT 1";
+        let query = sqlx::query_as::<_, Trace>(sql)
             .bind(trace_id)
-            .fetch_one(self.pool.postgres())
-            .await
-            .map_err(StorageError::from)
+            .fetch_one(self.pool.postgres());
+
+        match self.pool.run_query(REPOSITORY, "get_by_trace_id", Some(sql), query).await {
+            Err(e) if e.is_not_found() => match self.get_archived(trace_id).await? {
+                Some(archived) => Err(StorageError::Archived(format!(
+                    "trace {} is stored in {}",
+                    trace_id, archived.location
+                ))),
+                None => Err(e),
+            },
+            result => result,
+        }
+    }
+
+    /// Get where an archived trace is stored, if it was archived.
+    pub async fn get_archived(&self, trace_id: &str) -> StorageResult<Option<ArchivedTrace>> {
+        let sql = "SELECT trace_id, start_time, location, archived_at FROM archived_traces WHERE trace_id = $1";
+        let query = sqlx::query_as::<_, ArchivedTrace>(sql)
+            .bind(trace_id)
+            .fetch_optional(self.pool.postgres());
+
+        self.pool.run_query(REPOSITORY, "get_archived", Some(sql), query).await
     }
 
     /// Get a trace with all its spans.
@@ -93,6 +144,11 @@ impl TraceRepository {
             bind_index += 1;
         }
 
+        if filters.attributes.is_some() {
+            query.push_str(&format!(" AND attributes @> ${}", bind_index));
+            bind_index += 1;
+        }
+
         query.push_str(" ORDER BY start_time DESC");
 
         if let Some(limit) = filters.limit {
@@ -125,6 +181,9 @@ impl TraceRepository {
         if let Some(max_duration) = filters.max_duration_us {
             q = q.bind(max_duration);
         }
+        if let Some(attributes) = &filters.attributes {
+            q = q.bind(attributes);
+        }
         if let Some(limit) = filters.limit {
             q = q.bind(limit);
         }
@@ -132,9 +191,9 @@ impl TraceRepository {
             q = q.bind(offset);
         }
 
-        q.fetch_all(self.pool.postgres())
+        self.pool
+            .run_query(REPOSITORY, "list", Some(query.as_str()), q.fetch_all(self.pool.postgres()))
             .await
-            .map_err(StorageError::from)
     }
 
     /// Get traces for a time range with pagination.
@@ -155,33 +214,43 @@ impl TraceRepository {
 
     /// Get all spans for a trace.
     pub async fn get_spans(&self, trace_id: Uuid) -> StorageResult<Vec<TraceSpan>> {
-        sqlx::query_as::<_, TraceSpan>(
-            "SELECT * FROM trace_spans WHERE trace_id = $1 ORDER BY start_time ASC"
-        )
-        .bind(trace_id)
-        .fetch_all(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)
+        let sql = "SELECT * FROM trace_spans WHERE trace_id = $1 ORDER BY start_time ASC";
+        let query = sqlx::query_as::<_, TraceSpan>(sql)
+            .bind(trace_id)
+            .fetch_all(self.pool.postgres());
+
+        let spans = self.pool.run_query(REPOSITORY, "get_spans", Some(sql), query).await?;
+        self.expand_spans(spans).await
     }
 
     /// Get a specific span by ID.
     pub async fn get_span_by_id(&self, span_id: Uuid) -> StorageResult<TraceSpan> {
-        sqlx::query_as::<_, TraceSpan>("SELECT * FROM trace_spans WHERE id = $1")
+        let sql = "SELECT * FROM trace_spans WHERE id = $1";
+        let query = sqlx::query_as::<_, TraceSpan>(sql)
             .bind(span_id)
-            .fetch_one(self.pool.postgres())
-            .await
-            .map_err(StorageError::from)
+            .fetch_one(self.pool.postgres());
+
+        let span = self.pool.run_query(REPOSITORY, "get_span_by_id", Some(sql), query).await?;
+        self.expand_span(span).await
     }
 
     /// Get all events for a span.
     pub async fn get_events(&self, span_id: Uuid) -> StorageResult<Vec<TraceEvent>> {
-        sqlx::query_as::<_, TraceEvent>(
-            "SELECT * FROM trace_events WHERE span_id = $1 ORDER BY timestamp ASC"
-        )
-        .bind(span_id)
-        .fetch_all(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)
+        let sql = "SELECT * FROM trace_events WHERE span_id = $1 ORDER BY timestamp ASC";
+        let query = sqlx::query_as::<_, TraceEvent>(sql)
+            .bind(span_id)
+            .fetch_all(self.pool.postgres());
+
+        let events = self.pool.run_query(REPOSITORY, "get_events", Some(sql), query).await?;
+        let mut expanded = Vec::with_capacity(events.len());
+        for mut event in events {
+            if let Some(encryptor) = &self.encryptor {
+                encryptor.decrypt_event(&mut event).await?;
+            }
+            event.attributes = decompress_json(event.attributes)?;
+            expanded.push(event);
+        }
+        Ok(expanded)
     }
 
     /// Search traces by service name and time range.
@@ -191,22 +260,164 @@ impl TraceRepository {
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> StorageResult<Vec<Trace>> {
-        sqlx::query_as::<_, Trace>(
-            r#"
+        let sql = r#"
             SELECT * FROM traces
             WHERE service_name = $1
               AND start_time >= $2
               AND start_time <= $3
             ORDER BY start_time DESC
             LIMIT 100
-            "#
-        )
-        .bind(service_name)
-        .bind(start_time)
-        .bind(end_time)
-        .fetch_all(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)
+            "#;
+        let query = sqlx::query_as::<_, Trace>(sql)
+            .bind(service_name)
+            .bind(start_time)
+            .bind(end_time)
+            .fetch_all(self.pool.postgres());
+
+        self.pool.run_query(REPOSITORY, "search_by_service", Some(sql), query).await
+    }
+
+    /// Find traces whose attributes contain `key` with exactly `value`.
+    ///
+    /// Uses JSONB containment (`@>`), which is served by the GIN index on
+    /// `attributes`. Attribute keys are matched literally, so dotted OTel
+    /// keys such as `gen_ai.system` work as-is.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use llm_observatory_storage::repositories::TraceRepository;
+    /// # async fn example(repo: TraceRepository) -> Result<(), Box<dyn std::error::Error>> {
+    /// let traces = repo
+    ///     .find_by_attribute("gen_ai.system", serde_json::json!("openai"), 100)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn find_by_attribute(
+        &self,
+        key: &str,
+        value: serde_json::Value,
+        limit: i64,
+    ) -> StorageResult<Vec<Trace>> {
+        let filters = TraceFilters {
+            limit: Some(limit),
+            ..Default::default()
+        };
+        self.find_by_attributes(attribute_object(key, value), filters).await
+    }
+
+    /// Find traces whose attributes contain every key/value pair in `attributes`.
+    ///
+    /// `attributes` must be a JSON object; it is combined with the other filters.
+    pub async fn find_by_attributes(
+        &self,
+        attributes: serde_json::Value,
+        filters: TraceFilters,
+    ) -> StorageResult<Vec<Trace>> {
+        validate_attribute_filter(&attributes)?;
+
+        let mut filters = filters;
+        filters.attributes = Some(attributes);
+        self.list(filters).await
+    }
+
+    /// Find traces that have an attribute `key`, regardless of its value.
+    pub async fn find_by_attribute_key(&self, key: &str, limit: i64) -> StorageResult<Vec<Trace>> {
+        let sql = "SELECT * FROM traces WHERE attributes ? $1 ORDER BY start_time DESC LIMIT $2";
+        let query = sqlx::query_as::<_, Trace>(sql)
+            .bind(key)
+            .bind(limit)
+            .fetch_all(self.pool.postgres());
+
+        self.pool
+            .run_query(REPOSITORY, "find_by_attribute_key", Some(sql), query)
+            .await
+    }
+
+    /// Find spans whose attributes contain `key` with exactly `value`.
+    ///
+    /// Encrypted attributes never match.
+    pub async fn find_spans_by_attribute(
+        &self,
+        key: &str,
+        value: serde_json::Value,
+        limit: i64,
+    ) -> StorageResult<Vec<TraceSpan>> {
+        let sql = "SELECT * FROM trace_spans WHERE attributes @> $1 ORDER BY start_time DESC LIMIT $2";
+        let query = sqlx::query_as::<_, TraceSpan>(sql)
+            .bind(attribute_object(key, value))
+            .bind(limit)
+            .fetch_all(self.pool.postgres());
+
+        let spans = self
+            .pool
+            .run_query(REPOSITORY, "find_spans_by_attribute", Some(sql), query)
+            .await?;
+        self.expand_spans(spans).await
+    }
+
+    /// Find spans with a link into the trace `trace_id` (hex format),
+    /// optionally only links to its span `span_id`.
+    ///
+    /// In agent fan-out the sub-agents' spans link back to the span that
+    /// started them, so this finds the work a trace fanned out. Uses JSONB
+    /// containment on `links`, served by its GIN index.
+    pub async fn find_spans_linking_to(
+        &self,
+        trace_id: &str,
+        span_id: Option<&str>,
+        limit: i64,
+    ) -> StorageResult<Vec<TraceSpan>> {
+        let sql = "SELECT * FROM trace_spans WHERE links @> $1 ORDER BY start_time ASC LIMIT $2";
+        let query = sqlx::query_as::<_, TraceSpan>(sql)
+            .bind(link_filter(trace_id, span_id))
+            .bind(limit)
+            .fetch_all(self.pool.postgres());
+
+        let spans = self
+            .pool
+            .run_query(REPOSITORY, "find_spans_linking_to", Some(sql), query)
+            .await?;
+        self.expand_spans(spans).await
+    }
+
+    /// Find the traces linked to the trace `trace_id` (hex format), in
+    /// either direction: traces its spans link to, and traces with spans
+    /// linking to it. Ordered by start time.
+    pub async fn find_linked_traces(&self, trace_id: &str, limit: i64) -> StorageResult<Vec<Trace>> {
+        // Links compressed before migration 042 are envelope objects
+        let sql = r#"
+            WITH outgoing AS (
+                SELECT link->>'trace_id' AS trace_id
+                FROM traces t
+                JOIN trace_spans s ON s.trace_id = t.id
+                CROSS JOIN LATERAL jsonb_array_elements(
+                    CASE WHEN jsonb_typeof(s.links) = 'array' THEN s.links ELSE '[]'::jsonb END
+                ) AS link
+                WHERE t.trace_id = $1
+            ),
+            incoming AS (
+                SELECT s.trace_id AS id
+                FROM trace_spans s
+                WHERE s.links @> $2
+            )
+            SELECT * FROM traces
+            WHERE trace_id <> $1
+              AND (trace_id IN (SELECT trace_id FROM outgoing)
+                   OR id IN (SELECT id FROM incoming))
+            ORDER BY start_time ASC
+            LIMIT $3
+            "#;
+        let query = sqlx::query_as::<_, Trace>(sql)
+            .bind(trace_id)
+            .bind(link_filter(trace_id, None))
+            .bind(limit)
+            .fetch_all(self.pool.postgres());
+
+        self.pool
+            .run_query(REPOSITORY, "find_linked_traces", Some(sql), query)
+            .await
     }
 
     /// Search traces with errors.
@@ -265,7 +476,7 @@ impl TraceRepository {
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> StorageResult<TraceStats> {
-        let row = sqlx::query!(
+        let query = sqlx::query!(
             r#"
             SELECT
                 COUNT(*) as "total_traces!",
@@ -280,9 +491,9 @@ impl TraceRepository {
             start_time,
             end_time
         )
-        .fetch_one(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)?;
+        .fetch_one(self.pool.postgres());
+
+        let row = self.pool.run_query(REPOSITORY, "get_stats", None, query).await?;
 
         Ok(TraceStats {
             total_traces: row.total_traces,
@@ -296,16 +507,114 @@ impl TraceRepository {
 
     /// Delete old traces (for data retention).
     pub async fn delete_before(&self, before: DateTime<Utc>) -> StorageResult<u64> {
-        let result = sqlx::query!(
+        let query = sqlx::query!(
             "DELETE FROM traces WHERE start_time < $1",
             before
         )
-        .execute(self.pool.postgres())
-        .await
-        .map_err(StorageError::from)?;
+        .execute(self.pool.postgres());
+
+        let result = self.pool.run_query(REPOSITORY, "delete_before", None, query).await?;
 
         Ok(result.rows_affected())
     }
+
+    /// Re-wrap the data keys of up to `batch_size` spans and events whose
+    /// encrypted fields do not use the active key-encryption key.
+    ///
+    /// Run after rotating keys until it returns 0; the old key can then be
+    /// retired. Returns the number of rows updated.
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if the pool has no encryptor.
+    pub async fn rewrap_encrypted(&self, batch_size: i64) -> StorageResult<u64> {
+        let encryptor = self
+            .pool
+            .encryptor()
+            .ok_or_else(|| StorageError::validation("Encryption is not configured"))?;
+        let active_key_id = encryptor.provider().active_key_id().to_string();
+
+        let sql = r#"
+            SELECT * FROM trace_spans
+            WHERE (events ? '$encrypted' AND events->>'kid' <> $1)
+               OR EXISTS (
+                   SELECT 1 FROM jsonb_each(attributes) AS a(key, value)
+                   WHERE jsonb_typeof(a.value) = 'object'
+                     AND a.value ? '$encrypted'
+                     AND a.value->>'kid' <> $1
+               )
+            LIMIT $2
+            "#;
+        let query = sqlx::query_as::<_, TraceSpan>(sql)
+            .bind(&active_key_id)
+            .bind(batch_size)
+            .fetch_all(self.pool.postgres());
+        let spans = self.pool.run_query(REPOSITORY, "rewrap_spans", Some(sql), query).await?;
+
+        let mut updated = 0;
+        for mut span in spans {
+            if !encryptor.rewrap_span(&mut span).await? {
+                continue;
+            }
+            let sql = "UPDATE trace_spans SET attributes = $2, events = $3 WHERE id = $1";
+            let query = sqlx::query(sql)
+                .bind(span.id)
+                .bind(&span.attributes)
+                .bind(&span.events)
+                .execute(self.pool.postgres());
+            self.pool.run_query(REPOSITORY, "rewrap_spans", Some(sql), query).await?;
+            updated += 1;
+        }
+
+        let sql = r#"
+            SELECT * FROM trace_events
+            WHERE attributes ? '$encrypted' AND attributes->>'kid' <> $1
+            LIMIT $2
+            "#;
+        let query = sqlx::query_as::<_, TraceEvent>(sql)
+            .bind(&active_key_id)
+            .bind(batch_size)
+            .fetch_all(self.pool.postgres());
+        let events = self.pool.run_query(REPOSITORY, "rewrap_events", Some(sql), query).await?;
+
+        for mut event in events {
+            if !encryptor.rewrap_event(&mut event).await? {
+                continue;
+            }
+            let sql = "UPDATE trace_events SET attributes = $2 WHERE id = $1";
+            let query = sqlx::query(sql)
+                .bind(event.id)
+                .bind(&event.attributes)
+                .execute(self.pool.postgres());
+            self.pool.run_query(REPOSITORY, "rewrap_events", Some(sql), query).await?;
+            updated += 1;
+        }
+
+        if updated > 0 {
+            tracing::info!(
+                rows = updated,
+                key_id = %active_key_id,
+                "Re-wrapped payload data keys"
+            );
+        }
+        Ok(updated)
+    }
+
+    /// Decrypt (if enabled) and decompress a span.
+    async fn expand_span(&self, mut span: TraceSpan) -> StorageResult<TraceSpan> {
+        if let Some(encryptor) = &self.encryptor {
+            encryptor.decrypt_span(&mut span).await?;
+        }
+        decompress_span(span)
+    }
+
+    async fn expand_spans(&self, spans: Vec<TraceSpan>) -> StorageResult<Vec<TraceSpan>> {
+        let mut expanded = Vec::with_capacity(spans.len());
+        for span in spans {
+            expanded.push(self.expand_span(span).await?);
+        }
+        Ok(expanded)
+    }
 }
 
 /// Filters for querying traces.
@@ -329,6 +638,9 @@ pub struct TraceFilters {
     /// Maximum duration in microseconds
     pub max_duration_us: Option<i64>,
 
+    /// Attributes that must be contained in the trace attributes (JSON object)
+    pub attributes: Option<serde_json::Value>,
+
     /// Limit number of results
     pub limit: Option<i64>,
 
@@ -336,6 +648,57 @@ pub struct TraceFilters {
     pub offset: Option<i64>,
 }
 
+/// Build a single-key JSON object for containment queries.
+fn attribute_object(key: &str, value: serde_json::Value) -> serde_json::Value {
+    let mut map = serde_json::Map::new();
+    map.insert(key.to_string(), value);
+    serde_json::Value::Object(map)
+}
+
+/// Build a link array for containment queries on `links`.
+fn link_filter(trace_id: &str, span_id: Option<&str>) -> serde_json::Value {
+    let mut link = serde_json::Map::new();
+    link.insert("trace_id".to_string(), serde_json::json!(trace_id));
+    if let Some(span_id) = span_id {
+        link.insert("span_id".to_string(), serde_json::json!(span_id));
+    }
+    serde_json::Value::Array(vec![serde_json::Value::Object(link)])
+}
+
+/// Ensure an attribute filter is a non-empty JSON object.
+fn validate_attribute_filter(attributes: &serde_json::Value) -> StorageResult<()> {
+    match attributes {
+        serde_json::Value::Object(map) if !map.is_empty() => Ok(()),
+        _ => Err(StorageError::validation(
+            "Attribute filter must be a non-empty JSON object",
+        )),
+    }
+}
+
+/// Expand span events (and links, before migration 042) stored compressed
+/// by the trace writer.
+fn decompress_span(mut span: TraceSpan) -> StorageResult<TraceSpan> {
+    span.events = decompress_json_opt(span.events)?;
+    span.links = decompress_json_opt(span.links)?;
+    Ok(span)
+}
+
+/// A trace moved to object storage.
+#[derive(Debug, Clone, FromRow)]
+pub struct ArchivedTrace {
+    /// Trace ID (hex format)
+    pub trace_id: String,
+
+    /// Start time of the trace
+    pub start_time: DateTime<Utc>,
+
+    /// Directory of the Parquet files holding the trace
+    pub location: String,
+
+    /// When the trace was archived
+    pub archived_at: DateTime<Utc>,
+}
+
 /// Statistics about traces.
 #[derive(Debug, Clone)]
 pub struct TraceStats {
@@ -369,5 +732,34 @@ mod tests {
         let filters = TraceFilters::default();
         assert!(filters.service_name.is_none());
         assert!(filters.limit.is_none());
+        assert!(filters.attributes.is_none());
+    }
+
+    #[test]
+    fn test_attribute_object() {
+        let obj = attribute_object("gen_ai.system", serde_json::json!("openai"));
+        assert_eq!(obj, serde_json::json!({"gen_ai.system": "openai"}));
+    }
+
+    #[test]
+    fn test_link_filter() {
+        assert_eq!(
+            link_filter("0af7651916cd43dd8448eb211c80319c", None),
+            serde_json::json!([{"trace_id": "0af7651916cd43dd8448eb211c80319c"}])
+        );
+        assert_eq!(
+            link_filter("0af7651916cd43dd8448eb211c80319c", Some("b7ad6b7169203331")),
+            serde_json::json!([{
+                "trace_id": "0af7651916cd43dd8448eb211c80319c",
+                "span_id": "b7ad6b7169203331"
+            }])
+        );
+    }
+
+    #[test]
+    fn test_validate_attribute_filter() {
+        assert!(validate_attribute_filter(&serde_json::json!({"env": "prod"})).is_ok());
+        assert!(validate_attribute_filter(&serde_json::json!({})).is_err());
+        assert!(validate_attribute_filter(&serde_json::json!(["env"])).is_err());
     }
 }
@@ -0,0 +1,500 @@
+//! Data residency: routing each organization's writes to its region.
+//!
+//! A [`RegionRouter`] holds one [`StoragePool`] per region of the
+//! [`ResidencyConfig`](crate::config::ResidencyConfig): the `postgres` target
+//! for the primary region and one pool per entry of `regions`. The ingest
+//! host writes the collector's spans with [`RegionalTraceWriter`], which
+//! splits them by their `org_id` attribute, and takes the pool of an
+//! organization's region with [`RegionRouter::pool_for_org`] for the other
+//! writers and repositories.
+//!
+//! Regions fail independently. Each region's health is checked every
+//! `health_check_interval_secs`, and writes for an unhealthy region are
+//! rejected with a retryable [`StorageError::ConnectionError`] instead of
+//! waiting on its database. They are never redirected to another region. Only
+//! the primary region must be reachable at startup; other regions that can't
+//! be reached are connected by a later health check.
+
+use crate::config::{PostgresConfig, ResidencyConfig, StorageConfig};
+use crate::error::{StorageError, StorageResult};
+use crate::metrics::StorageMetrics;
+use crate::models::TraceSpan;
+use crate::pool::StoragePool;
+use crate::shutdown::ShutdownReport;
+use crate::writers::trace::TraceWriter;
+use futures::future::join_all;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+/// Span attribute holding the organization ID
+pub const ORG_ID_ATTRIBUTE: &str = "org_id";
+
+/// Health of one region.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegionHealth {
+    /// Region name
+    pub region: String,
+
+    /// Whether this is the primary region
+    pub primary: bool,
+
+    /// Whether a pool to the region's database is open
+    pub connected: bool,
+
+    /// Whether the region accepts writes
+    pub healthy: bool,
+
+    /// Error of the last failed connection attempt or health check
+    pub last_error: Option<String>,
+}
+
+/// Target, pool and health of one region.
+struct Region {
+    name: String,
+    config: StorageConfig,
+    pool: RwLock<Option<StoragePool>>,
+    healthy: AtomicBool,
+    last_error: Mutex<Option<String>>,
+}
+
+impl Region {
+    fn new(name: &str, config: StorageConfig) -> Self {
+        Self {
+            name: name.to_string(),
+            config,
+            pool: RwLock::new(None),
+            healthy: AtomicBool::new(false),
+            last_error: Mutex::new(None),
+        }
+    }
+
+    fn pool(&self) -> Option<StoragePool> {
+        self.pool.read().unwrap().clone()
+    }
+
+    fn set_pool(&self, pool: StoragePool) {
+        *self.pool.write().unwrap() = Some(pool);
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    /// Record the outcome of a connection attempt or health check.
+    fn record_health(&self, result: Result<(), String>) {
+        let healthy = result.is_ok();
+        if self.healthy.swap(healthy, Ordering::Relaxed) != healthy {
+            match &result {
+                Ok(()) => tracing::info!(region = %self.name, "Storage region is healthy"),
+                Err(e) => tracing::warn!(region = %self.name, "Storage region is unhealthy: {}", e),
+            }
+        }
+        *self.last_error.lock().unwrap() = result.err();
+        StorageMetrics::new().update_region_health(&self.name, healthy);
+    }
+
+    /// Open the region's pool if it isn't open yet, then check its health.
+    async fn check(&self) {
+        let pool = match self.pool() {
+            Some(pool) => pool,
+            None => match StoragePool::new(self.config.clone()).await {
+                Ok(pool) => {
+                    tracing::info!(region = %self.name, "Connected to storage region");
+                    self.set_pool(pool.clone());
+                    pool
+                }
+                Err(e) => {
+                    self.record_health(Err(e.to_string()));
+                    return;
+                }
+            },
+        };
+
+        let result = match pool.health_check().await {
+            Ok(result) if result.postgres_healthy => Ok(()),
+            Ok(result) => Err(format!(
+                "PostgreSQL unavailable (circuit {})",
+                result.circuit_state.as_str()
+            )),
+            Err(e) => Err(e.to_string()),
+        };
+        self.record_health(result);
+    }
+
+    fn health(&self, primary: bool) -> RegionHealth {
+        RegionHealth {
+            region: self.name.clone(),
+            primary,
+            connected: self.pool.read().unwrap().is_some(),
+            healthy: self.is_healthy(),
+            last_error: self.last_error.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// Pools of the data residency regions and the organizations pinned to them.
+///
+/// Clones share the pools. Health checks stop once every clone is dropped.
+#[derive(Clone)]
+pub struct RegionRouter {
+    primary_region: String,
+    regions: Arc<HashMap<String, Region>>,
+    org_regions: Arc<HashMap<String, String>>,
+}
+
+impl RegionRouter {
+    /// Connect to every region and start the region health checks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the residency configuration is invalid or the
+    /// primary region can't be reached. Other regions that can't be reached
+    /// start unhealthy.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use llm_observatory_storage::{RegionRouter, StorageConfig};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let router = RegionRouter::connect(StorageConfig::from_env()?).await?;
+    /// let pool = router.pool_for_org(Some("acme-eu"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn connect(config: StorageConfig) -> StorageResult<Self> {
+        let router = Self::new(&config)?;
+
+        let primary = &router.regions[&router.primary_region];
+        primary.set_pool(StoragePool::new(primary.config.clone()).await?);
+        primary.record_health(Ok(()));
+
+        join_all(
+            router
+                .regions
+                .values()
+                .filter(|region| region.name != router.primary_region)
+                .map(Region::check),
+        )
+        .await;
+
+        router.spawn_health_checks(config.residency.health_check_interval());
+        Ok(router)
+    }
+
+    /// Router over the configured regions, none of them connected.
+    fn new(config: &StorageConfig) -> StorageResult<Self> {
+        let residency = &config.residency;
+        residency.validate()?;
+
+        let mut regions = HashMap::new();
+        regions.insert(
+            residency.primary_region.clone(),
+            Region::new(
+                &residency.primary_region,
+                region_config(config, &config.postgres),
+            ),
+        );
+        for (name, target) in &residency.regions {
+            regions.insert(
+                name.clone(),
+                Region::new(name, region_config(config, target)),
+            );
+        }
+
+        Ok(Self {
+            primary_region: residency.primary_region.clone(),
+            regions: Arc::new(regions),
+            org_regions: Arc::new(residency.org_regions.clone()),
+        })
+    }
+
+    /// Check every region's health each `interval` while the router is in use.
+    fn spawn_health_checks(&self, interval: Duration) {
+        let regions = Arc::downgrade(&self.regions);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                let Some(regions) = regions.upgrade() else {
+                    break;
+                };
+                join_all(regions.values().map(Region::check)).await;
+            }
+        });
+    }
+
+    /// Name of the region served by the `postgres` target.
+    pub fn primary_region(&self) -> &str {
+        &self.primary_region
+    }
+
+    /// Region of an organization: its pinned region, otherwise the primary
+    /// region. Data without an organization belongs to the primary region.
+    pub fn region_for_org(&self, org_id: Option<&str>) -> &str {
+        org_id
+            .and_then(|org_id| self.org_regions.get(org_id))
+            .map(String::as_str)
+            .unwrap_or(&self.primary_region)
+    }
+
+    /// Pool of a region.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::ConfigError`] for an unknown region and a
+    /// retryable [`StorageError::ConnectionError`] while the region is
+    /// unavailable.
+    pub fn pool(&self, region: &str) -> StorageResult<StoragePool> {
+        let target = self.regions.get(region).ok_or_else(|| {
+            StorageError::ConfigError(format!("Unknown storage region '{}'", region))
+        })?;
+
+        match target.pool() {
+            Some(pool) if target.is_healthy() => Ok(pool),
+            _ => {
+                StorageMetrics::new().record_region_rejection(region);
+                Err(StorageError::ConnectionError(format!(
+                    "Storage region '{}' is unavailable",
+                    region
+                )))
+            }
+        }
+    }
+
+    /// Pool of an organization's region.
+    ///
+    /// # Errors
+    ///
+    /// Returns a retryable [`StorageError::ConnectionError`] while the region
+    /// is unavailable.
+    pub fn pool_for_org(&self, org_id: Option<&str>) -> StorageResult<StoragePool> {
+        self.pool(self.region_for_org(org_id))
+    }
+
+    /// Group items by the region of their organization.
+    pub fn partition<T, F>(&self, items: Vec<T>, org_id: F) -> HashMap<String, Vec<T>>
+    where
+        F: Fn(&T) -> Option<&str>,
+    {
+        let mut batches: HashMap<String, Vec<T>> = HashMap::new();
+        for item in items {
+            let region = self.region_for_org(org_id(&item)).to_string();
+            batches.entry(region).or_default().push(item);
+        }
+        batches
+    }
+
+    /// Check every region's health now, connecting to regions without a
+    /// pool.
+    pub async fn check_health(&self) {
+        join_all(self.regions.values().map(Region::check)).await;
+    }
+
+    /// Health of every region, by region name.
+    pub fn health(&self) -> Vec<RegionHealth> {
+        let mut health: Vec<_> = self
+            .regions
+            .values()
+            .map(|region| region.health(region.name == self.primary_region))
+            .collect();
+        health.sort_by(|a, b| a.region.cmp(&b.region));
+        health
+    }
+
+    /// Flush buffered writes and close every connected region's pool.
+    pub async fn shutdown(&self, timeout: Duration) -> HashMap<String, ShutdownReport> {
+        let shutdowns = self.regions.values().filter_map(|region| {
+            let pool = region.pool()?;
+            Some(async move { (region.name.clone(), pool.shutdown(timeout).await) })
+        });
+        join_all(shutdowns).await.into_iter().collect()
+    }
+}
+
+/// Configuration of a region's pool: the shared settings with the region's
+/// PostgreSQL target.
+fn region_config(config: &StorageConfig, target: &PostgresConfig) -> StorageConfig {
+    let mut config = config.clone();
+    config.postgres = target.clone();
+    config.residency = ResidencyConfig::default();
+    config
+}
+
+/// Organization ID of a span.
+fn span_org_id(span: &TraceSpan) -> Option<&str> {
+    span.attributes.get(ORG_ID_ATTRIBUTE)?.as_str()
+}
+
+/// Spans a region did not accept.
+#[derive(Debug)]
+pub struct RegionWriteFailure {
+    /// Region name
+    pub region: String,
+
+    /// Why the write failed
+    pub error: StorageError,
+
+    /// Spans rejected before reaching the region's writer because the region
+    /// was unavailable, to be retried later; empty if the region's writer
+    /// failed to flush
+    pub spans: Vec<TraceSpan>,
+}
+
+/// Trace writer that writes each organization's spans to its region.
+///
+/// Keeps one [`TraceWriter`] per region, created with the region's pool when
+/// the region first receives spans.
+#[derive(Clone)]
+pub struct RegionalTraceWriter {
+    router: RegionRouter,
+    writers: Arc<Mutex<HashMap<String, TraceWriter>>>,
+}
+
+impl RegionalTraceWriter {
+    /// Create a writer over the router's regions.
+    pub fn new(router: RegionRouter) -> Self {
+        Self {
+            router,
+            writers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Get the region router.
+    pub fn router(&self) -> &RegionRouter {
+        &self.router
+    }
+
+    /// Trace writer of an organization's region, e.g. for its traces and
+    /// events.
+    ///
+    /// # Errors
+    ///
+    /// Returns a retryable [`StorageError::ConnectionError`] while the region
+    /// is unavailable.
+    pub fn writer_for_org(&self, org_id: Option<&str>) -> StorageResult<TraceWriter> {
+        self.writer(self.router.region_for_org(org_id))
+    }
+
+    fn writer(&self, region: &str) -> StorageResult<TraceWriter> {
+        let pool = self.router.pool(region)?;
+        let mut writers = self.writers.lock().unwrap();
+        Ok(writers
+            .entry(region.to_string())
+            .or_insert_with(|| TraceWriter::new(pool))
+            .clone())
+    }
+
+    /// Write spans to the regions of their organizations.
+    ///
+    /// Regions are written concurrently and independently: spans of a region
+    /// that is unavailable or fails to flush are reported in the returned
+    /// failures, and the other regions' spans are still written.
+    pub async fn write_spans(&self, spans: Vec<TraceSpan>) -> Vec<RegionWriteFailure> {
+        let batches = self.router.partition(spans, span_org_id);
+        let writes = batches.into_iter().map(|(region, spans)| async move {
+            let writer = match self.writer(&region) {
+                Ok(writer) => writer,
+                Err(error) => {
+                    return Some(RegionWriteFailure {
+                        region,
+                        error,
+                        spans,
+                    })
+                }
+            };
+            writer
+                .write_spans(spans)
+                .await
+                .err()
+                .map(|error| RegionWriteFailure {
+                    region,
+                    error,
+                    spans: Vec::new(),
+                })
+        });
+
+        join_all(writes).await.into_iter().flatten().collect()
+    }
+
+    /// Flush every region's writer.
+    ///
+    /// All writers are flushed; the first error is returned.
+    pub async fn flush(&self) -> StorageResult<()> {
+        let writers: Vec<_> = self.writers.lock().unwrap().values().cloned().collect();
+        join_all(writers.iter().map(TraceWriter::flush))
+            .await
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> StorageConfig {
+        let mut config = StorageConfig::in_memory();
+        let mut eu = config.postgres.clone();
+        eu.host = "db.eu.internal".to_string();
+        config.residency.primary_region = "us".to_string();
+        config.residency.regions.insert("eu".to_string(), eu);
+        config
+            .residency
+            .org_regions
+            .insert("acme-eu".to_string(), "eu".to_string());
+        config
+    }
+
+    #[test]
+    fn test_region_for_org() {
+        let router = RegionRouter::new(&config()).unwrap();
+        assert_eq!(router.primary_region(), "us");
+        assert_eq!(router.region_for_org(Some("acme-eu")), "eu");
+        assert_eq!(router.region_for_org(Some("globex")), "us");
+        assert_eq!(router.region_for_org(None), "us");
+        assert_eq!(router.regions["eu"].config.postgres.host, "db.eu.internal");
+        assert!(router.regions["eu"].config.residency.regions.is_empty());
+
+        let batches = router.partition(
+            vec![Some("acme-eu"), Some("globex"), None, Some("acme-eu")],
+            |org_id| *org_id,
+        );
+        assert_eq!(batches["eu"].len(), 2);
+        assert_eq!(batches["us"].len(), 2);
+
+        let mut invalid = config();
+        invalid
+            .residency
+            .org_regions
+            .insert("initech".to_string(), "apac".to_string());
+        assert!(RegionRouter::new(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_unavailable_region_rejects_writes() {
+        let router = RegionRouter::new(&config()).unwrap();
+
+        let error = router.pool_for_org(Some("acme-eu")).err().unwrap();
+        assert!(error.is_retryable());
+        assert!(matches!(
+            router.pool("apac").err().unwrap(),
+            StorageError::ConfigError(_)
+        ));
+
+        router.regions["eu"].record_health(Err("connection refused".to_string()));
+        let health = router.health();
+        assert_eq!(health.len(), 2);
+        assert_eq!(health[0].region, "eu");
+        assert!(!health[0].primary && !health[0].connected && !health[0].healthy);
+        assert_eq!(health[0].last_error.as_deref(), Some("connection refused"));
+        assert!(health[1].primary);
+    }
+}
@@ -0,0 +1,296 @@
+//! Graceful shutdown and flush coordination.
+//!
+//! Writers buffer rows in memory between flushes, so stopping the process
+//! without flushing loses them. Buffering components register with the
+//! pool's [`ShutdownCoordinator`] (the trace, metric and log writers do so on
+//! creation) and stay registered while they hold the returned
+//! [`Registration`]; [`StoragePool::shutdown`](crate::StoragePool::shutdown)
+//! then:
+//!
+//! 1. stops accepting new writes (they fail with [`StorageError::ShuttingDown`]),
+//! 2. flushes every registered component concurrently, within a deadline,
+//! 3. closes the connection pool,
+//!
+//! and returns a [`ShutdownReport`] with how many records each component
+//! flushed and dropped.
+
+use crate::error::{StorageError, StorageResult};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+/// A component holding records that must be flushed before shutdown
+/// (writers, Redis publishers, ...).
+#[async_trait]
+pub trait Flushable: Send + Sync {
+    /// Name used in the shutdown report, e.g. `"trace_writer"`.
+    fn name(&self) -> &'static str;
+
+    /// Number of records currently buffered.
+    async fn pending(&self) -> usize;
+
+    /// Write all buffered records.
+    async fn flush(&self) -> StorageResult<()>;
+}
+
+/// Shutdown outcome of one component.
+#[derive(Debug, Clone)]
+pub struct ComponentShutdown {
+    /// Component name
+    pub name: &'static str,
+
+    /// Records written during shutdown
+    pub flushed: usize,
+
+    /// Records lost because the flush failed, timed out, or arrived too late
+    pub dropped: usize,
+
+    /// Whether the flush did not finish before the deadline
+    pub timed_out: bool,
+
+    /// Error of a failed flush
+    pub error: Option<String>,
+}
+
+/// Outcome of a graceful shutdown.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownReport {
+    /// Per-component results, in registration order
+    pub components: Vec<ComponentShutdown>,
+
+    /// Time spent flushing
+    pub duration: Duration,
+}
+
+impl ShutdownReport {
+    /// Total records written during shutdown.
+    pub fn flushed(&self) -> usize {
+        self.components.iter().map(|c| c.flushed).sum()
+    }
+
+    /// Total records lost.
+    pub fn dropped(&self) -> usize {
+        self.components.iter().map(|c| c.dropped).sum()
+    }
+
+    /// Whether every component flushed all of its records in time.
+    pub fn is_clean(&self) -> bool {
+        self.components
+            .iter()
+            .all(|c| c.dropped == 0 && !c.timed_out && c.error.is_none())
+    }
+}
+
+/// Keeps a component registered with a [`ShutdownCoordinator`]; dropping
+/// it deregisters the component.
+///
+/// Components usually hold a pool, which holds the coordinator, so the
+/// coordinator's reference to a component is only released here.
+#[must_use = "the component is deregistered when the registration is dropped"]
+pub struct Registration {
+    id: u64,
+    coordinator: Weak<ShutdownCoordinator>,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Some(coordinator) = self.coordinator.upgrade() {
+            coordinator
+                .components
+                .lock()
+                .unwrap()
+                .retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+/// Tracks buffering components and whether the storage layer is shutting down.
+#[derive(Default)]
+pub struct ShutdownCoordinator {
+    shutting_down: AtomicBool,
+    next_id: AtomicU64,
+    components: Mutex<Vec<(u64, Arc<dyn Flushable>)>>,
+}
+
+impl ShutdownCoordinator {
+    /// Create a coordinator with no registered components.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a component to flush on shutdown, until the returned
+    /// registration is dropped.
+    pub fn register(self: &Arc<Self>, component: Arc<dyn Flushable>) -> Registration {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.components.lock().unwrap().push((id, component));
+        Registration {
+            id,
+            coordinator: Arc::downgrade(self),
+        }
+    }
+
+    /// Number of registered components.
+    pub fn registered(&self) -> usize {
+        self.components.lock().unwrap().len()
+    }
+
+    /// Check whether shutdown has started.
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::Acquire)
+    }
+
+    /// Fail with [`StorageError::ShuttingDown`] once shutdown has started.
+    pub fn check_accepting_writes(&self) -> StorageResult<()> {
+        if self.is_shutting_down() {
+            return Err(StorageError::ShuttingDown(
+                "new writes are no longer accepted".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Stop accepting writes and flush every registered component.
+    ///
+    /// Components are flushed concurrently; a flush still running when
+    /// `timeout` expires is cancelled and its records are counted as dropped.
+    pub async fn drain(&self, timeout: Duration) -> ShutdownReport {
+        self.shutting_down.store(true, Ordering::Release);
+
+        let start = Instant::now();
+        let deadline = tokio::time::Instant::now() + timeout;
+        let components: Vec<Arc<dyn Flushable>> = self
+            .components
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, component)| component.clone())
+            .collect();
+
+        let components = futures::future::join_all(
+            components
+                .iter()
+                .map(|component| drain_component(component.as_ref(), deadline)),
+        )
+        .await;
+
+        ShutdownReport {
+            components,
+            duration: start.elapsed(),
+        }
+    }
+}
+
+async fn drain_component(
+    component: &dyn Flushable,
+    deadline: tokio::time::Instant,
+) -> ComponentShutdown {
+    let pending = component.pending().await;
+    let result = tokio::time::timeout_at(deadline, component.flush()).await;
+    // Records that raced with the start of shutdown and were not flushed
+    let remaining = component.pending().await;
+
+    let (flushed, timed_out, error) = match result {
+        Ok(Ok(())) => (pending, false, None),
+        Ok(Err(e)) => (0, false, Some(e.to_string())),
+        Err(_) => (0, true, None),
+    };
+
+    ComponentShutdown {
+        name: component.name(),
+        flushed,
+        dropped: pending - flushed + remaining,
+        timed_out,
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    struct MockComponent {
+        pending: AtomicUsize,
+        delay: Duration,
+        fail: bool,
+    }
+
+    impl MockComponent {
+        fn new(pending: usize, delay: Duration, fail: bool) -> Arc<Self> {
+            Arc::new(Self {
+                pending: AtomicUsize::new(pending),
+                delay,
+                fail,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Flushable for MockComponent {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        async fn pending(&self) -> usize {
+            self.pending.load(Ordering::SeqCst)
+        }
+
+        async fn flush(&self) -> StorageResult<()> {
+            // Like the writers, take the buffer before inserting
+            self.pending.store(0, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            if self.fail {
+                return Err(StorageError::query("insert failed"));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drain_reports_flushed_and_dropped() {
+        let coordinator = Arc::new(ShutdownCoordinator::new());
+        let _registrations = [
+            coordinator.register(MockComponent::new(10, Duration::ZERO, false)),
+            coordinator.register(MockComponent::new(5, Duration::ZERO, true)),
+            coordinator.register(MockComponent::new(3, Duration::from_secs(60), false)),
+        ];
+
+        assert!(coordinator.check_accepting_writes().is_ok());
+        let report = coordinator.drain(Duration::from_millis(50)).await;
+        assert!(coordinator.check_accepting_writes().is_err());
+
+        assert_eq!(report.flushed(), 10);
+        assert_eq!(report.dropped(), 8);
+        assert!(!report.is_clean());
+        assert!(report.components[1].error.is_some());
+        assert!(report.components[2].timed_out);
+    }
+
+    #[tokio::test]
+    async fn test_drain_clean() {
+        let coordinator = Arc::new(ShutdownCoordinator::new());
+        let _registration = coordinator.register(MockComponent::new(4, Duration::ZERO, false));
+
+        let report = coordinator.drain(Duration::from_secs(1)).await;
+        assert_eq!(report.flushed(), 4);
+        assert!(report.is_clean());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_registration_releases_component() {
+        let coordinator = Arc::new(ShutdownCoordinator::new());
+        let component = MockComponent::new(4, Duration::ZERO, false);
+        let registration = coordinator.register(component.clone());
+        let _other = coordinator.register(MockComponent::new(1, Duration::ZERO, false));
+        assert_eq!(Arc::strong_count(&component), 2);
+
+        drop(registration);
+        assert_eq!(coordinator.registered(), 1);
+        assert_eq!(Arc::strong_count(&component), 1);
+
+        let report = coordinator.drain(Duration::from_secs(1)).await;
+        assert_eq!(report.components.len(), 1);
+        assert_eq!(report.flushed(), 1);
+    }
+}
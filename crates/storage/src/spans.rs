@@ -0,0 +1,137 @@
+//! Tracing spans for storage operations.
+//!
+//! Batch flushes, repository queries, connection acquisition and Redis
+//! commands run inside `tracing` spans named `storage.*`. Spans carry
+//! OpenTelemetry semantic convention fields (`otel.kind`, `db.system`,
+//! `db.operation`, `db.statement`) so that, with a `tracing-opentelemetry`
+//! layer installed, storage latency shows up inside the same trace as the
+//! request that caused it, e.g. collector ingest followed by the batch write.
+//!
+//! Which spans are created is controlled by
+//! [`TracingVerbosity`](crate::config::TracingVerbosity); disabled spans are
+//! [`Span::none`] and cost nothing beyond the verbosity check.
+
+use crate::config::TracingVerbosity;
+use crate::error::StorageResult;
+use crate::writers::batching::FlushReason;
+use tracing::field::Empty;
+use tracing::Span;
+
+/// Span for flushing a writer's buffer.
+pub fn flush(
+    verbosity: TracingVerbosity,
+    writer: &'static str,
+    reason: FlushReason,
+    rows: usize,
+) -> Span {
+    if verbosity < TracingVerbosity::Basic {
+        return Span::none();
+    }
+    tracing::info_span!(
+        "storage.flush",
+        otel.kind = "client",
+        otel.status_code = Empty,
+        db.system = "postgresql",
+        storage.writer = writer,
+        storage.flush_reason = reason.as_str(),
+        storage.rows = rows,
+        error = Empty,
+    )
+}
+
+/// Span for inserting one table's rows during a flush (detailed only).
+pub fn insert(verbosity: TracingVerbosity, table: &'static str, rows: usize) -> Span {
+    if verbosity < TracingVerbosity::Detailed {
+        return Span::none();
+    }
+    tracing::info_span!(
+        "storage.insert",
+        otel.kind = "client",
+        otel.status_code = Empty,
+        db.system = "postgresql",
+        db.operation = "INSERT",
+        db.sql.table = table,
+        storage.rows = rows,
+        error = Empty,
+    )
+}
+
+/// Span for a repository query.
+///
+/// The SQL is recorded, sanitized, only at detailed verbosity.
+pub fn query(
+    verbosity: TracingVerbosity,
+    repository: &'static str,
+    method: &'static str,
+    sql: Option<&str>,
+) -> Span {
+    if verbosity < TracingVerbosity::Basic {
+        return Span::none();
+    }
+    let span = tracing::info_span!(
+        "storage.query",
+        otel.kind = "client",
+        otel.status_code = Empty,
+        db.system = "postgresql",
+        db.operation = method,
+        db.statement = Empty,
+        storage.repository = repository,
+        error = Empty,
+    );
+    if verbosity >= TracingVerbosity::Detailed {
+        if let Some(sql) = sql {
+            span.record("db.statement", crate::query::sanitize_sql(sql).as_str());
+        }
+    }
+    span
+}
+
+/// Span for acquiring a PostgreSQL connection from the pool.
+pub fn acquire(verbosity: TracingVerbosity) -> Span {
+    if verbosity < TracingVerbosity::Basic {
+        return Span::none();
+    }
+    tracing::info_span!(
+        "storage.acquire",
+        otel.status_code = Empty,
+        db.system = "postgresql",
+        error = Empty,
+    )
+}
+
+/// Span for a Redis command.
+pub fn redis(verbosity: TracingVerbosity, command: &'static str) -> Span {
+    if verbosity < TracingVerbosity::Basic {
+        return Span::none();
+    }
+    tracing::info_span!(
+        "storage.redis",
+        otel.kind = "client",
+        otel.status_code = Empty,
+        db.system = "redis",
+        db.operation = command,
+        error = Empty,
+    )
+}
+
+/// Mark `span` as failed if `result` is an error.
+pub fn record_result<T>(span: &Span, result: &StorageResult<T>) {
+    if let Err(e) = result {
+        span.record("otel.status_code", "ERROR");
+        span.record("error", e.to_string().as_str());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_spans() {
+        assert!(flush(TracingVerbosity::Off, "trace", FlushReason::Rows, 10).is_none());
+        assert!(query(TracingVerbosity::Off, "trace_repository", "list", None).is_none());
+        assert!(acquire(TracingVerbosity::Off).is_none());
+        assert!(redis(TracingVerbosity::Off, "PING").is_none());
+        assert!(insert(TracingVerbosity::Basic, "traces", 10).is_none());
+    }
+}
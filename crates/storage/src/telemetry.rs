@@ -0,0 +1,427 @@
+//! OTLP export of storage metrics.
+//!
+//! Storage metrics ([`StorageMetrics`](crate::metrics::StorageMetrics), pool
+//! gauges, writer batch sizes) are recorded through the `metrics` facade.
+//! [`install`] sets the global recorder that receives them: the Prometheus
+//! recorder behind the health server's `/metrics` and, when
+//! [`TelemetryConfig::otlp_enabled`] is set, an OpenTelemetry meter provider
+//! pushing the same metrics to an OTLP endpoint, so storage internals land in
+//! the same pipeline as the traces and metrics of the other components.
+//!
+//! Metric names follow OpenTelemetry conventions on the OTLP side: counters
+//! drop their `_total` suffix (Prometheus-compatible backends add it back),
+//! and `_seconds` and `_bytes` metrics carry the units `s` and `By`.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use llm_observatory_storage::{telemetry, StorageConfig};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = StorageConfig::from_env()?;
+//! let metrics = telemetry::install(&config.telemetry)?;
+//!
+//! // ... serve `metrics.prometheus_handle()` and run the storage layer ...
+//!
+//! // Export the last metrics before exiting
+//! metrics.shutdown()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::config::{OtlpProtocol, TelemetryConfig};
+use crate::error::{StorageError, StorageResult};
+use dashmap::DashMap;
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    SharedString, Unit,
+};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use metrics_util::layers::FanoutBuilder;
+use opentelemetry::metrics::{Meter, MeterProvider as _};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::{MetricExporter, WithExportConfig};
+use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+use opentelemetry_sdk::{runtime, Resource};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Instrumentation scope of the exported metrics.
+const METER_NAME: &str = "llm-observatory-storage";
+
+/// How often Prometheus histograms are drained into their summaries.
+const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Installed metrics recorders.
+pub struct MetricsTelemetry {
+    prometheus_handle: PrometheusHandle,
+    meter_provider: Option<SdkMeterProvider>,
+}
+
+impl MetricsTelemetry {
+    /// Handle rendering the metrics in Prometheus format.
+    pub fn prometheus_handle(&self) -> PrometheusHandle {
+        self.prometheus_handle.clone()
+    }
+
+    /// Whether metrics are exported over OTLP.
+    pub fn otlp_enabled(&self) -> bool {
+        self.meter_provider.is_some()
+    }
+
+    /// Export pending metrics now.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the export fails.
+    pub fn flush(&self) -> StorageResult<()> {
+        match &self.meter_provider {
+            Some(provider) => provider
+                .force_flush()
+                .map_err(|e| StorageError::Internal(format!("OTLP metrics export failed: {}", e))),
+            None => Ok(()),
+        }
+    }
+
+    /// Export pending metrics and stop the OTLP exporter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the final export fails.
+    pub fn shutdown(&self) -> StorageResult<()> {
+        match &self.meter_provider {
+            Some(provider) => provider.shutdown().map_err(|e| {
+                StorageError::Internal(format!("OTLP metrics shutdown failed: {}", e))
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Install the global metrics recorder.
+///
+/// Metrics always go to a Prometheus recorder; with OTLP enabled they are
+/// also exported every `export_interval_secs`. Must be called within a Tokio
+/// runtime when OTLP is enabled, since the exporter runs on it.
+///
+/// # Errors
+///
+/// Returns an error if the OTLP exporter cannot be built or a global
+/// recorder is already installed.
+pub fn install(config: &TelemetryConfig) -> StorageResult<MetricsTelemetry> {
+    config.validate()?;
+
+    let prometheus = PrometheusBuilder::new().build_recorder();
+    let prometheus_handle = prometheus.handle();
+
+    let meter_provider = if config.otlp_enabled {
+        Some(meter_provider(config)?)
+    } else {
+        None
+    };
+
+    let installed = match &meter_provider {
+        Some(provider) => {
+            let otlp = OtlpRecorder::new(provider.meter(METER_NAME));
+            let fanout = FanoutBuilder::default()
+                .add_recorder(prometheus)
+                .add_recorder(otlp)
+                .build();
+            metrics::set_global_recorder(fanout).is_ok()
+        }
+        None => metrics::set_global_recorder(prometheus).is_ok(),
+    };
+    if !installed {
+        return Err(StorageError::ConfigError(
+            "A global metrics recorder is already installed".to_string(),
+        ));
+    }
+
+    spawn_upkeep(prometheus_handle.clone());
+
+    if config.otlp_enabled {
+        tracing::info!(
+            endpoint = %config.otlp_endpoint,
+            protocol = ?config.otlp_protocol,
+            interval_secs = config.export_interval_secs,
+            "Exporting storage metrics over OTLP"
+        );
+    }
+
+    Ok(MetricsTelemetry {
+        prometheus_handle,
+        meter_provider,
+    })
+}
+
+/// Build the meter provider exporting to the configured endpoint.
+fn meter_provider(config: &TelemetryConfig) -> StorageResult<SdkMeterProvider> {
+    let exporter = match config.otlp_protocol {
+        OtlpProtocol::Grpc => MetricExporter::builder()
+            .with_tonic()
+            .with_endpoint(config.otlp_endpoint.clone())
+            .with_timeout(config.export_timeout())
+            .build(),
+        OtlpProtocol::HttpProtobuf => MetricExporter::builder()
+            .with_http()
+            .with_endpoint(config.otlp_endpoint.clone())
+            .with_timeout(config.export_timeout())
+            .build(),
+    }
+    .map_err(|e| {
+        StorageError::ConfigError(format!("Failed to build OTLP metric exporter: {}", e))
+    })?;
+
+    let reader = PeriodicReader::builder(exporter, runtime::Tokio)
+        .with_interval(config.export_interval())
+        .with_timeout(config.export_timeout())
+        .build();
+
+    Ok(SdkMeterProvider::builder()
+        .with_reader(reader)
+        .with_resource(Resource::new([KeyValue::new(
+            "service.name",
+            config.service_name.clone(),
+        )]))
+        .build())
+}
+
+/// Drain Prometheus histograms periodically, as the recorder's own
+/// installer does; without it they grow until the next scrape.
+fn spawn_upkeep(handle: PrometheusHandle) {
+    std::thread::Builder::new()
+        .name("metrics-upkeep".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(UPKEEP_INTERVAL);
+            handle.run_upkeep();
+        })
+        .expect("Failed to spawn metrics upkeep thread");
+}
+
+/// Name of a metric on the OTLP side.
+fn otel_name(name: &str, counter: bool) -> String {
+    match name.strip_suffix("_total") {
+        Some(stripped) if counter => stripped.to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// Unit of a metric, from its name's suffix.
+fn otel_unit(name: &str) -> Option<&'static str> {
+    if name.ends_with("_seconds") {
+        Some("s")
+    } else if name.ends_with("_bytes") {
+        Some("By")
+    } else {
+        None
+    }
+}
+
+fn attributes(key: &Key) -> Vec<KeyValue> {
+    key.labels()
+        .map(|label| KeyValue::new(label.key().to_string(), label.value().to_string()))
+        .collect()
+}
+
+/// `metrics` recorder forwarding to OpenTelemetry instruments.
+///
+/// Handles are cached per key (name and labels), since the `metrics` macros
+/// register them on every call and gauges keep their value in the handle.
+struct OtlpRecorder {
+    meter: Meter,
+    descriptions: DashMap<String, SharedString>,
+    counters: DashMap<Key, Arc<OtlpCounter>>,
+    gauges: DashMap<Key, Arc<OtlpGauge>>,
+    histograms: DashMap<Key, Arc<OtlpHistogram>>,
+}
+
+impl OtlpRecorder {
+    fn new(meter: Meter) -> Self {
+        Self {
+            meter,
+            descriptions: DashMap::new(),
+            counters: DashMap::new(),
+            gauges: DashMap::new(),
+            histograms: DashMap::new(),
+        }
+    }
+
+    fn description(&self, name: &str) -> String {
+        self.descriptions
+            .get(name)
+            .map(|description| description.to_string())
+            .unwrap_or_default()
+    }
+}
+
+impl Recorder for OtlpRecorder {
+    fn describe_counter(&self, key: KeyName, _unit: Option<Unit>, description: SharedString) {
+        self.descriptions
+            .insert(key.as_str().to_string(), description);
+    }
+
+    fn describe_gauge(&self, key: KeyName, _unit: Option<Unit>, description: SharedString) {
+        self.descriptions
+            .insert(key.as_str().to_string(), description);
+    }
+
+    fn describe_histogram(&self, key: KeyName, _unit: Option<Unit>, description: SharedString) {
+        self.descriptions
+            .insert(key.as_str().to_string(), description);
+    }
+
+    fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+        let handle = self
+            .counters
+            .entry(key.clone())
+            .or_insert_with(|| {
+                let name = key.name();
+                let mut builder = self
+                    .meter
+                    .u64_counter(otel_name(name, true))
+                    .with_description(self.description(name));
+                if let Some(unit) = otel_unit(name) {
+                    builder = builder.with_unit(unit);
+                }
+                Arc::new(OtlpCounter {
+                    counter: builder.build(),
+                    attributes: attributes(key),
+                    value: AtomicU64::new(0),
+                })
+            })
+            .clone();
+        Counter::from_arc(handle)
+    }
+
+    fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+        let handle = self
+            .gauges
+            .entry(key.clone())
+            .or_insert_with(|| {
+                let name = key.name();
+                let mut builder = self
+                    .meter
+                    .f64_gauge(otel_name(name, false))
+                    .with_description(self.description(name));
+                if let Some(unit) = otel_unit(name) {
+                    builder = builder.with_unit(unit);
+                }
+                Arc::new(OtlpGauge {
+                    gauge: builder.build(),
+                    attributes: attributes(key),
+                    value: Mutex::new(0.0),
+                })
+            })
+            .clone();
+        Gauge::from_arc(handle)
+    }
+
+    fn register_histogram(&self, key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+        let handle = self
+            .histograms
+            .entry(key.clone())
+            .or_insert_with(|| {
+                let name = key.name();
+                let mut builder = self
+                    .meter
+                    .f64_histogram(otel_name(name, false))
+                    .with_description(self.description(name));
+                if let Some(unit) = otel_unit(name) {
+                    builder = builder.with_unit(unit);
+                }
+                Arc::new(OtlpHistogram {
+                    histogram: builder.build(),
+                    attributes: attributes(key),
+                })
+            })
+            .clone();
+        Histogram::from_arc(handle)
+    }
+}
+
+struct OtlpCounter {
+    counter: opentelemetry::metrics::Counter<u64>,
+    attributes: Vec<KeyValue>,
+    /// Total so far, to turn absolute values into increments
+    value: AtomicU64,
+}
+
+impl CounterFn for OtlpCounter {
+    fn increment(&self, value: u64) {
+        self.value.fetch_add(value, Ordering::Relaxed);
+        self.counter.add(value, &self.attributes);
+    }
+
+    fn absolute(&self, value: u64) {
+        let previous = self.value.fetch_max(value, Ordering::Relaxed);
+        if value > previous {
+            self.counter.add(value - previous, &self.attributes);
+        }
+    }
+}
+
+struct OtlpGauge {
+    gauge: opentelemetry::metrics::Gauge<f64>,
+    attributes: Vec<KeyValue>,
+    /// Current value, which increments and decrements apply to
+    value: Mutex<f64>,
+}
+
+impl OtlpGauge {
+    fn update(&self, f: impl FnOnce(f64) -> f64) {
+        let mut value = self.value.lock().unwrap();
+        *value = f(*value);
+        self.gauge.record(*value, &self.attributes);
+    }
+}
+
+impl GaugeFn for OtlpGauge {
+    fn increment(&self, value: f64) {
+        self.update(|current| current + value);
+    }
+
+    fn decrement(&self, value: f64) {
+        self.update(|current| current - value);
+    }
+
+    fn set(&self, value: f64) {
+        self.update(|_| value);
+    }
+}
+
+struct OtlpHistogram {
+    histogram: opentelemetry::metrics::Histogram<f64>,
+    attributes: Vec<KeyValue>,
+}
+
+impl HistogramFn for OtlpHistogram {
+    fn record(&self, value: f64) {
+        self.histogram.record(value, &self.attributes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_otel_name() {
+        assert_eq!(otel_name("storage_writes_total", true), "storage_writes");
+        assert_eq!(
+            otel_name("storage_writes_total", false),
+            "storage_writes_total"
+        );
+        assert_eq!(
+            otel_name("storage_write_duration_seconds", false),
+            "storage_write_duration_seconds"
+        );
+    }
+
+    #[test]
+    fn test_otel_unit() {
+        assert_eq!(otel_unit("storage_write_duration_seconds"), Some("s"));
+        assert_eq!(otel_unit("storage_batch_bytes"), Some("By"));
+        assert_eq!(otel_unit("storage_pool_connections"), None);
+    }
+}
@@ -0,0 +1,300 @@
+//! Daily top-N rollups of LLM requests.
+//!
+//! "Top models by cost" and similar rankings group every `llm_traces` row of
+//! their time range. The [`TopNMaterializer`] keeps per-organization daily
+//! totals by model, environment and user in `top_n_daily` (see migration
+//! `030_top_n_daily.sql`), so rankings over long ranges read one row per day
+//! and value instead.
+//!
+//! A day is materialized once it is over, in one transaction that replaces
+//! its rows. The `late_arrival_days` days before it are recomputed at the same
+//! time, so traces arriving up to that late are still counted. The refresh
+//! state records the complete range, `[materialized_from, refreshed_through)`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm_observatory_storage::{top_n::TopNMaterializer, StorageConfig, StoragePool};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let pool = StoragePool::new(StorageConfig::from_env()?).await?;
+//! let materializer = TopNMaterializer::new(pool);
+//!
+//! // Run once now, then keep running in the background
+//! materializer.run().await?;
+//! let _handle = materializer.start();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::config::TopNConfig;
+use crate::error::StorageResult;
+use crate::pool::StoragePool;
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+
+/// A grouping of `llm_traces` materialized into `top_n_daily`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TopNDimension {
+    /// Grouping column of `llm_traces`, stored as `top_n_daily.dimension`
+    pub column: &'static str,
+
+    /// Whether rows are also grouped by provider
+    pub by_provider: bool,
+}
+
+/// Dimensions kept in `top_n_daily`.
+pub const DIMENSIONS: [TopNDimension; 3] = [
+    TopNDimension {
+        column: "model",
+        by_provider: true,
+    },
+    TopNDimension {
+        column: "environment",
+        by_provider: false,
+    },
+    TopNDimension {
+        column: "user_id",
+        by_provider: false,
+    },
+];
+
+/// Complete range of `top_n_daily`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RefreshState {
+    /// First materialized day
+    pub materialized_from: DateTime<Utc>,
+
+    /// End of the last materialized day
+    pub refreshed_through: DateTime<Utc>,
+}
+
+/// Summary of a materializer run.
+#[derive(Debug, Clone, Default)]
+pub struct TopNReport {
+    /// Days recomputed
+    pub days: u64,
+
+    /// Rows written
+    pub rows: u64,
+}
+
+/// Start of the UTC day containing `ts`.
+pub fn day_start(ts: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&ts.date_naive().and_time(NaiveTime::MIN))
+}
+
+/// Days to recompute at `now`, oldest first.
+///
+/// Nothing is recomputed until a new day has completed. The first run
+/// backfills `backfill_days` days.
+pub fn days_to_refresh(
+    state: Option<&RefreshState>,
+    now: DateTime<Utc>,
+    config: &TopNConfig,
+) -> Vec<DateTime<Utc>> {
+    let today = day_start(now);
+    let first = match state {
+        Some(state) if state.refreshed_through >= today => return Vec::new(),
+        Some(state) => (day_start(state.refreshed_through)
+            - Duration::days(config.late_arrival_days as i64))
+        .max(state.materialized_from),
+        None => today - Duration::days(config.backfill_days as i64),
+    };
+
+    let mut days = Vec::new();
+    let mut day = first;
+    while day < today {
+        days.push(day);
+        day += Duration::days(1);
+    }
+    days
+}
+
+/// Maintains the daily top-N rollups.
+#[derive(Clone)]
+pub struct TopNMaterializer {
+    pool: StoragePool,
+    config: TopNConfig,
+}
+
+impl TopNMaterializer {
+    /// Create a materializer using the pool's top-N configuration.
+    pub fn new(pool: StoragePool) -> Self {
+        let config = pool.config().top_n.clone();
+        Self::with_config(pool, config)
+    }
+
+    /// Create a materializer with an explicit configuration.
+    pub fn with_config(pool: StoragePool, config: TopNConfig) -> Self {
+        Self { pool, config }
+    }
+
+    /// Read the complete range of `top_n_daily`, if anything was materialized.
+    pub async fn refresh_state(&self) -> StorageResult<Option<RefreshState>> {
+        let row: Option<(DateTime<Utc>, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT materialized_from, refreshed_through FROM top_n_refresh_state WHERE id",
+        )
+        .fetch_optional(self.pool.postgres())
+        .await?;
+
+        Ok(
+            row.map(|(materialized_from, refreshed_through)| RefreshState {
+                materialized_from,
+                refreshed_through,
+            }),
+        )
+    }
+
+    /// Recompute the rows of the day starting at `day`.
+    ///
+    /// Returns the number of rows written.
+    pub async fn materialize_day(&self, day: DateTime<Utc>) -> StorageResult<u64> {
+        let mut tx = self.pool.postgres().begin().await?;
+
+        sqlx::query("DELETE FROM top_n_daily WHERE bucket = $1")
+            .bind(day)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut rows = 0;
+        for dimension in DIMENSIONS {
+            let (provider, group_by) = if dimension.by_provider {
+                ("provider", ", provider")
+            } else {
+                ("''", "")
+            };
+            let sql = format!(
+                "INSERT INTO top_n_daily (org_id, bucket, dimension, dimension_value, provider, \
+                 total_cost, prompt_cost, completion_cost, request_count, total_tokens, \
+                 error_count, duration_sum_ms) \
+                 SELECT org_id, $1, '{column}', {column}, {provider}, \
+                 COALESCE(SUM(total_cost_usd), 0)::DOUBLE PRECISION, \
+                 COALESCE(SUM(prompt_cost_usd), 0)::DOUBLE PRECISION, \
+                 COALESCE(SUM(completion_cost_usd), 0)::DOUBLE PRECISION, \
+                 COUNT(*), \
+                 COALESCE(SUM(total_tokens), 0), \
+                 COUNT(*) FILTER (WHERE status_code = 'ERROR'), \
+                 COALESCE(SUM(duration_ms), 0)::DOUBLE PRECISION \
+                 FROM llm_traces \
+                 WHERE ts >= $1 AND ts < $2 AND org_id IS NOT NULL AND {column} IS NOT NULL \
+                 GROUP BY org_id, {column}{group_by}",
+                column = dimension.column,
+                provider = provider,
+                group_by = group_by,
+            );
+            let result = sqlx::query(&sql)
+                .bind(day)
+                .bind(day + Duration::days(1))
+                .execute(&mut *tx)
+                .await?;
+            rows += result.rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(rows)
+    }
+
+    /// Materialize the days completed since the last run.
+    pub async fn run(&self) -> StorageResult<TopNReport> {
+        let mut report = TopNReport::default();
+        if !self.config.enabled {
+            return Ok(report);
+        }
+
+        let now = Utc::now();
+        let days = days_to_refresh(self.refresh_state().await?.as_ref(), now, &self.config);
+        let Some(first) = days.first().copied() else {
+            return Ok(report);
+        };
+
+        for day in days {
+            report.rows += self.materialize_day(day).await?;
+            report.days += 1;
+        }
+
+        sqlx::query(
+            "INSERT INTO top_n_refresh_state (id, materialized_from, refreshed_through, updated_at) \
+             VALUES (true, $1, $2, NOW()) \
+             ON CONFLICT (id) DO UPDATE \
+             SET refreshed_through = EXCLUDED.refreshed_through, updated_at = NOW()",
+        )
+        .bind(first)
+        .bind(day_start(now))
+        .execute(self.pool.postgres())
+        .await?;
+
+        tracing::info!(
+            "Top-N materialization: {} days, {} rows",
+            report.days,
+            report.rows
+        );
+
+        Ok(report)
+    }
+
+    /// Start periodic materialization.
+    ///
+    /// Returns a handle that can be used to stop the materialization task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let materializer = self.clone();
+        let interval = self.config.interval();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = materializer.run().await {
+                    tracing::error!("Top-N materialization error: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_days_to_refresh() {
+        let config = TopNConfig {
+            backfill_days: 3,
+            late_arrival_days: 1,
+            ..TopNConfig::default()
+        };
+        let now = ts("2025-11-12T15:30:00Z");
+
+        assert_eq!(
+            days_to_refresh(None, now, &config),
+            vec![
+                ts("2025-11-09T00:00:00Z"),
+                ts("2025-11-10T00:00:00Z"),
+                ts("2025-11-11T00:00:00Z"),
+            ]
+        );
+
+        let mut state = RefreshState {
+            materialized_from: ts("2025-11-09T00:00:00Z"),
+            refreshed_through: ts("2025-11-12T00:00:00Z"),
+        };
+        assert!(days_to_refresh(Some(&state), now, &config).is_empty());
+
+        // A new day completed: recompute it and the day before
+        state.refreshed_through = ts("2025-11-11T00:00:00Z");
+        assert_eq!(
+            days_to_refresh(Some(&state), now, &config),
+            vec![ts("2025-11-10T00:00:00Z"), ts("2025-11-11T00:00:00Z")]
+        );
+
+        // Late days never reach before the materialized range
+        state.materialized_from = ts("2025-11-11T00:00:00Z");
+        assert_eq!(
+            days_to_refresh(Some(&state), now, &config),
+            vec![ts("2025-11-11T00:00:00Z")]
+        );
+    }
+}
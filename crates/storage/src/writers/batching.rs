@@ -0,0 +1,327 @@
+//! Adaptive batching policy shared by the buffered writers.
+//!
+//! A batch is flushed when it reaches the row limit, the estimated byte limit,
+//! or when its oldest row has waited longer than the latency limit. With
+//! adaptive sizing enabled, the row limit shrinks when flushes run slower than
+//! the target duration and grows back when they are fast.
+
+use crate::config::BatchingConfig;
+use crate::models::{LogRecord, Metric, MetricDataPoint, Trace, TraceEvent, TraceSpan};
+use std::time::{Duration, Instant};
+
+/// Fixed per-row overhead used when estimating payload size (ids, timestamps, numerics).
+const ROW_OVERHEAD_BYTES: usize = 64;
+
+/// Reason a batch was flushed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlushReason {
+    /// The row limit was reached
+    Rows,
+    /// The estimated byte limit was reached
+    Bytes,
+    /// The oldest buffered row exceeded the latency limit
+    Latency,
+    /// The flush was requested explicitly
+    Manual,
+    /// The storage pool is shutting down
+    Shutdown,
+}
+
+impl FlushReason {
+    /// Label used for metrics.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FlushReason::Rows => "rows",
+            FlushReason::Bytes => "bytes",
+            FlushReason::Latency => "latency",
+            FlushReason::Manual => "manual",
+            FlushReason::Shutdown => "shutdown",
+        }
+    }
+}
+
+/// Tracks buffered volume and decides when a writer should flush.
+#[derive(Debug, Clone)]
+pub struct AdaptiveBatcher {
+    config: BatchingConfig,
+    row_limit: usize,
+    pending_rows: usize,
+    pending_bytes: usize,
+    oldest: Option<Instant>,
+}
+
+impl AdaptiveBatcher {
+    /// Create a new batcher from a batching policy.
+    pub fn new(config: BatchingConfig) -> Self {
+        let row_limit = config.max_rows;
+        Self {
+            config,
+            row_limit,
+            pending_rows: 0,
+            pending_bytes: 0,
+            oldest: None,
+        }
+    }
+
+    /// Get the batching policy.
+    pub fn config(&self) -> &BatchingConfig {
+        &self.config
+    }
+
+    /// Current effective row limit.
+    pub fn row_limit(&self) -> usize {
+        self.row_limit
+    }
+
+    /// Number of rows currently accounted for.
+    pub fn pending_rows(&self) -> usize {
+        self.pending_rows
+    }
+
+    /// Estimated bytes currently accounted for.
+    pub fn pending_bytes(&self) -> usize {
+        self.pending_bytes
+    }
+
+    /// Account for rows appended to the buffer.
+    pub fn record_append(&mut self, rows: usize, bytes: usize) {
+        if rows == 0 {
+            return;
+        }
+        if self.oldest.is_none() {
+            self.oldest = Some(Instant::now());
+        }
+        self.pending_rows += rows;
+        self.pending_bytes += bytes;
+    }
+
+    /// Check whether the buffer should be flushed now.
+    pub fn flush_reason(&self) -> Option<FlushReason> {
+        if self.pending_rows == 0 {
+            return None;
+        }
+        if self.pending_rows >= self.row_limit {
+            return Some(FlushReason::Rows);
+        }
+        if self.pending_bytes >= self.config.max_bytes {
+            return Some(FlushReason::Bytes);
+        }
+        match self.oldest {
+            Some(oldest) if oldest.elapsed() >= self.config.max_latency() => {
+                Some(FlushReason::Latency)
+            }
+            _ => None,
+        }
+    }
+
+    /// Reset pending counters after the buffer has been drained.
+    pub fn reset(&mut self) {
+        self.pending_rows = 0;
+        self.pending_bytes = 0;
+        self.oldest = None;
+    }
+
+    /// Feed back the duration of a completed flush.
+    ///
+    /// Halves the row limit when a flush took more than twice the target,
+    /// and grows it by a quarter when it finished under half the target.
+    pub fn record_flush(&mut self, rows: usize, elapsed: Duration) {
+        if !self.config.adaptive || rows == 0 {
+            return;
+        }
+
+        let target = Duration::from_millis(self.config.target_flush_ms);
+        let new_limit = if elapsed > target * 2 {
+            self.row_limit / 2
+        } else if elapsed < target / 2 && rows >= self.row_limit / 2 {
+            self.row_limit + (self.row_limit / 4).max(1)
+        } else {
+            self.row_limit
+        };
+
+        self.row_limit = new_limit.clamp(self.config.min_rows, self.config.max_rows);
+    }
+
+    /// How often a background task should poll for latency-based flushes.
+    pub fn poll_interval(&self) -> Duration {
+        (self.config.max_latency() / 2).max(Duration::from_millis(10))
+    }
+}
+
+/// Approximate in-memory payload size of a row, used for byte-based flushing.
+pub trait EstimateSize {
+    /// Estimated payload size in bytes.
+    fn estimated_size(&self) -> usize;
+}
+
+fn json_size(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::Null | serde_json::Value::Bool(_) => 4,
+        serde_json::Value::Number(_) => 8,
+        serde_json::Value::String(s) => s.len() + 2,
+        serde_json::Value::Array(items) => 2 + items.iter().map(json_size).sum::<usize>(),
+        serde_json::Value::Object(map) => {
+            2 + map.iter().map(|(k, v)| k.len() + 3 + json_size(v)).sum::<usize>()
+        }
+    }
+}
+
+fn opt_json_size(value: &Option<serde_json::Value>) -> usize {
+    value.as_ref().map(json_size).unwrap_or(0)
+}
+
+fn opt_str_size(value: &Option<String>) -> usize {
+    value.as_ref().map(|s| s.len()).unwrap_or(0)
+}
+
+impl EstimateSize for Trace {
+    fn estimated_size(&self) -> usize {
+        ROW_OVERHEAD_BYTES
+            + self.trace_id.len()
+            + self.service_name.len()
+            + self.status.len()
+            + opt_str_size(&self.status_message)
+            + opt_str_size(&self.root_span_name)
+            + json_size(&self.attributes)
+            + json_size(&self.resource_attributes)
+    }
+}
+
+impl EstimateSize for TraceSpan {
+    fn estimated_size(&self) -> usize {
+        ROW_OVERHEAD_BYTES
+            + self.span_id.len()
+            + opt_str_size(&self.parent_span_id)
+            + self.name.len()
+            + self.kind.len()
+            + self.service_name.len()
+            + self.status.len()
+            + opt_str_size(&self.status_message)
+            + json_size(&self.attributes)
+            + opt_json_size(&self.events)
+            + opt_json_size(&self.links)
+    }
+}
+
+impl EstimateSize for TraceEvent {
+    fn estimated_size(&self) -> usize {
+        ROW_OVERHEAD_BYTES + self.name.len() + json_size(&self.attributes)
+    }
+}
+
+impl EstimateSize for Metric {
+    fn estimated_size(&self) -> usize {
+        ROW_OVERHEAD_BYTES
+            + self.name.len()
+            + opt_str_size(&self.description)
+            + opt_str_size(&self.unit)
+            + self.metric_type.len()
+            + self.service_name.len()
+            + json_size(&self.attributes)
+            + json_size(&self.resource_attributes)
+    }
+}
+
+impl EstimateSize for MetricDataPoint {
+    fn estimated_size(&self) -> usize {
+        ROW_OVERHEAD_BYTES
+            + opt_json_size(&self.buckets)
+            + opt_json_size(&self.quantiles)
+            + opt_json_size(&self.exemplars)
+            + json_size(&self.attributes)
+    }
+}
+
+impl EstimateSize for LogRecord {
+    fn estimated_size(&self) -> usize {
+        ROW_OVERHEAD_BYTES
+            + self.severity_text.len()
+            + self.body.len()
+            + self.service_name.len()
+            + opt_str_size(&self.trace_id)
+            + opt_str_size(&self.span_id)
+            + json_size(&self.attributes)
+            + json_size(&self.resource_attributes)
+            + opt_str_size(&self.scope_name)
+            + opt_str_size(&self.scope_version)
+            + opt_json_size(&self.scope_attributes)
+            + opt_str_size(&self.pattern_id)
+    }
+}
+
+/// Sum the estimated size of a slice of rows.
+pub fn estimate_batch_size<T: EstimateSize>(rows: &[T]) -> usize {
+    rows.iter().map(EstimateSize::estimated_size).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> BatchingConfig {
+        BatchingConfig {
+            max_rows: 100,
+            min_rows: 10,
+            max_bytes: 1024,
+            max_latency_ms: 200,
+            target_flush_ms: 50,
+            adaptive: true,
+        }
+    }
+
+    #[test]
+    fn test_flush_on_rows() {
+        let mut batcher = AdaptiveBatcher::new(policy());
+        batcher.record_append(99, 10);
+        assert_eq!(batcher.flush_reason(), None);
+        batcher.record_append(1, 10);
+        assert_eq!(batcher.flush_reason(), Some(FlushReason::Rows));
+    }
+
+    #[test]
+    fn test_flush_on_bytes() {
+        let mut batcher = AdaptiveBatcher::new(policy());
+        batcher.record_append(1, 2048);
+        assert_eq!(batcher.flush_reason(), Some(FlushReason::Bytes));
+    }
+
+    #[test]
+    fn test_flush_on_latency() {
+        let mut config = policy();
+        config.max_latency_ms = 1;
+        let mut batcher = AdaptiveBatcher::new(config);
+        batcher.record_append(1, 1);
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(batcher.flush_reason(), Some(FlushReason::Latency));
+
+        batcher.reset();
+        assert_eq!(batcher.flush_reason(), None);
+    }
+
+    #[test]
+    fn test_adaptive_shrink_and_grow() {
+        let mut batcher = AdaptiveBatcher::new(policy());
+        batcher.record_flush(100, Duration::from_millis(500));
+        assert_eq!(batcher.row_limit(), 50);
+
+        for _ in 0..10 {
+            batcher.record_flush(100, Duration::from_millis(500));
+        }
+        assert_eq!(batcher.row_limit(), 10);
+
+        for _ in 0..20 {
+            let limit = batcher.row_limit();
+            batcher.record_flush(limit, Duration::from_millis(1));
+        }
+        assert_eq!(batcher.row_limit(), 100);
+    }
+
+    #[test]
+    fn test_non_adaptive_keeps_limit() {
+        let mut config = policy();
+        config.adaptive = false;
+        let mut batcher = AdaptiveBatcher::new(config);
+        batcher.record_flush(100, Duration::from_secs(5));
+        assert_eq!(batcher.row_limit(), 100);
+    }
+}
@@ -0,0 +1,248 @@
+//! Attribute cardinality limits for metric data points.
+//!
+//! Unbounded attribute values (user IDs, request IDs) make every data point
+//! a new series. [`CardinalityLimiter`] tracks, per metric, the distinct
+//! values of each attribute key and the distinct attribute sets, and rewrites
+//! data points that would exceed the [`CardinalityConfig`] limits:
+//!
+//! 1. A value beyond `max_values_per_attribute` for its key is replaced by
+//!    [`OVERFLOW_VALUE`], so the values of every key stay bounded.
+//! 2. An attribute set beyond the metric's series limit is replaced by
+//!    `{"__overflow__": "true"}`, so all excess points share one series.
+//!
+//! Values and attribute sets admitted before a limit was reached keep being
+//! written unchanged, so existing series are not broken up.
+
+use crate::config::CardinalityConfig;
+use crate::models::MetricDataPoint;
+use serde_json::{Map, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use uuid::Uuid;
+
+/// Replacement for attribute values, and key of the series, beyond a limit.
+pub const OVERFLOW_VALUE: &str = "__overflow__";
+
+/// Data points rewritten by [`CardinalityLimiter::apply`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CardinalityOutcome {
+    /// Data points with attribute values replaced by [`OVERFLOW_VALUE`]
+    pub attribute_overflows: u64,
+
+    /// Data points moved to the overflow series
+    pub series_overflows: u64,
+}
+
+/// Values and attribute sets admitted for one metric.
+#[derive(Debug, Default)]
+struct MetricCardinality {
+    values: HashMap<String, HashSet<String>>,
+    series: HashSet<u64>,
+}
+
+/// Per-metric attribute cardinality limiter used by the metric writer.
+#[derive(Debug)]
+pub struct CardinalityLimiter {
+    config: CardinalityConfig,
+    names: HashMap<Uuid, String>,
+    metrics: HashMap<Uuid, MetricCardinality>,
+}
+
+impl CardinalityLimiter {
+    /// Create a limiter enforcing `config`.
+    pub fn new(config: CardinalityConfig) -> Self {
+        Self {
+            config,
+            names: HashMap::new(),
+            metrics: HashMap::new(),
+        }
+    }
+
+    /// Record the name of a metric, for its series limit override.
+    ///
+    /// Data points of metrics never registered use the default limit.
+    pub fn register_metric(&mut self, id: Uuid, name: &str) {
+        self.names.entry(id).or_insert_with(|| name.to_string());
+    }
+
+    /// Number of distinct attribute sets admitted for a metric.
+    pub fn series_count(&self, metric_id: Uuid) -> usize {
+        self.metrics
+            .get(&metric_id)
+            .map_or(0, |tracked| tracked.series.len())
+    }
+
+    /// Rewrite the attributes of data points that exceed a limit.
+    pub fn apply(&mut self, data_points: &mut [MetricDataPoint]) -> CardinalityOutcome {
+        let mut outcome = CardinalityOutcome::default();
+        if !self.config.enabled {
+            return outcome;
+        }
+
+        for data_point in data_points {
+            let series_limit = self
+                .names
+                .get(&data_point.metric_id)
+                .map_or(self.config.max_series_per_metric, |name| {
+                    self.config.series_limit(name)
+                });
+            let tracked = self.metrics.entry(data_point.metric_id).or_default();
+
+            let Value::Object(attributes) = &mut data_point.attributes else {
+                continue;
+            };
+
+            let mut capped = false;
+            for (key, value) in attributes.iter_mut() {
+                let text = match &*value {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                let values = tracked.values.entry(key.clone()).or_default();
+                if values.contains(&text) {
+                    continue;
+                }
+                if values.len() < self.config.max_values_per_attribute {
+                    values.insert(text);
+                } else {
+                    *value = Value::String(OVERFLOW_VALUE.to_string());
+                    capped = true;
+                }
+            }
+
+            let series = series_key(attributes);
+            if !tracked.series.contains(&series) {
+                if tracked.series.len() < series_limit {
+                    tracked.series.insert(series);
+                } else {
+                    data_point.attributes = overflow_series();
+                    outcome.series_overflows += 1;
+                    continue;
+                }
+            }
+
+            if capped {
+                outcome.attribute_overflows += 1;
+            }
+        }
+
+        outcome
+    }
+}
+
+/// Attributes of the series collecting data points beyond the series limit.
+fn overflow_series() -> Value {
+    let mut attributes = Map::new();
+    attributes.insert(
+        OVERFLOW_VALUE.to_string(),
+        Value::String("true".to_string()),
+    );
+    Value::Object(attributes)
+}
+
+/// Hash of an attribute set, independent of key order.
+fn series_key(attributes: &Map<String, Value>) -> u64 {
+    let mut pairs: Vec<_> = attributes.iter().collect();
+    pairs.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut hasher = DefaultHasher::new();
+    for (key, value) in pairs {
+        key.hash(&mut hasher);
+        value.to_string().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn data_point(metric_id: Uuid, attributes: Value) -> MetricDataPoint {
+        MetricDataPoint {
+            id: Uuid::new_v4(),
+            metric_id,
+            timestamp: Utc::now(),
+            value: Some(1.0),
+            count: None,
+            sum: None,
+            min: None,
+            max: None,
+            buckets: None,
+            quantiles: None,
+            exemplars: None,
+            attributes,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_attribute_values_overflow() {
+        let metric_id = Uuid::new_v4();
+        let mut limiter = CardinalityLimiter::new(CardinalityConfig {
+            max_values_per_attribute: 2,
+            ..CardinalityConfig::default()
+        });
+
+        let mut points: Vec<_> = ["u1", "u2", "u3", "u1"]
+            .iter()
+            .map(|user| data_point(metric_id, json!({"model": "gpt-4", "user_id": user})))
+            .collect();
+        let outcome = limiter.apply(&mut points);
+
+        assert_eq!(outcome.attribute_overflows, 1);
+        assert_eq!(outcome.series_overflows, 0);
+        assert_eq!(points[2].attributes["user_id"], OVERFLOW_VALUE);
+        assert_eq!(points[2].attributes["model"], "gpt-4");
+        assert_eq!(points[3].attributes["user_id"], "u1");
+        assert_eq!(limiter.series_count(metric_id), 3);
+    }
+
+    #[test]
+    fn test_series_overflow() {
+        let metric_id = Uuid::new_v4();
+        let other_id = Uuid::new_v4();
+        let mut config = CardinalityConfig {
+            max_series_per_metric: 2,
+            ..CardinalityConfig::default()
+        };
+        config
+            .metric_limits
+            .insert("llm_requests_total".to_string(), 3);
+        let mut limiter = CardinalityLimiter::new(config);
+        limiter.register_metric(other_id, "llm_requests_total");
+
+        let mut points: Vec<_> = (0..4)
+            .flat_map(|i| {
+                [
+                    data_point(metric_id, json!({"model": format!("m{}", i)})),
+                    data_point(other_id, json!({"model": format!("m{}", i)})),
+                ]
+            })
+            .collect();
+        // Known series stay below the limit
+        points.push(data_point(metric_id, json!({"model": "m0"})));
+        let outcome = limiter.apply(&mut points);
+
+        assert_eq!(outcome.series_overflows, 3);
+        assert_eq!(points[4].attributes, json!({"__overflow__": "true"}));
+        assert_eq!(points[5].attributes, json!({"model": "m2"}));
+        assert_eq!(points[7].attributes, json!({"__overflow__": "true"}));
+        assert_eq!(points[8].attributes, json!({"model": "m0"}));
+        assert_eq!(limiter.series_count(metric_id), 2);
+
+        let mut limiter = CardinalityLimiter::new(CardinalityConfig {
+            enabled: false,
+            max_series_per_metric: 1,
+            ..CardinalityConfig::default()
+        });
+        let mut points = vec![
+            data_point(metric_id, json!({"model": "a"})),
+            data_point(metric_id, json!({"model": "b"})),
+        ];
+        assert_eq!(limiter.apply(&mut points), CardinalityOutcome::default());
+        assert_eq!(points[1].attributes, json!({"model": "b"}));
+    }
+}
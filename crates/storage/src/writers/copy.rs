@@ -483,7 +483,8 @@ impl CopyWriter {
         let copy_stmt = "COPY logs (
             id, timestamp, observed_timestamp, severity_number, severity_text,
             body, service_name, trace_id, span_id, trace_flags, attributes,
-            resource_attributes, scope_name, scope_version, scope_attributes, created_at
+            resource_attributes, scope_name, scope_version, scope_attributes, pattern_id,
+            created_at
         ) FROM STDIN BINARY";
 
         let sink = client.copy_in(copy_stmt).await.map_err(|e| {
@@ -508,6 +509,7 @@ impl CopyWriter {
                 Type::TEXT,        // scope_name (nullable)
                 Type::TEXT,        // scope_version (nullable)
                 Type::JSONB,       // scope_attributes (nullable)
+                Type::TEXT,        // pattern_id (nullable)
                 Type::TIMESTAMPTZ, // created_at
             ],
         );
@@ -531,6 +533,7 @@ impl CopyWriter {
                 &log.scope_name,
                 &log.scope_version,
                 &log.scope_attributes,
+                &log.pattern_id,
                 &log.created_at,
             ];
 
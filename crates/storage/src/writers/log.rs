@@ -1,8 +1,14 @@
 //! Log writer for batch insertion of log data.
 
+use crate::config::BatchingConfig;
 use crate::error::{StorageError, StorageResult};
+use crate::metrics::StorageMetrics;
 use crate::models::LogRecord;
 use crate::pool::StoragePool;
+use crate::shutdown::{Flushable, Registration};
+use crate::writers::batching::{estimate_batch_size, AdaptiveBatcher, FlushReason};
+use async_trait::async_trait;
+use tracing::Instrument;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -14,6 +20,11 @@ pub struct LogWriter {
     pool: StoragePool,
     buffer: Arc<RwLock<LogBuffer>>,
     config: WriterConfig,
+    metrics: StorageMetrics,
+    /// Shutdown registration, shared by the clones the caller holds; the
+    /// clone registered with the pool has none, so dropping the last of them
+    /// releases the writer
+    registration: Option<Arc<Registration>>,
 }
 
 /// Configuration for the log writer.
@@ -27,14 +38,54 @@ pub struct WriterConfig {
 
     /// Maximum number of concurrent insert operations
     pub max_concurrency: usize,
+
+    /// Lower bound for the adaptive batch size
+    pub min_batch_size: usize,
+
+    /// Maximum estimated batch payload in bytes before flushing
+    pub max_batch_bytes: usize,
+
+    /// Maximum time a row may wait in the buffer (in milliseconds)
+    pub max_latency_ms: u64,
+
+    /// Target duration of a single flush used for adaptive sizing (in milliseconds)
+    pub target_flush_ms: u64,
+
+    /// Whether to adapt the batch size to recent write latency
+    pub adaptive: bool,
 }
 
 impl Default for WriterConfig {
     fn default() -> Self {
+        Self::from(&BatchingConfig::log_defaults())
+    }
+}
+
+impl From<&BatchingConfig> for WriterConfig {
+    fn from(batching: &BatchingConfig) -> Self {
         Self {
-            batch_size: 1000,
+            batch_size: batching.max_rows,
             flush_interval_secs: 5,
             max_concurrency: 4,
+            min_batch_size: batching.min_rows,
+            max_batch_bytes: batching.max_bytes,
+            max_latency_ms: batching.max_latency_ms,
+            target_flush_ms: batching.target_flush_ms,
+            adaptive: batching.adaptive,
+        }
+    }
+}
+
+impl WriterConfig {
+    /// Batching policy described by this configuration.
+    pub fn batching(&self) -> BatchingConfig {
+        BatchingConfig {
+            max_rows: self.batch_size,
+            min_rows: self.min_batch_size.clamp(1, self.batch_size.max(1)),
+            max_bytes: self.max_batch_bytes,
+            max_latency_ms: self.max_latency_ms,
+            target_flush_ms: self.target_flush_ms,
+            adaptive: self.adaptive,
         }
     }
 }
@@ -42,54 +93,63 @@ impl Default for WriterConfig {
 /// Internal buffer for log data.
 struct LogBuffer {
     logs: Vec<LogRecord>,
+    batcher: AdaptiveBatcher,
 }
 
-impl Default for LogBuffer {
-    fn default() -> Self {
-        Self { logs: Vec::new() }
+impl LogBuffer {
+    fn new(batching: BatchingConfig) -> Self {
+        Self {
+            logs: Vec::new(),
+            batcher: AdaptiveBatcher::new(batching),
+        }
     }
 }
 
 impl LogWriter {
     /// Create a new log writer.
+    ///
+    /// Uses the log batching policy from the pool's [`StorageConfig`](crate::StorageConfig).
     pub fn new(pool: StoragePool) -> Self {
-        Self::with_config(pool, WriterConfig::default())
+        let config = WriterConfig::from(&pool.config().writers.log);
+        Self::with_config(pool, config)
     }
 
     /// Create a new log writer with custom configuration.
+    ///
+    /// The writer registers with the pool so its buffer is flushed by
+    /// [`StoragePool::shutdown`] while any clone of it is alive; flush
+    /// before dropping the last clone to keep its buffered rows.
     pub fn with_config(pool: StoragePool, config: WriterConfig) -> Self {
-        Self {
+        let mut writer = Self {
             pool,
-            buffer: Arc::new(RwLock::new(LogBuffer::default())),
+            buffer: Arc::new(RwLock::new(LogBuffer::new(config.batching()))),
             config,
-        }
+            metrics: StorageMetrics::new(),
+            registration: None,
+        };
+        let registration = writer.pool.register_flushable(Arc::new(writer.clone()));
+        writer.registration = Some(Arc::new(registration));
+        writer
     }
 
     /// Write a single log record.
     ///
     /// The log will be buffered and inserted in the next batch.
     pub async fn write_log(&self, log: LogRecord) -> StorageResult<()> {
-        let mut buffer = self.buffer.write().await;
-        buffer.logs.push(log);
-
-        // Auto-flush if batch size reached
-        if buffer.logs.len() >= self.config.batch_size {
-            drop(buffer);
-            self.flush().await?;
-        }
-
-        Ok(())
+        self.write_logs(vec![log]).await
     }
 
     /// Write multiple log records in a batch.
     pub async fn write_logs(&self, logs: Vec<LogRecord>) -> StorageResult<()> {
+        self.pool.check_accepting_writes()?;
         let mut buffer = self.buffer.write().await;
+        buffer.batcher.record_append(logs.len(), estimate_batch_size(&logs));
         buffer.logs.extend(logs);
 
-        // Auto-flush if batch size reached
-        if buffer.logs.len() >= self.config.batch_size {
+        // Auto-flush if a batch limit was reached
+        if let Some(reason) = buffer.batcher.flush_reason() {
             drop(buffer);
-            self.flush().await?;
+            self.flush_with_reason(reason).await?;
         }
 
         Ok(())
@@ -97,19 +157,43 @@ impl LogWriter {
 
     /// Flush all buffered data to the database.
     pub async fn flush(&self) -> StorageResult<()> {
+        self.flush_with_reason(FlushReason::Manual).await
+    }
+
+    /// Flush all buffered data, recording what triggered the flush.
+    async fn flush_with_reason(&self, reason: FlushReason) -> StorageResult<()> {
         let mut buffer = self.buffer.write().await;
 
         // Take all buffered data
         let logs = std::mem::take(&mut buffer.logs);
+        buffer.batcher.reset();
 
         drop(buffer); // Release lock during insertion
 
-        // Insert logs
-        if !logs.is_empty() {
-            self.insert_logs(logs).await?;
+        let rows = logs.len();
+        if rows == 0 {
+            return Ok(());
         }
+        let start = std::time::Instant::now();
+        let verbosity = self.pool.config().tracing.verbosity;
+        let span = crate::spans::flush(verbosity, "log", reason, rows);
+        let result = self.insert_logs(logs).instrument(span.clone()).await;
+        crate::spans::record_result(&span, &result);
+        let elapsed = start.elapsed();
 
-        Ok(())
+        self.metrics.record_flush("log", result.is_ok());
+        if result.is_ok() {
+            let mut buffer = self.buffer.write().await;
+            buffer.batcher.record_flush(rows, elapsed);
+            let limit = buffer.batcher.row_limit();
+            drop(buffer);
+
+            self.metrics
+                .record_flush_trigger("log", reason.as_str(), rows, elapsed.as_secs_f64());
+            self.metrics.update_batch_row_limit("log", limit);
+        }
+
+        result
     }
 
     /// Insert logs using batch insert.
@@ -124,7 +208,7 @@ impl LogWriter {
         let mut query_builder = sqlx::QueryBuilder::new(
             "INSERT INTO logs (id, timestamp, observed_timestamp, severity_number, severity_text, \
              body, service_name, trace_id, span_id, trace_flags, attributes, resource_attributes, \
-             scope_name, scope_version, scope_attributes, created_at) "
+             scope_name, scope_version, scope_attributes, pattern_id, created_at) "
         );
 
         query_builder.push_values(logs, |mut b, log| {
@@ -143,12 +227,15 @@ impl LogWriter {
                 .push_bind(log.scope_name)
                 .push_bind(log.scope_version)
                 .push_bind(log.scope_attributes)
+                .push_bind(log.pattern_id)
                 .push_bind(log.created_at);
         });
 
-        query_builder
-            .build()
-            .execute(self.pool.postgres())
+        self.pool
+            .guarded(async {
+                query_builder.build().execute(self.pool.postgres()).await?;
+                Ok(())
+            })
             .await?;
 
         let elapsed = start.elapsed();
@@ -167,33 +254,60 @@ impl LogWriter {
         let buffer = self.buffer.read().await;
         BufferStats {
             logs_buffered: buffer.logs.len(),
+            bytes_buffered: buffer.batcher.pending_bytes(),
+            batch_row_limit: buffer.batcher.row_limit(),
         }
     }
 
-    /// Start automatic flushing based on time interval.
+    /// Start automatic flushing based on the latency limit and flush interval.
     ///
     /// Returns a handle that can be used to stop the auto-flush task.
     pub fn start_auto_flush(&self) -> tokio::task::JoinHandle<()> {
         let writer = self.clone();
-        let interval = std::time::Duration::from_secs(self.config.flush_interval_secs);
+        let flush_interval = std::time::Duration::from_secs(self.config.flush_interval_secs);
 
         tokio::spawn(async move {
-            let mut ticker = tokio::time::interval(interval);
+            let poll = writer.buffer.read().await.batcher.poll_interval();
+            let mut ticker = tokio::time::interval(poll.min(flush_interval));
             loop {
                 ticker.tick().await;
-                if let Err(e) = writer.flush().await {
-                    tracing::error!("Auto-flush error: {}", e);
+                let reason = writer.buffer.read().await.batcher.flush_reason();
+                if let Some(reason) = reason {
+                    if let Err(e) = writer.flush_with_reason(reason).await {
+                        tracing::error!("Auto-flush error: {}", e);
+                    }
                 }
             }
         })
     }
 }
 
+#[async_trait]
+impl Flushable for LogWriter {
+    fn name(&self) -> &'static str {
+        "log_writer"
+    }
+
+    async fn pending(&self) -> usize {
+        self.buffer_stats().await.logs_buffered
+    }
+
+    async fn flush(&self) -> StorageResult<()> {
+        self.flush_with_reason(FlushReason::Shutdown).await
+    }
+}
+
 /// Statistics about the writer's buffer.
 #[derive(Debug, Clone)]
 pub struct BufferStats {
     /// Number of logs currently buffered
     pub logs_buffered: usize,
+
+    /// Estimated size of buffered rows in bytes
+    pub bytes_buffered: usize,
+
+    /// Current effective batch row limit
+    pub batch_row_limit: usize,
 }
 
 #[cfg(test)]
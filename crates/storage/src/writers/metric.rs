@@ -1,19 +1,33 @@
 //! Metric writer for batch insertion of metric data.
 
+use crate::config::{BatchingConfig, CardinalityConfig};
 use crate::error::{StorageError, StorageResult};
+use crate::metrics::StorageMetrics;
 use crate::models::{Metric, MetricDataPoint};
 use crate::pool::StoragePool;
+use crate::shutdown::{Flushable, Registration};
+use crate::writers::batching::{estimate_batch_size, AdaptiveBatcher, EstimateSize, FlushReason};
+use crate::writers::cardinality::CardinalityLimiter;
+use async_trait::async_trait;
+use tracing::Instrument;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 /// Writer for batch insertion of metric data.
 ///
 /// This writer buffers metrics and data points, inserting them in batches for improved performance.
+/// Data point attributes are capped by the pool's
+/// [`CardinalityConfig`](crate::config::CardinalityConfig) before buffering.
 #[derive(Clone)]
 pub struct MetricWriter {
     pool: StoragePool,
     buffer: Arc<RwLock<MetricBuffer>>,
     config: WriterConfig,
+    metrics: StorageMetrics,
+    /// Shutdown registration, shared by the clones the caller holds; the
+    /// clone registered with the pool has none, so dropping the last of them
+    /// releases the writer
+    registration: Option<Arc<Registration>>,
 }
 
 /// Configuration for the metric writer.
@@ -27,14 +41,54 @@ pub struct WriterConfig {
 
     /// Maximum number of concurrent insert operations
     pub max_concurrency: usize,
+
+    /// Lower bound for the adaptive batch size
+    pub min_batch_size: usize,
+
+    /// Maximum estimated batch payload in bytes before flushing
+    pub max_batch_bytes: usize,
+
+    /// Maximum time a row may wait in the buffer (in milliseconds)
+    pub max_latency_ms: u64,
+
+    /// Target duration of a single flush used for adaptive sizing (in milliseconds)
+    pub target_flush_ms: u64,
+
+    /// Whether to adapt the batch size to recent write latency
+    pub adaptive: bool,
 }
 
 impl Default for WriterConfig {
     fn default() -> Self {
+        Self::from(&BatchingConfig::metric_defaults())
+    }
+}
+
+impl From<&BatchingConfig> for WriterConfig {
+    fn from(batching: &BatchingConfig) -> Self {
         Self {
-            batch_size: 500,
+            batch_size: batching.max_rows,
             flush_interval_secs: 5,
             max_concurrency: 4,
+            min_batch_size: batching.min_rows,
+            max_batch_bytes: batching.max_bytes,
+            max_latency_ms: batching.max_latency_ms,
+            target_flush_ms: batching.target_flush_ms,
+            adaptive: batching.adaptive,
+        }
+    }
+}
+
+impl WriterConfig {
+    /// Batching policy described by this configuration.
+    pub fn batching(&self) -> BatchingConfig {
+        BatchingConfig {
+            max_rows: self.batch_size,
+            min_rows: self.min_batch_size.clamp(1, self.batch_size.max(1)),
+            max_bytes: self.max_batch_bytes,
+            max_latency_ms: self.max_latency_ms,
+            target_flush_ms: self.target_flush_ms,
+            adaptive: self.adaptive,
         }
     }
 }
@@ -43,57 +97,76 @@ impl Default for WriterConfig {
 struct MetricBuffer {
     metrics: Vec<Metric>,
     data_points: Vec<MetricDataPoint>,
+    batcher: AdaptiveBatcher,
+    cardinality: CardinalityLimiter,
 }
 
-impl Default for MetricBuffer {
-    fn default() -> Self {
+impl MetricBuffer {
+    fn new(batching: BatchingConfig, cardinality: CardinalityConfig) -> Self {
         Self {
             metrics: Vec::new(),
             data_points: Vec::new(),
+            batcher: AdaptiveBatcher::new(batching),
+            cardinality: CardinalityLimiter::new(cardinality),
         }
     }
+
+    /// Append rows and return the flush trigger, if any.
+    fn append<T: EstimateSize>(&mut self, rows: &[T]) -> Option<FlushReason> {
+        self.batcher.record_append(rows.len(), estimate_batch_size(rows));
+        self.batcher.flush_reason()
+    }
 }
 
 impl MetricWriter {
     /// Create a new metric writer.
+    ///
+    /// Uses the metric batching policy from the pool's [`StorageConfig`](crate::StorageConfig).
     pub fn new(pool: StoragePool) -> Self {
-        Self::with_config(pool, WriterConfig::default())
+        let config = WriterConfig::from(&pool.config().writers.metric);
+        Self::with_config(pool, config)
     }
 
     /// Create a new metric writer with custom configuration.
+    ///
+    /// The writer registers with the pool so its buffer is flushed by
+    /// [`StoragePool::shutdown`] while any clone of it is alive; flush
+    /// before dropping the last clone to keep its buffered rows.
     pub fn with_config(pool: StoragePool, config: WriterConfig) -> Self {
-        Self {
+        let buffer = MetricBuffer::new(config.batching(), pool.config().cardinality.clone());
+        let mut writer = Self {
             pool,
-            buffer: Arc::new(RwLock::new(MetricBuffer::default())),
+            buffer: Arc::new(RwLock::new(buffer)),
             config,
-        }
+            metrics: StorageMetrics::new(),
+            registration: None,
+        };
+        let registration = writer.pool.register_flushable(Arc::new(writer.clone()));
+        writer.registration = Some(Arc::new(registration));
+        writer
     }
 
     /// Write a single metric definition.
     ///
     /// The metric will be buffered and inserted in the next batch.
     pub async fn write_metric(&self, metric: Metric) -> StorageResult<()> {
-        let mut buffer = self.buffer.write().await;
-        buffer.metrics.push(metric);
-
-        // Auto-flush if batch size reached
-        if buffer.metrics.len() >= self.config.batch_size {
-            drop(buffer);
-            self.flush().await?;
-        }
-
-        Ok(())
+        self.write_metrics(vec![metric]).await
     }
 
     /// Write multiple metrics in a batch.
     pub async fn write_metrics(&self, metrics: Vec<Metric>) -> StorageResult<()> {
+        self.pool.check_accepting_writes()?;
         let mut buffer = self.buffer.write().await;
+        for metric in &metrics {
+            buffer.cardinality.register_metric(metric.id, &metric.name);
+        }
+        let reason = buffer.append(&metrics);
         buffer.metrics.extend(metrics);
 
-        // Auto-flush if batch size reached
-        if buffer.metrics.len() >= self.config.batch_size {
+        // Auto-flush if a batch limit was reached
+        if let Some(reason) = reason {
             drop(buffer);
-            self.flush().await?;
+            self.flush_with_reason(reason).await?;
         }
 
         Ok(())
@@ -101,27 +174,34 @@ impl MetricWriter {
 
     /// Write a single data point.
     pub async fn write_data_point(&self, data_point: MetricDataPoint) -> StorageResult<()> {
-        let mut buffer = self.buffer.write().await;
-        buffer.data_points.push(data_point);
-
-        // Auto-flush if batch size reached
-        if buffer.data_points.len() >= self.config.batch_size {
-            drop(buffer);
-            self.flush().await?;
-        }
-
-        Ok(())
+        self.write_data_points(vec![data_point]).await
     }
 
     /// Write multiple data points in a batch.
-    pub async fn write_data_points(&self, data_points: Vec<MetricDataPoint>) -> StorageResult<()> {
+    ///
+    /// Attributes beyond a cardinality limit are replaced before buffering.
+    pub async fn write_data_points(
+        &self,
+        mut data_points: Vec<MetricDataPoint>,
+    ) -> StorageResult<()> {
+        self.pool.check_accepting_writes()?;
         let mut buffer = self.buffer.write().await;
+        let outcome = buffer.cardinality.apply(&mut data_points);
+        if outcome.attribute_overflows > 0 {
+            self.metrics
+                .record_cardinality_overflow("attribute", outcome.attribute_overflows);
+        }
+        if outcome.series_overflows > 0 {
+            self.metrics
+                .record_cardinality_overflow("series", outcome.series_overflows);
+        }
+        let reason = buffer.append(&data_points);
         buffer.data_points.extend(data_points);
 
-        // Auto-flush if batch size reached
-        if buffer.data_points.len() >= self.config.batch_size {
+        // Auto-flush if a batch limit was reached
+        if let Some(reason) = reason {
             drop(buffer);
-            self.flush().await?;
+            self.flush_with_reason(reason).await?;
         }
 
         Ok(())
@@ -129,22 +209,64 @@ impl MetricWriter {
 
     /// Flush all buffered data to the database.
     pub async fn flush(&self) -> StorageResult<()> {
+        self.flush_with_reason(FlushReason::Manual).await
+    }
+
+    /// Flush all buffered data, recording what triggered the flush.
+    async fn flush_with_reason(&self, reason: FlushReason) -> StorageResult<()> {
         let mut buffer = self.buffer.write().await;
 
         // Take all buffered data
         let metrics = std::mem::take(&mut buffer.metrics);
         let data_points = std::mem::take(&mut buffer.data_points);
+        buffer.batcher.reset();
 
         drop(buffer); // Release lock during insertion
 
+        let rows = metrics.len() + data_points.len();
+        if rows == 0 {
+            return Ok(());
+        }
+        let start = std::time::Instant::now();
+        let verbosity = self.pool.config().tracing.verbosity;
+        let span = crate::spans::flush(verbosity, "metric", reason, rows);
+        let result = self.insert_buffered(metrics, data_points).instrument(span.clone()).await;
+        crate::spans::record_result(&span, &result);
+        let elapsed = start.elapsed();
+
+        self.metrics.record_flush("metric", result.is_ok());
+        if result.is_ok() {
+            let mut buffer = self.buffer.write().await;
+            buffer.batcher.record_flush(rows, elapsed);
+            let limit = buffer.batcher.row_limit();
+            drop(buffer);
+
+            self.metrics
+                .record_flush_trigger("metric", reason.as_str(), rows, elapsed.as_secs_f64());
+            self.metrics.update_batch_row_limit("metric", limit);
+        }
+
+        result
+    }
+
+    /// Insert drained buffer contents.
+    async fn insert_buffered(
+        &self,
+        metrics: Vec<Metric>,
+        data_points: Vec<MetricDataPoint>,
+    ) -> StorageResult<()> {
+        let verbosity = self.pool.config().tracing.verbosity;
+
         // Insert metrics
         if !metrics.is_empty() {
-            self.insert_metrics(metrics).await?;
+            let span = crate::spans::insert(verbosity, "metrics", metrics.len());
+            self.insert_metrics(metrics).instrument(span).await?;
         }
 
         // Insert data points
         if !data_points.is_empty() {
-            self.insert_data_points(data_points).await?;
+            let span = crate::spans::insert(verbosity, "metric_data_points", data_points.len());
+            self.insert_data_points(data_points).instrument(span).await?;
         }
 
         Ok(())
@@ -188,9 +310,11 @@ impl MetricWriter {
              updated_at = EXCLUDED.updated_at"
         );
 
-        query_builder
-            .build()
-            .execute(self.pool.postgres())
+        self.pool
+            .guarded(async {
+                query_builder.build().execute(self.pool.postgres()).await?;
+                Ok(())
+            })
             .await?;
 
         let elapsed = start.elapsed();
@@ -234,9 +358,11 @@ impl MetricWriter {
                 .push_bind(dp.created_at);
         });
 
-        query_builder
-            .build()
-            .execute(self.pool.postgres())
+        self.pool
+            .guarded(async {
+                query_builder.build().execute(self.pool.postgres()).await?;
+                Ok(())
+            })
             .await?;
 
         let elapsed = start.elapsed();
@@ -256,8 +382,48 @@ impl MetricWriter {
         BufferStats {
             metrics_buffered: buffer.metrics.len(),
             data_points_buffered: buffer.data_points.len(),
+            bytes_buffered: buffer.batcher.pending_bytes(),
+            batch_row_limit: buffer.batcher.row_limit(),
         }
     }
+
+    /// Start automatic flushing based on the latency limit and flush interval.
+    ///
+    /// Returns a handle that can be used to stop the auto-flush task.
+    pub fn start_auto_flush(&self) -> tokio::task::JoinHandle<()> {
+        let writer = self.clone();
+        let flush_interval = std::time::Duration::from_secs(self.config.flush_interval_secs);
+
+        tokio::spawn(async move {
+            let poll = writer.buffer.read().await.batcher.poll_interval();
+            let mut ticker = tokio::time::interval(poll.min(flush_interval));
+            loop {
+                ticker.tick().await;
+                let reason = writer.buffer.read().await.batcher.flush_reason();
+                if let Some(reason) = reason {
+                    if let Err(e) = writer.flush_with_reason(reason).await {
+                        tracing::error!("Auto-flush error: {}", e);
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl Flushable for MetricWriter {
+    fn name(&self) -> &'static str {
+        "metric_writer"
+    }
+
+    async fn pending(&self) -> usize {
+        let stats = self.buffer_stats().await;
+        stats.metrics_buffered + stats.data_points_buffered
+    }
+
+    async fn flush(&self) -> StorageResult<()> {
+        self.flush_with_reason(FlushReason::Shutdown).await
+    }
 }
 
 /// Statistics about the writer's buffer.
@@ -268,6 +434,12 @@ pub struct BufferStats {
 
     /// Number of data points currently buffered
     pub data_points_buffered: usize,
+
+    /// Estimated size of buffered rows in bytes
+    pub bytes_buffered: usize,
+
+    /// Current effective batch row limit
+    pub batch_row_limit: usize,
 }
 
 #[cfg(test)]
@@ -3,11 +3,19 @@
 //! This module provides efficient batch writers for inserting traces, metrics,
 //! and logs into the database.
 //!
+//! Buffered writers flush on whichever comes first of a row limit, an estimated
+//! byte limit, or a maximum buffering latency (see [`batching`]). The metric
+//! writer caps the attribute cardinality of data points (see [`cardinality`]).
+//! Trace writes can be spread over parallel writers by trace ID (see [`sharded`]).
+//!
 //! Two write methods are available:
 //! - **INSERT** (default): Standard batch INSERT using sqlx QueryBuilder
 //! - **COPY**: PostgreSQL COPY protocol for 10-100x faster batch inserts
 
+pub mod batching;
+pub mod cardinality;
 pub mod trace;
+pub mod sharded;
 pub mod metric;
 pub mod log;
 pub mod copy;
@@ -15,7 +23,10 @@ pub mod instrumented;
 pub mod copy_instrumented;
 
 // Re-exports
-pub use trace::{TraceWriter, WriteMethod};
+pub use batching::{AdaptiveBatcher, FlushReason};
+pub use cardinality::{CardinalityLimiter, CardinalityOutcome};
+pub use trace::{ConflictMode, TraceWriter, WriteMethod};
+pub use sharded::ShardedTraceWriter;
 pub use metric::MetricWriter;
 pub use log::LogWriter;
 pub use copy::CopyWriter;
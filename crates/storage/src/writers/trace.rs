@@ -1,26 +1,43 @@
 //! Trace writer for batch insertion of trace data.
 
+use crate::compression::{compress_json, compress_json_opt};
+use crate::config::{BatchingConfig, CompressionConfig};
+use crate::encryption::FieldEncryptor;
 use crate::error::{StorageError, StorageResult};
+use crate::metrics::StorageMetrics;
 use crate::models::{Trace, TraceSpan, TraceEvent};
 use crate::pool::StoragePool;
+use crate::shutdown::{Flushable, Registration};
+use crate::writers::batching::{estimate_batch_size, AdaptiveBatcher, EstimateSize, FlushReason};
+use async_trait::async_trait;
+use tracing::Instrument;
+use std::collections::HashSet;
 use std::sync::Arc;
+use uuid::Uuid;
 use tokio::sync::RwLock;
 
 /// Writer for batch insertion of trace data.
 ///
 /// This writer buffers traces and inserts them in batches for improved performance.
+/// Batches are flushed when the row, byte or latency limit of the configured
+/// [`BatchingConfig`] is reached.
 #[derive(Clone)]
 pub struct TraceWriter {
     pool: StoragePool,
     buffer: Arc<RwLock<TraceBuffer>>,
     config: WriterConfig,
     stats: Arc<RwLock<WriteStats>>,
+    metrics: StorageMetrics,
+    /// Shutdown registration, shared by the clones the caller holds; the
+    /// clone registered with the pool has none, so dropping the last of them
+    /// releases the writer
+    registration: Option<Arc<Registration>>,
 }
 
 /// Configuration for the trace writer.
 #[derive(Debug, Clone)]
 pub struct WriterConfig {
-    /// Maximum number of traces to buffer before flushing
+    /// Maximum number of rows to buffer before flushing
     pub batch_size: usize,
 
     /// Maximum time to wait before flushing (in seconds)
@@ -28,14 +45,91 @@ pub struct WriterConfig {
 
     /// Maximum number of concurrent insert operations
     pub max_concurrency: usize,
+
+    /// Lower bound for the adaptive batch size
+    pub min_batch_size: usize,
+
+    /// Maximum estimated batch payload in bytes before flushing
+    pub max_batch_bytes: usize,
+
+    /// Maximum time a row may wait in the buffer (in milliseconds)
+    pub max_latency_ms: u64,
+
+    /// Target duration of a single flush used for adaptive sizing (in milliseconds)
+    pub target_flush_ms: u64,
+
+    /// Whether to adapt the batch size to recent write latency
+    pub adaptive: bool,
+
+    /// How rows that already exist (same trace_id / span_id) are handled
+    pub conflict_mode: ConflictMode,
+
+    /// Whether to parse span events into `trace_events` rows, so they can
+    /// be queried across the spans of a trace
+    pub extract_events: bool,
+
+    /// Compression of span events and event attributes
+    pub compression: CompressionConfig,
+
+    /// Encryption of sensitive span attributes, span events and event
+    /// attributes (none writes them in plaintext)
+    pub encryption: Option<Arc<FieldEncryptor>>,
+}
+
+/// How the writer resolves traces and spans that were already written.
+///
+/// Re-delivered OTLP batches and collector replays resend spans that are
+/// already stored. `Merge` makes those writes idempotent: stored rows are
+/// combined with the incoming ones instead of being duplicated or clobbered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictMode {
+    /// Merge incoming rows into stored rows (earliest start, latest end,
+    /// sticky error status, merged attributes)
+    #[default]
+    Merge,
+
+    /// Replace stored values with the incoming row
+    Overwrite,
+
+    /// Keep the stored row and discard the incoming one
+    Ignore,
 }
 
 impl Default for WriterConfig {
     fn default() -> Self {
+        Self::from(&BatchingConfig::trace_defaults())
+    }
+}
+
+impl From<&BatchingConfig> for WriterConfig {
+    fn from(batching: &BatchingConfig) -> Self {
         Self {
-            batch_size: 100,
+            batch_size: batching.max_rows,
             flush_interval_secs: 5,
             max_concurrency: 4,
+            min_batch_size: batching.min_rows,
+            max_batch_bytes: batching.max_bytes,
+            max_latency_ms: batching.max_latency_ms,
+            target_flush_ms: batching.target_flush_ms,
+            adaptive: batching.adaptive,
+            conflict_mode: ConflictMode::default(),
+            extract_events: true,
+            compression: CompressionConfig::default(),
+            encryption: None,
+        }
+    }
+}
+
+impl WriterConfig {
+    /// Batching policy described by this configuration.
+    pub fn batching(&self) -> BatchingConfig {
+        BatchingConfig {
+            max_rows: self.batch_size,
+            min_rows: self.min_batch_size.clamp(1, self.batch_size.max(1)),
+            max_bytes: self.max_batch_bytes,
+            max_latency_ms: self.max_latency_ms,
+            target_flush_ms: self.target_flush_ms,
+            adaptive: self.adaptive,
         }
     }
 }
@@ -45,31 +139,61 @@ struct TraceBuffer {
     traces: Vec<Trace>,
     spans: Vec<TraceSpan>,
     events: Vec<TraceEvent>,
+    batcher: AdaptiveBatcher,
 }
 
-impl Default for TraceBuffer {
-    fn default() -> Self {
+impl TraceBuffer {
+    fn new(batching: BatchingConfig) -> Self {
         Self {
             traces: Vec::new(),
             spans: Vec::new(),
             events: Vec::new(),
+            batcher: AdaptiveBatcher::new(batching),
         }
     }
+
+    /// Append rows and return the flush trigger, if any.
+    fn append<T: EstimateSize>(&mut self, rows: &[T]) -> Option<FlushReason> {
+        self.batcher.record_append(rows.len(), estimate_batch_size(rows));
+        self.batcher.flush_reason()
+    }
 }
 
 impl TraceWriter {
     /// Create a new trace writer.
+    ///
+    /// Uses the trace batching policy and payload compression from the
+    /// pool's [`StorageConfig`](crate::StorageConfig) and the pool's
+    /// encryptor.
     pub fn new(pool: StoragePool) -> Self {
-        Self::with_config(pool, WriterConfig::default())
+        let mut config = WriterConfig::from(&pool.config().writers.trace);
+        config.compression = pool.config().compression.clone();
+        config.encryption = pool.encryptor().cloned();
+        Self::with_config(pool, config)
     }
 
     /// Create a new trace writer with custom configuration.
+    ///
+    /// The writer registers with the pool so its buffer is flushed by
+    /// [`StoragePool::shutdown`] while any clone of it is alive; flush
+    /// before dropping the last clone to keep its buffered rows.
     pub fn with_config(pool: StoragePool, config: WriterConfig) -> Self {
+        let mut writer = Self::unregistered(pool, config);
+        let registration = writer.pool.register_flushable(Arc::new(writer.clone()));
+        writer.registration = Some(Arc::new(registration));
+        writer
+    }
+
+    /// Create a writer that is flushed on shutdown by its owner rather than
+    /// by the pool.
+    pub(crate) fn unregistered(pool: StoragePool, config: WriterConfig) -> Self {
         Self {
             pool,
-            buffer: Arc::new(RwLock::new(TraceBuffer::default())),
+            buffer: Arc::new(RwLock::new(TraceBuffer::new(config.batching()))),
             config,
             stats: Arc::new(RwLock::new(WriteStats::default())),
+            metrics: StorageMetrics::new(),
+            registration: None,
         }
     }
 
@@ -77,27 +201,20 @@ impl TraceWriter {
     ///
     /// The trace will be buffered and inserted in the next batch.
     pub async fn write_trace(&self, trace: Trace) -> StorageResult<()> {
-        let mut buffer = self.buffer.write().await;
-        buffer.traces.push(trace);
-
-        // Auto-flush if batch size reached
-        if buffer.traces.len() >= self.config.batch_size {
-            drop(buffer); // Release lock before flushing
-            self.flush().await?;
-        }
-
-        Ok(())
+        self.write_traces(vec![trace]).await
     }
 
     /// Write multiple traces in a batch.
     pub async fn write_traces(&self, traces: Vec<Trace>) -> StorageResult<()> {
+        self.pool.check_accepting_writes()?;
         let mut buffer = self.buffer.write().await;
+        let reason = buffer.append(&traces);
         buffer.traces.extend(traces);
 
-        // Auto-flush if batch size reached
-        if buffer.traces.len() >= self.config.batch_size {
-            drop(buffer);
-            self.flush().await?;
+        // Auto-flush if a batch limit was reached
+        if let Some(reason) = reason {
+            drop(buffer); // Release lock before flushing
+            self.flush_with_reason(reason).await?;
         }
 
         Ok(())
@@ -105,27 +222,20 @@ impl TraceWriter {
 
     /// Write a single span.
     pub async fn write_span(&self, span: TraceSpan) -> StorageResult<()> {
-        let mut buffer = self.buffer.write().await;
-        buffer.spans.push(span);
-
-        // Auto-flush if batch size reached
-        if buffer.spans.len() >= self.config.batch_size {
-            drop(buffer);
-            self.flush().await?;
-        }
-
-        Ok(())
+        self.write_spans(vec![span]).await
     }
 
     /// Write multiple spans in a batch.
     pub async fn write_spans(&self, spans: Vec<TraceSpan>) -> StorageResult<()> {
+        self.pool.check_accepting_writes()?;
         let mut buffer = self.buffer.write().await;
+        let reason = buffer.append(&spans);
         buffer.spans.extend(spans);
 
-        // Auto-flush if batch size reached
-        if buffer.spans.len() >= self.config.batch_size {
+        // Auto-flush if a batch limit was reached
+        if let Some(reason) = reason {
             drop(buffer);
-            self.flush().await?;
+            self.flush_with_reason(reason).await?;
         }
 
         Ok(())
@@ -133,30 +243,100 @@ impl TraceWriter {
 
     /// Write a single event.
     pub async fn write_event(&self, event: TraceEvent) -> StorageResult<()> {
+        self.pool.check_accepting_writes()?;
         let mut buffer = self.buffer.write().await;
+        let reason = buffer.append(std::slice::from_ref(&event));
         buffer.events.push(event);
 
+        if let Some(reason) = reason {
+            drop(buffer);
+            self.flush_with_reason(reason).await?;
+        }
+
         Ok(())
     }
 
     /// Flush all buffered data to the database.
     pub async fn flush(&self) -> StorageResult<()> {
+        self.flush_with_reason(FlushReason::Manual).await
+    }
+
+    /// Flush all buffered data, recording what triggered the flush.
+    async fn flush_with_reason(&self, reason: FlushReason) -> StorageResult<()> {
         let mut buffer = self.buffer.write().await;
 
         // Take all buffered data
         let traces = std::mem::take(&mut buffer.traces);
         let spans = std::mem::take(&mut buffer.spans);
         let events = std::mem::take(&mut buffer.events);
+        buffer.batcher.reset();
 
         drop(buffer); // Release lock during insertion
 
+        let rows = traces.len() + spans.len() + events.len();
+        if rows == 0 {
+            return Ok(());
+        }
+        let start = std::time::Instant::now();
+        let verbosity = self.pool.config().tracing.verbosity;
+        let span = crate::spans::flush(verbosity, "trace", reason, rows);
+        let result = self.insert_buffered(traces, spans, events).instrument(span.clone()).await;
+        crate::spans::record_result(&span, &result);
+        let elapsed = start.elapsed();
+
+        self.metrics.record_flush("trace", result.is_ok());
+        if result.is_ok() {
+            let mut buffer = self.buffer.write().await;
+            buffer.batcher.record_flush(rows, elapsed);
+            let limit = buffer.batcher.row_limit();
+            drop(buffer);
+
+            self.metrics
+                .record_flush_trigger("trace", reason.as_str(), rows, elapsed.as_secs_f64());
+            self.metrics.update_batch_row_limit("trace", limit);
+        }
+
+        result
+    }
+
+    /// Insert drained buffer contents.
+    async fn insert_buffered(
+        &self,
+        traces: Vec<Trace>,
+        spans: Vec<TraceSpan>,
+        events: Vec<TraceEvent>,
+    ) -> StorageResult<()> {
+        // Postgres rejects an upsert that touches the same row twice, so
+        // collapse duplicates inside the batch first.
+        let (traces, spans) = {
+            let mode = self.config.conflict_mode;
+            let trace_count = traces.len();
+            let span_count = spans.len();
+            let traces = dedupe_by_key(traces, mode, |t| t.trace_id.clone(), merge_trace);
+            let spans = dedupe_by_key(spans, mode, span_key, merge_span);
+
+            let collapsed = (trace_count - traces.len()) + (span_count - spans.len());
+            if collapsed > 0 {
+                tracing::debug!("Collapsed {} duplicate rows within batch", collapsed);
+                let mut stats = self.stats.write().await;
+                stats.duplicates_collapsed += collapsed as u64;
+            }
+
+            (traces, spans)
+        };
+        let (events, extracted) = self.extract_events(&spans, events);
+        let (spans, events) = self.compress_payloads(spans, events)?;
+        let (spans, mut events) = self.encrypt_payloads(spans, events).await?;
+
         // Insert traces with retry logic
         if !traces.is_empty() {
             let count = traces.len();
             let traces_clone = traces.clone();
             self.with_retry(|| async {
                 self.insert_traces(traces_clone.clone()).await
-            }).await?;
+            })
+            .instrument(crate::spans::insert(self.pool.config().tracing.verbosity, "traces", count))
+            .await?;
 
             // Update stats
             let mut stats = self.stats.write().await;
@@ -168,9 +348,15 @@ impl TraceWriter {
         if !spans.is_empty() {
             let count = spans.len();
             let spans_clone = spans.clone();
-            self.with_retry(|| async {
+            let inserted = self.with_retry(|| async {
                 self.insert_spans(spans_clone.clone()).await
-            }).await?;
+            })
+            .instrument(crate::spans::insert(self.pool.config().tracing.verbosity, "trace_spans", count))
+            .await?;
+
+            // Events of spans that were already stored were extracted when
+            // they were first written
+            events.retain(|e| !extracted.contains(&e.id) || inserted.contains(&e.span_id));
 
             // Update stats
             let mut stats = self.stats.write().await;
@@ -184,7 +370,9 @@ impl TraceWriter {
             let events_clone = events.clone();
             self.with_retry(|| async {
                 self.insert_events(events_clone.clone()).await
-            }).await?;
+            })
+            .instrument(crate::spans::insert(self.pool.config().tracing.verbosity, "trace_events", count))
+            .await?;
 
             // Update stats
             let mut stats = self.stats.write().await;
@@ -195,6 +383,77 @@ impl TraceWriter {
         Ok(())
     }
 
+    /// Parse the events of spans into event rows, appended to `events`.
+    ///
+    /// Returns the events and the IDs of the extracted ones. Done before
+    /// compression and encryption, which make span events unreadable.
+    fn extract_events(
+        &self,
+        spans: &[TraceSpan],
+        mut events: Vec<TraceEvent>,
+    ) -> (Vec<TraceEvent>, HashSet<Uuid>) {
+        let mut extracted = HashSet::new();
+        if !self.config.extract_events {
+            return (events, extracted);
+        }
+
+        for span in spans {
+            for event in TraceEvent::from_span_events(span) {
+                extracted.insert(event.id);
+                events.push(event);
+            }
+        }
+
+        (events, extracted)
+    }
+
+    /// Compress large span events and event attributes.
+    ///
+    /// Span links stay plain JSON so they can be queried.
+    ///
+    /// Done after deduplication so merging still sees plain JSON.
+    fn compress_payloads(
+        &self,
+        mut spans: Vec<TraceSpan>,
+        mut events: Vec<TraceEvent>,
+    ) -> StorageResult<(Vec<TraceSpan>, Vec<TraceEvent>)> {
+        let config = &self.config.compression;
+        if !config.is_enabled() {
+            return Ok((spans, events));
+        }
+
+        for span in &mut spans {
+            span.events = compress_json_opt(span.events.take(), config)?;
+        }
+        for event in &mut events {
+            event.attributes = compress_json(std::mem::take(&mut event.attributes), config)?;
+        }
+
+        Ok((spans, events))
+    }
+
+    /// Encrypt sensitive span attributes, span events and event attributes.
+    ///
+    /// Done after compression, which cannot shrink ciphertext.
+    async fn encrypt_payloads(
+        &self,
+        mut spans: Vec<TraceSpan>,
+        mut events: Vec<TraceEvent>,
+    ) -> StorageResult<(Vec<TraceSpan>, Vec<TraceEvent>)> {
+        let Some(encryptor) = &self.config.encryption else {
+            return Ok((spans, events));
+        };
+
+        for span in &mut spans {
+            encryptor.encrypt_span(span).await?;
+        }
+        for event in &mut events {
+            encryptor.encrypt_event(event).await?;
+        }
+
+        Ok((spans, events))
+    }
+
     /// Insert traces using batch insert.
     async fn insert_traces(&self, traces: Vec<Trace>) -> StorageResult<()> {
         if traces.is_empty() {
@@ -229,19 +488,13 @@ impl TraceWriter {
         });
 
         // Add ON CONFLICT clause to handle duplicates
-        query_builder.push(
-            " ON CONFLICT (trace_id) DO UPDATE SET \
-             end_time = EXCLUDED.end_time, \
-             duration_us = EXCLUDED.duration_us, \
-             status = EXCLUDED.status, \
-             status_message = EXCLUDED.status_message, \
-             span_count = EXCLUDED.span_count, \
-             updated_at = EXCLUDED.updated_at"
-        );
+        query_builder.push(trace_conflict_clause(self.config.conflict_mode));
 
-        query_builder
-            .build()
-            .execute(self.pool.postgres())
+        self.pool
+            .guarded(async {
+                query_builder.build().execute(self.pool.postgres()).await?;
+                Ok(())
+            })
             .await?;
 
         let elapsed = start.elapsed();
@@ -256,9 +509,12 @@ impl TraceWriter {
     }
 
     /// Insert spans using batch insert.
-    async fn insert_spans(&self, spans: Vec<TraceSpan>) -> StorageResult<()> {
+    ///
+    /// Returns the IDs of the spans that were inserted rather than merged
+    /// into, or ignored in favor of, a stored span.
+    async fn insert_spans(&self, spans: Vec<TraceSpan>) -> StorageResult<HashSet<Uuid>> {
         if spans.is_empty() {
-            return Ok(());
+            return Ok(HashSet::new());
         }
 
         tracing::debug!("Inserting {} spans", spans.len());
@@ -289,20 +545,16 @@ impl TraceWriter {
                 .push_bind(span.created_at);
         });
 
-        // Add ON CONFLICT clause to handle duplicates
-        query_builder.push(
-            " ON CONFLICT (span_id) DO UPDATE SET \
-             end_time = EXCLUDED.end_time, \
-             duration_us = EXCLUDED.duration_us, \
-             status = EXCLUDED.status, \
-             status_message = EXCLUDED.status_message, \
-             attributes = EXCLUDED.attributes, \
-             events = EXCLUDED.events"
-        );
+        // Add ON CONFLICT clause to handle duplicates; xmax is 0 for
+        // inserted rows and set for rows updated by the upsert
+        query_builder.push(span_conflict_clause(self.config.conflict_mode));
+        query_builder.push(" RETURNING id, xmax = 0");
 
-        query_builder
-            .build()
-            .execute(self.pool.postgres())
+        let rows: Vec<(Uuid, bool)> = self
+            .pool
+            .guarded(async {
+                Ok(query_builder.build_query_as().fetch_all(self.pool.postgres()).await?)
+            })
             .await?;
 
         let elapsed = start.elapsed();
@@ -313,7 +565,11 @@ impl TraceWriter {
             spans.len() as f64 / elapsed.as_secs_f64()
         );
 
-        Ok(())
+        Ok(rows
+            .into_iter()
+            .filter(|(_, inserted)| *inserted)
+            .map(|(id, _)| id)
+            .collect())
     }
 
     /// Insert events using batch insert.
@@ -338,9 +594,11 @@ impl TraceWriter {
                 .push_bind(event.created_at);
         });
 
-        query_builder
-            .build()
-            .execute(self.pool.postgres())
+        self.pool
+            .guarded(async {
+                query_builder.build().execute(self.pool.postgres()).await?;
+                Ok(())
+            })
             .await?;
 
         let elapsed = start.elapsed();
@@ -361,9 +619,33 @@ impl TraceWriter {
             traces_buffered: buffer.traces.len(),
             spans_buffered: buffer.spans.len(),
             events_buffered: buffer.events.len(),
+            bytes_buffered: buffer.batcher.pending_bytes(),
+            batch_row_limit: buffer.batcher.row_limit(),
         }
     }
 
+    /// Start automatic flushing based on the latency limit and flush interval.
+    ///
+    /// Returns a handle that can be used to stop the auto-flush task.
+    pub fn start_auto_flush(&self) -> tokio::task::JoinHandle<()> {
+        let writer = self.clone();
+        let flush_interval = std::time::Duration::from_secs(self.config.flush_interval_secs);
+
+        tokio::spawn(async move {
+            let poll = writer.buffer.read().await.batcher.poll_interval();
+            let mut ticker = tokio::time::interval(poll.min(flush_interval));
+            loop {
+                ticker.tick().await;
+                let reason = writer.buffer.read().await.batcher.flush_reason();
+                if let Some(reason) = reason {
+                    if let Err(e) = writer.flush_with_reason(reason).await {
+                        tracing::error!("Auto-flush error: {}", e);
+                    }
+                }
+            }
+        })
+    }
+
     /// Get write statistics.
     pub async fn write_stats(&self) -> WriteStats {
         let stats = self.stats.read().await;
@@ -547,6 +829,178 @@ impl TraceWriter {
     }
 }
 
+/// SQL expression picking the merged status of two rows.
+///
+/// `error` is sticky, and `unset` never replaces a concrete status.
+fn merged_status_sql(table: &str) -> String {
+    format!(
+        "CASE WHEN {t}.status = 'error' OR EXCLUDED.status = 'unset' THEN {t}.status \
+         ELSE EXCLUDED.status END",
+        t = table
+    )
+}
+
+/// ON CONFLICT clause for the traces table.
+fn trace_conflict_clause(mode: ConflictMode) -> String {
+    match mode {
+        ConflictMode::Ignore => " ON CONFLICT (trace_id) DO NOTHING".to_string(),
+        ConflictMode::Overwrite => " ON CONFLICT (trace_id) DO UPDATE SET \
+             end_time = EXCLUDED.end_time, \
+             duration_us = EXCLUDED.duration_us, \
+             status = EXCLUDED.status, \
+             status_message = EXCLUDED.status_message, \
+             span_count = EXCLUDED.span_count, \
+             updated_at = EXCLUDED.updated_at"
+            .to_string(),
+        ConflictMode::Merge => format!(
+            " ON CONFLICT (trace_id) DO UPDATE SET \
+             start_time = LEAST(traces.start_time, EXCLUDED.start_time), \
+             end_time = GREATEST(traces.end_time, EXCLUDED.end_time), \
+             duration_us = GREATEST(traces.duration_us, EXCLUDED.duration_us), \
+             status = {status}, \
+             status_message = COALESCE(EXCLUDED.status_message, traces.status_message), \
+             root_span_name = COALESCE(traces.root_span_name, EXCLUDED.root_span_name), \
+             attributes = traces.attributes || EXCLUDED.attributes, \
+             resource_attributes = traces.resource_attributes || EXCLUDED.resource_attributes, \
+             span_count = GREATEST(traces.span_count, EXCLUDED.span_count), \
+             updated_at = GREATEST(traces.updated_at, EXCLUDED.updated_at)",
+            status = merged_status_sql("traces")
+        ),
+    }
+}
+
+/// ON CONFLICT clause for the trace_spans table.
+fn span_conflict_clause(mode: ConflictMode) -> String {
+    match mode {
+        ConflictMode::Ignore => " ON CONFLICT (span_id, start_time) DO NOTHING".to_string(),
+        ConflictMode::Overwrite => " ON CONFLICT (span_id, start_time) DO UPDATE SET \
+             end_time = EXCLUDED.end_time, \
+             duration_us = EXCLUDED.duration_us, \
+             status = EXCLUDED.status, \
+             status_message = EXCLUDED.status_message, \
+             attributes = EXCLUDED.attributes, \
+             events = EXCLUDED.events"
+            .to_string(),
+        ConflictMode::Merge => format!(
+            " ON CONFLICT (span_id, start_time) DO UPDATE SET \
+             end_time = GREATEST(trace_spans.end_time, EXCLUDED.end_time), \
+             duration_us = GREATEST(trace_spans.duration_us, EXCLUDED.duration_us), \
+             status = {status}, \
+             status_message = COALESCE(EXCLUDED.status_message, trace_spans.status_message), \
+             attributes = trace_spans.attributes || EXCLUDED.attributes, \
+             events = COALESCE(EXCLUDED.events, trace_spans.events), \
+             links = COALESCE(EXCLUDED.links, trace_spans.links)",
+            status = merged_status_sql("trace_spans")
+        ),
+    }
+}
+
+/// Merged status of two rows, mirroring [`merged_status_sql`].
+fn merge_status(existing: &str, incoming: &str) -> String {
+    if existing == "error" || incoming == "unset" {
+        existing.to_string()
+    } else {
+        incoming.to_string()
+    }
+}
+
+/// Shallow-merge two JSON objects, with keys from `incoming` winning.
+fn merge_json(existing: &mut serde_json::Value, incoming: serde_json::Value) {
+    match (existing.as_object_mut(), incoming) {
+        (Some(target), serde_json::Value::Object(source)) => target.extend(source),
+        (_, incoming) if !incoming.is_null() => *existing = incoming,
+        _ => {}
+    }
+}
+
+fn max_opt<T: Ord>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Merge an incoming trace into an existing one, mirroring the SQL merge.
+fn merge_trace(existing: &mut Trace, incoming: Trace) {
+    existing.start_time = existing.start_time.min(incoming.start_time);
+    existing.end_time = max_opt(existing.end_time, incoming.end_time);
+    existing.duration_us = max_opt(existing.duration_us, incoming.duration_us);
+    existing.status = merge_status(&existing.status, &incoming.status);
+    existing.status_message = incoming.status_message.or(existing.status_message.take());
+    if existing.root_span_name.is_none() {
+        existing.root_span_name = incoming.root_span_name;
+    }
+    merge_json(&mut existing.attributes, incoming.attributes);
+    merge_json(&mut existing.resource_attributes, incoming.resource_attributes);
+    existing.span_count = existing.span_count.max(incoming.span_count);
+    existing.updated_at = existing.updated_at.max(incoming.updated_at);
+}
+
+/// Merge an incoming span into an existing one, mirroring the SQL merge.
+fn merge_span(existing: &mut TraceSpan, incoming: TraceSpan) {
+    existing.end_time = max_opt(existing.end_time, incoming.end_time);
+    existing.duration_us = max_opt(existing.duration_us, incoming.duration_us);
+    existing.status = merge_status(&existing.status, &incoming.status);
+    existing.status_message = incoming.status_message.or(existing.status_message.take());
+    merge_json(&mut existing.attributes, incoming.attributes);
+    if incoming.events.is_some() {
+        existing.events = incoming.events;
+    }
+    if incoming.links.is_some() {
+        existing.links = incoming.links;
+    }
+}
+
+/// Conflict key of a span, matching the `(span_id, start_time)` unique index.
+fn span_key(span: &TraceSpan) -> String {
+    format!("{}@{}", span.span_id, span.start_time.timestamp_micros())
+}
+
+/// Collapse rows sharing the same key according to the conflict mode.
+///
+/// The first occurrence keeps its position in the batch.
+fn dedupe_by_key<T, K, M>(rows: Vec<T>, mode: ConflictMode, key: K, merge: M) -> Vec<T>
+where
+    K: Fn(&T) -> String,
+    M: Fn(&mut T, T),
+{
+    let mut index: std::collections::HashMap<String, usize> =
+        std::collections::HashMap::with_capacity(rows.len());
+    let mut out: Vec<T> = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        match index.get(&key(&row)) {
+            Some(&pos) => match mode {
+                ConflictMode::Merge => merge(&mut out[pos], row),
+                ConflictMode::Overwrite => out[pos] = row,
+                ConflictMode::Ignore => {}
+            },
+            None => {
+                index.insert(key(&row), out.len());
+                out.push(row);
+            }
+        }
+    }
+
+    out
+}
+
+#[async_trait]
+impl Flushable for TraceWriter {
+    fn name(&self) -> &'static str {
+        "trace_writer"
+    }
+
+    async fn pending(&self) -> usize {
+        let stats = self.buffer_stats().await;
+        stats.traces_buffered + stats.spans_buffered + stats.events_buffered
+    }
+
+    async fn flush(&self) -> StorageResult<()> {
+        self.flush_with_reason(FlushReason::Shutdown).await
+    }
+}
+
 /// Statistics about the writer's buffer.
 #[derive(Debug, Clone)]
 pub struct BufferStats {
@@ -558,6 +1012,12 @@ pub struct BufferStats {
 
     /// Number of events currently buffered
     pub events_buffered: usize,
+
+    /// Estimated size of buffered rows in bytes
+    pub bytes_buffered: usize,
+
+    /// Current effective batch row limit
+    pub batch_row_limit: usize,
 }
 
 /// Statistics about write operations.
@@ -577,6 +1037,9 @@ pub struct WriteStats {
 
     /// Number of retries
     pub retries: u64,
+
+    /// Number of duplicate traces/spans collapsed within a batch
+    pub duplicates_collapsed: u64,
 }
 
 #[cfg(test)]
@@ -588,6 +1051,65 @@ mod tests {
         let config = WriterConfig::default();
         assert_eq!(config.batch_size, 100);
         assert_eq!(config.flush_interval_secs, 5);
+        assert_eq!(config.conflict_mode, ConflictMode::Merge);
+    }
+
+    #[test]
+    fn test_merge_status() {
+        assert_eq!(merge_status("unset", "ok"), "ok");
+        assert_eq!(merge_status("ok", "unset"), "ok");
+        assert_eq!(merge_status("error", "ok"), "error");
+        assert_eq!(merge_status("ok", "error"), "error");
+    }
+
+    #[test]
+    fn test_dedupe_merges_replayed_spans() {
+        let trace_id = uuid::Uuid::new_v4();
+        let start = chrono::Utc::now();
+        let first = TraceSpan::new(trace_id, "span-1".into(), "op".into(), "svc".into(), start);
+        let mut replay = first.clone();
+        replay.end_time = Some(start + chrono::Duration::milliseconds(20));
+        replay.duration_us = Some(20_000);
+        replay.status = "ok".to_string();
+        replay.attributes = serde_json::json!({"llm.cost.amount_usd": 0.01});
+        let other = TraceSpan::new(trace_id, "span-2".into(), "op".into(), "svc".into(), start);
+
+        let spans = dedupe_by_key(
+            vec![first, other, replay],
+            ConflictMode::Merge,
+            span_key,
+            merge_span,
+        );
+
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].span_id, "span-1");
+        assert_eq!(spans[0].status, "ok");
+        assert_eq!(spans[0].duration_us, Some(20_000));
+        assert_eq!(spans[0].attributes["llm.cost.amount_usd"], 0.01);
+    }
+
+    #[test]
+    fn test_dedupe_ignore_keeps_first() {
+        let start = chrono::Utc::now();
+        let first = Trace::new("t1".into(), "svc-a".into(), start);
+        let second = Trace::new("t1".into(), "svc-b".into(), start);
+
+        let traces = dedupe_by_key(
+            vec![first, second],
+            ConflictMode::Ignore,
+            |t| t.trace_id.clone(),
+            merge_trace,
+        );
+
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].service_name, "svc-a");
+    }
+
+    #[test]
+    fn test_conflict_clauses() {
+        assert!(trace_conflict_clause(ConflictMode::Ignore).contains("DO NOTHING"));
+        assert!(span_conflict_clause(ConflictMode::Merge).contains("trace_spans.attributes || EXCLUDED.attributes"));
+        assert!(trace_conflict_clause(ConflictMode::Merge).contains("LEAST(traces.start_time"));
     }
 
     // Unit tests for UUID resolution functionality
@@ -709,6 +1231,10 @@ mod tests {
                 environment: Some("production".to_string()),
                 ..Default::default()
             };
+            llm_span
+                .metadata
+                .attributes
+                .insert("experiment.variant".to_string(), "treatment".to_string());
 
             let trace_span = TraceSpan::from(llm_span);
             let attrs = trace_span.attributes.as_object().unwrap();
@@ -716,6 +1242,7 @@ mod tests {
             assert_eq!(attrs.get("user.id").unwrap().as_str().unwrap(), "user123");
             assert_eq!(attrs.get("session.id").unwrap().as_str().unwrap(), "session456");
             assert_eq!(attrs.get("deployment.environment").unwrap().as_str().unwrap(), "production");
+            assert_eq!(attrs.get("experiment.variant").unwrap().as_str().unwrap(), "treatment");
             assert_eq!(trace_span.service_name, "production");
         }
 
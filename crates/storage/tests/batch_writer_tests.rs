@@ -65,6 +65,7 @@ async fn test_trace_writer_batch() -> Result<(), Box<dyn std::error::Error>> {
         batch_size: 50,
         flush_interval_secs: 5,
         max_concurrency: 4,
+        ..Default::default()
     };
 
     let writer = TraceWriter::with_config(pool.clone(), config);
@@ -161,6 +162,7 @@ async fn test_log_writer_auto_flush() -> Result<(), Box<dyn std::error::Error>>
             scope_name: None,
             scope_version: None,
             scope_attributes: None,
+            pattern_id: None,
             created_at: Utc::now(),
         };
 
@@ -258,6 +260,7 @@ async fn test_performance_benchmark() -> Result<(), Box<dyn std::error::Error>>
         batch_size: 500,
         flush_interval_secs: 10,
         max_concurrency: 8,
+        ..Default::default()
     };
 
     let writer = TraceWriter::with_config(pool.clone(), config);
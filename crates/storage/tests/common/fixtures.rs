@@ -182,6 +182,7 @@ pub fn create_test_log(
         scope_name: Some("test-scope".to_string()),
         scope_version: Some("1.0.0".to_string()),
         scope_attributes: Some(serde_json::json!({})),
+        pattern_id: None,
         created_at: now,
     }
 }
@@ -235,6 +236,7 @@ pub fn create_custom_log(
         scope_name: None,
         scope_version: None,
         scope_attributes: None,
+        pattern_id: None,
         created_at: now,
     }
 }
@@ -41,7 +41,9 @@ pub fn get_test_config(database_url: &str) -> StorageConfig {
             username: "test".to_string(),
             password: "test".to_string(),
             ssl_mode: "disable".to_string(),
+            ssl_root_cert: None,
             application_name: "llm-observatory-test".to_string(),
+            credentials: Default::default(),
         },
         redis: None,
         pool: llm_observatory_storage::config::PoolConfig {
@@ -57,6 +59,22 @@ pub fn get_test_config(database_url: &str) -> StorageConfig {
             max_delay_ms: 1000,
             backoff_multiplier: 2.0,
         },
+        writers: Default::default(),
+        partitioning: Default::default(),
+        circuit_breaker: Default::default(),
+        query: Default::default(),
+        compression: Default::default(),
+        health_history: Default::default(),
+        tracing: Default::default(),
+        cardinality: Default::default(),
+        downsampling: Default::default(),
+        top_n: Default::default(),
+        backend: Default::default(),
+        sqlite: Default::default(),
+        encryption: Default::default(),
+        residency: Default::default(),
+        lifecycle: Default::default(),
+        telemetry: Default::default(),
     }
 }
 
@@ -91,7 +109,9 @@ pub fn config_from_url(database_url: &str) -> StorageConfig {
             username,
             password,
             ssl_mode: "disable".to_string(),
+            ssl_root_cert: None,
             application_name: "llm-observatory-test".to_string(),
+            credentials: Default::default(),
         },
         redis: None,
         pool: llm_observatory_storage::config::PoolConfig {
@@ -107,6 +127,22 @@ pub fn config_from_url(database_url: &str) -> StorageConfig {
             max_delay_ms: 1000,
             backoff_multiplier: 2.0,
         },
+        writers: Default::default(),
+        partitioning: Default::default(),
+        circuit_breaker: Default::default(),
+        query: Default::default(),
+        compression: Default::default(),
+        health_history: Default::default(),
+        tracing: Default::default(),
+        cardinality: Default::default(),
+        downsampling: Default::default(),
+        top_n: Default::default(),
+        backend: Default::default(),
+        sqlite: Default::default(),
+        encryption: Default::default(),
+        residency: Default::default(),
+        lifecycle: Default::default(),
+        telemetry: Default::default(),
     }
 }
 
@@ -157,7 +193,7 @@ pub async fn run_test_migrations(pool: &StoragePool) {
         CREATE TABLE IF NOT EXISTS trace_spans (
             id UUID PRIMARY KEY,
             trace_id UUID NOT NULL,
-            span_id VARCHAR(32) UNIQUE NOT NULL,
+            span_id VARCHAR(32) NOT NULL,
             parent_span_id VARCHAR(32),
             name VARCHAR(255) NOT NULL,
             kind VARCHAR(50) NOT NULL,
@@ -170,7 +206,8 @@ pub async fn run_test_migrations(pool: &StoragePool) {
             attributes JSONB NOT NULL DEFAULT '{}',
             events JSONB,
             links JSONB,
-            created_at TIMESTAMPTZ NOT NULL
+            created_at TIMESTAMPTZ NOT NULL,
+            UNIQUE(span_id, start_time)
         )
         "#,
     )
@@ -256,6 +293,7 @@ pub async fn run_test_migrations(pool: &StoragePool) {
             scope_name VARCHAR(255),
             scope_version VARCHAR(50),
             scope_attributes JSONB,
+            pattern_id TEXT,
             created_at TIMESTAMPTZ NOT NULL
         )
         "#,
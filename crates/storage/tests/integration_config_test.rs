@@ -21,11 +21,29 @@ fn test_config_from_individual_components() {
             username: "testuser".to_string(),
             password: "testpass".to_string(),
             ssl_mode: "disable".to_string(),
+            ssl_root_cert: None,
             application_name: "test-app".to_string(),
+            credentials: Default::default(),
         },
         redis: None,
         pool: PoolConfig::default(),
         retry: RetryConfig::default(),
+        writers: Default::default(),
+        partitioning: Default::default(),
+        circuit_breaker: Default::default(),
+        query: Default::default(),
+        compression: Default::default(),
+        health_history: Default::default(),
+        tracing: Default::default(),
+        cardinality: Default::default(),
+        downsampling: Default::default(),
+        top_n: Default::default(),
+        backend: Default::default(),
+        sqlite: Default::default(),
+        encryption: Default::default(),
+        residency: Default::default(),
+        lifecycle: Default::default(),
+        telemetry: Default::default(),
     };
 
     assert_eq!(config.postgres.host, "localhost");
@@ -44,11 +62,29 @@ fn test_config_postgres_url_generation() {
             username: "user".to_string(),
             password: "pass123".to_string(),
             ssl_mode: "require".to_string(),
+            ssl_root_cert: None,
             application_name: "myapp".to_string(),
+            credentials: Default::default(),
         },
         redis: None,
         pool: PoolConfig::default(),
         retry: RetryConfig::default(),
+        writers: Default::default(),
+        partitioning: Default::default(),
+        circuit_breaker: Default::default(),
+        query: Default::default(),
+        compression: Default::default(),
+        health_history: Default::default(),
+        tracing: Default::default(),
+        cardinality: Default::default(),
+        downsampling: Default::default(),
+        top_n: Default::default(),
+        backend: Default::default(),
+        sqlite: Default::default(),
+        encryption: Default::default(),
+        residency: Default::default(),
+        lifecycle: Default::default(),
+        telemetry: Default::default(),
     };
 
     let url = config.postgres_url();
@@ -71,7 +107,9 @@ fn test_config_with_redis() {
             username: "user".to_string(),
             password: "pass".to_string(),
             ssl_mode: "disable".to_string(),
+            ssl_root_cert: None,
             application_name: "test".to_string(),
+            credentials: Default::default(),
         },
         redis: Some(RedisConfig {
             url: "redis://localhost:6379/0".to_string(),
@@ -80,6 +118,22 @@ fn test_config_with_redis() {
         }),
         pool: PoolConfig::default(),
         retry: RetryConfig::default(),
+        writers: Default::default(),
+        partitioning: Default::default(),
+        circuit_breaker: Default::default(),
+        query: Default::default(),
+        compression: Default::default(),
+        health_history: Default::default(),
+        tracing: Default::default(),
+        cardinality: Default::default(),
+        downsampling: Default::default(),
+        top_n: Default::default(),
+        backend: Default::default(),
+        sqlite: Default::default(),
+        encryption: Default::default(),
+        residency: Default::default(),
+        lifecycle: Default::default(),
+        telemetry: Default::default(),
     };
 
     assert!(config.redis.is_some());
@@ -135,7 +189,9 @@ fn test_postgres_config_validation_success() {
         username: "user".to_string(),
         password: "pass".to_string(),
         ssl_mode: "prefer".to_string(),
+        ssl_root_cert: None,
         application_name: "app".to_string(),
+        credentials: Default::default(),
     };
 
     assert!(config.validate().is_ok());
@@ -150,7 +206,9 @@ fn test_postgres_config_validation_empty_host() {
         username: "user".to_string(),
         password: "pass".to_string(),
         ssl_mode: "disable".to_string(),
+        ssl_root_cert: None,
         application_name: "app".to_string(),
+        credentials: Default::default(),
     };
 
     assert!(config.validate().is_err());
@@ -165,7 +223,9 @@ fn test_postgres_config_validation_zero_port() {
         username: "user".to_string(),
         password: "pass".to_string(),
         ssl_mode: "disable".to_string(),
+        ssl_root_cert: None,
         application_name: "app".to_string(),
+        credentials: Default::default(),
     };
 
     assert!(config.validate().is_err());
@@ -180,7 +240,9 @@ fn test_postgres_config_validation_empty_database() {
         username: "user".to_string(),
         password: "pass".to_string(),
         ssl_mode: "disable".to_string(),
+        ssl_root_cert: None,
         application_name: "app".to_string(),
+        credentials: Default::default(),
     };
 
     assert!(config.validate().is_err());
@@ -195,7 +257,9 @@ fn test_postgres_config_validation_invalid_ssl_mode() {
         username: "user".to_string(),
         password: "pass".to_string(),
         ssl_mode: "invalid".to_string(),
+        ssl_root_cert: None,
         application_name: "app".to_string(),
+        credentials: Default::default(),
     };
 
     assert!(config.validate().is_err());
@@ -213,7 +277,9 @@ fn test_postgres_config_validation_valid_ssl_modes() {
             username: "user".to_string(),
             password: "pass".to_string(),
             ssl_mode: ssl_mode.to_string(),
+            ssl_root_cert: None,
             application_name: "app".to_string(),
+            credentials: Default::default(),
         };
 
         assert!(config.validate().is_ok(), "SSL mode {} should be valid", ssl_mode);
@@ -428,7 +494,9 @@ fn test_config_duration_conversions() {
             username: "user".to_string(),
             password: "pass".to_string(),
             ssl_mode: "disable".to_string(),
+            ssl_root_cert: None,
             application_name: "app".to_string(),
+            credentials: Default::default(),
         },
         redis: None,
         pool: PoolConfig {
@@ -444,6 +512,22 @@ fn test_config_duration_conversions() {
             max_delay_ms: 7500,
             backoff_multiplier: 2.0,
         },
+        writers: Default::default(),
+        partitioning: Default::default(),
+        circuit_breaker: Default::default(),
+        query: Default::default(),
+        compression: Default::default(),
+        health_history: Default::default(),
+        tracing: Default::default(),
+        cardinality: Default::default(),
+        downsampling: Default::default(),
+        top_n: Default::default(),
+        backend: Default::default(),
+        sqlite: Default::default(),
+        encryption: Default::default(),
+        residency: Default::default(),
+        lifecycle: Default::default(),
+        telemetry: Default::default(),
     };
 
     assert_eq!(config.connect_timeout().as_secs(), 15);
@@ -463,7 +547,9 @@ fn test_full_config_validation() {
             username: "user".to_string(),
             password: "pass".to_string(),
             ssl_mode: "disable".to_string(),
+            ssl_root_cert: None,
             application_name: "app".to_string(),
+            credentials: Default::default(),
         },
         redis: Some(RedisConfig {
             url: "redis://localhost:6379".to_string(),
@@ -472,6 +558,22 @@ fn test_full_config_validation() {
         }),
         pool: PoolConfig::default(),
         retry: RetryConfig::default(),
+        writers: Default::default(),
+        partitioning: Default::default(),
+        circuit_breaker: Default::default(),
+        query: Default::default(),
+        compression: Default::default(),
+        health_history: Default::default(),
+        tracing: Default::default(),
+        cardinality: Default::default(),
+        downsampling: Default::default(),
+        top_n: Default::default(),
+        backend: Default::default(),
+        sqlite: Default::default(),
+        encryption: Default::default(),
+        residency: Default::default(),
+        lifecycle: Default::default(),
+        telemetry: Default::default(),
     };
 
     assert!(config.validate().is_ok());
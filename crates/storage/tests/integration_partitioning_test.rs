@@ -0,0 +1,151 @@
+//! Integration tests for partitioning existing tables.
+//!
+//! This test suite validates that migration 009 converts populated `logs`
+//! and `trace_spans` tables into partitioned parents, keeping their rows,
+//! including rows from today and the future, in the legacy partition.
+
+mod common;
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use common::*;
+use llm_observatory_storage::StoragePool;
+use uuid::Uuid;
+
+async fn insert_log(pool: &StoragePool, timestamp: DateTime<Utc>) {
+    sqlx::query(
+        "INSERT INTO logs (id, timestamp, observed_timestamp, severity_number, severity_text, \
+         body, service_name, created_at) \
+         VALUES ($1, $2, $2, 9, 'INFO', 'request handled', 'api', $2)",
+    )
+    .bind(Uuid::new_v4())
+    .bind(timestamp)
+    .execute(pool.postgres())
+    .await
+    .expect("Failed to insert log");
+}
+
+async fn insert_span(pool: &StoragePool, start_time: DateTime<Utc>) {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO trace_spans (id, trace_id, span_id, name, kind, service_name, \
+         start_time, status, created_at) \
+         VALUES ($1, $2, $3, 'llm.chat', 'client', 'api', $4, 'ok', $4)",
+    )
+    .bind(id)
+    .bind(Uuid::new_v4())
+    .bind(&id.simple().to_string()[..16])
+    .bind(start_time)
+    .execute(pool.postgres())
+    .await
+    .expect("Failed to insert span");
+}
+
+/// Partitions of a parent table with their bounds
+async fn partitions(pool: &StoragePool, table: &str) -> Vec<(String, String)> {
+    sqlx::query_as(
+        "SELECT c.relname::text, pg_get_expr(c.relpartbound, c.oid) \
+         FROM pg_inherits i \
+         JOIN pg_class c ON c.oid = i.inhrelid \
+         JOIN pg_class p ON p.oid = i.inhparent \
+         WHERE p.relname = $1 \
+         ORDER BY c.relname",
+    )
+    .bind(table)
+    .fetch_all(pool.postgres())
+    .await
+    .expect("Failed to list partitions")
+}
+
+/// Names of the indexes of a table
+async fn indexes(pool: &StoragePool, table: &str) -> Vec<String> {
+    sqlx::query_scalar("SELECT indexname::text FROM pg_indexes WHERE tablename = $1")
+        .bind(table)
+        .fetch_all(pool.postgres())
+        .await
+        .expect("Failed to list indexes")
+}
+
+#[tokio::test]
+#[ignore] // Requires database
+async fn test_partition_populated_tables() {
+    let (pool, _guard) = setup_test_pool().await;
+    cleanup_test_data(&pool).await;
+
+    let now = Utc::now();
+    let tomorrow = now + Duration::days(1);
+    insert_log(&pool, now - Duration::days(30)).await;
+    insert_log(&pool, now).await;
+    insert_log(&pool, tomorrow).await;
+    insert_span(&pool, now).await;
+
+    sqlx::raw_sql(include_str!("../migrations/009_partitioned_tables.sql"))
+        .execute(pool.postgres())
+        .await
+        .expect("Failed to apply migration 009");
+
+    // Existing rows stay in the legacy partition, which ends after the day
+    // of its newest row
+    let logs = partitions(&pool, "logs").await;
+    assert_eq!(
+        logs.iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>(),
+        ["logs_default", "logs_legacy"]
+    );
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM ONLY logs_legacy")
+        .fetch_one(pool.postgres())
+        .await
+        .unwrap();
+    assert_eq!(count, 3);
+
+    let (bound,): (DateTime<Utc>,) = sqlx::query_as(
+        "SELECT date_trunc('day', MAX(timestamp)) + INTERVAL '1 day' FROM logs_legacy",
+    )
+    .fetch_one(pool.postgres())
+    .await
+    .unwrap();
+    assert!(bound > tomorrow);
+    assert!(logs[1].1.starts_with("FOR VALUES FROM (MINVALUE) TO ("));
+
+    let spans = partitions(&pool, "trace_spans").await;
+    assert_eq!(spans.len(), 2);
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM trace_spans")
+        .fetch_one(pool.postgres())
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+
+    // The parents get their own indexes; the legacy ones are renamed
+    let span_indexes = indexes(&pool, "trace_spans").await;
+    assert!(span_indexes.contains(&"idx_trace_spans_trace_id".to_string()));
+    assert!(indexes(&pool, "trace_spans_legacy")
+        .await
+        .contains(&"idx_trace_spans_trace_id_legacy".to_string()));
+    assert!(indexes(&pool, "logs")
+        .await
+        .contains(&"idx_logs_service_name".to_string()));
+
+    // Later rows land in partitions created after the migration
+    let later = (bound + Duration::days(1))
+        .duration_trunc(Duration::days(1))
+        .unwrap();
+    sqlx::query(&format!(
+        "CREATE TABLE logs_later PARTITION OF logs FOR VALUES FROM ('{}') TO ('{}')",
+        later.to_rfc3339(),
+        (later + Duration::days(1)).to_rfc3339()
+    ))
+    .execute(pool.postgres())
+    .await
+    .expect("Failed to create partition");
+    insert_log(&pool, later + Duration::hours(1)).await;
+
+    // The CHECK constraint only served the attach
+    let (checks,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM pg_constraint \
+         WHERE conrelid = 'logs_legacy'::regclass AND conname = 'logs_legacy_bound'",
+    )
+    .fetch_one(pool.postgres())
+    .await
+    .unwrap();
+    assert_eq!(checks, 0);
+}
@@ -0,0 +1,195 @@
+//! Integration tests for row-level security.
+//!
+//! This test suite validates that transactions opened with
+//! `StoragePool::with_tenant` only see the rows of their organization once
+//! the tenant isolation policies of migrations 019 and 044 are in place.
+
+mod common;
+
+use chrono::Utc;
+use common::*;
+use llm_observatory_storage::{StorageError, StoragePool};
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+/// Database role restricted by the tenant isolation policies
+const TENANT_ROLE: &str = "rls_test_reader";
+
+/// Apply migration 044 with the function and role it needs from 019.
+///
+/// The test schema lacks the tables covered by 019, so only its
+/// prerequisites are created here.
+async fn enable_row_level_security(pool: &StoragePool) {
+    sqlx::raw_sql(
+        r#"
+        CREATE OR REPLACE FUNCTION app_current_org_id()
+        RETURNS TEXT
+        LANGUAGE sql
+        STABLE
+        AS $$
+            SELECT NULLIF(current_setting('app.org_id', true), '')
+        $$;
+
+        DO $$
+        BEGIN
+            IF NOT EXISTS (SELECT FROM pg_roles WHERE rolname = 'llm_observatory_tenant') THEN
+                CREATE ROLE llm_observatory_tenant NOLOGIN;
+            END IF;
+            IF NOT EXISTS (SELECT FROM pg_roles WHERE rolname = 'rls_test_reader') THEN
+                CREATE ROLE rls_test_reader NOLOGIN IN ROLE llm_observatory_tenant;
+            END IF;
+        END
+        $$;
+
+        GRANT SELECT ON traces, trace_spans, trace_events, logs TO rls_test_reader;
+        "#,
+    )
+    .execute(pool.postgres())
+    .await
+    .expect("Failed to create tenant role");
+
+    sqlx::raw_sql(include_str!(
+        "../migrations/044_trace_log_row_level_security.sql"
+    ))
+    .execute(pool.postgres())
+    .await
+    .expect("Failed to apply migration 044");
+}
+
+/// Insert a trace of `org_id` with one span, one span event and one log.
+///
+/// The organization is only on the trace's resource, so the span and the
+/// event inherit it.
+async fn insert_tenant_data(pool: &StoragePool, org_id: &str) {
+    let now = Utc::now();
+    let trace_id = Uuid::new_v4();
+    let span_id = Uuid::new_v4();
+
+    sqlx::query(
+        "INSERT INTO traces (id, trace_id, service_name, start_time, status, \
+         resource_attributes, created_at, updated_at) \
+         VALUES ($1, $2, 'api', $3, 'ok', jsonb_build_object('org_id', $4::TEXT), $3, $3)",
+    )
+    .bind(trace_id)
+    .bind(trace_id.simple().to_string())
+    .bind(now)
+    .bind(org_id)
+    .execute(pool.postgres())
+    .await
+    .expect("Failed to insert trace");
+
+    sqlx::query(
+        "INSERT INTO trace_spans (id, trace_id, span_id, name, kind, service_name, \
+         start_time, status, created_at) \
+         VALUES ($1, $2, $3, 'llm.chat', 'client', 'api', $4, 'ok', $4)",
+    )
+    .bind(span_id)
+    .bind(trace_id)
+    .bind(&span_id.simple().to_string()[..16])
+    .bind(now)
+    .execute(pool.postgres())
+    .await
+    .expect("Failed to insert span");
+
+    sqlx::query(
+        "INSERT INTO trace_events (id, span_id, name, timestamp, created_at) \
+         VALUES ($1, $2, 'guardrail.evaluation', $3, $3)",
+    )
+    .bind(Uuid::new_v4())
+    .bind(span_id)
+    .bind(now)
+    .execute(pool.postgres())
+    .await
+    .expect("Failed to insert span event");
+
+    sqlx::query(
+        "INSERT INTO logs (id, timestamp, observed_timestamp, severity_number, severity_text, \
+         body, service_name, attributes, created_at) \
+         VALUES ($1, $2, $2, 9, 'INFO', 'request handled', 'api', \
+         jsonb_build_object('org_id', $3::TEXT), $2)",
+    )
+    .bind(Uuid::new_v4())
+    .bind(now)
+    .bind(org_id)
+    .execute(pool.postgres())
+    .await
+    .expect("Failed to insert log");
+}
+
+/// Rows of each table visible in the transaction, as the tenant role
+async fn visible_rows(tx: &mut Transaction<'static, Postgres>) -> [i64; 4] {
+    sqlx::query(&format!("SET LOCAL ROLE {}", TENANT_ROLE))
+        .execute(&mut **tx)
+        .await
+        .expect("Failed to switch to the tenant role");
+
+    let mut counts = [0; 4];
+    for (count, table) in counts
+        .iter_mut()
+        .zip(["traces", "trace_spans", "trace_events", "logs"])
+    {
+        let (rows,): (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(&mut **tx)
+            .await
+            .expect("Failed to count rows");
+        *count = rows;
+    }
+    counts
+}
+
+#[tokio::test]
+#[ignore] // Requires database
+async fn test_with_tenant_isolates_organizations() {
+    let (pool, _guard) = setup_test_pool().await;
+    cleanup_test_data(&pool).await;
+    enable_row_level_security(&pool).await;
+
+    insert_tenant_data(&pool, "org-a").await;
+    insert_tenant_data(&pool, "org-a").await;
+    insert_tenant_data(&pool, "org-b").await;
+
+    let mut tx = pool.with_tenant("org-a").await.unwrap();
+    assert_eq!(visible_rows(&mut tx).await, [2, 2, 2, 2]);
+    let (org_ids,): (Vec<String>,) =
+        sqlx::query_as("SELECT ARRAY_AGG(DISTINCT resource_attributes->>'org_id') FROM traces")
+            .fetch_one(&mut *tx)
+            .await
+            .unwrap();
+    assert_eq!(org_ids, ["org-a"]);
+    tx.rollback().await.unwrap();
+
+    let mut tx = pool.with_tenant("org-b").await.unwrap();
+    assert_eq!(visible_rows(&mut tx).await, [1, 1, 1, 1]);
+    tx.rollback().await.unwrap();
+
+    // Without an organization, the tenant role sees nothing
+    let mut tx = pool.postgres().begin().await.unwrap();
+    assert_eq!(visible_rows(&mut tx).await, [0, 0, 0, 0]);
+    tx.rollback().await.unwrap();
+
+    // The setting ends with the transaction and does not leak to the
+    // connection's next user
+    let mut tx = pool.postgres().begin().await.unwrap();
+    let (org_id,): (Option<String>,) = sqlx::query_as("SELECT app_current_org_id()")
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+    assert_eq!(org_id, None);
+    tx.rollback().await.unwrap();
+
+    // Roles outside llm_observatory_tenant keep full access
+    let (rows,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM traces")
+        .fetch_one(pool.postgres())
+        .await
+        .unwrap();
+    assert_eq!(rows, 3);
+}
+
+#[tokio::test]
+#[ignore] // Requires database
+async fn test_with_tenant_rejects_empty_org_id() {
+    let (pool, _guard) = setup_test_pool().await;
+
+    let result = pool.with_tenant("").await;
+    assert!(matches!(result, Err(StorageError::ValidationError(_))));
+}
@@ -149,6 +149,40 @@ async fn test_trace_writer_event_insertion() {
     assert_eq!(result.0, 1);
 }
 
+#[tokio::test]
+async fn test_trace_writer_extracts_span_events() {
+    let (pool, _guard) = setup_test_pool().await;
+    cleanup_test_data(&pool).await;
+
+    let writer = TraceWriter::new(pool.clone());
+    let trace_id = Uuid::new_v4();
+    let mut span = create_test_span(trace_id, "span-events", "test-operation", "test-service");
+    span.events = Some(serde_json::json!([
+        {"name": "guardrail.evaluation", "timestamp": span.start_time.to_rfc3339()},
+        {"name": "tool.result", "timestamp": span.start_time.to_rfc3339()}
+    ]));
+
+    writer.write_span(span.clone()).await.unwrap();
+    writer.flush().await.unwrap();
+
+    // A redelivered span gets a new row ID but must not add its events again
+    let mut redelivered = span.clone();
+    redelivered.id = Uuid::new_v4();
+    writer.write_span(redelivered).await.unwrap();
+    writer.flush().await.unwrap();
+
+    let result: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM trace_events e JOIN trace_spans s ON e.span_id = s.id \
+         WHERE s.span_id = $1",
+    )
+    .bind(&span.span_id)
+    .fetch_one(pool.postgres())
+    .await
+    .unwrap();
+
+    assert_eq!(result.0, 2);
+}
+
 #[tokio::test]
 async fn test_trace_writer_write_stats() {
     let (pool, _guard) = setup_test_pool().await;
@@ -0,0 +1,235 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Native notification channels: Slack and PagerDuty.
+//!
+//! A channel receives the events of its organization that match its event
+//! types, alert rules and minimum severity, so for example critical alerts
+//! can page through PagerDuty while budget warnings go to a Slack channel.
+
+use crate::error::{WebhookError, WebhookResult};
+use crate::event::{EventType, Severity, WebhookEvent};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Kind of notification channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelKind {
+    /// Slack incoming webhook; the target is the webhook URL
+    Slack,
+    /// PagerDuty Events API v2; the target is the integration routing key
+    PagerDuty,
+}
+
+impl ChannelKind {
+    /// Name stored in `notification_channels.kind`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChannelKind::Slack => "slack",
+            ChannelKind::PagerDuty => "pager_duty",
+        }
+    }
+
+    /// Parse a channel kind name.
+    pub fn parse(name: &str) -> Option<Self> {
+        [ChannelKind::Slack, ChannelKind::PagerDuty]
+            .into_iter()
+            .find(|k| k.as_str() == name)
+    }
+}
+
+/// A registered notification channel.
+///
+/// The target (Slack webhook URL or PagerDuty routing key) is a credential
+/// and never serialized.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct NotificationChannel {
+    /// Channel ID
+    pub id: Uuid,
+    /// Owning organization
+    pub org_id: String,
+    /// slack or pager_duty
+    pub kind: String,
+    /// Display name, e.g. `#llm-costs` or `LLM on-call`
+    pub name: String,
+    /// Slack webhook URL or PagerDuty routing key
+    #[serde(skip_serializing)]
+    pub target: String,
+    /// Subscribed event types; empty subscribes to all
+    pub event_types: Vec<String>,
+    /// Alert rules and budgets routed to the channel; empty routes all
+    pub alert_rules: Vec<String>,
+    /// Lowest severity routed to the channel
+    pub min_severity: String,
+    /// Disabled channels receive no notifications
+    pub enabled: bool,
+    /// When the channel was registered
+    pub created_at: DateTime<Utc>,
+    /// When the channel was last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+impl NotificationChannel {
+    /// Kind of the channel, if it is known.
+    pub fn channel_kind(&self) -> Option<ChannelKind> {
+        ChannelKind::parse(&self.kind)
+    }
+
+    /// Whether the event should be sent to the channel.
+    ///
+    /// Test events only go to the channel they were fired for, so they are
+    /// never routed.
+    pub fn routes(&self, event: &WebhookEvent) -> bool {
+        let min_severity = Severity::parse(&self.min_severity).unwrap_or_default();
+
+        self.enabled
+            && event.event_type != EventType::Test
+            && event.severity >= min_severity
+            && (self.event_types.is_empty()
+                || self
+                    .event_types
+                    .iter()
+                    .any(|t| t == event.event_type.as_str()))
+            && (self.alert_rules.is_empty()
+                || event
+                    .rule
+                    .as_ref()
+                    .is_some_and(|rule| self.alert_rules.contains(rule)))
+    }
+}
+
+/// A notification channel to register.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewNotificationChannel {
+    /// slack or pager_duty
+    pub kind: ChannelKind,
+    /// Display name
+    pub name: String,
+    /// Slack webhook URL or PagerDuty routing key
+    pub target: String,
+    /// Event types to subscribe to; empty subscribes to all
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    /// Alert rules and budgets to route; empty routes all
+    #[serde(default)]
+    pub alert_rules: Vec<String>,
+    /// Lowest severity to route (default: info)
+    #[serde(default)]
+    pub min_severity: Severity,
+}
+
+impl NewNotificationChannel {
+    /// Check the name, target and event types.
+    pub fn validate(&self) -> WebhookResult<()> {
+        if self.name.trim().is_empty() || self.name.len() > 200 {
+            return Err(WebhookError::InvalidChannel(
+                "Name must be between 1 and 200 characters".to_string(),
+            ));
+        }
+
+        match self.kind {
+            ChannelKind::Slack if !self.target.starts_with("https://hooks.slack.com/") => {
+                return Err(WebhookError::InvalidChannel(
+                    "Slack target must be an incoming webhook URL (https://hooks.slack.com/...)"
+                        .to_string(),
+                ));
+            }
+            ChannelKind::PagerDuty
+                if self.target.len() != 32
+                    || !self.target.chars().all(|c| c.is_ascii_alphanumeric()) =>
+            {
+                return Err(WebhookError::InvalidChannel(
+                    "PagerDuty target must be a 32-character integration routing key".to_string(),
+                ));
+            }
+            _ => {}
+        }
+
+        if let Some(unknown) = self
+            .event_types
+            .iter()
+            .find(|t| EventType::parse(t).is_none())
+        {
+            return Err(WebhookError::InvalidChannel(format!(
+                "Unknown event type '{}'",
+                unknown
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(alert_rules: &[&str], min_severity: Severity) -> NotificationChannel {
+        NotificationChannel {
+            id: Uuid::new_v4(),
+            org_id: "org-1".to_string(),
+            kind: ChannelKind::PagerDuty.as_str().to_string(),
+            name: "LLM on-call".to_string(),
+            target: "0123456789abcdef0123456789abcdef".to_string(),
+            event_types: Vec::new(),
+            alert_rules: alert_rules.iter().map(|r| r.to_string()).collect(),
+            min_severity: min_severity.as_str().to_string(),
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn alert(rule: &str, severity: Severity) -> WebhookEvent {
+        WebhookEvent::new(EventType::AlertTriggered, "org-1", serde_json::json!({}))
+            .with_rule(rule)
+            .with_severity(severity)
+    }
+
+    #[test]
+    fn test_routes_by_rule_and_severity() {
+        let critical_only = channel(&[], Severity::Critical);
+        assert!(critical_only.routes(&alert("error_rate", Severity::Critical)));
+        assert!(!critical_only.routes(&alert("error_rate", Severity::Warning)));
+
+        let error_rate = channel(&["error_rate"], Severity::Info);
+        assert!(error_rate.routes(&alert("error_rate", Severity::Info)));
+        assert!(!error_rate.routes(&alert("latency", Severity::Critical)));
+        assert!(!error_rate.routes(&WebhookEvent::new(
+            EventType::AlertTriggered,
+            "org-1",
+            serde_json::json!({})
+        )));
+
+        let all = channel(&[], Severity::Info);
+        assert!(!all.routes(&WebhookEvent::test("org-1", all.id)));
+
+        let body = serde_json::to_value(&all).unwrap();
+        assert!(body.get("target").is_none());
+    }
+
+    #[test]
+    fn test_new_channel_validate() {
+        let slack: NewNotificationChannel = serde_json::from_value(serde_json::json!({
+            "kind": "slack",
+            "name": "#llm-costs",
+            "target": "https://hooks.slack.com/services/T000/B000/XXXX",
+            "event_types": ["budget.threshold_exceeded"],
+            "min_severity": "warning"
+        }))
+        .unwrap();
+        assert!(slack.validate().is_ok());
+
+        let mut invalid = slack.clone();
+        invalid.target = "https://example.com/hook".to_string();
+        assert!(invalid.validate().is_err());
+
+        let mut pager_duty = slack;
+        pager_duty.kind = ChannelKind::PagerDuty;
+        assert!(pager_duty.validate().is_err());
+        pager_duty.target = "0123456789abcdef0123456789abcdef".to_string();
+        assert!(pager_duty.validate().is_ok());
+    }
+}
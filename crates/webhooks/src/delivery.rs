@@ -0,0 +1,128 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Webhook and notification channel deliveries and their retry policy.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Status of a delivery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Not attempted yet
+    Pending,
+    /// Failed at least once; another attempt is scheduled
+    Retrying,
+    /// The endpoint answered with a 2xx status
+    Succeeded,
+    /// Every attempt failed
+    Failed,
+}
+
+impl DeliveryStatus {
+    /// Name stored in `webhook_deliveries.status`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Retrying => "retrying",
+            DeliveryStatus::Succeeded => "succeeded",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+/// A delivery of one event to one endpoint or channel, with its latest
+/// attempt.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct WebhookDelivery {
+    /// Delivery ID, sent as `X-Observatory-Delivery`
+    pub id: Uuid,
+    /// Target webhook endpoint
+    pub endpoint_id: Option<Uuid>,
+    /// Target notification channel
+    pub channel_id: Option<Uuid>,
+    /// Owning organization
+    pub org_id: String,
+    /// Event delivered
+    pub event_id: Uuid,
+    /// Event type, e.g. `alert.triggered`
+    pub event_type: String,
+    /// pending, retrying, succeeded or failed
+    pub status: String,
+    /// Attempts made so far
+    pub attempts: i32,
+    /// HTTP status of the latest attempt, if the endpoint answered
+    pub response_status: Option<i32>,
+    /// Error of the latest failed attempt
+    pub last_error: Option<String>,
+    /// When the next attempt is due, while retrying
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    /// When the delivery was created
+    pub created_at: DateTime<Utc>,
+    /// When the delivery succeeded or finally failed
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Retry policy with exponential backoff.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Attempts before a delivery is marked failed, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for each further retry
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between attempts
+    pub max_backoff: Duration,
+    /// Timeout of each request
+    pub request_timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 6,
+            initial_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(600),
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay after failed attempt number `attempt` (starting at 1), or
+    /// `None` once the attempts are used up.
+    pub fn backoff(&self, attempt: u32) -> Option<Duration> {
+        if attempt == 0 || attempt >= self.max_attempts {
+            return None;
+        }
+        let factor = 2u32.saturating_pow(attempt - 1);
+        Some(
+            self.initial_backoff
+                .saturating_mul(factor)
+                .min(self.max_backoff),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(1), Some(Duration::from_secs(5)));
+        assert_eq!(policy.backoff(2), Some(Duration::from_secs(10)));
+        assert_eq!(policy.backoff(3), Some(Duration::from_secs(20)));
+        assert_eq!(policy.backoff(5), Some(Duration::from_secs(80)));
+        assert_eq!(policy.backoff(6), None);
+
+        let capped = RetryPolicy {
+            max_attempts: 40,
+            ..Default::default()
+        };
+        assert_eq!(capped.backoff(39), Some(Duration::from_secs(600)));
+    }
+}
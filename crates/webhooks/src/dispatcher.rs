@@ -0,0 +1,473 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Webhook dispatcher.
+//!
+//! [`WebhookDispatcher::dispatch`] records a delivery for every endpoint
+//! subscribed to an event and every Slack or PagerDuty channel it is routed
+//! to, and sends them in the background, retrying failed attempts with the
+//! [`RetryPolicy`] backoff. Each attempt's result is recorded in
+//! `webhook_deliveries`. Deliveries still retrying when the process stops
+//! are not resumed.
+
+use crate::channel::{ChannelKind, NotificationChannel};
+use crate::delivery::{DeliveryStatus, RetryPolicy, WebhookDelivery};
+use crate::endpoint::WebhookEndpoint;
+use crate::error::{WebhookError, WebhookResult};
+use crate::event::WebhookEvent;
+use crate::signing::{self, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use crate::store::WebhookStore;
+use crate::{pagerduty, slack};
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// User agent of webhook requests
+const USER_AGENT: &str = concat!("llm-observatory-webhooks/", env!("CARGO_PKG_VERSION"));
+
+/// Where a delivery is sent
+enum Target {
+    Endpoint(WebhookEndpoint),
+    Channel(NotificationChannel),
+}
+
+impl Target {
+    /// Channel label of delivery metrics
+    fn channel(&self) -> &str {
+        match self {
+            Target::Endpoint(_) => "webhook",
+            Target::Channel(channel) => &channel.kind,
+        }
+    }
+}
+
+/// Sends webhook events to registered endpoints and notification channels.
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    store: WebhookStore,
+    client: reqwest::Client,
+    retry_policy: Arc<RetryPolicy>,
+    pagerduty_url: Arc<str>,
+}
+
+impl WebhookDispatcher {
+    /// Create a dispatcher with the default retry policy.
+    pub fn new(store: WebhookStore) -> Self {
+        Self::with_retry_policy(store, RetryPolicy::default())
+    }
+
+    /// Create a dispatcher with a custom retry policy.
+    pub fn with_retry_policy(store: WebhookStore, retry_policy: RetryPolicy) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(retry_policy.request_timeout)
+            .user_agent(USER_AGENT)
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            store,
+            client,
+            retry_policy: Arc::new(retry_policy),
+            pagerduty_url: Arc::from(pagerduty::EVENTS_URL),
+        }
+    }
+
+    /// Send PagerDuty events to another Events API v2 URL, e.g.
+    /// `https://events.eu.pagerduty.com/v2/enqueue` for the EU service region.
+    pub fn with_pagerduty_url(mut self, url: impl Into<String>) -> Self {
+        self.pagerduty_url = Arc::from(url.into());
+        self
+    }
+
+    /// The store endpoints, channels and deliveries are kept in.
+    pub fn store(&self) -> &WebhookStore {
+        &self.store
+    }
+
+    /// Deliver an event to every subscribed endpoint and routed channel of
+    /// its organization.
+    ///
+    /// Deliveries are recorded before this returns and sent in the
+    /// background. Returns the number of deliveries started.
+    pub async fn dispatch(&self, event: WebhookEvent) -> WebhookResult<usize> {
+        let endpoints = self
+            .store
+            .subscribed_endpoints(&event.org_id, event.event_type)
+            .await?;
+        let channels = self.store.enabled_channels(&event.org_id).await?;
+
+        let targets: Vec<Target> = endpoints
+            .into_iter()
+            .map(Target::Endpoint)
+            .chain(
+                channels
+                    .into_iter()
+                    .filter(|channel| channel.routes(&event))
+                    .map(Target::Channel),
+            )
+            .collect();
+        if targets.is_empty() {
+            debug!(event_type = %event.event_type, org_id = %event.org_id, "No webhook endpoints or channels subscribed");
+            return Ok(0);
+        }
+
+        let body = Arc::new(serde_json::to_vec(&event)?);
+        let event = Arc::new(event);
+        let mut started = 0;
+
+        for target in targets {
+            let delivery_id = self.create_delivery(&target, &event).await?;
+            let dispatcher = self.clone();
+            let event = Arc::clone(&event);
+            let body = Arc::clone(&body);
+            tokio::spawn(async move {
+                dispatcher
+                    .deliver(&target, delivery_id, &event, &body)
+                    .await;
+            });
+            started += 1;
+        }
+
+        info!(event_type = %event.event_type, org_id = %event.org_id, deliveries = started, "Dispatched webhook event");
+        Ok(started)
+    }
+
+    /// Send a test event to one endpoint, without retries, and return the
+    /// recorded delivery.
+    pub async fn test_fire(&self, endpoint: &WebhookEndpoint) -> WebhookResult<WebhookDelivery> {
+        let event = WebhookEvent::test(&endpoint.org_id, endpoint.id);
+        self.fire_once(Target::Endpoint(endpoint.clone()), event)
+            .await
+    }
+
+    /// Send a test event to one notification channel, without retries, and
+    /// return the recorded delivery.
+    ///
+    /// On PagerDuty this opens an info-severity incident.
+    pub async fn test_fire_channel(
+        &self,
+        channel: &NotificationChannel,
+    ) -> WebhookResult<WebhookDelivery> {
+        let event = WebhookEvent::test(&channel.org_id, channel.id);
+        self.fire_once(Target::Channel(channel.clone()), event)
+            .await
+    }
+
+    /// Make one signed delivery attempt, returning the response status.
+    ///
+    /// Any non-2xx response is an error.
+    pub async fn send(
+        &self,
+        endpoint: &WebhookEndpoint,
+        delivery_id: Uuid,
+        event: &WebhookEvent,
+        body: &[u8],
+    ) -> WebhookResult<u16> {
+        let timestamp = Utc::now().timestamp();
+        let signature = signing::sign(&endpoint.secret, timestamp, body);
+
+        let request = self
+            .client
+            .post(&endpoint.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(EVENT_HEADER, event.event_type.as_str())
+            .header(DELIVERY_HEADER, delivery_id.to_string())
+            .body(body.to_vec());
+        self.execute(request).await
+    }
+
+    /// Send an event to a Slack or PagerDuty channel, returning the response
+    /// status.
+    ///
+    /// Any non-2xx response is an error.
+    pub async fn send_to_channel(
+        &self,
+        channel: &NotificationChannel,
+        event: &WebhookEvent,
+    ) -> WebhookResult<u16> {
+        let request = match channel.channel_kind() {
+            Some(ChannelKind::Slack) => self
+                .client
+                .post(&channel.target)
+                .json(&slack::message(event)),
+            Some(ChannelKind::PagerDuty) => self
+                .client
+                .post(&*self.pagerduty_url)
+                .json(&pagerduty::event(&channel.target, event)),
+            None => {
+                return Err(WebhookError::InvalidChannel(format!(
+                    "Unknown channel kind '{}'",
+                    channel.kind
+                )))
+            }
+        };
+        self.execute(request).await
+    }
+
+    async fn execute(&self, request: reqwest::RequestBuilder) -> WebhookResult<u16> {
+        let started = Instant::now();
+        let response = request.send().await;
+        metrics::histogram!("webhook_request_duration_seconds")
+            .record(started.elapsed().as_secs_f64());
+
+        let status = response?.status();
+        if status.is_success() {
+            Ok(status.as_u16())
+        } else {
+            Err(WebhookError::Status(status.as_u16()))
+        }
+    }
+
+    async fn create_delivery(&self, target: &Target, event: &WebhookEvent) -> WebhookResult<Uuid> {
+        match target {
+            Target::Endpoint(endpoint) => self.store.create_delivery(endpoint, event).await,
+            Target::Channel(channel) => self.store.create_channel_delivery(channel, event).await,
+        }
+    }
+
+    async fn attempt(
+        &self,
+        target: &Target,
+        delivery_id: Uuid,
+        event: &WebhookEvent,
+        body: &[u8],
+    ) -> WebhookResult<u16> {
+        match target {
+            Target::Endpoint(endpoint) => self.send(endpoint, delivery_id, event, body).await,
+            Target::Channel(channel) => self.send_to_channel(channel, event).await,
+        }
+    }
+
+    /// Record a delivery and make a single attempt.
+    async fn fire_once(
+        &self,
+        target: Target,
+        event: WebhookEvent,
+    ) -> WebhookResult<WebhookDelivery> {
+        let body = serde_json::to_vec(&event)?;
+        let delivery_id = self.create_delivery(&target, &event).await?;
+
+        let result = self.attempt(&target, delivery_id, &event, &body).await;
+        let status = if result.is_ok() {
+            DeliveryStatus::Succeeded
+        } else {
+            DeliveryStatus::Failed
+        };
+        self.record(&target, delivery_id, &event, status, 1, &result, None)
+            .await?;
+
+        self.store
+            .get_delivery(delivery_id)
+            .await?
+            .ok_or(WebhookError::NotFound(delivery_id))
+    }
+
+    /// Send a delivery, retrying until it succeeds or the attempts run out.
+    async fn deliver(&self, target: &Target, delivery_id: Uuid, event: &WebhookEvent, body: &[u8]) {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let result = self.attempt(target, delivery_id, event, body).await;
+
+            let (status, backoff) = match &result {
+                Ok(_) => (DeliveryStatus::Succeeded, None),
+                Err(e) => match self.retry_policy.backoff(attempt) {
+                    Some(backoff) => {
+                        debug!(%delivery_id, attempt, error = %e, ?backoff, "Webhook delivery failed, retrying");
+                        (DeliveryStatus::Retrying, Some(backoff))
+                    }
+                    None => {
+                        warn!(%delivery_id, channel = target.channel(), attempts = attempt, error = %e, "Webhook delivery failed");
+                        (DeliveryStatus::Failed, None)
+                    }
+                },
+            };
+
+            let next_attempt_at = backoff
+                .and_then(|b| chrono::Duration::from_std(b).ok())
+                .map(|b| Utc::now() + b);
+            if let Err(e) = self
+                .record(
+                    target,
+                    delivery_id,
+                    event,
+                    status,
+                    attempt,
+                    &result,
+                    next_attempt_at,
+                )
+                .await
+            {
+                warn!(%delivery_id, error = %e, "Failed to record webhook delivery attempt");
+            }
+
+            match backoff {
+                Some(backoff) => tokio::time::sleep(backoff).await,
+                None => return,
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn record(
+        &self,
+        target: &Target,
+        delivery_id: Uuid,
+        event: &WebhookEvent,
+        status: DeliveryStatus,
+        attempts: u32,
+        result: &WebhookResult<u16>,
+        next_attempt_at: Option<chrono::DateTime<Utc>>,
+    ) -> WebhookResult<()> {
+        if status != DeliveryStatus::Retrying {
+            metrics::counter!(
+                "webhook_deliveries_total",
+                "channel" => target.channel().to_string(),
+                "event_type" => event.event_type.as_str(),
+                "status" => status.as_str()
+            )
+            .increment(1);
+        }
+
+        let response_status = match result {
+            Ok(code) | Err(WebhookError::Status(code)) => Some(*code),
+            Err(_) => None,
+        };
+        let error = result.as_ref().err().map(|e| e.to_string());
+
+        self.store
+            .record_attempt(
+                delivery_id,
+                status,
+                attempts,
+                response_status,
+                error.as_deref(),
+                next_attempt_at,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::HeaderMap, http::StatusCode, routing::post, Router};
+    use sqlx::postgres::PgPoolOptions;
+
+    fn endpoint(url: String) -> WebhookEndpoint {
+        WebhookEndpoint {
+            id: Uuid::new_v4(),
+            org_id: "org-1".to_string(),
+            url,
+            secret: signing::generate_secret(),
+            event_types: Vec::new(),
+            description: None,
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_signs_request() {
+        let endpoint = endpoint(String::new());
+        let secret = endpoint.secret.clone();
+
+        // Receiver that only accepts correctly signed requests
+        let app = Router::new().route(
+            "/hooks",
+            post(
+                move |headers: HeaderMap, body: axum::body::Bytes| async move {
+                    let header =
+                        |name: &str| headers.get(name).unwrap().to_str().unwrap().to_string();
+                    let timestamp: i64 = header(TIMESTAMP_HEADER).parse().unwrap();
+                    assert_eq!(header(EVENT_HEADER), "webhook.test");
+                    if signing::verify(&secret, timestamp, &body, &header(SIGNATURE_HEADER)) {
+                        StatusCode::NO_CONTENT
+                    } else {
+                        StatusCode::UNAUTHORIZED
+                    }
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+        let dispatcher = WebhookDispatcher::new(WebhookStore::new(pool));
+
+        let mut endpoint = endpoint;
+        endpoint.url = format!("http://{}/hooks", addr);
+        let event = WebhookEvent::test(&endpoint.org_id, endpoint.id);
+        let body = serde_json::to_vec(&event).unwrap();
+
+        let status = dispatcher
+            .send(&endpoint, Uuid::new_v4(), &event, &body)
+            .await
+            .unwrap();
+        assert_eq!(status, 204);
+
+        // A wrong secret is rejected by the receiver
+        endpoint.secret = signing::generate_secret();
+        let result = dispatcher
+            .send(&endpoint, Uuid::new_v4(), &event, &body)
+            .await;
+        assert!(matches!(result, Err(WebhookError::Status(401))));
+    }
+
+    #[tokio::test]
+    async fn test_send_to_pagerduty_channel() {
+        // Stand-in for the PagerDuty Events API
+        let app = Router::new().route(
+            "/v2/enqueue",
+            post(
+                |axum::Json(body): axum::Json<serde_json::Value>| async move {
+                    assert_eq!(body["routing_key"], "0123456789abcdef0123456789abcdef");
+                    assert_eq!(body["event_action"], "trigger");
+                    assert_eq!(body["payload"]["severity"], "critical");
+                    StatusCode::ACCEPTED
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+        let dispatcher = WebhookDispatcher::new(WebhookStore::new(pool))
+            .with_pagerduty_url(format!("http://{}/v2/enqueue", addr));
+
+        let channel = NotificationChannel {
+            id: Uuid::new_v4(),
+            org_id: "org-1".to_string(),
+            kind: ChannelKind::PagerDuty.as_str().to_string(),
+            name: "LLM on-call".to_string(),
+            target: "0123456789abcdef0123456789abcdef".to_string(),
+            event_types: Vec::new(),
+            alert_rules: Vec::new(),
+            min_severity: "critical".to_string(),
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let event = WebhookEvent::new(
+            crate::event::EventType::AlertTriggered,
+            "org-1",
+            serde_json::json!({"message": "Error rate 12%"}),
+        )
+        .with_severity(crate::event::Severity::Critical)
+        .with_rule("error_rate");
+
+        let status = dispatcher.send_to_channel(&channel, &event).await.unwrap();
+        assert_eq!(status, 202);
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Webhook endpoints registered by organizations.
+
+use crate::error::{WebhookError, WebhookResult};
+use crate::event::EventType;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Maximum length of an endpoint URL
+const MAX_URL_LENGTH: usize = 2048;
+
+/// A registered webhook endpoint.
+///
+/// The secret is never serialized; it is returned once, when the endpoint is
+/// registered.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct WebhookEndpoint {
+    /// Endpoint ID
+    pub id: Uuid,
+    /// Owning organization
+    pub org_id: String,
+    /// URL deliveries are POSTed to
+    pub url: String,
+    /// HMAC-SHA256 signing secret
+    #[serde(skip_serializing)]
+    pub secret: String,
+    /// Subscribed event types; empty subscribes to all
+    pub event_types: Vec<String>,
+    /// Free-form description
+    pub description: Option<String>,
+    /// Disabled endpoints receive no deliveries
+    pub enabled: bool,
+    /// When the endpoint was registered
+    pub created_at: DateTime<Utc>,
+    /// When the endpoint was last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookEndpoint {
+    /// Whether the endpoint should receive events of this type.
+    ///
+    /// Test events go to the endpoint they were fired for, whatever its
+    /// subscriptions.
+    pub fn subscribes_to(&self, event_type: EventType) -> bool {
+        self.enabled
+            && (event_type == EventType::Test
+                || self.event_types.is_empty()
+                || self.event_types.iter().any(|t| t == event_type.as_str()))
+    }
+}
+
+/// A webhook endpoint to register.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NewWebhookEndpoint {
+    /// URL deliveries are POSTed to (`https://`, or `http://` for testing)
+    pub url: String,
+    /// Event types to subscribe to; empty subscribes to all
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    /// Free-form description
+    pub description: Option<String>,
+}
+
+impl NewWebhookEndpoint {
+    /// Check the URL and event types.
+    pub fn validate(&self) -> WebhookResult<()> {
+        if !(self.url.starts_with("https://") || self.url.starts_with("http://")) {
+            return Err(WebhookError::InvalidEndpoint(
+                "URL must start with https:// or http://".to_string(),
+            ));
+        }
+
+        if self.url.len() > MAX_URL_LENGTH {
+            return Err(WebhookError::InvalidEndpoint(format!(
+                "URL must be at most {} characters",
+                MAX_URL_LENGTH
+            )));
+        }
+
+        if let Some(unknown) = self
+            .event_types
+            .iter()
+            .find(|t| EventType::parse(t).is_none())
+        {
+            let known: Vec<_> = EventType::ALL.iter().map(|t| t.as_str()).collect();
+            return Err(WebhookError::InvalidEndpoint(format!(
+                "Unknown event type '{}', expected one of: {}",
+                unknown,
+                known.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(event_types: &[&str]) -> WebhookEndpoint {
+        WebhookEndpoint {
+            id: Uuid::new_v4(),
+            org_id: "org-1".to_string(),
+            url: "https://example.com/hooks".to_string(),
+            secret: "whsec_test".to_string(),
+            event_types: event_types.iter().map(|t| t.to_string()).collect(),
+            description: None,
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_subscribes_to() {
+        let all = endpoint(&[]);
+        assert!(all.subscribes_to(EventType::AnomalyDetected));
+
+        let budgets = endpoint(&["budget.threshold_exceeded"]);
+        assert!(budgets.subscribes_to(EventType::BudgetThresholdExceeded));
+        assert!(!budgets.subscribes_to(EventType::AlertTriggered));
+        assert!(budgets.subscribes_to(EventType::Test));
+
+        let mut disabled = endpoint(&[]);
+        disabled.enabled = false;
+        assert!(!disabled.subscribes_to(EventType::Test));
+
+        let body = serde_json::to_value(&all).unwrap();
+        assert!(body.get("secret").is_none());
+    }
+
+    #[test]
+    fn test_new_endpoint_validate() {
+        let valid = NewWebhookEndpoint {
+            url: "https://example.com/hooks".to_string(),
+            event_types: vec!["alert.triggered".to_string()],
+            description: None,
+        };
+        assert!(valid.validate().is_ok());
+
+        let mut invalid = valid.clone();
+        invalid.url = "ftp://example.com".to_string();
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = valid;
+        invalid.event_types.push("alert.fired".to_string());
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Error types for webhook delivery.
+
+/// Result type alias for webhook operations.
+pub type WebhookResult<T> = std::result::Result<T, WebhookError>;
+
+/// Errors from registering endpoints and delivering webhooks.
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    /// Invalid endpoint registration
+    #[error("Invalid webhook endpoint: {0}")]
+    InvalidEndpoint(String),
+
+    /// Invalid notification channel registration
+    #[error("Invalid notification channel: {0}")]
+    InvalidChannel(String),
+
+    /// Endpoint not found
+    #[error("Webhook endpoint not found: {0}")]
+    NotFound(uuid::Uuid),
+
+    /// Request could not be sent or timed out
+    #[error("Webhook request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// Endpoint answered with a non-success status
+    #[error("Webhook endpoint returned HTTP {0}")]
+    Status(u16),
+
+    /// Database error
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    /// Serialization error
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
@@ -0,0 +1,281 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Webhook events.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Types of events delivered to webhook endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventType {
+    /// An alert rule started firing
+    #[serde(rename = "alert.triggered")]
+    AlertTriggered,
+    /// A firing alert resolved
+    #[serde(rename = "alert.resolved")]
+    AlertResolved,
+    /// Spend crossed a budget threshold
+    #[serde(rename = "budget.threshold_exceeded")]
+    BudgetThresholdExceeded,
+    /// An anomaly was detected in latency, cost or error rate
+    #[serde(rename = "anomaly.detected")]
+    AnomalyDetected,
+    /// Test event sent on request to check an endpoint
+    #[serde(rename = "webhook.test")]
+    Test,
+}
+
+impl EventType {
+    /// All event types, in documentation order.
+    pub const ALL: [EventType; 5] = [
+        EventType::AlertTriggered,
+        EventType::AlertResolved,
+        EventType::BudgetThresholdExceeded,
+        EventType::AnomalyDetected,
+        EventType::Test,
+    ];
+
+    /// Name used in payloads, the `X-Observatory-Event` header and storage.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventType::AlertTriggered => "alert.triggered",
+            EventType::AlertResolved => "alert.resolved",
+            EventType::BudgetThresholdExceeded => "budget.threshold_exceeded",
+            EventType::AnomalyDetected => "anomaly.detected",
+            EventType::Test => "webhook.test",
+        }
+    }
+
+    /// Parse an event type name.
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == name)
+    }
+
+    /// Human-readable title, used in chat and incident messages.
+    pub fn title(&self) -> &'static str {
+        match self {
+            EventType::AlertTriggered => "Alert triggered",
+            EventType::AlertResolved => "Alert resolved",
+            EventType::BudgetThresholdExceeded => "Budget threshold exceeded",
+            EventType::AnomalyDetected => "Anomaly detected",
+            EventType::Test => "Test notification",
+        }
+    }
+}
+
+impl std::fmt::Display for EventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Severity of an event, used to route it to notification channels.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Informational
+    #[default]
+    Info,
+    /// Needs attention
+    Warning,
+    /// Needs immediate action
+    Critical,
+}
+
+impl Severity {
+    /// Name used in payloads and storage.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        }
+    }
+
+    /// Parse a severity name.
+    pub fn parse(name: &str) -> Option<Self> {
+        [Severity::Info, Severity::Warning, Severity::Critical]
+            .into_iter()
+            .find(|s| s.as_str() == name)
+    }
+}
+
+/// Key of the generated incident narrative in the event data.
+pub const INCIDENT_SUMMARY_KEY: &str = "incident_summary";
+
+/// An event delivered to the endpoints of one organization.
+///
+/// Serialized as the JSON request body of each delivery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    /// Event ID; the same for every delivery and retry of the event
+    pub id: Uuid,
+    /// Event type
+    #[serde(rename = "type")]
+    pub event_type: EventType,
+    /// Organization the event belongs to
+    pub org_id: String,
+    /// When the event occurred
+    pub created_at: DateTime<Utc>,
+    /// Event severity
+    #[serde(default)]
+    pub severity: Severity,
+    /// Alert rule or budget that raised the event, e.g. `error_rate` or
+    /// `budget:monthly`; alert.triggered and alert.resolved events of the
+    /// same rule share it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// Event details, specific to the event type
+    pub data: serde_json::Value,
+}
+
+impl WebhookEvent {
+    /// Create an event occurring now.
+    pub fn new(event_type: EventType, org_id: impl Into<String>, data: serde_json::Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            event_type,
+            org_id: org_id.into(),
+            created_at: Utc::now(),
+            severity: Severity::Info,
+            rule: None,
+            data,
+        }
+    }
+
+    /// Set the severity.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Set the alert rule or budget that raised the event.
+    pub fn with_rule(mut self, rule: impl Into<String>) -> Self {
+        self.rule = Some(rule.into());
+        self
+    }
+
+    /// Attach an incident narrative, stored as `incident_summary` in the
+    /// event data. Data that is not an object is kept under `details`.
+    pub fn with_incident_summary(mut self, summary: impl Into<String>) -> Self {
+        if !self.data.is_object() {
+            let details = std::mem::take(&mut self.data);
+            self.data = if details.is_null() {
+                serde_json::json!({})
+            } else {
+                serde_json::json!({ "details": details })
+            };
+        }
+        if let Some(data) = self.data.as_object_mut() {
+            data.insert(
+                INCIDENT_SUMMARY_KEY.to_string(),
+                serde_json::Value::String(summary.into()),
+            );
+        }
+        self
+    }
+
+    /// Incident narrative generated for the event, if any.
+    pub fn incident_summary(&self) -> Option<&str> {
+        self.data.get(INCIDENT_SUMMARY_KEY).and_then(|s| s.as_str())
+    }
+
+    /// One-line summary: the `message` in the event data, or a description
+    /// of the event type.
+    pub fn summary(&self) -> String {
+        match self.data.get("message").and_then(|m| m.as_str()) {
+            Some(message) => message.to_string(),
+            None => match &self.rule {
+                Some(rule) => format!("{} ({})", self.event_type.title(), rule),
+                None => self.event_type.title().to_string(),
+            },
+        }
+    }
+
+    /// Create the test event sent by [`crate::WebhookDispatcher::test_fire`].
+    pub fn test(org_id: impl Into<String>, endpoint_id: Uuid) -> Self {
+        Self::new(
+            EventType::Test,
+            org_id,
+            serde_json::json!({
+                "endpoint_id": endpoint_id,
+                "message": "Test event from LLM Observatory",
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_type_names() {
+        for event_type in EventType::ALL {
+            assert_eq!(EventType::parse(event_type.as_str()), Some(event_type));
+            assert_eq!(
+                serde_json::to_value(event_type).unwrap(),
+                serde_json::json!(event_type.as_str())
+            );
+        }
+        assert_eq!(EventType::parse("budget.exceeded"), None);
+
+        let event = WebhookEvent::new(
+            EventType::BudgetThresholdExceeded,
+            "org-1",
+            serde_json::json!({"budget": "monthly", "threshold": 0.9}),
+        );
+        let body = serde_json::to_value(&event).unwrap();
+        assert_eq!(body["type"], "budget.threshold_exceeded");
+        assert_eq!(body["org_id"], "org-1");
+        assert_eq!(body["severity"], "info");
+        assert!(body.get("rule").is_none());
+        assert_eq!(event.summary(), "Budget threshold exceeded");
+    }
+
+    #[test]
+    fn test_incident_summary() {
+        let event = WebhookEvent::new(
+            EventType::AlertTriggered,
+            "org-1",
+            serde_json::json!({"message": "Error rate above 5%"}),
+        );
+        assert_eq!(event.incident_summary(), None);
+
+        let event = event.with_incident_summary("gpt-4o errors rose after 14:05.");
+        assert_eq!(
+            event.incident_summary(),
+            Some("gpt-4o errors rose after 14:05.")
+        );
+        assert_eq!(event.summary(), "Error rate above 5%");
+
+        let event = WebhookEvent::new(EventType::AlertTriggered, "org-1", serde_json::json!([1]))
+            .with_incident_summary("Narrative");
+        assert_eq!(event.data["details"], serde_json::json!([1]));
+        assert_eq!(event.incident_summary(), Some("Narrative"));
+    }
+
+    #[test]
+    fn test_severity() {
+        assert!(Severity::Critical > Severity::Warning);
+        assert!(Severity::Warning > Severity::Info);
+        assert_eq!(Severity::parse("warning"), Some(Severity::Warning));
+        assert_eq!(Severity::parse("error"), None);
+
+        let event = WebhookEvent::new(
+            EventType::AlertTriggered,
+            "org-1",
+            serde_json::json!({"message": "Error rate 12% over 5 minutes"}),
+        )
+        .with_severity(Severity::Critical)
+        .with_rule("error_rate");
+        let body = serde_json::to_value(&event).unwrap();
+        assert_eq!(body["severity"], "critical");
+        assert_eq!(body["rule"], "error_rate");
+        assert_eq!(event.summary(), "Error rate 12% over 5 minutes");
+    }
+}
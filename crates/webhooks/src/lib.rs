@@ -0,0 +1,42 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Webhook notifications for LLM Observatory.
+//!
+//! Organizations register webhook endpoints with a signing secret and the
+//! event types they want. Alerting, budget tracking and anomaly detection
+//! hand [`WebhookEvent`]s to a [`WebhookDispatcher`], which delivers them to
+//! every subscribed endpoint:
+//!
+//! - Each request body is signed with HMAC-SHA256 (see [`signing`]) so
+//!   receivers can verify it came from LLM Observatory
+//! - Failed deliveries are retried with exponential backoff ([`RetryPolicy`])
+//! - Every delivery and its status is recorded in `webhook_deliveries`
+//!   through the [`WebhookStore`]
+//!
+//! Events can also go to native Slack and PagerDuty
+//! [`NotificationChannel`]s, routed by event type, alert rule and severity:
+//! Slack receives Block Kit messages (see [`slack`]) and PagerDuty Events
+//! API v2 events (see [`pagerduty`]).
+
+#![warn(missing_docs, rust_2018_idioms)]
+#![deny(unsafe_code)]
+
+pub mod channel;
+pub mod delivery;
+pub mod dispatcher;
+pub mod endpoint;
+pub mod error;
+pub mod event;
+pub mod pagerduty;
+pub mod signing;
+pub mod slack;
+pub mod store;
+
+pub use channel::{ChannelKind, NewNotificationChannel, NotificationChannel};
+pub use delivery::{DeliveryStatus, RetryPolicy, WebhookDelivery};
+pub use dispatcher::WebhookDispatcher;
+pub use endpoint::{NewWebhookEndpoint, WebhookEndpoint};
+pub use error::{WebhookError, WebhookResult};
+pub use event::{EventType, Severity, WebhookEvent};
+pub use store::WebhookStore;
@@ -0,0 +1,92 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! PagerDuty Events API v2 events.
+//!
+//! `alert.resolved` events resolve the incident opened by the
+//! `alert.triggered` event of the same rule, since both share a dedup key;
+//! every other event type triggers one.
+
+use crate::event::{EventType, WebhookEvent};
+use serde_json::{json, Value};
+
+/// PagerDuty Events API v2 endpoint (US service region)
+pub const EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
+/// Source reported in PagerDuty incidents
+const SOURCE: &str = "llm-observatory";
+
+/// Build the Events API v2 event for an event, sent with a routing key.
+pub fn event(routing_key: &str, event: &WebhookEvent) -> Value {
+    let event_action = match event.event_type {
+        EventType::AlertResolved => "resolve",
+        _ => "trigger",
+    };
+
+    json!({
+        "routing_key": routing_key,
+        "event_action": event_action,
+        "dedup_key": dedup_key(event),
+        "payload": {
+            "summary": truncate(&event.summary(), 1024),
+            "source": SOURCE,
+            // info, warning and critical are PagerDuty severities as well
+            "severity": event.severity.as_str(),
+            "timestamp": event.created_at.to_rfc3339(),
+            "component": event.rule,
+            "class": event.event_type.as_str(),
+            "group": event.org_id,
+            "custom_details": event.data,
+        },
+    })
+}
+
+/// Events of one rule share a dedup key, so repeated triggers update the
+/// open incident and a resolve closes it. Events without a rule get their
+/// own incident.
+fn dedup_key(event: &WebhookEvent) -> String {
+    match &event.rule {
+        Some(rule) => format!("{}:{}", event.org_id, rule),
+        None => format!("{}:{}", event.org_id, event.id),
+    }
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    s.chars().take(max_chars).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Severity;
+
+    #[test]
+    fn test_trigger_and_resolve_share_dedup_key() {
+        let triggered = WebhookEvent::new(
+            EventType::AlertTriggered,
+            "org-1",
+            json!({"message": "P95 latency 4.2s", "p95_ms": 4200}),
+        )
+        .with_severity(Severity::Critical)
+        .with_rule("latency");
+        let resolved =
+            WebhookEvent::new(EventType::AlertResolved, "org-1", json!({})).with_rule("latency");
+
+        let trigger = event("routing-key", &triggered);
+        assert_eq!(trigger["event_action"], "trigger");
+        assert_eq!(trigger["routing_key"], "routing-key");
+        assert_eq!(trigger["payload"]["summary"], "P95 latency 4.2s");
+        assert_eq!(trigger["payload"]["severity"], "critical");
+        assert_eq!(trigger["payload"]["custom_details"]["p95_ms"], 4200);
+
+        let resolve = event("routing-key", &resolved);
+        assert_eq!(resolve["event_action"], "resolve");
+        assert_eq!(resolve["dedup_key"], trigger["dedup_key"]);
+
+        let anomaly = WebhookEvent::new(EventType::AnomalyDetected, "org-1", json!({}));
+        assert_eq!(
+            event("routing-key", &anomaly)["dedup_key"],
+            format!("org-1:{}", anomaly.id)
+        );
+    }
+}
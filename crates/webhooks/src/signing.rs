@@ -0,0 +1,88 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! HMAC-SHA256 signing of webhook requests.
+//!
+//! Each request carries the Unix timestamp it was signed at in
+//! [`TIMESTAMP_HEADER`] and the signature of `"{timestamp}.{body}"` in
+//! [`SIGNATURE_HEADER`], formatted as `sha256=<hex>`. Receivers recompute
+//! the signature with the endpoint secret (see [`verify`]) and should reject
+//! old timestamps to prevent replays.
+
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// Header carrying the signature, `sha256=<hex>`
+pub const SIGNATURE_HEADER: &str = "X-Observatory-Signature";
+
+/// Header carrying the Unix timestamp included in the signature
+pub const TIMESTAMP_HEADER: &str = "X-Observatory-Timestamp";
+
+/// Header carrying the event type, e.g. `alert.triggered`
+pub const EVENT_HEADER: &str = "X-Observatory-Event";
+
+/// Header carrying the delivery ID, the same across retries
+pub const DELIVERY_HEADER: &str = "X-Observatory-Delivery";
+
+/// Prefix of generated endpoint secrets
+const SECRET_PREFIX: &str = "whsec_";
+
+/// Generate a random endpoint secret (`whsec_` followed by 64 hex digits).
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .expect("system random number generator failed");
+    format!("{}{}", SECRET_PREFIX, hex::encode(bytes))
+}
+
+/// Sign a request body sent at `timestamp` (Unix seconds).
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, &signed_payload(timestamp, body));
+    format!("sha256={}", hex::encode(tag.as_ref()))
+}
+
+/// Verify a signature produced by [`sign`], in constant time.
+pub fn verify(secret: &str, timestamp: i64, body: &[u8], signature: &str) -> bool {
+    let Some(tag) = signature
+        .strip_prefix("sha256=")
+        .and_then(|hex_tag| hex::decode(hex_tag).ok())
+    else {
+        return false;
+    };
+
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    hmac::verify(&key, &signed_payload(timestamp, body), &tag).is_ok()
+}
+
+fn signed_payload(timestamp: i64, body: &[u8]) -> Vec<u8> {
+    let mut payload = format!("{}.", timestamp).into_bytes();
+    payload.extend_from_slice(body);
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let secret = generate_secret();
+        assert!(secret.starts_with(SECRET_PREFIX));
+        assert_eq!(secret.len(), SECRET_PREFIX.len() + 64);
+        assert_ne!(secret, generate_secret());
+
+        let body = br#"{"type":"webhook.test"}"#;
+        let signature = sign(&secret, 1_700_000_000, body);
+        assert!(signature.starts_with("sha256="));
+        assert!(verify(&secret, 1_700_000_000, body, &signature));
+
+        // Any change to the secret, timestamp or body invalidates it
+        assert!(!verify("whsec_other", 1_700_000_000, body, &signature));
+        assert!(!verify(&secret, 1_700_000_001, body, &signature));
+        assert!(!verify(&secret, 1_700_000_000, b"{}", &signature));
+        assert!(!verify(&secret, 1_700_000_000, body, "sha256=zz"));
+        assert!(!verify(&secret, 1_700_000_000, body, &signature[7..]));
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Slack messages in Block Kit format.
+//!
+//! Messages are posted to a Slack incoming webhook: a header with the event
+//! title, the event summary, the incident narrative when one was generated,
+//! the event data as fields (USD amounts
+//! formatted as currency) and the organization and time as context.
+
+use crate::event::{EventType, Severity, WebhookEvent, INCIDENT_SUMMARY_KEY};
+use serde_json::{json, Value};
+
+/// Maximum fields in a Slack section block
+const MAX_FIELDS: usize = 10;
+
+/// Maximum text length of a Slack section block, leaving room for the label
+const MAX_TEXT_CHARS: usize = 2900;
+
+/// Build the Slack message for an event.
+pub fn message(event: &WebhookEvent) -> Value {
+    let summary = event.summary();
+    let mut blocks = vec![
+        json!({
+            "type": "header",
+            "text": {
+                "type": "plain_text",
+                "text": format!("{} {}", emoji(event), event.event_type.title()),
+                "emoji": true,
+            },
+        }),
+        json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": summary },
+        }),
+    ];
+    if let Some(narrative) = event.incident_summary() {
+        blocks.push(json!({
+            "type": "section",
+            "text": {
+                "type": "mrkdwn",
+                "text": format!("*Incident summary*\n{}", truncate(narrative, MAX_TEXT_CHARS)),
+            },
+        }));
+    }
+
+    let mut fields = vec![mrkdwn_field(
+        "Severity",
+        capitalize(event.severity.as_str()),
+    )];
+    if let Some(rule) = &event.rule {
+        fields.push(mrkdwn_field("Rule", format!("`{}`", rule)));
+    }
+    if let Some(data) = event.data.as_object() {
+        fields.extend(
+            data.iter()
+                .filter(|(key, _)| !matches!(key.as_str(), "message" | INCIDENT_SUMMARY_KEY))
+                .filter_map(|(key, value)| {
+                    Some(mrkdwn_field(&label(key), format_value(key, value)?))
+                }),
+        );
+    }
+    fields.truncate(MAX_FIELDS);
+    blocks.push(json!({ "type": "section", "fields": fields }));
+
+    blocks.push(json!({
+        "type": "context",
+        "elements": [{
+            "type": "mrkdwn",
+            "text": format!(
+                "LLM Observatory · org `{}` · <!date^{}^{{date_short_pretty}} {{time}}|{}>",
+                event.org_id,
+                event.created_at.timestamp(),
+                event.created_at.to_rfc3339(),
+            ),
+        }],
+    }));
+
+    json!({
+        // Fallback for notifications and clients without Block Kit
+        "text": format!("{}: {}", event.event_type.title(), summary),
+        "blocks": blocks,
+    })
+}
+
+fn emoji(event: &WebhookEvent) -> &'static str {
+    match (event.event_type, event.severity) {
+        (EventType::AlertResolved, _) => ":white_check_mark:",
+        (EventType::Test, _) => ":wave:",
+        (_, Severity::Critical) => ":rotating_light:",
+        (_, Severity::Warning) => ":warning:",
+        (_, Severity::Info) => ":information_source:",
+    }
+}
+
+fn mrkdwn_field(label: &str, value: String) -> Value {
+    json!({ "type": "mrkdwn", "text": format!("*{}*\n{}", label, value) })
+}
+
+/// `spent_usd` -> `Spent`, `threshold_pct` -> `Threshold pct`
+fn label(key: &str) -> String {
+    capitalize(&key.trim_end_matches("_usd").replace('_', " "))
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    s.chars().take(max_chars).collect()
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Format scalar values; nested objects and arrays are left out.
+fn format_value(key: &str, value: &Value) -> Option<String> {
+    match value {
+        Value::Number(n) if key.ends_with("_usd") || key.contains("cost") => {
+            n.as_f64().map(|usd| format!("${:.2}", usd))
+        }
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(s.clone()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_message() {
+        let event = WebhookEvent::new(
+            EventType::BudgetThresholdExceeded,
+            "org-1",
+            json!({
+                "message": "Monthly budget 91% used",
+                "budget": "monthly",
+                "spent_usd": 912.4,
+                "limit_usd": 1000,
+                "by_model": {"gpt-4o": 700.0},
+            }),
+        )
+        .with_severity(Severity::Warning)
+        .with_rule("budget:monthly");
+
+        let message = message(&event);
+        assert_eq!(
+            message["text"],
+            "Budget threshold exceeded: Monthly budget 91% used"
+        );
+
+        let blocks = message["blocks"].as_array().unwrap();
+        assert_eq!(blocks[0]["type"], "header");
+        assert_eq!(
+            blocks[0]["text"]["text"],
+            ":warning: Budget threshold exceeded"
+        );
+        assert_eq!(blocks[1]["text"]["text"], "Monthly budget 91% used");
+
+        let fields: Vec<_> = blocks[2]["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["text"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "*Severity*\nWarning",
+                "*Rule*\n`budget:monthly`",
+                "*Budget*\nmonthly",
+                "*Limit*\n$1000.00",
+                "*Spent*\n$912.40",
+            ]
+        );
+        assert_eq!(blocks[3]["type"], "context");
+    }
+
+    #[test]
+    fn test_incident_summary_section() {
+        let event = WebhookEvent::new(
+            EventType::AlertTriggered,
+            "org-1",
+            json!({"message": "Error rate above 5%", "error_rate": 0.08}),
+        )
+        .with_severity(Severity::Critical)
+        .with_incident_summary("Errors on gpt-4o rose from 0.4% to 8% at 14:05.");
+
+        let message = message(&event);
+        let blocks = message["blocks"].as_array().unwrap();
+        assert_eq!(
+            blocks[2]["text"]["text"],
+            "*Incident summary*\nErrors on gpt-4o rose from 0.4% to 8% at 14:05."
+        );
+
+        let fields = blocks[3]["fields"].as_array().unwrap();
+        assert_eq!(fields.len(), 2);
+        assert!(fields.iter().all(|f| !f["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("*Incident summary*")));
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright 2025 LLM Observatory Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! PostgreSQL storage of webhook endpoints, notification channels and
+//! deliveries.
+//!
+//! Tables are created by storage migrations `023_webhooks.sql` and
+//! `024_notification_channels.sql`.
+
+use crate::channel::{NewNotificationChannel, NotificationChannel};
+use crate::delivery::{DeliveryStatus, WebhookDelivery};
+use crate::endpoint::{NewWebhookEndpoint, WebhookEndpoint};
+use crate::error::WebhookResult;
+use crate::event::{EventType, WebhookEvent};
+use crate::signing;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Columns of [`WebhookDelivery`]
+const DELIVERY_COLUMNS: &str =
+    "id, endpoint_id, channel_id, org_id, event_id, event_type, status, attempts, \
+     response_status, last_error, next_attempt_at, created_at, completed_at";
+
+/// Store for webhook endpoints, notification channels and deliveries.
+#[derive(Clone)]
+pub struct WebhookStore {
+    pool: PgPool,
+}
+
+impl WebhookStore {
+    /// Create a store on a read-write pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Register an endpoint with a newly generated secret.
+    ///
+    /// The endpoint should have been validated with
+    /// [`NewWebhookEndpoint::validate`].
+    pub async fn create_endpoint(
+        &self,
+        org_id: &str,
+        endpoint: &NewWebhookEndpoint,
+    ) -> WebhookResult<WebhookEndpoint> {
+        let created = sqlx::query_as::<_, WebhookEndpoint>(
+            r#"
+            INSERT INTO webhook_endpoints (org_id, url, secret, event_types, description)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(org_id)
+        .bind(&endpoint.url)
+        .bind(signing::generate_secret())
+        .bind(&endpoint.event_types)
+        .bind(&endpoint.description)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(created)
+    }
+
+    /// Endpoints of an organization, oldest first.
+    pub async fn list_endpoints(&self, org_id: &str) -> WebhookResult<Vec<WebhookEndpoint>> {
+        let endpoints = sqlx::query_as::<_, WebhookEndpoint>(
+            "SELECT * FROM webhook_endpoints WHERE org_id = $1 ORDER BY created_at",
+        )
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(endpoints)
+    }
+
+    /// One endpoint of an organization.
+    pub async fn get_endpoint(
+        &self,
+        org_id: &str,
+        id: Uuid,
+    ) -> WebhookResult<Option<WebhookEndpoint>> {
+        let endpoint = sqlx::query_as::<_, WebhookEndpoint>(
+            "SELECT * FROM webhook_endpoints WHERE org_id = $1 AND id = $2",
+        )
+        .bind(org_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(endpoint)
+    }
+
+    /// Delete an endpoint and its deliveries, returning whether it existed.
+    pub async fn delete_endpoint(&self, org_id: &str, id: Uuid) -> WebhookResult<bool> {
+        let result = sqlx::query("DELETE FROM webhook_endpoints WHERE org_id = $1 AND id = $2")
+            .bind(org_id)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Enabled endpoints of an organization subscribed to an event type.
+    pub async fn subscribed_endpoints(
+        &self,
+        org_id: &str,
+        event_type: EventType,
+    ) -> WebhookResult<Vec<WebhookEndpoint>> {
+        let endpoints = sqlx::query_as::<_, WebhookEndpoint>(
+            r#"
+            SELECT * FROM webhook_endpoints
+            WHERE org_id = $1
+              AND enabled
+              AND (cardinality(event_types) = 0 OR $2 = ANY(event_types))
+            "#,
+        )
+        .bind(org_id)
+        .bind(event_type.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(endpoints)
+    }
+
+    /// Register a notification channel.
+    ///
+    /// The channel should have been validated with
+    /// [`NewNotificationChannel::validate`].
+    pub async fn create_channel(
+        &self,
+        org_id: &str,
+        channel: &NewNotificationChannel,
+    ) -> WebhookResult<NotificationChannel> {
+        let created = sqlx::query_as::<_, NotificationChannel>(
+            r#"
+            INSERT INTO notification_channels
+                (org_id, kind, name, target, event_types, alert_rules, min_severity)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(org_id)
+        .bind(channel.kind.as_str())
+        .bind(&channel.name)
+        .bind(&channel.target)
+        .bind(&channel.event_types)
+        .bind(&channel.alert_rules)
+        .bind(channel.min_severity.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(created)
+    }
+
+    /// Notification channels of an organization, oldest first.
+    pub async fn list_channels(&self, org_id: &str) -> WebhookResult<Vec<NotificationChannel>> {
+        let channels = sqlx::query_as::<_, NotificationChannel>(
+            "SELECT * FROM notification_channels WHERE org_id = $1 ORDER BY created_at",
+        )
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(channels)
+    }
+
+    /// Enabled notification channels of an organization; routing by event
+    /// type, rule and severity is left to [`NotificationChannel::routes`].
+    pub async fn enabled_channels(&self, org_id: &str) -> WebhookResult<Vec<NotificationChannel>> {
+        let channels = sqlx::query_as::<_, NotificationChannel>(
+            "SELECT * FROM notification_channels WHERE org_id = $1 AND enabled",
+        )
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(channels)
+    }
+
+    /// One notification channel of an organization.
+    pub async fn get_channel(
+        &self,
+        org_id: &str,
+        id: Uuid,
+    ) -> WebhookResult<Option<NotificationChannel>> {
+        let channel = sqlx::query_as::<_, NotificationChannel>(
+            "SELECT * FROM notification_channels WHERE org_id = $1 AND id = $2",
+        )
+        .bind(org_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(channel)
+    }
+
+    /// Delete a notification channel and its deliveries, returning whether
+    /// it existed.
+    pub async fn delete_channel(&self, org_id: &str, id: Uuid) -> WebhookResult<bool> {
+        let result = sqlx::query("DELETE FROM notification_channels WHERE org_id = $1 AND id = $2")
+            .bind(org_id)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record a pending delivery of an event to an endpoint.
+    pub async fn create_delivery(
+        &self,
+        endpoint: &WebhookEndpoint,
+        event: &WebhookEvent,
+    ) -> WebhookResult<Uuid> {
+        self.insert_delivery(&endpoint.org_id, Some(endpoint.id), None, event)
+            .await
+    }
+
+    /// Record a pending delivery of an event to a notification channel.
+    pub async fn create_channel_delivery(
+        &self,
+        channel: &NotificationChannel,
+        event: &WebhookEvent,
+    ) -> WebhookResult<Uuid> {
+        self.insert_delivery(&channel.org_id, None, Some(channel.id), event)
+            .await
+    }
+
+    async fn insert_delivery(
+        &self,
+        org_id: &str,
+        endpoint_id: Option<Uuid>,
+        channel_id: Option<Uuid>,
+        event: &WebhookEvent,
+    ) -> WebhookResult<Uuid> {
+        let id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO webhook_deliveries
+                (endpoint_id, channel_id, org_id, event_id, event_type, payload, status)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id
+            "#,
+        )
+        .bind(endpoint_id)
+        .bind(channel_id)
+        .bind(org_id)
+        .bind(event.id)
+        .bind(event.event_type.as_str())
+        .bind(serde_json::to_value(event)?)
+        .bind(DeliveryStatus::Pending.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Record the result of a delivery attempt.
+    ///
+    /// `next_attempt_at` is set while the delivery is retrying; finished
+    /// deliveries get their completion time.
+    pub async fn record_attempt(
+        &self,
+        id: Uuid,
+        status: DeliveryStatus,
+        attempts: u32,
+        response_status: Option<u16>,
+        last_error: Option<&str>,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> WebhookResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2,
+                attempts = $3,
+                response_status = $4,
+                last_error = $5,
+                next_attempt_at = $6,
+                completed_at = CASE WHEN $2 IN ('succeeded', 'failed') THEN NOW() END
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status.as_str())
+        .bind(attempts as i32)
+        .bind(response_status.map(i32::from))
+        .bind(last_error)
+        .bind(next_attempt_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// One delivery.
+    pub async fn get_delivery(&self, id: Uuid) -> WebhookResult<Option<WebhookDelivery>> {
+        let sql = format!("SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries WHERE id = $1");
+        let delivery = sqlx::query_as::<_, WebhookDelivery>(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(delivery)
+    }
+
+    /// Recent deliveries to a notification channel, newest first.
+    pub async fn list_channel_deliveries(
+        &self,
+        org_id: &str,
+        channel_id: Uuid,
+        limit: i64,
+    ) -> WebhookResult<Vec<WebhookDelivery>> {
+        let sql = format!(
+            r#"
+            SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries
+            WHERE org_id = $1 AND channel_id = $2
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#
+        );
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(&sql)
+            .bind(org_id)
+            .bind(channel_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(deliveries)
+    }
+
+    /// Recent deliveries to an endpoint, newest first.
+    pub async fn list_deliveries(
+        &self,
+        org_id: &str,
+        endpoint_id: Uuid,
+        limit: i64,
+    ) -> WebhookResult<Vec<WebhookDelivery>> {
+        let sql = format!(
+            r#"
+            SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries
+            WHERE org_id = $1 AND endpoint_id = $2
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#
+        );
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(&sql)
+            .bind(org_id)
+            .bind(endpoint_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(deliveries)
+    }
+}
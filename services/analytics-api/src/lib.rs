@@ -8,4 +8,23 @@ pub mod services;
 pub use errors::{ApiError, ErrorCategory, ErrorCode};
 pub use middleware::{AuthContext, JwtClaims, RequireAuth, Role};
 pub use models::{AppState, AnalyticsQuery, ErrorResponse, HealthResponse};
+pub use services::admin::AdminService;
+pub use services::audit_log::AuditLogger;
+pub use services::cold_storage::ColdStorageService;
+pub use services::currency::{CurrencyService, FxQuote, FxRateSource};
+pub use services::data_access::DataAccessPolicy;
+pub use services::drift::DriftMonitor;
+pub use services::federation::FederationService;
+pub use services::incident_summary::IncidentSummaryService;
+pub use services::model_registry::ModelRegistryService;
+pub use services::natural_query::NaturalQueryService;
+pub use services::provider_health::ProviderHealthMonitor;
+pub use services::pseudonyms::PseudonymLookupService;
+pub use services::quarantine::QuarantineService;
+pub use services::query_cache::{CacheStatus, FreshnessPolicy, QueryCache};
+pub use services::rate_limits::RateLimitMonitor;
+pub use services::service_accounts::ServiceAccountService;
 pub use services::timescaledb::TimescaleDBService;
+pub use services::topology::TopologyMaterializer;
+pub use services::trace_deletion::TraceDeletionService;
+pub use services::webhooks::WebhookService;
@@ -1,4 +1,28 @@
-use analytics_api::{middleware::auth::JwtValidator, models::*, routes};
+use analytics_api::{
+    middleware::auth::JwtValidator,
+    middleware::oidc::OidcAuthenticator,
+    models::*,
+    routes,
+    services::admin::AdminService,
+    services::audit_log::{AuditLogger, DEFAULT_QUEUE_CAPACITY},
+    services::cold_storage::{ColdStorageService, DEFAULT_HOT_RETENTION_DAYS},
+    services::currency::{CurrencyService, FxRateSource, RemoteRateSource, StaticRateSource},
+    services::data_access::{DataAccessPolicy, SensitiveField},
+    services::drift::{DriftConfig, DriftMonitor},
+    services::federation::{FederationService, DEFAULT_LOCAL_REGION, DEFAULT_TIMEOUT},
+    services::incident_summary::{IncidentSummaryService, DEFAULT_WINDOW_MINUTES},
+    services::model_registry::ModelRegistryService,
+    services::natural_query::NaturalQueryService,
+    services::provider_health::{default_probes, ProviderHealthMonitor},
+    services::pseudonyms::{PseudonymLookupService, DEFAULT_KEY_PREFIX},
+    services::quarantine::QuarantineService,
+    services::query_cache::QueryCache,
+    services::rate_limits::{RateLimitAlertConfig, RateLimitMonitor},
+    services::service_accounts::ServiceAccountService,
+    services::topology::TopologyMaterializer,
+    services::trace_deletion::{TraceDeletionService, DEFAULT_BATCH_SIZE},
+    services::webhooks::WebhookService,
+};
 use axum::{
     extract::State,
     http::{header, HeaderValue, Method, StatusCode},
@@ -75,6 +99,190 @@ async fn main() -> anyhow::Result<()> {
         "default_jwt_secret_change_in_production_minimum_32_characters".to_string()
     });
 
+    // External identity providers (JSON array), e.g. Okta or Azure AD
+    let oidc = match std::env::var("OIDC_PROVIDERS") {
+        Ok(providers) => Some(Arc::new(OidcAuthenticator::parse(&providers)?)),
+        Err(_) => None,
+    };
+
+    // Vault of the collector's user and session pseudonyms (a dedicated
+    // Redis, not the cache)
+    let pseudonyms = match std::env::var("PSEUDONYM_VAULT_REDIS_URL") {
+        Ok(url) => {
+            let prefix = std::env::var("PSEUDONYM_VAULT_KEY_PREFIX")
+                .unwrap_or_else(|_| DEFAULT_KEY_PREFIX.to_string());
+            Arc::new(PseudonymLookupService::new(
+                Some(redis::Client::open(url)?),
+                prefix,
+            ))
+        }
+        Err(_) => Arc::new(PseudonymLookupService::disabled()),
+    };
+
+    // Other regions' databases or analytics APIs, for `scope=global`
+    let local_region =
+        std::env::var("FEDERATION_LOCAL_REGION").unwrap_or_else(|_| DEFAULT_LOCAL_REGION.to_string());
+    let federation_timeout = std::env::var("FEDERATION_TIMEOUT_SECS")
+        .ok()
+        .and_then(|t| t.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_TIMEOUT);
+    let federation = Arc::new(FederationService::parse(
+        local_region,
+        &std::env::var("FEDERATION_REGIONS").unwrap_or_default(),
+        federation_timeout,
+    )?);
+    if !federation.remote_regions().is_empty() {
+        info!(
+            local_region = federation.local_region(),
+            remote_regions = ?federation.remote_regions(),
+            "Cross-region federation enabled"
+        );
+    }
+
+    // Traces archived to Parquet, queried for ranges older than the hot
+    // retention window
+    let cold_storage = match std::env::var("COLD_STORAGE_URL") {
+        Ok(location) => {
+            let hot_retention_days = std::env::var("HOT_RETENTION_DAYS")
+                .ok()
+                .and_then(|d| d.parse().ok())
+                .unwrap_or(DEFAULT_HOT_RETENTION_DAYS);
+            let service =
+                ColdStorageService::new(&location, chrono::Duration::days(hot_retention_days)).await?;
+            info!(location = %location, hot_retention_days, "Cold storage queries enabled");
+            Arc::new(service)
+        }
+        Err(_) => Arc::new(ColdStorageService::disabled()),
+    };
+
+    // Model translating natural-language questions into metrics queries
+    let natural_query = match std::env::var("NL_QUERY_PROVIDER").ok().as_deref() {
+        Some("openai") => {
+            let model = std::env::var("NL_QUERY_MODEL").unwrap_or_else(|_| "gpt-4o-mini".to_string());
+            info!(provider = "openai", model = %model, "Natural-language queries enabled");
+            Arc::new(NaturalQueryService::new(
+                Arc::new(llm_observatory_providers::OpenAiProvider::from_env()?),
+                model,
+            ))
+        }
+        Some("anthropic") => {
+            let model = std::env::var("NL_QUERY_MODEL")
+                .unwrap_or_else(|_| "claude-3-5-haiku-latest".to_string());
+            info!(provider = "anthropic", model = %model, "Natural-language queries enabled");
+            Arc::new(NaturalQueryService::new(
+                Arc::new(llm_observatory_providers::AnthropicProvider::from_env()?),
+                model,
+            ))
+        }
+        Some(other) => anyhow::bail!("Unknown NL_QUERY_PROVIDER '{}' (expected openai or anthropic)", other),
+        None => Arc::new(NaturalQueryService::disabled()),
+    };
+
+    // Currency conversion for cost reporting
+    let display_currency = std::env::var("DISPLAY_CURRENCY").unwrap_or_else(|_| "USD".to_string());
+    let fx_refresh_secs: u64 = std::env::var("FX_RATES_REFRESH_SECS")
+        .ok()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(3600);
+    let fx_source: Arc<dyn FxRateSource> = match std::env::var("FX_RATES_URL") {
+        Ok(url) => Arc::new(RemoteRateSource::new(url)),
+        Err(_) => Arc::new(StaticRateSource::parse(
+            &std::env::var("FX_RATES").unwrap_or_default(),
+        )?),
+    };
+    let fx_is_remote = std::env::var("FX_RATES_URL").is_ok();
+
+    // Upstream provider health probes
+    let provider_health_enabled = std::env::var("PROVIDER_HEALTH_ENABLED")
+        .map(|v| v == "true")
+        .unwrap_or(false);
+    let provider_health_interval: u64 = std::env::var("PROVIDER_HEALTH_INTERVAL_SECS")
+        .ok()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(60);
+
+    // Service dependency graph materialization
+    let topology_enabled = std::env::var("TOPOLOGY_ENABLED")
+        .map(|v| v != "false")
+        .unwrap_or(true);
+    let topology_interval: u64 = std::env::var("TOPOLOGY_INTERVAL_SECS")
+        .ok()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(60);
+    let topology_bucket: u64 = std::env::var("TOPOLOGY_BUCKET_SECS")
+        .ok()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(300);
+
+    // Drift monitoring of prompt/response characteristics
+    let drift_enabled = std::env::var("DRIFT_ENABLED")
+        .map(|v| v != "false")
+        .unwrap_or(true);
+    let drift_interval: u64 = std::env::var("DRIFT_INTERVAL_SECS")
+        .ok()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(900);
+    let drift_defaults = DriftConfig::default();
+    let drift_config = DriftConfig {
+        sample_size: std::env::var("DRIFT_SAMPLE_SIZE")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(drift_defaults.sample_size),
+        baseline_days: std::env::var("DRIFT_BASELINE_DAYS")
+            .ok()
+            .and_then(|d| d.parse().ok())
+            .unwrap_or(drift_defaults.baseline_days),
+        min_requests: std::env::var("DRIFT_MIN_REQUESTS")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(drift_defaults.min_requests),
+        ..drift_defaults
+    };
+
+    // Approaching provider rate limit alerts
+    let rate_limit_alerts_enabled = std::env::var("RATE_LIMIT_ALERTS_ENABLED")
+        .map(|v| v != "false")
+        .unwrap_or(true);
+    let rate_limit_alert_interval: u64 = std::env::var("RATE_LIMIT_ALERT_INTERVAL_SECS")
+        .ok()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(60);
+    let rate_limit_defaults = RateLimitAlertConfig::default();
+    let rate_limit_config = RateLimitAlertConfig {
+        threshold: std::env::var("RATE_LIMIT_ALERT_THRESHOLD")
+            .ok()
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(rate_limit_defaults.threshold),
+        cooldown: std::env::var("RATE_LIMIT_ALERT_COOLDOWN_SECS")
+            .ok()
+            .and_then(|t| t.parse().ok())
+            .map(chrono::Duration::seconds)
+            .unwrap_or(rate_limit_defaults.cooldown),
+    };
+
+    // Field-level masking of trace content and user identifiers
+    let masked_fields = SensitiveField::parse_list(
+        &std::env::var("MASKED_FIELDS")
+            .unwrap_or_else(|_| "input_text,output_text,user_id,session_id".to_string()),
+    )
+    .map_err(anyhow::Error::msg)?;
+
+    // Audit trail of authenticated API calls; written with DATABASE_URL
+    let audit_log_enabled = std::env::var("AUDIT_LOG_ENABLED")
+        .map(|v| v != "false")
+        .unwrap_or(true);
+    let audit_log_queue_size: usize = std::env::var("AUDIT_LOG_QUEUE_SIZE")
+        .ok()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(DEFAULT_QUEUE_CAPACITY);
+
+    // Bulk trace deletion (DELETE /api/v1/traces); runs with DATABASE_URL
+    let trace_deletion_batch_size: i64 = std::env::var("TRACE_DELETION_BATCH_SIZE")
+        .ok()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(DEFAULT_BATCH_SIZE);
+
     // Initialize Prometheus metrics
     let prometheus_handle = setup_metrics_recorder()?;
     info!("Metrics exporter listening on port {}", metrics_port);
@@ -107,18 +315,197 @@ async fn main() -> anyhow::Result<()> {
         .await?;
     info!("Redis connection established");
 
+    // Load exchange rates
+    let currency = Arc::new(CurrencyService::new(&display_currency, fx_source).await?);
+    if fx_is_remote {
+        currency
+            .clone()
+            .spawn_refresh(Duration::from_secs(fx_refresh_secs));
+    }
+    info!(display_currency = %currency.display_currency(), "Currency service initialized");
+
+    // Start provider health monitor (incidents are written with the read-write URL)
+    let provider_health = if provider_health_enabled {
+        let write_pool = match std::env::var("DATABASE_URL") {
+            Ok(url) => Some(
+                sqlx::postgres::PgPoolOptions::new()
+                    .max_connections(2)
+                    .connect_lazy(&url)?,
+            ),
+            Err(_) => None,
+        };
+        let azure_endpoint = std::env::var("AZURE_OPENAI_ENDPOINT").ok();
+        let monitor = Arc::new(ProviderHealthMonitor::new(
+            default_probes(azure_endpoint.as_deref()),
+            write_pool,
+        ));
+        monitor
+            .clone()
+            .spawn(Duration::from_secs(provider_health_interval));
+        info!("Provider health monitor started");
+        monitor
+    } else {
+        Arc::new(ProviderHealthMonitor::disabled())
+    };
+
+    // Start topology materializer (edges are written with the read-write URL)
+    if topology_enabled {
+        match std::env::var("DATABASE_URL") {
+            Ok(url) => {
+                let write_pool = sqlx::postgres::PgPoolOptions::new()
+                    .max_connections(2)
+                    .connect_lazy(&url)?;
+                Arc::new(TopologyMaterializer::new(
+                    write_pool,
+                    Duration::from_secs(topology_bucket),
+                ))
+                .spawn(Duration::from_secs(topology_interval));
+            }
+            Err(_) => info!("DATABASE_URL not set, service topology materializer disabled"),
+        }
+    }
+
+    // Model writing incident narratives for firing alerts
+    let incident_window_minutes: i64 = std::env::var("INCIDENT_SUMMARY_WINDOW_MINUTES")
+        .ok()
+        .and_then(|m| m.parse().ok())
+        .unwrap_or(DEFAULT_WINDOW_MINUTES);
+    let incident_summaries = match std::env::var("INCIDENT_SUMMARY_PROVIDER").ok().as_deref() {
+        Some("openai") => {
+            let model = std::env::var("INCIDENT_SUMMARY_MODEL")
+                .unwrap_or_else(|_| "gpt-4o-mini".to_string());
+            info!(provider = "openai", model = %model, "Incident summaries enabled");
+            Arc::new(
+                IncidentSummaryService::new(
+                    Arc::new(llm_observatory_providers::OpenAiProvider::from_env()?),
+                    model,
+                    db_pool.clone(),
+                )
+                .with_window(chrono::Duration::minutes(incident_window_minutes)),
+            )
+        }
+        Some("anthropic") => {
+            let model = std::env::var("INCIDENT_SUMMARY_MODEL")
+                .unwrap_or_else(|_| "claude-3-5-haiku-latest".to_string());
+            info!(provider = "anthropic", model = %model, "Incident summaries enabled");
+            Arc::new(
+                IncidentSummaryService::new(
+                    Arc::new(llm_observatory_providers::AnthropicProvider::from_env()?),
+                    model,
+                    db_pool.clone(),
+                )
+                .with_window(chrono::Duration::minutes(incident_window_minutes)),
+            )
+        }
+        Some(other) => anyhow::bail!("Unknown INCIDENT_SUMMARY_PROVIDER '{}' (expected openai or anthropic)", other),
+        None => Arc::new(IncidentSummaryService::disabled()),
+    };
+
+    // Audit entries (API calls, unmasked trace access), trace deletions,
+    // quarantine replay requests, webhooks and the admin API use the
+    // read-write URL
+    let audit_pool = match std::env::var("DATABASE_URL") {
+        Ok(url) => Some(
+            sqlx::postgres::PgPoolOptions::new()
+                .max_connections(2)
+                .connect_lazy(&url)?,
+        ),
+        Err(_) => {
+            info!("DATABASE_URL not set, audit entries are only logged and trace deletion, quarantine replay, webhooks, the admin API and the model registry are disabled");
+            None
+        }
+    };
+    let data_access = Arc::new(DataAccessPolicy::new(masked_fields, audit_pool.clone()));
+    let trace_deletion = Arc::new(TraceDeletionService::new(
+        audit_pool.clone(),
+        trace_deletion_batch_size,
+    ));
+    let quarantine = Arc::new(QuarantineService::new(audit_pool.clone()));
+    let webhooks = Arc::new(
+        WebhookService::new(audit_pool.clone()).with_incident_summaries(incident_summaries),
+    );
+
+    // Start drift monitor (snapshots and alerts are written with the
+    // read-write URL)
+    if drift_enabled {
+        match std::env::var("DATABASE_URL") {
+            Ok(url) => {
+                let write_pool = sqlx::postgres::PgPoolOptions::new()
+                    .max_connections(2)
+                    .connect_lazy(&url)?;
+                Arc::new(DriftMonitor::new(write_pool, webhooks.clone(), drift_config))
+                    .spawn(Duration::from_secs(drift_interval));
+            }
+            Err(_) => info!("DATABASE_URL not set, drift monitor disabled"),
+        }
+    }
+
+    // Start rate limit monitor (alerts are claimed with the read-write URL)
+    if rate_limit_alerts_enabled {
+        match std::env::var("DATABASE_URL") {
+            Ok(url) => {
+                let write_pool = sqlx::postgres::PgPoolOptions::new()
+                    .max_connections(2)
+                    .connect_lazy(&url)?;
+                Arc::new(RateLimitMonitor::new(
+                    write_pool,
+                    webhooks.clone(),
+                    rate_limit_config,
+                ))
+                .spawn(Duration::from_secs(rate_limit_alert_interval));
+            }
+            Err(_) => info!("DATABASE_URL not set, rate limit monitor disabled"),
+        }
+    }
+
+    let admin = Arc::new(AdminService::new(audit_pool.clone()));
+    let model_registry = Arc::new(ModelRegistryService::new(audit_pool.clone()));
+    let service_accounts = Arc::new(ServiceAccountService::new(audit_pool.clone(), &jwt_secret));
+    let query_cache = Arc::new(QueryCache::new(redis_client.clone()));
+    let audit_logger = Arc::new(if audit_log_enabled {
+        AuditLogger::spawn(audit_pool, audit_log_queue_size)
+    } else {
+        AuditLogger::disabled()
+    });
+
     // Create application state
     let app_state = Arc::new(AppState {
         db_pool,
         redis_client,
         cache_ttl,
+        currency,
+        provider_health,
+        data_access,
+        trace_deletion,
+        quarantine,
+        query_cache,
+        webhooks,
+        admin,
+        service_accounts: service_accounts.clone(),
+        pseudonyms,
+        federation,
+        cold_storage,
+        natural_query,
+        model_registry,
     });
 
     // Create JWT validator
-    let jwt_validator = Arc::new(JwtValidator::new(&jwt_secret));
+    let mut jwt_validator = JwtValidator::new(&jwt_secret).with_service_accounts(service_accounts);
+    if let Some(oidc) = oidc {
+        for provider in oidc.providers() {
+            info!(provider = %provider.name(), issuer = %provider.issuer(), "OIDC provider enabled");
+        }
+        jwt_validator = jwt_validator.with_oidc(oidc);
+    }
+    let jwt_validator = Arc::new(jwt_validator);
 
     // Build application router
-    let app = build_router(app_state.clone(), jwt_validator, prometheus_handle);
+    let app = build_router(
+        app_state.clone(),
+        jwt_validator,
+        audit_logger,
+        prometheus_handle,
+    );
 
     // Start server
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
@@ -130,31 +517,74 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// CORS layer for the comma-separated `origins`
+fn cors_layer(origins: &str) -> CorsLayer {
+    CorsLayer::new()
+        .allow_origin(
+            origins
+                .split(',')
+                .filter_map(|origin| origin.trim().parse::<HeaderValue>().ok())
+                .collect::<Vec<_>>(),
+        )
+        // Every method a route uses, or browsers fail its preflight request
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+        .max_age(Duration::from_secs(3600))
+}
+
 /// Build the application router with all routes and middleware
 fn build_router(
     state: Arc<AppState>,
     jwt_validator: Arc<JwtValidator>,
+    audit_logger: Arc<AuditLogger>,
     prometheus_handle: PrometheusHandle,
 ) -> Router {
     // Create CORS layer
-    let cors = CorsLayer::new()
-        .allow_origin(
-            std::env::var("CORS_ORIGINS")
-                .unwrap_or_else(|_| "*".to_string())
-                .split(',')
-                .filter_map(|origin| origin.trim().parse::<HeaderValue>().ok())
-                .collect::<Vec<_>>(),
-        )
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
-        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
-        .max_age(Duration::from_secs(3600));
+    let cors = cors_layer(&std::env::var("CORS_ORIGINS").unwrap_or_else(|_| "*".to_string()));
 
-    // Protected API routes (require authentication and rate limiting)
+    // Protected API routes (require authentication and rate limiting; calls are
+    // recorded in the audit log)
     let protected_routes = Router::new()
         .merge(routes::traces::routes())
         .merge(routes::metrics::routes())
         .merge(routes::costs::routes())
+        .merge(routes::overview::routes())
+        .merge(routes::providers::routes())
+        .merge(routes::topology::routes())
+        .merge(routes::drift::routes())
+        .merge(routes::jaeger::routes())
+        .merge(routes::grafana::routes())
         .merge(routes::export::routes())
+        .merge(routes::experiments::routes())
+        .merge(routes::guardrails::routes())
+        .merge(routes::quarantine::routes())
+        .merge(routes::quotas::routes())
+        .merge(routes::recommendations::routes())
+        .merge(routes::context::routes())
+        .merge(routes::prompt_cache::routes())
+        .merge(routes::rate_limits::routes())
+        .merge(routes::retries::routes())
+        .merge(routes::concurrency::routes())
+        .merge(routes::model_registry::routes())
+        .merge(routes::query::routes())
+        .merge(routes::logs::routes())
+        .merge(routes::events::routes())
+        .merge(routes::span_names::routes())
+        .merge(routes::webhooks::routes())
+        .merge(routes::audit::routes())
+        .merge(routes::admin::routes())
+        .merge(routes::pseudonyms::routes())
+        .layer(middleware::from_fn_with_state(
+            audit_logger,
+            analytics_api::middleware::audit::audit_middleware,
+        ))
         .layer(middleware::from_fn_with_state(
             jwt_validator.clone(),
             analytics_api::middleware::auth::require_auth,
@@ -269,4 +699,38 @@ mod tests {
         // This ensures all routes are properly configured
         assert!(true);
     }
+
+    #[tokio::test]
+    async fn test_cors_preflight_allows_model_updates() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        // Model registry updates use PUT
+        let app = Router::new()
+            .route("/api/v1/model-registry/:id", axum::routing::put(|| async {}))
+            .layer(cors_layer("http://localhost:3000"));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri("/api/v1/model-registry/model-1")
+                    .header(header::ORIGIN, "http://localhost:3000")
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "PUT")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let allowed = response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_METHODS)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(allowed.split(',').any(|method| method.trim() == "PUT"));
+        assert!(allowed.split(',').any(|method| method.trim() == "PATCH"));
+    }
 }
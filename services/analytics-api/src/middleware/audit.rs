@@ -0,0 +1,361 @@
+//! API audit logging middleware
+//!
+//! Records every authenticated call (who, organization, endpoint, filters,
+//! rows returned, status and duration) through the [`AuditLogger`]. Must be
+//! layered inside `require_auth`, which provides the [`AuthContext`]; requests
+//! without one are passed through unrecorded.
+//!
+//! Filters are the query string parameters and, for JSON requests up to
+//! [`MAX_CAPTURED_REQUEST_BODY`], the request body. Values of secret-bearing
+//! keys (channel targets, webhook secrets, tokens, passwords, keys) are
+//! replaced with [`REDACTED`] before they are recorded, since the audit log
+//! is kept far longer than any credential should be. The row count is the
+//! length of a top-level JSON array or of a top-level `data` field (1 for a
+//! single `data` object); other responses have no row count.
+
+use crate::middleware::AuthContext;
+use crate::models::audit::AuditLogEntry;
+use crate::services::audit_log::AuditLogger;
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    extract::{MatchedPath, Query, Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Largest JSON request body recorded as filters
+pub const MAX_CAPTURED_REQUEST_BODY: usize = 64 * 1024;
+
+/// Largest JSON response body inspected for a row count
+pub const MAX_COUNTED_RESPONSE_BODY: usize = 16 * 1024 * 1024;
+
+/// Recorded in place of a secret value
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Keys whose values are always secret, e.g. a notification channel's
+/// `target` (Slack webhook URL or PagerDuty routing key)
+const SECRET_KEYS: &[&str] = &[
+    "target",
+    "secret",
+    "token",
+    "password",
+    "key",
+    "authorization",
+    "credentials",
+];
+
+/// Key suffixes marking secret values (`client_secret`, `access_token`,
+/// `api_key`, `apiKey`)
+const SECRET_KEY_SUFFIXES: &[&str] = &["secret", "token", "password", "_key", "apikey"];
+
+/// Record the call in the audit log
+pub async fn audit_middleware(
+    State(logger): State<Arc<AuditLogger>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(auth) = req.extensions().get::<AuthContext>().cloned() else {
+        return next.run(req).await;
+    };
+
+    let started = Instant::now();
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+    let endpoint = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| path.clone());
+    let query_params = Query::<BTreeMap<String, String>>::try_from_uri(req.uri())
+        .ok()
+        .filter(|Query(params)| !params.is_empty())
+        .and_then(|Query(params)| serde_json::to_value(params).ok())
+        .map(redact_secrets);
+
+    let (req, request_body) = capture_request_body(req).await;
+    let response = next.run(req).await;
+    let status_code = response.status().as_u16() as i32;
+    let (response, row_count) = count_response_rows(response).await;
+
+    logger.record(AuditLogEntry {
+        ts: Utc::now(),
+        request_id: auth.request_id,
+        org_id: auth.org_id,
+        user_id: auth.user_id,
+        role: format!("{:?}", auth.role).to_lowercase(),
+        auth_method: format!("{:?}", auth.auth_method).to_lowercase(),
+        method,
+        endpoint,
+        path,
+        query_params,
+        request_body,
+        status_code,
+        row_count,
+        duration_ms: started.elapsed().as_millis() as i64,
+    });
+
+    response
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"))
+}
+
+/// Take a copy of a small JSON request body, passing the body on unchanged.
+async fn capture_request_body(req: Request) -> (Request, Option<serde_json::Value>) {
+    let content_length = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+
+    if !is_json(req.headers())
+        || !content_length.is_some_and(|len| len > 0 && len <= MAX_CAPTURED_REQUEST_BODY)
+    {
+        return (req, None);
+    }
+
+    let (parts, body) = req.into_parts();
+    let bytes = to_bytes(body, MAX_CAPTURED_REQUEST_BODY)
+        .await
+        .unwrap_or_default();
+    let captured = serde_json::from_slice(&bytes).ok().map(redact_secrets);
+
+    (Request::from_parts(parts, Body::from(bytes)), captured)
+}
+
+/// Whether the value of a key is a credential
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEYS.contains(&key.as_str())
+        || SECRET_KEY_SUFFIXES
+            .iter()
+            .any(|suffix| key.ends_with(suffix))
+}
+
+/// Replace the values of secret-bearing keys, at any depth.
+pub fn redact_secrets(mut value: serde_json::Value) -> serde_json::Value {
+    redact_in_place(&mut value);
+    value
+}
+
+fn redact_in_place(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                // Numbers and flags under these keys (an SLO `target`) are kept
+                if is_secret_key(key) && (value.is_string() || value.is_object()) {
+                    *value = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact_in_place(value);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(redact_in_place),
+        _ => {}
+    }
+}
+
+/// Count the rows of an in-memory JSON response, passing it on unchanged.
+async fn count_response_rows(response: Response) -> (Response, Option<i64>) {
+    let counted_size = response
+        .body()
+        .size_hint()
+        .exact()
+        .is_some_and(|size| size as usize <= MAX_COUNTED_RESPONSE_BODY);
+
+    if !is_json(response.headers()) || !counted_size {
+        return (response, None);
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = to_bytes(body, MAX_COUNTED_RESPONSE_BODY)
+        .await
+        .unwrap_or_default();
+    let row_count = count_rows(&bytes);
+
+    (Response::from_parts(parts, Body::from(bytes)), row_count)
+}
+
+/// Rows in a JSON response body, without building the JSON value.
+pub fn count_rows(body: &[u8]) -> Option<i64> {
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    RowCounter { top_level: true }
+        .deserialize(&mut deserializer)
+        .ok()
+        .flatten()
+}
+
+/// Counts a top-level array or the top-level `data` field.
+struct RowCounter {
+    top_level: bool,
+}
+
+impl<'de> DeserializeSeed<'de> for RowCounter {
+    type Value = Option<i64>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for RowCounter {
+    type Value = Option<i64>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON array or object")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut count = 0;
+        while seq.next_element::<IgnoredAny>()?.is_some() {
+            count += 1;
+        }
+        Ok(Some(count))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut count = if self.top_level { None } else { Some(1) };
+        while let Some(key) = map.next_key::<String>()? {
+            if self.top_level && key == "data" {
+                count = map.next_value_seed(RowCounter { top_level: false })?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(count)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::auth::{AuthMethod, Role};
+    use axum::{
+        http::{Method, StatusCode},
+        routing::post,
+        Json, Router,
+    };
+    use serde_json::json;
+    use tokio::sync::mpsc;
+    use tower::ServiceExt;
+
+    fn auth() -> AuthContext {
+        AuthContext {
+            user_id: "user123".to_string(),
+            org_id: "org456".to_string(),
+            projects: vec![],
+            role: Role::Admin,
+            permissions: Role::Admin.default_permissions(),
+            auth_method: AuthMethod::Jwt,
+            request_id: "req123".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_redacts_credentials() {
+        let (sender, mut receiver) = mpsc::channel(1);
+        let logger = Arc::new(AuditLogger::from_sender(sender));
+        let app = Router::new()
+            .route(
+                "/api/v1/notification-channels",
+                post(|Json(body): Json<serde_json::Value>| async move {
+                    // The handler still gets the credential
+                    assert_eq!(
+                        body["target"],
+                        "https://hooks.slack.com/services/T0/B0/secret"
+                    );
+                    (StatusCode::CREATED, Json(json!({"id": "c1"})))
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                logger,
+                audit_middleware,
+            ))
+            .layer(axum::Extension(auth()));
+
+        let body = json!({
+            "kind": "slack",
+            "name": "On-call",
+            "target": "https://hooks.slack.com/services/T0/B0/secret",
+            "event_types": ["alert.fired"],
+            "options": {"api_key": "k-123", "signingSecret": "s-456"},
+        })
+        .to_string();
+        let request = axum::http::Request::builder()
+            .method(Method::POST)
+            .uri("/api/v1/notification-channels?access_token=t-789&limit=10")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::CONTENT_LENGTH, body.len())
+            .body(Body::from(body))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let entry = receiver.recv().await.unwrap();
+        assert_eq!(
+            entry.request_body,
+            Some(json!({
+                "kind": "slack",
+                "name": "On-call",
+                "target": REDACTED,
+                "event_types": ["alert.fired"],
+                "options": {"api_key": REDACTED, "signingSecret": REDACTED},
+            }))
+        );
+        assert_eq!(
+            entry.query_params,
+            Some(json!({"access_token": REDACTED, "limit": "10"}))
+        );
+        assert_eq!(entry.endpoint, "/api/v1/notification-channels");
+        assert_eq!(entry.status_code, 201);
+    }
+
+    #[test]
+    fn test_redact_secrets() {
+        assert_eq!(
+            redact_secrets(json!({
+                "secret": "whsec_1",
+                "filters": [{"password": "p", "model": "gpt-4o"}],
+                "token": null,
+                "monkey": "business",
+                "slo": {"target": 0.99},
+            })),
+            json!({
+                "secret": REDACTED,
+                "filters": [{"password": REDACTED, "model": "gpt-4o"}],
+                "token": null,
+                "monkey": "business",
+                "slo": {"target": 0.99},
+            })
+        );
+    }
+
+    #[test]
+    fn test_count_rows() {
+        assert_eq!(count_rows(br#"[{"a":1},{"a":2}]"#), Some(2));
+        assert_eq!(
+            count_rows(br#"{"status":"success","data":[1,2,3],"meta":{"data":[]}}"#),
+            Some(3)
+        );
+        assert_eq!(count_rows(br#"{"data":{"trace_id":"t1"}}"#), Some(1));
+        assert_eq!(count_rows(br#"{"data":null}"#), None);
+        assert_eq!(count_rows(br#"{"total_cost":1.5}"#), None);
+        assert_eq!(count_rows(b"not json"), None);
+    }
+}
@@ -5,6 +5,8 @@
 ///!
 ///! # Security Features
 ///! - JWT token validation with expiration checking
+///! - OIDC tokens from external identity providers (see [`super::oidc`])
+///! - Revocable service account tokens with scoped permissions
 ///! - API key hashing and validation
 ///! - Role-based access control
 ///! - Project-level authorization
@@ -33,6 +35,9 @@ use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation}
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
+
+use super::oidc::OidcAuthenticator;
+use crate::services::service_accounts::ServiceAccountService;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
@@ -108,6 +113,8 @@ pub enum Role {
     Viewer,
     /// Read-only access to cost and usage data
     Billing,
+    /// Machine identity; only the permissions of its service account
+    Service,
 }
 
 impl Role {
@@ -121,6 +128,9 @@ impl Role {
                 "read:costs".to_string(),
                 "write:evaluations".to_string(),
                 "write:feedback".to_string(),
+                "write:experiments".to_string(),
+                "read:trace_content".to_string(),
+                "read:user_identifiers".to_string(),
             ],
             Role::Viewer => vec![
                 "read:traces".to_string(),
@@ -128,6 +138,7 @@ impl Role {
                 "read:costs".to_string(),
             ],
             Role::Billing => vec!["read:costs".to_string(), "read:usage".to_string()],
+            Role::Service => vec![],
         }
     }
 
@@ -206,6 +217,10 @@ impl AuthContext {
 pub enum AuthMethod {
     /// JWT token authentication
     Jwt,
+    /// OIDC token from an external identity provider
+    Oidc,
+    /// Service account token
+    ServiceAccount,
     /// API key authentication
     ApiKey,
 }
@@ -304,6 +319,10 @@ impl IntoResponse for AuthError {
 pub struct JwtValidator {
     decoding_key: DecodingKey,
     validation: Validation,
+    /// Identity providers whose tokens are accepted besides our own
+    oidc: Option<Arc<OidcAuthenticator>>,
+    /// Checks service account tokens for revocation
+    service_accounts: Option<Arc<ServiceAccountService>>,
 }
 
 impl JwtValidator {
@@ -312,9 +331,45 @@ impl JwtValidator {
         Self {
             decoding_key: DecodingKey::from_secret(secret.as_bytes()),
             validation: Validation::default(),
+            oidc: None,
+            service_accounts: None,
         }
     }
 
+    /// Also accept tokens of the OIDC identity providers
+    pub fn with_oidc(mut self, oidc: Arc<OidcAuthenticator>) -> Self {
+        self.oidc = Some(oidc);
+        self
+    }
+
+    /// Accept service account tokens, checked against their service account
+    pub fn with_service_accounts(mut self, service_accounts: Arc<ServiceAccountService>) -> Self {
+        self.service_accounts = Some(service_accounts);
+        self
+    }
+
+    /// Validate a token issued by a configured OIDC provider, or by us
+    pub async fn authenticate(&self, token: &str) -> Result<(JwtClaims, AuthMethod), AuthError> {
+        if let Some(provider) = self.oidc.as_ref().and_then(|oidc| oidc.provider_for(token)) {
+            let claims = provider.validate(token).await?;
+            info!(provider = %provider.name(), "Successfully validated OIDC token for user: {}", claims.sub);
+            return Ok((claims, AuthMethod::Oidc));
+        }
+
+        let claims = self.validate(token)?;
+        if claims.role == Role::Service {
+            // Revocation and permission changes apply to issued tokens
+            let Some(service_accounts) = &self.service_accounts else {
+                warn!("Service account token rejected: service accounts are not enabled");
+                return Err(AuthError::InvalidToken);
+            };
+            let claims = service_accounts.verify(claims).await?;
+            return Ok((claims, AuthMethod::ServiceAccount));
+        }
+
+        Ok((claims, AuthMethod::Jwt))
+    }
+
     /// Validate and decode JWT token
     pub fn validate(&self, token: &str) -> Result<JwtClaims, AuthError> {
         let token_data = decode::<JwtClaims>(token, &self.decoding_key, &self.validation)
@@ -400,7 +455,13 @@ impl RequireAuth {
     pub fn any_role(jwt_secret: &str) -> Self {
         Self::new(
             jwt_secret,
-            vec![Role::Admin, Role::Developer, Role::Viewer, Role::Billing],
+            vec![
+                Role::Admin,
+                Role::Developer,
+                Role::Viewer,
+                Role::Billing,
+                Role::Service,
+            ],
         )
     }
 }
@@ -427,15 +488,17 @@ pub async fn require_auth(
         .ok_or(AuthError::InvalidHeader)?;
 
     // Validate token
-    let claims = validator.validate(token)?;
+    let (claims, auth_method) = validator.authenticate(token).await?;
 
     // Create auth context
-    let auth_context = AuthContext::from_claims(claims.clone(), request_id.clone());
+    let mut auth_context = AuthContext::from_claims(claims.clone(), request_id.clone());
+    auth_context.auth_method = auth_method;
 
     info!(
         user_id = %auth_context.user_id,
         org_id = %auth_context.org_id,
         role = ?auth_context.role,
+        auth_method = ?auth_context.auth_method,
         request_id = %request_id,
         "Authentication successful"
     );
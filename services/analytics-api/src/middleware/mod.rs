@@ -1,6 +1,8 @@
 // Authentication and authorization middleware
+pub mod audit;
 pub mod auth;
 pub mod caching;
+pub mod oidc;
 pub mod rate_limit;
 
 pub use auth::{AuthContext, JwtClaims, RequireAuth, Role};
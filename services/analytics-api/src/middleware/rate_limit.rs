@@ -53,7 +53,7 @@ impl RateLimitConfig {
                 requests_per_minute: 100_000,
                 burst_capacity: 120_000,
             },
-            Role::Developer => Self {
+            Role::Developer | Role::Service => Self {
                 requests_per_minute: 10_000,
                 burst_capacity: 12_000,
             },
@@ -1,8 +1,35 @@
+pub mod admin;
+pub mod audit;
+pub mod concurrency;
+pub mod context;
 pub mod costs;
+pub mod deletion;
+pub mod drift;
+pub mod events;
+pub mod experiments;
+pub mod guardrails;
 pub mod export;
+pub mod federation;
 pub mod filters;
+pub mod grafana;
+pub mod jaeger;
+pub mod logs;
 pub mod metrics;
+pub mod model_registry;
+pub mod natural_query;
+pub mod overview;
+pub mod prompt_cache;
+pub mod providers;
+pub mod pseudonyms;
+pub mod quarantine;
+pub mod quotas;
+pub mod rate_limits;
+pub mod recommendations;
+pub mod retries;
+pub mod span_names;
+pub mod topology;
 pub mod traces;
+pub mod webhooks;
 pub mod websocket;
 
 use chrono::{DateTime, Utc};
@@ -110,6 +137,16 @@ pub struct PerformanceMetrics {
     pub total_tokens: i64,
     /// Tokens per second
     pub tokens_per_second: f64,
+    /// Average time to first token in milliseconds (streaming requests)
+    pub avg_ttft_ms: Option<f64>,
+    /// 50th percentile time to first token
+    pub p50_ttft_ms: Option<f64>,
+    /// 95th percentile time to first token
+    pub p95_ttft_ms: Option<f64>,
+    /// 99th percentile time to first token
+    pub p99_ttft_ms: Option<f64>,
+    /// Average output tokens per second while generating
+    pub avg_output_tokens_per_second: Option<f64>,
     /// Time series data
     pub time_series: Vec<PerformanceDataPoint>,
 }
@@ -124,6 +161,50 @@ pub struct PerformanceDataPoint {
     pub total_tokens: i64,
 }
 
+impl PerformanceMetrics {
+    /// Combine the metrics of adjacent time ranges into those of
+    /// `[start, end)`.
+    ///
+    /// Counts, averages and extremes are exact. Percentiles cannot be
+    /// combined, so they are kept only when one side has no requests.
+    pub fn merge(self, other: PerformanceMetrics, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        let (mut merged, other) = match (self.request_count, other.request_count) {
+            (_, 0) => (self, None),
+            (0, _) => (other, None),
+            _ => (self, Some(other)),
+        };
+
+        if let Some(other) = other {
+            let requests = merged.request_count + other.request_count;
+            let weighted = |a: f64, b: f64| {
+                (a * merged.request_count as f64 + b * other.request_count as f64) / requests as f64
+            };
+            merged.avg_latency_ms = weighted(merged.avg_latency_ms, other.avg_latency_ms);
+            merged.min_latency_ms = merged.min_latency_ms.min(other.min_latency_ms);
+            merged.max_latency_ms = merged.max_latency_ms.max(other.max_latency_ms);
+            merged.avg_ttft_ms = None;
+            merged.avg_output_tokens_per_second = None;
+            merged.p50_latency_ms = None;
+            merged.p95_latency_ms = None;
+            merged.p99_latency_ms = None;
+            merged.p50_ttft_ms = None;
+            merged.p95_ttft_ms = None;
+            merged.p99_ttft_ms = None;
+            merged.request_count = requests;
+            merged.total_tokens += other.total_tokens;
+            merged.time_series.extend(other.time_series);
+            merged.time_series.sort_by_key(|point| point.timestamp);
+        }
+
+        let duration_seconds = (end - start).num_seconds() as f64;
+        if duration_seconds > 0.0 {
+            merged.throughput_rps = merged.request_count as f64 / duration_seconds;
+            merged.tokens_per_second = merged.total_tokens as f64 / duration_seconds;
+        }
+        merged
+    }
+}
+
 /// Percentile metrics (computed from raw data)
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct PercentileMetrics {
@@ -132,6 +213,166 @@ pub struct PercentileMetrics {
     pub p99: Option<f64>,
 }
 
+/// TTFT regression query parameters
+#[derive(Debug, Deserialize, Clone)]
+pub struct TtftRegressionQuery {
+    /// End of the current week (defaults to now)
+    pub end_time: Option<DateTime<Utc>>,
+    /// Filter by provider
+    pub provider: Option<String>,
+    /// Filter by model
+    pub model: Option<String>,
+    /// Filter by environment
+    pub environment: Option<String>,
+    /// TTFT increase (in percent) that counts as a regression
+    #[serde(default = "default_regression_threshold_pct")]
+    pub threshold_pct: f64,
+    /// Streaming requests needed in both weeks to compare a model
+    #[serde(default = "default_regression_min_requests")]
+    pub min_requests: i64,
+}
+
+fn default_regression_threshold_pct() -> f64 {
+    20.0
+}
+
+fn default_regression_min_requests() -> i64 {
+    50
+}
+
+/// Time to first token and generation speed statistics.
+///
+/// TTFT is only recorded for streaming requests. Output tokens per second is
+/// `completion_tokens` over the generation time (`duration_ms - ttft_ms`,
+/// or the full duration for non-streaming requests).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LatencyBreakdownStats {
+    /// Number of requests
+    pub request_count: i64,
+    /// Number of requests with a recorded TTFT
+    pub streaming_request_count: i64,
+    /// Average time to first token in milliseconds
+    pub avg_ttft_ms: Option<f64>,
+    /// 50th percentile time to first token
+    pub p50_ttft_ms: Option<f64>,
+    /// 95th percentile time to first token
+    pub p95_ttft_ms: Option<f64>,
+    /// 99th percentile time to first token
+    pub p99_ttft_ms: Option<f64>,
+    /// Average output tokens per second
+    pub avg_output_tokens_per_second: Option<f64>,
+    /// Median output tokens per second
+    pub p50_output_tokens_per_second: Option<f64>,
+    /// 5th percentile output tokens per second (the slowest generations)
+    pub p5_output_tokens_per_second: Option<f64>,
+}
+
+/// Latency breakdown response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LatencyBreakdown {
+    /// Statistics across all matching requests
+    pub overall: LatencyBreakdownStats,
+    /// Statistics per model, busiest first
+    pub by_model: Vec<ModelLatencyBreakdown>,
+    /// TTFT and generation speed over time
+    pub time_series: Vec<LatencyBreakdownDataPoint>,
+}
+
+/// Latency breakdown for a single model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelLatencyBreakdown {
+    pub provider: String,
+    pub model: String,
+    #[serde(flatten)]
+    pub stats: LatencyBreakdownStats,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LatencyBreakdownDataPoint {
+    pub timestamp: DateTime<Utc>,
+    pub request_count: i64,
+    pub p50_ttft_ms: Option<f64>,
+    pub p95_ttft_ms: Option<f64>,
+    pub avg_output_tokens_per_second: Option<f64>,
+}
+
+/// Week-over-week TTFT regression report
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TtftRegressionReport {
+    /// Start of the current week
+    pub current_start: DateTime<Utc>,
+    /// End of the current week
+    pub current_end: DateTime<Utc>,
+    /// Start of the previous week
+    pub previous_start: DateTime<Utc>,
+    /// Increase (in percent) flagged as a regression
+    pub threshold_pct: f64,
+    /// Number of models flagged
+    pub regressed_count: usize,
+    /// Comparison per model, regressions first
+    pub models: Vec<TtftRegression>,
+}
+
+/// TTFT comparison for one model between two consecutive weeks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TtftRegression {
+    pub provider: String,
+    pub model: String,
+    pub current_p50_ttft_ms: Option<f64>,
+    pub previous_p50_ttft_ms: Option<f64>,
+    pub current_p95_ttft_ms: Option<f64>,
+    pub previous_p95_ttft_ms: Option<f64>,
+    /// Change in median TTFT, in percent
+    pub p50_change_pct: Option<f64>,
+    /// Change in p95 TTFT, in percent
+    pub p95_change_pct: Option<f64>,
+    pub current_streaming_requests: i64,
+    pub previous_streaming_requests: i64,
+    /// Whether the median or p95 TTFT rose by at least the threshold, with
+    /// enough requests in both weeks
+    pub regressed: bool,
+}
+
+impl TtftRegression {
+    /// Compare a model's TTFT this week against the previous week.
+    pub fn compare(
+        current: &ModelLatencyBreakdown,
+        previous: Option<&LatencyBreakdownStats>,
+        threshold_pct: f64,
+        min_requests: i64,
+    ) -> Self {
+        let empty = LatencyBreakdownStats::default();
+        let previous = previous.unwrap_or(&empty);
+        let change = |now: Option<f64>, before: Option<f64>| match (now, before) {
+            (Some(now), Some(before)) if before > 0.0 => Some((now - before) / before * 100.0),
+            _ => None,
+        };
+
+        let p50_change_pct = change(current.stats.p50_ttft_ms, previous.p50_ttft_ms);
+        let p95_change_pct = change(current.stats.p95_ttft_ms, previous.p95_ttft_ms);
+        let enough_data = current.stats.streaming_request_count >= min_requests
+            && previous.streaming_request_count >= min_requests;
+        let regressed = enough_data
+            && [p50_change_pct, p95_change_pct]
+                .iter()
+                .any(|pct| pct.is_some_and(|pct| pct >= threshold_pct));
+
+        Self {
+            provider: current.provider.clone(),
+            model: current.model.clone(),
+            current_p50_ttft_ms: current.stats.p50_ttft_ms,
+            previous_p50_ttft_ms: previous.p50_ttft_ms,
+            current_p95_ttft_ms: current.stats.p95_ttft_ms,
+            previous_p95_ttft_ms: previous.p95_ttft_ms,
+            p50_change_pct,
+            p95_change_pct,
+            current_streaming_requests: current.stats.streaming_request_count,
+            previous_streaming_requests: previous.streaming_request_count,
+            regressed,
+        }
+    }
+}
+
 /// Quality metrics response
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QualityMetrics {
@@ -214,6 +455,8 @@ pub struct ModelComparisonResult {
 pub struct ModelMetrics {
     pub avg_latency_ms: f64,
     pub p95_latency_ms: Option<f64>,
+    pub p95_ttft_ms: Option<f64>,
+    pub avg_output_tokens_per_second: Option<f64>,
     pub avg_cost_usd: f64,
     pub total_cost_usd: f64,
     pub success_rate: f64,
@@ -294,6 +537,14 @@ pub struct PerformanceRow {
     pub total_tokens: Option<i64>,
 }
 
+#[derive(Debug, sqlx::FromRow)]
+pub struct ModelLatencyBreakdownRow {
+    pub provider: String,
+    pub model: String,
+    #[sqlx(flatten)]
+    pub stats: LatencyBreakdownStats,
+}
+
 #[derive(Debug, sqlx::FromRow)]
 pub struct QualityRow {
     pub bucket: DateTime<Utc>,
@@ -326,6 +577,20 @@ pub struct AppState {
     pub db_pool: sqlx::PgPool,
     pub redis_client: redis::Client,
     pub cache_ttl: u64,
+    pub currency: std::sync::Arc<crate::services::currency::CurrencyService>,
+    pub provider_health: std::sync::Arc<crate::services::provider_health::ProviderHealthMonitor>,
+    pub data_access: std::sync::Arc<crate::services::data_access::DataAccessPolicy>,
+    pub trace_deletion: std::sync::Arc<crate::services::trace_deletion::TraceDeletionService>,
+    pub quarantine: std::sync::Arc<crate::services::quarantine::QuarantineService>,
+    pub query_cache: std::sync::Arc<crate::services::query_cache::QueryCache>,
+    pub webhooks: std::sync::Arc<crate::services::webhooks::WebhookService>,
+    pub admin: std::sync::Arc<crate::services::admin::AdminService>,
+    pub service_accounts: std::sync::Arc<crate::services::service_accounts::ServiceAccountService>,
+    pub pseudonyms: std::sync::Arc<crate::services::pseudonyms::PseudonymLookupService>,
+    pub federation: std::sync::Arc<crate::services::federation::FederationService>,
+    pub cold_storage: std::sync::Arc<crate::services::cold_storage::ColdStorageService>,
+    pub natural_query: std::sync::Arc<crate::services::natural_query::NaturalQueryService>,
+    pub model_registry: std::sync::Arc<crate::services::model_registry::ModelRegistryService>,
 }
 
 /// API error response
@@ -345,3 +610,49 @@ pub struct HealthResponse {
     pub redis: String,
     pub timestamp: DateTime<Utc>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn metrics(request_count: i64, avg_latency_ms: f64, min: i32, max: i32) -> PerformanceMetrics {
+        PerformanceMetrics {
+            request_count,
+            avg_latency_ms,
+            min_latency_ms: min,
+            max_latency_ms: max,
+            p50_latency_ms: Some(avg_latency_ms),
+            p95_latency_ms: Some(max as f64),
+            p99_latency_ms: Some(max as f64),
+            throughput_rps: 0.0,
+            total_tokens: request_count * 100,
+            tokens_per_second: 0.0,
+            avg_ttft_ms: None,
+            p50_ttft_ms: None,
+            p95_ttft_ms: None,
+            p99_ttft_ms: None,
+            avg_output_tokens_per_second: None,
+            time_series: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_merge_performance_metrics() {
+        let start = Utc.with_ymd_and_hms(2025, 10, 1, 0, 0, 0).unwrap();
+        let end = start + chrono::Duration::seconds(100);
+
+        let merged = metrics(300, 100.0, 20, 900).merge(metrics(100, 200.0, 10, 500), start, end);
+        assert_eq!(merged.request_count, 400);
+        assert_eq!(merged.avg_latency_ms, 125.0);
+        assert_eq!((merged.min_latency_ms, merged.max_latency_ms), (10, 900));
+        assert_eq!(merged.total_tokens, 40_000);
+        assert_eq!(merged.throughput_rps, 4.0);
+        assert_eq!(merged.p95_latency_ms, None);
+
+        // An empty side keeps the other side's percentiles
+        let merged = metrics(0, 0.0, 0, 0).merge(metrics(100, 200.0, 10, 500), start, end);
+        assert_eq!(merged.p95_latency_ms, Some(500.0));
+        assert_eq!(merged.min_latency_ms, 10);
+    }
+}
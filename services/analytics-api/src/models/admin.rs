@@ -0,0 +1,595 @@
+//! # Admin Data Models
+//!
+//! Request and response types for the admin API: organizations, teams,
+//! team membership and per-organization settings (retention, content
+//! capture policy and ingestion quotas), stored in the tables of migration
+//! `033_organizations.sql`, and service accounts with their tokens
+//! (`034_service_accounts.sql`).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Maximum length of organization IDs
+pub const MAX_ORG_ID_LENGTH: usize = 64;
+
+/// Maximum length of organization and team names
+pub const MAX_NAME_LENGTH: usize = 200;
+
+/// Longest retention an organization can set, in days
+pub const MAX_RETENTION_DAYS: i32 = 3650;
+
+/// Lifetime of service account tokens without `ttl_days`
+pub const DEFAULT_TOKEN_TTL_DAYS: i64 = 90;
+
+/// Longest lifetime of a service account token, in days
+pub const MAX_TOKEN_TTL_DAYS: i64 = 365;
+
+/// Permissions service accounts can't be granted
+const USER_ONLY_PERMISSIONS: [&str; 2] = ["manage:organizations", "manage:organization"];
+
+// ============================================================================
+// Enums
+// ============================================================================
+
+/// Role of a user in a team
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TeamRole {
+    /// Member of the team
+    #[default]
+    Member,
+    /// Manages the team's members
+    Maintainer,
+}
+
+impl TeamRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TeamRole::Member => "member",
+            TeamRole::Maintainer => "maintainer",
+        }
+    }
+}
+
+/// Prompt and response content kept for an organization's spans
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapturePolicy {
+    /// Keep the text the SDK captured
+    Full,
+    /// Lengths and message counts only
+    MetadataOnly,
+    /// No content or content statistics
+    None,
+}
+
+impl CapturePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CapturePolicy::Full => "full",
+            CapturePolicy::MetadataOnly => "metadata_only",
+            CapturePolicy::None => "none",
+        }
+    }
+}
+
+/// Handling of spans over an organization's quota
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverQuotaAction {
+    Drop,
+    Sample,
+    Queue,
+}
+
+impl OverQuotaAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OverQuotaAction::Drop => "drop",
+            OverQuotaAction::Sample => "sample",
+            OverQuotaAction::Queue => "queue",
+        }
+    }
+}
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for POST /api/v1/admin/organizations
+#[derive(Debug, Deserialize, Clone)]
+pub struct CreateOrganizationRequest {
+    /// Organization ID, as carried in the `org_id` of spans and tokens
+    pub id: String,
+    pub name: String,
+}
+
+impl CreateOrganizationRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_org_id(&self.id)?;
+        validate_name("Organization", &self.name)
+    }
+}
+
+/// Request for PATCH /api/v1/admin/organizations/:org_id
+#[derive(Debug, Deserialize, Clone)]
+pub struct UpdateOrganizationRequest {
+    pub name: String,
+}
+
+impl UpdateOrganizationRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_name("Organization", &self.name)
+    }
+}
+
+/// Request for POST /api/v1/admin/organizations/:org_id/teams
+#[derive(Debug, Deserialize, Clone)]
+pub struct CreateTeamRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl CreateTeamRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_name("Team", &self.name)
+    }
+}
+
+/// Request for PATCH /api/v1/admin/organizations/:org_id/teams/:team_id
+///
+/// Omitted fields are left unchanged.
+#[derive(Debug, Deserialize, Clone)]
+pub struct UpdateTeamRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+impl UpdateTeamRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(name) = &self.name {
+            validate_name("Team", name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Request for PUT /api/v1/admin/organizations/:org_id/teams/:team_id/members/:user_id
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct TeamMemberRequest {
+    /// Role in the team (default: member)
+    #[serde(default)]
+    pub role: TeamRole,
+}
+
+/// Request for PUT /api/v1/admin/organizations/:org_id/settings
+///
+/// Replaces all settings; null or omitted settings use the system default.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct OrganizationSettingsRequest {
+    /// Days traces and logs are kept
+    pub retention_days: Option<i32>,
+    /// Content capture enforced by the collector
+    pub capture_policy: Option<CapturePolicy>,
+    /// Daily span limit
+    pub spans_per_day_limit: Option<i64>,
+    /// Daily byte limit (JSON-encoded span size)
+    pub bytes_per_day_limit: Option<i64>,
+    /// Handling of spans over quota
+    pub over_quota_action: Option<OverQuotaAction>,
+}
+
+impl OrganizationSettingsRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(days) = self.retention_days {
+            if !(1..=MAX_RETENTION_DAYS).contains(&days) {
+                return Err(format!(
+                    "Retention must be between 1 and {} days, got {}",
+                    MAX_RETENTION_DAYS, days
+                ));
+            }
+        }
+
+        for (name, limit) in [
+            ("spans_per_day_limit", self.spans_per_day_limit),
+            ("bytes_per_day_limit", self.bytes_per_day_limit),
+        ] {
+            if limit.is_some_and(|limit| limit < 0) {
+                return Err(format!("{} must not be negative", name));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Request for POST /api/v1/admin/organizations/:org_id/service-accounts
+#[derive(Debug, Deserialize, Clone)]
+pub struct CreateServiceAccountRequest {
+    pub name: String,
+    pub description: Option<String>,
+    /// Permissions of the account's tokens, e.g. `["write:traces"]`
+    pub permissions: Vec<String>,
+    /// Projects the account's tokens can access
+    #[serde(default)]
+    pub projects: Vec<String>,
+}
+
+impl CreateServiceAccountRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_name("Service account", &self.name)?;
+        validate_permissions(&self.permissions)
+    }
+}
+
+/// Request for PATCH /api/v1/admin/organizations/:org_id/service-accounts/:account_id
+///
+/// Omitted fields are left unchanged. Changes apply to tokens already
+/// issued.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct UpdateServiceAccountRequest {
+    pub description: Option<String>,
+    pub permissions: Option<Vec<String>>,
+    pub projects: Option<Vec<String>>,
+    /// Disable (reject all tokens) or re-enable the account
+    pub disabled: Option<bool>,
+}
+
+impl UpdateServiceAccountRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(permissions) = &self.permissions {
+            validate_permissions(permissions)?;
+        }
+        Ok(())
+    }
+}
+
+/// Request for POST /api/v1/admin/organizations/:org_id/service-accounts/:account_id/tokens
+#[derive(Debug, Deserialize, Clone)]
+pub struct CreateServiceAccountTokenRequest {
+    /// Name identifying where the token is used, e.g. `collector-eu-1`
+    pub name: String,
+    /// Days until the token expires (default: 90)
+    pub ttl_days: Option<i64>,
+}
+
+impl CreateServiceAccountTokenRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_name("Token", &self.name)?;
+        if !(1..=MAX_TOKEN_TTL_DAYS).contains(&self.ttl_days()) {
+            return Err(format!(
+                "Token lifetime must be between 1 and {} days, got {}",
+                MAX_TOKEN_TTL_DAYS,
+                self.ttl_days()
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn ttl_days(&self) -> i64 {
+        self.ttl_days.unwrap_or(DEFAULT_TOKEN_TTL_DAYS)
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// An organization
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Organization {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Response for GET /api/v1/admin/organizations
+#[derive(Debug, Serialize)]
+pub struct OrganizationListResponse {
+    pub organizations: Vec<Organization>,
+}
+
+/// A team of an organization
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Team {
+    pub id: Uuid,
+    pub org_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub member_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Response for GET /api/v1/admin/organizations/:org_id/teams
+#[derive(Debug, Serialize)]
+pub struct TeamListResponse {
+    pub teams: Vec<Team>,
+}
+
+/// A user's membership of a team
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct TeamMember {
+    pub user_id: String,
+    /// `member` or `maintainer`
+    pub role: String,
+    pub added_at: DateTime<Utc>,
+}
+
+/// Response for GET /api/v1/admin/organizations/:org_id/teams/:team_id/members
+#[derive(Debug, Serialize)]
+pub struct TeamMemberListResponse {
+    pub members: Vec<TeamMember>,
+}
+
+/// Settings of an organization; null settings use the system default
+#[derive(Debug, Serialize, PartialEq, sqlx::FromRow)]
+pub struct OrganizationSettings {
+    pub org_id: String,
+    pub retention_days: Option<i32>,
+    /// `full`, `metadata_only` or `none`
+    pub capture_policy: Option<String>,
+    pub spans_per_day_limit: Option<i64>,
+    pub bytes_per_day_limit: Option<i64>,
+    /// `drop`, `sample` or `queue`
+    pub over_quota_action: Option<String>,
+    /// Last change; null when never set
+    pub updated_at: Option<DateTime<Utc>>,
+    /// User who made the last change
+    pub updated_by: Option<String>,
+}
+
+impl OrganizationSettings {
+    /// Settings of an organization that never set any
+    pub fn defaults(org_id: String) -> Self {
+        Self {
+            org_id,
+            retention_days: None,
+            capture_policy: None,
+            spans_per_day_limit: None,
+            bytes_per_day_limit: None,
+            over_quota_action: None,
+            updated_at: None,
+            updated_by: None,
+        }
+    }
+}
+
+/// A service account of an organization
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ServiceAccount {
+    pub id: Uuid,
+    pub org_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+    pub projects: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    /// Set while the account is disabled
+    pub disabled_at: Option<DateTime<Utc>>,
+}
+
+/// Response for GET /api/v1/admin/organizations/:org_id/service-accounts
+#[derive(Debug, Serialize)]
+pub struct ServiceAccountListResponse {
+    pub service_accounts: Vec<ServiceAccount>,
+}
+
+/// A token issued to a service account, without the token itself
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ServiceAccountToken {
+    /// The token's `jti`
+    pub id: Uuid,
+    pub service_account_id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revoked_by: Option<String>,
+}
+
+/// Response for GET /api/v1/admin/organizations/:org_id/service-accounts/:account_id/tokens
+#[derive(Debug, Serialize)]
+pub struct ServiceAccountTokenListResponse {
+    pub tokens: Vec<ServiceAccountToken>,
+}
+
+/// Response for POST /api/v1/admin/organizations/:org_id/service-accounts/:account_id/tokens
+///
+/// The token is only returned here; it can't be retrieved later.
+#[derive(Debug, Serialize)]
+pub struct IssuedServiceAccountToken {
+    /// Bearer token for the API and OTLP exporters
+    pub token: String,
+    #[serde(flatten)]
+    pub details: ServiceAccountToken,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Organization IDs are lowercase letters, digits, `-` and `_`, so they can
+/// be used in span attributes, tokens and URLs unchanged.
+pub fn validate_org_id(id: &str) -> Result<(), String> {
+    if id.is_empty() || id.len() > MAX_ORG_ID_LENGTH {
+        return Err(format!(
+            "Organization ID must be 1 to {} characters",
+            MAX_ORG_ID_LENGTH
+        ));
+    }
+
+    if !id
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Organization ID may only contain lowercase letters, digits, '-' and '_', got '{}'",
+            id
+        ));
+    }
+
+    Ok(())
+}
+
+/// Service account permissions are `action:resource` permissions other than
+/// the organization management ones; the admin wildcard can't be granted.
+pub fn validate_permissions(permissions: &[String]) -> Result<(), String> {
+    if permissions.is_empty() {
+        return Err("Service accounts need at least one permission".to_string());
+    }
+
+    for permission in permissions {
+        let valid = permission
+            .split_once(':')
+            .is_some_and(|(action, resource)| {
+                [action, resource].iter().all(|part| {
+                    !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+                })
+            });
+        if !valid {
+            return Err(format!(
+                "Invalid permission '{}', expected e.g. 'write:traces'",
+                permission
+            ));
+        }
+        if USER_ONLY_PERMISSIONS.contains(&permission.as_str()) {
+            return Err(format!(
+                "Service accounts can't be granted '{}'",
+                permission
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_name(kind: &str, name: &str) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("{} name must not be empty", kind));
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(format!(
+            "{} name must be at most {} characters",
+            kind, MAX_NAME_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_organization_validation() {
+        let valid = CreateOrganizationRequest {
+            id: "acme-prod_2".to_string(),
+            name: "Acme".to_string(),
+        };
+        assert!(valid.validate().is_ok());
+
+        for id in ["", "Acme", "acme corp", &"a".repeat(65)] {
+            let request = CreateOrganizationRequest {
+                id: id.to_string(),
+                ..valid.clone()
+            };
+            assert!(request.validate().is_err(), "{:?} should be rejected", id);
+        }
+
+        assert!(UpdateOrganizationRequest {
+            name: "  ".to_string()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_settings_validation() {
+        let settings: OrganizationSettingsRequest = serde_json::from_value(serde_json::json!({
+            "retention_days": 30,
+            "capture_policy": "metadata_only",
+            "spans_per_day_limit": 1000000,
+            "over_quota_action": "sample"
+        }))
+        .unwrap();
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.capture_policy, Some(CapturePolicy::MetadataOnly));
+        assert_eq!(settings.bytes_per_day_limit, None);
+
+        let too_long = OrganizationSettingsRequest {
+            retention_days: Some(MAX_RETENTION_DAYS + 1),
+            ..Default::default()
+        };
+        assert!(too_long.validate().is_err());
+
+        let negative = OrganizationSettingsRequest {
+            bytes_per_day_limit: Some(-1),
+            ..Default::default()
+        };
+        assert!(negative.validate().is_err());
+
+        assert!(OrganizationSettingsRequest::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_service_account_permissions() {
+        let request = CreateServiceAccountRequest {
+            name: "collector".to_string(),
+            description: None,
+            permissions: vec!["write:traces".to_string()],
+            projects: vec![],
+        };
+        assert!(request.validate().is_ok());
+
+        for permissions in [
+            vec![],
+            vec!["*".to_string()],
+            vec!["traces".to_string()],
+            vec![
+                "write:traces".to_string(),
+                "manage:organization".to_string(),
+            ],
+        ] {
+            let request = CreateServiceAccountRequest {
+                permissions: permissions.clone(),
+                ..request.clone()
+            };
+            assert!(
+                request.validate().is_err(),
+                "{:?} should be rejected",
+                permissions
+            );
+        }
+    }
+
+    #[test]
+    fn test_token_lifetime() {
+        let request: CreateServiceAccountTokenRequest =
+            serde_json::from_str(r#"{"name": "collector-eu-1"}"#).unwrap();
+        assert!(request.validate().is_ok());
+        assert_eq!(request.ttl_days(), DEFAULT_TOKEN_TTL_DAYS);
+
+        for ttl_days in [0, MAX_TOKEN_TTL_DAYS + 1] {
+            let request = CreateServiceAccountTokenRequest {
+                ttl_days: Some(ttl_days),
+                ..request.clone()
+            };
+            assert!(request.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_team_member_role_defaults_to_member() {
+        let request: TeamMemberRequest = serde_json::from_str("{}").unwrap();
+        assert_eq!(request.role, TeamRole::Member);
+
+        let request: TeamMemberRequest = serde_json::from_str(r#"{"role": "maintainer"}"#).unwrap();
+        assert_eq!(request.role.as_str(), "maintainer");
+    }
+}
@@ -0,0 +1,160 @@
+//! # Audit Log Data Models
+//!
+//! Data structures for the API audit log (`audit_log`), which records every
+//! authenticated API call, and the data access audit log
+//! (`data_access_audit_log`), which records every response that returned
+//! masked-by-policy trace fields (prompt/response text, user identifiers)
+//! unmasked.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for GET /api/v1/audit/data-access
+#[derive(Debug, Deserialize, Clone)]
+pub struct DataAccessAuditQuery {
+    /// Start time (default: 7 days ago)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End time (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Only entries for this user
+    pub user_id: Option<String>,
+
+    /// Maximum entries (default: 100, max: 1000)
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+impl DataAccessAuditQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            if start >= end {
+                return Err("Start time must be before end time".to_string());
+            }
+        }
+
+        if self.limit < 1 || self.limit > 1000 {
+            return Err(format!(
+                "Limit must be between 1 and 1000, got {}",
+                self.limit
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Audit log entry for a response that returned unmasked data
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DataAccessAuditEntry {
+    pub ts: DateTime<Utc>,
+    pub org_id: String,
+    pub user_id: String,
+    pub role: String,
+    pub request_id: String,
+    /// Endpoint that returned the data, e.g. `GET /api/v1/traces`
+    pub endpoint: String,
+    /// Fields returned unmasked
+    pub fields: Vec<String>,
+    /// Traces in the response (at most 1000)
+    pub trace_ids: Vec<String>,
+}
+
+/// Response for GET /api/v1/audit/data-access
+#[derive(Debug, Serialize)]
+pub struct DataAccessAuditResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub entries: Vec<DataAccessAuditEntry>,
+}
+
+// ============================================================================
+// API Audit Log
+// ============================================================================
+
+/// Request for GET /api/v1/audit
+#[derive(Debug, Deserialize, Clone)]
+pub struct AuditLogQuery {
+    /// Start time (default: 24 hours ago)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End time (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Only calls by this user
+    pub user_id: Option<String>,
+
+    /// Only calls to this route, e.g. `/api/v1/traces/:trace_id`
+    pub endpoint: Option<String>,
+
+    /// Only calls with at least this status code, e.g. 400 for failures
+    pub min_status: Option<i32>,
+
+    /// Maximum entries (default: 100, max: 1000)
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+impl AuditLogQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            if start >= end {
+                return Err("Start time must be before end time".to_string());
+            }
+        }
+
+        if self.limit < 1 || self.limit > 1000 {
+            return Err(format!(
+                "Limit must be between 1 and 1000, got {}",
+                self.limit
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// One authenticated API call
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AuditLogEntry {
+    pub ts: DateTime<Utc>,
+    pub request_id: String,
+    pub org_id: String,
+    pub user_id: String,
+    pub role: String,
+    pub auth_method: String,
+    pub method: String,
+    /// Matched route, e.g. `/api/v1/traces/:trace_id`
+    pub endpoint: String,
+    /// Request path
+    pub path: String,
+    /// Query string parameters
+    pub query_params: Option<serde_json::Value>,
+    /// JSON request body (search filters, ...), if small enough to keep
+    pub request_body: Option<serde_json::Value>,
+    pub status_code: i32,
+    /// Rows returned, when the response is a list or has a `data` list
+    pub row_count: Option<i64>,
+    pub duration_ms: i64,
+}
+
+/// Response for GET /api/v1/audit
+#[derive(Debug, Serialize)]
+pub struct AuditLogResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub entries: Vec<AuditLogEntry>,
+}
@@ -0,0 +1,358 @@
+//! # Provider Concurrency Data Models
+//!
+//! Data structures for `GET /api/v1/concurrency`, which reports how many
+//! requests each provider had in flight over time, how long they waited
+//! for the provider's first byte, and when the provider was saturated.
+//!
+//! Queue times are read from the `queue_ms` and `execution_ms` columns of
+//! `llm_traces`, filled from the span attributes recorded by the collector's
+//! queue timing processor. Requests in flight are counted from each request's
+//! start (`ts`) and duration.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Default analysis window in hours
+const DEFAULT_WINDOW_HOURS: i64 = 24;
+
+/// Longest analysis window in days
+const MAX_WINDOW_DAYS: i64 = 7;
+
+/// Default factor over the baseline queue time at which a provider is saturated
+pub const DEFAULT_SATURATION_FACTOR: f64 = 2.0;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Time bucket of the concurrency series
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConcurrencyInterval {
+    #[default]
+    Minute,
+    Hour,
+}
+
+impl ConcurrencyInterval {
+    /// Unit for `date_trunc`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConcurrencyInterval::Minute => "minute",
+            ConcurrencyInterval::Hour => "hour",
+        }
+    }
+
+    /// Length of a bucket in milliseconds
+    pub fn millis(&self) -> i64 {
+        match self {
+            ConcurrencyInterval::Minute => 60_000,
+            ConcurrencyInterval::Hour => 3_600_000,
+        }
+    }
+}
+
+/// Query parameters for GET /api/v1/concurrency
+#[derive(Debug, Deserialize, Clone)]
+pub struct ConcurrencyRequest {
+    /// Start of the analysis window (default: 24 hours before end_time)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End of the analysis window (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub environment: Option<String>,
+
+    /// Bucket of the series (default: minute)
+    #[serde(default)]
+    pub interval: ConcurrencyInterval,
+
+    /// Factor over the provider's baseline queue time at which a bucket is
+    /// saturated (default: 2.0)
+    #[serde(default = "default_saturation_factor")]
+    pub saturation_factor: f64,
+}
+
+fn default_saturation_factor() -> f64 {
+    DEFAULT_SATURATION_FACTOR
+}
+
+impl ConcurrencyRequest {
+    /// Validate the request and resolve the analysis window
+    pub fn validate(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+        let end_time = self.end_time.unwrap_or_else(Utc::now);
+        let start_time = self
+            .start_time
+            .unwrap_or(end_time - Duration::hours(DEFAULT_WINDOW_HOURS));
+
+        if start_time >= end_time {
+            return Err("Start time must be before end time".to_string());
+        }
+
+        if end_time - start_time > Duration::days(MAX_WINDOW_DAYS) {
+            return Err(format!("Time range cannot exceed {} days", MAX_WINDOW_DAYS));
+        }
+
+        if !self.saturation_factor.is_finite() || self.saturation_factor <= 1.0 {
+            return Err("Saturation factor must be greater than 1".to_string());
+        }
+
+        Ok((start_time, end_time))
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for GET /api/v1/concurrency
+#[derive(Debug, Clone, Serialize)]
+pub struct ConcurrencyResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub interval: ConcurrencyInterval,
+    pub saturation_factor: f64,
+
+    /// Providers, most saturated first
+    pub providers: Vec<ProviderConcurrency>,
+}
+
+/// Concurrency and queue time of one provider
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProviderConcurrency {
+    pub provider: String,
+    pub request_count: i64,
+
+    /// Most requests in flight at once
+    pub peak_concurrency: i64,
+
+    /// Median of the buckets' average queue time; unknown without timed requests
+    pub baseline_queue_ms: Option<f64>,
+
+    /// Buckets where requests queued or were rate limited
+    pub saturated_buckets: usize,
+
+    /// Share of buckets that were saturated
+    pub saturation_rate: f64,
+
+    /// Lowest peak concurrency of the saturated buckets: how many requests
+    /// in flight the provider's limits currently allow before queuing
+    pub saturation_concurrency: Option<i64>,
+
+    /// Requests that failed with a rate limit error
+    pub rate_limited_count: i64,
+
+    pub buckets: Vec<ConcurrencyBucket>,
+}
+
+/// Requests of one provider in one bucket
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConcurrencyBucket {
+    pub bucket: DateTime<Utc>,
+
+    /// Requests started in the bucket
+    pub request_count: i64,
+
+    /// Average requests in flight: their total duration over the bucket length
+    pub mean_concurrency: f64,
+
+    /// Most requests in flight at once
+    pub peak_concurrency: i64,
+
+    /// Requests with a queue time
+    pub timed_count: i64,
+    pub avg_queue_ms: Option<f64>,
+    pub p95_queue_ms: Option<f64>,
+    pub avg_execution_ms: Option<f64>,
+
+    pub rate_limited_count: i64,
+
+    /// Queue time at least `saturation_factor` times the baseline, or
+    /// requests rate limited
+    pub saturated: bool,
+}
+
+// ============================================================================
+// Database Row Types
+// ============================================================================
+
+/// Requests of one provider in one bucket
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ConcurrencyRow {
+    pub provider: String,
+    pub bucket: DateTime<Utc>,
+    pub request_count: i64,
+    pub total_duration_ms: i64,
+    pub peak_concurrency: i64,
+    pub timed_count: i64,
+    pub avg_queue_ms: Option<f64>,
+    pub p95_queue_ms: Option<f64>,
+    pub avg_execution_ms: Option<f64>,
+    pub rate_limited_count: i64,
+}
+
+/// Median of unsorted values
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let n = values.len();
+    Some((values[(n - 1) / 2] + values[n / 2]) / 2.0)
+}
+
+/// Group rows by provider, flag saturated buckets against each provider's
+/// baseline queue time, and summarize each provider
+pub fn build_report(
+    rows: Vec<ConcurrencyRow>,
+    interval: ConcurrencyInterval,
+    saturation_factor: f64,
+) -> Vec<ProviderConcurrency> {
+    let mut by_provider: Vec<(String, Vec<ConcurrencyRow>)> = Vec::new();
+    for row in rows {
+        match by_provider.iter_mut().find(|(p, _)| *p == row.provider) {
+            Some((_, rows)) => rows.push(row),
+            None => by_provider.push((row.provider.clone(), vec![row])),
+        }
+    }
+
+    let mut providers: Vec<ProviderConcurrency> = by_provider
+        .into_iter()
+        .map(|(provider, mut rows)| {
+            rows.sort_by_key(|row| row.bucket);
+            let baseline_queue_ms = median(rows.iter().filter_map(|r| r.avg_queue_ms).collect());
+
+            let buckets: Vec<ConcurrencyBucket> = rows
+                .into_iter()
+                .map(|row| {
+                    let queued = match (row.avg_queue_ms, baseline_queue_ms) {
+                        (Some(queue_ms), Some(baseline)) => {
+                            queue_ms >= baseline * saturation_factor && queue_ms > 0.0
+                        }
+                        _ => false,
+                    };
+                    ConcurrencyBucket {
+                        bucket: row.bucket,
+                        request_count: row.request_count,
+                        mean_concurrency: row.total_duration_ms as f64 / interval.millis() as f64,
+                        peak_concurrency: row.peak_concurrency,
+                        timed_count: row.timed_count,
+                        avg_queue_ms: row.avg_queue_ms,
+                        p95_queue_ms: row.p95_queue_ms,
+                        avg_execution_ms: row.avg_execution_ms,
+                        rate_limited_count: row.rate_limited_count,
+                        saturated: queued || row.rate_limited_count > 0,
+                    }
+                })
+                .collect();
+
+            let saturated: Vec<&ConcurrencyBucket> =
+                buckets.iter().filter(|b| b.saturated).collect();
+            ProviderConcurrency {
+                provider,
+                request_count: buckets.iter().map(|b| b.request_count).sum(),
+                peak_concurrency: buckets
+                    .iter()
+                    .map(|b| b.peak_concurrency)
+                    .max()
+                    .unwrap_or(0),
+                baseline_queue_ms,
+                saturated_buckets: saturated.len(),
+                saturation_rate: if buckets.is_empty() {
+                    0.0
+                } else {
+                    saturated.len() as f64 / buckets.len() as f64
+                },
+                saturation_concurrency: saturated.iter().map(|b| b.peak_concurrency).min(),
+                rate_limited_count: buckets.iter().map(|b| b.rate_limited_count).sum(),
+                buckets,
+            }
+        })
+        .collect();
+
+    providers.sort_by(|a, b| {
+        b.saturation_rate
+            .total_cmp(&a.saturation_rate)
+            .then(b.request_count.cmp(&a.request_count))
+    });
+    providers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(
+        provider: &str,
+        minute: i64,
+        peak: i64,
+        queue_ms: f64,
+        rate_limited: i64,
+    ) -> ConcurrencyRow {
+        ConcurrencyRow {
+            provider: provider.to_string(),
+            bucket: DateTime::<Utc>::UNIX_EPOCH + Duration::minutes(minute),
+            request_count: 10,
+            total_duration_ms: 120_000,
+            peak_concurrency: peak,
+            timed_count: 10,
+            avg_queue_ms: Some(queue_ms),
+            p95_queue_ms: Some(queue_ms * 2.0),
+            avg_execution_ms: Some(1500.0),
+            rate_limited_count: rate_limited,
+        }
+    }
+
+    #[test]
+    fn test_build_report() {
+        let rows = vec![
+            row("openai", 2, 40, 900.0, 0),
+            row("openai", 0, 8, 200.0, 0),
+            row("openai", 1, 12, 250.0, 0),
+            row("openai", 3, 30, 300.0, 2),
+            row("anthropic", 0, 5, 400.0, 0),
+        ];
+
+        let providers = build_report(rows, ConcurrencyInterval::Minute, DEFAULT_SATURATION_FACTOR);
+
+        assert_eq!(providers.len(), 2);
+        let openai = &providers[0];
+        assert_eq!(openai.provider, "openai");
+        assert_eq!(openai.request_count, 40);
+        assert_eq!(openai.peak_concurrency, 40);
+        assert_eq!(openai.baseline_queue_ms, Some(275.0));
+
+        // Minute 2 queued, minute 3 was rate limited
+        let saturated: Vec<bool> = openai.buckets.iter().map(|b| b.saturated).collect();
+        assert_eq!(saturated, [false, false, true, true]);
+        assert_eq!(openai.saturated_buckets, 2);
+        assert_eq!(openai.saturation_rate, 0.5);
+        assert_eq!(openai.saturation_concurrency, Some(30));
+        assert_eq!(openai.rate_limited_count, 2);
+        assert_eq!(openai.buckets[0].mean_concurrency, 2.0);
+
+        let anthropic = &providers[1];
+        assert_eq!(anthropic.saturated_buckets, 0);
+        assert_eq!(anthropic.saturation_concurrency, None);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut request = ConcurrencyRequest {
+            start_time: None,
+            end_time: None,
+            provider: None,
+            model: None,
+            environment: None,
+            interval: ConcurrencyInterval::default(),
+            saturation_factor: default_saturation_factor(),
+        };
+        let (start, end) = request.validate().unwrap();
+        assert_eq!(end - start, Duration::hours(24));
+
+        request.saturation_factor = 1.0;
+        assert!(request.validate().is_err());
+    }
+}
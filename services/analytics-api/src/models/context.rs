@@ -0,0 +1,472 @@
+//! # Context-Window Utilization Data Models
+//!
+//! Data structures for the context-window endpoints, which relate each
+//! request's prompt tokens to the context window of its model. The context
+//! window is the `llm_observatory.model.context_window` attribute set by the
+//! collector's enrichment processor, falling back to the bundled model
+//! metadata; requests to models with an unknown context window are counted
+//! but not analyzed.
+//!
+//! Utilization is `prompt_tokens / context_window`, reported in ten bands of
+//! 10% each; prompts over the window count in the last band.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Default analysis window in days
+const DEFAULT_WINDOW_DAYS: i64 = 7;
+
+/// Longest analysis window in days
+const MAX_WINDOW_DAYS: i64 = 90;
+
+/// Number of utilization bands
+pub const UTILIZATION_BANDS: usize = 10;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Heatmap time bucket
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapInterval {
+    #[default]
+    Hour,
+    Day,
+}
+
+impl HeatmapInterval {
+    /// Unit for `date_trunc`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HeatmapInterval::Hour => "hour",
+            HeatmapInterval::Day => "day",
+        }
+    }
+}
+
+/// Query parameters for the context-window endpoints
+#[derive(Debug, Deserialize, Clone)]
+pub struct ContextUtilizationRequest {
+    /// Start of the analysis window (default: 7 days before end_time)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End of the analysis window (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub environment: Option<String>,
+
+    /// Utilization from which a request is at risk of truncation
+    #[serde(default = "default_risk_threshold")]
+    pub risk_threshold: f64,
+
+    /// Templates whose p95 utilization is below this waste context
+    #[serde(default = "default_waste_threshold")]
+    pub waste_threshold: f64,
+
+    /// Fewest requests a template needs to be reported
+    #[serde(default = "default_min_requests")]
+    pub min_requests: i64,
+
+    /// Heatmap bucket (default: hour)
+    #[serde(default)]
+    pub interval: HeatmapInterval,
+
+    /// Maximum templates returned
+    #[serde(default = "default_template_limit")]
+    pub limit: i64,
+}
+
+fn default_risk_threshold() -> f64 {
+    0.9
+}
+
+fn default_waste_threshold() -> f64 {
+    0.2
+}
+
+fn default_min_requests() -> i64 {
+    50
+}
+
+fn default_template_limit() -> i64 {
+    50
+}
+
+impl ContextUtilizationRequest {
+    /// Validate the request and resolve the analysis window
+    pub fn validate(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+        let end_time = self.end_time.unwrap_or_else(Utc::now);
+        let start_time = self
+            .start_time
+            .unwrap_or(end_time - Duration::days(DEFAULT_WINDOW_DAYS));
+
+        if start_time >= end_time {
+            return Err("Start time must be before end time".to_string());
+        }
+
+        if end_time - start_time > Duration::days(MAX_WINDOW_DAYS) {
+            return Err(format!("Time range cannot exceed {} days", MAX_WINDOW_DAYS));
+        }
+
+        for (name, value) in [
+            ("risk_threshold", self.risk_threshold),
+            ("waste_threshold", self.waste_threshold),
+        ] {
+            if !value.is_finite() || value <= 0.0 || value > 1.0 {
+                return Err(format!("{} must be in (0, 1]", name));
+            }
+        }
+
+        if self.min_requests < 1 {
+            return Err("min_requests must be at least 1".to_string());
+        }
+
+        if !(1..=500).contains(&self.limit) {
+            return Err("Limit must be between 1 and 500".to_string());
+        }
+
+        Ok((start_time, end_time))
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for GET /api/v1/context/utilization
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextUtilizationResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub risk_threshold: f64,
+    pub models: Vec<ModelContextUtilization>,
+
+    /// Requests to models with an unknown context window
+    pub unknown_context_requests: i64,
+}
+
+/// Prompt sizes of one model relative to its context window
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelContextUtilization {
+    pub provider: String,
+    pub model: String,
+    pub context_window: i64,
+    pub request_count: i64,
+    pub avg_prompt_tokens: f64,
+    pub p50_prompt_tokens: f64,
+    pub p95_prompt_tokens: f64,
+    pub p99_prompt_tokens: f64,
+    pub max_prompt_tokens: f64,
+    pub p50_utilization: f64,
+    pub p95_utilization: f64,
+
+    /// Requests at or above the risk threshold
+    pub at_risk_count: i64,
+    pub at_risk_rate: f64,
+
+    /// Prompts larger than the context window
+    pub over_limit_count: i64,
+
+    /// Responses cut off at the token limit (`finish_reason = length`)
+    pub length_finish_count: i64,
+
+    /// Requests per utilization band
+    pub distribution: Vec<UtilizationBand>,
+}
+
+/// Requests whose utilization is in `[from, to)`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UtilizationBand {
+    pub from: f64,
+    pub to: f64,
+    pub request_count: i64,
+}
+
+/// Response for GET /api/v1/context/heatmap
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextHeatmapResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub interval: HeatmapInterval,
+
+    /// Lower bound of each column's utilization band
+    pub bands: Vec<f64>,
+    pub rows: Vec<HeatmapRow>,
+}
+
+/// Requests per utilization band in one time bucket
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeatmapRow {
+    pub bucket: DateTime<Utc>,
+    pub request_counts: Vec<i64>,
+    pub prompt_tokens: i64,
+}
+
+/// Response for GET /api/v1/context/templates
+#[derive(Debug, Clone, Serialize)]
+pub struct WastefulTemplatesResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub waste_threshold: f64,
+    pub min_requests: i64,
+    pub templates: Vec<WastefulTemplate>,
+}
+
+/// A template whose prompts leave most of the model's context unused
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct WastefulTemplate {
+    /// Prompt template, or span name of spans without one
+    pub workload: String,
+    pub provider: String,
+    pub model: String,
+    pub context_window: f64,
+    pub request_count: i64,
+    pub avg_prompt_tokens: f64,
+    pub p95_prompt_tokens: f64,
+    pub p95_utilization: f64,
+
+    /// Context left unused by the p95 prompt
+    pub unused_context_tokens: f64,
+    pub prompt_cost_usd: Option<f64>,
+}
+
+// ============================================================================
+// Database Row Types
+// ============================================================================
+
+/// Prompt statistics of one model
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ModelUtilizationRow {
+    pub provider: String,
+    pub model: String,
+    pub context_window: f64,
+    pub request_count: i64,
+    pub avg_prompt_tokens: f64,
+    pub p50_prompt_tokens: f64,
+    pub p95_prompt_tokens: f64,
+    pub p99_prompt_tokens: f64,
+    pub max_prompt_tokens: f64,
+    pub p50_utilization: f64,
+    pub p95_utilization: f64,
+    pub at_risk_count: i64,
+    pub over_limit_count: i64,
+    pub length_finish_count: i64,
+}
+
+/// Requests of one model in one utilization band; `band` is None for
+/// requests to models with an unknown context window
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct BandRow {
+    pub provider: String,
+    pub model: String,
+    pub band: Option<i32>,
+    pub request_count: i64,
+}
+
+/// Requests in one utilization band and time bucket
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct HeatmapCellRow {
+    pub bucket: DateTime<Utc>,
+    pub band: i32,
+    pub request_count: i64,
+    pub prompt_tokens: i64,
+}
+
+/// Lower bounds of the utilization bands
+pub fn band_bounds() -> Vec<f64> {
+    (0..UTILIZATION_BANDS)
+        .map(|band| band as f64 / UTILIZATION_BANDS as f64)
+        .collect()
+}
+
+/// Combine per-model statistics with their band counts. Returns the models
+/// and the number of requests to models with an unknown context window.
+pub fn build_utilization(
+    rows: Vec<ModelUtilizationRow>,
+    bands: &[BandRow],
+) -> (Vec<ModelContextUtilization>, i64) {
+    let mut counts: BTreeMap<(&str, &str), Vec<i64>> = BTreeMap::new();
+    let mut unknown_context_requests = 0;
+    for row in bands {
+        match row.band {
+            Some(band) => {
+                let band = (band.max(0) as usize).min(UTILIZATION_BANDS - 1);
+                counts
+                    .entry((row.provider.as_str(), row.model.as_str()))
+                    .or_insert_with(|| vec![0; UTILIZATION_BANDS])[band] += row.request_count;
+            }
+            None => unknown_context_requests += row.request_count,
+        }
+    }
+
+    let models = rows
+        .into_iter()
+        .map(|row| {
+            let band_counts = counts
+                .get(&(row.provider.as_str(), row.model.as_str()))
+                .cloned()
+                .unwrap_or_else(|| vec![0; UTILIZATION_BANDS]);
+            let distribution = band_bounds()
+                .into_iter()
+                .zip(band_counts)
+                .map(|(from, request_count)| UtilizationBand {
+                    from,
+                    to: from + 1.0 / UTILIZATION_BANDS as f64,
+                    request_count,
+                })
+                .collect();
+
+            ModelContextUtilization {
+                at_risk_rate: if row.request_count > 0 {
+                    row.at_risk_count as f64 / row.request_count as f64
+                } else {
+                    0.0
+                },
+                provider: row.provider,
+                model: row.model,
+                context_window: row.context_window as i64,
+                request_count: row.request_count,
+                avg_prompt_tokens: row.avg_prompt_tokens,
+                p50_prompt_tokens: row.p50_prompt_tokens,
+                p95_prompt_tokens: row.p95_prompt_tokens,
+                p99_prompt_tokens: row.p99_prompt_tokens,
+                max_prompt_tokens: row.max_prompt_tokens,
+                p50_utilization: row.p50_utilization,
+                p95_utilization: row.p95_utilization,
+                at_risk_count: row.at_risk_count,
+                over_limit_count: row.over_limit_count,
+                length_finish_count: row.length_finish_count,
+                distribution,
+            }
+        })
+        .collect();
+
+    (models, unknown_context_requests)
+}
+
+/// Pivot heatmap cells into one row per time bucket
+pub fn build_heatmap(cells: &[HeatmapCellRow]) -> Vec<HeatmapRow> {
+    let mut rows: BTreeMap<DateTime<Utc>, HeatmapRow> = BTreeMap::new();
+    for cell in cells {
+        let row = rows.entry(cell.bucket).or_insert_with(|| HeatmapRow {
+            bucket: cell.bucket,
+            request_counts: vec![0; UTILIZATION_BANDS],
+            prompt_tokens: 0,
+        });
+        let band = (cell.band.max(0) as usize).min(UTILIZATION_BANDS - 1);
+        row.request_counts[band] += cell.request_count;
+        row.prompt_tokens += cell.prompt_tokens;
+    }
+    rows.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn request() -> ContextUtilizationRequest {
+        ContextUtilizationRequest {
+            start_time: None,
+            end_time: None,
+            provider: None,
+            model: None,
+            environment: None,
+            risk_threshold: default_risk_threshold(),
+            waste_threshold: default_waste_threshold(),
+            min_requests: default_min_requests(),
+            interval: HeatmapInterval::default(),
+            limit: default_template_limit(),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let (start, end) = request().validate().unwrap();
+        assert_eq!(end - start, Duration::days(7));
+
+        let mut invalid = request();
+        invalid.risk_threshold = 1.5;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = request();
+        invalid.waste_threshold = 0.0;
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_build_utilization() {
+        let row = ModelUtilizationRow {
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            context_window: 128_000.0,
+            request_count: 100,
+            avg_prompt_tokens: 20_000.0,
+            p50_prompt_tokens: 12_000.0,
+            p95_prompt_tokens: 120_000.0,
+            p99_prompt_tokens: 127_000.0,
+            max_prompt_tokens: 130_000.0,
+            p50_utilization: 0.094,
+            p95_utilization: 0.94,
+            at_risk_count: 8,
+            over_limit_count: 1,
+            length_finish_count: 3,
+        };
+        let band = |band: Option<i32>, request_count: i64| BandRow {
+            provider: "openai".to_string(),
+            model: (if band.is_some() {
+                "gpt-4o"
+            } else {
+                "custom-model"
+            })
+            .to_string(),
+            band,
+            request_count,
+        };
+        let bands = [
+            band(Some(0), 60),
+            band(Some(1), 32),
+            band(Some(9), 8),
+            band(None, 15),
+        ];
+
+        let (models, unknown) = build_utilization(vec![row], &bands);
+        assert_eq!(unknown, 15);
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].context_window, 128_000);
+        assert_eq!(models[0].at_risk_rate, 0.08);
+        let counts: Vec<i64> = models[0]
+            .distribution
+            .iter()
+            .map(|b| b.request_count)
+            .collect();
+        assert_eq!(counts, [60, 32, 0, 0, 0, 0, 0, 0, 0, 8]);
+        assert_eq!(models[0].distribution[9].from, 0.9);
+    }
+
+    #[test]
+    fn test_build_heatmap() {
+        let hour = |h| Utc.with_ymd_and_hms(2025, 1, 1, h, 0, 0).unwrap();
+        let cell = |h, band, request_count, prompt_tokens| HeatmapCellRow {
+            bucket: hour(h),
+            band,
+            request_count,
+            prompt_tokens,
+        };
+        let rows = build_heatmap(&[
+            cell(11, 2, 5, 900),
+            cell(10, 0, 3, 100),
+            cell(10, 9, 1, 9000),
+        ]);
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].bucket, hour(10));
+        assert_eq!(rows[0].request_counts, [3, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(rows[0].prompt_tokens, 9100);
+        assert_eq!(rows[1].request_counts[2], 5);
+    }
+}
@@ -5,7 +5,9 @@
 //! ## Endpoints
 //! - `GET /api/v1/costs/summary` - Comprehensive cost summary with trends
 //! - `GET /api/v1/costs/attribution` - Cost attribution by user, team, tag
-//! - `GET /api/v1/costs/forecast` - Cost forecasting with linear regression
+//! - `GET /api/v1/costs/forecast` - Seasonality-aware cost forecasting
+//! - `GET /api/v1/costs/explain` - Cost change decomposition between two periods
+//! - `GET /api/v1/costs/chargeback` - Monthly invoices per cost-allocation tag
 //! - `GET /api/v1/costs/budgets` - Budget management and alerts
 //! - `GET /api/v1/costs/budgets/{id}/history` - Budget alert history
 //!
@@ -13,7 +15,8 @@
 //! - Detailed cost breakdowns (by provider, model, user, team, tag)
 //! - Trend analysis (daily, weekly, monthly)
 //! - Top expensive traces identification
-//! - Linear regression-based forecasting
+//! - Forecasting with weekly seasonality, model selection and backtesting
+//! - Volume vs. price decomposition of cost changes
 //! - Budget threshold monitoring
 //! - Alert history tracking
 //!
@@ -22,9 +25,14 @@
 //! - Organization-level data isolation
 //! - SQL injection prevention via parameterized queries
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use crate::models::federation::{FederationInfo, Scope};
+use crate::services::currency::{FxQuote, BASE_CURRENCY};
+use crate::services::forecasting::{ForecastModel, ModelSelection, SelectionCriterion};
+use crate::services::top_n::TopNGrouping;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
 
 // ============================================================================
 // Enums and Types
@@ -76,6 +84,17 @@ impl AttributionDimension {
             AttributionDimension::Environment => "environment",
         }
     }
+
+    /// Ranking grouping, for dimensions kept in the daily top-N rollups
+    pub fn top_n_grouping(&self) -> Option<TopNGrouping> {
+        match self {
+            AttributionDimension::User => Some(TopNGrouping::User),
+            AttributionDimension::Provider => Some(TopNGrouping::Provider),
+            AttributionDimension::Model => Some(TopNGrouping::Model),
+            AttributionDimension::Environment => Some(TopNGrouping::Environment),
+            AttributionDimension::Team | AttributionDimension::Tag => None,
+        }
+    }
 }
 
 /// Forecast period for predictions
@@ -103,6 +122,23 @@ impl ForecastPeriod {
     }
 }
 
+/// Dimension forecast separately for each of its top values
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastDimension {
+    Provider,
+    Model,
+}
+
+impl ForecastDimension {
+    pub fn to_column_name(&self) -> &'static str {
+        match self {
+            ForecastDimension::Provider => "provider",
+            ForecastDimension::Model => "model",
+        }
+    }
+}
+
 /// Budget alert threshold type
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -158,6 +194,13 @@ pub struct CostSummaryRequest {
     /// Number of top traces to return (max 100)
     #[serde(default = "default_top_limit")]
     pub top_limit: i32,
+
+    /// Report amounts in this currency (ISO 4217, default: display currency)
+    pub currency: Option<String>,
+
+    /// `global` to merge the summaries of all regions
+    #[serde(default)]
+    pub scope: Scope,
 }
 
 fn default_true() -> bool {
@@ -214,8 +257,11 @@ pub struct CostAttributionRequest {
     #[serde(default = "default_attribution_limit")]
     pub limit: i32,
 
-    /// Minimum cost threshold (filter out items below this cost)
+    /// Minimum cost threshold in the reporting currency (filter out items below this cost)
     pub min_cost: Option<f64>,
+
+    /// Report amounts in this currency (ISO 4217, default: display currency)
+    pub currency: Option<String>,
 }
 
 fn default_attribution_limit() -> i32 {
@@ -272,12 +318,33 @@ pub struct CostForecastRequest {
     /// Include confidence intervals
     #[serde(default = "default_true")]
     pub include_confidence_intervals: bool,
+
+    /// Forecasting model (default: selected automatically)
+    pub forecast_model: Option<ForecastModel>,
+
+    /// Criterion for automatic model selection (default: backtest_mape)
+    #[serde(default)]
+    pub selection_criterion: SelectionCriterion,
+
+    /// Also forecast each of the top values of this dimension
+    pub group_by: Option<ForecastDimension>,
+
+    /// Number of dimension values forecast separately (default: 5, max: 20)
+    #[serde(default = "default_dimension_limit")]
+    pub dimension_limit: usize,
+
+    /// Report amounts in this currency (ISO 4217, default: display currency)
+    pub currency: Option<String>,
 }
 
 fn default_forecast_period() -> ForecastPeriod {
     ForecastPeriod::NextMonth
 }
 
+fn default_dimension_limit() -> usize {
+    5
+}
+
 impl CostForecastRequest {
     pub fn validate(&self) -> Result<(), String> {
         if let (Some(start), Some(end)) = (self.historical_start, self.historical_end) {
@@ -304,16 +371,202 @@ impl CostForecastRequest {
             _ => {}
         }
 
+        if self.dimension_limit == 0 || self.dimension_limit > 20 {
+            return Err("Dimension limit must be between 1 and 20".to_string());
+        }
+
         Ok(())
     }
 }
 
+/// Request for GET /api/v1/costs/explain
+#[derive(Debug, Deserialize, Clone)]
+pub struct CostExplainRequest {
+    /// Baseline period (`YYYY-MM` or `start/end` in ISO 8601)
+    pub period_a: String,
+
+    /// Comparison period (`YYYY-MM` or `start/end` in ISO 8601)
+    pub period_b: String,
+
+    /// Filter by provider
+    pub provider: Option<String>,
+
+    /// Filter by model
+    pub model: Option<String>,
+
+    /// Max contributors to return per dimension (max 100)
+    #[serde(default = "default_explain_limit")]
+    pub limit: usize,
+
+    /// Report amounts in this currency (ISO 4217, default: display currency)
+    pub currency: Option<String>,
+}
+
+fn default_explain_limit() -> usize {
+    20
+}
+
+impl CostExplainRequest {
+    /// Validate the request and resolve both periods.
+    pub fn validate(&self) -> Result<(PeriodRange, PeriodRange), String> {
+        let period_a = parse_period(&self.period_a).map_err(|e| format!("period_a: {}", e))?;
+        let period_b = parse_period(&self.period_b).map_err(|e| format!("period_b: {}", e))?;
+
+        for period in [&period_a, &period_b] {
+            if (period.end - period.start).num_days() > 365 {
+                return Err("Maximum period length is 365 days".to_string());
+            }
+        }
+
+        if self.limit < 1 || self.limit > 100 {
+            return Err("limit must be between 1 and 100".to_string());
+        }
+
+        Ok((period_a, period_b))
+    }
+}
+
+/// A half-open time range `[start, end)`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PeriodRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Parse a period given as a calendar month (`2025-10`) or an explicit
+/// `start/end` range (`2025-10-01T00:00:00Z/2025-10-15T00:00:00Z`).
+pub fn parse_period(input: &str) -> Result<PeriodRange, String> {
+    let input = input.trim();
+
+    if let Some((start, end)) = input.split_once('/') {
+        let start = DateTime::parse_from_rfc3339(start.trim())
+            .map_err(|_| format!("invalid start time '{}'", start.trim()))?
+            .with_timezone(&Utc);
+        let end = DateTime::parse_from_rfc3339(end.trim())
+            .map_err(|_| format!("invalid end time '{}'", end.trim()))?
+            .with_timezone(&Utc);
+
+        if start >= end {
+            return Err("start must be before end".to_string());
+        }
+
+        return Ok(PeriodRange { start, end });
+    }
+
+    let first = NaiveDate::parse_from_str(&format!("{}-01", input), "%Y-%m-%d")
+        .map_err(|_| format!("expected YYYY-MM or start/end, got '{}'", input))?;
+    let next = if first.month() == 12 {
+        NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+    }
+    .ok_or_else(|| format!("invalid month '{}'", input))?;
+
+    Ok(PeriodRange {
+        start: Utc.from_utc_datetime(&first.and_hms_opt(0, 0, 0).unwrap_or_default()),
+        end: Utc.from_utc_datetime(&next.and_hms_opt(0, 0, 0).unwrap_or_default()),
+    })
+}
+
+/// Format of GET /api/v1/costs/chargeback responses
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChargebackFormat {
+    /// JSON invoices
+    #[default]
+    Json,
+    /// CSV, one row per invoice line item
+    Csv,
+}
+
+/// Request for GET /api/v1/costs/chargeback
+#[derive(Debug, Deserialize, Clone)]
+pub struct ChargebackRequest {
+    /// Cost-allocation tag key to invoice by (e.g. `team`, `product`)
+    pub tag_key: String,
+
+    /// First invoiced month (`YYYY-MM`)
+    pub start_month: String,
+
+    /// Last invoiced month, inclusive (`YYYY-MM`, default: start_month)
+    pub end_month: Option<String>,
+
+    /// Only invoice this tag value
+    pub tag_value: Option<String>,
+
+    /// Invoice calls without the tag separately (default: true)
+    #[serde(default = "default_include_untagged")]
+    pub include_untagged: bool,
+
+    /// Response format (json or csv)
+    #[serde(default)]
+    pub format: ChargebackFormat,
+
+    /// Report amounts in this currency (ISO 4217, default: display currency)
+    pub currency: Option<String>,
+}
+
+fn default_include_untagged() -> bool {
+    true
+}
+
+/// Maximum number of months in one chargeback report
+pub const MAX_CHARGEBACK_MONTHS: usize = 24;
+
+impl ChargebackRequest {
+    /// Validate the request and resolve the invoiced range.
+    pub fn validate(&self) -> Result<PeriodRange, String> {
+        let valid_key = !self.tag_key.is_empty()
+            && self.tag_key.len() <= 64
+            && self
+                .tag_key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid_key {
+            return Err(
+                "tag_key must be 1-64 lowercase letters, digits or underscores".to_string(),
+            );
+        }
+
+        let month = |input: &str, name: &str| {
+            if input.contains('/') {
+                return Err(format!("{}: expected YYYY-MM, got '{}'", name, input));
+            }
+            parse_period(input).map_err(|e| format!("{}: {}", name, e))
+        };
+        let start = month(&self.start_month, "start_month")?;
+        let end = match &self.end_month {
+            Some(end_month) => month(end_month, "end_month")?,
+            None => start,
+        };
+
+        if end.start < start.start {
+            return Err("end_month must not be before start_month".to_string());
+        }
+        let months = (end.start.year() - start.start.year()) * 12
+            + end.start.month() as i32
+            - start.start.month() as i32
+            + 1;
+        if months as usize > MAX_CHARGEBACK_MONTHS {
+            return Err(format!(
+                "Maximum chargeback range is {} months",
+                MAX_CHARGEBACK_MONTHS
+            ));
+        }
+
+        Ok(PeriodRange {
+            start: start.start,
+            end: end.end,
+        })
+    }
+}
+
 // ============================================================================
 // Response Models
 // ============================================================================
 
 /// Response for GET /api/v1/costs/summary
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CostSummaryResponse {
     /// Summary metadata
     pub metadata: CostSummaryMetadata,
@@ -337,17 +590,67 @@ pub struct CostSummaryResponse {
     /// Top expensive traces (if requested)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_traces: Option<Vec<ExpensiveTrace>>,
+
+    /// Regions merged into a global summary (`scope=global` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub federation: Option<FederationInfo>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CostSummaryMetadata {
     pub start_time: DateTime<Utc>,
     pub end_time: DateTime<Utc>,
     pub period_days: i64,
     pub generated_at: DateTime<Utc>,
+    pub currency: CurrencyConversion,
+}
+
+/// Currency the response amounts are reported in.
+///
+/// Costs are stored in USD; the rate and original USD total are included so
+/// converted figures can be audited.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CurrencyConversion {
+    /// Reporting currency (ISO 4217)
+    pub currency: String,
+
+    /// Units of the reporting currency per 1 USD
+    pub usd_rate: f64,
+
+    /// Exchange rate source ("identity", "static", "remote")
+    pub rate_source: String,
+
+    /// When the exchange rate was obtained
+    pub rate_as_of: DateTime<Utc>,
+
+    /// Response total before conversion, in USD
+    pub original_total_usd: f64,
+}
+
+impl CurrencyConversion {
+    /// Unconverted USD amounts.
+    pub fn usd(original_total_usd: f64) -> Self {
+        Self {
+            currency: BASE_CURRENCY.to_string(),
+            usd_rate: 1.0,
+            rate_source: "identity".to_string(),
+            rate_as_of: Utc::now(),
+            original_total_usd,
+        }
+    }
+
+    pub(crate) fn from_quote(quote: &FxQuote, original_total_usd: f64) -> Self {
+        Self {
+            currency: quote.currency.clone(),
+            usd_rate: quote.rate,
+            rate_source: quote.source.clone(),
+            rate_as_of: quote.as_of,
+            original_total_usd,
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CostOverview {
     /// Total cost in USD
     pub total_cost: f64,
@@ -377,7 +680,7 @@ pub struct CostOverview {
     pub week_over_week_change: Option<f64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CostBreakdownItem {
     /// Dimension value (e.g., "openai", "gpt-4")
     pub name: String,
@@ -395,7 +698,7 @@ pub struct CostBreakdownItem {
     pub avg_cost_per_request: f64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CostTrends {
     /// Daily cost trend
     pub daily: Vec<CostDataPoint>,
@@ -410,14 +713,14 @@ pub struct CostTrends {
     pub growth_rate_weekly: f64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CostDataPoint {
     pub date: DateTime<Utc>,
     pub cost: f64,
     pub requests: i64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ExpensiveTrace {
     pub trace_id: String,
     pub timestamp: DateTime<Utc>,
@@ -430,7 +733,7 @@ pub struct ExpensiveTrace {
 }
 
 /// Response for GET /api/v1/costs/attribution
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CostAttributionResponse {
     /// Attribution metadata
     pub metadata: AttributionMetadata,
@@ -442,15 +745,16 @@ pub struct CostAttributionResponse {
     pub summary: AttributionSummary,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AttributionMetadata {
     pub dimension: String,
     pub start_time: DateTime<Utc>,
     pub end_time: DateTime<Utc>,
     pub total_items: usize,
+    pub currency: CurrencyConversion,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AttributionItem {
     /// Dimension value (user_id, team_id, etc.)
     pub dimension_value: String,
@@ -483,7 +787,7 @@ pub struct AttributionItem {
     pub by_model: HashMap<String, f64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AttributionSummary {
     pub total_cost: f64,
     pub total_requests: i64,
@@ -492,7 +796,7 @@ pub struct AttributionSummary {
 }
 
 /// Response for GET /api/v1/costs/forecast
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CostForecastResponse {
     /// Forecast metadata
     pub metadata: ForecastMetadata,
@@ -505,20 +809,28 @@ pub struct CostForecastResponse {
 
     /// Forecast summary
     pub summary: ForecastSummary,
+
+    /// Candidate models, their backtest accuracy and the one selected
+    pub model_selection: ModelSelection,
+
+    /// Forecasts of the top values of the `group_by` dimension
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_dimension: Vec<DimensionForecast>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ForecastMetadata {
     pub historical_start: DateTime<Utc>,
     pub historical_end: DateTime<Utc>,
     pub forecast_start: DateTime<Utc>,
     pub forecast_end: DateTime<Utc>,
     pub forecast_days: i32,
-    pub model_type: String, // "linear_regression"
+    pub model_type: String, // "linear_regression", "holt_winters" or "seasonal_decomposition"
     pub generated_at: DateTime<Utc>,
+    pub currency: CurrencyConversion,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ForecastDataPoint {
     pub date: DateTime<Utc>,
     pub forecasted_cost: f64,
@@ -526,7 +838,17 @@ pub struct ForecastDataPoint {
     pub upper_bound: Option<f64>,
 }
 
-#[derive(Debug, Serialize)]
+/// Forecast of one dimension value
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DimensionForecast {
+    pub dimension_value: String,
+    pub model_type: ForecastModel,
+    pub backtest_mape: Option<f64>,
+    pub total_forecasted_cost: f64,
+    pub forecast: Vec<ForecastDataPoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ForecastSummary {
     /// Total forecasted cost for the period
     pub total_forecasted_cost: f64,
@@ -540,8 +862,280 @@ pub struct ForecastSummary {
     /// R-squared value (model accuracy)
     pub r_squared: f64,
 
-    /// Mean absolute percentage error
+    /// Mean absolute percentage error of the fitted history
     pub mape: Option<f64>,
+
+    /// Mean absolute percentage error of the selected model's backtest
+    pub backtest_mape: Option<f64>,
+}
+
+/// Response for GET /api/v1/costs/explain
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CostExplainResponse {
+    /// Explain metadata
+    pub metadata: ExplainMetadata,
+
+    /// Overall cost change and its volume/price split
+    pub summary: ExplainSummary,
+
+    /// Contributions by provider
+    pub by_provider: Vec<CostContribution>,
+
+    /// Contributions by model
+    pub by_model: Vec<CostContribution>,
+
+    /// Contributions by team
+    pub by_team: Vec<CostContribution>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExplainMetadata {
+    pub period_a: PeriodRange,
+    pub period_b: PeriodRange,
+    pub generated_at: DateTime<Utc>,
+    pub currency: CurrencyConversion,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExplainSummary {
+    /// Total cost in period A
+    pub cost_a: f64,
+
+    /// Total cost in period B
+    pub cost_b: f64,
+
+    /// Cost change (B - A)
+    pub delta: f64,
+
+    /// Cost change as a percentage of period A (None if A had no cost)
+    pub delta_percentage: Option<f64>,
+
+    /// Change explained by token volume
+    pub volume_effect: f64,
+
+    /// Change explained by cost per token (pricing and model mix within a model)
+    pub price_effect: f64,
+
+    pub requests_a: i64,
+    pub requests_b: i64,
+    pub tokens_a: i64,
+    pub tokens_b: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CostContribution {
+    /// Dimension value (e.g., "openai", "gpt-4o", team ID)
+    pub name: String,
+
+    /// Cost in period A
+    pub cost_a: f64,
+
+    /// Cost in period B
+    pub cost_b: f64,
+
+    /// Cost change (B - A)
+    pub delta: f64,
+
+    /// Share of the total cost change (percentage)
+    pub contribution_percentage: f64,
+
+    /// Change explained by token volume
+    pub volume_effect: f64,
+
+    /// Change explained by cost per token
+    pub price_effect: f64,
+}
+
+/// Response for GET /api/v1/costs/chargeback
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChargebackResponse {
+    /// Report metadata
+    pub metadata: ChargebackMetadata,
+
+    /// One invoice per month and tag value, by month then cost
+    pub invoices: Vec<ChargebackInvoice>,
+
+    /// Totals over all invoices
+    pub summary: ChargebackSummary,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChargebackMetadata {
+    pub tag_key: String,
+    pub period: PeriodRange,
+    pub generated_at: DateTime<Utc>,
+    pub currency: CurrencyConversion,
+}
+
+/// Costs of one tag value in one month
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ChargebackInvoice {
+    /// Invoiced month (`YYYY-MM`)
+    pub month: String,
+
+    /// Tag value billed (None for calls without the tag)
+    pub tag_value: Option<String>,
+
+    pub total_cost: f64,
+    pub prompt_cost: f64,
+    pub completion_cost: f64,
+    pub request_count: i64,
+    pub total_tokens: i64,
+
+    /// Share of the month's total cost (percentage)
+    pub cost_percentage: f64,
+
+    /// Costs by provider and model, most expensive first
+    pub line_items: Vec<ChargebackLineItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ChargebackLineItem {
+    pub provider: String,
+    pub model: String,
+    pub request_count: i64,
+    pub total_tokens: i64,
+    pub prompt_cost: f64,
+    pub completion_cost: f64,
+    pub total_cost: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChargebackSummary {
+    pub total_cost: f64,
+    pub total_requests: i64,
+    pub invoice_count: usize,
+
+    /// Cost of calls without the tag
+    pub untagged_cost: f64,
+}
+
+impl ChargebackResponse {
+    /// Build invoices from per-month, tag value, provider and model rows.
+    pub fn from_rows(tag_key: &str, period: PeriodRange, rows: Vec<ChargebackRow>) -> Self {
+        let mut invoices: Vec<ChargebackInvoice> = Vec::new();
+        for row in rows {
+            let month = row.month.format("%Y-%m").to_string();
+            let index = match invoices
+                .iter()
+                .position(|i| i.month == month && i.tag_value == row.tag_value)
+            {
+                Some(index) => index,
+                None => {
+                    invoices.push(ChargebackInvoice {
+                        month,
+                        tag_value: row.tag_value,
+                        total_cost: 0.0,
+                        prompt_cost: 0.0,
+                        completion_cost: 0.0,
+                        request_count: 0,
+                        total_tokens: 0,
+                        cost_percentage: 0.0,
+                        line_items: Vec::new(),
+                    });
+                    invoices.len() - 1
+                }
+            };
+
+            let item = ChargebackLineItem {
+                provider: row.provider,
+                model: row.model,
+                request_count: row.request_count.unwrap_or(0),
+                total_tokens: row.total_tokens.unwrap_or(0),
+                prompt_cost: row.prompt_cost.unwrap_or(0.0),
+                completion_cost: row.completion_cost.unwrap_or(0.0),
+                total_cost: row.total_cost.unwrap_or(0.0),
+            };
+            let invoice = &mut invoices[index];
+            invoice.total_cost += item.total_cost;
+            invoice.prompt_cost += item.prompt_cost;
+            invoice.completion_cost += item.completion_cost;
+            invoice.request_count += item.request_count;
+            invoice.total_tokens += item.total_tokens;
+            invoice.line_items.push(item);
+        }
+
+        let mut month_totals: HashMap<String, f64> = HashMap::new();
+        for invoice in &invoices {
+            *month_totals.entry(invoice.month.clone()).or_default() += invoice.total_cost;
+        }
+        for invoice in &mut invoices {
+            let month_total = month_totals[&invoice.month];
+            if month_total > 0.0 {
+                invoice.cost_percentage = invoice.total_cost / month_total * 100.0;
+            }
+            invoice
+                .line_items
+                .sort_by(|a, b| b.total_cost.total_cmp(&a.total_cost));
+        }
+        invoices.sort_by(|a, b| {
+            a.month
+                .cmp(&b.month)
+                .then(b.total_cost.total_cmp(&a.total_cost))
+        });
+
+        let total_cost: f64 = invoices.iter().map(|i| i.total_cost).sum();
+        let summary = ChargebackSummary {
+            total_cost,
+            total_requests: invoices.iter().map(|i| i.request_count).sum(),
+            invoice_count: invoices.len(),
+            untagged_cost: invoices
+                .iter()
+                .filter(|i| i.tag_value.is_none())
+                .map(|i| i.total_cost)
+                .sum(),
+        };
+
+        Self {
+            metadata: ChargebackMetadata {
+                tag_key: tag_key.to_string(),
+                period,
+                generated_at: Utc::now(),
+                currency: CurrencyConversion::usd(total_cost),
+            },
+            invoices,
+            summary,
+        }
+    }
+
+    /// CSV with one row per invoice line item; untagged calls have an empty
+    /// tag value.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "month,tag_key,tag_value,provider,model,request_count,total_tokens,\
+             prompt_cost,completion_cost,total_cost,currency\n",
+        );
+        for invoice in &self.invoices {
+            for item in &invoice.line_items {
+                let fields = [
+                    invoice.month.clone(),
+                    self.metadata.tag_key.clone(),
+                    invoice.tag_value.clone().unwrap_or_default(),
+                    item.provider.clone(),
+                    item.model.clone(),
+                    item.request_count.to_string(),
+                    item.total_tokens.to_string(),
+                    format!("{:.6}", item.prompt_cost),
+                    format!("{:.6}", item.completion_cost),
+                    format!("{:.6}", item.total_cost),
+                    self.metadata.currency.currency.clone(),
+                ];
+                let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                csv.push_str(&row.join(","));
+                csv.push('\n');
+            }
+        }
+        csv
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
 }
 
 /// Budget configuration
@@ -575,6 +1169,161 @@ pub struct BudgetAlert {
     pub resolved_at: Option<DateTime<Utc>>,
 }
 
+// ============================================================================
+// Currency Conversion
+// ============================================================================
+
+impl CostSummaryResponse {
+    /// Convert all USD amounts into the quoted currency.
+    pub fn convert_currency(&mut self, quote: &FxQuote) {
+        self.metadata.currency = CurrencyConversion::from_quote(quote, self.overview.total_cost);
+        if quote.is_identity() {
+            return;
+        }
+
+        let o = &mut self.overview;
+        o.total_cost = quote.convert(o.total_cost);
+        o.prompt_cost = quote.convert(o.prompt_cost);
+        o.completion_cost = quote.convert(o.completion_cost);
+        o.avg_cost_per_request = quote.convert(o.avg_cost_per_request);
+        o.avg_cost_per_1k_tokens = quote.convert(o.avg_cost_per_1k_tokens);
+
+        for item in self
+            .by_provider
+            .iter_mut()
+            .chain(self.by_model.iter_mut())
+            .chain(self.by_environment.iter_mut())
+        {
+            item.cost = quote.convert(item.cost);
+            item.avg_cost_per_request = quote.convert(item.avg_cost_per_request);
+        }
+
+        if let Some(trends) = &mut self.trends {
+            for point in trends.daily.iter_mut().chain(trends.weekly.iter_mut()) {
+                point.cost = quote.convert(point.cost);
+            }
+        }
+
+        if let Some(traces) = &mut self.top_traces {
+            for trace in traces {
+                trace.cost = quote.convert(trace.cost);
+            }
+        }
+    }
+}
+
+impl CostAttributionResponse {
+    /// Convert all USD amounts into the quoted currency.
+    pub fn convert_currency(&mut self, quote: &FxQuote) {
+        self.metadata.currency = CurrencyConversion::from_quote(quote, self.summary.total_cost);
+        if quote.is_identity() {
+            return;
+        }
+
+        for item in &mut self.items {
+            item.total_cost = quote.convert(item.total_cost);
+            item.prompt_cost = quote.convert(item.prompt_cost);
+            item.completion_cost = quote.convert(item.completion_cost);
+            item.avg_cost_per_request = quote.convert(item.avg_cost_per_request);
+            for cost in item.by_provider.values_mut().chain(item.by_model.values_mut()) {
+                *cost = quote.convert(*cost);
+            }
+        }
+
+        self.summary.total_cost = quote.convert(self.summary.total_cost);
+        self.summary.avg_cost_per_item = quote.convert(self.summary.avg_cost_per_item);
+    }
+}
+
+impl CostForecastResponse {
+    /// Convert all USD amounts into the quoted currency.
+    pub fn convert_currency(&mut self, quote: &FxQuote) {
+        self.metadata.currency =
+            CurrencyConversion::from_quote(quote, self.summary.total_forecasted_cost);
+        if quote.is_identity() {
+            return;
+        }
+
+        for point in &mut self.historical {
+            point.cost = quote.convert(point.cost);
+        }
+
+        for point in &mut self.forecast {
+            point.forecasted_cost = quote.convert(point.forecasted_cost);
+            point.lower_bound = point.lower_bound.map(|v| quote.convert(v));
+            point.upper_bound = point.upper_bound.map(|v| quote.convert(v));
+        }
+
+        for dimension in &mut self.by_dimension {
+            dimension.total_forecasted_cost = quote.convert(dimension.total_forecasted_cost);
+            for point in &mut dimension.forecast {
+                point.forecasted_cost = quote.convert(point.forecasted_cost);
+                point.lower_bound = point.lower_bound.map(|v| quote.convert(v));
+                point.upper_bound = point.upper_bound.map(|v| quote.convert(v));
+            }
+        }
+
+        let s = &mut self.summary;
+        s.total_forecasted_cost = quote.convert(s.total_forecasted_cost);
+        s.avg_daily_cost = quote.convert(s.avg_daily_cost);
+        s.projected_monthly_cost = quote.convert(s.projected_monthly_cost);
+    }
+}
+
+impl CostExplainResponse {
+    /// Convert all USD amounts into the quoted currency.
+    pub fn convert_currency(&mut self, quote: &FxQuote) {
+        self.metadata.currency = CurrencyConversion::from_quote(quote, self.summary.delta);
+        if quote.is_identity() {
+            return;
+        }
+
+        let s = &mut self.summary;
+        s.cost_a = quote.convert(s.cost_a);
+        s.cost_b = quote.convert(s.cost_b);
+        s.delta = quote.convert(s.delta);
+        s.volume_effect = quote.convert(s.volume_effect);
+        s.price_effect = quote.convert(s.price_effect);
+
+        for item in self
+            .by_provider
+            .iter_mut()
+            .chain(self.by_model.iter_mut())
+            .chain(self.by_team.iter_mut())
+        {
+            item.cost_a = quote.convert(item.cost_a);
+            item.cost_b = quote.convert(item.cost_b);
+            item.delta = quote.convert(item.delta);
+            item.volume_effect = quote.convert(item.volume_effect);
+            item.price_effect = quote.convert(item.price_effect);
+        }
+    }
+}
+
+impl ChargebackResponse {
+    /// Convert all USD amounts into the quoted currency.
+    pub fn convert_currency(&mut self, quote: &FxQuote) {
+        self.metadata.currency = CurrencyConversion::from_quote(quote, self.summary.total_cost);
+        if quote.is_identity() {
+            return;
+        }
+
+        for invoice in &mut self.invoices {
+            invoice.total_cost = quote.convert(invoice.total_cost);
+            invoice.prompt_cost = quote.convert(invoice.prompt_cost);
+            invoice.completion_cost = quote.convert(invoice.completion_cost);
+            for item in &mut invoice.line_items {
+                item.total_cost = quote.convert(item.total_cost);
+                item.prompt_cost = quote.convert(item.prompt_cost);
+                item.completion_cost = quote.convert(item.completion_cost);
+            }
+        }
+
+        self.summary.total_cost = quote.convert(self.summary.total_cost);
+        self.summary.untagged_cost = quote.convert(self.summary.untagged_cost);
+    }
+}
+
 // ============================================================================
 // Internal Database Row Types
 // ============================================================================
@@ -641,13 +1390,221 @@ pub struct AttributionBreakdownRow {
 #[derive(Debug, sqlx::FromRow)]
 pub struct ForecastHistoricalRow {
     pub date: DateTime<Utc>,
+    /// Value of the `group_by` dimension; None for the overall series
+    pub dimension_value: Option<String>,
     pub cost: Option<f64>,
 }
 
+/// Row for explain query (costs for both periods per dimension value)
+#[derive(Debug, sqlx::FromRow)]
+pub struct CostExplainRow {
+    pub provider: Option<String>,
+    pub dimension_value: String,
+    pub cost_a: Option<f64>,
+    pub cost_b: Option<f64>,
+    pub requests_a: Option<i64>,
+    pub requests_b: Option<i64>,
+    pub tokens_a: Option<i64>,
+    pub tokens_b: Option<i64>,
+}
+
+/// Row for chargeback query (one per month, tag value, provider and model)
+#[derive(Debug, sqlx::FromRow)]
+pub struct ChargebackRow {
+    pub month: DateTime<Utc>,
+    pub tag_value: Option<String>,
+    pub provider: String,
+    pub model: String,
+    pub total_cost: Option<f64>,
+    pub prompt_cost: Option<f64>,
+    pub completion_cost: Option<f64>,
+    pub request_count: Option<i64>,
+    pub total_tokens: Option<i64>,
+}
+
+// ============================================================================
+// Federation
+// ============================================================================
+
+impl CostSummaryResponse {
+    /// Merge USD summaries of other regions into this one.
+    ///
+    /// Totals, breakdowns and trend points are summed per name or date, and
+    /// averages, shares and growth rates recomputed. Day-over-day and
+    /// week-over-week changes can't be derived from regional percentages and
+    /// are dropped. Trends are kept only if every region returned them; top
+    /// traces are re-ranked and cut to `top_limit`.
+    pub fn merge(mut self, others: Vec<CostSummaryResponse>, top_limit: usize) -> Self {
+        if others.is_empty() {
+            return self;
+        }
+
+        let mut trends = self.trends.take().map(|t| vec![t]);
+        let mut top_traces = self.top_traces.take();
+        let overview = &mut self.overview;
+        overview.day_over_day_change = None;
+        overview.week_over_week_change = None;
+
+        for other in others {
+            overview.total_cost += other.overview.total_cost;
+            overview.prompt_cost += other.overview.prompt_cost;
+            overview.completion_cost += other.overview.completion_cost;
+            overview.total_requests += other.overview.total_requests;
+            overview.total_tokens += other.overview.total_tokens;
+
+            self.by_provider.extend(other.by_provider);
+            self.by_model.extend(other.by_model);
+            self.by_environment.extend(other.by_environment);
+
+            trends = match (trends, other.trends) {
+                (Some(mut all), Some(t)) => {
+                    all.push(t);
+                    Some(all)
+                }
+                _ => None,
+            };
+            top_traces = match (top_traces, other.top_traces) {
+                (Some(mut all), Some(t)) => {
+                    all.extend(t);
+                    Some(all)
+                }
+                (all, None) => all,
+                (None, t) => t,
+            };
+        }
+
+        overview.avg_cost_per_request = if overview.total_requests > 0 {
+            overview.total_cost / overview.total_requests as f64
+        } else {
+            0.0
+        };
+        overview.avg_cost_per_1k_tokens = if overview.total_tokens > 0 {
+            (overview.total_cost / overview.total_tokens as f64) * 1000.0
+        } else {
+            0.0
+        };
+
+        self.by_provider = merge_breakdown(std::mem::take(&mut self.by_provider));
+        self.by_model = merge_breakdown(std::mem::take(&mut self.by_model));
+        self.by_environment = merge_breakdown(std::mem::take(&mut self.by_environment));
+
+        self.trends = trends.map(|all| {
+            let (daily, weekly): (Vec<_>, Vec<_>) =
+                all.into_iter().map(|t| (t.daily, t.weekly)).unzip();
+            let daily = merge_data_points(daily.into_iter().flatten());
+            let weekly = merge_data_points(weekly.into_iter().flatten());
+            CostTrends {
+                growth_rate_daily: calculate_growth_rate(&daily),
+                growth_rate_weekly: calculate_growth_rate(&weekly),
+                daily,
+                weekly,
+            }
+        });
+
+        self.top_traces = top_traces.map(|mut traces| {
+            traces.sort_by(|a, b| b.cost.partial_cmp(&a.cost).unwrap_or(Ordering::Equal));
+            traces.truncate(top_limit);
+            traces
+        });
+
+        self.metadata.generated_at = Utc::now();
+        self.metadata.currency = CurrencyConversion::usd(self.overview.total_cost);
+        self
+    }
+}
+
+/// Sum breakdown items with the same name and recompute their shares
+fn merge_breakdown(items: Vec<CostBreakdownItem>) -> Vec<CostBreakdownItem> {
+    let mut merged: Vec<CostBreakdownItem> = Vec::new();
+    for item in items {
+        match merged.iter_mut().find(|m| m.name == item.name) {
+            Some(m) => {
+                m.cost += item.cost;
+                m.requests += item.requests;
+            }
+            None => merged.push(item),
+        }
+    }
+
+    let total_cost: f64 = merged.iter().map(|item| item.cost).sum();
+    for item in &mut merged {
+        item.percentage = if total_cost > 0.0 {
+            (item.cost / total_cost) * 100.0
+        } else {
+            0.0
+        };
+        item.avg_cost_per_request = if item.requests > 0 {
+            item.cost / item.requests as f64
+        } else {
+            0.0
+        };
+    }
+
+    merged.sort_by(|a, b| b.cost.partial_cmp(&a.cost).unwrap_or(Ordering::Equal));
+    merged
+}
+
+/// Sum trend points with the same date, in date order
+fn merge_data_points(points: impl Iterator<Item = CostDataPoint>) -> Vec<CostDataPoint> {
+    let mut by_date: BTreeMap<DateTime<Utc>, (f64, i64)> = BTreeMap::new();
+    for point in points {
+        let entry = by_date.entry(point.date).or_default();
+        entry.0 += point.cost;
+        entry.1 += point.requests;
+    }
+
+    by_date
+        .into_iter()
+        .map(|(date, (cost, requests))| CostDataPoint {
+            date,
+            cost,
+            requests,
+        })
+        .collect()
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+/// Split a cost change into volume and price effects.
+///
+/// Uses the midpoint (Shapley) decomposition so the two effects always sum to
+/// the total change: volume is valued at the average unit price of both
+/// periods, and the price change is weighted by the average volume. A segment
+/// present in only one period is attributed entirely to volume.
+pub fn decompose_cost_delta(cost_a: f64, volume_a: f64, cost_b: f64, volume_b: f64) -> (f64, f64) {
+    let delta = cost_b - cost_a;
+
+    if volume_a <= 0.0 && volume_b <= 0.0 {
+        return (delta, 0.0);
+    }
+
+    let price_b = if volume_b > 0.0 { cost_b / volume_b } else { cost_a / volume_a };
+    let price_a = if volume_a > 0.0 { cost_a / volume_a } else { price_b };
+
+    let volume_effect = (volume_b - volume_a) * (price_a + price_b) / 2.0;
+    let price_effect = delta - volume_effect;
+
+    (volume_effect, price_effect)
+}
+
+/// Calculate growth rate from trend data
+pub fn calculate_growth_rate(data: &[CostDataPoint]) -> f64 {
+    if data.len() < 2 {
+        return 0.0;
+    }
+
+    let costs: Vec<(f64, f64)> = data
+        .iter()
+        .enumerate()
+        .map(|(i, point)| (i as f64, point.cost))
+        .collect();
+
+    let (slope, _, _) = calculate_linear_regression(&costs);
+    slope
+}
+
 /// Calculate linear regression for cost forecasting
 pub fn calculate_linear_regression(data: &[(f64, f64)]) -> (f64, f64, f64) {
     if data.is_empty() {
@@ -774,6 +1731,8 @@ mod tests {
             include_trends: true,
             include_top_traces: true,
             top_limit: 10,
+            currency: None,
+            scope: Scope::Region,
         };
 
         assert!(req.validate().is_ok());
@@ -791,8 +1750,271 @@ mod tests {
             environment: None,
             limit: 100,
             min_cost: None,
+            currency: None,
         };
 
         assert!(req.validate().is_ok());
     }
+
+    #[test]
+    fn test_forecast_currency_conversion() {
+        let now = Utc::now();
+        let quote = FxQuote {
+            currency: "EUR".to_string(),
+            rate: 0.5,
+            source: "static".to_string(),
+            as_of: now,
+        };
+
+        let mut response = CostForecastResponse {
+            metadata: ForecastMetadata {
+                historical_start: now,
+                historical_end: now,
+                forecast_start: now,
+                forecast_end: now,
+                forecast_days: 1,
+                model_type: "linear_regression".to_string(),
+                generated_at: now,
+                currency: CurrencyConversion::usd(20.0),
+            },
+            historical: vec![CostDataPoint {
+                date: now,
+                cost: 10.0,
+                requests: 1,
+            }],
+            forecast: vec![ForecastDataPoint {
+                date: now,
+                forecasted_cost: 20.0,
+                lower_bound: Some(16.0),
+                upper_bound: None,
+            }],
+            summary: ForecastSummary {
+                total_forecasted_cost: 20.0,
+                avg_daily_cost: 20.0,
+                projected_monthly_cost: 600.0,
+                r_squared: 1.0,
+                mape: None,
+                backtest_mape: Some(4.0),
+            },
+            model_selection: ModelSelection {
+                selected: ForecastModel::LinearRegression,
+                criterion: None,
+                backtest_days: 7,
+                candidates: Vec::new(),
+            },
+            by_dimension: vec![DimensionForecast {
+                dimension_value: "openai".to_string(),
+                model_type: ForecastModel::LinearRegression,
+                backtest_mape: None,
+                total_forecasted_cost: 12.0,
+                forecast: vec![ForecastDataPoint {
+                    date: now,
+                    forecasted_cost: 12.0,
+                    lower_bound: None,
+                    upper_bound: Some(14.0),
+                }],
+            }],
+        };
+
+        response.convert_currency(&quote);
+
+        assert_eq!(response.metadata.currency.currency, "EUR");
+        assert_eq!(response.metadata.currency.usd_rate, 0.5);
+        assert_eq!(response.metadata.currency.original_total_usd, 20.0);
+        assert_eq!(response.historical[0].cost, 5.0);
+        assert_eq!(response.forecast[0].forecasted_cost, 10.0);
+        assert_eq!(response.forecast[0].lower_bound, Some(8.0));
+        assert_eq!(response.summary.projected_monthly_cost, 300.0);
+        assert_eq!(response.summary.r_squared, 1.0);
+        assert_eq!(response.by_dimension[0].total_forecasted_cost, 6.0);
+        assert_eq!(response.by_dimension[0].forecast[0].upper_bound, Some(7.0));
+    }
+
+    #[test]
+    fn test_parse_period() {
+        let month = parse_period("2025-12").unwrap();
+        assert_eq!(month.start.to_rfc3339(), "2025-12-01T00:00:00+00:00");
+        assert_eq!(month.end.to_rfc3339(), "2026-01-01T00:00:00+00:00");
+
+        let range = parse_period("2025-10-01T00:00:00Z/2025-10-08T00:00:00Z").unwrap();
+        assert_eq!((range.end - range.start).num_days(), 7);
+
+        assert!(parse_period("2025-13").is_err());
+        assert!(parse_period("2025-10-08T00:00:00Z/2025-10-01T00:00:00Z").is_err());
+        assert!(parse_period("last month").is_err());
+    }
+
+    #[test]
+    fn test_decompose_cost_delta() {
+        // Volume doubles at the same price: all volume
+        let (volume, price) = decompose_cost_delta(10.0, 1000.0, 20.0, 2000.0);
+        assert!((volume - 10.0).abs() < 1e-9);
+        assert!(price.abs() < 1e-9);
+
+        // Same volume, price doubles: all price
+        let (volume, price) = decompose_cost_delta(10.0, 1000.0, 20.0, 1000.0);
+        assert!(volume.abs() < 1e-9);
+        assert!((price - 10.0).abs() < 1e-9);
+
+        // Both change: effects sum to the delta
+        let (volume, price) = decompose_cost_delta(10.0, 1000.0, 45.0, 3000.0);
+        assert!((volume + price - 35.0).abs() < 1e-9);
+
+        // New segment: all volume
+        assert_eq!(decompose_cost_delta(0.0, 0.0, 5.0, 100.0), (5.0, 0.0));
+        assert_eq!(decompose_cost_delta(5.0, 100.0, 0.0, 0.0), (-5.0, 0.0));
+    }
+
+    fn chargeback_row(month: &str, tag: Option<&str>, model: &str, cost: f64) -> ChargebackRow {
+        ChargebackRow {
+            month: parse_period(month).unwrap().start,
+            tag_value: tag.map(str::to_string),
+            provider: "openai".to_string(),
+            model: model.to_string(),
+            total_cost: Some(cost),
+            prompt_cost: Some(cost / 4.0),
+            completion_cost: Some(cost * 3.0 / 4.0),
+            request_count: Some(10),
+            total_tokens: Some(1000),
+        }
+    }
+
+    #[test]
+    fn test_chargeback_request_validation() {
+        let mut req = ChargebackRequest {
+            tag_key: "team".to_string(),
+            start_month: "2025-11".to_string(),
+            end_month: Some("2026-01".to_string()),
+            tag_value: None,
+            include_untagged: true,
+            format: ChargebackFormat::Json,
+            currency: None,
+        };
+        let period = req.validate().unwrap();
+        assert_eq!(period.start.to_rfc3339(), "2025-11-01T00:00:00+00:00");
+        assert_eq!(period.end.to_rfc3339(), "2026-02-01T00:00:00+00:00");
+
+        req.end_month = Some("2027-12".to_string());
+        assert!(req.validate().is_err());
+        req.end_month = Some("2025-10".to_string());
+        assert!(req.validate().is_err());
+        req.end_month = None;
+        req.tag_key = "Team Name".to_string();
+        assert!(req.validate().is_err());
+    }
+
+    #[test]
+    fn test_chargeback_invoices() {
+        let period = parse_period("2025-10").unwrap();
+        let rows = vec![
+            chargeback_row("2025-10", Some("search"), "gpt-4o", 30.0),
+            chargeback_row("2025-10", Some("search"), "gpt-4o-mini", 10.0),
+            chargeback_row("2025-10", None, "gpt-4o", 60.0),
+            chargeback_row("2025-11", Some("search"), "gpt-4o", 5.0),
+        ];
+
+        let response = ChargebackResponse::from_rows("team", period, rows);
+        assert_eq!(response.invoices.len(), 3);
+
+        let untagged = &response.invoices[0];
+        assert_eq!(untagged.month, "2025-10");
+        assert_eq!(untagged.tag_value, None);
+        assert_eq!(untagged.cost_percentage, 60.0);
+
+        let search = &response.invoices[1];
+        assert_eq!(search.tag_value.as_deref(), Some("search"));
+        assert_eq!(search.total_cost, 40.0);
+        assert_eq!(search.request_count, 20);
+        assert_eq!(search.line_items[0].model, "gpt-4o");
+
+        assert_eq!(response.invoices[2].month, "2025-11");
+        assert_eq!(response.invoices[2].cost_percentage, 100.0);
+        assert_eq!(response.summary.total_cost, 105.0);
+        assert_eq!(response.summary.untagged_cost, 60.0);
+
+        let csv = response.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].starts_with("month,tag_key,tag_value,provider,model,"));
+        assert_eq!(
+            lines[1],
+            "2025-10,team,,openai,gpt-4o,10,1000,15.000000,45.000000,60.000000,USD"
+        );
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+
+    fn regional_summary(provider: &str, cost: f64, requests: i64) -> CostSummaryResponse {
+        let date = Utc.with_ymd_and_hms(2025, 11, 1, 0, 0, 0).unwrap();
+        CostSummaryResponse {
+            metadata: CostSummaryMetadata {
+                start_time: date,
+                end_time: date + chrono::Duration::days(1),
+                period_days: 1,
+                generated_at: date,
+                currency: CurrencyConversion::usd(cost),
+            },
+            overview: CostOverview {
+                total_cost: cost,
+                prompt_cost: cost / 2.0,
+                completion_cost: cost / 2.0,
+                total_requests: requests,
+                total_tokens: requests * 100,
+                avg_cost_per_request: cost / requests as f64,
+                avg_cost_per_1k_tokens: cost / (requests * 100) as f64 * 1000.0,
+                day_over_day_change: Some(5.0),
+                week_over_week_change: None,
+            },
+            by_provider: vec![CostBreakdownItem {
+                name: provider.to_string(),
+                cost,
+                requests,
+                percentage: 100.0,
+                avg_cost_per_request: cost / requests as f64,
+            }],
+            by_model: Vec::new(),
+            by_environment: Vec::new(),
+            trends: Some(CostTrends {
+                daily: vec![CostDataPoint {
+                    date,
+                    cost,
+                    requests,
+                }],
+                weekly: Vec::new(),
+                growth_rate_daily: 0.0,
+                growth_rate_weekly: 0.0,
+            }),
+            top_traces: None,
+            federation: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_cost_summaries() {
+        let merged = regional_summary("openai", 30.0, 10).merge(
+            vec![
+                regional_summary("openai", 10.0, 10),
+                regional_summary("anthropic", 60.0, 20),
+            ],
+            10,
+        );
+
+        assert_eq!(merged.overview.total_cost, 100.0);
+        assert_eq!(merged.overview.total_requests, 40);
+        assert_eq!(merged.overview.avg_cost_per_request, 2.5);
+        assert_eq!(merged.overview.avg_cost_per_1k_tokens, 25.0);
+        assert_eq!(merged.overview.day_over_day_change, None);
+        assert_eq!(merged.metadata.currency.original_total_usd, 100.0);
+
+        assert_eq!(merged.by_provider.len(), 2);
+        assert_eq!(merged.by_provider[0].name, "anthropic");
+        assert_eq!(merged.by_provider[1].name, "openai");
+        assert_eq!(merged.by_provider[1].cost, 40.0);
+        assert_eq!(merged.by_provider[1].percentage, 40.0);
+        assert_eq!(merged.by_provider[1].avg_cost_per_request, 2.0);
+
+        let trends = merged.trends.unwrap();
+        assert_eq!(trends.daily.len(), 1);
+        assert_eq!(trends.daily[0].cost, 100.0);
+        assert_eq!(trends.daily[0].requests, 40);
+    }
 }
@@ -0,0 +1,193 @@
+//! # Trace Deletion Data Models
+//!
+//! Data structures for bulk trace deletion (`DELETE /api/v1/traces`). The
+//! request selects spans with the same filter model as
+//! `POST /api/v1/traces/search`; every trace with at least one matching span
+//! is deleted together with all of its spans and logs.
+
+use crate::models::filters::Filter;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Number of matching trace IDs returned by a dry run
+pub const SAMPLE_TRACE_IDS: i64 = 20;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for DELETE /api/v1/traces
+#[derive(Debug, Deserialize, Clone)]
+pub struct DeleteTracesRequest {
+    /// Filter selecting spans (required, so a request can't match everything
+    /// by accident)
+    pub filter: Filter,
+
+    /// Only spans at or after this time (default: no lower bound)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// Only spans before this time (default: now; spans arriving while the
+    /// job runs are never deleted)
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Only report what would be deleted (default: true)
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+impl DeleteTracesRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        self.filter
+            .validate()
+            .map_err(|e| format!("Invalid filter: {}", e))?;
+
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            if start >= end {
+                return Err("Start time must be before end time".to_string());
+            }
+        }
+
+        if let Some(end) = self.end_time {
+            if end > Utc::now() {
+                return Err("End time must not be in the future".to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for a dry run of DELETE /api/v1/traces
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceDeletionPreview {
+    pub dry_run: bool,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: DateTime<Utc>,
+    /// Traces with at least one matching span
+    pub matched_traces: i64,
+    /// All spans of the matched traces
+    pub matched_spans: i64,
+    /// Logs of the matched traces
+    pub matched_logs: i64,
+    /// Up to 20 matching trace IDs, for spot checks
+    pub sample_trace_ids: Vec<String>,
+}
+
+/// Bulk deletion job, returned by DELETE /api/v1/traces (202) and
+/// GET /api/v1/traces/deletions/:job_id
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct TraceDeletionJob {
+    pub job_id: Uuid,
+    pub requested_by: String,
+    /// pending, running, completed or failed
+    pub status: String,
+    pub filter: serde_json::Value,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: DateTime<Utc>,
+    pub matched_traces: i64,
+    pub matched_spans: i64,
+    pub matched_logs: i64,
+    pub deleted_traces: i64,
+    pub deleted_spans: i64,
+    pub deleted_logs: i64,
+    pub batches_completed: i32,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl TraceDeletionJob {
+    /// Deleted traces as a percentage of the traces matched at creation.
+    ///
+    /// Capped at 100; traces matching only after creation (late spans within
+    /// the time range) are deleted as well.
+    pub fn progress_percent(&self) -> f64 {
+        if self.status == "completed" {
+            return 100.0;
+        }
+        if self.matched_traces == 0 {
+            return 0.0;
+        }
+        (self.deleted_traces as f64 / self.matched_traces as f64 * 100.0).min(100.0)
+    }
+}
+
+/// Job with its progress, as returned by the API
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceDeletionJobResponse {
+    #[serde(flatten)]
+    pub job: TraceDeletionJob,
+    pub progress_percent: f64,
+}
+
+impl From<TraceDeletionJob> for TraceDeletionJobResponse {
+    fn from(job: TraceDeletionJob) -> Self {
+        let progress_percent = job.progress_percent();
+        Self {
+            job,
+            progress_percent,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn request(json: serde_json::Value) -> DeleteTracesRequest {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_dry_run_by_default() {
+        let req = request(serde_json::json!({
+            "filter": {"field": "environment", "operator": "eq", "value": "load-test"}
+        }));
+        assert!(req.dry_run);
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn test_filter_required() {
+        assert!(
+            serde_json::from_value::<DeleteTracesRequest>(serde_json::json!({
+                "dry_run": false
+            }))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let now = Utc::now();
+        let mut req = request(serde_json::json!({
+            "filter": {"field": "1; DROP TABLE llm_traces", "operator": "eq", "value": "x"}
+        }));
+        assert!(req.validate().is_err());
+
+        req.filter = request(serde_json::json!({
+            "filter": {"field": "provider", "operator": "eq", "value": "openai"}
+        }))
+        .filter;
+        req.start_time = Some(now - Duration::hours(1));
+        req.end_time = Some(now - Duration::hours(2));
+        assert!(req.validate().is_err());
+
+        req.end_time = Some(now + Duration::hours(1));
+        assert!(req.validate().is_err());
+
+        req.end_time = Some(now);
+        assert!(req.validate().is_ok());
+    }
+}
@@ -0,0 +1,198 @@
+//! # Drift Monitoring Data Models
+//!
+//! Data structures for `GET /api/v1/drift/snapshots` and
+//! `GET /api/v1/drift/alerts`, which return the hourly prompt/response
+//! characteristics and drift alerts written by the drift monitor.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Query parameters for GET /api/v1/drift/snapshots
+#[derive(Debug, Deserialize, Clone)]
+pub struct DriftSnapshotsRequest {
+    /// Start time (default: 24 hours ago)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End time (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    pub provider: Option<String>,
+    pub model: Option<String>,
+
+    /// Prompt template, or span name of spans without one
+    pub workload: Option<String>,
+
+    /// Maximum snapshots returned (default: 500)
+    #[serde(default = "default_snapshot_limit")]
+    pub limit: i64,
+}
+
+fn default_snapshot_limit() -> i64 {
+    500
+}
+
+impl DriftSnapshotsRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_range(self.start_time, self.end_time)?;
+        if !(1..=5000).contains(&self.limit) {
+            return Err("Limit must be between 1 and 5000".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Query parameters for GET /api/v1/drift/alerts
+#[derive(Debug, Deserialize, Clone)]
+pub struct DriftAlertsRequest {
+    /// Start time (default: 7 days ago)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End time (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    pub provider: Option<String>,
+    pub model: Option<String>,
+
+    /// prompt_length, response_length, refusal_rate, language or embedding
+    pub metric: Option<String>,
+
+    /// Maximum alerts returned (default: 100)
+    #[serde(default = "default_alert_limit")]
+    pub limit: i64,
+}
+
+fn default_alert_limit() -> i64 {
+    100
+}
+
+impl DriftAlertsRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_range(self.start_time, self.end_time)?;
+        if let Some(metric) = &self.metric {
+            if !DRIFT_METRICS.contains(&metric.as_str()) {
+                return Err(format!(
+                    "Unknown metric '{}' (expected one of {})",
+                    metric,
+                    DRIFT_METRICS.join(", ")
+                ));
+            }
+        }
+        if !(1..=1000).contains(&self.limit) {
+            return Err("Limit must be between 1 and 1000".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Metrics of `drift_alerts`
+pub const DRIFT_METRICS: [&str; 5] = [
+    "prompt_length",
+    "response_length",
+    "refusal_rate",
+    "language",
+    "embedding",
+];
+
+fn validate_range(
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+) -> Result<(), String> {
+    if let (Some(start), Some(end)) = (start_time, end_time) {
+        if start >= end {
+            return Err("Start time must be before end time".to_string());
+        }
+
+        if (end - start).num_days() > 90 {
+            return Err("Maximum time range is 90 days".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Prompt/response characteristics of one series in one hour
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct DriftSnapshot {
+    pub provider: String,
+    pub model: String,
+    pub workload: String,
+    pub bucket: DateTime<Utc>,
+    pub request_count: i64,
+    pub sample_count: i64,
+    pub prompt_length_mean: f64,
+    pub prompt_length_stddev: f64,
+    pub response_length_mean: f64,
+    pub response_length_stddev: f64,
+    pub refusal_rate: f64,
+    /// Share of responses per detected language
+    pub languages: Json<BTreeMap<String, f64>>,
+}
+
+/// Response for GET /api/v1/drift/snapshots
+#[derive(Debug, Serialize)]
+pub struct DriftSnapshotsResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub snapshots: Vec<DriftSnapshot>,
+}
+
+/// A characteristic of one series that drifted from its baseline
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct DriftAlert {
+    pub id: Uuid,
+    pub provider: String,
+    pub model: String,
+    pub workload: String,
+    /// Hour whose snapshot drifted
+    pub bucket: DateTime<Utc>,
+    pub metric: String,
+    pub baseline_value: f64,
+    pub current_value: f64,
+    pub score: f64,
+    pub threshold: f64,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// Response for GET /api/v1/drift/alerts
+#[derive(Debug, Serialize)]
+pub struct DriftAlertsResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub alerts: Vec<DriftAlert>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_alerts_request() {
+        let mut request = DriftAlertsRequest {
+            start_time: None,
+            end_time: None,
+            provider: None,
+            model: Some("gpt-4o".to_string()),
+            metric: Some("refusal_rate".to_string()),
+            limit: default_alert_limit(),
+        };
+        assert!(request.validate().is_ok());
+
+        request.metric = Some("latency".to_string());
+        assert!(request.validate().is_err());
+
+        request.metric = None;
+        request.limit = 0;
+        assert!(request.validate().is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! # Trace Event Data Models
+//!
+//! Data structures for `GET /api/v1/traces/:trace_id/events`, which merges
+//! the events recorded on all spans of a trace (guardrail hits, tool results,
+//! stream chunks) into one timeline, oldest first.
+//!
+//! Events are read from the `trace_events` table, filled by the storage trace
+//! writer from the events of each span it inserts, and joined to their spans
+//! in `trace_spans` and trace in `traces`. The writer may store event
+//! attributes compressed or encrypted; see [`decode_attributes`].
+
+use chrono::{DateTime, Utc};
+use llm_observatory_storage::compression::decompress_json;
+use llm_observatory_storage::encryption::is_encrypted;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Longest event name filter
+const MAX_NAME_LEN: usize = 255;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for GET /api/v1/traces/:trace_id/events
+#[derive(Debug, Deserialize, Clone)]
+pub struct TraceEventsQuery {
+    /// Only events of this span
+    pub span_id: Option<String>,
+
+    /// Only events with this name
+    pub name: Option<String>,
+
+    /// Maximum events (default: 1000, max: 1000)
+    #[serde(default = "default_trace_events_limit")]
+    pub limit: i64,
+}
+
+fn default_trace_events_limit() -> i64 {
+    1000
+}
+
+impl TraceEventsQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.limit < 1 || self.limit > 1000 {
+            return Err(format!(
+                "Limit must be between 1 and 1000, got {}",
+                self.limit
+            ));
+        }
+
+        if let Some(span_id) = &self.span_id {
+            if span_id.is_empty()
+                || span_id.len() > 16
+                || !span_id.bytes().all(|b| b.is_ascii_hexdigit())
+            {
+                return Err(format!("Invalid span ID '{}'", span_id));
+            }
+        }
+
+        if let Some(name) = &self.name {
+            if name.is_empty() || name.len() > MAX_NAME_LEN {
+                return Err(format!(
+                    "Event name must be 1 to {} characters",
+                    MAX_NAME_LEN
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for GET /api/v1/traces/:trace_id/events
+#[derive(Debug, Serialize)]
+pub struct TraceEventsResponse {
+    pub trace_id: String,
+
+    /// Start of the trace, which event offsets are relative to; unknown
+    /// when no events match
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// Events of all spans, oldest first
+    pub events: Vec<TimelineEvent>,
+
+    /// Whether more than `limit` events match
+    pub truncated: bool,
+}
+
+/// An event on the trace timeline
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimelineEvent {
+    pub id: Uuid,
+    pub timestamp: DateTime<Utc>,
+
+    /// Milliseconds since the start of the trace
+    pub offset_ms: f64,
+
+    pub span_id: String,
+    pub span_name: String,
+    pub service_name: String,
+    pub name: String,
+    pub attributes: Value,
+}
+
+// ============================================================================
+// Database Row Types
+// ============================================================================
+
+/// An event joined with its span and trace
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TraceEventRow {
+    pub id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    pub span_id: String,
+    pub span_name: String,
+    pub service_name: String,
+    pub name: String,
+    pub attributes: Value,
+    pub trace_start: DateTime<Utc>,
+}
+
+/// Why stored event attributes cannot be returned
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributesError {
+    /// Encrypted by the storage writer, whose keys the API does not hold
+    Encrypted,
+    /// A compression envelope that does not decompress
+    Invalid(String),
+}
+
+/// Decode event attributes as stored by the storage trace writer
+///
+/// Compressed attributes (`{"$compressed": ..., "data": ...}`) are
+/// decompressed, so they can be masked and returned like plain ones.
+/// Encrypted attributes are refused: the API holds no keys to decrypt them,
+/// and masking the envelope would hide nothing.
+pub fn decode_attributes(attributes: Value) -> Result<Value, AttributesError> {
+    if is_encrypted(&attributes) {
+        return Err(AttributesError::Encrypted);
+    }
+
+    decompress_json(attributes).map_err(|e| AttributesError::Invalid(e.to_string()))
+}
+
+/// Place events on the trace timeline, in the order queried
+///
+/// Offsets are relative to the trace start, or the first event when an
+/// event precedes it (clock skew between services).
+pub fn build_timeline(
+    rows: Vec<TraceEventRow>,
+) -> (Option<DateTime<Utc>>, Vec<TimelineEvent>) {
+    let start_time = rows
+        .iter()
+        .map(|row| row.trace_start.min(row.timestamp))
+        .min();
+
+    let events = rows
+        .into_iter()
+        .map(|row| TimelineEvent {
+            id: row.id,
+            timestamp: row.timestamp,
+            offset_ms: start_time.map_or(0.0, |start| {
+                (row.timestamp - start).num_microseconds().unwrap_or(0) as f64 / 1000.0
+            }),
+            span_id: row.span_id,
+            span_name: row.span_name,
+            service_name: row.service_name,
+            name: row.name,
+            attributes: row.attributes,
+        })
+        .collect();
+
+    (start_time, events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use llm_observatory_storage::compression::compress_json;
+    use llm_observatory_storage::config::{CompressionAlgorithm, CompressionConfig};
+    use serde_json::json;
+
+    fn row(span_id: &str, name: &str, offset_ms: i64) -> TraceEventRow {
+        let trace_start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        TraceEventRow {
+            id: Uuid::new_v4(),
+            timestamp: trace_start + Duration::milliseconds(offset_ms),
+            span_id: span_id.to_string(),
+            span_name: "llm.chat".to_string(),
+            service_name: "api".to_string(),
+            name: name.to_string(),
+            attributes: serde_json::json!({}),
+            trace_start,
+        }
+    }
+
+    #[test]
+    fn test_build_timeline() {
+        let (start_time, events) = build_timeline(vec![
+            row("00f067aa0ba902b7", "guardrail.evaluation", 120),
+            row("53995c3f42cd8ad8", "tool.result", 1500),
+        ]);
+
+        assert_eq!(
+            start_time,
+            Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].offset_ms, 120.0);
+        assert_eq!(events[1].span_id, "53995c3f42cd8ad8");
+        assert_eq!(events[1].offset_ms, 1500.0);
+
+        // An event before the trace start moves the start back
+        let (start_time, events) =
+            build_timeline(vec![row("00f067aa0ba902b7", "llm.request.sent", -5)]);
+        assert_eq!(start_time, Some(events[0].timestamp));
+        assert_eq!(events[0].offset_ms, 0.0);
+
+        assert_eq!(build_timeline(Vec::new()), (None, Vec::new()));
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut query = TraceEventsQuery {
+            span_id: Some("00f067aa0ba902b7".to_string()),
+            name: None,
+            limit: default_trace_events_limit(),
+        };
+        assert!(query.validate().is_ok());
+
+        query.span_id = Some("not-a-span".to_string());
+        assert!(query.validate().is_err());
+
+        query.span_id = None;
+        query.limit = 0;
+        assert!(query.validate().is_err());
+    }
+
+    #[test]
+    fn test_decode_attributes() {
+        let attributes = json!({
+            "gen_ai.prompt": "What is my balance? ".repeat(100),
+            "chunk": 1
+        });
+        let config = CompressionConfig {
+            algorithm: CompressionAlgorithm::Zstd,
+            min_size_bytes: 64,
+            level: 3,
+        };
+        let compressed = compress_json(attributes.clone(), &config).unwrap();
+        assert!(compressed.get("$compressed").is_some());
+
+        assert_eq!(decode_attributes(compressed).unwrap(), attributes);
+        assert_eq!(
+            decode_attributes(json!({"chunk": 1})).unwrap(),
+            json!({"chunk": 1})
+        );
+
+        let invalid = json!({"$compressed": "zstd", "data": "not base64!"});
+        assert!(matches!(
+            decode_attributes(invalid),
+            Err(AttributesError::Invalid(_))
+        ));
+
+        let encrypted = json!({
+            "$encrypted": "aes-256-gcm",
+            "kid": "kek-1",
+            "dek": "ZGVr",
+            "nonce": "bm9uY2U=",
+            "data": "ZGF0YQ=="
+        });
+        assert_eq!(
+            decode_attributes(encrypted),
+            Err(AttributesError::Encrypted)
+        );
+    }
+}
@@ -0,0 +1,468 @@
+//! # Experiment Data Models
+//!
+//! Data structures for the A/B experiment endpoints. Spans are assigned to a
+//! variant by the SDK (`experiment.id` and `experiment.variant` attributes);
+//! results compare each variant against the control, which is the first
+//! variant of the experiment.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Two-sided z value for 95% confidence
+const Z_95: f64 = 1.96;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for POST /api/v1/experiments
+#[derive(Debug, Deserialize, Clone)]
+pub struct CreateExperimentRequest {
+    /// Identifier recorded by the SDK in `experiment.id`
+    pub experiment_id: String,
+
+    /// Human-readable name
+    pub name: String,
+
+    /// Optional description
+    pub description: Option<String>,
+
+    /// Variant names; the first one is the control
+    pub variants: Vec<String>,
+
+    /// Start time (default: now)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End time (default: open-ended until ended)
+    pub end_time: Option<DateTime<Utc>>,
+}
+
+impl CreateExperimentRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.experiment_id.trim().is_empty() || self.experiment_id.len() > 128 {
+            return Err("experiment_id must be between 1 and 128 characters".to_string());
+        }
+
+        if self.name.trim().is_empty() {
+            return Err("name must not be empty".to_string());
+        }
+
+        if self.variants.len() < 2 {
+            return Err("An experiment needs at least two variants".to_string());
+        }
+
+        if self.variants.iter().any(|v| v.trim().is_empty()) {
+            return Err("Variant names must not be empty".to_string());
+        }
+
+        for (i, variant) in self.variants.iter().enumerate() {
+            if self.variants[..i].contains(variant) {
+                return Err(format!("Duplicate variant '{}'", variant));
+            }
+        }
+
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            if start >= end {
+                return Err("Start time must be before end time".to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Request for POST /api/v1/experiments/:id/feedback
+#[derive(Debug, Deserialize, Clone)]
+pub struct RecordFeedbackRequest {
+    /// Trace the feedback is about
+    pub trace_id: String,
+
+    /// Span within the trace (optional)
+    pub span_id: Option<String>,
+
+    /// Feedback score, e.g. 1/0 for thumbs up/down or a 1-5 rating
+    pub score: f64,
+
+    /// Free-text comment
+    pub comment: Option<String>,
+}
+
+impl RecordFeedbackRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.trace_id.trim().is_empty() {
+            return Err("trace_id must not be empty".to_string());
+        }
+
+        if !self.score.is_finite() {
+            return Err("score must be a finite number".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// A registered experiment
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Experiment {
+    pub experiment_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub variants: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Experiment {
+    /// The control variant, which the others are compared against
+    pub fn control(&self) -> &str {
+        self.variants
+            .first()
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+}
+
+/// Response for GET /api/v1/experiments
+#[derive(Debug, Serialize)]
+pub struct ExperimentListResponse {
+    pub experiments: Vec<Experiment>,
+}
+
+/// Response for POST /api/v1/experiments/:id/feedback
+#[derive(Debug, Serialize)]
+pub struct FeedbackResponse {
+    pub feedback_id: Uuid,
+    pub experiment_id: String,
+    pub trace_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Response for GET /api/v1/experiments/:id/results
+#[derive(Debug, Serialize)]
+pub struct ExperimentResults {
+    pub experiment: Experiment,
+
+    /// Time range the results cover
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+
+    /// Results per variant, control first
+    pub variants: Vec<VariantResult>,
+
+    /// Spans tagged with the experiment but with no or an unregistered variant
+    pub unknown_variant_requests: i64,
+}
+
+/// Metrics for one variant
+#[derive(Debug, Clone, Serialize)]
+pub struct VariantResult {
+    pub variant: String,
+    pub is_control: bool,
+    pub request_count: i64,
+    pub error_count: i64,
+    pub error_rate: f64,
+    pub total_cost_usd: f64,
+    pub avg_cost_usd: f64,
+    pub total_tokens: i64,
+    pub avg_latency_ms: Option<f64>,
+    pub p95_latency_ms: Option<f64>,
+    pub feedback_count: i64,
+    pub avg_feedback_score: Option<f64>,
+
+    /// Difference from the control (None for the control itself)
+    pub vs_control: Option<VariantComparison>,
+}
+
+/// Difference between a variant and the control
+#[derive(Debug, Clone, Serialize)]
+pub struct VariantComparison {
+    /// Relative change in average cost per request, in percent
+    pub avg_cost_change_pct: Option<f64>,
+
+    /// Relative change in average latency, in percent
+    pub avg_latency_change_pct: Option<f64>,
+
+    /// Relative change in P95 latency, in percent
+    pub p95_latency_change_pct: Option<f64>,
+
+    /// Absolute change in error rate (0.01 = one percentage point)
+    pub error_rate_change: f64,
+
+    /// Two-proportion z-score of the error rate difference
+    pub error_rate_z_score: Option<f64>,
+
+    /// Whether the error rate difference is significant at 95% confidence
+    pub error_rate_significant: bool,
+
+    /// Absolute change in average feedback score
+    pub feedback_score_change: Option<f64>,
+}
+
+// ============================================================================
+// Database Row Types
+// ============================================================================
+
+/// Span statistics per variant
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ExperimentVariantRow {
+    pub variant: Option<String>,
+    pub request_count: i64,
+    pub error_count: i64,
+    pub total_cost_usd: Option<f64>,
+    pub total_tokens: Option<i64>,
+    pub avg_duration_ms: Option<f64>,
+    pub p95_duration_ms: Option<f64>,
+}
+
+/// Feedback statistics per variant
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ExperimentFeedbackRow {
+    pub variant: Option<String>,
+    pub feedback_count: i64,
+    pub avg_score: Option<f64>,
+}
+
+// ============================================================================
+// Results
+// ============================================================================
+
+/// Combine per-variant span and feedback statistics into experiment results
+pub fn build_experiment_results(
+    experiment: Experiment,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    rows: &[ExperimentVariantRow],
+    feedback: &[ExperimentFeedbackRow],
+) -> ExperimentResults {
+    let mut variants: Vec<VariantResult> = experiment
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(i, variant)| {
+            let row = rows
+                .iter()
+                .find(|r| r.variant.as_deref() == Some(variant.as_str()));
+            let fb = feedback
+                .iter()
+                .find(|f| f.variant.as_deref() == Some(variant.as_str()));
+
+            let request_count = row.map_or(0, |r| r.request_count);
+            let error_count = row.map_or(0, |r| r.error_count);
+            let total_cost_usd = row.and_then(|r| r.total_cost_usd).unwrap_or(0.0);
+
+            VariantResult {
+                variant: variant.clone(),
+                is_control: i == 0,
+                request_count,
+                error_count,
+                error_rate: ratio(error_count as f64, request_count),
+                total_cost_usd,
+                avg_cost_usd: ratio(total_cost_usd, request_count),
+                total_tokens: row.and_then(|r| r.total_tokens).unwrap_or(0),
+                avg_latency_ms: row.and_then(|r| r.avg_duration_ms),
+                p95_latency_ms: row.and_then(|r| r.p95_duration_ms),
+                feedback_count: fb.map_or(0, |f| f.feedback_count),
+                avg_feedback_score: fb.and_then(|f| f.avg_score),
+                vs_control: None,
+            }
+        })
+        .collect();
+
+    if let Some((control, others)) = variants.split_first_mut() {
+        for variant in others {
+            variant.vs_control = Some(compare_to_control(control, variant));
+        }
+    }
+
+    let unknown_variant_requests = rows
+        .iter()
+        .filter(|r| {
+            r.variant
+                .as_ref()
+                .map_or(true, |v| !experiment.variants.contains(v))
+        })
+        .map(|r| r.request_count)
+        .sum();
+
+    ExperimentResults {
+        experiment,
+        start_time,
+        end_time,
+        variants,
+        unknown_variant_requests,
+    }
+}
+
+fn compare_to_control(control: &VariantResult, variant: &VariantResult) -> VariantComparison {
+    let change_pct = |value: Option<f64>, base: Option<f64>| match (value, base) {
+        (Some(value), Some(base)) if base > 0.0 => Some((value - base) / base * 100.0),
+        _ => None,
+    };
+    let has_requests = |v: &VariantResult| (v.request_count > 0).then_some(v.avg_cost_usd);
+
+    let error_rate_z_score = two_proportion_z(
+        control.error_count,
+        control.request_count,
+        variant.error_count,
+        variant.request_count,
+    );
+
+    VariantComparison {
+        avg_cost_change_pct: change_pct(has_requests(variant), has_requests(control)),
+        avg_latency_change_pct: change_pct(variant.avg_latency_ms, control.avg_latency_ms),
+        p95_latency_change_pct: change_pct(variant.p95_latency_ms, control.p95_latency_ms),
+        error_rate_change: variant.error_rate - control.error_rate,
+        error_rate_z_score,
+        error_rate_significant: error_rate_z_score.is_some_and(|z| z.abs() >= Z_95),
+        feedback_score_change: match (variant.avg_feedback_score, control.avg_feedback_score) {
+            (Some(variant), Some(control)) => Some(variant - control),
+            _ => None,
+        },
+    }
+}
+
+/// Pooled two-proportion z-score for `errors_b / total_b` vs `errors_a / total_a`
+fn two_proportion_z(errors_a: i64, total_a: i64, errors_b: i64, total_b: i64) -> Option<f64> {
+    if total_a == 0 || total_b == 0 {
+        return None;
+    }
+
+    let (n_a, n_b) = (total_a as f64, total_b as f64);
+    let pooled = (errors_a + errors_b) as f64 / (n_a + n_b);
+    let std_err = (pooled * (1.0 - pooled) * (1.0 / n_a + 1.0 / n_b)).sqrt();
+    if std_err == 0.0 {
+        return None;
+    }
+
+    Some((errors_b as f64 / n_b - errors_a as f64 / n_a) / std_err)
+}
+
+fn ratio(value: f64, count: i64) -> f64 {
+    if count > 0 {
+        value / count as f64
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn experiment() -> Experiment {
+        let now = Utc::now();
+        Experiment {
+            experiment_id: "prompt-v2".to_string(),
+            name: "Shorter system prompt".to_string(),
+            description: None,
+            variants: vec!["control".to_string(), "short".to_string()],
+            started_at: now - chrono::Duration::days(3),
+            ended_at: None,
+            created_at: now - chrono::Duration::days(3),
+        }
+    }
+
+    fn row(
+        variant: Option<&str>,
+        requests: i64,
+        errors: i64,
+        cost: f64,
+        avg_ms: f64,
+    ) -> ExperimentVariantRow {
+        ExperimentVariantRow {
+            variant: variant.map(str::to_string),
+            request_count: requests,
+            error_count: errors,
+            total_cost_usd: Some(cost),
+            total_tokens: Some(requests * 100),
+            avg_duration_ms: Some(avg_ms),
+            p95_duration_ms: Some(avg_ms * 2.0),
+        }
+    }
+
+    #[test]
+    fn test_build_experiment_results() {
+        let rows = vec![
+            row(Some("control"), 1000, 50, 10.0, 800.0),
+            row(Some("short"), 1000, 20, 8.0, 600.0),
+            row(Some("typo"), 7, 0, 0.1, 500.0),
+            row(None, 3, 0, 0.0, 500.0),
+        ];
+        let feedback = vec![
+            ExperimentFeedbackRow {
+                variant: Some("control".to_string()),
+                feedback_count: 40,
+                avg_score: Some(0.6),
+            },
+            ExperimentFeedbackRow {
+                variant: Some("short".to_string()),
+                feedback_count: 45,
+                avg_score: Some(0.7),
+            },
+        ];
+
+        let now = Utc::now();
+        let results = build_experiment_results(experiment(), now, now, &rows, &feedback);
+        assert_eq!(results.unknown_variant_requests, 10);
+        assert_eq!(results.variants.len(), 2);
+
+        let control = &results.variants[0];
+        assert!(control.is_control);
+        assert!(control.vs_control.is_none());
+        assert_eq!(control.error_rate, 0.05);
+
+        let short = &results.variants[1];
+        let comparison = short.vs_control.as_ref().unwrap();
+        assert!((comparison.avg_cost_change_pct.unwrap() + 20.0).abs() < 1e-9);
+        assert!((comparison.avg_latency_change_pct.unwrap() + 25.0).abs() < 1e-9);
+        assert!((comparison.error_rate_change + 0.03).abs() < 1e-9);
+        assert!(comparison.error_rate_significant);
+        assert!(comparison.error_rate_z_score.unwrap() < 0.0);
+        assert!((comparison.feedback_score_change.unwrap() - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_variant_without_traffic() {
+        let rows = vec![row(Some("control"), 100, 1, 1.0, 500.0)];
+        let now = Utc::now();
+        let results = build_experiment_results(experiment(), now, now, &rows, &[]);
+
+        let short = &results.variants[1];
+        assert_eq!(short.request_count, 0);
+        let comparison = short.vs_control.as_ref().unwrap();
+        assert_eq!(comparison.avg_cost_change_pct, None);
+        assert_eq!(comparison.error_rate_z_score, None);
+        assert!(!comparison.error_rate_significant);
+    }
+
+    #[test]
+    fn test_create_request_validation() {
+        let request = CreateExperimentRequest {
+            experiment_id: "prompt-v2".to_string(),
+            name: "Shorter prompt".to_string(),
+            description: None,
+            variants: vec!["control".to_string(), "short".to_string()],
+            start_time: None,
+            end_time: None,
+        };
+        assert!(request.validate().is_ok());
+
+        let single = CreateExperimentRequest {
+            variants: vec!["control".to_string()],
+            ..request.clone()
+        };
+        assert!(single.validate().is_err());
+
+        let duplicate = CreateExperimentRequest {
+            variants: vec!["a".to_string(), "b".to_string(), "a".to_string()],
+            ..request
+        };
+        assert!(duplicate.validate().is_err());
+    }
+}
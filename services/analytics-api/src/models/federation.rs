@@ -0,0 +1,57 @@
+//! # Federation Data Models
+//!
+//! Scope selection and partial-failure annotations for endpoints that can
+//! aggregate over every storage region (`?scope=global`).
+
+use serde::{Deserialize, Serialize};
+
+/// Regions an aggregate covers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// The region of this API's database
+    #[default]
+    Region,
+    /// Every configured region, merged
+    Global,
+}
+
+/// How a global result was assembled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FederationInfo {
+    /// Regions whose data is included
+    pub regions: Vec<String>,
+
+    /// Regions that could not be queried; their data is missing
+    pub failed_regions: Vec<RegionFailure>,
+
+    /// Whether any region is missing from the result
+    pub partial: bool,
+}
+
+/// A region left out of a global result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionFailure {
+    pub region: String,
+    pub error: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_deserialization() {
+        #[derive(Deserialize)]
+        struct Params {
+            #[serde(default)]
+            scope: Scope,
+        }
+
+        let params: Params = serde_json::from_str(r#"{"scope": "global"}"#).unwrap();
+        assert_eq!(params.scope, Scope::Global);
+        let params: Params = serde_json::from_str("{}").unwrap();
+        assert_eq!(params.scope, Scope::Region);
+        assert!(serde_json::from_str::<Params>(r#"{"scope": "world"}"#).is_err());
+    }
+}
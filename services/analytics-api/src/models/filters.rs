@@ -5,6 +5,7 @@
 ///! - Collection operators (in, not_in, contains, not_contains)
 ///! - Logical operators (AND, OR, NOT)
 ///! - Full-text search support
+///! - JSONB attribute filters (`attributes.<key>`) backed by GIN indexes
 ///! - SQL injection prevention
 ///! - Filter validation
 
@@ -44,6 +45,8 @@ pub enum FilterOperator {
     Regex,
     /// Full-text search
     Search,
+    /// Attribute key exists (`true`) or is absent (`false`)
+    Exists,
 }
 
 impl fmt::Display for FilterOperator {
@@ -63,6 +66,7 @@ impl fmt::Display for FilterOperator {
             FilterOperator::EndsWith => write!(f, "ILIKE"),
             FilterOperator::Regex => write!(f, "~*"),
             FilterOperator::Search => write!(f, "@@"),
+            FilterOperator::Exists => write!(f, "?"),
         }
     }
 }
@@ -107,6 +111,20 @@ impl FilterValue {
         }
     }
 
+    /// Convert to a JSON value for JSONB containment queries
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or(serde_json::Value::Null)
+    }
+
+    /// Convert to a raw (unquoted) parameter string
+    fn to_param_string(&self) -> String {
+        match self {
+            FilterValue::String(s) => s.clone(),
+            FilterValue::DateTime(dt) => dt.to_rfc3339(),
+            other => other.to_json().to_string(),
+        }
+    }
+
     /// Check if value is valid for the operator
     pub fn is_valid_for_operator(&self, operator: &FilterOperator) -> bool {
         match operator {
@@ -119,6 +137,7 @@ impl FilterValue {
             FilterOperator::Gt | FilterOperator::Gte | FilterOperator::Lt | FilterOperator::Lte => {
                 matches!(self, FilterValue::Int(_) | FilterValue::Float(_) | FilterValue::DateTime(_))
             }
+            FilterOperator::Exists => matches!(self, FilterValue::Bool(_)),
             FilterOperator::Eq | FilterOperator::Ne => true,
         }
     }
@@ -154,8 +173,17 @@ impl FieldFilter {
         Ok(())
     }
 
+    /// Attribute key if the filter targets `attributes.<key>`
+    pub fn attribute_key(&self) -> Option<&str> {
+        self.field.strip_prefix("attributes.")
+    }
+
     /// Check if field name is valid (whitelist approach)
     fn is_valid_field_name(&self, field: &str) -> bool {
+        if let Some(key) = field.strip_prefix("attributes.") {
+            return is_valid_attribute_key(key);
+        }
+
         matches!(
             field,
             "ts"
@@ -193,6 +221,10 @@ impl FieldFilter {
     pub fn to_sql(&self, param_index: &mut i32) -> Result<(String, Vec<String>), String> {
         self.validate()?;
 
+        if let Some(key) = self.attribute_key() {
+            return self.attribute_to_sql(key, param_index);
+        }
+
         let field = &self.field;
         let mut params = Vec::new();
 
@@ -293,7 +325,7 @@ impl FieldFilter {
             }
             FilterOperator::Search => {
                 if let FilterValue::String(s) = &self.value {
-                    params.push(s.clone());
+                    params.push(normalize_search_query(s)?);
 
                     // Use pre-computed tsvector columns with GIN indexes for optimal performance
                     // Maps field names to their corresponding tsvector columns
@@ -304,18 +336,251 @@ impl FieldFilter {
                         _ => "content_search",
                     };
 
-                    // Use plainto_tsquery for simple query parsing (handles multi-word queries)
+                    // Use websearch_to_tsquery so phrases, OR and negation work
                     // The GIN index on the tsvector column makes this very fast
-                    format!("{} @@ plainto_tsquery('english', ${})", search_column, param_index)
+                    format!("{} @@ websearch_to_tsquery('english', ${})", search_column, param_index)
                 } else {
                     return Err("SEARCH operator requires string value".to_string());
                 }
             }
+            FilterOperator::Exists => {
+                // Doesn't consume a placeholder
+                return match &self.value {
+                    FilterValue::Bool(true) => Ok((format!("{} IS NOT NULL", field), params)),
+                    FilterValue::Bool(false) => Ok((format!("{} IS NULL", field), params)),
+                    _ => Err("EXISTS operator requires boolean value".to_string()),
+                };
+            }
         };
 
         *param_index += 1;
         Ok((condition, params))
     }
+
+    /// Convert an `attributes.<key>` filter to SQL.
+    ///
+    /// Equality, IN and EXISTS use JSONB containment (`@>`) and key existence
+    /// (`?`) so the GIN index on `attributes` applies. Comparisons cast the
+    /// value only when it is a JSON number, or an ISO 8601 string for
+    /// timestamps, so one malformed attribute cannot fail the whole query.
+    /// Other operators compare the extracted text value. The key is always
+    /// bound as a parameter.
+    fn attribute_to_sql(&self, key: &str, param_index: &mut i32) -> Result<(String, Vec<String>), String> {
+        let containment = |value: serde_json::Value| {
+            let mut map = serde_json::Map::new();
+            map.insert(key.to_string(), value);
+            serde_json::Value::Object(map).to_string()
+        };
+
+        let mut params = Vec::new();
+
+        let condition = match &self.operator {
+            FilterOperator::Eq => {
+                params.push(containment(self.value.to_json()));
+                format!("attributes @> ${}::jsonb", param_index)
+            }
+            FilterOperator::Ne => {
+                params.push(containment(self.value.to_json()));
+                format!("NOT (attributes @> ${}::jsonb)", param_index)
+            }
+            FilterOperator::In | FilterOperator::NotIn => {
+                let values: Vec<serde_json::Value> = match self.value.to_json() {
+                    serde_json::Value::Array(values) if !values.is_empty() => values,
+                    _ => return Err("IN operator requires a non-empty array value".to_string()),
+                };
+
+                let conditions: Vec<String> = values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        params.push(containment(value));
+                        format!("attributes @> ${}::jsonb", *param_index + i as i32)
+                    })
+                    .collect();
+                *param_index += conditions.len() as i32 - 1;
+
+                let any = format!("({})", conditions.join(" OR "));
+                if self.operator == FilterOperator::In {
+                    any
+                } else {
+                    format!("NOT {}", any)
+                }
+            }
+            FilterOperator::Exists => {
+                params.push(key.to_string());
+                match &self.value {
+                    FilterValue::Bool(true) => format!("attributes ? ${}", param_index),
+                    _ => format!("NOT (attributes ? ${})", param_index),
+                }
+            }
+            FilterOperator::Gt | FilterOperator::Gte | FilterOperator::Lt | FilterOperator::Lte => {
+                // Only values of the compared type are cast; any other value
+                // makes the condition NULL rather than failing the query
+                let key_param = *param_index;
+                let (guard, cast) = match self.value {
+                    FilterValue::Int(_) | FilterValue::Float(_) => (
+                        format!("jsonb_typeof(attributes->${}) = 'number'", key_param),
+                        "numeric",
+                    ),
+                    FilterValue::DateTime(_) => (
+                        format!("attributes->>${} ~ '{}'", key_param, TIMESTAMP_PATTERN),
+                        "timestamptz",
+                    ),
+                    _ => {
+                        return Err(format!(
+                            "{:?} operator requires a numeric or timestamp value",
+                            self.operator
+                        ))
+                    }
+                };
+                params.push(key.to_string());
+                params.push(self.value.to_param_string());
+                *param_index += 1;
+                format!(
+                    "CASE WHEN {} THEN (attributes->>${})::{} END {} ${}::{}",
+                    guard, key_param, cast, self.operator, param_index, cast
+                )
+            }
+            FilterOperator::Contains
+            | FilterOperator::NotContains
+            | FilterOperator::StartsWith
+            | FilterOperator::EndsWith
+            | FilterOperator::Regex => {
+                let s = match &self.value {
+                    FilterValue::String(s) => s,
+                    _ => return Err(format!("{:?} operator requires string value", self.operator)),
+                };
+                let pattern = match self.operator {
+                    FilterOperator::Contains | FilterOperator::NotContains => format!("%{}%", s),
+                    FilterOperator::StartsWith => format!("{}%", s),
+                    FilterOperator::EndsWith => format!("%{}", s),
+                    _ => s.clone(),
+                };
+                params.push(key.to_string());
+                params.push(pattern);
+                *param_index += 1;
+                format!("attributes->>${} {} ${}", *param_index - 1, self.operator, param_index)
+            }
+            FilterOperator::Search => {
+                return Err("SEARCH operator is not supported on attributes".to_string());
+            }
+        };
+
+        *param_index += 1;
+        Ok((condition, params))
+    }
+}
+
+/// ISO 8601 timestamps, the only attribute strings cast for comparisons
+const TIMESTAMP_PATTERN: &str =
+    r"^\d{4}-\d{2}-\d{2}([T ]\d{2}:\d{2}(:\d{2}(\.\d+)?)?(Z|[+-]\d{2}(:?\d{2})?)?)?$";
+
+/// Check if an attribute key is safe to use in a filter.
+///
+/// Allows OTel-style keys such as `gen_ai.request.model`.
+pub fn is_valid_attribute_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= 128
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | ':'))
+}
+
+/// Maximum length of a full-text search query.
+const MAX_SEARCH_QUERY_LEN: usize = 512;
+
+/// Validate a full-text search query and normalize it for `websearch_to_tsquery`.
+///
+/// Supported syntax:
+/// - `timeout error` - both terms must match (implicit AND, `AND` is also accepted)
+/// - `"rate limit"` - exact phrase
+/// - `timeout OR deadline` - either term
+/// - `-retry` or `NOT retry` - exclude a term or phrase
+///
+/// Operators are case-insensitive. Returns an error for empty queries,
+/// unbalanced quotes and dangling operators, which `websearch_to_tsquery`
+/// would otherwise silently ignore.
+pub fn normalize_search_query(input: &str) -> Result<String, String> {
+    if input.len() > MAX_SEARCH_QUERY_LEN {
+        return Err(format!(
+            "Search query cannot exceed {} characters",
+            MAX_SEARCH_QUERY_LEN
+        ));
+    }
+
+    if input.matches('"').count() % 2 != 0 {
+        return Err("Search query has an unbalanced quote".to_string());
+    }
+
+    // Split into terms and quoted phrases
+    let mut tokens: Vec<(String, bool)> = Vec::new(); // (text, is_phrase)
+    for (i, part) in input.split('"').enumerate() {
+        if i % 2 == 1 {
+            let phrase = part.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !phrase.is_empty() {
+                tokens.push((phrase, true));
+            }
+        } else {
+            tokens.extend(part.split_whitespace().map(|t| (t.to_string(), false)));
+        }
+    }
+
+    let mut out: Vec<String> = Vec::new();
+    let mut negate = false;
+    let mut pending_or = false;
+
+    for (text, is_phrase) in tokens {
+        if !is_phrase {
+            match text.to_ascii_uppercase().as_str() {
+                "AND" | "&&" => {
+                    if out.is_empty() || pending_or || negate {
+                        return Err("AND must appear between two search terms".to_string());
+                    }
+                    continue;
+                }
+                "OR" | "||" => {
+                    if out.is_empty() || pending_or || negate {
+                        return Err("OR must appear between two search terms".to_string());
+                    }
+                    pending_or = true;
+                    continue;
+                }
+                "NOT" | "-" => {
+                    negate = true;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        let (text, negated) = match text.strip_prefix('-') {
+            Some(rest) if !is_phrase && !rest.is_empty() => (rest.to_string(), true),
+            _ => (text, negate),
+        };
+
+        if pending_or {
+            out.push("or".to_string());
+            pending_or = false;
+        }
+
+        let prefix = if negated { "-" } else { "" };
+        if is_phrase {
+            out.push(format!("{}\"{}\"", prefix, text));
+        } else {
+            out.push(format!("{}{}", prefix, text));
+        }
+        negate = false;
+    }
+
+    if pending_or || negate {
+        return Err("Search query ends with an operator".to_string());
+    }
+
+    if out.is_empty() {
+        return Err("Search query cannot be empty".to_string());
+    }
+
+    Ok(out.join(" "))
 }
 
 /// Logical operator for combining filters
@@ -363,6 +628,14 @@ impl Filter {
         }
     }
 
+    /// Field names referenced anywhere in the filter
+    pub fn fields(&self) -> Vec<&str> {
+        match self {
+            Filter::Field(field_filter) => vec![field_filter.field.as_str()],
+            Filter::Logical { filters, .. } => filters.iter().flat_map(Filter::fields).collect(),
+        }
+    }
+
     /// Convert to SQL WHERE clause
     pub fn to_sql(&self, param_index: &mut i32) -> Result<(String, Vec<String>), String> {
         self.validate()?;
@@ -480,6 +753,7 @@ impl AdvancedSearchRequest {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
 
     #[test]
     fn test_field_filter_validation() {
@@ -541,6 +815,132 @@ mod tests {
         assert!(sql.contains("AND"));
     }
 
+    #[test]
+    fn test_attribute_eq_uses_containment() {
+        let filter = FieldFilter {
+            field: "attributes.gen_ai.system".to_string(),
+            operator: FilterOperator::Eq,
+            value: FilterValue::String("openai".to_string()),
+        };
+
+        let mut param_index = 3;
+        let (sql, params) = filter.to_sql(&mut param_index).unwrap();
+        assert_eq!(sql, "attributes @> $3::jsonb");
+        assert_eq!(params, vec![r#"{"gen_ai.system":"openai"}"#.to_string()]);
+        assert_eq!(param_index, 4);
+    }
+
+    #[test]
+    fn test_attribute_in_and_compare() {
+        let filter = FieldFilter {
+            field: "attributes.region".to_string(),
+            operator: FilterOperator::In,
+            value: FilterValue::Array(vec!["us".to_string(), "eu".to_string()]),
+        };
+        let mut param_index = 1;
+        let (sql, params) = filter.to_sql(&mut param_index).unwrap();
+        assert_eq!(sql, "(attributes @> $1::jsonb OR attributes @> $2::jsonb)");
+        assert_eq!(params.len(), 2);
+        assert_eq!(param_index, 3);
+
+        let filter = FieldFilter {
+            field: "attributes.retry_count".to_string(),
+            operator: FilterOperator::Gte,
+            value: FilterValue::Int(2),
+        };
+        let (sql, params) = filter.to_sql(&mut param_index).unwrap();
+        assert_eq!(
+            sql,
+            "CASE WHEN jsonb_typeof(attributes->$3) = 'number' \
+             THEN (attributes->>$3)::numeric END >= $4::numeric"
+        );
+        assert_eq!(params, vec!["retry_count".to_string(), "2".to_string()]);
+        assert_eq!(param_index, 5);
+    }
+
+    #[test]
+    fn test_attribute_compare_guards_casts() {
+        let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let filter = FieldFilter {
+            field: "attributes.cache.expires_at".to_string(),
+            operator: FilterOperator::Lt,
+            value: FilterValue::DateTime(since),
+        };
+        let mut param_index = 1;
+        let (sql, params) = filter.to_sql(&mut param_index).unwrap();
+        assert_eq!(
+            sql,
+            format!(
+                "CASE WHEN attributes->>$1 ~ '{}' THEN (attributes->>$1)::timestamptz END \
+                 < $2::timestamptz",
+                TIMESTAMP_PATTERN
+            )
+        );
+        assert_eq!(params[1], since.to_rfc3339());
+
+        // A string would be cast as a number and fail the query on any
+        // non-numeric attribute
+        let filter = FieldFilter {
+            field: "attributes.retry_count".to_string(),
+            operator: FilterOperator::Gt,
+            value: FilterValue::String("2".to_string()),
+        };
+        assert!(filter.validate().is_err());
+        assert!(filter.to_sql(&mut param_index).is_err());
+    }
+
+    #[test]
+    fn test_attribute_exists() {
+        let filter = FieldFilter {
+            field: "attributes.user.tier".to_string(),
+            operator: FilterOperator::Exists,
+            value: FilterValue::Bool(true),
+        };
+        let mut param_index = 1;
+        let (sql, params) = filter.to_sql(&mut param_index).unwrap();
+        assert_eq!(sql, "attributes ? $1");
+        assert_eq!(params, vec!["user.tier".to_string()]);
+    }
+
+    #[test]
+    fn test_attribute_key_validation() {
+        let filter = FieldFilter {
+            field: "attributes.x'; DROP TABLE llm_traces; --".to_string(),
+            operator: FilterOperator::Eq,
+            value: FilterValue::String("y".to_string()),
+        };
+        assert!(filter.validate().is_err());
+        assert!(is_valid_attribute_key("gen_ai.request.model"));
+        assert!(!is_valid_attribute_key(""));
+    }
+
+    #[test]
+    fn test_normalize_search_query() {
+        assert_eq!(normalize_search_query("timeout error").unwrap(), "timeout error");
+        assert_eq!(normalize_search_query("timeout AND error").unwrap(), "timeout error");
+        assert_eq!(
+            normalize_search_query("\"rate  limit\" OR timeout").unwrap(),
+            "\"rate limit\" or timeout"
+        );
+        assert_eq!(
+            normalize_search_query("error NOT retry -\"try again\"").unwrap(),
+            "error -retry -\"try again\""
+        );
+        assert_eq!(normalize_search_query("a or b").unwrap(), "a or b");
+    }
+
+    #[test]
+    fn test_normalize_search_query_errors() {
+        assert!(normalize_search_query("").is_err());
+        assert!(normalize_search_query("   ").is_err());
+        assert!(normalize_search_query("\"unterminated").is_err());
+        assert!(normalize_search_query("OR timeout").is_err());
+        assert!(normalize_search_query("timeout OR").is_err());
+        assert!(normalize_search_query("timeout OR AND error").is_err());
+        assert!(normalize_search_query("timeout NOT").is_err());
+        assert!(normalize_search_query(&"a ".repeat(300)).is_err());
+    }
+
     #[test]
     fn test_in_operator() {
         let filter = FieldFilter {
@@ -591,7 +991,7 @@ mod tests {
         // Should use input_text_search tsvector column
         assert!(sql.contains("input_text_search"));
         assert!(sql.contains("@@"));
-        assert!(sql.contains("plainto_tsquery"));
+        assert!(sql.contains("websearch_to_tsquery"));
         assert_eq!(params.len(), 1);
         assert_eq!(params[0], "authentication error");
     }
@@ -613,7 +1013,7 @@ mod tests {
         // Should use output_text_search tsvector column
         assert!(sql.contains("output_text_search"));
         assert!(sql.contains("@@"));
-        assert!(sql.contains("plainto_tsquery"));
+        assert!(sql.contains("websearch_to_tsquery"));
         assert_eq!(params.len(), 1);
     }
 
@@ -634,7 +1034,7 @@ mod tests {
         // Should use content_search tsvector column (combined)
         assert!(sql.contains("content_search"));
         assert!(sql.contains("@@"));
-        assert!(sql.contains("plainto_tsquery"));
+        assert!(sql.contains("websearch_to_tsquery"));
         assert_eq!(params.len(), 1);
     }
 
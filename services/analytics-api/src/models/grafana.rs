@@ -0,0 +1,631 @@
+//! # Grafana JSON Datasource Models
+//!
+//! Request and response shapes of the Grafana JSON / SimpleJSON datasource
+//! contract, served from the TimescaleDB continuous aggregates.
+//!
+//! ## Targets
+//! A query target is a metric name, optionally split into one series per
+//! dimension value:
+//! - `request_count`
+//! - `total_cost_usd by model`
+//!
+//! ## Annotations
+//! The annotation query is `incidents` or `incidents:<provider>` and returns
+//! upstream provider incidents as region annotations.
+
+use crate::models::providers::ProviderIncident;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Upper bound on datapoints per series when Grafana sends none
+const DEFAULT_MAX_DATA_POINTS: i64 = 1000;
+
+/// Ranges longer than this are always served from hourly rollups
+const MAX_MINUTE_RANGE_HOURS: i64 = 24;
+
+// ============================================================================
+// Enums and Types
+// ============================================================================
+
+/// Metrics exposed to Grafana
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrafanaMetric {
+    RequestCount,
+    TotalCost,
+    TotalTokens,
+    AvgDuration,
+    MaxDuration,
+    ErrorCount,
+    ErrorRate,
+}
+
+impl GrafanaMetric {
+    pub const ALL: [GrafanaMetric; 7] = [
+        GrafanaMetric::RequestCount,
+        GrafanaMetric::TotalCost,
+        GrafanaMetric::TotalTokens,
+        GrafanaMetric::AvgDuration,
+        GrafanaMetric::MaxDuration,
+        GrafanaMetric::ErrorCount,
+        GrafanaMetric::ErrorRate,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GrafanaMetric::RequestCount => "request_count",
+            GrafanaMetric::TotalCost => "total_cost_usd",
+            GrafanaMetric::TotalTokens => "total_tokens",
+            GrafanaMetric::AvgDuration => "avg_duration_ms",
+            GrafanaMetric::MaxDuration => "max_duration_ms",
+            GrafanaMetric::ErrorCount => "error_count",
+            GrafanaMetric::ErrorRate => "error_rate",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.name() == name)
+    }
+
+    /// SQL expression re-aggregating the metric across rollup rows
+    pub fn to_sql(&self, source: AggregateSource) -> &'static str {
+        match (self, source) {
+            (GrafanaMetric::RequestCount, _) => "SUM(request_count)",
+            (GrafanaMetric::TotalCost, _) => "SUM(total_cost_usd)",
+            (GrafanaMetric::TotalTokens, _) => "SUM(total_tokens)",
+            (GrafanaMetric::AvgDuration, _) => {
+                "SUM(avg_duration_ms * request_count) / NULLIF(SUM(request_count), 0)"
+            }
+            (GrafanaMetric::MaxDuration, _) => "MAX(max_duration_ms)",
+            // The 1-minute rollup is grouped by status_code instead of
+            // carrying an error_count column
+            (GrafanaMetric::ErrorCount, AggregateSource::OneMinute) => {
+                "SUM(request_count) FILTER (WHERE status_code = 'ERROR')"
+            }
+            (GrafanaMetric::ErrorCount, AggregateSource::OneHour) => "SUM(error_count)",
+            (GrafanaMetric::ErrorRate, AggregateSource::OneMinute) => {
+                "SUM(request_count) FILTER (WHERE status_code = 'ERROR')::DOUBLE PRECISION / NULLIF(SUM(request_count), 0)"
+            }
+            (GrafanaMetric::ErrorRate, AggregateSource::OneHour) => {
+                "SUM(error_count)::DOUBLE PRECISION / NULLIF(SUM(request_count), 0)"
+            }
+        }
+    }
+}
+
+/// Dimensions usable in `by` clauses and ad hoc filters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrafanaDimension {
+    Provider,
+    Model,
+    Environment,
+}
+
+impl GrafanaDimension {
+    pub const ALL: [GrafanaDimension; 3] = [
+        GrafanaDimension::Provider,
+        GrafanaDimension::Model,
+        GrafanaDimension::Environment,
+    ];
+
+    pub fn to_column_name(&self) -> &'static str {
+        match self {
+            GrafanaDimension::Provider => "provider",
+            GrafanaDimension::Model => "model",
+            GrafanaDimension::Environment => "environment",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.to_column_name() == name)
+    }
+
+    /// Whether the 1-minute rollup carries this column
+    pub fn available_per_minute(&self) -> bool {
+        !matches!(self, GrafanaDimension::Environment)
+    }
+}
+
+/// Continuous aggregate a query is served from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateSource {
+    OneMinute,
+    OneHour,
+}
+
+impl AggregateSource {
+    pub fn table(&self) -> &'static str {
+        match self {
+            AggregateSource::OneMinute => "llm_metrics_1min",
+            AggregateSource::OneHour => "llm_metrics_1hour",
+        }
+    }
+
+    /// Width of one rollup row in seconds
+    pub fn resolution_secs(&self) -> i64 {
+        match self {
+            AggregateSource::OneMinute => 60,
+            AggregateSource::OneHour => 3600,
+        }
+    }
+
+    /// Pick the finest rollup that can serve the query.
+    ///
+    /// Minute rollups are used for short ranges with sub-hour intervals, as
+    /// long as every requested dimension exists there.
+    pub fn select(
+        range: &GrafanaRange,
+        interval_ms: Option<i64>,
+        dimensions: &[GrafanaDimension],
+    ) -> Self {
+        let short_range = range.to - range.from <= chrono::Duration::hours(MAX_MINUTE_RANGE_HOURS);
+        let fine_interval = interval_ms.map_or(true, |ms| ms < 3_600_000);
+
+        if short_range && fine_interval && dimensions.iter().all(|d| d.available_per_minute()) {
+            AggregateSource::OneMinute
+        } else {
+            AggregateSource::OneHour
+        }
+    }
+
+    /// Bucket width for a query: the panel interval rounded up to whole
+    /// rollup rows, widened so no series exceeds `max_data_points`
+    pub fn bucket_secs(
+        &self,
+        range: &GrafanaRange,
+        interval_ms: Option<i64>,
+        max_data_points: Option<i64>,
+    ) -> i64 {
+        let resolution = self.resolution_secs();
+        let range_secs = (range.to - range.from).num_seconds().max(0);
+        let max_points = max_data_points.filter(|p| *p > 0).unwrap_or(DEFAULT_MAX_DATA_POINTS);
+
+        let requested = interval_ms.unwrap_or(0) / 1000;
+        let minimum = (range_secs + max_points - 1) / max_points;
+        let secs = requested.max(minimum).max(resolution);
+
+        (secs + resolution - 1) / resolution * resolution
+    }
+}
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Time range of a Grafana request
+#[derive(Debug, Clone, Deserialize)]
+pub struct GrafanaRange {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+/// Request for POST /search
+#[derive(Debug, Default, Deserialize)]
+pub struct GrafanaSearchRequest {
+    /// Text typed into the metric picker
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
+/// Request for POST /query
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrafanaQueryRequest {
+    pub range: GrafanaRange,
+    pub interval_ms: Option<i64>,
+    pub max_data_points: Option<i64>,
+    pub targets: Vec<GrafanaTarget>,
+    #[serde(default)]
+    pub adhoc_filters: Vec<GrafanaAdhocFilter>,
+}
+
+/// One query of a panel
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrafanaTarget {
+    #[serde(default)]
+    pub target: String,
+    pub ref_id: Option<String>,
+    /// `timeserie` (default) or `table`
+    #[serde(rename = "type")]
+    pub target_type: Option<String>,
+    /// Set when the query is disabled in the panel editor
+    #[serde(default)]
+    pub hide: bool,
+}
+
+/// Dashboard ad hoc filter
+#[derive(Debug, Deserialize)]
+pub struct GrafanaAdhocFilter {
+    pub key: String,
+    pub operator: String,
+    pub value: String,
+}
+
+/// Request for POST /annotations
+#[derive(Debug, Deserialize)]
+pub struct GrafanaAnnotationRequest {
+    pub range: GrafanaRange,
+    pub annotation: GrafanaAnnotationQuery,
+}
+
+/// Annotation definition from the dashboard settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrafanaAnnotationQuery {
+    pub name: String,
+    #[serde(default)]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub enable: bool,
+    #[serde(default, rename = "iconColor", skip_serializing_if = "Option::is_none")]
+    pub icon_color: Option<String>,
+}
+
+/// Request for POST /tag-values
+#[derive(Debug, Deserialize)]
+pub struct GrafanaTagValuesRequest {
+    pub key: String,
+}
+
+/// A parsed query target
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedTarget {
+    pub metric: GrafanaMetric,
+    pub group_by: Option<GrafanaDimension>,
+}
+
+/// A validated ad hoc filter
+#[derive(Debug, Clone, PartialEq)]
+pub struct DimensionFilter {
+    pub dimension: GrafanaDimension,
+    pub negate: bool,
+    pub value: String,
+}
+
+impl GrafanaRange {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.from >= self.to {
+            return Err("range.from must be before range.to".to_string());
+        }
+
+        if (self.to - self.from).num_days() > 365 {
+            return Err("Maximum time range is 365 days".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+impl GrafanaQueryRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        self.range.validate()?;
+
+        if self.targets.len() > 20 {
+            return Err("Maximum 20 targets per query".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Parse the ad hoc filters of the request
+    pub fn filters(&self) -> Result<Vec<DimensionFilter>, String> {
+        self.adhoc_filters
+            .iter()
+            .map(|f| {
+                let dimension = GrafanaDimension::parse(&f.key)
+                    .ok_or_else(|| format!("Unsupported filter key: {}", f.key))?;
+                let negate = match f.operator.as_str() {
+                    "=" => false,
+                    "!=" => true,
+                    other => return Err(format!("Unsupported filter operator: {}", other)),
+                };
+                Ok(DimensionFilter {
+                    dimension,
+                    negate,
+                    value: f.value.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl GrafanaTarget {
+    pub fn is_table(&self) -> bool {
+        self.target_type.as_deref() == Some("table")
+    }
+}
+
+/// Parse `<metric>` or `<metric> by <dimension>`
+pub fn parse_target(target: &str) -> Result<ParsedTarget, String> {
+    let mut parts = target.split_whitespace();
+    let metric_name = parts.next().ok_or_else(|| "Empty target".to_string())?;
+    let metric = GrafanaMetric::parse(metric_name)
+        .ok_or_else(|| format!("Unknown metric: {}", metric_name))?;
+
+    let group_by = match (parts.next(), parts.next(), parts.next()) {
+        (None, _, _) => None,
+        (Some("by"), Some(dim), None) => Some(
+            GrafanaDimension::parse(dim).ok_or_else(|| format!("Unknown dimension: {}", dim))?,
+        ),
+        _ => return Err(format!("Invalid target '{}': expected '<metric> [by <dimension>]'", target)),
+    };
+
+    Ok(ParsedTarget { metric, group_by })
+}
+
+/// Provider named by an annotation query, `None` for all providers
+pub fn parse_annotation_query(query: Option<&str>) -> Result<Option<String>, String> {
+    let query = query.map(str::trim).unwrap_or_default();
+    match query.split_once(':') {
+        _ if query.is_empty() || query == "incidents" => Ok(None),
+        Some(("incidents", provider)) if !provider.trim().is_empty() => {
+            Ok(Some(provider.trim().to_string()))
+        }
+        _ => Err(format!(
+            "Invalid annotation query '{}': expected 'incidents' or 'incidents:<provider>'",
+            query
+        )),
+    }
+}
+
+/// Metric names and `by` variants matching the metric picker text
+pub fn search_targets(filter: Option<&str>) -> Vec<String> {
+    let filter = filter.map(str::trim).unwrap_or_default();
+
+    GrafanaMetric::ALL
+        .iter()
+        .flat_map(|metric| {
+            std::iter::once(metric.name().to_string()).chain(
+                GrafanaDimension::ALL
+                    .iter()
+                    .map(move |dim| format!("{} by {}", metric.name(), dim.to_column_name())),
+            )
+        })
+        .filter(|target| target.contains(filter))
+        .collect()
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// One result of POST /query
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum GrafanaQueryResult {
+    TimeSeries(GrafanaTimeSeries),
+    Table(GrafanaTable),
+}
+
+/// Datapoints are `[value, unix_ms]` pairs
+#[derive(Debug, Serialize, PartialEq)]
+pub struct GrafanaTimeSeries {
+    pub target: String,
+    pub datapoints: Vec<(Option<f64>, i64)>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GrafanaTable {
+    #[serde(rename = "type")]
+    pub table_type: &'static str,
+    pub columns: Vec<GrafanaColumn>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GrafanaColumn {
+    pub text: String,
+    #[serde(rename = "type")]
+    pub column_type: &'static str,
+}
+
+/// Key returned by POST /tag-keys
+#[derive(Debug, Serialize)]
+pub struct GrafanaTagKey {
+    #[serde(rename = "type")]
+    pub key_type: &'static str,
+    pub text: &'static str,
+}
+
+/// Value returned by POST /tag-values
+#[derive(Debug, Serialize)]
+pub struct GrafanaTagValue {
+    pub text: String,
+}
+
+/// One annotation of POST /annotations
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrafanaAnnotation {
+    pub annotation: GrafanaAnnotationQuery,
+    /// Start in unix milliseconds
+    pub time: i64,
+    /// End in unix milliseconds, absent while the incident is ongoing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_end: Option<i64>,
+    pub is_region: bool,
+    pub title: String,
+    pub text: String,
+    pub tags: Vec<String>,
+}
+
+// ============================================================================
+// Internal Database Row Types
+// ============================================================================
+
+/// One bucket of one series
+#[derive(Debug, sqlx::FromRow)]
+pub struct GrafanaSeriesRow {
+    pub time: Option<DateTime<Utc>>,
+    pub series: Option<String>,
+    pub value: Option<f64>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Group rows into one time series per dimension value.
+///
+/// Grouped series are labelled Prometheus-style, e.g.
+/// `request_count{model="gpt-4o"}`.
+pub fn to_time_series(target: &ParsedTarget, rows: Vec<GrafanaSeriesRow>) -> Vec<GrafanaTimeSeries> {
+    let mut series: BTreeMap<String, Vec<(Option<f64>, i64)>> = BTreeMap::new();
+
+    for row in rows {
+        let Some(time) = row.time else { continue };
+
+        let label = match target.group_by {
+            Some(dim) => format!(
+                "{}{{{}=\"{}\"}}",
+                target.metric.name(),
+                dim.to_column_name(),
+                row.series.as_deref().unwrap_or_default()
+            ),
+            None => target.metric.name().to_string(),
+        };
+
+        series
+            .entry(label)
+            .or_default()
+            .push((row.value, time.timestamp_millis()));
+    }
+
+    series
+        .into_iter()
+        .map(|(target, mut datapoints)| {
+            datapoints.sort_by_key(|(_, ts)| *ts);
+            GrafanaTimeSeries { target, datapoints }
+        })
+        .collect()
+}
+
+/// Render range totals as a table with one row per dimension value
+pub fn to_table(target: &ParsedTarget, rows: Vec<GrafanaSeriesRow>) -> GrafanaTable {
+    let mut columns = Vec::with_capacity(2);
+    if let Some(dim) = target.group_by {
+        columns.push(GrafanaColumn {
+            text: dim.to_column_name().to_string(),
+            column_type: "string",
+        });
+    }
+    columns.push(GrafanaColumn {
+        text: target.metric.name().to_string(),
+        column_type: "number",
+    });
+
+    let rows = rows
+        .into_iter()
+        .map(|row| {
+            let mut cells = Vec::with_capacity(2);
+            if target.group_by.is_some() {
+                cells.push(serde_json::Value::from(row.series.unwrap_or_default()));
+            }
+            cells.push(serde_json::Value::from(row.value));
+            cells
+        })
+        .collect();
+
+    GrafanaTable {
+        table_type: "table",
+        columns,
+        rows,
+    }
+}
+
+/// Render a provider incident as a region annotation
+pub fn incident_annotation(
+    annotation: &GrafanaAnnotationQuery,
+    incident: ProviderIncident,
+) -> GrafanaAnnotation {
+    GrafanaAnnotation {
+        annotation: annotation.clone(),
+        time: incident.started_at.timestamp_millis(),
+        time_end: incident.resolved_at.map(|t| t.timestamp_millis()),
+        is_region: true,
+        title: format!("{} {}", incident.provider, incident.status.replace('_', " ")),
+        text: incident.description.unwrap_or_default(),
+        tags: vec![incident.provider, incident.status, incident.source],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn range(hours: i64) -> GrafanaRange {
+        let to = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        GrafanaRange {
+            from: to - Duration::hours(hours),
+            to,
+        }
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            parse_target("total_cost_usd by model").unwrap(),
+            ParsedTarget {
+                metric: GrafanaMetric::TotalCost,
+                group_by: Some(GrafanaDimension::Model),
+            }
+        );
+        assert_eq!(parse_target(" error_rate ").unwrap().group_by, None);
+        assert!(parse_target("").is_err());
+        assert!(parse_target("latency").is_err());
+        assert!(parse_target("error_rate by user_id").is_err());
+        assert!(parse_target("error_rate model").is_err());
+
+        assert_eq!(search_targets(Some("cost_usd by")).len(), 3);
+        assert_eq!(parse_annotation_query(Some("incidents:openai")).unwrap().as_deref(), Some("openai"));
+        assert_eq!(parse_annotation_query(None).unwrap(), None);
+        assert!(parse_annotation_query(Some("deploys")).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_source_selection() {
+        assert_eq!(AggregateSource::select(&range(6), Some(60_000), &[]), AggregateSource::OneMinute);
+        assert_eq!(AggregateSource::select(&range(72), Some(60_000), &[]), AggregateSource::OneHour);
+        assert_eq!(
+            AggregateSource::select(&range(6), None, &[GrafanaDimension::Environment]),
+            AggregateSource::OneHour
+        );
+
+        // 6h at a 30s panel interval: clamped to whole minutes
+        assert_eq!(AggregateSource::OneMinute.bucket_secs(&range(6), Some(30_000), Some(1000)), 60);
+        // 6h with at most 100 points: 216s, rounded up to 4 minutes
+        assert_eq!(AggregateSource::OneMinute.bucket_secs(&range(6), Some(30_000), Some(100)), 240);
+        assert_eq!(AggregateSource::OneHour.bucket_secs(&range(72), Some(60_000), None), 3600);
+    }
+
+    #[test]
+    fn test_to_time_series() {
+        let target = parse_target("request_count by model").unwrap();
+        let t0 = Utc.with_ymd_and_hms(2024, 6, 1, 10, 0, 0).unwrap();
+        let row = |minutes: i64, model: &str, value: f64| GrafanaSeriesRow {
+            time: Some(t0 + Duration::minutes(minutes)),
+            series: Some(model.to_string()),
+            value: Some(value),
+        };
+
+        let series = to_time_series(
+            &target,
+            vec![row(1, "gpt-4o", 3.0), row(0, "gpt-4o", 5.0), row(0, "claude", 2.0)],
+        );
+
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].target, "request_count{model=\"claude\"}");
+        assert_eq!(
+            series[1].datapoints,
+            vec![
+                (Some(5.0), t0.timestamp_millis()),
+                (Some(3.0), (t0 + Duration::minutes(1)).timestamp_millis()),
+            ]
+        );
+
+        let table = to_table(&target, vec![row(0, "gpt-4o", 8.0)]);
+        assert_eq!(table.columns.len(), 2);
+        assert_eq!(table.rows[0], vec![serde_json::json!("gpt-4o"), serde_json::json!(8.0)]);
+    }
+}
@@ -0,0 +1,302 @@
+//! # Guardrail Data Models
+//!
+//! Data structures for `GET /api/v1/guardrails/violations`, which reports
+//! content-safety violation rates from the `guardrail_events` table (written
+//! from the collector's guardrail extraction) against the request counts in
+//! `llm_traces`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Dimension to break violation rates down by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ViolationGroupBy {
+    Model,
+    Team,
+    #[default]
+    Category,
+}
+
+impl ViolationGroupBy {
+    /// Group key expression over `guardrail_events`
+    pub fn event_key_sql(&self) -> &'static str {
+        match self {
+            ViolationGroupBy::Model => "model",
+            ViolationGroupBy::Team => "COALESCE(team_id, 'unassigned')",
+            ViolationGroupBy::Category => "COALESCE(category, 'uncategorized')",
+        }
+    }
+
+    /// Group key expression over `llm_traces`, or `None` when every group is
+    /// rated against the total request count
+    pub fn request_key_sql(&self) -> Option<&'static str> {
+        match self {
+            ViolationGroupBy::Model => Some("model"),
+            ViolationGroupBy::Team => Some("COALESCE(attributes->>'team.id', 'unassigned')"),
+            ViolationGroupBy::Category => None,
+        }
+    }
+}
+
+/// Request for GET /api/v1/guardrails/violations
+#[derive(Debug, Deserialize, Clone)]
+pub struct GuardrailViolationsRequest {
+    /// Start time (default: 24 hours ago)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End time (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Breakdown dimension (default: category)
+    #[serde(default)]
+    pub group_by: ViolationGroupBy,
+
+    /// Filter by provider
+    pub provider: Option<String>,
+
+    /// Filter by model
+    pub model: Option<String>,
+
+    /// Filter by environment
+    pub environment: Option<String>,
+}
+
+impl GuardrailViolationsRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            if start >= end {
+                return Err("Start time must be before end time".to_string());
+            }
+
+            if (end - start).num_days() > 90 {
+                return Err("Maximum time range is 90 days".to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for GET /api/v1/guardrails/violations
+#[derive(Debug, Serialize)]
+pub struct GuardrailViolationsResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub group_by: ViolationGroupBy,
+    /// Requests in the time range
+    pub total_requests: i64,
+    /// Requests with at least one violation
+    pub violating_requests: i64,
+    /// Blocked and flagged outcomes
+    pub violation_count: i64,
+    pub blocked_count: i64,
+    pub flagged_count: i64,
+    /// violating_requests / total_requests
+    pub violation_rate: f64,
+    /// Groups, most violating requests first
+    pub groups: Vec<ViolationGroup>,
+}
+
+/// Violation rate for one model, team or category
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ViolationGroup {
+    pub key: String,
+    /// Requests the rate is computed against; the total for categories
+    pub request_count: i64,
+    pub violating_requests: i64,
+    pub violation_count: i64,
+    pub blocked_count: i64,
+    pub flagged_count: i64,
+    pub violation_rate: f64,
+    pub avg_score: Option<f64>,
+}
+
+// ============================================================================
+// Internal Database Row Types
+// ============================================================================
+
+/// Violation counts per group; the row with no key holds the totals
+#[derive(Debug, sqlx::FromRow)]
+pub struct ViolationCountRow {
+    pub key: Option<String>,
+    pub violating_requests: Option<i64>,
+    pub violation_count: Option<i64>,
+    pub blocked_count: Option<i64>,
+    pub flagged_count: Option<i64>,
+    pub avg_score: Option<f64>,
+}
+
+/// Request counts per group; the row with no key holds the total
+#[derive(Debug, sqlx::FromRow)]
+pub struct RequestCountRow {
+    pub key: Option<String>,
+    pub request_count: Option<i64>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn rate(numerator: i64, denominator: i64) -> f64 {
+    if denominator > 0 {
+        numerator as f64 / denominator as f64
+    } else {
+        0.0
+    }
+}
+
+/// Combine violation and request counts into the violation-rate report.
+pub fn build_violation_report(
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    group_by: ViolationGroupBy,
+    violations: &[ViolationCountRow],
+    requests: &[RequestCountRow],
+) -> GuardrailViolationsResponse {
+    let total_requests = requests
+        .iter()
+        .find(|row| row.key.is_none())
+        .and_then(|row| row.request_count)
+        .unwrap_or(0);
+    let requests_by_key: HashMap<&str, i64> = requests
+        .iter()
+        .filter_map(|row| Some((row.key.as_deref()?, row.request_count.unwrap_or(0))))
+        .collect();
+
+    let mut groups: Vec<ViolationGroup> = violations
+        .iter()
+        .filter_map(|row| {
+            let key = row.key.clone()?;
+            let request_count = match group_by.request_key_sql() {
+                Some(_) => requests_by_key.get(key.as_str()).copied().unwrap_or(0),
+                None => total_requests,
+            };
+            let violating_requests = row.violating_requests.unwrap_or(0);
+
+            Some(ViolationGroup {
+                key,
+                request_count,
+                violating_requests,
+                violation_count: row.violation_count.unwrap_or(0),
+                blocked_count: row.blocked_count.unwrap_or(0),
+                flagged_count: row.flagged_count.unwrap_or(0),
+                violation_rate: rate(violating_requests, request_count),
+                avg_score: row.avg_score,
+            })
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        b.violating_requests
+            .cmp(&a.violating_requests)
+            .then_with(|| a.key.cmp(&b.key))
+    });
+
+    let totals = violations.iter().find(|row| row.key.is_none());
+    let total = |f: fn(&ViolationCountRow) -> Option<i64>| totals.and_then(f).unwrap_or(0);
+    let violating_requests = total(|row| row.violating_requests);
+
+    GuardrailViolationsResponse {
+        start_time,
+        end_time,
+        group_by,
+        total_requests,
+        violating_requests,
+        violation_count: total(|row| row.violation_count),
+        blocked_count: total(|row| row.blocked_count),
+        flagged_count: total(|row| row.flagged_count),
+        violation_rate: rate(violating_requests, total_requests),
+        groups,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violations(
+        key: Option<&str>,
+        requests: i64,
+        blocked: i64,
+        flagged: i64,
+    ) -> ViolationCountRow {
+        ViolationCountRow {
+            key: key.map(str::to_string),
+            violating_requests: Some(requests),
+            violation_count: Some(blocked + flagged),
+            blocked_count: Some(blocked),
+            flagged_count: Some(flagged),
+            avg_score: Some(0.8),
+        }
+    }
+
+    fn requests(key: Option<&str>, count: i64) -> RequestCountRow {
+        RequestCountRow {
+            key: key.map(str::to_string),
+            request_count: Some(count),
+        }
+    }
+
+    #[test]
+    fn test_violation_rates_by_model() {
+        let now = Utc::now();
+        let report = build_violation_report(
+            now - chrono::Duration::hours(24),
+            now,
+            ViolationGroupBy::Model,
+            &[
+                violations(Some("gpt-4o"), 5, 2, 3),
+                violations(Some("claude-3-opus"), 10, 10, 0),
+                violations(None, 15, 12, 3),
+            ],
+            &[
+                requests(Some("gpt-4o"), 100),
+                requests(Some("claude-3-opus"), 50),
+                requests(Some("gpt-4o-mini"), 850),
+                requests(None, 1000),
+            ],
+        );
+
+        assert_eq!(report.total_requests, 1000);
+        assert_eq!(report.violating_requests, 15);
+        assert_eq!(report.blocked_count, 12);
+        assert!((report.violation_rate - 0.015).abs() < 1e-9);
+
+        assert_eq!(report.groups.len(), 2);
+        assert_eq!(report.groups[0].key, "claude-3-opus");
+        assert!((report.groups[0].violation_rate - 0.2).abs() < 1e-9);
+        assert_eq!(report.groups[1].request_count, 100);
+        assert!((report.groups[1].violation_rate - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_category_rates_use_total_requests() {
+        let now = Utc::now();
+        let report = build_violation_report(
+            now - chrono::Duration::hours(24),
+            now,
+            ViolationGroupBy::Category,
+            &[
+                violations(Some("hate"), 4, 0, 4),
+                violations(Some("pii"), 4, 4, 0),
+                violations(None, 6, 4, 4),
+            ],
+            &[requests(None, 200)],
+        );
+
+        assert_eq!(report.violation_count, 8);
+        assert_eq!(report.violating_requests, 6);
+        assert_eq!(report.groups[0].key, "hate");
+        assert!(report.groups.iter().all(|g| g.request_count == 200));
+        assert!((report.groups[1].violation_rate - 0.02).abs() < 1e-9);
+    }
+}
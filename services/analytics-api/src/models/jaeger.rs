@@ -0,0 +1,582 @@
+//! # Jaeger Query API Data Models
+//!
+//! Data structures for the Jaeger HTTP query API facade (`/api/services`,
+//! `/api/operations`, `/api/traces`), in the JSON format expected by the
+//! Jaeger UI and Grafana's Jaeger datasource.
+//!
+//! Every span in `llm_traces` becomes a Jaeger span. LLM fields (provider,
+//! model, tokens, cost) are exposed as span tags using the GenAI semantic
+//! convention names. Span links become `FOLLOWS_FROM` references, which the
+//! Jaeger UI shows as links to the other trace.
+
+use crate::models::traces::Trace;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Default number of traces returned by a search
+pub const DEFAULT_TRACE_LIMIT: i64 = 20;
+
+/// Maximum number of traces returned by a search
+pub const MAX_TRACE_LIMIT: i64 = 1500;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Query parameters for GET /api/traces
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct JaegerTraceQuery {
+    /// Service name (required by the Jaeger UI)
+    pub service: Option<String>,
+
+    /// Operation (span name)
+    pub operation: Option<String>,
+
+    /// Start of the time range in microseconds since the epoch
+    pub start: Option<i64>,
+
+    /// End of the time range in microseconds since the epoch
+    pub end: Option<i64>,
+
+    /// Time range ending now (e.g. "1h", "2d"); used when `start` is absent
+    pub lookback: Option<String>,
+
+    /// Maximum number of traces
+    pub limit: Option<i64>,
+
+    /// Minimum span duration (e.g. "1.2s", "100ms")
+    pub min_duration: Option<String>,
+
+    /// Maximum span duration
+    pub max_duration: Option<String>,
+
+    /// JSON object of tag filters, e.g. `{"error":"true"}`
+    pub tags: Option<String>,
+}
+
+/// Query parameters for GET /api/operations
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JaegerOperationsQuery {
+    pub service: String,
+    pub span_kind: Option<String>,
+}
+
+/// Validated trace search
+#[derive(Debug, Clone, PartialEq)]
+pub struct JaegerTraceSearch {
+    pub service: Option<String>,
+    pub operation: Option<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub limit: i64,
+    pub min_duration_ms: Option<i32>,
+    pub max_duration_ms: Option<i32>,
+    /// Only traces with an errored span
+    pub error: Option<bool>,
+    /// Attribute containment filter
+    pub attributes: Option<Value>,
+}
+
+impl JaegerTraceQuery {
+    /// Validate the query and resolve defaults relative to `now`.
+    pub fn to_search(&self, now: DateTime<Utc>) -> Result<JaegerTraceSearch, String> {
+        let end_time = match self.end {
+            Some(end) => from_micros(end)?,
+            None => now,
+        };
+        let start_time = match (self.start, &self.lookback) {
+            (Some(start), _) => from_micros(start)?,
+            (None, Some(lookback)) if lookback != "custom" => end_time - parse_duration(lookback)?,
+            _ => end_time - Duration::hours(1),
+        };
+        if start_time >= end_time {
+            return Err("start must be before end".to_string());
+        }
+
+        let limit = self.limit.unwrap_or(DEFAULT_TRACE_LIMIT);
+        if !(1..=MAX_TRACE_LIMIT).contains(&limit) {
+            return Err(format!("limit must be between 1 and {}", MAX_TRACE_LIMIT));
+        }
+
+        let duration_ms = |s: &Option<String>| -> Result<Option<i32>, String> {
+            s.as_deref()
+                .filter(|s| !s.is_empty())
+                .map(|s| parse_duration(s).map(|d| d.num_milliseconds().min(i32::MAX as i64) as i32))
+                .transpose()
+        };
+
+        let (error, attributes) = match self.tags.as_deref().filter(|t| !t.is_empty()) {
+            Some(tags) => parse_tags(tags)?,
+            None => (None, None),
+        };
+
+        Ok(JaegerTraceSearch {
+            service: self.service.clone().filter(|s| !s.is_empty()),
+            operation: self
+                .operation
+                .clone()
+                .filter(|o| !o.is_empty() && o != "all"),
+            start_time,
+            end_time,
+            limit,
+            min_duration_ms: duration_ms(&self.min_duration)?,
+            max_duration_ms: duration_ms(&self.max_duration)?,
+            error,
+            attributes,
+        })
+    }
+}
+
+fn from_micros(micros: i64) -> Result<DateTime<Utc>, String> {
+    Utc.timestamp_micros(micros)
+        .single()
+        .ok_or_else(|| format!("Invalid timestamp: {}", micros))
+}
+
+/// Parse a Go-style duration as used by the Jaeger UI ("500us", "1.5s", "2h").
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .ok_or_else(|| format!("Duration '{}' is missing a unit", s))?;
+    let (value, unit) = s.split_at(split);
+    let value: f64 = value
+        .parse()
+        .map_err(|_| format!("Invalid duration '{}'", s))?;
+
+    let micros_per_unit = match unit {
+        "us" | "µs" => 1.0,
+        "ms" => 1_000.0,
+        "s" => 1_000_000.0,
+        "m" => 60_000_000.0,
+        "h" => 3_600_000_000.0,
+        "d" => 86_400_000_000.0,
+        _ => return Err(format!("Unknown duration unit '{}'", unit)),
+    };
+
+    Ok(Duration::microseconds((value * micros_per_unit) as i64))
+}
+
+/// Split Jaeger tag filters into the error flag and attribute filters.
+fn parse_tags(tags: &str) -> Result<(Option<bool>, Option<Value>), String> {
+    let tags: HashMap<String, Value> =
+        serde_json::from_str(tags).map_err(|e| format!("Invalid tags: {}", e))?;
+
+    let mut error = None;
+    let mut attributes = serde_json::Map::new();
+    for (key, value) in tags {
+        if key == "error" {
+            error = Some(matches!(value.as_str(), Some("true")) || value == Value::Bool(true));
+        } else {
+            attributes.insert(key, value);
+        }
+    }
+
+    Ok((error, (!attributes.is_empty()).then_some(Value::Object(attributes))))
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Jaeger response envelope
+#[derive(Debug, Serialize)]
+pub struct JaegerResponse<T> {
+    pub data: T,
+    pub total: usize,
+    pub limit: usize,
+    pub offset: usize,
+    pub errors: Option<Vec<JaegerError>>,
+}
+
+impl<T> JaegerResponse<T> {
+    pub fn new(data: T, total: usize) -> Self {
+        Self {
+            data,
+            total,
+            limit: 0,
+            offset: 0,
+            errors: None,
+        }
+    }
+}
+
+/// Jaeger error entry
+#[derive(Debug, Serialize)]
+pub struct JaegerError {
+    pub code: u16,
+    pub msg: String,
+}
+
+/// Operation of a service
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct JaegerOperation {
+    pub name: String,
+    pub span_kind: String,
+}
+
+/// A trace with all its spans
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JaegerTrace {
+    #[serde(rename = "traceID")]
+    pub trace_id: String,
+    pub spans: Vec<JaegerSpan>,
+    pub processes: BTreeMap<String, JaegerProcess>,
+    pub warnings: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JaegerSpan {
+    #[serde(rename = "traceID")]
+    pub trace_id: String,
+    #[serde(rename = "spanID")]
+    pub span_id: String,
+    pub operation_name: String,
+    pub references: Vec<JaegerReference>,
+    pub flags: u32,
+    /// Microseconds since the epoch
+    pub start_time: i64,
+    /// Microseconds
+    pub duration: i64,
+    pub tags: Vec<JaegerKeyValue>,
+    pub logs: Vec<JaegerLog>,
+    #[serde(rename = "processID")]
+    pub process_id: String,
+    pub warnings: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JaegerReference {
+    pub ref_type: String,
+    #[serde(rename = "traceID")]
+    pub trace_id: String,
+    #[serde(rename = "spanID")]
+    pub span_id: String,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct JaegerKeyValue {
+    pub key: String,
+    #[serde(rename = "type")]
+    pub value_type: String,
+    pub value: Value,
+}
+
+impl JaegerKeyValue {
+    pub fn string(key: &str, value: impl Into<String>) -> Self {
+        Self {
+            key: key.to_string(),
+            value_type: "string".to_string(),
+            value: Value::String(value.into()),
+        }
+    }
+
+    pub fn int64(key: &str, value: i64) -> Self {
+        Self {
+            key: key.to_string(),
+            value_type: "int64".to_string(),
+            value: value.into(),
+        }
+    }
+
+    pub fn float64(key: &str, value: f64) -> Self {
+        Self {
+            key: key.to_string(),
+            value_type: "float64".to_string(),
+            value: value.into(),
+        }
+    }
+
+    pub fn bool(key: &str, value: bool) -> Self {
+        Self {
+            key: key.to_string(),
+            value_type: "bool".to_string(),
+            value: value.into(),
+        }
+    }
+
+    /// Convert a JSON attribute, serializing arrays and objects as strings
+    fn from_json(key: &str, value: &Value) -> Option<Self> {
+        match value {
+            Value::Null => None,
+            Value::Bool(b) => Some(Self::bool(key, *b)),
+            Value::Number(n) => Some(match n.as_i64() {
+                Some(i) => Self::int64(key, i),
+                None => Self::float64(key, n.as_f64().unwrap_or_default()),
+            }),
+            Value::String(s) => Some(Self::string(key, s.clone())),
+            other => Some(Self::string(key, other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct JaegerLog {
+    pub timestamp: i64,
+    pub fields: Vec<JaegerKeyValue>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JaegerProcess {
+    pub service_name: String,
+    pub tags: Vec<JaegerKeyValue>,
+}
+
+// ============================================================================
+// Conversion
+// ============================================================================
+
+/// Group spans into Jaeger traces, keeping the order in which traces first
+/// appear. Each distinct service becomes one process per trace.
+pub fn to_jaeger_traces(spans: Vec<Trace>) -> Vec<JaegerTrace> {
+    let mut traces: Vec<JaegerTrace> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for span in spans {
+        let position = *index.entry(span.trace_id.clone()).or_insert_with(|| {
+            traces.push(JaegerTrace {
+                trace_id: span.trace_id.clone(),
+                spans: Vec::new(),
+                processes: BTreeMap::new(),
+                warnings: None,
+            });
+            traces.len() - 1
+        });
+        let trace = &mut traces[position];
+
+        let service_name = span
+            .service_name
+            .clone()
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "unknown".to_string());
+        let process_id = match trace
+            .processes
+            .iter()
+            .find(|(_, p)| p.service_name == service_name)
+        {
+            Some((id, _)) => id.clone(),
+            None => {
+                let id = format!("p{}", trace.processes.len() + 1);
+                trace.processes.insert(
+                    id.clone(),
+                    JaegerProcess {
+                        service_name,
+                        tags: Vec::new(),
+                    },
+                );
+                id
+            }
+        };
+
+        trace.spans.push(to_jaeger_span(span, process_id));
+    }
+
+    for trace in &mut traces {
+        trace.spans.sort_by_key(|s| s.start_time);
+    }
+    traces
+}
+
+fn to_jaeger_span(span: Trace, process_id: String) -> JaegerSpan {
+    let mut references: Vec<JaegerReference> = span
+        .parent_span_id
+        .as_ref()
+        .filter(|p| !p.is_empty())
+        .map(|parent| {
+            vec![JaegerReference {
+                ref_type: "CHILD_OF".to_string(),
+                trace_id: span.trace_id.clone(),
+                span_id: parent.clone(),
+            }]
+        })
+        .unwrap_or_default();
+    // Span links, e.g. to the agent span that fanned out this trace
+    references.extend(span.linked_spans().into_iter().map(|link| JaegerReference {
+        ref_type: "FOLLOWS_FROM".to_string(),
+        trace_id: link.trace_id,
+        span_id: link.span_id,
+    }));
+
+    let mut tags = vec![
+        JaegerKeyValue::string("gen_ai.system", span.provider.clone()),
+        JaegerKeyValue::string("gen_ai.request.model", span.model.clone()),
+    ];
+    if let Some(tokens) = span.prompt_tokens {
+        tags.push(JaegerKeyValue::int64("gen_ai.usage.input_tokens", tokens as i64));
+    }
+    if let Some(tokens) = span.completion_tokens {
+        tags.push(JaegerKeyValue::int64("gen_ai.usage.output_tokens", tokens as i64));
+    }
+    if let Some(cost) = span.total_cost_usd {
+        tags.push(JaegerKeyValue::float64("llm.cost.total_usd", cost));
+    }
+    if let Some(ttft) = span.ttft_ms {
+        tags.push(JaegerKeyValue::int64("llm.latency.ttft_ms", ttft as i64));
+    }
+    if let Some(status) = &span.status_code {
+        tags.push(JaegerKeyValue::string("otel.status_code", status.clone()));
+        if status.eq_ignore_ascii_case("error") {
+            tags.push(JaegerKeyValue::bool("error", true));
+        }
+    }
+    if let Some(message) = &span.error_message {
+        tags.push(JaegerKeyValue::string("otel.status_description", message.clone()));
+    }
+    if let Some(user_id) = &span.user_id {
+        tags.push(JaegerKeyValue::string("user.id", user_id.clone()));
+    }
+    if let Some(session_id) = &span.session_id {
+        tags.push(JaegerKeyValue::string("session.id", session_id.clone()));
+    }
+    if let Some(environment) = &span.environment {
+        tags.push(JaegerKeyValue::string("deployment.environment", environment.clone()));
+    }
+    if let Some(Value::Object(attributes)) = &span.attributes {
+        let mut keys: Vec<&String> = attributes.keys().collect();
+        keys.sort();
+        for key in keys {
+            if tags.iter().any(|t| &t.key == key) {
+                continue;
+            }
+            if let Some(tag) = JaegerKeyValue::from_json(key, &attributes[key]) {
+                tags.push(tag);
+            }
+        }
+    }
+
+    JaegerSpan {
+        trace_id: span.trace_id,
+        span_id: span.span_id,
+        operation_name: span.span_name.unwrap_or_else(|| "llm.request".to_string()),
+        references,
+        flags: 1,
+        start_time: span.ts.timestamp_micros(),
+        duration: span.duration_ms.unwrap_or(0) as i64 * 1_000,
+        tags,
+        logs: Vec::new(),
+        process_id,
+        warnings: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(trace_id: &str, span_id: &str, parent: Option<&str>, service: &str, offset_ms: i64) -> Trace {
+        Trace {
+            ts: Utc.timestamp_opt(1_700_000_000, 0).unwrap() + Duration::milliseconds(offset_ms),
+            trace_id: trace_id.to_string(),
+            span_id: span_id.to_string(),
+            parent_span_id: parent.map(String::from),
+            service_name: Some(service.to_string()),
+            span_name: Some("llm.chat".to_string()),
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            input_text: None,
+            output_text: None,
+            prompt_tokens: Some(10),
+            completion_tokens: Some(20),
+            total_tokens: Some(30),
+            prompt_cost_usd: None,
+            completion_cost_usd: None,
+            total_cost_usd: Some(0.002),
+            duration_ms: Some(250),
+            ttft_ms: None,
+            status_code: Some("ERROR".to_string()),
+            error_message: None,
+            user_id: None,
+            session_id: None,
+            environment: None,
+            tags: None,
+            attributes: Some(serde_json::json!({"gen_ai.system": "ignored", "retry": 2})),
+            links: None,
+            search_rank: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("1.5s").unwrap(), Duration::milliseconds(1500));
+        assert_eq!(parse_duration("100ms").unwrap(), Duration::milliseconds(100));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::hours(2));
+        assert_eq!(parse_duration("250us").unwrap(), Duration::microseconds(250));
+        assert!(parse_duration("10").is_err());
+        assert!(parse_duration("10y").is_err());
+    }
+
+    #[test]
+    fn test_trace_query_to_search() {
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let query = JaegerTraceQuery {
+            service: Some("chat-api".to_string()),
+            operation: Some("all".to_string()),
+            lookback: Some("2h".to_string()),
+            min_duration: Some("1s".to_string()),
+            tags: Some(r#"{"error":"true","gen_ai.system":"openai"}"#.to_string()),
+            ..Default::default()
+        };
+
+        let search = query.to_search(now).unwrap();
+        assert_eq!(search.start_time, now - Duration::hours(2));
+        assert_eq!(search.operation, None);
+        assert_eq!(search.limit, DEFAULT_TRACE_LIMIT);
+        assert_eq!(search.min_duration_ms, Some(1000));
+        assert_eq!(search.error, Some(true));
+        assert_eq!(search.attributes, Some(serde_json::json!({"gen_ai.system": "openai"})));
+
+        let invalid = JaegerTraceQuery {
+            limit: Some(0),
+            ..Default::default()
+        };
+        assert!(invalid.to_search(now).is_err());
+    }
+
+    #[test]
+    fn test_to_jaeger_traces() {
+        let traces = to_jaeger_traces(vec![
+            span("t1", "child", Some("root"), "gateway", 10),
+            span("t1", "root", None, "app", 0),
+            span("t2", "other", None, "app", 0),
+        ]);
+        assert_eq!(traces.len(), 2);
+
+        let trace = &traces[0];
+        assert_eq!(trace.trace_id, "t1");
+        assert_eq!(trace.processes.len(), 2);
+        assert_eq!(trace.spans[0].span_id, "root");
+        assert!(trace.spans[0].references.is_empty());
+
+        let child = &trace.spans[1];
+        assert_eq!(child.references[0].span_id, "root");
+        assert_eq!(child.duration, 250_000);
+        assert_eq!(trace.processes[&child.process_id].service_name, "gateway");
+        assert!(child.tags.contains(&JaegerKeyValue::bool("error", true)));
+        assert!(child.tags.contains(&JaegerKeyValue::int64("retry", 2)));
+        assert_eq!(
+            child.tags.iter().filter(|t| t.key == "gen_ai.system").count(),
+            1
+        );
+
+        let mut linked = span("t3", "worker", None, "agent", 0);
+        linked.links = Some(serde_json::json!([{"trace_id": "t1", "span_id": "root"}]));
+        let references = &to_jaeger_traces(vec![linked])[0].spans[0].references;
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].ref_type, "FOLLOWS_FROM");
+        assert_eq!(references[0].trace_id, "t1");
+
+        let json = serde_json::to_value(&traces[1]).unwrap();
+        assert_eq!(json["traceID"], "t2");
+        assert_eq!(json["spans"][0]["spanID"], "other");
+        assert_eq!(json["spans"][0]["processID"], "p1");
+    }
+}
@@ -0,0 +1,545 @@
+//! # Log Data Models
+//!
+//! Data structures for the log API, which reads the `logs` table written by
+//! the storage log writer:
+//! - `GET /api/v1/logs`: log records filtered by time, severity, service and
+//!   body text, with cursor pagination
+//! - `GET /api/v1/traces/:trace_id/logs`: the log records of a trace, linked
+//!   by `trace_id` or by the `span_id` of one of its spans
+//! - `GET /api/v1/logs/patterns`: the log patterns mined by the collector
+//!   (`log_patterns`) with their record counts from the hourly
+//!   `log_pattern_counts` table, compared against the window before to show
+//!   the trend
+//!
+//! Log records belong to the organization in their `org_id` attribute, or
+//! else their resource's.
+
+use super::{PaginationMetadata, ResponseMetadata, ResponseStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Hours of pattern counts kept in `log_pattern_counts`
+pub const PATTERN_RETENTION_HOURS: i64 = 90 * 24;
+
+/// Maximum time range of a log query, in days
+pub const MAX_LOG_QUERY_DAYS: i64 = 30;
+
+/// Maximum length of the `search` text
+const MAX_SEARCH_LEN: usize = 256;
+
+/// Count ratio to the previous window from which a pattern is rising
+const RISING_RATIO: f64 = 1.5;
+
+/// Count ratio to the previous window up to which a pattern is falling
+const FALLING_RATIO: f64 = 0.5;
+
+/// Severity names and the lowest OTLP severity number of each
+const SEVERITIES: [(&str, i32); 6] = [
+    ("trace", 1),
+    ("debug", 5),
+    ("info", 9),
+    ("warn", 13),
+    ("error", 17),
+    ("fatal", 21),
+];
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for GET /api/v1/logs
+#[derive(Debug, Deserialize, Clone)]
+pub struct LogQuery {
+    /// Start time (default: 24 hours before end time)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End time (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Only records of this service
+    pub service_name: Option<String>,
+
+    /// Only records of at least this severity
+    /// (trace, debug, info, warn, error, fatal)
+    pub min_severity: Option<String>,
+
+    /// Only records whose body contains this text (case-insensitive)
+    pub search: Option<String>,
+
+    /// Only records of this trace
+    pub trace_id: Option<String>,
+
+    /// Only records of this log pattern
+    pub pattern_id: Option<String>,
+
+    /// Pagination cursor from the previous page
+    pub cursor: Option<String>,
+
+    /// Records per page (default: 100, max: 1000)
+    #[serde(default = "default_log_limit")]
+    pub limit: i32,
+}
+
+fn default_log_limit() -> i32 {
+    100
+}
+
+impl LogQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            if start >= end {
+                return Err("Start time must be before end time".to_string());
+            }
+
+            if (end - start).num_days() > MAX_LOG_QUERY_DAYS {
+                return Err(format!("Maximum time range is {} days", MAX_LOG_QUERY_DAYS));
+            }
+        }
+
+        validate_severity(self.min_severity.as_deref())?;
+
+        if let Some(search) = &self.search {
+            if search.trim().is_empty() || search.len() > MAX_SEARCH_LEN {
+                return Err(format!(
+                    "Search text must be 1 to {} characters",
+                    MAX_SEARCH_LEN
+                ));
+            }
+        }
+
+        if let Some(trace_id) = &self.trace_id {
+            validate_trace_id(trace_id)?;
+        }
+
+        if self.limit < 1 || self.limit > 1000 {
+            return Err(format!(
+                "Limit must be between 1 and 1000, got {}",
+                self.limit
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Lowest severity number matching `min_severity`
+    pub fn min_severity_number(&self) -> i32 {
+        severity_number(self.min_severity.as_deref())
+    }
+
+    /// ILIKE pattern of `search`, with wildcards in the text escaped
+    pub fn search_pattern(&self) -> Option<String> {
+        self.search.as_deref().map(|search| {
+            let escaped = search
+                .trim()
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            format!("%{}%", escaped)
+        })
+    }
+}
+
+/// Request for GET /api/v1/traces/:trace_id/logs
+#[derive(Debug, Deserialize, Clone)]
+pub struct TraceLogsQuery {
+    /// Only records of this span
+    pub span_id: Option<String>,
+
+    /// Maximum records (default: 1000, max: 1000)
+    #[serde(default = "default_trace_logs_limit")]
+    pub limit: i64,
+}
+
+fn default_trace_logs_limit() -> i64 {
+    1000
+}
+
+impl TraceLogsQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.limit < 1 || self.limit > 1000 {
+            return Err(format!(
+                "Limit must be between 1 and 1000, got {}",
+                self.limit
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Check that a trace ID is 1 to 32 hex digits.
+pub fn validate_trace_id(trace_id: &str) -> Result<(), String> {
+    if trace_id.is_empty()
+        || trace_id.len() > 32
+        || !trace_id.bytes().all(|b| b.is_ascii_hexdigit())
+    {
+        return Err(format!("Invalid trace ID '{}'", trace_id));
+    }
+
+    Ok(())
+}
+
+fn validate_severity(severity: Option<&str>) -> Result<(), String> {
+    if let Some(severity) = severity {
+        if !SEVERITIES.iter().any(|(name, _)| *name == severity) {
+            return Err(format!(
+                "Invalid min_severity '{}', expected one of: {}",
+                severity,
+                SEVERITIES.map(|(name, _)| name).join(", ")
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Lowest severity number of a severity name; 0 (all records) when unset
+fn severity_number(severity: Option<&str>) -> i32 {
+    severity
+        .and_then(|severity| SEVERITIES.iter().find(|(name, _)| *name == severity))
+        .map_or(0, |(_, number)| *number)
+}
+
+/// Position after the last record of a page: records are ordered by time
+/// and ID, newest first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LogCursor {
+    pub timestamp: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl LogCursor {
+    /// Encode cursor to base64 string
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_string(self).unwrap();
+        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, json.as_bytes())
+    }
+
+    /// Decode cursor from base64 string
+    pub fn decode(cursor: &str) -> Result<Self, String> {
+        let bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, cursor)
+            .map_err(|e| format!("Invalid cursor format: {}", e))?;
+
+        let json =
+            String::from_utf8(bytes).map_err(|e| format!("Invalid cursor encoding: {}", e))?;
+
+        serde_json::from_str(&json).map_err(|e| format!("Invalid cursor structure: {}", e))
+    }
+}
+
+/// Request for GET /api/v1/logs/patterns
+#[derive(Debug, Deserialize, Clone)]
+pub struct LogPatternsQuery {
+    /// Hours in the window, ending with the current hour (default: 24); the
+    /// trend compares it with the same number of hours before
+    #[serde(default = "default_window_hours")]
+    pub window_hours: i64,
+
+    /// Only patterns of this service
+    pub service_name: Option<String>,
+
+    /// Only patterns with records of at least this severity
+    /// (trace, debug, info, warn, error, fatal)
+    pub min_severity: Option<String>,
+
+    /// Only patterns first seen in the window
+    #[serde(default)]
+    pub new_only: bool,
+
+    /// Maximum patterns (default: 50, max: 500)
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_window_hours() -> i64 {
+    24
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+impl LogPatternsQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        // The window and the one before it must both be retained
+        let max_window_hours = PATTERN_RETENTION_HOURS / 2;
+        if self.window_hours < 1 || self.window_hours > max_window_hours {
+            return Err(format!(
+                "window_hours must be between 1 and {}, got {}",
+                max_window_hours, self.window_hours
+            ));
+        }
+
+        validate_severity(self.min_severity.as_deref())?;
+
+        if self.limit < 1 || self.limit > 500 {
+            return Err(format!(
+                "Limit must be between 1 and 500, got {}",
+                self.limit
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Lowest severity number matching `min_severity`
+    pub fn min_severity_number(&self) -> i32 {
+        severity_number(self.min_severity.as_deref())
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for GET /api/v1/logs
+#[derive(Debug, Serialize)]
+pub struct PaginatedLogResponse {
+    pub status: ResponseStatus,
+    /// Records, newest first
+    pub data: Vec<LogEntry>,
+    pub pagination: PaginationMetadata,
+    pub meta: ResponseMetadata,
+}
+
+/// Response for GET /api/v1/traces/:trace_id/logs
+#[derive(Debug, Serialize)]
+pub struct TraceLogsResponse {
+    pub trace_id: String,
+    /// Records, oldest first
+    pub logs: Vec<LogEntry>,
+    /// Whether more records than `limit` are linked to the trace
+    pub truncated: bool,
+}
+
+/// A log record
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct LogEntry {
+    pub id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    pub observed_timestamp: DateTime<Utc>,
+    /// OTLP severity number (1-24)
+    pub severity_number: i32,
+    pub severity_text: String,
+    pub body: String,
+    pub service_name: String,
+    pub trace_id: Option<String>,
+    pub span_id: Option<String>,
+    pub attributes: serde_json::Value,
+    pub resource_attributes: serde_json::Value,
+    pub scope_name: Option<String>,
+    /// Log pattern mined by the collector
+    pub pattern_id: Option<String>,
+}
+
+/// Response for GET /api/v1/logs/patterns
+#[derive(Debug, Serialize)]
+pub struct LogPatternsResponse {
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    /// Patterns with records in the window, most frequent first
+    pub patterns: Vec<LogPatternSummary>,
+}
+
+/// A log pattern with its counts in the window and the window before
+#[derive(Debug, Serialize, PartialEq)]
+pub struct LogPatternSummary {
+    pub pattern_id: String,
+    pub service_name: String,
+    /// Log body template, with `<*>` for varying tokens
+    pub template: String,
+    /// Highest OTLP severity number of the records
+    pub severity_number: i32,
+    pub count: i64,
+    pub previous_count: i64,
+    /// (count - previous_count) / previous_count * 100
+    pub change_percent: Option<f64>,
+    pub trend: PatternTrend,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Change of a pattern's count against the window before
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternTrend {
+    /// First seen in the window
+    New,
+    /// At least 1.5 times the previous count, or absent before
+    Rising,
+    /// At most half the previous count
+    Falling,
+    Stable,
+}
+
+// ============================================================================
+// Internal Database Row Types
+// ============================================================================
+
+/// Pattern with its counts in the window and the window before
+#[derive(Debug, sqlx::FromRow)]
+pub struct LogPatternRow {
+    pub pattern_id: String,
+    pub service_name: String,
+    pub template: String,
+    pub severity_number: i32,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub count: i64,
+    pub previous_count: i64,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Summarize a pattern of the window starting at `window_start`.
+pub fn build_log_pattern(row: LogPatternRow, window_start: DateTime<Utc>) -> LogPatternSummary {
+    let change_percent = (row.previous_count > 0)
+        .then(|| (row.count - row.previous_count) as f64 / row.previous_count as f64 * 100.0);
+
+    let ratio = row.count as f64 / row.previous_count as f64;
+    let trend = if row.first_seen >= window_start {
+        PatternTrend::New
+    } else if row.previous_count == 0 || ratio >= RISING_RATIO {
+        PatternTrend::Rising
+    } else if ratio <= FALLING_RATIO {
+        PatternTrend::Falling
+    } else {
+        PatternTrend::Stable
+    };
+
+    LogPatternSummary {
+        pattern_id: row.pattern_id,
+        service_name: row.service_name,
+        template: row.template,
+        severity_number: row.severity_number,
+        count: row.count,
+        previous_count: row.previous_count,
+        change_percent,
+        trend,
+        first_seen: row.first_seen,
+        last_seen: row.last_seen,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_log_pattern_trend() {
+        let window_start = Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap();
+        let row = |first_seen: DateTime<Utc>, count: i64, previous_count: i64| LogPatternRow {
+            pattern_id: "9f86d081884c7d65".to_string(),
+            service_name: "api".to_string(),
+            template: "Connection refused by upstream <*>".to_string(),
+            severity_number: 17,
+            first_seen,
+            last_seen: window_start + Duration::hours(3),
+            count,
+            previous_count,
+        };
+        let before = window_start - Duration::days(3);
+
+        let new = build_log_pattern(row(window_start + Duration::hours(1), 40, 0), window_start);
+        assert_eq!(new.trend, PatternTrend::New);
+        assert_eq!(new.change_percent, None);
+
+        let rising = build_log_pattern(row(before, 300, 100), window_start);
+        assert_eq!(rising.trend, PatternTrend::Rising);
+        assert_eq!(rising.change_percent, Some(200.0));
+
+        assert_eq!(
+            build_log_pattern(row(before, 40, 100), window_start).trend,
+            PatternTrend::Falling
+        );
+        assert_eq!(
+            build_log_pattern(row(before, 110, 100), window_start).trend,
+            PatternTrend::Stable
+        );
+        assert_eq!(
+            build_log_pattern(row(before, 5, 0), window_start).trend,
+            PatternTrend::Rising
+        );
+
+        let query = LogPatternsQuery {
+            window_hours: 24,
+            service_name: None,
+            min_severity: Some("error".to_string()),
+            new_only: false,
+            limit: 50,
+        };
+        assert!(query.validate().is_ok());
+        assert_eq!(query.min_severity_number(), 17);
+        assert!(LogPatternsQuery {
+            min_severity: Some("loud".to_string()),
+            ..query.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(LogPatternsQuery {
+            window_hours: 24 * 60,
+            ..query
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_log_query() {
+        let query = LogQuery {
+            start_time: None,
+            end_time: None,
+            service_name: Some("api".to_string()),
+            min_severity: Some("warn".to_string()),
+            search: Some(" 100%_done\\ ".to_string()),
+            trace_id: Some("4bf92f3577b34da6a3ce929d0e0e4736".to_string()),
+            pattern_id: None,
+            cursor: None,
+            limit: 100,
+        };
+        assert!(query.validate().is_ok());
+        assert_eq!(query.min_severity_number(), 13);
+        assert_eq!(
+            query.search_pattern().as_deref(),
+            Some("%100\\%\\_done\\\\%")
+        );
+
+        assert!(LogQuery {
+            trace_id: Some("not-a-trace".to_string()),
+            ..query.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(LogQuery {
+            search: Some("  ".to_string()),
+            ..query.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(LogQuery {
+            limit: 0,
+            ..query.clone()
+        }
+        .validate()
+        .is_err());
+        let end = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+        assert!(LogQuery {
+            start_time: Some(end - Duration::days(31)),
+            end_time: Some(end),
+            ..query
+        }
+        .validate()
+        .is_err());
+
+        let cursor = LogCursor {
+            timestamp: end,
+            id: Uuid::new_v4(),
+        };
+        assert_eq!(LogCursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(LogCursor::decode("not a cursor").is_err());
+    }
+}
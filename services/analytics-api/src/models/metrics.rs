@@ -6,6 +6,8 @@
 //! - `GET /api/v1/metrics` - Time-series metrics query
 //! - `GET /api/v1/metrics/summary` - Metrics summary with period comparison
 //! - `POST /api/v1/metrics/query` - Custom metrics query with advanced features
+//! - `GET /api/v1/metrics/exemplars` - Sample traces behind histogram buckets
+//! - `GET /api/v1/metrics/cardinality` - Metrics with the most attribute sets
 //!
 //! ## Features
 //! - Multiple metric types (duration, cost, tokens, errors, throughput)
@@ -14,6 +16,8 @@
 //! - Group by multiple dimensions
 //! - HAVING clause support for filtering aggregated results
 //! - Automatic continuous aggregate table selection
+//! - Percentiles merged from collector histograms instead of raw trace scans
+//! - Summary top items ranked from the daily top-N rollups
 //!
 //! ## Security
 //! - All metric names validated against whitelist
@@ -21,9 +25,13 @@
 //! - Query complexity limits enforced
 //! - SQL injection prevention via parameterized queries
 
+use crate::models::federation::FederationInfo;
+use crate::services::top_n::{TopNOrder, TopNRow};
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
 
 // ============================================================================
 // Enums and Types
@@ -94,6 +102,28 @@ impl MetricType {
     pub fn requires_raw_data(&self) -> bool {
         false // Most metrics are available in aggregates
     }
+
+    /// Returns the collector histogram backing this metric and the suffix of
+    /// its percentile columns (e.g. `p95_duration_ms`)
+    pub fn histogram(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            MetricType::Duration => Some(("llm.request.duration", "duration_ms")),
+            MetricType::TotalCost => Some(("llm.request.cost", "cost_usd")),
+            _ => None,
+        }
+    }
+
+    /// Returns the llm_traces column behind this metric's percentiles and the
+    /// suffix of its percentile columns (e.g. `p95_ttft_ms`)
+    pub fn percentile_column(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            MetricType::Duration => Some(("duration_ms", "duration_ms")),
+            MetricType::TotalCost => Some(("total_cost_usd", "cost_usd")),
+            MetricType::TotalTokens => Some(("total_tokens", "tokens")),
+            MetricType::TimeToFirstToken => Some(("ttft_ms", "ttft_ms")),
+            _ => None,
+        }
+    }
 }
 
 /// Aggregation functions for metrics
@@ -136,6 +166,17 @@ impl AggregationFunction {
         }
     }
 
+    /// Returns the quantile of a percentile aggregation
+    pub fn quantile(&self) -> Option<f64> {
+        match self {
+            AggregationFunction::P50 => Some(0.50),
+            AggregationFunction::P90 => Some(0.90),
+            AggregationFunction::P95 => Some(0.95),
+            AggregationFunction::P99 => Some(0.99),
+            _ => None,
+        }
+    }
+
     /// Returns whether this aggregation requires raw data query
     pub fn requires_raw_data(&self) -> bool {
         matches!(
@@ -226,12 +267,76 @@ impl DimensionName {
     }
 }
 
+/// Source of percentile queries that collector histograms cannot answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PercentileSource {
+    /// Hourly `percentile_agg` sketches in `llm_percentiles_1hour`, rolled up
+    /// per time bucket (requires the TimescaleDB toolkit)
+    Sketch,
+    /// `PERCENTILE_CONT` over raw `llm_traces` rows
+    Raw,
+}
+
+impl PercentileSource {
+    /// Returns the data source reported in query metadata
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PercentileSource::Sketch => "percentile_agg",
+            PercentileSource::Raw => "raw",
+        }
+    }
+
+    /// Returns the table queried
+    pub fn table(&self) -> &'static str {
+        match self {
+            PercentileSource::Sketch => "llm_percentiles_1hour",
+            PercentileSource::Raw => "llm_traces",
+        }
+    }
+
+    /// Returns the SQL expression for an aggregation of a percentile column,
+    /// as returned by [`MetricType::percentile_column`]
+    pub fn aggregate_sql(
+        &self,
+        aggregation: &AggregationFunction,
+        column: &str,
+        suffix: &str,
+    ) -> String {
+        match self {
+            PercentileSource::Sketch => {
+                let sketch = format!("rollup({}_sketch)", suffix);
+                match aggregation {
+                    AggregationFunction::Avg => format!("mean({})", sketch),
+                    AggregationFunction::Sum => {
+                        format!("mean({0}) * num_vals({0})", sketch)
+                    }
+                    AggregationFunction::Count => format!("num_vals({})", sketch),
+                    AggregationFunction::Min => format!("MIN(min_{})::DOUBLE PRECISION", suffix),
+                    AggregationFunction::Max => format!("MAX(max_{})::DOUBLE PRECISION", suffix),
+                    percentile => format!(
+                        "approx_percentile({:.2}, {})",
+                        percentile.quantile().unwrap_or(0.5),
+                        sketch
+                    ),
+                }
+            }
+            PercentileSource::Raw => match aggregation {
+                AggregationFunction::Count => format!("COUNT({})::DOUBLE PRECISION", column),
+                percentile if percentile.requires_raw_data() => {
+                    format!("{} {}::DOUBLE PRECISION)", percentile.to_sql(), column)
+                }
+                other => format!("{}({})::DOUBLE PRECISION", other.to_sql(), column),
+            },
+        }
+    }
+}
+
 // ============================================================================
 // Request Models
 // ============================================================================
 
 /// Request for GET /api/v1/metrics
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MetricsQueryRequest {
     /// Metric names to query (e.g., ["request_count", "duration", "total_cost"])
     pub metrics: Vec<MetricType>,
@@ -313,6 +418,62 @@ fn default_limit() -> i32 {
     1000
 }
 
+/// Request for GET /api/v1/metrics/exemplars
+#[derive(Debug, Deserialize, Clone)]
+pub struct ExemplarsQueryRequest {
+    /// Histogram metric name (e.g., "llm.request.duration", "llm.request.cost")
+    pub metric: String,
+
+    /// Start time (default: 1 hour ago)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End time (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Only exemplars with a value >= min_value
+    pub min_value: Option<f64>,
+
+    /// Only exemplars with a value <= max_value
+    pub max_value: Option<f64>,
+
+    /// Filter by provider
+    pub provider: Option<String>,
+
+    /// Filter by model
+    pub model: Option<String>,
+
+    /// Maximum number of exemplars (max 100)
+    #[serde(default = "default_exemplar_limit")]
+    pub limit: i64,
+}
+
+fn default_exemplar_limit() -> i64 {
+    20
+}
+
+/// Request for GET /api/v1/metrics/cardinality
+#[derive(Debug, Deserialize, Clone)]
+pub struct CardinalityReportRequest {
+    /// Hours of data points to inspect, ending now (max 168)
+    #[serde(default = "default_cardinality_window_hours")]
+    pub window_hours: i64,
+
+    /// Filter by service
+    pub service_name: Option<String>,
+
+    /// Maximum number of metrics (max 100)
+    #[serde(default = "default_cardinality_limit")]
+    pub limit: i64,
+}
+
+fn default_cardinality_window_hours() -> i64 {
+    24
+}
+
+fn default_cardinality_limit() -> i64 {
+    20
+}
+
 /// Metric with its aggregation function
 #[derive(Debug, Deserialize, Clone)]
 pub struct MetricAggregation {
@@ -390,7 +551,7 @@ pub struct SortConfig {
 // ============================================================================
 
 /// Response for GET /api/v1/metrics
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MetricsResponse {
     /// Query metadata
     pub metadata: MetricsMetadata,
@@ -398,14 +559,14 @@ pub struct MetricsResponse {
     pub data: Vec<MetricDataPoint>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MetricsMetadata {
     pub interval: String,
     pub start_time: DateTime<Utc>,
     pub end_time: DateTime<Utc>,
     pub metrics: Vec<String>,
     pub group_by: Vec<String>,
-    pub data_source: String, // "aggregate" or "raw"
+    pub data_source: String, // "aggregate", "histogram", "percentile_agg" or "raw"
     pub total_points: usize,
 }
 
@@ -418,7 +579,18 @@ pub struct MetricDataPoint {
     pub metrics: HashMap<String, MetricValue>,
 }
 
-#[derive(Debug, Serialize)]
+impl<'de> Deserialize<'de> for MetricDataPoint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (timestamp, dimensions, metrics) = split_data_point(deserializer)?;
+        Ok(MetricDataPoint {
+            timestamp,
+            dimensions,
+            metrics,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum MetricValue {
     Integer(i64),
@@ -426,8 +598,65 @@ pub enum MetricValue {
     Null,
 }
 
-/// Response for GET /api/v1/metrics/summary
+/// Response for GET /api/v1/metrics/exemplars
+#[derive(Debug, Serialize)]
+pub struct ExemplarsResponse {
+    pub metric: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    /// Exemplars, most recent first
+    pub exemplars: Vec<MetricExemplar>,
+}
+
+/// A sample request attached to a histogram bucket
+#[derive(Debug, Serialize)]
+pub struct MetricExemplar {
+    pub trace_id: String,
+    pub span_id: String,
+    pub value: f64,
+    pub timestamp: DateTime<Utc>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub attributes: serde_json::Value,
+    /// Link to the trace detail endpoint
+    pub trace_url: String,
+}
+
+/// Response for GET /api/v1/metrics/cardinality
 #[derive(Debug, Serialize)]
+pub struct CardinalityReportResponse {
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    /// Metrics, most attribute sets first
+    pub metrics: Vec<MetricCardinality>,
+}
+
+/// Attribute cardinality of one metric
+#[derive(Debug, Serialize)]
+pub struct MetricCardinality {
+    pub metric: String,
+    pub service_name: String,
+    /// Distinct attribute sets
+    pub series: i64,
+    pub data_points: i64,
+    /// Data points with attributes replaced by the storage cardinality limits
+    pub overflow_points: i64,
+    pub overflow_percent: f64,
+    /// Attribute keys with the most distinct values
+    pub top_attributes: Vec<AttributeCardinality>,
+}
+
+/// Distinct values of one attribute key of a metric
+#[derive(Debug, Serialize, PartialEq)]
+pub struct AttributeCardinality {
+    pub key: String,
+    pub distinct_values: i64,
+    /// Whether values of this key were replaced by `__overflow__`
+    pub overflowed: bool,
+}
+
+/// Response for GET /api/v1/metrics/summary
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MetricsSummaryResponse {
     /// Current period summary
     pub current_period: PeriodSummary,
@@ -439,9 +668,12 @@ pub struct MetricsSummaryResponse {
     pub top_items: TopItems,
     /// Quality metrics
     pub quality: QualitySummary,
+    /// Regions merged into a global summary (`scope=global` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub federation: Option<FederationInfo>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PeriodSummary {
     pub start_time: DateTime<Utc>,
     pub end_time: DateTime<Utc>,
@@ -456,7 +688,7 @@ pub struct PeriodSummary {
     pub unique_sessions: i64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PeriodChanges {
     pub requests_change_pct: f64,
     pub cost_change_pct: f64,
@@ -464,7 +696,7 @@ pub struct PeriodChanges {
     pub error_rate_change_pct: f64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TopItems {
     pub by_cost: Vec<TopItem>,
     pub by_requests: Vec<TopItem>,
@@ -472,7 +704,7 @@ pub struct TopItems {
     pub by_errors: Vec<TopItem>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TopItem {
     pub provider: String,
     pub model: String,
@@ -480,7 +712,48 @@ pub struct TopItem {
     pub percentage: f64,
 }
 
-#[derive(Debug, Serialize)]
+impl TopItem {
+    /// Build a ranked list from provider/model rows.
+    ///
+    /// `percentage` is each row's share of the listed rows' cost, requests,
+    /// request time or errors. Rows without errors are left out of the error
+    /// ranking.
+    pub fn ranked(rows: Vec<TopNRow>, order: TopNOrder) -> Vec<TopItem> {
+        let share_of = |row: &TopNRow| match order {
+            TopNOrder::Cost => row.total_cost,
+            TopNOrder::Requests => row.request_count as f64,
+            TopNOrder::AvgDuration => row.duration_sum_ms,
+            TopNOrder::Errors => row.error_count as f64,
+        };
+        let rows: Vec<TopNRow> = rows
+            .into_iter()
+            .filter(|row| order != TopNOrder::Errors || row.error_count > 0)
+            .collect();
+        let total: f64 = rows.iter().map(share_of).sum();
+
+        rows.into_iter()
+            .map(|row| {
+                let percentage = if total > 0.0 {
+                    share_of(&row) / total * 100.0
+                } else {
+                    0.0
+                };
+                let value = match order {
+                    TopNOrder::AvgDuration => row.avg_duration_ms(),
+                    _ => share_of(&row),
+                };
+                TopItem {
+                    provider: row.provider,
+                    model: row.name,
+                    value,
+                    percentage,
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct QualitySummary {
     pub error_count: i64,
     pub success_count: i64,
@@ -489,7 +762,7 @@ pub struct QualitySummary {
     pub most_common_errors: Vec<ErrorSummaryItem>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorSummaryItem {
     pub status_code: String,
     pub count: i64,
@@ -498,13 +771,13 @@ pub struct ErrorSummaryItem {
 }
 
 /// Response for POST /api/v1/metrics/query
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CustomMetricsResponse {
     pub metadata: CustomMetricsMetadata,
     pub data: Vec<CustomMetricDataPoint>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CustomMetricsMetadata {
     pub interval: String,
     pub start_time: DateTime<Utc>,
@@ -524,6 +797,66 @@ pub struct CustomMetricDataPoint {
     pub metrics: HashMap<String, MetricValue>,
 }
 
+impl<'de> Deserialize<'de> for CustomMetricDataPoint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (timestamp, dimensions, metrics) = split_data_point(deserializer)?;
+        Ok(CustomMetricDataPoint {
+            timestamp,
+            dimensions,
+            metrics,
+        })
+    }
+}
+
+/// Fields of a data point read back from its flattened JSON form
+type DataPointFields = (
+    DateTime<Utc>,
+    HashMap<String, String>,
+    HashMap<String, MetricValue>,
+);
+
+/// Split a flattened data point into its dimensions and metrics
+///
+/// Both maps are flattened into the same object, so they are told apart by
+/// value: dimensions are strings, metrics are numbers or null.
+fn split_data_point<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<DataPointFields, D::Error> {
+    let mut fields = serde_json::Map::deserialize(deserializer)?;
+    let timestamp = fields
+        .remove("timestamp")
+        .ok_or_else(|| D::Error::missing_field("timestamp"))?;
+    let timestamp = DateTime::<Utc>::deserialize(timestamp).map_err(D::Error::custom)?;
+
+    let mut dimensions = HashMap::new();
+    let mut metrics = HashMap::new();
+    for (key, value) in fields {
+        match value {
+            serde_json::Value::String(value) => {
+                dimensions.insert(key, value);
+            }
+            serde_json::Value::Null => {
+                metrics.insert(key, MetricValue::Null);
+            }
+            serde_json::Value::Number(number) => {
+                let value = match number.as_i64() {
+                    Some(value) => MetricValue::Integer(value),
+                    None => MetricValue::Float(number.as_f64().unwrap_or_default()),
+                };
+                metrics.insert(key, value);
+            }
+            other => {
+                return Err(D::Error::custom(format!(
+                    "unexpected value for '{}': {}",
+                    key, other
+                )))
+            }
+        }
+    }
+
+    Ok((timestamp, dimensions, metrics))
+}
+
 // ============================================================================
 // Internal Database Row Types
 // ============================================================================
@@ -585,6 +918,56 @@ pub struct TopItemRow {
     pub value: Option<f64>,
 }
 
+/// Exemplar row, unpacked from metric_data_points.exemplars
+#[derive(Debug, sqlx::FromRow)]
+pub struct ExemplarRow {
+    pub exemplar: serde_json::Value,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+}
+
+/// Metric cardinality row
+#[derive(Debug, sqlx::FromRow)]
+pub struct MetricCardinalityRow {
+    pub metric_id: Uuid,
+    pub name: String,
+    pub service_name: String,
+    pub series: i64,
+    pub data_points: i64,
+    pub overflow_points: i64,
+}
+
+/// Attribute cardinality row, per metric and attribute key
+#[derive(Debug, sqlx::FromRow)]
+pub struct AttributeCardinalityRow {
+    pub metric_id: Uuid,
+    pub key: String,
+    pub distinct_values: i64,
+    pub overflowed: bool,
+}
+
+/// Histogram bucket row, summed per time bucket, group and boundary
+#[derive(Debug, sqlx::FromRow)]
+pub struct HistogramBucketRow {
+    pub bucket: DateTime<Utc>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub boundary: f64,
+    pub count: i64,
+}
+
+/// Histogram totals row, per time bucket and group
+#[derive(Debug, sqlx::FromRow)]
+pub struct HistogramTotalsRow {
+    pub bucket: DateTime<Utc>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub count: i64,
+    pub sum: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
 /// Error summary row
 #[derive(Debug, sqlx::FromRow)]
 pub struct ErrorSummaryRow {
@@ -593,6 +976,257 @@ pub struct ErrorSummaryRow {
     pub sample_error_message: Option<String>,
 }
 
+// ============================================================================
+// Federation
+// ============================================================================
+
+impl PeriodChanges {
+    /// Percentage changes from `previous` to `current`; a change from zero
+    /// counts as 100%.
+    pub fn between(current: &PeriodSummary, previous: &PeriodSummary) -> PeriodChanges {
+        let calc_change = |curr: f64, prev: f64| -> f64 {
+            if prev == 0.0 {
+                if curr > 0.0 {
+                    100.0
+                } else {
+                    0.0
+                }
+            } else {
+                ((curr - prev) / prev) * 100.0
+            }
+        };
+
+        PeriodChanges {
+            requests_change_pct: calc_change(
+                current.total_requests as f64,
+                previous.total_requests as f64,
+            ),
+            cost_change_pct: calc_change(current.total_cost_usd, previous.total_cost_usd),
+            duration_change_pct: calc_change(current.avg_duration_ms, previous.avg_duration_ms),
+            error_rate_change_pct: calc_change(current.error_rate, previous.error_rate),
+        }
+    }
+}
+
+impl MetricsSummaryResponse {
+    /// Merge summaries of other regions into this one.
+    ///
+    /// Counts and costs are summed; average durations and rates are weighted
+    /// by requests, and the p95 duration is the highest regional p95 (an upper
+    /// bound). The previous period and changes are kept only if every region
+    /// returned them. Top items are re-ranked and cut to `top_limit`.
+    pub fn merge(self, others: Vec<MetricsSummaryResponse>, top_limit: usize) -> Self {
+        if others.is_empty() {
+            return self;
+        }
+
+        let mut current = vec![self.current_period];
+        let mut previous = self.previous_period.map(|p| vec![p]);
+        let mut top_items = vec![self.top_items];
+        let mut quality = vec![self.quality];
+        for other in others {
+            current.push(other.current_period);
+            previous = match (previous, other.previous_period) {
+                (Some(mut all), Some(p)) => {
+                    all.push(p);
+                    Some(all)
+                }
+                _ => None,
+            };
+            top_items.push(other.top_items);
+            quality.push(other.quality);
+        }
+
+        let current_period = PeriodSummary::merge(current);
+        let previous_period = previous.map(PeriodSummary::merge);
+        let changes = previous_period
+            .as_ref()
+            .map(|previous| PeriodChanges::between(&current_period, previous));
+
+        MetricsSummaryResponse {
+            current_period,
+            previous_period,
+            changes,
+            top_items: TopItems::merge(top_items, top_limit),
+            quality: QualitySummary::merge(quality),
+            federation: None,
+        }
+    }
+}
+
+impl PeriodSummary {
+    /// Combine summaries of the same period from several regions
+    fn merge(summaries: Vec<PeriodSummary>) -> PeriodSummary {
+        let mut summaries = summaries.into_iter();
+        let mut merged = summaries.next().expect("at least one period summary");
+        let weighted = |s: &PeriodSummary, value: f64| value * s.total_requests as f64;
+        let mut duration_sum = weighted(&merged, merged.avg_duration_ms);
+        let mut error_sum = weighted(&merged, merged.error_rate);
+        let mut success_sum = weighted(&merged, merged.success_rate);
+
+        for summary in summaries {
+            duration_sum += weighted(&summary, summary.avg_duration_ms);
+            error_sum += weighted(&summary, summary.error_rate);
+            success_sum += weighted(&summary, summary.success_rate);
+            merged.total_requests += summary.total_requests;
+            merged.total_cost_usd += summary.total_cost_usd;
+            merged.total_tokens += summary.total_tokens;
+            merged.p95_duration_ms = match (merged.p95_duration_ms, summary.p95_duration_ms) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            };
+            merged.unique_users += summary.unique_users;
+            merged.unique_sessions += summary.unique_sessions;
+        }
+
+        if merged.total_requests > 0 {
+            let total_requests = merged.total_requests as f64;
+            merged.avg_duration_ms = duration_sum / total_requests;
+            merged.error_rate = error_sum / total_requests;
+            merged.success_rate = success_sum / total_requests;
+        }
+        merged
+    }
+}
+
+impl TopItems {
+    /// Combine regional rankings of provider/model pairs
+    fn merge(all: Vec<TopItems>, limit: usize) -> TopItems {
+        let mut by_cost = Vec::new();
+        let mut by_requests = Vec::new();
+        let mut by_duration = Vec::new();
+        let mut by_errors = Vec::new();
+
+        for items in all {
+            // Average durations are weighted by the pair's requests in the region
+            let requests: HashMap<(&str, &str), f64> = items
+                .by_requests
+                .iter()
+                .map(|i| ((i.provider.as_str(), i.model.as_str()), i.value))
+                .collect();
+            for item in &items.by_duration {
+                let weight = requests
+                    .get(&(item.provider.as_str(), item.model.as_str()))
+                    .copied()
+                    .unwrap_or(1.0);
+                by_duration.push((item.provider.clone(), item.model.clone(), item.value * weight, weight));
+            }
+
+            by_cost.extend(items.by_cost);
+            by_requests.extend(items.by_requests);
+            by_errors.extend(items.by_errors);
+        }
+
+        let summed = |items: Vec<TopItem>| {
+            items
+                .into_iter()
+                .map(|i| (i.provider, i.model, i.value, 1.0))
+                .collect()
+        };
+
+        TopItems {
+            by_cost: TopItem::merged(summed(by_cost), false, limit),
+            by_requests: TopItem::merged(summed(by_requests), false, limit),
+            by_duration: TopItem::merged(by_duration, true, limit),
+            by_errors: TopItem::merged(summed(by_errors), false, limit),
+        }
+    }
+}
+
+impl TopItem {
+    /// Rank `(provider, model, total, weight)` entries, summed per pair.
+    ///
+    /// With `average`, each pair's value is its total over its weight.
+    /// `percentage` is the share of the summed totals, as in [`TopItem::ranked`].
+    fn merged(entries: Vec<(String, String, f64, f64)>, average: bool, limit: usize) -> Vec<TopItem> {
+        let mut pairs: Vec<(String, String, f64, f64)> = Vec::new();
+        for (provider, model, total, weight) in entries {
+            match pairs.iter_mut().find(|p| p.0 == provider && p.1 == model) {
+                Some(pair) => {
+                    pair.2 += total;
+                    pair.3 += weight;
+                }
+                None => pairs.push((provider, model, total, weight)),
+            }
+        }
+        let grand_total: f64 = pairs.iter().map(|p| p.2).sum();
+
+        let mut items: Vec<TopItem> = pairs
+            .into_iter()
+            .map(|(provider, model, total, weight)| TopItem {
+                provider,
+                model,
+                value: if average && weight > 0.0 {
+                    total / weight
+                } else {
+                    total
+                },
+                percentage: if grand_total > 0.0 {
+                    total / grand_total * 100.0
+                } else {
+                    0.0
+                },
+            })
+            .collect();
+        items.sort_by(|a, b| b.value.partial_cmp(&a.value).unwrap_or(std::cmp::Ordering::Equal));
+        items.truncate(limit);
+        items
+    }
+}
+
+impl QualitySummary {
+    /// Sum regional error counts and re-rank the most common errors
+    fn merge(summaries: Vec<QualitySummary>) -> QualitySummary {
+        let mut error_count = 0;
+        let mut success_count = 0;
+        let mut errors: Vec<ErrorSummaryItem> = Vec::new();
+
+        for summary in summaries {
+            error_count += summary.error_count;
+            success_count += summary.success_count;
+            for item in summary.most_common_errors {
+                match errors.iter_mut().find(|e| e.status_code == item.status_code) {
+                    Some(existing) => {
+                        existing.count += item.count;
+                        if existing.sample_message.is_none() {
+                            existing.sample_message = item.sample_message;
+                        }
+                    }
+                    None => errors.push(item),
+                }
+            }
+        }
+
+        let total_count = error_count + success_count;
+        let error_rate = if total_count > 0 {
+            error_count as f64 / total_count as f64
+        } else {
+            0.0
+        };
+
+        errors.sort_by(|a, b| b.count.cmp(&a.count));
+        errors.truncate(MOST_COMMON_ERRORS_LIMIT);
+        for item in &mut errors {
+            item.percentage = if error_count > 0 {
+                (item.count as f64 / error_count as f64) * 100.0
+            } else {
+                0.0
+            };
+        }
+
+        QualitySummary {
+            error_count,
+            success_count,
+            error_rate,
+            success_rate: 1.0 - error_rate,
+            most_common_errors: errors,
+        }
+    }
+}
+
+/// Number of status codes listed in a quality summary
+const MOST_COMMON_ERRORS_LIMIT: usize = 10;
+
 // ============================================================================
 // Validation
 // ============================================================================
@@ -626,6 +1260,211 @@ impl MetricsQueryRequest {
 
         Ok(())
     }
+
+    /// Returns whether collector histograms can answer this query. They are
+    /// kept per provider and model for latency and cost only.
+    pub fn histogram_supported(&self) -> bool {
+        self.metrics.iter().all(|m| m.histogram().is_some())
+            && self
+                .group_by
+                .iter()
+                .all(|d| matches!(d, DimensionName::Provider | DimensionName::Model))
+            && self.environment.is_none()
+            && self.user_id.is_none()
+    }
+
+    /// Returns whether the hourly percentile sketches can answer this query.
+    /// They cover hour and day buckets without user or session breakdowns.
+    pub fn sketch_supported(&self) -> bool {
+        matches!(self.interval, TimeInterval::OneHour | TimeInterval::OneDay)
+            && self.group_by.iter().all(|d| d.available_in_aggregates())
+            && self.user_id.is_none()
+    }
+}
+
+impl ExemplarsQueryRequest {
+    /// Validates the request
+    pub fn validate(&self) -> Result<(), String> {
+        if self.metric.trim().is_empty() {
+            return Err("Metric name must be specified".to_string());
+        }
+
+        if self.limit < 1 || self.limit > 100 {
+            return Err("Limit must be between 1 and 100".to_string());
+        }
+
+        if let (Some(min), Some(max)) = (self.min_value, self.max_value) {
+            if min > max {
+                return Err("min_value must be <= max_value".to_string());
+            }
+        }
+
+        // Validate time range
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            if start >= end {
+                return Err("Start time must be before end time".to_string());
+            }
+
+            let duration = end - start;
+            if duration.num_days() > 90 {
+                return Err("Maximum time range is 90 days".to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl CardinalityReportRequest {
+    /// Validates the request
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window_hours < 1 || self.window_hours > 168 {
+            return Err("window_hours must be between 1 and 168".to_string());
+        }
+
+        if self.limit < 1 || self.limit > 100 {
+            return Err("Limit must be between 1 and 100".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Attribute keys listed per metric in the cardinality report
+pub const TOP_ATTRIBUTES: usize = 5;
+
+/// Build the report entry of a metric from its attribute rows.
+pub fn build_metric_cardinality(
+    row: MetricCardinalityRow,
+    mut attributes: Vec<AttributeCardinalityRow>,
+) -> MetricCardinality {
+    attributes.sort_by(|a, b| {
+        b.distinct_values
+            .cmp(&a.distinct_values)
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    attributes.truncate(TOP_ATTRIBUTES);
+
+    let overflow_percent = if row.data_points > 0 {
+        row.overflow_points as f64 / row.data_points as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    MetricCardinality {
+        metric: row.name,
+        service_name: row.service_name,
+        series: row.series,
+        data_points: row.data_points,
+        overflow_points: row.overflow_points,
+        overflow_percent,
+        top_attributes: attributes
+            .into_iter()
+            .map(|attribute| AttributeCardinality {
+                key: attribute.key,
+                distinct_values: attribute.distinct_values,
+                overflowed: attribute.overflowed,
+            })
+            .collect(),
+    }
+}
+
+/// Time bucket, provider and model of a merged histogram
+pub type HistogramKey = (DateTime<Utc>, Option<String>, Option<String>);
+
+/// Histogram merged from the delta data points of one time bucket and group
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergedHistogram {
+    /// (upper boundary, count) ordered by boundary
+    pub buckets: Vec<(f64, i64)>,
+    pub count: i64,
+    pub sum: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl MergedHistogram {
+    /// Estimates the `q` quantile, assuming values are spread evenly within
+    /// their bucket. The first bucket starts at `min` (or zero), and values
+    /// above the last boundary resolve to `max` (or the last boundary).
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        let bucketed: i64 = self.buckets.iter().map(|(_, count)| count).sum();
+        let total = self.count.max(bucketed);
+        if total == 0 || !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+
+        let rank = q * total as f64;
+        let mut seen = 0i64;
+        let mut lower = None;
+        let mut estimate = None;
+        for &(boundary, count) in &self.buckets {
+            let start = lower.unwrap_or(match self.min {
+                Some(min) if min <= boundary => min,
+                _ if boundary > 0.0 => 0.0,
+                _ => boundary,
+            });
+            if count > 0 && rank <= (seen + count) as f64 {
+                let fraction = (rank - seen as f64) / count as f64;
+                estimate = Some(start + (boundary - start) * fraction.max(0.0));
+                break;
+            }
+            seen += count;
+            lower = Some(boundary);
+        }
+
+        let estimate = estimate
+            .or(self.max)
+            .or_else(|| self.buckets.last().map(|(boundary, _)| *boundary))?;
+        let estimate = self.min.map_or(estimate, |min| estimate.max(min));
+        Some(self.max.map_or(estimate, |max| estimate.min(max)))
+    }
+
+    /// Computes an aggregation over the recorded values
+    pub fn aggregate(&self, aggregation: &AggregationFunction) -> MetricValue {
+        let value = match aggregation {
+            AggregationFunction::Count => return MetricValue::Integer(self.count),
+            AggregationFunction::Sum => Some(self.sum),
+            AggregationFunction::Avg => (self.count > 0).then(|| self.sum / self.count as f64),
+            AggregationFunction::Min => self.min,
+            AggregationFunction::Max => self.max,
+            percentile => percentile.quantile().and_then(|q| self.quantile(q)),
+        };
+        value.map_or(MetricValue::Null, MetricValue::Float)
+    }
+}
+
+/// Merge histogram rows into one histogram per time bucket and group.
+pub fn merge_histogram_rows(
+    totals: Vec<HistogramTotalsRow>,
+    buckets: Vec<HistogramBucketRow>,
+) -> BTreeMap<HistogramKey, MergedHistogram> {
+    let mut histograms: BTreeMap<HistogramKey, MergedHistogram> = totals
+        .into_iter()
+        .map(|row| {
+            let histogram = MergedHistogram {
+                buckets: Vec::new(),
+                count: row.count,
+                sum: row.sum,
+                min: row.min,
+                max: row.max,
+            };
+            ((row.bucket, row.provider, row.model), histogram)
+        })
+        .collect();
+
+    for row in buckets {
+        histograms
+            .entry((row.bucket, row.provider, row.model))
+            .or_default()
+            .buckets
+            .push((row.boundary, row.count));
+    }
+    for histogram in histograms.values_mut() {
+        histogram.buckets.sort_by(|a, b| a.0.total_cmp(&b.0));
+    }
+
+    histograms
 }
 
 impl CustomMetricsQueryRequest {
@@ -726,6 +1565,100 @@ mod tests {
         assert!(req.validate().is_err());
     }
 
+    #[test]
+    fn test_metric_data_point_round_trip() {
+        let point = MetricDataPoint {
+            timestamp: Utc::now(),
+            dimensions: HashMap::from([("provider".to_string(), "openai".to_string())]),
+            metrics: HashMap::from([
+                ("request_count".to_string(), MetricValue::Integer(42)),
+                ("p95_duration_ms".to_string(), MetricValue::Float(1250.0)),
+                ("total_cost_usd".to_string(), MetricValue::Null),
+            ]),
+        };
+
+        let json = serde_json::to_string(&point).unwrap();
+        let parsed: MetricDataPoint = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.timestamp, point.timestamp);
+        assert_eq!(parsed.dimensions, point.dimensions);
+        assert!(matches!(
+            parsed.metrics["request_count"],
+            MetricValue::Integer(42)
+        ));
+        assert!(matches!(parsed.metrics["p95_duration_ms"], MetricValue::Float(v) if v == 1250.0));
+        assert!(matches!(
+            parsed.metrics["total_cost_usd"],
+            MetricValue::Null
+        ));
+    }
+
+    #[test]
+    fn test_percentile_source_selection() {
+        let mut req = MetricsQueryRequest {
+            metrics: vec![MetricType::Duration, MetricType::TotalCost],
+            interval: TimeInterval::OneHour,
+            start_time: None,
+            end_time: None,
+            provider: None,
+            model: None,
+            environment: None,
+            user_id: None,
+            group_by: vec![DimensionName::Provider, DimensionName::Model],
+            aggregation: Some(AggregationFunction::P95),
+            include_percentiles: true,
+        };
+        assert!(req.histogram_supported());
+        assert!(req.sketch_supported());
+
+        // Histograms have no environment attribute or TTFT metric
+        req.environment = Some("production".to_string());
+        assert!(!req.histogram_supported());
+        assert!(req.sketch_supported());
+        req.environment = None;
+        req.metrics.push(MetricType::TimeToFirstToken);
+        assert!(!req.histogram_supported());
+
+        // Sketches are hourly and not kept per user
+        req.interval = TimeInterval::FiveMinutes;
+        assert!(!req.sketch_supported());
+        req.interval = TimeInterval::OneDay;
+        req.group_by.push(DimensionName::UserId);
+        assert!(!req.sketch_supported());
+    }
+
+    #[test]
+    fn test_percentile_aggregate_sql() {
+        assert_eq!(
+            PercentileSource::Sketch.aggregate_sql(
+                &AggregationFunction::P95,
+                "duration_ms",
+                "duration_ms"
+            ),
+            "approx_percentile(0.95, rollup(duration_ms_sketch))"
+        );
+        assert_eq!(
+            PercentileSource::Sketch.aggregate_sql(&AggregationFunction::Max, "ttft_ms", "ttft_ms"),
+            "MAX(max_ttft_ms)::DOUBLE PRECISION"
+        );
+        assert_eq!(
+            PercentileSource::Raw.aggregate_sql(
+                &AggregationFunction::P50,
+                "total_cost_usd",
+                "cost_usd"
+            ),
+            "PERCENTILE_CONT(0.50) WITHIN GROUP (ORDER BY total_cost_usd::DOUBLE PRECISION)"
+        );
+        assert_eq!(
+            PercentileSource::Raw.aggregate_sql(
+                &AggregationFunction::Avg,
+                "total_tokens",
+                "tokens"
+            ),
+            "AVG(total_tokens)::DOUBLE PRECISION"
+        );
+    }
+
     #[test]
     fn test_custom_metrics_query_validation() {
         let start = Utc::now() - chrono::Duration::days(1);
@@ -749,4 +1682,174 @@ mod tests {
 
         assert!(req.validate().is_ok());
     }
+
+    #[test]
+    fn test_exemplars_query_validation() {
+        let mut req = ExemplarsQueryRequest {
+            metric: "llm.request.duration".to_string(),
+            start_time: None,
+            end_time: None,
+            min_value: Some(1000.0),
+            max_value: Some(5000.0),
+            provider: None,
+            model: None,
+            limit: default_exemplar_limit(),
+        };
+        assert!(req.validate().is_ok());
+
+        req.min_value = Some(6000.0);
+        assert!(req.validate().is_err());
+
+        req.min_value = None;
+        req.limit = 101;
+        assert!(req.validate().is_err());
+    }
+
+    #[test]
+    fn test_build_metric_cardinality() {
+        let metric_id = Uuid::new_v4();
+        let attribute =
+            |key: &str, distinct_values: i64, overflowed: bool| AttributeCardinalityRow {
+                metric_id,
+                key: key.to_string(),
+                distinct_values,
+                overflowed,
+            };
+        let row = MetricCardinalityRow {
+            metric_id,
+            name: "llm_requests_total".to_string(),
+            service_name: "gateway".to_string(),
+            series: 2000,
+            data_points: 8000,
+            overflow_points: 2000,
+        };
+        let attributes = vec![
+            attribute("model", 12, false),
+            attribute("user_id", 501, true),
+            attribute("provider", 4, false),
+            attribute("region", 6, false),
+            attribute("status", 3, false),
+            attribute("env", 3, false),
+        ];
+
+        let metric = build_metric_cardinality(row, attributes);
+        assert_eq!(metric.overflow_percent, 25.0);
+        assert_eq!(metric.top_attributes.len(), TOP_ATTRIBUTES);
+        assert_eq!(
+            metric.top_attributes[0],
+            AttributeCardinality {
+                key: "user_id".to_string(),
+                distinct_values: 501,
+                overflowed: true,
+            }
+        );
+        assert_eq!(metric.top_attributes[4].key, "env");
+
+        let req = CardinalityReportRequest {
+            window_hours: 24 * 8,
+            service_name: None,
+            limit: default_cardinality_limit(),
+        };
+        assert!(req.validate().is_err());
+    }
+
+    #[test]
+    fn test_histogram_percentiles() {
+        let bucket = Utc::now();
+        let totals = |model: &str, count: i64, sum: f64, min: Option<f64>| HistogramTotalsRow {
+            bucket,
+            provider: Some("openai".to_string()),
+            model: Some(model.to_string()),
+            count,
+            sum,
+            min,
+            max: min.map(|_| 4000.0),
+        };
+        let row = |model: &str, boundary: f64, count: i64| HistogramBucketRow {
+            bucket,
+            provider: Some("openai".to_string()),
+            model: Some(model.to_string()),
+            boundary,
+            count,
+        };
+
+        let histograms = merge_histogram_rows(
+            vec![
+                totals("gpt-4o", 20, 5000.0, Some(20.0)),
+                totals("gpt-4o-mini", 0, 0.0, None),
+            ],
+            vec![row("gpt-4o", 250.0, 9), row("gpt-4o", 100.0, 10)],
+        );
+        assert_eq!(histograms.len(), 2);
+
+        let key = |model: &str| (bucket, Some("openai".to_string()), Some(model.to_string()));
+        let histogram = &histograms[&key("gpt-4o")];
+        assert_eq!(histogram.buckets, vec![(100.0, 10), (250.0, 9)]);
+
+        // Rank 10 of 20 closes the first bucket, which starts at min
+        assert_eq!(histogram.quantile(0.5), Some(100.0));
+        assert!(matches!(
+            histogram.aggregate(&AggregationFunction::P90),
+            MetricValue::Float(v) if (v - (100.0 + 150.0 * 8.0 / 9.0)).abs() < 1e-9
+        ));
+        // The one value above 250ms resolves to max
+        assert!(matches!(
+            histogram.aggregate(&AggregationFunction::P99),
+            MetricValue::Float(v) if v == 4000.0
+        ));
+        assert!(matches!(
+            histogram.aggregate(&AggregationFunction::Avg),
+            MetricValue::Float(v) if v == 250.0
+        ));
+        assert!(matches!(
+            histogram.aggregate(&AggregationFunction::Count),
+            MetricValue::Integer(20)
+        ));
+
+        let empty = &histograms[&key("gpt-4o-mini")];
+        assert!(matches!(
+            empty.aggregate(&AggregationFunction::P50),
+            MetricValue::Null
+        ));
+        assert_eq!(
+            MetricType::Duration.histogram(),
+            Some(("llm.request.duration", "duration_ms"))
+        );
+        assert_eq!(MetricType::RequestCount.histogram(), None);
+    }
+
+    #[test]
+    fn test_ranked_top_items() {
+        let row = |model: &str, cost: f64, requests: i64, errors: i64, duration: f64| TopNRow {
+            provider: "openai".to_string(),
+            name: model.to_string(),
+            total_cost: cost,
+            prompt_cost: 0.0,
+            completion_cost: 0.0,
+            request_count: requests,
+            total_tokens: 0,
+            error_count: errors,
+            duration_sum_ms: duration,
+        };
+        let rows = || {
+            vec![
+                row("gpt-4o", 30.0, 100, 4, 50_000.0),
+                row("gpt-4o-mini", 10.0, 300, 0, 30_000.0),
+            ]
+        };
+
+        let by_cost = TopItem::ranked(rows(), TopNOrder::Cost);
+        assert_eq!(by_cost[0].model, "gpt-4o");
+        assert_eq!(by_cost[0].value, 30.0);
+        assert_eq!(by_cost[0].percentage, 75.0);
+
+        let by_duration = TopItem::ranked(rows(), TopNOrder::AvgDuration);
+        assert_eq!(by_duration[0].value, 500.0);
+        assert_eq!(by_duration[1].value, 100.0);
+        assert_eq!(by_duration[1].percentage, 37.5);
+
+        let by_errors = TopItem::ranked(rows(), TopNOrder::Errors);
+        assert_eq!(by_errors.len(), 1);
+        assert_eq!(by_errors[0].percentage, 100.0);
+    }
 }
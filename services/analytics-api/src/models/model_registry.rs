@@ -0,0 +1,256 @@
+//! # Model Registry Data Models
+//!
+//! Data structures for the model registry endpoints, where organizations
+//! register custom and fine-tuned models with their own pricing, aliases and
+//! base model, and for the rollup of usage by base model.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Longest model ID or alias
+const MAX_MODEL_NAME_LEN: usize = 256;
+
+/// Most aliases per model
+const MAX_ALIASES: usize = 20;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for POST /api/v1/model-registry and PUT /api/v1/model-registry/:id
+///
+/// PUT replaces the registration; omitted fields are cleared.
+#[derive(Debug, Deserialize, Clone)]
+pub struct RegisterModelRequest {
+    /// Model ID as reported on spans, e.g. `ft:gpt-4o-mini-2024-07-18:acme::abc123`
+    pub model_id: String,
+
+    pub provider: Option<String>,
+    pub display_name: Option<String>,
+
+    /// Other names the model is reported under
+    #[serde(default)]
+    pub aliases: Vec<String>,
+
+    /// Model this one was fine-tuned from or deploys
+    pub base_model: Option<String>,
+
+    /// USD per 1000 prompt tokens (unset: the base model's pricing)
+    pub prompt_cost_per_1k: Option<f64>,
+
+    /// USD per 1000 completion tokens; set together with the prompt price
+    pub completion_cost_per_1k: Option<f64>,
+
+    /// USD per 1000 cached prompt tokens (unset: the prompt price)
+    pub cached_prompt_cost_per_1k: Option<f64>,
+}
+
+impl RegisterModelRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_model_name("model_id", &self.model_id)?;
+
+        if self.aliases.len() > MAX_ALIASES {
+            return Err(format!("At most {} aliases are allowed", MAX_ALIASES));
+        }
+        for (i, alias) in self.aliases.iter().enumerate() {
+            validate_model_name("Alias", alias)?;
+            if alias == &self.model_id || self.aliases[..i].contains(alias) {
+                return Err(format!("Alias '{}' is listed twice", alias));
+            }
+        }
+
+        if let Some(base_model) = &self.base_model {
+            validate_model_name("base_model", base_model)?;
+            if base_model == &self.model_id || self.aliases.contains(base_model) {
+                return Err("A model cannot be its own base model".to_string());
+            }
+        }
+
+        for (name, price) in [
+            ("prompt_cost_per_1k", self.prompt_cost_per_1k),
+            ("completion_cost_per_1k", self.completion_cost_per_1k),
+            ("cached_prompt_cost_per_1k", self.cached_prompt_cost_per_1k),
+        ] {
+            if let Some(price) = price {
+                if !price.is_finite() || price < 0.0 {
+                    return Err(format!("{} must be a non-negative number", name));
+                }
+            }
+        }
+
+        if self.prompt_cost_per_1k.is_some() != self.completion_cost_per_1k.is_some() {
+            return Err(
+                "prompt_cost_per_1k and completion_cost_per_1k must be set together".to_string(),
+            );
+        }
+        if self.cached_prompt_cost_per_1k.is_some() && self.prompt_cost_per_1k.is_none() {
+            return Err("cached_prompt_cost_per_1k requires prompt_cost_per_1k".to_string());
+        }
+
+        if self.prompt_cost_per_1k.is_none() && self.base_model.is_none() {
+            return Err("A model needs pricing or a base model".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Names the model is reported under: its ID and aliases
+    pub fn names(&self) -> Vec<String> {
+        std::iter::once(self.model_id.clone())
+            .chain(self.aliases.iter().cloned())
+            .collect()
+    }
+}
+
+fn validate_model_name(what: &str, name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err(format!("{} cannot be empty", what));
+    }
+    if name.len() > MAX_MODEL_NAME_LEN {
+        return Err(format!(
+            "{} cannot be longer than {} characters",
+            what, MAX_MODEL_NAME_LEN
+        ));
+    }
+    if name.chars().any(char::is_whitespace) {
+        return Err(format!("{} cannot contain whitespace", what));
+    }
+    Ok(())
+}
+
+/// Query parameters for GET /api/v1/model-registry/rollup
+#[derive(Debug, Deserialize, Clone)]
+pub struct BaseModelRollupRequest {
+    /// Start of the window (default: 30 days before end_time)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End of the window (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    pub provider: Option<String>,
+    pub environment: Option<String>,
+
+    /// Maximum base models returned, most expensive first (default: 100)
+    #[serde(default = "default_rollup_limit")]
+    pub limit: i64,
+}
+
+fn default_rollup_limit() -> i64 {
+    100
+}
+
+impl BaseModelRollupRequest {
+    /// Validate the request and resolve the window
+    pub fn validate(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+        let end_time = self.end_time.unwrap_or_else(Utc::now);
+        let start_time = self.start_time.unwrap_or(end_time - Duration::days(30));
+
+        if start_time >= end_time {
+            return Err("Start time must be before end time".to_string());
+        }
+
+        if end_time - start_time > Duration::days(90) {
+            return Err("Time range cannot exceed 90 days".to_string());
+        }
+
+        if !(1..=1000).contains(&self.limit) {
+            return Err("Limit must be between 1 and 1000".to_string());
+        }
+
+        Ok((start_time, end_time))
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// A custom or fine-tuned model registered by the organization
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct RegisteredModel {
+    pub id: Uuid,
+    pub model_id: String,
+    pub provider: Option<String>,
+    pub display_name: Option<String>,
+    pub aliases: Vec<String>,
+    pub base_model: Option<String>,
+    pub prompt_cost_per_1k: Option<f64>,
+    pub completion_cost_per_1k: Option<f64>,
+    pub cached_prompt_cost_per_1k: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: Option<String>,
+}
+
+/// Response for GET /api/v1/model-registry
+#[derive(Debug, Serialize)]
+pub struct RegisteredModelListResponse {
+    pub models: Vec<RegisteredModel>,
+}
+
+/// Usage of all models sharing a base model
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct BaseModelRollup {
+    /// Root of the models' lineage (registered base models, then fine-tune IDs)
+    pub base_model: String,
+
+    /// Models reported on spans that resolve to the base model
+    pub models: Vec<String>,
+
+    pub request_count: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_cost_usd: f64,
+}
+
+/// Response for GET /api/v1/model-registry/rollup
+#[derive(Debug, Serialize)]
+pub struct BaseModelRollupResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub base_models: Vec<BaseModelRollup>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> RegisterModelRequest {
+        RegisterModelRequest {
+            model_id: "ft:gpt-4o-mini-2024-07-18:acme::abc123".to_string(),
+            provider: Some("openai".to_string()),
+            display_name: None,
+            aliases: vec!["support-bot".to_string()],
+            base_model: Some("gpt-4o-mini".to_string()),
+            prompt_cost_per_1k: Some(0.0003),
+            completion_cost_per_1k: Some(0.0012),
+            cached_prompt_cost_per_1k: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_register_request() {
+        assert!(request().validate().is_ok());
+
+        let mut invalid = request();
+        invalid.completion_cost_per_1k = None;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = request();
+        invalid.aliases.push(invalid.model_id.clone());
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = request();
+        invalid.base_model = Some("support-bot".to_string());
+        assert!(invalid.validate().is_err());
+
+        // Without pricing, the base model supplies it
+        let mut no_pricing = request();
+        no_pricing.prompt_cost_per_1k = None;
+        no_pricing.completion_cost_per_1k = None;
+        assert!(no_pricing.validate().is_ok());
+        no_pricing.base_model = None;
+        assert!(no_pricing.validate().is_err());
+    }
+}
@@ -0,0 +1,34 @@
+//! # Natural-Language Query Data Models
+//!
+//! Data structures for `POST /api/v1/query/natural`. A question is
+//! translated by a model into a metrics query (the request of
+//! `GET /api/v1/metrics`), which is validated and executed like any other
+//! metrics query. The generated query is returned with the answer, so users
+//! can check what was actually asked of the data.
+
+use crate::models::metrics::{MetricsQueryRequest, MetricsResponse};
+use serde::{Deserialize, Serialize};
+
+/// Request for POST /api/v1/query/natural
+#[derive(Debug, Clone, Deserialize)]
+pub struct NaturalQueryRequest {
+    /// Question about the organization's LLM traffic, e.g. "What did each
+    /// model cost per day last week?"
+    pub question: String,
+}
+
+/// Response for POST /api/v1/query/natural
+#[derive(Debug, Serialize)]
+pub struct NaturalQueryResponse {
+    /// The question asked
+    pub question: String,
+
+    /// Metrics query the question was translated into
+    pub query: MetricsQueryRequest,
+
+    /// Result of the query
+    pub answer: MetricsResponse,
+
+    /// Model that translated the question
+    pub translated_by: String,
+}
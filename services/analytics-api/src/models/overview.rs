@@ -0,0 +1,415 @@
+//! # Dashboard Overview Data Models
+//!
+//! Data structures for `GET /api/v1/overview`, a single composed payload for
+//! rendering an overview dashboard without one request per widget.
+//!
+//! ## Sections
+//! - Cost summary with period-over-period change
+//! - Top models by request volume
+//! - Error rate
+//! - Latency percentiles
+//! - Active alerts (threshold breaches in the current window)
+//! - Recent anomalies (hourly cost/volume outliers)
+
+use crate::models::costs::{AlertSeverity, CurrencyConversion};
+use crate::services::currency::FxQuote;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Error rate at which a warning alert is raised
+pub const ERROR_RATE_WARNING: f64 = 0.05;
+
+/// Error rate at which a critical alert is raised
+pub const ERROR_RATE_CRITICAL: f64 = 0.10;
+
+/// P95 latency (ms) at which a warning alert is raised
+pub const P95_LATENCY_WARNING_MS: f64 = 10_000.0;
+
+/// Number of preceding hourly buckets used as the anomaly baseline
+pub const ANOMALY_BASELINE_BUCKETS: usize = 24;
+
+/// Z-score above which a bucket is reported as an anomaly
+pub const ANOMALY_Z_THRESHOLD: f64 = 3.0;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for GET /api/v1/overview
+#[derive(Debug, Deserialize, Clone)]
+pub struct OverviewRequest {
+    /// Start time (default: 24 hours ago)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End time (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Filter by environment
+    pub environment: Option<String>,
+
+    /// Number of top models to return (max 20)
+    #[serde(default = "default_top_models")]
+    pub top_models: i32,
+
+    /// Report amounts in this currency (ISO 4217, default: display currency)
+    pub currency: Option<String>,
+}
+
+fn default_top_models() -> i32 {
+    5
+}
+
+impl OverviewRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            if start >= end {
+                return Err("Start time must be before end time".to_string());
+            }
+
+            if (end - start).num_days() > 90 {
+                return Err("Maximum time range is 90 days".to_string());
+            }
+        }
+
+        if self.top_models < 1 || self.top_models > 20 {
+            return Err("top_models must be between 1 and 20".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for GET /api/v1/overview
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverviewResponse {
+    pub metadata: OverviewMetadata,
+    pub cost: OverviewCost,
+    pub top_models: Vec<OverviewModel>,
+    pub errors: OverviewErrors,
+    pub latency: OverviewLatency,
+    pub active_alerts: Vec<OverviewAlert>,
+    pub recent_anomalies: Vec<OverviewAnomaly>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverviewMetadata {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    pub currency: CurrencyConversion,
+
+    /// Sections that failed to load and contain default values
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unavailable_sections: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OverviewCost {
+    pub total_cost: f64,
+    pub total_requests: i64,
+    pub total_tokens: i64,
+    pub avg_cost_per_request: f64,
+
+    /// Cost of the preceding period of equal length
+    pub previous_period_cost: f64,
+
+    /// Change versus the preceding period (percentage)
+    pub change_percentage: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverviewModel {
+    pub provider: String,
+    pub model: String,
+    pub requests: i64,
+    pub cost: f64,
+    pub avg_duration_ms: f64,
+    pub error_rate: f64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OverviewErrors {
+    pub total_requests: i64,
+    pub error_count: i64,
+    pub error_rate: f64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OverviewLatency {
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverviewAlert {
+    /// Alert kind ("error_rate", "latency", "cost_anomaly")
+    pub kind: String,
+    pub severity: AlertSeverity,
+    pub message: String,
+    pub value: f64,
+    pub threshold: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OverviewAnomaly {
+    /// Hourly bucket start
+    pub bucket: DateTime<Utc>,
+
+    /// Metric ("cost" or "requests")
+    pub metric: String,
+
+    pub value: f64,
+
+    /// Baseline mean of the preceding buckets
+    pub expected: f64,
+
+    pub z_score: f64,
+}
+
+impl OverviewResponse {
+    /// Convert all USD amounts into the quoted currency.
+    pub fn convert_currency(&mut self, quote: &FxQuote) {
+        self.metadata.currency = CurrencyConversion::from_quote(quote, self.cost.total_cost);
+        if quote.is_identity() {
+            return;
+        }
+
+        let c = &mut self.cost;
+        c.total_cost = quote.convert(c.total_cost);
+        c.avg_cost_per_request = quote.convert(c.avg_cost_per_request);
+        c.previous_period_cost = quote.convert(c.previous_period_cost);
+
+        for model in &mut self.top_models {
+            model.cost = quote.convert(model.cost);
+        }
+
+        for anomaly in self.recent_anomalies.iter_mut().filter(|a| a.metric == "cost") {
+            anomaly.value = quote.convert(anomaly.value);
+            anomaly.expected = quote.convert(anomaly.expected);
+        }
+
+        for alert in self.active_alerts.iter_mut().filter(|a| a.kind == "cost_anomaly") {
+            alert.value = quote.convert(alert.value);
+            alert.threshold = quote.convert(alert.threshold);
+        }
+    }
+}
+
+// ============================================================================
+// Internal Database Row Types
+// ============================================================================
+
+/// Row for overview cost totals
+#[derive(Debug, sqlx::FromRow)]
+pub struct OverviewCostRow {
+    pub total_cost: Option<f64>,
+    pub previous_cost: Option<f64>,
+    pub total_requests: Option<i64>,
+    pub total_tokens: Option<i64>,
+    pub error_count: Option<i64>,
+}
+
+/// Row for top models query
+#[derive(Debug, sqlx::FromRow)]
+pub struct OverviewModelRow {
+    pub provider: String,
+    pub model: String,
+    pub requests: Option<i64>,
+    pub cost: Option<f64>,
+    pub avg_duration_ms: Option<f64>,
+    pub error_count: Option<i64>,
+}
+
+/// Row for latency percentiles
+#[derive(Debug, sqlx::FromRow)]
+pub struct OverviewLatencyRow {
+    pub p50_ms: Option<f64>,
+    pub p95_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+}
+
+/// Row for hourly series used in anomaly detection
+#[derive(Debug, sqlx::FromRow)]
+pub struct OverviewHourlyRow {
+    pub bucket: DateTime<Utc>,
+    pub cost: Option<f64>,
+    pub requests: Option<i64>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Find buckets that deviate from the mean of the preceding `baseline` buckets
+/// by at least `z_threshold` standard deviations.
+///
+/// Only buckets at or after `since` are reported; earlier buckets are used as
+/// baseline only. Buckets whose baseline has zero variance are skipped.
+pub fn detect_anomalies(
+    metric: &str,
+    series: &[(DateTime<Utc>, f64)],
+    since: DateTime<Utc>,
+    baseline: usize,
+    z_threshold: f64,
+) -> Vec<OverviewAnomaly> {
+    let mut anomalies = Vec::new();
+
+    for i in baseline..series.len() {
+        let (bucket, value) = series[i];
+        if bucket < since {
+            continue;
+        }
+
+        let window = &series[i - baseline..i];
+        let mean = window.iter().map(|(_, v)| v).sum::<f64>() / baseline as f64;
+        let variance =
+            window.iter().map(|(_, v)| (v - mean).powi(2)).sum::<f64>() / baseline as f64;
+        let std_dev = variance.sqrt();
+
+        if std_dev <= f64::EPSILON {
+            continue;
+        }
+
+        let z_score = (value - mean) / std_dev;
+        if z_score.abs() >= z_threshold {
+            anomalies.push(OverviewAnomaly {
+                bucket,
+                metric: metric.to_string(),
+                value,
+                expected: mean,
+                z_score,
+            });
+        }
+    }
+
+    anomalies
+}
+
+/// Derive threshold alerts from the assembled snapshot.
+pub fn evaluate_alerts(
+    errors: &OverviewErrors,
+    latency: &OverviewLatency,
+    anomalies: &[OverviewAnomaly],
+) -> Vec<OverviewAlert> {
+    let mut alerts = Vec::new();
+
+    if errors.error_rate >= ERROR_RATE_WARNING {
+        let (severity, threshold) = if errors.error_rate >= ERROR_RATE_CRITICAL {
+            (AlertSeverity::Critical, ERROR_RATE_CRITICAL)
+        } else {
+            (AlertSeverity::Warning, ERROR_RATE_WARNING)
+        };
+        alerts.push(OverviewAlert {
+            kind: "error_rate".to_string(),
+            severity,
+            message: format!("Error rate is {:.1}%", errors.error_rate * 100.0),
+            value: errors.error_rate,
+            threshold,
+        });
+    }
+
+    if latency.p95_ms >= P95_LATENCY_WARNING_MS {
+        alerts.push(OverviewAlert {
+            kind: "latency".to_string(),
+            severity: AlertSeverity::Warning,
+            message: format!("P95 latency is {:.0}ms", latency.p95_ms),
+            value: latency.p95_ms,
+            threshold: P95_LATENCY_WARNING_MS,
+        });
+    }
+
+    // Only cost spikes are actionable; drops and volume changes stay as anomalies
+    if let Some(latest) = anomalies
+        .iter()
+        .filter(|a| a.metric == "cost" && a.z_score > 0.0)
+        .max_by_key(|a| a.bucket)
+    {
+        alerts.push(OverviewAlert {
+            kind: "cost_anomaly".to_string(),
+            severity: AlertSeverity::Warning,
+            message: format!(
+                "Hourly cost spike at {} ({:.1} standard deviations above baseline)",
+                latest.bucket.to_rfc3339(),
+                latest.z_score
+            ),
+            value: latest.value,
+            threshold: latest.expected,
+        });
+    }
+
+    alerts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn series(values: &[f64]) -> Vec<(DateTime<Utc>, f64)> {
+        let start = Utc::now() - Duration::hours(values.len() as i64);
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (start + Duration::hours(i as i64), *v))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_anomalies() {
+        let data = series(&[10.0, 12.0, 11.0, 9.0, 10.0, 50.0, 11.0]);
+        let anomalies = detect_anomalies("cost", &data, data[0].0, 4, 3.0);
+
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].value, 50.0);
+        assert!(anomalies[0].z_score > 3.0);
+
+        // Buckets before `since` are baseline only
+        let anomalies = detect_anomalies("cost", &data, data[6].0, 4, 3.0);
+        assert!(anomalies.is_empty());
+
+        // Flat baseline has no variance
+        let flat = series(&[5.0, 5.0, 5.0, 20.0]);
+        assert!(detect_anomalies("cost", &flat, flat[0].0, 3, 3.0).is_empty());
+    }
+
+    #[test]
+    fn test_evaluate_alerts() {
+        let errors = OverviewErrors {
+            total_requests: 100,
+            error_count: 12,
+            error_rate: 0.12,
+        };
+        let latency = OverviewLatency {
+            p50_ms: 500.0,
+            p95_ms: 2_000.0,
+            p99_ms: 4_000.0,
+        };
+
+        let alerts = evaluate_alerts(&errors, &latency, &[]);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, "error_rate");
+        assert_eq!(alerts[0].severity, AlertSeverity::Critical);
+
+        assert!(evaluate_alerts(&OverviewErrors::default(), &latency, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_overview_request_validation() {
+        let req = OverviewRequest {
+            start_time: None,
+            end_time: None,
+            environment: None,
+            top_models: 5,
+            currency: None,
+        };
+        assert!(req.validate().is_ok());
+
+        let req = OverviewRequest { top_models: 0, ..req };
+        assert!(req.validate().is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! # Prompt Cache Data Models
+//!
+//! Data structures for `GET /api/v1/prompt-cache`, which reports how often
+//! requests hit the provider's prompt cache and what caching saved, per model
+//! or per prompt template.
+//!
+//! Cache usage is read from the `cache_read_tokens`, `cache_write_tokens` and
+//! `cache_savings_usd` columns of `llm_traces`, filled from the span
+//! attributes recorded by the collector's cost processor.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Default analysis window in days
+const DEFAULT_WINDOW_DAYS: i64 = 7;
+
+/// Longest analysis window in days
+const MAX_WINDOW_DAYS: i64 = 90;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// How cache usage is grouped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptCacheGrouping {
+    /// Per provider and model
+    #[default]
+    Model,
+    /// Per prompt template (span name of spans without one), provider and model
+    Template,
+}
+
+/// Query parameters for GET /api/v1/prompt-cache
+#[derive(Debug, Deserialize, Clone)]
+pub struct PromptCacheRequest {
+    /// Start of the analysis window (default: 7 days before end_time)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End of the analysis window (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub environment: Option<String>,
+
+    /// Grouping (default: model)
+    #[serde(default)]
+    pub group_by: PromptCacheGrouping,
+
+    /// Maximum groups returned, most savings first
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    100
+}
+
+impl PromptCacheRequest {
+    /// Validate the request and resolve the analysis window
+    pub fn validate(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+        let end_time = self.end_time.unwrap_or_else(Utc::now);
+        let start_time = self
+            .start_time
+            .unwrap_or(end_time - Duration::days(DEFAULT_WINDOW_DAYS));
+
+        if start_time >= end_time {
+            return Err("Start time must be before end time".to_string());
+        }
+
+        if end_time - start_time > Duration::days(MAX_WINDOW_DAYS) {
+            return Err(format!("Time range cannot exceed {} days", MAX_WINDOW_DAYS));
+        }
+
+        if !(1..=1000).contains(&self.limit) {
+            return Err("Limit must be between 1 and 1000".to_string());
+        }
+
+        Ok((start_time, end_time))
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for GET /api/v1/prompt-cache
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptCacheResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub group_by: PromptCacheGrouping,
+
+    /// All requests in the window, including groups beyond the limit
+    pub totals: PromptCacheStats,
+    pub groups: Vec<PromptCacheGroup>,
+}
+
+/// Prompt cache usage of one model or template
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PromptCacheGroup {
+    pub provider: String,
+    pub model: String,
+
+    /// Prompt template; only set when grouping by template
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workload: Option<String>,
+
+    #[serde(flatten)]
+    pub stats: PromptCacheStats,
+}
+
+/// Cache hits, cached tokens and realized savings of a set of requests
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PromptCacheStats {
+    pub request_count: i64,
+
+    /// Requests that read from the prompt cache
+    pub cache_hit_count: i64,
+
+    /// Requests that wrote to the prompt cache
+    pub cache_write_count: i64,
+
+    /// Share of requests that read from the prompt cache
+    pub hit_rate: f64,
+
+    pub prompt_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+
+    /// Share of prompt tokens served from the prompt cache
+    pub token_hit_rate: f64,
+
+    pub prompt_cost_usd: f64,
+
+    /// Cost without prompt caching minus the actual cost; negative when
+    /// cache writes were not repaid by later reads
+    pub cache_savings_usd: f64,
+}
+
+// ============================================================================
+// Database Row Types
+// ============================================================================
+
+/// Cache usage of one group
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PromptCacheRow {
+    pub provider: String,
+    pub model: String,
+    pub workload: Option<String>,
+    pub request_count: i64,
+    pub cache_hit_count: i64,
+    pub cache_write_count: i64,
+    pub prompt_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub prompt_cost_usd: f64,
+    pub cache_savings_usd: f64,
+}
+
+impl PromptCacheStats {
+    /// Add the counts of a group, without updating the rates
+    fn add(&mut self, row: &PromptCacheRow) {
+        self.request_count += row.request_count;
+        self.cache_hit_count += row.cache_hit_count;
+        self.cache_write_count += row.cache_write_count;
+        self.prompt_tokens += row.prompt_tokens;
+        self.cache_read_tokens += row.cache_read_tokens;
+        self.cache_write_tokens += row.cache_write_tokens;
+        self.prompt_cost_usd += row.prompt_cost_usd;
+        self.cache_savings_usd += row.cache_savings_usd;
+    }
+
+    /// Compute the hit rates from the counts
+    fn with_rates(mut self) -> Self {
+        self.hit_rate = ratio(self.cache_hit_count, self.request_count);
+        self.token_hit_rate = ratio(self.cache_read_tokens, self.prompt_tokens);
+        self
+    }
+}
+
+fn ratio(part: i64, whole: i64) -> f64 {
+    if whole > 0 {
+        part as f64 / whole as f64
+    } else {
+        0.0
+    }
+}
+
+/// Compute the totals and the hit rates of each group, and keep the `limit`
+/// groups with the most savings
+pub fn build_report(
+    rows: Vec<PromptCacheRow>,
+    limit: usize,
+) -> (PromptCacheStats, Vec<PromptCacheGroup>) {
+    let mut totals = PromptCacheStats::default();
+    let mut groups: Vec<PromptCacheGroup> = rows
+        .into_iter()
+        .map(|row| {
+            totals.add(&row);
+            let mut stats = PromptCacheStats::default();
+            stats.add(&row);
+            PromptCacheGroup {
+                provider: row.provider,
+                model: row.model,
+                workload: row.workload,
+                stats: stats.with_rates(),
+            }
+        })
+        .collect();
+
+    groups.sort_by(|a, b| {
+        b.stats
+            .cache_savings_usd
+            .total_cmp(&a.stats.cache_savings_usd)
+            .then(b.stats.request_count.cmp(&a.stats.request_count))
+    });
+    groups.truncate(limit);
+
+    (totals.with_rates(), groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(model: &str, request_count: i64, cache_hit_count: i64, savings: f64) -> PromptCacheRow {
+        PromptCacheRow {
+            provider: "anthropic".to_string(),
+            model: model.to_string(),
+            workload: None,
+            request_count,
+            cache_hit_count,
+            cache_write_count: 1,
+            prompt_tokens: request_count * 1000,
+            cache_read_tokens: cache_hit_count * 800,
+            cache_write_tokens: 800,
+            prompt_cost_usd: 1.0,
+            cache_savings_usd: savings,
+        }
+    }
+
+    #[test]
+    fn test_build_report() {
+        let rows = vec![
+            row("claude-3-5-haiku-20241022", 10, 0, -0.01),
+            row("claude-3-5-sonnet-20241022", 100, 75, 2.5),
+            row("claude-3-opus-20240229", 40, 10, 0.5),
+        ];
+
+        let (totals, groups) = build_report(rows, 2);
+
+        assert_eq!(totals.request_count, 150);
+        assert_eq!(totals.cache_hit_count, 85);
+        assert!((totals.cache_savings_usd - 2.99).abs() < 1e-9);
+        assert!((totals.token_hit_rate - 68_000.0 / 150_000.0).abs() < 1e-9);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].model, "claude-3-5-sonnet-20241022");
+        assert_eq!(groups[0].stats.hit_rate, 0.75);
+        assert_eq!(groups[0].stats.token_hit_rate, 0.6);
+        assert_eq!(groups[1].model, "claude-3-opus-20240229");
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut request = PromptCacheRequest {
+            start_time: None,
+            end_time: None,
+            provider: None,
+            model: None,
+            environment: None,
+            group_by: PromptCacheGrouping::Template,
+            limit: default_limit(),
+        };
+        let (start, end) = request.validate().unwrap();
+        assert_eq!(end - start, Duration::days(7));
+
+        request.limit = 0;
+        assert!(request.validate().is_err());
+    }
+}
@@ -0,0 +1,238 @@
+//! # Provider Status Data Models
+//!
+//! Data structures for `GET /api/v1/providers/status`, which combines the
+//! provider health monitor's current view, recorded upstream incidents, and
+//! error spikes in the organization's own traces.
+
+use crate::services::provider_health::ProviderHealthSnapshot;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for GET /api/v1/providers/status
+#[derive(Debug, Deserialize, Clone)]
+pub struct ProviderStatusRequest {
+    /// Start time (default: 24 hours ago)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End time (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Filter by provider
+    pub provider: Option<String>,
+
+    /// Hourly error rate counted as a spike (0.0-1.0)
+    #[serde(default = "default_spike_error_rate")]
+    pub spike_error_rate: f64,
+
+    /// Minimum requests in an hour for it to count as a spike
+    #[serde(default = "default_spike_min_requests")]
+    pub spike_min_requests: i64,
+}
+
+fn default_spike_error_rate() -> f64 {
+    0.05
+}
+
+fn default_spike_min_requests() -> i64 {
+    10
+}
+
+impl ProviderStatusRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            if start >= end {
+                return Err("Start time must be before end time".to_string());
+            }
+
+            if (end - start).num_days() > 90 {
+                return Err("Maximum time range is 90 days".to_string());
+            }
+        }
+
+        if !(0.0..=1.0).contains(&self.spike_error_rate) {
+            return Err("spike_error_rate must be between 0 and 1".to_string());
+        }
+
+        if self.spike_min_requests < 1 {
+            return Err("spike_min_requests must be at least 1".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for GET /api/v1/providers/status
+#[derive(Debug, Serialize)]
+pub struct ProviderStatusResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+
+    /// Latest probe result per provider
+    pub current: Vec<ProviderHealthSnapshot>,
+
+    /// Upstream incidents overlapping the time range
+    pub incidents: Vec<ProviderIncident>,
+
+    /// Hourly error spikes in our traces, matched to upstream incidents
+    pub error_spikes: Vec<ErrorSpike>,
+}
+
+/// Upstream provider incident
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ProviderIncident {
+    pub incident_id: Uuid,
+    pub provider: String,
+    pub status: String,
+    pub description: Option<String>,
+    pub source: String,
+    pub started_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl ProviderIncident {
+    /// Whether the incident was ongoing at any point in `[start, end)`
+    pub fn overlaps(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        self.started_at < end && self.resolved_at.map_or(true, |resolved| resolved > start)
+    }
+}
+
+/// An hour with an elevated error rate for one provider
+#[derive(Debug, Serialize)]
+pub struct ErrorSpike {
+    pub provider: String,
+    pub bucket: DateTime<Utc>,
+    pub request_count: i64,
+    pub error_count: i64,
+    pub error_rate: f64,
+
+    /// Upstream incident ongoing during the hour, if any
+    pub incident_id: Option<Uuid>,
+
+    /// Whether the spike coincides with an upstream incident
+    pub upstream_outage: bool,
+}
+
+// ============================================================================
+// Internal Database Row Types
+// ============================================================================
+
+/// Row for hourly per-provider error counts
+#[derive(Debug, sqlx::FromRow)]
+pub struct ProviderErrorRow {
+    pub provider: String,
+    pub bucket: DateTime<Utc>,
+    pub request_count: Option<i64>,
+    pub error_count: Option<i64>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Select hours whose error rate crosses the spike threshold and match each
+/// to an overlapping incident for the same provider.
+pub fn correlate_error_spikes(
+    rows: &[ProviderErrorRow],
+    incidents: &[ProviderIncident],
+    spike_error_rate: f64,
+    spike_min_requests: i64,
+) -> Vec<ErrorSpike> {
+    rows.iter()
+        .filter_map(|row| {
+            let request_count = row.request_count.unwrap_or(0);
+            let error_count = row.error_count.unwrap_or(0);
+            if request_count < spike_min_requests {
+                return None;
+            }
+
+            let error_rate = error_count as f64 / request_count as f64;
+            if error_rate < spike_error_rate {
+                return None;
+            }
+
+            let bucket_end = row.bucket + Duration::hours(1);
+            let incident_id = incidents
+                .iter()
+                .find(|i| i.provider == row.provider && i.overlaps(row.bucket, bucket_end))
+                .map(|i| i.incident_id);
+
+            Some(ErrorSpike {
+                provider: row.provider.clone(),
+                bucket: row.bucket,
+                request_count,
+                error_count,
+                error_rate,
+                incident_id,
+                upstream_outage: incident_id.is_some(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn incident(provider: &str, started_at: DateTime<Utc>, resolved_at: Option<DateTime<Utc>>) -> ProviderIncident {
+        ProviderIncident {
+            incident_id: Uuid::new_v4(),
+            provider: provider.to_string(),
+            status: "major_outage".to_string(),
+            description: None,
+            source: "statuspage".to_string(),
+            started_at,
+            resolved_at,
+        }
+    }
+
+    fn row(provider: &str, bucket: DateTime<Utc>, requests: i64, errors: i64) -> ProviderErrorRow {
+        ProviderErrorRow {
+            provider: provider.to_string(),
+            bucket,
+            request_count: Some(requests),
+            error_count: Some(errors),
+        }
+    }
+
+    #[test]
+    fn test_incident_overlaps() {
+        let now = Utc::now();
+        let resolved = incident("openai", now - Duration::hours(3), Some(now - Duration::hours(2)));
+        assert!(resolved.overlaps(now - Duration::hours(4), now - Duration::hours(2)));
+        assert!(!resolved.overlaps(now - Duration::hours(2), now));
+
+        let ongoing = incident("openai", now - Duration::hours(1), None);
+        assert!(ongoing.overlaps(now, now + Duration::hours(1)));
+    }
+
+    #[test]
+    fn test_correlate_error_spikes() {
+        let now = Utc::now();
+        let outage = incident("openai", now - Duration::minutes(30), None);
+
+        let rows = vec![
+            row("openai", now - Duration::hours(1), 100, 40),
+            row("openai", now - Duration::hours(5), 100, 1),
+            row("anthropic", now - Duration::hours(1), 100, 20),
+            row("anthropic", now - Duration::hours(2), 5, 5),
+        ];
+
+        let spikes = correlate_error_spikes(&rows, &[outage.clone()], 0.05, 10);
+
+        assert_eq!(spikes.len(), 2);
+        assert_eq!(spikes[0].provider, "openai");
+        assert_eq!(spikes[0].incident_id, Some(outage.incident_id));
+        assert!(spikes[0].upstream_outage);
+        assert_eq!(spikes[1].provider, "anthropic");
+        assert!(!spikes[1].upstream_outage);
+    }
+}
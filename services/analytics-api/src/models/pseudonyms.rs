@@ -0,0 +1,14 @@
+//! # Pseudonym Data Models
+//!
+//! Data structures for `GET /api/v1/pseudonyms/:pseudonym`, which resolves a
+//! user or session pseudonym to the identifier it replaced.
+
+use serde::Serialize;
+
+/// Response for GET /api/v1/pseudonyms/:pseudonym
+#[derive(Debug, Serialize)]
+pub struct PseudonymResolution {
+    pub pseudonym: String,
+    /// Original user or session identifier
+    pub value: String,
+}
@@ -0,0 +1,254 @@
+//! # Quarantine Data Models
+//!
+//! Data structures for browsing and replaying spans the collector
+//! quarantined because they failed schema validation, PII redaction or
+//! another processor (`rejected_spans`). Entries expire after
+//! [`QUARANTINE_RETENTION_DAYS`], whether or not they were replayed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Days quarantined spans are kept (retention policy of `rejected_spans`)
+pub const QUARANTINE_RETENTION_DAYS: i64 = 30;
+
+/// Failing stages recorded by the collector
+pub const STAGES: [&str; 3] = ["schema", "pii", "processing"];
+
+/// Replay states of a quarantined span
+pub const REPLAY_STATUSES: [&str; 5] =
+    ["quarantined", "pending", "replaying", "replayed", "failed"];
+
+/// Maximum IDs in one replay request
+pub const MAX_REPLAY_IDS: usize = 1000;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for GET /api/v1/quarantine
+#[derive(Debug, Deserialize, Clone)]
+pub struct QuarantineListQuery {
+    /// Start time (default: retention period ago)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End time (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Only spans that failed in this stage
+    pub stage: Option<String>,
+
+    /// Only spans of this service
+    pub service_name: Option<String>,
+
+    /// Only spans in this replay state
+    pub replay_status: Option<String>,
+
+    /// Maximum entries (default: 100, max: 1000)
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+impl QuarantineListQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_time_range(self.start_time, self.end_time)?;
+        validate_stage(self.stage.as_deref())?;
+
+        if let Some(status) = &self.replay_status {
+            if !REPLAY_STATUSES.contains(&status.as_str()) {
+                return Err(format!(
+                    "Invalid replay_status '{}', expected one of: {}",
+                    status,
+                    REPLAY_STATUSES.join(", ")
+                ));
+            }
+        }
+
+        if self.limit < 1 || self.limit > 1000 {
+            return Err(format!(
+                "Limit must be between 1 and 1000, got {}",
+                self.limit
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Request for POST /api/v1/quarantine/replay
+///
+/// Selects quarantined spans by ID or by stage, service, schema and time
+/// range. Spans that are already pending, replaying or replayed are skipped.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ReplayQuarantineRequest {
+    /// Quarantine entry IDs
+    #[serde(default)]
+    pub ids: Vec<Uuid>,
+
+    /// Only spans that failed in this stage
+    pub stage: Option<String>,
+
+    /// Only spans of this service
+    pub service_name: Option<String>,
+
+    /// Only spans that failed this schema, e.g. `checkout-api@2.1.0`
+    pub schema_name: Option<String>,
+
+    /// Only spans quarantined at or after this time
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// Only spans quarantined before this time
+    pub end_time: Option<DateTime<Utc>>,
+}
+
+impl ReplayQuarantineRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        // Require a selector, so a request can't replay everything by accident
+        if self.ids.is_empty()
+            && self.stage.is_none()
+            && self.service_name.is_none()
+            && self.schema_name.is_none()
+        {
+            return Err("Select spans by ids, stage, service_name or schema_name".to_string());
+        }
+
+        if self.ids.len() > MAX_REPLAY_IDS {
+            return Err(format!(
+                "At most {} ids per request, got {}",
+                MAX_REPLAY_IDS,
+                self.ids.len()
+            ));
+        }
+
+        validate_time_range(self.start_time, self.end_time)?;
+        validate_stage(self.stage.as_deref())
+    }
+}
+
+fn validate_time_range(
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+) -> Result<(), String> {
+    if let (Some(start), Some(end)) = (start_time, end_time) {
+        if start >= end {
+            return Err("Start time must be before end time".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn validate_stage(stage: Option<&str>) -> Result<(), String> {
+    match stage {
+        Some(stage) if !STAGES.contains(&stage) => Err(format!(
+            "Invalid stage '{}', expected one of: {}",
+            stage,
+            STAGES.join(", ")
+        )),
+        _ => Ok(()),
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// A quarantined span, without its payload
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct QuarantineEntry {
+    pub id: Uuid,
+    pub ts: DateTime<Utc>,
+    pub trace_id: String,
+    pub span_id: String,
+    pub service_name: Option<String>,
+    pub service_version: Option<String>,
+    /// schema, pii or processing
+    pub stage: String,
+    pub failure_reason: String,
+    pub schema_name: Option<String>,
+    pub violations: Vec<String>,
+    /// quarantined, pending, replaying, replayed or failed
+    pub replay_status: String,
+    pub replay_requested_at: Option<DateTime<Utc>>,
+    pub replayed_at: Option<DateTime<Utc>>,
+    pub replay_attempts: i32,
+    pub replay_error: Option<String>,
+    /// When the entry is removed by the retention policy
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Response for GET /api/v1/quarantine
+#[derive(Debug, Serialize)]
+pub struct QuarantineListResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub entries: Vec<QuarantineEntry>,
+}
+
+/// Response for GET /api/v1/quarantine/:id
+#[derive(Debug, Serialize)]
+pub struct QuarantineEntryDetail {
+    #[serde(flatten)]
+    pub entry: QuarantineEntry,
+    /// The span as received; omitted without `read:trace_content`, since it
+    /// may hold unredacted prompts and responses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<serde_json::Value>,
+}
+
+/// Response for POST /api/v1/quarantine/replay
+#[derive(Debug, Serialize)]
+pub struct ReplayQuarantineResponse {
+    /// Spans marked for replay by the collector
+    pub requested: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_query_validate() {
+        let query: QuarantineListQuery = serde_json::from_value(serde_json::json!({
+            "stage": "pii",
+            "replay_status": "failed"
+        }))
+        .unwrap();
+        assert_eq!(query.limit, 100);
+        assert!(query.validate().is_ok());
+
+        let mut invalid = query.clone();
+        invalid.stage = Some("sampling".to_string());
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = query;
+        invalid.replay_status = Some("done".to_string());
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_replay_request_requires_selector() {
+        assert!(ReplayQuarantineRequest::default().validate().is_err());
+
+        let by_id = ReplayQuarantineRequest {
+            ids: vec![Uuid::new_v4()],
+            ..Default::default()
+        };
+        assert!(by_id.validate().is_ok());
+
+        let by_schema = ReplayQuarantineRequest {
+            schema_name: Some("checkout-api@2.1.0".to_string()),
+            ..Default::default()
+        };
+        assert!(by_schema.validate().is_ok());
+
+        let too_many = ReplayQuarantineRequest {
+            ids: (0..=MAX_REPLAY_IDS).map(|_| Uuid::new_v4()).collect(),
+            ..Default::default()
+        };
+        assert!(too_many.validate().is_err());
+    }
+}
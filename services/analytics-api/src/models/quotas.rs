@@ -0,0 +1,182 @@
+//! # Ingestion Quota Data Models
+//!
+//! Data structures for `GET /api/v1/quotas/usage`, which reports an
+//! organization's ingestion on a UTC day against its quota. Usage and limits
+//! come from the `ingestion_usage` table, written from the collector's quota
+//! processor.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Days of usage kept in `ingestion_usage`
+pub const USAGE_RETENTION_DAYS: i64 = 400;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for GET /api/v1/quotas/usage
+#[derive(Debug, Deserialize, Clone)]
+pub struct QuotaUsageQuery {
+    /// UTC day (default: today)
+    pub day: Option<NaiveDate>,
+}
+
+impl QuotaUsageQuery {
+    pub fn validate(&self, today: NaiveDate) -> Result<(), String> {
+        if let Some(day) = self.day {
+            if day > today {
+                return Err("Day must not be in the future".to_string());
+            }
+
+            if (today - day).num_days() > USAGE_RETENTION_DAYS {
+                return Err(format!("Usage is kept for {} days", USAGE_RETENTION_DAYS));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for GET /api/v1/quotas/usage
+#[derive(Debug, Serialize, PartialEq)]
+pub struct QuotaUsageResponse {
+    pub org_id: String,
+    pub day: NaiveDate,
+    pub spans: QuotaDimension,
+    pub bytes: QuotaDimension,
+    /// Over-quota spans dropped, sampled out or queued
+    pub throttled_spans: i64,
+    /// Whether the span or byte limit is reached
+    pub over_quota: bool,
+    /// Handling of spans over quota: `drop`, `sample` or `queue`
+    pub over_quota_action: Option<String>,
+    /// Last usage flush from the collectors
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Usage of spans or bytes against its daily limit
+#[derive(Debug, Serialize, PartialEq)]
+pub struct QuotaDimension {
+    pub used: i64,
+    /// Daily limit; unlimited when null
+    pub limit: Option<i64>,
+    pub remaining: Option<i64>,
+    /// used / limit * 100
+    pub used_percent: Option<f64>,
+}
+
+impl QuotaDimension {
+    fn new(used: i64, limit: Option<i64>) -> Self {
+        Self {
+            used,
+            limit,
+            remaining: limit.map(|limit| (limit - used).max(0)),
+            used_percent: limit
+                .filter(|limit| *limit > 0)
+                .map(|limit| used as f64 / limit as f64 * 100.0),
+        }
+    }
+
+    fn reached(&self) -> bool {
+        self.limit.is_some_and(|limit| self.used >= limit)
+    }
+}
+
+// ============================================================================
+// Internal Database Row Types
+// ============================================================================
+
+/// Row of `ingestion_usage`
+#[derive(Debug, sqlx::FromRow)]
+pub struct IngestionUsageRow {
+    pub span_count: i64,
+    pub byte_count: i64,
+    pub throttled_spans: i64,
+    pub spans_per_day_limit: Option<i64>,
+    pub bytes_per_day_limit: Option<i64>,
+    pub over_quota_action: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Build the usage report of a day; without a row the organization has not
+/// ingested anything and no quota is known.
+pub fn build_quota_usage(
+    org_id: String,
+    day: NaiveDate,
+    row: Option<IngestionUsageRow>,
+) -> QuotaUsageResponse {
+    let Some(row) = row else {
+        return QuotaUsageResponse {
+            org_id,
+            day,
+            spans: QuotaDimension::new(0, None),
+            bytes: QuotaDimension::new(0, None),
+            throttled_spans: 0,
+            over_quota: false,
+            over_quota_action: None,
+            updated_at: None,
+        };
+    };
+
+    let spans = QuotaDimension::new(row.span_count, row.spans_per_day_limit);
+    let bytes = QuotaDimension::new(row.byte_count, row.bytes_per_day_limit);
+    QuotaUsageResponse {
+        org_id,
+        day,
+        over_quota: spans.reached() || bytes.reached(),
+        spans,
+        bytes,
+        throttled_spans: row.throttled_spans,
+        over_quota_action: Some(row.over_quota_action),
+        updated_at: Some(row.updated_at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_usage_report() {
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let report = build_quota_usage(
+            "org-free".to_string(),
+            day,
+            Some(IngestionUsageRow {
+                span_count: 100_000,
+                byte_count: 250_000_000,
+                throttled_spans: 42,
+                spans_per_day_limit: Some(100_000),
+                bytes_per_day_limit: Some(500_000_000),
+                over_quota_action: "drop".to_string(),
+                updated_at: Utc::now(),
+            }),
+        );
+
+        assert!(report.over_quota);
+        assert_eq!(report.spans.remaining, Some(0));
+        assert_eq!(report.bytes.remaining, Some(250_000_000));
+        assert_eq!(report.bytes.used_percent, Some(50.0));
+
+        let empty = build_quota_usage("org-new".to_string(), day, None);
+        assert!(!empty.over_quota);
+        assert_eq!(empty.spans.limit, None);
+
+        let today = day;
+        assert!(QuotaUsageQuery {
+            day: Some(day.succ_opt().unwrap())
+        }
+        .validate(today)
+        .is_err());
+        assert!(QuotaUsageQuery { day: None }.validate(today).is_ok());
+    }
+}
@@ -0,0 +1,266 @@
+//! # Provider Rate Limit Data Models
+//!
+//! Data structures for `GET /api/v1/rate-limits`, which reports the latest
+//! rate-limit state providers returned to each organization, per provider
+//! and model, and whether a limit is nearly used up.
+//!
+//! The state is read from `provider_rate_limits`, kept up to date from the
+//! `llm_observatory.rate_limit.*` span attributes the SDK records from the
+//! providers' response headers.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Default share of a limit used at which it is approaching
+pub const DEFAULT_APPROACHING_THRESHOLD: f64 = 0.9;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Query parameters for GET /api/v1/rate-limits
+#[derive(Debug, Deserialize, Clone)]
+pub struct RateLimitRequest {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+
+    /// Share of a limit used at which it is approaching (default: 0.9)
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+
+    /// Only return limits that are approaching
+    #[serde(default)]
+    pub approaching_only: bool,
+}
+
+fn default_threshold() -> f64 {
+    DEFAULT_APPROACHING_THRESHOLD
+}
+
+impl RateLimitRequest {
+    /// Validate the request
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.threshold > 0.0 && self.threshold <= 1.0) {
+            return Err("Threshold must be greater than 0 and at most 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Database Models
+// ============================================================================
+
+/// A row of `provider_rate_limits`
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RateLimitRow {
+    pub org_id: String,
+    pub provider: String,
+    pub model: String,
+    pub requests_limit: Option<i64>,
+    pub requests_remaining: Option<i64>,
+    pub requests_reset_at: Option<DateTime<Utc>>,
+    pub tokens_limit: Option<i64>,
+    pub tokens_remaining: Option<i64>,
+    pub tokens_reset_at: Option<DateTime<Utc>>,
+    pub observed_at: DateTime<Utc>,
+    pub trace_id: String,
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Kind of rate limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKind {
+    Requests,
+    Tokens,
+}
+
+impl RateLimitKind {
+    /// Get the kind as used in alert rules (`rate_limit:<kind>`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitKind::Requests => "requests",
+            RateLimitKind::Tokens => "tokens",
+        }
+    }
+}
+
+/// One request or token limit
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RateLimitWindow {
+    pub limit: Option<i64>,
+    pub remaining: Option<i64>,
+    pub reset_at: Option<DateTime<Utc>>,
+
+    /// Share of the limit used; unknown without both limit and remaining
+    pub utilization: Option<f64>,
+
+    /// Whether the window has reset since the state was reported
+    pub reset: bool,
+}
+
+impl RateLimitWindow {
+    fn new(
+        limit: Option<i64>,
+        remaining: Option<i64>,
+        reset_at: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let utilization = match (limit, remaining) {
+            (Some(limit), Some(remaining)) if limit > 0 => {
+                Some(1.0 - remaining.clamp(0, limit) as f64 / limit as f64)
+            }
+            _ => None,
+        };
+        Self {
+            limit,
+            remaining,
+            reset_at,
+            utilization,
+            reset: reset_at.is_some_and(|at| at <= now),
+        }
+    }
+
+    /// Whether at least `threshold` of the limit is used in a window that
+    /// has not reset yet
+    pub fn is_approaching(&self, threshold: f64) -> bool {
+        !self.reset && self.utilization.is_some_and(|u| u >= threshold)
+    }
+}
+
+/// Latest rate-limit state of one provider and model
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProviderRateLimit {
+    pub provider: String,
+    pub model: String,
+    pub requests: RateLimitWindow,
+    pub tokens: RateLimitWindow,
+
+    /// When the state was reported
+    pub observed_at: DateTime<Utc>,
+
+    /// Trace of the request that reported the state
+    pub trace_id: String,
+
+    /// Limits at or above the threshold
+    pub approaching: Vec<RateLimitKind>,
+}
+
+impl ProviderRateLimit {
+    /// Build the state of a row as of `now`
+    pub fn from_row(row: RateLimitRow, threshold: f64, now: DateTime<Utc>) -> Self {
+        let requests = RateLimitWindow::new(
+            row.requests_limit,
+            row.requests_remaining,
+            row.requests_reset_at,
+            now,
+        );
+        let tokens = RateLimitWindow::new(
+            row.tokens_limit,
+            row.tokens_remaining,
+            row.tokens_reset_at,
+            now,
+        );
+        let approaching = [
+            (RateLimitKind::Requests, &requests),
+            (RateLimitKind::Tokens, &tokens),
+        ]
+        .into_iter()
+        .filter(|(_, window)| window.is_approaching(threshold))
+        .map(|(kind, _)| kind)
+        .collect();
+
+        Self {
+            provider: row.provider,
+            model: row.model,
+            requests,
+            tokens,
+            observed_at: row.observed_at,
+            trace_id: row.trace_id,
+            approaching,
+        }
+    }
+
+    /// Highest utilization of the limits whose window has not reset
+    pub fn max_utilization(&self) -> f64 {
+        [&self.requests, &self.tokens]
+            .into_iter()
+            .filter(|window| !window.reset)
+            .filter_map(|window| window.utilization)
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Response for GET /api/v1/rate-limits
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimitResponse {
+    pub threshold: f64,
+
+    /// Limits, most used first
+    pub limits: Vec<ProviderRateLimit>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn row(requests_remaining: i64, tokens_remaining: i64, reset_in: Duration) -> RateLimitRow {
+        let now = Utc::now();
+        RateLimitRow {
+            org_id: "org-1".to_string(),
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            requests_limit: Some(500),
+            requests_remaining: Some(requests_remaining),
+            requests_reset_at: Some(now + reset_in),
+            tokens_limit: Some(30_000),
+            tokens_remaining: Some(tokens_remaining),
+            tokens_reset_at: Some(now + reset_in),
+            observed_at: now,
+            trace_id: "trace-1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_from_row() {
+        let now = Utc::now();
+        let limit = ProviderRateLimit::from_row(
+            row(450, 1_500, Duration::seconds(30)),
+            DEFAULT_APPROACHING_THRESHOLD,
+            now,
+        );
+        assert!((limit.requests.utilization.unwrap() - 0.1).abs() < 1e-9);
+        assert!((limit.tokens.utilization.unwrap() - 0.95).abs() < 1e-9);
+        assert_eq!(limit.approaching, vec![RateLimitKind::Tokens]);
+        assert!((limit.max_utilization() - 0.95).abs() < 1e-9);
+
+        // Windows that reset since are no longer approaching
+        let limit = ProviderRateLimit::from_row(
+            row(0, 0, Duration::seconds(-1)),
+            DEFAULT_APPROACHING_THRESHOLD,
+            now,
+        );
+        assert!(limit.requests.reset);
+        assert!(limit.approaching.is_empty());
+        assert_eq!(limit.max_utilization(), 0.0);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut request = RateLimitRequest {
+            provider: None,
+            model: None,
+            threshold: default_threshold(),
+            approaching_only: false,
+        };
+        assert!(request.validate().is_ok());
+
+        request.threshold = 1.5;
+        assert!(request.validate().is_err());
+    }
+}
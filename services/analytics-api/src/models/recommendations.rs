@@ -0,0 +1,531 @@
+//! # Model Recommendation Data Models
+//!
+//! Data structures for the model routing recommendations endpoint. Spans are
+//! grouped into workloads, by prompt template (`prompt.template` attribute)
+//! or span name, and each model serving a workload is compared against the
+//! other models serving the same workload. A model is suggested when it is
+//! markedly cheaper or faster, its error rate is not worse, and its feedback
+//! scores do not drop by more than the allowed amount.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Default analysis window in days
+const DEFAULT_WINDOW_DAYS: i64 = 30;
+
+/// Longest analysis window in days
+const MAX_WINDOW_DAYS: i64 = 90;
+
+/// Days in the month savings are projected over
+const DAYS_PER_MONTH: f64 = 30.0;
+
+/// Smallest cost reduction for a cheaper suggestion (0.1 = 10%)
+const MIN_COST_REDUCTION: f64 = 0.1;
+
+/// Smallest P95 latency reduction for a faster suggestion (0.2 = 20%)
+const MIN_LATENCY_REDUCTION: f64 = 0.2;
+
+/// Largest error rate increase a suggestion may have (0.01 = one point)
+const MAX_ERROR_RATE_INCREASE: f64 = 0.01;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// How spans are grouped into workloads
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkloadGrouping {
+    /// By `prompt.template` attribute, falling back to the span name
+    #[default]
+    PromptTemplate,
+    /// By span name
+    SpanName,
+}
+
+impl WorkloadGrouping {
+    /// SQL expression of the workload of a `llm_traces` row
+    pub fn to_sql_expression(&self) -> &'static str {
+        match self {
+            WorkloadGrouping::PromptTemplate => {
+                "COALESCE(NULLIF(attributes->>'prompt.template', ''), span_name)"
+            }
+            WorkloadGrouping::SpanName => "span_name",
+        }
+    }
+}
+
+/// Query parameters for GET /api/v1/recommendations/models
+#[derive(Debug, Deserialize, Clone)]
+pub struct ModelRecommendationRequest {
+    /// Start of the analysis window (default: 30 days before end_time)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End of the analysis window (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Workload grouping (default: prompt_template)
+    #[serde(default)]
+    pub group_by: WorkloadGrouping,
+
+    /// Filter by environment
+    pub environment: Option<String>,
+
+    /// Fewest requests a model needs on a workload to be compared
+    #[serde(default = "default_min_requests")]
+    pub min_requests: i64,
+
+    /// Largest average feedback score drop a suggestion may have
+    #[serde(default = "default_max_quality_drop")]
+    pub max_quality_drop: f64,
+
+    /// Maximum number of recommendations
+    #[serde(default = "default_recommendation_limit")]
+    pub limit: usize,
+}
+
+fn default_min_requests() -> i64 {
+    50
+}
+
+fn default_max_quality_drop() -> f64 {
+    0.05
+}
+
+fn default_recommendation_limit() -> usize {
+    50
+}
+
+impl ModelRecommendationRequest {
+    /// Validate the request and resolve the analysis window
+    pub fn validate(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+        let end_time = self.end_time.unwrap_or_else(Utc::now);
+        let start_time = self
+            .start_time
+            .unwrap_or(end_time - Duration::days(DEFAULT_WINDOW_DAYS));
+
+        if start_time >= end_time {
+            return Err("Start time must be before end time".to_string());
+        }
+
+        if end_time - start_time > Duration::days(MAX_WINDOW_DAYS) {
+            return Err(format!("Time range cannot exceed {} days", MAX_WINDOW_DAYS));
+        }
+
+        if self.min_requests < 1 {
+            return Err("min_requests must be at least 1".to_string());
+        }
+
+        if !self.max_quality_drop.is_finite() || self.max_quality_drop < 0.0 {
+            return Err("max_quality_drop must be a non-negative number".to_string());
+        }
+
+        if self.limit == 0 || self.limit > 500 {
+            return Err("Limit must be between 1 and 500".to_string());
+        }
+
+        Ok((start_time, end_time))
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for GET /api/v1/recommendations/models
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelRecommendationResponse {
+    pub metadata: RecommendationMetadata,
+    pub recommendations: Vec<ModelRecommendation>,
+    pub summary: RecommendationSummary,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecommendationMetadata {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub group_by: WorkloadGrouping,
+    pub min_requests: i64,
+    pub max_quality_drop: f64,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Why a model is suggested
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationKind {
+    /// Lower cost per request
+    Cheaper,
+    /// Lower P95 latency at no higher cost
+    Faster,
+}
+
+/// A suggested model substitution for one workload
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelRecommendation {
+    /// Prompt template or span name
+    pub workload: String,
+    pub kind: RecommendationKind,
+    pub current: WorkloadModelStats,
+    pub suggested: WorkloadModelStats,
+
+    /// Relative change in average cost per request, in percent
+    pub cost_change_pct: Option<f64>,
+
+    /// Relative change in P95 latency, in percent
+    pub p95_latency_change_pct: Option<f64>,
+
+    /// Absolute change in error rate (0.01 = one percentage point)
+    pub error_rate_change: f64,
+
+    /// Change in average feedback score; None unless both models have feedback
+    pub quality_score_delta: Option<f64>,
+
+    /// Monthly savings if the current model's traffic moved to the
+    /// suggested model, at the window's request rate
+    pub projected_monthly_savings_usd: f64,
+}
+
+/// Statistics of one model on one workload
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkloadModelStats {
+    pub provider: String,
+    pub model: String,
+    pub request_count: i64,
+    pub error_rate: f64,
+    pub avg_cost_usd: f64,
+    pub avg_latency_ms: Option<f64>,
+    pub p95_latency_ms: Option<f64>,
+    pub feedback_count: i64,
+    pub avg_quality_score: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecommendationSummary {
+    /// Workloads with at least two models meeting min_requests
+    pub workloads_compared: usize,
+    pub recommendation_count: usize,
+
+    /// Sum of the best projected savings of each current model
+    pub total_projected_monthly_savings_usd: f64,
+}
+
+// ============================================================================
+// Database Row Types
+// ============================================================================
+
+/// Span and feedback statistics per workload and model
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct WorkloadModelRow {
+    pub workload: Option<String>,
+    pub provider: String,
+    pub model: String,
+    pub request_count: i64,
+    pub error_count: i64,
+    pub total_cost_usd: Option<f64>,
+    pub avg_duration_ms: Option<f64>,
+    pub p95_duration_ms: Option<f64>,
+    pub feedback_count: i64,
+    pub avg_score: Option<f64>,
+}
+
+impl From<&WorkloadModelRow> for WorkloadModelStats {
+    fn from(row: &WorkloadModelRow) -> Self {
+        let ratio = |value: f64| {
+            if row.request_count > 0 {
+                value / row.request_count as f64
+            } else {
+                0.0
+            }
+        };
+
+        Self {
+            provider: row.provider.clone(),
+            model: row.model.clone(),
+            request_count: row.request_count,
+            error_rate: ratio(row.error_count as f64),
+            avg_cost_usd: ratio(row.total_cost_usd.unwrap_or(0.0)),
+            avg_latency_ms: row.avg_duration_ms,
+            p95_latency_ms: row.p95_duration_ms,
+            feedback_count: row.feedback_count,
+            avg_quality_score: row.avg_score,
+        }
+    }
+}
+
+// ============================================================================
+// Recommendations
+// ============================================================================
+
+/// Compare the models of each workload and suggest substitutions, largest
+/// projected savings first
+pub fn build_recommendations(
+    rows: &[WorkloadModelRow],
+    request: &ModelRecommendationRequest,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+) -> ModelRecommendationResponse {
+    let window_days = (end_time - start_time).num_seconds() as f64 / 86_400.0;
+    let monthly_factor = if window_days > 0.0 {
+        DAYS_PER_MONTH / window_days
+    } else {
+        0.0
+    };
+
+    let mut workloads: BTreeMap<&str, Vec<WorkloadModelStats>> = BTreeMap::new();
+    for row in rows {
+        if let Some(workload) = row.workload.as_deref() {
+            if row.request_count >= request.min_requests {
+                workloads.entry(workload).or_default().push(row.into());
+            }
+        }
+    }
+    workloads.retain(|_, models| models.len() >= 2);
+
+    let mut recommendations = Vec::new();
+    let mut total_savings = 0.0;
+    for (workload, models) in &workloads {
+        for current in models {
+            let recommend = |suggested: &WorkloadModelStats, kind| {
+                recommendation(workload, kind, current, suggested, monthly_factor)
+            };
+            let candidates: Vec<&WorkloadModelStats> = models
+                .iter()
+                .filter(|m| m.model != current.model || m.provider != current.provider)
+                .filter(|m| acceptable(current, m, request.max_quality_drop))
+                .collect();
+
+            let cheaper = candidates
+                .iter()
+                .filter(|m| m.avg_cost_usd < current.avg_cost_usd * (1.0 - MIN_COST_REDUCTION))
+                .min_by(|a, b| a.avg_cost_usd.total_cmp(&b.avg_cost_usd))
+                .map(|m| recommend(m, RecommendationKind::Cheaper));
+
+            let faster = candidates
+                .iter()
+                .filter(|m| m.avg_cost_usd <= current.avg_cost_usd)
+                .filter(|m| match (m.p95_latency_ms, current.p95_latency_ms) {
+                    (Some(p95), Some(base)) => p95 < base * (1.0 - MIN_LATENCY_REDUCTION),
+                    _ => false,
+                })
+                .min_by(|a, b| {
+                    let p95 = |m: &WorkloadModelStats| m.p95_latency_ms.unwrap_or(f64::MAX);
+                    p95(a).total_cmp(&p95(b))
+                })
+                .map(|m| recommend(m, RecommendationKind::Faster))
+                .filter(|faster| {
+                    cheaper
+                        .as_ref()
+                        .map_or(true, |c| c.suggested != faster.suggested)
+                });
+
+            total_savings += cheaper
+                .iter()
+                .chain(faster.iter())
+                .map(|r| r.projected_monthly_savings_usd)
+                .fold(0.0, f64::max);
+            recommendations.extend(cheaper);
+            recommendations.extend(faster);
+        }
+    }
+
+    recommendations.sort_by(|a, b| {
+        b.projected_monthly_savings_usd
+            .total_cmp(&a.projected_monthly_savings_usd)
+    });
+    recommendations.truncate(request.limit);
+
+    ModelRecommendationResponse {
+        metadata: RecommendationMetadata {
+            start_time,
+            end_time,
+            group_by: request.group_by,
+            min_requests: request.min_requests,
+            max_quality_drop: request.max_quality_drop,
+            generated_at: Utc::now(),
+        },
+        summary: RecommendationSummary {
+            workloads_compared: workloads.len(),
+            recommendation_count: recommendations.len(),
+            total_projected_monthly_savings_usd: total_savings,
+        },
+        recommendations,
+    }
+}
+
+/// Whether `suggested` is no less reliable than `current` and, when both have
+/// feedback, its quality is within `max_quality_drop`
+fn acceptable(
+    current: &WorkloadModelStats,
+    suggested: &WorkloadModelStats,
+    max_quality_drop: f64,
+) -> bool {
+    if suggested.error_rate > current.error_rate + MAX_ERROR_RATE_INCREASE {
+        return false;
+    }
+    match (suggested.avg_quality_score, current.avg_quality_score) {
+        (Some(suggested), Some(current)) => suggested >= current - max_quality_drop,
+        _ => true,
+    }
+}
+
+fn recommendation(
+    workload: &str,
+    kind: RecommendationKind,
+    current: &WorkloadModelStats,
+    suggested: &WorkloadModelStats,
+    monthly_factor: f64,
+) -> ModelRecommendation {
+    let change_pct = |value: Option<f64>, base: Option<f64>| match (value, base) {
+        (Some(value), Some(base)) if base > 0.0 => Some((value - base) / base * 100.0),
+        _ => None,
+    };
+    let savings_per_request = (current.avg_cost_usd - suggested.avg_cost_usd).max(0.0);
+
+    ModelRecommendation {
+        workload: workload.to_string(),
+        kind,
+        cost_change_pct: change_pct(Some(suggested.avg_cost_usd), Some(current.avg_cost_usd)),
+        p95_latency_change_pct: change_pct(suggested.p95_latency_ms, current.p95_latency_ms),
+        error_rate_change: suggested.error_rate - current.error_rate,
+        quality_score_delta: match (suggested.avg_quality_score, current.avg_quality_score) {
+            (Some(suggested), Some(current)) => Some(suggested - current),
+            _ => None,
+        },
+        projected_monthly_savings_usd: savings_per_request
+            * current.request_count as f64
+            * monthly_factor,
+        current: current.clone(),
+        suggested: suggested.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> ModelRecommendationRequest {
+        ModelRecommendationRequest {
+            start_time: None,
+            end_time: None,
+            group_by: WorkloadGrouping::default(),
+            environment: None,
+            min_requests: 50,
+            max_quality_drop: 0.05,
+            limit: 50,
+        }
+    }
+
+    fn row(
+        workload: &str,
+        model: &str,
+        requests: i64,
+        errors: i64,
+        cost: f64,
+        p95_ms: f64,
+        score: Option<f64>,
+    ) -> WorkloadModelRow {
+        WorkloadModelRow {
+            workload: Some(workload.to_string()),
+            provider: "openai".to_string(),
+            model: model.to_string(),
+            request_count: requests,
+            error_count: errors,
+            total_cost_usd: Some(cost),
+            avg_duration_ms: Some(p95_ms / 2.0),
+            p95_duration_ms: Some(p95_ms),
+            feedback_count: if score.is_some() { 10 } else { 0 },
+            avg_score: score,
+        }
+    }
+
+    #[test]
+    fn test_build_recommendations() {
+        let rows = vec![
+            // summarize: gpt-4o-mini is cheaper at similar quality
+            row("summarize", "gpt-4o", 3000, 30, 30.0, 2000.0, Some(0.80)),
+            row(
+                "summarize",
+                "gpt-4o-mini",
+                1000,
+                10,
+                1.0,
+                1800.0,
+                Some(0.78),
+            ),
+            // classify: the cheap model loses too much quality, the other is faster
+            row("classify", "gpt-4o", 1000, 0, 10.0, 1500.0, Some(0.90)),
+            row("classify", "gpt-4o-mini", 1000, 0, 0.5, 700.0, Some(0.70)),
+            row("classify", "gpt-4.1-nano", 200, 1, 1.9, 500.0, Some(0.88)),
+            // too little traffic to compare
+            row("translate", "gpt-4o", 1000, 0, 10.0, 1500.0, None),
+            row("translate", "gpt-4o-mini", 10, 0, 0.01, 500.0, None),
+        ];
+
+        let end = Utc::now();
+        let start = end - Duration::days(30);
+        let response = build_recommendations(&rows, &request(), start, end);
+
+        assert_eq!(response.summary.workloads_compared, 2);
+        assert_eq!(response.recommendations.len(), 2);
+
+        let cheaper = &response.recommendations[0];
+        assert_eq!(cheaper.workload, "summarize");
+        assert_eq!(cheaper.kind, RecommendationKind::Cheaper);
+        assert_eq!(cheaper.current.model, "gpt-4o");
+        assert_eq!(cheaper.suggested.model, "gpt-4o-mini");
+        // (0.01 - 0.001) per request over 3000 requests a month
+        assert!((cheaper.projected_monthly_savings_usd - 27.0).abs() < 1e-9);
+        assert!((cheaper.quality_score_delta.unwrap() + 0.02).abs() < 1e-9);
+        assert!((cheaper.cost_change_pct.unwrap() + 90.0).abs() < 1e-9);
+
+        let faster = &response.recommendations[1];
+        assert_eq!(faster.workload, "classify");
+        assert_eq!(faster.kind, RecommendationKind::Faster);
+        assert_eq!(faster.suggested.model, "gpt-4.1-nano");
+        assert!((faster.quality_score_delta.unwrap() + 0.02).abs() < 1e-9);
+        assert!((faster.projected_monthly_savings_usd - 0.5).abs() < 1e-9);
+
+        assert!((response.summary.total_projected_monthly_savings_usd - 27.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_savings_scale_to_a_month() {
+        let rows = vec![
+            row("chat", "gpt-4o", 700, 0, 7.0, 1000.0, None),
+            row("chat", "gpt-4o-mini", 700, 0, 0.7, 1000.0, None),
+        ];
+
+        let end = Utc::now();
+        let start = end - Duration::days(7);
+        let response = build_recommendations(&rows, &request(), start, end);
+
+        assert_eq!(response.recommendations.len(), 1);
+        // 0.009 per request, 100 requests a day
+        let savings = response.recommendations[0].projected_monthly_savings_usd;
+        assert!((savings - 27.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_request_validation() {
+        let (start, end) = request().validate().unwrap();
+        assert_eq!(end - start, Duration::days(DEFAULT_WINDOW_DAYS));
+
+        let too_long = ModelRecommendationRequest {
+            start_time: Some(end - Duration::days(91)),
+            end_time: Some(end),
+            ..request()
+        };
+        assert!(too_long.validate().is_err());
+
+        let negative_drop = ModelRecommendationRequest {
+            max_quality_drop: -0.1,
+            ..request()
+        };
+        assert!(negative_drop.validate().is_err());
+
+        let grouping: WorkloadGrouping = serde_json::from_str("\"span_name\"").unwrap();
+        assert_eq!(grouping.to_sql_expression(), "span_name");
+    }
+}
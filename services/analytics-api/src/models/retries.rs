@@ -0,0 +1,290 @@
+//! # Retry and Fallback Data Models
+//!
+//! Data structures for `GET /api/v1/retries`, which reports how often calls
+//! to each model are retried and how often they give up on it for a
+//! fallback model, and why.
+//!
+//! Attempts are read from the `llm.retry.attempt`, `llm.fallback.from_model`
+//! and `llm.fallback.reason` span attributes recorded by the SDK's
+//! `with_retries` / `with_fallback` helpers. Spans without them count as
+//! calls that succeeded or failed on their first attempt.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Default analysis window in days
+const DEFAULT_WINDOW_DAYS: i64 = 7;
+
+/// Longest analysis window in days
+const MAX_WINDOW_DAYS: i64 = 90;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Query parameters for GET /api/v1/retries
+#[derive(Debug, Deserialize, Clone)]
+pub struct RetryRequest {
+    /// Start of the analysis window (default: 7 days before end_time)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End of the analysis window (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub environment: Option<String>,
+
+    /// Maximum models returned, most retried and fallen back from first
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    100
+}
+
+impl RetryRequest {
+    /// Validate the request and resolve the analysis window
+    pub fn validate(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+        let end_time = self.end_time.unwrap_or_else(Utc::now);
+        let start_time = self
+            .start_time
+            .unwrap_or(end_time - Duration::days(DEFAULT_WINDOW_DAYS));
+
+        if start_time >= end_time {
+            return Err("Start time must be before end time".to_string());
+        }
+
+        if end_time - start_time > Duration::days(MAX_WINDOW_DAYS) {
+            return Err(format!("Time range cannot exceed {} days", MAX_WINDOW_DAYS));
+        }
+
+        if !(1..=1000).contains(&self.limit) {
+            return Err("Limit must be between 1 and 1000".to_string());
+        }
+
+        Ok((start_time, end_time))
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for GET /api/v1/retries
+#[derive(Debug, Clone, Serialize)]
+pub struct RetryResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+
+    /// All requests in the window, including models beyond the limit
+    pub totals: RetryStats,
+    pub models: Vec<ModelRetryStats>,
+}
+
+/// Retries and fallbacks of one provider and model
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelRetryStats {
+    pub provider: String,
+    pub model: String,
+
+    #[serde(flatten)]
+    pub stats: RetryStats,
+
+    /// Fallbacks away from the model by reason (`rate_limited`, `timeout`,
+    /// `server_error`, `model_not_found` or `error`)
+    pub fallback_reasons: BTreeMap<String, i64>,
+}
+
+/// Retries and fallbacks of a set of requests
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RetryStats {
+    /// Requests sent, every attempt counted
+    pub request_count: i64,
+
+    /// Requests that were a retry of the same model
+    pub retry_count: i64,
+
+    /// Share of requests that were retries
+    pub retry_rate: f64,
+
+    /// Calls given up on for a fallback model
+    pub fallback_count: i64,
+
+    /// Share of calls reaching the model that fell back to another model
+    pub fallback_rate: f64,
+
+    /// Calls that reached the model as a fallback from another model
+    pub fallback_to_count: i64,
+}
+
+// ============================================================================
+// Database Row Types
+// ============================================================================
+
+/// Attempts sent to one provider and model
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RetryRow {
+    pub provider: String,
+    pub model: String,
+    pub request_count: i64,
+    pub retry_count: i64,
+    pub fallback_to_count: i64,
+}
+
+/// Fallbacks away from one provider and model for one reason
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct FallbackRow {
+    pub provider: String,
+    pub from_model: String,
+    pub reason: String,
+    pub fallback_count: i64,
+}
+
+impl RetryStats {
+    /// Compute the rates from the counts
+    fn with_rates(mut self) -> Self {
+        self.retry_rate = ratio(self.retry_count, self.request_count);
+        // Retries do not reach the model again
+        self.fallback_rate = ratio(self.fallback_count, self.request_count - self.retry_count);
+        self
+    }
+}
+
+fn ratio(part: i64, whole: i64) -> f64 {
+    if whole > 0 {
+        part as f64 / whole as f64
+    } else {
+        0.0
+    }
+}
+
+/// Join the attempts of each model with the fallbacks away from it, compute
+/// the totals and rates, and keep the `limit` models with the most retries
+/// and fallbacks
+pub fn build_report(
+    rows: Vec<RetryRow>,
+    fallbacks: Vec<FallbackRow>,
+    limit: usize,
+) -> (RetryStats, Vec<ModelRetryStats>) {
+    let mut reasons: HashMap<(String, String), BTreeMap<String, i64>> = HashMap::new();
+    for fallback in fallbacks {
+        *reasons
+            .entry((fallback.provider, fallback.from_model))
+            .or_default()
+            .entry(fallback.reason)
+            .or_default() += fallback.fallback_count;
+    }
+
+    let mut totals = RetryStats::default();
+    let mut models: Vec<ModelRetryStats> = rows
+        .into_iter()
+        .map(|row| {
+            let fallback_reasons = reasons
+                .remove(&(row.provider.clone(), row.model.clone()))
+                .unwrap_or_default();
+            let stats = RetryStats {
+                request_count: row.request_count,
+                retry_count: row.retry_count,
+                fallback_count: fallback_reasons.values().sum(),
+                fallback_to_count: row.fallback_to_count,
+                ..Default::default()
+            };
+            totals.request_count += stats.request_count;
+            totals.retry_count += stats.retry_count;
+            totals.fallback_count += stats.fallback_count;
+            totals.fallback_to_count += stats.fallback_to_count;
+
+            ModelRetryStats {
+                provider: row.provider,
+                model: row.model,
+                stats: stats.with_rates(),
+                fallback_reasons,
+            }
+        })
+        .collect();
+
+    models.sort_by(|a, b| {
+        (b.stats.retry_count + b.stats.fallback_count)
+            .cmp(&(a.stats.retry_count + a.stats.fallback_count))
+            .then(b.stats.request_count.cmp(&a.stats.request_count))
+    });
+    models.truncate(limit);
+
+    (totals.with_rates(), models)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(model: &str, request_count: i64, retry_count: i64, fallback_to: i64) -> RetryRow {
+        RetryRow {
+            provider: "openai".to_string(),
+            model: model.to_string(),
+            request_count,
+            retry_count,
+            fallback_to_count: fallback_to,
+        }
+    }
+
+    fn fallback(from_model: &str, reason: &str, count: i64) -> FallbackRow {
+        FallbackRow {
+            provider: "openai".to_string(),
+            from_model: from_model.to_string(),
+            reason: reason.to_string(),
+            fallback_count: count,
+        }
+    }
+
+    #[test]
+    fn test_build_report() {
+        let rows = vec![
+            row("gpt-4o", 120, 20, 0),
+            row("gpt-4o-mini", 15, 0, 10),
+            row("gpt-3.5-turbo", 50, 0, 0),
+        ];
+        let fallbacks = vec![
+            fallback("gpt-4o", "rate_limited", 8),
+            fallback("gpt-4o", "server_error", 2),
+        ];
+
+        let (totals, models) = build_report(rows, fallbacks, 2);
+
+        assert_eq!(totals.request_count, 185);
+        assert_eq!(totals.retry_count, 20);
+        assert_eq!(totals.fallback_count, 10);
+        assert_eq!(totals.fallback_to_count, 10);
+
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].model, "gpt-4o");
+        assert!((models[0].stats.retry_rate - 20.0 / 120.0).abs() < 1e-9);
+        assert!((models[0].stats.fallback_rate - 0.1).abs() < 1e-9);
+        assert_eq!(models[0].fallback_reasons["rate_limited"], 8);
+        assert_eq!(models[0].fallback_reasons["server_error"], 2);
+
+        // Models nothing fell back from have no reasons
+        assert_eq!(models[1].model, "gpt-3.5-turbo");
+        assert!(models[1].fallback_reasons.is_empty());
+        assert_eq!(models[1].stats.fallback_rate, 0.0);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut request = RetryRequest {
+            start_time: None,
+            end_time: None,
+            provider: None,
+            model: None,
+            environment: None,
+            limit: default_limit(),
+        };
+        let (start, end) = request.validate().unwrap();
+        assert_eq!(end - start, Duration::days(7));
+
+        request.limit = 0;
+        assert!(request.validate().is_err());
+    }
+}
@@ -0,0 +1,206 @@
+//! # Span Name Cardinality Data Models
+//!
+//! Data structures for `GET /api/v1/span-names/cardinality`, which finds span
+//! names that embed dynamic IDs (`chat-completion-for-user-123`) and so split
+//! one operation into many names.
+//!
+//! Names are grouped by template: the `llm_observatory.span_name.template`
+//! attribute recorded by the collector's span name processor or, for spans
+//! that did not pass through it, the name with every segment containing a
+//! digit masked as `{id}` the same way. A template is reported when it has at
+//! least `min_distinct_names` distinct names, with a rewrite rule for the
+//! collector's `processors.span_names.rules`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Placeholder for a dynamic segment in a template
+pub const ID_PLACEHOLDER: &str = "{id}";
+
+/// Default distinct names at which a template is reported
+const DEFAULT_MIN_DISTINCT_NAMES: i64 = 20;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for GET /api/v1/span-names/cardinality
+#[derive(Debug, Deserialize, Clone)]
+pub struct SpanNameCardinalityRequest {
+    /// Hours of spans to inspect, ending now (max 168)
+    #[serde(default = "default_window_hours")]
+    pub window_hours: i64,
+
+    /// Filter by environment
+    pub environment: Option<String>,
+
+    /// Distinct names at which a template is reported (default: 20)
+    #[serde(default = "default_min_distinct_names")]
+    pub min_distinct_names: i64,
+
+    /// Maximum number of templates (max 500)
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_window_hours() -> i64 {
+    24
+}
+
+fn default_min_distinct_names() -> i64 {
+    DEFAULT_MIN_DISTINCT_NAMES
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+impl SpanNameCardinalityRequest {
+    /// Validates the request
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window_hours < 1 || self.window_hours > 168 {
+            return Err("window_hours must be between 1 and 168".to_string());
+        }
+
+        if self.min_distinct_names < 2 {
+            return Err("min_distinct_names must be at least 2".to_string());
+        }
+
+        if self.limit < 1 || self.limit > 500 {
+            return Err("Limit must be between 1 and 500".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for GET /api/v1/span-names/cardinality
+#[derive(Debug, Serialize)]
+pub struct SpanNameCardinalityResponse {
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub min_distinct_names: i64,
+
+    /// Templates with the most distinct names first
+    pub templates: Vec<SpanNameTemplate>,
+}
+
+/// A span name template with many distinct names
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpanNameTemplate {
+    /// Template, with `{id}` for dynamic segments
+    pub template: String,
+    pub distinct_names: i64,
+    pub span_count: i64,
+
+    /// Spans the collector already renamed to the template
+    pub normalized_count: i64,
+
+    /// A few of the names, sorted
+    pub sample_names: Vec<String>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+
+    /// Collector rule renaming the names to the template
+    pub suggested_rule: SpanNameRule,
+}
+
+/// A rule for the collector's `processors.span_names.rules`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpanNameRule {
+    pub pattern: String,
+    pub template: String,
+}
+
+// ============================================================================
+// Database Row Types
+// ============================================================================
+
+/// Names of one template
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SpanNameTemplateRow {
+    pub template: String,
+    pub distinct_names: i64,
+    pub span_count: i64,
+    pub normalized_count: i64,
+    pub sample_names: Vec<String>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Rule matching the names of a template: the template as an anchored
+/// regular expression, with each `{id}` matching any text
+pub fn suggested_rule(template: &str) -> SpanNameRule {
+    let pattern = template
+        .split(ID_PLACEHOLDER)
+        .map(escape_regex)
+        .collect::<Vec<_>>()
+        .join(".+");
+
+    SpanNameRule {
+        pattern: format!("^{}$", pattern),
+        template: template.to_string(),
+    }
+}
+
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Build the report entry of a template
+pub fn build_template(row: SpanNameTemplateRow) -> SpanNameTemplate {
+    SpanNameTemplate {
+        suggested_rule: suggested_rule(&row.template),
+        template: row.template,
+        distinct_names: row.distinct_names,
+        span_count: row.span_count,
+        normalized_count: row.normalized_count,
+        sample_names: row.sample_names,
+        first_seen: row.first_seen,
+        last_seen: row.last_seen,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggested_rule() {
+        let rule = suggested_rule("chat.completion-for-user-{id}");
+        assert_eq!(rule.pattern, r"^chat\.completion\-for\-user\-.+$");
+        assert_eq!(rule.template, "chat.completion-for-user-{id}");
+
+        let rule = suggested_rule("GET /orders/{id}/items/{id}");
+        assert_eq!(rule.pattern, "^GET /orders/.+/items/.+$");
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut request = SpanNameCardinalityRequest {
+            window_hours: default_window_hours(),
+            environment: None,
+            min_distinct_names: default_min_distinct_names(),
+            limit: default_limit(),
+        };
+        assert!(request.validate().is_ok());
+
+        request.min_distinct_names = 1;
+        assert!(request.validate().is_err());
+
+        request.min_distinct_names = 2;
+        request.window_hours = 169;
+        assert!(request.validate().is_err());
+    }
+}
@@ -0,0 +1,217 @@
+//! # Service Topology Data Models
+//!
+//! Data structures for `GET /api/v1/topology`, which returns the dependency
+//! graph between services, models and tools materialized in the
+//! `service_edges` table.
+
+use crate::services::topology::{NodeKind, TopologyNode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for GET /api/v1/topology
+#[derive(Debug, Deserialize, Clone)]
+pub struct TopologyRequest {
+    /// Start time (default: 1 hour ago)
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// End time (default: now)
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Only edges touching the node with this name
+    pub node: Option<String>,
+}
+
+impl TopologyRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            if start >= end {
+                return Err("Start time must be before end time".to_string());
+            }
+
+            if (end - start).num_days() > 30 {
+                return Err("Maximum time range is 30 days".to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for GET /api/v1/topology
+#[derive(Debug, Serialize)]
+pub struct TopologyResponse {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub nodes: Vec<TopologyNodeStats>,
+    pub edges: Vec<TopologyEdge>,
+}
+
+/// A node with its incoming and outgoing call counts
+#[derive(Debug, Serialize, PartialEq)]
+pub struct TopologyNodeStats {
+    #[serde(flatten)]
+    pub node: TopologyNode,
+    pub calls_in: i64,
+    pub calls_out: i64,
+    /// Failed calls into this node
+    pub errors_in: i64,
+}
+
+/// Calls from one node to another over the time range
+#[derive(Debug, Serialize, PartialEq)]
+pub struct TopologyEdge {
+    pub source: TopologyNode,
+    pub target: TopologyNode,
+    pub call_count: i64,
+    pub error_count: i64,
+    pub error_rate: f64,
+    pub avg_duration_ms: f64,
+    /// Call-weighted average of the per-bucket p95 durations
+    pub p95_duration_ms: f64,
+}
+
+// ============================================================================
+// Internal Database Row Types
+// ============================================================================
+
+/// Edge aggregated over the time range
+#[derive(Debug, sqlx::FromRow)]
+pub struct TopologyEdgeRow {
+    pub source_kind: String,
+    pub source_name: String,
+    pub target_kind: String,
+    pub target_name: String,
+    pub call_count: Option<i64>,
+    pub error_count: Option<i64>,
+    pub avg_duration_ms: Option<f64>,
+    pub p95_duration_ms: Option<f64>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Build the graph from aggregated edge rows.
+///
+/// Rows with an unknown node kind are skipped. Nodes are sorted by kind and
+/// name.
+pub fn build_topology(rows: &[TopologyEdgeRow]) -> (Vec<TopologyNodeStats>, Vec<TopologyEdge>) {
+    let mut nodes: BTreeMap<TopologyNode, TopologyNodeStats> = BTreeMap::new();
+    let mut edges = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        let (Some(source_kind), Some(target_kind)) =
+            (NodeKind::parse(&row.source_kind), NodeKind::parse(&row.target_kind))
+        else {
+            continue;
+        };
+
+        let source = TopologyNode {
+            kind: source_kind,
+            name: row.source_name.clone(),
+        };
+        let target = TopologyNode {
+            kind: target_kind,
+            name: row.target_name.clone(),
+        };
+        let call_count = row.call_count.unwrap_or(0);
+        let error_count = row.error_count.unwrap_or(0);
+
+        node_stats(&mut nodes, &source).calls_out += call_count;
+        let target_stats = node_stats(&mut nodes, &target);
+        target_stats.calls_in += call_count;
+        target_stats.errors_in += error_count;
+
+        edges.push(TopologyEdge {
+            source,
+            target,
+            call_count,
+            error_count,
+            error_rate: if call_count > 0 {
+                error_count as f64 / call_count as f64
+            } else {
+                0.0
+            },
+            avg_duration_ms: row.avg_duration_ms.unwrap_or(0.0),
+            p95_duration_ms: row.p95_duration_ms.unwrap_or(0.0),
+        });
+    }
+
+    (nodes.into_values().collect(), edges)
+}
+
+fn node_stats<'a>(
+    nodes: &'a mut BTreeMap<TopologyNode, TopologyNodeStats>,
+    node: &TopologyNode,
+) -> &'a mut TopologyNodeStats {
+    nodes.entry(node.clone()).or_insert_with(|| TopologyNodeStats {
+        node: node.clone(),
+        calls_in: 0,
+        calls_out: 0,
+        errors_in: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(source: (&str, &str), target: (&str, &str), calls: i64, errors: i64) -> TopologyEdgeRow {
+        TopologyEdgeRow {
+            source_kind: source.0.to_string(),
+            source_name: source.1.to_string(),
+            target_kind: target.0.to_string(),
+            target_name: target.1.to_string(),
+            call_count: Some(calls),
+            error_count: Some(errors),
+            avg_duration_ms: Some(120.0),
+            p95_duration_ms: Some(400.0),
+        }
+    }
+
+    #[test]
+    fn test_build_topology() {
+        let rows = vec![
+            row(("service", "app"), ("service", "gateway"), 100, 5),
+            row(("service", "gateway"), ("model", "openai/gpt-4o"), 90, 9),
+            row(("model", "openai/gpt-4o"), ("tool", "search"), 30, 0),
+            row(("service", "app"), ("queue", "jobs"), 10, 0),
+        ];
+
+        let (nodes, edges) = build_topology(&rows);
+        assert_eq!(edges.len(), 3);
+        assert_eq!(edges[1].error_rate, 0.1);
+
+        let names: Vec<&str> = nodes.iter().map(|n| n.node.name.as_str()).collect();
+        assert_eq!(names, vec!["app", "gateway", "openai/gpt-4o", "search"]);
+
+        let gateway = &nodes[1];
+        assert_eq!(gateway.calls_in, 100);
+        assert_eq!(gateway.calls_out, 90);
+        assert_eq!(gateway.errors_in, 5);
+
+        let model = &nodes[2];
+        assert_eq!(model.node.kind, NodeKind::Model);
+        assert_eq!(model.errors_in, 9);
+    }
+
+    #[test]
+    fn test_topology_request_validation() {
+        let now = Utc::now();
+        let request = TopologyRequest {
+            start_time: Some(now),
+            end_time: Some(now - chrono::Duration::hours(1)),
+            node: None,
+        };
+        assert!(request.validate().is_err());
+    }
+}
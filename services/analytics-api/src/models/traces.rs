@@ -39,8 +39,9 @@ pub struct TraceQuery {
     // Metadata filters
     pub environment: Option<String>,
     pub tags: Option<String>, // Comma-separated
+    pub attributes: Option<String>, // JSON object, matched with JSONB containment
 
-    // Search
+    // Full-text search (supports "phrases", OR and -negation)
     pub search: Option<String>,
 
     // Pagination
@@ -82,6 +83,7 @@ impl Default for TraceQuery {
             status: None,
             environment: None,
             tags: None,
+            attributes: None,
             search: None,
             cursor: None,
             limit: 50,
@@ -93,6 +95,30 @@ impl Default for TraceQuery {
     }
 }
 
+impl TraceQuery {
+    /// Parse the `attributes` filter into a JSON object.
+    ///
+    /// Returns `Ok(None)` when no attribute filter was given.
+    pub fn attribute_filter(&self) -> Result<Option<serde_json::Value>, String> {
+        let Some(raw) = &self.attributes else {
+            return Ok(None);
+        };
+
+        let value: serde_json::Value = serde_json::from_str(raw)
+            .map_err(|e| format!("Invalid attributes filter: {}", e))?;
+
+        match &value {
+            serde_json::Value::Object(map) if !map.is_empty() => {
+                if let Some(key) = map.keys().find(|k| !crate::models::filters::is_valid_attribute_key(k)) {
+                    return Err(format!("Invalid attribute key: {}", key));
+                }
+                Ok(Some(value))
+            }
+            _ => Err("Attributes filter must be a non-empty JSON object".to_string()),
+        }
+    }
+}
+
 /// Sort order
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -203,9 +229,35 @@ pub struct Trace {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attributes: Option<serde_json::Value>,
+
+    // Links to causally related spans: [{trace_id, span_id, attributes}]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(default)]
+    pub links: Option<serde_json::Value>,
+
+    // Full-text search relevance (only present when `search` is used)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(default)]
+    pub search_rank: Option<f32>,
 }
 
 impl Trace {
+    /// Spans this span links to, skipping malformed links
+    pub fn linked_spans(&self) -> Vec<LinkedSpan> {
+        match &self.links {
+            Some(serde_json::Value::Array(links)) => links
+                .iter()
+                .filter_map(|link| {
+                    Some(LinkedSpan {
+                        trace_id: link.get("trace_id")?.as_str()?.to_string(),
+                        span_id: link.get("span_id")?.as_str()?.to_string(),
+                    })
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
     /// Calculate total cost if not present
     pub fn calculate_total_cost(&mut self) {
         if self.total_cost_usd.is_none() {
@@ -275,9 +327,43 @@ pub struct ResponseMetadata {
 pub struct SingleTraceResponse {
     pub status: ResponseStatus,
     pub data: Trace,
+
+    /// Traces related to this one by span links
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub linked_traces: Vec<LinkedTrace>,
+
     pub meta: ResponseMetadata,
 }
 
+/// Span referenced by a span link
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkedSpan {
+    pub trace_id: String,
+    pub span_id: String,
+}
+
+/// Which side of a span link a trace is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LinkDirection {
+    /// Spans of the requested trace link to the linked trace
+    Outgoing,
+    /// Spans of the linked trace link to the requested trace, e.g. sub-agents
+    /// started by it
+    Incoming,
+}
+
+/// Trace related to another trace by span links
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LinkedTrace {
+    pub trace_id: String,
+    pub direction: LinkDirection,
+
+    /// Links between the two traces
+    pub link_count: i64,
+}
+
 /// Trace statistics
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TraceStats {
@@ -312,6 +398,30 @@ mod tests {
         assert_eq!(cursor.span_id, decoded.span_id);
     }
 
+    #[test]
+    fn test_linked_spans() {
+        let trace: Trace = serde_json::from_value(serde_json::json!({
+            "ts": "2025-01-01T00:00:00Z",
+            "trace_id": "t1",
+            "span_id": "s1",
+            "provider": "openai",
+            "model": "gpt-4o",
+            "links": [
+                {"trace_id": "t2", "span_id": "s2", "attributes": {}},
+                {"trace_id": "t3"}
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            trace.linked_spans(),
+            [LinkedSpan {
+                trace_id: "t2".to_string(),
+                span_id: "s2".to_string(),
+            }]
+        );
+    }
+
     #[test]
     fn test_trace_cost_calculation() {
         let mut trace = Trace {
@@ -340,6 +450,8 @@ mod tests {
             environment: None,
             tags: None,
             attributes: None,
+            links: None,
+            search_rank: None,
         };
 
         trace.calculate_total_cost();
@@ -349,6 +461,23 @@ mod tests {
         assert_eq!(trace.total_tokens, Some(150));
     }
 
+    #[test]
+    fn test_attribute_filter_parsing() {
+        let query = TraceQuery {
+            attributes: Some(r#"{"gen_ai.system":"openai","retries":2}"#.to_string()),
+            ..Default::default()
+        };
+        let filter = query.attribute_filter().unwrap().unwrap();
+        assert_eq!(filter["gen_ai.system"], "openai");
+
+        let invalid = TraceQuery {
+            attributes: Some(r#"["gen_ai.system"]"#.to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.attribute_filter().is_err());
+        assert!(TraceQuery::default().attribute_filter().unwrap().is_none());
+    }
+
     #[test]
     fn test_trace_query_defaults() {
         let query = TraceQuery::default();
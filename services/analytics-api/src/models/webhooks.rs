@@ -0,0 +1,89 @@
+//! # Webhook Data Models
+//!
+//! Request and response types for registering webhook endpoints and Slack
+//! and PagerDuty notification channels and inspecting their deliveries.
+//! Endpoints, channels and deliveries themselves are defined by the
+//! `llm-observatory-webhooks` crate.
+
+use llm_observatory_webhooks::{NotificationChannel, WebhookDelivery, WebhookEndpoint};
+use serde::{Deserialize, Serialize};
+
+pub use llm_observatory_webhooks::{NewNotificationChannel, NewWebhookEndpoint};
+
+// ============================================================================
+// Request Models
+// ============================================================================
+
+/// Request for GET /api/v1/webhooks/:id/deliveries and
+/// GET /api/v1/notification-channels/:id/deliveries
+#[derive(Debug, Deserialize, Clone)]
+pub struct DeliveryListQuery {
+    /// Maximum deliveries (default: 50, max: 500)
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+impl DeliveryListQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.limit < 1 || self.limit > 500 {
+            return Err(format!(
+                "Limit must be between 1 and 500, got {}",
+                self.limit
+            ));
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Response Models
+// ============================================================================
+
+/// Response for POST /api/v1/webhooks
+///
+/// The only response that includes the signing secret.
+#[derive(Debug, Serialize)]
+pub struct CreatedWebhookEndpoint {
+    #[serde(flatten)]
+    pub endpoint: WebhookEndpoint,
+    /// HMAC-SHA256 signing secret; store it, it is not shown again
+    pub secret: String,
+}
+
+/// Response for GET /api/v1/webhooks
+#[derive(Debug, Serialize)]
+pub struct WebhookEndpointListResponse {
+    pub endpoints: Vec<WebhookEndpoint>,
+}
+
+/// Response for GET /api/v1/notification-channels
+#[derive(Debug, Serialize)]
+pub struct NotificationChannelListResponse {
+    pub channels: Vec<NotificationChannel>,
+}
+
+/// Response for GET /api/v1/webhooks/:id/deliveries and
+/// GET /api/v1/notification-channels/:id/deliveries
+#[derive(Debug, Serialize)]
+pub struct DeliveryListResponse {
+    pub deliveries: Vec<WebhookDelivery>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delivery_list_query_validate() {
+        let query: DeliveryListQuery = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(query.limit, 50);
+        assert!(query.validate().is_ok());
+
+        assert!(DeliveryListQuery { limit: 0 }.validate().is_err());
+        assert!(DeliveryListQuery { limit: 501 }.validate().is_err());
+    }
+}
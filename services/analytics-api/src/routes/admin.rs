@@ -0,0 +1,709 @@
+//! # Admin API Routes
+//!
+//! - `GET /api/v1/admin/organizations` lists organizations
+//! - `POST /api/v1/admin/organizations` creates an organization
+//! - `GET /api/v1/admin/organizations/:org_id` returns an organization
+//! - `PATCH /api/v1/admin/organizations/:org_id` renames an organization
+//! - `DELETE /api/v1/admin/organizations/:org_id` deletes an organization
+//!   with its teams and settings
+//! - `GET /api/v1/admin/organizations/:org_id/settings` returns the
+//!   retention, capture policy and quota settings
+//! - `PUT /api/v1/admin/organizations/:org_id/settings` replaces them
+//! - `GET /api/v1/admin/organizations/:org_id/teams` lists teams
+//! - `POST /api/v1/admin/organizations/:org_id/teams` creates a team
+//! - `GET`, `PATCH` and `DELETE /api/v1/admin/organizations/:org_id/teams/:team_id`
+//!   read, update and delete a team
+//! - `GET /api/v1/admin/organizations/:org_id/teams/:team_id/members` lists
+//!   team members
+//! - `PUT` and `DELETE /api/v1/admin/organizations/:org_id/teams/:team_id/members/:user_id`
+//!   add a user to a team (or change their role) and remove them
+//! - `GET` and `POST /api/v1/admin/organizations/:org_id/service-accounts`
+//!   list and create service accounts
+//! - `GET`, `PATCH` and `DELETE /api/v1/admin/organizations/:org_id/service-accounts/:account_id`
+//!   read, update (permissions, projects, disabling) and delete a service
+//!   account
+//! - `GET` and `POST /api/v1/admin/organizations/:org_id/service-accounts/:account_id/tokens`
+//!   list and issue tokens
+//! - `DELETE /api/v1/admin/organizations/:org_id/service-accounts/:account_id/tokens/:token_id`
+//!   revokes a token
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Listing, creating and deleting organizations requires
+//!   `manage:organizations`, granted explicitly to platform operators; the
+//!   admin role does not imply it
+//! - An organization's own resources (name, settings, teams, members,
+//!   service accounts) also
+//!   accept `manage:organization` (admins by default) for the caller's own
+//!   organization
+//!
+//! ## Enforcement
+//! Quotas and capture policies are loaded into the collector's quota and
+//! capture policy processors on their next sync; retention applies on the
+//! next run of `apply_organization_retention()`.
+
+use crate::middleware::AuthContext;
+use crate::models::admin::*;
+use crate::models::{AppState, ErrorResponse};
+use crate::services::admin::AdminError;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get, put},
+    Json, Router,
+};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// Permission to manage every organization
+const MANAGE_ORGANIZATIONS: &str = "manage:organizations";
+
+/// Permission to manage the caller's own organization
+const MANAGE_ORGANIZATION: &str = "manage:organization";
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create admin routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/api/v1/admin/organizations",
+            get(list_organizations).post(create_organization),
+        )
+        .route(
+            "/api/v1/admin/organizations/:org_id",
+            get(get_organization)
+                .patch(update_organization)
+                .delete(delete_organization),
+        )
+        .route(
+            "/api/v1/admin/organizations/:org_id/settings",
+            get(get_settings).put(put_settings),
+        )
+        .route(
+            "/api/v1/admin/organizations/:org_id/teams",
+            get(list_teams).post(create_team),
+        )
+        .route(
+            "/api/v1/admin/organizations/:org_id/teams/:team_id",
+            get(get_team).patch(update_team).delete(delete_team),
+        )
+        .route(
+            "/api/v1/admin/organizations/:org_id/teams/:team_id/members",
+            get(list_members),
+        )
+        .route(
+            "/api/v1/admin/organizations/:org_id/teams/:team_id/members/:user_id",
+            put(put_member).delete(remove_member),
+        )
+        .route(
+            "/api/v1/admin/organizations/:org_id/service-accounts",
+            get(list_service_accounts).post(create_service_account),
+        )
+        .route(
+            "/api/v1/admin/organizations/:org_id/service-accounts/:account_id",
+            get(get_service_account)
+                .patch(update_service_account)
+                .delete(delete_service_account),
+        )
+        .route(
+            "/api/v1/admin/organizations/:org_id/service-accounts/:account_id/tokens",
+            get(list_tokens).post(issue_token),
+        )
+        .route(
+            "/api/v1/admin/organizations/:org_id/service-accounts/:account_id/tokens/:token_id",
+            delete(revoke_token),
+        )
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    Internal(String),
+    ServiceUnavailable(String),
+}
+
+impl From<AdminError> for ApiError {
+    fn from(e: AdminError) -> Self {
+        match e {
+            AdminError::Disabled => ApiError::ServiceUnavailable(e.to_string()),
+            AdminError::NotFound(_) => ApiError::NotFound(e.to_string()),
+            AdminError::Conflict(_) => ApiError::Conflict(e.to_string()),
+            AdminError::Signing(_) | AdminError::Database(_) => {
+                error!(error = %e, "Admin operation failed");
+                ApiError::Internal(e.to_string())
+            }
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, "not_found", msg),
+            ApiError::Conflict(msg) => (StatusCode::CONFLICT, "conflict", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+            ApiError::ServiceUnavailable(msg) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "service_unavailable", msg)
+            }
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+/// Whether the caller manages every organization. Checked against the
+/// explicit permissions, as the admin role's wildcard only covers its own
+/// organization here.
+fn is_operator(auth: &AuthContext) -> bool {
+    auth.permissions.iter().any(|p| p == MANAGE_ORGANIZATIONS)
+}
+
+fn require_operator(auth: &AuthContext) -> Result<(), ApiError> {
+    if is_operator(auth) {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(
+            "Insufficient permissions to manage organizations".to_string(),
+        ))
+    }
+}
+
+fn require_org_admin(auth: &AuthContext, org_id: &str) -> Result<(), ApiError> {
+    if is_operator(auth) || (auth.org_id == org_id && auth.has_permission(MANAGE_ORGANIZATION)) {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(format!(
+            "Insufficient permissions to manage organization {}",
+            org_id
+        )))
+    }
+}
+
+// ============================================================================
+// Endpoint: GET/POST /api/v1/admin/organizations
+// ============================================================================
+
+/// GET /api/v1/admin/organizations - All organizations
+#[instrument(skip(state, auth))]
+async fn list_organizations(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+) -> Result<Json<OrganizationListResponse>, ApiError> {
+    // Check permissions
+    require_operator(&auth)?;
+
+    let organizations = state.admin.list_organizations().await?;
+    Ok(Json(OrganizationListResponse { organizations }))
+}
+
+/// POST /api/v1/admin/organizations - Create an organization
+///
+/// ## Example
+/// ```bash
+/// curl -X POST 'http://localhost:8080/api/v1/admin/organizations' \
+///   -H "Authorization: Bearer $JWT_TOKEN" \
+///   -H "Content-Type: application/json" \
+///   -d '{"id": "acme", "name": "Acme Corp"}'
+/// ```
+#[instrument(skip(state, auth, request))]
+async fn create_organization(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Json(request): Json<CreateOrganizationRequest>,
+) -> Result<(StatusCode, Json<Organization>), ApiError> {
+    // Check permissions
+    require_operator(&auth)?;
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let organization = state.admin.create_organization(&request).await?;
+    Ok((StatusCode::CREATED, Json(organization)))
+}
+
+// ============================================================================
+// Endpoint: GET/PATCH/DELETE /api/v1/admin/organizations/:org_id
+// ============================================================================
+
+/// GET /api/v1/admin/organizations/:org_id - An organization
+#[instrument(skip(state, auth))]
+async fn get_organization(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(org_id): Path<String>,
+) -> Result<Json<Organization>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    Ok(Json(state.admin.get_organization(&org_id).await?))
+}
+
+/// PATCH /api/v1/admin/organizations/:org_id - Rename an organization
+#[instrument(skip(state, auth, request))]
+async fn update_organization(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(org_id): Path<String>,
+    Json(request): Json<UpdateOrganizationRequest>,
+) -> Result<Json<Organization>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    Ok(Json(
+        state.admin.update_organization(&org_id, &request).await?,
+    ))
+}
+
+/// DELETE /api/v1/admin/organizations/:org_id - Delete an organization with
+/// its teams, memberships and settings
+///
+/// Traces are kept; delete them with `DELETE /api/v1/traces` first.
+#[instrument(skip(state, auth))]
+async fn delete_organization(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(org_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    // Check permissions
+    require_operator(&auth)?;
+
+    state.admin.delete_organization(&org_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Endpoint: GET/PUT /api/v1/admin/organizations/:org_id/settings
+// ============================================================================
+
+/// GET /api/v1/admin/organizations/:org_id/settings - Retention, capture
+/// policy and quota settings; null settings use the system default
+#[instrument(skip(state, auth))]
+async fn get_settings(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(org_id): Path<String>,
+) -> Result<Json<OrganizationSettings>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    Ok(Json(state.admin.settings(&org_id).await?))
+}
+
+/// PUT /api/v1/admin/organizations/:org_id/settings - Replace the settings
+///
+/// Omitted settings are reset to the system default.
+///
+/// ## Example
+/// ```bash
+/// curl -X PUT 'http://localhost:8080/api/v1/admin/organizations/acme/settings' \
+///   -H "Authorization: Bearer $JWT_TOKEN" \
+///   -H "Content-Type: application/json" \
+///   -d '{"retention_days": 30, "capture_policy": "metadata_only", "spans_per_day_limit": 1000000, "over_quota_action": "sample"}'
+/// ```
+#[instrument(skip(state, auth, request))]
+async fn put_settings(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(org_id): Path<String>,
+    Json(request): Json<OrganizationSettingsRequest>,
+) -> Result<Json<OrganizationSettings>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let settings = state
+        .admin
+        .put_settings(&org_id, &request, &auth.user_id)
+        .await?;
+    Ok(Json(settings))
+}
+
+// ============================================================================
+// Endpoint: GET/POST /api/v1/admin/organizations/:org_id/teams
+// ============================================================================
+
+/// GET /api/v1/admin/organizations/:org_id/teams - Teams of an organization
+#[instrument(skip(state, auth))]
+async fn list_teams(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(org_id): Path<String>,
+) -> Result<Json<TeamListResponse>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    let teams = state.admin.list_teams(&org_id).await?;
+    Ok(Json(TeamListResponse { teams }))
+}
+
+/// POST /api/v1/admin/organizations/:org_id/teams - Create a team
+#[instrument(skip(state, auth, request))]
+async fn create_team(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(org_id): Path<String>,
+    Json(request): Json<CreateTeamRequest>,
+) -> Result<(StatusCode, Json<Team>), ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let team = state.admin.create_team(&org_id, &request).await?;
+    Ok((StatusCode::CREATED, Json(team)))
+}
+
+// ============================================================================
+// Endpoint: GET/PATCH/DELETE /api/v1/admin/organizations/:org_id/teams/:team_id
+// ============================================================================
+
+/// GET /api/v1/admin/organizations/:org_id/teams/:team_id - A team
+#[instrument(skip(state, auth))]
+async fn get_team(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path((org_id, team_id)): Path<(String, Uuid)>,
+) -> Result<Json<Team>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    Ok(Json(state.admin.get_team(&org_id, team_id).await?))
+}
+
+/// PATCH /api/v1/admin/organizations/:org_id/teams/:team_id - Rename or
+/// describe a team
+#[instrument(skip(state, auth, request))]
+async fn update_team(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path((org_id, team_id)): Path<(String, Uuid)>,
+    Json(request): Json<UpdateTeamRequest>,
+) -> Result<Json<Team>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    Ok(Json(
+        state.admin.update_team(&org_id, team_id, &request).await?,
+    ))
+}
+
+/// DELETE /api/v1/admin/organizations/:org_id/teams/:team_id - Delete a team
+/// and its memberships
+#[instrument(skip(state, auth))]
+async fn delete_team(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path((org_id, team_id)): Path<(String, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    state.admin.delete_team(&org_id, team_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Endpoint: /api/v1/admin/organizations/:org_id/teams/:team_id/members
+// ============================================================================
+
+/// GET /api/v1/admin/organizations/:org_id/teams/:team_id/members - Members
+/// of a team
+#[instrument(skip(state, auth))]
+async fn list_members(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path((org_id, team_id)): Path<(String, Uuid)>,
+) -> Result<Json<TeamMemberListResponse>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    let members = state.admin.list_members(&org_id, team_id).await?;
+    Ok(Json(TeamMemberListResponse { members }))
+}
+
+/// PUT /api/v1/admin/organizations/:org_id/teams/:team_id/members/:user_id -
+/// Add a user to a team, or change their role
+///
+/// ## Example
+/// ```bash
+/// curl -X PUT 'http://localhost:8080/api/v1/admin/organizations/acme/teams/8f14e45f-ceea-467f-a8f0-2b1c3d4e5f60/members/alice' \
+///   -H "Authorization: Bearer $JWT_TOKEN" \
+///   -H "Content-Type: application/json" \
+///   -d '{"role": "maintainer"}'
+/// ```
+#[instrument(skip(state, auth, request))]
+async fn put_member(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path((org_id, team_id, user_id)): Path<(String, Uuid, String)>,
+    Json(request): Json<TeamMemberRequest>,
+) -> Result<Json<TeamMember>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    let member = state
+        .admin
+        .put_member(&org_id, team_id, &user_id, request.role)
+        .await?;
+    Ok(Json(member))
+}
+
+/// DELETE /api/v1/admin/organizations/:org_id/teams/:team_id/members/:user_id -
+/// Remove a user from a team
+#[instrument(skip(state, auth))]
+async fn remove_member(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path((org_id, team_id, user_id)): Path<(String, Uuid, String)>,
+) -> Result<StatusCode, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    state
+        .admin
+        .remove_member(&org_id, team_id, &user_id)
+        .await?;
+    info!(org_id = %org_id, team_id = %team_id, user_id = %user_id, "Removed team member");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Endpoint: /api/v1/admin/organizations/:org_id/service-accounts
+// ============================================================================
+
+/// GET /api/v1/admin/organizations/:org_id/service-accounts - Service
+/// accounts of an organization
+#[instrument(skip(state, auth))]
+async fn list_service_accounts(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(org_id): Path<String>,
+) -> Result<Json<ServiceAccountListResponse>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    let service_accounts = state.service_accounts.list(&org_id).await?;
+    Ok(Json(ServiceAccountListResponse { service_accounts }))
+}
+
+/// POST /api/v1/admin/organizations/:org_id/service-accounts - Create a
+/// service account
+///
+/// ## Example
+/// ```bash
+/// curl -X POST 'http://localhost:8080/api/v1/admin/organizations/acme/service-accounts' \
+///   -H "Authorization: Bearer $JWT_TOKEN" \
+///   -H "Content-Type: application/json" \
+///   -d '{"name": "collectors", "permissions": ["write:traces"]}'
+/// ```
+#[instrument(skip(state, auth, request))]
+async fn create_service_account(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(org_id): Path<String>,
+    Json(request): Json<CreateServiceAccountRequest>,
+) -> Result<(StatusCode, Json<ServiceAccount>), ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let account = state
+        .service_accounts
+        .create(&org_id, &request, &auth.user_id)
+        .await?;
+    Ok((StatusCode::CREATED, Json(account)))
+}
+
+/// GET /api/v1/admin/organizations/:org_id/service-accounts/:account_id - A
+/// service account
+#[instrument(skip(state, auth))]
+async fn get_service_account(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path((org_id, account_id)): Path<(String, Uuid)>,
+) -> Result<Json<ServiceAccount>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    Ok(Json(state.service_accounts.get(&org_id, account_id).await?))
+}
+
+/// PATCH /api/v1/admin/organizations/:org_id/service-accounts/:account_id -
+/// Change a service account's permissions or projects, or disable it
+///
+/// Changes apply to tokens already issued.
+#[instrument(skip(state, auth, request))]
+async fn update_service_account(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path((org_id, account_id)): Path<(String, Uuid)>,
+    Json(request): Json<UpdateServiceAccountRequest>,
+) -> Result<Json<ServiceAccount>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    Ok(Json(
+        state
+            .service_accounts
+            .update(&org_id, account_id, &request)
+            .await?,
+    ))
+}
+
+/// DELETE /api/v1/admin/organizations/:org_id/service-accounts/:account_id -
+/// Delete a service account and its tokens
+#[instrument(skip(state, auth))]
+async fn delete_service_account(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path((org_id, account_id)): Path<(String, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    state.service_accounts.delete(&org_id, account_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Endpoint: /api/v1/admin/organizations/:org_id/service-accounts/:account_id/tokens
+// ============================================================================
+
+/// GET /api/v1/admin/organizations/:org_id/service-accounts/:account_id/tokens -
+/// Tokens of a service account, without the tokens themselves
+#[instrument(skip(state, auth))]
+async fn list_tokens(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path((org_id, account_id)): Path<(String, Uuid)>,
+) -> Result<Json<ServiceAccountTokenListResponse>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    let tokens = state
+        .service_accounts
+        .list_tokens(&org_id, account_id)
+        .await?;
+    Ok(Json(ServiceAccountTokenListResponse { tokens }))
+}
+
+/// POST /api/v1/admin/organizations/:org_id/service-accounts/:account_id/tokens -
+/// Issue a token; it is only returned in this response
+///
+/// ## Example
+/// ```bash
+/// curl -X POST 'http://localhost:8080/api/v1/admin/organizations/acme/service-accounts/8f14e45f-ceea-467f-a8f0-2b1c3d4e5f60/tokens' \
+///   -H "Authorization: Bearer $JWT_TOKEN" \
+///   -H "Content-Type: application/json" \
+///   -d '{"name": "collector-eu-1", "ttl_days": 30}'
+/// ```
+#[instrument(skip(state, auth, request))]
+async fn issue_token(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path((org_id, account_id)): Path<(String, Uuid)>,
+    Json(request): Json<CreateServiceAccountTokenRequest>,
+) -> Result<(StatusCode, Json<IssuedServiceAccountToken>), ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let token = state
+        .service_accounts
+        .issue_token(&org_id, account_id, &request, &auth.user_id)
+        .await?;
+    Ok((StatusCode::CREATED, Json(token)))
+}
+
+/// DELETE /api/v1/admin/organizations/:org_id/service-accounts/:account_id/tokens/:token_id -
+/// Revoke a token
+#[instrument(skip(state, auth))]
+async fn revoke_token(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path((org_id, account_id, token_id)): Path<(String, Uuid, Uuid)>,
+) -> Result<Json<ServiceAccountToken>, ApiError> {
+    // Check permissions
+    require_org_admin(&auth, &org_id)?;
+
+    let token = state
+        .service_accounts
+        .revoke_token(&org_id, account_id, token_id, &auth.user_id)
+        .await?;
+    Ok(Json(token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::{auth::AuthMethod, Role};
+
+    fn auth(role: Role, permissions: &[&str]) -> AuthContext {
+        AuthContext {
+            user_id: "user-1".to_string(),
+            org_id: "acme".to_string(),
+            projects: vec![],
+            role,
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            auth_method: AuthMethod::Jwt,
+            request_id: "req-1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_org_admins_manage_only_their_organization() {
+        let admin = auth(Role::Admin, &["*"]);
+        assert!(require_org_admin(&admin, "acme").is_ok());
+        assert!(require_org_admin(&admin, "globex").is_err());
+        assert!(require_operator(&admin).is_err());
+
+        let delegated = auth(Role::Developer, &[MANAGE_ORGANIZATION]);
+        assert!(require_org_admin(&delegated, "acme").is_ok());
+
+        let viewer = auth(Role::Viewer, &["read:metrics"]);
+        assert!(require_org_admin(&viewer, "acme").is_err());
+    }
+
+    #[test]
+    fn test_operators_manage_every_organization() {
+        let operator = auth(Role::Viewer, &[MANAGE_ORGANIZATIONS]);
+        assert!(require_operator(&operator).is_ok());
+        assert!(require_org_admin(&operator, "globex").is_ok());
+    }
+}
@@ -0,0 +1,215 @@
+//! # Audit Log API Routes
+//!
+//! - `GET /api/v1/audit` lists authenticated API calls, newest first
+//! - `GET /api/v1/audit/data-access` lists who received unmasked trace data
+//!   (prompt/response text, user identifiers), newest first
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `read:audit`
+//! - Entries are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::audit::*;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create audit log routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/api/v1/audit", get(get_audit_log))
+        .route("/api/v1/audit/data-access", get(get_data_access_log))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/audit
+// ============================================================================
+
+/// GET /api/v1/audit - API call audit trail
+///
+/// ## Query Parameters
+/// - `start_time`: Start of time range (ISO 8601) - default: 24 hours ago
+/// - `end_time`: End of time range (ISO 8601) - default: now
+/// - `user_id`: Only calls by this user
+/// - `endpoint`: Only calls to this route (e.g. `/api/v1/traces/:trace_id`)
+/// - `min_status`: Only calls with at least this status code
+/// - `limit`: Maximum entries - default: 100, max: 1000
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/audit?user_id=user-42&min_status=400' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_audit_log(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<AuditLogQuery>,
+) -> Result<Json<AuditLogResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("read:audit") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read the audit log".to_string(),
+        ));
+    }
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let end_time = request.end_time.unwrap_or_else(Utc::now);
+    let start_time = request
+        .start_time
+        .unwrap_or_else(|| end_time - Duration::hours(24));
+
+    info!(org_id = %auth.org_id, user_id = ?request.user_id, "Querying audit log");
+
+    let entries = sqlx::query_as::<_, AuditLogEntry>(
+        r#"
+        SELECT
+            ts, request_id, org_id, user_id, role, auth_method,
+            method, endpoint, path, query_params, request_body,
+            status_code, row_count, duration_ms
+        FROM audit_log
+        WHERE org_id = $1
+          AND ts >= $2
+          AND ts < $3
+          AND ($4::TEXT IS NULL OR user_id = $4)
+          AND ($5::TEXT IS NULL OR endpoint = $5)
+          AND ($6::INTEGER IS NULL OR status_code >= $6)
+        ORDER BY ts DESC
+        LIMIT $7
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(start_time)
+    .bind(end_time)
+    .bind(&request.user_id)
+    .bind(&request.endpoint)
+    .bind(request.min_status)
+    .bind(request.limit)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to query audit log");
+        ApiError::Internal(format!("Database query failed: {}", e))
+    })?;
+
+    Ok(Json(AuditLogResponse {
+        start_time,
+        end_time,
+        entries,
+    }))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/audit/data-access
+// ============================================================================
+
+/// GET /api/v1/audit/data-access - Unmasked data access log
+///
+/// ## Query Parameters
+/// - `start_time`: Start of time range (ISO 8601) - default: 7 days ago
+/// - `end_time`: End of time range (ISO 8601) - default: now
+/// - `user_id`: Only entries for this user
+/// - `limit`: Maximum entries - default: 100, max: 1000
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/audit/data-access?user_id=user-42' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_data_access_log(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<DataAccessAuditQuery>,
+) -> Result<Json<DataAccessAuditResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("read:audit") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read the audit log".to_string(),
+        ));
+    }
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let end_time = request.end_time.unwrap_or_else(Utc::now);
+    let start_time = request
+        .start_time
+        .unwrap_or_else(|| end_time - Duration::days(7));
+
+    info!(org_id = %auth.org_id, user_id = ?request.user_id, "Querying data access audit log");
+
+    let entries = sqlx::query_as::<_, DataAccessAuditEntry>(
+        r#"
+        SELECT ts, org_id, user_id, role, request_id, endpoint, fields, trace_ids
+        FROM data_access_audit_log
+        WHERE org_id = $1
+          AND ts >= $2
+          AND ts < $3
+          AND ($4::TEXT IS NULL OR user_id = $4)
+        ORDER BY ts DESC
+        LIMIT $5
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(start_time)
+    .bind(end_time)
+    .bind(&request.user_id)
+    .bind(request.limit)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to query data access audit log");
+        ApiError::Internal(format!("Database query failed: {}", e))
+    })?;
+
+    Ok(Json(DataAccessAuditResponse {
+        start_time,
+        end_time,
+        entries,
+    }))
+}
@@ -0,0 +1,205 @@
+//! # Provider Concurrency API Routes
+//!
+//! Providers limit how many requests and tokens an organization may use,
+//! and as traffic approaches those limits requests wait longer for the
+//! provider's first byte before they fail with 429s. This endpoint reports
+//! requests in flight and queue time per provider over time, flagging
+//! saturated periods, so limits can be raised before they bite.
+//!
+//! ## Endpoints
+//! - GET /api/v1/concurrency - Concurrency, queue time and saturation per provider
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `metrics:read`
+//! - Results are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::concurrency::*;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create concurrency routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/api/v1/concurrency", get(get_concurrency))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        error!(error = %err, "Concurrency query failed");
+        ApiError::Internal(format!("Database query failed: {}", err))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/concurrency
+// ============================================================================
+
+/// GET /api/v1/concurrency - Concurrency, queue time and saturation per provider
+///
+/// Reports, per provider and bucket, the requests started, the average and
+/// peak number of requests in flight, the queue time (request sent to first
+/// byte) and execution time recorded by the collector, and requests that
+/// failed with a rate limit error. A bucket is saturated when its average
+/// queue time reaches `saturation_factor` times the provider's baseline (the
+/// median over all buckets) or requests were rate limited; the lowest peak
+/// concurrency of the saturated buckets shows how much parallelism the
+/// current limits allow.
+///
+/// Requests started before the window are not counted as in flight.
+///
+/// Query Parameters:
+/// - start_time, end_time: Analysis window (default: the last 24 hours, at most 7 days)
+/// - provider, model, environment: Filters (optional)
+/// - interval: `minute` or `hour` (default: minute)
+/// - saturation_factor: Queue time over the baseline at which a bucket is saturated (default: 2.0)
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/concurrency?provider=openai&interval=hour' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_concurrency(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<ConcurrencyRequest>,
+) -> Result<Json<ConcurrencyResponse>, ApiError> {
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read concurrency metrics".to_string(),
+        ));
+    }
+    let (start_time, end_time) = request.validate().map_err(ApiError::BadRequest)?;
+    let unit = request.interval.as_str();
+
+    // Requests in flight change by +1 at each start and -1 at each end; at
+    // equal times ends are counted first
+    let query = format!(
+        r#"
+        WITH calls AS (
+            SELECT
+                provider,
+                ts AS started_at,
+                ts + duration_ms * INTERVAL '1 millisecond' AS ended_at,
+                duration_ms,
+                queue_ms,
+                execution_ms,
+                status_code = 'ERROR'
+                    AND (error_message ILIKE '%429%' OR error_message ILIKE '%rate limit%')
+                    AS rate_limited
+            FROM llm_traces
+            WHERE attributes->>'org_id' = $1
+              AND ts >= $2
+              AND ts < $3
+              AND ($4::TEXT IS NULL OR provider = $4)
+              AND ($5::TEXT IS NULL OR model = $5)
+              AND ($6::TEXT IS NULL OR environment = $6)
+        ),
+        changes AS (
+            SELECT provider, started_at AS at, 1 AS delta FROM calls
+            UNION ALL
+            SELECT provider, ended_at AS at, -1 AS delta FROM calls
+        ),
+        in_flight AS (
+            SELECT
+                provider,
+                at,
+                SUM(delta) OVER (
+                    PARTITION BY provider
+                    ORDER BY at, delta
+                    ROWS UNBOUNDED PRECEDING
+                ) AS concurrent
+            FROM changes
+        ),
+        peaks AS (
+            SELECT provider, date_trunc('{unit}', at) AS bucket, MAX(concurrent) AS peak_concurrency
+            FROM in_flight
+            GROUP BY 1, 2
+        )
+        SELECT
+            calls.provider,
+            date_trunc('{unit}', calls.started_at) AS bucket,
+            COUNT(*) AS request_count,
+            COALESCE(SUM(calls.duration_ms), 0)::BIGINT AS total_duration_ms,
+            COALESCE(MAX(peaks.peak_concurrency), 0)::BIGINT AS peak_concurrency,
+            COUNT(calls.queue_ms) AS timed_count,
+            AVG(calls.queue_ms)::FLOAT8 AS avg_queue_ms,
+            PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY calls.queue_ms)::FLOAT8 AS p95_queue_ms,
+            AVG(calls.execution_ms)::FLOAT8 AS avg_execution_ms,
+            COUNT(*) FILTER (WHERE calls.rate_limited) AS rate_limited_count
+        FROM calls
+        LEFT JOIN peaks
+            ON peaks.provider = calls.provider
+           AND peaks.bucket = date_trunc('{unit}', calls.started_at)
+        GROUP BY 1, 2
+        "#,
+    );
+    let rows = sqlx::query_as::<_, ConcurrencyRow>(&query)
+        .bind(&auth.org_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(&request.provider)
+        .bind(&request.model)
+        .bind(&request.environment)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    let providers = build_report(rows, request.interval, request.saturation_factor);
+
+    info!(
+        org_id = %auth.org_id,
+        providers = providers.len(),
+        saturated = providers.iter().filter(|p| p.saturated_buckets > 0).count(),
+        "Concurrency report computed"
+    );
+
+    Ok(Json(ConcurrencyResponse {
+        start_time,
+        end_time,
+        interval: request.interval,
+        saturation_factor: request.saturation_factor,
+        providers,
+    }))
+}
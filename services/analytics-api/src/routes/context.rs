@@ -0,0 +1,445 @@
+//! # Context-Window Utilization API Routes
+//!
+//! How much of each model's context window prompts use, to guide prompt
+//! optimization: prompts close to the limit risk truncation, while templates
+//! that use a small fraction of a large window pay for context they do not
+//! need.
+//!
+//! ## Endpoints
+//! - GET /api/v1/context/utilization - Prompt-token distribution and truncation risk per model
+//! - GET /api/v1/context/heatmap - Requests per utilization band over time
+//! - GET /api/v1/context/templates - Templates whose p95 prompt uses little of the context window
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `metrics:read`
+//! - Results are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::context::*;
+use crate::models::recommendations::WorkloadGrouping;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use llm_observatory_providers::pricing::PRICING_DB;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create context-window routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/api/v1/context/utilization", get(get_context_utilization))
+        .route("/api/v1/context/heatmap", get(get_context_heatmap))
+        .route("/api/v1/context/templates", get(get_wasteful_templates))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        error!(error = %err, "Context utilization query failed");
+        ApiError::Internal(format!("Database query failed: {}", err))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Shared Query Parts
+// ============================================================================
+
+/// Spans with prompt tokens in the window and the context window of their
+/// model: the enrichment attribute, else the bundled model metadata ($4, $5).
+///
+/// Binds: $1 org, $2 start, $3 end, $4 models, $5 context windows,
+/// $6 provider, $7 model, $8 environment.
+fn spans_cte() -> String {
+    format!(
+        r#"
+        spans AS (
+            SELECT
+                s.*,
+                COALESCE(s.attribute_context_window, known.context_window) AS context_window
+            FROM (
+                SELECT
+                    ts,
+                    provider,
+                    model,
+                    {workload} AS workload,
+                    finish_reason,
+                    prompt_tokens::FLOAT8 AS prompt_tokens,
+                    prompt_cost_usd::FLOAT8 AS prompt_cost_usd,
+                    (attributes->>'llm_observatory.model.context_window')::FLOAT8
+                        AS attribute_context_window
+                FROM llm_traces
+                WHERE attributes->>'org_id' = $1
+                  AND ts >= $2
+                  AND ts < $3
+                  AND prompt_tokens > 0
+                  AND ($6::TEXT IS NULL OR provider = $6)
+                  AND ($7::TEXT IS NULL OR model = $7)
+                  AND ($8::TEXT IS NULL OR environment = $8)
+            ) s
+            LEFT JOIN UNNEST($4::TEXT[], $5::FLOAT8[]) AS known(model, context_window)
+              ON known.model = s.model
+        )
+        "#,
+        workload = WorkloadGrouping::PromptTemplate.to_sql_expression()
+    )
+}
+
+/// Context windows of the models used in the window, from the bundled model
+/// metadata (dated model names resolve to their base model)
+async fn known_context_windows(
+    pool: &PgPool,
+    org_id: &str,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+) -> Result<(Vec<String>, Vec<f64>), sqlx::Error> {
+    let models: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT model
+        FROM llm_traces
+        WHERE attributes->>'org_id' = $1
+          AND ts >= $2
+          AND ts < $3
+        "#,
+    )
+    .bind(org_id)
+    .bind(start_time)
+    .bind(end_time)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(models
+        .into_iter()
+        .filter_map(|model| {
+            let info = PRICING_DB.get_model_info(&model).ok()?;
+            Some((model, info.context_window as f64))
+        })
+        .unzip())
+}
+
+fn check_permission(auth: &AuthContext) -> Result<(), ApiError> {
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read context utilization".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/context/utilization
+// ============================================================================
+
+/// GET /api/v1/context/utilization - Context-window utilization per model
+///
+/// Query Parameters:
+/// - start_time, end_time: Analysis window (default: the last 7 days, at most 90)
+/// - provider, model, environment: Filters (optional)
+/// - risk_threshold: Utilization flagged as at risk of truncation (default: 0.9)
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/context/utilization?model=gpt-4o' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_context_utilization(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<ContextUtilizationRequest>,
+) -> Result<Json<ContextUtilizationResponse>, ApiError> {
+    check_permission(&auth)?;
+    let (start_time, end_time) = request.validate().map_err(ApiError::BadRequest)?;
+    let (models, windows) =
+        known_context_windows(&state.db_pool, &auth.org_id, start_time, end_time).await?;
+
+    let stats_query = format!(
+        r#"
+        WITH {spans}
+        SELECT
+            provider,
+            model,
+            MAX(context_window) AS context_window,
+            COUNT(*) AS request_count,
+            AVG(prompt_tokens) AS avg_prompt_tokens,
+            PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY prompt_tokens) AS p50_prompt_tokens,
+            PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY prompt_tokens) AS p95_prompt_tokens,
+            PERCENTILE_CONT(0.99) WITHIN GROUP (ORDER BY prompt_tokens) AS p99_prompt_tokens,
+            MAX(prompt_tokens) AS max_prompt_tokens,
+            PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY prompt_tokens / context_window) AS p50_utilization,
+            PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY prompt_tokens / context_window) AS p95_utilization,
+            COUNT(*) FILTER (WHERE prompt_tokens / context_window >= $9) AS at_risk_count,
+            COUNT(*) FILTER (WHERE prompt_tokens > context_window) AS over_limit_count,
+            COUNT(*) FILTER (WHERE finish_reason = 'length') AS length_finish_count
+        FROM spans
+        WHERE context_window > 0
+        GROUP BY provider, model
+        ORDER BY request_count DESC
+        "#,
+        spans = spans_cte()
+    );
+    let rows = sqlx::query_as::<_, ModelUtilizationRow>(&stats_query)
+        .bind(&auth.org_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(&models)
+        .bind(&windows)
+        .bind(&request.provider)
+        .bind(&request.model)
+        .bind(&request.environment)
+        .bind(request.risk_threshold)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    let bands_query = format!(
+        r#"
+        WITH {spans}
+        SELECT
+            provider,
+            model,
+            CASE WHEN context_window > 0
+                THEN LEAST(FLOOR(prompt_tokens / context_window * {bands}), {bands} - 1)::INT
+            END AS band,
+            COUNT(*) AS request_count
+        FROM spans
+        GROUP BY 1, 2, 3
+        "#,
+        spans = spans_cte(),
+        bands = UTILIZATION_BANDS
+    );
+    let bands = sqlx::query_as::<_, BandRow>(&bands_query)
+        .bind(&auth.org_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(&models)
+        .bind(&windows)
+        .bind(&request.provider)
+        .bind(&request.model)
+        .bind(&request.environment)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    let (models, unknown_context_requests) = build_utilization(rows, &bands);
+
+    info!(
+        org_id = %auth.org_id,
+        models = models.len(),
+        unknown_context_requests,
+        "Context utilization computed"
+    );
+
+    Ok(Json(ContextUtilizationResponse {
+        start_time,
+        end_time,
+        risk_threshold: request.risk_threshold,
+        models,
+        unknown_context_requests,
+    }))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/context/heatmap
+// ============================================================================
+
+/// GET /api/v1/context/heatmap - Requests per utilization band over time
+///
+/// Returns one row per hour or day with the request count in each 10%
+/// utilization band and the prompt tokens sent.
+///
+/// Query Parameters:
+/// - start_time, end_time: Analysis window (default: the last 7 days, at most 90)
+/// - provider, model, environment: Filters (optional)
+/// - interval: hour (default) or day
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/context/heatmap?interval=day' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_context_heatmap(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<ContextUtilizationRequest>,
+) -> Result<Json<ContextHeatmapResponse>, ApiError> {
+    check_permission(&auth)?;
+    let (start_time, end_time) = request.validate().map_err(ApiError::BadRequest)?;
+    let (models, windows) =
+        known_context_windows(&state.db_pool, &auth.org_id, start_time, end_time).await?;
+
+    let query = format!(
+        r#"
+        WITH {spans}
+        SELECT
+            date_trunc($9, ts) AS bucket,
+            LEAST(FLOOR(prompt_tokens / context_window * {bands}), {bands} - 1)::INT AS band,
+            COUNT(*) AS request_count,
+            SUM(prompt_tokens)::BIGINT AS prompt_tokens
+        FROM spans
+        WHERE context_window > 0
+        GROUP BY 1, 2
+        "#,
+        spans = spans_cte(),
+        bands = UTILIZATION_BANDS
+    );
+    let cells = sqlx::query_as::<_, HeatmapCellRow>(&query)
+        .bind(&auth.org_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(&models)
+        .bind(&windows)
+        .bind(&request.provider)
+        .bind(&request.model)
+        .bind(&request.environment)
+        .bind(request.interval.as_str())
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    let rows = build_heatmap(&cells);
+
+    info!(org_id = %auth.org_id, buckets = rows.len(), "Context heatmap computed");
+
+    Ok(Json(ContextHeatmapResponse {
+        start_time,
+        end_time,
+        interval: request.interval,
+        bands: band_bounds(),
+        rows,
+    }))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/context/templates
+// ============================================================================
+
+/// GET /api/v1/context/templates - Templates wasting context
+///
+/// Lists templates (the `prompt.template` attribute, falling back to the
+/// span name) whose p95 prompt uses less than `waste_threshold` of the
+/// model's context window, most prompt spend first. Such templates are
+/// candidates for a smaller-context, cheaper model or a trimmed prompt.
+///
+/// Query Parameters:
+/// - start_time, end_time: Analysis window (default: the last 7 days, at most 90)
+/// - provider, model, environment: Filters (optional)
+/// - waste_threshold: p95 utilization below which a template is listed (default: 0.2)
+/// - min_requests: Fewest requests of a template on a model (default: 50)
+/// - limit: Maximum templates (default: 50, max: 500)
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/context/templates?waste_threshold=0.1' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_wasteful_templates(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<ContextUtilizationRequest>,
+) -> Result<Json<WastefulTemplatesResponse>, ApiError> {
+    check_permission(&auth)?;
+    let (start_time, end_time) = request.validate().map_err(ApiError::BadRequest)?;
+    let (models, windows) =
+        known_context_windows(&state.db_pool, &auth.org_id, start_time, end_time).await?;
+
+    let query = format!(
+        r#"
+        WITH {spans},
+        templates AS (
+            SELECT
+                workload,
+                provider,
+                model,
+                MAX(context_window) AS context_window,
+                COUNT(*) AS request_count,
+                AVG(prompt_tokens) AS avg_prompt_tokens,
+                PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY prompt_tokens) AS p95_prompt_tokens,
+                PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY prompt_tokens / context_window) AS p95_utilization,
+                SUM(prompt_cost_usd) AS prompt_cost_usd
+            FROM spans
+            WHERE context_window > 0
+            GROUP BY workload, provider, model
+        )
+        SELECT
+            workload,
+            provider,
+            model,
+            context_window,
+            request_count,
+            avg_prompt_tokens,
+            p95_prompt_tokens,
+            p95_utilization,
+            context_window - p95_prompt_tokens AS unused_context_tokens,
+            prompt_cost_usd
+        FROM templates
+        WHERE request_count >= $9
+          AND p95_utilization < $10
+        ORDER BY prompt_cost_usd DESC NULLS LAST, request_count DESC
+        LIMIT $11
+        "#,
+        spans = spans_cte()
+    );
+    let templates = sqlx::query_as::<_, WastefulTemplate>(&query)
+        .bind(&auth.org_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(&models)
+        .bind(&windows)
+        .bind(&request.provider)
+        .bind(&request.model)
+        .bind(&request.environment)
+        .bind(request.min_requests)
+        .bind(request.waste_threshold)
+        .bind(request.limit)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    info!(org_id = %auth.org_id, templates = templates.len(), "Wasteful templates computed");
+
+    Ok(Json(WastefulTemplatesResponse {
+        start_time,
+        end_time,
+        waste_threshold: request.waste_threshold,
+        min_requests: request.min_requests,
+        templates,
+    }))
+}
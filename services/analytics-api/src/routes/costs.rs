@@ -5,15 +5,25 @@
 //! ## Endpoints
 //! - `GET /api/v1/costs/summary` - Comprehensive cost summary with trends and breakdowns
 //! - `GET /api/v1/costs/attribution` - Cost attribution by user, team, tag
-//! - `GET /api/v1/costs/forecast` - Cost forecasting with linear regression
+//! - `GET /api/v1/costs/forecast` - Seasonality-aware cost forecasting with model selection
+//! - `GET /api/v1/costs/explain` - Decompose the cost change between two periods
+//! - `GET /api/v1/costs/chargeback` - Monthly invoices per cost-allocation tag (JSON or CSV)
 //!
 //! ## Features
 //! - Detailed cost breakdowns by provider, model, environment
 //! - Trend analysis (daily, weekly growth rates)
 //! - Top expensive traces identification
-//! - Linear regression forecasting
+//! - Linear regression, Holt-Winters and seasonal decomposition forecasting,
+//!   selected by AIC or backtest MAPE (see `services::forecasting`)
 //! - Cost attribution across multiple dimensions
-//! - Redis caching for all endpoints
+//! - Chargeback by cost-allocation tag (`llm_traces.cost_tags`)
+//! - Provider, model, environment and user rankings read the daily top-N
+//!   rollups where possible (see `services::top_n`)
+//! - Currency conversion (`?currency=EUR`) with the rate used recorded in metadata
+//! - Summaries of ranges older than the hot retention window read archived
+//!   traces from Parquet (see `services::cold_storage`)
+//! - Redis caching for all endpoints (amounts cached in USD), served
+//!   stale-while-revalidate with a `Cache-Status` header
 //!
 //! ## Security
 //! - JWT authentication required
@@ -22,20 +32,25 @@
 
 use crate::middleware::AuthContext;
 use crate::models::costs::*;
+use crate::models::federation::Scope;
 use crate::models::{AppState, ErrorResponse};
+use crate::services::cold_storage::ColdStorageService;
+use crate::services::currency::CurrencyError;
+use crate::services::query_cache::{CachedJson, FreshnessPolicy, CACHE_STATUS};
+use crate::services::forecasting::{self, daily_series, Forecast};
+use crate::services::top_n::{self, TopNFilters, TopNGrouping, TopNOrder, TopNQuery};
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
+    extract::{Query, RawQuery, State},
+    http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     routing::get,
     Json, Router,
 };
 use chrono::{DateTime, Duration, Utc};
-use redis::AsyncCommands;
 use sqlx::PgPool;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{error, info, instrument, warn};
+use tracing::{error, info, instrument};
 
 // ============================================================================
 // Router Configuration
@@ -47,8 +62,20 @@ pub fn routes() -> Router<Arc<AppState>> {
         .route("/api/v1/costs/summary", get(get_cost_summary))
         .route("/api/v1/costs/attribution", get(get_cost_attribution))
         .route("/api/v1/costs/forecast", get(get_cost_forecast))
+        .route("/api/v1/costs/explain", get(get_cost_explain))
+        .route("/api/v1/costs/chargeback", get(get_cost_chargeback))
 }
 
+/// Summaries, attributions and explanations are fresh for five minutes and
+/// served stale for an hour
+const REPORT_FRESHNESS: FreshnessPolicy = FreshnessPolicy::new(300, 3600);
+
+/// Forecasts are fitted on whole days of history
+const FORECAST_FRESHNESS: FreshnessPolicy = FreshnessPolicy::new(1800, 21600);
+
+/// Chargeback invoices cover whole months
+const CHARGEBACK_FRESHNESS: FreshnessPolicy = FreshnessPolicy::new(3600, 86400);
+
 // ============================================================================
 // API Error Type
 // ============================================================================
@@ -62,6 +89,19 @@ pub enum ApiError {
     Internal(String),
 }
 
+impl ApiError {
+    /// Message of the error, reported for a failed region
+    fn into_message(self) -> String {
+        match self {
+            ApiError::BadRequest(msg)
+            | ApiError::Unauthorized(msg)
+            | ApiError::Forbidden(msg)
+            | ApiError::NotFound(msg)
+            | ApiError::Internal(msg) => msg,
+        }
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status, error_type, message) = match self {
@@ -84,6 +124,12 @@ impl IntoResponse for ApiError {
     }
 }
 
+impl From<CurrencyError> for ApiError {
+    fn from(err: CurrencyError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
 // ============================================================================
 // Endpoint 1: GET /api/v1/costs/summary
 // ============================================================================
@@ -106,18 +152,23 @@ impl IntoResponse for ApiError {
 /// - `include_trends`: Include trend analysis - default: true
 /// - `include_top_traces`: Include top expensive traces - default: true
 /// - `top_limit`: Number of top traces to return (max 100) - default: 10
+/// - `currency`: Reporting currency (ISO 4217) - default: configured display currency
+/// - `scope`: `region` for this API's region, `global` to merge every
+///   configured region - default: region
 ///
 /// ## Example
 /// ```bash
 /// curl -X GET 'http://localhost:8080/api/v1/costs/summary?start_time=2025-10-01T00:00:00Z&end_time=2025-11-01T00:00:00Z&include_trends=true' \
 ///   -H "Authorization: Bearer $JWT_TOKEN"
 /// ```
-#[instrument(skip(state, auth))]
+#[instrument(skip(state, auth, headers, raw_query))]
 async fn get_cost_summary(
     State(state): State<Arc<AppState>>,
     auth: AuthContext,
+    headers: HeaderMap,
+    RawQuery(raw_query): RawQuery,
     Query(request): Query<CostSummaryRequest>,
-) -> Result<Json<CostSummaryResponse>, ApiError> {
+) -> Result<CachedJson<CostSummaryResponse>, ApiError> {
     // Check permissions
     if !auth.has_permission("costs:read") {
         return Err(ApiError::Forbidden(
@@ -127,6 +178,7 @@ async fn get_cost_summary(
 
     // Validate request
     request.validate().map_err(ApiError::BadRequest)?;
+    let quote = state.currency.resolve(request.currency.as_deref())?;
 
     info!(
         org_id = %auth.organization_id,
@@ -141,25 +193,83 @@ async fn get_cost_summary(
         .start_time
         .unwrap_or_else(|| end_time - Duration::days(30));
 
-    // Generate cache key
+    // Serve from cache (in USD), refreshing stale results in the background
     let cache_key = generate_summary_cache_key(&request, &auth.organization_id, start_time, end_time);
+    let pool = state.db_pool.clone();
+    let cold_storage = state.cold_storage.clone();
+    let org_id = auth.organization_id.clone();
+    let regional_request = request.clone();
+    let (mut response, cache_status) = state
+        .query_cache
+        .get_or_compute(&cache_key, REPORT_FRESHNESS, move || async move {
+            execute_tiered_cost_summary(&pool, &cold_storage, &request, &org_id, start_time, end_time)
+                .await
+        })
+        .await?;
+
+    // Merge the other regions' summaries (in USD) for the global scope
+    if regional_request.scope == Scope::Global {
+        let remote = state
+            .federation
+            .query_regions(
+                "/api/v1/costs/summary",
+                raw_query.as_deref(),
+                headers.get(header::AUTHORIZATION),
+                |pool| {
+                    let request = &regional_request;
+                    let org_id = &auth.organization_id;
+                    async move {
+                        execute_cost_summary(&pool, request, org_id, start_time, end_time)
+                            .await
+                            .map_err(ApiError::into_message)
+                    }
+                },
+            )
+            .await;
 
-    // Try cache
-    if let Ok(cached) = try_get_from_cache(&state, &cache_key).await {
-        info!("Returning cached cost summary");
-        return Ok(Json(cached));
+        let (mut summaries, federation) = state.federation.collect(response, remote);
+        let local = summaries.remove(0);
+        response = local.merge(summaries, regional_request.top_limit as usize);
+        response.federation = Some(federation);
     }
 
-    // Execute query
-    let response =
-        execute_cost_summary(&state.db_pool, &request, &auth.organization_id, start_time, end_time).await?;
+    info!(total_cost = response.overview.total_cost, cache_status = ?cache_status, "Cost summary completed");
+
+    response.convert_currency(&quote);
+    Ok(CachedJson(response, cache_status))
+}
 
-    // Cache result
-    cache_cost_summary(&state, &cache_key, &response).await;
+/// Execute cost summary query, reading the part of the range older than
+/// the hot retention window from cold storage
+async fn execute_tiered_cost_summary(
+    pool: &PgPool,
+    cold_storage: &ColdStorageService,
+    request: &CostSummaryRequest,
+    org_id: &str,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+) -> Result<CostSummaryResponse, ApiError> {
+    let split = cold_storage.split(start_time, end_time);
+    let Some((cold_start, cold_end)) = split.cold else {
+        return execute_cost_summary(pool, request, org_id, start_time, end_time).await;
+    };
 
-    info!(total_cost = response.overview.total_cost, "Cost summary completed");
+    let cold = cold_storage
+        .cost_summary(org_id, request, cold_start, cold_end)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to query archived cost summary");
+            ApiError::Internal(e.to_string())
+        })?;
+    let Some((hot_start, hot_end)) = split.hot else {
+        return Ok(cold);
+    };
 
-    Ok(Json(response))
+    let hot = execute_cost_summary(pool, request, org_id, hot_start, hot_end).await?;
+    let mut summary = hot.merge(vec![cold], request.top_limit as usize);
+    summary.metadata.start_time = start_time;
+    summary.metadata.period_days = (end_time - start_time).num_days();
+    Ok(summary)
 }
 
 /// Execute cost summary query
@@ -174,10 +284,33 @@ async fn execute_cost_summary(
     let overview = query_cost_overview(pool, org_id, start_time, end_time, request).await?;
 
     // Query breakdowns
-    let by_provider = query_cost_breakdown(pool, org_id, start_time, end_time, "provider", request).await?;
-    let by_model = query_cost_breakdown(pool, org_id, start_time, end_time, "model", request).await?;
-    let by_environment =
-        query_cost_breakdown(pool, org_id, start_time, end_time, "environment", request).await?;
+    let by_provider = query_cost_breakdown(
+        pool,
+        org_id,
+        start_time,
+        end_time,
+        TopNGrouping::Provider,
+        request,
+    )
+    .await?;
+    let by_model = query_cost_breakdown(
+        pool,
+        org_id,
+        start_time,
+        end_time,
+        TopNGrouping::Model,
+        request,
+    )
+    .await?;
+    let by_environment = query_cost_breakdown(
+        pool,
+        org_id,
+        start_time,
+        end_time,
+        TopNGrouping::Environment,
+        request,
+    )
+    .await?;
 
     // Query trends if requested
     let trends = if request.include_trends {
@@ -201,6 +334,7 @@ async fn execute_cost_summary(
         end_time,
         period_days,
         generated_at: Utc::now(),
+        currency: CurrencyConversion::usd(overview.total_cost),
     };
 
     Ok(CostSummaryResponse {
@@ -211,6 +345,7 @@ async fn execute_cost_summary(
         by_environment,
         trends,
         top_traces,
+        federation: None,
     })
 }
 
@@ -324,71 +459,36 @@ async fn query_cost_breakdown(
     org_id: &str,
     start_time: DateTime<Utc>,
     end_time: DateTime<Utc>,
-    dimension: &str,
+    dimension: TopNGrouping,
     request: &CostSummaryRequest,
 ) -> Result<Vec<CostBreakdownItem>, ApiError> {
-    let mut where_clauses = vec![
-        "org_id = $1".to_string(),
-        "ts >= $2".to_string(),
-        "ts < $3".to_string(),
-    ];
-    let mut param_index = 4;
-
-    if request.provider.is_some() {
-        where_clauses.push(format!("provider = ${}", param_index));
-        param_index += 1;
-    }
-    if request.model.is_some() {
-        where_clauses.push(format!("model = ${}", param_index));
-        param_index += 1;
-    }
-    if request.environment.is_some() {
-        where_clauses.push(format!("environment = ${}", param_index));
-        param_index += 1;
-    }
-    if request.user_id.is_some() {
-        where_clauses.push(format!("user_id = ${}", param_index));
-    }
-
-    let query_str = format!(
-        r#"
-        SELECT
-            {} AS name,
-            SUM(total_cost_usd) AS cost,
-            COUNT(*) AS requests
-        FROM llm_traces
-        WHERE {}
-        GROUP BY {}
-        ORDER BY cost DESC
-        LIMIT 50
-        "#,
-        dimension,
-        where_clauses.join(" AND "),
-        dimension
-    );
-
-    let mut query = sqlx::query_as::<_, CostBreakdownRow>(&query_str)
-        .bind(org_id)
-        .bind(start_time)
-        .bind(end_time);
-
-    if let Some(ref provider) = request.provider {
-        query = query.bind(provider);
-    }
-    if let Some(ref model) = request.model {
-        query = query.bind(model);
-    }
-    if let Some(ref environment) = request.environment {
-        query = query.bind(environment);
-    }
-    if let Some(ref user_id) = request.user_id {
-        query = query.bind(user_id);
-    }
+    let query = TopNQuery {
+        start: start_time,
+        end: end_time,
+        grouping: dimension,
+        filters: TopNFilters {
+            provider: request.provider.clone(),
+            model: request.model.clone(),
+            environment: request.environment.clone(),
+            user_id: request.user_id.clone(),
+        },
+        order: TopNOrder::Cost,
+        limit: 50,
+    };
 
-    let rows = query.fetch_all(pool).await.map_err(|e| {
-        error!(error = %e, dimension = dimension, "Failed to query cost breakdown");
-        ApiError::Internal(format!("Database query failed: {}", e))
-    })?;
+    let rows: Vec<CostBreakdownRow> = top_n::query_top_n(pool, org_id, &query)
+        .await
+        .map_err(|e| {
+            error!(error = %e, dimension = ?dimension, "Failed to query cost breakdown");
+            ApiError::Internal(format!("Database query failed: {}", e))
+        })?
+        .into_iter()
+        .map(|row| CostBreakdownRow {
+            name: row.name,
+            cost: Some(row.total_cost),
+            requests: Some(row.request_count),
+        })
+        .collect();
 
     let total_cost: f64 = rows.iter().map(|r| r.cost.unwrap_or(0.0)).sum();
 
@@ -529,22 +629,6 @@ async fn query_cost_trend_data(
     Ok(data_points)
 }
 
-/// Calculate growth rate from trend data
-fn calculate_growth_rate(data: &[CostDataPoint]) -> f64 {
-    if data.len() < 2 {
-        return 0.0;
-    }
-
-    let costs: Vec<(f64, f64)> = data
-        .iter()
-        .enumerate()
-        .map(|(i, point)| (i as f64, point.cost))
-        .collect();
-
-    let (slope, _, _) = calculate_linear_regression(&costs);
-    slope
-}
-
 /// Query top expensive traces
 async fn query_top_expensive_traces(
     pool: &PgPool,
@@ -654,7 +738,8 @@ async fn query_top_expensive_traces(
 /// - `model`: Filter by model
 /// - `environment`: Filter by environment
 /// - `limit`: Max items to return (max 1000) - default: 100
-/// - `min_cost`: Minimum cost threshold
+/// - `min_cost`: Minimum cost threshold, in the reporting currency
+/// - `currency`: Reporting currency (ISO 4217) - default: configured display currency
 ///
 /// ## Example
 /// ```bash
@@ -665,8 +750,8 @@ async fn query_top_expensive_traces(
 async fn get_cost_attribution(
     State(state): State<Arc<AppState>>,
     auth: AuthContext,
-    Query(request): Query<CostAttributionRequest>,
-) -> Result<Json<CostAttributionResponse>, ApiError> {
+    Query(mut request): Query<CostAttributionRequest>,
+) -> Result<CachedJson<CostAttributionResponse>, ApiError> {
     // Check permissions
     if !auth.has_permission("costs:read") {
         return Err(ApiError::Forbidden(
@@ -676,6 +761,10 @@ async fn get_cost_attribution(
 
     // Validate request
     request.validate().map_err(ApiError::BadRequest)?;
+    let quote = state.currency.resolve(request.currency.as_deref())?;
+
+    // Costs are filtered in USD
+    request.min_cost = request.min_cost.map(|min_cost| min_cost / quote.rate);
 
     info!(
         org_id = %auth.organization_id,
@@ -683,25 +772,21 @@ async fn get_cost_attribution(
         "Querying cost attribution"
     );
 
-    // Generate cache key
+    // Serve from cache (in USD), refreshing stale results in the background
     let cache_key = generate_attribution_cache_key(&request, &auth.organization_id);
+    let pool = state.db_pool.clone();
+    let org_id = auth.organization_id.clone();
+    let (mut response, cache_status) = state
+        .query_cache
+        .get_or_compute(&cache_key, REPORT_FRESHNESS, move || async move {
+            execute_cost_attribution(&pool, &request, &org_id).await
+        })
+        .await?;
 
-    // Try cache
-    if let Ok(cached) = try_get_from_cache(&state, &cache_key).await {
-        info!("Returning cached cost attribution");
-        return Ok(Json(cached));
-    }
-
-    // Execute query
-    let response =
-        execute_cost_attribution(&state.db_pool, &request, &auth.organization_id).await?;
-
-    // Cache result
-    cache_cost_attribution(&state, &cache_key, &response).await;
-
-    info!(items = response.items.len(), "Cost attribution completed");
+    info!(items = response.items.len(), cache_status = ?cache_status, "Cost attribution completed");
 
-    Ok(Json(response))
+    response.convert_currency(&quote);
+    Ok(CachedJson(response, cache_status))
 }
 
 /// Execute cost attribution query
@@ -710,6 +795,84 @@ async fn execute_cost_attribution(
     request: &CostAttributionRequest,
     org_id: &str,
 ) -> Result<CostAttributionResponse, ApiError> {
+    let rows = match request.dimension.top_n_grouping() {
+        Some(grouping) => query_attribution_top_n(pool, request, org_id, grouping).await?,
+        None => query_attribution_rows(pool, request, org_id).await?,
+    };
+
+    let total_cost: f64 = rows.iter().map(|r| r.total_cost.unwrap_or(0.0)).sum();
+    let total_requests: i64 = rows.iter().map(|r| r.request_count.unwrap_or(0)).sum();
+
+    let items: Vec<AttributionItem> = rows
+        .into_iter()
+        .filter(|row| {
+            if let Some(min_cost) = request.min_cost {
+                row.total_cost.unwrap_or(0.0) >= min_cost
+            } else {
+                true
+            }
+        })
+        .map(|row| {
+            let cost = row.total_cost.unwrap_or(0.0);
+            let requests = row.request_count.unwrap_or(0);
+            let cost_percentage = if total_cost > 0.0 {
+                (cost / total_cost) * 100.0
+            } else {
+                0.0
+            };
+            let avg_cost_per_request = if requests > 0 {
+                cost / requests as f64
+            } else {
+                0.0
+            };
+
+            AttributionItem {
+                dimension_value: row.dimension_value,
+                total_cost: cost,
+                prompt_cost: row.prompt_cost.unwrap_or(0.0),
+                completion_cost: row.completion_cost.unwrap_or(0.0),
+                request_count: requests,
+                total_tokens: row.total_tokens.unwrap_or(0),
+                cost_percentage,
+                avg_cost_per_request,
+                by_provider: HashMap::new(), // Would require additional query
+                by_model: HashMap::new(),    // Would require additional query
+            }
+        })
+        .collect();
+
+    let metadata = AttributionMetadata {
+        dimension: format!("{:?}", request.dimension),
+        start_time: request.start_time,
+        end_time: request.end_time,
+        total_items: items.len(),
+        currency: CurrencyConversion::usd(total_cost),
+    };
+
+    let summary = AttributionSummary {
+        total_cost,
+        total_requests,
+        unique_items: items.len() as i64,
+        avg_cost_per_item: if !items.is_empty() {
+            total_cost / items.len() as f64
+        } else {
+            0.0
+        },
+    };
+
+    Ok(CostAttributionResponse {
+        metadata,
+        items,
+        summary,
+    })
+}
+
+/// Query attribution rows from llm_traces
+async fn query_attribution_rows(
+    pool: &PgPool,
+    request: &CostAttributionRequest,
+    org_id: &str,
+) -> Result<Vec<AttributionRow>, ApiError> {
     let dimension_col = request.dimension.to_column_name();
 
     // Build query
@@ -772,84 +935,64 @@ async fn execute_cost_attribution(
 
     query = query.bind(request.limit);
 
-    let rows = query.fetch_all(pool).await.map_err(|e| {
+    query.fetch_all(pool).await.map_err(|e| {
         error!(error = %e, "Failed to query cost attribution");
         ApiError::Internal(format!("Database query failed: {}", e))
-    })?;
-
-    let total_cost: f64 = rows.iter().map(|r| r.total_cost.unwrap_or(0.0)).sum();
-    let total_requests: i64 = rows.iter().map(|r| r.request_count.unwrap_or(0)).sum();
-
-    let items: Vec<AttributionItem> = rows
-        .into_iter()
-        .filter(|row| {
-            if let Some(min_cost) = request.min_cost {
-                row.total_cost.unwrap_or(0.0) >= min_cost
-            } else {
-                true
-            }
-        })
-        .map(|row| {
-            let cost = row.total_cost.unwrap_or(0.0);
-            let requests = row.request_count.unwrap_or(0);
-            let cost_percentage = if total_cost > 0.0 {
-                (cost / total_cost) * 100.0
-            } else {
-                0.0
-            };
-            let avg_cost_per_request = if requests > 0 {
-                cost / requests as f64
-            } else {
-                0.0
-            };
-
-            AttributionItem {
-                dimension_value: row.dimension_value,
-                total_cost: cost,
-                prompt_cost: row.prompt_cost.unwrap_or(0.0),
-                completion_cost: row.completion_cost.unwrap_or(0.0),
-                request_count: requests,
-                total_tokens: row.total_tokens.unwrap_or(0),
-                cost_percentage,
-                avg_cost_per_request,
-                by_provider: HashMap::new(), // Would require additional query
-                by_model: HashMap::new(),    // Would require additional query
-            }
-        })
-        .collect();
-
-    let metadata = AttributionMetadata {
-        dimension: format!("{:?}", request.dimension),
-        start_time: request.start_time,
-        end_time: request.end_time,
-        total_items: items.len(),
-    };
+    })
+}
 
-    let summary = AttributionSummary {
-        total_cost,
-        total_requests,
-        unique_items: items.len() as i64,
-        avg_cost_per_item: if !items.is_empty() {
-            total_cost / items.len() as f64
-        } else {
-            0.0
+/// Query attribution rows from the daily top-N rollups
+async fn query_attribution_top_n(
+    pool: &PgPool,
+    request: &CostAttributionRequest,
+    org_id: &str,
+    grouping: TopNGrouping,
+) -> Result<Vec<AttributionRow>, ApiError> {
+    let query = TopNQuery {
+        start: request.start_time,
+        end: request.end_time,
+        grouping,
+        filters: TopNFilters {
+            provider: request.provider.clone(),
+            model: request.model.clone(),
+            environment: request.environment.clone(),
+            user_id: None,
         },
+        order: TopNOrder::Cost,
+        limit: request.limit as i64,
     };
 
-    Ok(CostAttributionResponse {
-        metadata,
-        items,
-        summary,
-    })
+    let rows = top_n::query_top_n(pool, org_id, &query)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to query cost attribution");
+            ApiError::Internal(format!("Database query failed: {}", e))
+        })?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| AttributionRow {
+            dimension_value: row.name,
+            total_cost: Some(row.total_cost),
+            prompt_cost: Some(row.prompt_cost),
+            completion_cost: Some(row.completion_cost),
+            request_count: Some(row.request_count),
+            total_tokens: Some(row.total_tokens),
+        })
+        .collect())
 }
 
 // ============================================================================
 // Endpoint 3: GET /api/v1/costs/forecast
 // ============================================================================
 
-/// GET /api/v1/costs/forecast - Cost forecasting with linear regression
+/// GET /api/v1/costs/forecast - Seasonality-aware cost forecasting
 ///
-/// Forecasts future costs based on historical data using linear regression.
+/// Forecasts daily costs from the complete days of the historical range. The
+/// model is either requested or selected automatically among linear
+/// regression, Holt-Winters and seasonal decomposition (weekly season, which
+/// needs at least 14 days of history); the response reports every
+/// candidate's AIC and backtest MAPE over the last 7 days.
 ///
 /// ## Query Parameters
 /// - `historical_start`: Historical data start (ISO 8601) - default: 30 days ago
@@ -859,10 +1002,15 @@ async fn execute_cost_attribution(
 /// - `model`: Filter by model
 /// - `environment`: Filter by environment
 /// - `include_confidence_intervals`: Include confidence intervals - default: true
+/// - `forecast_model`: linear_regression, holt_winters or seasonal_decomposition - default: automatic
+/// - `selection_criterion`: aic or backtest_mape - default: backtest_mape
+/// - `group_by`: Also forecast the top values of provider or model
+/// - `dimension_limit`: Number of dimension values forecast (max 20) - default: 5
+/// - `currency`: Reporting currency (ISO 4217) - default: configured display currency
 ///
 /// ## Example
 /// ```bash
-/// curl -X GET 'http://localhost:8080/api/v1/costs/forecast?forecast_period=next_month&include_confidence_intervals=true' \
+/// curl -X GET 'http://localhost:8080/api/v1/costs/forecast?forecast_period=next_month&group_by=model' \
 ///   -H "Authorization: Bearer $JWT_TOKEN"
 /// ```
 #[instrument(skip(state, auth))]
@@ -870,7 +1018,7 @@ async fn get_cost_forecast(
     State(state): State<Arc<AppState>>,
     auth: AuthContext,
     Query(request): Query<CostForecastRequest>,
-) -> Result<Json<CostForecastResponse>, ApiError> {
+) -> Result<CachedJson<CostForecastResponse>, ApiError> {
     // Check permissions
     if !auth.has_permission("costs:read") {
         return Err(ApiError::Forbidden(
@@ -880,6 +1028,7 @@ async fn get_cost_forecast(
 
     // Validate request
     request.validate().map_err(ApiError::BadRequest)?;
+    let quote = state.currency.resolve(request.currency.as_deref())?;
 
     info!(
         org_id = %auth.organization_id,
@@ -893,34 +1042,21 @@ async fn get_cost_forecast(
         .historical_start
         .unwrap_or_else(|| historical_end - Duration::days(30));
 
-    // Generate cache key
+    // Serve from cache (in USD), refreshing stale results in the background
     let cache_key = generate_forecast_cache_key(&request, &auth.organization_id, historical_start, historical_end);
+    let pool = state.db_pool.clone();
+    let org_id = auth.organization_id.clone();
+    let (mut response, cache_status) = state
+        .query_cache
+        .get_or_compute(&cache_key, FORECAST_FRESHNESS, move || async move {
+            execute_cost_forecast(&pool, &request, &org_id, historical_start, historical_end).await
+        })
+        .await?;
 
-    // Try cache
-    if let Ok(cached) = try_get_from_cache(&state, &cache_key).await {
-        info!("Returning cached cost forecast");
-        return Ok(Json(cached));
-    }
-
-    // Execute forecast
-    let response =
-        execute_cost_forecast(&state.db_pool, &request, &auth.organization_id, historical_start, historical_end)
-            .await?;
+    info!(cache_status = ?cache_status, "Cost forecast completed");
 
-    // Cache result (shorter TTL for forecasts)
-    let mut redis_conn = state.redis_client.get_async_connection().await.map_err(|e| {
-        warn!(error = %e, "Redis connection error");
-        ApiError::Internal("Cache error".to_string())
-    })?;
-    if let Ok(serialized) = serde_json::to_string(&response) {
-        let _: Result<(), _> = redis_conn
-            .set_ex(&cache_key, serialized, 1800) // 30 min cache
-            .await;
-    }
-
-    info!("Cost forecast completed");
-
-    Ok(Json(response))
+    response.convert_currency(&quote);
+    Ok(CachedJson(response, cache_status))
 }
 
 /// Execute cost forecast
@@ -931,56 +1067,42 @@ async fn execute_cost_forecast(
     historical_start: DateTime<Utc>,
     historical_end: DateTime<Utc>,
 ) -> Result<CostForecastResponse, ApiError> {
-    // Query historical data
-    let historical = query_forecast_historical_data(pool, org_id, historical_start, historical_end, request).await?;
-
-    if historical.len() < 2 {
+    // Query historical data as a series of complete days
+    let rows =
+        query_forecast_historical_data(pool, org_id, historical_start, historical_end, request, None).await?;
+    let points: Vec<(DateTime<Utc>, f64)> =
+        rows.iter().map(|row| (row.date, row.cost.unwrap_or(0.0))).collect();
+    let series = daily_series(historical_start, historical_end, &points);
+
+    if series.len() < 2 {
         return Err(ApiError::BadRequest(
-            "Insufficient historical data for forecasting (need at least 2 data points)".to_string(),
+            "Insufficient historical data for forecasting (need at least 2 complete days)".to_string(),
         ));
     }
 
-    // Prepare data for linear regression
-    let data_points: Vec<(f64, f64)> = historical
-        .iter()
-        .enumerate()
-        .map(|(i, point)| (i as f64, point.cost))
-        .collect();
-
-    // Calculate linear regression
-    let (slope, intercept, r_squared) = calculate_linear_regression(&data_points);
-
-    // Generate forecast
+    let costs: Vec<f64> = series.iter().map(|(_, cost)| *cost).collect();
     let forecast_days = request.forecast_period.to_days();
-    let mut forecast_points = Vec::new();
-
-    for i in 0..forecast_days {
-        let x = (historical.len() + i as usize) as f64;
-        let forecasted_cost = intercept + slope * x;
-
-        // Calculate confidence interval (simplified - using standard error)
-        let (lower_bound, upper_bound) = if request.include_confidence_intervals {
-            let std_error = forecasted_cost * 0.1; // Simplified 10% margin
-            (
-                Some((forecasted_cost - 1.96 * std_error).max(0.0)),
-                Some(forecasted_cost + 1.96 * std_error),
-            )
-        } else {
-            (None, None)
-        };
-
-        forecast_points.push(ForecastDataPoint {
-            date: historical_end + Duration::days(i as i64 + 1),
-            forecasted_cost: forecasted_cost.max(0.0),
-            lower_bound,
-            upper_bound,
-        });
-    }
+    let forecast_start = series[series.len() - 1].0 + Duration::days(1);
+
+    // Fit the requested model, or select one
+    let forecast = forecasting::forecast(
+        &costs,
+        forecast_days as usize,
+        request.forecast_model,
+        request.selection_criterion,
+    )
+    .ok_or_else(|| {
+        let model = request.forecast_model.unwrap_or(forecasting::ForecastModel::LinearRegression);
+        ApiError::BadRequest(format!(
+            "The {} model needs at least {} complete days of history",
+            model.as_str(),
+            model.min_points()
+        ))
+    })?;
 
-    // Calculate MAPE
-    let actual: Vec<f64> = historical.iter().map(|p| p.cost).collect();
-    let predicted: Vec<f64> = data_points.iter().map(|(x, _)| intercept + slope * x).collect();
-    let mape = calculate_mape(&actual, &predicted);
+    let forecast_points = forecast_data_points(&forecast, forecast_start, request.include_confidence_intervals);
+    let mape = calculate_mape(&costs, &forecast.fitted);
+    let backtest_mape = selected_backtest_mape(&forecast);
 
     let total_forecasted_cost: f64 = forecast_points.iter().map(|p| p.forecasted_cost).sum();
     let avg_daily_cost = if !forecast_points.is_empty() {
@@ -990,40 +1112,159 @@ async fn execute_cost_forecast(
     };
     let projected_monthly_cost = avg_daily_cost * 30.0;
 
+    // Forecast the top values of the requested dimension separately
+    let by_dimension = match request.group_by {
+        Some(dimension) => {
+            let rows = query_forecast_historical_data(
+                pool,
+                org_id,
+                historical_start,
+                historical_end,
+                request,
+                Some(dimension),
+            )
+            .await?;
+            forecast_dimensions(&rows, request, historical_start, historical_end, forecast_start)
+        }
+        None => Vec::new(),
+    };
+
     let metadata = ForecastMetadata {
         historical_start,
         historical_end,
-        forecast_start: historical_end + Duration::days(1),
-        forecast_end: historical_end + Duration::days(forecast_days as i64),
+        forecast_start,
+        forecast_end: forecast_start + Duration::days(forecast_days as i64 - 1),
         forecast_days,
-        model_type: "linear_regression".to_string(),
+        model_type: forecast.model.as_str().to_string(),
         generated_at: Utc::now(),
+        currency: CurrencyConversion::usd(total_forecasted_cost),
     };
 
     let summary = ForecastSummary {
         total_forecasted_cost,
         avg_daily_cost,
         projected_monthly_cost,
-        r_squared,
+        r_squared: forecast.r_squared(&costs),
         mape,
+        backtest_mape,
     };
 
+    let historical = series
+        .into_iter()
+        .map(|(date, cost)| CostDataPoint {
+            date,
+            cost,
+            requests: 0, // Not needed for forecast
+        })
+        .collect();
+
     Ok(CostForecastResponse {
         metadata,
         historical,
         forecast: forecast_points,
         summary,
+        model_selection: forecast.selection,
+        by_dimension,
     })
 }
 
-/// Query historical data for forecasting
+/// Forecast each of the `dimension_limit` values with the highest
+/// historical cost
+fn forecast_dimensions(
+    rows: &[ForecastHistoricalRow],
+    request: &CostForecastRequest,
+    historical_start: DateTime<Utc>,
+    historical_end: DateTime<Utc>,
+    forecast_start: DateTime<Utc>,
+) -> Vec<DimensionForecast> {
+    let mut by_value: HashMap<&str, Vec<(DateTime<Utc>, f64)>> = HashMap::new();
+    for row in rows {
+        if let Some(value) = row.dimension_value.as_deref() {
+            by_value
+                .entry(value)
+                .or_default()
+                .push((row.date, row.cost.unwrap_or(0.0)));
+        }
+    }
+
+    let mut values: Vec<(&str, f64)> = by_value
+        .iter()
+        .map(|(value, points)| (*value, points.iter().map(|(_, cost)| cost).sum()))
+        .collect();
+    values.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+    values.truncate(request.dimension_limit);
+
+    values
+        .into_iter()
+        .filter_map(|(value, _)| {
+            let series = daily_series(historical_start, historical_end, &by_value[value]);
+            let costs: Vec<f64> = series.iter().map(|(_, cost)| *cost).collect();
+            let forecast = forecasting::forecast(
+                &costs,
+                request.forecast_period.to_days() as usize,
+                request.forecast_model,
+                request.selection_criterion,
+            )?;
+            let points = forecast_data_points(&forecast, forecast_start, request.include_confidence_intervals);
+
+            Some(DimensionForecast {
+                dimension_value: value.to_string(),
+                model_type: forecast.model,
+                backtest_mape: selected_backtest_mape(&forecast),
+                total_forecasted_cost: points.iter().map(|p| p.forecasted_cost).sum(),
+                forecast: points,
+            })
+        })
+        .collect()
+}
+
+/// Daily forecast points starting at `start`
+fn forecast_data_points(
+    forecast: &Forecast,
+    start: DateTime<Utc>,
+    include_confidence_intervals: bool,
+) -> Vec<ForecastDataPoint> {
+    forecast
+        .values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let (lower_bound, upper_bound) = if include_confidence_intervals {
+                let (lower, upper) = forecast.interval(i + 1);
+                (Some(lower), Some(upper))
+            } else {
+                (None, None)
+            };
+
+            ForecastDataPoint {
+                date: start + Duration::days(i as i64),
+                forecasted_cost: *value,
+                lower_bound,
+                upper_bound,
+            }
+        })
+        .collect()
+}
+
+fn selected_backtest_mape(forecast: &Forecast) -> Option<f64> {
+    forecast
+        .selection
+        .candidates
+        .iter()
+        .find(|c| c.model == forecast.model)
+        .and_then(|c| c.backtest_mape)
+}
+
+/// Query daily historical costs for forecasting, per value of `dimension`
+/// when given
 async fn query_forecast_historical_data(
     pool: &PgPool,
     org_id: &str,
     start_time: DateTime<Utc>,
     end_time: DateTime<Utc>,
     request: &CostForecastRequest,
-) -> Result<Vec<CostDataPoint>, ApiError> {
+    dimension: Option<ForecastDimension>,
+) -> Result<Vec<ForecastHistoricalRow>, ApiError> {
     let mut where_clauses = vec![
         "org_id = $2".to_string(),
         "ts >= $3".to_string(),
@@ -1043,16 +1284,20 @@ async fn query_forecast_historical_data(
         where_clauses.push(format!("environment = ${}", param_index));
     }
 
+    let dimension_column = dimension.map_or("NULL::TEXT", |d| d.to_column_name());
+
     let query_str = format!(
         r#"
         SELECT
             time_bucket($1, ts) AS date,
-            SUM(total_cost_usd) AS cost
+            {} AS dimension_value,
+            SUM(total_cost_usd)::float8 AS cost
         FROM llm_traces
         WHERE {}
-        GROUP BY date
+        GROUP BY 1, 2
         ORDER BY date ASC
         "#,
+        dimension_column,
         where_clauses.join(" AND ")
     );
 
@@ -1072,21 +1317,428 @@ async fn query_forecast_historical_data(
         query = query.bind(environment);
     }
 
-    let rows = query.fetch_all(pool).await.map_err(|e| {
+    query.fetch_all(pool).await.map_err(|e| {
         error!(error = %e, "Failed to query forecast historical data");
         ApiError::Internal(format!("Database query failed: {}", e))
-    })?;
+    })
+}
 
-    let data_points: Vec<CostDataPoint> = rows
-        .into_iter()
-        .map(|row| CostDataPoint {
-            date: row.date,
-            cost: row.cost.unwrap_or(0.0),
-            requests: 0, // Not needed for forecast
+// ============================================================================
+// Endpoint 4: GET /api/v1/costs/explain
+// ============================================================================
+
+/// GET /api/v1/costs/explain - Explain the cost change between two periods
+///
+/// Decomposes the cost delta from period A to period B into contributions by
+/// provider, model and team, and splits each into a volume effect (more or
+/// fewer tokens) and a price effect (cost per token). Provider and model
+/// figures come from the daily continuous aggregate, so periods are resolved
+/// at day granularity.
+///
+/// ## Query Parameters
+/// - `period_a`: Baseline period (`YYYY-MM` or `start/end` ISO 8601) - required
+/// - `period_b`: Comparison period (`YYYY-MM` or `start/end` ISO 8601) - required
+/// - `provider`: Filter by provider
+/// - `model`: Filter by model
+/// - `limit`: Max contributors per dimension (max 100) - default: 20
+/// - `currency`: Reporting currency (ISO 4217) - default: configured display currency
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/costs/explain?period_a=2025-09&period_b=2025-10' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_cost_explain(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<CostExplainRequest>,
+) -> Result<CachedJson<CostExplainResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("costs:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read cost data".to_string(),
+        ));
+    }
+
+    // Validate request
+    let (period_a, period_b) = request.validate().map_err(ApiError::BadRequest)?;
+    let quote = state.currency.resolve(request.currency.as_deref())?;
+
+    info!(
+        org_id = %auth.organization_id,
+        period_a = %request.period_a,
+        period_b = %request.period_b,
+        "Explaining cost change"
+    );
+
+    // Serve from cache (in USD), refreshing stale results in the background
+    let cache_key = generate_explain_cache_key(&request, &auth.organization_id, &period_a, &period_b);
+    let pool = state.db_pool.clone();
+    let org_id = auth.organization_id.clone();
+    let (mut response, cache_status) = state
+        .query_cache
+        .get_or_compute(&cache_key, REPORT_FRESHNESS, move || async move {
+            execute_cost_explain(&pool, &request, &org_id, period_a, period_b).await
         })
-        .collect();
+        .await?;
 
-    Ok(data_points)
+    info!(delta = response.summary.delta, cache_status = ?cache_status, "Cost explanation completed");
+
+    response.convert_currency(&quote);
+    Ok(CachedJson(response, cache_status))
+}
+
+/// Execute cost explain queries
+async fn execute_cost_explain(
+    pool: &PgPool,
+    request: &CostExplainRequest,
+    org_id: &str,
+    period_a: PeriodRange,
+    period_b: PeriodRange,
+) -> Result<CostExplainResponse, ApiError> {
+    let model_rows = query_explain_rows(pool, request, org_id, &period_a, &period_b, "model").await?;
+    let team_rows = query_explain_rows(pool, request, org_id, &period_a, &period_b, "team").await?;
+
+    let by_model: Vec<CostContribution> = model_rows.iter().map(contribution_from_row).collect();
+
+    // Provider contributions are the sum of their models, so mix shifts
+    // between models of one provider show up as volume rather than price
+    let mut providers: HashMap<String, CostContribution> = HashMap::new();
+    for (row, model) in model_rows.iter().zip(by_model.iter()) {
+        let name = row.provider.clone().unwrap_or_else(|| "unknown".to_string());
+        let entry = providers.entry(name.clone()).or_insert_with(|| CostContribution {
+            name,
+            cost_a: 0.0,
+            cost_b: 0.0,
+            delta: 0.0,
+            contribution_percentage: 0.0,
+            volume_effect: 0.0,
+            price_effect: 0.0,
+        });
+        entry.cost_a += model.cost_a;
+        entry.cost_b += model.cost_b;
+        entry.delta += model.delta;
+        entry.volume_effect += model.volume_effect;
+        entry.price_effect += model.price_effect;
+    }
+
+    let cost_a: f64 = by_model.iter().map(|c| c.cost_a).sum();
+    let cost_b: f64 = by_model.iter().map(|c| c.cost_b).sum();
+    let delta = cost_b - cost_a;
+
+    let summary = ExplainSummary {
+        cost_a,
+        cost_b,
+        delta,
+        delta_percentage: if cost_a > 0.0 {
+            Some(delta / cost_a * 100.0)
+        } else {
+            None
+        },
+        volume_effect: by_model.iter().map(|c| c.volume_effect).sum(),
+        price_effect: by_model.iter().map(|c| c.price_effect).sum(),
+        requests_a: model_rows.iter().map(|r| r.requests_a.unwrap_or(0)).sum(),
+        requests_b: model_rows.iter().map(|r| r.requests_b.unwrap_or(0)).sum(),
+        tokens_a: model_rows.iter().map(|r| r.tokens_a.unwrap_or(0)).sum(),
+        tokens_b: model_rows.iter().map(|r| r.tokens_b.unwrap_or(0)).sum(),
+    };
+
+    let by_team = team_rows.iter().map(contribution_from_row).collect();
+
+    Ok(CostExplainResponse {
+        metadata: ExplainMetadata {
+            period_a,
+            period_b,
+            generated_at: Utc::now(),
+            currency: CurrencyConversion::usd(delta),
+        },
+        summary,
+        by_provider: rank_contributions(providers.into_values().collect(), delta, request.limit),
+        by_model: rank_contributions(by_model, delta, request.limit),
+        by_team: rank_contributions(by_team, delta, request.limit),
+    })
+}
+
+/// Query per-dimension costs for both periods in one pass
+///
+/// `model` rows come from the daily aggregate; `team` rows come from raw
+/// traces because the aggregates are not grouped by team.
+async fn query_explain_rows(
+    pool: &PgPool,
+    request: &CostExplainRequest,
+    org_id: &str,
+    period_a: &PeriodRange,
+    period_b: &PeriodRange,
+    dimension: &str,
+) -> Result<Vec<CostExplainRow>, ApiError> {
+    let (table, time_col, provider_col, dimension_col, requests_expr) = match dimension {
+        "team" => (
+            "llm_traces",
+            "ts",
+            "NULL::TEXT",
+            "COALESCE(team_id, 'unassigned')",
+            "1",
+        ),
+        _ => ("llm_metrics_1day", "bucket", "provider", "model", "request_count"),
+    };
+
+    let mut where_clauses = vec![
+        "org_id = $1".to_string(),
+        format!(
+            "(({t} >= $2 AND {t} < $3) OR ({t} >= $4 AND {t} < $5))",
+            t = time_col
+        ),
+    ];
+    let mut param_index = 6;
+
+    if request.provider.is_some() {
+        where_clauses.push(format!("provider = ${}", param_index));
+        param_index += 1;
+    }
+    if request.model.is_some() {
+        where_clauses.push(format!("model = ${}", param_index));
+    }
+
+    let in_a = format!("FILTER (WHERE {t} >= $2 AND {t} < $3)", t = time_col);
+    let in_b = format!("FILTER (WHERE {t} >= $4 AND {t} < $5)", t = time_col);
+
+    let query_str = format!(
+        r#"
+        SELECT
+            {provider} AS provider,
+            {dimension} AS dimension_value,
+            SUM(total_cost_usd) {in_a} AS cost_a,
+            SUM(total_cost_usd) {in_b} AS cost_b,
+            SUM({requests}) {in_a}::BIGINT AS requests_a,
+            SUM({requests}) {in_b}::BIGINT AS requests_b,
+            SUM(total_tokens) {in_a}::BIGINT AS tokens_a,
+            SUM(total_tokens) {in_b}::BIGINT AS tokens_b
+        FROM {table}
+        WHERE {where_clause}
+        GROUP BY 1, 2
+        "#,
+        provider = provider_col,
+        dimension = dimension_col,
+        requests = requests_expr,
+        in_a = in_a,
+        in_b = in_b,
+        table = table,
+        where_clause = where_clauses.join(" AND "),
+    );
+
+    let mut query = sqlx::query_as::<_, CostExplainRow>(&query_str)
+        .bind(org_id)
+        .bind(period_a.start)
+        .bind(period_a.end)
+        .bind(period_b.start)
+        .bind(period_b.end);
+
+    if let Some(ref provider) = request.provider {
+        query = query.bind(provider);
+    }
+    if let Some(ref model) = request.model {
+        query = query.bind(model);
+    }
+
+    query.fetch_all(pool).await.map_err(|e| {
+        error!(error = %e, dimension, "Failed to query cost explanation");
+        ApiError::Internal(format!("Database query failed: {}", e))
+    })
+}
+
+/// Build a contribution from an explain row, using tokens as the volume measure
+fn contribution_from_row(row: &CostExplainRow) -> CostContribution {
+    let cost_a = row.cost_a.unwrap_or(0.0);
+    let cost_b = row.cost_b.unwrap_or(0.0);
+    let (volume_effect, price_effect) = decompose_cost_delta(
+        cost_a,
+        row.tokens_a.unwrap_or(0) as f64,
+        cost_b,
+        row.tokens_b.unwrap_or(0) as f64,
+    );
+
+    CostContribution {
+        name: row.dimension_value.clone(),
+        cost_a,
+        cost_b,
+        delta: cost_b - cost_a,
+        contribution_percentage: 0.0,
+        volume_effect,
+        price_effect,
+    }
+}
+
+/// Set each item's share of the total change and keep the largest movers
+fn rank_contributions(
+    mut items: Vec<CostContribution>,
+    total_delta: f64,
+    limit: usize,
+) -> Vec<CostContribution> {
+    for item in &mut items {
+        item.contribution_percentage = if total_delta != 0.0 {
+            item.delta / total_delta * 100.0
+        } else {
+            0.0
+        };
+    }
+
+    items.sort_by(|a, b| b.delta.abs().total_cmp(&a.delta.abs()));
+    items.truncate(limit);
+    items
+}
+
+// ============================================================================
+// Endpoint 5: GET /api/v1/costs/chargeback
+// ============================================================================
+
+/// GET /api/v1/costs/chargeback - Monthly invoices per cost-allocation tag
+///
+/// Bills each value of a cost-allocation tag (`cost.tag.<key>` span
+/// attributes, stored in `llm_traces.cost_tags`) for every month of the
+/// range, with provider/model line items.
+///
+/// ## Query Parameters
+/// - `tag_key`: Tag to invoice by, e.g. `team` - required
+/// - `start_month`: First month (`YYYY-MM`) - required
+/// - `end_month`: Last month, inclusive (`YYYY-MM`, max 24 months) - default: start_month
+/// - `tag_value`: Only invoice this tag value
+/// - `include_untagged`: Invoice calls without the tag separately - default: true
+/// - `format`: `json` or `csv` (one row per line item) - default: json
+/// - `currency`: Reporting currency (ISO 4217) - default: configured display currency
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/costs/chargeback?tag_key=team&start_month=2025-10&format=csv' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_cost_chargeback(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<ChargebackRequest>,
+) -> Result<Response, ApiError> {
+    // Check permissions
+    if !auth.has_permission("costs:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read cost data".to_string(),
+        ));
+    }
+
+    // Validate request
+    let period = request.validate().map_err(ApiError::BadRequest)?;
+    let quote = state.currency.resolve(request.currency.as_deref())?;
+
+    info!(
+        org_id = %auth.organization_id,
+        tag_key = %request.tag_key,
+        start = %period.start,
+        end = %period.end,
+        "Generating chargeback report"
+    );
+
+    // Generate cache key
+    let cache_key = generate_chargeback_cache_key(&request, &auth.organization_id, &period);
+
+    // Serve from cache (in USD), refreshing stale results in the background
+    let pool = state.db_pool.clone();
+    let org_id = auth.organization_id.clone();
+    let query = request.clone();
+    let (mut response, cache_status) = state
+        .query_cache
+        .get_or_compute(&cache_key, CHARGEBACK_FRESHNESS, move || async move {
+            let rows = query_chargeback_rows(&pool, &query, &org_id, &period).await?;
+            Ok::<_, ApiError>(ChargebackResponse::from_rows(&query.tag_key, period, rows))
+        })
+        .await?;
+
+    info!(
+        invoices = response.invoices.len(),
+        cache_status = ?cache_status,
+        "Chargeback report completed"
+    );
+
+    response.convert_currency(&quote);
+    match request.format {
+        ChargebackFormat::Json => Ok(CachedJson(response, cache_status).into_response()),
+        ChargebackFormat::Csv => {
+            let filename = format!(
+                "chargeback-{}-{}.csv",
+                request.tag_key,
+                request.end_month.as_deref().map_or_else(
+                    || request.start_month.clone(),
+                    |end| format!("{}-to-{}", request.start_month, end)
+                )
+            );
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        format!("attachment; filename=\"{}\"", filename),
+                    ),
+                ],
+                [(CACHE_STATUS, cache_status.header_value())],
+                response.to_csv(),
+            )
+                .into_response())
+        }
+    }
+}
+
+/// Query chargeback rows from llm_traces, grouped by UTC month, tag value,
+/// provider and model
+async fn query_chargeback_rows(
+    pool: &PgPool,
+    request: &ChargebackRequest,
+    org_id: &str,
+    period: &PeriodRange,
+) -> Result<Vec<ChargebackRow>, ApiError> {
+    let mut where_clauses = vec![
+        "org_id = $1".to_string(),
+        "ts >= $2".to_string(),
+        "ts < $3".to_string(),
+    ];
+
+    if request.tag_value.is_some() {
+        where_clauses.push("cost_tags->>$4 = $5".to_string());
+    } else if !request.include_untagged {
+        where_clauses.push("cost_tags ? $4".to_string());
+    }
+
+    let query_str = format!(
+        r#"
+        SELECT
+            date_trunc('month', ts AT TIME ZONE 'UTC') AT TIME ZONE 'UTC' AS month,
+            cost_tags->>$4 AS tag_value,
+            provider,
+            model,
+            SUM(total_cost_usd)::DOUBLE PRECISION AS total_cost,
+            SUM(prompt_cost_usd)::DOUBLE PRECISION AS prompt_cost,
+            SUM(completion_cost_usd)::DOUBLE PRECISION AS completion_cost,
+            COUNT(*) AS request_count,
+            SUM(total_tokens)::BIGINT AS total_tokens
+        FROM llm_traces
+        WHERE {}
+        GROUP BY 1, 2, 3, 4
+        "#,
+        where_clauses.join(" AND ")
+    );
+
+    let mut query = sqlx::query_as::<_, ChargebackRow>(&query_str)
+        .bind(org_id)
+        .bind(period.start)
+        .bind(period.end)
+        .bind(&request.tag_key);
+
+    if let Some(ref tag_value) = request.tag_value {
+        query = query.bind(tag_value);
+    }
+
+    query.fetch_all(pool).await.map_err(|e| {
+        error!(error = %e, "Failed to query chargeback");
+        ApiError::Internal(format!("Database query failed: {}", e))
+    })
 }
 
 // ============================================================================
@@ -1130,38 +1782,53 @@ fn generate_forecast_cache_key(
     historical_end: DateTime<Utc>,
 ) -> String {
     format!(
-        "costs:forecast:{}:{}:{}:{:?}",
+        "costs:forecast:{}:{}:{}:{:?}:{}:{}:{}:{:?}:{:?}:{:?}:{}:{}",
         org_id,
         historical_start.to_rfc3339(),
         historical_end.to_rfc3339(),
-        request.forecast_period
+        request.forecast_period,
+        request.provider.as_deref().unwrap_or("all"),
+        request.model.as_deref().unwrap_or("all"),
+        request.environment.as_deref().unwrap_or("all"),
+        request.forecast_model,
+        request.selection_criterion,
+        request.group_by,
+        request.dimension_limit,
+        request.include_confidence_intervals
     )
 }
 
-async fn try_get_from_cache<T: serde::de::DeserializeOwned>(
-    state: &Arc<AppState>,
-    cache_key: &str,
-) -> Result<T, ()> {
-    let mut redis_conn = state.redis_client.get_async_connection().await.map_err(|e| {
-        warn!(error = %e, "Redis connection error");
-    })?;
-
-    let cached: String = redis_conn.get(cache_key).await.map_err(|_| ())?;
-    serde_json::from_str(&cached).map_err(|_| ())
-}
-
-async fn cache_cost_summary(state: &Arc<AppState>, cache_key: &str, response: &CostSummaryResponse) {
-    if let Ok(serialized) = serde_json::to_string(response) {
-        if let Ok(mut conn) = state.redis_client.get_async_connection().await {
-            let _: Result<(), _> = conn.set_ex(cache_key, serialized, state.cache_ttl).await;
-        }
-    }
+fn generate_explain_cache_key(
+    request: &CostExplainRequest,
+    org_id: &str,
+    period_a: &PeriodRange,
+    period_b: &PeriodRange,
+) -> String {
+    format!(
+        "costs:explain:{}:{}:{}:{}:{}:{}:{}:{}",
+        org_id,
+        period_a.start.to_rfc3339(),
+        period_a.end.to_rfc3339(),
+        period_b.start.to_rfc3339(),
+        period_b.end.to_rfc3339(),
+        request.provider.as_deref().unwrap_or("all"),
+        request.model.as_deref().unwrap_or("all"),
+        request.limit
+    )
 }
 
-async fn cache_cost_attribution(state: &Arc<AppState>, cache_key: &str, response: &CostAttributionResponse) {
-    if let Ok(serialized) = serde_json::to_string(response) {
-        if let Ok(mut conn) = state.redis_client.get_async_connection().await {
-            let _: Result<(), _> = conn.set_ex(cache_key, serialized, state.cache_ttl).await;
-        }
-    }
+fn generate_chargeback_cache_key(
+    request: &ChargebackRequest,
+    org_id: &str,
+    period: &PeriodRange,
+) -> String {
+    format!(
+        "costs:chargeback:{}:{}:{}:{}:{}:{}",
+        org_id,
+        request.tag_key,
+        period.start.to_rfc3339(),
+        period.end.to_rfc3339(),
+        request.tag_value.as_deref().unwrap_or("all"),
+        request.include_untagged
+    )
 }
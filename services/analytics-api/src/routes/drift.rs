@@ -0,0 +1,218 @@
+//! # Drift Monitoring API Routes
+//!
+//! Hourly prompt/response characteristics per model and workload, and the
+//! drift alerts raised when they move away from their baseline (see
+//! `services::drift`).
+//!
+//! ## Endpoints
+//! - GET /api/v1/drift/snapshots - Hourly snapshots per provider, model and workload
+//! - GET /api/v1/drift/alerts - Drift alerts, newest first
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `metrics:read`
+//! - Snapshots and alerts are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::drift::*;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create drift monitoring routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/api/v1/drift/snapshots", get(get_drift_snapshots))
+        .route("/api/v1/drift/alerts", get(get_drift_alerts))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        error!(error = %err, "Drift query failed");
+        ApiError::Internal(format!("Database query failed: {}", err))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/drift/snapshots
+// ============================================================================
+
+/// GET /api/v1/drift/snapshots - Hourly prompt/response characteristics
+///
+/// ## Query Parameters
+/// - `start_time`: Start of time range (ISO 8601) - default: 24 hours ago
+/// - `end_time`: End of time range (ISO 8601) - default: now
+/// - `provider`, `model`, `workload`: Only snapshots of this series
+/// - `limit`: Maximum snapshots (default: 500)
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/drift/snapshots?model=gpt-4o' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_drift_snapshots(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<DriftSnapshotsRequest>,
+) -> Result<Json<DriftSnapshotsResponse>, ApiError> {
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read drift snapshots".to_string(),
+        ));
+    }
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let end_time = request.end_time.unwrap_or_else(Utc::now);
+    let start_time = request
+        .start_time
+        .unwrap_or_else(|| end_time - Duration::hours(24));
+
+    let snapshots = sqlx::query_as::<_, DriftSnapshot>(
+        r#"
+        SELECT
+            provider, model, workload, bucket, request_count, sample_count,
+            prompt_length_mean, prompt_length_stddev,
+            response_length_mean, response_length_stddev,
+            refusal_rate, languages
+        FROM drift_snapshots
+        WHERE org_id = $1
+          AND bucket >= $2
+          AND bucket < $3
+          AND ($4::TEXT IS NULL OR provider = $4)
+          AND ($5::TEXT IS NULL OR model = $5)
+          AND ($6::TEXT IS NULL OR workload = $6)
+        ORDER BY bucket DESC, request_count DESC
+        LIMIT $7
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(start_time)
+    .bind(end_time)
+    .bind(&request.provider)
+    .bind(&request.model)
+    .bind(&request.workload)
+    .bind(request.limit)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    info!(org_id = %auth.org_id, snapshots = snapshots.len(), "Drift snapshots query completed");
+
+    Ok(Json(DriftSnapshotsResponse {
+        start_time,
+        end_time,
+        snapshots,
+    }))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/drift/alerts
+// ============================================================================
+
+/// GET /api/v1/drift/alerts - Drift alerts, newest first
+///
+/// ## Query Parameters
+/// - `start_time`: Start of time range (ISO 8601) - default: 7 days ago
+/// - `end_time`: End of time range (ISO 8601) - default: now
+/// - `provider`, `model`: Only alerts of this provider or model
+/// - `metric`: prompt_length, response_length, refusal_rate, language or embedding
+/// - `limit`: Maximum alerts (default: 100)
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/drift/alerts?metric=refusal_rate' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_drift_alerts(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<DriftAlertsRequest>,
+) -> Result<Json<DriftAlertsResponse>, ApiError> {
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read drift alerts".to_string(),
+        ));
+    }
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let end_time = request.end_time.unwrap_or_else(Utc::now);
+    let start_time = request
+        .start_time
+        .unwrap_or_else(|| end_time - Duration::days(7));
+
+    let alerts = sqlx::query_as::<_, DriftAlert>(
+        r#"
+        SELECT
+            id, provider, model, workload, bucket, metric,
+            baseline_value, current_value, score, threshold, detected_at
+        FROM drift_alerts
+        WHERE org_id = $1
+          AND detected_at >= $2
+          AND detected_at < $3
+          AND ($4::TEXT IS NULL OR provider = $4)
+          AND ($5::TEXT IS NULL OR model = $5)
+          AND ($6::TEXT IS NULL OR metric = $6)
+        ORDER BY detected_at DESC
+        LIMIT $7
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(start_time)
+    .bind(end_time)
+    .bind(&request.provider)
+    .bind(&request.model)
+    .bind(&request.metric)
+    .bind(request.limit)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    info!(org_id = %auth.org_id, alerts = alerts.len(), "Drift alerts query completed");
+
+    Ok(Json(DriftAlertsResponse {
+        start_time,
+        end_time,
+        alerts,
+    }))
+}
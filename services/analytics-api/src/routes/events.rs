@@ -0,0 +1,218 @@
+//! # Trace Event API Routes
+//!
+//! Span events record what happened inside a span: guardrail hits, tool
+//! results, stream chunks. This endpoint merges the events of all spans of a
+//! trace into one time-ordered timeline, so a request can be followed across
+//! services without opening each span.
+//!
+//! ## Endpoints
+//! - GET /api/v1/traces/:trace_id/events - Event timeline of a trace
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `read:traces`
+//! - Events are organization-scoped
+//! - Event attributes carrying prompt and response text or user identifiers
+//!   are masked as on traces (`[REDACTED]` without `read:trace_content` or
+//!   `read:user_identifiers`); unmasked responses are recorded in the data
+//!   access audit log
+//! - Compressed event attributes are decompressed before masking; events
+//!   whose attributes the storage writer encrypted are refused with 501, as
+//!   the API holds no decryption keys
+
+use crate::middleware::AuthContext;
+use crate::models::events::*;
+use crate::models::logs::validate_trace_id;
+use crate::models::{AppState, ErrorResponse};
+use crate::services::tenant::with_tenant;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create trace event routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/api/v1/traces/:trace_id/events", get(get_trace_events))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    NotImplemented(String),
+    Internal(String),
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        error!(error = %err, "Trace event query failed");
+        ApiError::Internal(format!("Database query failed: {}", err))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::NotImplemented(msg) => (StatusCode::NOT_IMPLEMENTED, "not_implemented", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/traces/:trace_id/events
+// ============================================================================
+
+/// GET /api/v1/traces/:trace_id/events - Event timeline of a trace
+///
+/// Returns the events of all spans of the trace, oldest first, each with its
+/// span and its offset from the start of the trace. Events at the same time
+/// are ordered by span.
+///
+/// Events are parsed from spans when the storage trace writer inserts them;
+/// spans written before `trace_events` existed have no events here.
+///
+/// ## Query Parameters
+/// - `span_id`: Only events of this span
+/// - `name`: Only events with this name, e.g. `guardrail.evaluation`
+/// - `limit`: Maximum events (1-1000) - default: 1000
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/traces/4bf92f3577b34da6a3ce929d0e0e4736/events' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_trace_events(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(trace_id): Path<String>,
+    Query(request): Query<TraceEventsQuery>,
+) -> Result<Json<TraceEventsResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("read:traces") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read trace events".to_string(),
+        ));
+    }
+
+    // Validate request
+    validate_trace_id(&trace_id).map_err(ApiError::BadRequest)?;
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    info!(org_id = %auth.org_id, %trace_id, "Querying trace events");
+
+    // Spans carry the organization in their attributes, or inherit the
+    // trace's resource
+    let mut tx = with_tenant(&state.db_pool, &auth.org_id).await?;
+    let mut rows = sqlx::query_as::<_, TraceEventRow>(
+        r#"
+        SELECT
+            e.id,
+            e.timestamp,
+            s.span_id,
+            s.name AS span_name,
+            s.service_name,
+            e.name,
+            e.attributes,
+            t.start_time AS trace_start
+        FROM traces t
+        JOIN trace_spans s ON s.trace_id = t.id
+        JOIN trace_events e ON e.span_id = s.id
+        WHERE t.trace_id = $2
+          AND COALESCE(
+              s.attributes->>'org_id',
+              t.attributes->>'org_id',
+              t.resource_attributes->>'org_id'
+          ) = $1
+          AND ($3::TEXT IS NULL OR s.span_id = $3)
+          AND ($4::TEXT IS NULL OR e.name = $4)
+        ORDER BY e.timestamp, s.span_id, e.id
+        LIMIT $5
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(&trace_id)
+    .bind(&request.span_id)
+    .bind(&request.name)
+    .bind(request.limit + 1)
+    .fetch_all(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let truncated = rows.len() > request.limit as usize;
+    rows.truncate(request.limit as usize);
+
+    // Masking needs the attributes as written, not their storage envelopes
+    for row in &mut rows {
+        row.attributes = match decode_attributes(std::mem::take(&mut row.attributes)) {
+            Ok(attributes) => attributes,
+            Err(AttributesError::Encrypted) => {
+                return Err(ApiError::NotImplemented(
+                    "Span event attributes are encrypted at rest and cannot be read \
+                     through the API"
+                        .to_string(),
+                ));
+            }
+            Err(AttributesError::Invalid(e)) => {
+                error!(event_id = %row.id, error = %e, "Failed to decode span event attributes");
+                return Err(ApiError::Internal(
+                    "Failed to decode span event attributes".to_string(),
+                ));
+            }
+        };
+    }
+
+    state
+        .data_access
+        .apply_to_event_attributes(
+            &auth,
+            "GET /api/v1/traces/:trace_id/events",
+            &trace_id,
+            rows.iter_mut().map(|row| &mut row.attributes),
+        )
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to record data access audit entry");
+            ApiError::Internal("Failed to record data access audit entry".to_string())
+        })?;
+    let (start_time, events) = build_timeline(rows);
+
+    info!(
+        org_id = %auth.org_id,
+        %trace_id,
+        events = events.len(),
+        truncated,
+        "Trace events returned"
+    );
+
+    Ok(Json(TraceEventsResponse {
+        trace_id,
+        start_time,
+        events,
+        truncated,
+    }))
+}
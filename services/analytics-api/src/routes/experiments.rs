@@ -0,0 +1,425 @@
+//! # Experiment API Routes
+//!
+//! A/B experiment registration, feedback and per-variant results. Spans join
+//! an experiment through the `experiment.id` and `experiment.variant`
+//! attributes set by the SDK.
+//!
+//! ## Endpoints
+//! - POST /api/v1/experiments - Register an experiment
+//! - GET /api/v1/experiments - List experiments
+//! - GET /api/v1/experiments/:experiment_id - Get an experiment
+//! - POST /api/v1/experiments/:experiment_id/end - End a running experiment
+//! - POST /api/v1/experiments/:experiment_id/feedback - Record a feedback score
+//! - GET /api/v1/experiments/:experiment_id/results - Compare variants
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Reading requires `metrics:read`, registering and ending `write:experiments`,
+//!   feedback `write:feedback`
+//! - Experiments are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::experiments::*;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create experiment routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/api/v1/experiments",
+            get(list_experiments).post(create_experiment),
+        )
+        .route("/api/v1/experiments/:experiment_id", get(get_experiment))
+        .route(
+            "/api/v1/experiments/:experiment_id/end",
+            post(end_experiment),
+        )
+        .route(
+            "/api/v1/experiments/:experiment_id/feedback",
+            post(record_feedback),
+        )
+        .route(
+            "/api/v1/experiments/:experiment_id/results",
+            get(get_experiment_results),
+        )
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    Internal(String),
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        error!(error = %err, "Experiment query failed");
+        ApiError::Internal(format!("Database query failed: {}", err))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, "not_found", msg),
+            ApiError::Conflict(msg) => (StatusCode::CONFLICT, "conflict", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+fn require_permission(auth: &AuthContext, permission: &str) -> Result<(), ApiError> {
+    if auth.has_permission(permission) {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(format!(
+            "Missing permission '{}'",
+            permission
+        )))
+    }
+}
+
+async fn fetch_experiment(
+    state: &AppState,
+    org_id: &str,
+    experiment_id: &str,
+) -> Result<Experiment, ApiError> {
+    sqlx::query_as::<_, Experiment>(
+        r#"
+        SELECT experiment_id, name, description, variants, started_at, ended_at, created_at
+        FROM experiments
+        WHERE org_id = $1 AND experiment_id = $2
+        "#,
+    )
+    .bind(org_id)
+    .bind(experiment_id)
+    .fetch_optional(&state.db_pool)
+    .await?
+    .ok_or_else(|| ApiError::NotFound(format!("Experiment '{}' not found", experiment_id)))
+}
+
+// ============================================================================
+// Endpoint: POST /api/v1/experiments
+// ============================================================================
+
+/// POST /api/v1/experiments - Register an experiment
+///
+/// ## Example
+/// ```bash
+/// curl -X POST 'http://localhost:8080/api/v1/experiments' \
+///   -H "Authorization: Bearer $JWT_TOKEN" \
+///   -H 'Content-Type: application/json' \
+///   -d '{"experiment_id": "prompt-v2", "name": "Shorter prompt", "variants": ["control", "short"]}'
+/// ```
+#[instrument(skip(state, auth, request))]
+async fn create_experiment(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Json(request): Json<CreateExperimentRequest>,
+) -> Result<(StatusCode, Json<Experiment>), ApiError> {
+    require_permission(&auth, "write:experiments")?;
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let experiment = sqlx::query_as::<_, Experiment>(
+        r#"
+        INSERT INTO experiments (org_id, experiment_id, name, description, variants, started_at, ended_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (org_id, experiment_id) DO NOTHING
+        RETURNING experiment_id, name, description, variants, started_at, ended_at, created_at
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(&request.experiment_id)
+    .bind(&request.name)
+    .bind(&request.description)
+    .bind(&request.variants)
+    .bind(request.start_time.unwrap_or_else(Utc::now))
+    .bind(request.end_time)
+    .fetch_optional(&state.db_pool)
+    .await?
+    .ok_or_else(|| {
+        ApiError::Conflict(format!(
+            "Experiment '{}' already exists",
+            request.experiment_id
+        ))
+    })?;
+
+    info!(
+        org_id = %auth.org_id,
+        experiment_id = %experiment.experiment_id,
+        variants = experiment.variants.len(),
+        "Experiment created"
+    );
+
+    Ok((StatusCode::CREATED, Json(experiment)))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/experiments
+// ============================================================================
+
+/// GET /api/v1/experiments - List experiments, most recent first
+#[instrument(skip(state, auth))]
+async fn list_experiments(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+) -> Result<Json<ExperimentListResponse>, ApiError> {
+    require_permission(&auth, "metrics:read")?;
+
+    let experiments = sqlx::query_as::<_, Experiment>(
+        r#"
+        SELECT experiment_id, name, description, variants, started_at, ended_at, created_at
+        FROM experiments
+        WHERE org_id = $1
+        ORDER BY started_at DESC
+        "#,
+    )
+    .bind(&auth.org_id)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    Ok(Json(ExperimentListResponse { experiments }))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/experiments/:experiment_id
+// ============================================================================
+
+/// GET /api/v1/experiments/:experiment_id - Get an experiment
+#[instrument(skip(state, auth))]
+async fn get_experiment(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(experiment_id): Path<String>,
+) -> Result<Json<Experiment>, ApiError> {
+    require_permission(&auth, "metrics:read")?;
+
+    Ok(Json(
+        fetch_experiment(&state, &auth.org_id, &experiment_id).await?,
+    ))
+}
+
+// ============================================================================
+// Endpoint: POST /api/v1/experiments/:experiment_id/end
+// ============================================================================
+
+/// POST /api/v1/experiments/:experiment_id/end - End a running experiment
+///
+/// Results keep covering the experiment's time range, so traffic still tagged
+/// with the experiment afterwards is not counted.
+#[instrument(skip(state, auth))]
+async fn end_experiment(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(experiment_id): Path<String>,
+) -> Result<Json<Experiment>, ApiError> {
+    require_permission(&auth, "write:experiments")?;
+
+    let ended = sqlx::query_as::<_, Experiment>(
+        r#"
+        UPDATE experiments
+        SET ended_at = NOW()
+        WHERE org_id = $1
+          AND experiment_id = $2
+          AND (ended_at IS NULL OR ended_at > NOW())
+          AND started_at < NOW()
+        RETURNING experiment_id, name, description, variants, started_at, ended_at, created_at
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(&experiment_id)
+    .fetch_optional(&state.db_pool)
+    .await?;
+
+    match ended {
+        Some(experiment) => {
+            info!(org_id = %auth.org_id, experiment_id = %experiment_id, "Experiment ended");
+            Ok(Json(experiment))
+        }
+        None => {
+            // Distinguish a missing experiment from one that is not running
+            fetch_experiment(&state, &auth.org_id, &experiment_id).await?;
+            Err(ApiError::Conflict(format!(
+                "Experiment '{}' is not running",
+                experiment_id
+            )))
+        }
+    }
+}
+
+// ============================================================================
+// Endpoint: POST /api/v1/experiments/:experiment_id/feedback
+// ============================================================================
+
+/// POST /api/v1/experiments/:experiment_id/feedback - Record a feedback score
+///
+/// The variant is not part of the request: it is read from the trace's spans
+/// when results are computed.
+#[instrument(skip(state, auth, request))]
+async fn record_feedback(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(experiment_id): Path<String>,
+    Json(request): Json<RecordFeedbackRequest>,
+) -> Result<(StatusCode, Json<FeedbackResponse>), ApiError> {
+    require_permission(&auth, "write:feedback")?;
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    fetch_experiment(&state, &auth.org_id, &experiment_id).await?;
+
+    let feedback_id = Uuid::new_v4();
+    let created_at = Utc::now();
+
+    sqlx::query(
+        r#"
+        INSERT INTO experiment_feedback (
+            feedback_id, org_id, experiment_id, trace_id, span_id, score, comment, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(feedback_id)
+    .bind(&auth.org_id)
+    .bind(&experiment_id)
+    .bind(&request.trace_id)
+    .bind(&request.span_id)
+    .bind(request.score)
+    .bind(&request.comment)
+    .bind(created_at)
+    .execute(&state.db_pool)
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(FeedbackResponse {
+            feedback_id,
+            experiment_id,
+            trace_id: request.trace_id,
+            created_at,
+        }),
+    ))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/experiments/:experiment_id/results
+// ============================================================================
+
+/// GET /api/v1/experiments/:experiment_id/results - Compare variants
+///
+/// Compares cost, latency, error rate and feedback scores of each variant
+/// against the control over the experiment's time range (up to now while it
+/// is running).
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/experiments/prompt-v2/results' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_experiment_results(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(experiment_id): Path<String>,
+) -> Result<Json<ExperimentResults>, ApiError> {
+    require_permission(&auth, "metrics:read")?;
+
+    let experiment = fetch_experiment(&state, &auth.org_id, &experiment_id).await?;
+    let start_time = experiment.started_at;
+    let end_time = experiment
+        .ended_at
+        .map_or_else(Utc::now, |ended| ended.min(Utc::now()));
+
+    let rows = sqlx::query_as::<_, ExperimentVariantRow>(
+        r#"
+        SELECT
+            attributes->>'experiment.variant' AS variant,
+            COUNT(*) AS request_count,
+            COUNT(*) FILTER (WHERE status_code = 'ERROR') AS error_count,
+            SUM(total_cost_usd)::float8 AS total_cost_usd,
+            SUM(total_tokens)::BIGINT AS total_tokens,
+            AVG(duration_ms)::float8 AS avg_duration_ms,
+            PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY duration_ms) AS p95_duration_ms
+        FROM llm_traces
+        WHERE attributes->>'org_id' = $1
+          AND attributes->>'experiment.id' = $2
+          AND ts >= $3
+          AND ts < $4
+        GROUP BY 1
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(&experiment_id)
+    .bind(start_time)
+    .bind(end_time)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    let feedback = sqlx::query_as::<_, ExperimentFeedbackRow>(
+        r#"
+        SELECT
+            s.variant,
+            COUNT(*) AS feedback_count,
+            AVG(f.score) AS avg_score
+        FROM experiment_feedback f
+        JOIN (
+            SELECT DISTINCT trace_id, attributes->>'experiment.variant' AS variant
+            FROM llm_traces
+            WHERE attributes->>'org_id' = $1
+              AND attributes->>'experiment.id' = $2
+              AND ts >= $3
+              AND ts < $4
+        ) s ON s.trace_id = f.trace_id
+        WHERE f.org_id = $1
+          AND f.experiment_id = $2
+        GROUP BY s.variant
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(&experiment_id)
+    .bind(start_time)
+    .bind(end_time)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    let results = build_experiment_results(experiment, start_time, end_time, &rows, &feedback);
+
+    info!(
+        org_id = %auth.org_id,
+        experiment_id = %experiment_id,
+        requests = results.variants.iter().map(|v| v.request_count).sum::<i64>(),
+        "Experiment results computed"
+    );
+
+    Ok(Json(results))
+}
@@ -0,0 +1,338 @@
+//! # Grafana JSON Datasource API
+//!
+//! Implements the Grafana JSON / SimpleJSON datasource contract over the
+//! TimescaleDB continuous aggregates, so dashboards can be built without
+//! database credentials. Point the datasource URL at `/api/v1/grafana`:
+//! - `GET /api/v1/grafana/` - Connection test
+//! - `POST /api/v1/grafana/search` - Metric names for the target picker
+//! - `POST /api/v1/grafana/query` - Time series and table data
+//! - `POST /api/v1/grafana/annotations` - Provider incidents
+//! - `POST /api/v1/grafana/tag-keys` - Ad hoc filter keys
+//! - `POST /api/v1/grafana/tag-values` - Ad hoc filter values
+//!
+//! ## Security
+//! - JWT authentication required (send `Authorization: Bearer` from the
+//!   datasource custom headers)
+//! - Requires `metrics:read`
+//! - Metric data is organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::grafana::*;
+use crate::models::providers::ProviderIncident;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Duration;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create Grafana datasource routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/api/v1/grafana", get(test_connection))
+        .route("/api/v1/grafana/", get(test_connection))
+        .route("/api/v1/grafana/search", post(search))
+        .route("/api/v1/grafana/query", post(query))
+        .route("/api/v1/grafana/annotations", post(annotations))
+        .route("/api/v1/grafana/tag-keys", post(tag_keys))
+        .route("/api/v1/grafana/tag-values", post(tag_values))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg)
+            }
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+fn require_read(auth: &AuthContext) -> Result<(), ApiError> {
+    if auth.has_permission("metrics:read") {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(
+            "Insufficient permissions to read metrics".to_string(),
+        ))
+    }
+}
+
+fn database_error(e: sqlx::Error) -> ApiError {
+    error!(error = %e, "Grafana datasource query failed");
+    ApiError::Internal(format!("Database query failed: {}", e))
+}
+
+// ============================================================================
+// Endpoints
+// ============================================================================
+
+/// GET /api/v1/grafana/ - Connection test used by "Save & test"
+async fn test_connection(auth: AuthContext) -> Result<StatusCode, ApiError> {
+    require_read(&auth)?;
+    Ok(StatusCode::OK)
+}
+
+/// POST /api/v1/grafana/search - Metric names for the target picker
+#[instrument(skip(auth))]
+async fn search(
+    auth: AuthContext,
+    Json(request): Json<GrafanaSearchRequest>,
+) -> Result<Json<Vec<String>>, ApiError> {
+    require_read(&auth)?;
+    Ok(Json(search_targets(request.target.as_deref())))
+}
+
+/// POST /api/v1/grafana/query - Time series and table data
+///
+/// Each target is `<metric>` or `<metric> by <dimension>`. Time series are
+/// bucketed at the panel interval (at least one rollup row); table targets
+/// return totals over the range. Dashboard ad hoc filters on `provider`,
+/// `model` and `environment` apply to every target.
+///
+/// ## Example
+/// ```bash
+/// curl -X POST 'http://localhost:8080/api/v1/grafana/query' \
+///   -H "Authorization: Bearer $JWT_TOKEN" -H 'Content-Type: application/json' \
+///   -d '{"range":{"from":"2024-06-01T00:00:00Z","to":"2024-06-01T06:00:00Z"},
+///        "intervalMs":60000,"targets":[{"refId":"A","target":"total_cost_usd by model"}]}'
+/// ```
+#[instrument(skip(state, auth, request))]
+async fn query(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Json(request): Json<GrafanaQueryRequest>,
+) -> Result<Json<Vec<GrafanaQueryResult>>, ApiError> {
+    require_read(&auth)?;
+
+    request.validate().map_err(ApiError::BadRequest)?;
+    let filters = request.filters().map_err(ApiError::BadRequest)?;
+
+    info!(
+        org_id = %auth.org_id,
+        targets = request.targets.len(),
+        "Grafana datasource query"
+    );
+
+    let mut results = Vec::new();
+    for target in request.targets.iter().filter(|t| !t.hide && !t.target.is_empty()) {
+        let parsed = parse_target(&target.target).map_err(ApiError::BadRequest)?;
+
+        let mut dimensions: Vec<GrafanaDimension> = filters.iter().map(|f| f.dimension).collect();
+        dimensions.extend(parsed.group_by);
+        let source = AggregateSource::select(&request.range, request.interval_ms, &dimensions);
+
+        let bucket_secs = (!target.is_table()).then(|| {
+            source.bucket_secs(&request.range, request.interval_ms, request.max_data_points)
+        });
+
+        let rows = query_series(
+            &state.db_pool,
+            &auth.org_id,
+            &request.range,
+            &parsed,
+            &filters,
+            source,
+            bucket_secs,
+        )
+        .await?;
+
+        if target.is_table() {
+            results.push(GrafanaQueryResult::Table(to_table(&parsed, rows)));
+        } else {
+            results.extend(
+                to_time_series(&parsed, rows)
+                    .into_iter()
+                    .map(GrafanaQueryResult::TimeSeries),
+            );
+        }
+    }
+
+    Ok(Json(results))
+}
+
+/// Query one target, bucketed when `bucket_secs` is set, otherwise totalled
+async fn query_series(
+    pool: &PgPool,
+    org_id: &str,
+    range: &GrafanaRange,
+    target: &ParsedTarget,
+    filters: &[DimensionFilter],
+    source: AggregateSource,
+    bucket_secs: Option<i64>,
+) -> Result<Vec<GrafanaSeriesRow>, ApiError> {
+    let time_expr = match bucket_secs {
+        Some(_) => "time_bucket($4::INTERVAL, bucket)",
+        None => "NULL::TIMESTAMPTZ",
+    };
+    let series_expr = target
+        .group_by
+        .map_or("NULL::TEXT", |dim| dim.to_column_name());
+
+    let mut where_clauses = vec![
+        "org_id = $1".to_string(),
+        "bucket >= $2".to_string(),
+        "bucket < $3".to_string(),
+    ];
+    for (i, filter) in filters.iter().enumerate() {
+        where_clauses.push(format!(
+            "{} {} ${}",
+            filter.dimension.to_column_name(),
+            if filter.negate { "IS DISTINCT FROM" } else { "=" },
+            i + 5
+        ));
+    }
+
+    let query_str = format!(
+        r#"
+        SELECT
+            {} AS time,
+            {} AS series,
+            ({})::DOUBLE PRECISION AS value
+        FROM {}
+        WHERE {}
+        GROUP BY 1, 2
+        ORDER BY {}
+        LIMIT 10000
+        "#,
+        time_expr,
+        series_expr,
+        target.metric.to_sql(source),
+        source.table(),
+        where_clauses.join(" AND "),
+        if bucket_secs.is_some() { "1, 2" } else { "3 DESC NULLS LAST" }
+    );
+
+    let mut query = sqlx::query_as::<_, GrafanaSeriesRow>(&query_str)
+        .bind(org_id)
+        .bind(range.from)
+        .bind(range.to)
+        .bind(format!("{} seconds", bucket_secs.unwrap_or(0)));
+    for filter in filters {
+        query = query.bind(&filter.value);
+    }
+
+    query.fetch_all(pool).await.map_err(database_error)
+}
+
+/// POST /api/v1/grafana/annotations - Provider incidents
+///
+/// The annotation query is `incidents` (all providers) or
+/// `incidents:<provider>`.
+#[instrument(skip(state, auth, request))]
+async fn annotations(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Json(request): Json<GrafanaAnnotationRequest>,
+) -> Result<Json<Vec<GrafanaAnnotation>>, ApiError> {
+    require_read(&auth)?;
+
+    request.range.validate().map_err(ApiError::BadRequest)?;
+    let provider = parse_annotation_query(request.annotation.query.as_deref())
+        .map_err(ApiError::BadRequest)?;
+
+    let incidents = sqlx::query_as::<_, ProviderIncident>(
+        r#"
+        SELECT incident_id, provider, status, description, source, started_at, resolved_at
+        FROM provider_incidents
+        WHERE started_at < $2
+          AND (resolved_at IS NULL OR resolved_at > $1)
+          AND ($3::TEXT IS NULL OR provider = $3)
+        ORDER BY started_at
+        "#,
+    )
+    .bind(request.range.from)
+    .bind(request.range.to)
+    .bind(&provider)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(database_error)?;
+
+    Ok(Json(
+        incidents
+            .into_iter()
+            .map(|incident| incident_annotation(&request.annotation, incident))
+            .collect(),
+    ))
+}
+
+/// POST /api/v1/grafana/tag-keys - Ad hoc filter keys
+async fn tag_keys(auth: AuthContext) -> Result<Json<Vec<GrafanaTagKey>>, ApiError> {
+    require_read(&auth)?;
+
+    Ok(Json(
+        GrafanaDimension::ALL
+            .iter()
+            .map(|dim| GrafanaTagKey {
+                key_type: "string",
+                text: dim.to_column_name(),
+            })
+            .collect(),
+    ))
+}
+
+/// POST /api/v1/grafana/tag-values - Values seen for a key in the last 7 days
+#[instrument(skip(state, auth))]
+async fn tag_values(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Json(request): Json<GrafanaTagValuesRequest>,
+) -> Result<Json<Vec<GrafanaTagValue>>, ApiError> {
+    require_read(&auth)?;
+
+    let dimension = GrafanaDimension::parse(&request.key)
+        .ok_or_else(|| ApiError::BadRequest(format!("Unsupported tag key: {}", request.key)))?;
+
+    let query_str = format!(
+        r#"
+        SELECT DISTINCT {0}
+        FROM llm_metrics_1hour
+        WHERE org_id = $1 AND bucket >= $2 AND {0} IS NOT NULL
+        ORDER BY {0}
+        LIMIT 1000
+        "#,
+        dimension.to_column_name()
+    );
+
+    let values = sqlx::query_scalar::<_, String>(&query_str)
+        .bind(&auth.org_id)
+        .bind(chrono::Utc::now() - Duration::days(7))
+        .fetch_all(&state.db_pool)
+        .await
+        .map_err(database_error)?;
+
+    Ok(Json(
+        values.into_iter().map(|text| GrafanaTagValue { text }).collect(),
+    ))
+}
@@ -0,0 +1,202 @@
+//! # Guardrail Analytics API Route
+//!
+//! `GET /api/v1/guardrails/violations` returns content-safety violation rates
+//! by model, team or category. Violations come from the `guardrail_events`
+//! table; rates are computed against the request counts in `llm_traces`.
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `metrics:read`
+//! - Events and requests are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::guardrails::*;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create guardrail routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/api/v1/guardrails/violations", get(get_violations))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/guardrails/violations
+// ============================================================================
+
+/// GET /api/v1/guardrails/violations - Guardrail violation rates
+///
+/// ## Query Parameters
+/// - `start_time`: Start of time range (ISO 8601) - default: 24 hours ago
+/// - `end_time`: End of time range (ISO 8601) - default: now
+/// - `group_by`: `model`, `team` or `category` - default: `category`
+/// - `provider`: Filter by provider
+/// - `model`: Filter by model
+/// - `environment`: Filter by environment
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/guardrails/violations?group_by=team' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_violations(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<GuardrailViolationsRequest>,
+) -> Result<Json<GuardrailViolationsResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read guardrail violations".to_string(),
+        ));
+    }
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let end_time = request.end_time.unwrap_or_else(Utc::now);
+    let start_time = request
+        .start_time
+        .unwrap_or_else(|| end_time - Duration::hours(24));
+
+    info!(
+        org_id = %auth.org_id,
+        group_by = ?request.group_by,
+        "Querying guardrail violation rates"
+    );
+
+    // Per-group counts plus a totals row (NULL key). A request counts once per
+    // group however many of its guardrails fired.
+    let event_key = request.group_by.event_key_sql();
+    let violations_sql = format!(
+        r#"
+        SELECT
+            {event_key} AS key,
+            COUNT(DISTINCT (trace_id, span_id)) AS violating_requests,
+            COUNT(*) AS violation_count,
+            COUNT(*) FILTER (WHERE action = 'blocked') AS blocked_count,
+            COUNT(*) FILTER (WHERE action = 'flagged') AS flagged_count,
+            AVG(score) AS avg_score
+        FROM guardrail_events
+        WHERE org_id = $1
+          AND ts >= $2
+          AND ts < $3
+          AND ($4::TEXT IS NULL OR provider = $4)
+          AND ($5::TEXT IS NULL OR model = $5)
+          AND ($6::TEXT IS NULL OR environment = $6)
+        GROUP BY GROUPING SETS (({event_key}), ())
+        "#
+    );
+
+    let violations = sqlx::query_as::<_, ViolationCountRow>(&violations_sql)
+        .bind(&auth.org_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(&request.provider)
+        .bind(&request.model)
+        .bind(&request.environment)
+        .fetch_all(&state.db_pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to query guardrail events");
+            ApiError::Internal(format!("Database query failed: {}", e))
+        })?;
+
+    // Request counts per group plus a total row; categories are all rated
+    // against the total.
+    let request_filters = "attributes->>'org_id' = $1
+          AND ts >= $2
+          AND ts < $3
+          AND ($4::TEXT IS NULL OR provider = $4)
+          AND ($5::TEXT IS NULL OR model = $5)
+          AND ($6::TEXT IS NULL OR environment = $6)";
+    let requests_sql = match request.group_by.request_key_sql() {
+        Some(request_key) => format!(
+            r#"
+            SELECT {request_key} AS key, COUNT(*) AS request_count
+            FROM llm_traces
+            WHERE {request_filters}
+            GROUP BY GROUPING SETS (({request_key}), ())
+            "#
+        ),
+        None => format!(
+            r#"
+            SELECT NULL::TEXT AS key, COUNT(*) AS request_count
+            FROM llm_traces
+            WHERE {request_filters}
+            "#
+        ),
+    };
+
+    let requests = sqlx::query_as::<_, RequestCountRow>(&requests_sql)
+        .bind(&auth.org_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(&request.provider)
+        .bind(&request.model)
+        .bind(&request.environment)
+        .fetch_all(&state.db_pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to query request counts");
+            ApiError::Internal(format!("Database query failed: {}", e))
+        })?;
+
+    let response = build_violation_report(
+        start_time,
+        end_time,
+        request.group_by,
+        &violations,
+        &requests,
+    );
+
+    info!(
+        groups = response.groups.len(),
+        violation_rate = response.violation_rate,
+        "Guardrail violation query completed"
+    );
+
+    Ok(Json(response))
+}
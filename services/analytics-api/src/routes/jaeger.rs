@@ -0,0 +1,342 @@
+//! # Jaeger Query API Facade
+//!
+//! Serves the subset of the Jaeger HTTP query API used by the Jaeger UI and
+//! Grafana's Jaeger datasource, backed by `llm_traces`:
+//! - `GET /api/services` - Service names
+//! - `GET /api/services/:service/operations` - Operation names of a service
+//! - `GET /api/operations?service=...` - Operations with span kind
+//! - `GET /api/traces` - Trace search
+//! - `GET /api/traces/:trace_id` - Single trace
+//!
+//! ## Security
+//! - JWT authentication required (configure the datasource to send an
+//!   `Authorization: Bearer` header)
+//! - Requires `read:traces`
+//! - Results are organization-scoped
+//! - Prompt/response text and user identifiers are masked unless the caller
+//!   has `read:trace_content` / `read:user_identifiers`
+
+use crate::middleware::AuthContext;
+use crate::models::jaeger::*;
+use crate::models::traces::Trace;
+use crate::models::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+/// Span columns selected for conversion to Jaeger spans
+const SPAN_COLUMNS: &str = r#"
+    ts, trace_id, span_id, parent_span_id,
+    service_name, span_name,
+    provider, model,
+    input_text, output_text,
+    prompt_tokens, completion_tokens, total_tokens,
+    prompt_cost_usd, completion_cost_usd, total_cost_usd,
+    duration_ms, ttft_ms,
+    status_code, error_message,
+    user_id, session_id, environment,
+    tags, attributes, links
+"#;
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create Jaeger query API routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/api/services", get(get_services))
+        .route("/api/services/:service/operations", get(get_service_operations))
+        .route("/api/operations", get(get_operations))
+        .route("/api/traces", get(find_traces))
+        .route("/api/traces/:trace_id", get(get_trace))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+/// Errors rendered in the Jaeger response envelope
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    NotFound(String),
+    Internal(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, msg) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+        };
+
+        let body = Json(JaegerResponse {
+            data: serde_json::Value::Null,
+            total: 0,
+            limit: 0,
+            offset: 0,
+            errors: Some(vec![JaegerError {
+                code: status.as_u16(),
+                msg,
+            }]),
+        });
+
+        (status, body).into_response()
+    }
+}
+
+fn require_read(auth: &AuthContext) -> Result<(), ApiError> {
+    if auth.has_permission("read:traces") {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(
+            "Insufficient permissions to read traces".to_string(),
+        ))
+    }
+}
+
+fn database_error(e: sqlx::Error) -> ApiError {
+    error!(error = %e, "Jaeger facade query failed");
+    ApiError::Internal(format!("Database query failed: {}", e))
+}
+
+// ============================================================================
+// Endpoints: services and operations
+// ============================================================================
+
+/// GET /api/services - Service names
+#[instrument(skip(state, auth))]
+async fn get_services(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+) -> Result<Json<JaegerResponse<Vec<String>>>, ApiError> {
+    require_read(&auth)?;
+
+    let services = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT DISTINCT service_name
+        FROM llm_traces
+        WHERE org_id = $1 AND service_name IS NOT NULL
+        ORDER BY service_name
+        "#,
+    )
+    .bind(&auth.org_id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(database_error)?;
+
+    let total = services.len();
+    Ok(Json(JaegerResponse::new(services, total)))
+}
+
+/// GET /api/services/:service/operations - Operation names of a service
+#[instrument(skip(state, auth))]
+async fn get_service_operations(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(service): Path<String>,
+) -> Result<Json<JaegerResponse<Vec<String>>>, ApiError> {
+    require_read(&auth)?;
+
+    let operations = query_operations(&state, &auth.org_id, &service, None).await?;
+    let names: Vec<String> = operations.into_iter().map(|o| o.name).collect();
+
+    let total = names.len();
+    Ok(Json(JaegerResponse::new(names, total)))
+}
+
+/// GET /api/operations?service=...&spanKind=... - Operations with span kind
+#[instrument(skip(state, auth))]
+async fn get_operations(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(query): Query<JaegerOperationsQuery>,
+) -> Result<Json<JaegerResponse<Vec<JaegerOperation>>>, ApiError> {
+    require_read(&auth)?;
+
+    let operations = query_operations(
+        &state,
+        &auth.org_id,
+        &query.service,
+        query.span_kind.as_deref().filter(|k| !k.is_empty()),
+    )
+    .await?;
+
+    let total = operations.len();
+    Ok(Json(JaegerResponse::new(operations, total)))
+}
+
+async fn query_operations(
+    state: &AppState,
+    org_id: &str,
+    service: &str,
+    span_kind: Option<&str>,
+) -> Result<Vec<JaegerOperation>, ApiError> {
+    sqlx::query_as::<_, JaegerOperation>(
+        r#"
+        SELECT DISTINCT span_name AS name, span_kind
+        FROM llm_traces
+        WHERE org_id = $1
+          AND service_name = $2
+          AND ($3::TEXT IS NULL OR span_kind = $3)
+        ORDER BY name
+        "#,
+    )
+    .bind(org_id)
+    .bind(service)
+    .bind(span_kind)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(database_error)
+}
+
+// ============================================================================
+// Endpoints: traces
+// ============================================================================
+
+/// GET /api/traces - Search traces
+///
+/// Matches traces with at least one span satisfying the filters and returns
+/// every span of the most recent matching traces.
+///
+/// ## Query Parameters
+/// - `service`, `operation`: Span service and name
+/// - `start`, `end`: Time range in microseconds since the epoch
+/// - `lookback`: Time range ending now when `start` is absent (e.g. "1h")
+/// - `limit`: Maximum traces - default: 20
+/// - `minDuration`, `maxDuration`: Span duration bounds (e.g. "1.2s")
+/// - `tags`: JSON object of attribute filters; `error` matches failed spans
+#[instrument(skip(state, auth))]
+async fn find_traces(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(query): Query<JaegerTraceQuery>,
+) -> Result<Json<JaegerResponse<Vec<JaegerTrace>>>, ApiError> {
+    require_read(&auth)?;
+
+    let search = query.to_search(Utc::now()).map_err(ApiError::BadRequest)?;
+
+    // Masked attributes can't be used as tag filters
+    if let Some(Value::Object(tags)) = &search.attributes {
+        let fields: Vec<String> = tags.keys().map(|key| format!("attributes.{}", key)).collect();
+        state
+            .data_access
+            .check_filter_fields(&auth, fields.iter().map(String::as_str))
+            .map_err(ApiError::Forbidden)?;
+    }
+
+    info!(org_id = %auth.org_id, service = ?search.service, "Jaeger trace search");
+
+    let trace_ids = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT trace_id
+        FROM llm_traces
+        WHERE org_id = $1
+          AND ts >= $2
+          AND ts <= $3
+          AND ($4::TEXT IS NULL OR service_name = $4)
+          AND ($5::TEXT IS NULL OR span_name = $5)
+          AND ($6::INTEGER IS NULL OR duration_ms >= $6)
+          AND ($7::INTEGER IS NULL OR duration_ms <= $7)
+          AND ($8::BOOLEAN IS NULL OR (status_code = 'ERROR') = $8)
+          AND ($9::JSONB IS NULL OR attributes @> $9)
+        GROUP BY trace_id
+        ORDER BY MAX(ts) DESC
+        LIMIT $10
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(search.start_time)
+    .bind(search.end_time)
+    .bind(&search.service)
+    .bind(&search.operation)
+    .bind(search.min_duration_ms)
+    .bind(search.max_duration_ms)
+    .bind(search.error)
+    .bind(&search.attributes)
+    .bind(search.limit)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(database_error)?;
+
+    let spans = fetch_spans(&state, &auth, "GET /api/traces", &trace_ids).await?;
+
+    // Keep the recency order of the search
+    let mut traces = to_jaeger_traces(spans);
+    traces.sort_by_key(|t| trace_ids.iter().position(|id| *id == t.trace_id));
+
+    let total = traces.len();
+    Ok(Json(JaegerResponse::new(traces, total)))
+}
+
+/// GET /api/traces/:trace_id - Single trace
+#[instrument(skip(state, auth))]
+async fn get_trace(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(trace_id): Path<String>,
+) -> Result<Json<JaegerResponse<Vec<JaegerTrace>>>, ApiError> {
+    require_read(&auth)?;
+
+    let spans = fetch_spans(
+        &state,
+        &auth,
+        "GET /api/traces/:trace_id",
+        std::slice::from_ref(&trace_id),
+    )
+    .await?;
+    if spans.is_empty() {
+        return Err(ApiError::NotFound(format!("trace not found: {}", trace_id)));
+    }
+
+    let traces = to_jaeger_traces(spans);
+    let total = traces.len();
+    Ok(Json(JaegerResponse::new(traces, total)))
+}
+
+/// Fetch the spans of the traces, masked for the caller.
+async fn fetch_spans(
+    state: &AppState,
+    auth: &AuthContext,
+    endpoint: &str,
+    trace_ids: &[String],
+) -> Result<Vec<Trace>, ApiError> {
+    if trace_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sql = format!(
+        "SELECT {} FROM llm_traces WHERE org_id = $1 AND trace_id = ANY($2) ORDER BY ts",
+        SPAN_COLUMNS
+    );
+
+    let mut spans = sqlx::query_as::<_, Trace>(&sql)
+        .bind(&auth.org_id)
+        .bind(trace_ids)
+        .fetch_all(&state.db_pool)
+        .await
+        .map_err(database_error)?;
+
+    state
+        .data_access
+        .apply(auth, endpoint, &mut spans)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to record data access audit entry");
+            ApiError::Internal("Failed to record data access audit entry".to_string())
+        })?;
+
+    Ok(spans)
+}
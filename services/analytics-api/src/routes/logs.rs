@@ -0,0 +1,407 @@
+//! # Log API Routes
+//!
+//! - `GET /api/v1/logs` searches log records by time, severity, service and
+//!   body text, newest first, with cursor pagination
+//! - `GET /api/v1/traces/:trace_id/logs` returns the log records of a trace:
+//!   records carrying its trace ID, and records carrying only the span ID of
+//!   one of its spans
+//! - `GET /api/v1/logs/patterns` lists the log patterns the collector mined
+//!   from log bodies, with their record counts in a window and the trend
+//!   against the window before, so new or surging error patterns stand out
+//!   after a deployment
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `read:traces`
+//! - Logs and patterns are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::logs::*;
+use crate::models::{
+    AppState, ErrorResponse, PaginationMetadata, ResponseMetadata, ResponseStatus,
+};
+use crate::services::tenant::with_tenant;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{Duration, DurationRound, Utc};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{error, info, instrument};
+
+/// Columns of [`LogEntry`]
+const LOG_COLUMNS: &str = "id, timestamp, observed_timestamp, severity_number, severity_text, \
+     body, service_name, trace_id, span_id, attributes, resource_attributes, scope_name, \
+     pattern_id";
+
+/// Organization of a log record: its own `org_id` attribute, else its
+/// resource's
+const LOG_ORG: &str = "COALESCE(attributes->>'org_id', resource_attributes->>'org_id')";
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create log routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/api/v1/logs", get(list_logs))
+        .route("/api/v1/logs/patterns", get(get_log_patterns))
+        .route("/api/v1/traces/:trace_id/logs", get(get_trace_logs))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/logs
+// ============================================================================
+
+/// GET /api/v1/logs - Search log records
+///
+/// ## Query Parameters
+/// - `start_time`: Start of time range (ISO 8601) - default: 24 hours before `end_time`
+/// - `end_time`: End of time range (ISO 8601) - default: now
+/// - `service_name`: Filter by service
+/// - `min_severity`: `trace`, `debug`, `info`, `warn`, `error` or `fatal`
+/// - `search`: Text the body contains (case-insensitive)
+/// - `trace_id`: Filter by trace
+/// - `pattern_id`: Filter by log pattern
+/// - `cursor`: Pagination cursor from the previous response
+/// - `limit`: Records per page (1-1000) - default: 100
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/logs?service_name=api&min_severity=warn&search=timeout' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn list_logs(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<LogQuery>,
+) -> Result<Json<PaginatedLogResponse>, ApiError> {
+    let started = Instant::now();
+
+    // Check permissions
+    if !auth.has_permission("read:traces") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read logs".to_string(),
+        ));
+    }
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+    let cursor = request
+        .cursor
+        .as_deref()
+        .map(LogCursor::decode)
+        .transpose()
+        .map_err(|e| ApiError::BadRequest(format!("Invalid cursor: {}", e)))?;
+
+    let end_time = request.end_time.unwrap_or_else(Utc::now);
+    let start_time = request
+        .start_time
+        .unwrap_or_else(|| end_time - Duration::hours(24));
+
+    info!(
+        org_id = %auth.org_id,
+        service_name = ?request.service_name,
+        "Querying logs"
+    );
+
+    let sql = format!(
+        r#"
+        SELECT {LOG_COLUMNS}
+        FROM logs
+        WHERE {LOG_ORG} = $1
+          AND timestamp >= $2
+          AND timestamp < $3
+          AND ($4::TEXT IS NULL OR service_name = $4)
+          AND severity_number >= $5
+          AND ($6::TEXT IS NULL OR body ILIKE $6)
+          AND ($7::TEXT IS NULL OR trace_id = $7)
+          AND ($8::TEXT IS NULL OR pattern_id = $8)
+          AND ($9::TIMESTAMPTZ IS NULL OR (timestamp, id) < ($9, $10::UUID))
+        ORDER BY timestamp DESC, id DESC
+        LIMIT $11
+        "#
+    );
+
+    let mut data = async {
+        let mut tx = with_tenant(&state.db_pool, &auth.org_id).await?;
+        let data = sqlx::query_as::<_, LogEntry>(&sql)
+            .bind(&auth.org_id)
+            .bind(start_time)
+            .bind(end_time)
+            .bind(&request.service_name)
+            .bind(request.min_severity_number())
+            .bind(request.search_pattern())
+            .bind(&request.trace_id)
+            .bind(&request.pattern_id)
+            .bind(cursor.as_ref().map(|cursor| cursor.timestamp))
+            .bind(cursor.as_ref().map(|cursor| cursor.id))
+            .bind(i64::from(request.limit) + 1)
+            .fetch_all(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(data)
+    }
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to query logs");
+        ApiError::Internal(format!("Database query failed: {}", e))
+    })?;
+
+    let has_more = data.len() > request.limit as usize;
+    data.truncate(request.limit as usize);
+    let next_cursor = if has_more {
+        data.last().map(|log| {
+            LogCursor {
+                timestamp: log.timestamp,
+                id: log.id,
+            }
+            .encode()
+        })
+    } else {
+        None
+    };
+
+    let execution_time_ms = started.elapsed().as_millis() as u64;
+    info!(
+        logs_returned = data.len(),
+        has_more, execution_time_ms, "Log query completed"
+    );
+
+    Ok(Json(PaginatedLogResponse {
+        status: ResponseStatus::Success,
+        data,
+        pagination: PaginationMetadata {
+            cursor: next_cursor,
+            has_more,
+            limit: request.limit,
+            total: None,
+        },
+        meta: ResponseMetadata {
+            timestamp: Utc::now(),
+            execution_time_ms,
+            cached: false,
+            version: "1.0".to_string(),
+            request_id: Some(auth.request_id.clone()),
+        },
+    }))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/traces/:trace_id/logs
+// ============================================================================
+
+/// GET /api/v1/traces/:trace_id/logs - Log records of a trace
+///
+/// Returns records whose `trace_id` is the trace, plus records without it
+/// whose `span_id` is one of the trace's spans in `llm_traces`.
+///
+/// ## Query Parameters
+/// - `span_id`: Only records of this span
+/// - `limit`: Maximum records (1-1000) - default: 1000
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/traces/4bf92f3577b34da6a3ce929d0e0e4736/logs' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_trace_logs(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(trace_id): Path<String>,
+    Query(request): Query<TraceLogsQuery>,
+) -> Result<Json<TraceLogsResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("read:traces") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read logs".to_string(),
+        ));
+    }
+
+    // Validate request
+    validate_trace_id(&trace_id).map_err(ApiError::BadRequest)?;
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    info!(org_id = %auth.org_id, %trace_id, "Querying trace logs");
+
+    let sql = format!(
+        r#"
+        SELECT {LOG_COLUMNS}
+        FROM logs
+        WHERE {LOG_ORG} = $1
+          AND (
+              trace_id = $2
+              OR (trace_id IS NULL AND span_id IN (
+                  SELECT span_id FROM llm_traces
+                  WHERE trace_id = $2 AND attributes->>'org_id' = $1
+              ))
+          )
+          AND ($3::TEXT IS NULL OR span_id = $3)
+        ORDER BY timestamp, id
+        LIMIT $4
+        "#
+    );
+
+    let mut logs = async {
+        let mut tx = with_tenant(&state.db_pool, &auth.org_id).await?;
+        let logs = sqlx::query_as::<_, LogEntry>(&sql)
+            .bind(&auth.org_id)
+            .bind(&trace_id)
+            .bind(&request.span_id)
+            .bind(request.limit + 1)
+            .fetch_all(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(logs)
+    }
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to query trace logs");
+        ApiError::Internal(format!("Database query failed: {}", e))
+    })?;
+
+    let truncated = logs.len() > request.limit as usize;
+    logs.truncate(request.limit as usize);
+
+    Ok(Json(TraceLogsResponse {
+        trace_id,
+        logs,
+        truncated,
+    }))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/logs/patterns
+// ============================================================================
+
+/// GET /api/v1/logs/patterns - Log patterns with counts and trend
+///
+/// ## Query Parameters
+/// - `window_hours`: Hours in the window, ending with the current hour - default: 24
+/// - `service_name`: Filter by service
+/// - `min_severity`: `trace`, `debug`, `info`, `warn`, `error` or `fatal`
+/// - `new_only`: Only patterns first seen in the window - default: false
+/// - `limit`: Maximum patterns (1-500) - default: 50
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/logs/patterns?min_severity=error&window_hours=6' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_log_patterns(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<LogPatternsQuery>,
+) -> Result<Json<LogPatternsResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("read:traces") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read log patterns".to_string(),
+        ));
+    }
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    // Counts are hourly, so windows end with the current hour
+    let now = Utc::now();
+    let window_end = now.duration_trunc(Duration::hours(1)).unwrap_or(now) + Duration::hours(1);
+    let window_start = window_end - Duration::hours(request.window_hours);
+    let previous_start = window_start - Duration::hours(request.window_hours);
+
+    info!(
+        org_id = %auth.org_id,
+        window_hours = request.window_hours,
+        "Querying log patterns"
+    );
+
+    let rows = sqlx::query_as::<_, LogPatternRow>(
+        r#"
+        WITH counts AS (
+            SELECT
+                pattern_id,
+                COALESCE(SUM(count) FILTER (WHERE bucket >= $2), 0)::BIGINT AS count,
+                COALESCE(SUM(count) FILTER (WHERE bucket < $2), 0)::BIGINT AS previous_count
+            FROM log_pattern_counts
+            WHERE org_id = $1
+              AND bucket >= $3
+              AND bucket < $4
+            GROUP BY pattern_id
+        )
+        SELECT p.pattern_id, p.service_name, p.template, p.severity_number,
+               p.first_seen, p.last_seen, c.count, c.previous_count
+        FROM counts c
+        JOIN log_patterns p ON p.org_id = $1 AND p.pattern_id = c.pattern_id
+        WHERE c.count > 0
+          AND ($5::TEXT IS NULL OR p.service_name = $5)
+          AND p.severity_number >= $6
+          AND (NOT $7 OR p.first_seen >= $2)
+        ORDER BY c.count DESC, p.pattern_id
+        LIMIT $8
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(window_start)
+    .bind(previous_start)
+    .bind(window_end)
+    .bind(&request.service_name)
+    .bind(request.min_severity_number())
+    .bind(request.new_only)
+    .bind(request.limit)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to query log patterns");
+        ApiError::Internal(format!("Database query failed: {}", e))
+    })?;
+
+    let patterns: Vec<LogPatternSummary> = rows
+        .into_iter()
+        .map(|row| build_log_pattern(row, window_start))
+        .collect();
+
+    info!(patterns = patterns.len(), "Log pattern query completed");
+
+    Ok(Json(LogPatternsResponse {
+        window_start,
+        window_end,
+        patterns,
+    }))
+}
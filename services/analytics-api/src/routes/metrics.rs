@@ -4,11 +4,15 @@
 //! - `GET /api/v1/metrics` - Time-series metrics query
 //! - `GET /api/v1/metrics/summary` - Metrics summary with period comparison
 //! - `POST /api/v1/metrics/query` - Custom metrics query with advanced features
+//! - `GET /api/v1/metrics/exemplars` - Sample traces behind histogram buckets
+//! - `GET /api/v1/metrics/cardinality` - Metrics with the most attribute sets
 //!
 //! ## Features
 //! - Automatic continuous aggregate table selection for performance
-//! - Fall-back to raw data for percentile queries
-//! - Redis caching with intelligent cache keys
+//! - Percentile queries merged from collector histograms, or rolled up from
+//!   `percentile_agg` sketches with a `PERCENTILE_CONT` fallback
+//! - Summary top items read from the daily top-N rollups
+//! - Redis caching with stale-while-revalidate and a `Cache-Status` header
 //! - Full auth and permission checking
 //! - SQL injection prevention via parameterized queries
 //! - Query complexity limits
@@ -26,23 +30,25 @@
 //! - Query complexity limits
 
 use crate::middleware::AuthContext;
+use crate::models::federation::Scope;
 use crate::models::metrics::*;
 use crate::models::{AppState, ErrorResponse};
+use crate::services::query_cache::{CachedJson, FreshnessPolicy};
+use crate::services::top_n::{self, TopNFilters, TopNGrouping, TopNOrder, TopNQuery};
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
+    extract::{Query, RawQuery, State},
+    http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
 use chrono::{DateTime, Duration, Utc};
-use redis::AsyncCommands;
 use serde::Deserialize;
 use serde_json::json;
 use sqlx::{PgPool, Row};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use tracing::{error, info, instrument, warn};
+use tracing::{error, info, instrument};
 
 // ============================================================================
 // Router Configuration
@@ -54,8 +60,16 @@ pub fn routes() -> Router<Arc<AppState>> {
         .route("/api/v1/metrics", get(get_metrics))
         .route("/api/v1/metrics/summary", get(get_metrics_summary))
         .route("/api/v1/metrics/query", post(query_custom_metrics))
+        .route("/api/v1/metrics/exemplars", get(get_metric_exemplars))
+        .route("/api/v1/metrics/cardinality", get(get_metric_cardinality))
 }
 
+/// Time-series results are fresh for a minute and served stale for ten
+const METRICS_FRESHNESS: FreshnessPolicy = FreshnessPolicy::new(60, 600);
+
+/// Summaries compare whole periods and change slowly
+const SUMMARY_FRESHNESS: FreshnessPolicy = FreshnessPolicy::new(300, 1800);
+
 // ============================================================================
 // API Error Type
 // ============================================================================
@@ -69,6 +83,19 @@ pub enum ApiError {
     Internal(String),
 }
 
+impl ApiError {
+    /// Message of the error, reported for a failed region
+    fn into_message(self) -> String {
+        match self {
+            ApiError::BadRequest(msg)
+            | ApiError::Unauthorized(msg)
+            | ApiError::Forbidden(msg)
+            | ApiError::NotFound(msg)
+            | ApiError::Internal(msg) => msg,
+        }
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status, error_type, message) = match self {
@@ -109,8 +136,14 @@ impl IntoResponse for ApiError {
 /// - environment: Filter by environment (optional)
 /// - user_id: Filter by user ID (optional)
 /// - group_by: Comma-separated dimensions (e.g., "provider,model")
-/// - aggregation: Aggregation function (avg, sum, min, max, count, p50, p95, p99)
-/// - include_percentiles: Whether to include percentile calculations (slower)
+/// - aggregation: Aggregation function (avg, sum, min, max, count, p50, p90, p95, p99)
+/// - include_percentiles: Whether to include p50/p90/p95/p99 columns (slower)
+///
+/// Percentile queries read collector histograms where they cover the request
+/// (duration and cost by provider and model), then the hourly `percentile_agg`
+/// sketches, and fall back to `PERCENTILE_CONT` on raw traces for minute
+/// buckets, user breakdowns or databases without the TimescaleDB toolkit.
+/// `metadata.data_source` reports which was used.
 ///
 /// ## Examples
 ///
@@ -128,12 +161,17 @@ impl IntoResponse for ApiError {
 /// ```
 /// GET /api/v1/metrics?metrics=total_cost,request_count&interval=1day&group_by=provider,model
 /// ```
+///
+/// Latency percentiles by environment:
+/// ```
+/// GET /api/v1/metrics?metrics=duration,time_to_first_token&interval=1hour&group_by=environment&include_percentiles=true
+/// ```
 #[instrument(skip(state, auth))]
 async fn get_metrics(
     State(state): State<Arc<AppState>>,
     auth: AuthContext,
     Query(params): Query<MetricsQueryParams>,
-) -> Result<Json<MetricsResponse>, ApiError> {
+) -> Result<CachedJson<MetricsResponse>, ApiError> {
     // Check permissions
     if !auth.has_permission("metrics:read") {
         return Err(ApiError::Forbidden(
@@ -152,28 +190,25 @@ async fn get_metrics(
         "Querying metrics"
     );
 
-    // Generate cache key
+    // Serve from cache, refreshing stale results in the background
     let cache_key = generate_metrics_cache_key(&request, &auth.organization_id);
-
-    // Try cache
-    if let Ok(cached) = try_get_from_cache(&state, &cache_key).await {
-        info!("Returning cached metrics");
-        return Ok(Json(cached));
-    }
-
-    // Execute query
-    let response = execute_metrics_query(&state.db_pool, &request, &auth.organization_id).await?;
-
-    // Cache result
-    cache_metrics_response(&state, &cache_key, &response).await;
+    let pool = state.db_pool.clone();
+    let org_id = auth.organization_id.clone();
+    let (response, cache_status) = state
+        .query_cache
+        .get_or_compute(&cache_key, METRICS_FRESHNESS, move || async move {
+            execute_metrics_query(&pool, &request, &org_id).await
+        })
+        .await?;
 
     info!(
         data_points = response.data.len(),
         data_source = %response.metadata.data_source,
+        cache_status = ?cache_status,
         "Metrics query completed"
     );
 
-    Ok(Json(response))
+    Ok(CachedJson(response, cache_status))
 }
 
 /// Helper struct for query params (axum can't directly deserialize complex enums)
@@ -268,18 +303,22 @@ impl MetricsQueryParams {
 /// - model: Filter by model (optional)
 /// - environment: Filter by environment (optional)
 /// - compare_previous_period: Whether to include previous period comparison - default: true
+/// - scope: `region` for this API's region, `global` to merge every configured
+///   region - default: region
 ///
 /// ## Example
 ///
 /// ```
 /// GET /api/v1/metrics/summary?start_time=2025-01-01T00:00:00Z&end_time=2025-01-02T00:00:00Z
 /// ```
-#[instrument(skip(state, auth))]
+#[instrument(skip(state, auth, headers, raw_query))]
 async fn get_metrics_summary(
     State(state): State<Arc<AppState>>,
     auth: AuthContext,
+    headers: HeaderMap,
+    RawQuery(raw_query): RawQuery,
     Query(params): Query<SummaryQueryParams>,
-) -> Result<Json<MetricsSummaryResponse>, ApiError> {
+) -> Result<CachedJson<MetricsSummaryResponse>, ApiError> {
     // Check permissions
     if !auth.has_permission("metrics:read") {
         return Err(ApiError::Forbidden(
@@ -325,37 +364,65 @@ async fn get_metrics_summary(
         params.environment.as_deref().unwrap_or("all")
     );
 
-    // Try cache
-    if let Ok(cached) = try_get_from_cache(&state, &cache_key).await {
-        info!("Returning cached metrics summary");
-        return Ok(Json(cached));
+    // Serve from cache, refreshing stale results in the background
+    let pool = state.db_pool.clone();
+    let org_id = auth.organization_id.clone();
+    let regional_params = params.clone();
+    let (mut response, cache_status) = state
+        .query_cache
+        .get_or_compute(&cache_key, SUMMARY_FRESHNESS, move || async move {
+            execute_metrics_summary(&pool, &org_id, start_time, end_time, &params).await
+        })
+        .await?;
+
+    // Merge the other regions' summaries for the global scope
+    if regional_params.scope == Scope::Global {
+        let remote = state
+            .federation
+            .query_regions(
+                "/api/v1/metrics/summary",
+                raw_query.as_deref(),
+                headers.get(header::AUTHORIZATION),
+                |pool| {
+                    let params = &regional_params;
+                    let org_id = &auth.organization_id;
+                    async move {
+                        execute_metrics_summary(&pool, org_id, start_time, end_time, params)
+                            .await
+                            .map_err(ApiError::into_message)
+                    }
+                },
+            )
+            .await;
+
+        let (mut summaries, federation) = state.federation.collect(response, remote);
+        let local = summaries.remove(0);
+        response = local.merge(summaries, TOP_ITEMS_LIMIT as usize);
+        response.federation = Some(federation);
     }
 
-    // Execute summary queries
-    let current_period = query_period_summary(
-        &state.db_pool,
-        &auth.organization_id,
-        start_time,
-        end_time,
-        &params,
-    )
-    .await?;
+    info!(cache_status = ?cache_status, "Metrics summary query completed");
+
+    Ok(CachedJson(response, cache_status))
+}
+
+/// Execute the summary queries for a period
+async fn execute_metrics_summary(
+    pool: &PgPool,
+    org_id: &str,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    params: &SummaryQueryParams,
+) -> Result<MetricsSummaryResponse, ApiError> {
+    let current_period = query_period_summary(pool, org_id, start_time, end_time, params).await?;
 
     let (previous_period, changes) = if params.compare_previous_period {
-        let period_duration = duration;
         let prev_end = start_time;
-        let prev_start = prev_end - period_duration;
-
-        let prev_summary = query_period_summary(
-            &state.db_pool,
-            &auth.organization_id,
-            prev_start,
-            prev_end,
-            &params,
-        )
-        .await?;
+        let prev_start = prev_end - (end_time - start_time);
+
+        let prev_summary = query_period_summary(pool, org_id, prev_start, prev_end, params).await?;
 
-        let changes = calculate_period_changes(&current_period, &prev_summary);
+        let changes = PeriodChanges::between(&current_period, &prev_summary);
 
         (Some(prev_summary), Some(changes))
     } else {
@@ -363,42 +430,22 @@ async fn get_metrics_summary(
     };
 
     // Query top items
-    let top_items = query_top_items(
-        &state.db_pool,
-        &auth.organization_id,
-        start_time,
-        end_time,
-        &params,
-    )
-    .await?;
+    let top_items = query_top_items(pool, org_id, start_time, end_time, params).await?;
 
     // Query quality metrics
-    let quality = query_quality_summary(
-        &state.db_pool,
-        &auth.organization_id,
-        start_time,
-        end_time,
-        &params,
-    )
-    .await?;
+    let quality = query_quality_summary(pool, org_id, start_time, end_time, params).await?;
 
-    let response = MetricsSummaryResponse {
+    Ok(MetricsSummaryResponse {
         current_period,
         previous_period,
         changes,
         top_items,
         quality,
-    };
-
-    // Cache result
-    cache_metrics_summary(&state, &cache_key, &response).await;
-
-    info!("Metrics summary query completed");
-
-    Ok(Json(response))
+        federation: None,
+    })
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 struct SummaryQueryParams {
     start_time: Option<DateTime<Utc>>,
     end_time: Option<DateTime<Utc>>,
@@ -407,6 +454,8 @@ struct SummaryQueryParams {
     environment: Option<String>,
     #[serde(default = "default_true")]
     compare_previous_period: bool,
+    #[serde(default)]
+    scope: Scope,
 }
 
 fn default_true() -> bool {
@@ -452,7 +501,7 @@ async fn query_custom_metrics(
     State(state): State<Arc<AppState>>,
     auth: AuthContext,
     Json(request): Json<CustomMetricsQueryRequest>,
-) -> Result<Json<CustomMetricsResponse>, ApiError> {
+) -> Result<CachedJson<CustomMetricsResponse>, ApiError> {
     // Check permissions
     if !auth.has_permission("metrics:query") {
         return Err(ApiError::Forbidden(
@@ -470,36 +519,322 @@ async fn query_custom_metrics(
         "Executing custom metrics query"
     );
 
-    // Generate cache key
+    // Serve from cache, refreshing stale results in the background
     let cache_key = generate_custom_query_cache_key(&request, &auth.organization_id);
+    let pool = state.db_pool.clone();
+    let org_id = auth.organization_id.clone();
+    let (response, cache_status) = state
+        .query_cache
+        .get_or_compute(&cache_key, METRICS_FRESHNESS, move || async move {
+            execute_custom_metrics_query(&pool, &request, &org_id).await
+        })
+        .await?;
+
+    info!(
+        rows = response.data.len(),
+        cache_status = ?cache_status,
+        "Custom metrics query completed"
+    );
+
+    Ok(CachedJson(response, cache_status))
+}
+
+// ============================================================================
+// Endpoint 4: GET /api/v1/metrics/exemplars
+// ============================================================================
+
+/// GET /api/v1/metrics/exemplars - Sample traces behind histogram buckets
+///
+/// The collector attaches the latest request in each latency and cost
+/// histogram bucket as an exemplar. This endpoint returns those exemplars so
+/// a spike on a chart can link to concrete traces.
+///
+/// Query Parameters:
+/// - metric: Histogram metric name (llm.request.duration, llm.request.cost)
+/// - start_time: Start of time range (ISO 8601) - default: 1 hour ago
+/// - end_time: End of time range (ISO 8601) - default: now
+/// - min_value / max_value: Value range, e.g. the bucket of the spike (optional)
+/// - provider: Filter by provider (optional)
+/// - model: Filter by model (optional)
+/// - limit: Maximum exemplars to return (1-100) - default: 20
+///
+/// ## Example
+///
+/// Traces behind a latency spike above 5 seconds:
+/// ```
+/// GET /api/v1/metrics/exemplars?metric=llm.request.duration&min_value=5000&start_time=2025-01-01T10:00:00Z&end_time=2025-01-01T10:05:00Z
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_metric_exemplars(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<ExemplarsQueryRequest>,
+) -> Result<Json<ExemplarsResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read metrics".to_string(),
+        ));
+    }
+
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let end_time = request.end_time.unwrap_or_else(Utc::now);
+    let start_time = request
+        .start_time
+        .unwrap_or_else(|| end_time - Duration::hours(1));
+
+    info!(
+        org_id = %auth.org_id,
+        metric = %request.metric,
+        "Querying metric exemplars"
+    );
+
+    let exemplars = query_exemplars(&state.db_pool, &request, start_time, end_time).await?;
+
+    info!(exemplars = exemplars.len(), "Exemplar query completed");
+
+    Ok(Json(ExemplarsResponse {
+        metric: request.metric,
+        start_time,
+        end_time,
+        exemplars,
+    }))
+}
+
+// ============================================================================
+// Endpoint 5: GET /api/v1/metrics/cardinality
+// ============================================================================
 
-    // Try cache
-    if let Ok(cached) = try_get_from_cache(&state, &cache_key).await {
-        info!("Returning cached custom metrics query");
-        return Ok(Json(cached));
+/// GET /api/v1/metrics/cardinality - Metrics with the most attribute sets
+///
+/// Unbounded attribute values such as user IDs make every data point a new
+/// series. This endpoint lists the metrics with the most distinct attribute
+/// sets in the window, the attribute keys with the most distinct values, and
+/// how many data points the storage cardinality limits rewrote to
+/// `__overflow__`.
+///
+/// Query Parameters:
+/// - window_hours: Hours to inspect, ending now (1-168) - default: 24
+/// - service_name: Filter by service (optional)
+/// - limit: Maximum metrics to return (1-100) - default: 20
+///
+/// ## Example
+/// ```
+/// GET /api/v1/metrics/cardinality?window_hours=6&limit=10
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_metric_cardinality(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<CardinalityReportRequest>,
+) -> Result<Json<CardinalityReportResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read metrics".to_string(),
+        ));
     }
 
-    // Execute custom query
-    let response =
-        execute_custom_metrics_query(&state.db_pool, &request, &auth.organization_id).await?;
+    request.validate().map_err(ApiError::BadRequest)?;
 
-    // Cache result
-    cache_custom_metrics_response(&state, &cache_key, &response).await;
+    let window_end = Utc::now();
+    let window_start = window_end - Duration::hours(request.window_hours);
 
     info!(
-        rows = response.data.len(),
-        "Custom metrics query completed"
+        window_hours = request.window_hours,
+        service_name = ?request.service_name,
+        "Querying metric cardinality"
     );
 
-    Ok(Json(response))
+    let metrics =
+        query_metric_cardinality(&state.db_pool, &request, window_start, window_end).await?;
+
+    info!(
+        metrics = metrics.len(),
+        "Metric cardinality query completed"
+    );
+
+    Ok(Json(CardinalityReportResponse {
+        window_start,
+        window_end,
+        metrics,
+    }))
 }
 
 // ============================================================================
 // Query Execution Functions
 // ============================================================================
 
+/// Query the metrics with the most attribute sets, with their top attribute keys
+async fn query_metric_cardinality(
+    pool: &PgPool,
+    request: &CardinalityReportRequest,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+) -> Result<Vec<MetricCardinality>, ApiError> {
+    // Overflowed points hold "__overflow__" as an attribute key (series limit)
+    // or value (attribute limit)
+    let rows = sqlx::query_as::<_, MetricCardinalityRow>(
+        r#"
+        SELECT
+            m.id AS metric_id,
+            m.name,
+            m.service_name,
+            COUNT(DISTINCT mdp.attributes) AS series,
+            COUNT(*) AS data_points,
+            COUNT(*) FILTER (WHERE mdp.attributes::TEXT LIKE '%"\_\_overflow\_\_"%') AS overflow_points
+        FROM metric_data_points mdp
+        JOIN metrics m ON mdp.metric_id = m.id
+        WHERE mdp.timestamp >= $1
+          AND mdp.timestamp < $2
+          AND ($3::TEXT IS NULL OR m.service_name = $3)
+        GROUP BY m.id, m.name, m.service_name
+        ORDER BY series DESC, m.name
+        LIMIT $4
+        "#,
+    )
+    .bind(window_start)
+    .bind(window_end)
+    .bind(&request.service_name)
+    .bind(request.limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Metric cardinality query failed: {}", e);
+        ApiError::Internal("Failed to query metric cardinality".to_string())
+    })?;
+
+    if rows.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let metric_ids: Vec<_> = rows.iter().map(|row| row.metric_id).collect();
+    let attribute_rows = sqlx::query_as::<_, AttributeCardinalityRow>(
+        r#"
+        SELECT
+            mdp.metric_id,
+            a.key,
+            COUNT(DISTINCT a.value) AS distinct_values,
+            BOOL_OR(a.value = '__overflow__') AS overflowed
+        FROM metric_data_points mdp
+        CROSS JOIN LATERAL jsonb_each_text(
+            CASE WHEN jsonb_typeof(mdp.attributes) = 'object' THEN mdp.attributes ELSE '{}'::JSONB END
+        ) AS a(key, value)
+        WHERE mdp.metric_id = ANY($1)
+          AND mdp.timestamp >= $2
+          AND mdp.timestamp < $3
+          AND a.key <> '__overflow__'
+        GROUP BY mdp.metric_id, a.key
+        "#,
+    )
+    .bind(&metric_ids)
+    .bind(window_start)
+    .bind(window_end)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Attribute cardinality query failed: {}", e);
+        ApiError::Internal("Failed to query metric cardinality".to_string())
+    })?;
+
+    let mut attributes: HashMap<_, Vec<_>> = HashMap::new();
+    for row in attribute_rows {
+        attributes.entry(row.metric_id).or_default().push(row);
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let metric_attributes = attributes.remove(&row.metric_id).unwrap_or_default();
+            build_metric_cardinality(row, metric_attributes)
+        })
+        .collect())
+}
+
+/// Query exemplars stored on metric data points
+async fn query_exemplars(
+    pool: &PgPool,
+    request: &ExemplarsQueryRequest,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+) -> Result<Vec<MetricExemplar>, ApiError> {
+    let rows = sqlx::query_as::<_, ExemplarRow>(
+        r#"
+        SELECT
+            e.exemplar,
+            mdp.attributes->>'provider' AS provider,
+            mdp.attributes->>'model' AS model
+        FROM metric_data_points mdp
+        JOIN metrics m ON mdp.metric_id = m.id
+        CROSS JOIN LATERAL jsonb_array_elements(mdp.exemplars) AS e(exemplar)
+        WHERE m.name = $1
+          AND mdp.exemplars IS NOT NULL
+          AND mdp.timestamp >= $2
+          AND mdp.timestamp <= $3
+          AND ($4::DOUBLE PRECISION IS NULL OR (e.exemplar->>'value')::DOUBLE PRECISION >= $4)
+          AND ($5::DOUBLE PRECISION IS NULL OR (e.exemplar->>'value')::DOUBLE PRECISION <= $5)
+          AND ($6::TEXT IS NULL OR mdp.attributes->>'provider' = $6)
+          AND ($7::TEXT IS NULL OR mdp.attributes->>'model' = $7)
+        ORDER BY e.exemplar->>'timestamp' DESC
+        LIMIT $8
+        "#,
+    )
+    .bind(&request.metric)
+    .bind(start_time)
+    .bind(end_time)
+    .bind(request.min_value)
+    .bind(request.max_value)
+    .bind(&request.provider)
+    .bind(&request.model)
+    .bind(request.limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Exemplar query failed: {}", e);
+        ApiError::Internal("Failed to query exemplars".to_string())
+    })?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| to_metric_exemplar(row.exemplar, row.provider, row.model))
+        .collect())
+}
+
+/// Convert a stored exemplar into its API form, skipping malformed entries
+fn to_metric_exemplar(
+    exemplar: serde_json::Value,
+    provider: Option<String>,
+    model: Option<String>,
+) -> Option<MetricExemplar> {
+    let trace_id = exemplar.get("trace_id")?.as_str()?.to_string();
+    let timestamp = exemplar
+        .get("timestamp")?
+        .as_str()?
+        .parse::<DateTime<Utc>>()
+        .ok()?;
+
+    Some(MetricExemplar {
+        trace_url: format!("/api/v1/traces/{}", trace_id),
+        span_id: exemplar
+            .get("span_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        value: exemplar.get("value")?.as_f64()?,
+        attributes: exemplar
+            .get("attributes")
+            .cloned()
+            .unwrap_or_else(|| json!({})),
+        trace_id,
+        timestamp,
+        provider,
+        model,
+    })
+}
+
 /// Execute metrics query
-async fn execute_metrics_query(
+pub(crate) async fn execute_metrics_query(
     pool: &PgPool,
     request: &MetricsQueryRequest,
     org_id: &str,
@@ -516,10 +851,19 @@ async fn execute_metrics_query(
             .iter()
             .any(|d| !d.available_in_aggregates());
 
-    let (data_source, data) = if use_raw_data {
-        // Query raw data (slower but supports percentiles)
-        let rows = query_raw_metrics(pool, request, org_id).await?;
-        ("raw", rows)
+    let (data_source, data) = if use_raw_data && request.histogram_supported() {
+        // Merge collector histograms (supports percentiles)
+        let rows = query_histogram_metrics(pool, request, org_id).await?;
+        ("histogram", rows)
+    } else if use_raw_data {
+        // Roll up percentile sketches, or sort raw traces without the toolkit
+        let source = if request.sketch_supported() && percentile_sketches_available(pool).await? {
+            PercentileSource::Sketch
+        } else {
+            PercentileSource::Raw
+        };
+        let rows = query_percentile_metrics(pool, request, org_id, source).await?;
+        (source.as_str(), rows)
     } else {
         // Query aggregate tables (faster)
         let rows = query_aggregate_metrics(pool, request, org_id).await?;
@@ -693,18 +1037,320 @@ async fn query_aggregate_metrics(
     Ok(data_points)
 }
 
-/// Query from raw traces table (for percentiles)
-async fn query_raw_metrics(
+/// Items per summary top list
+const TOP_ITEMS_LIMIT: i64 = 10;
+
+/// Filters shared by the histogram queries. Histograms are scoped to the
+/// organization through their `org_id` data point attribute.
+const HISTOGRAM_FILTERS: &str = r#"
+    m.name = $2
+    AND mdp.attributes->>'org_id' = $3
+    AND mdp.timestamp >= $4
+    AND mdp.timestamp < $5
+    AND ($6::TEXT IS NULL OR mdp.attributes->>'provider' = $6)
+    AND ($7::TEXT IS NULL OR mdp.attributes->>'model' = $7)
+    AND mdp.buckets IS NOT NULL
+"#;
+
+/// Query percentiles and other aggregations from collector histograms
+///
+/// Latency and cost histograms hold delta counts per provider and model, so
+/// each time bucket and group is answered by summing bucket counts per
+/// boundary instead of sorting every raw trace. Only called for requests
+/// where `histogram_supported()` holds.
+async fn query_histogram_metrics(
     pool: &PgPool,
     request: &MetricsQueryRequest,
     org_id: &str,
 ) -> Result<Vec<MetricDataPoint>, ApiError> {
-    // This would query llm_traces directly for percentile calculations
-    // Implementation similar to query_aggregate_metrics but using PERCENTILE_CONT
-    // For now, return error suggesting to use aggregates
-    Err(ApiError::BadRequest(
-        "Percentile queries not yet implemented. Use aggregate queries for now.".to_string(),
-    ))
+    let interval = request.interval.to_pg_interval();
+    let start_time = request
+        .start_time
+        .unwrap_or_else(|| Utc::now() - Duration::days(1));
+    let end_time = request.end_time.unwrap_or_else(Utc::now);
+    let aggregation = request
+        .aggregation
+        .as_ref()
+        .unwrap_or(&AggregationFunction::Avg);
+
+    let group = |dim: DimensionName| {
+        if request.group_by.contains(&dim) {
+            format!("mdp.attributes->>'{}'", dim.to_column_name())
+        } else {
+            "NULL::TEXT".to_string()
+        }
+    };
+    let groups = format!(
+        "time_bucket($1::INTERVAL, mdp.timestamp) AS bucket, {} AS provider, {} AS model",
+        group(DimensionName::Provider),
+        group(DimensionName::Model)
+    );
+    let buckets_sql = format!(
+        r#"
+        SELECT
+            {},
+            (b.bucket->>'boundary')::DOUBLE PRECISION AS boundary,
+            SUM((b.bucket->>'count')::BIGINT)::BIGINT AS count
+        FROM metric_data_points mdp
+        JOIN metrics m ON mdp.metric_id = m.id
+        CROSS JOIN LATERAL jsonb_array_elements(mdp.buckets) AS b(bucket)
+        WHERE {}
+        GROUP BY 1, 2, 3, 4
+        "#,
+        groups, HISTOGRAM_FILTERS
+    );
+    let totals_sql = format!(
+        r#"
+        SELECT
+            {},
+            COALESCE(SUM(mdp.count), 0)::BIGINT AS count,
+            COALESCE(SUM(mdp.sum), 0)::DOUBLE PRECISION AS sum,
+            CASE WHEN COUNT(mdp.min) = COUNT(*) THEN MIN(mdp.min) END AS min,
+            CASE WHEN COUNT(mdp.max) = COUNT(*) THEN MAX(mdp.max) END AS max
+        FROM metric_data_points mdp
+        JOIN metrics m ON mdp.metric_id = m.id
+        WHERE {}
+        GROUP BY 1, 2, 3
+        "#,
+        groups, HISTOGRAM_FILTERS
+    );
+
+    let mut points: BTreeMap<HistogramKey, HashMap<String, MetricValue>> = BTreeMap::new();
+    for metric in &request.metrics {
+        let Some((histogram, suffix)) = metric.histogram() else {
+            continue;
+        };
+
+        let totals = sqlx::query_as::<_, HistogramTotalsRow>(&totals_sql)
+            .bind(interval)
+            .bind(histogram)
+            .bind(org_id)
+            .bind(start_time)
+            .bind(end_time)
+            .bind(&request.provider)
+            .bind(&request.model)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to query histogram totals");
+                ApiError::Internal(format!("Database query failed: {}", e))
+            })?;
+        let buckets = sqlx::query_as::<_, HistogramBucketRow>(&buckets_sql)
+            .bind(interval)
+            .bind(histogram)
+            .bind(org_id)
+            .bind(start_time)
+            .bind(end_time)
+            .bind(&request.provider)
+            .bind(&request.model)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to query histogram buckets");
+                ApiError::Internal(format!("Database query failed: {}", e))
+            })?;
+
+        for (key, merged) in merge_histogram_rows(totals, buckets) {
+            let values = points.entry(key).or_default();
+            values.insert(
+                metric.to_column_name().to_string(),
+                merged.aggregate(aggregation),
+            );
+            if request.include_percentiles {
+                for (label, percentile) in [
+                    ("p50", AggregationFunction::P50),
+                    ("p90", AggregationFunction::P90),
+                    ("p95", AggregationFunction::P95),
+                    ("p99", AggregationFunction::P99),
+                ] {
+                    values.insert(
+                        format!("{}_{}", label, suffix),
+                        merged.aggregate(&percentile),
+                    );
+                }
+            }
+        }
+    }
+
+    // Newest buckets first, as for aggregate queries
+    Ok(points
+        .into_iter()
+        .rev()
+        .map(|((timestamp, provider, model), metrics)| {
+            let mut dimensions = HashMap::new();
+            for (dim, value) in [
+                (DimensionName::Provider, provider),
+                (DimensionName::Model, model),
+            ] {
+                if request.group_by.contains(&dim) {
+                    dimensions.insert(dim.to_column_name().to_string(), value.unwrap_or_default());
+                }
+            }
+            MetricDataPoint {
+                timestamp,
+                dimensions,
+                metrics,
+            }
+        })
+        .collect())
+}
+
+/// Check whether the `llm_percentiles_1hour` sketch aggregate exists
+///
+/// Migration 032 only creates it when the TimescaleDB toolkit is available.
+async fn percentile_sketches_available(pool: &PgPool) -> Result<bool, ApiError> {
+    sqlx::query_scalar::<_, bool>("SELECT to_regclass('llm_percentiles_1hour') IS NOT NULL")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to check for percentile sketches");
+            ApiError::Internal(format!("Database query failed: {}", e))
+        })
+}
+
+/// Query percentiles and other aggregations from sketches or raw traces
+///
+/// Used for the metrics, dimensions and filters collector histograms do not
+/// cover. Sketches are merged with `rollup` per time bucket and group, so
+/// percentiles over days never touch raw rows; on vanilla Postgres the same
+/// values come from `PERCENTILE_CONT` over `llm_traces`.
+async fn query_percentile_metrics(
+    pool: &PgPool,
+    request: &MetricsQueryRequest,
+    org_id: &str,
+    source: PercentileSource,
+) -> Result<Vec<MetricDataPoint>, ApiError> {
+    if let Some(metric) = request
+        .metrics
+        .iter()
+        .find(|m| m.percentile_column().is_none())
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Percentiles are not available for metric {:?}; supported: duration, time_to_first_token, total_cost, total_tokens",
+            metric
+        )));
+    }
+
+    let interval = request.interval.to_pg_interval();
+    let start_time = request
+        .start_time
+        .unwrap_or_else(|| Utc::now() - Duration::days(1));
+    let end_time = request.end_time.unwrap_or_else(Utc::now);
+    let aggregation = request
+        .aggregation
+        .as_ref()
+        .unwrap_or(&AggregationFunction::Avg);
+    let (time_column, org_filter) = match source {
+        PercentileSource::Sketch => ("bucket", "org_id = $2"),
+        PercentileSource::Raw => ("ts", "attributes->>'org_id' = $2"),
+    };
+
+    let mut select_fields = vec![format!(
+        "time_bucket($1::INTERVAL, {}) AS timestamp",
+        time_column
+    )];
+    let mut group_by_fields = vec!["timestamp".to_string()];
+    for dim in &request.group_by {
+        select_fields.push(dim.to_column_name().to_string());
+        group_by_fields.push(dim.to_column_name().to_string());
+    }
+
+    let mut value_columns = Vec::new();
+    for metric in &request.metrics {
+        let Some((column, suffix)) = metric.percentile_column() else {
+            continue;
+        };
+        select_fields.push(format!(
+            "{} AS {}",
+            source.aggregate_sql(aggregation, column, suffix),
+            metric.to_column_name()
+        ));
+        value_columns.push(metric.to_column_name().to_string());
+        if request.include_percentiles {
+            for (label, percentile) in [
+                ("p50", AggregationFunction::P50),
+                ("p90", AggregationFunction::P90),
+                ("p95", AggregationFunction::P95),
+                ("p99", AggregationFunction::P99),
+            ] {
+                let alias = format!("{}_{}", label, suffix);
+                select_fields.push(format!(
+                    "{} AS {}",
+                    source.aggregate_sql(&percentile, column, suffix),
+                    alias
+                ));
+                value_columns.push(alias);
+            }
+        }
+    }
+
+    let mut where_clauses = vec![
+        org_filter.to_string(),
+        format!("{} >= $3", time_column),
+        format!("{} < $4", time_column),
+        "($5::TEXT IS NULL OR provider = $5)".to_string(),
+        "($6::TEXT IS NULL OR model = $6)".to_string(),
+        "($7::TEXT IS NULL OR environment = $7)".to_string(),
+    ];
+    if source == PercentileSource::Raw {
+        where_clauses.push("($8::TEXT IS NULL OR user_id = $8)".to_string());
+    }
+
+    let query_str = format!(
+        "SELECT {} FROM {} WHERE {} GROUP BY {} ORDER BY timestamp DESC LIMIT 10000",
+        select_fields.join(", "),
+        source.table(),
+        where_clauses.join(" AND "),
+        group_by_fields.join(", ")
+    );
+
+    info!(query = %query_str, source = source.as_str(), "Executing percentile metrics query");
+
+    let mut query = sqlx::query(&query_str)
+        .bind(interval)
+        .bind(org_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(&request.provider)
+        .bind(&request.model)
+        .bind(&request.environment);
+    if source == PercentileSource::Raw {
+        query = query.bind(&request.user_id);
+    }
+    let rows = query.fetch_all(pool).await.map_err(|e| {
+        error!(error = %e, "Failed to query percentile metrics");
+        ApiError::Internal(format!("Database query failed: {}", e))
+    })?;
+
+    let mut data_points = Vec::with_capacity(rows.len());
+    for row in rows {
+        let timestamp: DateTime<Utc> = row.try_get("timestamp").map_err(|e| {
+            error!(error = %e, "Failed to parse timestamp");
+            ApiError::Internal("Failed to parse query results".to_string())
+        })?;
+
+        let mut dimensions = HashMap::new();
+        for dim in &request.group_by {
+            if let Ok(value) = row.try_get::<Option<String>, _>(dim.to_column_name()) {
+                dimensions.insert(dim.to_column_name().to_string(), value.unwrap_or_default());
+            }
+        }
+
+        let mut metrics = HashMap::new();
+        for col_name in &value_columns {
+            if let Ok(Some(value)) = row.try_get::<Option<f64>, _>(col_name.as_str()) {
+                metrics.insert(col_name.clone(), MetricValue::Float(value));
+            }
+        }
+
+        data_points.push(MetricDataPoint {
+            timestamp,
+            dimensions,
+            metrics,
+        });
+    }
+
+    Ok(data_points)
 }
 
 /// Query period summary
@@ -805,35 +1451,7 @@ async fn query_period_summary(
     })
 }
 
-/// Calculate period-over-period changes
-fn calculate_period_changes(
-    current: &PeriodSummary,
-    previous: &PeriodSummary,
-) -> PeriodChanges {
-    let calc_change = |curr: f64, prev: f64| -> f64 {
-        if prev == 0.0 {
-            if curr > 0.0 {
-                100.0
-            } else {
-                0.0
-            }
-        } else {
-            ((curr - prev) / prev) * 100.0
-        }
-    };
-
-    PeriodChanges {
-        requests_change_pct: calc_change(
-            current.total_requests as f64,
-            previous.total_requests as f64,
-        ),
-        cost_change_pct: calc_change(current.total_cost_usd, previous.total_cost_usd),
-        duration_change_pct: calc_change(current.avg_duration_ms, previous.avg_duration_ms),
-        error_rate_change_pct: calc_change(current.error_rate, previous.error_rate),
-    }
-}
-
-/// Query top items
+/// Query top provider/model pairs by cost, requests, duration and errors
 async fn query_top_items(
     pool: &PgPool,
     org_id: &str,
@@ -841,16 +1459,44 @@ async fn query_top_items(
     end_time: DateTime<Utc>,
     params: &SummaryQueryParams,
 ) -> Result<TopItems, ApiError> {
-    // For brevity, returning empty top items
-    // Full implementation would query by cost, requests, duration, errors
+    let ranking = |order| TopNQuery {
+        start: start_time,
+        end: end_time,
+        grouping: TopNGrouping::ProviderModel,
+        filters: TopNFilters {
+            provider: params.provider.clone(),
+            model: params.model.clone(),
+            environment: params.environment.clone(),
+            user_id: None,
+        },
+        order,
+        limit: TOP_ITEMS_LIMIT,
+    };
+
     Ok(TopItems {
-        by_cost: vec![],
-        by_requests: vec![],
-        by_duration: vec![],
-        by_errors: vec![],
+        by_cost: query_top_list(pool, org_id, ranking(TopNOrder::Cost)).await?,
+        by_requests: query_top_list(pool, org_id, ranking(TopNOrder::Requests)).await?,
+        by_duration: query_top_list(pool, org_id, ranking(TopNOrder::AvgDuration)).await?,
+        by_errors: query_top_list(pool, org_id, ranking(TopNOrder::Errors)).await?,
     })
 }
 
+/// Rank provider/model pairs for one summary top list
+async fn query_top_list(
+    pool: &PgPool,
+    org_id: &str,
+    query: TopNQuery,
+) -> Result<Vec<TopItem>, ApiError> {
+    let rows = top_n::query_top_n(pool, org_id, &query)
+        .await
+        .map_err(|e| {
+            error!(error = %e, order = ?query.order, "Failed to query top items");
+            ApiError::Internal(format!("Database query failed: {}", e))
+        })?;
+
+    Ok(TopItem::ranked(rows, query.order))
+}
+
 /// Query quality summary
 async fn query_quality_summary(
     pool: &PgPool,
@@ -1062,52 +1708,3 @@ fn generate_custom_query_cache_key(request: &CustomMetricsQueryRequest, org_id:
     let json_str = serde_json::to_string(request).unwrap_or_default();
     format!("metrics:custom:{}:{}", org_id, json_str)
 }
-
-/// Try to get from cache
-async fn try_get_from_cache<T: serde::de::DeserializeOwned>(
-    state: &Arc<AppState>,
-    cache_key: &str,
-) -> Result<T, ()> {
-    let mut redis_conn = state.redis_client.get_async_connection().await.map_err(|e| {
-        warn!(error = %e, "Redis connection error");
-    })?;
-
-    let cached: String = redis_conn.get(cache_key).await.map_err(|_| ())?;
-
-    serde_json::from_str(&cached).map_err(|_| ())
-}
-
-/// Cache metrics response
-async fn cache_metrics_response(state: &Arc<AppState>, cache_key: &str, response: &MetricsResponse) {
-    if let Ok(serialized) = serde_json::to_string(response) {
-        if let Ok(mut conn) = state.redis_client.get_async_connection().await {
-            let _: Result<(), _> = conn.set_ex(cache_key, serialized, state.cache_ttl).await;
-        }
-    }
-}
-
-/// Cache metrics summary
-async fn cache_metrics_summary(
-    state: &Arc<AppState>,
-    cache_key: &str,
-    response: &MetricsSummaryResponse,
-) {
-    if let Ok(serialized) = serde_json::to_string(response) {
-        if let Ok(mut conn) = state.redis_client.get_async_connection().await {
-            let _: Result<(), _> = conn.set_ex(cache_key, serialized, state.cache_ttl).await;
-        }
-    }
-}
-
-/// Cache custom metrics response
-async fn cache_custom_metrics_response(
-    state: &Arc<AppState>,
-    cache_key: &str,
-    response: &CustomMetricsResponse,
-) {
-    if let Ok(serialized) = serde_json::to_string(response) {
-        if let Ok(mut conn) = state.redis_client.get_async_connection().await {
-            let _: Result<(), _> = conn.set_ex(cache_key, serialized, state.cache_ttl).await;
-        }
-    }
-}
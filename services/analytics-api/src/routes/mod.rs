@@ -1,7 +1,32 @@
+pub mod admin;
+pub mod audit;
+pub mod concurrency;
+pub mod context;
 pub mod costs;
+pub mod drift;
+pub mod events;
+pub mod experiments;
 pub mod export;
+pub mod grafana;
+pub mod guardrails;
+pub mod jaeger;
+pub mod logs;
 pub mod metrics;
+pub mod model_registry;
 pub mod models;
+pub mod overview;
 pub mod performance;
+pub mod prompt_cache;
+pub mod providers;
+pub mod query;
+pub mod pseudonyms;
+pub mod quarantine;
 pub mod quality;
+pub mod quotas;
+pub mod rate_limits;
+pub mod recommendations;
+pub mod retries;
+pub mod span_names;
+pub mod topology;
 pub mod traces;
+pub mod webhooks;
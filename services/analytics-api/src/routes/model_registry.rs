@@ -0,0 +1,324 @@
+//! # Model Registry API Routes
+//!
+//! Fine-tuned and self-deployed models report IDs the bundled pricing does
+//! not know. Organizations register them here with their own pricing,
+//! aliases and base model; the collector's cost processor then prices their
+//! spans, and usage rolls up to the base model.
+//!
+//! ## Endpoints
+//! - GET /api/v1/model-registry - Registered models
+//! - POST /api/v1/model-registry - Register a model
+//! - GET /api/v1/model-registry/:id - A registered model
+//! - PUT /api/v1/model-registry/:id - Replace a registration
+//! - DELETE /api/v1/model-registry/:id - Remove a registration
+//! - GET /api/v1/model-registry/rollup - Usage and cost per base model
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Reads require `metrics:read`; writes require `manage:organization`
+//!   (admins by default)
+//! - Registrations and results are organization-scoped
+//!
+//! ## Enforcement
+//! Registrations are loaded into the pricing engine on the ingest host's next
+//! registry sync; spans already recorded keep their cost.
+
+use crate::middleware::AuthContext;
+use crate::models::model_registry::*;
+use crate::models::{AppState, ErrorResponse};
+use crate::services::model_registry::ModelRegistryError;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// Permission to register models for the caller's organization
+const MANAGE_ORGANIZATION: &str = "manage:organization";
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create model registry routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/api/v1/model-registry",
+            get(list_models).post(register_model),
+        )
+        .route("/api/v1/model-registry/rollup", get(get_rollup))
+        .route(
+            "/api/v1/model-registry/:id",
+            get(get_model).put(update_model).delete(delete_model),
+        )
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    Internal(String),
+    ServiceUnavailable(String),
+}
+
+impl From<ModelRegistryError> for ApiError {
+    fn from(e: ModelRegistryError) -> Self {
+        match e {
+            ModelRegistryError::Disabled => ApiError::ServiceUnavailable(e.to_string()),
+            ModelRegistryError::NotFound(_) => ApiError::NotFound(e.to_string()),
+            ModelRegistryError::Conflict(_) => ApiError::Conflict(e.to_string()),
+            ModelRegistryError::Database(_) => {
+                error!(error = %e, "Model registry operation failed");
+                ApiError::Internal(e.to_string())
+            }
+        }
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        error!(error = %err, "Base model rollup query failed");
+        ApiError::Internal(format!("Database query failed: {}", err))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, "not_found", msg),
+            ApiError::Conflict(msg) => (StatusCode::CONFLICT, "conflict", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+            ApiError::ServiceUnavailable(msg) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "service_unavailable", msg)
+            }
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+fn require_read(auth: &AuthContext) -> Result<(), ApiError> {
+    if auth.has_permission("metrics:read") {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(
+            "Insufficient permissions to read the model registry".to_string(),
+        ))
+    }
+}
+
+fn require_manage(auth: &AuthContext) -> Result<(), ApiError> {
+    if auth.has_permission(MANAGE_ORGANIZATION) {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(
+            "Insufficient permissions to manage the model registry".to_string(),
+        ))
+    }
+}
+
+// ============================================================================
+// Endpoint: GET/POST /api/v1/model-registry
+// ============================================================================
+
+/// GET /api/v1/model-registry - The organization's registered models
+#[instrument(skip(state, auth))]
+async fn list_models(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+) -> Result<Json<RegisteredModelListResponse>, ApiError> {
+    // Check permissions
+    require_read(&auth)?;
+
+    let models = state.model_registry.list(&auth.org_id).await?;
+    Ok(Json(RegisteredModelListResponse { models }))
+}
+
+/// POST /api/v1/model-registry - Register a model
+///
+/// Models without pricing cost what their base model costs. The model ID and
+/// aliases may not be registered for another of the organization's models.
+///
+/// ## Example
+/// ```bash
+/// curl -X POST 'http://localhost:8080/api/v1/model-registry' \
+///   -H "Authorization: Bearer $JWT_TOKEN" \
+///   -H "Content-Type: application/json" \
+///   -d '{"model_id": "ft:gpt-4o-mini-2024-07-18:acme::abc123", "aliases": ["support-bot"], "base_model": "gpt-4o-mini", "prompt_cost_per_1k": 0.0003, "completion_cost_per_1k": 0.0012}'
+/// ```
+#[instrument(skip(state, auth, request))]
+async fn register_model(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Json(request): Json<RegisterModelRequest>,
+) -> Result<(StatusCode, Json<RegisteredModel>), ApiError> {
+    // Check permissions
+    require_manage(&auth)?;
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let model = state
+        .model_registry
+        .create(&auth.org_id, &request, &auth.user_id)
+        .await?;
+    Ok((StatusCode::CREATED, Json(model)))
+}
+
+// ============================================================================
+// Endpoint: GET/PUT/DELETE /api/v1/model-registry/:id
+// ============================================================================
+
+/// GET /api/v1/model-registry/:id - A registered model
+#[instrument(skip(state, auth))]
+async fn get_model(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RegisteredModel>, ApiError> {
+    // Check permissions
+    require_read(&auth)?;
+
+    Ok(Json(state.model_registry.get(&auth.org_id, id).await?))
+}
+
+/// PUT /api/v1/model-registry/:id - Replace a registration
+///
+/// Omitted fields are cleared.
+#[instrument(skip(state, auth, request))]
+async fn update_model(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(id): Path<Uuid>,
+    Json(request): Json<RegisterModelRequest>,
+) -> Result<Json<RegisteredModel>, ApiError> {
+    // Check permissions
+    require_manage(&auth)?;
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let model = state
+        .model_registry
+        .update(&auth.org_id, id, &request, &auth.user_id)
+        .await?;
+    Ok(Json(model))
+}
+
+/// DELETE /api/v1/model-registry/:id - Remove a registration
+#[instrument(skip(state, auth))]
+async fn delete_model(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    // Check permissions
+    require_manage(&auth)?;
+
+    state.model_registry.delete(&auth.org_id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/model-registry/rollup
+// ============================================================================
+
+/// GET /api/v1/model-registry/rollup - Usage and cost per base model
+///
+/// Every model reported on spans is resolved to the root of its lineage with
+/// `model_base_model()`: registered base models are followed, then OpenAI
+/// fine-tune IDs (`ft:<base>:...`) resolve to the model they were tuned from.
+///
+/// Query Parameters:
+/// - start_time, end_time: Window (default: the last 30 days, at most 90)
+/// - provider, environment: Filters (optional)
+/// - limit: Maximum base models, most expensive first (default: 100, max: 1000)
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/model-registry/rollup' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_rollup(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<BaseModelRollupRequest>,
+) -> Result<Json<BaseModelRollupResponse>, ApiError> {
+    // Check permissions
+    require_read(&auth)?;
+
+    let (start_time, end_time) = request.validate().map_err(ApiError::BadRequest)?;
+
+    // Resolve each model once, not each span
+    let base_models = sqlx::query_as::<_, BaseModelRollup>(
+        r#"
+        WITH per_model AS (
+            SELECT
+                model,
+                COUNT(*) AS request_count,
+                COALESCE(SUM(prompt_tokens), 0)::BIGINT AS prompt_tokens,
+                COALESCE(SUM(completion_tokens), 0)::BIGINT AS completion_tokens,
+                COALESCE(SUM(total_cost_usd), 0)::FLOAT8 AS total_cost_usd
+            FROM llm_traces
+            WHERE attributes->>'org_id' = $1
+              AND ts >= $2
+              AND ts < $3
+              AND ($4::TEXT IS NULL OR provider = $4)
+              AND ($5::TEXT IS NULL OR environment = $5)
+            GROUP BY model
+        )
+        SELECT
+            model_base_model($1, model) AS base_model,
+            ARRAY_AGG(model ORDER BY total_cost_usd DESC) AS models,
+            SUM(request_count)::BIGINT AS request_count,
+            SUM(prompt_tokens)::BIGINT AS prompt_tokens,
+            SUM(completion_tokens)::BIGINT AS completion_tokens,
+            SUM(total_cost_usd)::FLOAT8 AS total_cost_usd
+        FROM per_model
+        GROUP BY 1
+        ORDER BY total_cost_usd DESC, base_model
+        LIMIT $6
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(start_time)
+    .bind(end_time)
+    .bind(&request.provider)
+    .bind(&request.environment)
+    .bind(request.limit)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    info!(
+        org_id = %auth.org_id,
+        base_models = base_models.len(),
+        "Base model rollup computed"
+    );
+
+    Ok(Json(BaseModelRollupResponse {
+        start_time,
+        end_time,
+        base_models,
+    }))
+}
@@ -0,0 +1,480 @@
+//! # Dashboard Overview API Route
+//!
+//! `GET /api/v1/overview` returns everything an overview dashboard needs in
+//! one response instead of one request per widget.
+//!
+//! ## Features
+//! - Section queries run concurrently against the continuous aggregates
+//!   (latency percentiles fall back to raw traces)
+//! - A failed section is reported in `metadata.unavailable_sections` instead of
+//!   failing the whole response
+//! - Alerts and anomalies are derived from the assembled snapshot
+//! - One Redis cache entry per organization, window and filter (amounts cached in USD)
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires both `costs:read` and `metrics:read`
+//! - Organization-level data isolation
+
+use crate::middleware::AuthContext;
+use crate::models::costs::CurrencyConversion;
+use crate::models::overview::*;
+use crate::models::{AppState, ErrorResponse};
+use crate::services::currency::CurrencyError;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use redis::AsyncCommands;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info, instrument, warn};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create overview routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/api/v1/overview", get(get_overview))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg)
+            }
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+impl From<CurrencyError> for ApiError {
+    fn from(err: CurrencyError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/overview
+// ============================================================================
+
+/// GET /api/v1/overview - Composed dashboard snapshot
+///
+/// Returns cost summary, top models, error rate, latency percentiles, active
+/// alerts and recent anomalies for a time window.
+///
+/// ## Query Parameters
+/// - `start_time`: Start of time range (ISO 8601) - default: 24 hours ago
+/// - `end_time`: End of time range (ISO 8601) - default: now (rounded down to the minute)
+/// - `environment`: Filter by environment
+/// - `top_models`: Number of top models to return (max 20) - default: 5
+/// - `currency`: Reporting currency (ISO 4217) - default: configured display currency
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/overview?environment=production' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_overview(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<OverviewRequest>,
+) -> Result<Json<OverviewResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("costs:read") || !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read overview".to_string(),
+        ));
+    }
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+    let quote = state.currency.resolve(request.currency.as_deref())?;
+
+    // Round the default window so repeated dashboard loads share a cache entry
+    let end_time = match request.end_time {
+        Some(end) => end,
+        None => {
+            let now = Utc::now();
+            now.duration_trunc(Duration::minutes(1)).unwrap_or(now)
+        }
+    };
+    let start_time = request
+        .start_time
+        .unwrap_or_else(|| end_time - Duration::hours(24));
+
+    info!(org_id = %auth.org_id, %start_time, %end_time, "Querying overview");
+
+    let cache_key = format!(
+        "overview:{}:{}:{}:{}:{}",
+        auth.org_id,
+        start_time.to_rfc3339(),
+        end_time.to_rfc3339(),
+        request.environment.as_deref().unwrap_or("all"),
+        request.top_models
+    );
+
+    // Try cache
+    if let Ok(mut conn) = state.redis_client.get_multiplexed_async_connection().await {
+        if let Ok(cached) = conn.get::<_, String>(&cache_key).await {
+            if let Ok(mut response) = serde_json::from_str::<OverviewResponse>(&cached) {
+                info!("Returning cached overview");
+                response.convert_currency(&quote);
+                return Ok(Json(response));
+            }
+        }
+    }
+
+    let mut response =
+        execute_overview(&state.db_pool, &request, &auth.org_id, start_time, end_time).await;
+
+    // Don't cache partial snapshots
+    if response.metadata.unavailable_sections.is_empty() {
+        match serde_json::to_string(&response) {
+            Ok(serialized) => {
+                if let Ok(mut conn) = state.redis_client.get_multiplexed_async_connection().await {
+                    let _: Result<(), _> = conn.set_ex(&cache_key, serialized, state.cache_ttl).await;
+                }
+            }
+            Err(e) => warn!(error = %e, "Failed to serialize overview for cache"),
+        }
+    }
+
+    info!(
+        alerts = response.active_alerts.len(),
+        anomalies = response.recent_anomalies.len(),
+        "Overview completed"
+    );
+
+    response.convert_currency(&quote);
+    Ok(Json(response))
+}
+
+/// Run all section queries concurrently and assemble the response
+async fn execute_overview(
+    pool: &PgPool,
+    request: &OverviewRequest,
+    org_id: &str,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+) -> OverviewResponse {
+    let environment = request.environment.as_deref();
+
+    let (totals, top_models, latency, hourly) = tokio::join!(
+        query_totals(pool, org_id, start_time, end_time, environment),
+        query_top_models(pool, org_id, start_time, end_time, environment, request.top_models),
+        query_latency(pool, org_id, start_time, end_time, environment),
+        query_hourly_series(pool, org_id, start_time, end_time, environment),
+    );
+
+    let mut unavailable_sections = Vec::new();
+
+    let (cost, errors) = section("cost", totals, &mut unavailable_sections).unwrap_or_default();
+    let top_models = section("top_models", top_models, &mut unavailable_sections).unwrap_or_default();
+    let latency = section("latency", latency, &mut unavailable_sections).unwrap_or_default();
+    let recent_anomalies = section("recent_anomalies", hourly, &mut unavailable_sections)
+        .map(|hourly| anomalies_from_series(&hourly, start_time, end_time))
+        .unwrap_or_default();
+
+    let active_alerts = evaluate_alerts(&errors, &latency, &recent_anomalies);
+
+    OverviewResponse {
+        metadata: OverviewMetadata {
+            start_time,
+            end_time,
+            generated_at: Utc::now(),
+            currency: CurrencyConversion::usd(cost.total_cost),
+            unavailable_sections,
+        },
+        cost,
+        top_models,
+        errors,
+        latency,
+        active_alerts,
+        recent_anomalies,
+    }
+}
+
+/// Unwrap a section result, recording the section as unavailable on failure
+fn section<T>(name: &str, result: Result<T, sqlx::Error>, unavailable: &mut Vec<String>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            error!(error = %e, section = name, "Failed to query overview section");
+            unavailable.push(name.to_string());
+            None
+        }
+    }
+}
+
+/// Query cost and error totals for the window and the preceding period
+async fn query_totals(
+    pool: &PgPool,
+    org_id: &str,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    environment: Option<&str>,
+) -> Result<(OverviewCost, OverviewErrors), sqlx::Error> {
+    let previous_start = start_time - (end_time - start_time);
+
+    let query_str = format!(
+        r#"
+        SELECT
+            SUM(total_cost_usd) FILTER (WHERE bucket >= $2) AS total_cost,
+            SUM(total_cost_usd) FILTER (WHERE bucket < $2) AS previous_cost,
+            SUM(request_count) FILTER (WHERE bucket >= $2)::BIGINT AS total_requests,
+            SUM(total_tokens) FILTER (WHERE bucket >= $2)::BIGINT AS total_tokens,
+            SUM(error_count) FILTER (WHERE bucket >= $2)::BIGINT AS error_count
+        FROM llm_metrics_1hour
+        WHERE org_id = $1 AND bucket >= $4 AND bucket < $3 {}
+        "#,
+        if environment.is_some() { "AND environment = $5" } else { "" }
+    );
+
+    let mut query = sqlx::query_as::<_, OverviewCostRow>(&query_str)
+        .bind(org_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(previous_start);
+    if let Some(environment) = environment {
+        query = query.bind(environment);
+    }
+
+    let row = query.fetch_one(pool).await?;
+
+    let total_cost = row.total_cost.unwrap_or(0.0);
+    let previous_period_cost = row.previous_cost.unwrap_or(0.0);
+    let total_requests = row.total_requests.unwrap_or(0);
+    let error_count = row.error_count.unwrap_or(0);
+
+    let cost = OverviewCost {
+        total_cost,
+        total_requests,
+        total_tokens: row.total_tokens.unwrap_or(0),
+        avg_cost_per_request: if total_requests > 0 {
+            total_cost / total_requests as f64
+        } else {
+            0.0
+        },
+        previous_period_cost,
+        change_percentage: if previous_period_cost > 0.0 {
+            Some((total_cost - previous_period_cost) / previous_period_cost * 100.0)
+        } else {
+            None
+        },
+    };
+
+    let errors = OverviewErrors {
+        total_requests,
+        error_count,
+        error_rate: if total_requests > 0 {
+            error_count as f64 / total_requests as f64
+        } else {
+            0.0
+        },
+    };
+
+    Ok((cost, errors))
+}
+
+/// Query the most-used models in the window
+async fn query_top_models(
+    pool: &PgPool,
+    org_id: &str,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    environment: Option<&str>,
+    limit: i32,
+) -> Result<Vec<OverviewModel>, sqlx::Error> {
+    let query_str = format!(
+        r#"
+        SELECT
+            provider,
+            model,
+            SUM(request_count)::BIGINT AS requests,
+            SUM(total_cost_usd) AS cost,
+            SUM(avg_duration_ms * request_count) / NULLIF(SUM(request_count), 0) AS avg_duration_ms,
+            SUM(error_count)::BIGINT AS error_count
+        FROM llm_metrics_1hour
+        WHERE org_id = $1 AND bucket >= $2 AND bucket < $3 {}
+        GROUP BY provider, model
+        ORDER BY requests DESC
+        LIMIT $4
+        "#,
+        if environment.is_some() { "AND environment = $5" } else { "" }
+    );
+
+    let mut query = sqlx::query_as::<_, OverviewModelRow>(&query_str)
+        .bind(org_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(limit);
+    if let Some(environment) = environment {
+        query = query.bind(environment);
+    }
+
+    let rows = query.fetch_all(pool).await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let requests = row.requests.unwrap_or(0);
+            OverviewModel {
+                provider: row.provider,
+                model: row.model,
+                requests,
+                cost: row.cost.unwrap_or(0.0),
+                avg_duration_ms: row.avg_duration_ms.unwrap_or(0.0),
+                error_rate: if requests > 0 {
+                    row.error_count.unwrap_or(0) as f64 / requests as f64
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect())
+}
+
+/// Query latency percentiles (raw traces; aggregates don't store percentiles)
+async fn query_latency(
+    pool: &PgPool,
+    org_id: &str,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    environment: Option<&str>,
+) -> Result<OverviewLatency, sqlx::Error> {
+    let query_str = format!(
+        r#"
+        SELECT
+            PERCENTILE_CONT(0.50) WITHIN GROUP (ORDER BY duration_ms) AS p50_ms,
+            PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY duration_ms) AS p95_ms,
+            PERCENTILE_CONT(0.99) WITHIN GROUP (ORDER BY duration_ms) AS p99_ms
+        FROM llm_traces
+        WHERE org_id = $1 AND ts >= $2 AND ts < $3 {}
+        "#,
+        if environment.is_some() { "AND environment = $4" } else { "" }
+    );
+
+    let mut query = sqlx::query_as::<_, OverviewLatencyRow>(&query_str)
+        .bind(org_id)
+        .bind(start_time)
+        .bind(end_time);
+    if let Some(environment) = environment {
+        query = query.bind(environment);
+    }
+
+    let row = query.fetch_one(pool).await?;
+
+    Ok(OverviewLatency {
+        p50_ms: row.p50_ms.unwrap_or(0.0),
+        p95_ms: row.p95_ms.unwrap_or(0.0),
+        p99_ms: row.p99_ms.unwrap_or(0.0),
+    })
+}
+
+/// Query hourly cost and request counts, including the anomaly baseline
+async fn query_hourly_series(
+    pool: &PgPool,
+    org_id: &str,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    environment: Option<&str>,
+) -> Result<Vec<OverviewHourlyRow>, sqlx::Error> {
+    let baseline_start = start_time - Duration::hours(ANOMALY_BASELINE_BUCKETS as i64);
+
+    let query_str = format!(
+        r#"
+        SELECT
+            bucket,
+            SUM(total_cost_usd) AS cost,
+            SUM(request_count)::BIGINT AS requests
+        FROM llm_metrics_1hour
+        WHERE org_id = $1 AND bucket >= $2 AND bucket < $3 {}
+        GROUP BY bucket
+        ORDER BY bucket
+        "#,
+        if environment.is_some() { "AND environment = $4" } else { "" }
+    );
+
+    let mut query = sqlx::query_as::<_, OverviewHourlyRow>(&query_str)
+        .bind(org_id)
+        .bind(baseline_start)
+        .bind(end_time);
+    if let Some(environment) = environment {
+        query = query.bind(environment);
+    }
+
+    query.fetch_all(pool).await
+}
+
+/// Fill empty hours with zero and detect cost and volume anomalies
+fn anomalies_from_series(
+    rows: &[OverviewHourlyRow],
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+) -> Vec<OverviewAnomaly> {
+    let by_bucket: HashMap<DateTime<Utc>, &OverviewHourlyRow> =
+        rows.iter().map(|row| (row.bucket, row)).collect();
+
+    let baseline_start = start_time - Duration::hours(ANOMALY_BASELINE_BUCKETS as i64);
+    let first = baseline_start
+        .duration_trunc(Duration::hours(1))
+        .unwrap_or(baseline_start);
+
+    let mut cost = Vec::new();
+    let mut requests = Vec::new();
+    let mut bucket = first;
+    while bucket < end_time {
+        let row = by_bucket.get(&bucket);
+        cost.push((bucket, row.and_then(|r| r.cost).unwrap_or(0.0)));
+        requests.push((bucket, row.and_then(|r| r.requests).unwrap_or(0) as f64));
+        bucket += Duration::hours(1);
+    }
+
+    let mut anomalies = detect_anomalies(
+        "cost",
+        &cost,
+        start_time,
+        ANOMALY_BASELINE_BUCKETS,
+        ANOMALY_Z_THRESHOLD,
+    );
+    anomalies.extend(detect_anomalies(
+        "requests",
+        &requests,
+        start_time,
+        ANOMALY_BASELINE_BUCKETS,
+        ANOMALY_Z_THRESHOLD,
+    ));
+
+    // Most recent first
+    anomalies.sort_by(|a, b| b.bucket.cmp(&a.bucket));
+    anomalies
+}
@@ -7,6 +7,7 @@ use axum::{
     routing::get,
     Json, Router,
 };
+use chrono::{Duration, Utc};
 use redis::AsyncCommands;
 use serde_json::json;
 use std::sync::Arc;
@@ -14,13 +15,19 @@ use tracing::{error, info, instrument};
 
 /// Create performance routes
 pub fn routes() -> Router<Arc<AppState>> {
-    Router::new().route("/api/v1/analytics/performance", get(get_performance_metrics))
+    Router::new()
+        .route("/api/v1/analytics/performance", get(get_performance_metrics))
+        .route("/api/v1/analytics/performance/latency", get(get_latency_breakdown))
+        .route(
+            "/api/v1/analytics/performance/ttft-regressions",
+            get(get_ttft_regressions),
+        )
 }
 
 /// GET /api/v1/analytics/performance - Get performance metrics
 ///
 /// Returns performance metrics including latency percentiles (P50, P95, P99),
-/// throughput, and token processing statistics.
+/// time to first token, throughput, and token processing statistics.
 ///
 /// Query Parameters:
 /// - start_time: Start of time range (ISO 8601)
@@ -30,9 +37,14 @@ pub fn routes() -> Router<Arc<AppState>> {
 /// - environment: Filter by environment (optional)
 /// - granularity: Time bucket granularity (1min, 1hour, 1day) - default: 1hour
 ///
-/// Note: Percentile calculations (P50, P95, P99) are only available for granularities
-/// of 1min or when querying raw data, as they require ordered-set aggregates that
-/// cannot be computed from pre-aggregated data in continuous aggregates.
+/// Note: Percentile calculations (P50, P95, P99) and TTFT statistics are only
+/// available for granularities of 1min or when querying raw data, as they require
+/// ordered-set aggregates that cannot be computed from pre-aggregated data in
+/// continuous aggregates.
+///
+/// With cold storage enabled, the part of the range older than the hot
+/// retention window is read from archived Parquet files. Percentiles and TTFT
+/// averages of a range spanning both tiers are null.
 #[instrument(skip(state))]
 async fn get_performance_metrics(
     State(state): State<Arc<AppState>>,
@@ -75,15 +87,53 @@ async fn get_performance_metrics(
         }
     }
 
-    // Query database
+    // Query the database, and cold storage for the archived part of the range
+    let end_time = query.end_time.unwrap_or_else(Utc::now);
+    let start_time = query.start_time.unwrap_or_else(|| end_time - Duration::days(7));
+    let split = state.cold_storage.split(start_time, end_time);
+
     let service = TimescaleDBService::new(state.db_pool.clone());
-    let metrics = service
-        .get_performance_metrics(&query)
-        .await
-        .map_err(|e| {
-            error!("Database query error: {}", e);
-            ApiError::Internal(format!("Failed to fetch performance metrics: {}", e))
-        })?;
+    let hot = match split.hot {
+        Some((start, end)) => {
+            let hot_query = AnalyticsQuery {
+                start_time: Some(start),
+                end_time: Some(end),
+                ..query.clone()
+            };
+            let metrics = service
+                .get_performance_metrics(&hot_query)
+                .await
+                .map_err(|e| {
+                    error!("Database query error: {}", e);
+                    ApiError::Internal(format!("Failed to fetch performance metrics: {}", e))
+                })?;
+            Some(metrics)
+        }
+        None => None,
+    };
+    let cold = match split.cold {
+        Some((start, end)) => {
+            let metrics = state
+                .cold_storage
+                .performance_metrics(&query, start, end)
+                .await
+                .map_err(|e| {
+                    error!("Cold storage query error: {}", e);
+                    ApiError::Internal(format!("Failed to fetch performance metrics: {}", e))
+                })?;
+            Some(metrics)
+        }
+        None => None,
+    };
+    let metrics = match (hot, cold) {
+        (Some(hot), Some(cold)) => hot.merge(cold, start_time, end_time),
+        (Some(metrics), None) | (None, Some(metrics)) => metrics,
+        (None, None) => {
+            return Err(ApiError::BadRequest(
+                "start_time must be before end_time".to_string(),
+            ))
+        }
+    };
 
     // Cache the result
     let serialized = serde_json::to_string(&metrics).unwrap();
@@ -99,6 +149,143 @@ async fn get_performance_metrics(
     Ok(Json(metrics))
 }
 
+/// GET /api/v1/analytics/performance/latency - Get latency breakdown
+///
+/// Splits latency into time to first token (TTFT) and generation speed
+/// (output tokens per second), overall, per model and over time. TTFT is only
+/// recorded for streaming requests.
+///
+/// Query Parameters:
+/// - start_time: Start of time range (ISO 8601)
+/// - end_time: End of time range (ISO 8601)
+/// - provider: Filter by provider (optional)
+/// - model: Filter by model (optional)
+/// - environment: Filter by environment (optional)
+/// - granularity: Time series bucket size (1min, 1hour, 1day) - default: 1hour
+#[instrument(skip(state))]
+async fn get_latency_breakdown(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AnalyticsQuery>,
+) -> Result<Json<LatencyBreakdown>, ApiError> {
+    info!(
+        "Fetching latency breakdown: provider={:?}, model={:?}, granularity={}",
+        query.provider, query.model, query.granularity
+    );
+
+    let cache_key = format!(
+        "performance:latency:{}:{}:{}:{}:{}:{}",
+        query.start_time.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        query.end_time.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        query.provider.as_deref().unwrap_or("all"),
+        query.model.as_deref().unwrap_or("all"),
+        query.environment.as_deref().unwrap_or("all"),
+        query.granularity
+    );
+
+    let mut redis_conn = state.redis_client.get_async_connection().await.map_err(|e| {
+        error!("Redis connection error: {}", e);
+        ApiError::Internal("Failed to connect to cache".to_string())
+    })?;
+
+    if let Ok(cached) = redis_conn.get::<_, String>(&cache_key).await {
+        if let Ok(result) = serde_json::from_str::<LatencyBreakdown>(&cached) {
+            info!("Returning cached latency breakdown");
+            return Ok(Json(result));
+        }
+    }
+
+    let service = TimescaleDBService::new(state.db_pool.clone());
+    let breakdown = service.get_latency_breakdown(&query).await.map_err(|e| {
+        error!("Database query error: {}", e);
+        ApiError::Internal(format!("Failed to fetch latency breakdown: {}", e))
+    })?;
+
+    let serialized = serde_json::to_string(&breakdown).unwrap();
+    let _: Result<(), _> = redis_conn
+        .set_ex(&cache_key, serialized, state.cache_ttl)
+        .await;
+
+    info!(
+        "Latency breakdown fetched: p95_ttft={:?}ms, avg_output_tps={:?}, models={}",
+        breakdown.overall.p95_ttft_ms,
+        breakdown.overall.avg_output_tokens_per_second,
+        breakdown.by_model.len()
+    );
+
+    Ok(Json(breakdown))
+}
+
+/// GET /api/v1/analytics/performance/ttft-regressions - Detect TTFT regressions
+///
+/// Compares each model's median and P95 time to first token over the last
+/// 7 days against the 7 days before, and flags models whose TTFT rose by at
+/// least `threshold_pct`.
+///
+/// Query Parameters:
+/// - end_time: End of the current week (ISO 8601) - default: now
+/// - provider: Filter by provider (optional)
+/// - model: Filter by model (optional)
+/// - environment: Filter by environment (optional)
+/// - threshold_pct: Increase that counts as a regression - default: 20
+/// - min_requests: Streaming requests needed in both weeks - default: 50
+#[instrument(skip(state))]
+async fn get_ttft_regressions(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<TtftRegressionQuery>,
+) -> Result<Json<TtftRegressionReport>, ApiError> {
+    if !query.threshold_pct.is_finite() || query.threshold_pct <= 0.0 {
+        return Err(ApiError::BadRequest(
+            "threshold_pct must be greater than 0".to_string(),
+        ));
+    }
+    if query.min_requests < 1 {
+        return Err(ApiError::BadRequest(
+            "min_requests must be at least 1".to_string(),
+        ));
+    }
+
+    let cache_key = format!(
+        "performance:ttft-regressions:{}:{}:{}:{}:{}:{}",
+        query.end_time.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        query.provider.as_deref().unwrap_or("all"),
+        query.model.as_deref().unwrap_or("all"),
+        query.environment.as_deref().unwrap_or("all"),
+        query.threshold_pct,
+        query.min_requests
+    );
+
+    let mut redis_conn = state.redis_client.get_async_connection().await.map_err(|e| {
+        error!("Redis connection error: {}", e);
+        ApiError::Internal("Failed to connect to cache".to_string())
+    })?;
+
+    if let Ok(cached) = redis_conn.get::<_, String>(&cache_key).await {
+        if let Ok(result) = serde_json::from_str::<TtftRegressionReport>(&cached) {
+            info!("Returning cached TTFT regression report");
+            return Ok(Json(result));
+        }
+    }
+
+    let service = TimescaleDBService::new(state.db_pool.clone());
+    let report = service.detect_ttft_regressions(&query).await.map_err(|e| {
+        error!("Database query error: {}", e);
+        ApiError::Internal(format!("Failed to detect TTFT regressions: {}", e))
+    })?;
+
+    let serialized = serde_json::to_string(&report).unwrap();
+    let _: Result<(), _> = redis_conn
+        .set_ex(&cache_key, serialized, state.cache_ttl)
+        .await;
+
+    info!(
+        "TTFT regressions checked: {} of {} models regressed",
+        report.regressed_count,
+        report.models.len()
+    );
+
+    Ok(Json(report))
+}
+
 /// API error type
 #[derive(Debug)]
 pub enum ApiError {
@@ -164,4 +351,58 @@ mod tests {
         assert!(!has_percentiles("1hour"));
         assert!(!has_percentiles("1day"));
     }
+
+    fn model_stats(p50: f64, p95: f64, streaming: i64) -> LatencyBreakdownStats {
+        LatencyBreakdownStats {
+            request_count: streaming,
+            streaming_request_count: streaming,
+            p50_ttft_ms: Some(p50),
+            p95_ttft_ms: Some(p95),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_ttft_regression_flags() {
+        let current = ModelLatencyBreakdown {
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            stats: model_stats(400.0, 1300.0, 500),
+        };
+
+        // p95 went from 1000ms to 1300ms (+30%)
+        let previous = model_stats(390.0, 1000.0, 500);
+        let result = TtftRegression::compare(&current, Some(&previous), 20.0, 50);
+        assert!(result.regressed);
+        assert!((result.p95_change_pct.unwrap() - 30.0).abs() < 1e-9);
+
+        // Below threshold
+        let result = TtftRegression::compare(&current, Some(&previous), 50.0, 50);
+        assert!(!result.regressed);
+
+        // Too few requests last week to compare
+        let sparse = model_stats(200.0, 500.0, 10);
+        let result = TtftRegression::compare(&current, Some(&sparse), 20.0, 50);
+        assert!(!result.regressed);
+
+        // New model without history
+        let result = TtftRegression::compare(&current, None, 20.0, 50);
+        assert!(!result.regressed);
+        assert_eq!(result.p50_change_pct, None);
+        assert_eq!(result.previous_streaming_requests, 0);
+    }
+
+    #[test]
+    fn test_latency_breakdown_flattens_model_stats() {
+        let breakdown = ModelLatencyBreakdown {
+            provider: "anthropic".to_string(),
+            model: "claude-3-haiku".to_string(),
+            stats: model_stats(250.0, 600.0, 100),
+        };
+
+        let value = serde_json::to_value(&breakdown).unwrap();
+        assert_eq!(value["model"], "claude-3-haiku");
+        assert_eq!(value["p95_ttft_ms"], 600.0);
+        assert_eq!(value["streaming_request_count"], 100);
+    }
 }
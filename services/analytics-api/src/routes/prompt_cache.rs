@@ -0,0 +1,179 @@
+//! # Prompt Cache API Routes
+//!
+//! Provider-side prompt caching (Anthropic cache reads and writes, OpenAI
+//! cached input) bills cached prompt tokens at a discount, so the cache hit
+//! rate of a model or template drives its effective cost. This endpoint
+//! reports hit rates and the savings realized by caching.
+//!
+//! ## Endpoints
+//! - GET /api/v1/prompt-cache - Cache hit rates and savings per model or template
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `metrics:read`
+//! - Results are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::prompt_cache::*;
+use crate::models::recommendations::WorkloadGrouping;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create prompt cache routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/api/v1/prompt-cache", get(get_prompt_cache))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        error!(error = %err, "Prompt cache query failed");
+        ApiError::Internal(format!("Database query failed: {}", err))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/prompt-cache
+// ============================================================================
+
+/// GET /api/v1/prompt-cache - Cache hit rates and realized savings
+///
+/// Reports, per provider and model (or per prompt template with
+/// `group_by=template`), the share of requests that read from the prompt
+/// cache, the share of prompt tokens served from it and the savings:
+/// the cost without caching minus the actual cost, as recorded by the
+/// collector's cost processor. Totals cover all groups in the window.
+///
+/// Query Parameters:
+/// - start_time, end_time: Analysis window (default: the last 7 days, at most 90)
+/// - provider, model, environment: Filters (optional)
+/// - group_by: `model` or `template` (default: model)
+/// - limit: Maximum groups, most savings first (default: 100, max: 1000)
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/prompt-cache?group_by=template' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_prompt_cache(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<PromptCacheRequest>,
+) -> Result<Json<PromptCacheResponse>, ApiError> {
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read prompt cache metrics".to_string(),
+        ));
+    }
+    let (start_time, end_time) = request.validate().map_err(ApiError::BadRequest)?;
+
+    // Grouping by model groups on a constant workload
+    let workload = match request.group_by {
+        PromptCacheGrouping::Model => "NULL::TEXT",
+        PromptCacheGrouping::Template => WorkloadGrouping::PromptTemplate.to_sql_expression(),
+    };
+
+    let query = format!(
+        r#"
+        SELECT
+            provider,
+            model,
+            workload,
+            COUNT(*) AS request_count,
+            COUNT(*) FILTER (WHERE cache_read_tokens > 0) AS cache_hit_count,
+            COUNT(*) FILTER (WHERE cache_write_tokens > 0) AS cache_write_count,
+            COALESCE(SUM(prompt_tokens), 0)::BIGINT AS prompt_tokens,
+            COALESCE(SUM(cache_read_tokens), 0)::BIGINT AS cache_read_tokens,
+            COALESCE(SUM(cache_write_tokens), 0)::BIGINT AS cache_write_tokens,
+            COALESCE(SUM(prompt_cost_usd), 0)::FLOAT8 AS prompt_cost_usd,
+            COALESCE(SUM(cache_savings_usd), 0)::FLOAT8 AS cache_savings_usd
+        FROM (
+            SELECT
+                provider,
+                model,
+                {workload} AS workload,
+                prompt_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
+                prompt_cost_usd,
+                cache_savings_usd
+            FROM llm_traces
+            WHERE attributes->>'org_id' = $1
+              AND ts >= $2
+              AND ts < $3
+              AND prompt_tokens > 0
+              AND ($4::TEXT IS NULL OR provider = $4)
+              AND ($5::TEXT IS NULL OR model = $5)
+              AND ($6::TEXT IS NULL OR environment = $6)
+        ) spans
+        GROUP BY provider, model, workload
+        "#,
+    );
+    let rows = sqlx::query_as::<_, PromptCacheRow>(&query)
+        .bind(&auth.org_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(&request.provider)
+        .bind(&request.model)
+        .bind(&request.environment)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    let (totals, groups) = build_report(rows, request.limit);
+
+    info!(
+        org_id = %auth.org_id,
+        groups = groups.len(),
+        hit_rate = totals.hit_rate,
+        "Prompt cache report computed"
+    );
+
+    Ok(Json(PromptCacheResponse {
+        start_time,
+        end_time,
+        group_by: request.group_by,
+        totals,
+        groups,
+    }))
+}
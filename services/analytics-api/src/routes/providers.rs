@@ -0,0 +1,181 @@
+//! # Provider Status API Route
+//!
+//! `GET /api/v1/providers/status` reports upstream provider health and
+//! correlates error spikes in the organization's traces with upstream
+//! incidents, answering "is it us or them?".
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `metrics:read`
+//! - Error spikes are organization-scoped; provider incidents are global
+
+use crate::middleware::AuthContext;
+use crate::models::providers::*;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create provider status routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/api/v1/providers/status", get(get_provider_status))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg)
+            }
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/providers/status
+// ============================================================================
+
+/// GET /api/v1/providers/status - Upstream provider status and correlation
+///
+/// ## Query Parameters
+/// - `start_time`: Start of time range (ISO 8601) - default: 24 hours ago
+/// - `end_time`: End of time range (ISO 8601) - default: now
+/// - `provider`: Filter by provider
+/// - `spike_error_rate`: Hourly error rate counted as a spike - default: 0.05
+/// - `spike_min_requests`: Minimum hourly requests for a spike - default: 10
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/providers/status?provider=openai' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_provider_status(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<ProviderStatusRequest>,
+) -> Result<Json<ProviderStatusResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read provider status".to_string(),
+        ));
+    }
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let end_time = request.end_time.unwrap_or_else(Utc::now);
+    let start_time = request
+        .start_time
+        .unwrap_or_else(|| end_time - Duration::hours(24));
+
+    info!(org_id = %auth.org_id, provider = ?request.provider, "Querying provider status");
+
+    let incidents = sqlx::query_as::<_, ProviderIncident>(
+        r#"
+        SELECT incident_id, provider, status, description, source, started_at, resolved_at
+        FROM provider_incidents
+        WHERE started_at < $2
+          AND (resolved_at IS NULL OR resolved_at > $1)
+          AND ($3::TEXT IS NULL OR provider = $3)
+        ORDER BY started_at DESC
+        "#,
+    )
+    .bind(start_time)
+    .bind(end_time)
+    .bind(&request.provider)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to query provider incidents");
+        ApiError::Internal(format!("Database query failed: {}", e))
+    })?;
+
+    let error_rows = sqlx::query_as::<_, ProviderErrorRow>(
+        r#"
+        SELECT
+            provider,
+            bucket,
+            SUM(request_count)::BIGINT AS request_count,
+            SUM(error_count)::BIGINT AS error_count
+        FROM llm_metrics_1hour
+        WHERE org_id = $1
+          AND bucket >= $2
+          AND bucket < $3
+          AND ($4::TEXT IS NULL OR provider = $4)
+        GROUP BY provider, bucket
+        ORDER BY bucket DESC, provider
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(start_time)
+    .bind(end_time)
+    .bind(&request.provider)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to query provider error rates");
+        ApiError::Internal(format!("Database query failed: {}", e))
+    })?;
+
+    let error_spikes = correlate_error_spikes(
+        &error_rows,
+        &incidents,
+        request.spike_error_rate,
+        request.spike_min_requests,
+    );
+
+    let current = state
+        .provider_health
+        .statuses()
+        .into_iter()
+        .filter(|s| request.provider.as_deref().map_or(true, |p| s.provider == p))
+        .collect();
+
+    info!(
+        incidents = incidents.len(),
+        spikes = error_spikes.len(),
+        "Provider status completed"
+    );
+
+    Ok(Json(ProviderStatusResponse {
+        start_time,
+        end_time,
+        current,
+        incidents,
+        error_spikes,
+    }))
+}
@@ -0,0 +1,126 @@
+//! # Pseudonym API Routes
+//!
+//! - `GET /api/v1/pseudonyms/:pseudonym` resolves a user or session
+//!   pseudonym written by the collector to the identifier it replaced
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires an explicit `resolve:pseudonyms` grant (no role has it by
+//!   default, and the admin wildcard does not imply it)
+//! - Only pseudonyms of the caller's organization resolve
+//! - Every resolution is recorded in the data access audit log, even when
+//!   the pseudonym is unknown
+
+use crate::middleware::AuthContext;
+use crate::models::pseudonyms::PseudonymResolution;
+use crate::models::{AppState, ErrorResponse};
+use crate::services::pseudonyms::{can_resolve, PseudonymError};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create pseudonym routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/api/v1/pseudonyms/:pseudonym", get(resolve_pseudonym))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    NotFound(String),
+    Internal(String),
+    ServiceUnavailable(String),
+}
+
+impl From<PseudonymError> for ApiError {
+    fn from(e: PseudonymError) -> Self {
+        match e {
+            PseudonymError::Disabled => ApiError::ServiceUnavailable(e.to_string()),
+            PseudonymError::InvalidPseudonym(_) => ApiError::BadRequest(e.to_string()),
+            PseudonymError::Vault(_) => {
+                error!(error = %e, "Pseudonym lookup failed");
+                ApiError::Internal("Pseudonym lookup failed".to_string())
+            }
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, "not_found", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+            ApiError::ServiceUnavailable(msg) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "service_unavailable", msg)
+            }
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/pseudonyms/:pseudonym
+// ============================================================================
+
+/// GET /api/v1/pseudonyms/:pseudonym - Original identifier of a pseudonym
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/pseudonyms/ps_3f2a9c0e8b7d6a5f4e3d2c1b0a998877' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn resolve_pseudonym(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(pseudonym): Path<String>,
+) -> Result<Json<PseudonymResolution>, ApiError> {
+    // Check permissions
+    if !can_resolve(&auth) {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to resolve pseudonyms".to_string(),
+        ));
+    }
+
+    let value = state.pseudonyms.resolve(&auth.org_id, &pseudonym).await?;
+
+    state
+        .data_access
+        .record_pseudonym_resolution(&auth, &pseudonym)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to record data access audit entry");
+            ApiError::Internal("Failed to record data access audit entry".to_string())
+        })?;
+
+    info!(user_id = %auth.user_id, found = value.is_some(), "Resolved pseudonym");
+
+    let value =
+        value.ok_or_else(|| ApiError::NotFound(format!("Pseudonym {} not found", pseudonym)))?;
+
+    Ok(Json(PseudonymResolution { pseudonym, value }))
+}
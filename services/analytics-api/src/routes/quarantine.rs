@@ -0,0 +1,284 @@
+//! # Quarantine API Routes
+//!
+//! - `GET /api/v1/quarantine` lists spans the collector quarantined, newest
+//!   first
+//! - `GET /api/v1/quarantine/:id` returns one quarantined span with its
+//!   payload
+//! - `POST /api/v1/quarantine/replay` marks quarantined spans for replay
+//!   through the collector pipeline
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Browsing requires `read:traces`; the span payload additionally requires
+//!   `read:trace_content`, since spans quarantined before PII redaction hold
+//!   unredacted content
+//! - Replay requires `replay:quarantine` (admins only by default)
+//! - Entries are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::quarantine::*;
+use crate::models::{AppState, ErrorResponse};
+use crate::services::data_access::CONTENT_PERMISSION;
+use crate::services::quarantine::QuarantineError;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// Columns of [`QuarantineEntry`]
+const ENTRY_COLUMNS: &str = "id, ts, trace_id, span_id, service_name, service_version, stage, \
+     failure_reason, schema_name, violations, replay_status, replay_requested_at, replayed_at, \
+     replay_attempts, replay_error";
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create quarantine routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/api/v1/quarantine", get(list_quarantine))
+        .route("/api/v1/quarantine/replay", post(replay_quarantine))
+        .route("/api/v1/quarantine/:id", get(get_quarantine_entry))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    NotFound(String),
+    Internal(String),
+    ServiceUnavailable(String),
+}
+
+impl From<QuarantineError> for ApiError {
+    fn from(e: QuarantineError) -> Self {
+        match e {
+            QuarantineError::Disabled => ApiError::ServiceUnavailable(e.to_string()),
+            QuarantineError::Database(_) => {
+                error!(error = %e, "Quarantine replay request failed");
+                ApiError::Internal(e.to_string())
+            }
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, "not_found", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+            ApiError::ServiceUnavailable(msg) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "service_unavailable", msg)
+            }
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/quarantine
+// ============================================================================
+
+/// GET /api/v1/quarantine - Quarantined spans
+///
+/// ## Query Parameters
+/// - `start_time`: Start of time range (ISO 8601) - default: 30 days ago
+/// - `end_time`: End of time range (ISO 8601) - default: now
+/// - `stage`: `schema`, `pii` or `processing`
+/// - `service_name`: Only spans of this service
+/// - `replay_status`: `quarantined`, `pending`, `replaying`, `replayed` or `failed`
+/// - `limit`: Maximum entries - default: 100, max: 1000
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/quarantine?stage=schema&service_name=checkout-api' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn list_quarantine(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<QuarantineListQuery>,
+) -> Result<Json<QuarantineListResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("read:traces") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read quarantined spans".to_string(),
+        ));
+    }
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let end_time = request.end_time.unwrap_or_else(Utc::now);
+    let start_time = request
+        .start_time
+        .unwrap_or_else(|| end_time - Duration::days(QUARANTINE_RETENTION_DAYS));
+
+    info!(org_id = %auth.org_id, stage = ?request.stage, "Querying quarantined spans");
+
+    let sql = format!(
+        r#"
+        SELECT {ENTRY_COLUMNS}, ts + INTERVAL '{QUARANTINE_RETENTION_DAYS} days' AS expires_at
+        FROM rejected_spans
+        WHERE org_id = $1
+          AND ts >= $2
+          AND ts < $3
+          AND ($4::TEXT IS NULL OR stage = $4)
+          AND ($5::TEXT IS NULL OR service_name = $5)
+          AND ($6::TEXT IS NULL OR replay_status = $6)
+        ORDER BY ts DESC
+        LIMIT $7
+        "#
+    );
+
+    let entries = sqlx::query_as::<_, QuarantineEntry>(&sql)
+        .bind(&auth.org_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(&request.stage)
+        .bind(&request.service_name)
+        .bind(&request.replay_status)
+        .bind(request.limit)
+        .fetch_all(&state.db_pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to query quarantined spans");
+            ApiError::Internal(format!("Database query failed: {}", e))
+        })?;
+
+    Ok(Json(QuarantineListResponse {
+        start_time,
+        end_time,
+        entries,
+    }))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/quarantine/:id
+// ============================================================================
+
+/// GET /api/v1/quarantine/:id - One quarantined span
+///
+/// Includes the span as received when the caller has `read:trace_content`.
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/quarantine/8f14e45f-ceea-467f-a8f0-2b1c3d4e5f60' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_quarantine_entry(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<QuarantineEntryDetail>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("read:traces") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read quarantined spans".to_string(),
+        ));
+    }
+
+    let sql = format!(
+        r#"
+        SELECT {ENTRY_COLUMNS}, ts + INTERVAL '{QUARANTINE_RETENTION_DAYS} days' AS expires_at
+        FROM rejected_spans
+        WHERE org_id = $1 AND id = $2
+        "#
+    );
+
+    let entry = sqlx::query_as::<_, QuarantineEntry>(&sql)
+        .bind(&auth.org_id)
+        .bind(id)
+        .fetch_optional(&state.db_pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to query quarantined span");
+            ApiError::Internal(format!("Database query failed: {}", e))
+        })?
+        .ok_or_else(|| ApiError::NotFound(format!("Quarantined span {} not found", id)))?;
+
+    let span = if auth.has_permission(CONTENT_PERMISSION) {
+        sqlx::query_scalar::<_, serde_json::Value>(
+            "SELECT span FROM rejected_spans WHERE id = $1 AND ts = $2",
+        )
+        .bind(entry.id)
+        .bind(entry.ts)
+        .fetch_optional(&state.db_pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to query quarantined span payload");
+            ApiError::Internal(format!("Database query failed: {}", e))
+        })?
+    } else {
+        None
+    };
+
+    Ok(Json(QuarantineEntryDetail { entry, span }))
+}
+
+// ============================================================================
+// Endpoint: POST /api/v1/quarantine/replay
+// ============================================================================
+
+/// POST /api/v1/quarantine/replay - Replay quarantined spans
+///
+/// Marks the selected spans `pending`; the collector replays them through
+/// its processors and marks them `replayed` or `failed`. Spans already
+/// pending, replaying or replayed are skipped.
+///
+/// ## Example
+/// ```bash
+/// curl -X POST 'http://localhost:8080/api/v1/quarantine/replay' \
+///   -H "Authorization: Bearer $JWT_TOKEN" \
+///   -H "Content-Type: application/json" \
+///   -d '{"stage": "schema", "schema_name": "checkout-api@2.1.0"}'
+/// ```
+#[instrument(skip(state, auth, request))]
+async fn replay_quarantine(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Json(request): Json<ReplayQuarantineRequest>,
+) -> Result<(StatusCode, Json<ReplayQuarantineResponse>), ApiError> {
+    // Check permissions
+    if !auth.has_permission("replay:quarantine") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to replay quarantined spans".to_string(),
+        ));
+    }
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let requested = state
+        .quarantine
+        .request_replay(&auth.org_id, &request)
+        .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ReplayQuarantineResponse { requested }),
+    ))
+}
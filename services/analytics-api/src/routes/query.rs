@@ -0,0 +1,166 @@
+//! # Natural-Language Query Routes
+//!
+//! "Ask your observability": questions in plain language answered from the
+//! metrics API.
+//!
+//! ## Endpoints
+//! - POST /api/v1/query/natural - Translate a question into a metrics query and run it
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `metrics:read`
+//! - The model only produces a whitelisted metrics query (see
+//!   `services::natural_query`), never SQL; the query is scoped to the
+//!   caller's organization like `GET /api/v1/metrics`
+
+use crate::middleware::AuthContext;
+use crate::models::natural_query::*;
+use crate::models::{AppState, ErrorResponse};
+use crate::routes::metrics::{self, execute_metrics_query};
+use crate::services::natural_query::NaturalQueryError;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::{info, instrument, warn};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create natural-language query routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/api/v1/query/natural", post(query_natural))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Unprocessable(String),
+    BadGateway(String),
+    ServiceUnavailable(String),
+    Internal(String),
+}
+
+impl From<NaturalQueryError> for ApiError {
+    fn from(err: NaturalQueryError) -> Self {
+        match err {
+            NaturalQueryError::Disabled => ApiError::ServiceUnavailable(err.to_string()),
+            NaturalQueryError::InvalidQuestion => ApiError::BadRequest(err.to_string()),
+            NaturalQueryError::Untranslatable(_) => ApiError::Unprocessable(err.to_string()),
+            NaturalQueryError::Provider(_) => {
+                warn!(error = %err, "Natural-language query translation failed");
+                ApiError::BadGateway(err.to_string())
+            }
+        }
+    }
+}
+
+impl From<metrics::ApiError> for ApiError {
+    fn from(err: metrics::ApiError) -> Self {
+        match err {
+            metrics::ApiError::BadRequest(msg) => ApiError::Unprocessable(msg),
+            metrics::ApiError::Unauthorized(msg) | metrics::ApiError::Forbidden(msg) => {
+                ApiError::Forbidden(msg)
+            }
+            metrics::ApiError::NotFound(msg) | metrics::ApiError::Internal(msg) => {
+                ApiError::Internal(msg)
+            }
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Unprocessable(msg) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "untranslatable_question", msg)
+            }
+            ApiError::BadGateway(msg) => (StatusCode::BAD_GATEWAY, "model_error", msg),
+            ApiError::ServiceUnavailable(msg) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "not_configured", msg)
+            }
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: POST /api/v1/query/natural
+// ============================================================================
+
+/// POST /api/v1/query/natural - Answer a question about LLM traffic
+///
+/// The question is translated into a metrics query by the configured model,
+/// validated, and executed for the caller's organization. The response holds
+/// the generated query next to the result.
+///
+/// Request Body:
+/// ```json
+/// {"question": "What was the p95 latency per model each day last week?"}
+/// ```
+///
+/// Returns `422` when the question cannot be expressed as a metrics query
+/// and `503` when no model is configured.
+///
+/// ## Example
+/// ```bash
+/// curl -X POST 'http://localhost:8080/api/v1/query/natural' \
+///   -H "Authorization: Bearer $JWT_TOKEN" \
+///   -H "Content-Type: application/json" \
+///   -d '{"question": "How much did we spend on gpt-4o yesterday?"}'
+/// ```
+#[instrument(skip(state, auth, request))]
+async fn query_natural(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Json(request): Json<NaturalQueryRequest>,
+) -> Result<Json<NaturalQueryResponse>, ApiError> {
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read metrics".to_string(),
+        ));
+    }
+
+    let query = state
+        .natural_query
+        .translate(&request.question, Utc::now())
+        .await?;
+
+    info!(
+        org_id = %auth.org_id,
+        metrics = ?query.metrics,
+        interval = ?query.interval,
+        group_by = ?query.group_by,
+        "Executing natural-language query"
+    );
+
+    let answer = execute_metrics_query(&state.db_pool, &query, &auth.org_id).await?;
+
+    Ok(Json(NaturalQueryResponse {
+        question: request.question,
+        query,
+        answer,
+        translated_by: state.natural_query.model().to_string(),
+    }))
+}
@@ -0,0 +1,116 @@
+//! # Ingestion Quota API Route
+//!
+//! `GET /api/v1/quotas/usage` returns the organization's ingestion on a UTC
+//! day (spans, bytes and throttled spans) against its daily quota, from the
+//! `ingestion_usage` table the collectors flush their usage to.
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `read:usage` or `read:metrics`
+//! - Usage is organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::quotas::*;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create quota routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/api/v1/quotas/usage", get(get_quota_usage))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/quotas/usage
+// ============================================================================
+
+/// GET /api/v1/quotas/usage - Ingestion usage against the quota
+///
+/// ## Query Parameters
+/// - `day`: UTC day (YYYY-MM-DD) - default: today
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/quotas/usage' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_quota_usage(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<QuotaUsageQuery>,
+) -> Result<Json<QuotaUsageResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("read:usage") && !auth.has_permission("read:metrics") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read ingestion usage".to_string(),
+        ));
+    }
+
+    // Validate request
+    let today = Utc::now().date_naive();
+    request.validate(today).map_err(ApiError::BadRequest)?;
+    let day = request.day.unwrap_or(today);
+
+    info!(org_id = %auth.org_id, %day, "Querying ingestion usage");
+
+    let row = sqlx::query_as::<_, IngestionUsageRow>(
+        r#"
+        SELECT span_count, byte_count, throttled_spans, spans_per_day_limit,
+               bytes_per_day_limit, over_quota_action, updated_at
+        FROM ingestion_usage
+        WHERE org_id = $1 AND day = $2
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(day)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to query ingestion usage");
+        ApiError::Internal(format!("Database query failed: {}", e))
+    })?;
+
+    Ok(Json(build_quota_usage(auth.org_id.clone(), day, row)))
+}
@@ -0,0 +1,152 @@
+//! # Provider Rate Limit API Routes
+//!
+//! Providers report with every response how much of the organization's
+//! request and token rate limits is left. The SDK records those headers on
+//! spans and storage keeps the latest state per provider and model; this
+//! endpoint reports it, flagging limits that are nearly used up before
+//! requests start failing with 429s.
+//!
+//! ## Endpoints
+//! - GET /api/v1/rate-limits - Latest rate-limit state per provider and model
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `metrics:read`
+//! - Results are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::rate_limits::*;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create rate limit routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/api/v1/rate-limits", get(get_rate_limits))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        error!(error = %err, "Rate limit query failed");
+        ApiError::Internal(format!("Database query failed: {}", err))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/rate-limits
+// ============================================================================
+
+/// GET /api/v1/rate-limits - Latest provider rate-limit state
+///
+/// Reports, per provider and model, the request and token limits from the
+/// most recent response that carried rate-limit headers: the limit, what was
+/// remaining, when it resets and the share used. A limit is approaching when
+/// at least `threshold` of it is used and its window has not reset since.
+///
+/// Query Parameters:
+/// - provider, model: Filters (optional)
+/// - threshold: Share of a limit used at which it is approaching (default: 0.9)
+/// - approaching_only: Only return approaching limits (default: false)
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/rate-limits?approaching_only=true' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_rate_limits(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<RateLimitRequest>,
+) -> Result<Json<RateLimitResponse>, ApiError> {
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read rate limits".to_string(),
+        ));
+    }
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let rows = sqlx::query_as::<_, RateLimitRow>(
+        r#"
+        SELECT
+            org_id, provider, model,
+            requests_limit, requests_remaining, requests_reset_at,
+            tokens_limit, tokens_remaining, tokens_reset_at,
+            observed_at, trace_id
+        FROM provider_rate_limits
+        WHERE org_id = $1
+          AND ($2::TEXT IS NULL OR provider = $2)
+          AND ($3::TEXT IS NULL OR model = $3)
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(&request.provider)
+    .bind(&request.model)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    let now = Utc::now();
+    let mut limits: Vec<ProviderRateLimit> = rows
+        .into_iter()
+        .map(|row| ProviderRateLimit::from_row(row, request.threshold, now))
+        .filter(|limit| !request.approaching_only || !limit.approaching.is_empty())
+        .collect();
+    limits.sort_by(|a, b| {
+        b.max_utilization()
+            .total_cmp(&a.max_utilization())
+            .then_with(|| (&a.provider, &a.model).cmp(&(&b.provider, &b.model)))
+    });
+
+    info!(
+        org_id = %auth.org_id,
+        limits = limits.len(),
+        approaching = limits.iter().filter(|l| !l.approaching.is_empty()).count(),
+        "Rate limits reported"
+    );
+
+    Ok(Json(RateLimitResponse {
+        threshold: request.threshold,
+        limits,
+    }))
+}
@@ -0,0 +1,171 @@
+//! # Recommendation API Routes
+//!
+//! Model routing recommendations computed from collected latency, cost and
+//! feedback data.
+//!
+//! ## Endpoints
+//! - GET /api/v1/recommendations/models - Suggest cheaper or faster models per workload
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `metrics:read`
+//! - Recommendations are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::recommendations::*;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create recommendation routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route(
+        "/api/v1/recommendations/models",
+        get(get_model_recommendations),
+    )
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        error!(error = %err, "Recommendation query failed");
+        ApiError::Internal(format!("Database query failed: {}", err))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/recommendations/models
+// ============================================================================
+
+/// GET /api/v1/recommendations/models - Suggest model substitutions
+///
+/// Groups spans into workloads by prompt template (falling back to the span
+/// name) or by span name, and compares the models serving each workload.
+/// Feedback scores recorded for the spans' traces are used as quality scores.
+///
+/// Query Parameters:
+/// - start_time, end_time: Analysis window (default: the last 30 days, at most 90)
+/// - group_by: prompt_template (default) or span_name
+/// - environment: Filter by environment (optional)
+/// - min_requests: Fewest requests a model needs on a workload (default: 50)
+/// - max_quality_drop: Largest feedback score drop accepted (default: 0.05)
+/// - limit: Maximum recommendations (default: 50, max: 500)
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/recommendations/models?group_by=span_name' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_model_recommendations(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<ModelRecommendationRequest>,
+) -> Result<Json<ModelRecommendationResponse>, ApiError> {
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Missing permission 'metrics:read'".to_string(),
+        ));
+    }
+    let (start_time, end_time) = request.validate().map_err(ApiError::BadRequest)?;
+
+    let query = format!(
+        r#"
+        WITH spans AS (
+            SELECT
+                {workload} AS workload,
+                provider,
+                model,
+                trace_id,
+                status_code,
+                total_cost_usd,
+                duration_ms
+            FROM llm_traces
+            WHERE attributes->>'org_id' = $1
+              AND ts >= $2
+              AND ts < $3
+              AND ($4::TEXT IS NULL OR environment = $4)
+        ),
+        feedback AS (
+            SELECT trace_id, AVG(score) AS score
+            FROM experiment_feedback
+            WHERE org_id = $1
+              AND created_at >= $2
+            GROUP BY trace_id
+        )
+        SELECT
+            s.workload,
+            s.provider,
+            s.model,
+            COUNT(*) AS request_count,
+            COUNT(*) FILTER (WHERE s.status_code = 'ERROR') AS error_count,
+            SUM(s.total_cost_usd)::float8 AS total_cost_usd,
+            AVG(s.duration_ms)::float8 AS avg_duration_ms,
+            PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY s.duration_ms) AS p95_duration_ms,
+            COUNT(f.score) AS feedback_count,
+            AVG(f.score) AS avg_score
+        FROM spans s
+        LEFT JOIN feedback f ON f.trace_id = s.trace_id
+        GROUP BY s.workload, s.provider, s.model
+        "#,
+        workload = request.group_by.to_sql_expression()
+    );
+
+    let rows = sqlx::query_as::<_, WorkloadModelRow>(&query)
+        .bind(&auth.org_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(&request.environment)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+    let response = build_recommendations(&rows, &request, start_time, end_time);
+
+    info!(
+        org_id = %auth.org_id,
+        workloads = response.summary.workloads_compared,
+        recommendations = response.summary.recommendation_count,
+        "Model recommendations computed"
+    );
+
+    Ok(Json(response))
+}
@@ -0,0 +1,187 @@
+//! # Retry and Fallback API Routes
+//!
+//! Calls made through the SDK's `with_retries` / `with_fallback` helpers
+//! record every attempt as a span of its own, numbered and tied to the call,
+//! and mark the attempt that moved on to a fallback model with the model it
+//! gave up on and why. This endpoint reports per model how often requests
+//! are retries and how often calls fall back away from it.
+//!
+//! ## Endpoints
+//! - GET /api/v1/retries - Retry rates and fallback frequency per model
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `metrics:read`
+//! - Results are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::retries::*;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create retry routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/api/v1/retries", get(get_retries))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        error!(error = %err, "Retry query failed");
+        ApiError::Internal(format!("Database query failed: {}", err))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/retries
+// ============================================================================
+
+/// GET /api/v1/retries - Retry rates and fallback frequency per model
+///
+/// Reports, per provider and model, the requests sent (every attempt
+/// counted), the share that were retries of the same model, the share of
+/// calls reaching the model that gave up on it for a fallback model, broken
+/// down by reason, and the calls that reached it as a fallback. Totals cover
+/// all models in the window.
+///
+/// Query Parameters:
+/// - start_time, end_time: Analysis window (default: the last 7 days, at most 90)
+/// - provider, model, environment: Filters (optional)
+/// - limit: Maximum models, most retries and fallbacks first (default: 100, max: 1000)
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/retries?provider=openai' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_retries(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<RetryRequest>,
+) -> Result<Json<RetryResponse>, ApiError> {
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read retry metrics".to_string(),
+        ));
+    }
+    let (start_time, end_time) = request.validate().map_err(ApiError::BadRequest)?;
+
+    // A later attempt without a fallback model is a retry of the same model
+    let rows = sqlx::query_as::<_, RetryRow>(
+        r#"
+        SELECT
+            provider,
+            model,
+            COUNT(*) AS request_count,
+            COUNT(*) FILTER (
+                WHERE numeric_attribute(attributes, 'llm.retry.attempt') > 1
+                  AND attributes->>'llm.fallback.from_model' IS NULL
+            ) AS retry_count,
+            COUNT(*) FILTER (
+                WHERE attributes->>'llm.fallback.from_model' IS NOT NULL
+            ) AS fallback_to_count
+        FROM llm_traces
+        WHERE attributes->>'org_id' = $1
+          AND ts >= $2
+          AND ts < $3
+          AND ($4::TEXT IS NULL OR provider = $4)
+          AND ($5::TEXT IS NULL OR model = $5)
+          AND ($6::TEXT IS NULL OR environment = $6)
+        GROUP BY provider, model
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(start_time)
+    .bind(end_time)
+    .bind(&request.provider)
+    .bind(&request.model)
+    .bind(&request.environment)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    // Fallbacks are recorded on the first attempt of the model fallen back to
+    let fallbacks = sqlx::query_as::<_, FallbackRow>(
+        r#"
+        SELECT
+            provider,
+            attributes->>'llm.fallback.from_model' AS from_model,
+            COALESCE(attributes->>'llm.fallback.reason', 'error') AS reason,
+            COUNT(*) AS fallback_count
+        FROM llm_traces
+        WHERE attributes->>'org_id' = $1
+          AND ts >= $2
+          AND ts < $3
+          AND attributes->>'llm.fallback.from_model' IS NOT NULL
+          AND ($4::TEXT IS NULL OR provider = $4)
+          AND ($5::TEXT IS NULL OR attributes->>'llm.fallback.from_model' = $5)
+          AND ($6::TEXT IS NULL OR environment = $6)
+        GROUP BY 1, 2, 3
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(start_time)
+    .bind(end_time)
+    .bind(&request.provider)
+    .bind(&request.model)
+    .bind(&request.environment)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    let (totals, models) = build_report(rows, fallbacks, request.limit);
+
+    info!(
+        org_id = %auth.org_id,
+        models = models.len(),
+        retry_rate = totals.retry_rate,
+        fallback_rate = totals.fallback_rate,
+        "Retry report computed"
+    );
+
+    Ok(Json(RetryResponse {
+        start_time,
+        end_time,
+        totals,
+        models,
+    }))
+}
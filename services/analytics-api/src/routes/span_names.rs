@@ -0,0 +1,183 @@
+//! # Span Name Cardinality API Routes
+//!
+//! Span names that embed dynamic IDs split one operation into thousands of
+//! names, so latency and cost grouped by span name stop meaning anything.
+//! This endpoint finds the offending names, grouped by template, with a
+//! collector rule that normalizes them.
+//!
+//! ## Endpoints
+//! - GET /api/v1/span-names/cardinality - Span name templates with many distinct names
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `read:traces`
+//! - Results are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::span_names::*;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create span name routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/api/v1/span-names/cardinality", get(get_span_name_cardinality))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        error!(error = %err, "Span name cardinality query failed");
+        ApiError::Internal(format!("Database query failed: {}", err))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/span-names/cardinality
+// ============================================================================
+
+/// GET /api/v1/span-names/cardinality - Span name templates with many distinct names
+///
+/// Groups the span names of the window by template and reports templates
+/// with at least `min_distinct_names` distinct names. The template is the
+/// one recorded by the collector's span name processor, or else the name
+/// masked the same way: segments between `-`, `_`, `.`, `/`, `:` and spaces
+/// that contain a digit become `{id}`, and runs of them one `{id}`. Names of
+/// spans the collector renamed count under their original name.
+///
+/// Query Parameters:
+/// - window_hours: Hours to inspect, ending now (1-168) - default: 24
+/// - environment: Filter by environment (optional)
+/// - min_distinct_names: Distinct names at which a template is reported - default: 20
+/// - limit: Maximum templates to return (1-500) - default: 50
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/span-names/cardinality?window_hours=6' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_span_name_cardinality(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<SpanNameCardinalityRequest>,
+) -> Result<Json<SpanNameCardinalityResponse>, ApiError> {
+    if !auth.has_permission("read:traces") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read span names".to_string(),
+        ));
+    }
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let window_end = Utc::now();
+    let window_start = window_end - Duration::hours(request.window_hours);
+
+    let rows = sqlx::query_as::<_, SpanNameTemplateRow>(
+        r#"
+        WITH names AS (
+            SELECT
+                ts,
+                span_name,
+                COALESCE(attributes->>'llm_observatory.span_name.original', span_name) AS name,
+                attributes->>'llm_observatory.span_name.template' AS recorded_template
+            FROM llm_traces
+            WHERE attributes->>'org_id' = $1
+              AND ts >= $2
+              AND ts < $3
+              AND ($4::TEXT IS NULL OR environment = $4)
+        ),
+        templated AS (
+            SELECT
+                ts,
+                span_name,
+                name,
+                COALESCE(
+                    recorded_template,
+                    regexp_replace(
+                        regexp_replace(name, '[^-_./: ]*[0-9][^-_./: ]*', '{id}', 'g'),
+                        '\{id\}([-_./: ]\{id\})+', '{id}', 'g'
+                    )
+                ) AS template
+            FROM names
+        )
+        SELECT
+            template,
+            COUNT(DISTINCT name) AS distinct_names,
+            COUNT(*) AS span_count,
+            COUNT(*) FILTER (WHERE span_name = template) AS normalized_count,
+            (ARRAY_AGG(DISTINCT name ORDER BY name))[1:5] AS sample_names,
+            MIN(ts) AS first_seen,
+            MAX(ts) AS last_seen
+        FROM templated
+        WHERE template <> name
+        GROUP BY template
+        HAVING COUNT(DISTINCT name) >= $5
+        ORDER BY distinct_names DESC, span_count DESC, template
+        LIMIT $6
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(window_start)
+    .bind(window_end)
+    .bind(&request.environment)
+    .bind(request.min_distinct_names)
+    .bind(request.limit)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    let templates: Vec<SpanNameTemplate> = rows.into_iter().map(build_template).collect();
+
+    info!(
+        org_id = %auth.org_id,
+        window_hours = request.window_hours,
+        templates = templates.len(),
+        "Span name cardinality computed"
+    );
+
+    Ok(Json(SpanNameCardinalityResponse {
+        window_start,
+        window_end,
+        min_distinct_names: request.min_distinct_names,
+        templates,
+    }))
+}
@@ -0,0 +1,147 @@
+//! # Service Topology API Route
+//!
+//! `GET /api/v1/topology` returns the dependency graph between services,
+//! models and tools (app → gateway → provider → tool) with call counts,
+//! error rates and latency per edge, from edges materialized by the topology
+//! materializer.
+//!
+//! ## Security
+//! - JWT authentication required
+//! - Requires `metrics:read`
+//! - Edges are organization-scoped
+
+use crate::middleware::AuthContext;
+use crate::models::topology::*;
+use crate::models::{AppState, ErrorResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create topology routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/api/v1/topology", get(get_topology))
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::Internal(msg) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg)
+            }
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/topology
+// ============================================================================
+
+/// GET /api/v1/topology - Service dependency graph
+///
+/// ## Query Parameters
+/// - `start_time`: Start of time range (ISO 8601) - default: 1 hour ago
+/// - `end_time`: End of time range (ISO 8601) - default: now
+/// - `node`: Only edges into or out of the node with this name
+///
+/// ## Example
+/// ```bash
+/// curl -X GET 'http://localhost:8080/api/v1/topology?node=gateway' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_topology(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Query(request): Query<TopologyRequest>,
+) -> Result<Json<TopologyResponse>, ApiError> {
+    // Check permissions
+    if !auth.has_permission("metrics:read") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to read topology".to_string(),
+        ));
+    }
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let end_time = request.end_time.unwrap_or_else(Utc::now);
+    let start_time = request
+        .start_time
+        .unwrap_or_else(|| end_time - Duration::hours(1));
+
+    info!(org_id = %auth.org_id, node = ?request.node, "Querying service topology");
+
+    let rows = sqlx::query_as::<_, TopologyEdgeRow>(
+        r#"
+        SELECT
+            source_kind,
+            source_name,
+            target_kind,
+            target_name,
+            SUM(call_count)::BIGINT AS call_count,
+            SUM(error_count)::BIGINT AS error_count,
+            SUM(avg_duration_ms * call_count) / NULLIF(SUM(call_count), 0) AS avg_duration_ms,
+            SUM(p95_duration_ms * call_count) / NULLIF(SUM(call_count), 0) AS p95_duration_ms
+        FROM service_edges
+        WHERE org_id = $1
+          AND bucket >= $2
+          AND bucket < $3
+          AND ($4::TEXT IS NULL OR source_name = $4 OR target_name = $4)
+        GROUP BY source_kind, source_name, target_kind, target_name
+        ORDER BY call_count DESC
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(start_time)
+    .bind(end_time)
+    .bind(&request.node)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to query service edges");
+        ApiError::Internal(format!("Database query failed: {}", e))
+    })?;
+
+    let (nodes, edges) = build_topology(&rows);
+
+    info!(nodes = nodes.len(), edges = edges.len(), "Topology query completed");
+
+    Ok(Json(TopologyResponse {
+        start_time,
+        end_time,
+        nodes,
+        edges,
+    }))
+}
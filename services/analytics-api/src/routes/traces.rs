@@ -6,9 +6,18 @@
 ///! - `GET /api/v1/traces` - List traces with filtering and pagination
 ///! - `POST /api/v1/traces/search` - Advanced search with complex filters and operators
 ///! - `GET /api/v1/traces/:trace_id` - Get a single trace by ID
+///! - `DELETE /api/v1/traces` - Bulk delete traces matching a search filter
+///! - `GET /api/v1/traces/deletions/:job_id` - Progress of a bulk deletion
 ///!
 ///! # Authentication
-///! All endpoints require authentication via JWT token or API key.
+///! All endpoints require authentication via JWT token or API key. Bulk
+///! deletion requires `delete:traces` (admins only).
+///!
+///! # Field-Level Masking
+///! `input_text`/`output_text` require `read:trace_content` and
+///! `user_id`/`session_id` require `read:user_identifiers`; without them the
+///! fields are returned as `[REDACTED]` and can't be searched or filtered on.
+///! Responses that include them unmasked are recorded in the audit log.
 ///!
 ///! # Rate Limiting
 ///! Rate limits are enforced based on user role:
@@ -17,8 +26,11 @@
 ///! - Viewer: 1,000 req/min
 
 use crate::middleware::AuthContext;
+use crate::models::deletion::{DeleteTracesRequest, TraceDeletionJobResponse};
 use crate::models::traces::*;
+use crate::models::filters::normalize_search_query;
 use crate::models::{AdvancedSearchRequest, AppState, ErrorResponse, Filter};
+use crate::services::trace_deletion::{self, TraceDeletionError, TraceSelection};
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
@@ -31,15 +43,17 @@ use redis::AsyncCommands;
 use serde_json::json;
 use sqlx::{postgres::PgRow, Row};
 use std::sync::Arc;
+use uuid::Uuid;
 use std::time::Instant;
 use tracing::{error, info, instrument, warn};
 
 /// Create trace routes
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
-        .route("/api/v1/traces", get(list_traces))
+        .route("/api/v1/traces", get(list_traces).delete(delete_traces))
         .route("/api/v1/traces/search", post(search_traces))
         .route("/api/v1/traces/:trace_id", get(get_trace_by_id))
+        .route("/api/v1/traces/deletions/:job_id", get(get_deletion_job))
 }
 
 /// GET /api/v1/traces - List traces with filtering and pagination
@@ -60,10 +74,14 @@ pub fn routes() -> Router<Arc<AppState>> {
 /// - `environment`: Filter by environment
 /// - `user_id`, `session_id`: Filter by user or session
 /// - `tags`: Comma-separated tags to filter
-/// - `search`: Full-text search in input/output
+/// - `attributes`: JSON object of attribute key/values the trace must contain
+///   (e.g. `{"gen_ai.system":"openai"}`)
+/// - `search`: Full-text search in input/output. Terms are ANDed; supports
+///   `"exact phrase"`, `OR` and `-term`/`NOT term`. Matching traces carry a
+///   `search_rank` relevance score
 /// - `cursor`: Pagination cursor from previous response
 /// - `limit`: Results per page (default: 50, max: 1000)
-/// - `sort_by`: Field to sort by (default: "ts")
+/// - `sort_by`: Field to sort by (default: "ts", "search_rank" with `search`)
 /// - `sort_order`: "asc" or "desc" (default: "desc")
 /// - `fields`: Comma-separated fields to include
 /// - `include`: Include related data ("children", "evaluations")
@@ -110,6 +128,13 @@ async fn list_traces(
         ));
     }
 
+    // Masked fields can't be searched or filtered on
+    let filter_fields = trace_query_filter_fields(&query);
+    state
+        .data_access
+        .check_filter_fields(&auth, filter_fields.iter().map(String::as_str))
+        .map_err(ApiError::Forbidden)?;
+
     // Validate and enforce project access
     let project_id = auth
         .require_project_access(query.project_id.as_deref())
@@ -134,8 +159,9 @@ async fn list_traces(
     if query.cursor.is_none() {
         if let Ok(mut redis_conn) = state.redis_client.get_async_connection().await {
             if let Ok(cached) = redis_conn.get::<_, String>(&cache_key).await {
-                if let Ok(response) = serde_json::from_str::<PaginatedTraceResponse>(&cached) {
+                if let Ok(mut response) = serde_json::from_str::<PaginatedTraceResponse>(&cached) {
                     info!("Returning cached trace list");
+                    mask_traces(&state, &auth, "GET /api/v1/traces", &mut response.data).await?;
                     return Ok(Json(response));
                 }
             }
@@ -151,6 +177,7 @@ async fn list_traces(
     if has_more {
         data.pop(); // Remove the extra record
     }
+    mask_traces(&state, &auth, "GET /api/v1/traces", &mut data).await?;
 
     // Generate next cursor
     let next_cursor = if has_more {
@@ -240,6 +267,8 @@ async fn list_traces(
 /// - Collection: `in`, `not_in`, `contains`, `not_contains`
 /// - String: `starts_with`, `ends_with`, `regex`
 /// - Full-text: `search`
+/// - Attributes: use `attributes.<key>` as the field (e.g. `attributes.gen_ai.system`);
+///   `eq`, `ne`, `in`, `not_in` and `exists` use the GIN index on `attributes`
 ///
 /// # Logical Operators
 /// - `and`: All filters must match
@@ -300,6 +329,12 @@ async fn search_traces(
         filter.validate().map_err(|e| {
             ApiError::BadRequest(format!("Invalid filter: {}", e))
         })?;
+
+        // Masked fields can't be searched or filtered on
+        state
+            .data_access
+            .check_filter_fields(&auth, filter.fields())
+            .map_err(ApiError::Forbidden)?;
     }
 
     // Generate cache key
@@ -309,8 +344,10 @@ async fn search_traces(
     if search_req.cursor.is_none() {
         if let Ok(mut redis_conn) = state.redis_client.get_async_connection().await {
             if let Ok(cached) = redis_conn.get::<_, String>(&cache_key).await {
-                if let Ok(response) = serde_json::from_str::<PaginatedTraceResponse>(&cached) {
+                if let Ok(mut response) = serde_json::from_str::<PaginatedTraceResponse>(&cached) {
                     info!("Returning cached search results");
+                    mask_traces(&state, &auth, "POST /api/v1/traces/search", &mut response.data)
+                        .await?;
                     return Ok(Json(response));
                 }
             }
@@ -333,6 +370,7 @@ async fn search_traces(
     if has_more {
         data.pop(); // Remove the extra record
     }
+    mask_traces(&state, &auth, "POST /api/v1/traces/search", &mut data).await?;
 
     // Generate next cursor
     let next_cursor = if has_more {
@@ -390,7 +428,9 @@ async fn search_traces(
 
 /// GET /api/v1/traces/:trace_id - Get a single trace by ID
 ///
-/// Returns a single trace with all its details.
+/// Returns a single trace with all its details, and the traces related to
+/// it by span links: traces its spans link to (`outgoing`) and traces with
+/// spans linking to it (`incoming`), e.g. the sub-agents an agent fanned out.
 ///
 /// # Path Parameters
 /// - `trace_id`: The trace ID to retrieve
@@ -403,6 +443,9 @@ async fn search_traces(
 /// {
 ///   "status": "success",
 ///   "data": {...trace...},
+///   "linked_traces": [
+///     {"trace_id": "0af7651916cd43dd8448eb211c80319c", "direction": "incoming", "link_count": 1}
+///   ],
 ///   "meta": {
 ///     "timestamp": "2025-11-05T10:00:00Z",
 ///     "execution_time_ms": 12,
@@ -438,8 +481,15 @@ async fn get_trace_by_id(
     // Try to get from cache
     if let Ok(mut redis_conn) = state.redis_client.get_async_connection().await {
         if let Ok(cached) = redis_conn.get::<_, String>(&cache_key).await {
-            if let Ok(response) = serde_json::from_str::<SingleTraceResponse>(&cached) {
+            if let Ok(mut response) = serde_json::from_str::<SingleTraceResponse>(&cached) {
                 info!("Returning cached trace");
+                mask_traces(
+                    &state,
+                    &auth,
+                    "GET /api/v1/traces/:trace_id",
+                    std::slice::from_mut(&mut response.data),
+                )
+                .await?;
                 return Ok(Json(response));
             }
         }
@@ -458,7 +508,7 @@ async fn get_trace_by_id(
             duration_ms, ttft_ms,
             status_code, error_message,
             user_id, session_id, environment,
-            tags, attributes
+            tags, attributes, links
         FROM llm_traces
         WHERE trace_id = $1
         ORDER BY ts DESC
@@ -486,12 +536,22 @@ async fn get_trace_by_id(
     // Fill in calculated fields
     trace.calculate_total_cost();
     trace.calculate_total_tokens();
+    mask_traces(
+        &state,
+        &auth,
+        "GET /api/v1/traces/:trace_id",
+        std::slice::from_mut(&mut trace),
+    )
+    .await?;
+
+    let linked_traces = fetch_linked_traces(&state, &auth, &trace_id).await?;
 
     let execution_time = start_time.elapsed().as_millis() as u64;
 
     let response = SingleTraceResponse {
         status: ResponseStatus::Success,
         data: trace,
+        linked_traces,
         meta: ResponseMetadata {
             timestamp: Utc::now(),
             execution_time_ms: execution_time,
@@ -516,6 +576,173 @@ async fn get_trace_by_id(
     Ok(Json(response))
 }
 
+/// Traces related to `trace_id` by span links, in both directions
+async fn fetch_linked_traces(
+    state: &AppState,
+    auth: &AuthContext,
+    trace_id: &str,
+) -> Result<Vec<LinkedTrace>, ApiError> {
+    // Links are stored as [{trace_id, span_id, attributes}]; incoming links
+    // are found by containment, served by the GIN index on links
+    sqlx::query_as::<_, LinkedTrace>(
+        r#"
+        SELECT link->>'trace_id' AS trace_id, 'outgoing' AS direction, COUNT(*) AS link_count
+        FROM llm_traces t
+        CROSS JOIN LATERAL jsonb_array_elements(
+            CASE WHEN jsonb_typeof(t.links) = 'array' THEN t.links ELSE '[]'::jsonb END
+        ) AS link
+        WHERE t.attributes->>'org_id' = $1
+          AND t.trace_id = $2
+          AND link->>'trace_id' <> $2
+        GROUP BY 1
+        UNION ALL
+        SELECT trace_id, 'incoming' AS direction, COUNT(*) AS link_count
+        FROM llm_traces
+        WHERE attributes->>'org_id' = $1
+          AND links @> $3
+          AND trace_id <> $2
+        GROUP BY 1
+        ORDER BY direction, trace_id
+        "#,
+    )
+    .bind(&auth.org_id)
+    .bind(trace_id)
+    .bind(json!([{ "trace_id": trace_id }]))
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| {
+        error!("Database query error: {}", e);
+        ApiError::Internal(format!("Failed to fetch linked traces: {}", e))
+    })
+}
+
+/// DELETE /api/v1/traces - Bulk delete traces matching a search filter
+///
+/// Selects spans with the same filter model as `POST /api/v1/traces/search`
+/// and deletes every matching trace with all of its spans and logs. Defaults
+/// to a dry run that only reports what would be deleted; with
+/// `"dry_run": false` a background job deletes the traces in batches and its
+/// progress is available at `GET /api/v1/traces/deletions/:job_id`.
+///
+/// Cached responses (up to 5 minutes for single traces) may still return
+/// deleted traces until they expire.
+///
+/// # Request Body
+/// - `filter`: Filter expression (required)
+/// - `start_time`: Only spans at or after this time - default: no lower bound
+/// - `end_time`: Only spans before this time - default: now
+/// - `dry_run`: Only count matches - default: true
+///
+/// # Response
+/// Dry run (200):
+/// ```json
+/// {
+///   "dry_run": true,
+///   "end_time": "2025-11-05T10:00:00Z",
+///   "matched_traces": 1200,
+///   "matched_spans": 4800,
+///   "matched_logs": 950,
+///   "sample_trace_ids": ["..."]
+/// }
+/// ```
+///
+/// Otherwise 202 with the created job (see `GET /api/v1/traces/deletions/:job_id`).
+#[instrument(skip(state, auth, request))]
+async fn delete_traces(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Json(request): Json<DeleteTracesRequest>,
+) -> Result<Response, ApiError> {
+    info!(
+        user_id = %auth.user_id,
+        org_id = %auth.org_id,
+        dry_run = request.dry_run,
+        "Bulk trace deletion requested"
+    );
+
+    // Check permission
+    if !auth.has_permission("delete:traces") {
+        warn!(user_id = %auth.user_id, "Insufficient permissions to delete traces");
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to delete traces".to_string(),
+        ));
+    }
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    // Masked fields can't be used to select traces either
+    state
+        .data_access
+        .check_filter_fields(&auth, request.filter.fields())
+        .map_err(ApiError::Forbidden)?;
+
+    let selection = TraceSelection {
+        org_id: auth.org_id.clone(),
+        filter: request.filter,
+        start_time: request.start_time,
+        end_time: request.end_time.unwrap_or_else(Utc::now),
+    };
+
+    if request.dry_run {
+        let preview = trace_deletion::preview(&state.db_pool, &selection).await?;
+        return Ok(Json(preview).into_response());
+    }
+
+    let job = state
+        .trace_deletion
+        .start(selection, &auth.user_id)
+        .await?;
+
+    warn!(
+        user_id = %auth.user_id,
+        org_id = %auth.org_id,
+        job_id = %job.job_id,
+        matched_traces = job.matched_traces,
+        "Bulk trace deletion started"
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(TraceDeletionJobResponse::from(job))).into_response())
+}
+
+/// GET /api/v1/traces/deletions/:job_id - Progress of a bulk deletion
+///
+/// # Response
+/// ```json
+/// {
+///   "job_id": "550e8400-e29b-41d4-a716-446655440000",
+///   "status": "running",
+///   "matched_traces": 1200,
+///   "deleted_traces": 600,
+///   "deleted_spans": 2400,
+///   "deleted_logs": 480,
+///   "batches_completed": 6,
+///   "progress_percent": 50.0,
+///   ...
+/// }
+/// ```
+#[instrument(skip(state, auth))]
+async fn get_deletion_job(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<TraceDeletionJobResponse>, ApiError> {
+    // Check permission
+    if !auth.has_permission("delete:traces") {
+        return Err(ApiError::Forbidden(
+            "Insufficient permissions to view trace deletions".to_string(),
+        ));
+    }
+
+    let job = state
+        .trace_deletion
+        .get_job(&auth.org_id, job_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Deletion job '{}' not found", job_id)))?;
+
+    Ok(Json(job.into()))
+}
+
 /// Query traces from database with filters
 async fn query_traces(
     pool: &sqlx::PgPool,
@@ -536,12 +763,19 @@ async fn query_traces(
             duration_ms, ttft_ms,
             status_code, error_message,
             user_id, session_id, environment,
-            tags, attributes
+            tags, attributes{search_rank}
         FROM llm_traces
         WHERE 1=1
         "#,
     );
 
+    let search_query = query
+        .search
+        .as_deref()
+        .map(normalize_search_query)
+        .transpose()
+        .map_err(ApiError::BadRequest)?;
+
     let mut bind_index = 1;
     let mut bindings: Vec<Box<dyn sqlx::Encode<'_, sqlx::Postgres> + Send + Sync>> = Vec::new();
 
@@ -634,18 +868,40 @@ async fn query_traces(
         bind_index += 1;
     }
 
-    // Full-text search
-    if let Some(search) = &query.search {
+    // Attribute containment filter (served by the GIN index on attributes)
+    let attribute_filter = query
+        .attribute_filter()
+        .map_err(ApiError::BadRequest)?
+        .map(|value| value.to_string());
+    if attribute_filter.is_some() {
+        sql.push_str(&format!(" AND attributes @> ${}::jsonb", bind_index));
+        bind_index += 1;
+    }
+
+    // Full-text search on the weighted input/output tsvector (GIN indexed)
+    let search_rank = if search_query.is_some() {
         sql.push_str(&format!(
-            " AND (input_text ILIKE ${} OR output_text ILIKE ${})",
-            bind_index,
-            bind_index + 1
+            " AND content_search @@ websearch_to_tsquery('english', ${})",
+            bind_index
         ));
-        bind_index += 2;
-    }
+        let column = format!(
+            ",\n            ts_rank_cd(content_search, websearch_to_tsquery('english', ${})) AS search_rank",
+            bind_index
+        );
+        bind_index += 1;
+        column
+    } else {
+        String::new()
+    };
+    sql = sql.replace("{search_rank}", &search_rank);
 
     // Order by
     let sort_by = query.sort_by.as_deref().unwrap_or("ts");
+    if sort_by == "search_rank" && search_query.is_none() {
+        return Err(ApiError::BadRequest(
+            "sort_by=search_rank requires a search query".to_string(),
+        ));
+    }
     let sort_order = match query.sort_order {
         Some(SortOrder::Asc) => "ASC",
         _ => "DESC",
@@ -727,10 +983,12 @@ async fn query_traces(
         sqlx_query = sqlx_query.bind(project_id);
     }
 
-    // Create search pattern before using it (lifetime issue)
-    let search_pattern = query.search.as_ref().map(|s| format!("%{}%", s));
-    if let Some(ref pattern) = search_pattern {
-        sqlx_query = sqlx_query.bind(pattern).bind(pattern);
+    if let Some(ref attributes) = attribute_filter {
+        sqlx_query = sqlx_query.bind(attributes);
+    }
+
+    if let Some(ref search) = search_query {
+        sqlx_query = sqlx_query.bind(search);
     }
 
     sqlx_query = sqlx_query.bind(limit);
@@ -750,6 +1008,46 @@ async fn query_traces(
     Ok(traces)
 }
 
+/// Apply field-level masking for the caller, auditing unmasked access.
+async fn mask_traces(
+    state: &AppState,
+    auth: &AuthContext,
+    endpoint: &str,
+    traces: &mut [Trace],
+) -> Result<(), ApiError> {
+    state
+        .data_access
+        .apply(auth, endpoint, traces)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to record data access audit entry");
+            ApiError::Internal("Failed to record data access audit entry".to_string())
+        })
+}
+
+/// Sensitive fields a trace list query searches or filters on
+fn trace_query_filter_fields(query: &TraceQuery) -> Vec<String> {
+    let mut fields = Vec::new();
+    if query.search.is_some() {
+        fields.push("input_text".to_string());
+        fields.push("output_text".to_string());
+    }
+    if query.user_id.is_some() {
+        fields.push("user_id".to_string());
+    }
+    if query.session_id.is_some() {
+        fields.push("session_id".to_string());
+    }
+    if let Some(attributes) = query
+        .attributes
+        .as_deref()
+        .and_then(|a| serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(a).ok())
+    {
+        fields.extend(attributes.keys().map(|key| format!("attributes.{}", key)));
+    }
+    fields
+}
+
 /// Validate and clamp limit
 fn validate_limit(limit: i32) -> Result<i32, ApiError> {
     if limit < 1 {
@@ -784,6 +1082,9 @@ fn generate_cache_key(user_id: &str, query: &TraceQuery, project_id: &str) -> St
     query.min_duration.hash(&mut hasher);
     query.max_duration.hash(&mut hasher);
     query.environment.hash(&mut hasher);
+    query.attributes.hash(&mut hasher);
+    query.search.hash(&mut hasher);
+    query.sort_by.hash(&mut hasher);
     query.limit.hash(&mut hasher);
 
     let hash = hasher.finish();
@@ -1013,6 +1314,20 @@ pub enum ApiError {
     NotFound(String),
     Forbidden(String),
     Internal(String),
+    ServiceUnavailable(String),
+}
+
+impl From<TraceDeletionError> for ApiError {
+    fn from(e: TraceDeletionError) -> Self {
+        match e {
+            TraceDeletionError::Disabled => ApiError::ServiceUnavailable(e.to_string()),
+            TraceDeletionError::Filter(_) => ApiError::BadRequest(e.to_string()),
+            TraceDeletionError::Database(_) => {
+                error!(error = %e, "Trace deletion query failed");
+                ApiError::Internal(e.to_string())
+            }
+        }
+    }
 }
 
 impl IntoResponse for ApiError {
@@ -1022,6 +1337,9 @@ impl IntoResponse for ApiError {
             ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, "NOT_FOUND", msg),
             ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "FORBIDDEN", msg),
             ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR", msg),
+            ApiError::ServiceUnavailable(msg) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "SERVICE_UNAVAILABLE", msg)
+            }
         };
 
         let body = Json(json!({
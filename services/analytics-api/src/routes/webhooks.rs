@@ -0,0 +1,423 @@
+//! # Webhook API Routes
+//!
+//! - `POST /api/v1/webhooks` registers a webhook endpoint
+//! - `GET /api/v1/webhooks` lists the organization's endpoints
+//! - `DELETE /api/v1/webhooks/:id` deletes an endpoint and its deliveries
+//! - `POST /api/v1/webhooks/:id/test` sends a test event to an endpoint
+//! - `GET /api/v1/webhooks/:id/deliveries` lists recent deliveries
+//! - `POST /api/v1/notification-channels` registers a Slack or PagerDuty
+//!   channel
+//! - `GET /api/v1/notification-channels` lists the organization's channels
+//! - `DELETE /api/v1/notification-channels/:id` deletes a channel
+//! - `POST /api/v1/notification-channels/:id/test` sends a test notification
+//! - `GET /api/v1/notification-channels/:id/deliveries` lists recent
+//!   deliveries
+//!
+//! ## Security
+//! - JWT authentication required
+//! - All endpoints require `manage:webhooks` (admins only by default)
+//! - Endpoints and channels are organization-scoped; the signing secret is
+//!   only returned on registration, and channel targets (Slack webhook URLs,
+//!   PagerDuty routing keys) are never returned
+
+use crate::middleware::AuthContext;
+use crate::models::webhooks::*;
+use crate::models::{AppState, ErrorResponse};
+use crate::services::webhooks::WebhookServiceError;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use llm_observatory_webhooks::{
+    NotificationChannel, WebhookDelivery, WebhookEndpoint, WebhookError,
+};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// Permission required by every webhook endpoint
+const MANAGE_PERMISSION: &str = "manage:webhooks";
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Create webhook routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/api/v1/webhooks", post(create_webhook).get(list_webhooks))
+        .route("/api/v1/webhooks/:id", delete(delete_webhook))
+        .route("/api/v1/webhooks/:id/test", post(test_webhook))
+        .route("/api/v1/webhooks/:id/deliveries", get(list_deliveries))
+        .route(
+            "/api/v1/notification-channels",
+            post(create_channel).get(list_channels),
+        )
+        .route("/api/v1/notification-channels/:id", delete(delete_channel))
+        .route("/api/v1/notification-channels/:id/test", post(test_channel))
+        .route(
+            "/api/v1/notification-channels/:id/deliveries",
+            get(list_channel_deliveries),
+        )
+}
+
+// ============================================================================
+// API Error Type
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Forbidden(String),
+    NotFound(String),
+    Internal(String),
+    ServiceUnavailable(String),
+}
+
+impl From<WebhookServiceError> for ApiError {
+    fn from(e: WebhookServiceError) -> Self {
+        match e {
+            WebhookServiceError::Disabled => ApiError::ServiceUnavailable(e.to_string()),
+            WebhookServiceError::Webhook(WebhookError::NotFound(id)) => {
+                ApiError::NotFound(format!("Webhook {} not found", id))
+            }
+            WebhookServiceError::Webhook(_) => {
+                error!(error = %e, "Webhook operation failed");
+                ApiError::Internal(e.to_string())
+            }
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, "not_found", msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+            ApiError::ServiceUnavailable(msg) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "service_unavailable", msg)
+            }
+        };
+
+        let body = Json(ErrorResponse {
+            error: error_type.to_string(),
+            message,
+            details: None,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+fn require_manage(auth: &AuthContext) -> Result<(), ApiError> {
+    if auth.has_permission(MANAGE_PERMISSION) {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(
+            "Insufficient permissions to manage webhooks".to_string(),
+        ))
+    }
+}
+
+async fn find_endpoint(
+    state: &AppState,
+    auth: &AuthContext,
+    id: Uuid,
+) -> Result<WebhookEndpoint, ApiError> {
+    state
+        .webhooks
+        .get(&auth.org_id, id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Webhook {} not found", id)))
+}
+
+async fn find_channel(
+    state: &AppState,
+    auth: &AuthContext,
+    id: Uuid,
+) -> Result<NotificationChannel, ApiError> {
+    state
+        .webhooks
+        .get_channel(&auth.org_id, id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Notification channel {} not found", id)))
+}
+
+// ============================================================================
+// Endpoint: POST /api/v1/webhooks
+// ============================================================================
+
+/// POST /api/v1/webhooks - Register a webhook endpoint
+///
+/// Returns the endpoint with its signing secret, which is not shown again.
+///
+/// ## Example
+/// ```bash
+/// curl -X POST 'http://localhost:8080/api/v1/webhooks' \
+///   -H "Authorization: Bearer $JWT_TOKEN" \
+///   -H "Content-Type: application/json" \
+///   -d '{"url": "https://hooks.example.com/observatory", "event_types": ["budget.threshold_exceeded"]}'
+/// ```
+#[instrument(skip(state, auth, request))]
+async fn create_webhook(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Json(request): Json<NewWebhookEndpoint>,
+) -> Result<(StatusCode, Json<CreatedWebhookEndpoint>), ApiError> {
+    // Check permissions
+    require_manage(&auth)?;
+
+    // Validate request
+    request
+        .validate()
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let endpoint = state.webhooks.register(&auth.org_id, &request).await?;
+    info!(org_id = %auth.org_id, endpoint_id = %endpoint.id, "Registered webhook endpoint");
+
+    let secret = endpoint.secret.clone();
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedWebhookEndpoint { endpoint, secret }),
+    ))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/webhooks
+// ============================================================================
+
+/// GET /api/v1/webhooks - Webhook endpoints of the organization
+#[instrument(skip(state, auth))]
+async fn list_webhooks(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+) -> Result<Json<WebhookEndpointListResponse>, ApiError> {
+    // Check permissions
+    require_manage(&auth)?;
+
+    let endpoints = state.webhooks.list(&auth.org_id).await?;
+    Ok(Json(WebhookEndpointListResponse { endpoints }))
+}
+
+// ============================================================================
+// Endpoint: DELETE /api/v1/webhooks/:id
+// ============================================================================
+
+/// DELETE /api/v1/webhooks/:id - Delete a webhook endpoint and its deliveries
+#[instrument(skip(state, auth))]
+async fn delete_webhook(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    // Check permissions
+    require_manage(&auth)?;
+
+    if !state.webhooks.delete(&auth.org_id, id).await? {
+        return Err(ApiError::NotFound(format!("Webhook {} not found", id)));
+    }
+
+    info!(org_id = %auth.org_id, endpoint_id = %id, "Deleted webhook endpoint");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Endpoint: POST /api/v1/webhooks/:id/test
+// ============================================================================
+
+/// POST /api/v1/webhooks/:id/test - Send a test event
+///
+/// Sends a signed `webhook.test` event once, without retries, and returns
+/// the delivery with the endpoint's response status or error. Disabled
+/// endpoints and event subscriptions are ignored.
+///
+/// ## Example
+/// ```bash
+/// curl -X POST 'http://localhost:8080/api/v1/webhooks/8f14e45f-ceea-467f-a8f0-2b1c3d4e5f60/test' \
+///   -H "Authorization: Bearer $JWT_TOKEN"
+/// ```
+#[instrument(skip(state, auth))]
+async fn test_webhook(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WebhookDelivery>, ApiError> {
+    // Check permissions
+    require_manage(&auth)?;
+
+    let endpoint = find_endpoint(&state, &auth, id).await?;
+
+    let delivery = state.webhooks.test_fire(&endpoint).await?;
+    Ok(Json(delivery))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/webhooks/:id/deliveries
+// ============================================================================
+
+/// GET /api/v1/webhooks/:id/deliveries - Recent deliveries, newest first
+///
+/// ## Query Parameters
+/// - `limit`: Maximum deliveries - default: 50, max: 500
+#[instrument(skip(state, auth))]
+async fn list_deliveries(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(id): Path<Uuid>,
+    Query(request): Query<DeliveryListQuery>,
+) -> Result<Json<DeliveryListResponse>, ApiError> {
+    // Check permissions
+    require_manage(&auth)?;
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let endpoint = find_endpoint(&state, &auth, id).await?;
+    let deliveries = state
+        .webhooks
+        .deliveries(&auth.org_id, endpoint.id, request.limit)
+        .await?;
+
+    Ok(Json(DeliveryListResponse { deliveries }))
+}
+
+// ============================================================================
+// Endpoint: POST /api/v1/notification-channels
+// ============================================================================
+
+/// POST /api/v1/notification-channels - Register a Slack or PagerDuty channel
+///
+/// Events are routed to the channel by event type, alert rule and minimum
+/// severity; empty `event_types` and `alert_rules` match everything.
+///
+/// ## Example
+/// ```bash
+/// curl -X POST 'http://localhost:8080/api/v1/notification-channels' \
+///   -H "Authorization: Bearer $JWT_TOKEN" \
+///   -H "Content-Type: application/json" \
+///   -d '{"kind": "pager_duty", "name": "LLM on-call", "target": "'$PD_ROUTING_KEY'", "min_severity": "critical"}'
+/// ```
+#[instrument(skip(state, auth, request))]
+async fn create_channel(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Json(request): Json<NewNotificationChannel>,
+) -> Result<(StatusCode, Json<NotificationChannel>), ApiError> {
+    // Check permissions
+    require_manage(&auth)?;
+
+    // Validate request
+    request
+        .validate()
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let channel = state
+        .webhooks
+        .register_channel(&auth.org_id, &request)
+        .await?;
+    info!(org_id = %auth.org_id, channel_id = %channel.id, kind = %channel.kind, "Registered notification channel");
+
+    Ok((StatusCode::CREATED, Json(channel)))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/notification-channels
+// ============================================================================
+
+/// GET /api/v1/notification-channels - Notification channels of the
+/// organization
+#[instrument(skip(state, auth))]
+async fn list_channels(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+) -> Result<Json<NotificationChannelListResponse>, ApiError> {
+    // Check permissions
+    require_manage(&auth)?;
+
+    let channels = state.webhooks.list_channels(&auth.org_id).await?;
+    Ok(Json(NotificationChannelListResponse { channels }))
+}
+
+// ============================================================================
+// Endpoint: DELETE /api/v1/notification-channels/:id
+// ============================================================================
+
+/// DELETE /api/v1/notification-channels/:id - Delete a channel and its
+/// deliveries
+#[instrument(skip(state, auth))]
+async fn delete_channel(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    // Check permissions
+    require_manage(&auth)?;
+
+    if !state.webhooks.delete_channel(&auth.org_id, id).await? {
+        return Err(ApiError::NotFound(format!(
+            "Notification channel {} not found",
+            id
+        )));
+    }
+
+    info!(org_id = %auth.org_id, channel_id = %id, "Deleted notification channel");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Endpoint: POST /api/v1/notification-channels/:id/test
+// ============================================================================
+
+/// POST /api/v1/notification-channels/:id/test - Send a test notification
+///
+/// Sends a `webhook.test` event once, without retries, and returns the
+/// delivery. On PagerDuty this opens an info-severity incident.
+#[instrument(skip(state, auth))]
+async fn test_channel(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WebhookDelivery>, ApiError> {
+    // Check permissions
+    require_manage(&auth)?;
+
+    let channel = find_channel(&state, &auth, id).await?;
+    let delivery = state.webhooks.test_fire_channel(&channel).await?;
+    Ok(Json(delivery))
+}
+
+// ============================================================================
+// Endpoint: GET /api/v1/notification-channels/:id/deliveries
+// ============================================================================
+
+/// GET /api/v1/notification-channels/:id/deliveries - Recent deliveries,
+/// newest first
+///
+/// ## Query Parameters
+/// - `limit`: Maximum deliveries - default: 50, max: 500
+#[instrument(skip(state, auth))]
+async fn list_channel_deliveries(
+    State(state): State<Arc<AppState>>,
+    auth: AuthContext,
+    Path(id): Path<Uuid>,
+    Query(request): Query<DeliveryListQuery>,
+) -> Result<Json<DeliveryListResponse>, ApiError> {
+    // Check permissions
+    require_manage(&auth)?;
+
+    // Validate request
+    request.validate().map_err(ApiError::BadRequest)?;
+
+    let channel = find_channel(&state, &auth, id).await?;
+    let deliveries = state
+        .webhooks
+        .channel_deliveries(&auth.org_id, channel.id, request.limit)
+        .await?;
+
+    Ok(Json(DeliveryListResponse { deliveries }))
+}
@@ -0,0 +1,374 @@
+//! # Organization Administration
+//!
+//! Organizations, teams, team membership and per-organization settings,
+//! written with the read-write connection. The settings are enforced
+//! elsewhere: the ingest host loads quotas and capture policies into the
+//! collector's processors, and `apply_organization_retention()` deletes
+//! traces and logs past an organization's retention.
+
+use crate::models::admin::*;
+use sqlx::PgPool;
+use tracing::info;
+use uuid::Uuid;
+
+/// PostgreSQL unique violation
+const UNIQUE_VIOLATION: &str = "23505";
+
+/// Errors from admin operations
+#[derive(Debug, thiserror::Error)]
+pub enum AdminError {
+    #[error("The admin API requires DATABASE_URL (read-write connection)")]
+    Disabled,
+
+    #[error("{0} not found")]
+    NotFound(String),
+
+    #[error("{0} already exists")]
+    Conflict(String),
+
+    #[error("Failed to sign token: {0}")]
+    Signing(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Map a unique violation to [`AdminError::Conflict`] on `what`.
+pub(crate) fn conflict(what: String) -> impl FnOnce(sqlx::Error) -> AdminError {
+    move |e| match &e {
+        sqlx::Error::Database(db) if db.code().as_deref() == Some(UNIQUE_VIOLATION) => {
+            AdminError::Conflict(what)
+        }
+        _ => AdminError::Database(e),
+    }
+}
+
+/// Columns of [`Team`], with its member count
+const TEAM_COLUMNS: &str = "t.id, t.org_id, t.name, t.description, \
+     (SELECT COUNT(*) FROM team_members m WHERE m.team_id = t.id) AS member_count, \
+     t.created_at, t.updated_at";
+
+/// Tenants and their settings, written with the read-write connection
+pub struct AdminService {
+    /// Read-write pool (None rejects every operation)
+    pool: Option<PgPool>,
+}
+
+impl AdminService {
+    pub fn new(pool: Option<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    /// A service that rejects every operation
+    pub fn disabled() -> Self {
+        Self::new(None)
+    }
+
+    fn pool(&self) -> Result<&PgPool, AdminError> {
+        self.pool.as_ref().ok_or(AdminError::Disabled)
+    }
+
+    // ------------------------------------------------------------------------
+    // Organizations
+    // ------------------------------------------------------------------------
+
+    /// All organizations, by ID
+    pub async fn list_organizations(&self) -> Result<Vec<Organization>, AdminError> {
+        Ok(sqlx::query_as::<_, Organization>(
+            "SELECT id, name, created_at, updated_at FROM organizations ORDER BY id",
+        )
+        .fetch_all(self.pool()?)
+        .await?)
+    }
+
+    pub async fn get_organization(&self, org_id: &str) -> Result<Organization, AdminError> {
+        sqlx::query_as::<_, Organization>(
+            "SELECT id, name, created_at, updated_at FROM organizations WHERE id = $1",
+        )
+        .bind(org_id)
+        .fetch_optional(self.pool()?)
+        .await?
+        .ok_or_else(|| AdminError::NotFound(format!("Organization {}", org_id)))
+    }
+
+    pub async fn create_organization(
+        &self,
+        request: &CreateOrganizationRequest,
+    ) -> Result<Organization, AdminError> {
+        let organization = sqlx::query_as::<_, Organization>(
+            r#"
+            INSERT INTO organizations (id, name)
+            VALUES ($1, $2)
+            RETURNING id, name, created_at, updated_at
+            "#,
+        )
+        .bind(&request.id)
+        .bind(request.name.trim())
+        .fetch_one(self.pool()?)
+        .await
+        .map_err(conflict(format!("Organization {}", request.id)))?;
+
+        info!(org_id = %organization.id, "Created organization");
+        Ok(organization)
+    }
+
+    pub async fn update_organization(
+        &self,
+        org_id: &str,
+        request: &UpdateOrganizationRequest,
+    ) -> Result<Organization, AdminError> {
+        sqlx::query_as::<_, Organization>(
+            r#"
+            UPDATE organizations SET name = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, created_at, updated_at
+            "#,
+        )
+        .bind(org_id)
+        .bind(request.name.trim())
+        .fetch_optional(self.pool()?)
+        .await?
+        .ok_or_else(|| AdminError::NotFound(format!("Organization {}", org_id)))
+    }
+
+    /// Delete an organization with its teams, memberships and settings.
+    /// Its traces are kept; delete them with a trace deletion job.
+    pub async fn delete_organization(&self, org_id: &str) -> Result<(), AdminError> {
+        let result = sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(self.pool()?)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AdminError::NotFound(format!("Organization {}", org_id)));
+        }
+
+        info!(org_id = %org_id, "Deleted organization");
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------
+    // Teams
+    // ------------------------------------------------------------------------
+
+    /// Teams of an organization, by name
+    pub async fn list_teams(&self, org_id: &str) -> Result<Vec<Team>, AdminError> {
+        self.get_organization(org_id).await?;
+
+        let sql = format!("SELECT {TEAM_COLUMNS} FROM teams t WHERE t.org_id = $1 ORDER BY t.name");
+        Ok(sqlx::query_as::<_, Team>(&sql)
+            .bind(org_id)
+            .fetch_all(self.pool()?)
+            .await?)
+    }
+
+    pub async fn get_team(&self, org_id: &str, team_id: Uuid) -> Result<Team, AdminError> {
+        let sql = format!("SELECT {TEAM_COLUMNS} FROM teams t WHERE t.org_id = $1 AND t.id = $2");
+        sqlx::query_as::<_, Team>(&sql)
+            .bind(org_id)
+            .bind(team_id)
+            .fetch_optional(self.pool()?)
+            .await?
+            .ok_or_else(|| AdminError::NotFound(format!("Team {}", team_id)))
+    }
+
+    pub async fn create_team(
+        &self,
+        org_id: &str,
+        request: &CreateTeamRequest,
+    ) -> Result<Team, AdminError> {
+        self.get_organization(org_id).await?;
+
+        let team_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO teams (org_id, name, description) VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(org_id)
+        .bind(request.name.trim())
+        .bind(&request.description)
+        .fetch_one(self.pool()?)
+        .await
+        .map_err(conflict(format!("Team {}", request.name.trim())))?;
+
+        info!(org_id = %org_id, team_id = %team_id, "Created team");
+        self.get_team(org_id, team_id).await
+    }
+
+    pub async fn update_team(
+        &self,
+        org_id: &str,
+        team_id: Uuid,
+        request: &UpdateTeamRequest,
+    ) -> Result<Team, AdminError> {
+        let name = request.name.as_deref().map(str::trim);
+        let result = sqlx::query(
+            r#"
+            UPDATE teams
+            SET name = COALESCE($3, name),
+                description = COALESCE($4, description),
+                updated_at = NOW()
+            WHERE org_id = $1 AND id = $2
+            "#,
+        )
+        .bind(org_id)
+        .bind(team_id)
+        .bind(name)
+        .bind(&request.description)
+        .execute(self.pool()?)
+        .await
+        .map_err(conflict(format!("Team {}", name.unwrap_or_default())))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AdminError::NotFound(format!("Team {}", team_id)));
+        }
+        self.get_team(org_id, team_id).await
+    }
+
+    /// Delete a team and its memberships
+    pub async fn delete_team(&self, org_id: &str, team_id: Uuid) -> Result<(), AdminError> {
+        let result = sqlx::query("DELETE FROM teams WHERE org_id = $1 AND id = $2")
+            .bind(org_id)
+            .bind(team_id)
+            .execute(self.pool()?)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AdminError::NotFound(format!("Team {}", team_id)));
+        }
+
+        info!(org_id = %org_id, team_id = %team_id, "Deleted team");
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------
+    // Team Members
+    // ------------------------------------------------------------------------
+
+    /// Members of a team, by user ID
+    pub async fn list_members(
+        &self,
+        org_id: &str,
+        team_id: Uuid,
+    ) -> Result<Vec<TeamMember>, AdminError> {
+        self.get_team(org_id, team_id).await?;
+
+        Ok(sqlx::query_as::<_, TeamMember>(
+            "SELECT user_id, role, added_at FROM team_members WHERE team_id = $1 ORDER BY user_id",
+        )
+        .bind(team_id)
+        .fetch_all(self.pool()?)
+        .await?)
+    }
+
+    /// Add a user to a team, or change their role if already a member
+    pub async fn put_member(
+        &self,
+        org_id: &str,
+        team_id: Uuid,
+        user_id: &str,
+        role: TeamRole,
+    ) -> Result<TeamMember, AdminError> {
+        self.get_team(org_id, team_id).await?;
+
+        let member = sqlx::query_as::<_, TeamMember>(
+            r#"
+            INSERT INTO team_members (team_id, user_id, role)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (team_id, user_id) DO UPDATE SET role = EXCLUDED.role
+            RETURNING user_id, role, added_at
+            "#,
+        )
+        .bind(team_id)
+        .bind(user_id)
+        .bind(role.as_str())
+        .fetch_one(self.pool()?)
+        .await?;
+
+        info!(org_id = %org_id, team_id = %team_id, user_id = %user_id, role = role.as_str(), "Set team member");
+        Ok(member)
+    }
+
+    pub async fn remove_member(
+        &self,
+        org_id: &str,
+        team_id: Uuid,
+        user_id: &str,
+    ) -> Result<(), AdminError> {
+        self.get_team(org_id, team_id).await?;
+
+        let result = sqlx::query("DELETE FROM team_members WHERE team_id = $1 AND user_id = $2")
+            .bind(team_id)
+            .bind(user_id)
+            .execute(self.pool()?)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AdminError::NotFound(format!("Member {}", user_id)));
+        }
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------
+    // Settings
+    // ------------------------------------------------------------------------
+
+    /// Settings of an organization, all unset if it never set any
+    pub async fn settings(&self, org_id: &str) -> Result<OrganizationSettings, AdminError> {
+        self.get_organization(org_id).await?;
+
+        let settings = sqlx::query_as::<_, OrganizationSettings>(
+            r#"
+            SELECT org_id, retention_days, capture_policy, spans_per_day_limit,
+                   bytes_per_day_limit, over_quota_action, updated_at, updated_by
+            FROM organization_settings
+            WHERE org_id = $1
+            "#,
+        )
+        .bind(org_id)
+        .fetch_optional(self.pool()?)
+        .await?;
+
+        Ok(settings.unwrap_or_else(|| OrganizationSettings::defaults(org_id.to_string())))
+    }
+
+    /// Replace the settings of an organization
+    pub async fn put_settings(
+        &self,
+        org_id: &str,
+        request: &OrganizationSettingsRequest,
+        updated_by: &str,
+    ) -> Result<OrganizationSettings, AdminError> {
+        self.get_organization(org_id).await?;
+
+        let settings = sqlx::query_as::<_, OrganizationSettings>(
+            r#"
+            INSERT INTO organization_settings (
+                org_id, retention_days, capture_policy, spans_per_day_limit,
+                bytes_per_day_limit, over_quota_action, updated_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (org_id) DO UPDATE SET
+                retention_days = EXCLUDED.retention_days,
+                capture_policy = EXCLUDED.capture_policy,
+                spans_per_day_limit = EXCLUDED.spans_per_day_limit,
+                bytes_per_day_limit = EXCLUDED.bytes_per_day_limit,
+                over_quota_action = EXCLUDED.over_quota_action,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            RETURNING org_id, retention_days, capture_policy, spans_per_day_limit,
+                      bytes_per_day_limit, over_quota_action, updated_at, updated_by
+            "#,
+        )
+        .bind(org_id)
+        .bind(request.retention_days)
+        .bind(request.capture_policy.map(|policy| policy.as_str()))
+        .bind(request.spans_per_day_limit)
+        .bind(request.bytes_per_day_limit)
+        .bind(request.over_quota_action.map(|action| action.as_str()))
+        .bind(updated_by)
+        .fetch_one(self.pool()?)
+        .await?;
+
+        info!(org_id = %org_id, updated_by = %updated_by, "Updated organization settings");
+        Ok(settings)
+    }
+}
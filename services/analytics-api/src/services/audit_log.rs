@@ -0,0 +1,114 @@
+//! # API Audit Log Writer
+//!
+//! Writes [`AuditLogEntry`] records to the `audit_log` table off the request
+//! path. Entries are queued on a bounded channel and inserted in batches by
+//! a background task; when the queue is full, entries are dropped and counted
+//! in `analytics_audit_entries_dropped_total` rather than slowing requests
+//! down.
+
+use crate::models::audit::AuditLogEntry;
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+/// Default number of entries queued before new entries are dropped
+pub const DEFAULT_QUEUE_CAPACITY: usize = 10_000;
+
+/// Maximum entries per INSERT
+const BATCH_SIZE: usize = 500;
+
+/// Queues audit log entries for the background writer
+pub struct AuditLogger {
+    sender: Option<mpsc::Sender<AuditLogEntry>>,
+}
+
+impl AuditLogger {
+    /// Start the background writer. Without a pool, entries are only logged.
+    pub fn spawn(pool: Option<PgPool>, capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::channel(capacity);
+        tokio::spawn(write_entries(pool, receiver));
+        Self {
+            sender: Some(sender),
+        }
+    }
+
+    /// Queue entries on a channel the caller reads, instead of writing them
+    pub fn from_sender(sender: mpsc::Sender<AuditLogEntry>) -> Self {
+        Self {
+            sender: Some(sender),
+        }
+    }
+
+    /// An audit logger that records nothing
+    pub fn disabled() -> Self {
+        Self { sender: None }
+    }
+
+    /// Queue an entry without waiting.
+    pub fn record(&self, entry: AuditLogEntry) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+
+        if let Err(e) = sender.try_send(entry) {
+            metrics::counter!("analytics_audit_entries_dropped_total").increment(1);
+            warn!(error = %e, "Audit log queue full, entry dropped");
+        }
+    }
+}
+
+async fn write_entries(pool: Option<PgPool>, mut receiver: mpsc::Receiver<AuditLogEntry>) {
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    while receiver.recv_many(&mut batch, BATCH_SIZE).await > 0 {
+        match &pool {
+            Some(pool) => {
+                if let Err(e) = insert_batch(pool, &batch).await {
+                    metrics::counter!("analytics_audit_write_errors_total").increment(1);
+                    error!(error = %e, entries = batch.len(), "Failed to write audit log entries");
+                }
+            }
+            None => {
+                for entry in &batch {
+                    info!(
+                        target: "audit_log",
+                        org_id = %entry.org_id,
+                        user_id = %entry.user_id,
+                        method = %entry.method,
+                        endpoint = %entry.endpoint,
+                        status = entry.status_code,
+                        rows = ?entry.row_count,
+                        "API call"
+                    );
+                }
+            }
+        }
+        batch.clear();
+    }
+}
+
+async fn insert_batch(pool: &PgPool, entries: &[AuditLogEntry]) -> Result<(), sqlx::Error> {
+    let mut query_builder = sqlx::QueryBuilder::new(
+        "INSERT INTO audit_log (ts, request_id, org_id, user_id, role, auth_method, method, \
+         endpoint, path, query_params, request_body, status_code, row_count, duration_ms) ",
+    );
+
+    query_builder.push_values(entries, |mut b, entry| {
+        b.push_bind(entry.ts)
+            .push_bind(&entry.request_id)
+            .push_bind(&entry.org_id)
+            .push_bind(&entry.user_id)
+            .push_bind(&entry.role)
+            .push_bind(&entry.auth_method)
+            .push_bind(&entry.method)
+            .push_bind(&entry.endpoint)
+            .push_bind(&entry.path)
+            .push_bind(&entry.query_params)
+            .push_bind(&entry.request_body)
+            .push_bind(entry.status_code)
+            .push_bind(entry.row_count)
+            .push_bind(entry.duration_ms);
+    });
+
+    query_builder.build().execute(pool).await?;
+    Ok(())
+}
@@ -0,0 +1,655 @@
+//! # Cold Storage Queries
+//!
+//! Traces older than the hot retention window are archived from Postgres to
+//! Parquet files in object storage. With the `datafusion` feature and
+//! `COLD_STORAGE_URL` set, this service runs cost and performance queries
+//! over those files with DataFusion, so reports keep covering archived time
+//! ranges.
+//!
+//! A requested range is split at the tier boundary (now minus the hot
+//! retention): the part before it is read from Parquet, the part after it
+//! from Postgres, and the routes merge the two results. The boundary moves
+//! with time, so a range is never read from both tiers and the merged
+//! result counts every trace once, as long as traces are deleted from
+//! Postgres only after they are archived.
+//!
+//! The Parquet files hold `llm_traces` rows, one file per archived chunk,
+//! with at least these columns: `ts` (timestamp), `org_id`, `trace_id`,
+//! `provider`, `model`, `environment`, `user_id`, `prompt_tokens`,
+//! `completion_tokens`, `total_tokens`, `prompt_cost_usd`,
+//! `completion_cost_usd`, `total_cost_usd`, `duration_ms` and `ttft_ms`.
+//! Every file under the location is read; DataFusion skips row groups
+//! outside the queried time range using the Parquet statistics.
+
+use crate::models::costs::{CostSummaryRequest, CostSummaryResponse};
+use crate::models::{AnalyticsQuery, PerformanceMetrics};
+use chrono::{DateTime, Duration, Utc};
+
+/// Days of traces kept in Postgres unless configured
+pub const DEFAULT_HOT_RETENTION_DAYS: i64 = 90;
+
+/// Errors from cold storage queries
+#[derive(Debug, thiserror::Error)]
+pub enum ColdStorageError {
+    #[error("Cold storage queries require the analytics API to be built with the `datafusion` feature")]
+    Unsupported,
+
+    #[error("Invalid cold storage location '{0}': {1}")]
+    InvalidLocation(String, String),
+
+    #[error("Cold storage query failed: {0}")]
+    Query(String),
+}
+
+/// A requested time range split between the storage tiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TierSplit {
+    /// Part of the range read from Parquet
+    pub cold: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    /// Part of the range read from Postgres
+    pub hot: Option<(DateTime<Utc>, DateTime<Utc>)>,
+}
+
+impl TierSplit {
+    /// Split `[start, end)` at `boundary`.
+    pub fn at(boundary: DateTime<Utc>, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        let cold = (start < boundary).then(|| (start, end.min(boundary)));
+        let hot = (end > boundary).then(|| (start.max(boundary), end));
+        Self { cold, hot }
+    }
+
+    /// The whole range read from Postgres
+    pub fn hot_only(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            cold: None,
+            hot: Some((start, end)),
+        }
+    }
+
+    /// Whether both tiers contribute to the range
+    pub fn spans_both(&self) -> bool {
+        self.cold.is_some() && self.hot.is_some()
+    }
+}
+
+/// Queries over traces archived to Parquet
+pub struct ColdStorageService {
+    engine: Option<engine::Engine>,
+    hot_retention: Duration,
+}
+
+impl ColdStorageService {
+    /// A service without cold storage; every range is read from Postgres.
+    pub fn disabled() -> Self {
+        Self {
+            engine: None,
+            hot_retention: Duration::days(DEFAULT_HOT_RETENTION_DAYS),
+        }
+    }
+
+    /// Query the Parquet files under `location` (a local directory,
+    /// `file://` or `s3://` URL) for traces older than `hot_retention`.
+    ///
+    /// S3 credentials and region are read from the standard `AWS_*`
+    /// environment variables.
+    pub async fn new(location: &str, hot_retention: Duration) -> Result<Self, ColdStorageError> {
+        Ok(Self {
+            engine: Some(engine::Engine::new(location).await?),
+            hot_retention,
+        })
+    }
+
+    /// Whether archived traces are queried
+    pub fn is_enabled(&self) -> bool {
+        self.engine.is_some()
+    }
+
+    /// Start of the time range still stored in Postgres
+    pub fn boundary(&self) -> DateTime<Utc> {
+        Utc::now() - self.hot_retention
+    }
+
+    /// Split `[start, end)` between the tiers. Without cold storage the
+    /// whole range is hot.
+    pub fn split(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> TierSplit {
+        if self.is_enabled() {
+            TierSplit::at(self.boundary(), start, end)
+        } else {
+            TierSplit::hot_only(start, end)
+        }
+    }
+
+    /// Cost summary of the archived traces of `org_id` in `[start, end)`
+    pub async fn cost_summary(
+        &self,
+        org_id: &str,
+        request: &CostSummaryRequest,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<CostSummaryResponse, ColdStorageError> {
+        self.engine()?.cost_summary(org_id, request, start, end).await
+    }
+
+    /// Performance metrics of the archived traces in `[start, end)`
+    pub async fn performance_metrics(
+        &self,
+        query: &AnalyticsQuery,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<PerformanceMetrics, ColdStorageError> {
+        self.engine()?.performance_metrics(query, start, end).await
+    }
+
+    fn engine(&self) -> Result<&engine::Engine, ColdStorageError> {
+        self.engine.as_ref().ok_or(ColdStorageError::Unsupported)
+    }
+}
+
+#[cfg(not(feature = "datafusion"))]
+mod engine {
+    use super::*;
+
+    /// No engine can be created without DataFusion
+    pub enum Engine {}
+
+    impl Engine {
+        pub async fn new(_location: &str) -> Result<Self, ColdStorageError> {
+            Err(ColdStorageError::Unsupported)
+        }
+
+        pub async fn cost_summary(
+            &self,
+            _org_id: &str,
+            _request: &CostSummaryRequest,
+            _start: DateTime<Utc>,
+            _end: DateTime<Utc>,
+        ) -> Result<CostSummaryResponse, ColdStorageError> {
+            match *self {}
+        }
+
+        pub async fn performance_metrics(
+            &self,
+            _query: &AnalyticsQuery,
+            _start: DateTime<Utc>,
+            _end: DateTime<Utc>,
+        ) -> Result<PerformanceMetrics, ColdStorageError> {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(feature = "datafusion")]
+mod engine {
+    use super::*;
+    use crate::models::costs::{
+        calculate_growth_rate, CostBreakdownItem, CostDataPoint, CostOverview, CostSummaryMetadata,
+        CostTrends, CurrencyConversion, ExpensiveTrace,
+    };
+    use crate::models::PerformanceDataPoint;
+    use datafusion::arrow::array::{Array, ArrayRef, AsArray};
+    use datafusion::arrow::compute::cast;
+    use datafusion::arrow::datatypes::{DataType, Float64Type, Int64Type, TimeUnit, TimestampMicrosecondType};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::prelude::{ParquetReadOptions, SessionContext};
+    use datafusion::scalar::ScalarValue;
+    use std::sync::Arc;
+
+    /// Name of the archived traces table in the DataFusion session
+    const TABLE: &str = "llm_traces";
+
+    /// Origin of trend buckets; TimescaleDB's `time_bucket` origin, so
+    /// weekly points from both tiers fall on the same dates
+    const BUCKET_ORIGIN: &str = "2000-01-03T00:00:00Z";
+
+    /// Output tokens per second while generating, as in the Postgres queries
+    const OUTPUT_TOKENS_PER_SECOND_SQL: &str = "CASE WHEN completion_tokens > 0 \
+        AND duration_ms > COALESCE(ttft_ms, 0) \
+        THEN CAST(completion_tokens AS DOUBLE) * 1000.0 / (duration_ms - COALESCE(ttft_ms, 0)) END";
+
+    /// DataFusion session with the archive registered as `llm_traces`
+    pub struct Engine {
+        ctx: SessionContext,
+    }
+
+    impl Engine {
+        pub async fn new(location: &str) -> Result<Self, ColdStorageError> {
+            let invalid = |e: String| ColdStorageError::InvalidLocation(location.to_string(), e);
+            let ctx = SessionContext::new();
+
+            let mut table_path = location.to_string();
+            if !table_path.ends_with('/') {
+                table_path.push('/');
+            }
+            if let Some(bucket) = location.strip_prefix("s3://").and_then(|rest| rest.split('/').next()) {
+                let store = object_store::aws::AmazonS3Builder::from_env()
+                    .with_bucket_name(bucket)
+                    .build()
+                    .map_err(|e| invalid(e.to_string()))?;
+                let url = url::Url::parse(&format!("s3://{}", bucket)).map_err(|e| invalid(e.to_string()))?;
+                ctx.register_object_store(&url, Arc::new(store));
+            }
+
+            ctx.register_parquet(TABLE, &table_path, ParquetReadOptions::default())
+                .await
+                .map_err(|e| invalid(e.to_string()))?;
+
+            Ok(Self { ctx })
+        }
+
+        pub async fn cost_summary(
+            &self,
+            org_id: &str,
+            request: &CostSummaryRequest,
+            start: DateTime<Utc>,
+            end: DateTime<Utc>,
+        ) -> Result<CostSummaryResponse, ColdStorageError> {
+            let mut filters = Filters::new(start, end);
+            filters.eq("org_id", Some(org_id));
+            filters.eq("provider", request.provider.as_deref());
+            filters.eq("model", request.model.as_deref());
+            filters.eq("environment", request.environment.as_deref());
+            filters.eq("user_id", request.user_id.as_deref());
+
+            let overview = self
+                .query(
+                    &format!(
+                        "SELECT SUM(total_cost_usd), SUM(prompt_cost_usd), SUM(completion_cost_usd), \
+                         COUNT(*), SUM(total_tokens) FROM {} WHERE {}",
+                        TABLE, filters.clause
+                    ),
+                    &filters,
+                )
+                .await?;
+            let total_cost = f64s(&overview, 0)?.first().copied().flatten().unwrap_or(0.0);
+            let total_requests = i64s(&overview, 3)?.first().copied().flatten().unwrap_or(0);
+            let total_tokens = i64s(&overview, 4)?.first().copied().flatten().unwrap_or(0);
+
+            let overview = CostOverview {
+                total_cost,
+                prompt_cost: f64s(&overview, 1)?.first().copied().flatten().unwrap_or(0.0),
+                completion_cost: f64s(&overview, 2)?.first().copied().flatten().unwrap_or(0.0),
+                total_requests,
+                total_tokens,
+                avg_cost_per_request: if total_requests > 0 {
+                    total_cost / total_requests as f64
+                } else {
+                    0.0
+                },
+                avg_cost_per_1k_tokens: if total_tokens > 0 {
+                    (total_cost / total_tokens as f64) * 1000.0
+                } else {
+                    0.0
+                },
+                day_over_day_change: None,
+                week_over_week_change: None,
+            };
+
+            let trends = if request.include_trends {
+                let daily = self.cost_trend(&filters, "1 day").await?;
+                let weekly = self.cost_trend(&filters, "7 days").await?;
+                Some(CostTrends {
+                    growth_rate_daily: calculate_growth_rate(&daily),
+                    growth_rate_weekly: calculate_growth_rate(&weekly),
+                    daily,
+                    weekly,
+                })
+            } else {
+                None
+            };
+
+            let top_traces = if request.include_top_traces {
+                Some(self.top_traces(&filters, request.top_limit).await?)
+            } else {
+                None
+            };
+
+            Ok(CostSummaryResponse {
+                metadata: CostSummaryMetadata {
+                    start_time: start,
+                    end_time: end,
+                    period_days: (end - start).num_days(),
+                    generated_at: Utc::now(),
+                    currency: CurrencyConversion::usd(total_cost),
+                },
+                overview,
+                by_provider: self.cost_breakdown(&filters, "provider").await?,
+                by_model: self.cost_breakdown(&filters, "model").await?,
+                by_environment: self.cost_breakdown(&filters, "environment").await?,
+                trends,
+                top_traces,
+                federation: None,
+            })
+        }
+
+        pub async fn performance_metrics(
+            &self,
+            query: &AnalyticsQuery,
+            start: DateTime<Utc>,
+            end: DateTime<Utc>,
+        ) -> Result<PerformanceMetrics, ColdStorageError> {
+            let mut filters = Filters::new(start, end);
+            filters.eq("provider", query.provider.as_deref());
+            filters.eq("model", query.model.as_deref());
+            filters.eq("environment", query.environment.as_deref());
+
+            let stats = self
+                .query(
+                    &format!(
+                        "SELECT COUNT(*), AVG(duration_ms), MIN(duration_ms), MAX(duration_ms), \
+                         approx_percentile_cont(duration_ms, 0.50), \
+                         approx_percentile_cont(duration_ms, 0.95), \
+                         approx_percentile_cont(duration_ms, 0.99), \
+                         SUM(total_tokens), AVG(ttft_ms), \
+                         approx_percentile_cont(ttft_ms, 0.50), \
+                         approx_percentile_cont(ttft_ms, 0.95), \
+                         approx_percentile_cont(ttft_ms, 0.99), \
+                         AVG({}) FROM {} WHERE {}",
+                        OUTPUT_TOKENS_PER_SECOND_SQL, TABLE, filters.clause
+                    ),
+                    &filters,
+                )
+                .await?;
+            let first_f64 = |column| -> Result<Option<f64>, ColdStorageError> {
+                Ok(f64s(&stats, column)?.first().copied().flatten())
+            };
+            let first_i64 = |column| -> Result<Option<i64>, ColdStorageError> {
+                Ok(i64s(&stats, column)?.first().copied().flatten())
+            };
+
+            let request_count = first_i64(0)?.unwrap_or(0);
+            let total_tokens = first_i64(7)?.unwrap_or(0);
+            let duration_seconds = (end - start).num_seconds() as f64;
+            let per_second = |value: f64| {
+                if duration_seconds > 0.0 {
+                    value / duration_seconds
+                } else {
+                    0.0
+                }
+            };
+
+            let interval = match query.granularity.as_str() {
+                "1min" => "1 minute",
+                "1day" => "1 day",
+                _ => "1 hour",
+            };
+            let series = self
+                .query(
+                    &format!(
+                        "SELECT date_bin(INTERVAL '{}', ts, TIMESTAMP '{}') AS bucket, \
+                         AVG(duration_ms), MIN(duration_ms), MAX(duration_ms), COUNT(*), \
+                         COALESCE(SUM(total_tokens), 0) \
+                         FROM {} WHERE {} GROUP BY bucket ORDER BY bucket",
+                        interval, BUCKET_ORIGIN, TABLE, filters.clause
+                    ),
+                    &filters,
+                )
+                .await?;
+            let time_series = times(&series, 0)?
+                .into_iter()
+                .zip(f64s(&series, 1)?)
+                .zip(i64s(&series, 2)?.into_iter().zip(i64s(&series, 3)?))
+                .zip(i64s(&series, 4)?.into_iter().zip(i64s(&series, 5)?))
+                .filter_map(|(((timestamp, avg), (min, max)), (requests, tokens))| {
+                    Some(PerformanceDataPoint {
+                        timestamp: timestamp?,
+                        avg_latency_ms: avg.unwrap_or(0.0),
+                        min_latency_ms: min.unwrap_or(0) as i32,
+                        max_latency_ms: max.unwrap_or(0) as i32,
+                        request_count: requests.unwrap_or(0),
+                        total_tokens: tokens.unwrap_or(0),
+                    })
+                })
+                .collect();
+
+            Ok(PerformanceMetrics {
+                request_count,
+                avg_latency_ms: first_f64(1)?.unwrap_or(0.0),
+                min_latency_ms: first_i64(2)?.unwrap_or(0) as i32,
+                max_latency_ms: first_i64(3)?.unwrap_or(0) as i32,
+                p50_latency_ms: first_f64(4)?,
+                p95_latency_ms: first_f64(5)?,
+                p99_latency_ms: first_f64(6)?,
+                throughput_rps: per_second(request_count as f64),
+                total_tokens,
+                tokens_per_second: per_second(total_tokens as f64),
+                avg_ttft_ms: first_f64(8)?,
+                p50_ttft_ms: first_f64(9)?,
+                p95_ttft_ms: first_f64(10)?,
+                p99_ttft_ms: first_f64(11)?,
+                avg_output_tokens_per_second: first_f64(12)?,
+                time_series,
+            })
+        }
+
+        async fn cost_breakdown(
+            &self,
+            filters: &Filters,
+            dimension: &str,
+        ) -> Result<Vec<CostBreakdownItem>, ColdStorageError> {
+            let batches = self
+                .query(
+                    &format!(
+                        "SELECT COALESCE({dim}, 'unknown') AS name, SUM(total_cost_usd) AS cost, COUNT(*) \
+                         FROM {} WHERE {} GROUP BY COALESCE({dim}, 'unknown') ORDER BY cost DESC LIMIT 50",
+                        TABLE,
+                        filters.clause,
+                        dim = dimension
+                    ),
+                    filters,
+                )
+                .await?;
+
+            let rows: Vec<(String, f64, i64)> = strings(&batches, 0)?
+                .into_iter()
+                .zip(f64s(&batches, 1)?)
+                .zip(i64s(&batches, 2)?)
+                .map(|((name, cost), requests)| (name.unwrap_or_default(), cost.unwrap_or(0.0), requests.unwrap_or(0)))
+                .collect();
+            let total_cost: f64 = rows.iter().map(|(_, cost, _)| cost).sum();
+
+            Ok(rows
+                .into_iter()
+                .map(|(name, cost, requests)| CostBreakdownItem {
+                    name,
+                    cost,
+                    requests,
+                    percentage: if total_cost > 0.0 { (cost / total_cost) * 100.0 } else { 0.0 },
+                    avg_cost_per_request: if requests > 0 { cost / requests as f64 } else { 0.0 },
+                })
+                .collect())
+        }
+
+        async fn cost_trend(&self, filters: &Filters, interval: &str) -> Result<Vec<CostDataPoint>, ColdStorageError> {
+            let batches = self
+                .query(
+                    &format!(
+                        "SELECT date_bin(INTERVAL '{}', ts, TIMESTAMP '{}') AS date, \
+                         SUM(total_cost_usd), COUNT(*) \
+                         FROM {} WHERE {} GROUP BY date ORDER BY date",
+                        interval, BUCKET_ORIGIN, TABLE, filters.clause
+                    ),
+                    filters,
+                )
+                .await?;
+
+            Ok(times(&batches, 0)?
+                .into_iter()
+                .zip(f64s(&batches, 1)?)
+                .zip(i64s(&batches, 2)?)
+                .filter_map(|((date, cost), requests)| {
+                    Some(CostDataPoint {
+                        date: date?,
+                        cost: cost.unwrap_or(0.0),
+                        requests: requests.unwrap_or(0),
+                    })
+                })
+                .collect())
+        }
+
+        async fn top_traces(&self, filters: &Filters, limit: i32) -> Result<Vec<ExpensiveTrace>, ColdStorageError> {
+            let batches = self
+                .query(
+                    &format!(
+                        "SELECT trace_id, ts, provider, model, total_cost_usd, total_tokens, duration_ms, user_id \
+                         FROM {} WHERE {} ORDER BY total_cost_usd DESC NULLS LAST LIMIT {}",
+                        TABLE,
+                        filters.clause,
+                        limit.max(0)
+                    ),
+                    filters,
+                )
+                .await?;
+
+            let trace_ids = strings(&batches, 0)?;
+            let timestamps = times(&batches, 1)?;
+            let providers = strings(&batches, 2)?;
+            let models = strings(&batches, 3)?;
+            let costs = f64s(&batches, 4)?;
+            let tokens = i64s(&batches, 5)?;
+            let durations = i64s(&batches, 6)?;
+            let user_ids = strings(&batches, 7)?;
+
+            Ok((0..trace_ids.len())
+                .filter_map(|i| {
+                    Some(ExpensiveTrace {
+                        trace_id: trace_ids[i].clone()?,
+                        timestamp: timestamps[i]?,
+                        provider: providers[i].clone().unwrap_or_default(),
+                        model: models[i].clone().unwrap_or_default(),
+                        cost: costs[i].unwrap_or(0.0),
+                        tokens: tokens[i].unwrap_or(0),
+                        duration_ms: durations[i].unwrap_or(0) as i32,
+                        user_id: user_ids[i].clone(),
+                    })
+                })
+                .collect())
+        }
+
+        /// Run `sql` with the filter values bound to its placeholders.
+        async fn query(&self, sql: &str, filters: &Filters) -> Result<Vec<RecordBatch>, ColdStorageError> {
+            let query_error = |e: datafusion::error::DataFusionError| ColdStorageError::Query(e.to_string());
+            self.ctx
+                .sql(sql)
+                .await
+                .map_err(query_error)?
+                .with_param_values(filters.params.clone())
+                .map_err(query_error)?
+                .collect()
+                .await
+                .map_err(query_error)
+        }
+    }
+
+    /// WHERE clause over the archive with its values as `$n` placeholders
+    struct Filters {
+        clause: String,
+        params: Vec<ScalarValue>,
+    }
+
+    impl Filters {
+        /// Rows with `ts` in `[start, end)`
+        fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+            let timestamp = |t: DateTime<Utc>| ScalarValue::TimestampMicrosecond(Some(t.timestamp_micros()), Some("+00:00".into()));
+            Self {
+                clause: "ts >= $1 AND ts < $2".to_string(),
+                params: vec![timestamp(start), timestamp(end)],
+            }
+        }
+
+        /// Rows whose `column` equals `value`, if given
+        fn eq(&mut self, column: &str, value: Option<&str>) {
+            if let Some(value) = value {
+                self.params.push(ScalarValue::Utf8(Some(value.to_string())));
+                self.clause.push_str(&format!(" AND {} = ${}", column, self.params.len()));
+            }
+        }
+    }
+
+    /// Values of one column across batches, cast to `data_type`
+    fn column(batches: &[RecordBatch], index: usize, data_type: &DataType) -> Result<Vec<ArrayRef>, ColdStorageError> {
+        batches
+            .iter()
+            .map(|batch| cast(batch.column(index), data_type).map_err(|e| ColdStorageError::Query(e.to_string())))
+            .collect()
+    }
+
+    fn f64s(batches: &[RecordBatch], index: usize) -> Result<Vec<Option<f64>>, ColdStorageError> {
+        Ok(column(batches, index, &DataType::Float64)?
+            .iter()
+            .flat_map(|array| array.as_primitive::<Float64Type>().iter().collect::<Vec<_>>())
+            .collect())
+    }
+
+    fn i64s(batches: &[RecordBatch], index: usize) -> Result<Vec<Option<i64>>, ColdStorageError> {
+        Ok(column(batches, index, &DataType::Int64)?
+            .iter()
+            .flat_map(|array| array.as_primitive::<Int64Type>().iter().collect::<Vec<_>>())
+            .collect())
+    }
+
+    fn strings(batches: &[RecordBatch], index: usize) -> Result<Vec<Option<String>>, ColdStorageError> {
+        Ok(column(batches, index, &DataType::Utf8)?
+            .iter()
+            .flat_map(|array| {
+                array
+                    .as_string::<i32>()
+                    .iter()
+                    .map(|value| value.map(str::to_string))
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+
+    fn times(batches: &[RecordBatch], index: usize) -> Result<Vec<Option<DateTime<Utc>>>, ColdStorageError> {
+        let data_type = DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into()));
+        Ok(column(batches, index, &data_type)?
+            .iter()
+            .flat_map(|array| {
+                let array = array.as_primitive::<TimestampMicrosecondType>();
+                (0..array.len())
+                    .map(|i| array.is_valid(i).then(|| DateTime::from_timestamp_micros(array.value(i))).flatten())
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn day(d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 10, d, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_tier_split() {
+        let boundary = day(10);
+
+        // Range across the boundary
+        let split = TierSplit::at(boundary, day(1), day(20));
+        assert_eq!(split.cold, Some((day(1), day(10))));
+        assert_eq!(split.hot, Some((day(10), day(20))));
+        assert!(split.spans_both());
+
+        // Entirely archived
+        let split = TierSplit::at(boundary, day(1), day(5));
+        assert_eq!(split.cold, Some((day(1), day(5))));
+        assert_eq!(split.hot, None);
+
+        // Entirely hot, including a range starting at the boundary
+        let split = TierSplit::at(boundary, day(10), day(20));
+        assert_eq!(split.cold, None);
+        assert_eq!(split.hot, Some((day(10), day(20))));
+    }
+
+    #[test]
+    fn test_disabled_service_reads_postgres_only() {
+        let service = ColdStorageService::disabled();
+        assert!(!service.is_enabled());
+
+        let split = service.split(day(1), day(20));
+        assert_eq!(split, TierSplit::hot_only(day(1), day(20)));
+    }
+}
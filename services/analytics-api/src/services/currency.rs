@@ -0,0 +1,380 @@
+//! # Currency Conversion Service
+//!
+//! All costs are stored in USD. This service converts reported amounts into a
+//! display currency using exchange rates from a pluggable source:
+//! - `StaticRateSource` - fixed rates from configuration (`FX_RATES`)
+//! - `RemoteRateSource` - rates fetched from an HTTP endpoint and refreshed
+//!   periodically
+//!
+//! Every conversion returns an `FxQuote` carrying the rate, its source and the
+//! time it was fetched, so responses can record exactly how a USD amount was
+//! converted.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Currency all costs are stored in.
+pub const BASE_CURRENCY: &str = "USD";
+
+/// Currency conversion errors
+#[derive(Debug, thiserror::Error)]
+pub enum CurrencyError {
+    #[error("Invalid currency code '{0}': expected a 3-letter ISO 4217 code")]
+    InvalidCode(String),
+
+    #[error("No exchange rate available for {0}")]
+    UnsupportedCurrency(String),
+
+    #[error("Invalid exchange rate configuration: {0}")]
+    InvalidConfig(String),
+
+    #[error("Failed to fetch exchange rates: {0}")]
+    Fetch(String),
+}
+
+/// A set of USD exchange rates.
+#[derive(Debug, Clone)]
+pub struct FxRates {
+    /// Units of each currency per 1 USD, keyed by uppercase ISO 4217 code
+    pub rates: HashMap<String, f64>,
+
+    /// When the rates were obtained
+    pub as_of: DateTime<Utc>,
+}
+
+/// Exchange rate used for a conversion.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FxQuote {
+    /// Target currency code
+    pub currency: String,
+
+    /// Units of the target currency per 1 USD
+    pub rate: f64,
+
+    /// Name of the rate source
+    pub source: String,
+
+    /// When the rate was obtained
+    pub as_of: DateTime<Utc>,
+}
+
+impl FxQuote {
+    /// Convert a USD amount into the quoted currency.
+    pub fn convert(&self, amount_usd: f64) -> f64 {
+        amount_usd * self.rate
+    }
+
+    /// Whether the quote is the identity USD conversion.
+    pub fn is_identity(&self) -> bool {
+        self.currency == BASE_CURRENCY
+    }
+}
+
+/// Source of USD exchange rates.
+#[async_trait]
+pub trait FxRateSource: Send + Sync {
+    /// Fetch the current rates.
+    async fn fetch_rates(&self) -> Result<FxRates, CurrencyError>;
+
+    /// Source name recorded on each quote.
+    fn name(&self) -> &str;
+}
+
+/// Fixed exchange rates from configuration.
+#[derive(Debug, Clone)]
+pub struct StaticRateSource {
+    rates: HashMap<String, f64>,
+}
+
+impl StaticRateSource {
+    /// Create a static source from a rate map.
+    pub fn new(rates: HashMap<String, f64>) -> Self {
+        Self { rates }
+    }
+
+    /// Parse rates from a `CODE=rate` list, e.g. `EUR=0.92,GBP=0.79`.
+    pub fn parse(config: &str) -> Result<Self, CurrencyError> {
+        let mut rates = HashMap::new();
+
+        for entry in config.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (code, rate) = entry
+                .split_once('=')
+                .ok_or_else(|| CurrencyError::InvalidConfig(format!("expected CODE=rate, got '{}'", entry)))?;
+
+            let code = normalize_currency_code(code)?;
+            let rate: f64 = rate
+                .trim()
+                .parse()
+                .map_err(|_| CurrencyError::InvalidConfig(format!("invalid rate for {}: '{}'", code, rate.trim())))?;
+
+            if !rate.is_finite() || rate <= 0.0 {
+                return Err(CurrencyError::InvalidConfig(format!(
+                    "rate for {} must be positive",
+                    code
+                )));
+            }
+
+            rates.insert(code, rate);
+        }
+
+        Ok(Self::new(rates))
+    }
+}
+
+#[async_trait]
+impl FxRateSource for StaticRateSource {
+    async fn fetch_rates(&self) -> Result<FxRates, CurrencyError> {
+        Ok(FxRates {
+            rates: self.rates.clone(),
+            as_of: Utc::now(),
+        })
+    }
+
+    fn name(&self) -> &str {
+        "static"
+    }
+}
+
+/// Exchange rates fetched from an HTTP endpoint.
+///
+/// The endpoint must return USD-based rates as JSON with a `rates` object,
+/// e.g. `{"base": "USD", "rates": {"EUR": 0.92, "GBP": 0.79}}`.
+#[derive(Debug, Clone)]
+pub struct RemoteRateSource {
+    client: reqwest::Client,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemoteRatesBody {
+    #[serde(default)]
+    base: Option<String>,
+    rates: HashMap<String, f64>,
+}
+
+impl RemoteRateSource {
+    /// Create a remote source for the given URL.
+    pub fn new(url: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            url: url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl FxRateSource for RemoteRateSource {
+    async fn fetch_rates(&self) -> Result<FxRates, CurrencyError> {
+        let body: RemoteRatesBody = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| CurrencyError::Fetch(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| CurrencyError::Fetch(e.to_string()))?;
+
+        if let Some(base) = body.base.as_deref() {
+            if !base.eq_ignore_ascii_case(BASE_CURRENCY) {
+                return Err(CurrencyError::Fetch(format!(
+                    "expected {} base rates, got {}",
+                    BASE_CURRENCY, base
+                )));
+            }
+        }
+
+        let rates = body
+            .rates
+            .into_iter()
+            .filter(|(_, rate)| rate.is_finite() && *rate > 0.0)
+            .map(|(code, rate)| (code.to_ascii_uppercase(), rate))
+            .collect();
+
+        Ok(FxRates {
+            rates,
+            as_of: Utc::now(),
+        })
+    }
+
+    fn name(&self) -> &str {
+        "remote"
+    }
+}
+
+/// Currency conversion service with cached rates.
+pub struct CurrencyService {
+    display_currency: String,
+    source: Arc<dyn FxRateSource>,
+    rates: RwLock<FxRates>,
+}
+
+impl CurrencyService {
+    /// Create a service and load the initial rates from the source.
+    pub async fn new(
+        display_currency: &str,
+        source: Arc<dyn FxRateSource>,
+    ) -> Result<Self, CurrencyError> {
+        let display_currency = normalize_currency_code(display_currency)?;
+        let rates = source.fetch_rates().await?;
+
+        let service = Self {
+            display_currency,
+            source,
+            rates: RwLock::new(rates),
+        };
+
+        // Fail fast on a display currency that can never be converted
+        service.quote(&service.display_currency)?;
+
+        Ok(service)
+    }
+
+    /// A USD-only service that performs no conversion.
+    pub fn usd_only() -> Self {
+        Self {
+            display_currency: BASE_CURRENCY.to_string(),
+            source: Arc::new(StaticRateSource::new(HashMap::new())),
+            rates: RwLock::new(FxRates {
+                rates: HashMap::new(),
+                as_of: Utc::now(),
+            }),
+        }
+    }
+
+    /// Default currency used when a request does not specify one.
+    pub fn display_currency(&self) -> &str {
+        &self.display_currency
+    }
+
+    /// Reload rates from the source, keeping the previous rates on failure.
+    pub async fn refresh(&self) -> Result<(), CurrencyError> {
+        let rates = self.source.fetch_rates().await?;
+        let count = rates.rates.len();
+
+        if let Ok(mut current) = self.rates.write() {
+            *current = rates;
+        }
+
+        info!(source = self.source.name(), currencies = count, "Exchange rates refreshed");
+        Ok(())
+    }
+
+    /// Refresh rates in the background at a fixed interval.
+    pub fn spawn_refresh(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately and rates were loaded in new()
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.refresh().await {
+                    warn!(error = %e, "Exchange rate refresh failed, keeping previous rates");
+                }
+            }
+        })
+    }
+
+    /// Quote for the requested currency, or the display currency if none.
+    pub fn resolve(&self, requested: Option<&str>) -> Result<FxQuote, CurrencyError> {
+        match requested {
+            Some(code) => self.quote(&normalize_currency_code(code)?),
+            None => self.quote(&self.display_currency),
+        }
+    }
+
+    /// Quote for a normalized currency code.
+    fn quote(&self, currency: &str) -> Result<FxQuote, CurrencyError> {
+        let rates = self.rates.read().map_err(|_| {
+            error!("Exchange rate lock poisoned");
+            CurrencyError::UnsupportedCurrency(currency.to_string())
+        })?;
+
+        let rate = if currency == BASE_CURRENCY {
+            1.0
+        } else {
+            *rates
+                .rates
+                .get(currency)
+                .ok_or_else(|| CurrencyError::UnsupportedCurrency(currency.to_string()))?
+        };
+
+        Ok(FxQuote {
+            currency: currency.to_string(),
+            rate,
+            source: self.source.name().to_string(),
+            as_of: rates.as_of,
+        })
+    }
+}
+
+/// Validate and uppercase an ISO 4217 currency code.
+pub fn normalize_currency_code(code: &str) -> Result<String, CurrencyError> {
+    let code = code.trim();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(CurrencyError::InvalidCode(code.to_string()));
+    }
+    Ok(code.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_static_rates() {
+        let source = StaticRateSource::parse("eur=0.92, GBP=0.79,").unwrap();
+        assert_eq!(source.rates.get("EUR"), Some(&0.92));
+        assert_eq!(source.rates.get("GBP"), Some(&0.79));
+
+        assert!(StaticRateSource::parse("EUR").is_err());
+        assert!(StaticRateSource::parse("EUR=abc").is_err());
+        assert!(StaticRateSource::parse("EUR=-1").is_err());
+        assert!(StaticRateSource::parse("EURO=0.9").is_err());
+    }
+
+    #[test]
+    fn test_normalize_currency_code() {
+        assert_eq!(normalize_currency_code(" eur ").unwrap(), "EUR");
+        assert!(normalize_currency_code("E1R").is_err());
+        assert!(normalize_currency_code("").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_quotes() {
+        let source = StaticRateSource::parse("EUR=0.5").unwrap();
+        let service = CurrencyService::new("usd", Arc::new(source)).await.unwrap();
+
+        let default = service.resolve(None).unwrap();
+        assert!(default.is_identity());
+        assert_eq!(default.convert(10.0), 10.0);
+
+        let eur = service.resolve(Some("eur")).unwrap();
+        assert_eq!(eur.currency, "EUR");
+        assert_eq!(eur.source, "static");
+        assert_eq!(eur.convert(10.0), 5.0);
+
+        assert!(matches!(
+            service.resolve(Some("JPY")),
+            Err(CurrencyError::UnsupportedCurrency(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_display_currency_rejected() {
+        let source = StaticRateSource::parse("EUR=0.92").unwrap();
+        assert!(CurrencyService::new("GBP", Arc::new(source)).await.is_err());
+    }
+}
@@ -0,0 +1,506 @@
+//! # Field-Level Data Access
+//!
+//! Query-time masking of sensitive trace fields, and the audit log of who
+//! received them unmasked.
+//!
+//! Prompt and response text (`input_text`, `output_text`) require the
+//! `read:trace_content` permission; user identifiers (`user_id`,
+//! `session_id`) require `read:user_identifiers`. Callers without the
+//! permission get [`MASKED_VALUE`] in place of the field and of the span and
+//! span event attributes carrying the same data (`gen_ai.prompt`,
+//! `user.id`, ...).
+//! Which fields are subject to masking is configured with `MASKED_FIELDS`.
+//!
+//! Every response that returns at least one masked-by-policy field unmasked
+//! is recorded in the `data_access_audit_log` table before it is sent.
+
+use crate::middleware::AuthContext;
+use crate::models::audit::DataAccessAuditEntry;
+use crate::models::traces::Trace;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::collections::BTreeSet;
+use tracing::info;
+
+/// Replacement for masked values
+pub const MASKED_VALUE: &str = "[REDACTED]";
+
+/// Permission to read prompt and response text
+pub const CONTENT_PERMISSION: &str = "read:trace_content";
+
+/// Permission to read user and session identifiers
+pub const IDENTIFIERS_PERMISSION: &str = "read:user_identifiers";
+
+/// Maximum trace IDs recorded per audit entry
+const MAX_AUDITED_TRACE_IDS: usize = 1000;
+
+/// A trace field subject to masking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitiveField {
+    InputText,
+    OutputText,
+    UserId,
+    SessionId,
+}
+
+impl SensitiveField {
+    pub const ALL: [SensitiveField; 4] = [
+        SensitiveField::InputText,
+        SensitiveField::OutputText,
+        SensitiveField::UserId,
+        SensitiveField::SessionId,
+    ];
+
+    /// Column name in `llm_traces`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SensitiveField::InputText => "input_text",
+            SensitiveField::OutputText => "output_text",
+            SensitiveField::UserId => "user_id",
+            SensitiveField::SessionId => "session_id",
+        }
+    }
+
+    /// Parse a column name
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.as_str() == name)
+    }
+
+    /// Parse a comma-separated list of column names (`MASKED_FIELDS`)
+    pub fn parse_list(list: &str) -> Result<Vec<Self>, String> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| Self::parse(name).ok_or_else(|| format!("Unknown masked field: {}", name)))
+            .collect()
+    }
+
+    /// Permission required to read the field unmasked
+    pub fn permission(&self) -> &'static str {
+        match self {
+            SensitiveField::InputText | SensitiveField::OutputText => CONTENT_PERMISSION,
+            SensitiveField::UserId | SensitiveField::SessionId => IDENTIFIERS_PERMISSION,
+        }
+    }
+
+    /// Span attributes carrying the same data
+    pub fn attribute_keys(&self) -> &'static [&'static str] {
+        match self {
+            SensitiveField::InputText => &["gen_ai.prompt"],
+            SensitiveField::OutputText => &["gen_ai.completion"],
+            SensitiveField::UserId => &["user.id", "enduser.id"],
+            SensitiveField::SessionId => &["session.id"],
+        }
+    }
+
+    /// Sensitive field a search filter field refers to (a column name or
+    /// `attributes.<key>`)
+    pub fn for_filter_field(field: &str) -> Option<Self> {
+        match field.strip_prefix("attributes.") {
+            Some(key) => Self::ALL
+                .into_iter()
+                .find(|f| f.attribute_keys().contains(&key)),
+            None => Self::parse(field),
+        }
+    }
+
+    fn get(self, trace: &Trace) -> Option<&String> {
+        match self {
+            SensitiveField::InputText => trace.input_text.as_ref(),
+            SensitiveField::OutputText => trace.output_text.as_ref(),
+            SensitiveField::UserId => trace.user_id.as_ref(),
+            SensitiveField::SessionId => trace.session_id.as_ref(),
+        }
+    }
+
+    fn get_mut(self, trace: &mut Trace) -> &mut Option<String> {
+        match self {
+            SensitiveField::InputText => &mut trace.input_text,
+            SensitiveField::OutputText => &mut trace.output_text,
+            SensitiveField::UserId => &mut trace.user_id,
+            SensitiveField::SessionId => &mut trace.session_id,
+        }
+    }
+
+    /// Whether the trace carries a value for the field
+    fn is_present(self, trace: &Trace) -> bool {
+        self.get(trace).is_some()
+            || trace
+                .attributes
+                .as_ref()
+                .is_some_and(|attributes| self.in_attributes(attributes))
+    }
+
+    /// Whether the attributes carry a value for the field
+    fn in_attributes(self, attributes: &Value) -> bool {
+        match attributes {
+            Value::Object(attributes) => self
+                .attribute_keys()
+                .iter()
+                .any(|key| attributes.contains_key(*key)),
+            _ => false,
+        }
+    }
+
+    fn mask(self, trace: &mut Trace) {
+        if let Some(value) = self.get_mut(trace) {
+            *value = MASKED_VALUE.to_string();
+        }
+        if let Some(attributes) = &mut trace.attributes {
+            self.mask_attributes(attributes);
+        }
+    }
+
+    fn mask_attributes(self, attributes: &mut Value) {
+        if let Value::Object(attributes) = attributes {
+            for key in self.attribute_keys() {
+                if let Some(value) = attributes.get_mut(*key) {
+                    *value = Value::from(MASKED_VALUE);
+                }
+            }
+        }
+    }
+}
+
+/// Fields masked and unmasked for one caller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldMask {
+    pub masked: Vec<SensitiveField>,
+    pub unmasked: Vec<SensitiveField>,
+}
+
+impl FieldMask {
+    /// Whether the field is masked for this caller
+    pub fn masks(&self, field: SensitiveField) -> bool {
+        self.masked.contains(&field)
+    }
+
+    /// Mask the traces in place, returning the policy fields that are
+    /// returned unmasked with a value.
+    pub fn apply(&self, traces: &mut [Trace]) -> Vec<SensitiveField> {
+        let mut exposed = BTreeSet::new();
+        for trace in traces.iter_mut() {
+            for field in &self.masked {
+                field.mask(trace);
+            }
+            for field in &self.unmasked {
+                if field.is_present(trace) {
+                    exposed.insert(*field);
+                }
+            }
+        }
+        exposed.into_iter().collect()
+    }
+
+    /// Mask attribute maps other than trace attributes, e.g. those of span
+    /// events, in place, returning the policy fields that are returned
+    /// unmasked with a value.
+    pub fn apply_attributes<'a>(
+        &self,
+        attributes: impl IntoIterator<Item = &'a mut Value>,
+    ) -> Vec<SensitiveField> {
+        let mut exposed = BTreeSet::new();
+        for attributes in attributes {
+            for field in &self.masked {
+                field.mask_attributes(attributes);
+            }
+            for field in &self.unmasked {
+                if field.in_attributes(attributes) {
+                    exposed.insert(*field);
+                }
+            }
+        }
+        exposed.into_iter().collect()
+    }
+}
+
+/// Masking policy and audit log writer
+pub struct DataAccessPolicy {
+    /// Fields subject to masking
+    fields: Vec<SensitiveField>,
+    /// Pool for writing the audit log (None logs entries instead)
+    pool: Option<PgPool>,
+}
+
+impl DataAccessPolicy {
+    pub fn new(fields: Vec<SensitiveField>, pool: Option<PgPool>) -> Self {
+        Self { fields, pool }
+    }
+
+    /// A policy that masks nothing and audits nothing
+    pub fn unrestricted() -> Self {
+        Self::new(Vec::new(), None)
+    }
+
+    /// Fields masked for the caller
+    pub fn mask_for(&self, auth: &AuthContext) -> FieldMask {
+        let (unmasked, masked) = self
+            .fields
+            .iter()
+            .copied()
+            .partition(|field| auth.has_permission(field.permission()));
+        FieldMask { masked, unmasked }
+    }
+
+    /// Reject search filters on fields masked for the caller, so masked
+    /// values can't be recovered by searching for them.
+    pub fn check_filter_fields<'a>(
+        &self,
+        auth: &AuthContext,
+        fields: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(), String> {
+        let mask = self.mask_for(auth);
+        for field in fields {
+            if let Some(sensitive) = SensitiveField::for_filter_field(field) {
+                if mask.masks(sensitive) {
+                    return Err(format!(
+                        "Filtering on {} requires the {} permission",
+                        sensitive.as_str(),
+                        sensitive.permission()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Mask the traces for the caller and audit any unmasked access.
+    pub async fn apply(
+        &self,
+        auth: &AuthContext,
+        endpoint: &str,
+        traces: &mut [Trace],
+    ) -> Result<(), sqlx::Error> {
+        let exposed = self.mask_for(auth).apply(traces);
+        if exposed.is_empty() {
+            return Ok(());
+        }
+
+        let mut trace_ids: Vec<String> = traces.iter().map(|t| t.trace_id.clone()).collect();
+        trace_ids.sort();
+        trace_ids.dedup();
+        trace_ids.truncate(MAX_AUDITED_TRACE_IDS);
+
+        self.record_exposure(auth, endpoint, &exposed, trace_ids)
+            .await
+    }
+
+    /// Mask the attributes of one trace's span events for the caller and
+    /// audit any unmasked access.
+    pub async fn apply_to_event_attributes<'a>(
+        &self,
+        auth: &AuthContext,
+        endpoint: &str,
+        trace_id: &str,
+        attributes: impl IntoIterator<Item = &'a mut Value>,
+    ) -> Result<(), sqlx::Error> {
+        let exposed = self.mask_for(auth).apply_attributes(attributes);
+        if exposed.is_empty() {
+            return Ok(());
+        }
+
+        self.record_exposure(auth, endpoint, &exposed, vec![trace_id.to_string()])
+            .await
+    }
+
+    async fn record_exposure(
+        &self,
+        auth: &AuthContext,
+        endpoint: &str,
+        exposed: &[SensitiveField],
+        trace_ids: Vec<String>,
+    ) -> Result<(), sqlx::Error> {
+        self.record(&DataAccessAuditEntry {
+            ts: Utc::now(),
+            org_id: auth.org_id.clone(),
+            user_id: auth.user_id.clone(),
+            role: format!("{:?}", auth.role).to_lowercase(),
+            request_id: auth.request_id.clone(),
+            endpoint: endpoint.to_string(),
+            fields: exposed.iter().map(|f| f.as_str().to_string()).collect(),
+            trace_ids,
+        })
+        .await
+    }
+
+    /// Audit the resolution of a pseudonym to the identifier it replaced.
+    ///
+    /// The endpoint of the entry names the pseudonym, so the log shows whose
+    /// identifier was revealed.
+    pub async fn record_pseudonym_resolution(
+        &self,
+        auth: &AuthContext,
+        pseudonym: &str,
+    ) -> Result<(), sqlx::Error> {
+        self.record(&DataAccessAuditEntry {
+            ts: Utc::now(),
+            org_id: auth.org_id.clone(),
+            user_id: auth.user_id.clone(),
+            role: format!("{:?}", auth.role).to_lowercase(),
+            request_id: auth.request_id.clone(),
+            endpoint: format!("GET /api/v1/pseudonyms/{}", pseudonym),
+            fields: vec!["pseudonym".to_string()],
+            trace_ids: Vec::new(),
+        })
+        .await
+    }
+
+    async fn record(&self, entry: &DataAccessAuditEntry) -> Result<(), sqlx::Error> {
+        info!(
+            target: "data_access_audit",
+            org_id = %entry.org_id,
+            user_id = %entry.user_id,
+            endpoint = %entry.endpoint,
+            fields = ?entry.fields,
+            traces = entry.trace_ids.len(),
+            "Unmasked trace data returned"
+        );
+
+        let Some(pool) = &self.pool else {
+            return Ok(());
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO data_access_audit_log
+                (ts, org_id, user_id, role, request_id, endpoint, fields, trace_ids)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(entry.ts)
+        .bind(&entry.org_id)
+        .bind(&entry.user_id)
+        .bind(&entry.role)
+        .bind(&entry.request_id)
+        .bind(&entry.endpoint)
+        .bind(&entry.fields)
+        .bind(&entry.trace_ids)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::{auth::AuthMethod, Role};
+
+    fn auth(permissions: &[&str]) -> AuthContext {
+        AuthContext {
+            user_id: "user-1".to_string(),
+            org_id: "org-1".to_string(),
+            projects: vec![],
+            role: Role::Viewer,
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            auth_method: AuthMethod::Jwt,
+            request_id: "req-1".to_string(),
+        }
+    }
+
+    fn trace() -> Trace {
+        serde_json::from_value(serde_json::json!({
+            "ts": "2025-11-05T10:00:00Z",
+            "trace_id": "trace-1",
+            "span_id": "span-1",
+            "provider": "openai",
+            "model": "gpt-4o",
+            "input_text": "What is my balance?",
+            "output_text": "Your balance is $42.",
+            "user_id": "alice@example.com",
+            "attributes": {"user.id": "alice@example.com", "gen_ai.system": "openai"}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_viewer_gets_masked_traces() {
+        let policy = DataAccessPolicy::new(SensitiveField::ALL.to_vec(), None);
+        let mut traces = vec![trace()];
+
+        let exposed = policy.mask_for(&auth(&["read:traces"])).apply(&mut traces);
+
+        assert!(exposed.is_empty());
+        let trace = &traces[0];
+        assert_eq!(trace.input_text.as_deref(), Some(MASKED_VALUE));
+        assert_eq!(trace.output_text.as_deref(), Some(MASKED_VALUE));
+        assert_eq!(trace.user_id.as_deref(), Some(MASKED_VALUE));
+        assert_eq!(trace.session_id, None);
+        let attributes = trace.attributes.as_ref().unwrap();
+        assert_eq!(attributes["user.id"], MASKED_VALUE);
+        assert_eq!(attributes["gen_ai.system"], "openai");
+    }
+
+    #[test]
+    fn test_permissions_unmask_fields() {
+        let policy = DataAccessPolicy::new(SensitiveField::ALL.to_vec(), None);
+        let mut traces = vec![trace()];
+
+        let mask = policy.mask_for(&auth(&["read:traces", CONTENT_PERMISSION]));
+        let exposed = mask.apply(&mut traces);
+
+        assert_eq!(
+            exposed,
+            vec![SensitiveField::InputText, SensitiveField::OutputText]
+        );
+        assert_eq!(traces[0].input_text.as_deref(), Some("What is my balance?"));
+        assert_eq!(traces[0].user_id.as_deref(), Some(MASKED_VALUE));
+
+        assert!(policy
+            .check_filter_fields(&auth(&[CONTENT_PERMISSION]), ["output_text", "model"])
+            .is_ok());
+        assert!(policy
+            .check_filter_fields(&auth(&[CONTENT_PERMISSION]), ["attributes.user.id"])
+            .is_err());
+    }
+
+    #[test]
+    fn test_configured_fields() {
+        assert_eq!(
+            SensitiveField::parse_list("input_text, output_text").unwrap(),
+            vec![SensitiveField::InputText, SensitiveField::OutputText]
+        );
+        assert!(SensitiveField::parse_list("prompt").is_err());
+
+        let policy = DataAccessPolicy::new(vec![SensitiveField::InputText], None);
+        let mut traces = vec![trace()];
+        policy.mask_for(&auth(&[])).apply(&mut traces);
+        assert_eq!(traces[0].input_text.as_deref(), Some(MASKED_VALUE));
+        assert_eq!(traces[0].user_id.as_deref(), Some("alice@example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_viewer_gets_masked_event_attributes() {
+        let policy = DataAccessPolicy::new(SensitiveField::ALL.to_vec(), None);
+        let mut events = vec![
+            serde_json::json!({"gen_ai.prompt": "What is my balance?", "chunk": 1}),
+            serde_json::json!({"user.id": "alice@example.com"}),
+        ];
+
+        policy
+            .apply_to_event_attributes(
+                &auth(&["read:traces"]),
+                "GET /api/v1/traces/:trace_id/events",
+                "trace-1",
+                events.iter_mut(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(events[0]["gen_ai.prompt"], MASKED_VALUE);
+        assert_eq!(events[0]["chunk"], 1);
+        assert_eq!(events[1]["user.id"], MASKED_VALUE);
+
+        // Unmasked with the permission, and reported for the audit log
+        let mut events = vec![serde_json::json!({"gen_ai.completion": "Your balance is $42."})];
+        let mask = policy.mask_for(&auth(&["read:traces", CONTENT_PERMISSION]));
+        let exposed = mask.apply_attributes(events.iter_mut());
+
+        assert_eq!(exposed, vec![SensitiveField::OutputText]);
+        assert_eq!(events[0]["gen_ai.completion"], "Your balance is $42.");
+    }
+}
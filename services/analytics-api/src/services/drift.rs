@@ -0,0 +1,896 @@
+//! # Drift Monitoring
+//!
+//! Catches silent behavior changes, such as a provider updating a model
+//! behind the same name, by watching what goes into and comes out of each
+//! model. Every hour a random sample of requests is taken per series
+//! (organization, provider, model and workload, the `prompt.template`
+//! attribute falling back to the span name) and summarized into the
+//! `drift_snapshots` table:
+//! - prompt and response length (mean and standard deviation, in characters)
+//! - refusal rate: share of responses containing a refusal phrase
+//! - language distribution of the responses
+//! - embedding centroid: mean of L2-normalized feature-hashed bag-of-words
+//!   vectors of the responses, so no embedding model is needed
+//!
+//! Each snapshot is compared with its baseline, the request-weighted
+//! combination of the series' snapshots over the preceding days. A
+//! characteristic drifts when:
+//! - a length mean moves by more than `length_shift` baseline standard
+//!   deviations
+//! - the refusal rate rises by more than `refusal_rate_increase`
+//! - the total variation distance of the language shares exceeds
+//!   `language_distance`
+//! - the cosine distance between the centroids exceeds `embedding_distance`
+//!
+//! Drift is recorded in `drift_alerts` and sent as `anomaly.detected`
+//! webhook events with the rule `drift:<metric>`.
+//!
+//! ## Configuration
+//! - `DRIFT_ENABLED` - run the monitor (default: true; needs DATABASE_URL)
+//! - `DRIFT_INTERVAL_SECS` - how often the last hour is summarized (default: 900)
+//! - `DRIFT_SAMPLE_SIZE` - requests sampled per series and hour (default: 500)
+//! - `DRIFT_BASELINE_DAYS` - days of snapshots in the baseline (default: 7)
+//! - `DRIFT_MIN_REQUESTS` - requests a snapshot and its baseline need to be
+//!   compared (default: 50)
+
+use crate::models::recommendations::WorkloadGrouping;
+use crate::services::webhooks::WebhookService;
+use chrono::{DateTime, Duration as ChronoDuration, DurationRound, Utc};
+use llm_observatory_webhooks::{EventType, Severity, WebhookEvent};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+/// Dimensions of the hashed bag-of-words embedding
+pub const EMBEDDING_DIMS: usize = 64;
+
+/// Characters of each response looked at for refusals
+const REFUSAL_PREFIX_CHARS: usize = 300;
+
+/// Phrases marking a refusal, matched case-insensitively near the start of
+/// a response
+const REFUSAL_PHRASES: &[&str] = &[
+    "i can't help with",
+    "i cannot help with",
+    "i can't assist with",
+    "i cannot assist with",
+    "i'm unable to",
+    "i am unable to",
+    "i'm not able to",
+    "i am not able to",
+    "i won't be able to",
+    "i'm sorry, but i can",
+    "i apologize, but i can",
+    "as an ai language model",
+    "i must decline",
+];
+
+/// Drift thresholds and sampling settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriftConfig {
+    /// Requests sampled per series and hour
+    pub sample_size: i64,
+    /// Days of snapshots combined into the baseline
+    pub baseline_days: i64,
+    /// Requests a snapshot and its baseline need to be compared
+    pub min_requests: i64,
+    /// Largest shift of a length mean, in baseline standard deviations
+    pub length_shift: f64,
+    /// Largest rise of the refusal rate (0.05 = 5 percentage points)
+    pub refusal_rate_increase: f64,
+    /// Largest total variation distance of the language shares
+    pub language_distance: f64,
+    /// Largest cosine distance between embedding centroids
+    pub embedding_distance: f64,
+}
+
+impl Default for DriftConfig {
+    fn default() -> Self {
+        Self {
+            sample_size: 500,
+            baseline_days: 7,
+            min_requests: 50,
+            length_shift: 1.0,
+            refusal_rate_increase: 0.05,
+            language_distance: 0.2,
+            embedding_distance: 0.15,
+        }
+    }
+}
+
+/// A characteristic watched for drift
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftMetric {
+    PromptLength,
+    ResponseLength,
+    RefusalRate,
+    Language,
+    Embedding,
+}
+
+impl DriftMetric {
+    /// Get the metric as stored in `drift_alerts`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DriftMetric::PromptLength => "prompt_length",
+            DriftMetric::ResponseLength => "response_length",
+            DriftMetric::RefusalRate => "refusal_rate",
+            DriftMetric::Language => "language",
+            DriftMetric::Embedding => "embedding",
+        }
+    }
+}
+
+/// Characteristics of one series in one hour (or of its baseline)
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriftStats {
+    pub request_count: i64,
+    pub sample_count: i64,
+    pub prompt_length_mean: f64,
+    pub prompt_length_stddev: f64,
+    pub response_length_mean: f64,
+    pub response_length_stddev: f64,
+    pub refusal_rate: f64,
+    pub languages: BTreeMap<String, f64>,
+    pub embedding_centroid: Vec<f64>,
+}
+
+impl DriftStats {
+    /// Summarize sampled `(prompt, response)` pairs of a series that had
+    /// `request_count` requests.
+    pub fn from_samples(request_count: i64, samples: &[(String, String)]) -> Self {
+        let n = samples.len().max(1) as f64;
+        let prompt_lengths: Vec<f64> = samples
+            .iter()
+            .map(|(prompt, _)| prompt.chars().count() as f64)
+            .collect();
+        let response_lengths: Vec<f64> = samples
+            .iter()
+            .map(|(_, response)| response.chars().count() as f64)
+            .collect();
+        let (prompt_length_mean, prompt_length_stddev) = mean_stddev(&prompt_lengths);
+        let (response_length_mean, response_length_stddev) = mean_stddev(&response_lengths);
+
+        let refusals = samples
+            .iter()
+            .filter(|(_, response)| is_refusal(response))
+            .count();
+
+        let mut language_counts: BTreeMap<String, usize> = BTreeMap::new();
+        for (_, response) in samples {
+            *language_counts
+                .entry(detect_language(response).to_string())
+                .or_insert(0) += 1;
+        }
+        let languages = language_counts
+            .into_iter()
+            .map(|(language, count)| (language, count as f64 / n))
+            .collect();
+
+        let mut embedding_centroid = vec![0.0; EMBEDDING_DIMS];
+        for (_, response) in samples {
+            for (sum, value) in embedding_centroid.iter_mut().zip(embed(response)) {
+                *sum += value / n;
+            }
+        }
+
+        Self {
+            request_count,
+            sample_count: samples.len() as i64,
+            prompt_length_mean,
+            prompt_length_stddev,
+            response_length_mean,
+            response_length_stddev,
+            refusal_rate: refusals as f64 / n,
+            languages,
+            embedding_centroid,
+        }
+    }
+
+    /// Combine snapshots into a baseline, weighted by their requests.
+    /// Standard deviations are pooled, including the spread between the
+    /// snapshots' means.
+    pub fn combine(snapshots: &[DriftStats]) -> Option<Self> {
+        let total: i64 = snapshots.iter().map(|s| s.request_count).sum();
+        if total == 0 {
+            return None;
+        }
+        let weight = |s: &DriftStats| s.request_count as f64 / total as f64;
+        let weighted = |f: &dyn Fn(&DriftStats) -> f64| -> f64 {
+            snapshots.iter().map(|s| weight(s) * f(s)).sum()
+        };
+
+        let prompt_length_mean = weighted(&|s| s.prompt_length_mean);
+        let prompt_second_moment =
+            weighted(&|s| s.prompt_length_stddev.powi(2) + s.prompt_length_mean.powi(2));
+        let response_length_mean = weighted(&|s| s.response_length_mean);
+        let response_second_moment =
+            weighted(&|s| s.response_length_stddev.powi(2) + s.response_length_mean.powi(2));
+
+        let mut languages = BTreeMap::new();
+        let mut embedding_centroid = vec![0.0; EMBEDDING_DIMS];
+        for snapshot in snapshots {
+            for (language, share) in &snapshot.languages {
+                *languages.entry(language.clone()).or_insert(0.0) += weight(snapshot) * share;
+            }
+            for (sum, value) in embedding_centroid
+                .iter_mut()
+                .zip(&snapshot.embedding_centroid)
+            {
+                *sum += weight(snapshot) * value;
+            }
+        }
+
+        Some(Self {
+            request_count: total,
+            sample_count: snapshots.iter().map(|s| s.sample_count).sum(),
+            prompt_length_mean,
+            prompt_length_stddev: (prompt_second_moment - prompt_length_mean.powi(2))
+                .max(0.0)
+                .sqrt(),
+            response_length_mean,
+            response_length_stddev: (response_second_moment - response_length_mean.powi(2))
+                .max(0.0)
+                .sqrt(),
+            refusal_rate: weighted(&|s| s.refusal_rate),
+            languages,
+            embedding_centroid,
+        })
+    }
+}
+
+/// A characteristic that moved beyond its threshold
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriftFinding {
+    pub metric: DriftMetric,
+    pub baseline_value: f64,
+    pub current_value: f64,
+    pub score: f64,
+    pub threshold: f64,
+}
+
+impl DriftFinding {
+    /// One-line description for notifications
+    pub fn message(&self, provider: &str, model: &str, workload: &str) -> String {
+        let what = match self.metric {
+            DriftMetric::PromptLength => format!(
+                "prompt length moved from {:.0} to {:.0} characters",
+                self.baseline_value, self.current_value
+            ),
+            DriftMetric::ResponseLength => format!(
+                "response length moved from {:.0} to {:.0} characters",
+                self.baseline_value, self.current_value
+            ),
+            DriftMetric::RefusalRate => format!(
+                "refusal rate rose from {:.1}% to {:.1}%",
+                self.baseline_value * 100.0,
+                self.current_value * 100.0
+            ),
+            DriftMetric::Language => format!(
+                "language mix shifted (distance {:.2}, top language share {:.0}% -> {:.0}%)",
+                self.score,
+                self.baseline_value * 100.0,
+                self.current_value * 100.0
+            ),
+            DriftMetric::Embedding => format!(
+                "response content shifted (centroid distance {:.2})",
+                self.score
+            ),
+        };
+        format!("Drift on {}/{} ({}): {}", provider, model, workload, what)
+    }
+}
+
+/// Compare a snapshot with its baseline.
+pub fn detect_drift(
+    baseline: &DriftStats,
+    current: &DriftStats,
+    config: &DriftConfig,
+) -> Vec<DriftFinding> {
+    let mut findings = Vec::new();
+
+    let lengths = [
+        (
+            DriftMetric::PromptLength,
+            baseline.prompt_length_mean,
+            baseline.prompt_length_stddev,
+            current.prompt_length_mean,
+        ),
+        (
+            DriftMetric::ResponseLength,
+            baseline.response_length_mean,
+            baseline.response_length_stddev,
+            current.response_length_mean,
+        ),
+    ];
+    for (metric, baseline_mean, baseline_stddev, current_mean) in lengths {
+        // A constant baseline would flag any change; treat one character
+        // as the smallest meaningful spread
+        let score = (current_mean - baseline_mean).abs() / baseline_stddev.max(1.0);
+        if score > config.length_shift {
+            findings.push(DriftFinding {
+                metric,
+                baseline_value: baseline_mean,
+                current_value: current_mean,
+                score,
+                threshold: config.length_shift,
+            });
+        }
+    }
+
+    let refusal_increase = current.refusal_rate - baseline.refusal_rate;
+    if refusal_increase > config.refusal_rate_increase {
+        findings.push(DriftFinding {
+            metric: DriftMetric::RefusalRate,
+            baseline_value: baseline.refusal_rate,
+            current_value: current.refusal_rate,
+            score: refusal_increase,
+            threshold: config.refusal_rate_increase,
+        });
+    }
+
+    let language_distance = total_variation(&baseline.languages, &current.languages);
+    if language_distance > config.language_distance {
+        let top = baseline
+            .languages
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(language, _)| language.as_str());
+        let share = |languages: &BTreeMap<String, f64>| {
+            top.and_then(|t| languages.get(t)).copied().unwrap_or(0.0)
+        };
+        findings.push(DriftFinding {
+            metric: DriftMetric::Language,
+            baseline_value: share(&baseline.languages),
+            current_value: share(&current.languages),
+            score: language_distance,
+            threshold: config.language_distance,
+        });
+    }
+
+    let embedding_distance =
+        cosine_distance(&baseline.embedding_centroid, &current.embedding_centroid);
+    if embedding_distance > config.embedding_distance {
+        findings.push(DriftFinding {
+            metric: DriftMetric::Embedding,
+            baseline_value: 0.0,
+            current_value: embedding_distance,
+            score: embedding_distance,
+            threshold: config.embedding_distance,
+        });
+    }
+
+    findings
+}
+
+/// Whether a response refuses the request
+pub fn is_refusal(response: &str) -> bool {
+    let head: String = response
+        .chars()
+        .take(REFUSAL_PREFIX_CHARS)
+        .collect::<String>()
+        .to_lowercase()
+        .replace('\u{2019}', "'");
+    REFUSAL_PHRASES.iter().any(|phrase| head.contains(phrase))
+}
+
+/// Detect the language of a text: by script, and by common words for
+/// Latin-script text. Returns an ISO 639-1 code, or `und` when undetermined.
+pub fn detect_language(text: &str) -> &'static str {
+    let mut scripts: HashMap<&'static str, usize> = HashMap::new();
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        let script = match c as u32 {
+            0x0370..=0x03FF => "el",
+            0x0400..=0x04FF => "ru",
+            0x0590..=0x05FF => "he",
+            0x0600..=0x06FF => "ar",
+            0x0900..=0x097F => "hi",
+            0x0E00..=0x0E7F => "th",
+            0x3040..=0x30FF => "ja",
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => "ko",
+            0x4E00..=0x9FFF => "zh",
+            _ if c.is_ascii_alphabetic() || ('\u{00C0}'..='\u{024F}').contains(&c) => "latin",
+            _ => "other",
+        };
+        *scripts.entry(script).or_insert(0) += 1;
+    }
+
+    // Kana marks Japanese even when most characters are kanji
+    if scripts.contains_key("ja") {
+        return "ja";
+    }
+    let Some((script, _)) = scripts
+        .into_iter()
+        .filter(|(script, _)| *script != "other")
+        .max_by_key(|(script, count)| (*count, *script))
+    else {
+        return "und";
+    };
+    if script != "latin" {
+        return script;
+    }
+
+    const STOPWORDS: &[(&str, &[&str])] = &[
+        ("en", &["the", "and", "is", "of", "to", "you", "that", "it"]),
+        ("es", &["el", "la", "que", "de", "los", "es", "por", "una"]),
+        (
+            "fr",
+            &["le", "la", "les", "est", "et", "des", "une", "vous"],
+        ),
+        (
+            "de",
+            &["der", "die", "und", "ist", "das", "nicht", "ein", "sie"],
+        ),
+        (
+            "pt",
+            &["o", "que", "não", "uma", "para", "com", "os", "você"],
+        ),
+        ("it", &["il", "che", "di", "è", "per", "una", "non", "sono"]),
+    ];
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(200)
+        .map(str::to_lowercase)
+        .collect();
+    STOPWORDS
+        .iter()
+        .map(|(language, stopwords)| {
+            let hits = words
+                .iter()
+                .filter(|w| stopwords.contains(&w.as_str()))
+                .count();
+            (hits, *language)
+        })
+        .filter(|(hits, _)| *hits > 0)
+        .max_by_key(|(hits, language)| (*hits, std::cmp::Reverse(*language)))
+        .map(|(_, language)| language)
+        .unwrap_or("und")
+}
+
+/// Feature-hashed bag-of-words vector of a text, L2-normalized. Words are
+/// hashed with FNV-1a so centroids stay comparable across releases.
+pub fn embed(text: &str) -> Vec<f64> {
+    let mut vector = vec![0.0; EMBEDDING_DIMS];
+    for word in text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        let hash = fnv1a(&word.to_lowercase());
+        let sign = if hash & (1 << 63) == 0 { 1.0 } else { -1.0 };
+        vector[(hash % EMBEDDING_DIMS as u64) as usize] += sign;
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn mean_stddev(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    (mean, variance.sqrt())
+}
+
+/// Half the L1 distance between two distributions (0 = same, 1 = disjoint)
+fn total_variation(a: &BTreeMap<String, f64>, b: &BTreeMap<String, f64>) -> f64 {
+    let keys: std::collections::BTreeSet<_> = a.keys().chain(b.keys()).collect();
+    keys.into_iter()
+        .map(|k| (a.get(k).unwrap_or(&0.0) - b.get(k).unwrap_or(&0.0)).abs())
+        .sum::<f64>()
+        / 2.0
+}
+
+/// 1 - cosine similarity; 0 when either vector is zero
+fn cosine_distance(a: &[f64], b: &[f64]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    1.0 - dot / (norm_a * norm_b)
+}
+
+/// A sampled request of one series
+#[derive(Debug, sqlx::FromRow)]
+struct SampleRow {
+    org_id: String,
+    provider: String,
+    model: String,
+    workload: String,
+    request_count: i64,
+    input_text: Option<String>,
+    output_text: Option<String>,
+}
+
+/// A stored snapshot of one series
+#[derive(Debug, sqlx::FromRow)]
+struct SnapshotRow {
+    request_count: i64,
+    sample_count: i64,
+    prompt_length_mean: f64,
+    prompt_length_stddev: f64,
+    response_length_mean: f64,
+    response_length_stddev: f64,
+    refusal_rate: f64,
+    languages: Json<BTreeMap<String, f64>>,
+    embedding_centroid: Vec<f64>,
+}
+
+impl From<SnapshotRow> for DriftStats {
+    fn from(row: SnapshotRow) -> Self {
+        Self {
+            request_count: row.request_count,
+            sample_count: row.sample_count,
+            prompt_length_mean: row.prompt_length_mean,
+            prompt_length_stddev: row.prompt_length_stddev,
+            response_length_mean: row.response_length_mean,
+            response_length_stddev: row.response_length_stddev,
+            refusal_rate: row.refusal_rate,
+            languages: row.languages.0,
+            embedding_centroid: row.embedding_centroid,
+        }
+    }
+}
+
+/// Periodically snapshots prompt/response characteristics and raises drift
+/// alerts
+pub struct DriftMonitor {
+    pool: PgPool,
+    webhooks: Arc<WebhookService>,
+    config: DriftConfig,
+}
+
+impl DriftMonitor {
+    /// Create a monitor writing with `pool` and notifying through `webhooks`.
+    pub fn new(pool: PgPool, webhooks: Arc<WebhookService>, config: DriftConfig) -> Self {
+        Self {
+            pool,
+            webhooks,
+            config,
+        }
+    }
+
+    /// Snapshot the last complete hour before `now` and compare each series
+    /// with its baseline. Returns the number of drift alerts raised.
+    pub async fn run_once(&self, now: DateTime<Utc>) -> Result<usize, sqlx::Error> {
+        let hour = ChronoDuration::hours(1);
+        let bucket = now.duration_trunc(hour).unwrap_or(now) - hour;
+
+        let rows = sqlx::query_as::<_, SampleRow>(&format!(
+            r#"
+            SELECT org_id, provider, model, workload, request_count, input_text, output_text
+            FROM (
+                SELECT
+                    attributes->>'org_id' AS org_id,
+                    provider,
+                    model,
+                    {workload} AS workload,
+                    input_text,
+                    output_text,
+                    COUNT(*) OVER series AS request_count,
+                    ROW_NUMBER() OVER (series ORDER BY random()) AS sample_rank
+                FROM llm_traces
+                WHERE ts >= $1
+                  AND ts < $2
+                  AND attributes->>'org_id' IS NOT NULL
+                  AND output_text IS NOT NULL
+                WINDOW series AS (PARTITION BY attributes->>'org_id', provider, model, {workload})
+            ) sampled
+            WHERE sample_rank <= $3
+            "#,
+            workload = WorkloadGrouping::PromptTemplate.to_sql_expression()
+        ))
+        .bind(bucket)
+        .bind(bucket + hour)
+        .bind(self.config.sample_size)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut series: BTreeMap<(String, String, String, String), (i64, Vec<(String, String)>)> =
+            BTreeMap::new();
+        for row in rows {
+            let entry = series
+                .entry((row.org_id, row.provider, row.model, row.workload))
+                .or_insert((row.request_count, Vec::new()));
+            entry.1.push((
+                row.input_text.unwrap_or_default(),
+                row.output_text.unwrap_or_default(),
+            ));
+        }
+
+        let mut alerts = 0;
+        for ((org_id, provider, model, workload), (request_count, samples)) in &series {
+            let current = DriftStats::from_samples(*request_count, samples);
+            self.store_snapshot(org_id, provider, model, workload, bucket, &current)
+                .await?;
+            if current.request_count < self.config.min_requests {
+                continue;
+            }
+
+            let baseline_rows = sqlx::query_as::<_, SnapshotRow>(
+                r#"
+                SELECT
+                    request_count, sample_count,
+                    prompt_length_mean, prompt_length_stddev,
+                    response_length_mean, response_length_stddev,
+                    refusal_rate, languages, embedding_centroid
+                FROM drift_snapshots
+                WHERE org_id = $1
+                  AND provider = $2
+                  AND model = $3
+                  AND workload = $4
+                  AND bucket >= $5
+                  AND bucket < $6
+                "#,
+            )
+            .bind(org_id)
+            .bind(provider)
+            .bind(model)
+            .bind(workload)
+            .bind(bucket - ChronoDuration::days(self.config.baseline_days))
+            .bind(bucket)
+            .fetch_all(&self.pool)
+            .await?;
+            let snapshots: Vec<DriftStats> = baseline_rows.into_iter().map(Into::into).collect();
+            let Some(baseline) = DriftStats::combine(&snapshots) else {
+                continue;
+            };
+            if baseline.request_count < self.config.min_requests {
+                continue;
+            }
+
+            for finding in detect_drift(&baseline, &current, &self.config) {
+                let raised = sqlx::query(
+                    r#"
+                    INSERT INTO drift_alerts (
+                        org_id, provider, model, workload, bucket, metric,
+                        baseline_value, current_value, score, threshold
+                    )
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                    ON CONFLICT ON CONSTRAINT drift_alerts_once_per_bucket DO NOTHING
+                    "#,
+                )
+                .bind(org_id)
+                .bind(provider)
+                .bind(model)
+                .bind(workload)
+                .bind(bucket)
+                .bind(finding.metric.as_str())
+                .bind(finding.baseline_value)
+                .bind(finding.current_value)
+                .bind(finding.score)
+                .bind(finding.threshold)
+                .execute(&self.pool)
+                .await?
+                .rows_affected()
+                    > 0;
+                if !raised {
+                    continue;
+                }
+
+                alerts += 1;
+                let event = WebhookEvent::new(
+                    EventType::AnomalyDetected,
+                    org_id.as_str(),
+                    serde_json::json!({
+                        "message": finding.message(provider, model, workload),
+                        "provider": provider,
+                        "model": model,
+                        "workload": workload,
+                        "metric": finding.metric.as_str(),
+                        "baseline_value": finding.baseline_value,
+                        "current_value": finding.current_value,
+                        "score": finding.score,
+                        "threshold": finding.threshold,
+                        "hour": bucket,
+                    }),
+                )
+                .with_severity(Severity::Warning)
+                .with_rule(format!("drift:{}", finding.metric.as_str()));
+                self.webhooks.notify(event).await;
+            }
+        }
+
+        debug!(series = series.len(), alerts, "Drift snapshots computed");
+        Ok(alerts)
+    }
+
+    async fn store_snapshot(
+        &self,
+        org_id: &str,
+        provider: &str,
+        model: &str,
+        workload: &str,
+        bucket: DateTime<Utc>,
+        stats: &DriftStats,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO drift_snapshots (
+                org_id, provider, model, workload, bucket, request_count, sample_count,
+                prompt_length_mean, prompt_length_stddev,
+                response_length_mean, response_length_stddev,
+                refusal_rate, languages, embedding_centroid, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, NOW())
+            ON CONFLICT (org_id, provider, model, workload, bucket) DO UPDATE SET
+                request_count = EXCLUDED.request_count,
+                sample_count = EXCLUDED.sample_count,
+                prompt_length_mean = EXCLUDED.prompt_length_mean,
+                prompt_length_stddev = EXCLUDED.prompt_length_stddev,
+                response_length_mean = EXCLUDED.response_length_mean,
+                response_length_stddev = EXCLUDED.response_length_stddev,
+                refusal_rate = EXCLUDED.refusal_rate,
+                languages = EXCLUDED.languages,
+                embedding_centroid = EXCLUDED.embedding_centroid,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(org_id)
+        .bind(provider)
+        .bind(model)
+        .bind(workload)
+        .bind(bucket)
+        .bind(stats.request_count)
+        .bind(stats.sample_count)
+        .bind(stats.prompt_length_mean)
+        .bind(stats.prompt_length_stddev)
+        .bind(stats.response_length_mean)
+        .bind(stats.response_length_stddev)
+        .bind(stats.refusal_rate)
+        .bind(Json(&stats.languages))
+        .bind(&stats.embedding_centroid)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Run at a fixed interval in the background.
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        info!(
+            sample_size = self.config.sample_size,
+            baseline_days = self.config.baseline_days,
+            "Drift monitor started"
+        );
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.run_once(Utc::now()).await {
+                    Ok(alerts) => metrics::counter!("drift_alerts_total").increment(alerts as u64),
+                    Err(e) => error!(error = %e, "Failed to compute drift snapshots"),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(prompt: &str, response: &str, n: usize) -> Vec<(String, String)> {
+        (0..n)
+            .map(|_| (prompt.to_string(), response.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_refusal_and_language() {
+        assert!(is_refusal("I’m sorry, but I can’t help with that request."));
+        assert!(is_refusal(
+            "As an AI language model, I do not have opinions."
+        ));
+        assert!(!is_refusal("Sure, here is the summary of the document."));
+
+        assert_eq!(
+            detect_language("The answer is that you need to restart it."),
+            "en"
+        );
+        assert_eq!(
+            detect_language("Die Antwort ist, dass Sie das nicht tun sollten."),
+            "de"
+        );
+        assert_eq!(
+            detect_language("La respuesta es que el servidor está caído."),
+            "es"
+        );
+        assert_eq!(detect_language("Ответ: перезапустите сервер."), "ru");
+        assert_eq!(detect_language("サーバーを再起動してください。"), "ja");
+        assert_eq!(detect_language("12345 !!!"), "und");
+    }
+
+    #[test]
+    fn test_embed() {
+        let a = embed("restart the database server");
+        let norm = a.iter().map(|v| v * v).sum::<f64>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-9);
+        assert_eq!(a, embed("Restart the DATABASE server"));
+        assert!(cosine_distance(&a, &embed("the database server restart")) < 1e-9);
+        assert!(cosine_distance(&a, &embed("lovely weather in paris today")) > 0.5);
+        assert!(embed("").iter().all(|v| *v == 0.0));
+    }
+
+    #[test]
+    fn test_combine_pools_variance() {
+        let short = DriftStats::from_samples(100, &samples("hi", "ok", 10));
+        let long = DriftStats::from_samples(100, &samples("hello", "okay!", 10));
+        assert_eq!(short.prompt_length_stddev, 0.0);
+
+        let baseline = DriftStats::combine(&[short, long]).unwrap();
+        assert_eq!(baseline.request_count, 200);
+        assert_eq!(baseline.prompt_length_mean, 3.5);
+        assert!((baseline.prompt_length_stddev - 1.5).abs() < 1e-9);
+        assert_eq!(baseline.languages.get("und"), Some(&1.0));
+        assert!(DriftStats::combine(&[]).is_none());
+    }
+
+    #[test]
+    fn test_detect_drift() {
+        let config = DriftConfig::default();
+        let mut baseline_samples = samples(
+            "Summarize this ticket for the support team",
+            "The customer reports that the export is failing and asks for a refund.",
+            90,
+        );
+        baseline_samples.extend(samples(
+            "Summarize this ticket for the support team please",
+            "The user says that the invoice is wrong and the total is too high.",
+            10,
+        ));
+        let baseline = DriftStats::from_samples(1000, &baseline_samples);
+
+        let same = DriftStats::from_samples(100, &baseline_samples);
+        assert!(detect_drift(&baseline, &same, &config).is_empty());
+
+        let mut drifted_samples = samples(
+            "Summarize this ticket for the support team",
+            "I'm sorry, but I can't help with that.",
+            50,
+        );
+        drifted_samples.extend(samples(
+            "Summarize this ticket for the support team",
+            "Der Kunde meldet, dass der Export nicht funktioniert und die Rechnung falsch ist.",
+            50,
+        ));
+        let drifted = DriftStats::from_samples(100, &drifted_samples);
+
+        let findings = detect_drift(&baseline, &drifted, &config);
+        let metrics: Vec<_> = findings.iter().map(|f| f.metric).collect();
+        assert_eq!(
+            metrics,
+            [
+                DriftMetric::ResponseLength,
+                DriftMetric::RefusalRate,
+                DriftMetric::Language,
+                DriftMetric::Embedding,
+            ]
+        );
+
+        let refusal = &findings[1];
+        assert_eq!(refusal.baseline_value, 0.0);
+        assert_eq!(refusal.current_value, 0.5);
+        assert_eq!(
+            refusal.message("openai", "gpt-4o", "support-summary"),
+            "Drift on openai/gpt-4o (support-summary): refusal rate rose from 0.0% to 50.0%"
+        );
+    }
+}
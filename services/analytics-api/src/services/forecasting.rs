@@ -0,0 +1,460 @@
+//! # Cost Forecasting
+//!
+//! Forecasting models for daily cost series, used by the cost forecast
+//! endpoint:
+//! - `LinearRegression` - straight-line trend, no seasonality
+//! - `HoltWinters` - additive triple exponential smoothing with a weekly season
+//! - `SeasonalDecomposition` - linear trend plus day-of-week seasonal indices
+//!
+//! LLM traffic usually dips on weekends, which a straight line cannot follow,
+//! so the seasonal models need at least two full weeks of history.
+//!
+//! ## Model Selection
+//! Without an explicit model, every model with enough history is fitted and
+//! scored by its AIC on the full series and by a backtest: the model is
+//! fitted without the last week, which is then forecast and compared with the
+//! actual costs (MAPE). The model with the lowest score for the requested
+//! criterion wins. When no model can be backtested, AIC is used instead.
+
+use crate::models::costs::{calculate_linear_regression, calculate_mape};
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Length of the seasonal cycle in days
+pub const SEASON_LENGTH: usize = 7;
+
+/// Days held out by the backtest
+pub const BACKTEST_DAYS: usize = 7;
+
+/// Two-sided z value for 95% confidence
+const Z_95: f64 = 1.96;
+
+/// Smoothing parameters tried when fitting Holt-Winters
+const HW_ALPHAS: [f64; 5] = [0.1, 0.3, 0.5, 0.7, 0.9];
+const HW_BETAS: [f64; 3] = [0.01, 0.1, 0.3];
+const HW_GAMMAS: [f64; 4] = [0.05, 0.1, 0.3, 0.5];
+
+/// Forecasting model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastModel {
+    LinearRegression,
+    HoltWinters,
+    SeasonalDecomposition,
+}
+
+impl ForecastModel {
+    /// Every model, in the order candidates are reported
+    pub const ALL: [ForecastModel; 3] = [
+        ForecastModel::LinearRegression,
+        ForecastModel::HoltWinters,
+        ForecastModel::SeasonalDecomposition,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ForecastModel::LinearRegression => "linear_regression",
+            ForecastModel::HoltWinters => "holt_winters",
+            ForecastModel::SeasonalDecomposition => "seasonal_decomposition",
+        }
+    }
+
+    /// Fewest daily points the model can be fitted on
+    pub fn min_points(&self) -> usize {
+        match self {
+            ForecastModel::LinearRegression => 2,
+            ForecastModel::HoltWinters | ForecastModel::SeasonalDecomposition => 2 * SEASON_LENGTH,
+        }
+    }
+
+    /// Number of estimated parameters, for AIC
+    fn parameter_count(&self) -> usize {
+        match self {
+            ForecastModel::LinearRegression => 2,
+            // Three smoothing parameters, initial level, trend and season
+            ForecastModel::HoltWinters => 5 + SEASON_LENGTH,
+            // Trend line and all but one seasonal index
+            ForecastModel::SeasonalDecomposition => 1 + SEASON_LENGTH,
+        }
+    }
+}
+
+/// Criterion used to pick a model
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionCriterion {
+    /// Akaike information criterion on the full series
+    Aic,
+    /// Mean absolute percentage error of the backtest
+    #[default]
+    BacktestMape,
+}
+
+/// Scores of one candidate model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateScore {
+    pub model: ForecastModel,
+    pub aic: f64,
+    /// None when the history is too short to backtest the model
+    pub backtest_mape: Option<f64>,
+}
+
+/// How the forecast model was chosen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelSelection {
+    /// Model used for the forecast
+    pub selected: ForecastModel,
+    /// Criterion the model was chosen by; None when it was requested
+    pub criterion: Option<SelectionCriterion>,
+    pub backtest_days: usize,
+    pub candidates: Vec<CandidateScore>,
+}
+
+/// A fitted model and its forecast
+#[derive(Debug, Clone)]
+pub struct Forecast {
+    pub model: ForecastModel,
+    /// One-step-ahead predictions for the history
+    pub fitted: Vec<f64>,
+    /// Forecast for the days after the history
+    pub values: Vec<f64>,
+    /// Standard deviation of the in-sample residuals
+    pub residual_std: f64,
+    pub selection: ModelSelection,
+}
+
+impl Forecast {
+    /// 95% prediction interval of the forecast `h` days ahead (1-based)
+    pub fn interval(&self, h: usize) -> (f64, f64) {
+        let value = self.values[h - 1];
+        let margin = Z_95 * self.residual_std * (h as f64).sqrt();
+        ((value - margin).max(0.0), value + margin)
+    }
+
+    /// Coefficient of determination of the fitted values
+    pub fn r_squared(&self, series: &[f64]) -> f64 {
+        let mean = series.iter().sum::<f64>() / series.len().max(1) as f64;
+        let ss_tot: f64 = series.iter().map(|y| (y - mean).powi(2)).sum();
+        if ss_tot == 0.0 {
+            return 0.0;
+        }
+        1.0 - sse(series, &self.fitted) / ss_tot
+    }
+}
+
+/// Dense daily series of the complete days in `[start, end)`.
+///
+/// `points` are daily buckets; days without a bucket cost nothing. The day
+/// containing `end` is left out unless `end` is midnight, so a partial
+/// current day does not look like a drop in spend.
+pub fn daily_series(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    points: &[(DateTime<Utc>, f64)],
+) -> Vec<(DateTime<Utc>, f64)> {
+    let day = Duration::days(1);
+    let (Ok(mut date), Ok(last)) = (start.duration_trunc(day), end.duration_trunc(day)) else {
+        return Vec::new();
+    };
+
+    let mut series = Vec::new();
+    while date < last {
+        let cost = points
+            .iter()
+            .filter(|(d, _)| d.duration_trunc(day).ok() == Some(date))
+            .map(|(_, cost)| cost)
+            .sum();
+        series.push((date, cost));
+        date += day;
+    }
+    series
+}
+
+/// Fit `model` to `series` and forecast `horizon` days; None if the series
+/// is too short for the model.
+pub fn fit(model: ForecastModel, series: &[f64], horizon: usize) -> Option<(Vec<f64>, Vec<f64>)> {
+    if series.len() < model.min_points() {
+        return None;
+    }
+
+    Some(match model {
+        ForecastModel::LinearRegression => linear(series, horizon),
+        ForecastModel::HoltWinters => holt_winters(series, horizon),
+        ForecastModel::SeasonalDecomposition => seasonal_decomposition(series, horizon),
+    })
+}
+
+/// Forecast `horizon` days of `series` with `model`, or with the best model
+/// by `criterion` when `model` is None.
+///
+/// Returns None if the series is too short for the requested model.
+pub fn forecast(
+    series: &[f64],
+    horizon: usize,
+    model: Option<ForecastModel>,
+    criterion: SelectionCriterion,
+) -> Option<Forecast> {
+    let candidates: Vec<CandidateScore> = ForecastModel::ALL
+        .iter()
+        .filter(|m| model.map_or(true, |requested| requested == **m))
+        .filter_map(|&m| {
+            let (fitted, _) = fit(m, series, 0)?;
+            Some(CandidateScore {
+                model: m,
+                aic: aic(sse(series, &fitted), series.len(), m.parameter_count()),
+                backtest_mape: backtest(m, series),
+            })
+        })
+        .collect();
+
+    let by_mape = candidates
+        .iter()
+        .filter_map(|c| c.backtest_mape.map(|mape| (c.model, mape)))
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    let by_aic = candidates
+        .iter()
+        .min_by(|a, b| a.aic.total_cmp(&b.aic))
+        .map(|c| (c.model, c.aic));
+
+    let (selected, criterion) = match (model, criterion, by_mape) {
+        (Some(requested), _, _) => (requested, None),
+        (None, SelectionCriterion::BacktestMape, Some((best, _))) => {
+            (best, Some(SelectionCriterion::BacktestMape))
+        }
+        (None, _, _) => (by_aic?.0, Some(SelectionCriterion::Aic)),
+    };
+
+    let (fitted, values) = fit(selected, series, horizon)?;
+    let residual_std = (sse(series, &fitted) / series.len() as f64).sqrt();
+
+    Some(Forecast {
+        model: selected,
+        fitted,
+        values: values.into_iter().map(|v| v.max(0.0)).collect(),
+        residual_std,
+        selection: ModelSelection {
+            selected,
+            criterion,
+            backtest_days: BACKTEST_DAYS,
+            candidates,
+        },
+    })
+}
+
+/// MAPE of forecasting the last `BACKTEST_DAYS` from the days before them
+fn backtest(model: ForecastModel, series: &[f64]) -> Option<f64> {
+    let train_len = series.len().checked_sub(BACKTEST_DAYS)?;
+    let (train, holdout) = series.split_at(train_len);
+    let (_, predicted) = fit(model, train, BACKTEST_DAYS)?;
+    let predicted: Vec<f64> = predicted.into_iter().map(|v| v.max(0.0)).collect();
+    calculate_mape(holdout, &predicted)
+}
+
+fn sse(actual: &[f64], fitted: &[f64]) -> f64 {
+    actual
+        .iter()
+        .zip(fitted)
+        .map(|(a, f)| (a - f).powi(2))
+        .sum()
+}
+
+fn aic(sse: f64, n: usize, parameters: usize) -> f64 {
+    let n = n as f64;
+    n * (sse.max(1e-12) / n).ln() + 2.0 * parameters as f64
+}
+
+fn linear(series: &[f64], horizon: usize) -> (Vec<f64>, Vec<f64>) {
+    let points: Vec<(f64, f64)> = series
+        .iter()
+        .enumerate()
+        .map(|(i, y)| (i as f64, *y))
+        .collect();
+    let (slope, intercept, _) = calculate_linear_regression(&points);
+    let line = |t: usize| intercept + slope * t as f64;
+
+    let fitted = (0..series.len()).map(line).collect();
+    let values = (series.len()..series.len() + horizon).map(line).collect();
+    (fitted, values)
+}
+
+/// Additive Holt-Winters, with smoothing parameters picked from a grid by
+/// in-sample squared error
+fn holt_winters(series: &[f64], horizon: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut best: Option<(f64, Vec<f64>, Vec<f64>)> = None;
+    for alpha in HW_ALPHAS {
+        for beta in HW_BETAS {
+            for gamma in HW_GAMMAS {
+                let (fitted, values) = holt_winters_with(series, horizon, alpha, beta, gamma);
+                let error = sse(series, &fitted);
+                if best.as_ref().map_or(true, |(e, _, _)| error < *e) {
+                    best = Some((error, fitted, values));
+                }
+            }
+        }
+    }
+    best.map(|(_, fitted, values)| (fitted, values))
+        .unwrap_or_default()
+}
+
+fn holt_winters_with(
+    series: &[f64],
+    horizon: usize,
+    alpha: f64,
+    beta: f64,
+    gamma: f64,
+) -> (Vec<f64>, Vec<f64>) {
+    let m = SEASON_LENGTH;
+    let first = series[..m].iter().sum::<f64>() / m as f64;
+    let second = series[m..2 * m].iter().sum::<f64>() / m as f64;
+
+    let mut level = first;
+    let mut trend = (second - first) / m as f64;
+    let mut seasonal: Vec<f64> = series[..m].iter().map(|y| y - first).collect();
+
+    let mut fitted = Vec::with_capacity(series.len());
+    for (t, y) in series.iter().enumerate() {
+        let s = seasonal[t % m];
+        fitted.push(level + trend + s);
+
+        let previous = level;
+        level = alpha * (y - s) + (1.0 - alpha) * (level + trend);
+        trend = beta * (level - previous) + (1.0 - beta) * trend;
+        seasonal[t % m] = gamma * (y - level) + (1.0 - gamma) * s;
+    }
+
+    let n = series.len();
+    let values = (1..=horizon)
+        .map(|h| level + h as f64 * trend + seasonal[(n + h - 1) % m])
+        .collect();
+    (fitted, values)
+}
+
+/// Linear trend of the deseasonalized series plus day-of-week indices of the
+/// detrended series
+fn seasonal_decomposition(series: &[f64], horizon: usize) -> (Vec<f64>, Vec<f64>) {
+    let m = SEASON_LENGTH;
+    let (trend, _) = linear(series, 0);
+
+    let mut indices = vec![0.0; m];
+    let mut counts = vec![0usize; m];
+    for (t, (y, trend)) in series.iter().zip(&trend).enumerate() {
+        indices[t % m] += y - trend;
+        counts[t % m] += 1;
+    }
+    for (index, count) in indices.iter_mut().zip(&counts) {
+        *index /= (*count).max(1) as f64;
+    }
+    let mean = indices.iter().sum::<f64>() / m as f64;
+    for index in &mut indices {
+        *index -= mean;
+    }
+
+    let deseasonalized: Vec<f64> = series
+        .iter()
+        .enumerate()
+        .map(|(t, y)| y - indices[t % m])
+        .collect();
+    let (trend, values) = linear(&deseasonalized, horizon);
+
+    let n = series.len();
+    let fitted = trend
+        .iter()
+        .enumerate()
+        .map(|(t, v)| v + indices[t % m])
+        .collect();
+    let values = values
+        .iter()
+        .enumerate()
+        .map(|(h, v)| v + indices[(n + h) % m])
+        .collect();
+    (fitted, values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Four weeks of weekday spend around 100 with quiet weekends
+    fn weekly_series() -> Vec<f64> {
+        (0..28)
+            .map(|t| {
+                let weekend = t % 7 >= 5;
+                let base = 100.0 + t as f64;
+                if weekend {
+                    base * 0.2
+                } else {
+                    base
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_daily_series_fills_gaps() {
+        let day = |d: u32, h: u32| Utc.with_ymd_and_hms(2025, 3, d, h, 0, 0).unwrap();
+        let points = vec![(day(1, 0), 5.0), (day(3, 0), 7.0), (day(4, 0), 9.0)];
+
+        let series = daily_series(day(1, 6), day(4, 12), &points);
+        let costs: Vec<f64> = series.iter().map(|(_, c)| *c).collect();
+        assert_eq!(costs, [5.0, 0.0, 7.0]);
+        assert_eq!(series[1].0, day(2, 0));
+    }
+
+    #[test]
+    fn test_seasonal_models_beat_linear_on_weekly_data() {
+        let series = weekly_series();
+
+        let forecast = forecast(&series, 14, None, SelectionCriterion::BacktestMape).unwrap();
+        assert_ne!(forecast.model, ForecastModel::LinearRegression);
+        assert_eq!(
+            forecast.selection.criterion,
+            Some(SelectionCriterion::BacktestMape)
+        );
+        assert_eq!(forecast.values.len(), 14);
+
+        // Day 28 is a Monday-like weekday, day 33 a weekend day
+        assert!(forecast.values[0] > 100.0);
+        assert!(forecast.values[5] < 60.0);
+
+        let linear = forecast
+            .selection
+            .candidates
+            .iter()
+            .find(|c| c.model == ForecastModel::LinearRegression)
+            .unwrap();
+        let selected = forecast
+            .selection
+            .candidates
+            .iter()
+            .find(|c| c.model == forecast.model)
+            .unwrap();
+        assert!(selected.backtest_mape.unwrap() < linear.backtest_mape.unwrap());
+        assert!(selected.aic < linear.aic);
+    }
+
+    #[test]
+    fn test_short_history_falls_back() {
+        let series: Vec<f64> = (0..10).map(|t| 10.0 + t as f64).collect();
+
+        let forecast = forecast(&series, 3, None, SelectionCriterion::BacktestMape).unwrap();
+        assert_eq!(forecast.model, ForecastModel::LinearRegression);
+        assert_eq!(forecast.selection.candidates.len(), 1);
+        assert!((forecast.values[0] - 20.0).abs() < 1e-9);
+        let (lower, upper) = forecast.interval(1);
+        assert!(lower <= forecast.values[0] && forecast.values[0] <= upper);
+
+        // Too short to backtest
+        let series = &series[..5];
+        let forecast = super::forecast(series, 3, None, SelectionCriterion::BacktestMape).unwrap();
+        assert_eq!(forecast.selection.criterion, Some(SelectionCriterion::Aic));
+
+        assert!(super::forecast(
+            series,
+            3,
+            Some(ForecastModel::HoltWinters),
+            SelectionCriterion::Aic
+        )
+        .is_none());
+    }
+}
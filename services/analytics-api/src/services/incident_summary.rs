@@ -0,0 +1,424 @@
+//! # Incident Summaries
+//!
+//! When an alert fires, gathers what the organization's traffic looked like
+//! around it and asks a configurable model for a short incident narrative:
+//! what changed, which models and teams are affected, and probable causes.
+//! [`WebhookService::notify`](crate::services::webhooks::WebhookService::notify)
+//! attaches the narrative to `alert.triggered` events as `incident_summary`,
+//! so it is stored with each delivery and shown by Slack and PagerDuty.
+//!
+//! The context sent to the model is aggregated: per-model request, error,
+//! latency and cost changes against the preceding window, errors per team
+//! (`team` cost-allocation tag), recent error messages and the most frequent
+//! error logs. Prompts and completions are never included.
+//!
+//! ## Configuration
+//! - `INCIDENT_SUMMARY_PROVIDER` - `openai` or `anthropic` (unset disables summaries)
+//! - `INCIDENT_SUMMARY_MODEL` - model writing the narrative (default: `gpt-4o-mini`
+//!   or `claude-3-5-haiku-latest`)
+//! - `INCIDENT_SUMMARY_WINDOW_MINUTES` - window before the alert that is
+//!   compared with the window preceding it (default: 60)
+
+use crate::services::tenant::with_tenant;
+use chrono::{DateTime, Duration, Utc};
+use llm_observatory_providers::{CompletionProvider, CompletionRequest};
+use llm_observatory_webhooks::{EventType, WebhookEvent};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Default window before the alert
+pub const DEFAULT_WINDOW_MINUTES: i64 = 60;
+
+/// Completion tokens allowed for the narrative
+const MAX_SUMMARY_TOKENS: u32 = 400;
+
+/// Longest wait for the model; the alert is sent without a summary after it
+const SUMMARY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Rows of each kind included in the context
+const MAX_ROWS: i64 = 10;
+
+/// Errors from incident summaries
+#[derive(Debug, thiserror::Error)]
+pub enum IncidentSummaryError {
+    #[error("Incident summaries are not configured")]
+    Disabled,
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Summary model request failed: {0}")]
+    Provider(String),
+
+    #[error("Summary model did not answer within {} seconds", SUMMARY_TIMEOUT.as_secs())]
+    Timeout,
+}
+
+/// Requests, errors, latency and cost of one model in the alert window and
+/// the window before it
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ModelChange {
+    pub provider: String,
+    pub model: String,
+    pub requests: i64,
+    pub errors: i64,
+    pub avg_duration_ms: Option<f64>,
+    pub cost_usd: f64,
+    pub previous_requests: i64,
+    pub previous_errors: i64,
+    pub previous_avg_duration_ms: Option<f64>,
+    pub previous_cost_usd: f64,
+}
+
+/// Errors of one team in the alert window
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TeamImpact {
+    pub team: String,
+    pub requests: i64,
+    pub errors: i64,
+}
+
+/// A recent failed request
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ErrorSample {
+    pub ts: DateTime<Utc>,
+    pub trace_id: String,
+    pub provider: String,
+    pub model: String,
+    pub error_message: Option<String>,
+}
+
+/// An error log message and how often it was seen
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ErrorLog {
+    pub service_name: Option<String>,
+    pub body: String,
+    pub occurrences: i64,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// What the organization's traffic looked like around an alert
+#[derive(Debug, Clone, Serialize)]
+pub struct IncidentContext {
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub models: Vec<ModelChange>,
+    pub teams: Vec<TeamImpact>,
+    pub recent_errors: Vec<ErrorSample>,
+    pub error_logs: Vec<ErrorLog>,
+}
+
+/// Writes incident narratives for firing alerts
+pub struct IncidentSummaryService {
+    provider: Option<Arc<dyn CompletionProvider>>,
+    model: String,
+    pool: Option<PgPool>,
+    window: Duration,
+}
+
+impl IncidentSummaryService {
+    /// A service without a model; alerts are sent without summaries.
+    pub fn disabled() -> Self {
+        Self {
+            provider: None,
+            model: String::new(),
+            pool: None,
+            window: Duration::minutes(DEFAULT_WINDOW_MINUTES),
+        }
+    }
+
+    /// Summarize alerts with `model` of `provider`, reading traces and logs
+    /// from `pool`.
+    pub fn new(
+        provider: Arc<dyn CompletionProvider>,
+        model: impl Into<String>,
+        pool: PgPool,
+    ) -> Self {
+        Self {
+            provider: Some(provider),
+            model: model.into(),
+            pool: Some(pool),
+            window: Duration::minutes(DEFAULT_WINDOW_MINUTES),
+        }
+    }
+
+    /// Set the window before the alert that is gathered.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Whether alerts are summarized
+    pub fn is_enabled(&self) -> bool {
+        self.provider.is_some()
+    }
+
+    /// Model writing the narratives
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Attach a narrative to `event` if it is an `alert.triggered` event and
+    /// summaries are enabled.
+    ///
+    /// Failures are logged and the event is returned unchanged, so a
+    /// summary never holds back the alert.
+    pub async fn attach(&self, event: WebhookEvent) -> WebhookEvent {
+        if !self.is_enabled() || event.event_type != EventType::AlertTriggered {
+            return event;
+        }
+        match self.summarize(&event).await {
+            Ok(summary) => event.with_incident_summary(summary),
+            Err(e) => {
+                warn!(error = %e, org_id = %event.org_id, rule = ?event.rule, "Failed to generate incident summary");
+                event
+            }
+        }
+    }
+
+    /// Gather the context of an alert and generate its narrative.
+    pub async fn summarize(&self, event: &WebhookEvent) -> Result<String, IncidentSummaryError> {
+        let provider = self
+            .provider
+            .as_ref()
+            .ok_or(IncidentSummaryError::Disabled)?;
+        let context = self.gather(&event.org_id, event.created_at).await?;
+
+        let request = CompletionRequest::new(&self.model, build_prompt(event, &context))
+            .with_system(SYSTEM_PROMPT)
+            .with_max_tokens(MAX_SUMMARY_TOKENS);
+        let completion = tokio::time::timeout(SUMMARY_TIMEOUT, provider.complete(&request))
+            .await
+            .map_err(|_| IncidentSummaryError::Timeout)?
+            .map_err(|e| IncidentSummaryError::Provider(e.to_string()))?;
+        debug!(
+            provider = provider.name(),
+            model = %self.model,
+            prompt_tokens = completion.prompt_tokens,
+            completion_tokens = completion.completion_tokens,
+            "Generated incident summary"
+        );
+
+        Ok(completion.text.trim().to_string())
+    }
+
+    /// Traffic of the organization in the window ending at `at`, compared
+    /// with the window before it.
+    pub async fn gather(
+        &self,
+        org_id: &str,
+        at: DateTime<Utc>,
+    ) -> Result<IncidentContext, IncidentSummaryError> {
+        let pool = self.pool.as_ref().ok_or(IncidentSummaryError::Disabled)?;
+        let window_start = at - self.window;
+        let previous_start = window_start - self.window;
+
+        let models = sqlx::query_as::<_, ModelChange>(
+            r#"
+            SELECT
+                provider,
+                model,
+                COUNT(*) FILTER (WHERE ts >= $3) AS requests,
+                COUNT(*) FILTER (WHERE ts >= $3 AND status_code = 'ERROR') AS errors,
+                (AVG(duration_ms) FILTER (WHERE ts >= $3))::float8 AS avg_duration_ms,
+                COALESCE(SUM(total_cost_usd) FILTER (WHERE ts >= $3), 0)::float8 AS cost_usd,
+                COUNT(*) FILTER (WHERE ts < $3) AS previous_requests,
+                COUNT(*) FILTER (WHERE ts < $3 AND status_code = 'ERROR') AS previous_errors,
+                (AVG(duration_ms) FILTER (WHERE ts < $3))::float8 AS previous_avg_duration_ms,
+                COALESCE(SUM(total_cost_usd) FILTER (WHERE ts < $3), 0)::float8 AS previous_cost_usd
+            FROM llm_traces
+            WHERE attributes->>'org_id' = $1
+              AND ts >= $2
+              AND ts < $4
+            GROUP BY provider, model
+            ORDER BY errors DESC, requests DESC
+            LIMIT $5
+            "#,
+        )
+        .bind(org_id)
+        .bind(previous_start)
+        .bind(window_start)
+        .bind(at)
+        .bind(MAX_ROWS)
+        .fetch_all(pool)
+        .await?;
+
+        let teams = sqlx::query_as::<_, TeamImpact>(
+            r#"
+            SELECT
+                COALESCE(cost_tags->>'team', 'untagged') AS team,
+                COUNT(*) AS requests,
+                COUNT(*) FILTER (WHERE status_code = 'ERROR') AS errors
+            FROM llm_traces
+            WHERE attributes->>'org_id' = $1
+              AND ts >= $2
+              AND ts < $3
+            GROUP BY 1
+            HAVING COUNT(*) FILTER (WHERE status_code = 'ERROR') > 0
+            ORDER BY errors DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(org_id)
+        .bind(window_start)
+        .bind(at)
+        .bind(MAX_ROWS)
+        .fetch_all(pool)
+        .await?;
+
+        let recent_errors = sqlx::query_as::<_, ErrorSample>(
+            r#"
+            SELECT ts, trace_id, provider, model, LEFT(error_message, 300) AS error_message
+            FROM llm_traces
+            WHERE attributes->>'org_id' = $1
+              AND ts >= $2
+              AND ts < $3
+              AND status_code = 'ERROR'
+            ORDER BY ts DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(org_id)
+        .bind(window_start)
+        .bind(at)
+        .bind(MAX_ROWS)
+        .fetch_all(pool)
+        .await?;
+
+        // Log ingestion is optional; summarize from traces alone without it
+        let error_logs = async {
+            let mut tx = with_tenant(pool, org_id).await?;
+            let error_logs = sqlx::query_as::<_, ErrorLog>(
+                r#"
+                SELECT
+                    service_name,
+                    LEFT(body, 300) AS body,
+                    COUNT(*) AS occurrences,
+                    MAX(timestamp) AS last_seen
+                FROM logs
+                WHERE COALESCE(attributes->>'org_id', resource_attributes->>'org_id') = $1
+                  AND timestamp >= $2
+                  AND timestamp < $3
+                  AND severity_number >= 17
+                GROUP BY service_name, LEFT(body, 300)
+                ORDER BY occurrences DESC
+                LIMIT $4
+                "#,
+            )
+            .bind(org_id)
+            .bind(window_start)
+            .bind(at)
+            .bind(MAX_ROWS)
+            .fetch_all(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            Ok::<_, sqlx::Error>(error_logs)
+        }
+        .await
+        .unwrap_or_else(|e| {
+            debug!(error = %e, "Error logs unavailable for incident summary");
+            Vec::new()
+        });
+
+        Ok(IncidentContext {
+            window_start,
+            window_end: at,
+            models,
+            teams,
+            recent_errors,
+            error_logs,
+        })
+    }
+}
+
+/// Instructions for the narrative
+pub const SYSTEM_PROMPT: &str = r#"You write incident summaries for engineers on call for LLM-powered applications.
+You receive a firing alert and aggregated telemetry: per-model traffic in the alert window
+next to the window before it, errors per team, recent error messages and frequent error logs.
+
+Write at most 150 words of plain text in three short parts:
+What changed: the measurable change, with numbers.
+Affected: the providers, models and teams involved.
+Probable causes: up to three candidates, most likely first, each tied to the evidence.
+
+Only use the data given. If it does not explain the alert, say so instead of guessing."#;
+
+/// The alert and its context, as given to the model
+pub fn build_prompt(event: &WebhookEvent, context: &IncidentContext) -> String {
+    let alert = serde_json::json!({
+        "rule": event.rule,
+        "severity": event.severity,
+        "message": event.summary(),
+        "fired_at": event.created_at,
+        "data": event.data,
+    });
+    format!(
+        "Alert:\n{}\n\nTelemetry:\n{}",
+        serde_json::to_string_pretty(&alert).unwrap_or_default(),
+        serde_json::to_string_pretty(context).unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm_observatory_webhooks::Severity;
+
+    #[test]
+    fn test_build_prompt() {
+        let at = Utc::now();
+        let event = WebhookEvent::new(
+            EventType::AlertTriggered,
+            "org-1",
+            serde_json::json!({"message": "Error rate above 5%", "error_rate": 0.08}),
+        )
+        .with_severity(Severity::Critical)
+        .with_rule("error_rate");
+        let context = IncidentContext {
+            window_start: at - Duration::minutes(60),
+            window_end: at,
+            models: vec![ModelChange {
+                provider: "openai".to_string(),
+                model: "gpt-4o".to_string(),
+                requests: 1200,
+                errors: 96,
+                avg_duration_ms: Some(2400.0),
+                cost_usd: 18.5,
+                previous_requests: 1150,
+                previous_errors: 4,
+                previous_avg_duration_ms: Some(1900.0),
+                previous_cost_usd: 17.2,
+            }],
+            teams: vec![TeamImpact {
+                team: "search".to_string(),
+                requests: 800,
+                errors: 90,
+            }],
+            recent_errors: Vec::new(),
+            error_logs: Vec::new(),
+        };
+
+        let prompt = build_prompt(&event, &context);
+        assert!(prompt.starts_with("Alert:\n"));
+        assert!(prompt.contains("\"rule\": \"error_rate\""));
+        assert!(prompt.contains("\"severity\": \"critical\""));
+        assert!(prompt.contains("\"previous_errors\": 4"));
+        assert!(prompt.contains("\"team\": \"search\""));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_service_leaves_event_unchanged() {
+        let service = IncidentSummaryService::disabled();
+        assert!(!service.is_enabled());
+
+        let event = WebhookEvent::new(EventType::AlertTriggered, "org-1", serde_json::json!({}));
+        let event = service.attach(event).await;
+        assert_eq!(event.incident_summary(), None);
+        assert!(matches!(
+            service.summarize(&event).await,
+            Err(IncidentSummaryError::Disabled)
+        ));
+    }
+}
@@ -1 +1,23 @@
+pub mod admin;
+pub mod audit_log;
+pub mod cold_storage;
+pub mod currency;
+pub mod data_access;
+pub mod drift;
+pub mod federation;
+pub mod forecasting;
+pub mod incident_summary;
+pub mod model_registry;
+pub mod natural_query;
+pub mod provider_health;
+pub mod pseudonyms;
+pub mod quarantine;
+pub mod query_cache;
+pub mod rate_limits;
+pub mod service_accounts;
+pub mod tenant;
 pub mod timescaledb;
+pub mod top_n;
+pub mod topology;
+pub mod trace_deletion;
+pub mod webhooks;
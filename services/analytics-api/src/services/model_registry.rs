@@ -0,0 +1,221 @@
+//! # Model Registry
+//!
+//! Custom and fine-tuned models registered by organizations, written with the
+//! read-write connection. The ingest host loads them into the pricing
+//! engine's registry (`llm_observatory_providers::MODEL_REGISTRY`), which
+//! prices spans of registered models before the bundled pricing.
+
+use crate::models::model_registry::*;
+use sqlx::PgPool;
+use tracing::info;
+use uuid::Uuid;
+
+/// PostgreSQL unique violation
+const UNIQUE_VIOLATION: &str = "23505";
+
+/// Errors from model registry operations
+#[derive(Debug, thiserror::Error)]
+pub enum ModelRegistryError {
+    #[error("The model registry requires DATABASE_URL (read-write connection)")]
+    Disabled,
+
+    #[error("{0} not found")]
+    NotFound(String),
+
+    #[error("{0} is already registered")]
+    Conflict(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Columns of [`RegisteredModel`]
+const MODEL_COLUMNS: &str = "id, model_id, provider, display_name, aliases, base_model, \
+     prompt_cost_per_1k, completion_cost_per_1k, cached_prompt_cost_per_1k, \
+     created_at, updated_at, updated_by";
+
+/// Registered models, written with the read-write connection
+pub struct ModelRegistryService {
+    /// Read-write pool (None rejects every operation)
+    pool: Option<PgPool>,
+}
+
+impl ModelRegistryService {
+    pub fn new(pool: Option<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    /// A service that rejects every operation
+    pub fn disabled() -> Self {
+        Self::new(None)
+    }
+
+    fn pool(&self) -> Result<&PgPool, ModelRegistryError> {
+        self.pool.as_ref().ok_or(ModelRegistryError::Disabled)
+    }
+
+    /// The organization's registered models, by model ID
+    pub async fn list(&self, org_id: &str) -> Result<Vec<RegisteredModel>, ModelRegistryError> {
+        Ok(sqlx::query_as::<_, RegisteredModel>(&format!(
+            "SELECT {} FROM model_registry WHERE org_id = $1 ORDER BY model_id",
+            MODEL_COLUMNS
+        ))
+        .bind(org_id)
+        .fetch_all(self.pool()?)
+        .await?)
+    }
+
+    pub async fn get(&self, org_id: &str, id: Uuid) -> Result<RegisteredModel, ModelRegistryError> {
+        sqlx::query_as::<_, RegisteredModel>(&format!(
+            "SELECT {} FROM model_registry WHERE org_id = $1 AND id = $2",
+            MODEL_COLUMNS
+        ))
+        .bind(org_id)
+        .bind(id)
+        .fetch_optional(self.pool()?)
+        .await?
+        .ok_or_else(|| ModelRegistryError::NotFound(format!("Model {}", id)))
+    }
+
+    pub async fn create(
+        &self,
+        org_id: &str,
+        request: &RegisterModelRequest,
+        updated_by: &str,
+    ) -> Result<RegisteredModel, ModelRegistryError> {
+        self.check_names(org_id, None, request).await?;
+
+        let model = sqlx::query_as::<_, RegisteredModel>(&format!(
+            r#"
+            INSERT INTO model_registry (
+                org_id, model_id, provider, display_name, aliases, base_model,
+                prompt_cost_per_1k, completion_cost_per_1k, cached_prompt_cost_per_1k,
+                updated_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING {}
+            "#,
+            MODEL_COLUMNS
+        ))
+        .bind(org_id)
+        .bind(&request.model_id)
+        .bind(&request.provider)
+        .bind(&request.display_name)
+        .bind(&request.aliases)
+        .bind(&request.base_model)
+        .bind(request.prompt_cost_per_1k)
+        .bind(request.completion_cost_per_1k)
+        .bind(request.cached_prompt_cost_per_1k)
+        .bind(updated_by)
+        .fetch_one(self.pool()?)
+        .await
+        .map_err(conflict(&request.model_id))?;
+
+        info!(org_id = %org_id, model_id = %model.model_id, "Registered model");
+        Ok(model)
+    }
+
+    /// Replace a registration
+    pub async fn update(
+        &self,
+        org_id: &str,
+        id: Uuid,
+        request: &RegisterModelRequest,
+        updated_by: &str,
+    ) -> Result<RegisteredModel, ModelRegistryError> {
+        self.check_names(org_id, Some(id), request).await?;
+
+        let model = sqlx::query_as::<_, RegisteredModel>(&format!(
+            r#"
+            UPDATE model_registry SET
+                model_id = $3,
+                provider = $4,
+                display_name = $5,
+                aliases = $6,
+                base_model = $7,
+                prompt_cost_per_1k = $8,
+                completion_cost_per_1k = $9,
+                cached_prompt_cost_per_1k = $10,
+                updated_by = $11,
+                updated_at = NOW()
+            WHERE org_id = $1 AND id = $2
+            RETURNING {}
+            "#,
+            MODEL_COLUMNS
+        ))
+        .bind(org_id)
+        .bind(id)
+        .bind(&request.model_id)
+        .bind(&request.provider)
+        .bind(&request.display_name)
+        .bind(&request.aliases)
+        .bind(&request.base_model)
+        .bind(request.prompt_cost_per_1k)
+        .bind(request.completion_cost_per_1k)
+        .bind(request.cached_prompt_cost_per_1k)
+        .bind(updated_by)
+        .fetch_optional(self.pool()?)
+        .await
+        .map_err(conflict(&request.model_id))?
+        .ok_or_else(|| ModelRegistryError::NotFound(format!("Model {}", id)))?;
+
+        info!(org_id = %org_id, model_id = %model.model_id, "Updated registered model");
+        Ok(model)
+    }
+
+    pub async fn delete(&self, org_id: &str, id: Uuid) -> Result<(), ModelRegistryError> {
+        let result = sqlx::query("DELETE FROM model_registry WHERE org_id = $1 AND id = $2")
+            .bind(org_id)
+            .bind(id)
+            .execute(self.pool()?)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(ModelRegistryError::NotFound(format!("Model {}", id)));
+        }
+
+        info!(org_id = %org_id, id = %id, "Deleted registered model");
+        Ok(())
+    }
+
+    /// Reject a model ID or alias another of the organization's models is
+    /// already registered under, so every name resolves to one model
+    async fn check_names(
+        &self,
+        org_id: &str,
+        id: Option<Uuid>,
+        request: &RegisterModelRequest,
+    ) -> Result<(), ModelRegistryError> {
+        let names = request.names();
+        let taken: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT name
+            FROM model_registry, UNNEST(ARRAY[model_id] || aliases) AS name
+            WHERE org_id = $1
+              AND ($2::UUID IS NULL OR id <> $2)
+              AND name = ANY($3)
+            LIMIT 1
+            "#,
+        )
+        .bind(org_id)
+        .bind(id)
+        .bind(&names)
+        .fetch_optional(self.pool()?)
+        .await?;
+
+        match taken {
+            Some(name) => Err(ModelRegistryError::Conflict(format!("Model {}", name))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Map a unique violation to [`ModelRegistryError::Conflict`] on `model_id`.
+fn conflict(model_id: &str) -> impl FnOnce(sqlx::Error) -> ModelRegistryError {
+    let what = format!("Model {}", model_id);
+    move |e| match &e {
+        sqlx::Error::Database(db) if db.code().as_deref() == Some(UNIQUE_VIOLATION) => {
+            ModelRegistryError::Conflict(what)
+        }
+        _ => ModelRegistryError::Database(e),
+    }
+}
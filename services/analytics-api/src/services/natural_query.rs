@@ -0,0 +1,215 @@
+//! # Natural-Language Queries
+//!
+//! Translates questions such as "which model had the worst p95 latency
+//! yesterday?" into a metrics query with a configurable model. The model
+//! never writes SQL: it answers with the JSON of a [`MetricsQueryRequest`],
+//! whose metrics, dimensions, intervals and aggregations are closed enums.
+//! Anything else fails to parse, and the parsed query goes through the same
+//! validation and organization scoping as `GET /api/v1/metrics`.
+//!
+//! ## Configuration
+//! - `NL_QUERY_PROVIDER` - `openai` or `anthropic` (unset disables the endpoint)
+//! - `NL_QUERY_MODEL` - model used for translation (default: `gpt-4o-mini`
+//!   or `claude-3-5-haiku-latest`)
+//!
+//! Provider credentials are read from the provider's usual variables
+//! (`OPENAI_API_KEY`, `ANTHROPIC_API_KEY`, ...).
+
+use crate::models::metrics::MetricsQueryRequest;
+use chrono::{DateTime, Utc};
+use llm_observatory_providers::{CompletionProvider, CompletionRequest};
+use std::sync::Arc;
+use tracing::debug;
+
+/// Longest accepted question, in characters
+pub const MAX_QUESTION_CHARS: usize = 500;
+
+/// Completion tokens allowed for the generated query
+const MAX_QUERY_TOKENS: u32 = 512;
+
+/// Errors from natural-language queries
+#[derive(Debug, thiserror::Error)]
+pub enum NaturalQueryError {
+    #[error("Natural-language queries are not configured")]
+    Disabled,
+
+    #[error("Question must be between 1 and {} characters", MAX_QUESTION_CHARS)]
+    InvalidQuestion,
+
+    #[error("Query model request failed: {0}")]
+    Provider(String),
+
+    #[error("The question could not be translated into a metrics query: {0}")]
+    Untranslatable(String),
+}
+
+/// Translates questions into metrics queries
+pub struct NaturalQueryService {
+    provider: Option<Arc<dyn CompletionProvider>>,
+    model: String,
+}
+
+impl NaturalQueryService {
+    /// A service without a model; every question is rejected.
+    pub fn disabled() -> Self {
+        Self {
+            provider: None,
+            model: String::new(),
+        }
+    }
+
+    /// Translate questions with `model` of `provider`.
+    pub fn new(provider: Arc<dyn CompletionProvider>, model: impl Into<String>) -> Self {
+        Self {
+            provider: Some(provider),
+            model: model.into(),
+        }
+    }
+
+    /// Whether questions are translated
+    pub fn is_enabled(&self) -> bool {
+        self.provider.is_some()
+    }
+
+    /// Model used for translation
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Translate `question` into a validated metrics query. Relative times
+    /// ("last week") are resolved against `now`.
+    pub async fn translate(
+        &self,
+        question: &str,
+        now: DateTime<Utc>,
+    ) -> Result<MetricsQueryRequest, NaturalQueryError> {
+        let provider = self.provider.as_ref().ok_or(NaturalQueryError::Disabled)?;
+        let question = question.trim();
+        if question.is_empty() || question.chars().count() > MAX_QUESTION_CHARS {
+            return Err(NaturalQueryError::InvalidQuestion);
+        }
+
+        let request = CompletionRequest::new(&self.model, question)
+            .with_system(system_prompt(now))
+            .with_max_tokens(MAX_QUERY_TOKENS);
+        let completion = provider
+            .complete(&request)
+            .await
+            .map_err(|e| NaturalQueryError::Provider(e.to_string()))?;
+        debug!(
+            provider = provider.name(),
+            model = %self.model,
+            prompt_tokens = completion.prompt_tokens,
+            completion_tokens = completion.completion_tokens,
+            "Translated natural-language question"
+        );
+
+        parse_query(&completion.text)
+    }
+}
+
+/// Instructions describing the query DSL to the model
+pub fn system_prompt(now: DateTime<Utc>) -> String {
+    format!(
+        r#"You translate questions about LLM API traffic into JSON metrics queries.
+Answer with one JSON object and nothing else. The current time is {now}.
+
+Fields:
+- "metrics" (required): 1 to 20 of request_count, duration, total_cost, prompt_cost,
+  completion_cost, total_tokens, prompt_tokens, completion_tokens, error_count,
+  success_count, error_rate, success_rate, throughput, time_to_first_token,
+  unique_users, unique_sessions
+- "interval" (required): one_minute, five_minutes, one_hour or one_day
+- "start_time", "end_time": RFC 3339 UTC times, at most 90 days apart
+- "provider", "model", "environment", "user_id": exact values to filter by
+- "group_by": up to 5 of provider, model, environment, status_code, user_id, session_id
+- "aggregation": avg, sum, min, max, count, p50, p90, p95 or p99
+- "include_percentiles": true to add p50/p90/p95/p99 columns
+
+Durations are in milliseconds and costs in USD. If the question cannot be answered
+with these fields, answer {{"error": "<short reason>"}}."#,
+        now = now.to_rfc3339()
+    )
+}
+
+/// Parse and validate the model's answer.
+///
+/// Markdown code fences and text around the JSON object are ignored.
+pub fn parse_query(text: &str) -> Result<MetricsQueryRequest, NaturalQueryError> {
+    let json = match (text.find('{'), text.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &text[start..=end],
+        _ => {
+            return Err(NaturalQueryError::Untranslatable(
+                "the model did not return a query".to_string(),
+            ))
+        }
+    };
+
+    let value: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| NaturalQueryError::Untranslatable(e.to_string()))?;
+    if let Some(reason) = value.get("error").and_then(|e| e.as_str()) {
+        return Err(NaturalQueryError::Untranslatable(reason.to_string()));
+    }
+
+    let query: MetricsQueryRequest = serde_json::from_value(value)
+        .map_err(|e| NaturalQueryError::Untranslatable(e.to_string()))?;
+    query.validate().map_err(NaturalQueryError::Untranslatable)?;
+    Ok(query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::metrics::{AggregationFunction, DimensionName, MetricType, TimeInterval};
+
+    #[test]
+    fn test_parse_query() {
+        let text = r#"```json
+{
+  "metrics": ["duration"],
+  "interval": "one_day",
+  "start_time": "2025-01-01T00:00:00Z",
+  "end_time": "2025-01-08T00:00:00Z",
+  "group_by": ["model"],
+  "aggregation": "p95"
+}
+```"#;
+        let query = parse_query(text).unwrap();
+        assert_eq!(query.metrics, vec![MetricType::Duration]);
+        assert_eq!(query.interval, TimeInterval::OneDay);
+        assert_eq!(query.group_by, vec![DimensionName::Model]);
+        assert_eq!(query.aggregation, Some(AggregationFunction::P95));
+        assert!(query.provider.is_none());
+    }
+
+    #[test]
+    fn test_parse_query_rejects_unsafe_or_invalid_queries() {
+        // Not a whitelisted metric
+        let text = r#"{"metrics": ["input_text"], "interval": "one_hour"}"#;
+        assert!(matches!(parse_query(text), Err(NaturalQueryError::Untranslatable(_))));
+
+        // Fails validation: range over 90 days
+        let text = r#"{"metrics": ["total_cost"], "interval": "one_day",
+            "start_time": "2024-01-01T00:00:00Z", "end_time": "2025-01-01T00:00:00Z"}"#;
+        assert!(matches!(parse_query(text), Err(NaturalQueryError::Untranslatable(_))));
+
+        // The model declined
+        let text = r#"{"error": "prompts are not queryable"}"#;
+        match parse_query(text) {
+            Err(NaturalQueryError::Untranslatable(reason)) => assert_eq!(reason, "prompts are not queryable"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        assert!(parse_query("SELECT * FROM llm_traces").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_service() {
+        let service = NaturalQueryService::disabled();
+        assert!(!service.is_enabled());
+        assert!(matches!(
+            service.translate("What did we spend yesterday?", Utc::now()).await,
+            Err(NaturalQueryError::Disabled)
+        ));
+    }
+}
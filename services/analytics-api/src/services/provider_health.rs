@@ -0,0 +1,413 @@
+//! # Provider Health Monitor
+//!
+//! Periodically probes upstream LLM providers and records availability
+//! incidents so error spikes in our traces can be correlated with upstream
+//! outages.
+//!
+//! ## Probes
+//! - `Statuspage` - Atlassian Statuspage `status.json` (OpenAI, Anthropic)
+//! - `Http` - lightweight reachability call against an API endpoint (Azure
+//!   OpenAI); any non-5xx response counts as operational
+//!
+//! The latest result per provider is kept in memory. Status transitions open,
+//! escalate and resolve rows in the `provider_incidents` table.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+/// Probe responses slower than this are reported as degraded
+const SLOW_PROBE_THRESHOLD: Duration = Duration::from_secs(5);
+
+/// Probe request timeout
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Provider availability status
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderStatus {
+    Operational,
+    Degraded,
+    PartialOutage,
+    MajorOutage,
+    /// The probe itself failed (network error or timeout)
+    Unreachable,
+}
+
+impl ProviderStatus {
+    /// Get the status name as stored in `provider_incidents.status`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderStatus::Operational => "operational",
+            ProviderStatus::Degraded => "degraded",
+            ProviderStatus::PartialOutage => "partial_outage",
+            ProviderStatus::MajorOutage => "major_outage",
+            ProviderStatus::Unreachable => "unreachable",
+        }
+    }
+
+    /// Severity rank used to decide whether an incident escalated
+    pub fn severity(&self) -> u8 {
+        match self {
+            ProviderStatus::Operational => 0,
+            ProviderStatus::Degraded => 1,
+            ProviderStatus::Unreachable => 2,
+            ProviderStatus::PartialOutage => 3,
+            ProviderStatus::MajorOutage => 4,
+        }
+    }
+
+    pub fn is_operational(&self) -> bool {
+        *self == ProviderStatus::Operational
+    }
+
+    /// Map a Statuspage `status.indicator` value
+    pub fn from_statuspage_indicator(indicator: &str) -> Self {
+        match indicator {
+            "none" => ProviderStatus::Operational,
+            "minor" | "maintenance" => ProviderStatus::Degraded,
+            "major" => ProviderStatus::PartialOutage,
+            "critical" => ProviderStatus::MajorOutage,
+            _ => ProviderStatus::Degraded,
+        }
+    }
+}
+
+/// How a provider is probed
+#[derive(Debug, Clone)]
+pub enum ProbeKind {
+    /// Atlassian Statuspage `api/v2/status.json` URL
+    Statuspage { url: String },
+    /// Any HTTP endpoint; non-5xx responses count as operational
+    Http { url: String },
+}
+
+impl ProbeKind {
+    pub fn source(&self) -> &'static str {
+        match self {
+            ProbeKind::Statuspage { .. } => "statuspage",
+            ProbeKind::Http { .. } => "http",
+        }
+    }
+}
+
+/// A probe for one provider
+#[derive(Debug, Clone)]
+pub struct ProviderProbe {
+    /// Provider name as used in `llm_traces.provider`
+    pub provider: String,
+    pub kind: ProbeKind,
+}
+
+/// Latest probe result for a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderHealthSnapshot {
+    pub provider: String,
+    pub status: ProviderStatus,
+    pub description: Option<String>,
+    pub source: String,
+    pub checked_at: DateTime<Utc>,
+    pub latency_ms: u64,
+}
+
+/// Incident table change implied by a status transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncidentChange {
+    Open,
+    Escalate,
+    Resolve,
+    None,
+}
+
+/// Decide how a status transition affects the provider's open incident.
+///
+/// An unknown previous status (first probe after startup) that is healthy
+/// resolves any incident left open by a previous process.
+pub fn incident_change(previous: Option<ProviderStatus>, current: ProviderStatus) -> IncidentChange {
+    match (previous, current.is_operational()) {
+        (None, true) => IncidentChange::Resolve,
+        (Some(prev), true) if !prev.is_operational() => IncidentChange::Resolve,
+        (Some(prev), false) if !prev.is_operational() => {
+            if current.severity() > prev.severity() {
+                IncidentChange::Escalate
+            } else {
+                IncidentChange::None
+            }
+        }
+        (_, false) => IncidentChange::Open,
+        _ => IncidentChange::None,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StatuspageBody {
+    status: StatuspageStatus,
+}
+
+#[derive(Debug, Deserialize)]
+struct StatuspageStatus {
+    indicator: String,
+    description: Option<String>,
+}
+
+/// Default probes for OpenAI and Anthropic status pages, plus Azure OpenAI
+/// when an endpoint is configured.
+pub fn default_probes(azure_endpoint: Option<&str>) -> Vec<ProviderProbe> {
+    let mut probes = vec![
+        ProviderProbe {
+            provider: "openai".to_string(),
+            kind: ProbeKind::Statuspage {
+                url: "https://status.openai.com/api/v2/status.json".to_string(),
+            },
+        },
+        ProviderProbe {
+            provider: "anthropic".to_string(),
+            kind: ProbeKind::Statuspage {
+                url: "https://status.anthropic.com/api/v2/status.json".to_string(),
+            },
+        },
+    ];
+
+    if let Some(endpoint) = azure_endpoint {
+        probes.push(ProviderProbe {
+            provider: "azure".to_string(),
+            kind: ProbeKind::Http {
+                url: format!("{}/openai/models?api-version=2024-02-01", endpoint.trim_end_matches('/')),
+            },
+        });
+    }
+
+    probes
+}
+
+/// Provider health monitor
+pub struct ProviderHealthMonitor {
+    probes: Vec<ProviderProbe>,
+    client: reqwest::Client,
+    statuses: RwLock<HashMap<String, ProviderHealthSnapshot>>,
+    /// Pool for recording incidents (None disables persistence)
+    pool: Option<PgPool>,
+}
+
+impl ProviderHealthMonitor {
+    /// Create a monitor for the given probes.
+    pub fn new(probes: Vec<ProviderProbe>, pool: Option<PgPool>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(PROBE_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            probes,
+            client,
+            statuses: RwLock::new(HashMap::new()),
+            pool,
+        }
+    }
+
+    /// A monitor with no probes.
+    pub fn disabled() -> Self {
+        Self::new(Vec::new(), None)
+    }
+
+    /// Latest status of every probed provider, sorted by provider name.
+    pub fn statuses(&self) -> Vec<ProviderHealthSnapshot> {
+        let mut statuses: Vec<_> = self
+            .statuses
+            .read()
+            .map(|s| s.values().cloned().collect())
+            .unwrap_or_default();
+        statuses.sort_by(|a, b| a.provider.cmp(&b.provider));
+        statuses
+    }
+
+    /// Probe every provider once and record status transitions.
+    pub async fn probe_all(&self) {
+        let results = futures::future::join_all(self.probes.iter().map(|p| self.probe(p))).await;
+
+        for snapshot in results {
+            let previous = self
+                .statuses
+                .read()
+                .ok()
+                .and_then(|s| s.get(&snapshot.provider).map(|p| p.status));
+
+            let change = incident_change(previous, snapshot.status);
+            if change != IncidentChange::None {
+                self.record_incident(change, &snapshot).await;
+            }
+
+            metrics::gauge!("provider_health_status", "provider" => snapshot.provider.clone())
+                .set(snapshot.status.severity() as f64);
+
+            if let Ok(mut statuses) = self.statuses.write() {
+                statuses.insert(snapshot.provider.clone(), snapshot);
+            }
+        }
+    }
+
+    /// Probe all providers at a fixed interval in the background.
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.probe_all().await;
+            }
+        })
+    }
+
+    async fn probe(&self, probe: &ProviderProbe) -> ProviderHealthSnapshot {
+        let started = Instant::now();
+
+        let (status, description) = match &probe.kind {
+            ProbeKind::Statuspage { url } => self.probe_statuspage(url).await,
+            ProbeKind::Http { url } => self.probe_http(url).await,
+        };
+
+        let elapsed = started.elapsed();
+        let status = if status.is_operational() && elapsed > SLOW_PROBE_THRESHOLD {
+            ProviderStatus::Degraded
+        } else {
+            status
+        };
+
+        debug!(provider = %probe.provider, status = status.as_str(), "Provider probed");
+
+        ProviderHealthSnapshot {
+            provider: probe.provider.clone(),
+            status,
+            description,
+            source: probe.kind.source().to_string(),
+            checked_at: Utc::now(),
+            latency_ms: elapsed.as_millis() as u64,
+        }
+    }
+
+    async fn probe_statuspage(&self, url: &str) -> (ProviderStatus, Option<String>) {
+        let response = match self.client.get(url).send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response,
+            Err(e) => return (ProviderStatus::Unreachable, Some(e.to_string())),
+        };
+
+        match response.json::<StatuspageBody>().await {
+            Ok(body) => (
+                ProviderStatus::from_statuspage_indicator(&body.status.indicator),
+                body.status.description,
+            ),
+            Err(e) => (
+                ProviderStatus::Unreachable,
+                Some(format!("Invalid status page response: {}", e)),
+            ),
+        }
+    }
+
+    async fn probe_http(&self, url: &str) -> (ProviderStatus, Option<String>) {
+        match self.client.get(url).send().await {
+            Ok(response) if response.status().is_server_error() => (
+                ProviderStatus::PartialOutage,
+                Some(format!("HTTP {}", response.status())),
+            ),
+            Ok(_) => (ProviderStatus::Operational, None),
+            Err(e) => (ProviderStatus::Unreachable, Some(e.to_string())),
+        }
+    }
+
+    async fn record_incident(&self, change: IncidentChange, snapshot: &ProviderHealthSnapshot) {
+        let Some(pool) = &self.pool else {
+            return;
+        };
+
+        let result = match change {
+            IncidentChange::Open => {
+                info!(provider = %snapshot.provider, status = snapshot.status.as_str(), "Provider incident opened");
+                sqlx::query(
+                    r#"
+                    INSERT INTO provider_incidents (provider, status, description, source, started_at)
+                    VALUES ($1, $2, $3, $4, $5)
+                    ON CONFLICT (provider) WHERE resolved_at IS NULL DO NOTHING
+                    "#,
+                )
+                .bind(&snapshot.provider)
+                .bind(snapshot.status.as_str())
+                .bind(&snapshot.description)
+                .bind(&snapshot.source)
+                .bind(snapshot.checked_at)
+                .execute(pool)
+                .await
+            }
+            IncidentChange::Escalate => {
+                warn!(provider = %snapshot.provider, status = snapshot.status.as_str(), "Provider incident escalated");
+                sqlx::query(
+                    r#"
+                    UPDATE provider_incidents
+                    SET status = $2, description = $3
+                    WHERE provider = $1 AND resolved_at IS NULL
+                    "#,
+                )
+                .bind(&snapshot.provider)
+                .bind(snapshot.status.as_str())
+                .bind(&snapshot.description)
+                .execute(pool)
+                .await
+            }
+            IncidentChange::Resolve => {
+                sqlx::query(
+                    r#"
+                    UPDATE provider_incidents
+                    SET resolved_at = $2
+                    WHERE provider = $1 AND resolved_at IS NULL
+                    "#,
+                )
+                .bind(&snapshot.provider)
+                .bind(snapshot.checked_at)
+                .execute(pool)
+                .await
+            }
+            IncidentChange::None => return,
+        };
+
+        if let Err(e) = result {
+            error!(error = %e, provider = %snapshot.provider, "Failed to record provider incident");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statuspage_indicator_mapping() {
+        assert_eq!(ProviderStatus::from_statuspage_indicator("none"), ProviderStatus::Operational);
+        assert_eq!(ProviderStatus::from_statuspage_indicator("minor"), ProviderStatus::Degraded);
+        assert_eq!(ProviderStatus::from_statuspage_indicator("major"), ProviderStatus::PartialOutage);
+        assert_eq!(ProviderStatus::from_statuspage_indicator("critical"), ProviderStatus::MajorOutage);
+    }
+
+    #[test]
+    fn test_incident_change() {
+        use ProviderStatus::*;
+
+        assert_eq!(incident_change(None, Operational), IncidentChange::Resolve);
+        assert_eq!(incident_change(None, MajorOutage), IncidentChange::Open);
+        assert_eq!(incident_change(Some(Operational), Degraded), IncidentChange::Open);
+        assert_eq!(incident_change(Some(Degraded), MajorOutage), IncidentChange::Escalate);
+        assert_eq!(incident_change(Some(MajorOutage), Degraded), IncidentChange::None);
+        assert_eq!(incident_change(Some(Degraded), Operational), IncidentChange::Resolve);
+        assert_eq!(incident_change(Some(Operational), Operational), IncidentChange::None);
+    }
+
+    #[test]
+    fn test_default_probes() {
+        assert_eq!(default_probes(None).len(), 2);
+
+        let probes = default_probes(Some("https://example.openai.azure.com/"));
+        assert_eq!(probes.len(), 3);
+        assert_eq!(probes[2].provider, "azure");
+    }
+}
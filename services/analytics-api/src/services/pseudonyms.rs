@@ -0,0 +1,172 @@
+//! # Pseudonym Lookup
+//!
+//! Reverse lookup of the user and session pseudonyms written by the
+//! collector's pseudonymization processor (`ps_<hex>`, a keyed hash of the
+//! original identifier). The collector records each pseudonym with its
+//! original value in a dedicated Redis, the pseudonym vault, which is the
+//! only place raw identifiers are kept; this service reads it for support
+//! workflows that must reach the actual user. The collector records each
+//! pseudonym under the organization of its span, and lookups only reach the
+//! caller's organization. Callers need the `resolve:pseudonyms` permission,
+//! granted explicitly (an admin's wildcard is not enough), and every lookup
+//! is audited.
+
+use crate::middleware::AuthContext;
+use redis::AsyncCommands;
+
+/// Permission to resolve pseudonyms
+pub const RESOLVE_PERMISSION: &str = "resolve:pseudonyms";
+
+/// Prefix of every pseudonym
+pub const PSEUDONYM_PREFIX: &str = "ps_";
+
+/// Hex digits after the prefix
+const PSEUDONYM_HEX_LEN: usize = 32;
+
+/// Default prefix of the vault keys, as written by the collector
+pub const DEFAULT_KEY_PREFIX: &str = "llmobs:pseudonym:";
+
+/// Errors from pseudonym lookups
+#[derive(Debug, thiserror::Error)]
+pub enum PseudonymError {
+    #[error("Pseudonym lookup requires PSEUDONYM_VAULT_REDIS_URL")]
+    Disabled,
+
+    #[error("Not a pseudonym: {0}")]
+    InvalidPseudonym(String),
+
+    #[error("Pseudonym vault error: {0}")]
+    Vault(#[from] redis::RedisError),
+}
+
+/// Whether the caller may resolve pseudonyms.
+///
+/// Only an explicit grant counts: unlike other permissions,
+/// `resolve:pseudonyms` is not implied by the admin role.
+pub fn can_resolve(auth: &AuthContext) -> bool {
+    auth.permissions.iter().any(|p| p == RESOLVE_PERMISSION)
+}
+
+/// Vault key of an organization's pseudonym, as written by the collector
+pub fn vault_key(prefix: &str, org_id: &str, pseudonym: &str) -> String {
+    format!("{}{}:{}", prefix, org_id, pseudonym)
+}
+
+/// Whether a value has the form of a pseudonym
+pub fn is_pseudonym(value: &str) -> bool {
+    value.strip_prefix(PSEUDONYM_PREFIX).is_some_and(|hex| {
+        hex.len() == PSEUDONYM_HEX_LEN && hex.bytes().all(|b| b.is_ascii_hexdigit())
+    })
+}
+
+/// Reader of the pseudonym vault
+pub struct PseudonymLookupService {
+    /// Vault client (None rejects lookups)
+    client: Option<redis::Client>,
+    key_prefix: String,
+}
+
+impl PseudonymLookupService {
+    pub fn new(client: Option<redis::Client>, key_prefix: impl Into<String>) -> Self {
+        Self {
+            client,
+            key_prefix: key_prefix.into(),
+        }
+    }
+
+    /// A service that rejects every lookup
+    pub fn disabled() -> Self {
+        Self::new(None, DEFAULT_KEY_PREFIX)
+    }
+
+    /// Original identifier of an organization's pseudonym, if the vault
+    /// still holds it.
+    pub async fn resolve(
+        &self,
+        org_id: &str,
+        pseudonym: &str,
+    ) -> Result<Option<String>, PseudonymError> {
+        let client = self.client.as_ref().ok_or(PseudonymError::Disabled)?;
+        if !is_pseudonym(pseudonym) {
+            return Err(PseudonymError::InvalidPseudonym(pseudonym.to_string()));
+        }
+
+        let mut connection = client.get_multiplexed_async_connection().await?;
+        let value: Option<String> = connection
+            .get(vault_key(&self.key_prefix, org_id, pseudonym))
+            .await?;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::{auth::AuthMethod, Role};
+
+    fn auth(org_id: &str, role: Role, permissions: Vec<String>) -> AuthContext {
+        AuthContext {
+            user_id: "user-1".to_string(),
+            org_id: org_id.to_string(),
+            projects: vec![],
+            role,
+            permissions,
+            auth_method: AuthMethod::Jwt,
+            request_id: "req-1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_requires_explicit_permission() {
+        let admin = auth("org-1", Role::Admin, Role::Admin.default_permissions());
+        assert!(admin.has_permission(RESOLVE_PERMISSION));
+        assert!(!can_resolve(&admin));
+
+        let granted = auth(
+            "org-1",
+            Role::Viewer,
+            vec!["read:traces".to_string(), RESOLVE_PERMISSION.to_string()],
+        );
+        assert!(can_resolve(&granted));
+    }
+
+    #[test]
+    fn test_vault_key_is_scoped_by_organization() {
+        let pseudonym = "ps_0123456789abcdef0123456789abcdef";
+        assert_eq!(
+            vault_key(DEFAULT_KEY_PREFIX, "org-a", pseudonym),
+            "llmobs:pseudonym:org-a:ps_0123456789abcdef0123456789abcdef"
+        );
+        assert_ne!(
+            vault_key(DEFAULT_KEY_PREFIX, "org-a", pseudonym),
+            vault_key(DEFAULT_KEY_PREFIX, "org-b", pseudonym)
+        );
+    }
+
+    #[test]
+    fn test_is_pseudonym() {
+        assert!(is_pseudonym("ps_0123456789abcdef0123456789abcdef"));
+        assert!(!is_pseudonym("ps_0123"));
+        assert!(!is_pseudonym("alice@example.com"));
+        assert!(!is_pseudonym("ps_0123456789abcdef0123456789abcdeg"));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_and_invalid() {
+        let disabled = PseudonymLookupService::disabled();
+        assert!(matches!(
+            disabled
+                .resolve("org-1", "ps_0123456789abcdef0123456789abcdef")
+                .await,
+            Err(PseudonymError::Disabled)
+        ));
+
+        // Malformed input never reaches the vault
+        let client = redis::Client::open("redis://127.0.0.1:1/").unwrap();
+        let service = PseudonymLookupService::new(Some(client), DEFAULT_KEY_PREFIX);
+        assert!(matches!(
+            service.resolve("org-1", "llmobs:*").await,
+            Err(PseudonymError::InvalidPseudonym(_))
+        ));
+    }
+}
@@ -0,0 +1,82 @@
+//! # Quarantine Replay
+//!
+//! Marks quarantined spans (`rejected_spans`) for replay after the rules
+//! they failed were fixed. Marked spans move to `pending`; the collector
+//! claims them, runs them through its processors again and marks them
+//! `replayed` or `failed`. Spans already pending, replaying or replayed are
+//! never marked again, so a replay can't export a span twice.
+
+use crate::models::quarantine::ReplayQuarantineRequest;
+use sqlx::PgPool;
+use tracing::info;
+
+/// Errors from replay requests
+#[derive(Debug, thiserror::Error)]
+pub enum QuarantineError {
+    #[error("Quarantine replay requires DATABASE_URL (read-write connection)")]
+    Disabled,
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Replay requests, written with the read-write connection
+pub struct QuarantineService {
+    /// Read-write pool (None rejects replay requests)
+    pool: Option<PgPool>,
+}
+
+impl QuarantineService {
+    pub fn new(pool: Option<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    /// A service that rejects every replay request
+    pub fn disabled() -> Self {
+        Self::new(None)
+    }
+
+    /// Mark the organization's selected spans for replay, returning how many
+    /// were marked.
+    pub async fn request_replay(
+        &self,
+        org_id: &str,
+        request: &ReplayQuarantineRequest,
+    ) -> Result<u64, QuarantineError> {
+        let pool = self.pool.as_ref().ok_or(QuarantineError::Disabled)?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE rejected_spans
+            SET replay_status = 'pending',
+                replay_requested_at = NOW(),
+                replay_error = NULL
+            WHERE org_id = $1
+              AND replay_status IN ('quarantined', 'failed')
+              AND (cardinality($2::UUID[]) = 0 OR id = ANY($2))
+              AND ($3::TEXT IS NULL OR stage = $3)
+              AND ($4::TEXT IS NULL OR service_name = $4)
+              AND ($5::TEXT IS NULL OR schema_name = $5)
+              AND ($6::TIMESTAMPTZ IS NULL OR ts >= $6)
+              AND ($7::TIMESTAMPTZ IS NULL OR ts < $7)
+            "#,
+        )
+        .bind(org_id)
+        .bind(&request.ids)
+        .bind(&request.stage)
+        .bind(&request.service_name)
+        .bind(&request.schema_name)
+        .bind(request.start_time)
+        .bind(request.end_time)
+        .execute(pool)
+        .await?;
+
+        info!(
+            org_id = %org_id,
+            requested = result.rows_affected(),
+            "Quarantined spans marked for replay"
+        );
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,445 @@
+//! # Query Result Cache
+//!
+//! Redis cache for analytics query results with stale-while-revalidate
+//! semantics. Each endpoint declares a `FreshnessPolicy`:
+//! - Within `fresh_for` of being computed, an entry is served as is
+//! - Within the following `stale_for`, the entry is still served immediately
+//!   while one background task recomputes and replaces it
+//! - After that Redis has expired the entry and the query runs inline
+//!
+//! Concurrent misses for the same key are coalesced within an API instance:
+//! the first request runs the query and the others await its result, so an
+//! expired dashboard refreshed by many clients costs one query. Coalesced
+//! requests are counted in `analytics_query_coalesced_total`.
+//!
+//! Every lookup reports a `CacheStatus`, which handlers return as an RFC 9211
+//! `Cache-Status` response header. Redis errors are logged and treated as
+//! misses, so the cache never fails a request.
+
+use axum::{
+    http::{HeaderName, HeaderValue},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use redis::AsyncCommands;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use tracing::{debug, warn};
+
+/// Name this cache reports itself as in `Cache-Status`.
+const CACHE_NAME: &str = "analytics-api";
+
+/// `Cache-Status` response header (RFC 9211).
+pub const CACHE_STATUS: HeaderName = HeaderName::from_static("cache-status");
+
+/// How long an endpoint's results are fresh, then servable while stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FreshnessPolicy {
+    /// Seconds a result is served without revalidation
+    pub fresh_for: u64,
+    /// Seconds after that a result is served while it is refreshed
+    pub stale_for: u64,
+}
+
+impl FreshnessPolicy {
+    /// Create a policy from fresh and stale windows in seconds
+    pub const fn new(fresh_for: u64, stale_for: u64) -> Self {
+        Self {
+            fresh_for,
+            stale_for,
+        }
+    }
+
+    /// Seconds Redis keeps an entry
+    fn expire_secs(&self) -> u64 {
+        (self.fresh_for + self.stale_for).max(1)
+    }
+
+    /// Seconds an entry computed at `computed_at` stays fresh at `now`;
+    /// negative once stale, `None` once too old to serve
+    pub fn remaining_ttl(&self, computed_at: DateTime<Utc>, now: DateTime<Utc>) -> Option<i64> {
+        let age = (now - computed_at).num_seconds().max(0);
+        let ttl = self.fresh_for as i64 - age;
+        (ttl > -(self.stale_for as i64)).then_some(ttl)
+    }
+}
+
+/// Outcome of a cache lookup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// Served from the cache; `ttl` is the remaining freshness in seconds,
+    /// negative when the entry was stale and is being revalidated
+    Hit { ttl: i64 },
+    /// Computed inline; `stored` is whether the result was cached
+    Miss { stored: bool },
+    /// Computed for a concurrent identical request and shared with this one
+    Coalesced,
+}
+
+impl CacheStatus {
+    /// Returns whether a stale entry was served
+    pub fn is_stale(&self) -> bool {
+        matches!(self, CacheStatus::Hit { ttl } if *ttl <= 0)
+    }
+
+    /// `Cache-Status` header value, e.g. `analytics-api; hit; ttl=42`
+    pub fn header_value(&self) -> HeaderValue {
+        let value = match self {
+            CacheStatus::Hit { ttl } if *ttl <= 0 => format!(
+                "{}; hit; ttl={}; detail=stale-while-revalidate",
+                CACHE_NAME, ttl
+            ),
+            CacheStatus::Hit { ttl } => format!("{}; hit; ttl={}", CACHE_NAME, ttl),
+            CacheStatus::Miss { stored: true } => format!("{}; fwd=miss; stored", CACHE_NAME),
+            CacheStatus::Miss { stored: false } => format!("{}; fwd=miss", CACHE_NAME),
+            CacheStatus::Coalesced => format!("{}; fwd=miss; collapsed", CACHE_NAME),
+        };
+        HeaderValue::from_str(&value).expect("cache status is a valid header value")
+    }
+}
+
+/// JSON response carrying the `Cache-Status` of the lookup that produced it.
+#[derive(Debug)]
+pub struct CachedJson<T>(pub T, pub CacheStatus);
+
+impl<T: Serialize> IntoResponse for CachedJson<T> {
+    fn into_response(self) -> Response {
+        let CachedJson(body, status) = self;
+        ([(CACHE_STATUS, status.header_value())], Json(body)).into_response()
+    }
+}
+
+/// Cached result with the time it was computed.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry<T> {
+    computed_at: DateTime<Utc>,
+    value: T,
+}
+
+/// Serialized `CacheEntry` of a finished computation, `None` if it failed
+type FlightResult = Option<Arc<str>>;
+
+/// Inline computations in progress, by cache key
+type Flights = Arc<Mutex<HashMap<String, watch::Receiver<Option<FlightResult>>>>>;
+
+/// Role of a request in a coalesced computation
+enum Flight {
+    /// Runs the computation and publishes its result
+    Leader(watch::Sender<Option<FlightResult>>, FlightGuard),
+    /// Awaits the leader's result
+    Follower(watch::Receiver<Option<FlightResult>>),
+}
+
+/// Removes a finished (or cancelled) computation from the in-flight map
+struct FlightGuard {
+    flights: Flights,
+    key: String,
+}
+
+impl Drop for FlightGuard {
+    fn drop(&mut self) {
+        if let Ok(mut flights) = self.flights.lock() {
+            flights.remove(&self.key);
+        }
+    }
+}
+
+/// Stale-while-revalidate cache for query results.
+#[derive(Clone)]
+pub struct QueryCache {
+    redis: redis::Client,
+    flights: Flights,
+}
+
+impl QueryCache {
+    /// Create a cache on the given Redis client
+    pub fn new(redis: redis::Client) -> Self {
+        Self {
+            redis,
+            flights: Arc::default(),
+        }
+    }
+
+    /// Return the cached result for `key`, or compute it
+    ///
+    /// Fresh entries are returned as is. Stale entries are returned
+    /// immediately and `compute` runs in the background, at most once per
+    /// key at a time across API instances. Without a usable entry `compute`
+    /// runs inline and its result is cached; concurrent requests for the
+    /// same key wait for that run instead of starting their own.
+    pub async fn get_or_compute<T, E, F, Fut>(
+        &self,
+        key: &str,
+        policy: FreshnessPolicy,
+        compute: F,
+    ) -> Result<(T, CacheStatus), E>
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        E: Debug + Send + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        if let Some(entry) = self.load::<T>(key).await {
+            if let Some(ttl) = policy.remaining_ttl(entry.computed_at, Utc::now()) {
+                if ttl <= 0 {
+                    self.revalidate(key, policy, compute).await;
+                }
+                return Ok((entry.value, CacheStatus::Hit { ttl }));
+            }
+        }
+
+        let (tx, _guard) = match self.join_flight(key) {
+            Flight::Leader(tx, guard) => (tx, guard),
+            Flight::Follower(mut rx) => {
+                metrics::counter!("analytics_query_coalesced_total", "query" => query_name(key))
+                    .increment(1);
+                let shared = rx
+                    .wait_for(Option::is_some)
+                    .await
+                    .ok()
+                    .and_then(|r| r.clone());
+                if let Some(entry) = shared
+                    .flatten()
+                    .and_then(|json| serde_json::from_str::<CacheEntry<T>>(&json).ok())
+                {
+                    return Ok((entry.value, CacheStatus::Coalesced));
+                }
+                // The leader failed or was cancelled; run the query for this request
+                let value = compute().await?;
+                let stored = self.store(key, policy, &value).await;
+                return Ok((value, CacheStatus::Miss { stored }));
+            }
+        };
+
+        let value = match compute().await {
+            Ok(value) => value,
+            Err(e) => {
+                let _ = tx.send(Some(None));
+                return Err(e);
+            }
+        };
+        let serialized = serde_json::to_string(&CacheEntry {
+            computed_at: Utc::now(),
+            value: &value,
+        })
+        .ok();
+        let stored = match &serialized {
+            Some(serialized) => self.store_serialized(key, policy, serialized.clone()).await,
+            None => false,
+        };
+        let _ = tx.send(Some(serialized.map(Arc::from)));
+        Ok((value, CacheStatus::Miss { stored }))
+    }
+
+    /// Join the in-progress computation of `key`, or become its leader
+    fn join_flight(&self, key: &str) -> Flight {
+        let mut flights = self.flights.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(rx) = flights.get(key) {
+            return Flight::Follower(rx.clone());
+        }
+        let (tx, rx) = watch::channel(None);
+        flights.insert(key.to_string(), rx);
+        Flight::Leader(
+            tx,
+            FlightGuard {
+                flights: self.flights.clone(),
+                key: key.to_string(),
+            },
+        )
+    }
+
+    /// Refresh a stale entry in the background, unless another request
+    /// already is
+    async fn revalidate<T, E, F, Fut>(&self, key: &str, policy: FreshnessPolicy, compute: F)
+    where
+        T: Serialize + Send + Sync + 'static,
+        E: Debug + Send + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        let lock_key = format!("{}:revalidating", key);
+        if !self.try_lock(&lock_key, policy.fresh_for.max(1)).await {
+            debug!(cache_key = %key, "Revalidation already in progress");
+            return;
+        }
+
+        let cache = self.clone();
+        let key = key.to_string();
+        tokio::spawn(async move {
+            match compute().await {
+                Ok(value) => {
+                    cache.store(&key, policy, &value).await;
+                    debug!(cache_key = %key, "Revalidated stale cache entry");
+                }
+                Err(e) => warn!(cache_key = %key, error = ?e, "Cache revalidation failed"),
+            }
+            if let Ok(mut conn) = cache.redis.get_multiplexed_async_connection().await {
+                let _: Result<(), _> = conn.del(&lock_key).await;
+            }
+        });
+    }
+
+    async fn load<T: DeserializeOwned>(&self, key: &str) -> Option<CacheEntry<T>> {
+        let mut conn = self
+            .redis
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| warn!(error = %e, "Redis connection error"))
+            .ok()?;
+        let cached: Option<String> = conn.get(key).await.ok()?;
+        serde_json::from_str(&cached?).ok()
+    }
+
+    async fn store<T: Serialize>(&self, key: &str, policy: FreshnessPolicy, value: &T) -> bool {
+        let entry = CacheEntry {
+            computed_at: Utc::now(),
+            value,
+        };
+        match serde_json::to_string(&entry) {
+            Ok(serialized) => self.store_serialized(key, policy, serialized).await,
+            Err(_) => false,
+        }
+    }
+
+    async fn store_serialized(
+        &self,
+        key: &str,
+        policy: FreshnessPolicy,
+        serialized: String,
+    ) -> bool {
+        match self.redis.get_multiplexed_async_connection().await {
+            Ok(mut conn) => conn
+                .set_ex::<_, _, ()>(key, serialized, policy.expire_secs())
+                .await
+                .is_ok(),
+            Err(e) => {
+                warn!(error = %e, "Redis connection error");
+                false
+            }
+        }
+    }
+
+    /// Take a short-lived lock, `SET NX EX`
+    async fn try_lock(&self, lock_key: &str, expire_secs: u64) -> bool {
+        let Ok(mut conn) = self.redis.get_multiplexed_async_connection().await else {
+            return false;
+        };
+        let reply: redis::RedisResult<Option<String>> = redis::cmd("SET")
+            .arg(lock_key)
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(expire_secs)
+            .query_async(&mut conn)
+            .await;
+        matches!(reply, Ok(Some(_)))
+    }
+}
+
+/// Metric label for a cache key: its first two segments, e.g. `costs:summary`
+fn query_name(key: &str) -> String {
+    key.splitn(3, ':').take(2).collect::<Vec<_>>().join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_remaining_ttl() {
+        let policy = FreshnessPolicy::new(60, 300);
+        let computed_at = Utc::now();
+
+        assert_eq!(policy.remaining_ttl(computed_at, computed_at), Some(60));
+        assert_eq!(
+            policy.remaining_ttl(computed_at, computed_at + Duration::seconds(45)),
+            Some(15)
+        );
+        // Stale but within the revalidation window
+        assert_eq!(
+            policy.remaining_ttl(computed_at, computed_at + Duration::seconds(120)),
+            Some(-60)
+        );
+        // Too old to serve
+        assert_eq!(
+            policy.remaining_ttl(computed_at, computed_at + Duration::seconds(360)),
+            None
+        );
+        assert_eq!(policy.expire_secs(), 360);
+    }
+
+    #[test]
+    fn test_cache_status_header() {
+        assert_eq!(
+            CacheStatus::Hit { ttl: 42 }.header_value(),
+            "analytics-api; hit; ttl=42"
+        );
+        assert_eq!(
+            CacheStatus::Hit { ttl: -5 }.header_value(),
+            "analytics-api; hit; ttl=-5; detail=stale-while-revalidate"
+        );
+        assert_eq!(
+            CacheStatus::Miss { stored: true }.header_value(),
+            "analytics-api; fwd=miss; stored"
+        );
+        assert_eq!(
+            CacheStatus::Coalesced.header_value(),
+            "analytics-api; fwd=miss; collapsed"
+        );
+        assert!(CacheStatus::Hit { ttl: 0 }.is_stale());
+        assert!(!CacheStatus::Miss { stored: false }.is_stale());
+    }
+
+    #[test]
+    fn test_query_name() {
+        assert_eq!(query_name("costs:summary:org-1:2025"), "costs:summary");
+        assert_eq!(
+            query_name("metrics:custom:org-1:{\"a\":1}"),
+            "metrics:custom"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_misses_are_coalesced() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Unreachable Redis: every lookup misses and nothing is stored
+        let cache = QueryCache::new(redis::Client::open("redis://127.0.0.1:1/").unwrap());
+        let executions = Arc::new(AtomicUsize::new(0));
+        let policy = FreshnessPolicy::new(60, 600);
+
+        let requests = (0..5).map(|_| {
+            let cache = cache.clone();
+            let executions = executions.clone();
+            tokio::spawn(async move {
+                cache
+                    .get_or_compute("metrics:query:org-1", policy, move || async move {
+                        executions.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                        Ok::<_, String>(vec![1, 2, 3])
+                    })
+                    .await
+                    .unwrap()
+            })
+        });
+        let results = futures::future::join_all(requests).await;
+
+        assert_eq!(executions.load(Ordering::SeqCst), 1);
+        let coalesced = results
+            .iter()
+            .map(|r| r.as_ref().unwrap())
+            .filter(|(value, status)| {
+                assert_eq!(value, &vec![1, 2, 3]);
+                *status == CacheStatus::Coalesced
+            })
+            .count();
+        assert_eq!(coalesced, 4);
+        assert!(cache.flights.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+//! # Provider Rate Limit Alerts
+//!
+//! Raises the "approaching provider rate limit" alert before requests start
+//! failing with 429s. Every interval the latest rate-limit state of each
+//! organization, provider and model (`provider_rate_limits`, kept up to date
+//! from the SDK's `llm_observatory.rate_limit.*` span attributes) is checked;
+//! a request or token limit approaches when at least `threshold` of it is
+//! used and its window has not reset yet.
+//!
+//! Approaching limits are sent as `alert.triggered` webhook events with the
+//! rule `rate_limit:requests` or `rate_limit:tokens`, critical once nothing
+//! is left. A model alerts at most once per `cooldown`.
+//!
+//! ## Configuration
+//! - `RATE_LIMIT_ALERTS_ENABLED` - run the monitor (default: true; needs DATABASE_URL)
+//! - `RATE_LIMIT_ALERT_INTERVAL_SECS` - how often the state is checked (default: 60)
+//! - `RATE_LIMIT_ALERT_THRESHOLD` - share of a limit used at which it alerts (default: 0.9)
+//! - `RATE_LIMIT_ALERT_COOLDOWN_SECS` - least time between alerts of a model (default: 900)
+
+use crate::models::rate_limits::{
+    ProviderRateLimit, RateLimitKind, RateLimitRow, DEFAULT_APPROACHING_THRESHOLD,
+};
+use crate::services::webhooks::WebhookService;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use llm_observatory_webhooks::{EventType, Severity, WebhookEvent};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+/// Rate-limit alert settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitAlertConfig {
+    /// Share of a limit used at which it alerts
+    pub threshold: f64,
+    /// Least time between alerts of one organization, provider and model
+    pub cooldown: ChronoDuration,
+}
+
+impl Default for RateLimitAlertConfig {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_APPROACHING_THRESHOLD,
+            cooldown: ChronoDuration::minutes(15),
+        }
+    }
+}
+
+/// Periodically checks provider rate-limit state and raises alerts
+pub struct RateLimitMonitor {
+    pool: PgPool,
+    webhooks: Arc<WebhookService>,
+    config: RateLimitAlertConfig,
+}
+
+impl RateLimitMonitor {
+    /// Create a monitor writing with `pool` and notifying through `webhooks`.
+    pub fn new(pool: PgPool, webhooks: Arc<WebhookService>, config: RateLimitAlertConfig) -> Self {
+        Self {
+            pool,
+            webhooks,
+            config,
+        }
+    }
+
+    /// Check the state of every model outside its cooldown as of `now`.
+    /// Returns the number of alerts raised.
+    pub async fn run_once(&self, now: DateTime<Utc>) -> Result<usize, sqlx::Error> {
+        let cooldown_start = now - self.config.cooldown;
+
+        // State reported before the last alert's cooldown cannot be current
+        let rows = sqlx::query_as::<_, RateLimitRow>(
+            r#"
+            SELECT
+                org_id, provider, model,
+                requests_limit, requests_remaining, requests_reset_at,
+                tokens_limit, tokens_remaining, tokens_reset_at,
+                observed_at, trace_id
+            FROM provider_rate_limits
+            WHERE observed_at >= $1
+              AND (alerted_at IS NULL OR alerted_at < $1)
+            "#,
+        )
+        .bind(cooldown_start)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut alerts = 0;
+        for row in rows {
+            let org_id = row.org_id.clone();
+            let limit = ProviderRateLimit::from_row(row, self.config.threshold, now);
+            if limit.approaching.is_empty() {
+                continue;
+            }
+
+            // Claim the alert, so concurrent monitors raise it once
+            let claimed = sqlx::query(
+                r#"
+                UPDATE provider_rate_limits
+                SET alerted_at = $4
+                WHERE org_id = $1
+                  AND provider = $2
+                  AND model = $3
+                  AND (alerted_at IS NULL OR alerted_at < $5)
+                "#,
+            )
+            .bind(&org_id)
+            .bind(&limit.provider)
+            .bind(&limit.model)
+            .bind(now)
+            .bind(cooldown_start)
+            .execute(&self.pool)
+            .await?
+            .rows_affected()
+                > 0;
+            if !claimed {
+                continue;
+            }
+
+            for kind in &limit.approaching {
+                let window = match kind {
+                    RateLimitKind::Requests => &limit.requests,
+                    RateLimitKind::Tokens => &limit.tokens,
+                };
+                let severity = if window.remaining.is_some_and(|r| r <= 0) {
+                    Severity::Critical
+                } else {
+                    Severity::Warning
+                };
+
+                alerts += 1;
+                let event = WebhookEvent::new(
+                    EventType::AlertTriggered,
+                    org_id.as_str(),
+                    serde_json::json!({
+                        "message": format!(
+                            "{}/{} has used {:.0}% of its {} rate limit ({} of {} left)",
+                            limit.provider,
+                            limit.model,
+                            window.utilization.unwrap_or_default() * 100.0,
+                            kind.as_str(),
+                            window.remaining.unwrap_or_default(),
+                            window.limit.unwrap_or_default(),
+                        ),
+                        "provider": limit.provider,
+                        "model": limit.model,
+                        "limit_type": kind.as_str(),
+                        "limit": window.limit,
+                        "remaining": window.remaining,
+                        "utilization": window.utilization,
+                        "reset_at": window.reset_at,
+                        "threshold": self.config.threshold,
+                        "observed_at": limit.observed_at,
+                        "trace_id": limit.trace_id,
+                    }),
+                )
+                .with_severity(severity)
+                .with_rule(format!("rate_limit:{}", kind.as_str()));
+                self.webhooks.notify(event).await;
+            }
+        }
+
+        debug!(alerts, "Rate limits checked");
+        Ok(alerts)
+    }
+
+    /// Run at a fixed interval in the background.
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        info!(
+            threshold = self.config.threshold,
+            cooldown_secs = self.config.cooldown.num_seconds(),
+            "Rate limit monitor started"
+        );
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.run_once(Utc::now()).await {
+                    Ok(alerts) => {
+                        metrics::counter!("rate_limit_alerts_total").increment(alerts as u64)
+                    }
+                    Err(e) => error!(error = %e, "Failed to check provider rate limits"),
+                }
+            }
+        })
+    }
+}
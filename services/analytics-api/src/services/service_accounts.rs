@@ -0,0 +1,492 @@
+//! # Service Accounts
+//!
+//! Machine identities of an organization, such as collectors and SDK
+//! deployments, with narrowly scoped permissions and expiring, revocable
+//! tokens. Tokens are JWTs signed with the API's secret:
+//!
+//! - `sub` is `sa:<service account ID>`, so the audit log attributes calls
+//!   to the service account
+//! - `jti` is the token's ID in `service_account_tokens`
+//! - `role` is `service`, which grants nothing beyond the account's
+//!   permissions
+//!
+//! [`ServiceAccountService::verify`] checks every such token against the
+//! database: revoked tokens and tokens of disabled or deleted accounts are
+//! rejected, and the account's current permissions and projects replace the
+//! ones in the token. Results are cached for [`STATUS_CACHE_TTL`], so changes
+//! made through another API instance apply within that time.
+
+use crate::middleware::auth::{AuthError, JwtClaims, JwtGenerator, Role};
+use crate::models::admin::*;
+use crate::services::admin::{conflict, AdminError};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Instant;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// How long a token's verification result is reused
+pub const STATUS_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Prefix of service account subjects
+pub const SUBJECT_PREFIX: &str = "sa:";
+
+/// Columns of [`ServiceAccount`]
+const ACCOUNT_COLUMNS: &str =
+    "id, org_id, name, description, permissions, projects, created_at, created_by, disabled_at";
+
+/// Columns of [`ServiceAccountToken`]
+const TOKEN_COLUMNS: &str =
+    "id, service_account_id, name, created_at, created_by, expires_at, revoked_at, revoked_by";
+
+/// Subject of a service account's tokens
+pub fn subject(account_id: Uuid) -> String {
+    format!("{}{}", SUBJECT_PREFIX, account_id)
+}
+
+/// Account of a valid token
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct ActiveAccount {
+    service_account_id: Uuid,
+    org_id: String,
+    permissions: Vec<String>,
+    projects: Vec<String>,
+}
+
+/// Verification result of a token; `None` when it must be rejected
+struct CachedStatus {
+    account: Option<ActiveAccount>,
+    checked_at: Instant,
+}
+
+/// Service accounts and their tokens
+pub struct ServiceAccountService {
+    /// Read-write pool (None rejects every operation and token)
+    pool: Option<PgPool>,
+    /// Signs issued tokens
+    generator: JwtGenerator,
+    /// Verification results by token ID
+    status: RwLock<HashMap<Uuid, CachedStatus>>,
+}
+
+impl ServiceAccountService {
+    pub fn new(pool: Option<PgPool>, jwt_secret: &str) -> Self {
+        Self {
+            pool,
+            generator: JwtGenerator::new(jwt_secret, 0),
+            status: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// A service that rejects every operation and token
+    pub fn disabled() -> Self {
+        Self::new(None, "")
+    }
+
+    fn pool(&self) -> Result<&PgPool, AdminError> {
+        self.pool.as_ref().ok_or(AdminError::Disabled)
+    }
+
+    /// Forget verification results, after a change to accounts or tokens
+    fn invalidate(&self) {
+        self.status.write().unwrap().clear();
+    }
+
+    async fn require_organization(&self, org_id: &str) -> Result<(), AdminError> {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM organizations WHERE id = $1)")
+                .bind(org_id)
+                .fetch_one(self.pool()?)
+                .await?;
+
+        if !exists {
+            return Err(AdminError::NotFound(format!("Organization {}", org_id)));
+        }
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------
+    // Service Accounts
+    // ------------------------------------------------------------------------
+
+    /// Service accounts of an organization, by name
+    pub async fn list(&self, org_id: &str) -> Result<Vec<ServiceAccount>, AdminError> {
+        self.require_organization(org_id).await?;
+
+        let sql = format!(
+            "SELECT {ACCOUNT_COLUMNS} FROM service_accounts WHERE org_id = $1 ORDER BY name"
+        );
+        Ok(sqlx::query_as::<_, ServiceAccount>(&sql)
+            .bind(org_id)
+            .fetch_all(self.pool()?)
+            .await?)
+    }
+
+    pub async fn get(&self, org_id: &str, account_id: Uuid) -> Result<ServiceAccount, AdminError> {
+        let sql =
+            format!("SELECT {ACCOUNT_COLUMNS} FROM service_accounts WHERE org_id = $1 AND id = $2");
+        sqlx::query_as::<_, ServiceAccount>(&sql)
+            .bind(org_id)
+            .bind(account_id)
+            .fetch_optional(self.pool()?)
+            .await?
+            .ok_or_else(|| AdminError::NotFound(format!("Service account {}", account_id)))
+    }
+
+    pub async fn create(
+        &self,
+        org_id: &str,
+        request: &CreateServiceAccountRequest,
+        created_by: &str,
+    ) -> Result<ServiceAccount, AdminError> {
+        self.require_organization(org_id).await?;
+
+        let sql = format!(
+            "INSERT INTO service_accounts (org_id, name, description, permissions, projects, created_by) \
+             VALUES ($1, $2, $3, $4, $5, $6) \
+             RETURNING {ACCOUNT_COLUMNS}"
+        );
+        let account = sqlx::query_as::<_, ServiceAccount>(&sql)
+            .bind(org_id)
+            .bind(request.name.trim())
+            .bind(&request.description)
+            .bind(&request.permissions)
+            .bind(&request.projects)
+            .bind(created_by)
+            .fetch_one(self.pool()?)
+            .await
+            .map_err(conflict(format!("Service account {}", request.name.trim())))?;
+
+        info!(
+            org_id = %org_id,
+            service_account_id = %account.id,
+            permissions = ?account.permissions,
+            created_by = %created_by,
+            "Created service account"
+        );
+        Ok(account)
+    }
+
+    /// Update an account; permission changes and disabling apply to tokens
+    /// already issued
+    pub async fn update(
+        &self,
+        org_id: &str,
+        account_id: Uuid,
+        request: &UpdateServiceAccountRequest,
+    ) -> Result<ServiceAccount, AdminError> {
+        let sql = format!(
+            r#"
+            UPDATE service_accounts
+            SET description = COALESCE($3, description),
+                permissions = COALESCE($4, permissions),
+                projects = COALESCE($5, projects),
+                disabled_at = CASE
+                    WHEN $6::BOOLEAN IS NULL THEN disabled_at
+                    WHEN $6 THEN COALESCE(disabled_at, NOW())
+                    ELSE NULL
+                END
+            WHERE org_id = $1 AND id = $2
+            RETURNING {ACCOUNT_COLUMNS}
+            "#
+        );
+        let account = sqlx::query_as::<_, ServiceAccount>(&sql)
+            .bind(org_id)
+            .bind(account_id)
+            .bind(&request.description)
+            .bind(&request.permissions)
+            .bind(&request.projects)
+            .bind(request.disabled)
+            .fetch_optional(self.pool()?)
+            .await?
+            .ok_or_else(|| AdminError::NotFound(format!("Service account {}", account_id)))?;
+
+        self.invalidate();
+        info!(
+            org_id = %org_id,
+            service_account_id = %account_id,
+            disabled = account.disabled_at.is_some(),
+            "Updated service account"
+        );
+        Ok(account)
+    }
+
+    /// Delete an account and its tokens
+    pub async fn delete(&self, org_id: &str, account_id: Uuid) -> Result<(), AdminError> {
+        let result = sqlx::query("DELETE FROM service_accounts WHERE org_id = $1 AND id = $2")
+            .bind(org_id)
+            .bind(account_id)
+            .execute(self.pool()?)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AdminError::NotFound(format!(
+                "Service account {}",
+                account_id
+            )));
+        }
+
+        self.invalidate();
+        info!(org_id = %org_id, service_account_id = %account_id, "Deleted service account");
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------
+    // Tokens
+    // ------------------------------------------------------------------------
+
+    /// Tokens of an account, newest first
+    pub async fn list_tokens(
+        &self,
+        org_id: &str,
+        account_id: Uuid,
+    ) -> Result<Vec<ServiceAccountToken>, AdminError> {
+        self.get(org_id, account_id).await?;
+
+        let sql = format!(
+            "SELECT {TOKEN_COLUMNS} FROM service_account_tokens \
+             WHERE service_account_id = $1 ORDER BY created_at DESC"
+        );
+        Ok(sqlx::query_as::<_, ServiceAccountToken>(&sql)
+            .bind(account_id)
+            .fetch_all(self.pool()?)
+            .await?)
+    }
+
+    /// Issue a token to an account. The token itself is not stored.
+    pub async fn issue_token(
+        &self,
+        org_id: &str,
+        account_id: Uuid,
+        request: &CreateServiceAccountTokenRequest,
+        created_by: &str,
+    ) -> Result<IssuedServiceAccountToken, AdminError> {
+        let account = self.get(org_id, account_id).await?;
+
+        let token_id = Uuid::new_v4();
+        let now = Utc::now();
+        let expires_at = now + Duration::days(request.ttl_days());
+        let token = self
+            .generator
+            .generate(JwtClaims {
+                sub: subject(account.id),
+                org_id: account.org_id,
+                projects: account.projects,
+                role: Role::Service,
+                permissions: account.permissions,
+                iat: now.timestamp(),
+                exp: expires_at.timestamp(),
+                jti: token_id.to_string(),
+            })
+            .map_err(|e| AdminError::Signing(e.to_string()))?;
+
+        let sql = format!(
+            "INSERT INTO service_account_tokens (id, service_account_id, name, created_by, expires_at) \
+             VALUES ($1, $2, $3, $4, $5) \
+             RETURNING {TOKEN_COLUMNS}"
+        );
+        let details = sqlx::query_as::<_, ServiceAccountToken>(&sql)
+            .bind(token_id)
+            .bind(account_id)
+            .bind(request.name.trim())
+            .bind(created_by)
+            .bind(expires_at)
+            .fetch_one(self.pool()?)
+            .await?;
+
+        info!(
+            org_id = %org_id,
+            service_account_id = %account_id,
+            token_id = %token_id,
+            expires_at = %expires_at,
+            created_by = %created_by,
+            "Issued service account token"
+        );
+        Ok(IssuedServiceAccountToken { token, details })
+    }
+
+    /// Revoke a token; revoking a revoked token keeps the first revocation
+    pub async fn revoke_token(
+        &self,
+        org_id: &str,
+        account_id: Uuid,
+        token_id: Uuid,
+        revoked_by: &str,
+    ) -> Result<ServiceAccountToken, AdminError> {
+        self.get(org_id, account_id).await?;
+
+        let sql = format!(
+            r#"
+            UPDATE service_account_tokens
+            SET revoked_at = COALESCE(revoked_at, NOW()),
+                revoked_by = COALESCE(revoked_by, $3)
+            WHERE id = $1 AND service_account_id = $2
+            RETURNING {TOKEN_COLUMNS}
+            "#
+        );
+        let token = sqlx::query_as::<_, ServiceAccountToken>(&sql)
+            .bind(token_id)
+            .bind(account_id)
+            .bind(revoked_by)
+            .fetch_optional(self.pool()?)
+            .await?
+            .ok_or_else(|| AdminError::NotFound(format!("Token {}", token_id)))?;
+
+        self.invalidate();
+        info!(
+            org_id = %org_id,
+            service_account_id = %account_id,
+            token_id = %token_id,
+            revoked_by = %revoked_by,
+            "Revoked service account token"
+        );
+        Ok(token)
+    }
+
+    // ------------------------------------------------------------------------
+    // Verification
+    // ------------------------------------------------------------------------
+
+    /// Check a validated service account token, returning its claims with
+    /// the account's current organization, permissions and projects.
+    pub async fn verify(&self, mut claims: JwtClaims) -> Result<JwtClaims, AuthError> {
+        let token_id = Uuid::parse_str(&claims.jti).map_err(|_| {
+            warn!(subject = %claims.sub, "Service account token with invalid ID");
+            AuthError::InvalidToken
+        })?;
+
+        let cached = self
+            .status
+            .read()
+            .unwrap()
+            .get(&token_id)
+            .filter(|status| status.checked_at.elapsed() < STATUS_CACHE_TTL)
+            .map(|status| status.account.clone());
+        let account = match cached {
+            Some(account) => account,
+            None => {
+                let account = self.load(token_id).await?;
+                self.status.write().unwrap().insert(
+                    token_id,
+                    CachedStatus {
+                        account: account.clone(),
+                        checked_at: Instant::now(),
+                    },
+                );
+                account
+            }
+        };
+
+        let Some(account) = account.filter(|a| claims.sub == subject(a.service_account_id)) else {
+            warn!(subject = %claims.sub, token_id = %token_id, "Revoked or unknown service account token");
+            return Err(AuthError::InvalidToken);
+        };
+
+        claims.org_id = account.org_id;
+        claims.permissions = account.permissions;
+        claims.projects = account.projects;
+        Ok(claims)
+    }
+
+    /// Account of a token that is neither revoked, expired nor of a
+    /// disabled account
+    async fn load(&self, token_id: Uuid) -> Result<Option<ActiveAccount>, AuthError> {
+        let Some(pool) = &self.pool else {
+            warn!(token_id = %token_id, "Service account token rejected: DATABASE_URL not set");
+            return Err(AuthError::InvalidToken);
+        };
+
+        sqlx::query_as::<_, ActiveAccount>(
+            r#"
+            SELECT a.id AS service_account_id, a.org_id, a.permissions, a.projects
+            FROM service_account_tokens t
+            JOIN service_accounts a ON a.id = t.service_account_id
+            WHERE t.id = $1
+              AND t.revoked_at IS NULL
+              AND t.expires_at > NOW()
+              AND a.disabled_at IS NULL
+            "#,
+        )
+        .bind(token_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, token_id = %token_id, "Failed to check service account token");
+            AuthError::Internal("Failed to check service account token".to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(account: &ActiveAccount, token_id: Uuid) -> JwtClaims {
+        JwtClaims {
+            sub: subject(account.service_account_id),
+            org_id: "acme".to_string(),
+            projects: vec![],
+            role: Role::Service,
+            permissions: vec!["write:traces".to_string(), "read:traces".to_string()],
+            iat: 0,
+            exp: i64::MAX,
+            jti: token_id.to_string(),
+        }
+    }
+
+    fn cache(service: &ServiceAccountService, token_id: Uuid, account: Option<ActiveAccount>) {
+        service.status.write().unwrap().insert(
+            token_id,
+            CachedStatus {
+                account,
+                checked_at: Instant::now(),
+            },
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_uses_current_permissions() {
+        let service = ServiceAccountService::disabled();
+        let account = ActiveAccount {
+            service_account_id: Uuid::new_v4(),
+            org_id: "acme".to_string(),
+            permissions: vec!["write:traces".to_string()],
+            projects: vec![],
+        };
+        let token_id = Uuid::new_v4();
+        cache(&service, token_id, Some(account.clone()));
+
+        let verified = service.verify(claims(&account, token_id)).await.unwrap();
+        assert_eq!(verified.permissions, vec!["write:traces".to_string()]);
+
+        // Tokens of another account are rejected
+        let mut other = claims(&account, token_id);
+        other.sub = subject(Uuid::new_v4());
+        assert!(service.verify(other).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_revoked_tokens() {
+        let service = ServiceAccountService::disabled();
+        let account = ActiveAccount {
+            service_account_id: Uuid::new_v4(),
+            org_id: "acme".to_string(),
+            permissions: vec!["write:traces".to_string()],
+            projects: vec![],
+        };
+        let token_id = Uuid::new_v4();
+        cache(&service, token_id, None);
+        assert!(matches!(
+            service.verify(claims(&account, token_id)).await,
+            Err(AuthError::InvalidToken)
+        ));
+
+        // Without a database, tokens can't be checked and are rejected
+        assert!(service
+            .verify(claims(&account, Uuid::new_v4()))
+            .await
+            .is_err());
+    }
+}
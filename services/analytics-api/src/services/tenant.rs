@@ -0,0 +1,53 @@
+//! # Tenant Transactions
+//!
+//! Reads of tenant data (`traces`, `trace_spans`, `trace_events` and `logs`)
+//! run in a transaction scoped to the caller's organization, the analytics
+//! API's counterpart of the storage crate's `StoragePool::with_tenant`. When
+//! the API connects as a role granted `llm_observatory_tenant`, the
+//! row-level security policies of storage migrations 019 and 044 then hide
+//! every other organization's rows, even from a query missing its org
+//! filter. Handlers keep their explicit org filters, so other roles are
+//! unaffected.
+
+use sqlx::{PgPool, Postgres, Transaction};
+
+/// Begin a transaction scoped to `org_id`.
+///
+/// `app.org_id` is set with `set_config(..., true)`, so it ends with the
+/// transaction and never leaks to the pooled connection's next user. An
+/// empty `org_id` is rejected: the policies would hide every row rather
+/// than fail, masking the caller's bug.
+pub async fn with_tenant(
+    pool: &PgPool,
+    org_id: &str,
+) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+    if org_id.is_empty() {
+        return Err(sqlx::Error::Configuration(
+            "Tenant organization ID must not be empty".into(),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("SELECT set_config('app.org_id', $1, true)")
+        .bind(org_id)
+        .execute(&mut *tx)
+        .await?;
+    Ok(tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::PgPoolOptions;
+
+    #[tokio::test]
+    async fn test_with_tenant_rejects_empty_org_id() {
+        // Never connects: the org ID is checked first
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/llm_observatory")
+            .unwrap();
+
+        let result = with_tenant(&pool, "").await;
+        assert!(matches!(result, Err(sqlx::Error::Configuration(_))));
+    }
+}
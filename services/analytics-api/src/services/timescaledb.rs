@@ -4,6 +4,12 @@ use chrono::{DateTime, Duration, Utc};
 use sqlx::PgPool;
 use tracing::{debug, error, instrument};
 
+/// Output tokens per second for a single request, measured over the
+/// generation phase (after the first token for streaming requests).
+const OUTPUT_TOKENS_PER_SECOND_SQL: &str = "CASE WHEN completion_tokens > 0 \
+    AND duration_ms > COALESCE(ttft_ms, 0) \
+    THEN completion_tokens::float8 * 1000.0 / (duration_ms - COALESCE(ttft_ms, 0)) END";
+
 /// Service for querying TimescaleDB analytics data
 pub struct TimescaleDBService {
     pool: PgPool,
@@ -372,14 +378,21 @@ impl TimescaleDBService {
         };
 
         // Calculate percentiles from raw data if needed
-        let percentiles = if query.granularity == "1min" || query.granularity == "raw" {
-            self.calculate_percentiles(query).await?
+        let (percentiles, latency) = if query.granularity == "1min" || query.granularity == "raw" {
+            (
+                self.calculate_percentiles(query).await?,
+                self.latency_breakdown_overall(query, start_time, end_time)
+                    .await?,
+            )
         } else {
-            PercentileMetrics {
-                p50: None,
-                p95: None,
-                p99: None,
-            }
+            (
+                PercentileMetrics {
+                    p50: None,
+                    p95: None,
+                    p99: None,
+                },
+                LatencyBreakdownStats::default(),
+            )
         };
 
         // Convert to time series
@@ -406,6 +419,11 @@ impl TimescaleDBService {
             throughput_rps,
             total_tokens,
             tokens_per_second,
+            avg_ttft_ms: latency.avg_ttft_ms,
+            p50_ttft_ms: latency.p50_ttft_ms,
+            p95_ttft_ms: latency.p95_ttft_ms,
+            p99_ttft_ms: latency.p99_ttft_ms,
+            avg_output_tokens_per_second: latency.avg_output_tokens_per_second,
             time_series,
         })
     }
@@ -463,6 +481,251 @@ impl TimescaleDBService {
         Ok(percentiles)
     }
 
+    /// Get time to first token and output tokens/sec statistics, overall,
+    /// per model and over time
+    #[instrument(skip(self))]
+    pub async fn get_latency_breakdown(&self, query: &AnalyticsQuery) -> Result<LatencyBreakdown> {
+        let (start_time, end_time) = self.get_time_range(query);
+
+        let overall = self
+            .latency_breakdown_overall(query, start_time, end_time)
+            .await?;
+        let by_model = self
+            .latency_breakdown_by_model(
+                start_time,
+                end_time,
+                query.provider.as_deref(),
+                query.model.as_deref(),
+                query.environment.as_deref(),
+            )
+            .await?;
+
+        let bucket_interval = match query.granularity.as_str() {
+            "1min" | "raw" => "1 minute",
+            "1day" => "1 day",
+            _ => "1 hour",
+        };
+        let where_clause = Self::trace_filter_clause(
+            query.provider.is_some(),
+            query.model.is_some(),
+            query.environment.is_some(),
+        );
+
+        let time_series_query = format!(
+            r#"
+            SELECT
+                time_bucket(INTERVAL '{}', ts) as timestamp,
+                COUNT(*) as request_count,
+                PERCENTILE_CONT(0.50) WITHIN GROUP (ORDER BY ttft_ms) as p50_ttft_ms,
+                PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY ttft_ms) as p95_ttft_ms,
+                AVG({}) as avg_output_tokens_per_second
+            FROM llm_traces
+            {}
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+            bucket_interval, OUTPUT_TOKENS_PER_SECOND_SQL, where_clause
+        );
+
+        let mut query_builder = sqlx::query_as::<_, LatencyBreakdownDataPoint>(&time_series_query)
+            .bind(start_time)
+            .bind(end_time);
+
+        if let Some(ref provider) = query.provider {
+            query_builder = query_builder.bind(provider);
+        }
+        if let Some(ref model) = query.model {
+            query_builder = query_builder.bind(model);
+        }
+        if let Some(ref environment) = query.environment {
+            query_builder = query_builder.bind(environment);
+        }
+
+        let time_series = query_builder.fetch_all(&self.pool).await?;
+
+        Ok(LatencyBreakdown {
+            overall,
+            by_model,
+            time_series,
+        })
+    }
+
+    /// Compare each model's TTFT over the last week against the week before
+    #[instrument(skip(self))]
+    pub async fn detect_ttft_regressions(
+        &self,
+        query: &TtftRegressionQuery,
+    ) -> Result<TtftRegressionReport> {
+        let current_end = query.end_time.unwrap_or_else(Utc::now);
+        let current_start = current_end - Duration::days(7);
+        let previous_start = current_start - Duration::days(7);
+
+        let provider = query.provider.as_deref();
+        let model = query.model.as_deref();
+        let environment = query.environment.as_deref();
+        let current = self
+            .latency_breakdown_by_model(current_start, current_end, provider, model, environment)
+            .await?;
+        let previous = self
+            .latency_breakdown_by_model(previous_start, current_start, provider, model, environment)
+            .await?;
+
+        let mut models: Vec<TtftRegression> = current
+            .iter()
+            .filter(|m| m.stats.streaming_request_count > 0)
+            .map(|m| {
+                let before = previous
+                    .iter()
+                    .find(|p| p.provider == m.provider && p.model == m.model)
+                    .map(|p| &p.stats);
+                TtftRegression::compare(m, before, query.threshold_pct, query.min_requests)
+            })
+            .collect();
+
+        // Regressions first, largest p95 increase first
+        models.sort_by(|a, b| {
+            b.regressed.cmp(&a.regressed).then_with(|| {
+                b.p95_change_pct
+                    .unwrap_or(f64::MIN)
+                    .total_cmp(&a.p95_change_pct.unwrap_or(f64::MIN))
+            })
+        });
+
+        Ok(TtftRegressionReport {
+            current_start,
+            current_end,
+            previous_start,
+            threshold_pct: query.threshold_pct,
+            regressed_count: models.iter().filter(|m| m.regressed).count(),
+            models,
+        })
+    }
+
+    /// TTFT and output tokens/sec across all requests matching the query
+    async fn latency_breakdown_overall(
+        &self,
+        query: &AnalyticsQuery,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<LatencyBreakdownStats> {
+        let where_clause = Self::trace_filter_clause(
+            query.provider.is_some(),
+            query.model.is_some(),
+            query.environment.is_some(),
+        );
+
+        let stats_query = format!(
+            "SELECT {} FROM llm_traces {}",
+            Self::latency_breakdown_columns(),
+            where_clause
+        );
+
+        let mut query_builder = sqlx::query_as::<_, LatencyBreakdownStats>(&stats_query)
+            .bind(start_time)
+            .bind(end_time);
+
+        if let Some(ref provider) = query.provider {
+            query_builder = query_builder.bind(provider);
+        }
+        if let Some(ref model) = query.model {
+            query_builder = query_builder.bind(model);
+        }
+        if let Some(ref environment) = query.environment {
+            query_builder = query_builder.bind(environment);
+        }
+
+        Ok(query_builder.fetch_one(&self.pool).await?)
+    }
+
+    /// TTFT and output tokens/sec per provider and model, busiest first
+    async fn latency_breakdown_by_model(
+        &self,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        provider: Option<&str>,
+        model: Option<&str>,
+        environment: Option<&str>,
+    ) -> Result<Vec<ModelLatencyBreakdown>> {
+        let where_clause =
+            Self::trace_filter_clause(provider.is_some(), model.is_some(), environment.is_some());
+
+        let stats_query = format!(
+            r#"
+            SELECT provider, model, {}
+            FROM llm_traces
+            {}
+            GROUP BY provider, model
+            ORDER BY request_count DESC
+            "#,
+            Self::latency_breakdown_columns(),
+            where_clause
+        );
+
+        let mut query_builder = sqlx::query_as::<_, ModelLatencyBreakdownRow>(&stats_query)
+            .bind(start_time)
+            .bind(end_time);
+
+        if let Some(provider) = provider {
+            query_builder = query_builder.bind(provider);
+        }
+        if let Some(model) = model {
+            query_builder = query_builder.bind(model);
+        }
+        if let Some(environment) = environment {
+            query_builder = query_builder.bind(environment);
+        }
+
+        let rows = query_builder.fetch_all(&self.pool).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ModelLatencyBreakdown {
+                provider: row.provider,
+                model: row.model,
+                stats: row.stats,
+            })
+            .collect())
+    }
+
+    /// Helper: Aggregate columns for [`LatencyBreakdownStats`]
+    fn latency_breakdown_columns() -> String {
+        format!(
+            r#"
+                COUNT(*) as request_count,
+                COUNT(ttft_ms) as streaming_request_count,
+                AVG(ttft_ms)::float8 as avg_ttft_ms,
+                PERCENTILE_CONT(0.50) WITHIN GROUP (ORDER BY ttft_ms) as p50_ttft_ms,
+                PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY ttft_ms) as p95_ttft_ms,
+                PERCENTILE_CONT(0.99) WITHIN GROUP (ORDER BY ttft_ms) as p99_ttft_ms,
+                AVG({tps}) as avg_output_tokens_per_second,
+                PERCENTILE_CONT(0.50) WITHIN GROUP (ORDER BY {tps}) as p50_output_tokens_per_second,
+                PERCENTILE_CONT(0.05) WITHIN GROUP (ORDER BY {tps}) as p5_output_tokens_per_second
+            "#,
+            tps = OUTPUT_TOKENS_PER_SECOND_SQL
+        )
+    }
+
+    /// Helper: WHERE clause over `llm_traces` with the time range bound to
+    /// $1 and $2, followed by the optional provider, model and environment
+    fn trace_filter_clause(provider: bool, model: bool, environment: bool) -> String {
+        let mut conditions = vec!["ts >= $1".to_string(), "ts <= $2".to_string()];
+        let mut param_count = 3;
+
+        if provider {
+            conditions.push(format!("provider = ${}", param_count));
+            param_count += 1;
+        }
+        if model {
+            conditions.push(format!("model = ${}", param_count));
+            param_count += 1;
+        }
+        if environment {
+            conditions.push(format!("environment = ${}", param_count));
+        }
+
+        format!("WHERE {}", conditions.join(" AND "))
+    }
+
     /// Get quality metrics
     #[instrument(skip(self))]
     pub async fn get_quality_metrics(&self, query: &AnalyticsQuery) -> Result<QualityMetrics> {
@@ -700,20 +963,26 @@ impl TimescaleDBService {
                 };
 
                 // Calculate percentiles
-                let percentile_query = r#"
+                let percentile_query = format!(
+                    r#"
                     SELECT
-                        PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY duration_ms) as p95
+                        PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY duration_ms) as p95,
+                        PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY ttft_ms) as p95_ttft,
+                        AVG({}) as avg_tps
                     FROM llm_traces
                     WHERE ts >= $1 AND ts <= $2 AND model = $3
-                "#;
-
-                let p95: Option<f64> = sqlx::query_scalar(percentile_query)
-                    .bind(start_time)
-                    .bind(end_time)
-                    .bind(model)
-                    .fetch_optional(&self.pool)
-                    .await?
-                    .flatten();
+                "#,
+                    OUTPUT_TOKENS_PER_SECOND_SQL
+                );
+
+                let (p95, p95_ttft, avg_tps): (Option<f64>, Option<f64>, Option<f64>) =
+                    sqlx::query_as(&percentile_query)
+                        .bind(start_time)
+                        .bind(end_time)
+                        .bind(model)
+                        .fetch_optional(&self.pool)
+                        .await?
+                        .unwrap_or_default();
 
                 results.push(ModelComparisonResult {
                     model: row.model.clone(),
@@ -721,6 +990,8 @@ impl TimescaleDBService {
                     metrics: ModelMetrics {
                         avg_latency_ms: row.avg_duration_ms.unwrap_or(0.0),
                         p95_latency_ms: p95,
+                        p95_ttft_ms: p95_ttft,
+                        avg_output_tokens_per_second: avg_tps,
                         avg_cost_usd,
                         total_cost_usd: row.total_cost_usd.unwrap_or(0.0),
                         success_rate,
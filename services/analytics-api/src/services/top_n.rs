@@ -0,0 +1,362 @@
+//! # Top-N Rankings
+//!
+//! Ranks providers, models, environments and users over a time range ("top
+//! models by cost") for the cost and metrics endpoints.
+//!
+//! ## Acceleration
+//! The storage top-N materializer keeps per-organization daily totals in
+//! `top_n_daily`. Complete days inside the materialized range are read from
+//! there; the partial days at either end of the range, and days not
+//! materialized yet, are grouped from `llm_traces`. Both parts are summed per
+//! value before ranking, so results match a plain `llm_traces` query.
+//!
+//! The rollup is skipped, and the whole range read from `llm_traces`, when a
+//! filter is not a key of the rollup rows (e.g. a provider filter on a
+//! ranking of users).
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use sqlx::PgPool;
+
+/// Grouping of a ranking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopNGrouping {
+    Provider,
+    Model,
+    ProviderModel,
+    Environment,
+    User,
+}
+
+impl TopNGrouping {
+    /// Dimension of the `top_n_daily` rows holding this grouping
+    fn dimension(&self) -> &'static str {
+        match self {
+            TopNGrouping::Provider | TopNGrouping::Model | TopNGrouping::ProviderModel => "model",
+            TopNGrouping::Environment => "environment",
+            TopNGrouping::User => "user_id",
+        }
+    }
+
+    /// Provider and name expressions of a ranked row
+    fn keys(&self) -> (&'static str, &'static str) {
+        match self {
+            TopNGrouping::Provider => ("provider", "provider"),
+            TopNGrouping::ProviderModel => ("provider", "name"),
+            TopNGrouping::Model | TopNGrouping::Environment | TopNGrouping::User => ("''", "name"),
+        }
+    }
+}
+
+/// Ranking order, highest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopNOrder {
+    Cost,
+    Requests,
+    AvgDuration,
+    Errors,
+}
+
+impl TopNOrder {
+    fn to_sql(self) -> &'static str {
+        match self {
+            TopNOrder::Cost => "SUM(total_cost)",
+            TopNOrder::Requests => "SUM(request_count)",
+            TopNOrder::AvgDuration => "SUM(duration_sum_ms) / NULLIF(SUM(request_count), 0)",
+            TopNOrder::Errors => "SUM(error_count)",
+        }
+    }
+}
+
+/// Equality filters on `llm_traces` columns
+#[derive(Debug, Clone, Default)]
+pub struct TopNFilters {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub environment: Option<String>,
+    pub user_id: Option<String>,
+}
+
+impl TopNFilters {
+    /// Filters as (`llm_traces` column, value)
+    fn columns(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("provider", &self.provider),
+            ("model", &self.model),
+            ("environment", &self.environment),
+            ("user_id", &self.user_id),
+        ]
+        .into_iter()
+        .filter_map(|(column, value)| Some((column, value.as_deref()?)))
+        .collect()
+    }
+}
+
+/// A ranking over `[start, end)`
+#[derive(Debug, Clone)]
+pub struct TopNQuery {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub grouping: TopNGrouping,
+    pub filters: TopNFilters,
+    pub order: TopNOrder,
+    pub limit: i64,
+}
+
+/// Totals of one ranked value
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TopNRow {
+    /// Provider, for provider and provider/model rankings; empty otherwise
+    pub provider: String,
+    /// Ranked value
+    pub name: String,
+    pub total_cost: f64,
+    pub prompt_cost: f64,
+    pub completion_cost: f64,
+    pub request_count: i64,
+    pub total_tokens: i64,
+    pub error_count: i64,
+    pub duration_sum_ms: f64,
+}
+
+impl TopNRow {
+    /// Average request duration
+    pub fn avg_duration_ms(&self) -> f64 {
+        if self.request_count > 0 {
+            self.duration_sum_ms / self.request_count as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Column of a `top_n_daily` row matching a filter on `column`, if the
+/// rollup rows of `grouping` are keyed by it
+fn rollup_column(grouping: TopNGrouping, column: &str) -> Option<&'static str> {
+    match (grouping.dimension(), column) {
+        ("model", "provider") => Some("provider"),
+        ("model", "model") | ("environment", "environment") | ("user_id", "user_id") => {
+            Some("dimension_value")
+        }
+        _ => None,
+    }
+}
+
+/// Whether a ranking can read the rollup
+pub fn supports(grouping: TopNGrouping, filters: &TopNFilters) -> bool {
+    filters
+        .columns()
+        .iter()
+        .all(|(column, _)| rollup_column(grouping, column).is_some())
+}
+
+/// Complete days of `[start, end)` within the materialized range
+/// `[materialized_from, refreshed_through)`
+pub fn materialized_days(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    materialized_from: DateTime<Utc>,
+    refreshed_through: DateTime<Utc>,
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let day = Duration::days(1);
+    let floor = |ts: DateTime<Utc>| ts.duration_trunc(day).unwrap_or(ts);
+    let ceil = |ts: DateTime<Utc>| {
+        let floored = floor(ts);
+        if floored < ts {
+            floored + day
+        } else {
+            floored
+        }
+    };
+
+    let first = ceil(start).max(materialized_from);
+    let last = floor(end).min(refreshed_through);
+    (first < last).then_some((first, last))
+}
+
+/// Run a ranking for an organization.
+pub async fn query_top_n(
+    pool: &PgPool,
+    org_id: &str,
+    request: &TopNQuery,
+) -> Result<Vec<TopNRow>, sqlx::Error> {
+    let TopNQuery {
+        start,
+        end,
+        grouping,
+        ref filters,
+        order,
+        limit,
+    } = *request;
+
+    let state: Option<(DateTime<Utc>, DateTime<Utc>)> = if supports(grouping, filters) {
+        sqlx::query_as(
+            "SELECT materialized_from, refreshed_through FROM top_n_refresh_state WHERE id",
+        )
+        .fetch_optional(pool)
+        .await?
+    } else {
+        None
+    };
+    let days = state.and_then(|(from, through)| materialized_days(start, end, from, through));
+
+    let filter_columns = filters.columns();
+    let raw_filters: String = filter_columns
+        .iter()
+        .enumerate()
+        .map(|(i, (column, _))| format!(" AND {} = ${}", column, i + 6))
+        .collect();
+
+    let rollup = match days {
+        Some(_) => {
+            let rollup_filters: String = filter_columns
+                .iter()
+                .enumerate()
+                .filter_map(|(i, (column, _))| {
+                    Some(format!(
+                        " AND {} = ${}",
+                        rollup_column(grouping, column)?,
+                        i + 6
+                    ))
+                })
+                .collect();
+            format!(
+                r#"
+                SELECT provider, dimension_value AS name, total_cost, prompt_cost, completion_cost,
+                       request_count, total_tokens, error_count, duration_sum_ms
+                FROM top_n_daily
+                WHERE org_id = $1 AND dimension = '{}' AND bucket >= $4 AND bucket < $5{}
+                UNION ALL"#,
+                grouping.dimension(),
+                rollup_filters
+            )
+        }
+        None => String::new(),
+    };
+
+    let raw_column = grouping.dimension();
+    let raw_provider = if raw_column == "model" {
+        "provider"
+    } else {
+        "''"
+    };
+    let (provider_key, name_key) = grouping.keys();
+    let query_str = format!(
+        r#"
+        WITH combined AS ({rollup}
+            SELECT
+                {raw_provider} AS provider,
+                {raw_column} AS name,
+                COALESCE(SUM(total_cost_usd), 0)::DOUBLE PRECISION AS total_cost,
+                COALESCE(SUM(prompt_cost_usd), 0)::DOUBLE PRECISION AS prompt_cost,
+                COALESCE(SUM(completion_cost_usd), 0)::DOUBLE PRECISION AS completion_cost,
+                COUNT(*) AS request_count,
+                COALESCE(SUM(total_tokens), 0)::BIGINT AS total_tokens,
+                COUNT(*) FILTER (WHERE status_code = 'ERROR') AS error_count,
+                COALESCE(SUM(duration_ms), 0)::DOUBLE PRECISION AS duration_sum_ms
+            FROM llm_traces
+            WHERE org_id = $1 AND {raw_column} IS NOT NULL
+              AND ((ts >= $2 AND ts < $4) OR (ts >= $5 AND ts < $3)){raw_filters}
+            GROUP BY 1, 2
+        )
+        SELECT
+            {provider_key} AS provider,
+            {name_key} AS name,
+            SUM(total_cost) AS total_cost,
+            SUM(prompt_cost) AS prompt_cost,
+            SUM(completion_cost) AS completion_cost,
+            SUM(request_count)::BIGINT AS request_count,
+            SUM(total_tokens)::BIGINT AS total_tokens,
+            SUM(error_count)::BIGINT AS error_count,
+            SUM(duration_sum_ms) AS duration_sum_ms
+        FROM combined
+        GROUP BY 1, 2
+        ORDER BY {order} DESC NULLS LAST, 2
+        LIMIT ${limit}
+        "#,
+        order = order.to_sql(),
+        limit = filter_columns.len() + 6,
+    );
+
+    // Without materialized days the raw part covers the whole range
+    let (first, last) = days.unwrap_or((end, end));
+    let mut query = sqlx::query_as::<_, TopNRow>(&query_str)
+        .bind(org_id)
+        .bind(start)
+        .bind(end)
+        .bind(first)
+        .bind(last);
+    for (_, value) in &filter_columns {
+        query = query.bind(*value);
+    }
+
+    query.bind(limit).fetch_all(pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_materialized_days() {
+        let from = ts("2025-10-01T00:00:00Z");
+        let through = ts("2025-11-12T00:00:00Z");
+
+        assert_eq!(
+            materialized_days(
+                ts("2025-11-01T06:30:00Z"),
+                ts("2025-11-12T15:00:00Z"),
+                from,
+                through
+            ),
+            Some((ts("2025-11-02T00:00:00Z"), ts("2025-11-12T00:00:00Z")))
+        );
+        assert_eq!(
+            materialized_days(
+                ts("2025-09-20T00:00:00Z"),
+                ts("2025-10-05T00:00:00Z"),
+                from,
+                through
+            ),
+            Some((ts("2025-10-01T00:00:00Z"), ts("2025-10-05T00:00:00Z")))
+        );
+        // No complete day inside the range
+        assert_eq!(
+            materialized_days(
+                ts("2025-11-05T06:00:00Z"),
+                ts("2025-11-06T05:00:00Z"),
+                from,
+                through
+            ),
+            None
+        );
+        assert_eq!(
+            materialized_days(
+                ts("2025-11-12T00:00:00Z"),
+                ts("2025-11-13T00:00:00Z"),
+                from,
+                through
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_supports() {
+        let mut filters = TopNFilters {
+            provider: Some("openai".to_string()),
+            ..TopNFilters::default()
+        };
+        assert!(supports(TopNGrouping::Model, &filters));
+        assert!(!supports(TopNGrouping::User, &filters));
+
+        filters.provider = None;
+        filters.environment = Some("production".to_string());
+        assert!(supports(TopNGrouping::Environment, &filters));
+        assert!(!supports(TopNGrouping::ProviderModel, &filters));
+        assert!(supports(TopNGrouping::User, &TopNFilters::default()));
+    }
+}
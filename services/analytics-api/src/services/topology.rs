@@ -0,0 +1,404 @@
+//! # Service Topology
+//!
+//! Derives dependency edges between services, models and tools from
+//! parent-child span relationships (e.g. app → gateway → openai/gpt-4o →
+//! search tool) and periodically materializes them into the `service_edges`
+//! table, which backs `GET /api/v1/topology`.
+//!
+//! ## Nodes
+//! Every span maps to a node:
+//! - `tool` - spans with a `gen_ai.tool.name` attribute, named after the tool
+//! - `model` - client spans calling a provider, named `provider/model`
+//! - `service` - everything else, named after the `service.name` resource
+//!   attribute
+//!
+//! A child span whose node differs from its parent's is one call along the
+//! edge parent → child. Spans within the same node are internal and ignored.
+//!
+//! ## Materialization
+//! Edges are aggregated into fixed time buckets. Each run recomputes the
+//! current and previous bucket, so spans arriving up to one bucket late are
+//! still counted.
+
+use chrono::{DateTime, Duration as ChronoDuration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+/// Default edge bucket size
+pub const DEFAULT_BUCKET: Duration = Duration::from_secs(300);
+
+/// Parent spans are looked up this far before the window start
+const PARENT_LOOKBACK: ChronoDuration = ChronoDuration::hours(1);
+
+/// Node type in the dependency graph
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Service,
+    Model,
+    Tool,
+}
+
+impl NodeKind {
+    /// Get the kind as stored in `service_edges`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeKind::Service => "service",
+            NodeKind::Model => "model",
+            NodeKind::Tool => "tool",
+        }
+    }
+
+    /// Parse a kind stored in `service_edges`
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "service" => Some(NodeKind::Service),
+            "model" => Some(NodeKind::Model),
+            "tool" => Some(NodeKind::Tool),
+            _ => None,
+        }
+    }
+}
+
+/// A node in the dependency graph
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TopologyNode {
+    pub kind: NodeKind,
+    pub name: String,
+}
+
+impl TopologyNode {
+    /// Classify a span into its node.
+    pub fn classify(
+        span_kind: &str,
+        provider: &str,
+        model: &str,
+        service_name: Option<&str>,
+        tool_name: Option<&str>,
+    ) -> Self {
+        if let Some(tool) = tool_name.filter(|t| !t.is_empty()) {
+            return Self {
+                kind: NodeKind::Tool,
+                name: tool.to_string(),
+            };
+        }
+
+        if span_kind.eq_ignore_ascii_case("client") && !provider.is_empty() {
+            return Self {
+                kind: NodeKind::Model,
+                name: format!("{}/{}", provider, model),
+            };
+        }
+
+        Self {
+            kind: NodeKind::Service,
+            name: service_name
+                .filter(|s| !s.is_empty())
+                .unwrap_or("unknown")
+                .to_string(),
+        }
+    }
+}
+
+/// A child span joined with its parent
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SpanLinkRow {
+    pub org_id: String,
+    pub ts: DateTime<Utc>,
+    pub duration_ms: i32,
+    pub status_code: String,
+    pub span_kind: String,
+    pub provider: String,
+    pub model: String,
+    pub service_name: Option<String>,
+    pub tool_name: Option<String>,
+    pub parent_span_kind: String,
+    pub parent_provider: String,
+    pub parent_model: String,
+    pub parent_service_name: Option<String>,
+    pub parent_tool_name: Option<String>,
+}
+
+impl SpanLinkRow {
+    fn source(&self) -> TopologyNode {
+        TopologyNode::classify(
+            &self.parent_span_kind,
+            &self.parent_provider,
+            &self.parent_model,
+            self.parent_service_name.as_deref(),
+            self.parent_tool_name.as_deref(),
+        )
+    }
+
+    fn target(&self) -> TopologyNode {
+        TopologyNode::classify(
+            &self.span_kind,
+            &self.provider,
+            &self.model,
+            self.service_name.as_deref(),
+            self.tool_name.as_deref(),
+        )
+    }
+}
+
+/// Aggregated calls along one edge in one bucket
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceEdge {
+    pub org_id: String,
+    pub bucket: DateTime<Utc>,
+    pub source: TopologyNode,
+    pub target: TopologyNode,
+    pub call_count: i64,
+    pub error_count: i64,
+    pub avg_duration_ms: f64,
+    pub p95_duration_ms: f64,
+}
+
+/// Aggregate span links into per-bucket edges, sorted by bucket, org and
+/// edge.
+pub fn aggregate_edges(rows: &[SpanLinkRow], bucket: Duration) -> Vec<ServiceEdge> {
+    let bucket = ChronoDuration::from_std(bucket).unwrap_or(ChronoDuration::minutes(5));
+
+    let mut calls: HashMap<_, (Vec<i32>, i64)> = HashMap::new();
+    for row in rows {
+        let (source, target) = (row.source(), row.target());
+        if source == target {
+            continue;
+        }
+
+        let bucket_start = row.ts.duration_trunc(bucket).unwrap_or(row.ts);
+        let entry = calls
+            .entry((bucket_start, row.org_id.clone(), source, target))
+            .or_default();
+        entry.0.push(row.duration_ms);
+        if row.status_code.eq_ignore_ascii_case("error") {
+            entry.1 += 1;
+        }
+    }
+
+    let mut edges: Vec<ServiceEdge> = calls
+        .into_iter()
+        .map(|((bucket, org_id, source, target), (mut durations, error_count))| {
+            durations.sort_unstable();
+            let call_count = durations.len() as i64;
+            let total: i64 = durations.iter().map(|d| *d as i64).sum();
+
+            ServiceEdge {
+                org_id,
+                bucket,
+                source,
+                target,
+                call_count,
+                error_count,
+                avg_duration_ms: total as f64 / call_count as f64,
+                p95_duration_ms: percentile(&durations, 0.95),
+            }
+        })
+        .collect();
+
+    edges.sort_by(|a, b| {
+        (a.bucket, &a.org_id, &a.source, &a.target).cmp(&(b.bucket, &b.org_id, &b.source, &b.target))
+    });
+    edges
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[i32], quantile: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (quantile * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1] as f64
+}
+
+/// Periodically materializes span relationships into `service_edges`
+pub struct TopologyMaterializer {
+    pool: PgPool,
+    bucket: Duration,
+}
+
+impl TopologyMaterializer {
+    /// Create a materializer writing with `pool`.
+    pub fn new(pool: PgPool, bucket: Duration) -> Self {
+        Self { pool, bucket }
+    }
+
+    /// Recompute the edges of the current and previous bucket.
+    pub async fn materialize(&self, now: DateTime<Utc>) -> Result<usize, sqlx::Error> {
+        let bucket = ChronoDuration::from_std(self.bucket).unwrap_or(ChronoDuration::minutes(5));
+        let window_start = now.duration_trunc(bucket).unwrap_or(now) - bucket;
+
+        let rows = sqlx::query_as::<_, SpanLinkRow>(
+            r#"
+            SELECT
+                c.org_id,
+                c.ts,
+                c.duration_ms,
+                c.status_code,
+                c.span_kind,
+                c.provider,
+                c.model,
+                c.resource_attributes->>'service.name' AS service_name,
+                c.attributes->>'gen_ai.tool.name' AS tool_name,
+                p.span_kind AS parent_span_kind,
+                p.provider AS parent_provider,
+                p.model AS parent_model,
+                p.resource_attributes->>'service.name' AS parent_service_name,
+                p.attributes->>'gen_ai.tool.name' AS parent_tool_name
+            FROM llm_traces c
+            JOIN llm_traces p
+              ON p.trace_id = c.trace_id
+             AND p.span_id = c.parent_span_id
+             AND p.ts >= $3
+             AND p.ts < $2
+            WHERE c.ts >= $1
+              AND c.ts < $2
+              AND c.parent_span_id IS NOT NULL
+            "#,
+        )
+        .bind(window_start)
+        .bind(now)
+        .bind(window_start - PARENT_LOOKBACK)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let edges = aggregate_edges(&rows, self.bucket);
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM service_edges WHERE bucket >= $1 AND bucket < $2")
+            .bind(window_start)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+
+        for edge in &edges {
+            sqlx::query(
+                r#"
+                INSERT INTO service_edges (
+                    org_id, bucket, source_kind, source_name, target_kind, target_name,
+                    call_count, error_count, avg_duration_ms, p95_duration_ms, updated_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, NOW())
+                "#,
+            )
+            .bind(&edge.org_id)
+            .bind(edge.bucket)
+            .bind(edge.source.kind.as_str())
+            .bind(&edge.source.name)
+            .bind(edge.target.kind.as_str())
+            .bind(&edge.target.name)
+            .bind(edge.call_count)
+            .bind(edge.error_count)
+            .bind(edge.avg_duration_ms)
+            .bind(edge.p95_duration_ms)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        debug!(spans = rows.len(), edges = edges.len(), "Service topology materialized");
+        Ok(edges.len())
+    }
+
+    /// Materialize at a fixed interval in the background.
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        info!(bucket_secs = self.bucket.as_secs(), "Service topology materializer started");
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.materialize(Utc::now()).await {
+                    Ok(edges) => metrics::gauge!("topology_edges").set(edges as f64),
+                    Err(e) => error!(error = %e, "Failed to materialize service topology"),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn link(
+        parent: (&str, &str, Option<&str>, Option<&str>),
+        child: (&str, &str, Option<&str>, Option<&str>),
+        minute: u32,
+        duration_ms: i32,
+        status_code: &str,
+    ) -> SpanLinkRow {
+        SpanLinkRow {
+            org_id: "org-1".to_string(),
+            ts: Utc.with_ymd_and_hms(2025, 1, 1, 10, minute, 0).unwrap(),
+            duration_ms,
+            status_code: status_code.to_string(),
+            span_kind: child.0.to_string(),
+            provider: child.1.to_string(),
+            model: "gpt-4o".to_string(),
+            service_name: child.2.map(String::from),
+            tool_name: child.3.map(String::from),
+            parent_span_kind: parent.0.to_string(),
+            parent_provider: parent.1.to_string(),
+            parent_model: "gpt-4o".to_string(),
+            parent_service_name: parent.2.map(String::from),
+            parent_tool_name: parent.3.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            TopologyNode::classify("client", "openai", "gpt-4o", Some("gateway"), None),
+            TopologyNode { kind: NodeKind::Model, name: "openai/gpt-4o".to_string() }
+        );
+        assert_eq!(
+            TopologyNode::classify("internal", "openai", "gpt-4o", Some("app"), Some("search")),
+            TopologyNode { kind: NodeKind::Tool, name: "search".to_string() }
+        );
+        assert_eq!(
+            TopologyNode::classify("server", "openai", "gpt-4o", None, None),
+            TopologyNode { kind: NodeKind::Service, name: "unknown".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_aggregate_edges() {
+        let app = ("server", "", Some("app"), None);
+        let app_internal = ("internal", "", Some("app"), None);
+        let gateway = ("server", "", Some("gateway"), None);
+        let model = ("client", "openai", Some("gateway"), None);
+
+        let mut rows = vec![
+            link(app, gateway, 1, 100, "OK"),
+            link(app, gateway, 2, 300, "ERROR"),
+            link(app, app_internal, 2, 50, "OK"),
+            link(gateway, model, 3, 80, "OK"),
+            link(app, gateway, 7, 200, "OK"),
+        ];
+        rows.extend((0..19).map(|i| link(gateway, model, 4, 10 + i, "OK")));
+
+        let edges = aggregate_edges(&rows, DEFAULT_BUCKET);
+        assert_eq!(edges.len(), 3);
+
+        let app_gateway = &edges[0];
+        assert_eq!(app_gateway.source.name, "app");
+        assert_eq!(app_gateway.target.name, "gateway");
+        assert_eq!(app_gateway.call_count, 2);
+        assert_eq!(app_gateway.error_count, 1);
+        assert_eq!(app_gateway.avg_duration_ms, 200.0);
+        assert_eq!(app_gateway.p95_duration_ms, 300.0);
+
+        let gateway_model = &edges[1];
+        assert_eq!(gateway_model.target.kind, NodeKind::Model);
+        assert_eq!(gateway_model.call_count, 20);
+        assert_eq!(gateway_model.p95_duration_ms, 28.0);
+
+        // 10:07 falls into the next 5 minute bucket
+        assert_eq!(edges[2].bucket, Utc.with_ymd_and_hms(2025, 1, 1, 10, 5, 0).unwrap());
+    }
+}
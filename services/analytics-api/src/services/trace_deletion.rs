@@ -0,0 +1,373 @@
+//! # Bulk Trace Deletion
+//!
+//! Deletes traces selected with a search filter, e.g. after load test
+//! traffic reached production or PII was ingested by accident.
+//!
+//! A trace is selected when at least one of its spans in `llm_traces`
+//! matches the filter and time range; all of the trace's spans and its logs
+//! in `llm_logs` are deleted. Deletion runs as a background job that
+//! deletes [`DEFAULT_BATCH_SIZE`] traces per transaction and records its
+//! progress in `trace_deletion_jobs`, so large deletions neither hold long
+//! locks nor time out the request. The selection's end time is fixed when
+//! the job is created, so traffic arriving while it runs is never deleted.
+
+use crate::models::deletion::{TraceDeletionJob, TraceDeletionPreview, SAMPLE_TRACE_IDS};
+use crate::models::filters::Filter;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+/// Default number of traces deleted per batch
+pub const DEFAULT_BATCH_SIZE: i64 = 1000;
+
+/// Columns of [`TraceDeletionJob`]
+const JOB_COLUMNS: &str = "job_id, requested_by, status, filter, start_time, end_time, \
+     matched_traces, matched_spans, matched_logs, deleted_traces, deleted_spans, deleted_logs, \
+     batches_completed, error_message, created_at, started_at, completed_at";
+
+/// Errors from trace deletion
+#[derive(Debug, thiserror::Error)]
+pub enum TraceDeletionError {
+    #[error("Trace deletion requires DATABASE_URL (read-write connection)")]
+    Disabled,
+
+    #[error("Filter error: {0}")]
+    Filter(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Spans selecting the traces to delete
+#[derive(Debug, Clone)]
+pub struct TraceSelection {
+    pub org_id: String,
+    pub filter: Filter,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: DateTime<Utc>,
+}
+
+impl TraceSelection {
+    /// WHERE condition on `llm_traces` and its parameters, starting at `$1`
+    /// (always the organization).
+    ///
+    /// Like the search query, all parameters are bound as text.
+    pub fn to_sql(&self) -> Result<(String, Vec<String>), String> {
+        let mut conditions = vec!["attributes->>'org_id' = $1".to_string()];
+        let mut params = vec![self.org_id.clone()];
+
+        if let Some(start_time) = self.start_time {
+            params.push(start_time.to_rfc3339());
+            conditions.push(format!("ts >= ${}::timestamptz", params.len()));
+        }
+
+        params.push(self.end_time.to_rfc3339());
+        conditions.push(format!("ts < ${}::timestamptz", params.len()));
+
+        let mut param_index = params.len() as i32 + 1;
+        let (filter_sql, filter_params) = self.filter.to_sql(&mut param_index)?;
+        conditions.push(format!("({})", filter_sql));
+        params.extend(filter_params);
+
+        Ok((conditions.join(" AND "), params))
+    }
+}
+
+/// Count the traces, spans and logs a selection would delete.
+pub async fn preview(
+    pool: &PgPool,
+    selection: &TraceSelection,
+) -> Result<TraceDeletionPreview, TraceDeletionError> {
+    let (condition, params) = selection.to_sql().map_err(TraceDeletionError::Filter)?;
+
+    let counts_sql = format!(
+        r#"
+        WITH matched AS (
+            SELECT DISTINCT trace_id FROM llm_traces WHERE {}
+        )
+        SELECT
+            (SELECT COUNT(*) FROM matched),
+            (SELECT COUNT(*) FROM llm_traces
+             WHERE attributes->>'org_id' = $1
+               AND trace_id IN (SELECT trace_id FROM matched)),
+            (SELECT COUNT(*) FROM llm_logs
+             WHERE trace_id IN (SELECT trace_id FROM matched))
+        "#,
+        condition
+    );
+    let mut counts = sqlx::query_as::<_, (i64, i64, i64)>(&counts_sql);
+    for param in &params {
+        counts = counts.bind(param);
+    }
+    let (matched_traces, matched_spans, matched_logs) = counts.fetch_one(pool).await?;
+
+    let sample_sql = format!(
+        "SELECT DISTINCT trace_id FROM llm_traces WHERE {} ORDER BY trace_id LIMIT {}",
+        condition, SAMPLE_TRACE_IDS
+    );
+    let mut sample = sqlx::query_scalar::<_, String>(&sample_sql);
+    for param in &params {
+        sample = sample.bind(param);
+    }
+    let sample_trace_ids = sample.fetch_all(pool).await?;
+
+    Ok(TraceDeletionPreview {
+        dry_run: true,
+        start_time: selection.start_time,
+        end_time: selection.end_time,
+        matched_traces,
+        matched_spans,
+        matched_logs,
+        sample_trace_ids,
+    })
+}
+
+/// Starts deletion jobs and reports their progress
+pub struct TraceDeletionService {
+    /// Read-write pool (None disables deletion)
+    pool: Option<PgPool>,
+    /// Traces deleted per transaction
+    batch_size: i64,
+}
+
+impl TraceDeletionService {
+    pub fn new(pool: Option<PgPool>, batch_size: i64) -> Self {
+        Self {
+            pool,
+            batch_size: batch_size.max(1),
+        }
+    }
+
+    /// A service that rejects every deletion
+    pub fn disabled() -> Self {
+        Self::new(None, DEFAULT_BATCH_SIZE)
+    }
+
+    fn pool(&self) -> Result<&PgPool, TraceDeletionError> {
+        self.pool.as_ref().ok_or(TraceDeletionError::Disabled)
+    }
+
+    /// Record a job for the selection and start deleting in the background.
+    pub async fn start(
+        &self,
+        selection: TraceSelection,
+        requested_by: &str,
+    ) -> Result<TraceDeletionJob, TraceDeletionError> {
+        let pool = self.pool()?;
+        let counts = preview(pool, &selection).await?;
+        let filter = serde_json::to_value(&selection.filter)
+            .map_err(|e| TraceDeletionError::Filter(e.to_string()))?;
+
+        let job = sqlx::query_as::<_, TraceDeletionJob>(&format!(
+            r#"
+            INSERT INTO trace_deletion_jobs (
+                org_id, requested_by, status, filter, start_time, end_time,
+                matched_traces, matched_spans, matched_logs
+            )
+            VALUES ($1, $2, 'pending', $3, $4, $5, $6, $7, $8)
+            RETURNING {}
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(&selection.org_id)
+        .bind(requested_by)
+        .bind(filter)
+        .bind(selection.start_time)
+        .bind(selection.end_time)
+        .bind(counts.matched_traces)
+        .bind(counts.matched_spans)
+        .bind(counts.matched_logs)
+        .fetch_one(pool)
+        .await?;
+
+        info!(
+            job_id = %job.job_id,
+            org_id = %selection.org_id,
+            requested_by,
+            matched_traces = counts.matched_traces,
+            "Trace deletion job created"
+        );
+
+        tokio::spawn(run_job(
+            pool.clone(),
+            job.job_id,
+            selection,
+            self.batch_size,
+        ));
+
+        Ok(job)
+    }
+
+    /// Get a job of the organization.
+    pub async fn get_job(
+        &self,
+        org_id: &str,
+        job_id: Uuid,
+    ) -> Result<Option<TraceDeletionJob>, TraceDeletionError> {
+        let job = sqlx::query_as::<_, TraceDeletionJob>(&format!(
+            "SELECT {} FROM trace_deletion_jobs WHERE org_id = $1 AND job_id = $2",
+            JOB_COLUMNS
+        ))
+        .bind(org_id)
+        .bind(job_id)
+        .fetch_optional(self.pool()?)
+        .await?;
+
+        Ok(job)
+    }
+}
+
+async fn run_job(pool: PgPool, job_id: Uuid, selection: TraceSelection, batch_size: i64) {
+    if let Err(e) = delete_batches(&pool, job_id, &selection, batch_size).await {
+        metrics::counter!("analytics_trace_deletion_failures_total").increment(1);
+        error!(job_id = %job_id, error = %e, "Trace deletion job failed");
+
+        let result = sqlx::query(
+            r#"
+            UPDATE trace_deletion_jobs
+            SET status = 'failed', error_message = $2, completed_at = NOW()
+            WHERE job_id = $1
+            "#,
+        )
+        .bind(job_id)
+        .bind(e.to_string())
+        .execute(&pool)
+        .await;
+
+        if let Err(e) = result {
+            error!(job_id = %job_id, error = %e, "Failed to mark trace deletion job as failed");
+        }
+    }
+}
+
+async fn delete_batches(
+    pool: &PgPool,
+    job_id: Uuid,
+    selection: &TraceSelection,
+    batch_size: i64,
+) -> Result<(), TraceDeletionError> {
+    sqlx::query(
+        "UPDATE trace_deletion_jobs SET status = 'running', started_at = NOW() WHERE job_id = $1",
+    )
+    .bind(job_id)
+    .execute(pool)
+    .await?;
+
+    let (condition, params) = selection.to_sql().map_err(TraceDeletionError::Filter)?;
+    let select_sql = format!(
+        "SELECT DISTINCT trace_id FROM llm_traces WHERE {} LIMIT {}",
+        condition, batch_size
+    );
+
+    let mut batches = 0;
+    loop {
+        let mut query = sqlx::query_scalar::<_, String>(&select_sql);
+        for param in &params {
+            query = query.bind(param);
+        }
+        let trace_ids = query.fetch_all(pool).await?;
+        if trace_ids.is_empty() {
+            break;
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let logs = sqlx::query("DELETE FROM llm_logs WHERE trace_id = ANY($1)")
+            .bind(&trace_ids)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        let spans = sqlx::query(
+            "DELETE FROM llm_traces WHERE attributes->>'org_id' = $1 AND trace_id = ANY($2)",
+        )
+        .bind(&selection.org_id)
+        .bind(&trace_ids)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        sqlx::query(
+            r#"
+            UPDATE trace_deletion_jobs
+            SET deleted_traces = deleted_traces + $2,
+                deleted_spans = deleted_spans + $3,
+                deleted_logs = deleted_logs + $4,
+                batches_completed = batches_completed + 1
+            WHERE job_id = $1
+            "#,
+        )
+        .bind(job_id)
+        .bind(trace_ids.len() as i64)
+        .bind(spans as i64)
+        .bind(logs as i64)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        batches += 1;
+        metrics::counter!("analytics_traces_deleted_total").increment(trace_ids.len() as u64);
+        debug!(job_id = %job_id, batch = batches, traces = trace_ids.len(), spans, logs, "Deleted trace batch");
+    }
+
+    sqlx::query(
+        "UPDATE trace_deletion_jobs SET status = 'completed', completed_at = NOW() WHERE job_id = $1",
+    )
+    .bind(job_id)
+    .execute(pool)
+    .await?;
+
+    info!(job_id = %job_id, batches, "Trace deletion job completed");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::filters::{FieldFilter, FilterOperator, FilterValue};
+    use chrono::TimeZone;
+
+    fn selection(start_time: Option<DateTime<Utc>>) -> TraceSelection {
+        TraceSelection {
+            org_id: "org-1".to_string(),
+            filter: Filter::Field(FieldFilter {
+                field: "environment".to_string(),
+                operator: FilterOperator::Eq,
+                value: FilterValue::String("load-test".to_string()),
+            }),
+            start_time,
+            end_time: Utc.with_ymd_and_hms(2025, 11, 5, 10, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_selection_sql() {
+        let (sql, params) = selection(None).to_sql().unwrap();
+        assert_eq!(
+            sql,
+            "attributes->>'org_id' = $1 AND ts < $2::timestamptz AND (environment = $3)"
+        );
+        assert_eq!(params.len(), 3);
+        assert_eq!(params[0], "org-1");
+
+        let start = Utc.with_ymd_and_hms(2025, 11, 1, 0, 0, 0).unwrap();
+        let (sql, params) = selection(Some(start)).to_sql().unwrap();
+        assert_eq!(
+            sql,
+            "attributes->>'org_id' = $1 AND ts >= $2::timestamptz \
+             AND ts < $3::timestamptz AND (environment = $4)"
+        );
+        assert_eq!(params.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_service() {
+        let service = TraceDeletionService::disabled();
+        assert!(matches!(
+            service.start(selection(None), "admin").await,
+            Err(TraceDeletionError::Disabled)
+        ));
+    }
+}
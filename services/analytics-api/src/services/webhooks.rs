@@ -0,0 +1,202 @@
+//! # Webhook Notifications
+//!
+//! Registers webhook endpoints and sends events to them through the
+//! `llm-observatory-webhooks` dispatcher, along with Slack and PagerDuty
+//! notification channels. Alerting, budget and anomaly checks call
+//! [`WebhookService::notify`]; the routes manage endpoints and channels and
+//! fire test events. Endpoints and deliveries are written with the
+//! read-write connection. `alert.triggered` events get an incident narrative
+//! first when [`IncidentSummaryService`] is configured.
+
+use llm_observatory_webhooks::{
+    NewNotificationChannel, NewWebhookEndpoint, NotificationChannel, WebhookDelivery,
+    WebhookDispatcher, WebhookEndpoint, WebhookError, WebhookEvent, WebhookStore,
+};
+use crate::services::incident_summary::IncidentSummaryService;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Errors from webhook operations
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookServiceError {
+    #[error("Webhooks require DATABASE_URL (read-write connection)")]
+    Disabled,
+
+    #[error(transparent)]
+    Webhook(#[from] WebhookError),
+}
+
+/// Webhook endpoints and deliveries
+pub struct WebhookService {
+    /// Dispatcher on the read-write pool (None disables webhooks)
+    dispatcher: Option<WebhookDispatcher>,
+    /// Narratives attached to firing alerts
+    incident_summaries: Arc<IncidentSummaryService>,
+}
+
+impl WebhookService {
+    pub fn new(pool: Option<PgPool>) -> Self {
+        Self {
+            dispatcher: pool.map(|pool| WebhookDispatcher::new(WebhookStore::new(pool))),
+            incident_summaries: Arc::new(IncidentSummaryService::disabled()),
+        }
+    }
+
+    /// Attach incident narratives from `summaries` to firing alerts
+    pub fn with_incident_summaries(mut self, summaries: Arc<IncidentSummaryService>) -> Self {
+        self.incident_summaries = summaries;
+        self
+    }
+
+    /// A service that rejects every webhook operation and drops events
+    pub fn disabled() -> Self {
+        Self::new(None)
+    }
+
+    fn dispatcher(&self) -> Result<&WebhookDispatcher, WebhookServiceError> {
+        self.dispatcher
+            .as_ref()
+            .ok_or(WebhookServiceError::Disabled)
+    }
+
+    /// Send an event to the subscribed endpoints of its organization.
+    ///
+    /// Failures are logged rather than returned, so a notification never
+    /// fails the check that raised it. Firing alerts are summarized before
+    /// they are sent when incident summaries are configured.
+    pub async fn notify(&self, event: WebhookEvent) {
+        let Some(dispatcher) = &self.dispatcher else {
+            return;
+        };
+        let event = self.incident_summaries.attach(event).await;
+        if let Err(e) = dispatcher.dispatch(event).await {
+            warn!(error = %e, "Failed to dispatch webhook event");
+        }
+    }
+
+    /// Register an endpoint, generating its signing secret
+    pub async fn register(
+        &self,
+        org_id: &str,
+        endpoint: &NewWebhookEndpoint,
+    ) -> Result<WebhookEndpoint, WebhookServiceError> {
+        Ok(self
+            .dispatcher()?
+            .store()
+            .create_endpoint(org_id, endpoint)
+            .await?)
+    }
+
+    /// The organization's endpoints
+    pub async fn list(&self, org_id: &str) -> Result<Vec<WebhookEndpoint>, WebhookServiceError> {
+        Ok(self.dispatcher()?.store().list_endpoints(org_id).await?)
+    }
+
+    /// One of the organization's endpoints
+    pub async fn get(
+        &self,
+        org_id: &str,
+        id: Uuid,
+    ) -> Result<Option<WebhookEndpoint>, WebhookServiceError> {
+        Ok(self.dispatcher()?.store().get_endpoint(org_id, id).await?)
+    }
+
+    /// Delete an endpoint and its deliveries, returning whether it existed
+    pub async fn delete(&self, org_id: &str, id: Uuid) -> Result<bool, WebhookServiceError> {
+        Ok(self
+            .dispatcher()?
+            .store()
+            .delete_endpoint(org_id, id)
+            .await?)
+    }
+
+    /// Send a test event to an endpoint and return the delivery
+    pub async fn test_fire(
+        &self,
+        endpoint: &WebhookEndpoint,
+    ) -> Result<WebhookDelivery, WebhookServiceError> {
+        Ok(self.dispatcher()?.test_fire(endpoint).await?)
+    }
+
+    /// Recent deliveries to an endpoint, newest first
+    pub async fn deliveries(
+        &self,
+        org_id: &str,
+        endpoint_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, WebhookServiceError> {
+        Ok(self
+            .dispatcher()?
+            .store()
+            .list_deliveries(org_id, endpoint_id, limit)
+            .await?)
+    }
+
+    /// Register a Slack or PagerDuty notification channel
+    pub async fn register_channel(
+        &self,
+        org_id: &str,
+        channel: &NewNotificationChannel,
+    ) -> Result<NotificationChannel, WebhookServiceError> {
+        Ok(self
+            .dispatcher()?
+            .store()
+            .create_channel(org_id, channel)
+            .await?)
+    }
+
+    /// The organization's notification channels
+    pub async fn list_channels(
+        &self,
+        org_id: &str,
+    ) -> Result<Vec<NotificationChannel>, WebhookServiceError> {
+        Ok(self.dispatcher()?.store().list_channels(org_id).await?)
+    }
+
+    /// One of the organization's notification channels
+    pub async fn get_channel(
+        &self,
+        org_id: &str,
+        id: Uuid,
+    ) -> Result<Option<NotificationChannel>, WebhookServiceError> {
+        Ok(self.dispatcher()?.store().get_channel(org_id, id).await?)
+    }
+
+    /// Delete a notification channel and its deliveries, returning whether
+    /// it existed
+    pub async fn delete_channel(
+        &self,
+        org_id: &str,
+        id: Uuid,
+    ) -> Result<bool, WebhookServiceError> {
+        Ok(self
+            .dispatcher()?
+            .store()
+            .delete_channel(org_id, id)
+            .await?)
+    }
+
+    /// Send a test event to a notification channel and return the delivery
+    pub async fn test_fire_channel(
+        &self,
+        channel: &NotificationChannel,
+    ) -> Result<WebhookDelivery, WebhookServiceError> {
+        Ok(self.dispatcher()?.test_fire_channel(channel).await?)
+    }
+
+    /// Recent deliveries to a notification channel, newest first
+    pub async fn channel_deliveries(
+        &self,
+        org_id: &str,
+        channel_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, WebhookServiceError> {
+        Ok(self
+            .dispatcher()?
+            .store()
+            .list_channel_deliveries(org_id, channel_id, limit)
+            .await?)
+    }
+}
@@ -29,8 +29,22 @@ async fn create_test_state() -> Arc<AppState> {
 
     Arc::new(AppState {
         db_pool,
+        query_cache: Arc::new(analytics_api::QueryCache::new(redis_client.clone())),
         redis_client,
         cache_ttl: 60, // Short TTL for tests
+        currency: Arc::new(analytics_api::CurrencyService::usd_only()),
+        provider_health: Arc::new(analytics_api::ProviderHealthMonitor::disabled()),
+        data_access: Arc::new(analytics_api::DataAccessPolicy::unrestricted()),
+        trace_deletion: Arc::new(analytics_api::TraceDeletionService::disabled()),
+        quarantine: Arc::new(analytics_api::QuarantineService::disabled()),
+        webhooks: Arc::new(analytics_api::WebhookService::disabled()),
+        admin: Arc::new(analytics_api::AdminService::disabled()),
+        service_accounts: Arc::new(analytics_api::ServiceAccountService::disabled()),
+        pseudonyms: Arc::new(analytics_api::PseudonymLookupService::disabled()),
+        federation: Arc::new(analytics_api::FederationService::disabled()),
+        cold_storage: Arc::new(analytics_api::ColdStorageService::disabled()),
+        natural_query: Arc::new(analytics_api::NaturalQueryService::disabled()),
+        model_registry: Arc::new(analytics_api::ModelRegistryService::disabled()),
     })
 }
 